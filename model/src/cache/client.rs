@@ -12,6 +12,8 @@ use redis::AsyncCommands;
 use serde::{de::DeserializeOwned, Serialize};
 use tracing::{instrument, trace};
 
+use crate::log::QueryLatency;
+
 /// Alias for Redis connection type.
 pub type RedisConnection<'a> = PooledConnection<'a, RedisConnectionManager>;
 
@@ -58,8 +60,9 @@ impl CacheClient {
     }
 
     /// Get a value from the cache.
-    #[instrument(skip(self))]
+    #[instrument(level = "debug", skip(self), fields(latency_ms))]
     pub async fn get<T: RedisModel>(&self, id: &T::Id) -> Result<Option<T>, anyhow::Error> {
+        let _latency = QueryLatency::start();
         let mut conn = self.conn().await?;
         let key = T::key_from(id);
 
@@ -69,9 +72,37 @@ impl CacheClient {
         value.map(RedisModel::deserialize_model).transpose()
     }
 
+    /// Get multiple values from the cache.
+    ///
+    /// The values are queried in a single pipeline, and returned in the order
+    /// of the ids. Missing values are [`None`].
+    #[instrument(level = "debug", skip(self), fields(latency_ms))]
+    pub async fn get_many<T>(&self, ids: &[T::Id]) -> Result<Vec<Option<T>>, anyhow::Error>
+    where
+        T: RedisModel,
+        T::Id: Sized,
+    {
+        let _latency = QueryLatency::start();
+        let mut conn = self.conn().await?;
+        let mut pipe = redis::pipe();
+
+        trace!("getting {} values", ids.len());
+        for id in ids {
+            pipe.get(T::key_from(id));
+        }
+
+        let values: Vec<Option<Vec<u8>>> = pipe.query_async(&mut *conn).await?;
+
+        values
+            .into_iter()
+            .map(|value| value.map(RedisModel::deserialize_model).transpose())
+            .collect()
+    }
+
     /// Set a value in the cache.
-    #[instrument(skip(self))]
+    #[instrument(level = "debug", skip(self), fields(latency_ms))]
     pub async fn set<T: RedisModel>(&self, value: &T) -> Result<(), anyhow::Error> {
+        let _latency = QueryLatency::start();
         let mut conn = self.conn().await?;
         let key = value.key();
 
@@ -86,9 +117,59 @@ impl CacheClient {
         Ok(())
     }
 
+    /// Set a value in the cache that expires after `expires_after` seconds.
+    ///
+    /// This is used for the models whose expiration is not known at compile
+    /// time, instead of [`RedisModel::EXPIRES_AFTER`].
+    #[instrument(level = "debug", skip(self), fields(latency_ms))]
+    pub async fn set_with_expiry<T: RedisModel>(
+        &self,
+        value: &T,
+        expires_after: usize,
+    ) -> Result<(), anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let mut conn = self.conn().await?;
+        let key = value.key();
+
+        trace!(value = ?value, "setting value for key {} (expires after {}s)", key, expires_after);
+        conn.set_ex(key, value.serialize_model()?, expires_after)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Set a value in the cache only if its key does not exist.
+    ///
+    /// The value expires after `expires_after` seconds. Returns `false` if the
+    /// key already exists. The check is atomic, so that only one of several
+    /// concurrent callers sets the value.
+    #[instrument(level = "debug", skip(self), fields(latency_ms))]
+    pub async fn set_if_absent<T: RedisModel>(
+        &self,
+        value: &T,
+        expires_after: usize,
+    ) -> Result<bool, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let mut conn = self.conn().await?;
+        let key = value.key();
+
+        trace!(value = ?value, "setting value for key {} if absent", key);
+        let result: Option<String> = redis::cmd("SET")
+            .arg(key)
+            .arg(value.serialize_model()?)
+            .arg("NX")
+            .arg("EX")
+            .arg(expires_after)
+            .query_async(&mut *conn)
+            .await?;
+
+        Ok(result.is_some())
+    }
+
     /// Delete a value from the cache.
-    #[instrument(skip(self))]
+    #[instrument(level = "debug", skip(self), fields(latency_ms))]
     pub async fn delete<T: RedisModel>(&self, value: &T) -> Result<(), anyhow::Error> {
+        let _latency = QueryLatency::start();
         let mut conn = self.conn().await?;
         let key = value.key();
 
@@ -97,6 +178,27 @@ impl CacheClient {
 
         Ok(())
     }
+
+    /// Consume a value from the cache.
+    ///
+    /// The value is retrieved and deleted atomically with `GETDEL`. If several
+    /// consumers (possibly in different processes) consume the same value
+    /// concurrently, only one of them gets `true`: the others must consider
+    /// the value as already handled.
+    #[instrument(level = "debug", skip(self), fields(latency_ms))]
+    pub async fn consume<T: RedisModel>(&self, value: &T) -> Result<bool, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let mut conn = self.conn().await?;
+        let key = value.key();
+
+        trace!("consuming value for key {}", key);
+        let value: Option<Vec<u8>> = redis::cmd("GETDEL")
+            .arg(key)
+            .query_async(&mut *conn)
+            .await?;
+
+        Ok(value.is_some())
+    }
 }
 
 /// Type representing a model stored in the cache.
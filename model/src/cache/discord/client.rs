@@ -1,5 +1,6 @@
 //! Methods to query Discord objects in the cache for [`CacheClient`].
 
+use redis::AsyncCommands;
 use tracing::{instrument, trace};
 use twilight_http::Client as HttpClient;
 use twilight_model::id::{marker::GuildMarker, Id};
@@ -86,4 +87,53 @@ impl CacheClient {
             Ok(Vec::new())
         }
     }
+
+    /// Get all the [`CachedGuild`] in the cache.
+    ///
+    /// This method scans the whole cache and should not be used frequently.
+    #[instrument(skip(self))]
+    pub async fn guilds(&self) -> Result<Vec<CachedGuild>, anyhow::Error> {
+        let mut conn = self.conn().await?;
+        let mut keys: Vec<String> = Vec::new();
+
+        {
+            let mut iter = conn.scan_match::<_, String>("c:guild:*").await?;
+
+            while let Some(key) = iter.next_item().await {
+                keys.push(key);
+            }
+        }
+
+        trace!("querying {} guilds", keys.len());
+        let mut pipe = redis::pipe();
+        for key in &keys {
+            pipe.get(key);
+        }
+
+        let value: Vec<_> = pipe.query_async(&mut *conn).await?;
+
+        value
+            .into_iter()
+            .map(RedisModel::deserialize_model)
+            .collect()
+    }
+
+    /// Get the ids of all the guilds the bot is in.
+    #[instrument(skip(self))]
+    pub async fn guild_ids(&self) -> Result<Vec<Id<GuildMarker>>, anyhow::Error> {
+        let mut conn = self.conn().await?;
+        let ids: Vec<u64> = conn.smembers(CachedGuild::ALL_KEY).await?;
+
+        Ok(ids.into_iter().filter_map(Id::new_checked).collect())
+    }
+
+    /// Get the number of guilds the bot is in.
+    ///
+    /// The count is aggregated across all shards.
+    #[instrument(skip(self))]
+    pub async fn guild_count(&self) -> Result<u64, anyhow::Error> {
+        let mut conn = self.conn().await?;
+
+        Ok(conn.scard(CachedGuild::ALL_KEY).await?)
+    }
 }
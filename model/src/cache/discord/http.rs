@@ -6,10 +6,16 @@
 use anyhow::anyhow;
 use twilight_http::{
     request::{
-        channel::{message::CreateMessage, UpdateChannelPermission},
+        channel::{
+            message::{CreateMessage, DeleteMessage, DeleteMessages},
+            reaction::{DeleteReaction, RequestReactionType},
+            thread::UpdateThread,
+            UpdateChannelPermission,
+        },
         guild::{
-            member::{AddRoleToMember, RemoveMember},
-            CreateGuildChannel,
+            member::{AddRoleToMember, RemoveMember, RemoveRoleFromMember, UpdateGuildMember},
+            role::UpdateRole,
+            CreateGuildChannel, GetAuditLog, UpdateGuild,
         },
     },
     Client as HttpClient,
@@ -18,9 +24,10 @@ use twilight_model::{
     guild::Permissions,
     http::permission_overwrite::PermissionOverwrite,
     id::{
-        marker::{ChannelMarker, GuildMarker, RoleMarker, UserMarker},
+        marker::{ChannelMarker, GuildMarker, MessageMarker, RoleMarker, UserMarker},
         Id,
     },
+    util::Timestamp,
 };
 
 use super::{permission::RoleOrdering, CachedRole};
@@ -66,9 +73,9 @@ impl<'a> CacheHttp<'a> {
         let (permissions, kind) = permissions.current_member().await?.channel(channel).await?;
 
         let send_messages = if kind.is_thread() {
-            Permissions::SEND_MESSAGES
-        } else {
             Permissions::SEND_MESSAGES_IN_THREADS
+        } else {
+            Permissions::SEND_MESSAGES
         };
 
         if !permissions
@@ -171,6 +178,42 @@ impl<'a> CacheHttp<'a> {
             .add_guild_member_role(self.guild_id, user_id, role_id))
     }
 
+    /// Remove a role from a member.
+    ///
+    /// This method ensures that the bot has the [`MANAGE_ROLES`] permission and
+    /// the role to remove is lower than the bot's highest role.
+    ///
+    /// [`MANAGE_ROLES`]: Permissions::MANAGE_ROLES
+    pub async fn remove_guild_member_role(
+        &'a self,
+        user_id: Id<UserMarker>,
+        role_id: Id<RoleMarker>,
+    ) -> Result<RemoveRoleFromMember<'a>, anyhow::Error> {
+        let permissions = self
+            .cache
+            .permissions(self.guild_id)
+            .await?
+            .current_member()
+            .await?;
+
+        if !permissions.guild().contains(Permissions::MANAGE_ROLES) {
+            return Err(anyhow!("missing permissions to remove role from member"));
+        }
+
+        let role = match self.cache.get::<CachedRole>(&role_id).await? {
+            Some(role) => role,
+            None => return Err(anyhow!("role to remove not found")),
+        };
+
+        if RoleOrdering::from(&role) >= permissions.highest_role() {
+            return Err(anyhow!("role to remove is higher than bot's highest role"));
+        }
+
+        Ok(self
+            .http
+            .remove_guild_member_role(self.guild_id, user_id, role_id))
+    }
+
     /// Kick a user from a guild.
     ///
     /// This method ensures that the bot has the [`KICK_MEMBERS`] permission. It
@@ -194,4 +237,304 @@ impl<'a> CacheHttp<'a> {
 
         Ok(self.http.remove_guild_member(self.guild_id, user_id))
     }
+
+    /// Update the guild settings.
+    ///
+    /// This method ensures that the bot has the [`MANAGE_GUILD`] permission.
+    ///
+    /// [`MANAGE_GUILD`]: Permissions::MANAGE_GUILD
+    pub async fn update_guild(&'a self) -> Result<UpdateGuild<'a>, anyhow::Error> {
+        let permissions = self
+            .cache
+            .permissions(self.guild_id)
+            .await?
+            .current_member()
+            .await?;
+
+        if !permissions.guild().contains(Permissions::MANAGE_GUILD) {
+            return Err(anyhow!("missing permissions to update guild"));
+        }
+
+        Ok(self.http.update_guild(self.guild_id))
+    }
+
+    /// Get the guild audit log.
+    ///
+    /// This method ensures that the bot has the [`VIEW_AUDIT_LOG`] permission.
+    ///
+    /// [`VIEW_AUDIT_LOG`]: Permissions::VIEW_AUDIT_LOG
+    pub async fn audit_log(&'a self) -> Result<GetAuditLog<'a>, anyhow::Error> {
+        let permissions = self
+            .cache
+            .permissions(self.guild_id)
+            .await?
+            .current_member()
+            .await?;
+
+        if !permissions.guild().contains(Permissions::VIEW_AUDIT_LOG) {
+            return Err(anyhow!("missing permissions to view audit log"));
+        }
+
+        Ok(self.http.audit_log(self.guild_id))
+    }
+
+    /// Update a role.
+    ///
+    /// This method ensures that the bot has the [`MANAGE_ROLES`] permission and
+    /// the role to update is lower than the bot's highest role.
+    ///
+    /// [`MANAGE_ROLES`]: Permissions::MANAGE_ROLES
+    pub async fn update_role(
+        &'a self,
+        role_id: Id<RoleMarker>,
+    ) -> Result<UpdateRole<'a>, anyhow::Error> {
+        let permissions = self
+            .cache
+            .permissions(self.guild_id)
+            .await?
+            .current_member()
+            .await?;
+
+        if !permissions.guild().contains(Permissions::MANAGE_ROLES) {
+            return Err(anyhow!("missing permissions to update role"));
+        }
+
+        let role = match self.cache.get::<CachedRole>(&role_id).await? {
+            Some(role) => role,
+            None => return Err(anyhow!("role to update not found")),
+        };
+
+        if RoleOrdering::from(&role) >= permissions.highest_role() {
+            return Err(anyhow!("role to update is higher than bot's highest role"));
+        }
+
+        Ok(self.http.update_role(self.guild_id, role_id))
+    }
+
+    /// Delete a reaction added by a user.
+    ///
+    /// This method ensures that the bot has the [`MANAGE_MESSAGES`] permission
+    /// in the channel.
+    ///
+    /// [`MANAGE_MESSAGES`]: Permissions::MANAGE_MESSAGES
+    pub async fn delete_reaction(
+        &'a self,
+        channel_id: Id<ChannelMarker>,
+        message_id: Id<MessageMarker>,
+        emoji: &'a RequestReactionType<'a>,
+        user_id: Id<UserMarker>,
+    ) -> Result<DeleteReaction<'a>, anyhow::Error> {
+        let (permissions, _) = self
+            .cache
+            .permissions(self.guild_id)
+            .await?
+            .current_member()
+            .await?
+            .channel(channel_id)
+            .await?;
+
+        if !permissions.contains(Permissions::MANAGE_MESSAGES) {
+            return Err(anyhow!("missing permissions to delete reaction"));
+        }
+
+        Ok(self
+            .http
+            .delete_reaction(channel_id, message_id, emoji, user_id))
+    }
+
+    /// Delete a message.
+    ///
+    /// This method ensures that the bot has the [`MANAGE_MESSAGES`] permission
+    /// in the channel.
+    ///
+    /// [`MANAGE_MESSAGES`]: Permissions::MANAGE_MESSAGES
+    pub async fn delete_message(
+        &'a self,
+        channel_id: Id<ChannelMarker>,
+        message_id: Id<MessageMarker>,
+    ) -> Result<DeleteMessage<'a>, anyhow::Error> {
+        self.check_manage_messages(channel_id).await?;
+
+        Ok(self.http.delete_message(channel_id, message_id))
+    }
+
+    /// Delete between 2 and 100 messages at once.
+    ///
+    /// This method ensures that the bot has the [`MANAGE_MESSAGES`] permission
+    /// in the channel. Messages older than two weeks cannot be deleted with
+    /// this endpoint.
+    ///
+    /// [`MANAGE_MESSAGES`]: Permissions::MANAGE_MESSAGES
+    pub async fn delete_messages(
+        &'a self,
+        channel_id: Id<ChannelMarker>,
+        message_ids: &'a [Id<MessageMarker>],
+    ) -> Result<DeleteMessages<'a>, anyhow::Error> {
+        self.check_manage_messages(channel_id).await?;
+
+        Ok(self.http.delete_messages(channel_id, message_ids))
+    }
+
+    /// Ensure the bot has the [`MANAGE_MESSAGES`] permission in a channel.
+    ///
+    /// [`MANAGE_MESSAGES`]: Permissions::MANAGE_MESSAGES
+    async fn check_manage_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<(), anyhow::Error> {
+        let (permissions, _) = self
+            .cache
+            .permissions(self.guild_id)
+            .await?
+            .current_member()
+            .await?
+            .channel(channel_id)
+            .await?;
+
+        if !permissions.contains(Permissions::MANAGE_MESSAGES) {
+            return Err(anyhow!("missing permissions to delete messages"));
+        }
+
+        Ok(())
+    }
+
+    /// Update a thread, such as a forum post.
+    ///
+    /// This method ensures that the bot has the [`MANAGE_THREADS`] permission
+    /// in the thread.
+    ///
+    /// [`MANAGE_THREADS`]: Permissions::MANAGE_THREADS
+    pub async fn update_thread(
+        &'a self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<UpdateThread<'a>, anyhow::Error> {
+        let (permissions, kind) = self
+            .cache
+            .permissions(self.guild_id)
+            .await?
+            .current_member()
+            .await?
+            .channel(channel_id)
+            .await?;
+
+        if !kind.is_thread() {
+            return Err(anyhow!("channel to update is not a thread"));
+        }
+
+        if !permissions.contains(Permissions::MANAGE_THREADS) {
+            return Err(anyhow!("missing permissions to update thread"));
+        }
+
+        Ok(self.http.update_thread(channel_id))
+    }
+
+    /// Timeout a member until a given time.
+    ///
+    /// This method ensures that the bot has the [`MODERATE_MEMBERS`] permission.
+    /// It does not check for the role hierarchy.
+    ///
+    /// [`MODERATE_MEMBERS`]: Permissions::MODERATE_MEMBERS
+    pub async fn timeout_member(
+        &'a self,
+        user_id: Id<UserMarker>,
+        until: Timestamp,
+    ) -> Result<UpdateGuildMember<'a>, anyhow::Error> {
+        let permissions = self
+            .cache
+            .permissions(self.guild_id)
+            .await?
+            .current_member()
+            .await?;
+
+        if !permissions.guild().contains(Permissions::MODERATE_MEMBERS) {
+            return Err(anyhow!("missing permissions to timeout member"));
+        }
+
+        Ok(self
+            .http
+            .update_guild_member(self.guild_id, user_id)
+            .communication_disabled_until(Some(until))?)
+    }
+
+    /// Remove the timeout of a member.
+    ///
+    /// This method ensures that the bot has the [`MODERATE_MEMBERS`] permission.
+    /// It does not check for the role hierarchy.
+    ///
+    /// [`MODERATE_MEMBERS`]: Permissions::MODERATE_MEMBERS
+    pub async fn remove_timeout(
+        &'a self,
+        user_id: Id<UserMarker>,
+    ) -> Result<UpdateGuildMember<'a>, anyhow::Error> {
+        let permissions = self
+            .cache
+            .permissions(self.guild_id)
+            .await?
+            .current_member()
+            .await?;
+
+        if !permissions.guild().contains(Permissions::MODERATE_MEMBERS) {
+            return Err(anyhow!("missing permissions to remove member timeout"));
+        }
+
+        Ok(self
+            .http
+            .update_guild_member(self.guild_id, user_id)
+            .communication_disabled_until(None)?)
+    }
+
+    /// Disconnect a member from its voice channel.
+    ///
+    /// This method ensures that the bot has the [`MOVE_MEMBERS`] permission.
+    /// It does not check for the role hierarchy.
+    ///
+    /// [`MOVE_MEMBERS`]: Permissions::MOVE_MEMBERS
+    pub async fn disconnect_member(
+        &'a self,
+        user_id: Id<UserMarker>,
+    ) -> Result<UpdateGuildMember<'a>, anyhow::Error> {
+        let permissions = self
+            .cache
+            .permissions(self.guild_id)
+            .await?
+            .current_member()
+            .await?;
+
+        if !permissions.guild().contains(Permissions::MOVE_MEMBERS) {
+            return Err(anyhow!("missing permissions to disconnect member"));
+        }
+
+        Ok(self
+            .http
+            .update_guild_member(self.guild_id, user_id)
+            .channel_id(None))
+    }
+
+    /// Update the nickname of a member.
+    ///
+    /// This method ensures that the bot has the [`MANAGE_NICKNAMES`] permission.
+    /// It does not check for the role hierarchy.
+    ///
+    /// [`MANAGE_NICKNAMES`]: Permissions::MANAGE_NICKNAMES
+    pub async fn update_nickname(
+        &'a self,
+        user_id: Id<UserMarker>,
+        nick: &'a str,
+    ) -> Result<UpdateGuildMember<'a>, anyhow::Error> {
+        let permissions = self
+            .cache
+            .permissions(self.guild_id)
+            .await?
+            .current_member()
+            .await?;
+
+        if !permissions.guild().contains(Permissions::MANAGE_NICKNAMES) {
+            return Err(anyhow!("missing permissions to update member nickname"));
+        }
+
+        Ok(self
+            .http
+            .update_guild_member(self.guild_id, user_id)
+            .nick(Some(nick))?)
+    }
 }
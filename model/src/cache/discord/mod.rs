@@ -18,6 +18,10 @@
 //! | Roles                 | `RoleCreate`, `RoleUpdate`, `RoleDelete`                          |
 //! | Current user member   | `MemberAdd`, `MemberUpdate`                                       |
 //!
+//! If the cache becomes inconsistent, for example after Redis has been flushed,
+//! the roles and channels of a guild can be rewritten from the HTTP API (see
+//! [`reconcile`]).
+//!
 //! [`Serialize`]: serde::Serialize
 //! [`Deserialize`]: serde::Deserialize
 
@@ -27,6 +31,7 @@ mod process;
 
 pub mod http;
 pub mod permission;
+pub mod reconcile;
 
 pub use model::{
     channel::CachedChannel,
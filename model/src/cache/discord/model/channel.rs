@@ -57,6 +57,13 @@ impl CachedChannel {
         .contains(&self.kind)
     }
 
+    /// Whether the channel is a forum channel.
+    ///
+    /// Posts of a forum channel are threads whose parent is the forum.
+    pub fn is_forum(&self) -> bool {
+        self.kind == ChannelType::GuildForum
+    }
+
     /// Whether a [`ChannelType`] can be cached with this model.
     pub(crate) fn is_cached(kind: ChannelType) -> bool {
         matches!(
@@ -66,6 +73,7 @@ impl CachedChannel {
                 | ChannelType::GuildStageVoice
                 | ChannelType::GuildCategory
                 | ChannelType::GuildNews
+                | ChannelType::GuildForum
                 | ChannelType::GuildPublicThread
                 | ChannelType::GuildPrivateThread
                 | ChannelType::GuildNewsThread
@@ -37,6 +37,12 @@ pub struct CachedGuild {
     /// Id of the guild's owner.
     #[serde_as(as = "IdAsU64")]
     pub owner_id: Id<UserMarker>,
+    /// Number of members in the guild.
+    ///
+    /// This value is only updated when the guild is received from the
+    /// gateway and may be outdated.
+    #[serde(default)]
+    pub member_count: u64,
     /// Information about the bot member in the guild.
     ///
     /// If this field is [`None`], the information has not been
@@ -70,6 +76,14 @@ pub struct CurrentMember {
     pub roles: HashSet<Id<RoleMarker>>,
 }
 
+impl CachedGuild {
+    /// Key of the set of the ids of all the cached guilds.
+    ///
+    /// Since guilds are identified by their id, a guild received by multiple
+    /// shards (e.g. when the shards are restarted) is only counted once.
+    pub const ALL_KEY: &'static str = "c:guilds";
+}
+
 impl RedisModel for CachedGuild {
     type Id = Id<GuildMarker>;
 
@@ -2,10 +2,13 @@
 //!
 //! This module allows to compute in-channel or guild permissions for a given
 //! member using [`twilight_util::permission_calculator`].
+//!
+//! The permissions are calculated from any [`CacheStore`], which defaults to
+//! the Redis [`CacheClient`].
 
-use std::cmp::Ordering;
+use std::{cmp::Ordering, error::Error, fmt};
 
-use anyhow::{anyhow, Context};
+use anyhow::Context;
 use tracing::{instrument, trace};
 use twilight_model::{
     channel::ChannelType,
@@ -17,27 +20,61 @@ use twilight_model::{
 };
 use twilight_util::permission_calculator::PermissionCalculator;
 
-use super::{CachedChannel, CachedGuild, CachedRole};
-use crate::cache::{CacheClient, RedisModel};
+use super::{CachedChannel, CachedGuild, CachedRole, CurrentMember};
+use crate::{cache::CacheClient, store::CacheStore};
+
+/// Error returned when a resource required to calculate permissions is not
+/// found in the cache.
+///
+/// Frequent cache misses may indicate that the cache is inconsistent, for
+/// example after Redis has been flushed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheMiss(pub &'static str);
+
+impl fmt::Display for CacheMiss {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} not found in cache", self.0)
+    }
+}
+
+impl Error for CacheMiss {}
+
+/// Error returned when the bot member of a guild is unknown.
+///
+/// The bot member is required for role hierarchy checks. It is received with
+/// the guild, and may be missing for a short time after the bot has joined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BotMemberUnknown;
+
+impl fmt::Display for BotMemberUnknown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("bot member unknown")
+    }
+}
+
+impl Error for BotMemberUnknown {}
 
 /// Calculate the permissions for a given guild.
-pub struct GuildPermissions<'a> {
-    client: &'a CacheClient,
+pub struct GuildPermissions<'a, C = CacheClient> {
+    client: &'a C,
     guild: CachedGuild,
 }
 
-impl<'a> GuildPermissions<'a> {
-    /// Initialize [`GuildPermissions`] with from a guild.
-    pub(crate) async fn new(
-        client: &'a CacheClient,
+impl<'a, C: CacheStore> GuildPermissions<'a, C> {
+    /// Initialize [`GuildPermissions`] from a guild.
+    ///
+    /// A [`CacheMiss`] error is returned if the guild is not found in the
+    /// cache.
+    pub async fn new(
+        client: &'a C,
         guild_id: Id<GuildMarker>,
-    ) -> Result<GuildPermissions<'a>, anyhow::Error> {
+    ) -> Result<GuildPermissions<'a, C>, anyhow::Error> {
         trace!("initialize permissions for guild {}", guild_id);
 
         if let Some(guild) = client.get::<CachedGuild>(&guild_id).await? {
             Ok(Self { client, guild })
         } else {
-            Err(anyhow!("guild not found in cache"))
+            Err(CacheMiss("guild").into())
         }
     }
 
@@ -47,35 +84,55 @@ impl<'a> GuildPermissions<'a> {
         &self,
         member_id: Id<UserMarker>,
         member_roles: &[Id<RoleMarker>],
-    ) -> Result<CachePermissions<'a>, anyhow::Error> {
+    ) -> Result<CachePermissions<'a, C>, anyhow::Error> {
         CachePermissions::new(self, member_id, member_roles).await
     }
 
     /// Compute permissions for the current bot member.
+    ///
+    /// A [`BotMemberUnknown`] error is returned if the bot member is missing
+    /// from the cached guild.
     #[instrument(skip(self))]
-    pub async fn current_member(&self) -> Result<CachePermissions<'a>, anyhow::Error> {
-        CachePermissions::current_member(self).await
+    pub async fn current_member(&self) -> Result<CachePermissions<'a, C>, anyhow::Error> {
+        let member = self
+            .guild
+            .current_member
+            .as_ref()
+            .ok_or(CacheMiss("current member"))
+            .context(BotMemberUnknown)?;
+
+        CachePermissions::current_member(self, member).await
+    }
+
+    /// Compute permissions for a bot member that is not from the cached guild.
+    ///
+    /// This is used when the bot member has been fetched from the Discord API
+    /// because it was missing from the cache.
+    #[instrument(skip(self))]
+    pub async fn bot_member(
+        &self,
+        member: &CurrentMember,
+    ) -> Result<CachePermissions<'a, C>, anyhow::Error> {
+        CachePermissions::current_member(self, member).await
     }
 }
 
 /// Calculate the permissions of a member with information from the cache.
-pub struct CachePermissions<'a> {
-    client: &'a CacheClient,
+pub struct CachePermissions<'a, C = CacheClient> {
+    client: &'a C,
     guild_id: Id<GuildMarker>,
     member_id: Id<UserMarker>,
     member_roles: MemberRoles,
     is_owner: bool,
 }
 
-impl<'a> CachePermissions<'a> {
+impl<'a, C: CacheStore> CachePermissions<'a, C> {
     /// Initialize [`CachePermissions`] from a cache client.
-    ///
-    /// If the guild is not found in the cache, [`None`] is returned.
     pub(crate) async fn new(
-        guild_permissions: &GuildPermissions<'a>,
+        guild_permissions: &GuildPermissions<'a, C>,
         member_id: Id<UserMarker>,
         member_roles: &[Id<RoleMarker>],
-    ) -> Result<CachePermissions<'a>, anyhow::Error> {
+    ) -> Result<CachePermissions<'a, C>, anyhow::Error> {
         let guild_id = guild_permissions.guild.id;
         let is_owner = member_id == guild_permissions.guild.owner_id;
 
@@ -93,14 +150,9 @@ impl<'a> CachePermissions<'a> {
 
     /// Initialize [`CachePermissions`] for the bot current member.
     pub(crate) async fn current_member(
-        guild_permissions: &GuildPermissions<'a>,
-    ) -> Result<CachePermissions<'a>, anyhow::Error> {
-        let member = guild_permissions
-            .guild
-            .current_member
-            .as_ref()
-            .context("current member not found in cache")?;
-
+        guild_permissions: &GuildPermissions<'a, C>,
+        member: &CurrentMember,
+    ) -> Result<CachePermissions<'a, C>, anyhow::Error> {
         let guild_id = guild_permissions.guild.id;
         let is_owner = member.id == guild_permissions.guild.owner_id;
 
@@ -172,16 +224,19 @@ impl<'a> CachePermissions<'a> {
             .client
             .get::<CachedChannel>(&channel)
             .await?
-            .context("channel not found in cache")?;
+            .ok_or(CacheMiss("channel"))?;
 
-        // If the channel is a thread, get the parent channel.
+        // If the channel is a thread, get the parent channel. The returned
+        // kind is still the kind of the thread, since some operations require
+        // different permissions in threads.
+        let kind = channel.kind;
         if channel.is_thread() {
             if let Some(parent_id) = channel.parent_id {
                 channel = self
                     .client
                     .get::<CachedChannel>(&parent_id)
                     .await?
-                    .context("parent channel not found in cache")?;
+                    .ok_or(CacheMiss("parent channel"))?;
             }
         }
 
@@ -197,9 +252,10 @@ impl<'a> CachePermissions<'a> {
         let calculator =
             PermissionCalculator::new(self.guild_id, self.member_id, everyone_role, &member_roles);
 
-        let kind = channel.kind;
-        let permissions =
-            calculator.in_channel(kind, &channel.permission_overwrites.unwrap_or_default());
+        let permissions = calculator.in_channel(
+            channel.kind,
+            &channel.permission_overwrites.unwrap_or_default(),
+        );
 
         Ok((permissions, kind))
     }
@@ -216,21 +272,19 @@ struct MemberRoles {
 impl MemberRoles {
     /// Query roles of a member in the cache.
     async fn query(
-        client: &CacheClient,
+        client: &impl CacheStore,
         guild_id: Id<GuildMarker>,
         member_roles: impl Iterator<Item = &Id<RoleMarker>>,
     ) -> Result<MemberRoles, anyhow::Error> {
         let everyone_id = guild_id.cast();
 
         // Get user roles
-        let mut pipe = redis::pipe();
-        for role in member_roles.copied().chain([everyone_id]) {
-            pipe.get(CachedRole::key_from(&role));
-        }
-
-        let mut conn = client.conn().await?;
-        let result: Vec<_> = pipe
-            .query_async(&mut *conn)
+        let ids = member_roles
+            .copied()
+            .chain([everyone_id])
+            .collect::<Vec<_>>();
+        let result = client
+            .get_many::<CachedRole>(&ids)
             .await
             .context("failed to query user roles")?;
 
@@ -238,8 +292,8 @@ impl MemberRoles {
         let mut everyone_role = None;
         let mut roles = Vec::new();
 
-        for value in result {
-            let role = CachedRole::deserialize_model(value)?;
+        for role in result {
+            let role = role.ok_or(CacheMiss("role"))?;
 
             if role.id == everyone_id {
                 everyone_role = Some(role);
@@ -251,7 +305,7 @@ impl MemberRoles {
         if let Some(everyone) = everyone_role {
             Ok(MemberRoles { everyone, roles })
         } else {
-            Err(anyhow!("everyone role not found in cache"))
+            Err(CacheMiss("everyone role").into())
         }
     }
 }
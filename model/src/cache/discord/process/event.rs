@@ -1,6 +1,7 @@
 //! Update the cache based on incoming event data.
 
 use async_trait::async_trait;
+use redis::AsyncCommands;
 use tracing::error;
 use twilight_model::{
     gateway::payload::incoming::{
@@ -66,9 +67,11 @@ impl UpdateCache for GuildDelete {
         redis: &CacheClient,
         _current_user: Id<ApplicationMarker>,
     ) -> Result<(), anyhow::Error> {
+        let mut conn = redis.conn().await?;
+        let _: () = conn.srem(CachedGuild::ALL_KEY, self.id.get()).await?;
+
         if let Some(guild) = redis.get::<CachedGuild>(&self.id).await? {
             // Remove all channels and roles from the cache.
-            let mut conn = redis.conn().await?;
             let mut pipe = redis::pipe();
             pipe.del(CachedGuild::key_from(&self.id));
 
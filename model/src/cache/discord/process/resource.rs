@@ -19,6 +19,7 @@ use twilight_model::{
 
 use crate::cache::{
     discord::{CachedChannel, CachedGuild, CachedRole, CurrentMember},
+    model::member_count,
     RedisModel,
 };
 
@@ -68,12 +69,19 @@ pub fn cache_guild(
         name: guild.name.clone(),
         icon: guild.icon,
         owner_id: guild.owner_id,
+        member_count: guild.member_count.unwrap_or_default(),
         current_member,
         roles,
         channels,
     };
 
     pipe.set(cached.key(), cached.serialize_model()?);
+    pipe.sadd(CachedGuild::ALL_KEY, guild.id.get());
+
+    // Reset the live member count to the exact value.
+    if let Some(count) = guild.member_count {
+        pipe.set(member_count::key(guild.id), count);
+    }
 
     Ok(())
 }
@@ -83,7 +91,15 @@ pub fn cache_role(
     role: &Role,
     guild_id: Id<GuildMarker>,
 ) -> Result<(), anyhow::Error> {
-    let cached = CachedRole {
+    let cached = cached_role(role, guild_id);
+    pipe.set(cached.key(), cached.serialize_model()?);
+
+    Ok(())
+}
+
+/// Convert a [`Role`] into a [`CachedRole`].
+pub fn cached_role(role: &Role, guild_id: Id<GuildMarker>) -> CachedRole {
+    CachedRole {
         id: role.id,
         guild_id,
         name: role.name.clone(),
@@ -93,28 +109,31 @@ pub fn cache_role(
         position: role.position,
         permissions: role.permissions,
         managed: role.managed,
-    };
-
-    pipe.set(cached.key(), cached.serialize_model()?);
-
-    Ok(())
+    }
 }
 
 pub fn cache_guild_channel(pipe: &mut Pipeline, channel: &Channel) -> Result<(), anyhow::Error> {
     if CachedChannel::is_cached(channel.kind) {
-        let cached = CachedChannel {
-            id: channel.id,
-            guild_id: channel.guild_id.context("missing guild id")?,
-            kind: channel.kind,
-            name: channel.name.clone().context("missing channel name")?,
-            parent_id: channel.parent_id,
-            permission_overwrites: channel.permission_overwrites.clone(),
-            position: channel.position,
-            rate_limit_per_user: channel.rate_limit_per_user,
-        };
-
+        let cached = cached_channel(channel)?;
         pipe.set(cached.key(), cached.serialize_model()?);
     }
 
     Ok(())
 }
+
+/// Convert a [`Channel`] into a [`CachedChannel`].
+///
+/// This function does not check whether the channel kind is cached (see
+/// [`CachedChannel::is_cached`]).
+pub fn cached_channel(channel: &Channel) -> Result<CachedChannel, anyhow::Error> {
+    Ok(CachedChannel {
+        id: channel.id,
+        guild_id: channel.guild_id.context("missing guild id")?,
+        kind: channel.kind,
+        name: channel.name.clone().context("missing channel name")?,
+        parent_id: channel.parent_id,
+        permission_overwrites: channel.permission_overwrites.clone(),
+        position: channel.position,
+        rate_limit_per_user: channel.rate_limit_per_user,
+    })
+}
@@ -0,0 +1,262 @@
+//! Reconcile the cache of a guild with the Discord API.
+//!
+//! The cache is only updated from gateway events. If it has been flushed or
+//! has missed events (for example after a Redis failover), the roles and
+//! channels of a guild can be rewritten from models fetched with the HTTP API
+//! using [`CacheClient::reconcile_guild`].
+//!
+//! Values already in the cache are compared with the fetched ones, and a
+//! [`ReconcileReport`] is returned. Cached values that no longer exist or
+//! are different indicate that some cache invalidation has been missed.
+
+use std::{collections::HashMap, hash::Hash};
+
+use tracing::{instrument, warn};
+use twilight_model::{
+    channel::Channel,
+    guild::{Guild, Member},
+    id::Id,
+};
+
+use super::{
+    process::resource::{cached_channel, cached_role},
+    CachedChannel, CachedGuild, CachedRole, CurrentMember,
+};
+use crate::cache::{CacheClient, RedisModel};
+
+/// Summary of a guild reconciliation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReconcileReport {
+    /// Reconciliation of the guild roles.
+    pub roles: ResourceReport,
+    /// Reconciliation of the guild channels.
+    pub channels: ResourceReport,
+}
+
+impl ReconcileReport {
+    /// Total number of discrepancies found.
+    pub fn discrepancies(&self) -> usize {
+        self.roles.discrepancies() + self.channels.discrepancies()
+    }
+}
+
+/// Summary of the reconciliation of a type of resource.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceReport {
+    /// Number of resources written in the cache.
+    pub refreshed: usize,
+    /// Number of resources that were not cached.
+    pub missing: usize,
+    /// Number of cached resources that no longer exist.
+    pub stale: usize,
+    /// Number of cached resources that were different.
+    pub outdated: usize,
+}
+
+impl ResourceReport {
+    /// Compare cached resources with the fetched ones.
+    fn compare<K, V>(cached: &HashMap<K, V>, fetched: &HashMap<K, V>) -> Self
+    where
+        K: Eq + Hash,
+        V: PartialEq,
+    {
+        let mut report = Self {
+            refreshed: fetched.len(),
+            ..Self::default()
+        };
+
+        for (id, value) in fetched {
+            match cached.get(id) {
+                Some(cached) if cached != value => report.outdated += 1,
+                Some(_) => {}
+                None => report.missing += 1,
+            }
+        }
+
+        report.stale = cached.keys().filter(|id| !fetched.contains_key(id)).count();
+
+        report
+    }
+
+    /// Number of discrepancies found.
+    ///
+    /// Missing resources are not counted since they are expected after the
+    /// cache has been flushed.
+    pub fn discrepancies(&self) -> usize {
+        self.stale + self.outdated
+    }
+}
+
+impl CacheClient {
+    /// Rewrite the cached roles and channels of a guild.
+    ///
+    /// The `guild` must have been fetched with the HTTP API. Its channels and
+    /// members are ignored and replaced by `channels` and `current_member`.
+    /// Cached threads are kept since they are not returned with the guild
+    /// channels.
+    ///
+    /// All the values are written in a single atomic pipeline.
+    #[instrument(skip_all, fields(guild_id = %guild.id))]
+    pub async fn reconcile_guild(
+        &self,
+        guild: &Guild,
+        channels: &[Channel],
+        current_member: &Member,
+    ) -> Result<ReconcileReport, anyhow::Error> {
+        let cached_guild = self.get::<CachedGuild>(&guild.id).await?;
+
+        let (cached_roles, cached_channels) = match &cached_guild {
+            Some(cached) => (
+                self.get_many::<CachedRole, _>(cached.roles.iter()).await?,
+                self.get_many::<CachedChannel, _>(cached.channels.iter())
+                    .await?,
+            ),
+            None => (Vec::new(), Vec::new()),
+        };
+
+        let (threads, cached_channels): (Vec<_>, Vec<_>) = cached_channels
+            .into_iter()
+            .partition(CachedChannel::is_thread);
+
+        let cached_roles: HashMap<_, _> = cached_roles.into_iter().map(|r| (r.id, r)).collect();
+        let cached_channels: HashMap<_, _> =
+            cached_channels.into_iter().map(|c| (c.id, c)).collect();
+
+        let roles: HashMap<_, _> = guild
+            .roles
+            .iter()
+            .map(|role| (role.id, cached_role(role, guild.id)))
+            .collect();
+        let channels: HashMap<_, _> = channels
+            .iter()
+            .filter(|channel| CachedChannel::is_cached(channel.kind))
+            .filter_map(|channel| match cached_channel(channel) {
+                Ok(cached) => Some((cached.id, cached)),
+                Err(error) => {
+                    warn!(error = ?error, channel = ?channel.id, "failed to convert guild channel");
+                    None
+                }
+            })
+            .collect();
+
+        let report = ReconcileReport {
+            roles: ResourceReport::compare(&cached_roles, &roles),
+            channels: ResourceReport::compare(&cached_channels, &channels),
+        };
+
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+
+        for id in cached_roles.keys().filter(|id| !roles.contains_key(id)) {
+            pipe.del(CachedRole::key_from(id));
+        }
+        for id in cached_channels
+            .keys()
+            .filter(|id| !channels.contains_key(id))
+        {
+            pipe.del(CachedChannel::key_from(id));
+        }
+
+        for role in roles.values() {
+            pipe.set(role.key(), role.serialize_model()?);
+        }
+        for channel in channels.values() {
+            pipe.set(channel.key(), channel.serialize_model()?);
+        }
+
+        // The member count is not returned by the HTTP API without the
+        // approximate counts, the cached value is kept in that case.
+        let member_count = guild
+            .member_count
+            .or(guild.approximate_member_count)
+            .or_else(|| cached_guild.as_ref().map(|cached| cached.member_count))
+            .unwrap_or_default();
+
+        let cached = CachedGuild {
+            id: guild.id,
+            unavailable: false,
+            name: guild.name.clone(),
+            icon: guild.icon,
+            owner_id: guild.owner_id,
+            member_count,
+            current_member: Some(CurrentMember {
+                id: current_member.user.id,
+                communication_disabled_until: current_member.communication_disabled_until,
+                roles: current_member.roles.iter().copied().collect(),
+            }),
+            roles: roles.keys().copied().collect(),
+            channels: channels
+                .keys()
+                .copied()
+                .chain(threads.iter().map(|thread| thread.id))
+                .collect(),
+        };
+
+        pipe.set(cached.key(), cached.serialize_model()?);
+        pipe.sadd(CachedGuild::ALL_KEY, guild.id.get());
+
+        let mut conn = self.conn().await?;
+        pipe.query_async::<_, ()>(&mut *conn).await?;
+
+        Ok(report)
+    }
+
+    /// Get several values from the cache.
+    ///
+    /// Values that are not found are skipped.
+    async fn get_many<'a, T, M: 'a>(
+        &self,
+        ids: impl Iterator<Item = &'a Id<M>>,
+    ) -> Result<Vec<T>, anyhow::Error>
+    where
+        T: RedisModel<Id = Id<M>>,
+    {
+        let mut pipe = redis::pipe();
+        for id in ids {
+            pipe.get(T::key_from(id));
+        }
+
+        let mut conn = self.conn().await?;
+        let values: Vec<Option<Vec<u8>>> = pipe.query_async(&mut *conn).await?;
+
+        values
+            .into_iter()
+            .flatten()
+            .map(RedisModel::deserialize_model)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare() {
+        let cached = HashMap::from([(1, "a"), (2, "b"), (3, "c")]);
+        let fetched = HashMap::from([(1, "a"), (2, "changed"), (4, "d")]);
+
+        let report = ResourceReport::compare(&cached, &fetched);
+
+        assert_eq!(
+            report,
+            ResourceReport {
+                refreshed: 3,
+                missing: 1,
+                stale: 1,
+                outdated: 1,
+            }
+        );
+        assert_eq!(report.discrepancies(), 2);
+    }
+
+    #[test]
+    fn test_compare_empty_cache() {
+        let fetched = HashMap::from([(1, "a"), (2, "b")]);
+
+        let report = ResourceReport::compare(&HashMap::new(), &fetched);
+
+        assert_eq!(report.missing, 2);
+        assert_eq!(report.discrepancies(), 0);
+    }
+}
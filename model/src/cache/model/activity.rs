@@ -0,0 +1,60 @@
+//! Message counts of the activity role.
+//!
+//! The number of messages sent by each member is stored in a Redis hash per
+//! guild, with a field per member. The counts of a guild expire after
+//! [`ACTIVITY_RETENTION`] without new messages.
+
+use redis::AsyncCommands;
+use tracing::instrument;
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+
+use crate::cache::CacheClient;
+
+/// Duration (in seconds) after which the counts of an inactive guild expire.
+pub const ACTIVITY_RETENTION: usize = 30 * 24 * 60 * 60;
+
+/// Get the key of the message counts of a guild.
+fn activity_key(guild_id: Id<GuildMarker>) -> String {
+    format!("activity:{guild_id}")
+}
+
+// Implementation of methods to manage the message counts.
+impl CacheClient {
+    /// Increment the message count of a member.
+    ///
+    /// Returns the new message count.
+    #[instrument(skip(self))]
+    pub async fn increment_activity(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<u32, anyhow::Error> {
+        let mut conn = self.conn().await?;
+        let key = activity_key(guild_id);
+
+        let (count,): (u32,) = redis::pipe()
+            .hincr(&key, user_id.get(), 1)
+            .expire(&key, ACTIVITY_RETENTION)
+            .ignore()
+            .query_async(&mut *conn)
+            .await?;
+
+        Ok(count)
+    }
+
+    /// Reset the message count of a member.
+    #[instrument(skip(self))]
+    pub async fn reset_activity(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<(), anyhow::Error> {
+        let mut conn = self.conn().await?;
+        let _: () = conn.hdel(activity_key(guild_id), user_id.get()).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+//! Deletions tracking.
+//!
+//! Recent channel and role deletions of each user are stored in a Redis sorted
+//! set, using the time at which the deletion happened as score. Deletions are
+//! identified by their audit log entry id, so a deletion tracked twice is only
+//! counted once.
+
+use redis::AsyncCommands;
+use time::{Duration, OffsetDateTime};
+use tracing::instrument;
+use twilight_model::id::{
+    marker::{AuditLogEntryMarker, GuildMarker, UserMarker},
+    Id,
+};
+use twilight_util::snowflake::Snowflake;
+
+use crate::cache::CacheClient;
+
+/// Get the key of the set of recent deletions of a user.
+fn key(guild_id: Id<GuildMarker>, user_id: Id<UserMarker>) -> String {
+    format!("deletions:{guild_id}:{user_id}")
+}
+
+// Implementation of methods to track deletions.
+impl CacheClient {
+    /// Track deletions made by a user.
+    ///
+    /// Returns the number of deletions made by the user during the last
+    /// `interval`, including the tracked ones.
+    #[instrument(skip(self))]
+    pub async fn track_deletions(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+        entries: &[Id<AuditLogEntryMarker>],
+        interval: Duration,
+    ) -> Result<usize, anyhow::Error> {
+        let mut conn = self.conn().await?;
+        let key = key(guild_id, user_id);
+
+        let now = OffsetDateTime::now_utc();
+        let now_millis = (now.unix_timestamp_nanos() / 1_000_000) as i64;
+        let interval_millis = interval.whole_milliseconds() as i64;
+
+        let mut pipe = redis::pipe();
+        for entry in entries {
+            pipe.zadd(&key, entry.get(), entry.timestamp()).ignore();
+        }
+
+        let (count,): (usize,) = pipe
+            .zrembyscore(&key, "-inf", now_millis - interval_millis)
+            .ignore()
+            .zcard(&key)
+            .expire(&key, interval.whole_seconds() as usize + 1)
+            .ignore()
+            .query_async(&mut *conn)
+            .await?;
+
+        Ok(count)
+    }
+
+    /// Remove the tracked deletions of a user.
+    #[instrument(skip(self))]
+    pub async fn clear_deletions(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<(), anyhow::Error> {
+        let mut conn = self.conn().await?;
+        let _: () = conn.del(key(guild_id, user_id)).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+//! Web API sessions.
+//!
+//! Requests to the web API are authenticated with a Discord OAuth access
+//! token. The user associated with a token is cached to avoid querying the
+//! Discord API on each request.
+//!
+//! Tokens are stored hashed, the cached sessions cannot be used to recover
+//! the original tokens.
+
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use twilight_model::id::{marker::UserMarker, Id};
+
+use crate::{cache::RedisModel, serde::IdAsU64};
+
+/// User authenticated with an access token.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ApiSession {
+    /// Hash of the access token.
+    pub token_hash: String,
+    /// Id of the user that owns the token.
+    #[serde_as(as = "IdAsU64")]
+    pub user_id: Id<UserMarker>,
+}
+
+impl RedisModel for ApiSession {
+    type Id = str;
+
+    /// Sessions expire after 10 minutes, so that revoked tokens are not
+    /// accepted for too long.
+    const EXPIRES_AFTER: Option<usize> = Some(10 * 60);
+
+    fn key(&self) -> String {
+        Self::key_from(&self.token_hash)
+    }
+
+    fn key_from(token_hash: &Self::Id) -> String {
+        format!("api:session:{token_hash}")
+    }
+}
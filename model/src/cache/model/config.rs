@@ -0,0 +1,24 @@
+//! Guild configuration cache.
+//!
+//! The guild configuration is stored in the database, but a copy is kept in
+//! the cache to be used when the database is unavailable. The cached value may
+//! be outdated and must not be used to update the configuration.
+
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::{cache::RedisModel, database::model::GuildConfig};
+
+impl RedisModel for GuildConfig {
+    type Id = Id<GuildMarker>;
+
+    /// Cached configurations expire after 7 days.
+    const EXPIRES_AFTER: Option<usize> = Some(7 * 24 * 60 * 60);
+
+    fn key(&self) -> String {
+        Self::key_from(&self.id)
+    }
+
+    fn key_from(id: &Self::Id) -> String {
+        format!("config:guild:{id}")
+    }
+}
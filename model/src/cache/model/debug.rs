@@ -0,0 +1,44 @@
+//! Debug guilds.
+//!
+//! The logs of some guilds can be raised to the `DEBUG` level to investigate
+//! issues (see [`crate::log`]). The list of these guilds is stored in a Redis
+//! set shared by all the instances of the bot.
+
+use redis::AsyncCommands;
+use tracing::instrument;
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::cache::CacheClient;
+
+/// Key of the debug guilds set.
+const DEBUG_GUILDS_KEY: &str = "debug:guilds";
+
+// Implementation of methods to manage debug guilds.
+impl CacheClient {
+    /// Get the guilds whose logs are raised to the `DEBUG` level.
+    #[instrument(skip(self))]
+    pub async fn debug_guilds(&self) -> Result<Vec<Id<GuildMarker>>, anyhow::Error> {
+        let mut conn = self.conn().await?;
+        let guilds: Vec<u64> = conn.smembers(DEBUG_GUILDS_KEY).await?;
+
+        Ok(guilds.into_iter().filter_map(Id::new_checked).collect())
+    }
+
+    /// Enable or disable the `DEBUG` logs of a guild.
+    #[instrument(skip(self))]
+    pub async fn set_debug_guild(
+        &self,
+        guild: Id<GuildMarker>,
+        enabled: bool,
+    ) -> Result<(), anyhow::Error> {
+        let mut conn = self.conn().await?;
+
+        if enabled {
+            let _: () = conn.sadd(DEBUG_GUILDS_KEY, guild.get()).await?;
+        } else {
+            let _: () = conn.srem(DEBUG_GUILDS_KEY, guild.get()).await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+//! First message tracking.
+//!
+//! Members are marked when they join a guild, and the mark is removed when
+//! they send their first message. Marks expire after [`FIRST_MESSAGE_EXPIRES`],
+//! so only the first message of recent members is checked.
+
+use redis::AsyncCommands;
+use tracing::instrument;
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+
+use crate::cache::CacheClient;
+
+/// Duration (in seconds) after which the mark of a member expires.
+pub const FIRST_MESSAGE_EXPIRES: usize = 24 * 60 * 60;
+
+/// Get the key of the mark of a member.
+fn key(guild_id: Id<GuildMarker>, user_id: Id<UserMarker>) -> String {
+    format!("first_message:{guild_id}:{user_id}")
+}
+
+// Implementation of methods to track the first message of members.
+impl CacheClient {
+    /// Mark a member that has not sent any message yet.
+    #[instrument(skip(self))]
+    pub async fn mark_first_message(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<(), anyhow::Error> {
+        let mut conn = self.conn().await?;
+        let _: () = conn
+            .set_ex(key(guild_id, user_id), 1, FIRST_MESSAGE_EXPIRES)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Remove the mark of a member.
+    ///
+    /// Returns `true` if the member was marked, in which case the message
+    /// being processed is their first message.
+    #[instrument(skip(self))]
+    pub async fn take_first_message(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<bool, anyhow::Error> {
+        let mut conn = self.conn().await?;
+        let removed: usize = conn.del(key(guild_id, user_id)).await?;
+
+        Ok(removed > 0)
+    }
+}
@@ -1,12 +1,21 @@
 //! State for interactions (buttons, select menus, modals).
+//!
+//! The state of components that perform an action is stored until the
+//! component is used. Each state records the users authorized to use the
+//! component (see [`PendingComponent`]).
 
+use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
-use serde_with::serde_as;
+use serde_with::{serde_as, DisplayFromStr};
 use time::OffsetDateTime;
 use twilight_model::{
+    channel::embed::Embed,
+    guild::Permissions,
     http::interaction::InteractionResponseData,
     id::{
-        marker::{GuildMarker, InteractionMarker, UserMarker},
+        marker::{
+            ChannelMarker, GuildMarker, InteractionMarker, MessageMarker, RoleMarker, UserMarker,
+        },
         Id,
     },
     user::User,
@@ -14,16 +23,28 @@ use twilight_model::{
 
 use crate::{
     cache::RedisModel,
-    database::model::ModlogType,
+    database::model::{ModlogType, ModlogUser},
     serde::{DateTimeAsI64, IdAsU64},
 };
 
+/// State of a pending component.
+///
+/// A component custom id may be reused by another user than the one it was
+/// sent to, which could allow to perform actions on its behalf. The
+/// interaction dispatcher checks that the user is authorized before running
+/// the component handler.
+pub trait PendingComponent: RedisModel {
+    /// Check whether a user is authorized to use the component.
+    fn is_authorized(&self, user: Id<UserMarker>) -> bool;
+}
+
 /// State for the "post in chat" button.
 #[serde_as]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PostInChatButton {
-    /// Response to send to the channel.
-    pub response: InteractionResponseData,
+    /// Message to post in the channel.
+    #[serde(flatten)]
+    pub message: PostInChatMessage,
     /// Initial interaction ID.
     pub interaction_id: Id<InteractionMarker>,
     /// Id of the initial interaction author.
@@ -31,6 +52,64 @@ pub struct PostInChatButton {
     pub author_id: Id<UserMarker>,
 }
 
+/// Message posted with the "post in chat" button.
+///
+/// Messages are rendered when the button is clicked, so that the public
+/// message uses the current guild configuration. Buttons created before this
+/// change stored the rendered response, and are still posted as-is until they
+/// expire.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum PostInChatMessage {
+    /// Message rendered from a template.
+    Template {
+        /// Id of the sanction case, if the message is about a sanction.
+        #[serde_as(as = "Option<DisplayFromStr>")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        case_id: Option<ObjectId>,
+        /// Template of the public message.
+        template: PublicMessageTemplate,
+    },
+    /// Legacy rendered response.
+    Legacy {
+        /// Response to send to the channel.
+        response: InteractionResponseData,
+    },
+}
+
+/// Template of a message posted with the "post in chat" button.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PublicMessageTemplate {
+    /// Sanction applied to a member.
+    ///
+    /// Each [`ModlogType`] has its own message.
+    Sanction {
+        /// Type of sanction.
+        kind: ModlogType,
+        /// Sanctioned user.
+        user: ModlogUser,
+        /// Moderator that applied the sanction.
+        ///
+        /// If [`None`], the moderator is hidden and the message shows the
+        /// moderation team instead (see [`ModerationConfig::anonymize`]).
+        ///
+        /// [`ModerationConfig::anonymize`]: crate::database::model::ModerationConfig::anonymize
+        #[serde(default)]
+        moderator: Option<ModlogUser>,
+        /// Reason of the sanction.
+        reason: Option<String>,
+    },
+    /// Profile of a user.
+    Profile {
+        /// User whose profile is shown.
+        user: ModlogUser,
+        /// When the user joined the guild, in seconds.
+        joined_at: Option<u64>,
+    },
+}
+
 impl RedisModel for PostInChatButton {
     type Id = str;
 
@@ -46,6 +125,12 @@ impl RedisModel for PostInChatButton {
     }
 }
 
+impl PendingComponent for PostInChatButton {
+    fn is_authorized(&self, user: Id<UserMarker>) -> bool {
+        self.author_id == user
+    }
+}
+
 /// State for a pending captcha.
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,6 +152,25 @@ pub struct PendingCaptcha {
     /// Time at which the captcha expires.
     #[serde_as(as = "DateTimeAsI64")]
     pub expires_at: OffsetDateTime,
+    /// Kind of the current challenge.
+    #[serde(default)]
+    pub kind: ChallengeKind,
+}
+
+/// Kind of challenge sent to a member that solves a captcha.
+///
+/// Image challenges are used by default. When too many images are rendered,
+/// members are asked to solve an arithmetic question instead. Both kinds share
+/// the same [`PendingCaptcha`], so attempts and expiration are tracked the same
+/// way.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChallengeKind {
+    /// Code displayed in a generated image.
+    #[default]
+    Image,
+    /// Simple arithmetic question, whose answer is the code.
+    Arithmetic,
 }
 
 impl RedisModel for PendingCaptcha {
@@ -89,6 +193,12 @@ impl RedisModel for PendingCaptcha {
     }
 }
 
+impl PendingComponent for PendingCaptcha {
+    fn is_authorized(&self, user: Id<UserMarker>) -> bool {
+        self.member_id == user
+    }
+}
+
 /// State for a pending sanction modal.
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -96,10 +206,36 @@ pub struct PendingSanction {
     /// Initial interaction ID.
     #[serde_as(as = "IdAsU64")]
     pub interaction_id: Id<InteractionMarker>,
+    /// Id of the moderator that initiated the sanction.
+    #[serde_as(as = "IdAsU64")]
+    pub author_id: Id<UserMarker>,
     /// Type of the pending modlog.
     pub kind: ModlogType,
     /// User targeted by the sanction.
     pub user: User,
+    /// Reason specified in the command, if any.
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Duration of the sanction (in minutes), for temporary sanctions.
+    #[serde(default)]
+    pub duration: Option<u32>,
+    /// Whether the user is not a member of the guild, for pre-emptive bans.
+    #[serde(default)]
+    pub pre_emptive: bool,
+    /// Period (in minutes) during which the messages of the user are deleted
+    /// after the sanction.
+    #[serde(default)]
+    pub cleanup: Option<u32>,
+    /// Whether the confirmation has been skipped although the user has just
+    /// been sanctioned.
+    #[serde(default)]
+    pub confirmation_skipped: bool,
+    /// Whether the sanction is a shadow sanction.
+    #[serde(default)]
+    pub shadow: bool,
+    /// Whether the ban is synchronized with the linked guilds.
+    #[serde(default)]
+    pub sync: bool,
 }
 
 impl RedisModel for PendingSanction {
@@ -116,3 +252,541 @@ impl RedisModel for PendingSanction {
         format!("pending:sanction:{id}")
     }
 }
+
+impl PendingComponent for PendingSanction {
+    fn is_authorized(&self, user: Id<UserMarker>) -> bool {
+        self.author_id == user
+    }
+}
+
+/// State for a pending bulk ban confirmation.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingBulkBan {
+    /// Initial interaction ID.
+    #[serde_as(as = "IdAsU64")]
+    pub interaction_id: Id<InteractionMarker>,
+    /// Id of the moderator that initiated the bulk ban.
+    #[serde_as(as = "IdAsU64")]
+    pub author_id: Id<UserMarker>,
+    /// Id of the guild.
+    #[serde_as(as = "IdAsU64")]
+    pub guild_id: Id<GuildMarker>,
+    /// Users to ban.
+    #[serde_as(as = "Vec<IdAsU64>")]
+    pub user_ids: Vec<Id<UserMarker>>,
+    /// Reason of the ban, if any.
+    pub reason: Option<String>,
+}
+
+impl RedisModel for PendingBulkBan {
+    type Id = str;
+
+    // Pending confirmations expires after 5 minutes
+    const EXPIRES_AFTER: Option<usize> = Some(5 * 60);
+
+    fn key(&self) -> String {
+        Self::key_from(&self.interaction_id.to_string())
+    }
+
+    fn key_from(id: &Self::Id) -> String {
+        format!("pending:bulk-ban:{id}")
+    }
+}
+
+impl PendingComponent for PendingBulkBan {
+    fn is_authorized(&self, user: Id<UserMarker>) -> bool {
+        self.author_id == user
+    }
+}
+
+/// State for a pending bulk unban confirmation.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingBulkUnban {
+    /// Initial interaction ID.
+    #[serde_as(as = "IdAsU64")]
+    pub interaction_id: Id<InteractionMarker>,
+    /// Id of the moderator that initiated the bulk unban.
+    #[serde_as(as = "IdAsU64")]
+    pub author_id: Id<UserMarker>,
+    /// Id of the guild.
+    #[serde_as(as = "IdAsU64")]
+    pub guild_id: Id<GuildMarker>,
+    /// Users to unban.
+    #[serde_as(as = "Vec<IdAsU64>")]
+    pub user_ids: Vec<Id<UserMarker>>,
+    /// Ids of the moderation logs of the bans, with the banned user.
+    #[serde_as(as = "Vec<(IdAsU64, _)>")]
+    pub cases: Vec<(Id<UserMarker>, ObjectId)>,
+    /// Reason of the unban, if any.
+    pub reason: Option<String>,
+}
+
+impl RedisModel for PendingBulkUnban {
+    type Id = str;
+
+    // Pending confirmations expires after 5 minutes
+    const EXPIRES_AFTER: Option<usize> = Some(5 * 60);
+
+    fn key(&self) -> String {
+        Self::key_from(&self.interaction_id.to_string())
+    }
+
+    fn key_from(id: &Self::Id) -> String {
+        format!("pending:bulk-unban:{id}")
+    }
+}
+
+impl PendingComponent for PendingBulkUnban {
+    fn is_authorized(&self, user: Id<UserMarker>) -> bool {
+        self.author_id == user
+    }
+}
+
+/// State for the "strip role" button of sentinel alerts.
+///
+/// If `member_id` is set, the role is removed from the member. Otherwise, the
+/// dangerous permissions are removed from the role.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingRoleStrip {
+    /// Id of the guild.
+    #[serde_as(as = "IdAsU64")]
+    pub guild_id: Id<GuildMarker>,
+    /// Role to strip.
+    #[serde_as(as = "IdAsU64")]
+    pub role_id: Id<RoleMarker>,
+    /// Member the role has been granted to.
+    #[serde_as(as = "Option<IdAsU64>")]
+    #[serde(default)]
+    pub member_id: Option<Id<UserMarker>>,
+    /// Dangerous permissions granted to the role.
+    pub permissions: Permissions,
+    /// Id of the user that made the change, if known.
+    #[serde_as(as = "Option<IdAsU64>")]
+    #[serde(default)]
+    pub actor_id: Option<Id<UserMarker>>,
+}
+
+impl PendingRoleStrip {
+    /// Get the id of the pending component, used in the button custom id.
+    pub fn id(&self) -> String {
+        match self.member_id {
+            Some(member_id) => format!("{}-{}-{member_id}", self.guild_id, self.role_id),
+            None => format!("{}-{}", self.guild_id, self.role_id),
+        }
+    }
+}
+
+impl RedisModel for PendingRoleStrip {
+    type Id = str;
+
+    // Alerts may be read late, the button expires after a day
+    const EXPIRES_AFTER: Option<usize> = Some(24 * 60 * 60);
+
+    fn key(&self) -> String {
+        Self::key_from(&self.id())
+    }
+
+    fn key_from(id: &Self::Id) -> String {
+        format!("pending:role-strip:{id}")
+    }
+}
+
+impl PendingComponent for PendingRoleStrip {
+    /// The button is sent in the logs channel, any moderator may use it
+    /// except the user that made the change. Permissions of the user are
+    /// checked by the component handler.
+    fn is_authorized(&self, user: Id<UserMarker>) -> bool {
+        self.actor_id != Some(user)
+    }
+}
+
+/// State of the review buttons of a member held by the raid mode.
+///
+/// Members with a recent account are timed out and posted in the review
+/// channel when the raid mode is configured to hold them for review. The
+/// state is kept for the duration of the timeout, so that moderators can
+/// review the member after a restart of the bot.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingJoinReview {
+    /// Id of the guild.
+    #[serde_as(as = "IdAsU64")]
+    pub guild_id: Id<GuildMarker>,
+    /// Member held for review.
+    #[serde_as(as = "IdAsU64")]
+    pub user_id: Id<UserMarker>,
+}
+
+impl PendingJoinReview {
+    /// Duration (in seconds) of the review.
+    ///
+    /// This is also the duration of the timeout applied to the member.
+    pub const DURATION: usize = 7 * 24 * 60 * 60;
+
+    /// Get the id of the pending component, used in the button custom id.
+    pub fn id(&self) -> String {
+        format!("{}-{}", self.guild_id, self.user_id)
+    }
+}
+
+impl RedisModel for PendingJoinReview {
+    type Id = str;
+
+    // The review ends with the timeout of the member
+    const EXPIRES_AFTER: Option<usize> = Some(Self::DURATION);
+
+    fn key(&self) -> String {
+        Self::key_from(&self.id())
+    }
+
+    fn key_from(id: &Self::Id) -> String {
+        format!("pending:join-review:{id}")
+    }
+}
+
+impl PendingComponent for PendingJoinReview {
+    /// The buttons are sent in the review channel, any moderator may use
+    /// them except the member under review. Permissions of the user are
+    /// checked by the component handler.
+    fn is_authorized(&self, user: Id<UserMarker>) -> bool {
+        self.user_id != user
+    }
+}
+
+/// State of the adjustment buttons of an automatic timeout.
+///
+/// The buttons are attached to the log of the filter that timed out the
+/// member. The state is kept for the maximum duration of a timeout, the
+/// handler checks whether the timeout is still active.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingTimeoutAdjust {
+    /// Id of the guild.
+    #[serde_as(as = "IdAsU64")]
+    pub guild_id: Id<GuildMarker>,
+    /// Member timed out.
+    #[serde_as(as = "IdAsU64")]
+    pub user_id: Id<UserMarker>,
+    /// Id of the moderation log of the timeout.
+    pub case_id: ObjectId,
+}
+
+impl RedisModel for PendingTimeoutAdjust {
+    type Id = str;
+
+    // Timeouts last at most 28 days
+    const EXPIRES_AFTER: Option<usize> = Some(28 * 24 * 60 * 60);
+
+    fn key(&self) -> String {
+        Self::key_from(&self.case_id.to_hex())
+    }
+
+    fn key_from(id: &Self::Id) -> String {
+        format!("pending:timeout-adjust:{id}")
+    }
+}
+
+impl PendingComponent for PendingTimeoutAdjust {
+    /// The buttons are sent in the logs channel, any moderator may use them
+    /// except the member timed out. Permissions of the user are checked by
+    /// the component handler.
+    fn is_authorized(&self, user: Id<UserMarker>) -> bool {
+        self.user_id != user
+    }
+}
+
+/// Kind of items restored from a structure snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestoreKind {
+    /// Deleted channels are recreated.
+    Channels,
+    /// Deleted roles are recreated.
+    Roles,
+}
+
+/// State for a pending restore confirmation.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingRestore {
+    /// Initial interaction ID.
+    #[serde_as(as = "IdAsU64")]
+    pub interaction_id: Id<InteractionMarker>,
+    /// Id of the administrator that initiated the restore.
+    #[serde_as(as = "IdAsU64")]
+    pub author_id: Id<UserMarker>,
+    /// Id of the guild.
+    #[serde_as(as = "IdAsU64")]
+    pub guild_id: Id<GuildMarker>,
+    /// Kind of items to restore.
+    pub kind: RestoreKind,
+    /// Id of the snapshot the items are restored from.
+    pub snapshot_id: ObjectId,
+}
+
+impl RedisModel for PendingRestore {
+    type Id = str;
+
+    // Pending confirmations expires after 5 minutes
+    const EXPIRES_AFTER: Option<usize> = Some(5 * 60);
+
+    fn key(&self) -> String {
+        Self::key_from(&self.interaction_id.to_string())
+    }
+
+    fn key_from(id: &Self::Id) -> String {
+        format!("pending:restore:{id}")
+    }
+}
+
+impl PendingComponent for PendingRestore {
+    fn is_authorized(&self, user: Id<UserMarker>) -> bool {
+        self.author_id == user
+    }
+}
+
+/// State for a pending preset confirmation.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingPreset {
+    /// Initial interaction ID.
+    #[serde_as(as = "IdAsU64")]
+    pub interaction_id: Id<InteractionMarker>,
+    /// Id of the member that selected the preset.
+    #[serde_as(as = "IdAsU64")]
+    pub author_id: Id<UserMarker>,
+    /// Id of the guild.
+    #[serde_as(as = "IdAsU64")]
+    pub guild_id: Id<GuildMarker>,
+    /// Name of the selected preset.
+    pub preset: String,
+}
+
+impl RedisModel for PendingPreset {
+    type Id = str;
+
+    // Pending confirmations expires after 5 minutes
+    const EXPIRES_AFTER: Option<usize> = Some(5 * 60);
+
+    fn key(&self) -> String {
+        Self::key_from(&self.interaction_id.to_string())
+    }
+
+    fn key_from(id: &Self::Id) -> String {
+        format!("pending:preset:{id}")
+    }
+}
+
+impl PendingComponent for PendingPreset {
+    fn is_authorized(&self, user: Id<UserMarker>) -> bool {
+        self.author_id == user
+    }
+}
+
+/// State for a pending overwrite of manually edited AutoMod rules.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingAutomodOverwrite {
+    /// Initial interaction ID.
+    #[serde_as(as = "IdAsU64")]
+    pub interaction_id: Id<InteractionMarker>,
+    /// Id of the administrator that updated the configuration.
+    #[serde_as(as = "IdAsU64")]
+    pub author_id: Id<UserMarker>,
+    /// Id of the guild.
+    #[serde_as(as = "IdAsU64")]
+    pub guild_id: Id<GuildMarker>,
+}
+
+impl RedisModel for PendingAutomodOverwrite {
+    type Id = str;
+
+    // Pending confirmations expires after 5 minutes
+    const EXPIRES_AFTER: Option<usize> = Some(5 * 60);
+
+    fn key(&self) -> String {
+        Self::key_from(&self.interaction_id.to_string())
+    }
+
+    fn key_from(id: &Self::Id) -> String {
+        format!("pending:automod:{id}")
+    }
+}
+
+impl PendingComponent for PendingAutomodOverwrite {
+    fn is_authorized(&self, user: Id<UserMarker>) -> bool {
+        self.author_id == user
+    }
+}
+
+/// State of a paginated message.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingPaginator {
+    /// Initial interaction ID.
+    #[serde_as(as = "IdAsU64")]
+    pub interaction_id: Id<InteractionMarker>,
+    /// Id of the user that invoked the command.
+    #[serde_as(as = "IdAsU64")]
+    pub author_id: Id<UserMarker>,
+    /// Pages of the message.
+    pub pages: Vec<Embed>,
+    /// Index of the currently displayed page.
+    pub page: usize,
+}
+
+impl RedisModel for PendingPaginator {
+    type Id = str;
+
+    // Paginated messages expires after 10 minutes
+    const EXPIRES_AFTER: Option<usize> = Some(10 * 60);
+
+    fn key(&self) -> String {
+        Self::key_from(&self.interaction_id.to_string())
+    }
+
+    fn key_from(id: &Self::Id) -> String {
+        format!("pending:paginator:{id}")
+    }
+}
+
+impl PendingComponent for PendingPaginator {
+    fn is_authorized(&self, user: Id<UserMarker>) -> bool {
+        self.author_id == user
+    }
+}
+
+/// State for a pending message report modal.
+///
+/// The reported message is stored when the context menu command is used,
+/// since it may be deleted before the modal is submitted.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingReport {
+    /// Initial interaction ID.
+    #[serde_as(as = "IdAsU64")]
+    pub interaction_id: Id<InteractionMarker>,
+    /// Id of the member that reported the message.
+    #[serde_as(as = "IdAsU64")]
+    pub author_id: Id<UserMarker>,
+    /// Channel where the message was sent.
+    #[serde_as(as = "IdAsU64")]
+    pub channel_id: Id<ChannelMarker>,
+    /// Reported message.
+    #[serde_as(as = "IdAsU64")]
+    pub message_id: Id<MessageMarker>,
+    /// Author of the reported message.
+    pub user: ModlogUser,
+    /// Content of the reported message.
+    pub content: String,
+}
+
+impl RedisModel for PendingReport {
+    type Id = str;
+
+    // Pending modals expires after 5 minutes
+    const EXPIRES_AFTER: Option<usize> = Some(5 * 60);
+
+    fn key(&self) -> String {
+        Self::key_from(&self.interaction_id.to_string())
+    }
+
+    fn key_from(id: &Self::Id) -> String {
+        format!("pending:report:{id}")
+    }
+}
+
+impl PendingComponent for PendingReport {
+    fn is_authorized(&self, user: Id<UserMarker>) -> bool {
+        self.author_id == user
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user() -> ModlogUser {
+        ModlogUser {
+            id: Id::new(3),
+            name: "Raider".to_owned(),
+            discriminator: 1234,
+            avatar: None,
+        }
+    }
+
+    #[test]
+    fn test_post_in_chat_template_roundtrip() {
+        let button = PostInChatButton {
+            message: PostInChatMessage::Template {
+                case_id: Some(ObjectId::new()),
+                template: PublicMessageTemplate::Sanction {
+                    kind: ModlogType::Ban,
+                    user: user(),
+                    moderator: None,
+                    reason: Some("Raid".to_owned()),
+                },
+            },
+            interaction_id: Id::new(1),
+            author_id: Id::new(2),
+        };
+
+        let serialized = button.serialize_model().unwrap();
+        let deserialized = PostInChatButton::deserialize_model(serialized).unwrap();
+
+        assert_eq!(button, deserialized);
+    }
+
+    #[test]
+    fn test_post_in_chat_profile_roundtrip() {
+        let button = PostInChatButton {
+            message: PostInChatMessage::Template {
+                case_id: None,
+                template: PublicMessageTemplate::Profile {
+                    user: user(),
+                    joined_at: Some(1_600_000_000),
+                },
+            },
+            interaction_id: Id::new(1),
+            author_id: Id::new(2),
+        };
+
+        let serialized = button.serialize_model().unwrap();
+        let deserialized = PostInChatButton::deserialize_model(serialized).unwrap();
+
+        assert_eq!(button, deserialized);
+    }
+
+    #[test]
+    fn test_post_in_chat_legacy() {
+        /// Shape of the buttons stored before templates were introduced.
+        #[serde_as]
+        #[derive(Serialize)]
+        struct LegacyButton {
+            response: InteractionResponseData,
+            interaction_id: Id<InteractionMarker>,
+            #[serde_as(as = "IdAsU64")]
+            author_id: Id<UserMarker>,
+        }
+
+        let response = InteractionResponseData {
+            content: Some("Profile".to_owned()),
+            ..Default::default()
+        };
+        let legacy = LegacyButton {
+            response: response.clone(),
+            interaction_id: Id::new(1),
+            author_id: Id::new(2),
+        };
+
+        let serialized = rmp_serde::to_vec_named(&legacy).unwrap();
+        let deserialized = PostInChatButton::deserialize_model(serialized).unwrap();
+
+        assert_eq!(deserialized.message, PostInChatMessage::Legacy { response });
+        assert_eq!(deserialized.interaction_id, Id::new(1));
+        assert_eq!(deserialized.author_id, Id::new(2));
+    }
+}
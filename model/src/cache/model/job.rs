@@ -0,0 +1,249 @@
+//! Scheduled jobs.
+//!
+//! Jobs are stored in a Redis sorted set, using the time at which they should
+//! run as score. This allows jobs to survive restarts of the bot. A job is only
+//! executed by the process that successfully removes it from the set, so the
+//! same job cannot be executed twice.
+
+use anyhow::Context;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use time::OffsetDateTime;
+use tracing::{error, instrument, trace};
+use twilight_model::id::{
+    marker::{ChannelMarker, GuildMarker, MessageMarker, UserMarker},
+    Id,
+};
+
+use crate::{
+    cache::CacheClient,
+    database::model::{ModlogUser, SyncOrigin},
+    serde::IdAsU64,
+};
+
+/// Key of the sorted set used to store scheduled jobs.
+const JOBS_KEY: &str = "scheduler:jobs";
+
+/// Job executed at a later time by the scheduler.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScheduledJob {
+    /// Delete a message sent by the bot.
+    DeleteMessage {
+        #[serde_as(as = "IdAsU64")]
+        channel_id: Id<ChannelMarker>,
+        #[serde_as(as = "IdAsU64")]
+        message_id: Id<MessageMarker>,
+    },
+    /// Send an announcement to the logs channel of every guild.
+    ///
+    /// The broadcast is sent in batches. The `cursor` field holds the id of
+    /// the last guild the announcement has been sent to, and is updated each
+    /// time the job is scheduled for the next batch.
+    Broadcast {
+        title: String,
+        message: String,
+        #[serde_as(as = "Option<IdAsU64>")]
+        cursor: Option<Id<GuildMarker>>,
+    },
+    /// Unmute a member once their mute has expired.
+    Unmute {
+        #[serde_as(as = "IdAsU64")]
+        guild_id: Id<GuildMarker>,
+        #[serde_as(as = "IdAsU64")]
+        user_id: Id<UserMarker>,
+    },
+    /// Take a snapshot of the channels and roles of every guild.
+    ///
+    /// Like [`ScheduledJob::Broadcast`], snapshots are taken in batches and
+    /// the `cursor` field holds the id of the last processed guild.
+    StructureSnapshots {
+        #[serde_as(as = "Option<IdAsU64>")]
+        cursor: Option<Id<GuildMarker>>,
+    },
+    /// Delete the moderation logs older than the retention period of each
+    /// guild.
+    ///
+    /// Guilds are processed in batches, the `cursor` field holds the id of
+    /// the last processed guild.
+    ModlogCleanup {
+        #[serde_as(as = "Option<IdAsU64>")]
+        cursor: Option<Id<GuildMarker>>,
+    },
+    /// Deliver a payload to the outbound webhook of a guild.
+    ///
+    /// The `attempt` field holds the number of previous failed attempts.
+    WebhookDelivery {
+        #[serde_as(as = "IdAsU64")]
+        guild_id: Id<GuildMarker>,
+        payload: String,
+        attempt: u8,
+    },
+    /// Remove a user from the watchlist once the watch has expired.
+    WatchExpire {
+        #[serde_as(as = "IdAsU64")]
+        guild_id: Id<GuildMarker>,
+        #[serde_as(as = "IdAsU64")]
+        user_id: Id<UserMarker>,
+    },
+    /// Mirror a ban in a linked guild.
+    ///
+    /// The `origin` field references the ban in the guild where it was
+    /// issued, and is stored in the modlog of the mirrored ban.
+    SyncBan {
+        #[serde_as(as = "IdAsU64")]
+        guild_id: Id<GuildMarker>,
+        user: ModlogUser,
+        moderator: ModlogUser,
+        reason: Option<String>,
+        origin: SyncOrigin,
+    },
+}
+
+impl ScheduledJob {
+    /// Serialize the job in MessagePack.
+    fn serialize(&self) -> Result<Vec<u8>, anyhow::Error> {
+        rmp_serde::to_vec_named(self).context("failed to serialize job")
+    }
+}
+
+// Implementation of methods to manage scheduled jobs.
+impl CacheClient {
+    /// Schedule a job to be executed at a given time.
+    #[instrument(skip(self))]
+    pub async fn schedule_job(
+        &self,
+        job: &ScheduledJob,
+        at: OffsetDateTime,
+    ) -> Result<(), anyhow::Error> {
+        let mut conn = self.conn().await?;
+
+        trace!("scheduling job at {}", at);
+        let _: () = conn
+            .zadd(JOBS_KEY, job.serialize()?, at.unix_timestamp())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Schedule a job if the same job is not already scheduled.
+    ///
+    /// Returns whether the job has been scheduled.
+    #[instrument(skip(self))]
+    pub async fn schedule_job_if_absent(
+        &self,
+        job: &ScheduledJob,
+        at: OffsetDateTime,
+    ) -> Result<bool, anyhow::Error> {
+        let mut conn = self.conn().await?;
+
+        let added: usize = redis::cmd("ZADD")
+            .arg(JOBS_KEY)
+            .arg("NX")
+            .arg(at.unix_timestamp())
+            .arg(job.serialize()?)
+            .query_async(&mut *conn)
+            .await?;
+
+        Ok(added > 0)
+    }
+
+    /// Claim the jobs that should be executed before a given time.
+    ///
+    /// Claimed jobs are removed from the cache, the caller is responsible for
+    /// executing them. At most `limit` jobs are returned.
+    #[instrument(skip(self))]
+    pub async fn claim_due_jobs(
+        &self,
+        now: OffsetDateTime,
+        limit: isize,
+    ) -> Result<Vec<ScheduledJob>, anyhow::Error> {
+        let mut conn = self.conn().await?;
+        let due: Vec<Vec<u8>> = conn
+            .zrangebyscore_limit(JOBS_KEY, "-inf", now.unix_timestamp(), 0, limit)
+            .await?;
+
+        let mut jobs = Vec::with_capacity(due.len());
+        for value in due {
+            // The job may have been claimed by another process in the meantime.
+            let removed: usize = conn.zrem(JOBS_KEY, &value).await?;
+            if removed == 0 {
+                continue;
+            }
+
+            match rmp_serde::from_slice(&value) {
+                Ok(job) => jobs.push(job),
+                Err(error) => error!(error = ?error, "failed to deserialize scheduled job"),
+            }
+        }
+
+        Ok(jobs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::oid::ObjectId;
+    use twilight_model::id::Id;
+
+    use super::ScheduledJob;
+    use crate::database::model::{ModlogUser, SyncOrigin};
+
+    #[test]
+    fn test_job_roundtrip() {
+        let user = ModlogUser {
+            id: Id::new(2),
+            name: "user".to_owned(),
+            discriminator: 1234,
+            avatar: None,
+        };
+
+        let jobs = [
+            ScheduledJob::DeleteMessage {
+                channel_id: Id::new(1),
+                message_id: Id::new(2),
+            },
+            ScheduledJob::Broadcast {
+                title: "Title".to_owned(),
+                message: "Message".to_owned(),
+                cursor: Some(Id::new(1)),
+            },
+            ScheduledJob::Unmute {
+                guild_id: Id::new(1),
+                user_id: Id::new(2),
+            },
+            ScheduledJob::StructureSnapshots {
+                cursor: Some(Id::new(1)),
+            },
+            ScheduledJob::ModlogCleanup { cursor: None },
+            ScheduledJob::WebhookDelivery {
+                guild_id: Id::new(1),
+                payload: r#"{"version":1}"#.to_owned(),
+                attempt: 2,
+            },
+            ScheduledJob::WatchExpire {
+                guild_id: Id::new(1),
+                user_id: Id::new(2),
+            },
+            ScheduledJob::SyncBan {
+                guild_id: Id::new(1),
+                user: user.clone(),
+                moderator: user,
+                reason: Some("Spam".to_owned()),
+                origin: SyncOrigin {
+                    guild_id: Id::new(3),
+                    case_id: ObjectId::parse_str("62aca55a551e9a0102351bda").unwrap(),
+                },
+            },
+        ];
+
+        for job in jobs {
+            let serialized = job.serialize().unwrap();
+            let deserialized: ScheduledJob = rmp_serde::from_slice(&serialized).unwrap();
+
+            assert_eq!(job, deserialized);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Member joins tracking.
+//!
+//! Recent joins of each guild are stored in a Redis sorted set, using the time
+//! at which the member joined as score. This is used to detect join bursts and
+//! to check whether the accounts of a burst look coordinated.
+//!
+//! At most [`MAX_TRACKED_JOINS`] joins are kept per guild, so that the size of
+//! the set stays bounded during large raids.
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use time::{Duration, OffsetDateTime};
+use tracing::{error, instrument};
+use twilight_model::{
+    id::{
+        marker::{GuildMarker, UserMarker},
+        Id,
+    },
+    util::ImageHash,
+};
+
+use crate::{cache::CacheClient, serde::IdAsU64};
+
+/// Maximum number of joins kept for each guild.
+pub const MAX_TRACKED_JOINS: isize = 200;
+
+/// Member that joined a guild.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TrackedJoin {
+    /// Id of the member.
+    #[serde_as(as = "IdAsU64")]
+    pub user_id: Id<UserMarker>,
+    /// Avatar hash of the member, [`None`] for a default avatar.
+    pub avatar: Option<ImageHash>,
+    /// Username of the member.
+    pub name: String,
+}
+
+/// Get the key of the set of recent joins of a guild.
+fn key(guild_id: Id<GuildMarker>) -> String {
+    format!("joins:{guild_id}")
+}
+
+// Implementation of methods to track joins.
+impl CacheClient {
+    /// Track a member that joined a guild.
+    ///
+    /// The members that joined the guild during the last `interval` are
+    /// returned, including the tracked one.
+    #[instrument(skip(self))]
+    pub async fn track_join(
+        &self,
+        guild_id: Id<GuildMarker>,
+        join: &TrackedJoin,
+        interval: Duration,
+    ) -> Result<Vec<TrackedJoin>, anyhow::Error> {
+        let mut conn = self.conn().await?;
+        let key = key(guild_id);
+
+        let now = OffsetDateTime::now_utc();
+        let now_millis = (now.unix_timestamp_nanos() / 1_000_000) as i64;
+        let interval_millis = interval.whole_milliseconds() as i64;
+        let value = rmp_serde::to_vec_named(join).context("failed to serialize join")?;
+
+        let (values,): (Vec<Vec<u8>>,) = redis::pipe()
+            .zrembyscore(&key, "-inf", now_millis - interval_millis)
+            .ignore()
+            .zadd(&key, value, now_millis)
+            .ignore()
+            .zremrangebyrank(&key, 0, -(MAX_TRACKED_JOINS + 1))
+            .ignore()
+            .zrange(&key, 0, -1)
+            .expire(&key, interval.whole_seconds() as usize + 1)
+            .ignore()
+            .query_async(&mut *conn)
+            .await?;
+
+        let joins = values
+            .into_iter()
+            .filter_map(|value| match rmp_serde::from_slice(&value) {
+                Ok(join) => Some(join),
+                Err(error) => {
+                    error!(error = ?error, "failed to deserialize tracked join");
+                    None
+                }
+            })
+            .collect();
+
+        Ok(joins)
+    }
+}
@@ -0,0 +1,43 @@
+//! Guild link codes.
+//!
+//! Affiliated guilds are linked with a code generated in one guild and used
+//! in the other. Codes are stored in the cache until they expire, and are
+//! consumed when used so that each code links a single guild.
+
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+
+use crate::{cache::RedisModel, serde::IdAsU64};
+
+/// Code generated to link a guild.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PendingGuildLink {
+    /// Code used to join the link.
+    pub code: String,
+    /// Guild where the code has been generated.
+    #[serde_as(as = "IdAsU64")]
+    pub guild_id: Id<GuildMarker>,
+    /// Administrator that generated the code.
+    #[serde_as(as = "IdAsU64")]
+    pub author_id: Id<UserMarker>,
+}
+
+impl RedisModel for PendingGuildLink {
+    type Id = str;
+
+    /// Codes expire after 10 minutes.
+    const EXPIRES_AFTER: Option<usize> = Some(10 * 60);
+
+    fn key(&self) -> String {
+        Self::key_from(&self.code)
+    }
+
+    fn key_from(code: &Self::Id) -> String {
+        format!("link:code:{code}")
+    }
+}
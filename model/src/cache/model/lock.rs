@@ -0,0 +1,339 @@
+//! Distributed locks.
+//!
+//! Some tasks must not run concurrently for the same resource, even across
+//! several instances of the bot. A [`DistributedLock`] is a Redis key with an
+//! expiration, so that a lock held by a crashed instance is eventually
+//! released and can be taken over by another instance.
+//!
+//! Each acquisition gets a fencing token greater than the tokens of every
+//! previous acquisition. The key stores the token of its holder, so that an
+//! instance whose lock has expired cannot extend or release the lock of the
+//! new holder. The token can also be passed to other systems to reject the
+//! writes of a previous holder.
+//!
+//! A lock is extended in background while it is held, and released when it is
+//! dropped. If the lock cannot be extended, it is reported as not held once
+//! its ttl has passed since the last successful extension.
+
+use std::{
+    future::Future,
+    mem,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Instant,
+};
+
+use time::Duration;
+use tokio::{runtime::Handle, task::JoinHandle};
+use tracing::{instrument, warn};
+
+use crate::cache::CacheClient;
+
+/// Key of the counter used to generate fencing tokens.
+const TOKEN_KEY: &str = "lock-token";
+
+/// Minimum interval between two extensions of a lock.
+const MIN_EXTEND_INTERVAL: std::time::Duration = std::time::Duration::from_millis(10);
+
+/// Acquire a lock and return its fencing token, or `nil` if the lock is held.
+const ACQUIRE_SCRIPT: &str = r"
+local token = redis.call('INCR', KEYS[2])
+if redis.call('SET', KEYS[1], token, 'NX', 'PX', ARGV[1]) then
+    return token
+end
+return false
+";
+
+/// Extend a lock if it is still held with the given token.
+const EXTEND_SCRIPT: &str = r"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('PEXPIRE', KEYS[1], ARGV[2])
+end
+return 0
+";
+
+/// Release a lock if it is still held with the given token.
+const RELEASE_SCRIPT: &str = r"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('DEL', KEYS[1])
+end
+return 0
+";
+
+/// Lock held on a shared resource.
+///
+/// The lock is released when this type is dropped. The release is done in
+/// background and errors are only logged, use [`DistributedLock::release`] to
+/// wait for the release.
+#[derive(Debug)]
+pub struct DistributedLock {
+    cache: CacheClient,
+    /// Redis key of the lock.
+    key: String,
+    /// Fencing token of the acquisition.
+    token: u64,
+    /// Holding state of the lock, updated by the extension task.
+    lease: Arc<Lease>,
+    /// Background task extending the lock.
+    extension: JoinHandle<()>,
+}
+
+impl DistributedLock {
+    /// Try to acquire a lock.
+    ///
+    /// Returns [`None`] if the lock is already held. The lock is extended every
+    /// third of `ttl` while held, and expires after `ttl` if the holder stops
+    /// without releasing it.
+    #[instrument(skip(cache))]
+    pub async fn acquire(
+        cache: &CacheClient,
+        key: &str,
+        ttl: Duration,
+    ) -> Result<Option<Self>, anyhow::Error> {
+        let mut conn = cache.conn().await?;
+        let key = format!("lock:{key}");
+        let lease = Arc::new(Lease::new(ttl));
+
+        let token: Option<u64> = redis::cmd("EVAL")
+            .arg(ACQUIRE_SCRIPT)
+            .arg(2)
+            .arg(&key)
+            .arg(TOKEN_KEY)
+            .arg(ttl_millis(ttl))
+            .query_async(&mut *conn)
+            .await?;
+
+        let token = match token {
+            Some(token) => token,
+            None => return Ok(None),
+        };
+
+        let extension = tokio::spawn(extend(
+            cache.clone(),
+            key.clone(),
+            token,
+            ttl,
+            lease.clone(),
+        ));
+
+        Ok(Some(Self {
+            cache: cache.clone(),
+            key,
+            token,
+            lease,
+            extension,
+        }))
+    }
+
+    /// Get the fencing token of the lock.
+    pub fn token(&self) -> u64 {
+        self.token
+    }
+
+    /// Whether the lock is still held.
+    ///
+    /// Returns `false` if the lock could not be extended before its expiration,
+    /// in which case it may have been acquired by another instance.
+    pub fn is_held(&self) -> bool {
+        self.lease.is_held()
+    }
+
+    /// Release the lock.
+    pub async fn release(mut self) -> Result<(), anyhow::Error> {
+        self.extension.abort();
+        let key = mem::take(&mut self.key);
+
+        release(&self.cache, &key, self.token).await
+    }
+}
+
+impl Drop for DistributedLock {
+    fn drop(&mut self) {
+        self.extension.abort();
+
+        // The lock has already been released with `release`.
+        if self.key.is_empty() {
+            return;
+        }
+
+        // Without runtime, the lock is released when it expires.
+        if let Ok(handle) = Handle::try_current() {
+            let cache = self.cache.clone();
+            let key = mem::take(&mut self.key);
+            let token = self.token;
+
+            handle.spawn(async move {
+                if let Err(error) = release(&cache, &key, token).await {
+                    warn!(error = ?error, key = %key, "failed to release lock");
+                }
+            });
+        }
+    }
+}
+
+/// Holding state of a lock.
+#[derive(Debug)]
+struct Lease {
+    /// Time after which the lock expires if it is not extended.
+    ttl: std::time::Duration,
+    /// Time of the acquisition or of the last successful extension.
+    ///
+    /// The time is taken before sending the request, so that the lock is
+    /// never reported as held after its expiration.
+    extended_at: Mutex<Instant>,
+    /// Set to `true` once the lock is known to be lost.
+    lost: AtomicBool,
+}
+
+impl Lease {
+    /// Initialize the state of a lock acquired now.
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl: std::time::Duration::try_from(ttl).unwrap_or_default(),
+            extended_at: Mutex::new(Instant::now()),
+            lost: AtomicBool::new(false),
+        }
+    }
+
+    /// Whether the lock is still held.
+    fn is_held(&self) -> bool {
+        let extended_at = *self.extended_at.lock().unwrap();
+
+        !self.lost.load(Ordering::Relaxed) && extended_at.elapsed() < self.ttl
+    }
+
+    /// Record a successful extension started at `started`.
+    fn extended(&self, started: Instant) {
+        *self.extended_at.lock().unwrap() = started;
+    }
+
+    /// Mark the lock as lost.
+    fn lose(&self) {
+        self.lost.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Extend a lock until it is released.
+async fn extend(cache: CacheClient, key: String, token: u64, ttl: Duration, lease: Arc<Lease>) {
+    extend_with(&key, &lease, || try_extend(&cache, &key, token, ttl)).await;
+}
+
+/// Extend a lock with the given extension function until it is released or
+/// lost.
+///
+/// The extension is retried until the lock expires, in which case it is
+/// marked as lost and no longer extended.
+async fn extend_with<F, Fut>(key: &str, lease: &Lease, mut try_extend: F)
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<bool, anyhow::Error>>,
+{
+    let interval = (lease.ttl / 3).max(MIN_EXTEND_INTERVAL);
+
+    loop {
+        tokio::time::sleep(interval).await;
+
+        let started = Instant::now();
+        match try_extend().await {
+            Ok(true) => lease.extended(started),
+            Ok(false) => {
+                warn!(key = %key, "lock expired before being released");
+                lease.lose();
+
+                return;
+            }
+            Err(error) => {
+                warn!(error = ?error, key = %key, "failed to extend lock");
+
+                if !lease.is_held() {
+                    lease.lose();
+
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Extend a lock if it is still held with the given token.
+///
+/// Returns `false` if the lock is no longer held.
+async fn try_extend(
+    cache: &CacheClient,
+    key: &str,
+    token: u64,
+    ttl: Duration,
+) -> Result<bool, anyhow::Error> {
+    let mut conn = cache.conn().await?;
+
+    let extended: i64 = redis::cmd("EVAL")
+        .arg(EXTEND_SCRIPT)
+        .arg(1)
+        .arg(key)
+        .arg(token)
+        .arg(ttl_millis(ttl))
+        .query_async(&mut *conn)
+        .await?;
+
+    Ok(extended == 1)
+}
+
+/// Release a lock if it is still held with the given token.
+async fn release(cache: &CacheClient, key: &str, token: u64) -> Result<(), anyhow::Error> {
+    let mut conn = cache.conn().await?;
+
+    redis::cmd("EVAL")
+        .arg(RELEASE_SCRIPT)
+        .arg(1)
+        .arg(key)
+        .arg(token)
+        .query_async::<_, ()>(&mut *conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Convert a lock ttl to milliseconds.
+fn ttl_millis(ttl: Duration) -> i64 {
+    ttl.whole_milliseconds().clamp(1, i64::MAX.into()) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration as StdDuration;
+
+    use anyhow::anyhow;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_extend_failing() {
+        let lease = Lease::new(Duration::milliseconds(60));
+        assert!(lease.is_held());
+
+        let extension = extend_with("test", &lease, || async {
+            Err(anyhow!("connection refused"))
+        });
+
+        // The extension stops once the lock has expired.
+        tokio::time::timeout(StdDuration::from_secs(1), extension)
+            .await
+            .unwrap();
+
+        assert!(!lease.is_held());
+    }
+
+    #[tokio::test]
+    async fn test_extend_success() {
+        let lease = Lease::new(Duration::milliseconds(60));
+        let extension = extend_with("test", &lease, || async { Ok(true) });
+
+        // The extension runs until the lock is released.
+        let result = tokio::time::timeout(StdDuration::from_millis(200), extension).await;
+
+        assert!(result.is_err());
+        assert!(lease.is_held());
+    }
+}
@@ -0,0 +1,109 @@
+//! Channel lockdown state.
+//!
+//! When a lockdown is engaged, the permission overwrite of the `@everyone`
+//! role in each locked channel is saved in a [`LockdownState`] before the
+//! channel is modified, so that it can be restored when the lockdown is
+//! lifted. The state is saved after each batch of channels and is marked as
+//! completed once every channel is locked. A state that is not completed and
+//! not being processed belongs to an interrupted engagement.
+
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use twilight_model::{
+    guild::Permissions,
+    id::{
+        marker::{ChannelMarker, GuildMarker, InteractionMarker, UserMarker},
+        Id,
+    },
+};
+
+use crate::{cache::RedisModel, serde::IdAsU64};
+
+/// Lockdown of a guild.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LockdownState {
+    /// Id of the guild.
+    #[serde_as(as = "IdAsU64")]
+    pub guild_id: Id<GuildMarker>,
+    /// Id of the interaction that engaged the lockdown.
+    #[serde_as(as = "IdAsU64")]
+    pub interaction_id: Id<InteractionMarker>,
+    /// Id of the moderator that engaged the lockdown.
+    #[serde_as(as = "IdAsU64")]
+    pub author_id: Id<UserMarker>,
+    /// Whether every channel has been locked.
+    pub completed: bool,
+    /// Channels modified by the lockdown.
+    pub channels: Vec<ChannelSnapshot>,
+}
+
+impl RedisModel for LockdownState {
+    type Id = Id<GuildMarker>;
+
+    fn key(&self) -> String {
+        Self::key_from(&self.guild_id)
+    }
+
+    fn key_from(guild_id: &Self::Id) -> String {
+        format!("lockdown:{guild_id}")
+    }
+}
+
+/// Permissions of the `@everyone` role in a channel before the lockdown.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelSnapshot {
+    /// Id of the channel.
+    #[serde_as(as = "IdAsU64")]
+    pub channel_id: Id<ChannelMarker>,
+    /// Permission overwrite of the `@everyone` role, [`None`] if the channel
+    /// had no overwrite for the role.
+    pub overwrite: Option<EveryoneOverwrite>,
+}
+
+/// Permission overwrite of the `@everyone` role.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EveryoneOverwrite {
+    /// Allowed permissions.
+    pub allow: Permissions,
+    /// Denied permissions.
+    pub deny: Permissions,
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::{guild::Permissions, id::Id};
+
+    use super::{ChannelSnapshot, EveryoneOverwrite, LockdownState};
+    use crate::cache::RedisModel;
+
+    #[test]
+    fn test_lockdown_state_roundtrip() {
+        let lockdown = LockdownState {
+            guild_id: Id::new(1),
+            interaction_id: Id::new(2),
+            author_id: Id::new(3),
+            completed: false,
+            channels: vec![
+                ChannelSnapshot {
+                    channel_id: Id::new(4),
+                    overwrite: None,
+                },
+                ChannelSnapshot {
+                    channel_id: Id::new(5),
+                    overwrite: Some(EveryoneOverwrite {
+                        allow: Permissions::ADD_REACTIONS,
+                        deny: Permissions::VIEW_CHANNEL,
+                    }),
+                },
+            ],
+        };
+
+        let serialized = lockdown.serialize_model().unwrap();
+        let deserialized = LockdownState::deserialize_model(serialized).unwrap();
+
+        assert_eq!(lockdown, deserialized);
+        assert_eq!(lockdown.key(), "lockdown:1");
+    }
+}
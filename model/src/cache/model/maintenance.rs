@@ -0,0 +1,45 @@
+//! Maintenance mode.
+//!
+//! When the maintenance mode is enabled, only a few commands remain available.
+//! The state is stored in Redis, so that it is shared by all the instances of
+//! the bot and kept across restarts.
+//!
+//! The [`Maintenance`] marker is stored while the maintenance mode is enabled.
+//! It is accessed through any [`CacheStore`], so the commands that check it
+//! can be tested with the in-memory store.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{cache::RedisModel, store::CacheStore};
+
+/// Marker stored while the maintenance mode is enabled.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Maintenance;
+
+impl Maintenance {
+    /// Check whether the maintenance mode is enabled.
+    pub async fn is_enabled(cache: &impl CacheStore) -> Result<bool, anyhow::Error> {
+        Ok(cache.get::<Self>(&()).await?.is_some())
+    }
+
+    /// Enable or disable the maintenance mode.
+    pub async fn set_enabled(cache: &impl CacheStore, enabled: bool) -> Result<(), anyhow::Error> {
+        if enabled {
+            cache.set(&Self).await
+        } else {
+            cache.delete(&Self).await
+        }
+    }
+}
+
+impl RedisModel for Maintenance {
+    type Id = ();
+
+    fn key(&self) -> String {
+        Self::key_from(&())
+    }
+
+    fn key_from(_: &Self::Id) -> String {
+        "maintenance".to_owned()
+    }
+}
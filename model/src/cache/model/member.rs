@@ -0,0 +1,87 @@
+//! Last-known members data.
+//!
+//! The bot does not cache guild members, but a record is kept for each member
+//! that joins or is updated in a guild. The record is kept after the member
+//! leaves the guild, to display the last-known data of the member.
+
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use twilight_model::{
+    id::{
+        marker::{GuildMarker, RoleMarker, UserMarker},
+        Id,
+    },
+    util::Timestamp,
+};
+
+use crate::{
+    cache::RedisModel,
+    serde::{IdAsU64, TimestampAsI64},
+};
+
+/// Last-known data of a guild member.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MemberRecord {
+    /// Id of the guild.
+    #[serde_as(as = "IdAsU64")]
+    pub guild_id: Id<GuildMarker>,
+    /// Id of the member.
+    #[serde_as(as = "IdAsU64")]
+    pub user_id: Id<UserMarker>,
+    /// When the member joined the guild.
+    #[serde_as(as = "TimestampAsI64")]
+    pub joined_at: Timestamp,
+    /// Roles of the member.
+    #[serde_as(as = "Vec<IdAsU64>")]
+    pub roles: Vec<Id<RoleMarker>>,
+    /// When the member left the guild.
+    ///
+    /// This field is [`None`] if the member is still in the guild.
+    #[serde_as(as = "Option<TimestampAsI64>")]
+    pub left_at: Option<Timestamp>,
+    /// Whether the member has not yet passed the membership screening.
+    #[serde(default)]
+    pub pending: bool,
+}
+
+impl RedisModel for MemberRecord {
+    type Id = (Id<GuildMarker>, Id<UserMarker>);
+
+    /// Records expire after 90 days without updates.
+    const EXPIRES_AFTER: Option<usize> = Some(90 * 24 * 60 * 60);
+
+    fn key(&self) -> String {
+        Self::key_from(&(self.guild_id, self.user_id))
+    }
+
+    fn key_from((guild_id, user_id): &Self::Id) -> String {
+        format!("member:{guild_id}:{user_id}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::{id::Id, util::Timestamp};
+
+    use super::MemberRecord;
+    use crate::cache::RedisModel;
+
+    #[test]
+    fn test_member_record_roundtrip() {
+        let record = MemberRecord {
+            guild_id: Id::new(1),
+            user_id: Id::new(2),
+            joined_at: Timestamp::from_secs(1_600_000_000).unwrap(),
+            roles: vec![Id::new(3), Id::new(4)],
+            left_at: Some(Timestamp::from_secs(1_650_000_000).unwrap()),
+            pending: true,
+        };
+
+        let serialized = record.serialize_model().unwrap();
+        let deserialized = MemberRecord::deserialize_model(serialized).unwrap();
+
+        assert_eq!(record, deserialized);
+        assert_eq!(record.key(), "member:1:2");
+    }
+}
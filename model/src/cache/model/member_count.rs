@@ -0,0 +1,67 @@
+//! Live member count of guilds.
+//!
+//! The member count of [`CachedGuild`] is only updated when the guild is
+//! received from the gateway. A separate Redis counter is incremented and
+//! decremented as members join and leave, and reset to the exact count each
+//! time the guild is cached.
+//!
+//! [`CachedGuild`]: crate::cache::discord::CachedGuild
+
+use tracing::instrument;
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::cache::CacheClient;
+
+/// Get the key of the member counter of a guild.
+pub(crate) fn key(guild_id: Id<GuildMarker>) -> String {
+    format!("member_count:{guild_id}")
+}
+
+// Implementation of methods to track the member count.
+impl CacheClient {
+    /// Update the member count of a guild.
+    ///
+    /// The counter is initialized with `initial` if it does not exist, then
+    /// `delta` is added to it. The updated member count is returned.
+    #[instrument(skip(self))]
+    pub async fn update_member_count(
+        &self,
+        guild_id: Id<GuildMarker>,
+        delta: i64,
+        initial: u64,
+    ) -> Result<u64, anyhow::Error> {
+        let mut conn = self.conn().await?;
+        let key = key(guild_id);
+
+        let (count,): (i64,) = redis::pipe()
+            .atomic()
+            .cmd("SET")
+            .arg(&key)
+            .arg(initial)
+            .arg("NX")
+            .ignore()
+            .incr(&key, delta)
+            .query_async(&mut *conn)
+            .await?;
+
+        Ok(count.max(0) as u64)
+    }
+
+    /// Get the member count of a guild.
+    ///
+    /// Returns [`None`] if the counter has not been initialized yet.
+    #[instrument(skip(self))]
+    pub async fn member_count(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<u64>, anyhow::Error> {
+        let mut conn = self.conn().await?;
+
+        let count: Option<i64> = redis::cmd("GET")
+            .arg(key(guild_id))
+            .query_async(&mut *conn)
+            .await?;
+
+        Ok(count.map(|count| count.max(0) as u64))
+    }
+}
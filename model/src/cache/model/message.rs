@@ -1,11 +1,20 @@
 //! Messages cache models.
+//!
+//! ## Author index
+//! Cached messages expire quickly, but the ids of the messages sent by each
+//! user are kept for a longer time in a Redis sorted set (using the message
+//! timestamp as score). This index is used to delete the recent messages of a
+//! sanctioned user.
 
+use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
+use time::{Duration, OffsetDateTime};
+use tracing::instrument;
 use twilight_model::{
     channel::Attachment,
     id::{
-        marker::{ChannelMarker, MessageMarker, RoleMarker, UserMarker},
+        marker::{ChannelMarker, GuildMarker, MessageMarker, RoleMarker, UserMarker},
         Id,
     },
     util::Timestamp,
@@ -13,10 +22,13 @@ use twilight_model::{
 use url::Url;
 
 use crate::{
-    cache::RedisModel,
+    cache::{CacheClient, RedisModel},
     serde::{IdAsU64, TimestampAsI64},
 };
 
+/// Duration during which the messages of a user are kept in the author index.
+pub const AUTHOR_INDEX_RETENTION: Duration = Duration::hours(24);
+
 /// Cached model of a [`Message`].
 ///
 /// [`Message`]: twilight_model::channel::message::Message
@@ -71,6 +83,35 @@ impl RedisModel for CachedMessage {
     }
 }
 
+/// Message deleted in a guild.
+///
+/// When a [`CachedMessage`] is deleted, a copy is kept for a longer time so
+/// that moderators can restore it with the `/restore-message` command.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DeletedMessage {
+    /// Guild where the message was sent.
+    #[serde_as(as = "IdAsU64")]
+    pub guild_id: Id<GuildMarker>,
+    /// Content of the deleted message.
+    pub message: CachedMessage,
+}
+
+impl RedisModel for DeletedMessage {
+    type Id = Id<MessageMarker>;
+
+    /// Deleted messages can be restored for 1 hour.
+    const EXPIRES_AFTER: Option<usize> = Some(60 * 60);
+
+    fn key(&self) -> String {
+        Self::key_from(&self.message.id)
+    }
+
+    fn key_from(id: &Self::Id) -> String {
+        format!("c:msg-deleted:{id}")
+    }
+}
+
 /// Kind of message link.
 ///
 /// This type is used in [`CachedMessage`].
@@ -83,3 +124,138 @@ pub enum MessageLink {
     /// URL that does not belong to one of the previous categories
     Other(Url),
 }
+
+/// Message referenced in the author index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexedMessage {
+    /// Channel of the message.
+    pub channel_id: Id<ChannelMarker>,
+    /// ID of the message.
+    pub message_id: Id<MessageMarker>,
+    /// Timestamp of when the message was created (in milliseconds).
+    pub timestamp: i64,
+}
+
+impl IndexedMessage {
+    /// Value stored in the sorted set.
+    fn value(&self) -> String {
+        format!("{}:{}", self.channel_id, self.message_id)
+    }
+
+    /// Parse a value of the sorted set.
+    fn parse(value: &str, timestamp: i64) -> Option<Self> {
+        let (channel_id, message_id) = value.split_once(':')?;
+
+        Some(Self {
+            channel_id: channel_id.parse().ok()?,
+            message_id: message_id.parse().ok()?,
+            timestamp,
+        })
+    }
+}
+
+/// Get the key of the author index of a user.
+fn author_key(guild_id: Id<GuildMarker>, user_id: Id<UserMarker>) -> String {
+    format!("c:msg-author:{guild_id}:{user_id}")
+}
+
+// Implementation of methods to manage the author index.
+impl CacheClient {
+    /// Add a message to the author index.
+    ///
+    /// Messages older than [`AUTHOR_INDEX_RETENTION`] are removed from the
+    /// index at the same time.
+    #[instrument(skip(self, message), fields(message_id = message.id.get()))]
+    pub async fn index_message(
+        &self,
+        guild_id: Id<GuildMarker>,
+        message: &CachedMessage,
+    ) -> Result<(), anyhow::Error> {
+        let mut conn = self.conn().await?;
+        let key = author_key(guild_id, message.author_id);
+
+        let indexed = IndexedMessage {
+            channel_id: message.channel_id,
+            message_id: message.id,
+            timestamp: message.timestamp.as_micros() / 1_000,
+        };
+        let now = OffsetDateTime::now_utc();
+        let oldest = millis(now - AUTHOR_INDEX_RETENTION);
+
+        let _: () = redis::pipe()
+            .zrembyscore(&key, "-inf", oldest)
+            .ignore()
+            .zadd(&key, indexed.value(), indexed.timestamp)
+            .ignore()
+            .expire(&key, AUTHOR_INDEX_RETENTION.whole_seconds() as usize)
+            .ignore()
+            .query_async(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Get the messages of a user sent after a given time.
+    #[instrument(skip(self))]
+    pub async fn author_messages(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+        since: OffsetDateTime,
+    ) -> Result<Vec<IndexedMessage>, anyhow::Error> {
+        let mut conn = self.conn().await?;
+        let key = author_key(guild_id, user_id);
+
+        let values: Vec<(String, i64)> = conn
+            .zrangebyscore_withscores(&key, millis(since), "+inf")
+            .await?;
+
+        Ok(values
+            .iter()
+            .filter_map(|(value, timestamp)| IndexedMessage::parse(value, *timestamp))
+            .collect())
+    }
+
+    /// Remove messages from the author index of a user.
+    #[instrument(skip(self, messages))]
+    pub async fn remove_author_messages(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+        messages: &[IndexedMessage],
+    ) -> Result<(), anyhow::Error> {
+        if messages.is_empty() {
+            return Ok(());
+        }
+
+        let mut conn = self.conn().await?;
+        let values = messages.iter().map(|m| m.value()).collect::<Vec<_>>();
+        let _: () = conn.zrem(author_key(guild_id, user_id), values).await?;
+
+        Ok(())
+    }
+}
+
+/// Convert a time into a timestamp in milliseconds.
+fn millis(time: OffsetDateTime) -> i64 {
+    (time.unix_timestamp_nanos() / 1_000_000) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_indexed_message_value() {
+        let message = IndexedMessage {
+            channel_id: Id::new(1),
+            message_id: Id::new(2),
+            timestamp: 3,
+        };
+
+        assert_eq!(message.value(), "1:2");
+        assert_eq!(IndexedMessage::parse(&message.value(), 3), Some(message));
+        assert_eq!(IndexedMessage::parse("1", 3), None);
+        assert_eq!(IndexedMessage::parse("a:2", 3), None);
+    }
+}
@@ -5,5 +5,25 @@
 //!
 //! [`discord`]: super::discord
 
+pub mod activity;
+pub mod antinuke;
+pub mod api;
+pub mod config;
+pub mod debug;
+pub mod first_message;
 pub mod interaction;
+pub mod job;
+pub mod join;
+pub mod link;
+pub mod lock;
+pub mod lockdown;
+pub mod maintenance;
+pub mod member;
+pub mod member_count;
 pub mod message;
+pub mod reaction;
+pub mod sanction;
+pub mod shard;
+pub mod throttle;
+pub mod voice;
+pub mod write_queue;
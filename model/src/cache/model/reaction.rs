@@ -0,0 +1,101 @@
+//! Reactions tracking.
+//!
+//! Recent reactions of each user are stored in a Redis sorted set, using the
+//! time at which the reaction was added as score. This is used to detect users
+//! that add reactions too quickly.
+
+use anyhow::Context;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use time::{Duration, OffsetDateTime};
+use tracing::{error, instrument};
+use twilight_model::{
+    channel::ReactionType,
+    id::{
+        marker::{ChannelMarker, GuildMarker, MessageMarker, UserMarker},
+        Id,
+    },
+};
+
+use crate::{cache::CacheClient, serde::IdAsU64};
+
+/// Reaction added by a user.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TrackedReaction {
+    /// Channel of the message.
+    #[serde_as(as = "IdAsU64")]
+    pub channel_id: Id<ChannelMarker>,
+    /// Message the reaction was added to.
+    #[serde_as(as = "IdAsU64")]
+    pub message_id: Id<MessageMarker>,
+    /// Emoji of the reaction.
+    pub emoji: ReactionType,
+}
+
+/// Get the key of the set of recent reactions of a user.
+fn key(guild_id: Id<GuildMarker>, user_id: Id<UserMarker>) -> String {
+    format!("reactions:{guild_id}:{user_id}")
+}
+
+// Implementation of methods to track reactions.
+impl CacheClient {
+    /// Track a reaction added by a user.
+    ///
+    /// The reactions added by the user during the last `interval` are returned,
+    /// including the tracked one.
+    #[instrument(skip(self))]
+    pub async fn track_reaction(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+        reaction: &TrackedReaction,
+        interval: Duration,
+    ) -> Result<Vec<TrackedReaction>, anyhow::Error> {
+        let mut conn = self.conn().await?;
+        let key = key(guild_id, user_id);
+
+        let now = OffsetDateTime::now_utc();
+        let now_millis = (now.unix_timestamp_nanos() / 1_000_000) as i64;
+        let interval_millis = interval.whole_milliseconds() as i64;
+        let value = rmp_serde::to_vec_named(reaction).context("failed to serialize reaction")?;
+
+        let (values,): (Vec<Vec<u8>>,) = redis::pipe()
+            .zrembyscore(&key, "-inf", now_millis - interval_millis)
+            .ignore()
+            .zadd(&key, value, now_millis)
+            .ignore()
+            .zrange(&key, 0, -1)
+            .expire(&key, interval.whole_seconds() as usize + 1)
+            .ignore()
+            .query_async(&mut *conn)
+            .await?;
+
+        let reactions = values
+            .into_iter()
+            .filter_map(|value| match rmp_serde::from_slice(&value) {
+                Ok(reaction) => Some(reaction),
+                Err(error) => {
+                    error!(error = ?error, "failed to deserialize tracked reaction");
+                    None
+                }
+            })
+            .collect();
+
+        Ok(reactions)
+    }
+
+    /// Remove the tracked reactions of a user.
+    #[instrument(skip(self))]
+    pub async fn clear_reactions(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<(), anyhow::Error> {
+        let mut conn = self.conn().await?;
+        let _: () = conn.del(key(guild_id, user_id)).await?;
+
+        Ok(())
+    }
+}
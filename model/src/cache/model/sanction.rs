@@ -0,0 +1,84 @@
+//! Sanction cooldown.
+//!
+//! When two moderators act on the same user at the same time, the user could
+//! be sanctioned twice. A short cooldown is stored after each sanction, during
+//! which another sanction on the same user requires a confirmation.
+//!
+//! The cooldown duration is configured per guild, so the key expiration is set
+//! with [`CacheStore::set_with_expiry`] or [`CacheStore::set_if_absent`]
+//! instead of [`RedisModel::EXPIRES_AFTER`].
+//!
+//! [`CacheStore::set_with_expiry`]: crate::store::CacheStore::set_with_expiry
+//! [`CacheStore::set_if_absent`]: crate::store::CacheStore::set_if_absent
+
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use time::OffsetDateTime;
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+
+use crate::{
+    cache::RedisModel,
+    database::model::ModlogType,
+    serde::{DateTimeAsI64, IdAsU64},
+};
+
+/// Last sanction of a user, kept during the sanction cooldown.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SanctionCooldown {
+    /// Id of the guild.
+    #[serde_as(as = "IdAsU64")]
+    pub guild_id: Id<GuildMarker>,
+    /// Id of the sanctioned user.
+    #[serde_as(as = "IdAsU64")]
+    pub user_id: Id<UserMarker>,
+    /// Id of the moderator that performed the sanction.
+    #[serde_as(as = "IdAsU64")]
+    pub moderator_id: Id<UserMarker>,
+    /// Type of the sanction.
+    pub kind: ModlogType,
+    /// Date of the sanction.
+    #[serde_as(as = "DateTimeAsI64")]
+    pub date: OffsetDateTime,
+}
+
+impl RedisModel for SanctionCooldown {
+    type Id = (Id<GuildMarker>, Id<UserMarker>);
+
+    fn key(&self) -> String {
+        Self::key_from(&(self.guild_id, self.user_id))
+    }
+
+    fn key_from((guild_id, user_id): &Self::Id) -> String {
+        format!("sanction-cooldown:{guild_id}:{user_id}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::OffsetDateTime;
+    use twilight_model::id::Id;
+
+    use super::SanctionCooldown;
+    use crate::{cache::RedisModel, database::model::ModlogType};
+
+    #[test]
+    fn test_sanction_cooldown_roundtrip() {
+        let cooldown = SanctionCooldown {
+            guild_id: Id::new(1),
+            user_id: Id::new(2),
+            moderator_id: Id::new(3),
+            kind: ModlogType::Kick,
+            date: OffsetDateTime::from_unix_timestamp(1_628_594_197).unwrap(),
+        };
+
+        let serialized = cooldown.serialize_model().unwrap();
+        let deserialized = SanctionCooldown::deserialize_model(serialized).unwrap();
+
+        assert_eq!(cooldown, deserialized);
+        assert_eq!(cooldown.key(), "sanction-cooldown:1:2");
+    }
+}
@@ -0,0 +1,93 @@
+//! Guild count of each shard.
+//!
+//! Each shard periodically publishes the number of guilds it handles in a
+//! Redis key. Keys expire if not refreshed, so the shards of a stopped process
+//! are no longer counted. The total guild count is the sum of the published
+//! counts, and is only partial until every shard has reported.
+
+use redis::AsyncCommands;
+use tracing::instrument;
+
+use crate::cache::CacheClient;
+
+/// Get the key of the guild count of a shard.
+fn key(shard_id: u64) -> String {
+    format!("shard:guilds:{shard_id}")
+}
+
+/// Guild count aggregated across all shards.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GuildCount {
+    /// Total number of guilds of the shards that have reported.
+    pub guilds: u64,
+    /// Number of shards that have reported their guild count.
+    pub reported: u64,
+    /// Total number of shards.
+    pub shards: u64,
+}
+
+impl GuildCount {
+    /// Whether every shard has reported its guild count.
+    pub fn is_complete(&self) -> bool {
+        self.reported >= self.shards
+    }
+}
+
+// Implementation of methods to aggregate the guild count.
+impl CacheClient {
+    /// Publish the guild count of a shard.
+    ///
+    /// The count expires after `expires` seconds if not published again.
+    #[instrument(skip(self))]
+    pub async fn publish_shard_guilds(
+        &self,
+        shard_id: u64,
+        count: u64,
+        expires: usize,
+    ) -> Result<(), anyhow::Error> {
+        let mut conn = self.conn().await?;
+        let _: () = conn.set_ex(key(shard_id), count, expires).await?;
+
+        Ok(())
+    }
+
+    /// Get the guild count aggregated across all shards.
+    #[instrument(skip(self))]
+    pub async fn shard_guild_count(&self, shards: u64) -> Result<GuildCount, anyhow::Error> {
+        if shards == 0 {
+            return Ok(GuildCount::default());
+        }
+
+        let mut conn = self.conn().await?;
+        let keys = (0..shards).map(key).collect::<Vec<_>>();
+
+        let counts: Vec<Option<u64>> = redis::cmd("MGET")
+            .arg(&keys)
+            .query_async(&mut *conn)
+            .await?;
+
+        Ok(GuildCount {
+            guilds: counts.iter().flatten().sum(),
+            reported: counts.iter().flatten().count() as u64,
+            shards,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GuildCount;
+
+    #[test]
+    fn test_guild_count_complete() {
+        let mut count = GuildCount {
+            guilds: 1500,
+            reported: 1,
+            shards: 2,
+        };
+        assert!(!count.is_complete());
+
+        count.reported = 2;
+        assert!(count.is_complete());
+    }
+}
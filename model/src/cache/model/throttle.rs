@@ -0,0 +1,63 @@
+//! Actions throttling.
+//!
+//! Some actions, such as notices sent in the logs channel, must not be
+//! executed too frequently. A Redis key with an expiration is used to know
+//! whether the action has been executed recently.
+//!
+//! Actions that can be executed several times during an interval use a
+//! counter instead (see [`CacheClient::rate_limit`]).
+
+use time::Duration;
+use tracing::instrument;
+
+use crate::cache::CacheClient;
+
+// Implementation of methods to throttle actions.
+impl CacheClient {
+    /// Check whether a throttled action can be executed.
+    ///
+    /// Returns `true` if the action has not been executed during the last
+    /// `interval`, in which case the action is marked as executed.
+    #[instrument(skip(self))]
+    pub async fn throttle(&self, key: &str, interval: Duration) -> Result<bool, anyhow::Error> {
+        let mut conn = self.conn().await?;
+
+        let result: Option<String> = redis::cmd("SET")
+            .arg(format!("throttle:{key}"))
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(interval.whole_seconds().max(1))
+            .query_async(&mut *conn)
+            .await?;
+
+        Ok(result.is_some())
+    }
+
+    /// Check whether a rate-limited action can be executed.
+    ///
+    /// Returns `true` if the action has been executed less than `limit` times
+    /// during the current `window`. The window starts on the first execution.
+    #[instrument(skip(self))]
+    pub async fn rate_limit(
+        &self,
+        key: &str,
+        limit: u32,
+        window: Duration,
+    ) -> Result<bool, anyhow::Error> {
+        let mut conn = self.conn().await?;
+        let key = format!("ratelimit:{key}");
+
+        let count: u32 = redis::cmd("INCR").arg(&key).query_async(&mut *conn).await?;
+
+        if count == 1 {
+            redis::cmd("EXPIRE")
+                .arg(&key)
+                .arg(window.whole_seconds().max(1))
+                .query_async::<_, ()>(&mut *conn)
+                .await?;
+        }
+
+        Ok(count <= limit)
+    }
+}
@@ -0,0 +1,93 @@
+//! Voice states of guild members.
+//!
+//! The `VoiceStateUpdate` event only contains the new voice state of a member.
+//! The last-known state of each member connected to a voice channel is stored
+//! to know whether the member joined, left or moved between channels.
+
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use twilight_model::{
+    id::{
+        marker::{ChannelMarker, GuildMarker, UserMarker},
+        Id,
+    },
+    voice::VoiceState,
+};
+
+use crate::{cache::RedisModel, serde::IdAsU64};
+
+/// Voice state of a member connected to a voice channel.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CachedVoiceState {
+    /// Id of the guild.
+    #[serde_as(as = "IdAsU64")]
+    pub guild_id: Id<GuildMarker>,
+    /// Id of the member.
+    #[serde_as(as = "IdAsU64")]
+    pub user_id: Id<UserMarker>,
+    /// Voice channel the member is connected to.
+    #[serde_as(as = "IdAsU64")]
+    pub channel_id: Id<ChannelMarker>,
+    /// Whether the member is muted by the server.
+    pub mute: bool,
+    /// Whether the member is deafened by the server.
+    pub deaf: bool,
+}
+
+impl CachedVoiceState {
+    /// Initialize a [`CachedVoiceState`] from a [`VoiceState`].
+    ///
+    /// Returns [`None`] if the member is not connected to a guild voice
+    /// channel.
+    pub fn from_voice_state(state: &VoiceState) -> Option<Self> {
+        Some(Self {
+            guild_id: state.guild_id?,
+            user_id: state.user_id,
+            channel_id: state.channel_id?,
+            mute: state.mute,
+            deaf: state.deaf,
+        })
+    }
+}
+
+impl RedisModel for CachedVoiceState {
+    type Id = (Id<GuildMarker>, Id<UserMarker>);
+
+    /// States expire after a day without updates, in case the disconnection
+    /// of the member has been missed.
+    const EXPIRES_AFTER: Option<usize> = Some(24 * 60 * 60);
+
+    fn key(&self) -> String {
+        Self::key_from(&(self.guild_id, self.user_id))
+    }
+
+    fn key_from((guild_id, user_id): &Self::Id) -> String {
+        format!("voice:{guild_id}:{user_id}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::id::Id;
+
+    use super::CachedVoiceState;
+    use crate::cache::RedisModel;
+
+    #[test]
+    fn test_voice_state_roundtrip() {
+        let state = CachedVoiceState {
+            guild_id: Id::new(1),
+            user_id: Id::new(2),
+            channel_id: Id::new(3),
+            mute: true,
+            deaf: false,
+        };
+
+        let serialized = state.serialize_model().unwrap();
+        let deserialized = CachedVoiceState::deserialize_model(serialized).unwrap();
+
+        assert_eq!(state, deserialized);
+        assert_eq!(state.key(), "voice:1:2");
+    }
+}
@@ -0,0 +1,160 @@
+//! Database write-behind queue.
+//!
+//! When the database is unavailable, some writes are stored in a Redis list to
+//! be executed once the database is available again. Writes are executed in
+//! the same order they were queued.
+//!
+//! The queue length is limited to protect Redis memory. When the queue is
+//! full, the oldest non-critical writes are dropped.
+
+use anyhow::Context;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use tracing::{instrument, trace, warn};
+use twilight_model::id::{
+    marker::{ChannelMarker, GuildMarker},
+    Id,
+};
+
+use crate::{cache::CacheClient, database::model::Modlog, serde::IdAsU64};
+
+/// Key of the list used to store pending writes.
+const QUEUE_KEY: &str = "database:write_queue";
+
+/// Maximum number of pending writes in the queue.
+pub const MAX_QUEUE_LEN: isize = 10_000;
+
+/// Database write waiting to be executed.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PendingWrite {
+    /// Insert a new modlog.
+    Modlog { modlog: Box<Modlog> },
+    /// Update the logs channel of a guild.
+    LogsChannel {
+        #[serde_as(as = "IdAsU64")]
+        guild_id: Id<GuildMarker>,
+        #[serde_as(as = "IdAsU64")]
+        channel_id: Id<ChannelMarker>,
+    },
+}
+
+impl PendingWrite {
+    /// Whether the write is critical.
+    ///
+    /// Critical writes are never dropped from the queue, even if it is full.
+    pub fn is_critical(&self) -> bool {
+        match self {
+            PendingWrite::Modlog { .. } => true,
+            PendingWrite::LogsChannel { .. } => false,
+        }
+    }
+
+    /// Serialize the write in MessagePack.
+    fn serialize(&self) -> Result<Vec<u8>, anyhow::Error> {
+        rmp_serde::to_vec_named(self).context("failed to serialize pending write")
+    }
+
+    /// Deserialize the write from MessagePack.
+    fn deserialize(value: &[u8]) -> Result<Self, anyhow::Error> {
+        rmp_serde::from_slice(value).context("failed to deserialize pending write")
+    }
+}
+
+// Implementation of methods to manage the write queue.
+impl CacheClient {
+    /// Add a write at the end of the queue.
+    ///
+    /// If the queue is full, the oldest non-critical write is dropped. The
+    /// new queue length is returned.
+    #[instrument(skip(self))]
+    pub async fn queue_write(&self, write: &PendingWrite) -> Result<usize, anyhow::Error> {
+        let mut conn = self.conn().await?;
+
+        let len: isize = conn.llen(QUEUE_KEY).await?;
+        if len >= MAX_QUEUE_LEN {
+            let values: Vec<Vec<u8>> = conn.lrange(QUEUE_KEY, 0, -1).await?;
+            let dropped = values.into_iter().find(|value| {
+                PendingWrite::deserialize(value)
+                    .map(|write| !write.is_critical())
+                    .unwrap_or(true)
+            });
+
+            match dropped {
+                Some(value) => {
+                    let _: () = conn.lrem(QUEUE_KEY, 1, value).await?;
+                    warn!("write queue is full, dropped oldest non-critical write");
+                }
+                None => warn!("write queue is full with critical writes"),
+            }
+        }
+
+        trace!("queuing write");
+        let len: usize = conn.rpush(QUEUE_KEY, write.serialize()?).await?;
+
+        Ok(len)
+    }
+
+    /// Get the first write of the queue, without removing it.
+    ///
+    /// The write must be removed with [`CacheClient::pop_write`] once it has
+    /// been executed. Writes that cannot be deserialized are removed.
+    #[instrument(skip(self))]
+    pub async fn peek_write(&self) -> Result<Option<PendingWrite>, anyhow::Error> {
+        let mut conn = self.conn().await?;
+
+        loop {
+            let value: Option<Vec<u8>> = conn.lindex(QUEUE_KEY, 0).await?;
+
+            match value.as_deref().map(PendingWrite::deserialize) {
+                Some(Ok(write)) => return Ok(Some(write)),
+                Some(Err(error)) => {
+                    warn!(error = ?error, "dropping invalid pending write");
+                    let _: () = conn.lpop(QUEUE_KEY, None).await?;
+                }
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Remove the first write of the queue.
+    #[instrument(skip(self))]
+    pub async fn pop_write(&self) -> Result<(), anyhow::Error> {
+        let mut conn = self.conn().await?;
+        let _: () = conn.lpop(QUEUE_KEY, None).await?;
+
+        Ok(())
+    }
+
+    /// Get the number of writes in the queue.
+    #[instrument(skip(self))]
+    pub async fn write_queue_len(&self) -> Result<usize, anyhow::Error> {
+        let mut conn = self.conn().await?;
+        let len: usize = conn.llen(QUEUE_KEY).await?;
+
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::id::Id;
+
+    use super::PendingWrite;
+
+    #[test]
+    fn test_pending_write_roundtrip() {
+        let write = PendingWrite::LogsChannel {
+            guild_id: Id::new(1),
+            channel_id: Id::new(2),
+        };
+
+        let serialized = write.serialize().unwrap();
+        let deserialized = PendingWrite::deserialize(&serialized).unwrap();
+
+        assert_eq!(write, deserialized);
+        assert!(!write.is_critical());
+    }
+}
@@ -4,10 +4,27 @@
 //! values are loaded at runtime from environment variables prefixed with
 //! `RAIDPROTECT_` (see [`parse_config`]). These variables can be loaded from
 //! a `.env` file.
+//!
+//! The bot configuration can also be loaded from a TOML file and overridden
+//! with command-line flags (see [`parse_bot_config`]). The keys of the file
+//! are the names of the environment variables without the prefix, in
+//! lowercase.
+
+use std::{fmt, net::SocketAddr, path::Path, str::FromStr};
 
-use std::net::SocketAddr;
+use anyhow::{bail, Context};
+use figment::{
+    providers::{Env, Format, Serialized, Toml},
+    Figment,
+};
+use serde::{de, Deserialize, Serialize};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
 
-use serde::{de, Deserialize};
+/// Prefix of the environment variables.
+const ENV_PREFIX: &str = "RAIDPROTECT_";
+
+/// Configuration file loaded if no file is explicitly set.
+const DEFAULT_CONFIG_FILE: &str = "raidprotect.toml";
 
 /// Parse configuration from environment variables.
 ///
@@ -18,14 +35,117 @@ where
     T: de::DeserializeOwned,
 {
     dotenv::dotenv().ok();
-    envy::prefixed("RAIDPROTECT_").from_env()
+    envy::prefixed(ENV_PREFIX).from_env()
+}
+
+/// Parse the bot configuration.
+///
+/// The configuration is merged from the following sources, each source
+/// overriding the previous ones:
+///
+/// - the TOML configuration `file`, or `raidprotect.toml` if it exists in the
+///   current directory
+/// - the environment variables prefixed with `RAIDPROTECT_` (also loaded from
+///   a `.env` file)
+/// - the `overrides`, usually parsed from command-line flags. Fields
+///   serialized as `None` are ignored.
+///
+/// The configuration is validated with [`BotConfig::validate`].
+pub fn parse_bot_config(
+    file: Option<&Path>,
+    overrides: impl Serialize,
+) -> Result<BotConfig, anyhow::Error> {
+    dotenv::dotenv().ok();
+
+    let file = match file {
+        Some(file) if !file.exists() => {
+            bail!("configuration file {} not found", file.display())
+        }
+        Some(file) => file,
+        None => Path::new(DEFAULT_CONFIG_FILE),
+    };
+
+    let config: BotConfig = Figment::new()
+        .merge(Toml::file(file))
+        .merge(Env::prefixed(ENV_PREFIX))
+        .merge(Serialized::defaults(overrides))
+        .extract()?;
+
+    config.validate()?;
+
+    Ok(config)
 }
 
 /// Base bot configuration model.
+///
+/// Secrets are redacted in the [`Debug`] output of this type, which can be
+/// safely logged.
 #[derive(Debug, Deserialize, Clone)]
 pub struct BotConfig {
     /// Discord bot token.
-    pub token: String,
+    pub token: Secret,
+    /// Ids of the bot operators.
+    ///
+    /// Operators have access to the `/admin` command. Multiple ids are
+    /// separated by commas.
+    #[serde(default, deserialize_with = "deserialize_list")]
+    pub operators: Vec<u64>,
+    /// Shards started by this process.
+    ///
+    /// If not set, all the shards recommended by Discord are started.
+    #[serde(default)]
+    pub shards: Option<ShardRange>,
+    /// Only run the background consumers.
+    ///
+    /// In this mode, the process does not connect to the Discord gateway and
+    /// only runs the scheduled jobs, the database write queue flusher and the
+    /// API server. This allows scaling the consumers independently from the
+    /// shards.
+    #[serde(default)]
+    pub consumer_only: bool,
+    /// Activity shown in the bot presence.
+    ///
+    /// The `{guilds}` placeholder is replaced by the number of guilds the bot
+    /// is in. If not set, the guild count is shown in the language set by
+    /// [`activity_lang`].
+    ///
+    /// [`activity_lang`]: Self::activity_lang
+    #[serde(default)]
+    pub activity: Option<String>,
+    /// Language of the default activity. Defaults to `en`.
+    #[serde(default = "default_activity_lang")]
+    pub activity_lang: String,
+    /// Maximum duration (in seconds) of an interaction handler.
+    ///
+    /// Handlers that take longer are cancelled. Defaults to 60 seconds.
+    #[serde(default = "default_interaction_timeout")]
+    pub interaction_timeout: u64,
+    /// Listening address of the health-check server.
+    ///
+    /// The server is disabled if no address is set.
+    #[serde(default)]
+    pub health_address: Option<SocketAddr>,
+    /// Listening address of the dashboard API server.
+    ///
+    /// The server is disabled if no address is set.
+    #[serde(default)]
+    pub api_address: Option<SocketAddr>,
+    /// Listening address of the Prometheus metrics exporter.
+    ///
+    /// The exporter is disabled if no address is set.
+    #[serde(default)]
+    pub metrics_address: Option<SocketAddr>,
+    /// Origins allowed to call the dashboard API from a browser.
+    ///
+    /// Multiple origins are separated by commas. Cross-origin requests are
+    /// rejected if no origin is set.
+    #[serde(default, deserialize_with = "deserialize_list")]
+    pub api_cors_origins: Vec<String>,
+    /// Maximum number of requests per minute for each API token.
+    ///
+    /// Defaults to 60 requests.
+    #[serde(default = "default_api_rate_limit")]
+    pub api_rate_limit: u32,
     /// Databases configuration.
     #[serde(flatten, default)]
     pub database: shared::DatabaseConfig,
@@ -34,6 +154,159 @@ pub struct BotConfig {
     pub log: shared::LogConfig,
 }
 
+impl BotConfig {
+    /// Check that the configuration values are valid.
+    ///
+    /// This does not check the connection to the external services.
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
+        if self.token.expose().trim().is_empty() {
+            bail!("the bot token is empty");
+        }
+
+        if self.operators.contains(&0) {
+            bail!("operator ids must not be zero");
+        }
+
+        if self.interaction_timeout == 0 {
+            bail!("the interaction timeout must not be zero");
+        }
+
+        if let Some(shards) = self.shards {
+            if self.consumer_only {
+                bail!("shards cannot be set in consumer-only mode");
+            }
+
+            shards.validate()?;
+        }
+
+        self.database.validate()
+    }
+}
+
+/// Secret configuration value.
+///
+/// The value is redacted in the [`Debug`] output.
+#[derive(Deserialize, Clone, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    /// Get the secret value.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[redacted]")
+    }
+}
+
+/// Range of shards started by a process.
+///
+/// The range is written as `from-to/total`, where `from` and `to` are the
+/// ids of the first and last shards (inclusive), and `total` is the total
+/// number of shards of the bot. For example, `0-3/8` starts the first four
+/// shards out of eight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, SerializeDisplay, DeserializeFromStr)]
+pub struct ShardRange {
+    /// Id of the first shard.
+    pub from: u64,
+    /// Id of the last shard.
+    pub to: u64,
+    /// Total number of shards.
+    pub total: u64,
+}
+
+impl ShardRange {
+    /// Check that the range is consistent with the total number of shards.
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
+        if self.total == 0 {
+            bail!("the total number of shards must not be zero");
+        }
+
+        if self.from > self.to || self.to >= self.total {
+            bail!("invalid shard range {self}");
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for ShardRange {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (range, total) = s.split_once('/').context("missing total shard count")?;
+        let (from, to) = range.split_once('-').context("missing last shard id")?;
+
+        Ok(Self {
+            from: from.trim().parse().context("invalid first shard id")?,
+            to: to.trim().parse().context("invalid last shard id")?,
+            total: total.trim().parse().context("invalid total shard count")?,
+        })
+    }
+}
+
+impl fmt::Display for ShardRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}/{}", self.from, self.to, self.total)
+    }
+}
+
+/// Deserialize a list that may be written as comma-separated values.
+///
+/// Environment variables cannot contain lists, so their values are separated
+/// by commas. Lists from the configuration file are also accepted.
+fn deserialize_list<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: de::Deserializer<'de>,
+    T: FromStr + Deserialize<'de>,
+    T::Err: fmt::Display,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum List<T> {
+        Seq(Vec<T>),
+        Single(u64),
+        Str(String),
+    }
+
+    let parse = |value: &str| value.trim().parse::<T>().map_err(de::Error::custom);
+
+    match List::<T>::deserialize(deserializer)? {
+        List::Seq(values) => Ok(values),
+        List::Single(value) => Ok(vec![parse(&value.to_string())?]),
+        List::Str(value) => value
+            .split(',')
+            .filter(|value| !value.trim().is_empty())
+            .map(parse)
+            .collect(),
+    }
+}
+
+/// Default language of the bot activity.
+fn default_activity_lang() -> String {
+    "en".to_owned()
+}
+
+/// Default interaction handler timeout.
+fn default_interaction_timeout() -> u64 {
+    60
+}
+
+/// Default API rate limit.
+fn default_api_rate_limit() -> u32 {
+    60
+}
+
 /// Base web api configuration model.
 #[derive(Debug, Deserialize, Clone)]
 pub struct WebConfig {
@@ -55,14 +328,23 @@ fn default_address() -> SocketAddr {
 
 /// Models shared by the base models.
 pub mod shared {
+    use std::fmt::{Debug, Formatter};
+
+    use anyhow::bail;
     use serde::{de, Deserialize};
     use tracing::Level;
     use tracing_appender::non_blocking::WorkerGuard;
+    use tracing_subscriber::{fmt, prelude::*};
+    use url::Url;
+
+    use crate::log::{GuildFilter, GuildLayer};
 
     /// Databases configuration model.
     ///
     /// This model holds configuration values for Redis and MongoDB database.
-    #[derive(Debug, Deserialize, Clone)]
+    /// The credentials of the connection uris are redacted in the [`Debug`]
+    /// output.
+    #[derive(Deserialize, Clone)]
     #[serde(default)]
     pub struct DatabaseConfig {
         /// Redis connection uri.
@@ -83,6 +365,53 @@ pub mod shared {
         pub mongodb_database: String,
     }
 
+    impl DatabaseConfig {
+        /// Check the scheme of the connection uris.
+        pub fn validate(&self) -> Result<(), anyhow::Error> {
+            match Url::parse(&self.redis_uri) {
+                Ok(uri) if matches!(uri.scheme(), "redis" | "rediss" | "redis+unix") => {}
+                _ => bail!("invalid redis uri, expected a redis:// uri"),
+            }
+
+            match Url::parse(&self.mongodb_uri) {
+                Ok(uri) if matches!(uri.scheme(), "mongodb" | "mongodb+srv") => {}
+                _ => bail!("invalid mongodb uri, expected a mongodb:// uri"),
+            }
+
+            if self.mongodb_database.is_empty() {
+                bail!("the mongodb database name is empty");
+            }
+
+            Ok(())
+        }
+    }
+
+    impl Debug for DatabaseConfig {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("DatabaseConfig")
+                .field("redis_uri", &redact_uri(&self.redis_uri))
+                .field("mongodb_uri", &redact_uri(&self.mongodb_uri))
+                .field("mongodb_database", &self.mongodb_database)
+                .finish()
+        }
+    }
+
+    /// Redact the password of a connection uri.
+    ///
+    /// Invalid uris are entirely redacted since they may contain credentials.
+    fn redact_uri(uri: &str) -> String {
+        let mut uri = match Url::parse(uri) {
+            Ok(uri) => uri,
+            Err(_) => return "[redacted]".to_owned(),
+        };
+
+        if uri.password().is_some() {
+            uri.set_password(Some("redacted")).ok();
+        }
+
+        uri.to_string()
+    }
+
     impl Default for DatabaseConfig {
         fn default() -> Self {
             Self {
@@ -111,9 +440,14 @@ pub mod shared {
     impl LogConfig {
         /// Init logger depending on the configured [`LogKind`].
         ///
+        /// The logs of the guilds set with [`set_debug_guilds`] are emitted up
+        /// to the `DEBUG` level regardless of the configured level.
+        ///
         /// The returned [`WorkerGuard`] must be stored in a variable
         /// and dropped when the main process exists. This ensure that
         /// all remaining logs are written when using [`LogKind::File`].
+        ///
+        /// [`set_debug_guilds`]: crate::log::set_debug_guilds
         pub fn init(&self, name: impl AsRef<str>) -> Option<WorkerGuard> {
             match self.log_type {
                 LogKind::Terminal => self.init_terminal(),
@@ -124,9 +458,13 @@ pub mod shared {
 
         /// Init logger with [`LogKind::Terminal`].
         fn init_terminal(&self) -> Option<WorkerGuard> {
-            tracing_subscriber::fmt()
-                .compact()
-                .with_max_level(self.log_level)
+            tracing_subscriber::registry()
+                .with(GuildLayer)
+                .with(
+                    fmt::layer()
+                        .compact()
+                        .with_filter(GuildFilter::new(self.log_level)),
+                )
                 .init();
 
             None
@@ -140,11 +478,15 @@ pub mod shared {
             let appender = tracing_appender::rolling::hourly(&self.log_folder, name);
             let (writer, guard) = tracing_appender::non_blocking(appender);
 
-            tracing_subscriber::fmt()
-                .compact()
-                .with_max_level(self.log_level)
-                .with_writer(writer)
-                .with_ansi(false)
+            tracing_subscriber::registry()
+                .with(GuildLayer)
+                .with(
+                    fmt::layer()
+                        .compact()
+                        .with_writer(writer)
+                        .with_ansi(false)
+                        .with_filter(GuildFilter::new(self.log_level)),
+                )
                 .init();
 
             Some(guard)
@@ -1,6 +1,10 @@
 use std::time::Duration;
 
-use mongodb::{bson::doc, options, Client, Database};
+use mongodb::{
+    bson::doc,
+    error::{Error as MongoError, ErrorKind, WriteFailure},
+    options, Client, Database,
+};
 
 /// Wrapper around a MongoDB [`Client`].
 ///
@@ -48,4 +52,32 @@ impl DbClient {
 
         Ok(())
     }
+
+    /// Check whether an error has been caused by the database being
+    /// unavailable.
+    ///
+    /// This returns `true` for network errors and server selection timeouts.
+    pub fn is_unavailable(error: &anyhow::Error) -> bool {
+        error
+            .chain()
+            .any(|error| match error.downcast_ref::<MongoError>() {
+                Some(error) => matches!(
+                    *error.kind,
+                    ErrorKind::ServerSelection { .. }
+                        | ErrorKind::Io(_)
+                        | ErrorKind::ConnectionPoolCleared { .. }
+                ),
+                None => false,
+            })
+    }
+
+    /// Check whether an error is a duplicate key error.
+    ///
+    /// This is the case when an upsert conflicts with a concurrent insert.
+    pub(crate) fn is_duplicate_key(error: &MongoError) -> bool {
+        matches!(
+            &*error.kind,
+            ErrorKind::Write(WriteFailure::WriteError(error)) if error.code == 11000
+        )
+    }
 }
@@ -0,0 +1,148 @@
+//! Features that can be enabled or disabled in a guild.
+
+use serde::{Deserialize, Serialize};
+
+/// Feature that can be toggled with the `/features` command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    SpamFilter,
+    LinkFilter,
+    RaidDetection,
+    JoinLogs,
+    MessageLogs,
+    Verification,
+    AutoSlowmode,
+}
+
+impl Feature {
+    /// List of all the features.
+    pub const ALL: [Feature; 7] = [
+        Feature::SpamFilter,
+        Feature::LinkFilter,
+        Feature::RaidDetection,
+        Feature::JoinLogs,
+        Feature::MessageLogs,
+        Feature::Verification,
+        Feature::AutoSlowmode,
+    ];
+
+    /// Get the name of the feature.
+    ///
+    /// This name is used in component custom ids.
+    pub fn name(self) -> &'static str {
+        match self {
+            Feature::SpamFilter => "spam-filter",
+            Feature::LinkFilter => "link-filter",
+            Feature::RaidDetection => "raid-detection",
+            Feature::JoinLogs => "join-logs",
+            Feature::MessageLogs => "message-logs",
+            Feature::Verification => "verification",
+            Feature::AutoSlowmode => "auto-slowmode",
+        }
+    }
+
+    /// Get a feature from its name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|feature| feature.name() == name)
+    }
+
+    /// Bit of the feature in a [`FeatureSet`].
+    const fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+/// Set of enabled [`Feature`]s.
+///
+/// The set is stored as a bitflag, where each bit corresponds to a feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FeatureSet(u32);
+
+impl FeatureSet {
+    /// Features enabled by default in new guilds.
+    ///
+    /// Guilds created before features could be disabled also use this value.
+    pub const DEFAULT: FeatureSet = FeatureSet::all();
+
+    /// Create a set with all the features.
+    pub const fn all() -> Self {
+        let mut bits = 0;
+        let mut i = 0;
+
+        while i < Feature::ALL.len() {
+            bits |= Feature::ALL[i].bit();
+            i += 1;
+        }
+
+        Self(bits)
+    }
+
+    /// Create an empty set.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Whether a feature is enabled.
+    pub fn contains(self, feature: Feature) -> bool {
+        self.0 & feature.bit() != 0
+    }
+
+    /// Enable or disable a feature.
+    pub fn set(&mut self, feature: Feature, enabled: bool) {
+        if enabled {
+            self.0 |= feature.bit();
+        } else {
+            self.0 &= !feature.bit();
+        }
+    }
+
+    /// Toggle a feature, and return whether it is now enabled.
+    pub fn toggle(&mut self, feature: Feature) -> bool {
+        let enabled = !self.contains(feature);
+        self.set(feature, enabled);
+
+        enabled
+    }
+}
+
+impl Default for FeatureSet {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Feature, FeatureSet};
+
+    #[test]
+    fn test_default_features() {
+        let features = FeatureSet::default();
+
+        for feature in Feature::ALL {
+            assert!(features.contains(feature));
+        }
+    }
+
+    #[test]
+    fn test_toggle_feature() {
+        let mut features = FeatureSet::empty();
+
+        assert!(features.toggle(Feature::JoinLogs));
+        assert!(features.contains(Feature::JoinLogs));
+        assert!(!features.contains(Feature::SpamFilter));
+
+        assert!(!features.toggle(Feature::JoinLogs));
+        assert_eq!(features, FeatureSet::empty());
+    }
+
+    #[test]
+    fn test_feature_name() {
+        for feature in Feature::ALL {
+            assert_eq!(Feature::from_name(feature.name()), Some(feature));
+        }
+
+        assert_eq!(Feature::from_name("unknown"), None);
+    }
+}
@@ -0,0 +1,124 @@
+//! Models for the `filter_feedback` collection.
+
+use mongodb::{
+    bson::{doc, oid::ObjectId},
+    options,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, skip_serializing_none};
+use time::OffsetDateTime;
+use tracing::instrument;
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+
+use super::{stats::StatsFilter, DbClient};
+use crate::{
+    log::QueryLatency,
+    serde::{datetime_to_bson, DateTimeAsBson, IdAsI64},
+};
+
+/// Message deleted by a filter and marked as a false positive.
+///
+/// This type represent a feedback stored in the `filter_feedback` collection
+/// of the database. The deleted message is identified by the fingerprint of
+/// its content, so that messages with the same content can be ignored by the
+/// filters while the feedback is recent.
+#[serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct FilterFeedback {
+    /// Unique ID of the feedback.
+    #[serde(rename = "_id")]
+    pub id: Option<ObjectId>,
+    /// Guild where the message was deleted.
+    #[serde_as(as = "IdAsI64")]
+    pub guild_id: Id<GuildMarker>,
+    /// Filter that deleted the message.
+    pub filter: StatsFilter,
+    /// Fingerprint of the content of the deleted message.
+    pub fingerprint: String,
+    /// Moderator that marked the message as a false positive.
+    #[serde_as(as = "IdAsI64")]
+    pub reporter: Id<UserMarker>,
+    /// Date of the feedback.
+    #[serde_as(as = "DateTimeAsBson")]
+    pub date: OffsetDateTime,
+}
+
+impl FilterFeedback {
+    /// Name of the MongoDB collection.
+    pub const COLLECTION: &'static str = "filter_feedback";
+}
+
+// Implementation of methods to query the database.
+impl DbClient {
+    /// Insert a new [`FilterFeedback`] in the database.
+    #[instrument(level = "debug", skip_all, fields(guild_id = feedback.guild_id.get(), latency_ms))]
+    pub async fn create_filter_feedback(
+        &self,
+        feedback: &FilterFeedback,
+    ) -> Result<(), anyhow::Error> {
+        let _latency = QueryLatency::start();
+
+        self.db()
+            .collection::<FilterFeedback>(FilterFeedback::COLLECTION)
+            .insert_one(feedback, None)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Get the most recent [`FilterFeedback`]s of a guild, most recent first.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn recent_filter_feedback(
+        &self,
+        guild_id: Id<GuildMarker>,
+        limit: i64,
+    ) -> Result<Vec<FilterFeedback>, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let options = options::FindOptions::builder()
+            .sort(doc! { "date": -1 })
+            .limit(limit)
+            .build();
+
+        let mut cursor = self
+            .db()
+            .collection::<FilterFeedback>(FilterFeedback::COLLECTION)
+            .find(doc! { "guild_id": guild_id.get() as i64 }, options)
+            .await?;
+
+        let mut feedback = Vec::new();
+        while cursor.advance().await? {
+            feedback.push(cursor.deserialize_current()?);
+        }
+
+        Ok(feedback)
+    }
+
+    /// Whether a content fingerprint has been marked as a false positive in
+    /// a guild after a given date.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn is_false_positive(
+        &self,
+        guild_id: Id<GuildMarker>,
+        fingerprint: &str,
+        after: OffsetDateTime,
+    ) -> Result<bool, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let query = doc! {
+            "guild_id": guild_id.get() as i64,
+            "fingerprint": fingerprint,
+            "date": { "$gte": datetime_to_bson(after) },
+        };
+
+        let feedback = self
+            .db()
+            .collection::<FilterFeedback>(FilterFeedback::COLLECTION)
+            .find_one(query, None)
+            .await?;
+
+        Ok(feedback.is_some())
+    }
+}
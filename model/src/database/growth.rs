@@ -0,0 +1,143 @@
+//! Models for the `growth` collection.
+//!
+//! The member count of each guild is sampled when members join or leave, at
+//! most once per sampling interval. Samples are stored in a single
+//! [`GuildGrowth`] document per guild, in a bounded array: only the last
+//! [`GuildGrowth::MAX_SAMPLES`] samples are kept, the oldest ones being
+//! removed as new samples are pushed.
+
+use anyhow::Context;
+use mongodb::{
+    bson::{doc, to_bson},
+    options::{FindOneAndUpdateOptions, ReturnDocument},
+};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use tracing::instrument;
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use super::DbClient;
+use crate::{log::QueryLatency, serde::IdAsI64};
+
+/// Member count history of a guild.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct GuildGrowth {
+    /// Guild of the history.
+    #[serde(rename = "_id")]
+    #[serde_as(as = "IdAsI64")]
+    pub guild_id: Id<GuildMarker>,
+    /// Member count samples, sorted by date.
+    #[serde(default)]
+    pub samples: Vec<GrowthSample>,
+    /// Last member count milestone announced in the guild.
+    #[serde(default)]
+    pub milestone: u64,
+}
+
+impl GuildGrowth {
+    /// Name of the MongoDB collection.
+    pub const COLLECTION: &'static str = "growth";
+
+    /// Maximum number of samples kept for each guild.
+    ///
+    /// With hourly samples, this keeps one week of history.
+    pub const MAX_SAMPLES: i64 = 7 * 24;
+}
+
+/// Member count of a guild at a given date.
+///
+/// Field names are shortened since many samples are stored for each guild.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct GrowthSample {
+    /// Date of the sample, as a Unix timestamp in seconds.
+    #[serde(rename = "t")]
+    pub at: i64,
+    /// Number of members in the guild.
+    #[serde(rename = "m")]
+    pub members: u64,
+}
+
+// Implementation of methods to query the database.
+impl DbClient {
+    /// Get the [`GuildGrowth`] of a guild, if any sample has been recorded.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn get_growth(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<GuildGrowth>, anyhow::Error> {
+        let _latency = QueryLatency::start();
+
+        let growth = self
+            .db()
+            .collection::<GuildGrowth>(GuildGrowth::COLLECTION)
+            .find_one(doc! { "_id": guild_id.get() as i64 }, None)
+            .await?;
+
+        Ok(growth)
+    }
+
+    /// Record a member count sample in the [`GuildGrowth`] of a guild.
+    ///
+    /// The sample is pushed at the end of the samples, and the oldest samples
+    /// are removed so that at most [`GuildGrowth::MAX_SAMPLES`] are kept. The
+    /// updated document is returned.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn record_growth_sample(
+        &self,
+        guild_id: Id<GuildMarker>,
+        sample: GrowthSample,
+    ) -> Result<GuildGrowth, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let options = FindOneAndUpdateOptions::builder()
+            .upsert(true)
+            .return_document(ReturnDocument::After)
+            .build();
+
+        let growth = self
+            .db()
+            .collection::<GuildGrowth>(GuildGrowth::COLLECTION)
+            .find_one_and_update(
+                doc! { "_id": guild_id.get() as i64 },
+                doc! {
+                    "$push": { "samples": {
+                        "$each": [to_bson(&sample)?],
+                        "$slice": -GuildGrowth::MAX_SAMPLES,
+                    } },
+                    "$setOnInsert": { "milestone": 0_i64 },
+                },
+                options,
+            )
+            .await?;
+
+        growth.context("no growth sent by the database")
+    }
+
+    /// Mark a member count milestone as announced in a guild.
+    ///
+    /// Returns `false` if this milestone or a higher one has already been
+    /// announced, so that concurrent joins only announce a milestone once.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn claim_growth_milestone(
+        &self,
+        guild_id: Id<GuildMarker>,
+        milestone: u64,
+    ) -> Result<bool, anyhow::Error> {
+        let _latency = QueryLatency::start();
+
+        let result = self
+            .db()
+            .collection::<GuildGrowth>(GuildGrowth::COLLECTION)
+            .update_one(
+                doc! {
+                    "_id": guild_id.get() as i64,
+                    "milestone": { "$lt": milestone as i64 },
+                },
+                doc! { "$set": { "milestone": milestone as i64 } },
+                None,
+            )
+            .await?;
+
+        Ok(result.modified_count > 0)
+    }
+}
@@ -1,19 +1,30 @@
 //! Models for the `guilds` collection.
 
+use std::fmt;
+
 use anyhow::Context;
 use mongodb::{
     bson::{doc, to_document},
-    options,
+    options::{self, FindOneAndUpdateOptions, ReturnDocument},
+    Cursor,
 };
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, skip_serializing_none};
-use twilight_model::id::{
-    marker::{ChannelMarker, GuildMarker, MessageMarker, RoleMarker},
-    Id,
+use tracing::instrument;
+use twilight_model::{
+    guild::VerificationLevel,
+    id::{
+        marker::{
+            AutoModerationRuleMarker, ChannelMarker, GuildMarker, MessageMarker, RoleMarker,
+            UserMarker,
+        },
+        Id,
+    },
 };
+use url::Url;
 
-use super::DbClient;
-use crate::serde::IdAsI64;
+use super::{feature::FeatureSet, log_routing::LogRouting, modlog::ModlogType, DbClient};
+use crate::{log::QueryLatency, serde::IdAsI64};
 
 /// Guild configuration.
 ///
@@ -31,24 +42,197 @@ pub struct GuildConfig {
     /// The configuration validation will fail if no logs chan is set,
     /// but this field may be [`None`] when the initial configuration
     /// has not yet be done.
+    ///
+    /// This is the default channel of the logs categories that are not routed
+    /// to another channel in [`log_routing`].
+    ///
+    /// [`log_routing`]: Self::log_routing
     #[serde_as(as = "Option<IdAsI64>")]
     #[serde(default)]
     pub logs_chan: Option<Id<ChannelMarker>>,
+    /// Channels where each category of logs is sent.
+    #[serde(default, skip_serializing_if = "LogRouting::is_empty")]
+    pub log_routing: LogRouting,
     /// Lang used for the global guild messages.
     #[serde(default = "default_lang")]
     pub lang: String,
+    /// Whether [`lang`] overrides the user locale for the interaction
+    /// responses visible by other members.
+    ///
+    /// [`lang`]: Self::lang
+    #[serde(default)]
+    pub lang_override: bool,
+    /// Whether [`lang`] also overrides the user locale for ephemeral replies.
+    ///
+    /// This setting has no effect if [`lang_override`] is disabled.
+    ///
+    /// [`lang`]: Self::lang
+    /// [`lang_override`]: Self::lang_override
+    #[serde(default)]
+    pub lang_override_ephemeral: bool,
     /// The moderation module configuration.
     #[serde(default)]
     pub moderation: ModerationConfig,
     /// The captcha module configuration.
     #[serde(default)]
     pub captcha: CaptchaConfig,
+    /// The raid mode configuration.
+    #[serde(default)]
+    pub raid_mode: RaidModeConfig,
+    /// The reaction spam module configuration.
+    #[serde(default)]
+    pub reaction_spam: ReactionSpamConfig,
+    /// The link filter module configuration.
+    #[serde(default)]
+    pub link_filter: LinkFilterConfig,
+    /// Custom pattern rules applied to the messages.
+    ///
+    /// Messages are checked against each rule in order, and the action of the
+    /// first matching rule is applied.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pattern_rules: Vec<PatternRule>,
+    /// Content rules of the channels.
+    ///
+    /// A channel has at most one rule: setting the rule of a channel replaces
+    /// its previous rule. Threads follow the rule of their parent channel.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channel_rules: Vec<ChannelRule>,
+    /// The first message filter configuration.
+    #[serde(default)]
+    pub first_message: FirstMessageConfig,
+    /// The anti-nuke module configuration.
+    #[serde(default)]
+    pub antinuke: AntinukeConfig,
+    /// The join burst detection configuration.
+    #[serde(default)]
+    pub join_burst: JoinBurstConfig,
+    /// The native AutoMod rules configuration.
+    #[serde(default)]
+    pub automod: AutomodConfig,
+    /// Roles ignored by the automatic moderation.
+    ///
+    /// Members with one of these roles are not checked by the filters applied
+    /// to messages, reactions and voice channels. Unlike the trusted roles of
+    /// each filter, these roles apply to every filter, but grant nothing else
+    /// (see [`is_automod_exempt`]).
+    ///
+    /// [`is_automod_exempt`]: Self::is_automod_exempt
+    #[serde_as(as = "Vec<IdAsI64>")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub automod_ignored_roles: Vec<Id<RoleMarker>>,
+    /// The grace period configuration of the automatic sanctions.
+    #[serde(default)]
+    pub grace: GraceConfig,
+    /// The voice channels module configuration.
+    #[serde(default)]
+    pub voice: VoiceConfig,
+    /// The member names normalization configuration.
+    #[serde(default)]
+    pub names: NameConfig,
+    /// The message pins logs configuration.
+    #[serde(default)]
+    pub pins: PinsConfig,
+    /// The member role changes logs configuration.
+    #[serde(default)]
+    pub role_logs: RoleLogsConfig,
+    /// The activity role configuration.
+    #[serde(default)]
+    pub activity_role: ActivityRoleConfig,
+    /// The tags configuration.
+    #[serde(default)]
+    pub tags: TagsConfig,
+    /// Synchronization of the bans with the linked guilds.
+    #[serde(default)]
+    pub ban_sync: BanSync,
+    /// Delay (in seconds) before public command replies are deleted.
+    ///
+    /// If [`None`], the replies are never deleted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delete_replies_after: Option<u16>,
+    /// Retention period (in days) of the moderation logs.
+    ///
+    /// Older moderation logs are periodically deleted. If [`None`], the
+    /// moderation logs are kept forever.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modlog_retention: Option<u16>,
+    /// Age (in hours) below which accounts are marked as new in logs.
+    ///
+    /// Set to `0` to disable the marker. Defaults to 7 days.
+    #[serde(default = "default_new_account_threshold")]
+    pub new_account_threshold: u32,
+    /// Features enabled in the guild.
+    ///
+    /// A disabled feature is skipped regardless of its own configuration.
+    #[serde(default)]
+    pub features: FeatureSet,
+    /// Whether alerts are sent when dangerous permissions are granted.
+    ///
+    /// Alerts are sent in the logs channel on ownership transfers, roles
+    /// gaining dangerous permissions and members receiving such roles.
+    #[serde(default)]
+    pub sentinel_alerts: bool,
+    /// Whether members pending the membership screening are handled
+    /// differently.
+    ///
+    /// If enabled, members are processed (captcha, raid mode) once they have
+    /// accepted the server rules instead of when they join.
+    #[serde(default)]
+    pub screening_aware: bool,
+    /// Role mentioned in the anti-raid alerts, if any.
+    ///
+    /// Mentions are rate-limited, so repeated alerts in a short time are sent
+    /// without pinging the role.
+    #[serde_as(as = "Option<IdAsI64>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raid_alert_role: Option<Id<RoleMarker>>,
+    /// Channel where the watchlist notifications are sent.
+    ///
+    /// If [`None`], the notifications are sent in the logs channel.
+    #[serde_as(as = "Option<IdAsI64>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watch_chan: Option<Id<ChannelMarker>>,
+    /// Duration (in days) after which watched users are removed from the
+    /// watchlist.
+    #[serde(default = "default_watch_duration")]
+    pub watch_duration: u16,
+    /// Channel where the messages reported by members are sent.
+    ///
+    /// If [`None`], members cannot report messages.
+    #[serde_as(as = "Option<IdAsI64>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reports_chan: Option<Id<ChannelMarker>>,
+    /// Channel where the member count milestones are announced.
+    ///
+    /// If [`None`], the milestones are not announced.
+    #[serde_as(as = "Option<IdAsI64>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub milestones_chan: Option<Id<ChannelMarker>>,
+    /// Webhook where moderation events are sent, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outbound_webhook: Option<OutboundWebhook>,
+    /// Whether the onboarding message has been sent to the guild owner.
+    ///
+    /// Guilds created before this field was added are considered onboarded.
+    #[serde(default = "default_onboarded")]
+    pub onboarded: bool,
 }
 
 fn default_lang() -> String {
     "fr".to_owned() // TODO: change default lang to english
 }
 
+fn default_new_account_threshold() -> u32 {
+    7 * 24
+}
+
+fn default_watch_duration() -> u16 {
+    30
+}
+
+fn default_onboarded() -> bool {
+    true
+}
+
 impl GuildConfig {
     /// Name of the MongoDB collection.
     pub const COLLECTION: &'static str = "guilds";
@@ -58,11 +242,77 @@ impl GuildConfig {
         Self {
             id,
             logs_chan: None,
+            log_routing: LogRouting::default(),
             lang: default_lang(),
+            lang_override: false,
+            lang_override_ephemeral: false,
             moderation: ModerationConfig::default(),
             captcha: CaptchaConfig::default(),
+            raid_mode: RaidModeConfig::default(),
+            reaction_spam: ReactionSpamConfig::default(),
+            link_filter: LinkFilterConfig::default(),
+            pattern_rules: Vec::new(),
+            channel_rules: Vec::new(),
+            first_message: FirstMessageConfig::default(),
+            antinuke: AntinukeConfig::default(),
+            join_burst: JoinBurstConfig::default(),
+            automod: AutomodConfig::default(),
+            automod_ignored_roles: Vec::new(),
+            grace: GraceConfig::default(),
+            voice: VoiceConfig::default(),
+            names: NameConfig::default(),
+            pins: PinsConfig::default(),
+            role_logs: RoleLogsConfig::default(),
+            activity_role: ActivityRoleConfig::default(),
+            tags: TagsConfig::default(),
+            ban_sync: BanSync::default(),
+            delete_replies_after: None,
+            modlog_retention: None,
+            new_account_threshold: default_new_account_threshold(),
+            features: FeatureSet::DEFAULT,
+            sentinel_alerts: false,
+            screening_aware: false,
+            raid_alert_role: None,
+            watch_chan: None,
+            watch_duration: default_watch_duration(),
+            reports_chan: None,
+            milestones_chan: None,
+            outbound_webhook: None,
+            onboarded: false,
         }
     }
+
+    /// Max length of the `automod_ignored_roles` field.
+    pub const MAX_AUTOMOD_IGNORED_ROLES_LEN: usize = 10;
+
+    /// Check whether a member is exempted from a filter.
+    ///
+    /// A member is exempted if they have one of the [`automod_ignored_roles`],
+    /// which apply to every filter, or one of the `trusted_roles` of the
+    /// filter. Neither list takes precedence over the other: having a role in
+    /// either list is enough.
+    ///
+    /// Ignored roles only exempt from the filters, while trusted roles may
+    /// be used by their module for other purposes. Neither of them grants
+    /// moderation permissions.
+    ///
+    /// [`automod_ignored_roles`]: Self::automod_ignored_roles
+    pub fn is_automod_exempt(
+        &self,
+        trusted_roles: &[Id<RoleMarker>],
+        roles: &[Id<RoleMarker>],
+    ) -> bool {
+        roles
+            .iter()
+            .any(|role| self.automod_ignored_roles.contains(role) || trusted_roles.contains(role))
+    }
+
+    /// Get the content rule of a channel.
+    pub fn channel_rule(&self, channel: Id<ChannelMarker>) -> Option<&ChannelRule> {
+        self.channel_rules
+            .iter()
+            .find(|rule| rule.channel_id == channel)
+    }
 }
 
 /// Configuration for the moderation module.
@@ -78,10 +328,169 @@ pub struct ModerationConfig {
     ///
     /// If set to `true`, moderators must specify a reason with each sanction.
     pub enforce_reason: bool,
-    /// Whether the moderator who has performed a sanction is hidden for the sanctioned user.
+    /// Whether the moderator who has performed a sanction is hidden from the
+    /// public messages and from the sanctioned user.
     ///
-    /// This is enabled by default.
+    /// The moderation team is shown instead. The moderator is still recorded
+    /// in the modlogs and in the audit log reason, which are only visible to
+    /// the staff. This is enabled by default.
     pub anonymize: bool,
+    /// Delay (in seconds) after a sanction during which a second sanction on
+    /// the same user requires a confirmation.
+    ///
+    /// This prevents a user from being sanctioned twice when multiple
+    /// moderators act at the same time. Set to `0` to disable. Defaults to 30
+    /// seconds.
+    pub sanction_cooldown: u16,
+    /// How members are muted with the `/mute` command.
+    pub mute_mode: MuteMode,
+    /// Whether timeouts applied with the `/mute` command are reapplied when
+    /// they are cleared by someone else than a moderator.
+    ///
+    /// This protects against other bots or accidental clicks. Moderators
+    /// should use the `/unmute` command to end a mute.
+    pub enforce_mutes: bool,
+    /// Sanction reasons suggested to moderators.
+    ///
+    /// Presets are suggested in the `reason` option of sanction commands and
+    /// before the reason modal is shown.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub reason_presets: Vec<String>,
+    /// Sanctions allowed for the members of each role.
+    ///
+    /// If empty, moderators can apply every sanction. Otherwise, moderators
+    /// can only apply the sanctions allowed by at least one of their roles.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sanction_roles: Vec<SanctionRole>,
+    /// Role assigned to quarantined members.
+    ///
+    /// The other roles of the member are removed during the quarantine, and
+    /// restored with the `/release` command.
+    #[serde_as(as = "Option<IdAsI64>")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quarantine_role: Option<Id<RoleMarker>>,
+    /// Sanctions applied without confirmation when the user has just been
+    /// sanctioned.
+    ///
+    /// Destructive sanctions always require a confirmation, even if they are
+    /// listed (see [`ModlogType::is_destructive`]).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skip_confirmation: Vec<ModlogType>,
+    /// Roles whose members apply sanctions without confirmation (fast mode).
+    ///
+    /// Unlike [`skip_confirmation`], this applies to every sanction,
+    /// including destructive ones.
+    ///
+    /// [`skip_confirmation`]: Self::skip_confirmation
+    #[serde_as(as = "Vec<IdAsI64>")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skip_confirmation_roles: Vec<Id<RoleMarker>>,
+    /// Whether sanctioned users are never notified by private message.
+    ///
+    /// This disables the private messages of every sanction, and takes
+    /// precedence over [`dm_skipped`]. Sanctions applied while private
+    /// messages are disabled are marked in the modlogs.
+    ///
+    /// [`dm_skipped`]: Self::dm_skipped
+    pub dm_disabled: bool,
+    /// Sanctions for which the sanctioned user is not notified by private
+    /// message.
+    ///
+    /// This is ignored if private messages are disabled for the whole guild
+    /// (see [`dm_disabled`]).
+    ///
+    /// [`dm_disabled`]: Self::dm_disabled
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dm_skipped: Vec<ModlogType>,
+    /// Public channel where sanctions are announced.
+    ///
+    /// This channel is visible to the members, unlike the logs channel. Only
+    /// the sanctions listed in [`announced`] are announced.
+    ///
+    /// [`announced`]: Self::announced
+    #[serde_as(as = "Option<IdAsI64>")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub announcement_channel: Option<Id<ChannelMarker>>,
+    /// Sanctions announced in the [`announcement_channel`].
+    ///
+    /// [`announcement_channel`]: Self::announcement_channel
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub announced: Vec<ModlogType>,
+}
+
+impl ModerationConfig {
+    /// Max length of the `reason_presets` field.
+    pub const MAX_REASON_PRESETS_LEN: usize = 25;
+    /// Max length of a reason preset.
+    pub const MAX_REASON_PRESET_LENGTH: usize = 100;
+
+    /// Max length of the `sanction_roles` field.
+    pub const MAX_SANCTION_ROLES_LEN: usize = 25;
+
+    /// Max length of the `skip_confirmation_roles` field.
+    pub const MAX_SKIP_CONFIRMATION_ROLES_LEN: usize = 10;
+
+    /// Find a reason preset, ignoring case.
+    pub fn reason_preset(&self, reason: &str) -> Option<&String> {
+        self.reason_presets
+            .iter()
+            .find(|preset| preset.to_lowercase() == reason.to_lowercase())
+    }
+
+    /// Whether a member with the given roles is allowed to apply a sanction.
+    ///
+    /// Every sanction is allowed if no sanction roles are configured.
+    pub fn sanction_allowed(&self, roles: &[Id<RoleMarker>], kind: ModlogType) -> bool {
+        self.sanction_roles.is_empty()
+            || self
+                .sanction_roles
+                .iter()
+                .any(|role| roles.contains(&role.role_id) && role.sanctions.contains(&kind))
+    }
+
+    /// Whether a member with the given roles is allowed to apply shadow
+    /// sanctions.
+    ///
+    /// Unlike other sanctions, shadow sanctions must be explicitly allowed
+    /// for a role, and are only allowed for administrators by default.
+    pub fn shadow_allowed(&self, roles: &[Id<RoleMarker>]) -> bool {
+        self.sanction_roles
+            .iter()
+            .any(|role| roles.contains(&role.role_id) && role.shadow)
+    }
+
+    /// Whether a sanction requires a confirmation when the user has just been
+    /// sanctioned by another moderator.
+    pub fn confirmation_required(&self, kind: ModlogType) -> bool {
+        kind.is_destructive() || !self.skip_confirmation.contains(&kind)
+    }
+
+    /// Whether a member with the given roles applies sanctions without
+    /// confirmation.
+    pub fn fast_mode(&self, roles: &[Id<RoleMarker>]) -> bool {
+        self.skip_confirmation_roles
+            .iter()
+            .any(|role| roles.contains(role))
+    }
+
+    /// Whether the user sanctioned with a sanction is notified by private
+    /// message.
+    ///
+    /// The guild-wide [`dm_disabled`] setting takes precedence over the
+    /// sanctions listed in [`dm_skipped`].
+    ///
+    /// [`dm_disabled`]: Self::dm_disabled
+    /// [`dm_skipped`]: Self::dm_skipped
+    pub fn dm_enabled(&self, kind: ModlogType) -> bool {
+        !self.dm_disabled && !self.dm_skipped.contains(&kind)
+    }
+
+    /// Get the channel where a sanction is announced, if the sanction is
+    /// announced.
+    pub fn announcement(&self, kind: ModlogType) -> Option<Id<ChannelMarker>> {
+        self.announcement_channel
+            .filter(|_| self.announced.contains(&kind))
+    }
 }
 
 impl Default for ModerationConfig {
@@ -90,10 +499,56 @@ impl Default for ModerationConfig {
             roles: Vec::new(),
             enforce_reason: false,
             anonymize: true,
+            sanction_cooldown: 30,
+            mute_mode: MuteMode::default(),
+            enforce_mutes: false,
+            reason_presets: Vec::new(),
+            sanction_roles: Vec::new(),
+            quarantine_role: None,
+            skip_confirmation: Vec::new(),
+            skip_confirmation_roles: Vec::new(),
+            dm_disabled: false,
+            dm_skipped: Vec::new(),
+            announcement_channel: None,
+            announced: Vec::new(),
         }
     }
 }
 
+/// Sanctions allowed for the members of a role.
+///
+/// See [`ModerationConfig::sanction_roles`].
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SanctionRole {
+    /// Role the sanctions are allowed for.
+    #[serde_as(as = "IdAsI64")]
+    pub role_id: Id<RoleMarker>,
+    /// Allowed sanctions.
+    pub sanctions: Vec<ModlogType>,
+    /// Whether the role allows to apply shadow sanctions.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub shadow: bool,
+}
+
+/// Mode used to mute members.
+///
+/// Discord timeouts are used by default. Guilds that disallow timeouts can use
+/// a role instead, which is configured with the `/config mute-role` command.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum MuteMode {
+    /// Members are muted with a Discord timeout.
+    #[default]
+    Timeout,
+    /// Members are muted by adding them a role.
+    Role {
+        #[serde_as(as = "IdAsI64")]
+        role_id: Id<RoleMarker>,
+    },
+}
+
 /// Configuration for the captcha module.
 #[serde_as]
 #[skip_serializing_none]
@@ -131,13 +586,851 @@ impl CaptchaConfig {
     pub const MAX_VERIFIED_ROLES_LEN: usize = 5;
 }
 
+/// Configuration for the raid mode.
+///
+/// The raid mode applies a set of stricter settings to the guild while it is
+/// enabled. The previous settings of the guild are stored to be restored when
+/// the raid mode is disabled.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct RaidModeConfig {
+    /// Whether the raid mode is enabled.
+    pub enabled: bool,
+    /// Minimum age (in hours) of the accounts allowed to join the guild.
+    ///
+    /// Members with a more recent account are handled according to the
+    /// configured [`action`](Self::action). If [`None`], the account age is
+    /// not checked.
+    pub account_age: Option<u32>,
+    /// Action applied to the members with a recent account.
+    pub action: RaidModeAction,
+    /// Channel where the members held for review are posted.
+    ///
+    /// If [`None`], they are posted in the logs channel of the joins.
+    #[serde_as(as = "Option<IdAsI64>")]
+    pub review_channel: Option<Id<ChannelMarker>>,
+    /// Role given to the members approved by a moderator.
+    #[serde_as(as = "Option<IdAsI64>")]
+    pub member_role: Option<Id<RoleMarker>>,
+    /// Verification level applied to the guild.
+    ///
+    /// If [`None`], the verification level is not changed.
+    pub verification_level: Option<VerificationLevel>,
+    /// Whether an alert is sent in the logs channel each time a member joins.
+    pub join_alerts: bool,
+    /// Verification level of the guild before the raid mode was enabled.
+    pub previous_verification_level: Option<VerificationLevel>,
+}
+
+impl Default for RaidModeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            account_age: Some(72),
+            action: RaidModeAction::default(),
+            review_channel: None,
+            member_role: None,
+            verification_level: Some(VerificationLevel::High),
+            join_alerts: true,
+            previous_verification_level: None,
+        }
+    }
+}
+
+/// Action applied by the raid mode to the members with a recent account.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RaidModeAction {
+    /// The member is kicked from the guild.
+    #[default]
+    Kick,
+    /// The member is timed out and held for review by a moderator.
+    ///
+    /// Moderators may approve the member, which gives the configured member
+    /// role, or kick them.
+    Review,
+}
+
+/// Configuration for the reaction spam module.
+///
+/// This module detects users that add reactions too quickly. When the
+/// threshold is exceeded, the reactions are removed and the user may be
+/// timed out.
+#[serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct ReactionSpamConfig {
+    /// Whether the module is enabled.
+    pub enabled: bool,
+    /// Maximum number of reactions allowed during the interval.
+    pub max_reactions: u16,
+    /// Interval (in seconds) during which reactions are counted.
+    pub interval: u16,
+    /// Duration (in seconds) of the timeout applied to the user.
+    ///
+    /// If [`None`], the user is not timed out.
+    pub timeout: Option<u32>,
+    /// Roles that are not checked by the module.
+    #[serde_as(as = "Vec<IdAsI64>")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub trusted_roles: Vec<Id<RoleMarker>>,
+}
+
+impl ReactionSpamConfig {
+    /// Max length of the `trusted_roles` field.
+    pub const MAX_TRUSTED_ROLES_LEN: usize = 10;
+}
+
+impl Default for ReactionSpamConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_reactions: 10,
+            interval: 5,
+            timeout: None,
+            trusted_roles: Vec::new(),
+        }
+    }
+}
+
+/// Configuration for the link filter module.
+///
+/// This module deletes messages containing Discord invites or external links
+/// that are not explicitly allowed. The user may also be timed out.
+#[serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct LinkFilterConfig {
+    /// Whether the module is enabled.
+    pub enabled: bool,
+    /// Whether Discord invites are filtered.
+    pub invites: bool,
+    /// Whether external links are filtered.
+    pub links: bool,
+    /// Domains allowed in external links, in lowercase.
+    ///
+    /// Subdomains of an allowed domain are also allowed.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed_domains: Vec<String>,
+    /// Codes of the allowed Discord invites, in lowercase.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed_invites: Vec<String>,
+    /// Duration (in seconds) of the timeout applied to the user.
+    ///
+    /// If [`None`], the user is not timed out.
+    pub timeout: Option<u32>,
+    /// Roles that are not checked by the module.
+    #[serde_as(as = "Vec<IdAsI64>")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub trusted_roles: Vec<Id<RoleMarker>>,
+}
+
+impl LinkFilterConfig {
+    /// Max length of the `allowed_domains` and `allowed_invites` fields.
+    pub const MAX_ALLOWED_LEN: usize = 50;
+
+    /// Max length of the `trusted_roles` field.
+    pub const MAX_TRUSTED_ROLES_LEN: usize = 10;
+
+    /// Whether a domain is allowed, either directly or as a subdomain of an
+    /// allowed domain.
+    pub fn domain_allowed(&self, domain: &str) -> bool {
+        domain_allowed(&self.allowed_domains, domain)
+    }
+
+    /// Whether a Discord invite code is allowed.
+    pub fn invite_allowed(&self, code: &str) -> bool {
+        let code = code.to_ascii_lowercase();
+
+        self.allowed_invites.contains(&code)
+    }
+}
+
+impl Default for LinkFilterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            invites: true,
+            links: false,
+            allowed_domains: Vec::new(),
+            allowed_invites: Vec::new(),
+            timeout: None,
+            trusted_roles: Vec::new(),
+        }
+    }
+}
+
+/// Custom pattern rule applied to the messages.
+///
+/// Patterns are regular expressions matched case-insensitively against the
+/// content of the messages. They are validated when the rule is created, and
+/// matched with an engine that runs in linear time.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct PatternRule {
+    /// Name of the rule, displayed in the logs.
+    pub name: String,
+    /// Regular expression matched against the messages.
+    pub pattern: String,
+    /// Action applied to the matching messages.
+    pub action: PatternAction,
+}
+
+impl PatternRule {
+    /// Max number of rules per guild.
+    pub const MAX_RULES: usize = 10;
+
+    /// Max length of the `name` field.
+    pub const MAX_NAME_LEN: usize = 32;
+
+    /// Max length of the `pattern` field.
+    pub const MAX_PATTERN_LEN: usize = 200;
+}
+
+/// Action applied by a [`PatternRule`].
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PatternAction {
+    /// The match is only logged.
+    Log,
+    /// The message is deleted.
+    #[default]
+    Delete,
+    /// The message is deleted and the user is timed out.
+    Timeout,
+}
+
+/// Content rule of a channel.
+///
+/// Messages that do not follow the rule are deleted, unless their author can
+/// manage the messages of the channel.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ChannelRule {
+    /// Id of the channel.
+    #[serde_as(as = "IdAsI64")]
+    pub channel_id: Id<ChannelMarker>,
+    /// Kind of content allowed in the channel.
+    pub kind: ChannelRuleKind,
+}
+
+impl ChannelRule {
+    /// Max number of channel rules per guild.
+    pub const MAX_RULES: usize = 25;
+
+    /// Max number of domains of a [`ChannelRuleKind::LinkOnlyFrom`] rule.
+    pub const MAX_DOMAINS: usize = 10;
+}
+
+/// Kind of content allowed by a [`ChannelRule`].
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChannelRuleKind {
+    /// Only messages with attachments are allowed.
+    MediaOnly,
+    /// Messages with attachments are not allowed.
+    TextOnly,
+    /// Only messages containing links to the listed domains are allowed.
+    ///
+    /// Subdomains of a listed domain are also allowed.
+    LinkOnlyFrom { domains: Vec<String> },
+}
+
+impl ChannelRuleKind {
+    /// Whether a domain is allowed by a [`ChannelRuleKind::LinkOnlyFrom`]
+    /// rule.
+    pub fn domain_allowed(&self, domain: &str) -> bool {
+        match self {
+            ChannelRuleKind::LinkOnlyFrom { domains } => domain_allowed(domains, domain),
+            _ => false,
+        }
+    }
+}
+
+/// Whether a domain is in a list of allowed domains, either directly or as a
+/// subdomain of an allowed domain.
+///
+/// The allowed domains must be in lowercase.
+fn domain_allowed(allowed: &[String], domain: &str) -> bool {
+    let domain = domain.to_ascii_lowercase();
+
+    allowed.iter().any(|allowed| {
+        domain == *allowed
+            || domain
+                .strip_suffix(allowed.as_str())
+                .map_or(false, |prefix| prefix.ends_with('.'))
+    })
+}
+
+/// Configuration for the first message filter.
+///
+/// Spam accounts often join a guild, post a scam and leave. The first message
+/// sent by new members is checked more strictly: messages containing links
+/// that are not allowed by the [`LinkFilterConfig`] or too many mentions are
+/// deleted, and the configured action is applied to the author.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct FirstMessageConfig {
+    /// Whether the filter is enabled.
+    pub enabled: bool,
+    /// Whether links that are not allowed by the link filter are forbidden.
+    pub links: bool,
+    /// Maximum number of mentions allowed.
+    pub max_mentions: u8,
+    /// Action applied to the author of a filtered message.
+    pub action: FirstMessageAction,
+}
+
+impl Default for FirstMessageConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            links: true,
+            max_mentions: 3,
+            action: FirstMessageAction::default(),
+        }
+    }
+}
+
+/// Action applied by the first message filter.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FirstMessageAction {
+    /// The message is deleted.
+    #[default]
+    Delete,
+    /// The message is deleted and the user is timed out.
+    Timeout,
+    /// The message is deleted and the user is banned.
+    Ban,
+}
+
+/// Configuration for the anti-nuke module.
+///
+/// This module detects users that delete many channels or roles in a short
+/// time, which may indicate a compromised moderator account or a rogue bot.
+/// When the threshold is exceeded, the configured action is applied to the
+/// user.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct AntinukeConfig {
+    /// Whether the module is enabled.
+    pub enabled: bool,
+    /// Maximum number of deletions allowed during the interval.
+    pub max_deletions: u16,
+    /// Interval (in seconds) during which deletions are counted.
+    pub interval: u16,
+    /// Action applied to users that exceed the threshold.
+    pub action: AntinukeAction,
+    /// Users (usually bots) that are not checked by the module.
+    #[serde_as(as = "Vec<IdAsI64>")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub trusted: Vec<Id<UserMarker>>,
+}
+
+impl AntinukeConfig {
+    /// Max length of the `trusted` field.
+    pub const MAX_TRUSTED_LEN: usize = 10;
+}
+
+impl Default for AntinukeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_deletions: 5,
+            interval: 60,
+            action: AntinukeAction::default(),
+            trusted: Vec::new(),
+        }
+    }
+}
+
+/// Action applied by the anti-nuke module.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AntinukeAction {
+    /// All the roles of the user are removed.
+    #[default]
+    StripRoles,
+    /// The user is banned from the guild.
+    Ban,
+}
+
+/// Configuration for the join burst detection.
+///
+/// A join burst is detected when more than `max_joins` members join the guild
+/// during the interval. Organic spikes (e.g. after the guild has been shared)
+/// are common, so the accounts of the burst are also checked for the enabled
+/// `signals`: a burst where at least `cluster_ratio` percent of the accounts
+/// share the same avatar or name pattern is reported with a high confidence.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct JoinBurstConfig {
+    /// Whether the module is enabled.
+    pub enabled: bool,
+    /// Maximum number of joins allowed during the interval.
+    pub max_joins: u16,
+    /// Interval (in seconds) during which joins are counted.
+    pub interval: u16,
+    /// Signals checked on the accounts of a burst.
+    pub signals: Vec<JoinBurstSignal>,
+    /// Percentage of the accounts of a burst that must share the same signal
+    /// value for the burst to be considered coordinated.
+    pub cluster_ratio: u8,
+}
+
+impl Default for JoinBurstConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_joins: 10,
+            interval: 30,
+            signals: vec![JoinBurstSignal::Avatar, JoinBurstSignal::Name],
+            cluster_ratio: 50,
+        }
+    }
+}
+
+/// Signal of coordinated accounts checked during a join burst.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum JoinBurstSignal {
+    /// Accounts use the same avatar, or all have a default avatar.
+    Avatar,
+    /// Accounts have the same name once digits are removed (e.g. `raider01`
+    /// and `raider02`).
+    Name,
+}
+
+/// Configuration of the native AutoMod rules.
+///
+/// When enabled, native Discord AutoMod rules derived from this configuration
+/// are created in the guild, so that basic protection keeps working when the
+/// bot is offline. The rules created by the bot are stored to detect when they
+/// have been edited manually.
+#[serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct AutomodConfig {
+    /// Whether the native rules are synchronized.
+    pub enabled: bool,
+    /// Keywords blocked by the keyword rule.
+    ///
+    /// The keyword rule is disabled if this list is empty.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+    /// Whether the spam rule is enabled.
+    pub spam: bool,
+    /// Maximum number of mentions allowed in a message.
+    ///
+    /// If [`None`], the mention spam rule is disabled.
+    pub mention_limit: Option<u8>,
+    /// Native rules created by RaidProtect.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<AutomodRule>,
+}
+
+impl AutomodConfig {
+    /// Max length of the `keywords` field.
+    pub const MAX_KEYWORDS_LEN: usize = 100;
+
+    /// Get the rule of a given kind created by RaidProtect.
+    pub fn rule(&self, kind: AutomodRuleKind) -> Option<&AutomodRule> {
+        self.rules.iter().find(|rule| rule.kind == kind)
+    }
+}
+
+/// Native AutoMod rule created by RaidProtect.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct AutomodRule {
+    /// Kind of the rule.
+    pub kind: AutomodRuleKind,
+    /// Id of the rule.
+    #[serde_as(as = "IdAsI64")]
+    pub id: Id<AutoModerationRuleMarker>,
+    /// Fingerprint of the rule when it was last synchronized.
+    ///
+    /// If the fingerprint of the current rule is different, the rule has been
+    /// edited manually.
+    pub fingerprint: String,
+}
+
+/// Kind of native AutoMod rule.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AutomodRuleKind {
+    /// Messages containing a blocked keyword.
+    Keyword,
+    /// Messages detected as spam by Discord.
+    Spam,
+    /// Messages with too many mentions.
+    MentionSpam,
+}
+
+/// Grace period of the automatic sanctions.
+///
+/// Trusted members (server boosters, long-standing members and members with
+/// one of the trusted roles) are not timed out on their first offense: they
+/// receive a warning instead, and only a repeated offense is sanctioned.
+#[serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct GraceConfig {
+    /// Whether the grace period is enabled.
+    pub enabled: bool,
+    /// Whether members boosting the guild are trusted.
+    pub boosters: bool,
+    /// Duration (in days) after which members are trusted.
+    ///
+    /// If [`None`], members are not trusted based on their tenure.
+    pub trusted_tenure: Option<u16>,
+    /// Roles whose members are trusted.
+    #[serde_as(as = "Vec<IdAsI64>")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub trusted_roles: Vec<Id<RoleMarker>>,
+}
+
+impl GraceConfig {
+    /// Max length of the `trusted_roles` field.
+    pub const MAX_TRUSTED_ROLES_LEN: usize = 10;
+}
+
+impl Default for GraceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            boosters: true,
+            trusted_tenure: None,
+            trusted_roles: Vec::new(),
+        }
+    }
+}
+
+/// Configuration for the voice channels module.
+///
+/// Voice state changes (joins, leaves, moves and server mutes) can be logged
+/// in a dedicated channel. The module also detects users that hop between
+/// voice channels too quickly, and applies the configured action to them.
+#[serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct VoiceConfig {
+    /// Channel where voice state changes are logged.
+    ///
+    /// If [`None`], voice state changes are not logged.
+    #[serde_as(as = "Option<IdAsI64>")]
+    pub logs_chan: Option<Id<ChannelMarker>>,
+    /// Whether the voice hop detection is enabled.
+    pub hop_detection: bool,
+    /// Maximum number of channel joins allowed during the interval.
+    pub max_hops: u16,
+    /// Interval (in seconds) during which channel joins are counted.
+    pub interval: u16,
+    /// Action applied to users that exceed the limit.
+    pub action: VoiceHopAction,
+    /// Duration (in seconds) of the timeout applied with
+    /// [`VoiceHopAction::Timeout`].
+    pub timeout: u32,
+    /// Roles that are not checked by the hop detection.
+    #[serde_as(as = "Vec<IdAsI64>")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub trusted_roles: Vec<Id<RoleMarker>>,
+}
+
+impl VoiceConfig {
+    /// Max length of the `trusted_roles` field.
+    pub const MAX_TRUSTED_ROLES_LEN: usize = 10;
+}
+
+impl Default for VoiceConfig {
+    fn default() -> Self {
+        Self {
+            logs_chan: None,
+            hop_detection: false,
+            max_hops: 5,
+            interval: 10,
+            action: VoiceHopAction::default(),
+            timeout: 10 * 60,
+            trusted_roles: Vec::new(),
+        }
+    }
+}
+
+/// Action applied by the voice hop detection.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum VoiceHopAction {
+    /// The user is disconnected from the voice channel.
+    #[default]
+    Disconnect,
+    /// The user is disconnected and timed out.
+    Timeout,
+}
+
+/// Configuration for the message pins logs.
+///
+/// Messages pinned and unpinned are logged in a dedicated channel, with the
+/// moderator that made the change.
+#[serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct PinsConfig {
+    /// Channel where pin changes are logged.
+    ///
+    /// If [`None`], pin changes are not logged.
+    #[serde_as(as = "Option<IdAsI64>")]
+    pub logs_chan: Option<Id<ChannelMarker>>,
+    /// Channels whose pin changes are not logged.
+    ///
+    /// Threads of an ignored channel are also ignored.
+    #[serde_as(as = "Vec<IdAsI64>")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ignored_channels: Vec<Id<ChannelMarker>>,
+}
+
+impl PinsConfig {
+    /// Max length of the `ignored_channels` field.
+    pub const MAX_IGNORED_CHANNELS_LEN: usize = 25;
+}
+
+/// Configuration for the member role changes logs.
+///
+/// Roles added to or removed from members are logged in a dedicated channel,
+/// with the moderator that made the change. Changes made by RaidProtect
+/// itself (for example sanction roles) are marked as such.
+#[serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct RoleLogsConfig {
+    /// Channel where role changes are logged.
+    ///
+    /// If [`None`], role changes are not logged.
+    #[serde_as(as = "Option<IdAsI64>")]
+    pub logs_chan: Option<Id<ChannelMarker>>,
+    /// Roles whose changes are not logged.
+    #[serde_as(as = "Vec<IdAsI64>")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ignored_roles: Vec<Id<RoleMarker>>,
+}
+
+impl RoleLogsConfig {
+    /// Max length of the `ignored_roles` field.
+    pub const MAX_IGNORED_ROLES_LEN: usize = 25;
+}
+
+/// Configuration for the tags.
+///
+/// Tags are named responses (such as answers to frequently asked questions)
+/// sent with the `/tag` command. Tags are managed by the members with the
+/// [`manager_roles`], in addition to the members that can manage the guild.
+///
+/// [`manager_roles`]: Self::manager_roles
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct TagsConfig {
+    /// Tags of the guild.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<Tag>,
+    /// Roles allowed to manage the tags.
+    #[serde_as(as = "Vec<IdAsI64>")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub manager_roles: Vec<Id<RoleMarker>>,
+}
+
+impl TagsConfig {
+    /// Max length of the `tags` field.
+    pub const MAX_TAGS_LEN: usize = 50;
+    /// Max length of the `manager_roles` field.
+    pub const MAX_MANAGER_ROLES_LEN: usize = 10;
+
+    /// Find a tag by name, ignoring case.
+    pub fn tag(&self, name: &str) -> Option<&Tag> {
+        let name = name.trim();
+
+        self.tags
+            .iter()
+            .find(|tag| tag.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Whether a member with the given roles is allowed to manage the tags.
+    ///
+    /// Members that can manage the guild are always allowed, this must be
+    /// checked separately.
+    pub fn can_manage(&self, roles: &[Id<RoleMarker>]) -> bool {
+        self.manager_roles.iter().any(|role| roles.contains(role))
+    }
+}
+
+/// Named response sent with the `/tag` command.
+///
+/// The content may be a template with variables (see the `/tag` command for
+/// the available variables).
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Tag {
+    /// Name of the tag.
+    pub name: String,
+    /// Content of the tag.
+    pub content: String,
+}
+
+impl Tag {
+    /// Max length of a tag name.
+    pub const MAX_NAME_LENGTH: usize = 32;
+    /// Max length of a tag content.
+    pub const MAX_CONTENT_LENGTH: usize = 2000;
+}
+
+/// Synchronization of the bans with the linked guilds.
+///
+/// Guilds are linked with the `/link` command. Bans issued with the `sync`
+/// option in a guild that sends its bans are mirrored in the linked guilds
+/// that receive bans.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BanSync {
+    /// Bans are neither sent nor received.
+    #[default]
+    Off,
+    /// Bans are sent to the linked guilds.
+    Send,
+    /// Bans are received from the linked guilds.
+    Receive,
+    /// Bans are sent to and received from the linked guilds.
+    Both,
+}
+
+impl BanSync {
+    /// Whether the bans of the guild are sent to the linked guilds.
+    pub fn sends(self) -> bool {
+        matches!(self, BanSync::Send | BanSync::Both)
+    }
+
+    /// Whether the bans of the linked guilds are received.
+    pub fn receives(self) -> bool {
+        matches!(self, BanSync::Receive | BanSync::Both)
+    }
+}
+
+/// Configuration for the activity role.
+///
+/// Members are granted a role once they have sent enough messages. Messages
+/// sent during the cooldown following a counted message are ignored, so that
+/// the role cannot be obtained by spamming.
+#[serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct ActivityRoleConfig {
+    /// Role granted once the threshold is reached.
+    ///
+    /// If [`None`], messages are not counted.
+    #[serde_as(as = "Option<IdAsI64>")]
+    pub role: Option<Id<RoleMarker>>,
+    /// Number of messages required to be granted the role.
+    pub threshold: u32,
+    /// Minimum delay (in seconds) between two counted messages.
+    pub cooldown: u16,
+    /// Whether granted roles are logged in the logs channel.
+    pub log: bool,
+}
+
+impl Default for ActivityRoleConfig {
+    fn default() -> Self {
+        Self {
+            role: None,
+            threshold: 50,
+            cooldown: 60,
+            log: true,
+        }
+    }
+}
+
+/// Configuration for the member names normalization.
+///
+/// Names with excessive combining marks (zalgo) or invisible characters are
+/// checked when a member joins or changes its nickname.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct NameConfig {
+    /// Whether the names normalization is enabled.
+    pub enabled: bool,
+    /// Action applied to members with a problematic name.
+    pub action: NameAction,
+    /// Nickname used when the cleaned name is empty.
+    pub fallback: String,
+}
+
+impl NameConfig {
+    /// Max length of the `fallback` field.
+    pub const MAX_FALLBACK_LENGTH: usize = 32;
+}
+
+impl Default for NameConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            action: NameAction::default(),
+            fallback: "Moderated nickname".to_owned(),
+        }
+    }
+}
+
+/// Action applied by the names normalization.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NameAction {
+    /// The nickname of the member is replaced with the cleaned name.
+    #[default]
+    Reset,
+    /// The member is reported in the logs channel.
+    Flag,
+}
+
+/// Outbound webhook configuration.
+///
+/// Moderation events are sent as JSON payloads to the webhook, signed with
+/// an HMAC of the payload derived from the `secret`. The secret is redacted in
+/// the [`Debug`] output.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct OutboundWebhook {
+    /// HTTPS url of the webhook.
+    pub url: Url,
+    /// Secret used to sign the payloads.
+    pub secret: String,
+    /// Number of consecutive failed deliveries.
+    #[serde(default)]
+    pub failures: u32,
+}
+
+impl fmt::Debug for OutboundWebhook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OutboundWebhook")
+            .field("url", &self.url)
+            .field("secret", &"[redacted]")
+            .field("failures", &self.failures)
+            .finish()
+    }
+}
+
 // Implementation of methods to query the database.
 impl DbClient {
     /// Get the [`GuildConfig`] for a given guild_id, if it exists.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
     pub async fn get_guild(
         &self,
         guild_id: Id<GuildMarker>,
     ) -> Result<Option<GuildConfig>, anyhow::Error> {
+        let _latency = QueryLatency::start();
         let query = GuildQuery { id: guild_id };
 
         let guild = self
@@ -150,10 +1443,12 @@ impl DbClient {
     }
 
     /// Get the [`GuildConfig`] for a given guild_id, or create it with default configuration.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
     pub async fn get_guild_or_create(
         &self,
         guild_id: Id<GuildMarker>,
     ) -> Result<GuildConfig, anyhow::Error> {
+        let _latency = QueryLatency::start();
         let query = GuildQuery { id: guild_id };
         let default_guild = GuildConfig::new(guild_id);
         let options = options::FindOneAndUpdateOptions::builder()
@@ -175,7 +1470,9 @@ impl DbClient {
     }
 
     /// Update or insert a [`GuildConfig`] in the database.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild.id.get(), latency_ms))]
     pub async fn update_guild(&self, guild: &GuildConfig) -> Result<(), anyhow::Error> {
+        let _latency = QueryLatency::start();
         let query = GuildQuery { id: guild.id };
         let options = options::ReplaceOptions::builder().upsert(true).build();
 
@@ -186,6 +1483,182 @@ impl DbClient {
 
         Ok(())
     }
+
+    /// Set the logs channel of a guild.
+    ///
+    /// Unlike [`DbClient::update_guild`], only the `logs_chan` field is updated.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn set_guild_logs_channel(
+        &self,
+        guild_id: Id<GuildMarker>,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<(), anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let query = GuildQuery { id: guild_id };
+        let options = options::UpdateOptions::builder().upsert(true).build();
+
+        self.db()
+            .collection::<GuildConfig>(GuildConfig::COLLECTION)
+            .update_one(
+                to_document(&query)?,
+                doc! { "$set": { "logs_chan": channel_id.get() as i64 } },
+                options,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Increment the number of consecutive failed deliveries of the outbound
+    /// webhook of a guild.
+    ///
+    /// Returns the new number of failures, or [`None`] if the guild has no
+    /// webhook configured.
+    #[instrument(level = "debug", skip(self), fields(latency_ms))]
+    pub async fn record_webhook_failure(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<u32>, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        let guild = self
+            .db()
+            .collection::<GuildConfig>(GuildConfig::COLLECTION)
+            .find_one_and_update(
+                doc! { "_id": guild_id.get() as i64, "outbound_webhook": { "$exists": true } },
+                doc! { "$inc": { "outbound_webhook.failures": 1 } },
+                options,
+            )
+            .await?;
+
+        Ok(guild
+            .and_then(|guild| guild.outbound_webhook)
+            .map(|webhook| webhook.failures))
+    }
+
+    /// Reset the number of consecutive failed deliveries of the outbound
+    /// webhook of a guild.
+    #[instrument(level = "debug", skip(self), fields(latency_ms))]
+    pub async fn reset_webhook_failures(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<(), anyhow::Error> {
+        let _latency = QueryLatency::start();
+
+        self.db()
+            .collection::<GuildConfig>(GuildConfig::COLLECTION)
+            .update_one(
+                doc! { "_id": guild_id.get() as i64, "outbound_webhook.failures": { "$gt": 0 } },
+                doc! { "$set": { "outbound_webhook.failures": 0 } },
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Remove the outbound webhook of a guild.
+    #[instrument(level = "debug", skip(self), fields(latency_ms))]
+    pub async fn disable_outbound_webhook(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<(), anyhow::Error> {
+        let _latency = QueryLatency::start();
+
+        self.db()
+            .collection::<GuildConfig>(GuildConfig::COLLECTION)
+            .update_one(
+                doc! { "_id": guild_id.get() as i64 },
+                doc! { "$unset": { "outbound_webhook": "" } },
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Find the [`GuildConfig`]s that have a logs channel configured.
+    ///
+    /// Guilds are sorted by id. If `after` is set, only the guilds with a
+    /// greater id are returned, which allows to iterate over all guilds in
+    /// multiple batches.
+    #[instrument(level = "debug", skip(self), fields(latency_ms))]
+    pub async fn find_guilds_with_logs(
+        &self,
+        after: Option<Id<GuildMarker>>,
+        limit: i64,
+    ) -> Result<Cursor<GuildConfig>, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let mut query = doc! { "logs_chan": { "$ne": null } };
+        if let Some(after) = after {
+            query.insert("_id", doc! { "$gt": after.get() as i64 });
+        }
+
+        let options = options::FindOptions::builder()
+            .sort(doc! { "_id": 1 })
+            .limit(limit)
+            .build();
+
+        let cursor = self
+            .db()
+            .collection::<GuildConfig>(GuildConfig::COLLECTION)
+            .find(query, options)
+            .await?;
+
+        Ok(cursor)
+    }
+
+    /// Find the [`GuildConfig`]s that have a modlog retention period.
+    ///
+    /// Like [`DbClient::find_guilds_with_logs`], guilds are sorted by id and
+    /// only the guilds with an id greater than `after` are returned.
+    #[instrument(level = "debug", skip(self), fields(latency_ms))]
+    pub async fn find_guilds_with_modlog_retention(
+        &self,
+        after: Option<Id<GuildMarker>>,
+        limit: i64,
+    ) -> Result<Cursor<GuildConfig>, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let mut query = doc! { "modlog_retention": { "$ne": null } };
+        if let Some(after) = after {
+            query.insert("_id", doc! { "$gt": after.get() as i64 });
+        }
+
+        let options = options::FindOptions::builder()
+            .sort(doc! { "_id": 1 })
+            .limit(limit)
+            .build();
+
+        let cursor = self
+            .db()
+            .collection::<GuildConfig>(GuildConfig::COLLECTION)
+            .find(query, options)
+            .await?;
+
+        Ok(cursor)
+    }
+
+    /// Mark a guild as onboarded.
+    ///
+    /// Returns `true` if the guild was not already onboarded, which ensures
+    /// that the onboarding message is only sent once.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn claim_onboarding(&self, guild_id: Id<GuildMarker>) -> Result<bool, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let mut query = to_document(&GuildQuery { id: guild_id })?;
+        query.insert("onboarded", false);
+
+        let result = self
+            .db()
+            .collection::<GuildConfig>(GuildConfig::COLLECTION)
+            .update_one(query, doc! { "$set": { "onboarded": true } }, None)
+            .await?;
+
+        Ok(result.modified_count == 1)
+    }
 }
 
 /// Query a guild with its guild_id
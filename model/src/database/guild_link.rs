@@ -0,0 +1,175 @@
+//! Models for the `guild_links` collection.
+
+use mongodb::bson::{doc, oid::ObjectId, Document};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, skip_serializing_none};
+use time::OffsetDateTime;
+use tracing::instrument;
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+
+use super::DbClient;
+use crate::{
+    log::QueryLatency,
+    serde::{DateTimeAsBson, IdAsI64},
+};
+
+/// Link between two affiliated guilds.
+///
+/// This type represent a link stored in the `guild_links` collection of the
+/// database. A link is created by the `/link join` command in the
+/// [`target`] guild, with a code generated by the `/link create` command in
+/// the [`source`] guild. Links are symmetric: bans are synchronized in both
+/// directions, depending on the [`BanSync`] configuration of each guild.
+///
+/// [`target`]: Self::target
+/// [`source`]: Self::source
+/// [`BanSync`]: super::guild::BanSync
+#[serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct GuildLink {
+    /// Unique ID of the link.
+    #[serde(rename = "_id")]
+    pub id: Option<ObjectId>,
+    /// Guild where the link code has been created.
+    #[serde_as(as = "IdAsI64")]
+    pub source: Id<GuildMarker>,
+    /// Guild that joined the link with the code.
+    #[serde_as(as = "IdAsI64")]
+    pub target: Id<GuildMarker>,
+    /// Administrator that joined the link.
+    #[serde_as(as = "IdAsI64")]
+    pub created_by: Id<UserMarker>,
+    /// Date of the link.
+    #[serde_as(as = "DateTimeAsBson")]
+    pub date: OffsetDateTime,
+}
+
+impl GuildLink {
+    /// Name of the MongoDB collection.
+    pub const COLLECTION: &'static str = "guild_links";
+
+    /// Maximum number of links of a guild.
+    pub const MAX_LINKS: u64 = 10;
+
+    /// Get the other guild of the link.
+    pub fn other(&self, guild_id: Id<GuildMarker>) -> Id<GuildMarker> {
+        if self.source == guild_id {
+            self.target
+        } else {
+            self.source
+        }
+    }
+}
+
+// Implementation of methods to query the database.
+impl DbClient {
+    /// Insert a new [`GuildLink`] in the database.
+    #[instrument(level = "debug", skip_all, fields(guild_id = link.source.get(), latency_ms))]
+    pub async fn create_guild_link(&self, link: &GuildLink) -> Result<(), anyhow::Error> {
+        let _latency = QueryLatency::start();
+
+        self.db()
+            .collection::<GuildLink>(GuildLink::COLLECTION)
+            .insert_one(link, None)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Get the [`GuildLink`]s of a guild, in either direction.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn find_guild_links(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<GuildLink>, anyhow::Error> {
+        let _latency = QueryLatency::start();
+
+        let mut cursor = self
+            .db()
+            .collection::<GuildLink>(GuildLink::COLLECTION)
+            .find(link_query(guild_id), None)
+            .await?;
+
+        let mut links = Vec::new();
+        while cursor.advance().await? {
+            links.push(cursor.deserialize_current()?);
+        }
+
+        Ok(links)
+    }
+
+    /// Count the [`GuildLink`]s of a guild.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn count_guild_links(&self, guild_id: Id<GuildMarker>) -> Result<u64, anyhow::Error> {
+        let _latency = QueryLatency::start();
+
+        let count = self
+            .db()
+            .collection::<GuildLink>(GuildLink::COLLECTION)
+            .count_documents(link_query(guild_id), None)
+            .await?;
+
+        Ok(count)
+    }
+
+    /// Whether two guilds are linked.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn is_guild_linked(
+        &self,
+        guild_id: Id<GuildMarker>,
+        other: Id<GuildMarker>,
+    ) -> Result<bool, anyhow::Error> {
+        let _latency = QueryLatency::start();
+
+        let link = self
+            .db()
+            .collection::<GuildLink>(GuildLink::COLLECTION)
+            .find_one(pair_query(guild_id, other), None)
+            .await?;
+
+        Ok(link.is_some())
+    }
+
+    /// Delete the [`GuildLink`] between two guilds.
+    ///
+    /// Returns whether a link has been deleted.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn delete_guild_link(
+        &self,
+        guild_id: Id<GuildMarker>,
+        other: Id<GuildMarker>,
+    ) -> Result<bool, anyhow::Error> {
+        let _latency = QueryLatency::start();
+
+        let result = self
+            .db()
+            .collection::<GuildLink>(GuildLink::COLLECTION)
+            .delete_many(pair_query(guild_id, other), None)
+            .await?;
+
+        Ok(result.deleted_count > 0)
+    }
+}
+
+/// Query the links of a guild, in either direction.
+fn link_query(guild_id: Id<GuildMarker>) -> Document {
+    let guild_id = guild_id.get() as i64;
+
+    doc! { "$or": [{ "source": guild_id }, { "target": guild_id }] }
+}
+
+/// Query the link between two guilds, in either direction.
+fn pair_query(guild_id: Id<GuildMarker>, other: Id<GuildMarker>) -> Document {
+    let (guild_id, other) = (guild_id.get() as i64, other.get() as i64);
+
+    doc! {
+        "$or": [
+            { "source": guild_id, "target": other },
+            { "source": other, "target": guild_id },
+        ]
+    }
+}
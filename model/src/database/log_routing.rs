@@ -0,0 +1,157 @@
+//! Routing of the logs messages to different channels.
+
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use twilight_model::id::{marker::ChannelMarker, Id};
+
+use crate::serde::IdAsI64;
+
+/// Category of a logs message.
+///
+/// Each category can be sent in its own channel with the `/config logs route`
+/// command.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum LogCategory {
+    /// Sanctions applied by moderators or by the automatic moderation.
+    Sanctions,
+    /// Members joining the guild.
+    Joins,
+    /// Messages restored by moderators.
+    Messages,
+    /// Messages, reactions, names and voice activity caught by a filter.
+    Filters,
+    /// Raid detection, raid mode and lockdown.
+    Raids,
+    /// Configuration changes and configuration issues.
+    Config,
+}
+
+impl LogCategory {
+    /// List of all the categories.
+    pub const ALL: [LogCategory; 6] = [
+        LogCategory::Sanctions,
+        LogCategory::Joins,
+        LogCategory::Messages,
+        LogCategory::Filters,
+        LogCategory::Raids,
+        LogCategory::Config,
+    ];
+}
+
+/// Destination of a [`LogCategory`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogDestination {
+    /// The logs are sent in the default logs channel.
+    Default,
+    /// The logs are sent in a dedicated channel.
+    Channel(Id<ChannelMarker>),
+    /// The logs are not sent.
+    Off,
+}
+
+/// Route of a [`LogCategory`] to a channel.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct LogRoute {
+    /// Category of the logs.
+    pub category: LogCategory,
+    /// Channel where the logs are sent.
+    ///
+    /// If [`None`], the logs of this category are not sent.
+    #[serde_as(as = "Option<IdAsI64>")]
+    #[serde(default)]
+    pub channel: Option<Id<ChannelMarker>>,
+}
+
+/// Routes of the logs categories of a guild.
+///
+/// Categories without a route are sent in the default logs channel, which is
+/// the `logs_chan` field of the guild configuration. Guilds configured before
+/// the logs could be routed thus keep sending every category in the same
+/// channel.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct LogRouting(Vec<LogRoute>);
+
+impl LogRouting {
+    /// Get the destination of a category.
+    pub fn get(&self, category: LogCategory) -> LogDestination {
+        match self.0.iter().find(|route| route.category == category) {
+            Some(LogRoute {
+                channel: Some(channel),
+                ..
+            }) => LogDestination::Channel(*channel),
+            Some(LogRoute { channel: None, .. }) => LogDestination::Off,
+            None => LogDestination::Default,
+        }
+    }
+
+    /// Set the destination of a category.
+    ///
+    /// Setting [`LogDestination::Default`] removes the route of the category.
+    pub fn set(&mut self, category: LogCategory, destination: LogDestination) {
+        self.0.retain(|route| route.category != category);
+
+        let channel = match destination {
+            LogDestination::Default => return,
+            LogDestination::Channel(channel) => Some(channel),
+            LogDestination::Off => None,
+        };
+
+        self.0.push(LogRoute { category, channel });
+    }
+
+    /// Whether no category is routed.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterate over the routes.
+    pub fn iter(&self) -> impl Iterator<Item = &LogRoute> {
+        self.0.iter()
+    }
+
+    /// Iterate mutably over the routes.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut LogRoute> {
+        self.0.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::id::Id;
+
+    use super::{LogCategory, LogDestination, LogRouting};
+
+    #[test]
+    fn test_default_destination() {
+        let routing = LogRouting::default();
+
+        for category in LogCategory::ALL {
+            assert_eq!(routing.get(category), LogDestination::Default);
+        }
+    }
+
+    #[test]
+    fn test_set_destination() {
+        let mut routing = LogRouting::default();
+
+        routing.set(LogCategory::Sanctions, LogDestination::Channel(Id::new(1)));
+        routing.set(LogCategory::Joins, LogDestination::Off);
+        routing.set(LogCategory::Sanctions, LogDestination::Channel(Id::new(2)));
+
+        assert_eq!(
+            routing.get(LogCategory::Sanctions),
+            LogDestination::Channel(Id::new(2))
+        );
+        assert_eq!(routing.get(LogCategory::Joins), LogDestination::Off);
+        assert_eq!(routing.get(LogCategory::Raids), LogDestination::Default);
+        assert_eq!(routing.iter().count(), 2);
+
+        routing.set(LogCategory::Sanctions, LogDestination::Default);
+        routing.set(LogCategory::Joins, LogDestination::Default);
+
+        assert!(routing.is_empty());
+    }
+}
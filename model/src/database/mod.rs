@@ -5,27 +5,89 @@
 //!
 //! ## MongoDB collections
 //! The following collections are used:
+//! - `filter_feedback` ([FilterFeedback]): filtered messages marked as false
+//!   positives
+//! - `growth` ([GuildGrowth]): member count history of guilds
+//! - `guild_links` ([GuildLink]): links between affiliated guilds
 //! - `guilds` ([GuildConfig]): configuration for guilds that uses the bot
 //! - `modlogs` ([Modlog]): moderation logs
+//! - `mutes` ([ActiveMute]): members muted with the mute role
+//! - `quarantines` ([QuarantineState]): quarantined members and their roles
+//! - `reports` ([Report]): messages reported by members
+//! - `stats` ([GuildStats]): statistics of guilds, updated incrementally
+//! - `structure_snapshots` ([StructureSnapshot]): snapshots of the channels
+//!   and roles of guilds
+//! - `watchlist` ([WatchlistEntry]): users watched by moderators
 //!
 //! Each collection name is exported as an associated constant.
 //!
+//! [FilterFeedback]: filter_feedback::FilterFeedback
+//! [GuildGrowth]: growth::GuildGrowth
+//! [GuildLink]: guild_link::GuildLink
 //! [GuildConfig]: guild::GuildConfig
 //! [Modlog]: modlog::Modlog
+//! [ActiveMute]: mute::ActiveMute
+//! [QuarantineState]: quarantine::QuarantineState
+//! [Report]: report::Report
+//! [GuildStats]: stats::GuildStats
+//! [StructureSnapshot]: snapshot::StructureSnapshot
+//! [WatchlistEntry]: watchlist::WatchlistEntry
 
 mod client;
+mod feature;
+mod filter_feedback;
+mod growth;
 mod guild;
+mod guild_link;
+mod log_routing;
 mod modlog;
+mod mute;
+mod quarantine;
+mod references;
+mod report;
+mod snapshot;
+mod stats;
+mod watchlist;
 
 pub use client::DbClient;
+pub use references::{
+    remap_channel_references, remap_role_references, validate_references, BrokenReference,
+    ReferenceKind,
+};
 
 pub mod model {
     //! Models used to represent data in the MongoDB database.
     //!
     //! See the [module documentation](crate::database) for more information.
 
+    pub use mongodb::bson::oid::ObjectId;
+
     pub use super::{
-        guild::{CaptchaConfig, GuildConfig, ModerationConfig},
-        modlog::{Modlog, ModlogType, ModlogUser},
+        feature::{Feature, FeatureSet},
+        filter_feedback::FilterFeedback,
+        growth::{GrowthSample, GuildGrowth},
+        guild::{
+            ActivityRoleConfig, AntinukeAction, AntinukeConfig, AutomodConfig, AutomodRule,
+            AutomodRuleKind, BanSync, CaptchaConfig, ChannelRule, ChannelRuleKind,
+            FirstMessageAction, FirstMessageConfig, GraceConfig, GuildConfig, JoinBurstConfig,
+            JoinBurstSignal, LinkFilterConfig, ModerationConfig, MuteMode, NameAction, NameConfig,
+            OutboundWebhook, PatternAction, PatternRule, PinsConfig, RaidModeAction,
+            RaidModeConfig, ReactionSpamConfig, RoleLogsConfig, SanctionRole, Tag, TagsConfig,
+            VoiceConfig, VoiceHopAction,
+        },
+        guild_link::GuildLink,
+        log_routing::{LogCategory, LogDestination, LogRoute, LogRouting},
+        modlog::{
+            AmendmentKind, DmStatus, KeptModlogs, Modlog, ModlogAmendment, ModlogCounts,
+            ModlogSearch, ModlogType, ModlogUser, SyncOrigin,
+        },
+        mute::ActiveMute,
+        quarantine::QuarantineState,
+        report::{Report, Reporter},
+        snapshot::{ChannelSnapshot, RoleSnapshot, StructureSnapshot},
+        stats::{
+            epoch_day, DailyStats, FilterCounts, GuildStats, StatsCounters, StatsEvent, StatsFilter,
+        },
+        watchlist::WatchlistEntry,
     };
 }
@@ -2,22 +2,27 @@
 
 use anyhow::anyhow;
 use mongodb::{
-    bson::{doc, oid::ObjectId, to_document, Bson},
-    Cursor,
+    bson::{doc, from_document, oid::ObjectId, to_document, Bson, Document},
+    options, Cursor, IndexModel,
 };
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, skip_serializing_none, DisplayFromStr};
 use time::OffsetDateTime;
+use tracing::instrument;
 use twilight_model::{
     id::{
         marker::{GuildMarker, UserMarker},
         Id,
     },
+    user::User,
     util::ImageHash,
 };
 
 use super::DbClient;
-use crate::serde::{DateTimeAsBson, IdAsI64};
+use crate::{
+    log::QueryLatency,
+    serde::{datetime_to_bson, DateTimeAsBson, IdAsI64},
+};
 
 /// Moderation log entry.
 ///
@@ -46,6 +51,45 @@ pub struct Modlog {
     pub reason: Option<String>,
     /// Optional notes attached to the moderation log.
     pub notes: Option<String>,
+    /// Whether the user was not a member of the guild when sanctioned.
+    ///
+    /// This is only the case for bans issued before the user joined the
+    /// guild (pre-emptive bans).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pre_emptive: bool,
+    /// Whether the sanction was applied without the confirmation usually
+    /// required when the user has just been sanctioned.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub confirmation_skipped: bool,
+    /// Whether the sanction is a shadow sanction.
+    ///
+    /// Shadow sanctions are only logged in the staff logs channel, and are
+    /// never sent to the sanctioned user or to external services.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub shadow: bool,
+    /// Whether the sanctioned user was not notified by private message
+    /// because private messages are disabled in the guild.
+    ///
+    /// See [`ModerationConfig::dm_disabled`].
+    ///
+    /// [`ModerationConfig::dm_disabled`]: super::ModerationConfig::dm_disabled
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dm_disabled: bool,
+    /// Delivery status of the private message sent to the sanctioned user.
+    ///
+    /// This is [`None`] for sanctions that are never notified (shadow
+    /// sanctions and pre-emptive bans), and for sanctions recorded before the
+    /// status was stored.
+    pub dm_status: Option<DmStatus>,
+    /// Origin of the ban, for bans mirrored from a linked guild.
+    ///
+    /// Synchronized bans are never synchronized again, so that bans cannot
+    /// loop between linked guilds.
+    pub synced_from: Option<SyncOrigin>,
+    /// Changes made to the sanction after it was applied, such as a mute
+    /// lifted or extended from the logs channel.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub amendments: Vec<ModlogAmendment>,
 }
 
 impl Modlog {
@@ -53,11 +97,232 @@ impl Modlog {
     pub const COLLECTION: &'static str = "modlogs";
 }
 
+/// Delivery status of the private message of a sanction.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DmStatus {
+    /// The private message has been delivered.
+    Delivered,
+    /// The user does not accept private messages from the bot.
+    ///
+    /// Discord returns the same error whether the user has closed their
+    /// private messages or blocked the bot, so both cases have this status.
+    Closed,
+    /// The private message could not be sent for another reason, or was not
+    /// sent in time.
+    Failed,
+    /// Private messages of the sanction are disabled in the guild.
+    Skipped,
+}
+
+/// Origin of a ban synchronized from a linked guild.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct SyncOrigin {
+    /// Guild where the ban was issued.
+    #[serde_as(as = "IdAsI64")]
+    pub guild_id: Id<GuildMarker>,
+    /// Id of the moderation log of the ban in the origin guild.
+    pub case_id: ObjectId,
+}
+
+/// Change made to a sanction after it was applied.
+#[serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ModlogAmendment {
+    /// Type of change.
+    pub kind: AmendmentKind,
+    /// Moderator that made the change.
+    pub actor: ModlogUser,
+    /// Date of the change.
+    #[serde_as(as = "DateTimeAsBson")]
+    pub date: OffsetDateTime,
+    /// New end of the sanction.
+    ///
+    /// This is [`None`] if the sanction has been lifted.
+    #[serde_as(as = "Option<DateTimeAsBson>")]
+    #[serde(default)]
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+/// Type of [`ModlogAmendment`].
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AmendmentKind {
+    /// The sanction has been lifted before its end.
+    Lift,
+    /// The sanction has been shortened.
+    Shorten,
+    /// The sanction has been extended.
+    Extend,
+}
+
 /// Type of modlog entry.
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum ModlogType {
+    Ban,
     Kick,
+    Mute,
+    Unmute,
+    Quarantine,
+    Release,
+}
+
+impl ModlogType {
+    /// Whether the sanction removes the user from the guild.
+    pub fn is_destructive(self) -> bool {
+        matches!(self, ModlogType::Ban | ModlogType::Kick)
+    }
+
+    /// Name of the type, as stored in the database.
+    pub fn name(self) -> &'static str {
+        match self {
+            ModlogType::Ban => "ban",
+            ModlogType::Kick => "kick",
+            ModlogType::Mute => "mute",
+            ModlogType::Unmute => "unmute",
+            ModlogType::Quarantine => "quarantine",
+            ModlogType::Release => "release",
+        }
+    }
+}
+
+/// Number of [`Modlog`]s of a guild for each [`ModlogType`].
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct ModlogCounts {
+    pub ban: u64,
+    pub kick: u64,
+    pub mute: u64,
+    pub unmute: u64,
+    pub quarantine: u64,
+    pub release: u64,
+}
+
+impl ModlogCounts {
+    /// Add the number of modlogs of a given type.
+    pub fn add(&mut self, kind: ModlogType, count: u64) {
+        match kind {
+            ModlogType::Ban => self.ban += count,
+            ModlogType::Kick => self.kick += count,
+            ModlogType::Mute => self.mute += count,
+            ModlogType::Unmute => self.unmute += count,
+            ModlogType::Quarantine => self.quarantine += count,
+            ModlogType::Release => self.release += count,
+        }
+    }
+}
+
+/// Filters of a [`Modlog`] search.
+///
+/// Filters are combined: a modlog must match all of them to be returned.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModlogSearch {
+    /// Words contained in the reason, ignoring case.
+    pub reason: Option<String>,
+    /// Type of the modlogs.
+    pub kind: Option<ModlogType>,
+    /// Moderator that issued the modlogs.
+    pub moderator: Option<Id<UserMarker>>,
+    /// User targeted by the modlogs.
+    pub user: Option<Id<UserMarker>>,
+    /// Only modlogs issued after this date.
+    pub after: Option<OffsetDateTime>,
+    /// Only modlogs issued before this date.
+    pub before: Option<OffsetDateTime>,
+}
+
+impl ModlogSearch {
+    /// Max length of the `reason` field.
+    pub const MAX_REASON_LENGTH: usize = 100;
+
+    /// Check whether the search has no filter.
+    ///
+    /// Such a search would return all the modlogs of the guild, so it should
+    /// be rejected.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// MongoDB query of the search in a guild.
+    ///
+    /// The reason is matched with the text index of the collection (see
+    /// [`DbClient::create_modlog_indexes`]), as a phrase: the searched words
+    /// must appear in the same order in the reason.
+    pub fn query(&self, guild_id: Id<GuildMarker>) -> Document {
+        let mut query = doc! { "guild_id": guild_id.get() as i64 };
+
+        if let Some(reason) = &self.reason {
+            let phrase = format!("\"{}\"", reason.replace('"', ""));
+            query.insert("$text", doc! { "$search": phrase });
+        }
+
+        if let Some(kind) = self.kind {
+            query.insert("kind", kind.name());
+        }
+
+        if let Some(moderator) = self.moderator {
+            query.insert("moderator.id", moderator.get() as i64);
+        }
+
+        if let Some(user) = self.user {
+            query.insert("user.id", user.get() as i64);
+        }
+
+        let mut date = Document::new();
+        if let Some(after) = self.after {
+            date.insert("$gte", datetime_to_bson(after));
+        }
+        if let Some(before) = self.before {
+            date.insert("$lt", datetime_to_bson(before));
+        }
+        if !date.is_empty() {
+            query.insert("date", date);
+        }
+
+        query
+    }
+
+    /// Check whether a modlog of a guild matches the search.
+    ///
+    /// This is the equivalent of [`ModlogSearch::query`] for modlogs that are
+    /// not stored in the database. Like the text index, words are split on
+    /// non-alphanumeric characters and compared ignoring case.
+    pub fn matches(&self, guild_id: Id<GuildMarker>, modlog: &Modlog) -> bool {
+        if modlog.guild_id != guild_id {
+            return false;
+        }
+
+        if let Some(reason) = &self.reason {
+            let phrase = words(reason);
+
+            match &modlog.reason {
+                Some(text) if contains_phrase(&words(text), &phrase) => {}
+                _ => return false,
+            }
+        }
+
+        self.kind.map_or(true, |kind| modlog.kind == kind)
+            && self.moderator.map_or(true, |id| modlog.moderator.id == id)
+            && self.user.map_or(true, |id| modlog.user.id == id)
+            && self.after.map_or(true, |after| modlog.date >= after)
+            && self.before.map_or(true, |before| modlog.date < before)
+    }
+}
+
+/// Split a text into lowercase words.
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Check whether a phrase appears in a list of words.
+fn contains_phrase(words: &[String], phrase: &[String]) -> bool {
+    phrase.is_empty() || words.windows(phrase.len()).any(|window| window == phrase)
 }
 
 /// User model stored with modlog information.
@@ -71,15 +336,28 @@ pub struct ModlogUser {
     pub id: Id<UserMarker>,
     pub name: String,
     pub discriminator: u16,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     #[serde_as(as = "Option<DisplayFromStr>")]
     pub avatar: Option<ImageHash>,
 }
 
+impl From<&User> for ModlogUser {
+    fn from(user: &User) -> Self {
+        Self {
+            id: user.id,
+            name: user.name.clone(),
+            discriminator: user.discriminator,
+            avatar: user.avatar,
+        }
+    }
+}
+
 // Implementation of methods to query the database.
 impl DbClient {
     /// Insert a new [`Modlog`] in the database.
+    #[instrument(level = "debug", skip_all, fields(guild_id = modlog.guild_id.get(), latency_ms))]
     pub async fn create_modlog(&self, modlog: &Modlog) -> Result<ObjectId, anyhow::Error> {
+        let _latency = QueryLatency::start();
         let result = self
             .db()
             .collection::<Modlog>(Modlog::COLLECTION)
@@ -93,7 +371,9 @@ impl DbClient {
     }
 
     /// Get a [`Modlog`] from the database with its id.
+    #[instrument(level = "debug", skip(self), fields(latency_ms))]
     pub async fn get_modlog(&self, id: ObjectId) -> Result<Option<Modlog>, anyhow::Error> {
+        let _latency = QueryLatency::start();
         let query = doc! { "_id": id };
 
         let modlog = self
@@ -105,13 +385,39 @@ impl DbClient {
         Ok(modlog)
     }
 
+    /// Append a [`ModlogAmendment`] to a [`Modlog`].
+    ///
+    /// Returns `false` if the modlog does not exist.
+    #[instrument(level = "debug", skip(self, amendment), fields(latency_ms))]
+    pub async fn amend_modlog(
+        &self,
+        id: ObjectId,
+        amendment: &ModlogAmendment,
+    ) -> Result<bool, anyhow::Error> {
+        let _latency = QueryLatency::start();
+
+        let result = self
+            .db()
+            .collection::<Modlog>(Modlog::COLLECTION)
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$push": { "amendments": to_document(amendment)? } },
+                None,
+            )
+            .await?;
+
+        Ok(result.matched_count > 0)
+    }
+
     /// Find multiple [`Modlog`]s from the database that match a given guild id
     /// and optional user id.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
     pub async fn find_modlogs(
         &self,
         guild_id: Id<GuildMarker>,
         user_id: Option<Id<UserMarker>>,
     ) -> Result<Cursor<Modlog>, anyhow::Error> {
+        let _latency = QueryLatency::start();
         let query = ModlogQuery { guild_id, user_id };
 
         let cursor = self
@@ -122,6 +428,205 @@ impl DbClient {
 
         Ok(cursor)
     }
+
+    /// Get a page of the [`Modlog`]s of a guild, most recent first.
+    ///
+    /// Pages start at `0`. Also returns the total number of modlogs of the
+    /// guild.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn find_modlogs_page(
+        &self,
+        guild_id: Id<GuildMarker>,
+        page: u64,
+        per_page: u64,
+    ) -> Result<(Vec<Modlog>, u64), anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let query = to_document(&ModlogQuery {
+            guild_id,
+            user_id: None,
+        })?;
+        let collection = self.db().collection::<Modlog>(Modlog::COLLECTION);
+
+        let total = collection.count_documents(query.clone(), None).await?;
+
+        let options = options::FindOptions::builder()
+            .sort(doc! { "date": -1 })
+            .skip(page.saturating_mul(per_page))
+            .limit(per_page as i64)
+            .build();
+        let mut cursor = collection.find(query, options).await?;
+
+        let mut modlogs = Vec::new();
+        while cursor.advance().await? {
+            modlogs.push(cursor.deserialize_current()?);
+        }
+
+        Ok((modlogs, total))
+    }
+
+    /// Search the [`Modlog`]s of a guild, most recent first.
+    ///
+    /// At most `limit` modlogs are returned. Empty searches should be
+    /// rejected before calling this method (see [`ModlogSearch::is_empty`]).
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn search_modlogs(
+        &self,
+        guild_id: Id<GuildMarker>,
+        search: &ModlogSearch,
+        limit: i64,
+    ) -> Result<Vec<Modlog>, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let options = options::FindOptions::builder()
+            .sort(doc! { "date": -1 })
+            .limit(limit)
+            .build();
+
+        let mut cursor = self
+            .db()
+            .collection::<Modlog>(Modlog::COLLECTION)
+            .find(search.query(guild_id), options)
+            .await?;
+
+        let mut modlogs = Vec::new();
+        while cursor.advance().await? {
+            modlogs.push(cursor.deserialize_current()?);
+        }
+
+        Ok(modlogs)
+    }
+
+    /// Create the indexes of the `modlogs` collection.
+    ///
+    /// Modlogs are always queried by guild and usually sorted by date. The
+    /// reasons are also indexed by guild in a text index, used to search the
+    /// modlogs by reason. Words are not stemmed, since reasons may be written
+    /// in any language. Indexes are not recreated if they already exist.
+    #[instrument(level = "debug", skip_all, fields(latency_ms))]
+    pub async fn create_modlog_indexes(&self) -> Result<(), anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let date_index = IndexModel::builder()
+            .keys(doc! { "guild_id": 1, "date": -1 })
+            .build();
+        let text_options = options::IndexOptions::builder()
+            .default_language("none".to_owned())
+            .build();
+        let text_index = IndexModel::builder()
+            .keys(doc! { "guild_id": 1, "reason": "text" })
+            .options(text_options)
+            .build();
+
+        self.db()
+            .collection::<Modlog>(Modlog::COLLECTION)
+            .create_indexes([date_index, text_index], None)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Count the [`Modlog`]s of a guild for each [`ModlogType`] and day.
+    ///
+    /// Days are numbered since the Unix epoch (see [`epoch_day`]).
+    ///
+    /// [`epoch_day`]: super::stats::epoch_day
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn count_modlogs_by_day(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<(ModlogType, i64, u64)>, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let day = doc! { "$floor": { "$divide": [{ "$toLong": "$date" }, 86_400_000_i64] } };
+        let pipeline = [
+            doc! { "$match": { "guild_id": guild_id.get() as i64 } },
+            doc! { "$group": {
+                "_id": { "kind": "$kind", "day": day },
+                "count": { "$sum": 1 },
+            } },
+        ];
+
+        let mut cursor = self
+            .db()
+            .collection::<Modlog>(Modlog::COLLECTION)
+            .aggregate(pipeline, None)
+            .await?;
+
+        let mut counts = Vec::new();
+        while cursor.advance().await? {
+            let group: ModlogGroup = from_document(cursor.deserialize_current()?)?;
+            counts.push((group.id.kind, group.id.day, group.count));
+        }
+
+        Ok(counts)
+    }
+
+    /// Delete the [`Modlog`]s of a guild older than a given date.
+    ///
+    /// The modlogs listed in `kept` are not deleted, so that the modlogs of
+    /// active sanctions are preserved (see [`KeptModlogs`]). Returns the
+    /// number of deleted modlogs.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn delete_modlogs_before(
+        &self,
+        guild_id: Id<GuildMarker>,
+        before: OffsetDateTime,
+        kept: &[KeptModlogs],
+    ) -> Result<u64, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let query = modlog_cleanup_query(guild_id, before, kept)?;
+
+        let result = self
+            .db()
+            .collection::<Modlog>(Modlog::COLLECTION)
+            .delete_many(query, None)
+            .await?;
+
+        Ok(result.deleted_count)
+    }
+}
+
+/// Modlogs of active sanctions, kept by [`DbClient::delete_modlogs_before`].
+///
+/// The modlogs of type `kind` of the listed users are kept, since they are
+/// needed to know why the sanction has been applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeptModlogs {
+    /// Type of the kept modlogs.
+    pub kind: ModlogType,
+    /// Users whose modlogs are kept.
+    pub users: Vec<Id<UserMarker>>,
+}
+
+/// Build the query of [`DbClient::delete_modlogs_before`].
+fn modlog_cleanup_query(
+    guild_id: Id<GuildMarker>,
+    before: OffsetDateTime,
+    kept: &[KeptModlogs],
+) -> Result<Document, anyhow::Error> {
+    let query = ModlogCleanupQuery {
+        guild_id,
+        date: DateBefore { before },
+    };
+    let mut query = to_document(&query)?;
+
+    let excluded = kept
+        .iter()
+        .filter(|kept| !kept.users.is_empty())
+        .map(|kept| {
+            let users = kept
+                .users
+                .iter()
+                .map(|id| id.get() as i64)
+                .collect::<Vec<_>>();
+
+            doc! { "kind": kept.kind.name(), "user.id": { "$in": users } }
+        })
+        .collect::<Vec<_>>();
+
+    // MongoDB rejects empty `$nor` arrays.
+    if !excluded.is_empty() {
+        query.insert("$nor", excluded);
+    }
+
+    Ok(query)
 }
 
 /// Query modlogs with guild_id and optional user_id
@@ -133,3 +638,84 @@ struct ModlogQuery {
     #[serde_as(as = "Option<IdAsI64>")]
     pub user_id: Option<Id<UserMarker>>,
 }
+
+/// Number of modlogs of a given type and day, returned by the aggregation of
+/// [`DbClient::count_modlogs_by_day`].
+#[derive(Debug, Deserialize)]
+struct ModlogGroup {
+    #[serde(rename = "_id")]
+    id: ModlogGroupId,
+    count: u64,
+}
+
+/// Key of a [`ModlogGroup`].
+#[derive(Debug, Deserialize)]
+struct ModlogGroupId {
+    kind: ModlogType,
+    day: i64,
+}
+
+/// Query modlogs of a guild older than a given date.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+struct ModlogCleanupQuery {
+    #[serde_as(as = "IdAsI64")]
+    pub guild_id: Id<GuildMarker>,
+    pub date: DateBefore,
+}
+
+/// Match dates before a given date.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+struct DateBefore {
+    #[serde_as(as = "DateTimeAsBson")]
+    #[serde(rename = "$lt")]
+    pub before: OffsetDateTime,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_modlog_cleanup_query() {
+        let before = OffsetDateTime::from_unix_timestamp(1_628_594_197).unwrap();
+        let kept = [
+            KeptModlogs {
+                kind: ModlogType::Mute,
+                users: vec![Id::new(2), Id::new(3)],
+            },
+            KeptModlogs {
+                kind: ModlogType::Quarantine,
+                users: vec![Id::new(4)],
+            },
+        ];
+
+        let query = modlog_cleanup_query(Id::new(1), before, &kept).unwrap();
+
+        assert_eq!(
+            query,
+            doc! {
+                "guild_id": 1_i64,
+                "date": { "$lt": datetime_to_bson(before) },
+                "$nor": [
+                    { "kind": "mute", "user.id": { "$in": [2_i64, 3_i64] } },
+                    { "kind": "quarantine", "user.id": { "$in": [4_i64] } },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_modlog_cleanup_query_nothing_kept() {
+        let before = OffsetDateTime::from_unix_timestamp(1_628_594_197).unwrap();
+        let kept = [KeptModlogs {
+            kind: ModlogType::Quarantine,
+            users: Vec::new(),
+        }];
+
+        let query = modlog_cleanup_query(Id::new(1), before, &kept).unwrap();
+
+        assert!(!query.contains_key("$nor"));
+    }
+}
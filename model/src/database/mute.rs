@@ -0,0 +1,164 @@
+//! Models for the `mutes` collection.
+
+use mongodb::{
+    bson::{doc, oid::ObjectId, to_document},
+    options,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, skip_serializing_none};
+use time::OffsetDateTime;
+use tracing::instrument;
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+
+use super::DbClient;
+use crate::{
+    log::QueryLatency,
+    serde::{DateTimeAsBson, IdAsI64},
+};
+
+/// Member muted by RaidProtect.
+///
+/// This type represent an active mute stored in the `mutes` collection of the
+/// database. It is used to reapply the mute role if the member leaves and
+/// rejoins the guild while muted.
+///
+/// Mutes applied with a Discord timeout are also stored, since Discord keeps
+/// them when a member rejoins but they can be cleared by other bots or by
+/// mistake. They are reapplied if [`ModerationConfig::enforce_mutes`] is
+/// enabled.
+///
+/// [`ModerationConfig::enforce_mutes`]: super::guild::ModerationConfig::enforce_mutes
+#[serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ActiveMute {
+    /// Guild where the member is muted.
+    #[serde_as(as = "IdAsI64")]
+    pub guild_id: Id<GuildMarker>,
+    /// Muted member.
+    #[serde_as(as = "IdAsI64")]
+    pub user_id: Id<UserMarker>,
+    /// Date at which the mute expires.
+    ///
+    /// If [`None`], the mute never expires.
+    #[serde_as(as = "Option<DateTimeAsBson>")]
+    #[serde(default)]
+    pub expires_at: Option<OffsetDateTime>,
+    /// Whether the member is muted with a Discord timeout instead of the
+    /// mute role.
+    #[serde(default)]
+    pub timeout: bool,
+    /// Id of the moderation log of the mute.
+    ///
+    /// This is [`None`] for mutes stored before the id was recorded.
+    #[serde(default)]
+    pub case_id: Option<ObjectId>,
+}
+
+impl ActiveMute {
+    /// Name of the MongoDB collection.
+    pub const COLLECTION: &'static str = "mutes";
+
+    /// Whether the mute has expired.
+    pub fn is_expired(&self, now: OffsetDateTime) -> bool {
+        self.expires_at
+            .map_or(false, |expires_at| expires_at <= now)
+    }
+}
+
+// Implementation of methods to query the database.
+impl DbClient {
+    /// Insert or replace an [`ActiveMute`] in the database.
+    #[instrument(level = "debug", skip_all, fields(guild_id = mute.guild_id.get(), latency_ms))]
+    pub async fn create_mute(&self, mute: &ActiveMute) -> Result<(), anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let query = MuteQuery {
+            guild_id: mute.guild_id,
+            user_id: mute.user_id,
+        };
+        let options = options::ReplaceOptions::builder().upsert(true).build();
+
+        self.db()
+            .collection::<ActiveMute>(ActiveMute::COLLECTION)
+            .replace_one(to_document(&query)?, mute, options)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Get the [`ActiveMute`] of a member, if it exists.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn get_mute(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<ActiveMute>, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let query = MuteQuery { guild_id, user_id };
+
+        let mute = self
+            .db()
+            .collection::<ActiveMute>(ActiveMute::COLLECTION)
+            .find_one(to_document(&query)?, None)
+            .await?;
+
+        Ok(mute)
+    }
+
+    /// Delete the [`ActiveMute`] of a member.
+    ///
+    /// Returns whether a mute has been deleted.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn delete_mute(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<bool, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let query = MuteQuery { guild_id, user_id };
+
+        let result = self
+            .db()
+            .collection::<ActiveMute>(ActiveMute::COLLECTION)
+            .delete_one(to_document(&query)?, None)
+            .await?;
+
+        Ok(result.deleted_count > 0)
+    }
+
+    /// Get the ids of the muted members of a guild.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn find_muted_users(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<Id<UserMarker>>, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let query = doc! { "guild_id": guild_id.get() as i64 };
+
+        let mut cursor = self
+            .db()
+            .collection::<ActiveMute>(ActiveMute::COLLECTION)
+            .find(query, None)
+            .await?;
+
+        let mut users = Vec::new();
+        while cursor.advance().await? {
+            users.push(cursor.deserialize_current()?.user_id);
+        }
+
+        Ok(users)
+    }
+}
+
+/// Query mutes with guild_id and user_id.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+struct MuteQuery {
+    #[serde_as(as = "IdAsI64")]
+    pub guild_id: Id<GuildMarker>,
+    #[serde_as(as = "IdAsI64")]
+    pub user_id: Id<UserMarker>,
+}
@@ -0,0 +1,145 @@
+//! Models for the `quarantines` collection.
+
+use mongodb::{
+    bson::{doc, to_document},
+    options,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use time::OffsetDateTime;
+use tracing::instrument;
+use twilight_model::id::{
+    marker::{GuildMarker, RoleMarker, UserMarker},
+    Id,
+};
+
+use super::DbClient;
+use crate::{
+    log::QueryLatency,
+    serde::{DateTimeAsBson, IdAsI64},
+};
+
+/// Member placed in quarantine.
+///
+/// This type represent a quarantine stored in the `quarantines` collection of
+/// the database. The roles of the member before the quarantine are stored to
+/// be restored when the member is released. The state is kept if the member
+/// leaves the guild, so that the quarantine role is added again on rejoin.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct QuarantineState {
+    /// Guild where the member is quarantined.
+    #[serde_as(as = "IdAsI64")]
+    pub guild_id: Id<GuildMarker>,
+    /// Quarantined member.
+    #[serde_as(as = "IdAsI64")]
+    pub user_id: Id<UserMarker>,
+    /// Roles of the member before the quarantine.
+    #[serde_as(as = "Vec<IdAsI64>")]
+    pub roles: Vec<Id<RoleMarker>>,
+    /// Date at which the member has been quarantined.
+    #[serde_as(as = "DateTimeAsBson")]
+    pub date: OffsetDateTime,
+}
+
+impl QuarantineState {
+    /// Name of the MongoDB collection.
+    pub const COLLECTION: &'static str = "quarantines";
+}
+
+// Implementation of methods to query the database.
+impl DbClient {
+    /// Insert or replace a [`QuarantineState`] in the database.
+    #[instrument(level = "debug", skip_all, fields(guild_id = quarantine.guild_id.get(), latency_ms))]
+    pub async fn create_quarantine(
+        &self,
+        quarantine: &QuarantineState,
+    ) -> Result<(), anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let query = QuarantineQuery {
+            guild_id: quarantine.guild_id,
+            user_id: quarantine.user_id,
+        };
+        let options = options::ReplaceOptions::builder().upsert(true).build();
+
+        self.db()
+            .collection::<QuarantineState>(QuarantineState::COLLECTION)
+            .replace_one(to_document(&query)?, quarantine, options)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Get the [`QuarantineState`] of a member, if it exists.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn get_quarantine(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<QuarantineState>, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let query = QuarantineQuery { guild_id, user_id };
+
+        let quarantine = self
+            .db()
+            .collection::<QuarantineState>(QuarantineState::COLLECTION)
+            .find_one(to_document(&query)?, None)
+            .await?;
+
+        Ok(quarantine)
+    }
+
+    /// Delete the [`QuarantineState`] of a member.
+    ///
+    /// Returns whether a quarantine has been deleted.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn delete_quarantine(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<bool, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let query = QuarantineQuery { guild_id, user_id };
+
+        let result = self
+            .db()
+            .collection::<QuarantineState>(QuarantineState::COLLECTION)
+            .delete_one(to_document(&query)?, None)
+            .await?;
+
+        Ok(result.deleted_count > 0)
+    }
+
+    /// Get the ids of the quarantined members of a guild.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn find_quarantined_users(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<Id<UserMarker>>, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let query = doc! { "guild_id": guild_id.get() as i64 };
+
+        let mut cursor = self
+            .db()
+            .collection::<QuarantineState>(QuarantineState::COLLECTION)
+            .find(query, None)
+            .await?;
+
+        let mut users = Vec::new();
+        while cursor.advance().await? {
+            users.push(cursor.deserialize_current()?.user_id);
+        }
+
+        Ok(users)
+    }
+}
+
+/// Query quarantines with guild_id and user_id.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+struct QuarantineQuery {
+    #[serde_as(as = "IdAsI64")]
+    pub guild_id: Id<GuildMarker>,
+    #[serde_as(as = "IdAsI64")]
+    pub user_id: Id<UserMarker>,
+}
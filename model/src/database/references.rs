@@ -0,0 +1,680 @@
+//! Validation of the channels and roles referenced in a [`GuildConfig`].
+//!
+//! The guild configuration references channels and roles that can be deleted
+//! at any time. The [`validate_references`] function checks these references
+//! against the channels and roles of the guild stored in the cache.
+//!
+//! When a deleted channel or role is recreated, the references to the old id
+//! are updated with [`remap_channel_references`] and [`remap_role_references`].
+
+use std::collections::HashSet;
+
+use twilight_model::id::{
+    marker::{ChannelMarker, RoleMarker},
+    Id,
+};
+
+use super::guild::{GuildConfig, MuteMode};
+
+/// Kind of reference in a [`GuildConfig`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    /// [`GuildConfig::logs_chan`]
+    LogsChannel,
+    /// [`CaptchaConfig::channel`](super::guild::CaptchaConfig::channel)
+    CaptchaChannel,
+    /// [`CaptchaConfig::logs`](super::guild::CaptchaConfig::logs)
+    CaptchaLogsChannel,
+    /// [`CaptchaConfig::role`](super::guild::CaptchaConfig::role)
+    CaptchaRole,
+    /// [`CaptchaConfig::verified_roles`](super::guild::CaptchaConfig::verified_roles)
+    CaptchaVerifiedRole,
+    /// [`ModerationConfig::roles`](super::guild::ModerationConfig::roles)
+    ModeratorRole,
+    /// [`ReactionSpamConfig::trusted_roles`](super::guild::ReactionSpamConfig::trusted_roles)
+    ReactionSpamTrustedRole,
+    /// [`LinkFilterConfig::trusted_roles`](super::guild::LinkFilterConfig::trusted_roles)
+    LinkFilterTrustedRole,
+    /// [`VoiceConfig::logs_chan`](super::guild::VoiceConfig::logs_chan)
+    VoiceLogsChannel,
+    /// [`VoiceConfig::trusted_roles`](super::guild::VoiceConfig::trusted_roles)
+    VoiceTrustedRole,
+    /// [`GuildConfig::automod_ignored_roles`]
+    AutomodIgnoredRole,
+    /// [`ActivityRoleConfig::role`](super::guild::ActivityRoleConfig::role)
+    ActivityRole,
+    /// [`GuildConfig::watch_chan`]
+    WatchChannel,
+    /// [`PinsConfig::logs_chan`](super::guild::PinsConfig::logs_chan)
+    PinsLogsChannel,
+    /// [`RoleLogsConfig::logs_chan`](super::guild::RoleLogsConfig::logs_chan)
+    RoleLogsChannel,
+    /// [`ModerationConfig::quarantine_role`](super::guild::ModerationConfig::quarantine_role)
+    QuarantineRole,
+    /// [`MuteMode::Role`](super::guild::MuteMode::Role)
+    MuteRole,
+    /// [`GuildConfig::raid_alert_role`]
+    RaidAlertRole,
+    /// [`GuildConfig::reports_chan`]
+    ReportsChannel,
+    /// [`GuildConfig::milestones_chan`]
+    MilestonesChannel,
+    /// [`GuildConfig::log_routing`]
+    LogRouteChannel,
+    /// [`RaidModeConfig::review_channel`](super::guild::RaidModeConfig::review_channel)
+    RaidModeReviewChannel,
+    /// [`RaidModeConfig::member_role`](super::guild::RaidModeConfig::member_role)
+    RaidModeMemberRole,
+    /// [`ModerationConfig::announcement_channel`](super::guild::ModerationConfig::announcement_channel)
+    AnnouncementChannel,
+    /// [`GraceConfig::trusted_roles`](super::guild::GraceConfig::trusted_roles)
+    GraceTrustedRole,
+    /// [`SanctionRole::role_id`](super::guild::SanctionRole::role_id)
+    SanctionRole,
+    /// [`ModerationConfig::skip_confirmation_roles`](super::guild::ModerationConfig::skip_confirmation_roles)
+    SkipConfirmationRole,
+    /// [`RoleLogsConfig::ignored_roles`](super::guild::RoleLogsConfig::ignored_roles)
+    RoleLogsIgnoredRole,
+    /// [`TagsConfig::manager_roles`](super::guild::TagsConfig::manager_roles)
+    TagManagerRole,
+    /// [`ChannelRule::channel_id`](super::guild::ChannelRule::channel_id)
+    ChannelRuleChannel,
+    /// [`PinsConfig::ignored_channels`](super::guild::PinsConfig::ignored_channels)
+    PinsIgnoredChannel,
+}
+
+/// Reference to a channel or role that no longer exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrokenReference {
+    /// Deleted channel.
+    Channel {
+        kind: ReferenceKind,
+        id: Id<ChannelMarker>,
+    },
+    /// Deleted role.
+    Role {
+        kind: ReferenceKind,
+        id: Id<RoleMarker>,
+    },
+}
+
+impl BrokenReference {
+    /// Get the kind of the reference.
+    pub fn kind(&self) -> ReferenceKind {
+        match self {
+            Self::Channel { kind, .. } | Self::Role { kind, .. } => *kind,
+        }
+    }
+}
+
+/// Check the channels and roles referenced in a [`GuildConfig`].
+///
+/// The `channels` and `roles` parameters are the channels and roles of the
+/// guild, usually from the [`CachedGuild`]. The references to channels or
+/// roles that are not in these sets are returned.
+///
+/// [`CachedGuild`]: crate::cache::discord::CachedGuild
+pub fn validate_references(
+    config: &GuildConfig,
+    channels: &HashSet<Id<ChannelMarker>>,
+    roles: &HashSet<Id<RoleMarker>>,
+) -> Vec<BrokenReference> {
+    let channel_refs = [
+        (ReferenceKind::LogsChannel, config.logs_chan),
+        (ReferenceKind::CaptchaChannel, config.captcha.channel),
+        (ReferenceKind::CaptchaLogsChannel, config.captcha.logs),
+        (ReferenceKind::VoiceLogsChannel, config.voice.logs_chan),
+        (ReferenceKind::WatchChannel, config.watch_chan),
+        (ReferenceKind::PinsLogsChannel, config.pins.logs_chan),
+        (ReferenceKind::RoleLogsChannel, config.role_logs.logs_chan),
+        (ReferenceKind::ReportsChannel, config.reports_chan),
+        (ReferenceKind::MilestonesChannel, config.milestones_chan),
+        (
+            ReferenceKind::RaidModeReviewChannel,
+            config.raid_mode.review_channel,
+        ),
+        (
+            ReferenceKind::AnnouncementChannel,
+            config.moderation.announcement_channel,
+        ),
+    ];
+
+    let route_refs = config
+        .log_routing
+        .iter()
+        .map(|route| (ReferenceKind::LogRouteChannel, route.channel));
+
+    let channel_rule_refs = config
+        .channel_rules
+        .iter()
+        .map(|rule| (ReferenceKind::ChannelRuleChannel, Some(rule.channel_id)));
+
+    let pins_ignored_refs = config
+        .pins
+        .ignored_channels
+        .iter()
+        .map(|id| (ReferenceKind::PinsIgnoredChannel, Some(*id)));
+
+    let mute_role = match config.moderation.mute_mode {
+        MuteMode::Role { role_id } => Some(role_id),
+        MuteMode::Timeout => None,
+    };
+
+    let role_refs = config
+        .captcha
+        .role
+        .iter()
+        .map(|id| (ReferenceKind::CaptchaRole, *id))
+        .chain(
+            config
+                .captcha
+                .verified_roles
+                .iter()
+                .map(|id| (ReferenceKind::CaptchaVerifiedRole, *id)),
+        )
+        .chain(
+            config
+                .moderation
+                .roles
+                .iter()
+                .map(|id| (ReferenceKind::ModeratorRole, *id)),
+        )
+        .chain(
+            config
+                .reaction_spam
+                .trusted_roles
+                .iter()
+                .map(|id| (ReferenceKind::ReactionSpamTrustedRole, *id)),
+        )
+        .chain(
+            config
+                .link_filter
+                .trusted_roles
+                .iter()
+                .map(|id| (ReferenceKind::LinkFilterTrustedRole, *id)),
+        )
+        .chain(
+            config
+                .voice
+                .trusted_roles
+                .iter()
+                .map(|id| (ReferenceKind::VoiceTrustedRole, *id)),
+        )
+        .chain(
+            config
+                .automod_ignored_roles
+                .iter()
+                .map(|id| (ReferenceKind::AutomodIgnoredRole, *id)),
+        )
+        .chain(
+            config
+                .grace
+                .trusted_roles
+                .iter()
+                .map(|id| (ReferenceKind::GraceTrustedRole, *id)),
+        )
+        .chain(
+            config
+                .activity_role
+                .role
+                .iter()
+                .map(|id| (ReferenceKind::ActivityRole, *id)),
+        )
+        .chain(
+            config
+                .moderation
+                .sanction_roles
+                .iter()
+                .map(|role| (ReferenceKind::SanctionRole, role.role_id)),
+        )
+        .chain(
+            config
+                .moderation
+                .skip_confirmation_roles
+                .iter()
+                .map(|id| (ReferenceKind::SkipConfirmationRole, *id)),
+        )
+        .chain(
+            config
+                .role_logs
+                .ignored_roles
+                .iter()
+                .map(|id| (ReferenceKind::RoleLogsIgnoredRole, *id)),
+        )
+        .chain(
+            config
+                .tags
+                .manager_roles
+                .iter()
+                .map(|id| (ReferenceKind::TagManagerRole, *id)),
+        )
+        .chain(
+            config
+                .moderation
+                .quarantine_role
+                .map(|id| (ReferenceKind::QuarantineRole, id)),
+        )
+        .chain(mute_role.map(|id| (ReferenceKind::MuteRole, id)))
+        .chain(
+            config
+                .raid_alert_role
+                .map(|id| (ReferenceKind::RaidAlertRole, id)),
+        )
+        .chain(
+            config
+                .raid_mode
+                .member_role
+                .map(|id| (ReferenceKind::RaidModeMemberRole, id)),
+        );
+
+    let broken_channels = channel_refs
+        .into_iter()
+        .chain(route_refs)
+        .chain(channel_rule_refs)
+        .chain(pins_ignored_refs)
+        .filter_map(|(kind, id)| id.map(|id| (kind, id)))
+        .filter(|(_, id)| !channels.contains(id))
+        .map(|(kind, id)| BrokenReference::Channel { kind, id });
+
+    let broken_roles = role_refs
+        .filter(|(_, id)| !roles.contains(id))
+        .map(|(kind, id)| BrokenReference::Role { kind, id });
+
+    broken_channels.chain(broken_roles).collect()
+}
+
+/// Replace the references to a channel in a [`GuildConfig`].
+///
+/// This is used when a deleted channel is recreated with a new id. Returns
+/// whether a reference has been updated.
+///
+/// A channel has at most one [`ChannelRule`]: if the new channel already has a
+/// rule, the rule of the old channel is removed instead of being remapped.
+///
+/// [`ChannelRule`]: super::guild::ChannelRule
+pub fn remap_channel_references(
+    config: &mut GuildConfig,
+    old: Id<ChannelMarker>,
+    new: Id<ChannelMarker>,
+) -> bool {
+    let mut updated = false;
+
+    for reference in [
+        &mut config.logs_chan,
+        &mut config.captcha.channel,
+        &mut config.captcha.logs,
+        &mut config.voice.logs_chan,
+        &mut config.watch_chan,
+        &mut config.pins.logs_chan,
+        &mut config.role_logs.logs_chan,
+        &mut config.reports_chan,
+        &mut config.milestones_chan,
+        &mut config.raid_mode.review_channel,
+        &mut config.moderation.announcement_channel,
+    ]
+    .into_iter()
+    .chain(
+        config
+            .log_routing
+            .iter_mut()
+            .map(|route| &mut route.channel),
+    ) {
+        if *reference == Some(old) {
+            *reference = Some(new);
+            updated = true;
+        }
+    }
+
+    for channel in config
+        .pins
+        .ignored_channels
+        .iter_mut()
+        .filter(|channel| **channel == old)
+    {
+        *channel = new;
+        updated = true;
+    }
+
+    if config.channel_rule(old).is_some() {
+        if config.channel_rule(new).is_some() {
+            config.channel_rules.retain(|rule| rule.channel_id != old);
+        } else {
+            for rule in config
+                .channel_rules
+                .iter_mut()
+                .filter(|rule| rule.channel_id == old)
+            {
+                rule.channel_id = new;
+            }
+        }
+
+        updated = true;
+    }
+
+    updated
+}
+
+/// Replace the references to a role in a [`GuildConfig`].
+///
+/// This is used when a deleted role is recreated with a new id. Returns
+/// whether a reference has been updated.
+pub fn remap_role_references(
+    config: &mut GuildConfig,
+    old: Id<RoleMarker>,
+    new: Id<RoleMarker>,
+) -> bool {
+    let mut updated = false;
+
+    for role in [
+        &mut config.captcha.role,
+        &mut config.activity_role.role,
+        &mut config.moderation.quarantine_role,
+        &mut config.raid_alert_role,
+        &mut config.raid_mode.member_role,
+    ] {
+        if *role == Some(old) {
+            *role = Some(new);
+            updated = true;
+        }
+    }
+
+    if let MuteMode::Role { role_id } = &mut config.moderation.mute_mode {
+        if *role_id == old {
+            *role_id = new;
+            updated = true;
+        }
+    }
+
+    for roles in [
+        &mut config.captcha.verified_roles,
+        &mut config.moderation.roles,
+        &mut config.reaction_spam.trusted_roles,
+        &mut config.link_filter.trusted_roles,
+        &mut config.voice.trusted_roles,
+        &mut config.automod_ignored_roles,
+        &mut config.grace.trusted_roles,
+        &mut config.moderation.skip_confirmation_roles,
+        &mut config.role_logs.ignored_roles,
+        &mut config.tags.manager_roles,
+    ] {
+        for role in roles.iter_mut().filter(|role| **role == old) {
+            *role = new;
+            updated = true;
+        }
+    }
+
+    for role in config
+        .moderation
+        .sanction_roles
+        .iter_mut()
+        .filter(|role| role.role_id == old)
+    {
+        role.role_id = new;
+        updated = true;
+    }
+
+    updated
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use twilight_model::id::Id;
+
+    use super::*;
+    use crate::database::model::{
+        ChannelRule, ChannelRuleKind, LogCategory, LogDestination, ModlogType, SanctionRole,
+    };
+
+    fn config() -> GuildConfig {
+        let mut config = GuildConfig::new(Id::new(1));
+        config.logs_chan = Some(Id::new(10));
+        config.captcha.channel = Some(Id::new(11));
+        config.captcha.logs = Some(Id::new(12));
+        config.captcha.role = Some(Id::new(20));
+        config.captcha.verified_roles = vec![Id::new(21)];
+        config.moderation.roles = vec![Id::new(22)];
+        config.reaction_spam.trusted_roles = vec![Id::new(23)];
+        config.voice.logs_chan = Some(Id::new(13));
+        config.voice.trusted_roles = vec![Id::new(24)];
+        config.activity_role.role = Some(Id::new(25));
+        config.link_filter.trusted_roles = vec![Id::new(26)];
+        config.watch_chan = Some(Id::new(14));
+        config.pins.logs_chan = Some(Id::new(15));
+        config.role_logs.logs_chan = Some(Id::new(16));
+        config.reports_chan = Some(Id::new(17));
+        config.milestones_chan = Some(Id::new(7));
+        config
+            .log_routing
+            .set(LogCategory::Sanctions, LogDestination::Channel(Id::new(18)));
+        config
+            .log_routing
+            .set(LogCategory::Joins, LogDestination::Off);
+        config.moderation.quarantine_role = Some(Id::new(27));
+        config.moderation.mute_mode = MuteMode::Role {
+            role_id: Id::new(28),
+        };
+        config.raid_alert_role = Some(Id::new(29));
+        config.raid_mode.review_channel = Some(Id::new(9));
+        config.raid_mode.member_role = Some(Id::new(39));
+        config.moderation.announcement_channel = Some(Id::new(8));
+        config.automod_ignored_roles = vec![Id::new(19)];
+        config.grace.trusted_roles = vec![Id::new(38)];
+        config.moderation.sanction_roles = vec![SanctionRole {
+            role_id: Id::new(34),
+            sanctions: vec![ModlogType::Kick],
+            shadow: false,
+        }];
+        config.moderation.skip_confirmation_roles = vec![Id::new(35)];
+        config.role_logs.ignored_roles = vec![Id::new(36)];
+        config.tags.manager_roles = vec![Id::new(37)];
+        config.channel_rules = vec![ChannelRule {
+            channel_id: Id::new(6),
+            kind: ChannelRuleKind::MediaOnly,
+        }];
+        config.pins.ignored_channels = vec![Id::new(5)];
+
+        config
+    }
+
+    fn channels() -> HashSet<Id<ChannelMarker>> {
+        [5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18]
+            .into_iter()
+            .map(Id::new)
+            .collect()
+    }
+
+    fn roles() -> HashSet<Id<RoleMarker>> {
+        [
+            19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 34, 35, 36, 37, 38, 39,
+        ]
+        .into_iter()
+        .map(Id::new)
+        .collect()
+    }
+
+    #[test]
+    fn test_valid_references() {
+        assert!(validate_references(&config(), &channels(), &roles()).is_empty());
+    }
+
+    #[test]
+    fn test_empty_config() {
+        let config = GuildConfig::new(Id::new(1));
+
+        assert!(validate_references(&config, &HashSet::new(), &HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn test_broken_channels() {
+        let cases = [
+            (10, ReferenceKind::LogsChannel),
+            (11, ReferenceKind::CaptchaChannel),
+            (12, ReferenceKind::CaptchaLogsChannel),
+            (13, ReferenceKind::VoiceLogsChannel),
+            (14, ReferenceKind::WatchChannel),
+            (15, ReferenceKind::PinsLogsChannel),
+            (16, ReferenceKind::RoleLogsChannel),
+            (17, ReferenceKind::ReportsChannel),
+            (18, ReferenceKind::LogRouteChannel),
+            (9, ReferenceKind::RaidModeReviewChannel),
+            (8, ReferenceKind::AnnouncementChannel),
+            (7, ReferenceKind::MilestonesChannel),
+            (6, ReferenceKind::ChannelRuleChannel),
+            (5, ReferenceKind::PinsIgnoredChannel),
+        ];
+
+        for (id, kind) in cases {
+            let mut channels = channels();
+            channels.remove(&Id::new(id));
+
+            assert_eq!(
+                validate_references(&config(), &channels, &roles()),
+                vec![BrokenReference::Channel {
+                    kind,
+                    id: Id::new(id)
+                }]
+            );
+        }
+    }
+
+    #[test]
+    fn test_broken_roles() {
+        let cases = [
+            (20, ReferenceKind::CaptchaRole),
+            (21, ReferenceKind::CaptchaVerifiedRole),
+            (22, ReferenceKind::ModeratorRole),
+            (23, ReferenceKind::ReactionSpamTrustedRole),
+            (24, ReferenceKind::VoiceTrustedRole),
+            (25, ReferenceKind::ActivityRole),
+            (26, ReferenceKind::LinkFilterTrustedRole),
+            (27, ReferenceKind::QuarantineRole),
+            (28, ReferenceKind::MuteRole),
+            (29, ReferenceKind::RaidAlertRole),
+            (19, ReferenceKind::AutomodIgnoredRole),
+            (39, ReferenceKind::RaidModeMemberRole),
+            (38, ReferenceKind::GraceTrustedRole),
+            (34, ReferenceKind::SanctionRole),
+            (35, ReferenceKind::SkipConfirmationRole),
+            (36, ReferenceKind::RoleLogsIgnoredRole),
+            (37, ReferenceKind::TagManagerRole),
+        ];
+
+        for (id, kind) in cases {
+            let mut roles = roles();
+            roles.remove(&Id::new(id));
+
+            assert_eq!(
+                validate_references(&config(), &channels(), &roles),
+                vec![BrokenReference::Role {
+                    kind,
+                    id: Id::new(id)
+                }]
+            );
+        }
+    }
+
+    #[test]
+    fn test_remap_channel_references() {
+        let mut config = config();
+
+        assert!(remap_channel_references(
+            &mut config,
+            Id::new(10),
+            Id::new(30)
+        ));
+        assert_eq!(config.logs_chan, Some(Id::new(30)));
+        assert!(remap_channel_references(
+            &mut config,
+            Id::new(16),
+            Id::new(31)
+        ));
+        assert_eq!(config.role_logs.logs_chan, Some(Id::new(31)));
+        assert!(remap_channel_references(
+            &mut config,
+            Id::new(18),
+            Id::new(32)
+        ));
+        assert_eq!(
+            config.log_routing.get(LogCategory::Sanctions),
+            LogDestination::Channel(Id::new(32))
+        );
+        assert!(remap_channel_references(
+            &mut config,
+            Id::new(6),
+            Id::new(33)
+        ));
+        assert_eq!(
+            config.channel_rule(Id::new(33)),
+            Some(&config.channel_rules[0])
+        );
+        assert_eq!(config.channel_rule(Id::new(6)), None);
+        assert!(remap_channel_references(
+            &mut config,
+            Id::new(5),
+            Id::new(34)
+        ));
+        assert_eq!(config.pins.ignored_channels, vec![Id::new(34)]);
+        assert!(!remap_channel_references(
+            &mut config,
+            Id::new(10),
+            Id::new(30)
+        ));
+    }
+
+    #[test]
+    fn test_remap_channel_rule_existing() {
+        let mut config = config();
+        config.channel_rules.push(ChannelRule {
+            channel_id: Id::new(4),
+            kind: ChannelRuleKind::TextOnly,
+        });
+
+        // The rule of the new channel is kept.
+        assert!(remap_channel_references(
+            &mut config,
+            Id::new(6),
+            Id::new(4)
+        ));
+        assert_eq!(
+            config.channel_rules,
+            vec![ChannelRule {
+                channel_id: Id::new(4),
+                kind: ChannelRuleKind::TextOnly,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_remap_role_references() {
+        let mut config = config();
+
+        assert!(remap_role_references(&mut config, Id::new(22), Id::new(40)));
+        assert_eq!(config.moderation.roles, vec![Id::new(40)]);
+        assert!(remap_role_references(&mut config, Id::new(20), Id::new(41)));
+        assert_eq!(config.captcha.role, Some(Id::new(41)));
+        assert!(remap_role_references(&mut config, Id::new(25), Id::new(42)));
+        assert_eq!(config.activity_role.role, Some(Id::new(42)));
+        assert!(remap_role_references(&mut config, Id::new(28), Id::new(43)));
+        assert_eq!(
+            config.moderation.mute_mode,
+            MuteMode::Role {
+                role_id: Id::new(43)
+            }
+        );
+        assert!(remap_role_references(&mut config, Id::new(34), Id::new(44)));
+        assert_eq!(config.moderation.sanction_roles[0].role_id, Id::new(44));
+        assert!(remap_role_references(&mut config, Id::new(35), Id::new(45)));
+        assert_eq!(config.moderation.skip_confirmation_roles, vec![Id::new(45)]);
+        assert!(remap_role_references(&mut config, Id::new(36), Id::new(46)));
+        assert_eq!(config.role_logs.ignored_roles, vec![Id::new(46)]);
+        assert!(remap_role_references(&mut config, Id::new(37), Id::new(47)));
+        assert_eq!(config.tags.manager_roles, vec![Id::new(47)]);
+        assert!(!remap_role_references(
+            &mut config,
+            Id::new(22),
+            Id::new(40)
+        ));
+    }
+}
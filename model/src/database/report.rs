@@ -0,0 +1,199 @@
+//! Models for the `reports` collection.
+
+use anyhow::{anyhow, Context};
+use mongodb::bson::{doc, oid::ObjectId, to_document, Bson};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, skip_serializing_none};
+use time::OffsetDateTime;
+use tracing::instrument;
+use twilight_model::id::{
+    marker::{ChannelMarker, GuildMarker, MessageMarker, UserMarker},
+    Id,
+};
+
+use super::{modlog::ModlogUser, DbClient};
+use crate::{
+    log::QueryLatency,
+    serde::{DateTimeAsBson, IdAsI64},
+};
+
+/// Message reported by members.
+///
+/// This type represent a report stored in the `reports` collection of the
+/// database. Reports of the same message sent shortly after the first one are
+/// merged into a single report, each member being appended to [`reporters`].
+///
+/// The reporters are never shown to other members, but are recorded to
+/// investigate abusive reports.
+///
+/// [`reporters`]: Self::reporters
+#[serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Report {
+    /// Unique ID of the report.
+    #[serde(rename = "_id")]
+    pub id: Option<ObjectId>,
+    /// Guild where the message was sent.
+    #[serde_as(as = "IdAsI64")]
+    pub guild_id: Id<GuildMarker>,
+    /// Channel where the message was sent.
+    #[serde_as(as = "IdAsI64")]
+    pub channel_id: Id<ChannelMarker>,
+    /// Reported message.
+    #[serde_as(as = "IdAsI64")]
+    pub message_id: Id<MessageMarker>,
+    /// Author of the reported message.
+    pub author: ModlogUser,
+    /// Content of the message when it was first reported.
+    pub content: String,
+    /// Members that reported the message.
+    pub reporters: Vec<Reporter>,
+    /// Message sent in the reports channel, once it has been sent.
+    #[serde_as(as = "Option<IdAsI64>")]
+    #[serde(default)]
+    pub alert_id: Option<Id<MessageMarker>>,
+    /// Date of the first report.
+    #[serde_as(as = "DateTimeAsBson")]
+    pub created_at: OffsetDateTime,
+    /// Moderator that resolved the report, if any.
+    #[serde_as(as = "Option<IdAsI64>")]
+    #[serde(default)]
+    pub resolved_by: Option<Id<UserMarker>>,
+}
+
+/// Member that reported a message.
+///
+/// This type is used in [`Report`].
+#[serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Reporter {
+    /// ID of the member.
+    #[serde_as(as = "IdAsI64")]
+    pub user_id: Id<UserMarker>,
+    /// Reason provided by the member.
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Date of the report.
+    #[serde_as(as = "DateTimeAsBson")]
+    pub date: OffsetDateTime,
+}
+
+impl Report {
+    /// Name of the MongoDB collection.
+    pub const COLLECTION: &'static str = "reports";
+
+    /// Whether the report has been resolved by a moderator.
+    pub fn is_resolved(&self) -> bool {
+        self.resolved_by.is_some()
+    }
+
+    /// Add a member to the reporters.
+    ///
+    /// Returns `false` if the member has already reported the message.
+    pub fn add_reporter(&mut self, reporter: Reporter) -> bool {
+        if self.reporters.iter().any(|r| r.user_id == reporter.user_id) {
+            return false;
+        }
+
+        self.reporters.push(reporter);
+        true
+    }
+}
+
+// Implementation of methods to query the database.
+impl DbClient {
+    /// Insert a new [`Report`] in the database.
+    #[instrument(level = "debug", skip_all, fields(guild_id = report.guild_id.get(), latency_ms))]
+    pub async fn create_report(&self, report: &Report) -> Result<ObjectId, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let result = self
+            .db()
+            .collection::<Report>(Report::COLLECTION)
+            .insert_one(report, None)
+            .await?;
+
+        match result.inserted_id {
+            Bson::ObjectId(id) => Ok(id),
+            other => Err(anyhow!("expected object id, got {:?}", other)),
+        }
+    }
+
+    /// Get a [`Report`] from the database with its id.
+    #[instrument(level = "debug", skip(self), fields(latency_ms))]
+    pub async fn get_report(&self, id: ObjectId) -> Result<Option<Report>, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let query = doc! { "_id": id };
+
+        let report = self
+            .db()
+            .collection::<Report>(Report::COLLECTION)
+            .find_one(query, None)
+            .await?;
+
+        Ok(report)
+    }
+
+    /// Find the unresolved [`Report`] of a message created after a given
+    /// date, if it exists.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn find_open_report(
+        &self,
+        guild_id: Id<GuildMarker>,
+        message_id: Id<MessageMarker>,
+        after: OffsetDateTime,
+    ) -> Result<Option<Report>, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let query = OpenReportQuery {
+            guild_id,
+            message_id,
+            created_at: DateAfter { after },
+            resolved_by: None,
+        };
+
+        let report = self
+            .db()
+            .collection::<Report>(Report::COLLECTION)
+            .find_one(to_document(&query)?, None)
+            .await?;
+
+        Ok(report)
+    }
+
+    /// Replace an existing [`Report`] in the database.
+    #[instrument(level = "debug", skip_all, fields(guild_id = report.guild_id.get(), latency_ms))]
+    pub async fn update_report(&self, report: &Report) -> Result<(), anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let id = report.id.context("missing report id")?;
+
+        self.db()
+            .collection::<Report>(Report::COLLECTION)
+            .replace_one(doc! { "_id": id }, report, None)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Query unresolved reports of a message created after a given date.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+struct OpenReportQuery {
+    #[serde_as(as = "IdAsI64")]
+    pub guild_id: Id<GuildMarker>,
+    #[serde_as(as = "IdAsI64")]
+    pub message_id: Id<MessageMarker>,
+    pub created_at: DateAfter,
+    #[serde_as(as = "Option<IdAsI64>")]
+    pub resolved_by: Option<Id<UserMarker>>,
+}
+
+/// Match dates after a given date.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+struct DateAfter {
+    #[serde_as(as = "DateTimeAsBson")]
+    #[serde(rename = "$gte")]
+    pub after: OffsetDateTime,
+}
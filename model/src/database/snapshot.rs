@@ -0,0 +1,197 @@
+//! Models for the `structure_snapshots` collection.
+
+use mongodb::{
+    bson::{doc, oid::ObjectId, Document},
+    options,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, skip_serializing_none};
+use time::OffsetDateTime;
+use tracing::instrument;
+use twilight_model::{
+    channel::{permission_overwrite::PermissionOverwrite, ChannelType},
+    guild::Permissions,
+    id::{
+        marker::{ChannelMarker, GuildMarker, RoleMarker},
+        Id,
+    },
+};
+
+use super::DbClient;
+use crate::{
+    log::QueryLatency,
+    serde::{DateTimeAsBson, IdAsI64},
+};
+
+/// Snapshot of the channels and roles of a guild.
+///
+/// Snapshots are taken periodically and stored in the `structure_snapshots`
+/// collection. They are used to recreate the channels and roles deleted
+/// since the snapshot was taken, for example after a nuke. Only the last
+/// [`StructureSnapshot::MAX_SNAPSHOTS`] snapshots of each guild are kept.
+#[serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct StructureSnapshot {
+    /// Unique ID of the snapshot.
+    #[serde(rename = "_id")]
+    pub id: Option<ObjectId>,
+    /// Guild of the snapshot.
+    #[serde_as(as = "IdAsI64")]
+    pub guild_id: Id<GuildMarker>,
+    /// Date of the snapshot.
+    #[serde_as(as = "DateTimeAsBson")]
+    pub date: OffsetDateTime,
+    /// Channels of the guild, excluding threads.
+    pub channels: Vec<ChannelSnapshot>,
+    /// Roles of the guild, excluding managed roles and `@everyone`.
+    pub roles: Vec<RoleSnapshot>,
+}
+
+impl StructureSnapshot {
+    /// Name of the MongoDB collection.
+    pub const COLLECTION: &'static str = "structure_snapshots";
+
+    /// Maximum number of snapshots kept for each guild.
+    pub const MAX_SNAPSHOTS: u64 = 7;
+}
+
+/// Channel stored in a [`StructureSnapshot`].
+#[serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ChannelSnapshot {
+    /// Id of the channel.
+    #[serde_as(as = "IdAsI64")]
+    pub id: Id<ChannelMarker>,
+    /// Name of the channel.
+    pub name: String,
+    /// Type of the channel.
+    pub kind: ChannelType,
+    /// Sorting position of the channel.
+    pub position: Option<i64>,
+    /// Id of the parent category.
+    #[serde_as(as = "Option<IdAsI64>")]
+    #[serde(default)]
+    pub parent_id: Option<Id<ChannelMarker>>,
+    /// Topic of the channel.
+    #[serde(default)]
+    pub topic: Option<String>,
+    /// Whether the channel is marked as NSFW.
+    #[serde(default)]
+    pub nsfw: bool,
+    /// Amount of seconds a user has to wait between two message.
+    #[serde(default)]
+    pub rate_limit_per_user: Option<u16>,
+    /// Permission overwrites of the channel.
+    #[serde(default)]
+    pub permission_overwrites: Vec<PermissionOverwrite>,
+}
+
+/// Role stored in a [`StructureSnapshot`].
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct RoleSnapshot {
+    /// Id of the role.
+    #[serde_as(as = "IdAsI64")]
+    pub id: Id<RoleMarker>,
+    /// Name of the role.
+    pub name: String,
+    /// Color of the role.
+    pub color: u32,
+    /// Whether the role is displayed separately in the member list.
+    pub hoist: bool,
+    /// Whether the role can be mentioned by everyone.
+    pub mentionable: bool,
+    /// Permissions of the role.
+    pub permissions: Permissions,
+    /// Sorting position of the role.
+    pub position: i64,
+}
+
+// Implementation of methods to query the database.
+impl DbClient {
+    /// Insert a new [`StructureSnapshot`] in the database.
+    ///
+    /// The oldest snapshots of the guild are deleted to only keep the last
+    /// [`StructureSnapshot::MAX_SNAPSHOTS`] snapshots.
+    #[instrument(level = "debug", skip_all, fields(guild_id = snapshot.guild_id.get(), latency_ms))]
+    pub async fn create_structure_snapshot(
+        &self,
+        snapshot: &StructureSnapshot,
+    ) -> Result<(), anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let collection = self
+            .db()
+            .collection::<StructureSnapshot>(StructureSnapshot::COLLECTION);
+
+        collection.insert_one(snapshot, None).await?;
+
+        let options = options::FindOptions::builder()
+            .sort(doc! { "date": -1 })
+            .skip(StructureSnapshot::MAX_SNAPSHOTS)
+            .projection(doc! { "_id": 1 })
+            .build();
+
+        let mut outdated = self
+            .db()
+            .collection::<Document>(StructureSnapshot::COLLECTION)
+            .find(guild_query(snapshot.guild_id), options)
+            .await?;
+
+        let mut ids = Vec::new();
+        while outdated.advance().await? {
+            ids.push(outdated.current().get_object_id("_id")?);
+        }
+
+        if !ids.is_empty() {
+            collection
+                .delete_many(doc! { "_id": { "$in": ids } }, None)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Get the most recent [`StructureSnapshot`] of a guild, if any.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn latest_structure_snapshot(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<StructureSnapshot>, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let options = options::FindOneOptions::builder()
+            .sort(doc! { "date": -1 })
+            .build();
+
+        let snapshot = self
+            .db()
+            .collection::<StructureSnapshot>(StructureSnapshot::COLLECTION)
+            .find_one(guild_query(guild_id), options)
+            .await?;
+
+        Ok(snapshot)
+    }
+
+    /// Get a [`StructureSnapshot`] from the database with its id.
+    #[instrument(level = "debug", skip(self), fields(latency_ms))]
+    pub async fn get_structure_snapshot(
+        &self,
+        id: ObjectId,
+    ) -> Result<Option<StructureSnapshot>, anyhow::Error> {
+        let _latency = QueryLatency::start();
+
+        let snapshot = self
+            .db()
+            .collection::<StructureSnapshot>(StructureSnapshot::COLLECTION)
+            .find_one(doc! { "_id": id }, None)
+            .await?;
+
+        Ok(snapshot)
+    }
+}
+
+/// Query the snapshots of a guild.
+fn guild_query(guild_id: Id<GuildMarker>) -> Document {
+    doc! { "guild_id": guild_id.get() as i64 }
+}
@@ -0,0 +1,550 @@
+//! Models for the `stats` collection.
+//!
+//! Statistics of a guild are stored in a single [`GuildStats`] document,
+//! updated incrementally with `$inc` each time a [`StatsEvent`] occurs. This
+//! avoids aggregating the `modlogs` collection each time the statistics are
+//! displayed.
+//!
+//! Daily buckets are stored for the last [`GuildStats::MAX_DAYS`] days with
+//! activity, to display trends. Days without activity have no bucket.
+
+use std::collections::BTreeMap;
+
+use mongodb::{
+    bson::{doc, to_document},
+    options,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use time::OffsetDateTime;
+use tracing::instrument;
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use super::{
+    modlog::{ModlogCounts, ModlogType},
+    DbClient,
+};
+use crate::{log::QueryLatency, serde::IdAsI64};
+
+/// Maximum number of attempts for updates that may conflict with concurrent
+/// updates.
+const MAX_ATTEMPTS: usize = 3;
+
+/// Statistics of a guild.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct GuildStats {
+    /// Guild of the statistics.
+    #[serde(rename = "_id")]
+    #[serde_as(as = "IdAsI64")]
+    pub guild_id: Id<GuildMarker>,
+    /// Version of the document, incremented on each update.
+    #[serde(default)]
+    pub version: u64,
+    /// Counters since the statistics are recorded.
+    #[serde(default)]
+    pub totals: StatsCounters,
+    /// Daily counters, sorted by day.
+    #[serde(default)]
+    pub days: Vec<DailyStats>,
+}
+
+impl GuildStats {
+    /// Name of the MongoDB collection.
+    pub const COLLECTION: &'static str = "stats";
+
+    /// Maximum number of daily buckets.
+    pub const MAX_DAYS: i64 = 90;
+
+    /// Initialize empty statistics.
+    pub fn new(guild_id: Id<GuildMarker>) -> Self {
+        Self {
+            guild_id,
+            version: 0,
+            totals: StatsCounters::default(),
+            days: Vec::new(),
+        }
+    }
+
+    /// Sum the daily counters of the last `days` days, including `today`.
+    pub fn last_days(&self, today: i64, days: i64) -> StatsCounters {
+        let mut counters = StatsCounters::default();
+
+        for bucket in self.days.iter().filter(|bucket| bucket.day > today - days) {
+            counters.merge(&bucket.counters);
+        }
+
+        counters
+    }
+
+    /// Replace the sanction counters with counters computed from the modlogs.
+    ///
+    /// Buckets older than [`GuildStats::MAX_DAYS`] are removed. Other counters
+    /// are kept since they cannot be computed from the modlogs.
+    pub fn rebuild_sanctions(
+        &mut self,
+        totals: ModlogCounts,
+        days: &BTreeMap<i64, ModlogCounts>,
+        today: i64,
+    ) {
+        self.totals.sanctions = totals;
+
+        let mut buckets = self
+            .days
+            .drain(..)
+            .map(|bucket| (bucket.day, bucket.counters))
+            .collect::<BTreeMap<_, _>>();
+
+        for counters in buckets.values_mut() {
+            counters.sanctions = ModlogCounts::default();
+        }
+
+        for (day, sanctions) in days {
+            buckets.entry(*day).or_default().sanctions = *sanctions;
+        }
+
+        self.days = buckets
+            .into_iter()
+            .filter(|(day, _)| *day > today - Self::MAX_DAYS)
+            .map(|(day, counters)| DailyStats { day, counters })
+            .collect();
+    }
+}
+
+/// Counters of a guild statistics.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct StatsCounters {
+    /// Number of sanctions for each type.
+    pub sanctions: ModlogCounts,
+    /// Number of detections of each filter.
+    pub filters: FilterCounts,
+    /// Number of detections of each filter marked as false positives.
+    pub false_positives: FilterCounts,
+    /// Number of raids detected.
+    pub raids: u64,
+    /// Number of messages deleted by the bot.
+    pub messages_deleted: u64,
+}
+
+impl StatsCounters {
+    /// Add an event to the counters.
+    pub fn add(&mut self, event: StatsEvent) {
+        match event {
+            StatsEvent::Sanction(kind) => self.sanctions.add(kind, 1),
+            StatsEvent::Filter(StatsFilter::ReactionSpam) => self.filters.reaction_spam += 1,
+            StatsEvent::Filter(StatsFilter::VoiceHop) => self.filters.voice_hop += 1,
+            StatsEvent::Filter(StatsFilter::Name) => self.filters.name += 1,
+            StatsEvent::Filter(StatsFilter::Link) => self.filters.link += 1,
+            StatsEvent::Filter(StatsFilter::Pattern) => self.filters.pattern += 1,
+            StatsEvent::Filter(StatsFilter::FirstMessage) => self.filters.first_message += 1,
+            StatsEvent::FalsePositive(filter) => self.false_positives.add(filter),
+            StatsEvent::Raid => self.raids += 1,
+            StatsEvent::MessagesDeleted(count) => self.messages_deleted += count,
+        }
+    }
+
+    /// Add the values of other counters.
+    pub fn merge(&mut self, other: &StatsCounters) {
+        self.sanctions.ban += other.sanctions.ban;
+        self.sanctions.kick += other.sanctions.kick;
+        self.sanctions.mute += other.sanctions.mute;
+        self.sanctions.unmute += other.sanctions.unmute;
+        self.sanctions.quarantine += other.sanctions.quarantine;
+        self.sanctions.release += other.sanctions.release;
+        self.filters.reaction_spam += other.filters.reaction_spam;
+        self.filters.voice_hop += other.filters.voice_hop;
+        self.filters.name += other.filters.name;
+        self.filters.link += other.filters.link;
+        self.filters.pattern += other.filters.pattern;
+        self.filters.first_message += other.filters.first_message;
+        self.false_positives.merge(&other.false_positives);
+        self.raids += other.raids;
+        self.messages_deleted += other.messages_deleted;
+    }
+
+    /// Total number of events.
+    ///
+    /// False positives are not counted since they are feedback on detections
+    /// that are already counted.
+    pub fn total(&self) -> u64 {
+        let sanctions = self.sanctions;
+        let filters = self.filters;
+
+        sanctions.ban
+            + sanctions.kick
+            + sanctions.mute
+            + sanctions.unmute
+            + sanctions.quarantine
+            + sanctions.release
+            + filters.reaction_spam
+            + filters.voice_hop
+            + filters.name
+            + filters.link
+            + filters.pattern
+            + filters.first_message
+            + self.raids
+            + self.messages_deleted
+    }
+}
+
+/// Number of detections of each [`StatsFilter`].
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct FilterCounts {
+    pub reaction_spam: u64,
+    pub voice_hop: u64,
+    pub name: u64,
+    pub link: u64,
+    pub pattern: u64,
+    pub first_message: u64,
+}
+
+impl FilterCounts {
+    /// Increment the count of a filter.
+    pub fn add(&mut self, filter: StatsFilter) {
+        match filter {
+            StatsFilter::ReactionSpam => self.reaction_spam += 1,
+            StatsFilter::VoiceHop => self.voice_hop += 1,
+            StatsFilter::Name => self.name += 1,
+            StatsFilter::Link => self.link += 1,
+            StatsFilter::Pattern => self.pattern += 1,
+            StatsFilter::FirstMessage => self.first_message += 1,
+        }
+    }
+
+    /// Add the counts of other counters.
+    pub fn merge(&mut self, other: &FilterCounts) {
+        self.reaction_spam += other.reaction_spam;
+        self.voice_hop += other.voice_hop;
+        self.name += other.name;
+        self.link += other.link;
+        self.pattern += other.pattern;
+        self.first_message += other.first_message;
+    }
+
+    /// Total count of all filters.
+    pub fn total(&self) -> u64 {
+        self.reaction_spam
+            + self.voice_hop
+            + self.name
+            + self.link
+            + self.pattern
+            + self.first_message
+    }
+}
+
+/// Counters of a single day.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct DailyStats {
+    /// Number of days since the Unix epoch (see [`epoch_day`]).
+    pub day: i64,
+    /// Counters of the day.
+    #[serde(default)]
+    pub counters: StatsCounters,
+}
+
+/// Event counted in the [`GuildStats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsEvent {
+    /// A sanction has been applied.
+    Sanction(ModlogType),
+    /// A filter detected a member.
+    Filter(StatsFilter),
+    /// A detection of a filter has been marked as a false positive.
+    FalsePositive(StatsFilter),
+    /// A raid has been detected.
+    Raid,
+    /// Messages have been deleted.
+    MessagesDeleted(u64),
+}
+
+impl StatsEvent {
+    /// Path of the incremented field in [`StatsCounters`].
+    fn field(self) -> &'static str {
+        match self {
+            StatsEvent::Sanction(ModlogType::Ban) => "sanctions.ban",
+            StatsEvent::Sanction(ModlogType::Kick) => "sanctions.kick",
+            StatsEvent::Sanction(ModlogType::Mute) => "sanctions.mute",
+            StatsEvent::Sanction(ModlogType::Unmute) => "sanctions.unmute",
+            StatsEvent::Sanction(ModlogType::Quarantine) => "sanctions.quarantine",
+            StatsEvent::Sanction(ModlogType::Release) => "sanctions.release",
+            StatsEvent::Filter(StatsFilter::ReactionSpam) => "filters.reaction_spam",
+            StatsEvent::Filter(StatsFilter::VoiceHop) => "filters.voice_hop",
+            StatsEvent::Filter(StatsFilter::Name) => "filters.name",
+            StatsEvent::Filter(StatsFilter::Link) => "filters.link",
+            StatsEvent::Filter(StatsFilter::Pattern) => "filters.pattern",
+            StatsEvent::Filter(StatsFilter::FirstMessage) => "filters.first_message",
+            StatsEvent::FalsePositive(StatsFilter::ReactionSpam) => "false_positives.reaction_spam",
+            StatsEvent::FalsePositive(StatsFilter::VoiceHop) => "false_positives.voice_hop",
+            StatsEvent::FalsePositive(StatsFilter::Name) => "false_positives.name",
+            StatsEvent::FalsePositive(StatsFilter::Link) => "false_positives.link",
+            StatsEvent::FalsePositive(StatsFilter::Pattern) => "false_positives.pattern",
+            StatsEvent::FalsePositive(StatsFilter::FirstMessage) => "false_positives.first_message",
+            StatsEvent::Raid => "raids",
+            StatsEvent::MessagesDeleted(_) => "messages_deleted",
+        }
+    }
+
+    /// Value added to the field.
+    fn count(self) -> i64 {
+        match self {
+            StatsEvent::MessagesDeleted(count) => count as i64,
+            _ => 1,
+        }
+    }
+}
+
+/// Filter counted in the [`GuildStats`].
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsFilter {
+    ReactionSpam,
+    VoiceHop,
+    Name,
+    Link,
+    Pattern,
+    FirstMessage,
+}
+
+impl StatsFilter {
+    /// All the filters.
+    pub const ALL: [StatsFilter; 6] = [
+        StatsFilter::ReactionSpam,
+        StatsFilter::VoiceHop,
+        StatsFilter::Name,
+        StatsFilter::Link,
+        StatsFilter::Pattern,
+        StatsFilter::FirstMessage,
+    ];
+
+    /// Identifier of the filter, used in component custom ids.
+    pub fn id(self) -> &'static str {
+        match self {
+            StatsFilter::ReactionSpam => "reaction_spam",
+            StatsFilter::VoiceHop => "voice_hop",
+            StatsFilter::Name => "name",
+            StatsFilter::Link => "link",
+            StatsFilter::Pattern => "pattern",
+            StatsFilter::FirstMessage => "first_message",
+        }
+    }
+
+    /// Get a filter from its identifier.
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|filter| filter.id() == id)
+    }
+}
+
+/// Get the number of days since the Unix epoch.
+pub fn epoch_day(date: OffsetDateTime) -> i64 {
+    date.unix_timestamp().div_euclid(24 * 60 * 60)
+}
+
+// Implementation of methods to query the database.
+impl DbClient {
+    /// Get the [`GuildStats`] of a guild, if any event has been recorded.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn get_stats(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<GuildStats>, anyhow::Error> {
+        let _latency = QueryLatency::start();
+
+        let stats = self
+            .db()
+            .collection::<GuildStats>(GuildStats::COLLECTION)
+            .find_one(doc! { "_id": guild_id.get() as i64 }, None)
+            .await?;
+
+        Ok(stats)
+    }
+
+    /// Record an event in the [`GuildStats`] of a guild.
+    ///
+    /// Counters are only updated with single `$inc` operations, so concurrent
+    /// updates are safe. The bucket of the day is pushed if it does not exist
+    /// yet, and the oldest buckets are removed.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn increment_stats(
+        &self,
+        guild_id: Id<GuildMarker>,
+        event: StatsEvent,
+        date: OffsetDateTime,
+    ) -> Result<(), anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let collection = self.db().collection::<GuildStats>(GuildStats::COLLECTION);
+
+        let (field, count) = (event.field(), event.count());
+        let (id, day) = (guild_id.get() as i64, epoch_day(date));
+
+        let mut counters = StatsCounters::default();
+        counters.add(event);
+        let bucket = to_document(&DailyStats { day, counters })?;
+
+        for _ in 0..MAX_ATTEMPTS {
+            // Increment the bucket of the day, if it exists.
+            let result = collection
+                .update_one(
+                    doc! { "_id": id, "days.day": day },
+                    doc! { "$inc": {
+                        "version": 1_i64,
+                        format!("totals.{field}"): count,
+                        format!("days.$.counters.{field}"): count,
+                    } },
+                    None,
+                )
+                .await?;
+
+            if result.matched_count > 0 {
+                return Ok(());
+            }
+
+            // Otherwise, push the bucket of the day. The query does not match
+            // if the bucket has been pushed concurrently, in which case the
+            // upsert fails with a duplicate key error.
+            let options = options::UpdateOptions::builder().upsert(true).build();
+            let result = collection
+                .update_one(
+                    doc! { "_id": id, "days.day": { "$ne": day } },
+                    doc! {
+                        "$inc": { "version": 1_i64, format!("totals.{field}"): count },
+                        "$push": { "days": {
+                            "$each": [bucket.clone()],
+                            "$sort": { "day": 1 },
+                            "$slice": -GuildStats::MAX_DAYS,
+                        } },
+                    },
+                    options,
+                )
+                .await;
+
+            match result {
+                Ok(_) => return Ok(()),
+                Err(error) if Self::is_duplicate_key(&error) => continue,
+                Err(error) => return Err(error.into()),
+            }
+        }
+
+        Err(anyhow::anyhow!("too many conflicts while updating stats"))
+    }
+
+    /// Recompute the sanction counters of a guild from its modlogs.
+    ///
+    /// The other counters are kept. The document is only replaced if it has
+    /// not been updated concurrently. Returns the previous and recomputed
+    /// sanction counters.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn recompute_stats(
+        &self,
+        guild_id: Id<GuildMarker>,
+        date: OffsetDateTime,
+    ) -> Result<(ModlogCounts, ModlogCounts), anyhow::Error> {
+        let today = epoch_day(date);
+        let mut totals = ModlogCounts::default();
+        let mut days = BTreeMap::<i64, ModlogCounts>::new();
+
+        for (kind, day, count) in self.count_modlogs_by_day(guild_id).await? {
+            totals.add(kind, count);
+            days.entry(day).or_default().add(kind, count);
+        }
+
+        let _latency = QueryLatency::start();
+        let collection = self.db().collection::<GuildStats>(GuildStats::COLLECTION);
+        let options = options::ReplaceOptions::builder().upsert(true).build();
+
+        for _ in 0..MAX_ATTEMPTS {
+            let current = collection
+                .find_one(doc! { "_id": guild_id.get() as i64 }, None)
+                .await?
+                .unwrap_or_else(|| GuildStats::new(guild_id));
+
+            let mut stats = current.clone();
+            stats.rebuild_sanctions(totals, &days, today);
+            stats.version += 1;
+
+            let query = doc! {
+                "_id": guild_id.get() as i64,
+                "version": current.version as i64,
+            };
+
+            match collection.replace_one(query, &stats, options.clone()).await {
+                Ok(_) => return Ok((current.totals.sanctions, totals)),
+                Err(error) if Self::is_duplicate_key(&error) => continue,
+                Err(error) => return Err(error.into()),
+            }
+        }
+
+        Err(anyhow::anyhow!(
+            "too many conflicts while recomputing stats"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epoch_day() {
+        let date = OffsetDateTime::from_unix_timestamp(86_400 * 3 + 10).unwrap();
+
+        assert_eq!(epoch_day(date), 3);
+        assert_eq!(
+            epoch_day(OffsetDateTime::from_unix_timestamp(-1).unwrap()),
+            -1
+        );
+    }
+
+    #[test]
+    fn test_counters_add() {
+        let mut counters = StatsCounters::default();
+        counters.add(StatsEvent::Sanction(ModlogType::Ban));
+        counters.add(StatsEvent::Filter(StatsFilter::Name));
+        counters.add(StatsEvent::MessagesDeleted(5));
+
+        assert_eq!(counters.sanctions.ban, 1);
+        assert_eq!(counters.filters.name, 1);
+        assert_eq!(counters.messages_deleted, 5);
+        assert_eq!(counters.total(), 7);
+    }
+
+    #[test]
+    fn test_last_days() {
+        let mut stats = GuildStats::new(Id::new(1));
+        for day in [1, 8, 9, 10] {
+            let mut counters = StatsCounters::default();
+            counters.add(StatsEvent::Raid);
+            stats.days.push(DailyStats { day, counters });
+        }
+
+        assert_eq!(stats.last_days(10, 7).raids, 3);
+        assert_eq!(stats.last_days(10, 1).raids, 1);
+    }
+
+    #[test]
+    fn test_rebuild_sanctions() {
+        let mut stats = GuildStats::new(Id::new(1));
+        let mut counters = StatsCounters::default();
+        counters.add(StatsEvent::Raid);
+        counters.add(StatsEvent::Sanction(ModlogType::Kick));
+        stats.days.push(DailyStats { day: 5, counters });
+        stats.days.push(DailyStats { day: 99, counters });
+
+        let mut day_counts = ModlogCounts::default();
+        day_counts.add(ModlogType::Ban, 2);
+        let days = BTreeMap::from([(3, day_counts), (99, day_counts)]);
+
+        stats.rebuild_sanctions(day_counts, &days, 100);
+
+        assert_eq!(stats.totals.sanctions.ban, 2);
+        // Day 5 is older than 90 days.
+        assert_eq!(
+            stats.days.iter().map(|day| day.day).collect::<Vec<_>>(),
+            [99]
+        );
+        assert_eq!(stats.days[0].counters.raids, 1);
+        assert_eq!(stats.days[0].counters.sanctions.kick, 0);
+        assert_eq!(stats.days[0].counters.sanctions.ban, 2);
+    }
+}
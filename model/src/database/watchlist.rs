@@ -0,0 +1,174 @@
+//! Models for the `watchlist` collection.
+
+use mongodb::{
+    bson::{doc, to_document},
+    options,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, skip_serializing_none};
+use time::OffsetDateTime;
+use tracing::instrument;
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+
+use super::DbClient;
+use crate::{
+    log::QueryLatency,
+    serde::{DateTimeAsBson, IdAsI64},
+};
+
+/// User watched by moderators.
+///
+/// This type represent a watched user stored in the `watchlist` collection of
+/// the database. While a user is watched, their activity is notified to the
+/// moderators that added the watch. Each moderator adding the same user is
+/// appended to [`moderators`], and the watch is extended.
+///
+/// [`moderators`]: Self::moderators
+#[serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct WatchlistEntry {
+    /// Guild where the user is watched.
+    #[serde_as(as = "IdAsI64")]
+    pub guild_id: Id<GuildMarker>,
+    /// Watched user.
+    #[serde_as(as = "IdAsI64")]
+    pub user_id: Id<UserMarker>,
+    /// Moderators that added the watch.
+    #[serde_as(as = "Vec<IdAsI64>")]
+    pub moderators: Vec<Id<UserMarker>>,
+    /// Optional note provided by the moderators.
+    #[serde(default)]
+    pub note: Option<String>,
+    /// Date at which the user has been added to the watchlist.
+    #[serde_as(as = "DateTimeAsBson")]
+    pub created_at: OffsetDateTime,
+    /// Date at which the watch expires.
+    #[serde_as(as = "DateTimeAsBson")]
+    pub expires_at: OffsetDateTime,
+}
+
+impl WatchlistEntry {
+    /// Name of the MongoDB collection.
+    pub const COLLECTION: &'static str = "watchlist";
+
+    /// Whether the watch has expired.
+    pub fn is_expired(&self, now: OffsetDateTime) -> bool {
+        self.expires_at <= now
+    }
+}
+
+// Implementation of methods to query the database.
+impl DbClient {
+    /// Insert or replace a [`WatchlistEntry`] in the database.
+    #[instrument(level = "debug", skip_all, fields(guild_id = entry.guild_id.get(), latency_ms))]
+    pub async fn create_watch(&self, entry: &WatchlistEntry) -> Result<(), anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let query = WatchQuery {
+            guild_id: entry.guild_id,
+            user_id: entry.user_id,
+        };
+        let options = options::ReplaceOptions::builder().upsert(true).build();
+
+        self.db()
+            .collection::<WatchlistEntry>(WatchlistEntry::COLLECTION)
+            .replace_one(to_document(&query)?, entry, options)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Get the [`WatchlistEntry`] of a user, if it exists.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn get_watch(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<WatchlistEntry>, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let query = WatchQuery { guild_id, user_id };
+
+        let entry = self
+            .db()
+            .collection::<WatchlistEntry>(WatchlistEntry::COLLECTION)
+            .find_one(to_document(&query)?, None)
+            .await?;
+
+        Ok(entry)
+    }
+
+    /// Delete the [`WatchlistEntry`] of a user.
+    ///
+    /// Returns whether an entry has been deleted.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn delete_watch(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<bool, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let query = WatchQuery { guild_id, user_id };
+
+        let result = self
+            .db()
+            .collection::<WatchlistEntry>(WatchlistEntry::COLLECTION)
+            .delete_one(to_document(&query)?, None)
+            .await?;
+
+        Ok(result.deleted_count > 0)
+    }
+
+    /// Get the [`WatchlistEntry`] of a guild, oldest first.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn find_watches(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<WatchlistEntry>, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let query = doc! { "guild_id": guild_id.get() as i64 };
+        let options = options::FindOptions::builder()
+            .sort(doc! { "created_at": 1 })
+            .build();
+
+        let mut cursor = self
+            .db()
+            .collection::<WatchlistEntry>(WatchlistEntry::COLLECTION)
+            .find(query, options)
+            .await?;
+
+        let mut entries = Vec::new();
+        while cursor.advance().await? {
+            entries.push(cursor.deserialize_current()?);
+        }
+
+        Ok(entries)
+    }
+
+    /// Count the watched users of a guild.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get(), latency_ms))]
+    pub async fn count_watches(&self, guild_id: Id<GuildMarker>) -> Result<u64, anyhow::Error> {
+        let _latency = QueryLatency::start();
+        let query = doc! { "guild_id": guild_id.get() as i64 };
+
+        let count = self
+            .db()
+            .collection::<WatchlistEntry>(WatchlistEntry::COLLECTION)
+            .count_documents(query, None)
+            .await?;
+
+        Ok(count)
+    }
+}
+
+/// Query watchlist entries with guild_id and user_id.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+struct WatchQuery {
+    #[serde_as(as = "IdAsI64")]
+    pub guild_id: Id<GuildMarker>,
+    #[serde_as(as = "IdAsI64")]
+    pub user_id: Id<UserMarker>,
+}
@@ -17,3 +17,5 @@ mod serde;
 pub mod cache;
 pub mod config;
 pub mod database;
+pub mod log;
+pub mod store;
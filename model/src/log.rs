@@ -0,0 +1,195 @@
+//! Logging utilities.
+//!
+//! Spans that concern a single guild carry a `guild_id` field, which allows
+//! to filter the logs of a guild. The logs of some guilds can be raised to
+//! the `DEBUG` level while the global level stays unchanged (see
+//! [`set_debug_guilds`]).
+//!
+//! Database and cache queries record their latency in the `latency_ms` field
+//! of their span (see [`QueryLatency`]).
+
+use std::{sync::RwLock, time::Instant};
+
+use tracing::{
+    debug,
+    field::{Field, Visit},
+    level_filters::LevelFilter,
+    span,
+    subscriber::Interest,
+    Level, Metadata, Span, Subscriber,
+};
+use tracing_subscriber::{
+    layer::{Context, Filter},
+    registry::LookupSpan,
+    Layer,
+};
+
+/// Ids of the guilds whose logs are raised to the `DEBUG` level.
+static DEBUG_GUILDS: RwLock<Vec<u64>> = RwLock::new(Vec::new());
+
+/// Set the guilds whose logs are raised to the `DEBUG` level.
+pub fn set_debug_guilds(guilds: Vec<u64>) {
+    *DEBUG_GUILDS.write().unwrap() = guilds;
+}
+
+/// Check whether the logs of a guild are raised to the `DEBUG` level.
+pub fn is_debug_guild(guild: u64) -> bool {
+    DEBUG_GUILDS.read().unwrap().contains(&guild)
+}
+
+/// Guild id of a span, stored in the span extensions.
+#[derive(Debug, Clone, Copy)]
+struct GuildSpan(u64);
+
+/// [`Layer`] storing the `guild_id` field of spans in their extensions.
+///
+/// This layer must be registered for [`GuildFilter`] to work.
+#[derive(Debug, Default)]
+pub struct GuildLayer;
+
+impl<S> Layer<S> for GuildLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let mut visitor = GuildVisitor(None);
+        attrs.record(&mut visitor);
+
+        if let (Some(guild), Some(span)) = (visitor.0, ctx.span(id)) {
+            span.extensions_mut().replace(GuildSpan(guild));
+        }
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        let mut visitor = GuildVisitor(None);
+        values.record(&mut visitor);
+
+        if let (Some(guild), Some(span)) = (visitor.0, ctx.span(id)) {
+            span.extensions_mut().replace(GuildSpan(guild));
+        }
+    }
+}
+
+/// Visitor extracting the `guild_id` field.
+struct GuildVisitor(Option<u64>);
+
+impl Visit for GuildVisitor {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == "guild_id" {
+            self.0 = Some(value);
+        }
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        if field.name() == "guild_id" {
+            self.0 = u64::try_from(value).ok();
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
+
+/// [`Filter`] enabling logs up to a maximum level, or up to the `DEBUG` level
+/// inside the spans of a debug guild.
+#[derive(Debug, Clone, Copy)]
+pub struct GuildFilter {
+    level: Level,
+}
+
+impl GuildFilter {
+    /// Create a new [`GuildFilter`] with the global maximum level.
+    pub fn new(level: Level) -> Self {
+        Self { level }
+    }
+
+    /// Check whether the current span is inside the span of a debug guild.
+    fn in_debug_guild<S>(cx: &Context<'_, S>) -> bool
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        let guilds = DEBUG_GUILDS.read().unwrap();
+        if guilds.is_empty() {
+            return false;
+        }
+
+        let span = match cx.lookup_current() {
+            Some(span) => span,
+            None => return false,
+        };
+
+        span.scope()
+            .any(|span| match span.extensions().get::<GuildSpan>() {
+                Some(GuildSpan(guild)) => guilds.contains(guild),
+                None => false,
+            })
+    }
+}
+
+impl<S> Filter<S> for GuildFilter
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn enabled(&self, meta: &Metadata<'_>, cx: &Context<'_, S>) -> bool {
+        if *meta.level() <= self.level {
+            return true;
+        }
+
+        *meta.level() <= Level::DEBUG && Self::in_debug_guild(cx)
+    }
+
+    fn callsite_enabled(&self, meta: &'static Metadata<'static>) -> Interest {
+        if *meta.level() <= self.level {
+            Interest::always()
+        } else if *meta.level() <= Level::DEBUG {
+            Interest::sometimes()
+        } else {
+            Interest::never()
+        }
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        Some(LevelFilter::from_level(self.level).max(LevelFilter::DEBUG))
+    }
+}
+
+/// Guard recording the latency of a query.
+///
+/// When dropped, the elapsed time is recorded in the `latency_ms` field of the
+/// current span, which must be declared with `fields(latency_ms)`.
+#[derive(Debug)]
+pub struct QueryLatency {
+    start: Instant,
+}
+
+impl QueryLatency {
+    /// Start measuring the latency of a query.
+    pub fn start() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for QueryLatency {
+    fn drop(&mut self) {
+        let latency = self.start.elapsed().as_millis() as u64;
+
+        Span::current().record("latency_ms", latency);
+        debug!(latency_ms = latency, "query completed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_guilds() {
+        set_debug_guilds(vec![1, 2]);
+        assert!(is_debug_guild(1));
+        assert!(!is_debug_guild(3));
+
+        set_debug_guilds(Vec::new());
+        assert!(!is_debug_guild(1));
+    }
+}
@@ -138,7 +138,7 @@ impl<'de> DeserializeAs<'de, OffsetDateTime> for DateTimeAsBson {
     {
         let datetime = bson::DateTime::deserialize(deserializer)?;
 
-        OffsetDateTime::from_unix_timestamp(datetime.timestamp_millis()).map_err(de::Error::custom)
+        datetime_from_bson(datetime).map_err(de::Error::custom)
     }
 }
 
@@ -147,12 +147,32 @@ impl SerializeAs<OffsetDateTime> for DateTimeAsBson {
     where
         S: Serializer,
     {
-        let datetime = bson::DateTime::from_millis(source.unix_timestamp());
-
-        datetime.serialize(serializer)
+        datetime_to_bson(*source).serialize(serializer)
     }
 }
 
+/// Convert a [`bson::DateTime`] stored with [`DateTimeAsBson`] into an
+/// [`OffsetDateTime`].
+///
+/// The stored value is the UNIX timestamp in seconds (not milliseconds, as
+/// the name of the BSON type suggests). This is kept as is for compatibility
+/// with the existing documents.
+///
+/// An error is returned if the date is outside of the range supported by
+/// [`OffsetDateTime`].
+pub fn datetime_from_bson(
+    datetime: bson::DateTime,
+) -> Result<OffsetDateTime, time::error::ComponentRange> {
+    OffsetDateTime::from_unix_timestamp(datetime.timestamp_millis())
+}
+
+/// Convert an [`OffsetDateTime`] into a [`bson::DateTime`].
+///
+/// See [`datetime_from_bson`] for the stored format.
+pub fn datetime_to_bson(datetime: OffsetDateTime) -> bson::DateTime {
+    bson::DateTime::from_millis(datetime.unix_timestamp())
+}
+
 /// Serialize [`OffsetDateTime`] as a UNIX timestamp ([`i64`]).
 ///
 /// This type implement [`SerializeAs`] and [`DeserializeAs`] and should be
@@ -183,6 +203,7 @@ impl SerializeAs<OffsetDateTime> for DateTimeAsI64 {
 
 #[cfg(test)]
 mod tests {
+    use mongodb::bson;
     use serde::{Deserialize, Serialize};
     use serde_test::{assert_de_tokens_error, assert_ser_tokens_error, assert_tokens, Token};
     use serde_with::serde_as;
@@ -192,7 +213,10 @@ mod tests {
         util::Timestamp,
     };
 
-    use super::{DateTimeAsBson, DateTimeAsI64, IdAsI64, IdAsU64, TimestampAsI64};
+    use super::{
+        datetime_from_bson, datetime_to_bson, DateTimeAsBson, DateTimeAsI64, IdAsI64, IdAsU64,
+        TimestampAsI64,
+    };
 
     #[serde_as]
     #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -354,4 +378,27 @@ mod tests {
             ],
         )
     }
+
+    #[test]
+    fn test_datetime_bson_epoch() {
+        let datetime = datetime_from_bson(bson::DateTime::from_millis(0)).unwrap();
+
+        assert_eq!(datetime, OffsetDateTime::UNIX_EPOCH);
+        assert_eq!(datetime_to_bson(datetime), bson::DateTime::from_millis(0));
+    }
+
+    #[test]
+    fn test_datetime_bson_far_future() {
+        // 9999-12-31 23:59:59 UTC, the maximum date supported by `time`.
+        let datetime = OffsetDateTime::from_unix_timestamp(253_402_300_799).unwrap();
+        let bson = datetime_to_bson(datetime);
+
+        assert_eq!(datetime_from_bson(bson).unwrap(), datetime);
+    }
+
+    #[test]
+    fn test_datetime_bson_out_of_range() {
+        assert!(datetime_from_bson(bson::DateTime::MAX).is_err());
+        assert!(datetime_from_bson(bson::DateTime::MIN).is_err());
+    }
 }
@@ -0,0 +1,318 @@
+//! In-memory stores.
+//!
+//! These stores keep the data in memory and are intended to be used in
+//! tests. Cached values are serialized with [`RedisModel::serialize_model`]
+//! like in Redis, so serialization errors are not hidden by the in-memory
+//! store.
+
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use super::{CacheStore, GuildStore, ModlogStore};
+use crate::{
+    cache::RedisModel,
+    database::model::{GuildConfig, Modlog, ModlogSearch, ObjectId},
+};
+
+/// In-memory [`GuildStore`].
+///
+/// It can be cheaply cloned, clones share the same data.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryGuildStore {
+    guilds: Arc<Mutex<HashMap<Id<GuildMarker>, GuildConfig>>>,
+}
+
+impl MemoryGuildStore {
+    /// Initialize a new empty [`MemoryGuildStore`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the stored [`GuildConfig`] of a guild, without creating it.
+    pub fn guild(&self, guild_id: Id<GuildMarker>) -> Option<GuildConfig> {
+        self.guilds().get(&guild_id).cloned()
+    }
+
+    fn guilds(&self) -> MutexGuard<'_, HashMap<Id<GuildMarker>, GuildConfig>> {
+        self.guilds
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+#[async_trait]
+impl GuildStore for MemoryGuildStore {
+    async fn get_guild_or_create(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<GuildConfig, anyhow::Error> {
+        let config = self
+            .guilds()
+            .entry(guild_id)
+            .or_insert_with(|| GuildConfig::new(guild_id))
+            .clone();
+
+        Ok(config)
+    }
+
+    async fn update_guild(&self, guild: &GuildConfig) -> Result<(), anyhow::Error> {
+        self.guilds().insert(guild.id, guild.clone());
+
+        Ok(())
+    }
+}
+
+/// In-memory [`ModlogStore`].
+///
+/// Searches are matched with [`ModlogSearch::matches`]. It can be cheaply
+/// cloned, clones share the same data.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryModlogStore {
+    modlogs: Arc<Mutex<Vec<Modlog>>>,
+}
+
+impl MemoryModlogStore {
+    /// Initialize a new empty [`MemoryModlogStore`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get all the stored modlogs, in insertion order.
+    pub fn modlogs(&self) -> Vec<Modlog> {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Modlog>> {
+        self.modlogs
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+    }
+
+    /// Get the modlogs matching a filter, most recent first.
+    fn sorted(&self, filter: impl Fn(&Modlog) -> bool) -> Vec<Modlog> {
+        let mut modlogs = self
+            .lock()
+            .iter()
+            .filter(|modlog| filter(modlog))
+            .cloned()
+            .collect::<Vec<_>>();
+        modlogs.sort_by_key(|modlog| Reverse(modlog.date));
+
+        modlogs
+    }
+}
+
+#[async_trait]
+impl ModlogStore for MemoryModlogStore {
+    async fn create_modlog(&self, modlog: &Modlog) -> Result<ObjectId, anyhow::Error> {
+        let id = ObjectId::new();
+        self.lock().push(Modlog {
+            id: Some(id),
+            ..modlog.clone()
+        });
+
+        Ok(id)
+    }
+
+    async fn get_modlog(&self, id: ObjectId) -> Result<Option<Modlog>, anyhow::Error> {
+        let modlog = self
+            .lock()
+            .iter()
+            .find(|modlog| modlog.id == Some(id))
+            .cloned();
+
+        Ok(modlog)
+    }
+
+    async fn find_modlogs_page(
+        &self,
+        guild_id: Id<GuildMarker>,
+        page: u64,
+        per_page: u64,
+    ) -> Result<(Vec<Modlog>, u64), anyhow::Error> {
+        let modlogs = self.sorted(|modlog| modlog.guild_id == guild_id);
+        let total = modlogs.len() as u64;
+
+        let page = modlogs
+            .into_iter()
+            .skip(page.saturating_mul(per_page) as usize)
+            .take(per_page as usize)
+            .collect();
+
+        Ok((page, total))
+    }
+
+    async fn search_modlogs(
+        &self,
+        guild_id: Id<GuildMarker>,
+        search: &ModlogSearch,
+        limit: i64,
+    ) -> Result<Vec<Modlog>, anyhow::Error> {
+        let mut modlogs = self.sorted(|modlog| search.matches(guild_id, modlog));
+
+        // Like MongoDB, a limit of 0 is equivalent to no limit.
+        if limit != 0 {
+            modlogs.truncate(limit.unsigned_abs() as usize);
+        }
+
+        Ok(modlogs)
+    }
+}
+
+/// In-memory [`CacheStore`].
+///
+/// Expired values are removed when they are accessed. It can be cheaply
+/// cloned, clones share the same data.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryCacheStore {
+    values: Arc<Mutex<HashMap<String, CachedValue>>>,
+}
+
+#[derive(Debug)]
+struct CachedValue {
+    value: Vec<u8>,
+    expires_at: Option<Instant>,
+}
+
+impl CachedValue {
+    /// Serialize a value that expires after `expires_after` seconds.
+    fn new<T: RedisModel>(value: &T, expires_after: Option<usize>) -> Result<Self, anyhow::Error> {
+        Ok(Self {
+            value: value.serialize_model()?,
+            expires_at: expires_after.map(|secs| Instant::now() + Duration::from_secs(secs as u64)),
+        })
+    }
+
+    fn is_expired(&self) -> bool {
+        self.expires_at
+            .map_or(false, |expires_at| expires_at <= Instant::now())
+    }
+}
+
+impl MemoryCacheStore {
+    /// Initialize a new empty [`MemoryCacheStore`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the number of stored values, including the expired ones.
+    pub fn len(&self) -> usize {
+        self.values().len()
+    }
+
+    /// Check whether the store is empty.
+    pub fn is_empty(&self) -> bool {
+        self.values().is_empty()
+    }
+
+    fn values(&self) -> MutexGuard<'_, HashMap<String, CachedValue>> {
+        self.values
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+    }
+
+    /// Remove a value and return it if it has not expired.
+    fn take(&self, key: &str) -> Option<Vec<u8>> {
+        let value = self.values().remove(key)?;
+
+        (!value.is_expired()).then_some(value.value)
+    }
+}
+
+#[async_trait]
+impl CacheStore for MemoryCacheStore {
+    async fn get<T>(&self, id: &T::Id) -> Result<Option<T>, anyhow::Error>
+    where
+        T: RedisModel + Send,
+        T::Id: Sync,
+    {
+        let key = T::key_from(id);
+        let mut values = self.values();
+
+        let value = match values.get(&key) {
+            Some(value) if !value.is_expired() => value.value.clone(),
+            Some(_) => {
+                values.remove(&key);
+                return Ok(None);
+            }
+            None => return Ok(None),
+        };
+
+        T::deserialize_model(value).map(Some)
+    }
+
+    async fn get_many<T>(&self, ids: &[T::Id]) -> Result<Vec<Option<T>>, anyhow::Error>
+    where
+        T: RedisModel + Send,
+        T::Id: Sized + Sync,
+    {
+        let mut values = Vec::with_capacity(ids.len());
+        for id in ids {
+            values.push(self.get::<T>(id).await?);
+        }
+
+        Ok(values)
+    }
+
+    async fn set<T>(&self, value: &T) -> Result<(), anyhow::Error>
+    where
+        T: RedisModel + Sync,
+    {
+        let cached = CachedValue::new(value, T::EXPIRES_AFTER)?;
+        self.values().insert(value.key(), cached);
+
+        Ok(())
+    }
+
+    async fn set_with_expiry<T>(&self, value: &T, expires_after: usize) -> Result<(), anyhow::Error>
+    where
+        T: RedisModel + Sync,
+    {
+        let cached = CachedValue::new(value, Some(expires_after))?;
+        self.values().insert(value.key(), cached);
+
+        Ok(())
+    }
+
+    async fn set_if_absent<T>(&self, value: &T, expires_after: usize) -> Result<bool, anyhow::Error>
+    where
+        T: RedisModel + Sync,
+    {
+        let cached = CachedValue::new(value, Some(expires_after))?;
+        let mut values = self.values();
+
+        if values
+            .get(&value.key())
+            .map_or(false, |value| !value.is_expired())
+        {
+            return Ok(false);
+        }
+
+        values.insert(value.key(), cached);
+
+        Ok(true)
+    }
+
+    async fn delete<T>(&self, value: &T) -> Result<(), anyhow::Error>
+    where
+        T: RedisModel + Sync,
+    {
+        self.values().remove(&value.key());
+
+        Ok(())
+    }
+
+    async fn consume<T>(&self, value: &T) -> Result<bool, anyhow::Error>
+    where
+        T: RedisModel + Sync,
+    {
+        Ok(self.take(&value.key()).is_some())
+    }
+}
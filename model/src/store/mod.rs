@@ -0,0 +1,227 @@
+//! Storage traits.
+//!
+//! Handlers access the database and the cache through the [`GuildStore`],
+//! [`ModlogStore`] and [`CacheStore`] traits instead of the concrete
+//! [`DbClient`] and [`CacheClient`] types, which are the default
+//! implementations. This allows running them against the in-memory
+//! implementations of the [`memory`] module in tests, without a MongoDB or
+//! Redis server.
+//!
+//! The in-memory implementations are checked against the default ones by the
+//! parity tests of the `store` test file.
+//!
+//! [`DbClient`]: crate::database::DbClient
+//! [`CacheClient`]: crate::cache::CacheClient
+
+pub mod memory;
+
+use async_trait::async_trait;
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::{
+    cache::{CacheClient, RedisModel},
+    database::{
+        model::{GuildConfig, Modlog, ModlogSearch, ObjectId},
+        DbClient,
+    },
+};
+
+/// Storage of the guild configurations.
+#[async_trait]
+pub trait GuildStore: Send + Sync {
+    /// Get the [`GuildConfig`] of a guild, or create it with the default
+    /// configuration.
+    async fn get_guild_or_create(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<GuildConfig, anyhow::Error>;
+
+    /// Update or insert a [`GuildConfig`].
+    async fn update_guild(&self, guild: &GuildConfig) -> Result<(), anyhow::Error>;
+}
+
+#[async_trait]
+impl GuildStore for DbClient {
+    async fn get_guild_or_create(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<GuildConfig, anyhow::Error> {
+        DbClient::get_guild_or_create(self, guild_id).await
+    }
+
+    async fn update_guild(&self, guild: &GuildConfig) -> Result<(), anyhow::Error> {
+        DbClient::update_guild(self, guild).await
+    }
+}
+
+/// Storage of the moderation logs.
+#[async_trait]
+pub trait ModlogStore: Send + Sync {
+    /// Insert a new [`Modlog`] and return its id.
+    async fn create_modlog(&self, modlog: &Modlog) -> Result<ObjectId, anyhow::Error>;
+
+    /// Get a [`Modlog`] with its id.
+    async fn get_modlog(&self, id: ObjectId) -> Result<Option<Modlog>, anyhow::Error>;
+
+    /// Get a page of the [`Modlog`]s of a guild, most recent first.
+    ///
+    /// Pages start at `0`. Also returns the total number of modlogs of the
+    /// guild.
+    async fn find_modlogs_page(
+        &self,
+        guild_id: Id<GuildMarker>,
+        page: u64,
+        per_page: u64,
+    ) -> Result<(Vec<Modlog>, u64), anyhow::Error>;
+
+    /// Search the [`Modlog`]s of a guild, most recent first.
+    ///
+    /// At most `limit` modlogs are returned.
+    async fn search_modlogs(
+        &self,
+        guild_id: Id<GuildMarker>,
+        search: &ModlogSearch,
+        limit: i64,
+    ) -> Result<Vec<Modlog>, anyhow::Error>;
+}
+
+#[async_trait]
+impl ModlogStore for DbClient {
+    async fn create_modlog(&self, modlog: &Modlog) -> Result<ObjectId, anyhow::Error> {
+        DbClient::create_modlog(self, modlog).await
+    }
+
+    async fn get_modlog(&self, id: ObjectId) -> Result<Option<Modlog>, anyhow::Error> {
+        DbClient::get_modlog(self, id).await
+    }
+
+    async fn find_modlogs_page(
+        &self,
+        guild_id: Id<GuildMarker>,
+        page: u64,
+        per_page: u64,
+    ) -> Result<(Vec<Modlog>, u64), anyhow::Error> {
+        DbClient::find_modlogs_page(self, guild_id, page, per_page).await
+    }
+
+    async fn search_modlogs(
+        &self,
+        guild_id: Id<GuildMarker>,
+        search: &ModlogSearch,
+        limit: i64,
+    ) -> Result<Vec<Modlog>, anyhow::Error> {
+        DbClient::search_modlogs(self, guild_id, search, limit).await
+    }
+}
+
+/// Storage of the [`RedisModel`] values.
+///
+/// The methods have the same behavior as the corresponding [`CacheClient`]
+/// methods.
+#[async_trait]
+pub trait CacheStore: Send + Sync {
+    /// Get a value from the store.
+    async fn get<T>(&self, id: &T::Id) -> Result<Option<T>, anyhow::Error>
+    where
+        T: RedisModel + Send,
+        T::Id: Sync;
+
+    /// Get multiple values from the store, in the order of the ids.
+    async fn get_many<T>(&self, ids: &[T::Id]) -> Result<Vec<Option<T>>, anyhow::Error>
+    where
+        T: RedisModel + Send,
+        T::Id: Sized + Sync;
+
+    /// Set a value in the store.
+    ///
+    /// The value expires after [`RedisModel::EXPIRES_AFTER`] seconds.
+    async fn set<T>(&self, value: &T) -> Result<(), anyhow::Error>
+    where
+        T: RedisModel + Sync;
+
+    /// Set a value in the store that expires after `expires_after` seconds.
+    async fn set_with_expiry<T>(
+        &self,
+        value: &T,
+        expires_after: usize,
+    ) -> Result<(), anyhow::Error>
+    where
+        T: RedisModel + Sync;
+
+    /// Set a value in the store only if it does not exist.
+    ///
+    /// Returns `false` if the value already exists.
+    async fn set_if_absent<T>(
+        &self,
+        value: &T,
+        expires_after: usize,
+    ) -> Result<bool, anyhow::Error>
+    where
+        T: RedisModel + Sync;
+
+    /// Delete a value from the store.
+    async fn delete<T>(&self, value: &T) -> Result<(), anyhow::Error>
+    where
+        T: RedisModel + Sync;
+
+    /// Consume a value from the store.
+    ///
+    /// Returns `true` if the value existed and has been deleted.
+    async fn consume<T>(&self, value: &T) -> Result<bool, anyhow::Error>
+    where
+        T: RedisModel + Sync;
+}
+
+#[async_trait]
+impl CacheStore for CacheClient {
+    async fn get<T>(&self, id: &T::Id) -> Result<Option<T>, anyhow::Error>
+    where
+        T: RedisModel + Send,
+        T::Id: Sync,
+    {
+        CacheClient::get(self, id).await
+    }
+
+    async fn get_many<T>(&self, ids: &[T::Id]) -> Result<Vec<Option<T>>, anyhow::Error>
+    where
+        T: RedisModel + Send,
+        T::Id: Sized + Sync,
+    {
+        CacheClient::get_many(self, ids).await
+    }
+
+    async fn set<T>(&self, value: &T) -> Result<(), anyhow::Error>
+    where
+        T: RedisModel + Sync,
+    {
+        CacheClient::set(self, value).await
+    }
+
+    async fn set_with_expiry<T>(&self, value: &T, expires_after: usize) -> Result<(), anyhow::Error>
+    where
+        T: RedisModel + Sync,
+    {
+        CacheClient::set_with_expiry(self, value, expires_after).await
+    }
+
+    async fn set_if_absent<T>(&self, value: &T, expires_after: usize) -> Result<bool, anyhow::Error>
+    where
+        T: RedisModel + Sync,
+    {
+        CacheClient::set_if_absent(self, value, expires_after).await
+    }
+
+    async fn delete<T>(&self, value: &T) -> Result<(), anyhow::Error>
+    where
+        T: RedisModel + Sync,
+    {
+        CacheClient::delete(self, value).await
+    }
+
+    async fn consume<T>(&self, value: &T) -> Result<bool, anyhow::Error>
+    where
+        T: RedisModel + Sync,
+    {
+        CacheClient::consume(self, value).await
+    }
+}
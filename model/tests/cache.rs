@@ -0,0 +1,152 @@
+//! Tests of the cache primitives that require a Redis server.
+//!
+//! These tests are ignored by default, run them with `cargo test -- --ignored`.
+//! The server uri can be set with the `REDIS_URI` environment variable.
+
+use std::{env, thread};
+
+use raidprotect_model::cache::{
+    model::{interaction::PendingAutomodOverwrite, lock::DistributedLock},
+    CacheClient,
+};
+use time::Duration;
+use twilight_model::id::Id;
+
+async fn client() -> CacheClient {
+    let uri = env::var("REDIS_URI").unwrap_or_else(|_| "redis://localhost:6379".to_owned());
+
+    CacheClient::connect(&uri).await.unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[ignore = "requires a Redis server"]
+async fn test_consume_concurrent() {
+    let cache = client().await;
+    let pending = PendingAutomodOverwrite {
+        interaction_id: Id::new(1),
+        author_id: Id::new(2),
+        guild_id: Id::new(3),
+    };
+
+    for _ in 0..50 {
+        cache.set(&pending).await.unwrap();
+
+        let consumers = (0..2).map(|_| {
+            let (cache, pending) = (cache.clone(), pending.clone());
+            tokio::spawn(async move { cache.consume(&pending).await.unwrap() })
+        });
+
+        let mut executed = 0;
+        for consumer in consumers.collect::<Vec<_>>() {
+            if consumer.await.unwrap() {
+                executed += 1;
+            }
+        }
+
+        assert_eq!(executed, 1);
+        assert!(cache
+            .get::<PendingAutomodOverwrite>(&pending.interaction_id.to_string())
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[ignore = "requires a Redis server"]
+async fn test_lock_contenders() {
+    let cache = client().await;
+    let mut last_token = 0;
+
+    for _ in 0..50 {
+        let contenders = (0..2).map(|_| {
+            let cache = cache.clone();
+            tokio::spawn(async move {
+                DistributedLock::acquire(&cache, "test-contenders", Duration::seconds(5))
+                    .await
+                    .unwrap()
+            })
+        });
+
+        let mut acquired = Vec::new();
+        for contender in contenders.collect::<Vec<_>>() {
+            acquired.extend(contender.await.unwrap());
+        }
+
+        assert_eq!(acquired.len(), 1);
+
+        let lock = acquired.pop().unwrap();
+        assert!(lock.token() > last_token);
+        last_token = lock.token();
+
+        lock.release().await.unwrap();
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[ignore = "requires a Redis server"]
+async fn test_lock_extension() {
+    let cache = client().await;
+    let ttl = Duration::milliseconds(300);
+
+    let lock = DistributedLock::acquire(&cache, "test-extension", ttl)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // The lock is extended while held.
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    assert!(lock.is_held());
+    assert!(DistributedLock::acquire(&cache, "test-extension", ttl)
+        .await
+        .unwrap()
+        .is_none());
+
+    lock.release().await.unwrap();
+    assert!(DistributedLock::acquire(&cache, "test-extension", ttl)
+        .await
+        .unwrap()
+        .is_some());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[ignore = "requires a Redis server"]
+async fn test_lock_expiry_takeover() {
+    let ttl = Duration::milliseconds(300);
+
+    // Simulate a crashed holder: the lock is neither released nor extended
+    // once the runtime of the holder is stopped.
+    let crashed_token = thread::spawn(move || {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+
+        runtime.block_on(async {
+            let cache = client().await;
+            let lock = DistributedLock::acquire(&cache, "test-takeover", ttl)
+                .await
+                .unwrap()
+                .unwrap();
+            let token = lock.token();
+            std::mem::forget(lock);
+
+            token
+        })
+    })
+    .join()
+    .unwrap();
+
+    let cache = client().await;
+    assert!(DistributedLock::acquire(&cache, "test-takeover", ttl)
+        .await
+        .unwrap()
+        .is_none());
+
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    let lock = DistributedLock::acquire(&cache, "test-takeover", ttl)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(lock.token() > crashed_token);
+
+    lock.release().await.unwrap();
+}
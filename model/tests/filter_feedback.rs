@@ -0,0 +1,31 @@
+use mongodb::bson::{self, DateTime};
+use pretty_assertions::assert_eq;
+use raidprotect_model::database::model::{FilterFeedback, StatsFilter};
+use time::OffsetDateTime;
+use twilight_model::id::Id;
+
+#[test]
+fn test_filter_feedback_bson() {
+    let feedback = FilterFeedback {
+        id: None,
+        guild_id: Id::new(1),
+        filter: StatsFilter::FirstMessage,
+        fingerprint: "0123456789abcdef".to_owned(),
+        reporter: Id::new(2),
+        date: OffsetDateTime::from_unix_timestamp(1_628_594_197).unwrap(),
+    };
+
+    let expected = bson::doc! {
+        "guild_id": 1_i64,
+        "filter": "first_message",
+        "fingerprint": "0123456789abcdef",
+        "reporter": 2_i64,
+        "date": DateTime::from_millis(1_628_594_197),
+    };
+
+    assert_eq!(bson::to_document(&feedback).unwrap(), expected);
+    assert_eq!(
+        bson::from_document::<FilterFeedback>(expected).unwrap(),
+        feedback
+    );
+}
@@ -0,0 +1,49 @@
+use mongodb::bson;
+use pretty_assertions::assert_eq;
+use raidprotect_model::database::model::{GrowthSample, GuildGrowth};
+use twilight_model::id::Id;
+
+fn growth() -> GuildGrowth {
+    GuildGrowth {
+        guild_id: Id::new(1),
+        samples: vec![
+            GrowthSample {
+                at: 1_628_594_197,
+                members: 120,
+            },
+            GrowthSample {
+                at: 1_628_597_797,
+                members: 125,
+            },
+        ],
+        milestone: 100,
+    }
+}
+
+#[test]
+fn test_growth_bson() {
+    let growth = growth();
+
+    let expected = bson::doc! {
+        "_id": 1_i64,
+        "samples": [
+            { "t": 1_628_594_197_i64, "m": 120_i64 },
+            { "t": 1_628_597_797_i64, "m": 125_i64 },
+        ],
+        "milestone": 100_i64,
+    };
+
+    assert_eq!(bson::to_document(&growth).unwrap(), expected);
+    assert_eq!(
+        bson::from_document::<GuildGrowth>(expected).unwrap(),
+        growth
+    );
+}
+
+#[test]
+fn test_growth_missing_fields() {
+    let growth = bson::from_document::<GuildGrowth>(bson::doc! { "_id": 1_i64 }).unwrap();
+
+    assert_eq!(growth.samples, Vec::new());
+    assert_eq!(growth.milestone, 0);
+}
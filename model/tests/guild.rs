@@ -1,8 +1,16 @@
 use mongodb::bson;
 use pretty_assertions::assert_eq;
-use raidprotect_model::database::model::{CaptchaConfig, GuildConfig, ModerationConfig};
+use raidprotect_model::database::model::{
+    ActivityRoleConfig, AntinukeAction, AntinukeConfig, AutomodConfig, AutomodRule,
+    AutomodRuleKind, BanSync, CaptchaConfig, ChannelRule, ChannelRuleKind, Feature, FeatureSet,
+    FirstMessageAction, FirstMessageConfig, GraceConfig, GuildConfig, JoinBurstConfig,
+    JoinBurstSignal, LinkFilterConfig, LogCategory, LogDestination, LogRouting, ModerationConfig,
+    ModlogType, MuteMode, NameAction, NameConfig, OutboundWebhook, PatternAction, PatternRule,
+    PinsConfig, RaidModeAction, RaidModeConfig, ReactionSpamConfig, RoleLogsConfig, SanctionRole,
+    Tag, TagsConfig, VoiceConfig, VoiceHopAction,
+};
 use serde_test::{assert_tokens, Token};
-use twilight_model::id::Id;
+use twilight_model::{guild::VerificationLevel, id::Id};
 
 #[test]
 fn test_guild_default() {
@@ -13,7 +21,7 @@ fn test_guild_default() {
         &[
             Token::Struct {
                 name: "GuildConfig",
-                len: 5,
+                len: 28,
             },
             Token::Str("_id"),
             Token::I64(1),
@@ -21,15 +29,33 @@ fn test_guild_default() {
             Token::None,
             Token::Str("lang"),
             Token::Str("fr"),
+            Token::Str("lang_override"),
+            Token::Bool(false),
+            Token::Str("lang_override_ephemeral"),
+            Token::Bool(false),
             Token::Str("moderation"),
             Token::Struct {
                 name: "ModerationConfig",
-                len: 2,
+                len: 6,
             },
             Token::Str("enforce_reason"),
             Token::Bool(false),
             Token::Str("anonymize"),
             Token::Bool(true),
+            Token::Str("sanction_cooldown"),
+            Token::U16(30),
+            Token::Str("mute_mode"),
+            Token::Struct {
+                name: "MuteMode",
+                len: 1,
+            },
+            Token::Str("mode"),
+            Token::Str("timeout"),
+            Token::StructEnd,
+            Token::Str("enforce_mutes"),
+            Token::Bool(false),
+            Token::Str("dm_disabled"),
+            Token::Bool(false),
             Token::StructEnd,
             Token::Str("captcha"),
             Token::Struct {
@@ -39,6 +65,217 @@ fn test_guild_default() {
             Token::Str("enabled"),
             Token::Bool(false),
             Token::StructEnd,
+            Token::Str("raid_mode"),
+            Token::Struct {
+                name: "RaidModeConfig",
+                len: 8,
+            },
+            Token::Str("enabled"),
+            Token::Bool(false),
+            Token::Str("account_age"),
+            Token::Some,
+            Token::U32(72),
+            Token::Str("action"),
+            Token::UnitVariant {
+                name: "RaidModeAction",
+                variant: "kick",
+            },
+            Token::Str("review_channel"),
+            Token::None,
+            Token::Str("member_role"),
+            Token::None,
+            Token::Str("verification_level"),
+            Token::Some,
+            Token::U8(3),
+            Token::Str("join_alerts"),
+            Token::Bool(true),
+            Token::Str("previous_verification_level"),
+            Token::None,
+            Token::StructEnd,
+            Token::Str("reaction_spam"),
+            Token::Struct {
+                name: "ReactionSpamConfig",
+                len: 3,
+            },
+            Token::Str("enabled"),
+            Token::Bool(false),
+            Token::Str("max_reactions"),
+            Token::U16(10),
+            Token::Str("interval"),
+            Token::U16(5),
+            Token::StructEnd,
+            Token::Str("link_filter"),
+            Token::Struct {
+                name: "LinkFilterConfig",
+                len: 3,
+            },
+            Token::Str("enabled"),
+            Token::Bool(false),
+            Token::Str("invites"),
+            Token::Bool(true),
+            Token::Str("links"),
+            Token::Bool(false),
+            Token::StructEnd,
+            Token::Str("first_message"),
+            Token::Struct {
+                name: "FirstMessageConfig",
+                len: 4,
+            },
+            Token::Str("enabled"),
+            Token::Bool(false),
+            Token::Str("links"),
+            Token::Bool(true),
+            Token::Str("max_mentions"),
+            Token::U8(3),
+            Token::Str("action"),
+            Token::UnitVariant {
+                name: "FirstMessageAction",
+                variant: "delete",
+            },
+            Token::StructEnd,
+            Token::Str("antinuke"),
+            Token::Struct {
+                name: "AntinukeConfig",
+                len: 4,
+            },
+            Token::Str("enabled"),
+            Token::Bool(false),
+            Token::Str("max_deletions"),
+            Token::U16(5),
+            Token::Str("interval"),
+            Token::U16(60),
+            Token::Str("action"),
+            Token::UnitVariant {
+                name: "AntinukeAction",
+                variant: "strip_roles",
+            },
+            Token::StructEnd,
+            Token::Str("join_burst"),
+            Token::Struct {
+                name: "JoinBurstConfig",
+                len: 5,
+            },
+            Token::Str("enabled"),
+            Token::Bool(false),
+            Token::Str("max_joins"),
+            Token::U16(10),
+            Token::Str("interval"),
+            Token::U16(30),
+            Token::Str("signals"),
+            Token::Seq { len: Some(2) },
+            Token::UnitVariant {
+                name: "JoinBurstSignal",
+                variant: "avatar",
+            },
+            Token::UnitVariant {
+                name: "JoinBurstSignal",
+                variant: "name",
+            },
+            Token::SeqEnd,
+            Token::Str("cluster_ratio"),
+            Token::U8(50),
+            Token::StructEnd,
+            Token::Str("automod"),
+            Token::Struct {
+                name: "AutomodConfig",
+                len: 2,
+            },
+            Token::Str("enabled"),
+            Token::Bool(false),
+            Token::Str("spam"),
+            Token::Bool(false),
+            Token::StructEnd,
+            Token::Str("grace"),
+            Token::Struct {
+                name: "GraceConfig",
+                len: 2,
+            },
+            Token::Str("enabled"),
+            Token::Bool(false),
+            Token::Str("boosters"),
+            Token::Bool(true),
+            Token::StructEnd,
+            Token::Str("voice"),
+            Token::Struct {
+                name: "VoiceConfig",
+                len: 5,
+            },
+            Token::Str("hop_detection"),
+            Token::Bool(false),
+            Token::Str("max_hops"),
+            Token::U16(5),
+            Token::Str("interval"),
+            Token::U16(10),
+            Token::Str("action"),
+            Token::UnitVariant {
+                name: "VoiceHopAction",
+                variant: "disconnect",
+            },
+            Token::Str("timeout"),
+            Token::U32(600),
+            Token::StructEnd,
+            Token::Str("names"),
+            Token::Struct {
+                name: "NameConfig",
+                len: 3,
+            },
+            Token::Str("enabled"),
+            Token::Bool(false),
+            Token::Str("action"),
+            Token::UnitVariant {
+                name: "NameAction",
+                variant: "reset",
+            },
+            Token::Str("fallback"),
+            Token::Str("Moderated nickname"),
+            Token::StructEnd,
+            Token::Str("pins"),
+            Token::Struct {
+                name: "PinsConfig",
+                len: 0,
+            },
+            Token::StructEnd,
+            Token::Str("role_logs"),
+            Token::Struct {
+                name: "RoleLogsConfig",
+                len: 0,
+            },
+            Token::StructEnd,
+            Token::Str("activity_role"),
+            Token::Struct {
+                name: "ActivityRoleConfig",
+                len: 3,
+            },
+            Token::Str("threshold"),
+            Token::U32(50),
+            Token::Str("cooldown"),
+            Token::U16(60),
+            Token::Str("log"),
+            Token::Bool(true),
+            Token::StructEnd,
+            Token::Str("tags"),
+            Token::Struct {
+                name: "TagsConfig",
+                len: 0,
+            },
+            Token::StructEnd,
+            Token::Str("ban_sync"),
+            Token::UnitVariant {
+                name: "BanSync",
+                variant: "off",
+            },
+            Token::Str("new_account_threshold"),
+            Token::U32(168),
+            Token::Str("features"),
+            Token::U32(127),
+            Token::Str("sentinel_alerts"),
+            Token::Bool(false),
+            Token::Str("screening_aware"),
+            Token::Bool(false),
+            Token::Str("watch_duration"),
+            Token::U16(30),
+            Token::Str("onboarded"),
+            Token::Bool(false),
             Token::StructEnd,
         ],
     );
@@ -46,14 +283,42 @@ fn test_guild_default() {
 
 #[test]
 fn test_guild_full() {
+    let mut features = FeatureSet::DEFAULT;
+    features.set(Feature::Verification, false);
+
+    let mut log_routing = LogRouting::default();
+    log_routing.set(LogCategory::Sanctions, LogDestination::Channel(Id::new(26)));
+    log_routing.set(LogCategory::Joins, LogDestination::Off);
+
     let guild = GuildConfig {
         id: Id::new(1),
         logs_chan: Some(Id::new(2)),
+        log_routing,
         lang: "en".to_owned(),
+        lang_override: true,
+        lang_override_ephemeral: false,
         moderation: ModerationConfig {
             roles: vec![Id::new(3), Id::new(4)],
             enforce_reason: true,
             anonymize: false,
+            sanction_cooldown: 10,
+            mute_mode: MuteMode::Role {
+                role_id: Id::new(5),
+            },
+            enforce_mutes: true,
+            reason_presets: vec!["Spam".to_owned()],
+            sanction_roles: vec![SanctionRole {
+                role_id: Id::new(4),
+                sanctions: vec![ModlogType::Mute, ModlogType::Unmute],
+                shadow: true,
+            }],
+            quarantine_role: Some(Id::new(6)),
+            skip_confirmation: vec![ModlogType::Mute],
+            skip_confirmation_roles: vec![Id::new(7)],
+            dm_disabled: true,
+            dm_skipped: vec![ModlogType::Kick],
+            announcement_channel: Some(Id::new(29)),
+            announced: vec![ModlogType::Ban],
         },
         captcha: CaptchaConfig {
             enabled: true,
@@ -63,6 +328,130 @@ fn test_guild_full() {
             verified_roles: vec![Id::new(8), Id::new(9)],
             logs: Some(Id::new(10)),
         },
+        raid_mode: RaidModeConfig {
+            enabled: true,
+            account_age: Some(24),
+            action: RaidModeAction::Review,
+            review_channel: Some(Id::new(27)),
+            member_role: Some(Id::new(28)),
+            verification_level: Some(VerificationLevel::VeryHigh),
+            join_alerts: false,
+            previous_verification_level: Some(VerificationLevel::Low),
+        },
+        reaction_spam: ReactionSpamConfig {
+            enabled: true,
+            max_reactions: 5,
+            interval: 10,
+            timeout: Some(60),
+            trusted_roles: vec![Id::new(11)],
+        },
+        link_filter: LinkFilterConfig {
+            enabled: true,
+            invites: true,
+            links: true,
+            allowed_domains: vec!["raidprotect.org".to_owned()],
+            allowed_invites: vec!["raidprotect".to_owned()],
+            timeout: Some(120),
+            trusted_roles: vec![Id::new(20)],
+        },
+        pattern_rules: vec![PatternRule {
+            name: "scam".to_owned(),
+            pattern: "free nitro".to_owned(),
+            action: PatternAction::Timeout,
+        }],
+        channel_rules: vec![ChannelRule {
+            channel_id: Id::new(21),
+            kind: ChannelRuleKind::LinkOnlyFrom {
+                domains: vec!["youtube.com".to_owned()],
+            },
+        }],
+        first_message: FirstMessageConfig {
+            enabled: true,
+            links: false,
+            max_mentions: 5,
+            action: FirstMessageAction::Ban,
+        },
+        antinuke: AntinukeConfig {
+            enabled: true,
+            max_deletions: 3,
+            interval: 30,
+            action: AntinukeAction::Ban,
+            trusted: vec![Id::new(12)],
+        },
+        join_burst: JoinBurstConfig {
+            enabled: true,
+            max_joins: 20,
+            interval: 60,
+            signals: vec![JoinBurstSignal::Name],
+            cluster_ratio: 30,
+        },
+        automod: AutomodConfig {
+            enabled: true,
+            keywords: vec!["spam".to_owned()],
+            spam: true,
+            mention_limit: Some(5),
+            rules: vec![AutomodRule {
+                kind: AutomodRuleKind::Keyword,
+                id: Id::new(14),
+                fingerprint: "abc".to_owned(),
+            }],
+        },
+        automod_ignored_roles: vec![Id::new(25)],
+        grace: GraceConfig {
+            enabled: true,
+            boosters: false,
+            trusted_tenure: Some(90),
+            trusted_roles: vec![Id::new(30)],
+        },
+        voice: VoiceConfig {
+            logs_chan: Some(Id::new(15)),
+            hop_detection: true,
+            max_hops: 3,
+            interval: 20,
+            action: VoiceHopAction::Timeout,
+            timeout: 300,
+            trusted_roles: vec![Id::new(16)],
+        },
+        names: NameConfig {
+            enabled: true,
+            action: NameAction::Flag,
+            fallback: "Renamed".to_owned(),
+        },
+        pins: PinsConfig {
+            logs_chan: Some(Id::new(17)),
+            ignored_channels: vec![Id::new(18)],
+        },
+        role_logs: RoleLogsConfig {
+            logs_chan: Some(Id::new(22)),
+            ignored_roles: vec![Id::new(23)],
+        },
+        activity_role: ActivityRoleConfig {
+            role: Some(Id::new(19)),
+            threshold: 100,
+            cooldown: 30,
+            log: false,
+        },
+        tags: TagsConfig {
+            tags: vec![Tag {
+                name: "rules".to_owned(),
+                content: "Please read the rules, {user}.".to_owned(),
+            }],
+            manager_roles: vec![Id::new(24)],
+        },
+        ban_sync: BanSync::Both,
+        delete_replies_after: Some(30),
+        modlog_retention: Some(365),
+        new_account_threshold: 24,
+        features,
+        sentinel_alerts: true,
+        screening_aware: true,
+        raid_alert_role: Some(Id::new(13)),
+        watch_chan: Some(Id::new(14)),
+        watch_duration: 7,
+        reports_chan: Some(Id::new(15)),
+        milestones_chan: Some(Id::new(31)),
+        outbound_webhook: None,
+        onboarded: true,
     };
 
     assert_tokens(
@@ -70,20 +459,52 @@ fn test_guild_full() {
         &[
             Token::Struct {
                 name: "GuildConfig",
-                len: 5,
+                len: 38,
             },
             Token::Str("_id"),
             Token::I64(1),
             Token::Str("logs_chan"),
             Token::Some,
             Token::I64(2),
+            Token::Str("log_routing"),
+            Token::Seq { len: Some(2) },
+            Token::Struct {
+                name: "LogRoute",
+                len: 2,
+            },
+            Token::Str("category"),
+            Token::UnitVariant {
+                name: "LogCategory",
+                variant: "sanctions",
+            },
+            Token::Str("channel"),
+            Token::Some,
+            Token::I64(26),
+            Token::StructEnd,
+            Token::Struct {
+                name: "LogRoute",
+                len: 2,
+            },
+            Token::Str("category"),
+            Token::UnitVariant {
+                name: "LogCategory",
+                variant: "joins",
+            },
+            Token::Str("channel"),
+            Token::None,
+            Token::StructEnd,
+            Token::SeqEnd,
             Token::Str("lang"),
             Token::Str("en"),
+            Token::Str("lang_override"),
+            Token::Bool(true),
+            Token::Str("lang_override_ephemeral"),
+            Token::Bool(false),
             // moderation
             Token::Str("moderation"),
             Token::Struct {
                 name: "ModerationConfig",
-                len: 3,
+                len: 15,
             },
             Token::Str("roles"),
             Token::Seq { len: Some(2) },
@@ -94,6 +515,80 @@ fn test_guild_full() {
             Token::Bool(true),
             Token::Str("anonymize"),
             Token::Bool(false),
+            Token::Str("sanction_cooldown"),
+            Token::U16(10),
+            Token::Str("mute_mode"),
+            Token::Struct {
+                name: "MuteMode",
+                len: 2,
+            },
+            Token::Str("mode"),
+            Token::Str("role"),
+            Token::Str("role_id"),
+            Token::I64(5),
+            Token::StructEnd,
+            Token::Str("enforce_mutes"),
+            Token::Bool(true),
+            Token::Str("reason_presets"),
+            Token::Seq { len: Some(1) },
+            Token::Str("Spam"),
+            Token::SeqEnd,
+            Token::Str("sanction_roles"),
+            Token::Seq { len: Some(1) },
+            Token::Struct {
+                name: "SanctionRole",
+                len: 3,
+            },
+            Token::Str("role_id"),
+            Token::I64(4),
+            Token::Str("sanctions"),
+            Token::Seq { len: Some(2) },
+            Token::UnitVariant {
+                name: "ModlogType",
+                variant: "mute",
+            },
+            Token::UnitVariant {
+                name: "ModlogType",
+                variant: "unmute",
+            },
+            Token::SeqEnd,
+            Token::Str("shadow"),
+            Token::Bool(true),
+            Token::StructEnd,
+            Token::SeqEnd,
+            Token::Str("quarantine_role"),
+            Token::Some,
+            Token::I64(6),
+            Token::Str("skip_confirmation"),
+            Token::Seq { len: Some(1) },
+            Token::UnitVariant {
+                name: "ModlogType",
+                variant: "mute",
+            },
+            Token::SeqEnd,
+            Token::Str("skip_confirmation_roles"),
+            Token::Seq { len: Some(1) },
+            Token::I64(7),
+            Token::SeqEnd,
+            Token::Str("dm_disabled"),
+            Token::Bool(true),
+            Token::Str("dm_skipped"),
+            Token::Seq { len: Some(1) },
+            Token::UnitVariant {
+                name: "ModlogType",
+                variant: "kick",
+            },
+            Token::SeqEnd,
+            Token::Str("announcement_channel"),
+            Token::Some,
+            Token::I64(29),
+            Token::Str("announced"),
+            Token::Seq { len: Some(1) },
+            Token::UnitVariant {
+                name: "ModlogType",
+                variant: "ban",
+            },
+            Token::SeqEnd,
             Token::StructEnd,
             // captcha
             Token::Str("captcha"),
@@ -121,6 +616,376 @@ fn test_guild_full() {
             Token::Some,
             Token::I64(10),
             Token::StructEnd,
+            // raid mode
+            Token::Str("raid_mode"),
+            Token::Struct {
+                name: "RaidModeConfig",
+                len: 8,
+            },
+            Token::Str("enabled"),
+            Token::Bool(true),
+            Token::Str("account_age"),
+            Token::Some,
+            Token::U32(24),
+            Token::Str("action"),
+            Token::UnitVariant {
+                name: "RaidModeAction",
+                variant: "review",
+            },
+            Token::Str("review_channel"),
+            Token::Some,
+            Token::I64(27),
+            Token::Str("member_role"),
+            Token::Some,
+            Token::I64(28),
+            Token::Str("verification_level"),
+            Token::Some,
+            Token::U8(4),
+            Token::Str("join_alerts"),
+            Token::Bool(false),
+            Token::Str("previous_verification_level"),
+            Token::Some,
+            Token::U8(1),
+            Token::StructEnd,
+            // reaction spam
+            Token::Str("reaction_spam"),
+            Token::Struct {
+                name: "ReactionSpamConfig",
+                len: 5,
+            },
+            Token::Str("enabled"),
+            Token::Bool(true),
+            Token::Str("max_reactions"),
+            Token::U16(5),
+            Token::Str("interval"),
+            Token::U16(10),
+            Token::Str("timeout"),
+            Token::Some,
+            Token::U32(60),
+            Token::Str("trusted_roles"),
+            Token::Seq { len: Some(1) },
+            Token::I64(11),
+            Token::SeqEnd,
+            Token::StructEnd,
+            Token::Str("link_filter"),
+            Token::Struct {
+                name: "LinkFilterConfig",
+                len: 7,
+            },
+            Token::Str("enabled"),
+            Token::Bool(true),
+            Token::Str("invites"),
+            Token::Bool(true),
+            Token::Str("links"),
+            Token::Bool(true),
+            Token::Str("allowed_domains"),
+            Token::Seq { len: Some(1) },
+            Token::Str("raidprotect.org"),
+            Token::SeqEnd,
+            Token::Str("allowed_invites"),
+            Token::Seq { len: Some(1) },
+            Token::Str("raidprotect"),
+            Token::SeqEnd,
+            Token::Str("timeout"),
+            Token::Some,
+            Token::U32(120),
+            Token::Str("trusted_roles"),
+            Token::Seq { len: Some(1) },
+            Token::I64(20),
+            Token::SeqEnd,
+            Token::StructEnd,
+            Token::Str("pattern_rules"),
+            Token::Seq { len: Some(1) },
+            Token::Struct {
+                name: "PatternRule",
+                len: 3,
+            },
+            Token::Str("name"),
+            Token::Str("scam"),
+            Token::Str("pattern"),
+            Token::Str("free nitro"),
+            Token::Str("action"),
+            Token::UnitVariant {
+                name: "PatternAction",
+                variant: "timeout",
+            },
+            Token::StructEnd,
+            Token::SeqEnd,
+            Token::Str("channel_rules"),
+            Token::Seq { len: Some(1) },
+            Token::Struct {
+                name: "ChannelRule",
+                len: 2,
+            },
+            Token::Str("channel_id"),
+            Token::I64(21),
+            Token::Str("kind"),
+            Token::Struct {
+                name: "ChannelRuleKind",
+                len: 2,
+            },
+            Token::Str("type"),
+            Token::Str("link_only_from"),
+            Token::Str("domains"),
+            Token::Seq { len: Some(1) },
+            Token::Str("youtube.com"),
+            Token::SeqEnd,
+            Token::StructEnd,
+            Token::StructEnd,
+            Token::SeqEnd,
+            Token::Str("first_message"),
+            Token::Struct {
+                name: "FirstMessageConfig",
+                len: 4,
+            },
+            Token::Str("enabled"),
+            Token::Bool(true),
+            Token::Str("links"),
+            Token::Bool(false),
+            Token::Str("max_mentions"),
+            Token::U8(5),
+            Token::Str("action"),
+            Token::UnitVariant {
+                name: "FirstMessageAction",
+                variant: "ban",
+            },
+            Token::StructEnd,
+            Token::Str("antinuke"),
+            Token::Struct {
+                name: "AntinukeConfig",
+                len: 5,
+            },
+            Token::Str("enabled"),
+            Token::Bool(true),
+            Token::Str("max_deletions"),
+            Token::U16(3),
+            Token::Str("interval"),
+            Token::U16(30),
+            Token::Str("action"),
+            Token::UnitVariant {
+                name: "AntinukeAction",
+                variant: "ban",
+            },
+            Token::Str("trusted"),
+            Token::Seq { len: Some(1) },
+            Token::I64(12),
+            Token::SeqEnd,
+            Token::StructEnd,
+            Token::Str("join_burst"),
+            Token::Struct {
+                name: "JoinBurstConfig",
+                len: 5,
+            },
+            Token::Str("enabled"),
+            Token::Bool(true),
+            Token::Str("max_joins"),
+            Token::U16(20),
+            Token::Str("interval"),
+            Token::U16(60),
+            Token::Str("signals"),
+            Token::Seq { len: Some(1) },
+            Token::UnitVariant {
+                name: "JoinBurstSignal",
+                variant: "name",
+            },
+            Token::SeqEnd,
+            Token::Str("cluster_ratio"),
+            Token::U8(30),
+            Token::StructEnd,
+            Token::Str("automod"),
+            Token::Struct {
+                name: "AutomodConfig",
+                len: 5,
+            },
+            Token::Str("enabled"),
+            Token::Bool(true),
+            Token::Str("keywords"),
+            Token::Seq { len: Some(1) },
+            Token::Str("spam"),
+            Token::SeqEnd,
+            Token::Str("spam"),
+            Token::Bool(true),
+            Token::Str("mention_limit"),
+            Token::Some,
+            Token::U8(5),
+            Token::Str("rules"),
+            Token::Seq { len: Some(1) },
+            Token::Struct {
+                name: "AutomodRule",
+                len: 3,
+            },
+            Token::Str("kind"),
+            Token::UnitVariant {
+                name: "AutomodRuleKind",
+                variant: "keyword",
+            },
+            Token::Str("id"),
+            Token::I64(14),
+            Token::Str("fingerprint"),
+            Token::Str("abc"),
+            Token::StructEnd,
+            Token::SeqEnd,
+            Token::StructEnd,
+            Token::Str("automod_ignored_roles"),
+            Token::Seq { len: Some(1) },
+            Token::I64(25),
+            Token::SeqEnd,
+            Token::Str("grace"),
+            Token::Struct {
+                name: "GraceConfig",
+                len: 4,
+            },
+            Token::Str("enabled"),
+            Token::Bool(true),
+            Token::Str("boosters"),
+            Token::Bool(false),
+            Token::Str("trusted_tenure"),
+            Token::Some,
+            Token::U16(90),
+            Token::Str("trusted_roles"),
+            Token::Seq { len: Some(1) },
+            Token::I64(30),
+            Token::SeqEnd,
+            Token::StructEnd,
+            Token::Str("voice"),
+            Token::Struct {
+                name: "VoiceConfig",
+                len: 7,
+            },
+            Token::Str("logs_chan"),
+            Token::Some,
+            Token::I64(15),
+            Token::Str("hop_detection"),
+            Token::Bool(true),
+            Token::Str("max_hops"),
+            Token::U16(3),
+            Token::Str("interval"),
+            Token::U16(20),
+            Token::Str("action"),
+            Token::UnitVariant {
+                name: "VoiceHopAction",
+                variant: "timeout",
+            },
+            Token::Str("timeout"),
+            Token::U32(300),
+            Token::Str("trusted_roles"),
+            Token::Seq { len: Some(1) },
+            Token::I64(16),
+            Token::SeqEnd,
+            Token::StructEnd,
+            Token::Str("names"),
+            Token::Struct {
+                name: "NameConfig",
+                len: 3,
+            },
+            Token::Str("enabled"),
+            Token::Bool(true),
+            Token::Str("action"),
+            Token::UnitVariant {
+                name: "NameAction",
+                variant: "flag",
+            },
+            Token::Str("fallback"),
+            Token::Str("Renamed"),
+            Token::StructEnd,
+            Token::Str("pins"),
+            Token::Struct {
+                name: "PinsConfig",
+                len: 2,
+            },
+            Token::Str("logs_chan"),
+            Token::Some,
+            Token::I64(17),
+            Token::Str("ignored_channels"),
+            Token::Seq { len: Some(1) },
+            Token::I64(18),
+            Token::SeqEnd,
+            Token::StructEnd,
+            Token::Str("role_logs"),
+            Token::Struct {
+                name: "RoleLogsConfig",
+                len: 2,
+            },
+            Token::Str("logs_chan"),
+            Token::Some,
+            Token::I64(22),
+            Token::Str("ignored_roles"),
+            Token::Seq { len: Some(1) },
+            Token::I64(23),
+            Token::SeqEnd,
+            Token::StructEnd,
+            Token::Str("activity_role"),
+            Token::Struct {
+                name: "ActivityRoleConfig",
+                len: 4,
+            },
+            Token::Str("role"),
+            Token::Some,
+            Token::I64(19),
+            Token::Str("threshold"),
+            Token::U32(100),
+            Token::Str("cooldown"),
+            Token::U16(30),
+            Token::Str("log"),
+            Token::Bool(false),
+            Token::StructEnd,
+            Token::Str("tags"),
+            Token::Struct {
+                name: "TagsConfig",
+                len: 2,
+            },
+            Token::Str("tags"),
+            Token::Seq { len: Some(1) },
+            Token::Struct {
+                name: "Tag",
+                len: 2,
+            },
+            Token::Str("name"),
+            Token::Str("rules"),
+            Token::Str("content"),
+            Token::Str("Please read the rules, {user}."),
+            Token::StructEnd,
+            Token::SeqEnd,
+            Token::Str("manager_roles"),
+            Token::Seq { len: Some(1) },
+            Token::I64(24),
+            Token::SeqEnd,
+            Token::StructEnd,
+            Token::Str("ban_sync"),
+            Token::UnitVariant {
+                name: "BanSync",
+                variant: "both",
+            },
+            Token::Str("delete_replies_after"),
+            Token::Some,
+            Token::U16(30),
+            Token::Str("modlog_retention"),
+            Token::Some,
+            Token::U16(365),
+            Token::Str("new_account_threshold"),
+            Token::U32(24),
+            Token::Str("features"),
+            Token::U32(95),
+            Token::Str("sentinel_alerts"),
+            Token::Bool(true),
+            Token::Str("screening_aware"),
+            Token::Bool(true),
+            Token::Str("raid_alert_role"),
+            Token::Some,
+            Token::I64(13),
+            Token::Str("watch_chan"),
+            Token::Some,
+            Token::I64(14),
+            Token::Str("watch_duration"),
+            Token::U16(7),
+            Token::Str("reports_chan"),
+            Token::Some,
+            Token::I64(15),
+            Token::Str("milestones_chan"),
+            Token::Some,
+            Token::I64(31),
+            Token::Str("onboarded"),
+            Token::Bool(true),
             Token::StructEnd,
         ],
     );
@@ -128,14 +993,38 @@ fn test_guild_full() {
 
 #[test]
 fn test_guild_bson() {
+    let mut features = FeatureSet::DEFAULT;
+    features.set(Feature::Verification, false);
+
     let guild = GuildConfig {
         id: Id::new(1),
         logs_chan: Some(Id::new(2)),
+        log_routing: LogRouting::default(),
         lang: "en".to_owned(),
+        lang_override: true,
+        lang_override_ephemeral: false,
         moderation: ModerationConfig {
             roles: vec![Id::new(3), Id::new(4)],
             enforce_reason: true,
             anonymize: false,
+            sanction_cooldown: 10,
+            mute_mode: MuteMode::Role {
+                role_id: Id::new(5),
+            },
+            enforce_mutes: true,
+            reason_presets: vec!["Spam".to_owned()],
+            sanction_roles: vec![SanctionRole {
+                role_id: Id::new(4),
+                sanctions: vec![ModlogType::Mute, ModlogType::Unmute],
+                shadow: true,
+            }],
+            quarantine_role: Some(Id::new(6)),
+            skip_confirmation: vec![ModlogType::Mute],
+            skip_confirmation_roles: vec![Id::new(7)],
+            dm_disabled: true,
+            dm_skipped: vec![ModlogType::Kick],
+            announcement_channel: Some(Id::new(29)),
+            announced: vec![ModlogType::Ban],
         },
         captcha: CaptchaConfig {
             enabled: true,
@@ -145,16 +1034,89 @@ fn test_guild_bson() {
             verified_roles: vec![Id::new(8), Id::new(9)],
             logs: Some(Id::new(10)),
         },
+        raid_mode: RaidModeConfig {
+            enabled: true,
+            account_age: Some(24),
+            action: RaidModeAction::Review,
+            review_channel: Some(Id::new(27)),
+            member_role: Some(Id::new(28)),
+            verification_level: Some(VerificationLevel::VeryHigh),
+            join_alerts: false,
+            previous_verification_level: Some(VerificationLevel::Low),
+        },
+        reaction_spam: ReactionSpamConfig {
+            enabled: true,
+            max_reactions: 5,
+            interval: 10,
+            timeout: Some(60),
+            trusted_roles: vec![Id::new(11)],
+        },
+        link_filter: LinkFilterConfig::default(),
+        pattern_rules: Vec::new(),
+        channel_rules: Vec::new(),
+        first_message: FirstMessageConfig::default(),
+        antinuke: AntinukeConfig {
+            enabled: true,
+            max_deletions: 3,
+            interval: 30,
+            action: AntinukeAction::Ban,
+            trusted: vec![Id::new(12)],
+        },
+        join_burst: JoinBurstConfig::default(),
+        automod: AutomodConfig::default(),
+        automod_ignored_roles: Vec::new(),
+        grace: GraceConfig::default(),
+        voice: VoiceConfig::default(),
+        names: NameConfig::default(),
+        pins: PinsConfig::default(),
+        role_logs: RoleLogsConfig::default(),
+        activity_role: ActivityRoleConfig::default(),
+        tags: TagsConfig::default(),
+        ban_sync: BanSync::Send,
+        delete_replies_after: Some(30),
+        modlog_retention: Some(365),
+        new_account_threshold: 24,
+        features,
+        sentinel_alerts: true,
+        screening_aware: true,
+        raid_alert_role: None,
+        watch_chan: None,
+        watch_duration: 30,
+        reports_chan: None,
+        milestones_chan: Some(Id::new(30)),
+        outbound_webhook: None,
+        onboarded: true,
     };
 
     let expected = bson::doc! {
         "_id": 1_i64,
         "logs_chan": 2_i64,
         "lang": "en".to_owned(),
+        "lang_override": true,
+        "lang_override_ephemeral": false,
         "moderation": {
             "roles": [3_i64, 4_i64],
             "enforce_reason": true,
             "anonymize": false,
+            "sanction_cooldown": 10_i32,
+            "mute_mode": {
+                "mode": "role",
+                "role_id": 5_i64,
+            },
+            "enforce_mutes": true,
+            "reason_presets": ["Spam"],
+            "sanction_roles": [{
+                "role_id": 4_i64,
+                "sanctions": ["mute", "unmute"],
+                "shadow": true,
+            }],
+            "quarantine_role": 6_i64,
+            "skip_confirmation": ["mute"],
+            "skip_confirmation_roles": [7_i64],
+            "dm_disabled": true,
+            "dm_skipped": ["kick"],
+            "announcement_channel": 29_i64,
+            "announced": ["ban"],
         },
         "captcha": {
             "enabled": true,
@@ -164,8 +1126,310 @@ fn test_guild_bson() {
             "verified_roles": [8_i64, 9_i64],
             "logs": 10_i64,
         },
+        "raid_mode": {
+            "enabled": true,
+            "account_age": 24_i64,
+            "action": "review",
+            "review_channel": 27_i64,
+            "member_role": 28_i64,
+            "verification_level": 4_i32,
+            "join_alerts": false,
+            "previous_verification_level": 1_i32,
+        },
+        "reaction_spam": {
+            "enabled": true,
+            "max_reactions": 5_i32,
+            "interval": 10_i32,
+            "timeout": 60_i64,
+            "trusted_roles": [11_i64],
+        },
+        "link_filter": {
+            "enabled": false,
+            "invites": true,
+            "links": false,
+        },
+        "first_message": {
+            "enabled": false,
+            "links": true,
+            "max_mentions": 3_i32,
+            "action": "delete",
+        },
+        "antinuke": {
+            "enabled": true,
+            "max_deletions": 3_i32,
+            "interval": 30_i32,
+            "action": "ban",
+            "trusted": [12_i64],
+        },
+        "join_burst": {
+            "enabled": false,
+            "max_joins": 10_i32,
+            "interval": 30_i32,
+            "signals": ["avatar", "name"],
+            "cluster_ratio": 50_i32,
+        },
+        "automod": {
+            "enabled": false,
+            "spam": false,
+        },
+        "grace": {
+            "enabled": false,
+            "boosters": true,
+        },
+        "voice": {
+            "hop_detection": false,
+            "max_hops": 5_i32,
+            "interval": 10_i32,
+            "action": "disconnect",
+            "timeout": 600_i64,
+        },
+        "names": {
+            "enabled": false,
+            "action": "reset",
+            "fallback": "Moderated nickname",
+        },
+        "pins": {},
+        "role_logs": {},
+        "activity_role": {
+            "threshold": 50_i64,
+            "cooldown": 60_i32,
+            "log": true,
+        },
+        "tags": {},
+        "ban_sync": "send",
+        "delete_replies_after": 30_i32,
+        "modlog_retention": 365_i32,
+        "new_account_threshold": 24_i64,
+        "features": 95_i64,
+        "sentinel_alerts": true,
+        "screening_aware": true,
+        "watch_duration": 30_i32,
+        "milestones_chan": 30_i64,
+        "onboarded": true,
     };
 
     assert_eq!(bson::to_document(&guild).unwrap(), expected);
     assert_eq!(bson::from_document::<GuildConfig>(expected).unwrap(), guild);
 }
+
+#[test]
+fn test_outbound_webhook_bson() {
+    let mut guild = GuildConfig::new(Id::new(1));
+    guild.outbound_webhook = Some(OutboundWebhook {
+        url: "https://example.com/hooks/raidprotect".parse().unwrap(),
+        secret: "secret".to_owned(),
+        failures: 3,
+    });
+
+    let document = bson::to_document(&guild).unwrap();
+    assert_eq!(
+        document.get_document("outbound_webhook").unwrap(),
+        &bson::doc! {
+            "url": "https://example.com/hooks/raidprotect",
+            "secret": "secret",
+            "failures": 3_i64,
+        }
+    );
+    assert_eq!(bson::from_document::<GuildConfig>(document).unwrap(), guild);
+}
+
+#[test]
+fn test_sanction_allowed() {
+    let mut config = ModerationConfig::default();
+    assert!(config.sanction_allowed(&[], ModlogType::Ban));
+
+    config.sanction_roles = vec![
+        SanctionRole {
+            role_id: Id::new(1),
+            sanctions: vec![ModlogType::Mute, ModlogType::Unmute],
+            shadow: false,
+        },
+        SanctionRole {
+            role_id: Id::new(2),
+            sanctions: vec![ModlogType::Kick, ModlogType::Ban],
+            shadow: true,
+        },
+    ];
+
+    assert!(config.sanction_allowed(&[Id::new(1)], ModlogType::Mute));
+    assert!(!config.sanction_allowed(&[Id::new(1)], ModlogType::Ban));
+    assert!(config.sanction_allowed(&[Id::new(1), Id::new(2)], ModlogType::Ban));
+    assert!(!config.sanction_allowed(&[Id::new(3)], ModlogType::Mute));
+}
+
+#[test]
+fn test_shadow_allowed() {
+    let mut config = ModerationConfig::default();
+    assert!(!config.shadow_allowed(&[Id::new(1)]));
+
+    config.sanction_roles = vec![
+        SanctionRole {
+            role_id: Id::new(1),
+            sanctions: vec![ModlogType::Mute],
+            shadow: false,
+        },
+        SanctionRole {
+            role_id: Id::new(2),
+            sanctions: vec![ModlogType::Ban],
+            shadow: true,
+        },
+    ];
+
+    assert!(!config.shadow_allowed(&[Id::new(1)]));
+    assert!(config.shadow_allowed(&[Id::new(1), Id::new(2)]));
+    assert!(!config.shadow_allowed(&[]));
+}
+
+#[test]
+fn test_confirmation_required() {
+    let mut config = ModerationConfig::default();
+    assert!(config.confirmation_required(ModlogType::Mute));
+
+    config.skip_confirmation = vec![ModlogType::Mute, ModlogType::Ban];
+
+    assert!(!config.confirmation_required(ModlogType::Mute));
+    assert!(config.confirmation_required(ModlogType::Unmute));
+    assert!(config.confirmation_required(ModlogType::Ban));
+}
+
+#[test]
+fn test_fast_mode() {
+    let mut config = ModerationConfig::default();
+
+    assert!(!config.fast_mode(&[Id::new(1)]));
+
+    config.skip_confirmation_roles = vec![Id::new(1), Id::new(2)];
+
+    assert!(config.fast_mode(&[Id::new(3), Id::new(2)]));
+    assert!(!config.fast_mode(&[Id::new(3)]));
+    assert!(!config.fast_mode(&[]));
+}
+
+#[test]
+fn test_dm_enabled() {
+    let mut config = ModerationConfig::default();
+    assert!(config.dm_enabled(ModlogType::Ban));
+
+    config.dm_skipped = vec![ModlogType::Mute];
+
+    assert!(!config.dm_enabled(ModlogType::Mute));
+    assert!(config.dm_enabled(ModlogType::Ban));
+
+    // The guild-wide setting takes precedence.
+    config.dm_disabled = true;
+    assert!(!config.dm_enabled(ModlogType::Ban));
+}
+
+#[test]
+fn test_announcement() {
+    let mut config = ModerationConfig {
+        announced: vec![ModlogType::Ban],
+        ..Default::default()
+    };
+    assert_eq!(config.announcement(ModlogType::Ban), None);
+
+    config.announcement_channel = Some(Id::new(1));
+
+    assert_eq!(config.announcement(ModlogType::Ban), Some(Id::new(1)));
+    assert_eq!(config.announcement(ModlogType::Kick), None);
+}
+
+#[test]
+fn test_link_filter_allowed() {
+    let config = LinkFilterConfig {
+        allowed_domains: vec!["raidprotect.org".to_owned()],
+        allowed_invites: vec!["raidprotect".to_owned()],
+        ..Default::default()
+    };
+
+    assert!(config.domain_allowed("raidprotect.org"));
+    assert!(config.domain_allowed("docs.RaidProtect.org"));
+    assert!(!config.domain_allowed("notraidprotect.org"));
+    assert!(!config.domain_allowed("raidprotect.org.evil.com"));
+
+    assert!(config.invite_allowed("RaidProtect"));
+    assert!(!config.invite_allowed("other"));
+}
+
+#[test]
+fn test_channel_rule() {
+    let mut config = GuildConfig::new(Id::new(1));
+    config.channel_rules = vec![ChannelRule {
+        channel_id: Id::new(2),
+        kind: ChannelRuleKind::LinkOnlyFrom {
+            domains: vec!["youtube.com".to_owned()],
+        },
+    }];
+
+    let rule = config.channel_rule(Id::new(2)).unwrap();
+    assert!(rule.kind.domain_allowed("www.YouTube.com"));
+    assert!(!rule.kind.domain_allowed("notyoutube.com"));
+    assert!(config.channel_rule(Id::new(3)).is_none());
+    assert!(!ChannelRuleKind::MediaOnly.domain_allowed("youtube.com"));
+}
+
+#[test]
+fn test_tag() {
+    let config = TagsConfig {
+        tags: vec![
+            Tag {
+                name: "Rules".to_owned(),
+                content: "Please read the rules.".to_owned(),
+            },
+            Tag {
+                name: "faq".to_owned(),
+                content: "See the FAQ.".to_owned(),
+            },
+        ],
+        manager_roles: vec![Id::new(1)],
+    };
+
+    assert_eq!(
+        config.tag(" rules ").unwrap().content,
+        "Please read the rules."
+    );
+    assert_eq!(config.tag("FAQ").unwrap().name, "faq");
+    assert_eq!(config.tag("missing"), None);
+    assert_eq!(TagsConfig::default().tag("rules"), None);
+
+    assert!(config.can_manage(&[Id::new(2), Id::new(1)]));
+    assert!(!config.can_manage(&[Id::new(2)]));
+    assert!(!config.can_manage(&[]));
+}
+
+#[test]
+fn test_automod_exempt() {
+    let mut config = GuildConfig::new(Id::new(1));
+    config.automod_ignored_roles = vec![Id::new(2)];
+    let trusted = [Id::new(3)];
+
+    // Either list is enough to be exempted.
+    assert!(config.is_automod_exempt(&trusted, &[Id::new(2)]));
+    assert!(config.is_automod_exempt(&trusted, &[Id::new(3)]));
+    assert!(config.is_automod_exempt(&trusted, &[Id::new(2), Id::new(3)]));
+    assert!(!config.is_automod_exempt(&trusted, &[Id::new(4)]));
+    assert!(!config.is_automod_exempt(&trusted, &[]));
+
+    // Ignored roles apply to filters without trusted roles.
+    assert!(config.is_automod_exempt(&[], &[Id::new(2)]));
+    assert!(!config.is_automod_exempt(&[], &[Id::new(3)]));
+
+    // Trusted roles of a filter do not apply to other filters.
+    config.automod_ignored_roles.clear();
+    assert!(!config.is_automod_exempt(&[], &[Id::new(2), Id::new(3)]));
+}
+
+#[test]
+fn test_outbound_webhook_debug_redacted() {
+    let webhook = OutboundWebhook {
+        url: "https://example.com/hooks".parse().unwrap(),
+        secret: "5bdcc146bf60754e6a042426089575c7".to_owned(),
+        failures: 0,
+    };
+
+    let debug = format!("{webhook:?}");
+
+    assert!(debug.contains("https://example.com/hooks"));
+    assert!(debug.contains("[redacted]"));
+    assert!(!debug.contains("5bdcc146bf60754e6a042426089575c7"));
+}
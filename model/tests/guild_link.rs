@@ -0,0 +1,40 @@
+use mongodb::bson::{self, DateTime};
+use pretty_assertions::assert_eq;
+use raidprotect_model::database::model::GuildLink;
+use time::OffsetDateTime;
+use twilight_model::id::Id;
+
+#[test]
+fn test_guild_link_bson() {
+    let link = GuildLink {
+        id: None,
+        source: Id::new(1),
+        target: Id::new(2),
+        created_by: Id::new(3),
+        date: OffsetDateTime::from_unix_timestamp(1_628_594_197).unwrap(),
+    };
+
+    let expected = bson::doc! {
+        "source": 1_i64,
+        "target": 2_i64,
+        "created_by": 3_i64,
+        "date": DateTime::from_millis(1_628_594_197),
+    };
+
+    assert_eq!(bson::to_document(&link).unwrap(), expected);
+    assert_eq!(bson::from_document::<GuildLink>(expected).unwrap(), link);
+}
+
+#[test]
+fn test_guild_link_other() {
+    let link = GuildLink {
+        id: None,
+        source: Id::new(1),
+        target: Id::new(2),
+        created_by: Id::new(3),
+        date: OffsetDateTime::from_unix_timestamp(1_628_594_197).unwrap(),
+    };
+
+    assert_eq!(link.other(Id::new(1)), Id::new(2));
+    assert_eq!(link.other(Id::new(2)), Id::new(1));
+}
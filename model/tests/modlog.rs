@@ -1,6 +1,9 @@
 use mongodb::bson::{self, oid::ObjectId, DateTime};
 use pretty_assertions::assert_eq;
-use raidprotect_model::database::model::{Modlog, ModlogType, ModlogUser};
+use raidprotect_model::database::model::{
+    AmendmentKind, DmStatus, Modlog, ModlogAmendment, ModlogSearch, ModlogType, ModlogUser,
+    SyncOrigin,
+};
 use serde_test::{assert_tokens, Configure, Token};
 use time::OffsetDateTime;
 use twilight_model::{id::Id, util::ImageHash};
@@ -28,6 +31,13 @@ fn test_modlog_full() {
         date: OffsetDateTime::from_unix_timestamp(1_628_594_197_123).unwrap(),
         reason: Some("reason".to_owned()),
         notes: Some("notes".to_owned()),
+        pre_emptive: false,
+        confirmation_skipped: false,
+        shadow: false,
+        dm_disabled: false,
+        dm_status: None,
+        synced_from: None,
+        amendments: Vec::new(),
     };
 
     assert_tokens(
@@ -146,6 +156,13 @@ fn test_modlog_bson() {
         date: OffsetDateTime::from_unix_timestamp(1_628_594_197_123).unwrap(),
         reason: Some("reason".to_owned()),
         notes: Some("notes".to_owned()),
+        pre_emptive: false,
+        confirmation_skipped: false,
+        shadow: false,
+        dm_disabled: false,
+        dm_status: Some(DmStatus::Delivered),
+        synced_from: None,
+        amendments: Vec::new(),
     };
 
     let expected = bson::doc! {
@@ -167,8 +184,185 @@ fn test_modlog_bson() {
         "date": DateTime::from_millis(1_628_594_197_123),
         "reason": "reason",
         "notes": "notes",
+        "dm_status": "delivered",
     };
 
     assert_eq!(bson::to_document(&modlog).unwrap(), expected);
     assert_eq!(bson::from_document::<Modlog>(expected).unwrap(), modlog);
 }
+
+#[test]
+fn test_modlog_pre_emptive_bson() {
+    let modlog = Modlog {
+        id: None,
+        kind: ModlogType::Ban,
+        guild_id: Id::new(1),
+        user: ModlogUser {
+            id: Id::new(2),
+            name: "username".to_owned(),
+            discriminator: 1234,
+            avatar: Some(ImageHash::parse("1acefe340fafb4ecefae407f3abdb323".as_bytes()).unwrap()),
+        },
+        moderator: ModlogUser {
+            id: Id::new(3),
+            name: "moderator".to_owned(),
+            discriminator: 4567,
+            avatar: Some(
+                ImageHash::parse("a_b2a6536641da91a0b59bd66557c56c36".as_bytes()).unwrap(),
+            ),
+        },
+        date: OffsetDateTime::from_unix_timestamp(1_628_594_197_123).unwrap(),
+        reason: None,
+        notes: None,
+        pre_emptive: true,
+        confirmation_skipped: true,
+        shadow: true,
+        dm_disabled: true,
+        dm_status: None,
+        synced_from: Some(SyncOrigin {
+            guild_id: Id::new(4),
+            case_id: ObjectId::parse_str("62aca55a551e9a0102351bda").unwrap(),
+        }),
+        amendments: Vec::new(),
+    };
+
+    let expected = bson::doc! {
+        "kind": "ban",
+        "guild_id": 1_i64,
+        "user": {
+            "id": 2_i64,
+            "name": "username",
+            "discriminator": 1234_i32,
+            "avatar": "1acefe340fafb4ecefae407f3abdb323",
+        },
+        "moderator": {
+            "id": 3_i64,
+            "name": "moderator",
+            "discriminator": 4567_i32,
+            "avatar": "a_b2a6536641da91a0b59bd66557c56c36",
+        },
+        "date": DateTime::from_millis(1_628_594_197_123),
+        "pre_emptive": true,
+        "confirmation_skipped": true,
+        "shadow": true,
+        "dm_disabled": true,
+        "synced_from": {
+            "guild_id": 4_i64,
+            "case_id": ObjectId::parse_str("62aca55a551e9a0102351bda").unwrap(),
+        },
+    };
+
+    assert_eq!(bson::to_document(&modlog).unwrap(), expected);
+    assert_eq!(bson::from_document::<Modlog>(expected).unwrap(), modlog);
+}
+
+#[test]
+fn test_modlog_amendments_bson() {
+    let user = |id| ModlogUser {
+        id: Id::new(id),
+        name: "username".to_owned(),
+        discriminator: 1234,
+        avatar: None,
+    };
+    let modlog = Modlog {
+        id: None,
+        kind: ModlogType::Mute,
+        guild_id: Id::new(1),
+        user: user(2),
+        moderator: user(3),
+        date: OffsetDateTime::from_unix_timestamp(1_628_594_197).unwrap(),
+        reason: None,
+        notes: None,
+        pre_emptive: false,
+        confirmation_skipped: false,
+        shadow: false,
+        dm_disabled: false,
+        dm_status: None,
+        synced_from: None,
+        amendments: vec![
+            ModlogAmendment {
+                kind: AmendmentKind::Extend,
+                actor: user(4),
+                date: OffsetDateTime::from_unix_timestamp(1_628_594_200).unwrap(),
+                expires_at: Some(OffsetDateTime::from_unix_timestamp(1_628_601_397).unwrap()),
+            },
+            ModlogAmendment {
+                kind: AmendmentKind::Lift,
+                actor: user(4),
+                date: OffsetDateTime::from_unix_timestamp(1_628_594_300).unwrap(),
+                expires_at: None,
+            },
+        ],
+    };
+
+    let user_doc = |id: i64| bson::doc! { "id": id, "name": "username", "discriminator": 1234_i32 };
+    let expected = bson::doc! {
+        "kind": "mute",
+        "guild_id": 1_i64,
+        "user": user_doc(2),
+        "moderator": user_doc(3),
+        "date": DateTime::from_millis(1_628_594_197),
+        "amendments": [
+            {
+                "kind": "extend",
+                "actor": user_doc(4),
+                "date": DateTime::from_millis(1_628_594_200),
+                "expires_at": DateTime::from_millis(1_628_601_397),
+            },
+            {
+                "kind": "lift",
+                "actor": user_doc(4),
+                "date": DateTime::from_millis(1_628_594_300),
+            },
+        ],
+    };
+
+    assert_eq!(bson::to_document(&modlog).unwrap(), expected);
+    assert_eq!(bson::from_document::<Modlog>(expected).unwrap(), modlog);
+}
+
+#[test]
+fn test_modlog_type_name() {
+    for kind in [
+        ModlogType::Ban,
+        ModlogType::Kick,
+        ModlogType::Mute,
+        ModlogType::Unmute,
+        ModlogType::Quarantine,
+        ModlogType::Release,
+    ] {
+        assert_eq!(bson::to_bson(&kind).unwrap(), bson::Bson::from(kind.name()));
+    }
+}
+
+#[test]
+fn test_modlog_search_query() {
+    let search = ModlogSearch::default();
+    assert!(search.is_empty());
+    assert_eq!(search.query(Id::new(1)), bson::doc! { "guild_id": 1_i64 });
+
+    let search = ModlogSearch {
+        reason: Some("scam \"link\"".to_owned()),
+        kind: Some(ModlogType::Ban),
+        moderator: Some(Id::new(2)),
+        user: Some(Id::new(3)),
+        after: Some(OffsetDateTime::from_unix_timestamp(1_628_594_197).unwrap()),
+        before: Some(OffsetDateTime::from_unix_timestamp(1_628_594_198).unwrap()),
+    };
+    assert!(!search.is_empty());
+
+    assert_eq!(
+        search.query(Id::new(1)),
+        bson::doc! {
+            "guild_id": 1_i64,
+            "$text": { "$search": r#""scam link""# },
+            "kind": "ban",
+            "moderator.id": 2_i64,
+            "user.id": 3_i64,
+            "date": {
+                "$gte": DateTime::from_millis(1_628_594_197),
+                "$lt": DateTime::from_millis(1_628_594_198),
+            },
+        }
+    );
+}
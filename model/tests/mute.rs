@@ -0,0 +1,47 @@
+use mongodb::bson::{self, oid::ObjectId, DateTime};
+use pretty_assertions::assert_eq;
+use raidprotect_model::database::model::ActiveMute;
+use time::OffsetDateTime;
+use twilight_model::id::Id;
+
+#[test]
+fn test_mute_bson() {
+    let mute = ActiveMute {
+        guild_id: Id::new(1),
+        user_id: Id::new(2),
+        expires_at: Some(OffsetDateTime::from_unix_timestamp(1_628_594_197_123).unwrap()),
+        timeout: true,
+        case_id: Some(ObjectId::parse_str("62aca55a551e9a0102351bda").unwrap()),
+    };
+
+    let expected = bson::doc! {
+        "guild_id": 1_i64,
+        "user_id": 2_i64,
+        "expires_at": DateTime::from_millis(1_628_594_197_123),
+        "timeout": true,
+        "case_id": ObjectId::parse_str("62aca55a551e9a0102351bda").unwrap(),
+    };
+
+    assert_eq!(bson::to_document(&mute).unwrap(), expected);
+    assert_eq!(bson::from_document::<ActiveMute>(expected).unwrap(), mute);
+}
+
+#[test]
+fn test_mute_permanent_bson() {
+    let mute = ActiveMute {
+        guild_id: Id::new(1),
+        user_id: Id::new(2),
+        expires_at: None,
+        timeout: false,
+        case_id: None,
+    };
+
+    let expected = bson::doc! {
+        "guild_id": 1_i64,
+        "user_id": 2_i64,
+        "timeout": false,
+    };
+
+    assert_eq!(bson::to_document(&mute).unwrap(), expected);
+    assert_eq!(bson::from_document::<ActiveMute>(expected).unwrap(), mute);
+}
@@ -0,0 +1,28 @@
+use mongodb::bson::{self, DateTime};
+use pretty_assertions::assert_eq;
+use raidprotect_model::database::model::QuarantineState;
+use time::OffsetDateTime;
+use twilight_model::id::Id;
+
+#[test]
+fn test_quarantine_bson() {
+    let quarantine = QuarantineState {
+        guild_id: Id::new(1),
+        user_id: Id::new(2),
+        roles: vec![Id::new(3), Id::new(4)],
+        date: OffsetDateTime::from_unix_timestamp(1_628_594_197_123).unwrap(),
+    };
+
+    let expected = bson::doc! {
+        "guild_id": 1_i64,
+        "user_id": 2_i64,
+        "roles": [3_i64, 4_i64],
+        "date": DateTime::from_millis(1_628_594_197_123),
+    };
+
+    assert_eq!(bson::to_document(&quarantine).unwrap(), expected);
+    assert_eq!(
+        bson::from_document::<QuarantineState>(expected).unwrap(),
+        quarantine
+    );
+}
@@ -0,0 +1,74 @@
+use mongodb::bson::{self, DateTime};
+use pretty_assertions::assert_eq;
+use raidprotect_model::database::model::{ModlogUser, Report, Reporter};
+use time::OffsetDateTime;
+use twilight_model::id::Id;
+
+fn report() -> Report {
+    Report {
+        id: None,
+        guild_id: Id::new(1),
+        channel_id: Id::new(2),
+        message_id: Id::new(3),
+        author: ModlogUser {
+            id: Id::new(4),
+            name: "username".to_owned(),
+            discriminator: 1234,
+            avatar: None,
+        },
+        content: "message content".to_owned(),
+        reporters: vec![Reporter {
+            user_id: Id::new(5),
+            reason: Some("spam".to_owned()),
+            date: OffsetDateTime::from_unix_timestamp(1_628_594_197).unwrap(),
+        }],
+        alert_id: Some(Id::new(6)),
+        created_at: OffsetDateTime::from_unix_timestamp(1_628_594_197).unwrap(),
+        resolved_by: None,
+    }
+}
+
+#[test]
+fn test_report_bson() {
+    let report = report();
+
+    let expected = bson::doc! {
+        "guild_id": 1_i64,
+        "channel_id": 2_i64,
+        "message_id": 3_i64,
+        "author": {
+            "id": 4_i64,
+            "name": "username",
+            "discriminator": 1234_i32,
+        },
+        "content": "message content",
+        "reporters": [{
+            "user_id": 5_i64,
+            "reason": "spam",
+            "date": DateTime::from_millis(1_628_594_197),
+        }],
+        "alert_id": 6_i64,
+        "created_at": DateTime::from_millis(1_628_594_197),
+    };
+
+    assert_eq!(bson::to_document(&report).unwrap(), expected);
+    assert_eq!(bson::from_document::<Report>(expected).unwrap(), report);
+}
+
+#[test]
+fn test_report_add_reporter() {
+    let mut report = report();
+    let reporter = |id| Reporter {
+        user_id: Id::new(id),
+        reason: None,
+        date: OffsetDateTime::from_unix_timestamp(1_628_594_297).unwrap(),
+    };
+
+    assert!(!report.add_reporter(reporter(5)));
+    assert!(report.add_reporter(reporter(7)));
+    assert_eq!(report.reporters.len(), 2);
+
+    assert!(!report.is_resolved());
+    report.resolved_by = Some(Id::new(8));
+    assert!(report.is_resolved());
+}
@@ -0,0 +1,43 @@
+use mongodb::bson;
+use pretty_assertions::assert_eq;
+use raidprotect_model::database::model::{ChannelSnapshot, RoleSnapshot, StructureSnapshot};
+use time::OffsetDateTime;
+use twilight_model::{channel::ChannelType, guild::Permissions, id::Id};
+
+#[test]
+fn test_snapshot_bson() {
+    let snapshot = StructureSnapshot {
+        id: None,
+        guild_id: Id::new(1),
+        date: OffsetDateTime::from_unix_timestamp(1_628_594_197).unwrap(),
+        channels: vec![ChannelSnapshot {
+            id: Id::new(2),
+            name: "general".to_owned(),
+            kind: ChannelType::GuildText,
+            position: Some(0),
+            parent_id: None,
+            topic: None,
+            nsfw: false,
+            rate_limit_per_user: None,
+            permission_overwrites: Vec::new(),
+        }],
+        roles: vec![RoleSnapshot {
+            id: Id::new(3),
+            name: "Moderator".to_owned(),
+            color: 0,
+            hoist: true,
+            mentionable: false,
+            permissions: Permissions::empty(),
+            position: 1,
+        }],
+    };
+
+    let document = bson::to_document(&snapshot).unwrap();
+
+    assert_eq!(document.get_i64("guild_id").unwrap(), 1);
+    assert!(!document.contains_key("_id"));
+    assert_eq!(
+        bson::from_document::<StructureSnapshot>(document).unwrap(),
+        snapshot
+    );
+}
@@ -0,0 +1,107 @@
+use mongodb::bson;
+use pretty_assertions::assert_eq;
+use raidprotect_model::database::model::{
+    DailyStats, GuildStats, ModlogType, StatsCounters, StatsEvent, StatsFilter,
+};
+use twilight_model::id::Id;
+
+#[test]
+fn test_stats_bson() {
+    let mut counters = StatsCounters::default();
+    counters.add(StatsEvent::Sanction(ModlogType::Mute));
+    counters.add(StatsEvent::Filter(StatsFilter::VoiceHop));
+    counters.add(StatsEvent::MessagesDeleted(12));
+    counters.add(StatsEvent::FalsePositive(StatsFilter::Link));
+
+    let stats = GuildStats {
+        guild_id: Id::new(1),
+        version: 3,
+        totals: counters,
+        days: vec![DailyStats {
+            day: 19_000,
+            counters,
+        }],
+    };
+
+    let counters_doc = bson::doc! {
+        "sanctions": {
+            "ban": 0_i64,
+            "kick": 0_i64,
+            "mute": 1_i64,
+            "unmute": 0_i64,
+            "quarantine": 0_i64,
+            "release": 0_i64,
+        },
+        "filters": {
+            "reaction_spam": 0_i64,
+            "voice_hop": 1_i64,
+            "name": 0_i64,
+            "link": 0_i64,
+            "pattern": 0_i64,
+            "first_message": 0_i64,
+        },
+        "false_positives": {
+            "reaction_spam": 0_i64,
+            "voice_hop": 0_i64,
+            "name": 0_i64,
+            "link": 1_i64,
+            "pattern": 0_i64,
+            "first_message": 0_i64,
+        },
+        "raids": 0_i64,
+        "messages_deleted": 12_i64,
+    };
+    let expected = bson::doc! {
+        "_id": 1_i64,
+        "version": 3_i64,
+        "totals": counters_doc.clone(),
+        "days": [{
+            "day": 19_000_i64,
+            "counters": counters_doc,
+        }],
+    };
+
+    assert_eq!(bson::to_document(&stats).unwrap(), expected);
+    assert_eq!(bson::from_document::<GuildStats>(expected).unwrap(), stats);
+}
+
+#[test]
+fn test_stats_missing_counters() {
+    let document = bson::doc! {
+        "_id": 1_i64,
+        "totals": { "raids": 2_i32 },
+    };
+    let stats = bson::from_document::<GuildStats>(document).unwrap();
+
+    assert_eq!(stats.totals.raids, 2);
+    assert_eq!(stats.totals.sanctions.ban, 0);
+    assert!(stats.days.is_empty());
+}
+
+#[test]
+fn test_stats_false_positives() {
+    let mut counters = StatsCounters::default();
+    counters.add(StatsEvent::Filter(StatsFilter::Pattern));
+    counters.add(StatsEvent::FalsePositive(StatsFilter::Pattern));
+
+    let mut merged = StatsCounters::default();
+    merged.merge(&counters);
+    merged.merge(&counters);
+
+    assert_eq!(merged.false_positives.pattern, 2);
+    assert_eq!(merged.false_positives.total(), 2);
+    assert_eq!(merged.total(), 2);
+}
+
+#[test]
+fn test_stats_filter_id() {
+    for filter in StatsFilter::ALL {
+        assert_eq!(StatsFilter::from_id(filter.id()), Some(filter));
+        assert_eq!(
+            bson::to_bson(&filter).unwrap(),
+            bson::Bson::String(filter.id().to_owned())
+        );
+    }
+
+    assert_eq!(StatsFilter::from_id("unknown"), None);
+}
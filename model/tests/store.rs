@@ -0,0 +1,400 @@
+//! Parity tests of the stores.
+//!
+//! Each check is run against the in-memory stores, and against the MongoDB
+//! and Redis clients. Tests that require a server are ignored by default, run
+//! them with `cargo test -- --ignored`. The servers uri can be set with the
+//! `MONGODB_URI` and `REDIS_URI` environment variables.
+
+use std::{collections::HashSet, env};
+
+use raidprotect_model::{
+    cache::{
+        discord::{permission::GuildPermissions, CachedGuild, CachedRole, CurrentMember},
+        model::{
+            interaction::PendingAutomodOverwrite, maintenance::Maintenance,
+            sanction::SanctionCooldown,
+        },
+        CacheClient,
+    },
+    database::{
+        model::{GuildConfig, Modlog, ModlogSearch, ModlogType, ModlogUser},
+        DbClient,
+    },
+    store::{
+        memory::{MemoryCacheStore, MemoryGuildStore, MemoryModlogStore},
+        CacheStore, GuildStore, ModlogStore,
+    },
+};
+use time::{Duration, OffsetDateTime};
+use twilight_model::{
+    guild::Permissions,
+    id::{
+        marker::{GuildMarker, RoleMarker},
+        Id,
+    },
+};
+
+async fn db() -> DbClient {
+    let uri = env::var("MONGODB_URI").unwrap_or_else(|_| "mongodb://localhost:27017".to_owned());
+
+    DbClient::connect(&uri, "raidprotect-test".to_owned())
+        .await
+        .unwrap()
+}
+
+async fn cache() -> CacheClient {
+    let uri = env::var("REDIS_URI").unwrap_or_else(|_| "redis://localhost:6379".to_owned());
+
+    CacheClient::connect(&uri).await.unwrap()
+}
+
+/// Guild id that is not used by previous runs of the tests.
+fn unique_guild_id() -> Id<GuildMarker> {
+    let nanos = OffsetDateTime::now_utc().unix_timestamp_nanos();
+
+    Id::new(nanos as u64)
+}
+
+async fn check_guild_store(store: &impl GuildStore, guild_id: Id<GuildMarker>) {
+    let mut config = store.get_guild_or_create(guild_id).await.unwrap();
+    assert_eq!(config, GuildConfig::new(guild_id));
+
+    config.lang = "fr".to_owned();
+    config.automod_ignored_roles = vec![Id::new(2)];
+    store.update_guild(&config).await.unwrap();
+
+    let updated = store.get_guild_or_create(guild_id).await.unwrap();
+    assert_eq!(updated, config);
+}
+
+fn modlog(
+    guild_id: Id<GuildMarker>,
+    kind: ModlogType,
+    date: OffsetDateTime,
+    reason: Option<&str>,
+) -> Modlog {
+    let user = ModlogUser {
+        id: Id::new(1),
+        name: "user".to_owned(),
+        discriminator: 1234,
+        avatar: None,
+    };
+
+    Modlog {
+        id: None,
+        kind,
+        guild_id,
+        user: user.clone(),
+        moderator: user,
+        date,
+        reason: reason.map(ToOwned::to_owned),
+        notes: None,
+        pre_emptive: false,
+        confirmation_skipped: false,
+        shadow: false,
+        dm_disabled: false,
+        dm_status: None,
+        synced_from: None,
+        amendments: Vec::new(),
+    }
+}
+
+async fn check_modlog_store(store: &impl ModlogStore, guild_id: Id<GuildMarker>) {
+    // Dates are stored with a millisecond precision in the database.
+    let date = OffsetDateTime::from_unix_timestamp(1_628_594_197).unwrap();
+    let modlogs = [
+        modlog(guild_id, ModlogType::Ban, date, Some("Scam link")),
+        modlog(
+            guild_id,
+            ModlogType::Kick,
+            date + Duration::HOUR,
+            Some("spam"),
+        ),
+        modlog(guild_id, ModlogType::Ban, date + Duration::hours(2), None),
+    ];
+
+    let mut ids = Vec::new();
+    for modlog in &modlogs {
+        ids.push(store.create_modlog(modlog).await.unwrap());
+    }
+    store
+        .create_modlog(&modlog(Id::new(1), ModlogType::Ban, date, Some("scam")))
+        .await
+        .unwrap();
+
+    let stored = store.get_modlog(ids[0]).await.unwrap().unwrap();
+    assert_eq!(stored.id, Some(ids[0]));
+    assert_eq!(Modlog { id: None, ..stored }, modlogs[0]);
+
+    // Modlogs are returned most recent first.
+    let (page, total) = store.find_modlogs_page(guild_id, 0, 2).await.unwrap();
+    assert_eq!(total, 3);
+    assert_eq!(
+        page.iter()
+            .map(|modlog| modlog.id.unwrap())
+            .collect::<Vec<_>>(),
+        [ids[2], ids[1]]
+    );
+
+    let (page, _) = store.find_modlogs_page(guild_id, 1, 2).await.unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].id, Some(ids[0]));
+
+    let search = |search: ModlogSearch, limit: i64| async move {
+        store
+            .search_modlogs(guild_id, &search, limit)
+            .await
+            .unwrap()
+            .iter()
+            .map(|modlog| modlog.id.unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    let reason = ModlogSearch {
+        reason: Some("SCAM".to_owned()),
+        ..Default::default()
+    };
+    assert_eq!(search(reason, 10).await, [ids[0]]);
+
+    // Reasons are searched as phrases.
+    let reversed = ModlogSearch {
+        reason: Some("link scam".to_owned()),
+        ..Default::default()
+    };
+    assert!(search(reversed, 10).await.is_empty());
+
+    let kind = ModlogSearch {
+        kind: Some(ModlogType::Ban),
+        ..Default::default()
+    };
+    assert_eq!(search(kind.clone(), 10).await, [ids[2], ids[0]]);
+    assert_eq!(search(kind, 1).await, [ids[2]]);
+
+    let user = ModlogSearch {
+        user: Some(Id::new(1)),
+        ..Default::default()
+    };
+    assert_eq!(search(user, 10).await, [ids[2], ids[1], ids[0]]);
+
+    let moderator = ModlogSearch {
+        moderator: Some(Id::new(2)),
+        ..Default::default()
+    };
+    assert!(search(moderator, 10).await.is_empty());
+
+    let dates = ModlogSearch {
+        after: Some(date + Duration::HOUR),
+        before: Some(date + Duration::hours(2)),
+        ..Default::default()
+    };
+    assert_eq!(search(dates, 10).await, [ids[1]]);
+}
+
+async fn check_cache_store(store: &impl CacheStore, interaction_id: u64) {
+    let pending = PendingAutomodOverwrite {
+        interaction_id: Id::new(interaction_id),
+        author_id: Id::new(2),
+        guild_id: Id::new(3),
+    };
+    let id = interaction_id.to_string();
+
+    let value = store.get::<PendingAutomodOverwrite>(&id).await.unwrap();
+    assert!(value.is_none());
+
+    store.set(&pending).await.unwrap();
+    let value = store.get::<PendingAutomodOverwrite>(&id).await.unwrap();
+    assert_eq!(value.map(|value| value.author_id), Some(Id::new(2)));
+
+    assert!(store.consume(&pending).await.unwrap());
+    assert!(!store.consume(&pending).await.unwrap());
+
+    store.set(&pending).await.unwrap();
+    store.delete(&pending).await.unwrap();
+    let value = store.get::<PendingAutomodOverwrite>(&id).await.unwrap();
+    assert!(value.is_none());
+
+    // Missing values are returned as `None`, in the order of the ids.
+    store.set(&pending).await.unwrap();
+    let values = store
+        .get_many::<PendingAutomodOverwrite>(&["0".to_owned(), id.clone()])
+        .await
+        .unwrap();
+    assert!(values[0].is_none());
+    assert_eq!(
+        values[1].as_ref().map(|value| value.author_id),
+        Some(Id::new(2))
+    );
+    store.delete(&pending).await.unwrap();
+}
+
+async fn check_cache_store_expiry(store: &impl CacheStore, guild_id: Id<GuildMarker>) {
+    let cooldown = |moderator: u64| SanctionCooldown {
+        guild_id,
+        user_id: Id::new(1),
+        moderator_id: Id::new(moderator),
+        kind: ModlogType::Kick,
+        date: OffsetDateTime::from_unix_timestamp(1_628_594_197).unwrap(),
+    };
+    let id = (guild_id, Id::new(1));
+
+    assert!(store.set_if_absent(&cooldown(2), 30).await.unwrap());
+    assert!(!store.set_if_absent(&cooldown(3), 30).await.unwrap());
+
+    let value = store.get::<SanctionCooldown>(&id).await.unwrap();
+    assert_eq!(value, Some(cooldown(2)));
+
+    store.set_with_expiry(&cooldown(3), 30).await.unwrap();
+    let value = store.get::<SanctionCooldown>(&id).await.unwrap();
+    assert_eq!(value, Some(cooldown(3)));
+
+    store.delete(&cooldown(3)).await.unwrap();
+}
+
+async fn check_maintenance(store: &impl CacheStore) {
+    Maintenance::set_enabled(store, true).await.unwrap();
+    assert!(Maintenance::is_enabled(store).await.unwrap());
+
+    Maintenance::set_enabled(store, false).await.unwrap();
+    assert!(!Maintenance::is_enabled(store).await.unwrap());
+}
+
+fn role(
+    guild_id: Id<GuildMarker>,
+    id: Id<RoleMarker>,
+    position: i64,
+    permissions: Permissions,
+) -> CachedRole {
+    CachedRole {
+        id,
+        guild_id,
+        name: format!("role-{position}"),
+        color: 0,
+        icon: None,
+        unicode_emoji: None,
+        position,
+        permissions,
+        managed: false,
+    }
+}
+
+async fn check_permissions(store: &impl CacheStore, guild_id: Id<GuildMarker>) {
+    let everyone = role(guild_id, guild_id.cast(), 0, Permissions::VIEW_CHANNEL);
+    let moderator = role(
+        guild_id,
+        Id::new(guild_id.get() + 1),
+        2,
+        Permissions::KICK_MEMBERS,
+    );
+    let bot = role(
+        guild_id,
+        Id::new(guild_id.get() + 2),
+        1,
+        Permissions::BAN_MEMBERS,
+    );
+
+    let guild = CachedGuild {
+        id: guild_id,
+        unavailable: false,
+        name: "guild".to_owned(),
+        icon: None,
+        owner_id: Id::new(1),
+        member_count: 3,
+        current_member: Some(CurrentMember {
+            id: Id::new(3),
+            communication_disabled_until: None,
+            roles: HashSet::from([bot.id]),
+        }),
+        roles: HashSet::from([everyone.id, moderator.id, bot.id]),
+        channels: HashSet::new(),
+    };
+
+    for role in [&everyone, &moderator, &bot] {
+        store.set(role).await.unwrap();
+    }
+    store.set(&guild).await.unwrap();
+
+    let permissions = GuildPermissions::new(store, guild_id).await.unwrap();
+
+    let member = permissions
+        .member(Id::new(2), &[moderator.id])
+        .await
+        .unwrap();
+    assert!(!member.is_owner());
+    assert_eq!(
+        member.guild(),
+        Permissions::VIEW_CHANNEL | Permissions::KICK_MEMBERS
+    );
+
+    let current = permissions.current_member().await.unwrap();
+    assert_eq!(
+        current.guild(),
+        Permissions::VIEW_CHANNEL | Permissions::BAN_MEMBERS
+    );
+    assert!(member.highest_role() > current.highest_role());
+
+    let owner = permissions.member(Id::new(1), &[]).await.unwrap();
+    assert!(owner.is_owner());
+    assert_eq!(owner.guild(), Permissions::all());
+
+    // Roles missing from the cache are reported.
+    assert!(permissions
+        .member(Id::new(2), &[Id::new(guild_id.get() + 3)])
+        .await
+        .is_err());
+
+    store.delete(&guild).await.unwrap();
+    for role in [&everyone, &moderator, &bot] {
+        store.delete(role).await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_memory_guild_store() {
+    let store = MemoryGuildStore::new();
+    assert!(store.guild(Id::new(1)).is_none());
+
+    check_guild_store(&store, Id::new(1)).await;
+    assert_eq!(store.guild(Id::new(1)).unwrap().lang, "fr");
+}
+
+#[tokio::test]
+async fn test_memory_modlog_store() {
+    let store = MemoryModlogStore::new();
+
+    check_modlog_store(&store, Id::new(2)).await;
+    assert_eq!(store.modlogs().len(), 4);
+}
+
+#[tokio::test]
+async fn test_memory_cache_store() {
+    let store = MemoryCacheStore::new();
+
+    check_cache_store(&store, 1).await;
+    check_cache_store_expiry(&store, Id::new(1)).await;
+    check_maintenance(&store).await;
+    check_permissions(&store, Id::new(10)).await;
+    assert!(store.is_empty());
+}
+
+#[tokio::test]
+#[ignore = "requires a MongoDB server"]
+async fn test_db_guild_store() {
+    check_guild_store(&db().await, unique_guild_id()).await;
+}
+
+#[tokio::test]
+#[ignore = "requires a MongoDB server"]
+async fn test_db_modlog_store() {
+    check_modlog_store(&db().await, unique_guild_id()).await;
+}
+
+#[tokio::test]
+#[ignore = "requires a Redis server"]
+async fn test_redis_cache_store() {
+    let store = cache().await;
+
+    check_cache_store(&store, unique_guild_id().get()).await;
+    check_cache_store_expiry(&store, unique_guild_id()).await;
+    check_maintenance(&store).await;
+    check_permissions(&store, unique_guild_id()).await;
+}
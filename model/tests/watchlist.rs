@@ -0,0 +1,47 @@
+use mongodb::bson::{self, DateTime};
+use pretty_assertions::assert_eq;
+use raidprotect_model::database::model::WatchlistEntry;
+use time::OffsetDateTime;
+use twilight_model::id::Id;
+
+#[test]
+fn test_watchlist_bson() {
+    let entry = WatchlistEntry {
+        guild_id: Id::new(1),
+        user_id: Id::new(2),
+        moderators: vec![Id::new(3), Id::new(4)],
+        note: Some("Suspicious messages".to_owned()),
+        created_at: OffsetDateTime::from_unix_timestamp(1_628_594_197).unwrap(),
+        expires_at: OffsetDateTime::from_unix_timestamp(1_631_186_197).unwrap(),
+    };
+
+    let expected = bson::doc! {
+        "guild_id": 1_i64,
+        "user_id": 2_i64,
+        "moderators": [3_i64, 4_i64],
+        "note": "Suspicious messages",
+        "created_at": DateTime::from_millis(1_628_594_197),
+        "expires_at": DateTime::from_millis(1_631_186_197),
+    };
+
+    assert_eq!(bson::to_document(&entry).unwrap(), expected);
+    assert_eq!(
+        bson::from_document::<WatchlistEntry>(expected).unwrap(),
+        entry
+    );
+}
+
+#[test]
+fn test_watchlist_expired() {
+    let entry = WatchlistEntry {
+        guild_id: Id::new(1),
+        user_id: Id::new(2),
+        moderators: vec![Id::new(3)],
+        note: None,
+        created_at: OffsetDateTime::from_unix_timestamp(1_628_594_197).unwrap(),
+        expires_at: OffsetDateTime::from_unix_timestamp(1_631_186_197).unwrap(),
+    };
+
+    assert!(!entry.is_expired(entry.created_at));
+    assert!(entry.is_expired(entry.expires_at));
+}
@@ -1,9 +1,93 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env,
+    fmt::Write as _,
+    fs,
+    path::Path,
+};
+
+/// Languages of the translations, with the path of their source file.
+const LANGUAGES: &[(&str, &str)] = &[("fr", "./locales/fr.json"), ("en", "./locales/en.json")];
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    rosetta_build::config()
-        .source("fr", "./locales/fr.json")
-        .source("en", "./locales/en.json")
-        .fallback("fr")
-        .generate()?;
+    let mut config = rosetta_build::config();
+    for (lang, path) in LANGUAGES {
+        config = config.source(*lang, *path);
+    }
+    config.fallback("fr").generate()?;
+
+    generate_report()?;
 
     Ok(())
 }
+
+/// Generate the report of the translations consistency.
+///
+/// Keys missing in a language fall back to French at compile time, so they
+/// cannot be detected at runtime. This report is included in the
+/// `translations` module and checked at startup.
+fn generate_report() -> Result<(), Box<dyn std::error::Error>> {
+    let mut sources = BTreeMap::new();
+    for (lang, path) in LANGUAGES {
+        println!("cargo:rerun-if-changed={path}");
+
+        let content = fs::read_to_string(path)?;
+        let values: BTreeMap<String, String> = serde_json::from_str(&content)?;
+        sources.insert(*lang, values);
+    }
+
+    let keys: BTreeSet<&String> = sources.values().flat_map(|values| values.keys()).collect();
+
+    let mut missing = Vec::new();
+    let mut mismatched = Vec::new();
+
+    for key in keys {
+        let mut parameters = BTreeSet::new();
+
+        for (lang, values) in &sources {
+            match values.get(key) {
+                Some(value) => {
+                    parameters.insert(placeholders(value));
+                }
+                None => missing.push((key.as_str(), *lang)),
+            }
+        }
+
+        if parameters.len() > 1 {
+            mismatched.push(key.as_str());
+        }
+    }
+
+    let mut report = String::new();
+    writeln!(
+        report,
+        "/// Translation keys missing in a language, as `(key, lang)`."
+    )?;
+    writeln!(
+        report,
+        "pub const MISSING_KEYS: &[(&str, &str)] = &{missing:?};"
+    )?;
+    writeln!(
+        report,
+        "/// Translation keys whose parameters differ between languages."
+    )?;
+    writeln!(
+        report,
+        "pub const MISMATCHED_KEYS: &[&str] = &{mismatched:?};"
+    )?;
+
+    let out = Path::new(&env::var("OUT_DIR")?).join("translations_report.rs");
+    fs::write(out, report)?;
+
+    Ok(())
+}
+
+/// Get the placeholders of a translation value.
+fn placeholders(value: &str) -> BTreeSet<&str> {
+    value
+        .split('{')
+        .skip(1)
+        .filter_map(|part| part.split_once('}'))
+        .map(|(name, _)| name)
+        .collect()
+}
@@ -0,0 +1,186 @@
+//! Authentication of API requests.
+//!
+//! Requests are authenticated with the Discord OAuth access token of the user,
+//! sent as a bearer token in the `Authorization` header. The token is
+//! exchanged for the id of the user with the Discord API, and the result is
+//! cached for a few minutes (see [`ApiSession`]).
+//!
+//! Users can only access the data of guilds where they have the
+//! [`MANAGE_GUILD`] permission, which is checked with the member cache.
+//!
+//! [`MANAGE_GUILD`]: Permissions::MANAGE_GUILD
+
+use anyhow::bail;
+use hyper::{client::HttpConnector, header, Body, Client, Request, StatusCode};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use once_cell::sync::Lazy;
+use raidprotect_model::cache::{
+    discord::CachedGuild,
+    model::{api::ApiSession, member::MemberRecord},
+};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use twilight_model::{
+    guild::Permissions,
+    id::{
+        marker::{GuildMarker, UserMarker},
+        Id,
+    },
+};
+
+use super::ApiError;
+use crate::cluster::ClusterState;
+
+/// Discord API endpoint returning the user that owns an access token.
+const CURRENT_USER_URL: &str = "https://discord.com/api/v10/users/@me";
+
+/// HTTP client used to query the Discord API.
+static CLIENT: Lazy<Client<HttpsConnector<HttpConnector>, Body>> = Lazy::new(|| {
+    let connector = HttpsConnectorBuilder::new()
+        .with_webpki_roots()
+        .https_only()
+        .enable_http1()
+        .build();
+
+    Client::builder().build(connector)
+});
+
+/// Get the bearer token of a request.
+pub fn bearer_token<T>(request: &Request<T>) -> Option<&str> {
+    let value = request
+        .headers()
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?;
+    let token = value.strip_prefix("Bearer ")?.trim();
+
+    (!token.is_empty()).then_some(token)
+}
+
+/// Hash an access token.
+///
+/// Hashed tokens are used as cache keys, so that the tokens are not stored.
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Get the id of the user that owns an access token.
+pub async fn authenticate(state: &ClusterState, token: &str) -> Result<Id<UserMarker>, ApiError> {
+    let token_hash = hash_token(token);
+
+    if let Some(session) = state.cache.get::<ApiSession>(&token_hash).await? {
+        return Ok(session.user_id);
+    }
+
+    let user_id = current_user(token).await?.ok_or(ApiError::Unauthorized)?;
+    state
+        .cache
+        .set(&ApiSession {
+            token_hash,
+            user_id,
+        })
+        .await?;
+
+    Ok(user_id)
+}
+
+/// Check whether a user is allowed to access the data of a guild.
+///
+/// Members without record in the cache are handled as members without roles,
+/// so only the guild owner is authorized until the member is recorded.
+pub async fn authorize(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+) -> Result<(), ApiError> {
+    // Guilds not in the cache are guilds the bot is not a member of.
+    if state.cache.get::<CachedGuild>(&guild_id).await?.is_none() {
+        return Err(ApiError::NotFound);
+    }
+
+    let roles = match state
+        .cache
+        .get::<MemberRecord>(&(guild_id, user_id))
+        .await?
+    {
+        Some(record) if record.left_at.is_some() => return Err(ApiError::Forbidden),
+        Some(record) => record.roles,
+        None => Vec::new(),
+    };
+
+    let permissions = state
+        .cache
+        .permissions(guild_id)
+        .await?
+        .member(user_id, &roles)
+        .await?;
+
+    if permissions.guild().contains(Permissions::MANAGE_GUILD) {
+        Ok(())
+    } else {
+        Err(ApiError::Forbidden)
+    }
+}
+
+/// User returned by the Discord API.
+#[derive(Debug, Deserialize)]
+struct CurrentUser {
+    id: Id<UserMarker>,
+}
+
+/// Query the Discord API to get the user that owns an access token.
+///
+/// Returns [`None`] if the token is invalid.
+async fn current_user(token: &str) -> Result<Option<Id<UserMarker>>, anyhow::Error> {
+    let request = Request::get(CURRENT_USER_URL)
+        .header(header::AUTHORIZATION, format!("Bearer {token}"))
+        .body(Body::empty())?;
+
+    let response = CLIENT.request(request).await?;
+
+    match response.status() {
+        status if status.is_success() => {}
+        StatusCode::UNAUTHORIZED => return Ok(None),
+        status => bail!("failed to get current user: status {status}"),
+    }
+
+    let body = hyper::body::to_bytes(response.into_body()).await?;
+    let user: CurrentUser = serde_json::from_slice(&body)?;
+
+    Ok(Some(user.id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(authorization: &str) -> Request<()> {
+        Request::get("/")
+            .header(header::AUTHORIZATION, authorization)
+            .body(())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_bearer_token() {
+        assert_eq!(bearer_token(&request("Bearer abc")), Some("abc"));
+    }
+
+    #[test]
+    fn test_bearer_token_invalid() {
+        assert_eq!(bearer_token(&request("Bot abc")), None);
+        assert_eq!(bearer_token(&request("Bearer ")), None);
+        assert_eq!(bearer_token(&Request::get("/").body(()).unwrap()), None);
+    }
+
+    #[test]
+    fn test_hash_token() {
+        let hash = hash_token("abc");
+
+        assert_eq!(
+            hash,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_ne!(hash_token("abd"), hash);
+    }
+}
@@ -0,0 +1,111 @@
+//! Guild endpoints.
+
+use raidprotect_model::store::ModlogStore;
+use twilight_model::id::{marker::GuildMarker, Id};
+use url::form_urlencoded;
+
+use super::{
+    model::{ApiGuildConfig, ApiGuildStats, ApiModlog, ApiModlogPage},
+    ApiError,
+};
+use crate::{
+    cluster::{ClusterState, HandlerState},
+    database,
+};
+
+/// Number of modlogs per page.
+const MODLOGS_PER_PAGE: u64 = 25;
+
+/// Maximum page index, to avoid expensive queries.
+const MAX_PAGE: u64 = 10_000;
+
+/// `GET /guilds/:id/config`
+pub async fn config(
+    state: &impl HandlerState,
+    guild_id: Id<GuildMarker>,
+) -> Result<ApiGuildConfig, ApiError> {
+    let config = database::guild_config(state, guild_id).await?;
+
+    Ok(ApiGuildConfig::from(&config))
+}
+
+/// `GET /guilds/:id/modlogs?page=`
+pub async fn modlogs(
+    state: &impl HandlerState,
+    guild_id: Id<GuildMarker>,
+    page: u64,
+) -> Result<ApiModlogPage, ApiError> {
+    let (modlogs, total) = state
+        .modlog_store()
+        .find_modlogs_page(guild_id, page, MODLOGS_PER_PAGE)
+        .await?;
+
+    Ok(ApiModlogPage {
+        page,
+        per_page: MODLOGS_PER_PAGE,
+        total,
+        modlogs: modlogs.iter().map(ApiModlog::from).collect(),
+    })
+}
+
+/// `GET /guilds/:id/stats`
+pub async fn stats(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+) -> Result<ApiGuildStats, ApiError> {
+    let config = database::guild_config(state, guild_id).await?;
+    let (stats, muted) = tokio::try_join!(
+        state.database.get_stats(guild_id),
+        state.database.find_muted_users(guild_id)
+    )?;
+
+    Ok(ApiGuildStats {
+        modlogs: stats
+            .map(|stats| stats.totals.sanctions)
+            .unwrap_or_default(),
+        active_mutes: muted.len(),
+        raid_mode: config.raid_mode.enabled,
+    })
+}
+
+/// Parse the `page` parameter of a query string.
+///
+/// The first page is returned if the parameter is missing.
+pub fn page(query: Option<&str>) -> Result<u64, ApiError> {
+    let value = query.and_then(|query| {
+        form_urlencoded::parse(query.as_bytes())
+            .find(|(key, _)| key == "page")
+            .map(|(_, value)| value)
+    });
+
+    match value {
+        Some(value) => match value.parse() {
+            Ok(page) if page <= MAX_PAGE => Ok(page),
+            _ => Err(ApiError::BadRequest),
+        },
+        None => Ok(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page() {
+        assert_eq!(page(None).unwrap(), 0);
+        assert_eq!(page(Some("")).unwrap(), 0);
+        assert_eq!(page(Some("page=3")).unwrap(), 3);
+        assert_eq!(page(Some("sort=date&page=2")).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_page_invalid() {
+        assert!(matches!(page(Some("page=-1")), Err(ApiError::BadRequest)));
+        assert!(matches!(page(Some("page=abc")), Err(ApiError::BadRequest)));
+        assert!(matches!(
+            page(Some("page=100000")),
+            Err(ApiError::BadRequest)
+        ));
+    }
+}
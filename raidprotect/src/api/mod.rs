@@ -0,0 +1,408 @@
+//! Dashboard API server.
+//!
+//! This HTTP server exposes read-only data of the guilds to the web dashboard.
+//! It is only started if an address is configured (see
+//! [`BotConfig::api_address`]).
+//!
+//! ## Endpoints
+//! - `GET /guilds/:id/config`: configuration of the guild.
+//! - `GET /guilds/:id/modlogs?page=`: moderation logs of the guild, most
+//!   recent first. Pages start at `0`.
+//! - `GET /guilds/:id/stats`: statistics of the guild.
+//!
+//! Responses are JSON objects that mirror the database models (see the
+//! [`model`] module). Errors are returned as `{"error": "<code>"}`.
+//!
+//! ## Authentication
+//! Requests must be authenticated with the Discord OAuth access token of the
+//! user (see the [`auth`] module). Each token is limited to
+//! [`BotConfig::api_rate_limit`] requests per minute.
+//!
+//! Cross-origin requests are only allowed from the origins configured in
+//! [`BotConfig::api_cors_origins`].
+//!
+//! [`BotConfig::api_address`]: raidprotect_model::config::BotConfig::api_address
+//! [`BotConfig::api_rate_limit`]: raidprotect_model::config::BotConfig::api_rate_limit
+//! [`BotConfig::api_cors_origins`]: raidprotect_model::config::BotConfig::api_cors_origins
+
+mod auth;
+mod guild;
+mod model;
+
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+
+use hyper::{
+    header::{self, HeaderValue},
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use raidprotect_model::config::BotConfig;
+use serde::Serialize;
+use time::Duration;
+use tracing::{error, info};
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::{cluster::ClusterState, util::shutdown::ShutdownSubscriber};
+
+/// Duration of the rate limit window.
+const RATE_LIMIT_WINDOW: Duration = Duration::minutes(1);
+
+/// Settings of the API server.
+#[derive(Debug, Clone)]
+pub struct ApiSettings {
+    /// Origins allowed to make cross-origin requests.
+    pub cors_origins: Vec<String>,
+    /// Maximum number of requests per minute for each token.
+    pub rate_limit: u32,
+}
+
+impl ApiSettings {
+    /// Get the API settings from the bot configuration.
+    pub fn from_config(config: &BotConfig) -> Self {
+        Self {
+            cors_origins: config.api_cors_origins.clone(),
+            rate_limit: config.api_rate_limit,
+        }
+    }
+
+    /// Whether cross-origin requests are allowed from an origin.
+    fn is_allowed_origin(&self, origin: &str) -> bool {
+        self.cors_origins.iter().any(|allowed| allowed == origin)
+    }
+}
+
+/// Run the API server until a shutdown signal is received.
+pub async fn run(
+    address: SocketAddr,
+    settings: ApiSettings,
+    state: ClusterState,
+    mut shutdown: ShutdownSubscriber,
+) {
+    let settings = Arc::new(settings);
+    let make_service = make_service_fn(move |_| {
+        let settings = settings.clone();
+        let state = state.clone();
+
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                handle(request, settings.clone(), state.clone())
+            }))
+        }
+    });
+
+    let server = match Server::try_bind(&address) {
+        Ok(builder) => builder.serve(make_service),
+        Err(error) => {
+            error!(error = ?error, "failed to bind api server");
+            return;
+        }
+    };
+
+    info!("api server listening on {address}");
+
+    let server = server.with_graceful_shutdown(async move { shutdown.wait_shutdown().await });
+    if let Err(error) = server.await {
+        error!(error = ?error, "api server error");
+    }
+}
+
+/// Handle an incoming request.
+///
+/// CORS headers are added to the response if the request origin is allowed.
+async fn handle(
+    request: Request<Body>,
+    settings: Arc<ApiSettings>,
+    state: ClusterState,
+) -> Result<Response<Body>, Infallible> {
+    let origin = request
+        .headers()
+        .get(header::ORIGIN)
+        .and_then(|origin| origin.to_str().ok())
+        .filter(|origin| settings.is_allowed_origin(origin))
+        .map(ToOwned::to_owned);
+
+    let mut response = if *request.method() == Method::OPTIONS {
+        preflight_response(origin.is_some())
+    } else {
+        match process(request, &settings, &state).await {
+            Ok(response) => response,
+            Err(error) => error.response(),
+        }
+    };
+
+    if let Some(origin) = origin {
+        add_cors_headers(&mut response, &origin);
+    }
+
+    Ok(response)
+}
+
+/// Process a request.
+async fn process(
+    request: Request<Body>,
+    settings: &ApiSettings,
+    state: &ClusterState,
+) -> Result<Response<Body>, ApiError> {
+    if *request.method() != Method::GET {
+        return Err(ApiError::MethodNotAllowed);
+    }
+
+    let route = Route::parse(request.uri().path()).ok_or(ApiError::NotFound)?;
+    let token = auth::bearer_token(&request).ok_or(ApiError::Unauthorized)?;
+
+    // Invalid tokens are also rate limited.
+    let key = format!("api:{}", auth::hash_token(token));
+    if !state
+        .cache
+        .rate_limit(&key, settings.rate_limit, RATE_LIMIT_WINDOW)
+        .await?
+    {
+        return Err(ApiError::RateLimited);
+    }
+
+    let user_id = auth::authenticate(state, token).await?;
+    auth::authorize(state, route.guild_id(), user_id).await?;
+
+    match route {
+        Route::Config(guild_id) => json_response(&guild::config(state, guild_id).await?),
+        Route::Modlogs(guild_id) => {
+            let page = guild::page(request.uri().query())?;
+
+            json_response(&guild::modlogs(state, guild_id, page).await?)
+        }
+        Route::Stats(guild_id) => json_response(&guild::stats(state, guild_id).await?),
+    }
+}
+
+/// Route of a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Route {
+    /// `/guilds/:id/config`
+    Config(Id<GuildMarker>),
+    /// `/guilds/:id/modlogs`
+    Modlogs(Id<GuildMarker>),
+    /// `/guilds/:id/stats`
+    Stats(Id<GuildMarker>),
+}
+
+impl Route {
+    /// Parse the route from a request path.
+    fn parse(path: &str) -> Option<Self> {
+        let mut segments = path.trim_matches('/').split('/');
+
+        if segments.next()? != "guilds" {
+            return None;
+        }
+
+        let guild_id = segments.next()?.parse().ok()?;
+        let route = match segments.next()? {
+            "config" => Self::Config(guild_id),
+            "modlogs" => Self::Modlogs(guild_id),
+            "stats" => Self::Stats(guild_id),
+            _ => return None,
+        };
+
+        match segments.next() {
+            Some(_) => None,
+            None => Some(route),
+        }
+    }
+
+    /// Id of the guild targeted by the route.
+    fn guild_id(self) -> Id<GuildMarker> {
+        match self {
+            Self::Config(guild_id) | Self::Modlogs(guild_id) | Self::Stats(guild_id) => guild_id,
+        }
+    }
+}
+
+/// Error returned by the API.
+#[derive(Debug)]
+pub enum ApiError {
+    /// The request parameters are invalid.
+    BadRequest,
+    /// The request is not authenticated or the token is invalid.
+    Unauthorized,
+    /// The user is not allowed to access the guild.
+    Forbidden,
+    /// The route or the guild does not exist.
+    NotFound,
+    /// The request method is not supported.
+    MethodNotAllowed,
+    /// The token exceeded the rate limit.
+    RateLimited,
+    /// An unexpected error occurred.
+    Internal(anyhow::Error),
+}
+
+impl ApiError {
+    /// Status code of the error.
+    fn status(&self) -> StatusCode {
+        match self {
+            Self::BadRequest => StatusCode::BAD_REQUEST,
+            Self::Unauthorized => StatusCode::UNAUTHORIZED,
+            Self::Forbidden => StatusCode::FORBIDDEN,
+            Self::NotFound => StatusCode::NOT_FOUND,
+            Self::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+            Self::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Error code returned in the response body.
+    fn code(&self) -> &'static str {
+        match self {
+            Self::BadRequest => "bad_request",
+            Self::Unauthorized => "unauthorized",
+            Self::Forbidden => "forbidden",
+            Self::NotFound => "not_found",
+            Self::MethodNotAllowed => "method_not_allowed",
+            Self::RateLimited => "rate_limited",
+            Self::Internal(_) => "internal_error",
+        }
+    }
+
+    /// Build the error response.
+    ///
+    /// Internal errors are logged, their details are not returned.
+    fn response(self) -> Response<Body> {
+        if let Self::Internal(error) = &self {
+            error!(error = ?error, "error while processing api request");
+        }
+
+        let body = serde_json::json!({ "error": self.code() });
+        let mut response = json_body(body.to_string());
+        *response.status_mut() = self.status();
+
+        response
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::Internal(error)
+    }
+}
+
+/// Build a JSON response.
+fn json_response(body: &impl Serialize) -> Result<Response<Body>, ApiError> {
+    let body = serde_json::to_vec(body).map_err(anyhow::Error::from)?;
+
+    Ok(json_body(body))
+}
+
+/// Build a response with a JSON body.
+fn json_body(body: impl Into<Body>) -> Response<Body> {
+    let mut response = Response::new(body.into());
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+
+    response
+}
+
+/// Build the response of a CORS preflight request.
+fn preflight_response(allowed: bool) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+
+    if allowed {
+        *response.status_mut() = StatusCode::NO_CONTENT;
+
+        let headers = response.headers_mut();
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_METHODS,
+            HeaderValue::from_static("GET, OPTIONS"),
+        );
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_HEADERS,
+            HeaderValue::from_static("Authorization"),
+        );
+        headers.insert(
+            header::ACCESS_CONTROL_MAX_AGE,
+            HeaderValue::from_static("86400"),
+        );
+    } else {
+        *response.status_mut() = StatusCode::FORBIDDEN;
+    }
+
+    response
+}
+
+/// Add the CORS headers to a response.
+fn add_cors_headers(response: &mut Response<Body>, origin: &str) {
+    let headers = response.headers_mut();
+
+    if let Ok(origin) = HeaderValue::from_str(origin) {
+        headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+    }
+    headers.insert(header::VARY, HeaderValue::from_static("Origin"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_parse() {
+        assert_eq!(
+            Route::parse("/guilds/1/config"),
+            Some(Route::Config(Id::new(1)))
+        );
+        assert_eq!(
+            Route::parse("/guilds/2/modlogs/"),
+            Some(Route::Modlogs(Id::new(2)))
+        );
+        assert_eq!(
+            Route::parse("/guilds/3/stats"),
+            Some(Route::Stats(Id::new(3)))
+        );
+    }
+
+    #[test]
+    fn test_route_parse_invalid() {
+        assert_eq!(Route::parse("/"), None);
+        assert_eq!(Route::parse("/guilds/1"), None);
+        assert_eq!(Route::parse("/guilds/0/config"), None);
+        assert_eq!(Route::parse("/guilds/abc/config"), None);
+        assert_eq!(Route::parse("/guilds/1/unknown"), None);
+        assert_eq!(Route::parse("/guilds/1/config/extra"), None);
+        assert_eq!(Route::parse("/users/1/config"), None);
+    }
+
+    #[test]
+    fn test_error_response() {
+        let response = ApiError::RateLimited.response();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            HeaderValue::from_static("application/json")
+        );
+    }
+
+    #[test]
+    fn test_preflight_response() {
+        let mut response = preflight_response(true);
+        add_cors_headers(&mut response, "https://dashboard.example.com");
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://dashboard.example.com"
+        );
+
+        assert_eq!(preflight_response(false).status(), StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn test_allowed_origin() {
+        let settings = ApiSettings {
+            cors_origins: vec!["https://dashboard.example.com".to_owned()],
+            rate_limit: 60,
+        };
+
+        assert!(settings.is_allowed_origin("https://dashboard.example.com"));
+        assert!(!settings.is_allowed_origin("https://example.com"));
+    }
+}
@@ -0,0 +1,573 @@
+//! Models returned by the API.
+//!
+//! These types mirror the database models, but ids are serialized as strings
+//! (the default serialization of [`Id`]) since JavaScript numbers cannot
+//! represent all Discord ids. Secrets, such as the outbound webhook secret,
+//! are never returned.
+
+use raidprotect_model::database::model::{
+    ActivityRoleConfig, AntinukeAction, AntinukeConfig, CaptchaConfig, ChannelRule,
+    ChannelRuleKind, DmStatus, FeatureSet, FirstMessageConfig, GraceConfig, GuildConfig,
+    JoinBurstConfig, LinkFilterConfig, LogCategory, LogRoute, ModerationConfig, Modlog,
+    ModlogCounts, ModlogType, ModlogUser, MuteMode, NameConfig, OutboundWebhook, PatternRule,
+    PinsConfig, RaidModeConfig, ReactionSpamConfig, RoleLogsConfig, SanctionRole, VoiceConfig,
+    VoiceHopAction,
+};
+use serde::Serialize;
+use twilight_model::id::{
+    marker::{ChannelMarker, GuildMarker, MessageMarker, RoleMarker, UserMarker},
+    Id,
+};
+
+/// Guild configuration.
+#[derive(Debug, Serialize)]
+pub struct ApiGuildConfig {
+    pub id: Id<GuildMarker>,
+    pub logs_chan: Option<Id<ChannelMarker>>,
+    pub log_routing: Vec<ApiLogRoute>,
+    pub lang: String,
+    pub lang_override: bool,
+    pub lang_override_ephemeral: bool,
+    pub moderation: ApiModerationConfig,
+    pub captcha: ApiCaptchaConfig,
+    pub raid_mode: RaidModeConfig,
+    pub reaction_spam: ApiReactionSpamConfig,
+    pub link_filter: ApiLinkFilterConfig,
+    pub pattern_rules: Vec<PatternRule>,
+    pub channel_rules: Vec<ApiChannelRule>,
+    pub first_message: FirstMessageConfig,
+    pub antinuke: ApiAntinukeConfig,
+    pub join_burst: JoinBurstConfig,
+    pub grace: ApiGraceConfig,
+    pub voice: ApiVoiceConfig,
+    pub names: NameConfig,
+    pub pins: ApiPinsConfig,
+    pub role_logs: ApiRoleLogsConfig,
+    pub activity_role: ApiActivityRoleConfig,
+    pub delete_replies_after: Option<u16>,
+    pub modlog_retention: Option<u16>,
+    pub new_account_threshold: u32,
+    pub features: FeatureSet,
+    pub sentinel_alerts: bool,
+    pub screening_aware: bool,
+    pub raid_alert_role: Option<Id<RoleMarker>>,
+    pub watch_chan: Option<Id<ChannelMarker>>,
+    pub watch_duration: u16,
+    pub reports_chan: Option<Id<ChannelMarker>>,
+    pub milestones_chan: Option<Id<ChannelMarker>>,
+    pub outbound_webhook: Option<ApiOutboundWebhook>,
+}
+
+impl From<&GuildConfig> for ApiGuildConfig {
+    fn from(config: &GuildConfig) -> Self {
+        Self {
+            id: config.id,
+            logs_chan: config.logs_chan,
+            log_routing: config.log_routing.iter().map(Into::into).collect(),
+            lang: config.lang.clone(),
+            lang_override: config.lang_override,
+            lang_override_ephemeral: config.lang_override_ephemeral,
+            moderation: (&config.moderation).into(),
+            captcha: (&config.captcha).into(),
+            raid_mode: config.raid_mode.clone(),
+            reaction_spam: (&config.reaction_spam).into(),
+            link_filter: (&config.link_filter).into(),
+            pattern_rules: config.pattern_rules.clone(),
+            channel_rules: config.channel_rules.iter().map(Into::into).collect(),
+            first_message: config.first_message.clone(),
+            antinuke: (&config.antinuke).into(),
+            join_burst: config.join_burst.clone(),
+            grace: (&config.grace).into(),
+            voice: (&config.voice).into(),
+            names: config.names.clone(),
+            pins: (&config.pins).into(),
+            role_logs: (&config.role_logs).into(),
+            activity_role: (&config.activity_role).into(),
+            delete_replies_after: config.delete_replies_after,
+            modlog_retention: config.modlog_retention,
+            new_account_threshold: config.new_account_threshold,
+            features: config.features,
+            sentinel_alerts: config.sentinel_alerts,
+            screening_aware: config.screening_aware,
+            raid_alert_role: config.raid_alert_role,
+            watch_chan: config.watch_chan,
+            watch_duration: config.watch_duration,
+            reports_chan: config.reports_chan,
+            milestones_chan: config.milestones_chan,
+            outbound_webhook: config.outbound_webhook.as_ref().map(Into::into),
+        }
+    }
+}
+
+/// Moderation module configuration.
+#[derive(Debug, Serialize)]
+pub struct ApiModerationConfig {
+    pub roles: Vec<Id<RoleMarker>>,
+    pub enforce_reason: bool,
+    pub anonymize: bool,
+    pub sanction_cooldown: u16,
+    pub mute_mode: ApiMuteMode,
+    pub enforce_mutes: bool,
+    pub reason_presets: Vec<String>,
+    pub sanction_roles: Vec<ApiSanctionRole>,
+    pub quarantine_role: Option<Id<RoleMarker>>,
+    pub skip_confirmation: Vec<ModlogType>,
+    pub skip_confirmation_roles: Vec<Id<RoleMarker>>,
+    pub dm_disabled: bool,
+    pub dm_skipped: Vec<ModlogType>,
+    pub announcement_channel: Option<Id<ChannelMarker>>,
+    pub announced: Vec<ModlogType>,
+}
+
+impl From<&ModerationConfig> for ApiModerationConfig {
+    fn from(config: &ModerationConfig) -> Self {
+        Self {
+            roles: config.roles.clone(),
+            enforce_reason: config.enforce_reason,
+            anonymize: config.anonymize,
+            sanction_cooldown: config.sanction_cooldown,
+            mute_mode: config.mute_mode.into(),
+            enforce_mutes: config.enforce_mutes,
+            reason_presets: config.reason_presets.clone(),
+            sanction_roles: config.sanction_roles.iter().map(Into::into).collect(),
+            quarantine_role: config.quarantine_role,
+            skip_confirmation: config.skip_confirmation.clone(),
+            skip_confirmation_roles: config.skip_confirmation_roles.clone(),
+            dm_disabled: config.dm_disabled,
+            dm_skipped: config.dm_skipped.clone(),
+            announcement_channel: config.announcement_channel,
+            announced: config.announced.clone(),
+        }
+    }
+}
+
+/// Sanctions allowed for the members of a role.
+#[derive(Debug, Serialize)]
+pub struct ApiSanctionRole {
+    pub role_id: Id<RoleMarker>,
+    pub sanctions: Vec<ModlogType>,
+    pub shadow: bool,
+}
+
+impl From<&SanctionRole> for ApiSanctionRole {
+    fn from(role: &SanctionRole) -> Self {
+        Self {
+            role_id: role.role_id,
+            sanctions: role.sanctions.clone(),
+            shadow: role.shadow,
+        }
+    }
+}
+
+/// Mode used to mute members.
+#[derive(Debug, Serialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum ApiMuteMode {
+    Timeout,
+    Role { role_id: Id<RoleMarker> },
+}
+
+impl From<MuteMode> for ApiMuteMode {
+    fn from(mode: MuteMode) -> Self {
+        match mode {
+            MuteMode::Timeout => Self::Timeout,
+            MuteMode::Role { role_id } => Self::Role { role_id },
+        }
+    }
+}
+
+/// Captcha module configuration.
+#[derive(Debug, Serialize)]
+pub struct ApiCaptchaConfig {
+    pub enabled: bool,
+    pub channel: Option<Id<ChannelMarker>>,
+    pub message: Option<Id<MessageMarker>>,
+    pub role: Option<Id<RoleMarker>>,
+    pub verified_roles: Vec<Id<RoleMarker>>,
+    pub logs: Option<Id<ChannelMarker>>,
+}
+
+impl From<&CaptchaConfig> for ApiCaptchaConfig {
+    fn from(config: &CaptchaConfig) -> Self {
+        Self {
+            enabled: config.enabled,
+            channel: config.channel,
+            message: config.message,
+            role: config.role,
+            verified_roles: config.verified_roles.clone(),
+            logs: config.logs,
+        }
+    }
+}
+
+/// Reaction spam module configuration.
+#[derive(Debug, Serialize)]
+pub struct ApiReactionSpamConfig {
+    pub enabled: bool,
+    pub max_reactions: u16,
+    pub interval: u16,
+    pub timeout: Option<u32>,
+    pub trusted_roles: Vec<Id<RoleMarker>>,
+}
+
+impl From<&ReactionSpamConfig> for ApiReactionSpamConfig {
+    fn from(config: &ReactionSpamConfig) -> Self {
+        Self {
+            enabled: config.enabled,
+            max_reactions: config.max_reactions,
+            interval: config.interval,
+            timeout: config.timeout,
+            trusted_roles: config.trusted_roles.clone(),
+        }
+    }
+}
+
+/// Link filter module configuration.
+#[derive(Debug, Serialize)]
+pub struct ApiLinkFilterConfig {
+    pub enabled: bool,
+    pub invites: bool,
+    pub links: bool,
+    pub allowed_domains: Vec<String>,
+    pub allowed_invites: Vec<String>,
+    pub timeout: Option<u32>,
+    pub trusted_roles: Vec<Id<RoleMarker>>,
+}
+
+impl From<&LinkFilterConfig> for ApiLinkFilterConfig {
+    fn from(config: &LinkFilterConfig) -> Self {
+        Self {
+            enabled: config.enabled,
+            invites: config.invites,
+            links: config.links,
+            allowed_domains: config.allowed_domains.clone(),
+            allowed_invites: config.allowed_invites.clone(),
+            timeout: config.timeout,
+            trusted_roles: config.trusted_roles.clone(),
+        }
+    }
+}
+
+/// Content rule of a channel.
+#[derive(Debug, Serialize)]
+pub struct ApiChannelRule {
+    pub channel_id: Id<ChannelMarker>,
+    pub kind: ChannelRuleKind,
+}
+
+impl From<&ChannelRule> for ApiChannelRule {
+    fn from(rule: &ChannelRule) -> Self {
+        Self {
+            channel_id: rule.channel_id,
+            kind: rule.kind.clone(),
+        }
+    }
+}
+
+/// Route of a logs category.
+#[derive(Debug, Serialize)]
+pub struct ApiLogRoute {
+    pub category: LogCategory,
+    pub channel: Option<Id<ChannelMarker>>,
+}
+
+impl From<&LogRoute> for ApiLogRoute {
+    fn from(route: &LogRoute) -> Self {
+        Self {
+            category: route.category,
+            channel: route.channel,
+        }
+    }
+}
+
+/// Grace period configuration.
+#[derive(Debug, Serialize)]
+pub struct ApiGraceConfig {
+    pub enabled: bool,
+    pub boosters: bool,
+    pub trusted_tenure: Option<u16>,
+    pub trusted_roles: Vec<Id<RoleMarker>>,
+}
+
+impl From<&GraceConfig> for ApiGraceConfig {
+    fn from(config: &GraceConfig) -> Self {
+        Self {
+            enabled: config.enabled,
+            boosters: config.boosters,
+            trusted_tenure: config.trusted_tenure,
+            trusted_roles: config.trusted_roles.clone(),
+        }
+    }
+}
+
+/// Voice channels module configuration.
+#[derive(Debug, Serialize)]
+pub struct ApiVoiceConfig {
+    pub logs_chan: Option<Id<ChannelMarker>>,
+    pub hop_detection: bool,
+    pub max_hops: u16,
+    pub interval: u16,
+    pub action: VoiceHopAction,
+    pub timeout: u32,
+    pub trusted_roles: Vec<Id<RoleMarker>>,
+}
+
+impl From<&VoiceConfig> for ApiVoiceConfig {
+    fn from(config: &VoiceConfig) -> Self {
+        Self {
+            logs_chan: config.logs_chan,
+            hop_detection: config.hop_detection,
+            max_hops: config.max_hops,
+            interval: config.interval,
+            action: config.action,
+            timeout: config.timeout,
+            trusted_roles: config.trusted_roles.clone(),
+        }
+    }
+}
+
+/// Message pins logs configuration.
+#[derive(Debug, Serialize)]
+pub struct ApiPinsConfig {
+    pub logs_chan: Option<Id<ChannelMarker>>,
+    pub ignored_channels: Vec<Id<ChannelMarker>>,
+}
+
+impl From<&PinsConfig> for ApiPinsConfig {
+    fn from(config: &PinsConfig) -> Self {
+        Self {
+            logs_chan: config.logs_chan,
+            ignored_channels: config.ignored_channels.clone(),
+        }
+    }
+}
+
+/// Member role changes logs configuration.
+#[derive(Debug, Serialize)]
+pub struct ApiRoleLogsConfig {
+    pub logs_chan: Option<Id<ChannelMarker>>,
+    pub ignored_roles: Vec<Id<RoleMarker>>,
+}
+
+impl From<&RoleLogsConfig> for ApiRoleLogsConfig {
+    fn from(config: &RoleLogsConfig) -> Self {
+        Self {
+            logs_chan: config.logs_chan,
+            ignored_roles: config.ignored_roles.clone(),
+        }
+    }
+}
+
+/// Activity role configuration.
+#[derive(Debug, Serialize)]
+pub struct ApiActivityRoleConfig {
+    pub role: Option<Id<RoleMarker>>,
+    pub threshold: u32,
+    pub cooldown: u16,
+    pub log: bool,
+}
+
+impl From<&ActivityRoleConfig> for ApiActivityRoleConfig {
+    fn from(config: &ActivityRoleConfig) -> Self {
+        Self {
+            role: config.role,
+            threshold: config.threshold,
+            cooldown: config.cooldown,
+            log: config.log,
+        }
+    }
+}
+
+/// Anti-nuke module configuration.
+#[derive(Debug, Serialize)]
+pub struct ApiAntinukeConfig {
+    pub enabled: bool,
+    pub max_deletions: u16,
+    pub interval: u16,
+    pub action: AntinukeAction,
+    pub trusted: Vec<Id<UserMarker>>,
+}
+
+impl From<&AntinukeConfig> for ApiAntinukeConfig {
+    fn from(config: &AntinukeConfig) -> Self {
+        Self {
+            enabled: config.enabled,
+            max_deletions: config.max_deletions,
+            interval: config.interval,
+            action: config.action,
+            trusted: config.trusted.clone(),
+        }
+    }
+}
+
+/// Outbound webhook configuration.
+///
+/// The webhook secret is not included.
+#[derive(Debug, Serialize)]
+pub struct ApiOutboundWebhook {
+    pub url: String,
+    pub failures: u32,
+}
+
+impl From<&OutboundWebhook> for ApiOutboundWebhook {
+    fn from(webhook: &OutboundWebhook) -> Self {
+        Self {
+            url: webhook.url.to_string(),
+            failures: webhook.failures,
+        }
+    }
+}
+
+/// Page of moderation logs.
+#[derive(Debug, Serialize)]
+pub struct ApiModlogPage {
+    /// Index of the page, starting at `0`.
+    pub page: u64,
+    /// Maximum number of modlogs per page.
+    pub per_page: u64,
+    /// Total number of modlogs of the guild.
+    pub total: u64,
+    pub modlogs: Vec<ApiModlog>,
+}
+
+/// Moderation log entry.
+#[derive(Debug, Serialize)]
+pub struct ApiModlog {
+    pub id: Option<String>,
+    pub kind: ModlogType,
+    pub guild_id: Id<GuildMarker>,
+    pub user: ApiModlogUser,
+    pub moderator: ApiModlogUser,
+    /// UNIX timestamp of the modlog, in seconds.
+    pub date: i64,
+    pub reason: Option<String>,
+    pub notes: Option<String>,
+    pub pre_emptive: bool,
+    pub confirmation_skipped: bool,
+    pub shadow: bool,
+    pub dm_disabled: bool,
+    pub dm_status: Option<DmStatus>,
+}
+
+impl From<&Modlog> for ApiModlog {
+    fn from(modlog: &Modlog) -> Self {
+        Self {
+            id: modlog.id.map(|id| id.to_hex()),
+            kind: modlog.kind,
+            guild_id: modlog.guild_id,
+            user: (&modlog.user).into(),
+            moderator: (&modlog.moderator).into(),
+            date: modlog.date.unix_timestamp(),
+            reason: modlog.reason.clone(),
+            notes: modlog.notes.clone(),
+            pre_emptive: modlog.pre_emptive,
+            confirmation_skipped: modlog.confirmation_skipped,
+            shadow: modlog.shadow,
+            dm_disabled: modlog.dm_disabled,
+            dm_status: modlog.dm_status,
+        }
+    }
+}
+
+/// User stored with a moderation log.
+#[derive(Debug, Serialize)]
+pub struct ApiModlogUser {
+    pub id: Id<UserMarker>,
+    pub name: String,
+    pub discriminator: u16,
+    pub avatar: Option<String>,
+}
+
+impl From<&ModlogUser> for ApiModlogUser {
+    fn from(user: &ModlogUser) -> Self {
+        Self {
+            id: user.id,
+            name: user.name.clone(),
+            discriminator: user.discriminator,
+            avatar: user.avatar.map(|avatar| avatar.to_string()),
+        }
+    }
+}
+
+/// Statistics of a guild.
+#[derive(Debug, Serialize)]
+pub struct ApiGuildStats {
+    /// Number of moderation logs for each type.
+    pub modlogs: ModlogCounts,
+    /// Number of members currently muted.
+    pub active_mutes: usize,
+    /// Whether the raid mode is enabled.
+    pub raid_mode: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use raidprotect_model::database::model::LogDestination;
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_utils::fixture;
+
+    #[test]
+    fn test_guild_config_ids_as_strings() {
+        let mut config = GuildConfig::new(Id::new(1));
+        config.logs_chan = Some(Id::new(2));
+        config.moderation.mute_mode = MuteMode::Role {
+            role_id: Id::new(3),
+        };
+        config.raid_alert_role = Some(Id::new(u64::MAX));
+        config
+            .log_routing
+            .set(LogCategory::Raids, LogDestination::Channel(Id::new(4)));
+
+        let json = serde_json::to_value(ApiGuildConfig::from(&config)).unwrap();
+
+        assert_eq!(json["id"], json!("1"));
+        assert_eq!(json["logs_chan"], json!("2"));
+        assert_eq!(
+            json["moderation"]["mute_mode"],
+            json!({ "mode": "role", "role_id": "3" })
+        );
+        assert_eq!(json["raid_alert_role"], json!("18446744073709551615"));
+        assert_eq!(
+            json["log_routing"],
+            json!([{ "category": "raids", "channel": "4" }])
+        );
+    }
+
+    #[test]
+    fn test_guild_config_hides_webhook_secret() {
+        let mut config = GuildConfig::new(Id::new(1));
+        config.outbound_webhook = Some(OutboundWebhook {
+            url: "https://example.com/hook".parse().unwrap(),
+            secret: "secret".to_owned(),
+            failures: 2,
+        });
+
+        let json = serde_json::to_value(ApiGuildConfig::from(&config)).unwrap();
+
+        assert_eq!(
+            json["outbound_webhook"],
+            json!({ "url": "https://example.com/hook", "failures": 2 })
+        );
+    }
+
+    #[test]
+    fn test_modlog() {
+        let user = fixture::modlog_user(2, "user");
+        let modlog = fixture::modlog(ModlogType::Kick)
+            .guild(Id::new(1))
+            .user(user.clone())
+            .moderator(user)
+            .date(1_600_000_000)
+            .reason("spam")
+            .build();
+
+        let json = serde_json::to_value(ApiModlog::from(&modlog)).unwrap();
+
+        assert_eq!(json["kind"], json!("kick"));
+        assert_eq!(json["guild_id"], json!("1"));
+        assert_eq!(json["user"]["id"], json!("2"));
+        assert_eq!(json["date"], json!(1_600_000_000));
+    }
+}
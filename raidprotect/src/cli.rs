@@ -0,0 +1,140 @@
+//! Command-line interface.
+//!
+//! The command-line flags override the values of the configuration file and
+//! environment variables (see [`parse_bot_config`]).
+//!
+//! With `--check-config`, the bot only validates its configuration and the
+//! connection to MongoDB, Redis and Discord, then exits.
+//!
+//! [`parse_bot_config`]: raidprotect_model::config::parse_bot_config
+
+use std::{future::Future, net::SocketAddr, path::PathBuf, time::Duration};
+
+use anyhow::bail;
+use clap::{Args, Parser};
+use raidprotect_model::{
+    cache::CacheClient,
+    config::{BotConfig, ShardRange},
+    database::DbClient,
+};
+use serde::Serialize;
+use twilight_http::Client as HttpClient;
+
+/// Maximum duration of each connectivity check.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// RaidProtect Discord bot.
+#[derive(Parser)]
+#[clap(version)]
+pub struct Cli {
+    /// Path of the TOML configuration file.
+    ///
+    /// Defaults to `raidprotect.toml` in the current directory, if it exists.
+    #[clap(long, short, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+    /// Check the configuration and the connection to MongoDB, Redis and
+    /// Discord, then exit.
+    #[clap(long)]
+    pub check_config: bool,
+    #[clap(flatten)]
+    pub overrides: ConfigOverrides,
+}
+
+/// Configuration values set with command-line flags.
+///
+/// This type does not implement `Debug` since it may contain the bot token.
+#[derive(Args, Serialize)]
+pub struct ConfigOverrides {
+    /// Discord bot token.
+    #[clap(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token: Option<String>,
+    /// Shards started by this process, written as `from-to/total`.
+    #[clap(long, value_name = "RANGE")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shards: Option<ShardRange>,
+    /// Only run the background consumers, without connecting to the gateway.
+    #[clap(long)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    consumer_only: bool,
+    /// MongoDB connection uri.
+    #[clap(long, value_name = "URI")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mongodb_uri: Option<String>,
+    /// Redis connection uri.
+    #[clap(long, value_name = "URI")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    redis_uri: Option<String>,
+    /// Listening address of the Prometheus metrics exporter.
+    #[clap(long, value_name = "ADDRESS")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metrics_address: Option<SocketAddr>,
+    /// Listening address of the health-check server.
+    #[clap(long, value_name = "ADDRESS")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    health_address: Option<SocketAddr>,
+    /// Ids of the bot operators, separated by commas.
+    #[clap(long, value_name = "IDS", value_delimiter = ',')]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    operators: Vec<u64>,
+}
+
+/// Check the connection to MongoDB, Redis and Discord.
+///
+/// The result of each check is printed, and an error is returned if any check
+/// failed.
+pub async fn check_config(config: &BotConfig) -> Result<(), anyhow::Error> {
+    let database = &config.database;
+
+    let checks = [
+        check("mongodb", async {
+            let client =
+                DbClient::connect(&database.mongodb_uri, database.mongodb_database.clone()).await?;
+            client.ping().await
+        })
+        .await,
+        check("redis", async {
+            CacheClient::connect(&database.redis_uri)
+                .await?
+                .ping()
+                .await
+        })
+        .await,
+        check("discord", async {
+            let http = HttpClient::new(config.token.expose().to_owned());
+            http.current_user_application()
+                .exec()
+                .await?
+                .model()
+                .await?;
+
+            Ok(())
+        })
+        .await,
+    ];
+
+    if checks.contains(&false) {
+        bail!("some connectivity checks failed");
+    }
+
+    Ok(())
+}
+
+/// Run a connectivity check with a timeout and print its result.
+async fn check(name: &str, future: impl Future<Output = Result<(), anyhow::Error>>) -> bool {
+    let result = match tokio::time::timeout(CHECK_TIMEOUT, future).await {
+        Ok(result) => result,
+        Err(_) => Err(anyhow::anyhow!("timed out")),
+    };
+
+    match result {
+        Ok(()) => {
+            println!("{name}: ok");
+            true
+        }
+        Err(error) => {
+            println!("{name}: {error:#}");
+            false
+        }
+    }
+}
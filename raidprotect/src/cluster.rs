@@ -1,30 +1,44 @@
 //! Shards cluster implementation.
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use anyhow::Context;
+use async_trait::async_trait;
 use futures_util::StreamExt;
 use raidprotect_model::{
-    cache::{discord::http::CacheHttp, CacheClient},
+    cache::{
+        discord::{
+            http::CacheHttp,
+            permission::{BotMemberUnknown, CacheMiss},
+            CachedGuild, CurrentMember,
+        },
+        CacheClient,
+    },
     config::BotConfig,
-    database::DbClient,
+    database::{model::GuildConfig, DbClient},
+    store::{CacheStore, GuildStore, ModlogStore},
+};
+use tracing::{info, info_span, instrument, trace, warn, Instrument};
+use twilight_gateway::{
+    cluster::{Events, ShardScheme},
+    Cluster, Intents,
 };
-use tracing::{info, info_span, instrument, trace};
-use twilight_gateway::{cluster::Events, Cluster, Intents};
 use twilight_http::Client as HttpClient;
 use twilight_model::{
-    gateway::{
-        payload::outgoing::update_presence::UpdatePresencePayload,
-        presence::{ActivityType, MinimalActivity, Status},
-    },
+    gateway::{payload::outgoing::update_presence::UpdatePresencePayload, presence::Status},
     id::{
-        marker::{ApplicationMarker, GuildMarker},
+        marker::{ApplicationMarker, GuildMarker, UserMarker},
         Id,
     },
 };
 
 use crate::{
-    event::ProcessEvent, interaction::register_commands, util::shutdown::ShutdownSubscriber,
+    event::ProcessEvent,
+    feature::{self, captcha::RenderGuard},
+    interaction::register_commands,
+    presence::{self, ActivityTemplate, ShardGuilds},
+    translations::Lang,
+    util::{self, bulk::BulkTasks, ratelimit::RateGuard, shutdown::ShutdownSubscriber},
 };
 
 /// Discord shards cluster.
@@ -39,6 +53,12 @@ pub struct ShardCluster {
     events: Events,
     /// Shared cluster state
     state: ClusterState,
+    /// Guilds handled by each shard.
+    guilds: ShardGuilds,
+    /// Activity shown in the bot presence.
+    activity: ActivityTemplate,
+    /// Guild count shown in the initial presence.
+    guild_count: u64,
 }
 
 impl ShardCluster {
@@ -46,9 +66,9 @@ impl ShardCluster {
     ///
     /// This method also initialize an [`HttpClient`] and a [`CacheClient`],
     /// that can be later retrieved using corresponding methods.
-    pub async fn new(config: BotConfig) -> Result<Self, anyhow::Error> {
+    pub async fn new(config: &BotConfig) -> Result<Self, anyhow::Error> {
         // Initialize HTTP client and get current user.
-        let http = Arc::new(HttpClient::new(config.token.clone()));
+        let http = Arc::new(HttpClient::new(config.token.expose().to_owned()));
         let application = http
             .current_user_application()
             .exec()
@@ -64,38 +84,88 @@ impl ShardCluster {
 
         let mongodb = DbClient::connect(
             &config.database.mongodb_uri,
-            config.database.mongodb_database,
+            config.database.mongodb_database.clone(),
         )
         .await?;
         mongodb
             .ping()
             .await
             .context("failed to connect to mongodb")?;
+        mongodb
+            .create_modlog_indexes()
+            .await
+            .context("failed to create modlog indexes")?;
 
         let intents = Intents::GUILDS
             | Intents::GUILD_MEMBERS
             | Intents::GUILD_MESSAGES
+            | Intents::GUILD_MESSAGE_REACTIONS
+            | Intents::GUILD_VOICE_STATES
             | Intents::MESSAGE_CONTENT;
 
-        let (cluster, events) = Cluster::builder(config.token, intents)
+        let activity = match config.activity.clone() {
+            Some(template) => ActivityTemplate::Custom(template),
+            None => ActivityTemplate::GuildCount(Lang::from(config.activity_lang.as_str())),
+        };
+
+        // The guild count from the previous run is used until the shards have
+        // reported their guild count.
+        let guild_count = redis.guild_count().await?;
+        let presence = UpdatePresencePayload {
+            activities: vec![activity.activity(guild_count)],
+            afk: false,
+            since: None,
+            status: Status::Online,
+        };
+
+        let mut builder = Cluster::builder(config.token.expose().to_owned(), intents)
             .http_client(http.clone())
-            .presence(presence())
-            .build()
-            .await?;
+            .presence(presence);
 
-        info!("started cluster with {} shards", cluster.shards().len());
+        if let Some(shards) = config.shards {
+            builder = builder.shard_scheme(ShardScheme::Range {
+                from: shards.from,
+                to: shards.to,
+                total: shards.total,
+            });
+        }
+
+        let (cluster, events) = builder.build().await?;
 
-        let state = ClusterState::new(redis, mongodb, http, current_user);
+        let shard_count = cluster.config().shard_scheme().total();
+        info!(
+            "started cluster with {} shards ({} in total)",
+            cluster.shards().len(),
+            shard_count
+        );
 
-        register_commands(&state, application.id).await;
+        let state = ClusterState::new(redis, mongodb, http, current_user, config, shard_count);
+
+        // Commands are registered by the processes running the shards.
+        if !config.consumer_only {
+            register_commands(&state, application.id).await;
+        }
 
         Ok(Self {
             cluster: Arc::new(cluster),
             events,
             state,
+            guilds: ShardGuilds::default(),
+            activity,
+            guild_count,
         })
     }
 
+    /// Get a reference to the inner twilight [`Cluster`].
+    pub fn gateway(&self) -> Arc<Cluster> {
+        self.cluster.clone()
+    }
+
+    /// Get a clone of the shared [`ClusterState`].
+    pub fn state(&self) -> ClusterState {
+        self.state.clone()
+    }
+
     /// Start the cluster and handle incoming events.
     ///
     /// A [`ShutdownSubscriber`] must be provided to gracefully stop the cluster.
@@ -107,46 +177,42 @@ impl ShardCluster {
             cluster.up().await;
         });
 
+        // Publish the guild count and update the presence periodically
+        let presence = tokio::spawn(presence::run(
+            self.cluster.clone(),
+            self.state.clone(),
+            self.guilds.clone(),
+            self.activity.clone(),
+            self.guild_count,
+        ));
+
         // Handle incoming events
         tokio::select! {
             _ = self.handle_events() => {},
             _ = shutdown.wait_shutdown() => {},
         };
 
+        presence.abort();
         self.cluster.down();
     }
 
     /// Handle incoming events
     async fn handle_events(&mut self) {
-        while let Some((_shard_id, event)) = self.events.next().await {
+        while let Some((shard_id, event)) = self.events.next().await {
             let span = info_span!("handle_event");
 
             span.in_scope(|| {
                 trace!(event = ?event, "received event");
+                self.guilds.update(shard_id, &event);
 
                 let state = self.state.clone();
-                tokio::spawn(event.process(state));
+                let span = util::log::event_span(&event);
+                tokio::spawn(event.process(state).instrument(span));
             });
         }
     }
 }
 
-/// Get the bot presence.
-fn presence() -> UpdatePresencePayload {
-    let activity = MinimalActivity {
-        kind: ActivityType::Watching,
-        name: String::from("raidprotect.org"),
-        url: None,
-    };
-
-    UpdatePresencePayload {
-        activities: vec![activity.into()],
-        afk: false,
-        since: None,
-        status: Status::Online,
-    }
-}
-
 /// Current state of the cluster.
 ///
 /// This type hold shared types such as the cache or the http client. It implement
@@ -157,26 +223,181 @@ pub struct ClusterState {
     pub database: DbClient,
     pub http: Arc<HttpClient>,
     pub current_user: Id<ApplicationMarker>,
+    /// Running bulk operations that can be cancelled.
+    pub bulk_tasks: BulkTasks,
+    /// Guard prioritizing requests sent to the Discord API.
+    pub rate_guard: RateGuard,
+    /// Guard limiting concurrent captcha renders.
+    pub captcha_renders: RenderGuard,
+    /// Ids of the bot operators.
+    operators: Arc<[Id<UserMarker>]>,
+    /// Maximum duration of an interaction handler.
+    interaction_timeout: Duration,
+    /// Total number of shards used by the bot, across all processes.
+    shard_count: u64,
 }
 
 impl ClusterState {
     /// Initialize a new [`ClusterState`].
+    ///
+    /// The operators and the interaction timeout are read from the bot
+    /// configuration.
     pub fn new(
         cache: CacheClient,
         mongodb: DbClient,
         http: Arc<HttpClient>,
         current_user: Id<ApplicationMarker>,
+        config: &BotConfig,
+        shard_count: u64,
     ) -> Self {
+        let operators: Vec<Id<UserMarker>> =
+            config.operators.iter().copied().map(Id::new).collect();
+
         Self {
             cache,
             database: mongodb,
             http,
             current_user,
+            bulk_tasks: BulkTasks::default(),
+            rate_guard: RateGuard::default(),
+            captcha_renders: RenderGuard::default(),
+            operators: operators.into(),
+            interaction_timeout: Duration::from_secs(config.interaction_timeout),
+            shard_count,
         }
     }
 
+    /// Check whether a user is a bot operator.
+    pub fn is_operator(&self, user_id: Id<UserMarker>) -> bool {
+        self.operators.contains(&user_id)
+    }
+
+    /// Get the maximum duration of an interaction handler.
+    pub fn interaction_timeout(&self) -> Duration {
+        self.interaction_timeout
+    }
+
+    /// Get the total number of shards used by the bot.
+    pub fn shard_count(&self) -> u64 {
+        self.shard_count
+    }
+
     /// Get the [`CacheHttp`] client associated with the cache client.
     pub fn cache_http(&self, guild_id: Id<GuildMarker>) -> CacheHttp {
         self.cache.http(&self.http, guild_id)
     }
+
+    /// Get the bot member of a guild.
+    ///
+    /// The member is cached when the guild is received, but may be missing
+    /// right after the bot has joined. In this case, it is fetched from the
+    /// Discord API and cached. A [`BotMemberUnknown`] error is returned if the
+    /// member cannot be found.
+    pub async fn own_member(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<CurrentMember, anyhow::Error> {
+        let mut guild = self
+            .cache
+            .get::<CachedGuild>(&guild_id)
+            .await?
+            .ok_or(CacheMiss("guild"))
+            .context(BotMemberUnknown)?;
+
+        if let Some(member) = guild.current_member {
+            return Ok(member);
+        }
+
+        metrics::increment_counter!("raidprotect_own_member_fallbacks_total");
+        warn!(guild = ?guild_id, "bot member not found in cache, fetching it");
+
+        let member = self
+            .http
+            .guild_member(guild_id, self.current_user.cast())
+            .exec()
+            .await
+            .context(BotMemberUnknown)?
+            .model()
+            .await?;
+
+        let member = CurrentMember {
+            id: member.user.id,
+            communication_disabled_until: member.communication_disabled_until,
+            roles: member.roles.into_iter().collect(),
+        };
+
+        guild.current_member = Some(member.clone());
+        self.cache.set(&guild).await?;
+
+        Ok(member)
+    }
+}
+
+/// Accessors of the state used by the interaction handlers.
+///
+/// Handlers that only need the stores take a `&impl HandlerState` instead of
+/// a [`ClusterState`], so they can be tested with in-memory stores (see the
+/// `test_utils` module).
+#[async_trait]
+pub trait HandlerState: Send + Sync {
+    /// Type of the guild configurations store.
+    type Guilds: GuildStore;
+    /// Type of the moderation logs store.
+    type Modlogs: ModlogStore;
+    /// Type of the cache store.
+    type Cache: CacheStore;
+
+    /// Get the guild configurations store.
+    fn guild_store(&self) -> &Self::Guilds;
+
+    /// Get the moderation logs store.
+    fn modlog_store(&self) -> &Self::Modlogs;
+
+    /// Get the cache store.
+    fn cache_store(&self) -> &Self::Cache;
+
+    /// Check whether a user is a bot operator.
+    fn is_operator(&self, user_id: Id<UserMarker>) -> bool;
+
+    /// Get the [`CurrentMember`] of the bot in a guild.
+    ///
+    /// See [`ClusterState::own_member`].
+    async fn own_member(&self, guild_id: Id<GuildMarker>) -> Result<CurrentMember, anyhow::Error>;
+
+    /// Check the references of a fetched [`GuildConfig`] in the background.
+    ///
+    /// The default implementation does nothing.
+    fn check_references(&self, _config: GuildConfig) {}
+}
+
+#[async_trait]
+impl HandlerState for ClusterState {
+    type Guilds = DbClient;
+    type Modlogs = DbClient;
+    type Cache = CacheClient;
+
+    fn guild_store(&self) -> &Self::Guilds {
+        &self.database
+    }
+
+    fn modlog_store(&self) -> &Self::Modlogs {
+        &self.database
+    }
+
+    fn cache_store(&self) -> &Self::Cache {
+        &self.cache
+    }
+
+    fn is_operator(&self, user_id: Id<UserMarker>) -> bool {
+        ClusterState::is_operator(self, user_id)
+    }
+
+    async fn own_member(&self, guild_id: Id<GuildMarker>) -> Result<CurrentMember, anyhow::Error> {
+        ClusterState::own_member(self, guild_id).await
+    }
+
+    fn check_references(&self, config: GuildConfig) {
+        let state = self.clone();
+        tokio::spawn(async move { feature::references::check(&state, &config).await });
+    }
 }
@@ -0,0 +1,174 @@
+//! Database access with fallback on the cache.
+//!
+//! This module wraps the database queries used by the bot to keep working when
+//! the database is unavailable:
+//!
+//! - guild configurations are read from the cache, where a copy is stored each
+//!   time the configuration is fetched from the database.
+//! - writes that can be delayed are stored in a write-behind queue (see
+//!   [`PendingWrite`]) and executed once the database is available again.
+//! - configuration updates, which require fresh data, fail with a
+//!   [`DatabaseUnavailable`] error.
+
+use std::{error::Error, fmt, time::Duration};
+
+use raidprotect_model::{
+    cache::model::write_queue::PendingWrite,
+    database::{model::GuildConfig, DbClient},
+    store::{CacheStore, GuildStore},
+};
+use tracing::{instrument, warn};
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::{
+    cluster::{ClusterState, HandlerState},
+    feature,
+    util::shutdown::ShutdownSubscriber,
+};
+
+/// Interval between two attempts to flush the write queue.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Error returned when the database is unavailable.
+///
+/// This error is added as context to the database error, and can be retrieved
+/// with [`anyhow::Error::downcast_ref`].
+#[derive(Debug, Clone, Copy)]
+pub struct DatabaseUnavailable;
+
+impl fmt::Display for DatabaseUnavailable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("database unavailable")
+    }
+}
+
+impl Error for DatabaseUnavailable {}
+
+/// Get the [`GuildConfig`] of a guild.
+///
+/// If the database is unavailable, the configuration stored in the cache is
+/// returned. This configuration may be outdated.
+pub async fn guild_config(
+    state: &impl HandlerState,
+    guild_id: Id<GuildMarker>,
+) -> Result<GuildConfig, anyhow::Error> {
+    match state.guild_store().get_guild_or_create(guild_id).await {
+        Ok(config) => {
+            if let Err(error) = state.cache_store().set(&config).await {
+                warn!(error = ?error, guild = ?guild_id, "failed to cache guild configuration");
+            }
+
+            Ok(config)
+        }
+        Err(error) if DbClient::is_unavailable(&error) => {
+            warn!(error = ?error, guild = ?guild_id, "database unavailable, using cached configuration");
+
+            match state.cache_store().get::<GuildConfig>(&guild_id).await? {
+                Some(config) => Ok(config),
+                None => Err(error.context(DatabaseUnavailable)),
+            }
+        }
+        Err(error) => Err(error),
+    }
+}
+
+/// Update the [`GuildConfig`] of a guild.
+///
+/// Configuration updates are never queued, a [`DatabaseUnavailable`] error is
+/// returned if the database is unavailable.
+pub async fn update_guild_config(
+    state: &impl HandlerState,
+    config: &GuildConfig,
+) -> Result<(), anyhow::Error> {
+    match state.guild_store().update_guild(config).await {
+        Ok(()) => {
+            if let Err(error) = state.cache_store().set(config).await {
+                warn!(error = ?error, guild = ?config.id, "failed to cache guild configuration");
+            }
+
+            Ok(())
+        }
+        Err(error) if DbClient::is_unavailable(&error) => Err(error.context(DatabaseUnavailable)),
+        Err(error) => Err(error),
+    }
+}
+
+/// Execute a write in the database.
+///
+/// If the database is unavailable, the write is added to the write queue. The
+/// write is also queued if the queue is not empty, to keep the writes order.
+pub async fn write(state: &ClusterState, write: PendingWrite) -> Result<(), anyhow::Error> {
+    if state.cache.write_queue_len().await? == 0 {
+        match execute_write(state, &write).await {
+            Ok(()) => return Ok(()),
+            Err(error) if DbClient::is_unavailable(&error) => {
+                warn!(error = ?error, "database unavailable, queuing write");
+            }
+            Err(error) => return Err(error),
+        }
+    }
+
+    let len = state.cache.queue_write(&write).await?;
+    metrics::gauge!("raidprotect_write_queue_depth", len as f64);
+
+    Ok(())
+}
+
+/// Execute a [`PendingWrite`].
+async fn execute_write(state: &ClusterState, write: &PendingWrite) -> Result<(), anyhow::Error> {
+    match write {
+        PendingWrite::Modlog { modlog } => {
+            let id = state.database.create_modlog(modlog).await?;
+            feature::webhook::modlog_created(state, modlog, id.to_hex()).await;
+        }
+        PendingWrite::LogsChannel {
+            guild_id,
+            channel_id,
+        } => {
+            state
+                .database
+                .set_guild_logs_channel(*guild_id, *channel_id)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Flush the write queue periodically until a shutdown signal is received.
+#[instrument(name = "write_queue", skip_all)]
+pub async fn run_flusher(state: ClusterState, mut shutdown: ShutdownSubscriber) {
+    let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {},
+            _ = shutdown.wait_shutdown() => break,
+        };
+
+        if let Err(error) = flush(&state).await {
+            warn!(error = ?error, "failed to flush write queue");
+        }
+    }
+}
+
+/// Execute the queued writes, in order.
+///
+/// The flush stops at the first error, the failed write is kept in the queue.
+async fn flush(state: &ClusterState) -> Result<(), anyhow::Error> {
+    let len = state.cache.write_queue_len().await?;
+    metrics::gauge!("raidprotect_write_queue_depth", len as f64);
+
+    if len == 0 {
+        return Ok(());
+    }
+
+    while let Some(write) = state.cache.peek_write().await? {
+        execute_write(state, &write).await?;
+        state.cache.pop_write().await?;
+    }
+
+    metrics::gauge!("raidprotect_write_queue_depth", 0.0);
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+//! Activity role.
+//!
+//! Members are granted the configured role once they have sent enough
+//! messages (see [`ActivityRoleConfig`]). Only one message is counted per
+//! cooldown, so spamming does not speed up the process.
+//!
+//! [`ActivityRoleConfig`]: raidprotect_model::database::model::ActivityRoleConfig
+
+use raidprotect_model::database::model::LogCategory;
+use time::Duration;
+use tracing::{debug, error};
+use twilight_http::request::AuditLogReason;
+use twilight_model::{
+    channel::Message,
+    id::{marker::GuildMarker, Id},
+};
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    interaction::{embed, util::GuildConfigExt},
+    util::logs,
+};
+
+/// Count a message sent in a guild.
+pub async fn message_create(message: &Message, state: &ClusterState) {
+    let guild_id = match message.guild_id {
+        Some(guild_id) => guild_id,
+        None => return,
+    };
+
+    if let Err(error) = message_create_inner(message, guild_id, state).await {
+        error!(error = ?error, guild = ?guild_id, "failed to update member activity");
+    }
+}
+
+async fn message_create_inner(
+    message: &Message,
+    guild_id: Id<GuildMarker>,
+    state: &ClusterState,
+) -> Result<(), anyhow::Error> {
+    let config = database::guild_config(state, guild_id).await?;
+    let activity = &config.activity_role;
+
+    let role_id = match activity.role {
+        Some(role_id) => role_id,
+        None => return Ok(()),
+    };
+
+    // Members that already have the role are not counted.
+    if let Some(member) = &message.member {
+        if member.roles.contains(&role_id) {
+            return Ok(());
+        }
+    }
+
+    let user_id = message.author.id;
+    let key = format!("activity:{guild_id}:{user_id}");
+    let cooldown = Duration::seconds(activity.cooldown.into());
+
+    if !state.cache.throttle(&key, cooldown).await? {
+        return Ok(());
+    }
+
+    let count = state.cache.increment_activity(guild_id, user_id).await?;
+
+    if count < activity.threshold {
+        return Ok(());
+    }
+
+    let lang = config.lang();
+    state
+        .cache_http(guild_id)
+        .add_guild_member_role(user_id, role_id)
+        .await?
+        .reason(lang.activity_role_reason())?
+        .exec()
+        .await?;
+
+    state.cache.reset_activity(guild_id, user_id).await?;
+    debug!(guild = ?guild_id, user = ?user_id, count, "activity role granted");
+
+    if activity.log {
+        let embed = embed::logs::activity_role(lang, user_id, role_id, count);
+        logs::post(state, &config, LogCategory::Messages, embed).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,364 @@
+//! Anti-nuke protection.
+//!
+//! A compromised moderator account or a rogue bot may delete many channels or
+//! roles in a short time. When the anti-nuke module is enabled, the channel
+//! and role deletions are attributed to their author with the audit log, and
+//! the configured action (see [`AntinukeAction`]) is applied to users that
+//! exceed the configured threshold. An alert is then sent in the logs channel
+//! (see [`raid_alert`]) and to the guild owner.
+//!
+//! The bot itself, the guild owner and the users trusted in the configuration
+//! are never sanctioned.
+//!
+//! ## Audit log lookups
+//! Audit log requests are rate-limited, so they are not made for each event.
+//! The first deletion schedules a lookup after [`BATCH_DELAY`], which handles
+//! all the deletions made in the meantime. Deletions are identified by their
+//! audit log entry id, so entries seen by several lookups are only counted
+//! once. Entries older than [`MAX_ENTRY_AGE`] are ignored, to never act on
+//! outdated information.
+
+use std::{collections::HashMap, time::Duration as StdDuration};
+
+use raidprotect_model::{
+    cache::discord::{permission::RoleOrdering, CachedGuild, CachedRole},
+    database::model::{AntinukeAction, GuildConfig},
+};
+use time::{Duration, OffsetDateTime};
+use tracing::{debug, error, warn};
+use twilight_http::request::AuditLogReason;
+use twilight_model::{
+    guild::{
+        audit_log::{AuditLogEntry, AuditLogEventType},
+        Permissions,
+    },
+    id::{
+        marker::{AuditLogEntryMarker, GuildMarker, UserMarker},
+        Id,
+    },
+};
+use twilight_util::snowflake::Snowflake;
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    feature::{automation, raid_alert},
+    interaction::{embed, util::GuildConfigExt},
+};
+
+/// Delay before the audit log is fetched after a deletion.
+const BATCH_DELAY: Duration = Duration::seconds(5);
+
+/// Maximum age of the audit log entries taken into account.
+const MAX_ENTRY_AGE: Duration = Duration::minutes(2);
+
+/// Number of audit log entries fetched for each event type.
+const AUDIT_LOG_LIMIT: u16 = 50;
+
+/// Handle `ChannelDelete` and `RoleDelete` events.
+pub async fn deletion(guild_id: Id<GuildMarker>, state: &ClusterState) {
+    if let Err(error) = deletion_inner(guild_id, state).await {
+        error!(error = ?error, guild = ?guild_id, "error while processing anti-nuke deletion");
+    }
+}
+
+async fn deletion_inner(
+    guild_id: Id<GuildMarker>,
+    state: &ClusterState,
+) -> Result<(), anyhow::Error> {
+    let config = database::guild_config(state, guild_id).await?;
+
+    if !config.antinuke.enabled {
+        return Ok(());
+    }
+
+    // A lookup is already scheduled for this guild.
+    let key = format!("antinuke:{guild_id}:lookup");
+    if !state.cache.throttle(&key, BATCH_DELAY).await? {
+        return Ok(());
+    }
+
+    let state = state.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(StdDuration::from_secs(BATCH_DELAY.whole_seconds() as u64)).await;
+
+        if let Err(error) = process_audit_log(&state, guild_id).await {
+            error!(error = ?error, guild = ?guild_id, "error while processing anti-nuke audit log");
+        }
+    });
+
+    Ok(())
+}
+
+/// Fetch the audit log and count the deletions of each user.
+async fn process_audit_log(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+) -> Result<(), anyhow::Error> {
+    let config = database::guild_config(state, guild_id).await?;
+
+    let mut entries = Vec::new();
+    for kind in [
+        AuditLogEventType::ChannelDelete,
+        AuditLogEventType::RoleDelete,
+    ] {
+        match fetch_audit_log(state, guild_id, kind).await {
+            Ok(fetched) => entries.extend(fetched),
+            Err(error) => {
+                // Deletions cannot be attributed without the audit log.
+                debug!(error = ?error, guild = ?guild_id, "failed to get audit log");
+                return Ok(());
+            }
+        }
+    }
+
+    let owner_id = state
+        .cache
+        .get::<CachedGuild>(&guild_id)
+        .await?
+        .map(|guild| guild.owner_id);
+
+    let now = OffsetDateTime::now_utc();
+    let interval = Duration::seconds(config.antinuke.interval.into());
+
+    for (user_id, entries) in recent_deletions(entries, now) {
+        if user_id == state.current_user.cast()
+            || Some(user_id) == owner_id
+            || config.antinuke.trusted.contains(&user_id)
+        {
+            continue;
+        }
+
+        let count = state
+            .cache
+            .track_deletions(guild_id, user_id, &entries, interval)
+            .await?;
+
+        if count <= config.antinuke.max_deletions.into() {
+            continue;
+        }
+
+        // The action is only applied once per interval.
+        let key = format!("antinuke:{guild_id}:{user_id}");
+        if !state.cache.throttle(&key, interval).await? {
+            continue;
+        }
+
+        state.cache.clear_deletions(guild_id, user_id).await?;
+
+        let applied = match apply_action(state, &config, user_id, count).await {
+            Ok(()) => true,
+            Err(error) => {
+                warn!(error = ?error, user = ?user_id, "failed to apply anti-nuke action");
+                false
+            }
+        };
+
+        alert(state, &config, owner_id, user_id, count, applied).await?;
+    }
+
+    Ok(())
+}
+
+/// Fetch the audit log entries of a given type.
+async fn fetch_audit_log(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    kind: AuditLogEventType,
+) -> Result<Vec<AuditLogEntry>, anyhow::Error> {
+    let audit_log = state
+        .cache_http(guild_id)
+        .audit_log()
+        .await?
+        .action_type(kind)
+        .limit(AUDIT_LOG_LIMIT)?
+        .exec()
+        .await?
+        .model()
+        .await?;
+
+    Ok(audit_log.entries)
+}
+
+/// Group the recent audit log entries by user.
+///
+/// Entries older than [`MAX_ENTRY_AGE`] and entries without user are
+/// ignored.
+fn recent_deletions(
+    entries: impl IntoIterator<Item = AuditLogEntry>,
+    now: OffsetDateTime,
+) -> HashMap<Id<UserMarker>, Vec<Id<AuditLogEntryMarker>>> {
+    let now_millis = (now.unix_timestamp_nanos() / 1_000_000) as i64;
+    let max_age = MAX_ENTRY_AGE.whole_milliseconds() as i64;
+
+    let mut deletions: HashMap<_, Vec<_>> = HashMap::new();
+    for entry in entries {
+        if now_millis - entry.id.timestamp() > max_age {
+            continue;
+        }
+
+        if let Some(user_id) = entry.user_id {
+            deletions.entry(user_id).or_default().push(entry.id);
+        }
+    }
+
+    deletions
+}
+
+/// Apply the configured action to a user.
+async fn apply_action(
+    state: &ClusterState,
+    config: &GuildConfig,
+    user_id: Id<UserMarker>,
+    count: usize,
+) -> Result<(), anyhow::Error> {
+    let guild_id = config.id;
+    let reason = config.lang().antinuke_reason(count);
+
+    match config.antinuke.action {
+        AntinukeAction::StripRoles => {
+            let member = state
+                .http
+                .guild_member(guild_id, user_id)
+                .exec()
+                .await?
+                .model()
+                .await?;
+
+            let bot = state
+                .cache
+                .permissions(guild_id)
+                .await?
+                .current_member()
+                .await?;
+            if !bot.guild().contains(Permissions::MANAGE_ROLES) {
+                anyhow::bail!("missing permissions to remove member roles");
+            }
+
+            // Managed roles and roles higher than the bot's highest role
+            // cannot be removed.
+            let mut kept = Vec::new();
+            for role_id in member.roles {
+                match state.cache.get::<CachedRole>(&role_id).await? {
+                    Some(role)
+                        if !role.managed && RoleOrdering::from(&role) < bot.highest_role() => {}
+                    _ => kept.push(role_id),
+                }
+            }
+
+            state
+                .http
+                .update_guild_member(guild_id, user_id)
+                .roles(&kept)
+                .reason(&reason)?
+                .exec()
+                .await?;
+        }
+        AntinukeAction::Ban => {
+            state
+                .http
+                .create_ban(guild_id, user_id)
+                .reason(&reason)?
+                .exec()
+                .await?;
+
+            let user = automation::unknown_user(user_id);
+            let moderator = automation::automatic_moderator(state.current_user.cast());
+            if let Err(error) =
+                automation::record_ban(state, guild_id, user, moderator, &reason).await
+            {
+                error!(error = ?error, guild = ?guild_id, "failed to record antinuke ban");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Send an alert in the logs channel and to the guild owner.
+///
+/// The alert sent in the logs channel mentions the configured alert role (see
+/// the [`raid_alert`] module).
+async fn alert(
+    state: &ClusterState,
+    config: &GuildConfig,
+    owner_id: Option<Id<UserMarker>>,
+    user_id: Id<UserMarker>,
+    count: usize,
+    applied: bool,
+) -> Result<(), anyhow::Error> {
+    let lang = config.lang();
+    let embed = embed::logs::antinuke(lang, user_id, count, config.antinuke.action, applied);
+
+    raid_alert::send(state, config, embed.clone()).await?;
+
+    if let Some(owner_id) = owner_id {
+        let result = async {
+            let channel = state
+                .http
+                .create_private_channel(owner_id)
+                .exec()
+                .await?
+                .model()
+                .await?;
+
+            let embed = embed::logs::private_copy(lang, embed, OffsetDateTime::now_utc());
+            state
+                .http
+                .create_message(channel.id)
+                .embeds(&[embed])?
+                .exec()
+                .await?;
+
+            Ok::<_, anyhow::Error>(())
+        };
+
+        // The owner may not accept private messages.
+        if let Err(error) = result.await {
+            debug!(error = ?error, guild = ?config.id, "failed to send anti-nuke alert to owner");
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Discord epoch, in milliseconds.
+    const DISCORD_EPOCH: i64 = 1_420_070_400_000;
+
+    fn entry(user_id: Option<u64>, timestamp: OffsetDateTime) -> AuditLogEntry {
+        let millis = (timestamp.unix_timestamp_nanos() / 1_000_000) as i64;
+        let id = ((millis - DISCORD_EPOCH) as u64) << 22;
+
+        AuditLogEntry {
+            action_type: AuditLogEventType::ChannelDelete,
+            changes: Vec::new(),
+            id: Id::new(id),
+            options: None,
+            reason: None,
+            target_id: None,
+            user_id: user_id.map(Id::new),
+        }
+    }
+
+    #[test]
+    fn test_recent_deletions() {
+        let now = OffsetDateTime::now_utc();
+        let entries = vec![
+            entry(Some(1), now - Duration::seconds(10)),
+            entry(Some(1), now - Duration::seconds(20)),
+            entry(Some(2), now - Duration::seconds(30)),
+            entry(Some(2), now - Duration::minutes(5)),
+            entry(None, now),
+        ];
+
+        let deletions = recent_deletions(entries, now);
+
+        assert_eq!(deletions.len(), 2);
+        assert_eq!(deletions[&Id::new(1)].len(), 2);
+        assert_eq!(deletions[&Id::new(2)].len(), 1);
+    }
+}
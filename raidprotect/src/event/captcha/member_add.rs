@@ -1,12 +1,21 @@
 //! Handle `MemberAdd` event.
 
-use raidprotect_model::cache::model::interaction::PendingCaptcha;
+use raidprotect_model::{
+    cache::model::interaction::{ChallengeKind, PendingCaptcha},
+    database::model::Feature,
+};
 use time::{Duration, OffsetDateTime};
 use tracing::{debug, error, instrument};
 use twilight_http::request::AuditLogReason;
-use twilight_model::guild::Member;
+use twilight_model::{
+    guild::Member,
+    id::{
+        marker::{GuildMarker, UserMarker},
+        Id,
+    },
+};
 
-use crate::{cluster::ClusterState, feature::captcha, translations::Lang};
+use crate::{cluster::ClusterState, database, feature::captcha, translations::Lang};
 
 /// Handle `MemberAdd` event.
 pub async fn member_add(member: &Member, state: &ClusterState) {
@@ -15,6 +24,17 @@ pub async fn member_add(member: &Member, state: &ClusterState) {
     }
 }
 
+/// Start the captcha of a member that accepted the membership screening.
+pub async fn member_accepted(
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    state: &ClusterState,
+) {
+    if let Err(error) = start_captcha(guild_id, user_id, state).await {
+        error!(error = ?error, guild = ?guild_id, user = ?user_id, "error while starting captcha");
+    }
+}
+
 async fn member_add_inner(member: &Member, state: &ClusterState) -> Result<(), anyhow::Error> {
     // Ensure the member has joined recently to ignore members sent on bot
     // startup.
@@ -25,11 +45,20 @@ async fn member_add_inner(member: &Member, state: &ClusterState) -> Result<(), a
         return Ok(());
     }
 
+    start_captcha(member.guild_id, member.user.id, state).await
+}
+
+/// Give the unverified role to a member and store the pending captcha.
+async fn start_captcha(
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    state: &ClusterState,
+) -> Result<(), anyhow::Error> {
     // Get the guild configuration.
-    let config = state.database.get_guild_or_create(member.guild_id).await?;
+    let config = database::guild_config(state, guild_id).await?;
     let lang = Lang::from(&*config.lang);
 
-    if !config.captcha.enabled {
+    if !config.captcha.enabled || !config.features.contains(Feature::Verification) {
         return Ok(());
     }
 
@@ -44,8 +73,8 @@ async fn member_add_inner(member: &Member, state: &ClusterState) -> Result<(), a
 
     // Give the unverified role to the member.
     if let Err(error) = state
-        .cache_http(member.guild_id)
-        .add_guild_member_role(member.user.id, role)
+        .cache_http(guild_id)
+        .add_guild_member_role(user_id, role)
         .await?
         .reason(lang.captcha_role_reason())?
         .exec()
@@ -58,11 +87,12 @@ async fn member_add_inner(member: &Member, state: &ClusterState) -> Result<(), a
 
     // Store the captcha in redis.
     let pending_captcha = PendingCaptcha {
-        guild_id: member.guild_id,
-        member_id: member.user.id,
+        guild_id,
+        member_id: user_id,
         code: String::new(), // Code generated on button click.
         regenerate_count: 0,
         expires_at: OffsetDateTime::now_utc() + captcha::DEFAULT_DURATION,
+        kind: ChallengeKind::Image,
     };
 
     let state_clone = state.clone();
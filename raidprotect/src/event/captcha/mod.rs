@@ -4,7 +4,11 @@
 //! following events are handled:
 //!
 //! - `MemberAdd`: when a member joins the server, the unverified role is added.
+//! - `MemberUpdate`: when a member accepts the membership screening, the
+//!   unverified role is added (see the [`screening`] module).
+//!
+//! [`screening`]: super::screening
 
 mod member_add;
 
-pub use member_add::member_add;
+pub use member_add::{member_accepted, member_add};
@@ -0,0 +1,230 @@
+//! Channel content rules.
+//!
+//! Channels can be restricted to a kind of content with a [`ChannelRule`],
+//! such as media-only channels. Messages that do not follow the rule of
+//! their channel (or of the parent channel for threads) are deleted, and a
+//! short notice explaining the rule is sent to the author. The notice is
+//! deleted after [`NOTICE_DURATION`] seconds.
+//!
+//! Members that can manage the messages of the channel are not checked.
+
+use raidprotect_model::{
+    cache::{discord::CachedChannel, model::job::ScheduledJob},
+    database::model::{ChannelRule, ChannelRuleKind, GuildConfig, StatsEvent},
+};
+use time::{Duration, OffsetDateTime};
+use tracing::{debug, error};
+use twilight_mention::Mention;
+use twilight_model::{
+    channel::{message::AllowedMentions, Message},
+    guild::Permissions,
+    id::{marker::GuildMarker, Id},
+};
+
+use crate::{
+    cluster::ClusterState,
+    database, feature,
+    interaction::util::GuildConfigExt,
+    translations::Lang,
+    util::link::{find_links, FoundLink},
+};
+
+/// Duration (in seconds) after which the notice sent to the author is deleted.
+const NOTICE_DURATION: i64 = 10;
+
+/// Check the content of a message sent in a guild.
+pub async fn message_create(message: &Message, state: &ClusterState) {
+    let guild_id = match message.guild_id {
+        Some(guild_id) => guild_id,
+        None => return,
+    };
+
+    if let Err(error) = message_create_inner(message, guild_id, state).await {
+        error!(error = ?error, guild = ?guild_id, "failed to check channel rule");
+    }
+}
+
+async fn message_create_inner(
+    message: &Message,
+    guild_id: Id<GuildMarker>,
+    state: &ClusterState,
+) -> Result<(), anyhow::Error> {
+    let config = database::guild_config(state, guild_id).await?;
+
+    if config.channel_rules.is_empty() {
+        return Ok(());
+    }
+
+    let rule = match channel_rule(state, &config, message).await? {
+        Some(rule) => rule,
+        None => return Ok(()),
+    };
+
+    let has_attachments = !message.attachments.is_empty();
+    if follows_rule(&rule.kind, &message.content, has_attachments) {
+        return Ok(());
+    }
+
+    // Members that can manage the messages are not checked.
+    if let Some(member) = &message.member {
+        if config.is_automod_exempt(&[], &member.roles) {
+            return Ok(());
+        }
+
+        let (permissions, _) = state
+            .cache
+            .permissions(guild_id)
+            .await?
+            .member(message.author.id, &member.roles)
+            .await?
+            .channel(message.channel_id)
+            .await?;
+
+        if permissions.contains(Permissions::MANAGE_MESSAGES) {
+            return Ok(());
+        }
+    }
+
+    debug!(guild = ?guild_id, channel = ?message.channel_id, "message deleted by channel rule");
+
+    state
+        .cache_http(guild_id)
+        .delete_message(message.channel_id, message.id)
+        .await?
+        .exec()
+        .await?;
+
+    feature::stats::record(state, guild_id, StatsEvent::MessagesDeleted(1)).await;
+
+    // Send a notice explaining the rule to the author.
+    let user_id = message.author.id;
+    let content = notice(config.lang(), &rule.kind, user_id.mention().to_string());
+    let allowed_mentions = AllowedMentions {
+        users: vec![user_id],
+        ..Default::default()
+    };
+
+    let notice = state
+        .http
+        .create_message(message.channel_id)
+        .content(&content)?
+        .allowed_mentions(Some(&allowed_mentions))
+        .exec()
+        .await?
+        .model()
+        .await?;
+
+    let job = ScheduledJob::DeleteMessage {
+        channel_id: notice.channel_id,
+        message_id: notice.id,
+    };
+    let delete_at = OffsetDateTime::now_utc() + Duration::seconds(NOTICE_DURATION);
+
+    state.cache.schedule_job(&job, delete_at).await
+}
+
+/// Get the rule of the channel of a message.
+///
+/// Threads inherit the rule of their parent channel.
+async fn channel_rule<'a>(
+    state: &ClusterState,
+    config: &'a GuildConfig,
+    message: &Message,
+) -> Result<Option<&'a ChannelRule>, anyhow::Error> {
+    if let Some(rule) = config.channel_rule(message.channel_id) {
+        return Ok(Some(rule));
+    }
+
+    let channel = state
+        .cache
+        .get::<CachedChannel>(&message.channel_id)
+        .await?;
+    let parent_id = match channel {
+        Some(channel) if channel.is_thread() => channel.parent_id,
+        _ => None,
+    };
+
+    Ok(parent_id.and_then(|parent_id| config.channel_rule(parent_id)))
+}
+
+/// Whether a message follows a channel rule.
+fn follows_rule(kind: &ChannelRuleKind, content: &str, has_attachments: bool) -> bool {
+    match kind {
+        ChannelRuleKind::MediaOnly => has_attachments,
+        ChannelRuleKind::TextOnly => !has_attachments,
+        ChannelRuleKind::LinkOnlyFrom { .. } => {
+            let links = find_links(content);
+
+            !links.is_empty()
+                && links.iter().all(|link| match link {
+                    FoundLink::Invite(_) => false,
+                    FoundLink::External { domain, .. } => kind.domain_allowed(domain),
+                })
+        }
+    }
+}
+
+/// Notice sent to the author of a deleted message.
+fn notice(lang: Lang, kind: &ChannelRuleKind, user: String) -> String {
+    match kind {
+        ChannelRuleKind::MediaOnly => lang.channel_rule_notice_media_only(user),
+        ChannelRuleKind::TextOnly => lang.channel_rule_notice_text_only(user),
+        ChannelRuleKind::LinkOnlyFrom { domains } => {
+            let domains = domains
+                .iter()
+                .map(|domain| format!("`{domain}`"))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            lang.channel_rule_notice_link_only(domains, user)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_follows_rule() {
+        let media = ChannelRuleKind::MediaOnly;
+        assert!(follows_rule(&media, "", true));
+        assert!(!follows_rule(&media, "hello", false));
+
+        let text = ChannelRuleKind::TextOnly;
+        assert!(follows_rule(&text, "hello", false));
+        assert!(!follows_rule(&text, "", true));
+
+        let links = ChannelRuleKind::LinkOnlyFrom {
+            domains: vec!["youtube.com".to_owned()],
+        };
+        assert!(follows_rule(
+            &links,
+            "https://www.youtube.com/watch?v=1",
+            false
+        ));
+        assert!(!follows_rule(&links, "hello", false));
+        assert!(!follows_rule(&links, "https://example.com", false));
+        assert!(!follows_rule(
+            &links,
+            "https://youtube.com/watch?v=1 https://example.com",
+            false
+        ));
+        assert!(!follows_rule(&links, "discord.gg/raid", false));
+    }
+
+    #[test]
+    fn test_notice() {
+        let kinds = [
+            ChannelRuleKind::MediaOnly,
+            ChannelRuleKind::TextOnly,
+            ChannelRuleKind::LinkOnlyFrom {
+                domains: vec!["youtube.com".to_owned()],
+            },
+        ];
+
+        for kind in &kinds {
+            notice(Lang::DEFAULT, kind, "<@1>".to_owned());
+        }
+    }
+}
@@ -0,0 +1,286 @@
+//! First message filter.
+//!
+//! Spam accounts often join a guild, post a scam and leave. The first message
+//! sent by new members is checked more strictly (see [`FirstMessageConfig`]):
+//! links that are not allowed by the link filter and mass mentions are
+//! forbidden, even if the link filter is disabled.
+//!
+//! Members are marked in the cache when they join the guild. The mark expires
+//! after a day, so members that have been in the guild for longer are not
+//! checked.
+//!
+//! Messages marked as false positives by moderators are not deleted (see the
+//! [`filter_feedback`] module).
+//!
+//! [`FirstMessageConfig`]: raidprotect_model::database::model::FirstMessageConfig
+//! [`filter_feedback`]: crate::feature::filter_feedback
+
+use raidprotect_model::database::model::{
+    FirstMessageAction, GuildConfig, LogCategory, ModlogUser, StatsEvent, StatsFilter,
+};
+use time::Duration;
+use tracing::{debug, error, info, warn};
+use twilight_http::request::AuditLogReason;
+use twilight_model::{
+    channel::Message,
+    guild::Member,
+    id::{marker::GuildMarker, Id},
+};
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    feature::{self, automation::AutoAction},
+    interaction::{embed, util::GuildConfigExt},
+    util::{
+        account::{account_created_at, new_account_marker},
+        link::{find_links, FoundLink},
+        logs::{self, LogMessage},
+    },
+};
+
+/// Duration (in seconds) of the timeout applied with
+/// [`FirstMessageAction::Timeout`].
+const TIMEOUT: i64 = 60 * 60;
+
+/// Reason why a first message is filtered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterReason {
+    /// The message contains a link that is not allowed.
+    Link(String),
+    /// The message contains too many mentions.
+    Mentions(usize),
+}
+
+/// Mark a member that joined a guild.
+pub async fn member_add(member: &Member, state: &ClusterState) {
+    if member.user.bot {
+        return;
+    }
+
+    if let Err(error) = member_add_inner(member, state).await {
+        error!(error = ?error, member = ?member, "failed to mark member first message");
+    }
+}
+
+async fn member_add_inner(member: &Member, state: &ClusterState) -> Result<(), anyhow::Error> {
+    let config = database::guild_config(state, member.guild_id).await?;
+
+    if config.first_message.enabled {
+        state
+            .cache
+            .mark_first_message(member.guild_id, member.user.id)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Check the first message sent by a member.
+pub async fn message_create(message: &Message, state: &ClusterState) {
+    let guild_id = match message.guild_id {
+        Some(guild_id) => guild_id,
+        None => return,
+    };
+
+    if let Err(error) = message_create_inner(message, guild_id, state).await {
+        error!(error = ?error, guild = ?guild_id, "failed to check first message");
+    }
+}
+
+async fn message_create_inner(
+    message: &Message,
+    guild_id: Id<GuildMarker>,
+    state: &ClusterState,
+) -> Result<(), anyhow::Error> {
+    let config = database::guild_config(state, guild_id).await?;
+
+    if !config.first_message.enabled {
+        return Ok(());
+    }
+
+    if let Some(member) = &message.member {
+        if config.is_automod_exempt(&[], &member.roles) {
+            return Ok(());
+        }
+    }
+
+    let user_id = message.author.id;
+    if !state.cache.take_first_message(guild_id, user_id).await? {
+        return Ok(());
+    }
+
+    let reason = match check_message(&config, message) {
+        Some(reason) => reason,
+        None => return Ok(()),
+    };
+
+    if feature::filter_feedback::is_suppressed(state, guild_id, &message.content).await {
+        debug!(guild = ?guild_id, user = ?user_id, "first message marked as false positive");
+        return Ok(());
+    }
+
+    info!(guild = ?guild_id, user = ?user_id, reason = ?reason, "first message filtered");
+
+    let event = StatsEvent::Filter(StatsFilter::FirstMessage);
+    feature::stats::record(state, guild_id, event).await;
+
+    state
+        .cache_http(guild_id)
+        .delete_message(message.channel_id, message.id)
+        .await?
+        .exec()
+        .await?;
+
+    feature::stats::record(state, guild_id, StatsEvent::MessagesDeleted(1)).await;
+
+    let lang = config.lang();
+    let action = config.first_message.action;
+    let (applied, auto_action) = match apply_action(state, &config, message, action).await {
+        Ok(auto_action) => (true, auto_action),
+        Err(error) => {
+            warn!(error = ?error, guild = ?guild_id, "failed to sanction member");
+            (false, None)
+        }
+    };
+
+    // Send a message in the logs channel.
+    let created_at = account_created_at(user_id);
+    let new_account = new_account_marker(created_at, config.new_account_threshold, lang);
+    let embed = embed::logs::first_message(
+        lang,
+        user_id,
+        message.channel_id,
+        &reason,
+        applied.then_some(action),
+        new_account,
+    );
+
+    let fingerprint = feature::filter_feedback::fingerprint(&message.content);
+    let mut components =
+        feature::filter_feedback::components(lang, StatsFilter::FirstMessage, &fingerprint);
+
+    if let Some(AutoAction::Timeout { case_id }) = auto_action {
+        components.push(feature::automation::adjust_components(lang, case_id));
+    }
+
+    let message = LogMessage::new(embed).components(components);
+    logs::post_message(state, &config, LogCategory::Filters, message).await?;
+
+    Ok(())
+}
+
+/// Check whether a first message is filtered.
+///
+/// Returns the reason displayed in the logs if the message is filtered.
+fn check_message(config: &GuildConfig, message: &Message) -> Option<FilterReason> {
+    let mentions = message.mentions.len()
+        + message.mention_roles.len()
+        + usize::from(message.mention_everyone);
+
+    if let Some(link) = filtered_link(config, &message.content) {
+        Some(FilterReason::Link(link))
+    } else if mentions > config.first_message.max_mentions.into() {
+        Some(FilterReason::Mentions(mentions))
+    } else {
+        None
+    }
+}
+
+/// Find a link that is not allowed in a first message.
+///
+/// Links allowed by the [`LinkFilterConfig`] are also allowed in first
+/// messages.
+///
+/// [`LinkFilterConfig`]: raidprotect_model::database::model::LinkFilterConfig
+fn filtered_link(config: &GuildConfig, content: &str) -> Option<String> {
+    if !config.first_message.links {
+        return None;
+    }
+
+    find_links(content)
+        .into_iter()
+        .find(|link| match link {
+            FoundLink::Invite(code) => !config.link_filter.invite_allowed(code),
+            FoundLink::External { domain, .. } => !config.link_filter.domain_allowed(domain),
+        })
+        .map(|link| link.to_string())
+}
+
+/// Apply the configured action to the author of a filtered message.
+///
+/// Returns the [`AutoAction`] of the timeout, if any.
+async fn apply_action(
+    state: &ClusterState,
+    config: &GuildConfig,
+    message: &Message,
+    action: FirstMessageAction,
+) -> Result<Option<AutoAction>, anyhow::Error> {
+    let guild_id = config.id;
+    let user_id = message.author.id;
+    let lang = config.lang();
+
+    match action {
+        FirstMessageAction::Delete => {}
+        FirstMessageAction::Timeout => {
+            // New members are never trusted, so the member information is not
+            // passed and the timeout is not softened.
+            let action = feature::automation::timeout(
+                state,
+                config,
+                &message.author,
+                None,
+                Duration::seconds(TIMEOUT),
+                lang.first_message_sanction_reason(),
+            )
+            .await?;
+
+            return Ok(Some(action));
+        }
+        FirstMessageAction::Ban => {
+            let reason = lang.first_message_sanction_reason();
+
+            state
+                .http
+                .create_ban(guild_id, user_id)
+                .reason(reason)?
+                .exec()
+                .await?;
+
+            let user = ModlogUser::from(&message.author);
+            let moderator = feature::automation::automatic_moderator(state.current_user.cast());
+            if let Err(error) =
+                feature::automation::record_ban(state, guild_id, user, moderator, reason).await
+            {
+                error!(error = ?error, guild = ?guild_id, "failed to record first message ban");
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use raidprotect_model::database::model::FirstMessageConfig;
+
+    use super::*;
+
+    #[test]
+    fn test_filtered_link() {
+        let mut config = GuildConfig::new(Id::new(1));
+        config.first_message = FirstMessageConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        config.link_filter.allowed_domains = vec!["raidprotect.org".to_owned()];
+
+        assert_eq!(filtered_link(&config, "hello"), None);
+        assert_eq!(filtered_link(&config, "https://docs.raidprotect.org"), None);
+        assert!(filtered_link(&config, "free nitro: https://dlscord.gift/abc").is_some());
+        assert!(filtered_link(&config, "discord.gg/raid").is_some());
+
+        config.first_message.links = false;
+        assert_eq!(filtered_link(&config, "discord.gg/raid"), None);
+    }
+}
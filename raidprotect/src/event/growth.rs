@@ -0,0 +1,331 @@
+//! Member count tracking.
+//!
+//! The member count of each guild is updated as members join and leave (see
+//! [`CacheClient::update_member_count`]), and sampled at most once per
+//! [`SAMPLE_INTERVAL`] in the [`GuildGrowth`] of the guild.
+//!
+//! ## Abnormal growth
+//!
+//! Bought members and raids make a guild grow much faster than usual. The
+//! growth since the last sample is compared with the average growth of the
+//! sampled history (see [`GrowthRate`]). The growth is abnormal if it exceeds
+//! both [`MIN_ABNORMAL_RATE`] members per hour and [`ABNORMAL_FACTOR`] times
+//! the usual growth. An abnormal growth is reported in the logs channel when
+//! a sample is recorded, and raises the confidence of join bursts (see
+//! [`join_burst`]).
+//!
+//! ## Milestones
+//!
+//! If [`GuildConfig::milestones_chan`] is set, a message is sent in this
+//! channel when the member count reaches a milestone (see [`is_milestone`]).
+//! Milestones are not announced while the growth is abnormal, and each
+//! milestone is only announced once.
+//!
+//! [`CacheClient::update_member_count`]: raidprotect_model::cache::CacheClient::update_member_count
+//! [`GuildGrowth`]: raidprotect_model::database::model::GuildGrowth
+//! [`GuildConfig::milestones_chan`]: raidprotect_model::database::model::GuildConfig::milestones_chan
+//! [`join_burst`]: super::join_burst
+
+use raidprotect_model::{
+    cache::discord::CachedGuild,
+    database::model::{Feature, GrowthSample, LogCategory},
+};
+use time::{Duration, OffsetDateTime};
+use tracing::{error, info};
+use twilight_model::{
+    gateway::payload::incoming::MemberRemove,
+    guild::Member,
+    id::{marker::GuildMarker, Id},
+};
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    interaction::{embed, util::GuildConfigExt},
+    util::logs,
+};
+
+/// Minimum interval between two member count samples.
+pub const SAMPLE_INTERVAL: Duration = Duration::HOUR;
+
+/// Minimum sampled history to compute the usual growth of a guild.
+pub const MIN_HISTORY: Duration = Duration::DAY;
+
+/// Minimum number of members gained per hour for an abnormal growth.
+pub const MIN_ABNORMAL_RATE: i64 = 20;
+
+/// Factor of the usual growth above which the growth is abnormal.
+pub const ABNORMAL_FACTOR: i64 = 5;
+
+/// Smallest member count announced as a milestone.
+const FIRST_MILESTONE: u64 = 100;
+
+/// Growth rate of a guild, in members per hour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GrowthRate {
+    /// Growth since the last sample.
+    pub current: i64,
+    /// Average growth of the sampled history.
+    ///
+    /// This field is [`None`] if the history is shorter than
+    /// [`MIN_HISTORY`].
+    pub usual: Option<i64>,
+}
+
+impl GrowthRate {
+    /// Whether the current growth is abnormal.
+    ///
+    /// The growth is never abnormal without enough history.
+    pub fn is_abnormal(&self) -> bool {
+        match self.usual {
+            Some(usual) => {
+                self.current >= MIN_ABNORMAL_RATE && self.current >= usual.max(1) * ABNORMAL_FACTOR
+            }
+            None => false,
+        }
+    }
+}
+
+/// Handle `MemberAdd` event.
+pub async fn member_add(member: &Member, state: &ClusterState) {
+    if let Err(error) = member_change(member.guild_id, 1, state).await {
+        error!(error = ?error, guild = ?member.guild_id, "failed to track member count");
+    }
+}
+
+/// Handle `MemberRemove` event.
+pub async fn member_remove(member: &MemberRemove, state: &ClusterState) {
+    if let Err(error) = member_change(member.guild_id, -1, state).await {
+        error!(error = ?error, guild = ?member.guild_id, "failed to track member count");
+    }
+}
+
+async fn member_change(
+    guild_id: Id<GuildMarker>,
+    delta: i64,
+    state: &ClusterState,
+) -> Result<(), anyhow::Error> {
+    let guild = match state.cache.get::<CachedGuild>(&guild_id).await? {
+        Some(guild) => guild,
+        None => return Ok(()),
+    };
+
+    let members = state
+        .cache
+        .update_member_count(guild_id, delta, guild.member_count)
+        .await?;
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+
+    if delta > 0 && is_milestone(members) {
+        announce_milestone(state, &guild, members, now).await?;
+    }
+
+    let key = format!("growth-sample:{guild_id}");
+    if !state.cache.throttle(&key, SAMPLE_INTERVAL).await? {
+        return Ok(());
+    }
+
+    let sample = GrowthSample { at: now, members };
+    let growth = state
+        .database
+        .record_growth_sample(guild_id, sample)
+        .await?;
+
+    // The growth is computed without the sample that has just been recorded.
+    let history = &growth.samples[..growth.samples.len().saturating_sub(1)];
+    match growth_rate(history, members, now) {
+        Some(rate) if rate.is_abnormal() => {
+            let config = database::guild_config(state, guild_id).await?;
+
+            if config.features.contains(Feature::RaidDetection) {
+                info!(guild = ?guild_id, rate = ?rate, "abnormal growth detected");
+
+                let embed = embed::logs::abnormal_growth(config.lang(), rate);
+                logs::post(state, &config, LogCategory::Raids, embed).await?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Get the current growth rate of a guild.
+///
+/// Returns [`None`] if the member count has not been sampled yet.
+pub async fn rate(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+) -> Result<Option<GrowthRate>, anyhow::Error> {
+    let members = match state.cache.member_count(guild_id).await? {
+        Some(members) => members,
+        None => return Ok(None),
+    };
+
+    let rate = state
+        .database
+        .get_growth(guild_id)
+        .await?
+        .and_then(|growth| {
+            let now = OffsetDateTime::now_utc().unix_timestamp();
+            growth_rate(&growth.samples, members, now)
+        });
+
+    Ok(rate)
+}
+
+/// Announce a member count milestone in the milestones channel.
+async fn announce_milestone(
+    state: &ClusterState,
+    guild: &CachedGuild,
+    members: u64,
+    now: i64,
+) -> Result<(), anyhow::Error> {
+    let config = database::guild_config(state, guild.id).await?;
+    let channel = match config.milestones_chan {
+        Some(channel) => channel,
+        None => return Ok(()),
+    };
+
+    // Milestones are claimed in the growth document, which is created with
+    // the first sample of the guild.
+    let growth = match state.database.get_growth(guild.id).await? {
+        Some(growth) => growth,
+        None => return Ok(()),
+    };
+
+    let rate = growth_rate(&growth.samples, members, now);
+    if rate.map_or(false, |rate| rate.is_abnormal()) {
+        info!(guild = ?guild.id, members, "milestone not announced due to abnormal growth");
+        return Ok(());
+    }
+
+    if !state
+        .database
+        .claim_growth_milestone(guild.id, members)
+        .await?
+    {
+        return Ok(());
+    }
+
+    let embed = embed::post_in_chat::milestone(config.lang(), &guild.name, members);
+    state
+        .http
+        .create_message(channel)
+        .embeds(&[embed])?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+/// Compute the growth rate from the sampled history.
+///
+/// The current growth is computed since the last sample, over at least
+/// [`SAMPLE_INTERVAL`] so that a few joins right after a sample are not
+/// extrapolated. Returns [`None`] if there is no sample.
+pub fn growth_rate(samples: &[GrowthSample], members: u64, now: i64) -> Option<GrowthRate> {
+    let (first, last) = (samples.first()?, samples.last()?);
+    let hour = Duration::HOUR.whole_seconds();
+
+    let elapsed = (now - last.at).max(SAMPLE_INTERVAL.whole_seconds());
+    let current = (members as i64 - last.members as i64) * hour / elapsed;
+
+    let history = last.at - first.at;
+    let usual = (history >= MIN_HISTORY.whole_seconds())
+        .then(|| (last.members as i64 - first.members as i64) * hour / history);
+
+    Some(GrowthRate { current, usual })
+}
+
+/// Whether a member count is a milestone.
+///
+/// Milestones are 1, 2.5 and 5 times a power of ten, starting at
+/// [`FIRST_MILESTONE`] (100, 250, 500, 1000, 2500...).
+pub fn is_milestone(members: u64) -> bool {
+    if members < FIRST_MILESTONE {
+        return false;
+    }
+
+    let mut significand = members;
+    while significand % 10 == 0 {
+        significand /= 10;
+    }
+
+    matches!(significand, 1 | 25 | 5)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 3600;
+
+    fn sample(hour: i64, members: u64) -> GrowthSample {
+        GrowthSample {
+            at: hour * HOUR,
+            members,
+        }
+    }
+
+    #[test]
+    fn test_is_milestone() {
+        for members in [100, 250, 500, 1000, 2500, 5000, 10_000, 1_000_000] {
+            assert!(is_milestone(members), "{members} is not a milestone");
+        }
+
+        for members in [0, 1, 25, 50, 150, 200, 1001, 7500] {
+            assert!(!is_milestone(members), "{members} is a milestone");
+        }
+    }
+
+    #[test]
+    fn test_growth_rate_empty() {
+        assert_eq!(growth_rate(&[], 100, 0), None);
+    }
+
+    #[test]
+    fn test_growth_rate_short_history() {
+        let samples = [sample(0, 100), sample(2, 110)];
+        let rate = growth_rate(&samples, 200, 3 * HOUR).unwrap();
+
+        assert_eq!(rate.current, 90);
+        assert_eq!(rate.usual, None);
+        assert!(!rate.is_abnormal());
+    }
+
+    #[test]
+    fn test_growth_rate_organic() {
+        let samples = [sample(0, 1000), sample(24, 1048)];
+
+        // Joins right after a sample are averaged over the sampling interval.
+        let rate = growth_rate(&samples, 1050, 24 * HOUR + 60).unwrap();
+        assert_eq!(
+            rate,
+            GrowthRate {
+                current: 2,
+                usual: Some(2)
+            }
+        );
+        assert!(!rate.is_abnormal());
+
+        // Members slowly joining while the bot was offline.
+        let rate = growth_rate(&samples, 1100, 48 * HOUR).unwrap();
+        assert_eq!(rate.current, 2);
+        assert!(!rate.is_abnormal());
+    }
+
+    #[test]
+    fn test_growth_rate_abnormal() {
+        let samples = [sample(0, 1000), sample(24, 1048)];
+
+        let rate = growth_rate(&samples, 1098, 24 * HOUR + 1800).unwrap();
+        assert_eq!(rate.current, 50);
+        assert!(rate.is_abnormal());
+
+        // Slow-growing guilds still need the minimum rate.
+        let samples = [sample(0, 1000), sample(48, 1000)];
+        let rate = growth_rate(&samples, 1015, 49 * HOUR).unwrap();
+        assert!(!rate.is_abnormal());
+    }
+}
@@ -0,0 +1,336 @@
+//! Join burst detection.
+//!
+//! Raids usually start with many accounts joining the guild at the same time.
+//! When the join burst detection is enabled, a burst is detected when more
+//! than the configured number of members join during the interval.
+//!
+//! A high join rate alone is not enough to detect a raid: guilds shared on a
+//! popular website also receive many joins. The accounts of a burst are thus
+//! checked for the configured [`JoinBurstSignal`]s, which are rarely shared
+//! by organic joins:
+//! - [`JoinBurstSignal::Avatar`]: accounts use the same avatar, or all have a
+//!   default avatar.
+//! - [`JoinBurstSignal::Name`]: accounts have the same name once digits are
+//!   removed, such as accounts created with sequential names.
+//!
+//! A signal matches when the largest group of accounts sharing the same value
+//! contains at least [`JoinBurstConfig::cluster_ratio`] percent of the burst.
+//! Bursts with matching signals, or happening while the guild grows much
+//! faster than usual (see [`growth`]), are reported with a high
+//! [`Confidence`] as an anti-raid alert (see [`raid_alert`]). Other bursts are
+//! only reported in the logs channel.
+//!
+//! Alerts are sent at most once per interval.
+
+use std::{collections::HashMap, hash::Hash};
+
+use raidprotect_model::{
+    cache::model::join::TrackedJoin,
+    database::model::{Feature, JoinBurstConfig, JoinBurstSignal, LogCategory},
+};
+use time::Duration;
+use tracing::{error, info};
+use twilight_model::guild::Member;
+
+use super::growth::{self, GrowthRate};
+use crate::{
+    cluster::ClusterState,
+    database,
+    feature::raid_alert,
+    interaction::{embed, util::GuildConfigExt},
+    util::logs,
+};
+
+/// Minimum length of a name pattern.
+///
+/// Shorter patterns, such as the pattern of names made only of digits, are
+/// too common to be a signal of coordinated accounts.
+const MIN_PATTERN_LENGTH: usize = 3;
+
+/// Confidence that a join burst is a raid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confidence {
+    /// Only the join rate exceeded the limit.
+    Low,
+    /// At least one signal of coordinated accounts matched, or the guild
+    /// grows abnormally.
+    High,
+}
+
+/// Signal that matched on the accounts of a join burst.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignalMatch {
+    /// Matched signal.
+    pub signal: JoinBurstSignal,
+    /// Number of accounts sharing the same signal value.
+    pub count: usize,
+}
+
+/// Join burst detected in a guild.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JoinBurst {
+    /// Number of joins during the interval.
+    pub joins: usize,
+    /// Signals that matched on the accounts of the burst.
+    pub signals: Vec<SignalMatch>,
+    /// Growth rate of the guild, if known.
+    pub growth: Option<GrowthRate>,
+}
+
+impl JoinBurst {
+    /// Confidence that the burst is a raid.
+    pub fn confidence(&self) -> Confidence {
+        match self.signals.is_empty() && !self.abnormal_growth() {
+            true => Confidence::Low,
+            false => Confidence::High,
+        }
+    }
+
+    /// Whether the guild grows abnormally during the burst.
+    pub fn abnormal_growth(&self) -> bool {
+        self.growth.map_or(false, |growth| growth.is_abnormal())
+    }
+}
+
+/// Handle `MemberAdd` event.
+pub async fn member_add(member: &Member, state: &ClusterState) {
+    if member.user.bot {
+        return;
+    }
+
+    if let Err(error) = member_add_inner(member, state).await {
+        error!(error = ?error, guild = ?member.guild_id, "error while processing join burst detection");
+    }
+}
+
+async fn member_add_inner(member: &Member, state: &ClusterState) -> Result<(), anyhow::Error> {
+    let guild_id = member.guild_id;
+    let config = database::guild_config(state, guild_id).await?;
+
+    if !config.join_burst.enabled || !config.features.contains(Feature::RaidDetection) {
+        return Ok(());
+    }
+
+    let join = TrackedJoin {
+        user_id: member.user.id,
+        avatar: member.user.avatar,
+        name: member.user.name.clone(),
+    };
+    let interval = Duration::seconds(config.join_burst.interval.into());
+    let joins = state.cache.track_join(guild_id, &join, interval).await?;
+
+    let mut burst = match detect(&config.join_burst, &joins) {
+        Some(burst) => burst,
+        None => return Ok(()),
+    };
+
+    // A single alert is sent for each burst.
+    let key = format!("join_burst:{guild_id}");
+    if !state.cache.throttle(&key, interval).await? {
+        return Ok(());
+    }
+
+    burst.growth = growth::rate(state, guild_id).await?;
+
+    let confidence = burst.confidence();
+    info!(
+        guild = ?guild_id,
+        joins = burst.joins,
+        confidence = ?confidence,
+        signals = ?burst.signals,
+        growth = ?burst.growth,
+        "join burst detected"
+    );
+
+    let lang = config.lang();
+    let embed = embed::logs::join_burst(lang, &burst, config.join_burst.interval);
+
+    match confidence {
+        Confidence::High => raid_alert::send(state, &config, embed).await?,
+        Confidence::Low => logs::post(state, &config, LogCategory::Raids, embed).await?,
+    }
+
+    Ok(())
+}
+
+/// Detect a join burst from the recent joins of a guild.
+///
+/// Returns [`None`] if the number of joins does not exceed the limit. The
+/// growth rate of the returned burst is not set.
+pub fn detect(config: &JoinBurstConfig, joins: &[TrackedJoin]) -> Option<JoinBurst> {
+    if joins.len() <= config.max_joins.into() {
+        return None;
+    }
+
+    let signals = config
+        .signals
+        .iter()
+        .filter_map(|&signal| {
+            let count = match signal {
+                JoinBurstSignal::Avatar => largest_cluster(joins.iter().map(|join| join.avatar)),
+                JoinBurstSignal::Name => {
+                    largest_cluster(joins.iter().filter_map(|join| name_pattern(&join.name)))
+                }
+            };
+
+            let matched = count >= 2 && count * 100 >= joins.len() * config.cluster_ratio as usize;
+            matched.then_some(SignalMatch { signal, count })
+        })
+        .collect();
+
+    Some(JoinBurst {
+        joins: joins.len(),
+        signals,
+        growth: None,
+    })
+}
+
+/// Get the size of the largest group of equal values.
+fn largest_cluster<T: Eq + Hash>(values: impl Iterator<Item = T>) -> usize {
+    let mut clusters: HashMap<T, usize> = HashMap::new();
+    for value in values {
+        *clusters.entry(value).or_default() += 1;
+    }
+
+    clusters.into_values().max().unwrap_or_default()
+}
+
+/// Get the pattern of a name.
+///
+/// The pattern is the lowercase name without digits, so that sequential names
+/// such as `raider01` and `Raider02` share the same pattern. Returns [`None`]
+/// if the pattern is shorter than [`MIN_PATTERN_LENGTH`].
+fn name_pattern(name: &str) -> Option<String> {
+    let pattern = name
+        .chars()
+        .filter(|c| !c.is_numeric())
+        .flat_map(char::to_lowercase)
+        .collect::<String>();
+    let pattern = pattern.trim();
+
+    if pattern.chars().count() < MIN_PATTERN_LENGTH {
+        return None;
+    }
+
+    Some(pattern.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::{id::Id, util::ImageHash};
+
+    use super::*;
+
+    const AVATAR: &str = "1acefe340fafb4ecefae407f3abdb323";
+
+    fn join(id: u64, name: &str, avatar: Option<&str>) -> TrackedJoin {
+        TrackedJoin {
+            user_id: Id::new(id),
+            avatar: avatar.map(|avatar| ImageHash::parse(avatar.as_bytes()).unwrap()),
+            name: name.to_owned(),
+        }
+    }
+
+    fn config(max_joins: u16) -> JoinBurstConfig {
+        JoinBurstConfig {
+            max_joins,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_name_pattern() {
+        assert_eq!(name_pattern("Raider01").as_deref(), Some("raider"));
+        assert_eq!(name_pattern("raider 2 "), name_pattern("RAIDER 3"));
+        assert_eq!(name_pattern("12345"), None);
+        assert_eq!(name_pattern("ab1"), None);
+    }
+
+    #[test]
+    fn test_detect_below_limit() {
+        let joins = vec![join(1, "raider1", None), join(2, "raider2", None)];
+
+        assert_eq!(detect(&config(2), &joins), None);
+    }
+
+    #[test]
+    fn test_detect_organic() {
+        let joins = vec![
+            join(1, "alice", Some(AVATAR)),
+            join(2, "bob", None),
+            join(3, "charlie", Some("a_b2a6536641da91a0b59bd66557c56c36")),
+            join(4, "dave", Some("b2a6536641da91a0b59bd66557c56c36")),
+        ];
+
+        let burst = detect(&config(3), &joins).unwrap();
+        assert_eq!(burst.joins, 4);
+        assert!(burst.signals.is_empty());
+        assert_eq!(burst.confidence(), Confidence::Low);
+    }
+
+    #[test]
+    fn test_detect_coordinated() {
+        let joins = vec![
+            join(1, "raider01", Some(AVATAR)),
+            join(2, "raider02", Some(AVATAR)),
+            join(3, "Raider03", Some(AVATAR)),
+            join(4, "alice", None),
+        ];
+
+        let burst = detect(&config(3), &joins).unwrap();
+        assert_eq!(
+            burst.signals,
+            [
+                SignalMatch {
+                    signal: JoinBurstSignal::Avatar,
+                    count: 3
+                },
+                SignalMatch {
+                    signal: JoinBurstSignal::Name,
+                    count: 3
+                },
+            ]
+        );
+        assert_eq!(burst.confidence(), Confidence::High);
+    }
+
+    #[test]
+    fn test_confidence_growth() {
+        let mut burst = JoinBurst {
+            joins: 12,
+            signals: Vec::new(),
+            growth: Some(GrowthRate {
+                current: 10,
+                usual: Some(2),
+            }),
+        };
+        assert_eq!(burst.confidence(), Confidence::Low);
+
+        burst.growth = Some(GrowthRate {
+            current: 50,
+            usual: Some(2),
+        });
+        assert_eq!(burst.confidence(), Confidence::High);
+    }
+
+    #[test]
+    fn test_detect_signals_config() {
+        let joins = vec![
+            join(1, "raider01", None),
+            join(2, "raider02", None),
+            join(3, "alice", Some(AVATAR)),
+            join(4, "bob", Some(AVATAR)),
+        ];
+
+        // Only the enabled signals are checked.
+        let mut config = config(3);
+        config.signals = vec![JoinBurstSignal::Name];
+        let burst = detect(&config, &joins).unwrap();
+        assert_eq!(burst.signals.len(), 1);
+
+        // Half of the accounts is not enough with a higher ratio.
+        config.cluster_ratio = 75;
+        let burst = detect(&config, &joins).unwrap();
+        assert_eq!(burst.confidence(), Confidence::Low);
+    }
+}
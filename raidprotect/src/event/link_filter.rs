@@ -0,0 +1,208 @@
+//! Invite and link spam detection.
+//!
+//! Messages containing Discord invites or external links that are not
+//! allowed by the [`LinkFilterConfig`] are deleted, and the author may be
+//! timed out. Links are detected even when obfuscated (see [`util::link`]).
+//! The timeout of trusted members is softened (see the [`automation`]
+//! module).
+//!
+//! The links of watched users are notified even if the filter is disabled,
+//! without deleting the message (see the [`watch`] module).
+//!
+//! Messages marked as false positives by moderators are not deleted (see the
+//! [`filter_feedback`] module).
+//!
+//! [`util::link`]: crate::util::link
+//! [`automation`]: crate::feature::automation
+//! [`watch`]: crate::feature::watch
+//! [`filter_feedback`]: crate::feature::filter_feedback
+
+use raidprotect_model::database::model::{
+    Feature, LinkFilterConfig, LogCategory, StatsEvent, StatsFilter,
+};
+use time::Duration;
+use tracing::{debug, error, info, warn};
+use twilight_model::{
+    channel::Message,
+    id::{marker::GuildMarker, Id},
+};
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    feature::{
+        self,
+        automation::{AutoAction, MemberTrust},
+        watch::WatchEvent,
+    },
+    interaction::{embed, util::GuildConfigExt},
+    util::{
+        account::{account_created_at, new_account_marker},
+        link::{find_links, FoundLink},
+        logs::{self, LogMessage},
+    },
+};
+
+/// Check the links of a message sent in a guild.
+pub async fn message_create(message: &Message, state: &ClusterState) {
+    let guild_id = match message.guild_id {
+        Some(guild_id) => guild_id,
+        None => return,
+    };
+
+    if let Err(error) = message_create_inner(message, guild_id, state).await {
+        error!(error = ?error, guild = ?guild_id, "failed to check message links");
+    }
+}
+
+async fn message_create_inner(
+    message: &Message,
+    guild_id: Id<GuildMarker>,
+    state: &ClusterState,
+) -> Result<(), anyhow::Error> {
+    let config = database::guild_config(state, guild_id).await?;
+    let link_filter = &config.link_filter;
+
+    // Links of watched users are notified even if the filter is disabled.
+    let dry_run = !link_filter.enabled || !config.features.contains(Feature::LinkFilter);
+
+    if let Some(member) = &message.member {
+        if config.is_automod_exempt(&link_filter.trusted_roles, &member.roles) {
+            return Ok(());
+        }
+    }
+
+    let link = match find_links(&message.content)
+        .into_iter()
+        .find(|link| is_filtered(link_filter, link))
+    {
+        Some(link) => link,
+        None => return Ok(()),
+    };
+
+    let user_id = message.author.id;
+    let watch_event = WatchEvent::LinkFilter {
+        channel: message.channel_id,
+        link: link.to_string(),
+        dry_run,
+    };
+
+    if dry_run {
+        feature::watch::notify(state, guild_id, user_id, watch_event).await;
+        return Ok(());
+    }
+
+    if feature::filter_feedback::is_suppressed(state, guild_id, &message.content).await {
+        debug!(guild = ?guild_id, user = ?user_id, "filtered link marked as false positive");
+        return Ok(());
+    }
+
+    info!(guild = ?guild_id, user = ?user_id, link = %link, "filtered link deleted");
+
+    let event = StatsEvent::Filter(StatsFilter::Link);
+    feature::stats::record(state, guild_id, event).await;
+
+    state
+        .cache_http(guild_id)
+        .delete_message(message.channel_id, message.id)
+        .await?
+        .exec()
+        .await?;
+
+    feature::stats::record(state, guild_id, StatsEvent::MessagesDeleted(1)).await;
+    feature::watch::notify(state, guild_id, user_id, watch_event).await;
+
+    let lang = config.lang();
+    let action = match link_filter.timeout {
+        Some(timeout) => {
+            let result = feature::automation::timeout(
+                state,
+                &config,
+                &message.author,
+                message.member.as_ref().map(MemberTrust::from),
+                Duration::seconds(timeout.into()),
+                lang.link_filter_reason(),
+            );
+
+            match result.await {
+                Ok(action) => Some(action),
+                Err(error) => {
+                    warn!(error = ?error, guild = ?guild_id, "failed to timeout member");
+                    None
+                }
+            }
+        }
+        None => None,
+    };
+
+    // Send a message in the logs channel.
+    let created_at = account_created_at(user_id);
+    let new_account = new_account_marker(created_at, config.new_account_threshold, lang);
+    let mut embed = embed::logs::link_filter(
+        lang,
+        user_id,
+        message.channel_id,
+        &link.to_string(),
+        matches!(action, Some(AutoAction::Timeout { .. })),
+        new_account,
+    );
+
+    if let Some(AutoAction::Softened(reason)) = action {
+        embed = embed::logs::softened(lang, embed, reason);
+    }
+
+    let fingerprint = feature::filter_feedback::fingerprint(&message.content);
+    let mut components =
+        feature::filter_feedback::components(lang, StatsFilter::Link, &fingerprint);
+
+    if let Some(AutoAction::Timeout { case_id }) = action {
+        components.push(feature::automation::adjust_components(lang, case_id));
+    }
+
+    let message = LogMessage::new(embed).components(components);
+    logs::post_message(state, &config, LogCategory::Filters, message).await?;
+
+    Ok(())
+}
+
+/// Whether a link is filtered by the configuration.
+fn is_filtered(config: &LinkFilterConfig, link: &FoundLink) -> bool {
+    match link {
+        FoundLink::Invite(code) => config.invites && !config.invite_allowed(code),
+        FoundLink::External { domain, .. } => config.links && !config.domain_allowed(domain),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_filtered() {
+        let config = LinkFilterConfig {
+            allowed_domains: vec!["raidprotect.org".to_owned()],
+            allowed_invites: vec!["raidprotect".to_owned()],
+            ..Default::default()
+        };
+        let external = |domain: &str| FoundLink::External {
+            domain: domain.to_owned(),
+            link: domain.to_owned(),
+        };
+
+        assert!(is_filtered(&config, &FoundLink::Invite("raid".to_owned())));
+        assert!(!is_filtered(
+            &config,
+            &FoundLink::Invite("raidprotect".to_owned())
+        ));
+
+        // External links are not filtered by default.
+        assert!(!is_filtered(&config, &external("example.com")));
+
+        let config = LinkFilterConfig {
+            links: true,
+            ..config
+        };
+        assert!(is_filtered(&config, &external("example.com")));
+        assert!(!is_filtered(&config, &external("docs.raidprotect.org")));
+    }
+}
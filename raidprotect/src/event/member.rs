@@ -0,0 +1,92 @@
+//! Members records.
+//!
+//! The last-known data of the guild members is recorded when they join, are
+//! updated or leave the guild. These records are displayed with the
+//! `/userinfo` command.
+
+use raidprotect_model::cache::model::member::MemberRecord;
+use time::OffsetDateTime;
+use tracing::error;
+use twilight_model::{
+    gateway::payload::incoming::{MemberRemove, MemberUpdate},
+    guild::Member,
+    id::{
+        marker::{GuildMarker, UserMarker},
+        Id,
+    },
+    util::Timestamp,
+};
+
+use crate::cluster::ClusterState;
+
+/// Record a member that joined a guild.
+pub async fn member_add(member: &Member, state: &ClusterState) {
+    let record = MemberRecord {
+        guild_id: member.guild_id,
+        user_id: member.user.id,
+        joined_at: member.joined_at,
+        roles: member.roles.clone(),
+        left_at: None,
+        pending: member.pending,
+    };
+
+    if let Err(error) = state.cache.set(&record).await {
+        error!(error = ?error, member = ?member, "failed to record member");
+    }
+}
+
+/// Record a member that has been updated.
+pub async fn member_update(member: &MemberUpdate, state: &ClusterState) {
+    let record = MemberRecord {
+        guild_id: member.guild_id,
+        user_id: member.user.id,
+        joined_at: member.joined_at,
+        roles: member.roles.clone(),
+        left_at: None,
+        pending: member.pending,
+    };
+
+    if let Err(error) = state.cache.set(&record).await {
+        error!(error = ?error, member = ?member, "failed to record member");
+    }
+}
+
+/// Get the record of a member, before a `MemberUpdate` event is processed.
+pub async fn cached_record(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+) -> Option<MemberRecord> {
+    match state.cache.get::<MemberRecord>(&(guild_id, user_id)).await {
+        Ok(record) => record,
+        Err(error) => {
+            error!(error = ?error, "failed to get member record");
+            None
+        }
+    }
+}
+
+/// Record a member that left a guild.
+///
+/// The record is only updated if the member has already been recorded.
+pub async fn member_remove(member: &MemberRemove, state: &ClusterState) {
+    if let Err(error) = member_remove_inner(member, state).await {
+        error!(error = ?error, member = ?member, "failed to record member");
+    }
+}
+
+async fn member_remove_inner(
+    member: &MemberRemove,
+    state: &ClusterState,
+) -> Result<(), anyhow::Error> {
+    let id = (member.guild_id, member.user.id);
+
+    if let Some(mut record) = state.cache.get::<MemberRecord>(&id).await? {
+        let now = Timestamp::from_secs(OffsetDateTime::now_utc().unix_timestamp())?;
+        record.left_at = Some(now);
+
+        state.cache.set(&record).await?;
+    }
+
+    Ok(())
+}
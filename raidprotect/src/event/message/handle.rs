@@ -1,5 +1,11 @@
 use anyhow::Context;
-use raidprotect_model::{cache::discord::CachedGuild, database::model::GuildConfig};
+use raidprotect_model::{
+    cache::{
+        discord::CachedGuild,
+        model::message::{CachedMessage, DeletedMessage},
+    },
+    database::model::GuildConfig,
+};
 use tracing::{error, info};
 use twilight_model::{channel::Message, gateway::payload::incoming::MessageDelete};
 
@@ -9,6 +15,8 @@ use super::{
 };
 use crate::{
     cluster::ClusterState,
+    database,
+    event::{activity, channel_rules, first_message, link_filter, pattern_rules},
     interaction::{component::captcha::verification_message, util::GuildConfigExt},
 };
 
@@ -25,6 +33,28 @@ pub async fn handle_message_create(message: Message, state: &ClusterState) {
     let parsed = parse_message(&message);
     state.cache.set(&parsed).await.ok();
 
+    // Index the message to allow the messages of a user to be cleaned up.
+    if let Some(guild_id) = message.guild_id {
+        if let Err(error) = state.cache.index_message(guild_id, &parsed).await {
+            error!(error = ?error, "failed to index message");
+        }
+    }
+
+    // Apply stricter checks to the first message of new members.
+    first_message::message_create(&message, state).await;
+
+    // Delete the message if it contains a filtered link.
+    link_filter::message_create(&message, state).await;
+
+    // Apply the custom pattern rules.
+    pattern_rules::message_create(&message, state).await;
+
+    // Delete the message if it does not follow the channel rule.
+    channel_rules::message_create(&message, state).await;
+
+    // Count the message for the activity role.
+    activity::message_create(&message, state).await;
+
     // Warn the user if they're using an old command.
     if is_old_command(&message.content) {
         let (message, state) = (message.clone(), state.clone());
@@ -55,9 +85,15 @@ async fn handle_message_delete_inner(
         .guild_id
         .context("missing guild_id in message delete event")?;
 
-    let mut config = state
-        .database
-        .get_guild_or_create(guild_id)
+    // Keep the content of the message to allow moderators to restore it.
+    if let Some(message) = state.cache.get::<CachedMessage>(&event.id).await? {
+        state
+            .cache
+            .set(&DeletedMessage { guild_id, message })
+            .await?;
+    }
+
+    let mut config = database::guild_config(state, guild_id)
         .await
         .context("failed to get guild configuration")?;
 
@@ -88,7 +124,7 @@ async fn resend_captcha_message(
 
     // Update guild configuration.
     config.captcha.message = Some(message.id);
-    state.database.update_guild(config).await?;
+    database::update_guild_config(state, config).await?;
 
     Ok(())
 }
@@ -3,9 +3,12 @@ use std::collections::HashMap;
 use anyhow::bail;
 use once_cell::sync::Lazy;
 use twilight_model::channel::Message;
-use twilight_util::builder::embed::EmbedBuilder;
 
-use crate::{cluster::ClusterState, interaction::embed::COLOR_TRANSPARENT, translations::Lang};
+use crate::{
+    cluster::ClusterState,
+    interaction::embed::{EmbedBuilder, COLOR_TRANSPARENT},
+    translations::Lang,
+};
 
 /// Mapping of old command names to new command names.
 static OLD_COMMANDS: Lazy<HashMap<&str, &str>> = Lazy::new(|| {
@@ -5,8 +5,27 @@
 //!
 //! The user-side event handling is done in the `raidprotect_handler` crate.
 
+mod activity;
+mod antinuke;
 mod captcha;
+mod channel_rules;
+pub mod first_message;
+pub mod growth;
+pub mod join_burst;
+mod link_filter;
+mod member;
 mod message;
+mod mute;
+mod name;
+mod pattern_rules;
+pub mod pins;
 mod process;
+mod quarantine;
+pub mod raid_mode;
+mod reaction;
+pub mod role_logs;
+mod screening;
+mod sentinel;
+pub mod voice;
 
 pub use process::ProcessEvent;
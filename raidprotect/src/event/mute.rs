@@ -0,0 +1,293 @@
+//! Mute reapplication.
+//!
+//! Members muted with the mute role could leave and rejoin the guild to get
+//! rid of the role. When a member joins, the role is added again if the
+//! member still has an active mute (see [`feature::sanction`]).
+//!
+//! Timeouts are kept by Discord when a member rejoins, but they can be
+//! cleared by other bots or by mistake. If [`ModerationConfig::enforce_mutes`]
+//! is enabled, a timeout cleared before the end of the mute is reapplied,
+//! unless it has been cleared by a moderator. The author is taken from the
+//! `MemberUpdate` audit log entries; if the audit log cannot be accessed, the
+//! timeout is reapplied anyway.
+//!
+//! [`feature::sanction`]: crate::feature::sanction
+//! [`ModerationConfig::enforce_mutes`]: raidprotect_model::database::model::ModerationConfig::enforce_mutes
+
+use std::time::Duration as StdDuration;
+
+use raidprotect_model::database::model::{ActiveMute, GuildConfig, LogCategory, MuteMode};
+use time::{Duration, OffsetDateTime};
+use tracing::{debug, error, info};
+use twilight_http::request::AuditLogReason;
+use twilight_model::{
+    gateway::payload::incoming::MemberUpdate,
+    guild::{audit_log::AuditLogEventType, Member, Permissions},
+    id::{
+        marker::{GuildMarker, UserMarker},
+        Id,
+    },
+    util::Timestamp,
+};
+use twilight_util::snowflake::Snowflake;
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    feature::sanction::MAX_TIMEOUT,
+    interaction::{embed, util::GuildConfigExt},
+    util::logs,
+};
+
+/// Delay before the audit log is fetched after a timeout is cleared.
+const AUDIT_LOG_DELAY: StdDuration = StdDuration::from_secs(2);
+
+/// Maximum age of the audit log entries taken into account.
+const MAX_ENTRY_AGE: Duration = Duration::seconds(30);
+
+/// Number of audit log entries searched for the author of a change.
+const AUDIT_LOG_LIMIT: u16 = 10;
+
+/// Margin (in seconds) between the end of a mute and the member timeout.
+///
+/// Timeouts ending within this margin are not considered as cleared.
+const EXPIRATION_MARGIN: i64 = 60;
+
+/// Add the mute role to a member that joined the guild while muted.
+pub async fn member_add(member: &Member, state: &ClusterState) {
+    if let Err(error) = member_add_inner(member, state).await {
+        error!(error = ?error, member = ?member, "failed to reapply mute role");
+    }
+}
+
+async fn member_add_inner(member: &Member, state: &ClusterState) -> Result<(), anyhow::Error> {
+    let config = database::guild_config(state, member.guild_id).await?;
+
+    // Timeouts are kept by Discord when a member rejoins.
+    let role_id = match config.moderation.mute_mode {
+        MuteMode::Role { role_id } => role_id,
+        MuteMode::Timeout => return Ok(()),
+    };
+
+    match state
+        .database
+        .get_mute(member.guild_id, member.user.id)
+        .await?
+    {
+        Some(mute) if !mute.timeout && !mute.is_expired(OffsetDateTime::now_utc()) => {}
+        _ => return Ok(()),
+    }
+
+    state
+        .cache_http(member.guild_id)
+        .add_guild_member_role(member.user.id, role_id)
+        .await?
+        .reason(config.lang().mute_rejoin_reason())?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+/// Reapply the timeout of a member if it has been cleared.
+pub async fn member_update(event: &MemberUpdate, state: &ClusterState) {
+    if let Err(error) = member_update_inner(event, state).await {
+        error!(error = ?error, member = ?event, "failed to enforce member timeout");
+    }
+}
+
+async fn member_update_inner(
+    event: &MemberUpdate,
+    state: &ClusterState,
+) -> Result<(), anyhow::Error> {
+    let config = database::guild_config(state, event.guild_id).await?;
+
+    if !config.moderation.enforce_mutes || event.user.bot {
+        return Ok(());
+    }
+
+    if cleared_timeout(state, event).await?.is_none() {
+        return Ok(());
+    }
+
+    // The audit log entry may be created after the event is received. The
+    // mute is checked again since it is deleted when the member is unmuted
+    // with the `/unmute` command.
+    tokio::time::sleep(AUDIT_LOG_DELAY).await;
+
+    let expires_at = match cleared_timeout(state, event).await? {
+        Some(ActiveMute {
+            expires_at: Some(expires_at),
+            ..
+        }) => expires_at,
+        _ => return Ok(()),
+    };
+
+    let clearer = match find_clearer(state, event.guild_id, event.user.id).await {
+        Ok(clearer) => clearer,
+        Err(error) => {
+            debug!(error = ?error, guild = ?event.guild_id, "failed to get audit log");
+            None
+        }
+    };
+
+    if let Some(clearer) = clearer {
+        if clearer == state.current_user.cast() {
+            return Ok(());
+        }
+
+        // The mute has been ended by a moderator, it is no longer enforced.
+        if is_moderator(state, &config, event.guild_id, clearer).await? {
+            state
+                .database
+                .delete_mute(event.guild_id, event.user.id)
+                .await?;
+
+            return Ok(());
+        }
+    }
+
+    info!(guild = ?event.guild_id, user = ?event.user.id, clearer = ?clearer, "reapplying cleared timeout");
+
+    let lang = config.lang();
+    let until = expires_at.min(OffsetDateTime::now_utc() + MAX_TIMEOUT);
+
+    state
+        .cache_http(event.guild_id)
+        .timeout_member(event.user.id, Timestamp::from_secs(until.unix_timestamp())?)
+        .await?
+        .reason(lang.mute_enforced_reason())?
+        .exec()
+        .await?;
+
+    let embed = embed::logs::mute_enforced(lang, event.user.id, clearer, expires_at);
+    logs::post(state, &config, LogCategory::Sanctions, embed).await
+}
+
+/// Get the active timeout of a member if it has been cleared before the end
+/// of the mute.
+async fn cleared_timeout(
+    state: &ClusterState,
+    event: &MemberUpdate,
+) -> Result<Option<ActiveMute>, anyhow::Error> {
+    let mute = state
+        .database
+        .get_mute(event.guild_id, event.user.id)
+        .await?;
+    let now = OffsetDateTime::now_utc();
+
+    Ok(mute.filter(|mute| {
+        mute.timeout
+            && !mute.is_expired(now)
+            && mute.expires_at.map_or(false, |expires_at| {
+                is_cleared(event.communication_disabled_until, expires_at, now)
+            })
+    }))
+}
+
+/// Whether a member timeout ends before the end of the mute.
+fn is_cleared(until: Option<Timestamp>, expires_at: OffsetDateTime, now: OffsetDateTime) -> bool {
+    match until {
+        Some(until) if until.as_secs() > now.unix_timestamp() => {
+            until.as_secs() + EXPIRATION_MARGIN < expires_at.unix_timestamp()
+        }
+        _ => true,
+    }
+}
+
+/// Find the author of the most recent timeout change of a member.
+async fn find_clearer(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+) -> Result<Option<Id<UserMarker>>, anyhow::Error> {
+    let audit_log = state
+        .cache_http(guild_id)
+        .audit_log()
+        .await?
+        .action_type(AuditLogEventType::MemberUpdate)
+        .limit(AUDIT_LOG_LIMIT)?
+        .exec()
+        .await?
+        .model()
+        .await?;
+
+    let now_millis = (OffsetDateTime::now_utc().unix_timestamp_nanos() / 1_000_000) as i64;
+    let clearer = audit_log
+        .entries
+        .into_iter()
+        .filter(|entry| {
+            now_millis - entry.id.timestamp() <= MAX_ENTRY_AGE.whole_milliseconds() as i64
+        })
+        .find(|entry| entry.target_id == Some(user_id.cast()))
+        .and_then(|entry| entry.user_id);
+
+    Ok(clearer)
+}
+
+/// Whether a user is a moderator of the guild.
+///
+/// Moderators are the guild owner, administrators and the members of the
+/// configured moderator roles. Bots are never considered as moderators.
+async fn is_moderator(
+    state: &ClusterState,
+    config: &GuildConfig,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+) -> Result<bool, anyhow::Error> {
+    let member = state
+        .http
+        .guild_member(guild_id, user_id)
+        .exec()
+        .await?
+        .model()
+        .await?;
+
+    if member.user.bot {
+        return Ok(false);
+    }
+
+    if member
+        .roles
+        .iter()
+        .any(|role| config.moderation.roles.contains(role))
+    {
+        return Ok(true);
+    }
+
+    let permissions = state
+        .cache
+        .permissions(guild_id)
+        .await?
+        .member(user_id, &member.roles)
+        .await?;
+
+    Ok(permissions.is_owner() || permissions.guild().contains(Permissions::ADMINISTRATOR))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_cleared() {
+        let now = OffsetDateTime::from_unix_timestamp(1_000_000).unwrap();
+        let expires_at = now + Duration::hours(1);
+        let timestamp = |date: OffsetDateTime| Timestamp::from_secs(date.unix_timestamp()).unwrap();
+
+        assert!(is_cleared(None, expires_at, now));
+        assert!(is_cleared(Some(timestamp(now)), expires_at, now));
+        assert!(is_cleared(
+            Some(timestamp(now + Duration::minutes(10))),
+            expires_at,
+            now
+        ));
+
+        assert!(!is_cleared(Some(timestamp(expires_at)), expires_at, now));
+        assert!(!is_cleared(
+            Some(timestamp(expires_at - Duration::seconds(30))),
+            expires_at,
+            now
+        ));
+    }
+}
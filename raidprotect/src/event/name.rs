@@ -0,0 +1,175 @@
+//! Member names normalization.
+//!
+//! The name of members that join a guild or update their nickname is checked
+//! for zalgo and invisible characters (see [`util::name`]). Depending on the
+//! configuration, the nickname of the member is replaced with a cleaned name,
+//! or the member is reported in the logs channel.
+//!
+//! [`util::name`]: crate::util::name
+
+use raidprotect_model::database::model::{
+    GuildConfig, LogCategory, NameAction, NameConfig, StatsEvent, StatsFilter,
+};
+use time::Duration;
+use tracing::{error, info, warn};
+use twilight_http::request::AuditLogReason;
+use twilight_model::{
+    gateway::payload::incoming::MemberUpdate,
+    guild::Member,
+    id::{
+        marker::{GuildMarker, RoleMarker},
+        Id,
+    },
+    user::User,
+};
+
+use crate::{
+    cluster::ClusterState,
+    database, feature,
+    interaction::{embed, util::GuildConfigExt},
+    util::{
+        account::{account_created_at, new_account_marker},
+        logs,
+        name::{check_name, normalize_name},
+    },
+};
+
+/// Interval during which a member is only reported once.
+///
+/// `MemberUpdate` events are also received when the roles of a member are
+/// updated, this avoids reporting the same name multiple times.
+const FLAG_INTERVAL: Duration = Duration::hours(1);
+
+/// Check the name of a member that joined a guild.
+pub async fn member_add(member: &Member, state: &ClusterState) {
+    let nick = member.nick.as_deref();
+
+    if let Err(error) =
+        check_member(state, member.guild_id, &member.user, nick, &member.roles).await
+    {
+        error!(error = ?error, member = ?member, "failed to check member name");
+    }
+}
+
+/// Check the name of a member that has been updated.
+pub async fn member_update(member: &MemberUpdate, state: &ClusterState) {
+    let nick = member.nick.as_deref();
+
+    if let Err(error) =
+        check_member(state, member.guild_id, &member.user, nick, &member.roles).await
+    {
+        error!(error = ?error, member = ?member, "failed to check member name");
+    }
+}
+
+async fn check_member(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    user: &User,
+    nick: Option<&str>,
+    roles: &[Id<RoleMarker>],
+) -> Result<(), anyhow::Error> {
+    if user.bot {
+        return Ok(());
+    }
+
+    // The username is displayed if the member has no nickname.
+    let name = nick.unwrap_or(&user.name);
+    let issue = match check_name(name) {
+        Some(issue) => issue,
+        None => return Ok(()),
+    };
+
+    let config = database::guild_config(state, guild_id).await?;
+
+    if !config.names.enabled || config.is_automod_exempt(&[], roles) {
+        return Ok(());
+    }
+
+    let key = format!("name:{guild_id}:{}", user.id);
+    if !state.cache.throttle(&key, FLAG_INTERVAL).await? {
+        return Ok(());
+    }
+
+    let event = StatsEvent::Filter(StatsFilter::Name);
+    feature::stats::record(state, guild_id, event).await;
+
+    let lang = config.lang();
+    let created_at = account_created_at(user.id);
+    let new_account = new_account_marker(created_at, config.new_account_threshold, lang);
+
+    let embed = match config.names.action {
+        NameAction::Reset => {
+            let nick = cleaned_nick(name, &config.names);
+            let reset = match reset_nick(state, &config, guild_id, user, &nick).await {
+                Ok(()) => {
+                    info!(guild = ?guild_id, user = ?user.id, issue = ?issue, "member nickname reset");
+                    Some(nick.as_str())
+                }
+                Err(error) => {
+                    warn!(error = ?error, guild = ?guild_id, "failed to reset member nickname");
+                    None
+                }
+            };
+
+            embed::logs::name_reset(lang, user.id, issue, reset, new_account)
+        }
+        NameAction::Flag => {
+            info!(guild = ?guild_id, user = ?user.id, issue = ?issue, "member name flagged");
+
+            embed::logs::name_flagged(lang, user.id, issue, new_account)
+        }
+    };
+
+    logs::post(state, &config, LogCategory::Filters, embed).await
+}
+
+/// Get the nickname that replaces a problematic name.
+///
+/// The configured fallback is used if nothing remains of the cleaned name.
+fn cleaned_nick(name: &str, config: &NameConfig) -> String {
+    let normalized = normalize_name(name);
+
+    match normalized.is_empty() {
+        true => config.fallback.clone(),
+        false => normalized
+            .chars()
+            .take(NameConfig::MAX_FALLBACK_LENGTH)
+            .collect(),
+    }
+}
+
+async fn reset_nick(
+    state: &ClusterState,
+    config: &GuildConfig,
+    guild_id: Id<GuildMarker>,
+    user: &User,
+    nick: &str,
+) -> Result<(), anyhow::Error> {
+    state
+        .cache_http(guild_id)
+        .update_nickname(user.id, nick)
+        .await?
+        .reason(config.lang().name_reset_reason())?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cleaned_nick() {
+        let config = NameConfig::default();
+
+        assert_eq!(cleaned_nick("Raid\u{200B}Protect", &config), "RaidProtect");
+        assert_eq!(cleaned_nick("\u{3164}", &config), config.fallback);
+        assert_eq!(
+            cleaned_nick(&format!("{}\u{200B}", "a".repeat(40)), &config).len(),
+            NameConfig::MAX_FALLBACK_LENGTH
+        );
+    }
+}
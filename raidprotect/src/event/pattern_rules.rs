@@ -0,0 +1,148 @@
+//! Custom pattern rules.
+//!
+//! Messages are checked against the pattern rules configured in the guild,
+//! and the action of the first matching rule is applied. Each match is
+//! logged with the name of the rule. Patterns are compiled with safety limits
+//! (see [`util::pattern`]). The timeout of trusted members is softened (see
+//! the [`automation`] module).
+//!
+//! Messages marked as false positives by moderators are not deleted (see the
+//! [`filter_feedback`] module).
+//!
+//! [`util::pattern`]: crate::util::pattern
+//! [`automation`]: crate::feature::automation
+//! [`filter_feedback`]: crate::feature::filter_feedback
+
+use raidprotect_model::database::model::{LogCategory, PatternAction, StatsEvent, StatsFilter};
+use time::Duration;
+use tracing::{debug, error, info, warn};
+use twilight_model::{
+    channel::Message,
+    id::{marker::GuildMarker, Id},
+};
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    feature::{
+        self,
+        automation::{AutoAction, MemberTrust},
+    },
+    interaction::{embed, util::GuildConfigExt},
+    util::{
+        account::{account_created_at, new_account_marker},
+        logs::{self, LogMessage},
+        pattern::find_match,
+    },
+};
+
+/// Duration (in seconds) of the timeout applied with [`PatternAction::Timeout`].
+const TIMEOUT: i64 = 10 * 60;
+
+/// Check a message sent in a guild against the pattern rules.
+pub async fn message_create(message: &Message, state: &ClusterState) {
+    let guild_id = match message.guild_id {
+        Some(guild_id) => guild_id,
+        None => return,
+    };
+
+    if let Err(error) = message_create_inner(message, guild_id, state).await {
+        error!(error = ?error, guild = ?guild_id, "failed to check pattern rules");
+    }
+}
+
+async fn message_create_inner(
+    message: &Message,
+    guild_id: Id<GuildMarker>,
+    state: &ClusterState,
+) -> Result<(), anyhow::Error> {
+    let config = database::guild_config(state, guild_id).await?;
+
+    if let Some(member) = &message.member {
+        if config.is_automod_exempt(&[], &member.roles) {
+            return Ok(());
+        }
+    }
+
+    let rule = match find_match(&config.pattern_rules, &message.content) {
+        Some(rule) => rule,
+        None => return Ok(()),
+    };
+
+    let user_id = message.author.id;
+    let deleted = rule.action != PatternAction::Log;
+
+    if deleted && feature::filter_feedback::is_suppressed(state, guild_id, &message.content).await {
+        debug!(guild = ?guild_id, rule = %rule.name, "pattern rule marked as false positive");
+        return Ok(());
+    }
+
+    info!(guild = ?guild_id, user = ?user_id, rule = %rule.name, "pattern rule matched");
+
+    let event = StatsEvent::Filter(StatsFilter::Pattern);
+    feature::stats::record(state, guild_id, event).await;
+
+    let lang = config.lang();
+    let mut action = None;
+
+    if deleted {
+        state
+            .cache_http(guild_id)
+            .delete_message(message.channel_id, message.id)
+            .await?
+            .exec()
+            .await?;
+
+        feature::stats::record(state, guild_id, StatsEvent::MessagesDeleted(1)).await;
+    }
+
+    if rule.action == PatternAction::Timeout {
+        let reason = lang.pattern_rule_reason(&rule.name);
+        let result = feature::automation::timeout(
+            state,
+            &config,
+            &message.author,
+            message.member.as_ref().map(MemberTrust::from),
+            Duration::seconds(TIMEOUT),
+            &reason,
+        );
+
+        match result.await {
+            Ok(result) => action = Some(result),
+            Err(error) => warn!(error = ?error, guild = ?guild_id, "failed to timeout member"),
+        }
+    }
+
+    // Send a message in the logs channel.
+    let created_at = account_created_at(user_id);
+    let new_account = new_account_marker(created_at, config.new_account_threshold, lang);
+    let mut embed = embed::logs::pattern_rule(
+        lang,
+        user_id,
+        message.channel_id,
+        rule,
+        matches!(action, Some(AutoAction::Timeout { .. })),
+        new_account,
+    );
+
+    if let Some(AutoAction::Softened(reason)) = action {
+        embed = embed::logs::softened(lang, embed, reason);
+    }
+
+    let mut components = match deleted {
+        true => {
+            let fingerprint = feature::filter_feedback::fingerprint(&message.content);
+            feature::filter_feedback::components(lang, StatsFilter::Pattern, &fingerprint)
+        }
+        false => Vec::new(),
+    };
+
+    if let Some(AutoAction::Timeout { case_id }) = action {
+        components.push(feature::automation::adjust_components(lang, case_id));
+    }
+
+    let message = LogMessage::new(embed).components(components);
+    logs::post_message(state, &config, LogCategory::Filters, message).await?;
+
+    Ok(())
+}
@@ -0,0 +1,278 @@
+//! Message pins logs.
+//!
+//! The `ChannelPinsUpdate` event does not tell which message has been pinned
+//! or unpinned, nor by whom. This information is taken from the audit log,
+//! which is fetched after [`AUDIT_LOG_DELAY`] since entries may be created
+//! after the event is received. Entries are identified by their id, so each
+//! of them is only logged once.
+//!
+//! If no matching audit log entry is found (for example if the bot is missing
+//! the permission to view the audit log), only the channel is logged.
+
+use std::time::Duration as StdDuration;
+
+use raidprotect_model::{cache::discord::CachedChannel, database::model::PinsConfig};
+use time::{Duration, OffsetDateTime};
+use tracing::{debug, error, warn};
+use twilight_model::{
+    gateway::payload::incoming::ChannelPinsUpdate,
+    guild::audit_log::{AuditLogEntry, AuditLogEventType},
+    id::{
+        marker::{AuditLogEntryMarker, ChannelMarker, GuildMarker, MessageMarker, UserMarker},
+        Id,
+    },
+};
+use twilight_util::snowflake::Snowflake;
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    interaction::{embed, util::GuildConfigExt},
+    util::TextProcessExt,
+};
+
+/// Delay before the audit log is fetched after a pin change.
+const AUDIT_LOG_DELAY: StdDuration = StdDuration::from_secs(2);
+
+/// Maximum age of the audit log entries taken into account.
+const MAX_ENTRY_AGE: Duration = Duration::seconds(30);
+
+/// Number of audit log entries fetched for each event type.
+const AUDIT_LOG_LIMIT: u16 = 10;
+
+/// Maximum length of the message content shown in the logs.
+const MAX_CONTENT_LENGTH: usize = 1000;
+
+/// Message pinned or unpinned by a moderator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PinChange {
+    /// Id of the audit log entry.
+    pub id: Id<AuditLogEntryMarker>,
+    /// Whether the message has been pinned or unpinned.
+    pub pinned: bool,
+    /// Channel of the message.
+    pub channel_id: Id<ChannelMarker>,
+    /// Pinned or unpinned message.
+    pub message_id: Id<MessageMarker>,
+    /// Moderator that made the change.
+    pub moderator_id: Option<Id<UserMarker>>,
+}
+
+impl PinChange {
+    /// Get the [`PinChange`] of an audit log entry.
+    ///
+    /// Returns [`None`] if the entry is not a pin change.
+    fn from_entry(entry: &AuditLogEntry) -> Option<Self> {
+        let pinned = match entry.action_type {
+            AuditLogEventType::MessagePin => true,
+            AuditLogEventType::MessageUnpin => false,
+            _ => return None,
+        };
+        let options = entry.options.as_ref()?;
+
+        Some(Self {
+            id: entry.id,
+            pinned,
+            channel_id: options.channel_id?,
+            message_id: options.message_id?,
+            moderator_id: entry.user_id,
+        })
+    }
+}
+
+/// Handle `ChannelPinsUpdate` event.
+pub async fn channel_pins_update(event: &ChannelPinsUpdate, state: &ClusterState) {
+    let guild_id = match event.guild_id {
+        Some(guild_id) => guild_id,
+        None => return,
+    };
+
+    if let Err(error) = channel_pins_update_inner(guild_id, event.channel_id, state).await {
+        error!(error = ?error, event = ?event, "error while processing `ChannelPinsUpdate` event");
+    }
+}
+
+async fn channel_pins_update_inner(
+    guild_id: Id<GuildMarker>,
+    channel_id: Id<ChannelMarker>,
+    state: &ClusterState,
+) -> Result<(), anyhow::Error> {
+    let config = database::guild_config(state, guild_id).await?;
+    let logs_chan = match config.pins.logs_chan {
+        Some(channel) => channel,
+        None => return Ok(()),
+    };
+
+    if is_ignored(state, &config.pins, channel_id).await? {
+        return Ok(());
+    }
+
+    tokio::time::sleep(AUDIT_LOG_DELAY).await;
+
+    let change = match find_change(state, guild_id, channel_id).await {
+        Ok(change) => change,
+        Err(error) => {
+            debug!(error = ?error, guild = ?guild_id, "failed to get audit log");
+            None
+        }
+    };
+
+    let lang = config.lang();
+    let embed = match change {
+        Some(change) => {
+            let content = message_content(state, &change).await;
+            embed::logs::pin_change(lang, guild_id, &change, content)
+        }
+        None => embed::logs::pins_updated(lang, channel_id),
+    };
+
+    if let Err(error) = state
+        .cache_http(guild_id)
+        .create_message(logs_chan)
+        .await?
+        .embeds(&[embed])?
+        .exec()
+        .await
+    {
+        warn!(error = ?error, guild = ?guild_id, "failed to send pin log");
+    }
+
+    Ok(())
+}
+
+/// Whether the pin changes of a channel are not logged.
+///
+/// Threads are ignored if their parent channel is ignored.
+async fn is_ignored(
+    state: &ClusterState,
+    config: &PinsConfig,
+    channel_id: Id<ChannelMarker>,
+) -> Result<bool, anyhow::Error> {
+    if config.ignored_channels.contains(&channel_id) {
+        return Ok(true);
+    }
+
+    let parent_id = state
+        .cache
+        .get::<CachedChannel>(&channel_id)
+        .await?
+        .filter(|channel| channel.is_thread())
+        .and_then(|channel| channel.parent_id);
+
+    Ok(parent_id.map_or(false, |parent| config.ignored_channels.contains(&parent)))
+}
+
+/// Find the audit log entry of a pin change in a channel.
+///
+/// The most recent entry that has not already been logged is returned.
+async fn find_change(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    channel_id: Id<ChannelMarker>,
+) -> Result<Option<PinChange>, anyhow::Error> {
+    let mut changes = Vec::new();
+
+    for kind in [
+        AuditLogEventType::MessagePin,
+        AuditLogEventType::MessageUnpin,
+    ] {
+        let audit_log = state
+            .cache_http(guild_id)
+            .audit_log()
+            .await?
+            .action_type(kind)
+            .limit(AUDIT_LOG_LIMIT)?
+            .exec()
+            .await?
+            .model()
+            .await?;
+
+        changes.extend(audit_log.entries.iter().filter_map(PinChange::from_entry));
+    }
+
+    for change in recent_changes(changes, channel_id, OffsetDateTime::now_utc()) {
+        let key = format!("pin:{}", change.id);
+
+        if state.cache.throttle(&key, MAX_ENTRY_AGE).await? {
+            return Ok(Some(change));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Filter the recent changes of a channel, most recent first.
+///
+/// Changes older than [`MAX_ENTRY_AGE`] are ignored.
+fn recent_changes(
+    changes: Vec<PinChange>,
+    channel_id: Id<ChannelMarker>,
+    now: OffsetDateTime,
+) -> Vec<PinChange> {
+    let now_millis = (now.unix_timestamp_nanos() / 1_000_000) as i64;
+    let max_age = MAX_ENTRY_AGE.whole_milliseconds() as i64;
+
+    let mut changes = changes
+        .into_iter()
+        .filter(|change| change.channel_id == channel_id)
+        .filter(|change| now_millis - change.id.timestamp() <= max_age)
+        .collect::<Vec<_>>();
+
+    changes.sort_by_key(|change| std::cmp::Reverse(change.id));
+    changes
+}
+
+/// Get the content of the pinned or unpinned message.
+///
+/// Returns [`None`] if the message cannot be fetched or has no text content.
+async fn message_content(state: &ClusterState, change: &PinChange) -> Option<String> {
+    let request = state.http.message(change.channel_id, change.message_id);
+
+    let message = match request.exec().await {
+        Ok(response) => response.model().await.ok()?,
+        Err(error) => {
+            debug!(error = ?error, "failed to fetch pinned message");
+            return None;
+        }
+    };
+
+    match message.content.is_empty() {
+        true => None,
+        false => Some(message.content.max_len(MAX_CONTENT_LENGTH)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Discord epoch, in milliseconds.
+    const DISCORD_EPOCH: i64 = 1_420_070_400_000;
+
+    fn change(channel_id: u64, timestamp: OffsetDateTime) -> PinChange {
+        let millis = (timestamp.unix_timestamp_nanos() / 1_000_000) as i64;
+        let id = ((millis - DISCORD_EPOCH) as u64) << 22;
+
+        PinChange {
+            id: Id::new(id),
+            pinned: true,
+            channel_id: Id::new(channel_id),
+            message_id: Id::new(1),
+            moderator_id: None,
+        }
+    }
+
+    #[test]
+    fn test_recent_changes() {
+        let now = OffsetDateTime::now_utc();
+        let changes = vec![
+            change(1, now - Duration::seconds(20)),
+            change(1, now - Duration::seconds(5)),
+            change(2, now - Duration::seconds(5)),
+            change(1, now - Duration::minutes(5)),
+        ];
+
+        let recent = recent_changes(changes.clone(), Id::new(1), now);
+        assert_eq!(recent, vec![changes[1], changes[0]]);
+    }
+}
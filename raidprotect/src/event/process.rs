@@ -6,7 +6,10 @@ use tracing::{debug, error, trace};
 use twilight_model::gateway::{event::Event as GatewayEvent, payload::incoming};
 
 use super::message::ALLOWED_MESSAGES_TYPES;
-use crate::cluster::ClusterState;
+use crate::{
+    cluster::ClusterState,
+    feature::{self, watch::WatchEvent},
+};
 
 /// Process incoming events.
 #[async_trait]
@@ -60,35 +63,96 @@ impl ProcessEvent for GatewayEvent {
             ChannelCreate,
             ChannelDelete,
             ChannelUpdate,
+            ChannelPinsUpdate,
             InteractionCreate,
             ThreadCreate,
             ThreadDelete,
             ThreadUpdate,
             RoleCreate,
+            RoleUpdate,
             RoleDelete,
             MemberAdd,
             MemberUpdate,
+            MemberRemove,
             MessageCreate,
-            MessageDelete
+            MessageDelete,
+            ReactionAdd,
+            VoiceStateUpdate
         }
     }
 }
 
 // Implementation of events only processed in cache
 process_cache_events! {
-    GuildCreate,
     GuildDelete,
     UnavailableGuild,
-    GuildUpdate,
     ChannelCreate,
-    ChannelDelete,
     ChannelUpdate,
     ThreadCreate,
     ThreadDelete,
     ThreadUpdate,
-    RoleCreate,
-    RoleDelete,
-    MemberUpdate
+    RoleCreate
+}
+
+#[async_trait]
+impl ProcessEvent for incoming::GuildCreate {
+    async fn process(self, state: ClusterState) {
+        let join = feature::onboarding::GuildJoin::from_guild(&self);
+        let guild_id = self.0.id;
+        super::voice::guild_create(&self.0, &state).await;
+        process_cache_event(self, &state).await;
+        feature::lockdown::recover(&state, guild_id).await;
+
+        if let Some(join) = join {
+            feature::onboarding::guild_join(&state, join).await;
+        }
+    }
+}
+
+#[async_trait]
+impl ProcessEvent for incoming::GuildUpdate {
+    async fn process(self, state: ClusterState) {
+        let owner = super::sentinel::cached_owner(&state, self.id).await;
+        process_cache_event(self.clone(), &state).await;
+        super::sentinel::guild_update(&self, owner, &state).await;
+    }
+}
+
+#[async_trait]
+impl ProcessEvent for incoming::ChannelDelete {
+    async fn process(self, state: ClusterState) {
+        process_cache_event(self.clone(), &state).await;
+
+        if let Some(guild_id) = self.guild_id {
+            feature::references::check_guild(&state, guild_id).await;
+            super::antinuke::deletion(guild_id, &state).await;
+        }
+    }
+}
+
+#[async_trait]
+impl ProcessEvent for incoming::ChannelPinsUpdate {
+    async fn process(self, state: ClusterState) {
+        super::pins::channel_pins_update(&self, &state).await;
+    }
+}
+
+#[async_trait]
+impl ProcessEvent for incoming::RoleUpdate {
+    async fn process(self, state: ClusterState) {
+        let permissions = super::sentinel::cached_permissions(&state, self.role.id).await;
+        process_cache_event(self.clone(), &state).await;
+        super::sentinel::role_update(&self, permissions, &state).await;
+    }
+}
+
+#[async_trait]
+impl ProcessEvent for incoming::RoleDelete {
+    async fn process(self, state: ClusterState) {
+        process_cache_event(self.clone(), &state).await;
+        feature::references::check_guild(&state, self.guild_id).await;
+        super::antinuke::deletion(self.guild_id, &state).await;
+    }
 }
 
 #[async_trait]
@@ -102,10 +166,62 @@ impl ProcessEvent for incoming::InteractionCreate {
 impl ProcessEvent for incoming::MemberAdd {
     async fn process(self, state: ClusterState) {
         process_cache_event(self.clone(), &state).await;
+        super::member::member_add(&self.0, &state).await;
+        super::growth::member_add(&self.0, &state).await;
+        super::mute::member_add(&self.0, &state).await;
+        super::quarantine::member_add(&self.0, &state).await;
+        super::name::member_add(&self.0, &state).await;
+        super::first_message::member_add(&self.0, &state).await;
+        super::join_burst::member_add(&self.0, &state).await;
+
+        let join = WatchEvent::Join;
+        feature::watch::notify(&state, self.guild_id, self.user.id, join).await;
+
+        // Members pending the membership screening are processed once they
+        // accept the rules.
+        if super::screening::member_add(&self.0, &state).await {
+            return;
+        }
+
+        // Members kicked by the raid mode don't need to complete the captcha.
+        if super::raid_mode::member_add(&self.0, &state).await {
+            return;
+        }
+
         super::captcha::member_add(&self.0, &state).await;
     }
 }
 
+#[async_trait]
+impl ProcessEvent for incoming::MemberUpdate {
+    async fn process(self, state: ClusterState) {
+        let previous = super::member::cached_record(&state, self.guild_id, self.user.id).await;
+        process_cache_event(self.clone(), &state).await;
+        super::member::member_update(&self, &state).await;
+
+        let roles = previous.as_ref().map(|record| record.roles.clone());
+        super::sentinel::member_update(&self, roles.clone(), &state).await;
+        super::screening::member_update(&self, previous.as_ref(), &state).await;
+        super::name::member_update(&self, &state).await;
+
+        // Mute enforcement and role logs wait for the audit log entry, so they
+        // are processed last.
+        super::mute::member_update(&self, &state).await;
+        super::role_logs::member_update(&self, roles, &state).await;
+    }
+}
+
+#[async_trait]
+impl ProcessEvent for incoming::MemberRemove {
+    async fn process(self, state: ClusterState) {
+        super::member::member_remove(&self, &state).await;
+        super::growth::member_remove(&self, &state).await;
+
+        let leave = WatchEvent::Leave;
+        feature::watch::notify(&state, self.guild_id, self.user.id, leave).await;
+    }
+}
+
 #[async_trait]
 impl ProcessEvent for incoming::MessageCreate {
     async fn process(self, state: ClusterState) {
@@ -123,3 +239,17 @@ impl ProcessEvent for incoming::MessageDelete {
         }
     }
 }
+
+#[async_trait]
+impl ProcessEvent for incoming::ReactionAdd {
+    async fn process(self, state: ClusterState) {
+        super::reaction::reaction_add(&self.0, &state).await;
+    }
+}
+
+#[async_trait]
+impl ProcessEvent for incoming::VoiceStateUpdate {
+    async fn process(self, state: ClusterState) {
+        super::voice::voice_state_update(&self.0, &state).await;
+    }
+}
@@ -0,0 +1,49 @@
+//! Quarantine role reapplication.
+//!
+//! Quarantined members could leave and rejoin the guild to get rid of the
+//! quarantine role. Their quarantine state is kept when they leave, and the
+//! role is added again when they rejoin (see [`feature::sanction`]).
+//!
+//! [`feature::sanction`]: crate::feature::sanction
+
+use tracing::error;
+use twilight_http::request::AuditLogReason;
+use twilight_model::guild::Member;
+
+use crate::{cluster::ClusterState, database, interaction::util::GuildConfigExt};
+
+/// Add the quarantine role to a member that joined the guild while
+/// quarantined.
+pub async fn member_add(member: &Member, state: &ClusterState) {
+    if let Err(error) = member_add_inner(member, state).await {
+        error!(error = ?error, member = ?member, "failed to reapply quarantine role");
+    }
+}
+
+async fn member_add_inner(member: &Member, state: &ClusterState) -> Result<(), anyhow::Error> {
+    let config = database::guild_config(state, member.guild_id).await?;
+
+    let role_id = match config.moderation.quarantine_role {
+        Some(role_id) => role_id,
+        None => return Ok(()),
+    };
+
+    if state
+        .database
+        .get_quarantine(member.guild_id, member.user.id)
+        .await?
+        .is_none()
+    {
+        return Ok(());
+    }
+
+    state
+        .cache_http(member.guild_id)
+        .add_guild_member_role(member.user.id, role_id)
+        .await?
+        .reason(config.lang().quarantine_rejoin_reason())?
+        .exec()
+        .await?;
+
+    Ok(())
+}
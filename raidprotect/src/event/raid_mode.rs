@@ -0,0 +1,364 @@
+//! Raid mode event processing.
+//!
+//! When the raid mode is enabled, the members that join the guild are checked
+//! against the configured account age and an alert may be sent in the logs
+//! channel.
+//!
+//! Members with a recent account are either kicked, or timed out and held for
+//! review by a moderator, depending on the configured [`RaidModeAction`]. The
+//! members held for review are posted in the review channel with buttons to
+//! approve or kick them (see [`PendingJoinReview`]).
+//!
+//! Joins are read from a [`JoinSource`]. They are usually received from the
+//! gateway, but may also be generated internally to simulate a raid (see
+//! [`feature::simulation`]).
+//!
+//! [`feature::simulation`]: crate::feature::simulation
+
+use async_trait::async_trait;
+use raidprotect_model::{
+    cache::model::interaction::PendingJoinReview,
+    database::model::{Feature, GuildConfig, LogCategory, RaidModeAction},
+};
+use time::{Duration, OffsetDateTime};
+use tracing::error;
+use twilight_http::request::AuditLogReason;
+use twilight_model::{
+    application::component::{button::ButtonStyle, ActionRow, Button, Component},
+    guild::Member,
+    id::{
+        marker::{GuildMarker, UserMarker},
+        Id,
+    },
+    util::Timestamp,
+};
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    interaction::{
+        embed,
+        util::{CustomId, GuildConfigExt},
+    },
+    util::{
+        account::{account_created_at, new_account_marker},
+        bulk::{self, BulkOptions, CancelToken},
+        logs::{self, LogMessage},
+        ratelimit::Priority,
+    },
+};
+
+/// Member join processed by the raid mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemberJoin {
+    /// Id of the guild the member joined.
+    pub guild_id: Id<GuildMarker>,
+    /// Id of the user that joined.
+    pub user_id: Id<UserMarker>,
+    /// Account creation date, in seconds.
+    pub created_at: u64,
+}
+
+impl From<&Member> for MemberJoin {
+    fn from(member: &Member) -> Self {
+        Self {
+            guild_id: member.guild_id,
+            user_id: member.user.id,
+            created_at: account_created_at(member.user.id),
+        }
+    }
+}
+
+/// Source of the member joins processed by the raid mode.
+#[async_trait]
+pub trait JoinSource: Send {
+    /// Get the next member join, or [`None`] if the source is exhausted.
+    async fn next_join(&mut self) -> Option<MemberJoin>;
+
+    /// Whether the joins are simulated.
+    ///
+    /// Simulated joins are processed as if the raid mode was enabled, never
+    /// trigger moderation actions and the resulting logs messages are tagged
+    /// as simulation.
+    fn simulated(&self) -> bool;
+}
+
+/// [`JoinSource`] for a join received from the gateway.
+pub struct GatewayJoin(Option<MemberJoin>);
+
+#[async_trait]
+impl JoinSource for GatewayJoin {
+    async fn next_join(&mut self) -> Option<MemberJoin> {
+        self.0.take()
+    }
+
+    fn simulated(&self) -> bool {
+        false
+    }
+}
+
+/// Statistics of the joins processed from a [`JoinSource`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JoinStats {
+    /// Number of processed joins.
+    pub joins: u64,
+    /// Number of kicked members.
+    ///
+    /// For simulated joins, this is the number of members that would have
+    /// been kicked.
+    pub kicked: u64,
+    /// Number of members held for review.
+    ///
+    /// For simulated joins, this is the number of members that would have
+    /// been held for review.
+    pub held: u64,
+}
+
+/// Outcome of a join processed by the raid mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JoinOutcome {
+    /// The member is allowed to join.
+    Allowed,
+    /// The member has been kicked.
+    Kicked,
+    /// The member has been held for review.
+    Held,
+}
+
+/// Handle `MemberAdd` event.
+///
+/// Returns `true` if the member has been kicked from the guild. Members held
+/// for review are not kicked.
+pub async fn member_add(member: &Member, state: &ClusterState) -> bool {
+    member_join(member.into(), state).await
+}
+
+/// Process a member join received from the gateway.
+///
+/// Members pending the membership screening join when they accept the rules
+/// (see the [`screening`] module).
+///
+/// Returns `true` if the member has been kicked from the guild.
+///
+/// [`screening`]: super::screening
+pub async fn member_join(join: MemberJoin, state: &ClusterState) -> bool {
+    let source = GatewayJoin(Some(join));
+
+    match process_joins(source, state).await {
+        Ok(stats) => stats.kicked > 0,
+        Err(error) => {
+            error!(error = ?error, join = ?join, "error while processing raid mode member join");
+
+            false
+        }
+    }
+}
+
+/// Process all the joins of a [`JoinSource`].
+pub async fn process_joins(
+    mut source: impl JoinSource,
+    state: &ClusterState,
+) -> Result<JoinStats, anyhow::Error> {
+    let mut stats = JoinStats::default();
+
+    while let Some(join) = source.next_join().await {
+        let mut config = database::guild_config(state, join.guild_id).await?;
+
+        if source.simulated() {
+            config.raid_mode.enabled = true;
+        } else if !config.raid_mode.enabled {
+            continue;
+        }
+
+        stats.joins += 1;
+        match process_join(state, &config, join, source.simulated()).await? {
+            JoinOutcome::Allowed => {}
+            JoinOutcome::Kicked => stats.kicked += 1,
+            JoinOutcome::Held => stats.held += 1,
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Process a single join.
+async fn process_join(
+    state: &ClusterState,
+    config: &GuildConfig,
+    join: MemberJoin,
+    simulated: bool,
+) -> Result<JoinOutcome, anyhow::Error> {
+    let account_age = OffsetDateTime::now_utc().unix_timestamp() - join.created_at as i64;
+
+    let recent = match config.raid_mode.account_age {
+        Some(_) if !config.features.contains(Feature::RaidDetection) => false,
+        Some(min_age) => account_age < Duration::hours(min_age.into()).whole_seconds(),
+        None => false,
+    };
+
+    let outcome = match (recent, config.raid_mode.action) {
+        (false, _) => JoinOutcome::Allowed,
+        (true, RaidModeAction::Kick) => {
+            if !simulated {
+                kick(state, config, join).await?;
+            }
+
+            JoinOutcome::Kicked
+        }
+        (true, RaidModeAction::Review) => {
+            // The review message replaces the join alert.
+            hold_for_review(state, config, join, simulated).await?;
+
+            return Ok(JoinOutcome::Held);
+        }
+    };
+
+    if config.raid_mode.join_alerts && config.features.contains(Feature::JoinLogs) {
+        let kicked = outcome == JoinOutcome::Kicked;
+        join_alert(state, config, join, kicked, simulated).await?;
+    }
+
+    Ok(outcome)
+}
+
+/// Kick a member with a recent account.
+///
+/// During a raid, many members are kicked in a short time. The kick is sent
+/// with the [`bulk`] utility so that it is retried once a rate limit ends
+/// instead of failing.
+async fn kick(
+    state: &ClusterState,
+    config: &GuildConfig,
+    join: MemberJoin,
+) -> Result<(), anyhow::Error> {
+    let reason = config.lang().raid_mode_account_age_reason();
+    let report = bulk::run(
+        [join.user_id],
+        BulkOptions::default(),
+        &CancelToken::new(),
+        &mut (),
+        |user_id| {
+            let cache_http = state.cache_http(join.guild_id);
+
+            state.rate_guard.send(Priority::Background, async move {
+                cache_http
+                    .remove_guild_member(user_id)
+                    .await?
+                    .reason(reason)?
+                    .exec()
+                    .await?;
+
+                Ok(())
+            })
+        },
+    )
+    .await;
+
+    if !report.failed.is_empty() {
+        anyhow::bail!("failed to kick member");
+    }
+
+    Ok(())
+}
+
+/// Hold a member for review.
+///
+/// The member is timed out for the duration of the review, and a message is
+/// sent in the review channel with buttons to approve or kick the member. If
+/// no review channel is configured, the message is sent in the logs channel
+/// of the joins.
+///
+/// Simulated joins only send the review message, without buttons.
+async fn hold_for_review(
+    state: &ClusterState,
+    config: &GuildConfig,
+    join: MemberJoin,
+    simulated: bool,
+) -> Result<(), anyhow::Error> {
+    let lang = config.lang();
+    let new_account = new_account_marker(join.created_at, config.new_account_threshold, lang);
+    let embed = embed::join_review::held(lang, join.user_id, join.created_at, new_account);
+
+    if simulated {
+        let embed = embed::logs::simulation(lang, embed);
+
+        return logs::post(state, config, LogCategory::Joins, embed).await;
+    }
+
+    let until = OffsetDateTime::now_utc() + Duration::seconds(PendingJoinReview::DURATION as i64);
+    state
+        .cache_http(join.guild_id)
+        .timeout_member(join.user_id, Timestamp::from_secs(until.unix_timestamp())?)
+        .await?
+        .reason(lang.raid_mode_review_reason())?
+        .exec()
+        .await?;
+
+    let pending = PendingJoinReview {
+        guild_id: join.guild_id,
+        user_id: join.user_id,
+    };
+    state.cache.set(&pending).await?;
+
+    let button = |name: &str, label: &str, style: ButtonStyle| {
+        Component::Button(Button {
+            custom_id: Some(CustomId::new(name, pending.id()).to_string()),
+            disabled: false,
+            emoji: None,
+            label: Some(label.to_owned()),
+            style,
+            url: None,
+        })
+    };
+    let components = vec![Component::ActionRow(ActionRow {
+        components: vec![
+            button(
+                "join-review-approve",
+                lang.join_review_approve_button(),
+                ButtonStyle::Success,
+            ),
+            button(
+                "join-review-kick",
+                lang.join_review_kick_button(),
+                ButtonStyle::Danger,
+            ),
+        ],
+    })];
+
+    match config.raid_mode.review_channel {
+        Some(channel) => {
+            state
+                .http
+                .create_message(channel)
+                .embeds(&[embed])?
+                .components(&components)?
+                .exec()
+                .await?;
+        }
+        None => {
+            let message = LogMessage::new(embed).components(components);
+            logs::post_message(state, config, LogCategory::Joins, message).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Send a join alert in the logs channel.
+async fn join_alert(
+    state: &ClusterState,
+    config: &GuildConfig,
+    join: MemberJoin,
+    kicked: bool,
+    simulated: bool,
+) -> Result<(), anyhow::Error> {
+    let lang = config.lang();
+    let new_account = new_account_marker(join.created_at, config.new_account_threshold, lang);
+    let mut embed =
+        embed::logs::join_alert(lang, join.user_id, join.created_at, kicked, new_account);
+    if simulated {
+        embed = embed::logs::simulation(lang, embed);
+    }
+
+    logs::post(state, config, LogCategory::Joins, embed).await
+}
@@ -0,0 +1,187 @@
+//! Reaction spam detection.
+//!
+//! This module handles the `ReactionAdd` event to detect users that add
+//! reactions too quickly. When the configured threshold is exceeded, the
+//! reactions of the user are removed and the user may be timed out. The
+//! timeout of trusted members is softened (see the [`automation`] module).
+//!
+//! [`automation`]: crate::feature::automation
+
+use raidprotect_model::{
+    cache::model::reaction::TrackedReaction,
+    database::model::{Feature, GuildConfig, LogCategory, StatsEvent, StatsFilter},
+};
+use time::Duration;
+use tracing::{error, warn};
+use twilight_http::request::channel::reaction::RequestReactionType;
+use twilight_model::{
+    channel::{Reaction, ReactionType},
+    guild::Member,
+    id::{marker::GuildMarker, Id},
+};
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    feature::{
+        self,
+        automation::{AutoAction, MemberTrust},
+    },
+    interaction::{embed, util::GuildConfigExt},
+    util::{
+        account::{account_created_at, new_account_marker},
+        bulk::{self, BulkOptions, CancelToken},
+        logs::{self, LogMessage},
+        ratelimit::Priority,
+    },
+};
+
+/// Maximum number of reactions deleted concurrently.
+const MAX_CONCURRENT_DELETIONS: usize = 4;
+
+/// Handle `ReactionAdd` event.
+pub async fn reaction_add(reaction: &Reaction, state: &ClusterState) {
+    if let Err(error) = reaction_add_inner(reaction, state).await {
+        error!(error = ?error, reaction = ?reaction, "error while processing `ReactionAdd` event");
+    }
+}
+
+async fn reaction_add_inner(
+    reaction: &Reaction,
+    state: &ClusterState,
+) -> Result<(), anyhow::Error> {
+    let (guild_id, member) = match (reaction.guild_id, &reaction.member) {
+        (Some(guild_id), Some(member)) => (guild_id, member),
+        _ => return Ok(()),
+    };
+
+    if member.user.bot {
+        return Ok(());
+    }
+
+    let config = database::guild_config(state, guild_id).await?;
+    let reaction_spam = &config.reaction_spam;
+
+    if !reaction_spam.enabled
+        || !config.features.contains(Feature::SpamFilter)
+        || config.is_automod_exempt(&reaction_spam.trusted_roles, &member.roles)
+    {
+        return Ok(());
+    }
+
+    // Track the reaction and check the threshold.
+    let tracked = TrackedReaction {
+        channel_id: reaction.channel_id,
+        message_id: reaction.message_id,
+        emoji: reaction.emoji.clone(),
+    };
+    let interval = Duration::seconds(reaction_spam.interval.into());
+    let reactions = state
+        .cache
+        .track_reaction(guild_id, reaction.user_id, &tracked, interval)
+        .await?;
+
+    if reactions.len() <= reaction_spam.max_reactions.into() {
+        return Ok(());
+    }
+
+    state
+        .cache
+        .clear_reactions(guild_id, reaction.user_id)
+        .await?;
+
+    let event = StatsEvent::Filter(StatsFilter::ReactionSpam);
+    feature::stats::record(state, guild_id, event).await;
+
+    sanction(state, &config, guild_id, member, &reactions).await
+}
+
+/// Remove the reactions and timeout the member.
+async fn sanction(
+    state: &ClusterState,
+    config: &GuildConfig,
+    guild_id: Id<GuildMarker>,
+    member: &Member,
+    reactions: &[TrackedReaction],
+) -> Result<(), anyhow::Error> {
+    let user_id = member.user.id;
+    let lang = config.lang();
+    let cache_http = state.cache_http(guild_id);
+    let options = BulkOptions {
+        concurrency: MAX_CONCURRENT_DELETIONS,
+        ..Default::default()
+    };
+
+    let report = bulk::run(
+        reactions,
+        options,
+        &CancelToken::new(),
+        &mut (),
+        |reaction| {
+            let cache_http = &cache_http;
+
+            state.rate_guard.send(Priority::Background, async move {
+                let emoji = request_reaction_type(&reaction.emoji);
+                cache_http
+                    .delete_reaction(reaction.channel_id, reaction.message_id, &emoji, user_id)
+                    .await?
+                    .exec()
+                    .await?;
+
+                Ok(())
+            })
+        },
+    )
+    .await;
+
+    if !report.failed.is_empty() {
+        let failed = report.failed.len();
+        warn!(guild = ?guild_id, failed, "failed to delete reactions");
+    }
+
+    let action = match config.reaction_spam.timeout {
+        Some(timeout) => Some(
+            feature::automation::timeout(
+                state,
+                config,
+                &member.user,
+                Some(MemberTrust::from(member)),
+                Duration::seconds(timeout.into()),
+                lang.reaction_spam_reason(),
+            )
+            .await?,
+        ),
+        None => None,
+    };
+
+    // Send a message in the logs channel.
+    let created_at = account_created_at(user_id);
+    let new_account = new_account_marker(created_at, config.new_account_threshold, lang);
+    let timeout = matches!(action, Some(AutoAction::Timeout { .. }));
+    let mut embed =
+        embed::logs::reaction_spam(lang, user_id, reactions.len(), timeout, new_account);
+
+    if let Some(AutoAction::Softened(reason)) = action {
+        embed = embed::logs::softened(lang, embed, reason);
+    }
+
+    let mut message = LogMessage::new(embed);
+    if let Some(AutoAction::Timeout { case_id }) = action {
+        message = message.components(vec![feature::automation::adjust_components(lang, case_id)]);
+    }
+
+    logs::post_message(state, config, LogCategory::Filters, message).await?;
+
+    Ok(())
+}
+
+/// Convert a [`ReactionType`] into a [`RequestReactionType`].
+fn request_reaction_type(emoji: &ReactionType) -> RequestReactionType<'_> {
+    match emoji {
+        ReactionType::Custom { id, name, .. } => RequestReactionType::Custom {
+            id: *id,
+            name: name.as_deref(),
+        },
+        ReactionType::Unicode { name } => RequestReactionType::Unicode { name },
+    }
+}
@@ -0,0 +1,230 @@
+//! Member role changes logs.
+//!
+//! Privilege escalations often go through roles given to members. When a
+//! logs channel is configured (see [`RoleLogsConfig`]), the roles added to or
+//! removed from members are logged, computed from the previous
+//! [`MemberRecord`] of the member.
+//!
+//! The author of the change is taken from the `MemberRoleUpdate` audit log
+//! entries, which are fetched after [`AUDIT_LOG_DELAY`] since entries may be
+//! created after the event is received. Changes made by RaidProtect itself,
+//! such as sanction roles, are marked as automatic and logged with the reason
+//! of the audit log entry.
+//!
+//! [`RoleLogsConfig`]: raidprotect_model::database::model::RoleLogsConfig
+//! [`MemberRecord`]: raidprotect_model::cache::model::member::MemberRecord
+
+use std::time::Duration as StdDuration;
+
+use time::{Duration, OffsetDateTime};
+use tracing::{debug, error, warn};
+use twilight_model::{
+    gateway::payload::incoming::MemberUpdate,
+    guild::audit_log::{AuditLogEntry, AuditLogEventType},
+    id::{
+        marker::{GuildMarker, RoleMarker, UserMarker},
+        Id,
+    },
+};
+use twilight_util::snowflake::Snowflake;
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    interaction::{embed, util::GuildConfigExt},
+};
+
+/// Delay before the audit log is fetched after a role change.
+const AUDIT_LOG_DELAY: StdDuration = StdDuration::from_secs(2);
+
+/// Maximum age of the audit log entries taken into account.
+const MAX_ENTRY_AGE: Duration = Duration::seconds(30);
+
+/// Number of audit log entries searched for the author of a change.
+const AUDIT_LOG_LIMIT: u16 = 10;
+
+/// Roles added to or removed from a member.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RoleChange {
+    /// Roles added to the member.
+    pub added: Vec<Id<RoleMarker>>,
+    /// Roles removed from the member.
+    pub removed: Vec<Id<RoleMarker>>,
+}
+
+impl RoleChange {
+    /// Compute the roles changes of a member.
+    ///
+    /// Changes of the `ignored` roles are not included.
+    fn new(
+        previous: &[Id<RoleMarker>],
+        current: &[Id<RoleMarker>],
+        ignored: &[Id<RoleMarker>],
+    ) -> Self {
+        let changed = |from: &[Id<RoleMarker>], to: &[Id<RoleMarker>]| {
+            to.iter()
+                .filter(|role| !from.contains(role) && !ignored.contains(role))
+                .copied()
+                .collect()
+        };
+
+        Self {
+            added: changed(previous, current),
+            removed: changed(current, previous),
+        }
+    }
+
+    /// Whether no role has been added or removed.
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Author of a role change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeAuthor {
+    /// The change has been made by RaidProtect.
+    Bot,
+    /// The change has been made by a moderator.
+    Moderator(Id<UserMarker>),
+    /// The author could not be found in the audit log.
+    Unknown,
+}
+
+/// Handle `MemberUpdate` event.
+///
+/// The `previous` roles must be retrieved before the cache is updated with
+/// the event.
+pub async fn member_update(
+    event: &MemberUpdate,
+    previous: Option<Vec<Id<RoleMarker>>>,
+    state: &ClusterState,
+) {
+    // Members without record have not been seen before, so the changed roles
+    // cannot be determined.
+    let previous = match previous {
+        Some(previous) => previous,
+        None => return,
+    };
+
+    if let Err(error) = member_update_inner(event, &previous, state).await {
+        error!(error = ?error, member = ?event, "error while processing role logs `MemberUpdate` event");
+    }
+}
+
+async fn member_update_inner(
+    event: &MemberUpdate,
+    previous: &[Id<RoleMarker>],
+    state: &ClusterState,
+) -> Result<(), anyhow::Error> {
+    // Most member updates don't change roles, so the change is computed
+    // before the configuration is retrieved.
+    if previous == event.roles {
+        return Ok(());
+    }
+
+    let config = database::guild_config(state, event.guild_id).await?;
+    let logs_chan = match config.role_logs.logs_chan {
+        Some(channel) => channel,
+        None => return Ok(()),
+    };
+
+    let change = RoleChange::new(previous, &event.roles, &config.role_logs.ignored_roles);
+    if change.is_empty() {
+        return Ok(());
+    }
+
+    tokio::time::sleep(AUDIT_LOG_DELAY).await;
+
+    let (author, reason) = match find_entry(state, event.guild_id, event.user.id).await {
+        Ok(Some(entry)) => {
+            let author = match entry.user_id {
+                Some(user) if user == state.current_user.cast() => ChangeAuthor::Bot,
+                Some(user) => ChangeAuthor::Moderator(user),
+                None => ChangeAuthor::Unknown,
+            };
+
+            (author, entry.reason)
+        }
+        Ok(None) => (ChangeAuthor::Unknown, None),
+        Err(error) => {
+            debug!(error = ?error, guild = ?event.guild_id, "failed to get audit log");
+            (ChangeAuthor::Unknown, None)
+        }
+    };
+
+    let lang = config.lang();
+    let embed = embed::logs::role_change(lang, event.user.id, &change, author, reason);
+
+    if let Err(error) = state
+        .cache_http(event.guild_id)
+        .create_message(logs_chan)
+        .await?
+        .embeds(&[embed])?
+        .exec()
+        .await
+    {
+        warn!(error = ?error, guild = ?event.guild_id, "failed to send role change log");
+    }
+
+    Ok(())
+}
+
+/// Find the most recent audit log entry of a member role change.
+async fn find_entry(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+) -> Result<Option<AuditLogEntry>, anyhow::Error> {
+    let audit_log = state
+        .cache_http(guild_id)
+        .audit_log()
+        .await?
+        .action_type(AuditLogEventType::MemberRoleUpdate)
+        .limit(AUDIT_LOG_LIMIT)?
+        .exec()
+        .await?
+        .model()
+        .await?;
+
+    let now = OffsetDateTime::now_utc();
+    let entry = audit_log
+        .entries
+        .into_iter()
+        .filter(|entry| is_recent(entry, now))
+        .find(|entry| entry.target_id == Some(user_id.cast()));
+
+    Ok(entry)
+}
+
+/// Whether an audit log entry is more recent than [`MAX_ENTRY_AGE`].
+fn is_recent(entry: &AuditLogEntry, now: OffsetDateTime) -> bool {
+    let now_millis = (now.unix_timestamp_nanos() / 1_000_000) as i64;
+
+    now_millis - entry.id.timestamp() <= MAX_ENTRY_AGE.whole_milliseconds() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_change() {
+        let previous = [Id::new(1), Id::new(2), Id::new(3)];
+        let current = [Id::new(1), Id::new(4), Id::new(5)];
+
+        let change = RoleChange::new(&previous, &current, &[Id::new(5)]);
+        assert_eq!(change.added, vec![Id::new(4)]);
+        assert_eq!(change.removed, vec![Id::new(2), Id::new(3)]);
+        assert!(!change.is_empty());
+    }
+
+    #[test]
+    fn test_role_change_ignored() {
+        let change = RoleChange::new(&[Id::new(1)], &[Id::new(1), Id::new(2)], &[Id::new(2)]);
+        assert!(change.is_empty());
+
+        let change = RoleChange::new(&[Id::new(1)], &[Id::new(1)], &[]);
+        assert!(change.is_empty());
+    }
+}
@@ -0,0 +1,134 @@
+//! Membership screening.
+//!
+//! Guilds using the Discord membership screening require new members to
+//! accept the server rules before they can interact. Until then, members are
+//! marked as `pending` and are already gated by Discord.
+//!
+//! When [`GuildConfig::screening_aware`] is enabled, pending members are not
+//! processed when they join. The raid mode and the captcha are instead applied
+//! when the member accepts the rules, which is detected from the `pending`
+//! flag of the previous [`MemberRecord`] on `MemberUpdate` events.
+//!
+//! [`GuildConfig::screening_aware`]: raidprotect_model::database::model::GuildConfig::screening_aware
+
+use raidprotect_model::cache::model::member::MemberRecord;
+use tracing::{debug, error};
+use twilight_model::{gateway::payload::incoming::MemberUpdate, guild::Member};
+
+use super::{
+    captcha,
+    raid_mode::{self, MemberJoin},
+};
+use crate::{cluster::ClusterState, database, util::account::account_created_at};
+
+/// Handle `MemberAdd` event.
+///
+/// Returns `true` if the member is pending the membership screening and must
+/// not be processed yet.
+pub async fn member_add(member: &Member, state: &ClusterState) -> bool {
+    if !member.pending {
+        return false;
+    }
+
+    match database::guild_config(state, member.guild_id).await {
+        Ok(config) => config.screening_aware,
+        Err(error) => {
+            error!(error = ?error, member = ?member, "error while processing screening `MemberAdd` event");
+
+            false
+        }
+    }
+}
+
+/// Handle `MemberUpdate` event.
+///
+/// The `previous` record must be retrieved before the cache is updated with
+/// the event.
+pub async fn member_update(
+    event: &MemberUpdate,
+    previous: Option<&MemberRecord>,
+    state: &ClusterState,
+) {
+    if !screening_accepted(previous, event.pending) {
+        return;
+    }
+
+    let config = match database::guild_config(state, event.guild_id).await {
+        Ok(config) => config,
+        Err(error) => {
+            error!(error = ?error, member = ?event, "error while processing screening `MemberUpdate` event");
+
+            return;
+        }
+    };
+
+    if !config.screening_aware {
+        return;
+    }
+
+    debug!(guild = ?event.guild_id, user = ?event.user.id, "member accepted the membership screening");
+
+    let join = MemberJoin {
+        guild_id: event.guild_id,
+        user_id: event.user.id,
+        created_at: account_created_at(event.user.id),
+    };
+
+    // Members kicked by the raid mode don't need to complete the captcha.
+    if raid_mode::member_join(join, state).await {
+        return;
+    }
+
+    captcha::member_accepted(event.guild_id, event.user.id, state).await;
+}
+
+/// Whether a member has just accepted the membership screening.
+///
+/// Members without record have not been seen before, so the transition
+/// cannot be determined.
+fn screening_accepted(previous: Option<&MemberRecord>, pending: bool) -> bool {
+    match previous {
+        Some(record) => record.pending && !pending,
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::{id::Id, util::Timestamp};
+
+    use super::*;
+
+    fn record(pending: bool) -> MemberRecord {
+        MemberRecord {
+            guild_id: Id::new(1),
+            user_id: Id::new(2),
+            joined_at: Timestamp::from_secs(1_600_000_000).unwrap(),
+            roles: Vec::new(),
+            left_at: None,
+            pending,
+        }
+    }
+
+    #[test]
+    fn test_screening_accepted() {
+        assert!(screening_accepted(Some(&record(true)), false));
+    }
+
+    #[test]
+    fn test_screening_still_pending() {
+        assert!(!screening_accepted(Some(&record(true)), true));
+    }
+
+    #[test]
+    fn test_screening_already_accepted() {
+        assert!(!screening_accepted(Some(&record(false)), false));
+        assert!(!screening_accepted(Some(&record(false)), true));
+    }
+
+    #[test]
+    fn test_screening_unknown_member() {
+        assert!(!screening_accepted(None, false));
+        assert!(!screening_accepted(None, true));
+    }
+}
@@ -0,0 +1,416 @@
+//! Dangerous permission changes alerts.
+//!
+//! Compromised administrator accounts often start by granting themselves
+//! dangerous permissions. When enabled in the guild configuration, an alert is
+//! sent in the logs channel when:
+//! - the guild ownership is transferred,
+//! - a role gains [`DANGEROUS_PERMISSIONS`],
+//! - a member is given a role with [`DANGEROUS_PERMISSIONS`].
+//!
+//! The author of the change is retrieved from the guild audit log, if the bot
+//! has access to it. No action is taken automatically, but role alerts include
+//! a button to strip the role (see [`PendingRoleStrip`]).
+//!
+//! Since the alerts are computed from the previous state of the cache, the
+//! previous state must be retrieved before the cache is updated with the
+//! event.
+//!
+//! ## Bulk role edits
+//! Giving a role to many members at once would send as many alerts. Alerts
+//! about the same role are throttled for [`ALERT_INTERVAL`].
+
+use raidprotect_model::{
+    cache::{
+        discord::{CachedGuild, CachedRole},
+        model::interaction::PendingRoleStrip,
+    },
+    database::model::{GuildConfig, LogCategory},
+};
+use time::Duration;
+use tracing::{debug, error};
+use twilight_model::{
+    application::component::{button::ButtonStyle, ActionRow, Button, Component},
+    channel::embed::Embed,
+    gateway::payload::incoming::{GuildUpdate, MemberUpdate, RoleUpdate},
+    guild::{audit_log::AuditLogEventType, Permissions},
+    id::{
+        marker::{GenericMarker, GuildMarker, RoleMarker, UserMarker},
+        Id,
+    },
+};
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    interaction::{
+        embed,
+        util::{CustomId, GuildConfigExt},
+    },
+    util::logs::{self, LogMessage},
+};
+
+/// Permissions that trigger an alert when granted.
+pub const DANGEROUS_PERMISSIONS: Permissions = Permissions::ADMINISTRATOR
+    .union(Permissions::BAN_MEMBERS)
+    .union(Permissions::MANAGE_WEBHOOKS);
+
+/// Minimum interval between two alerts about the same role.
+const ALERT_INTERVAL: Duration = Duration::minutes(1);
+
+/// Number of audit log entries searched for the author of a change.
+const AUDIT_LOG_LIMIT: u16 = 10;
+
+/// Get the cached owner of a guild, before a `GuildUpdate` event is
+/// processed.
+pub async fn cached_owner(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+) -> Option<Id<UserMarker>> {
+    match state.cache.get::<CachedGuild>(&guild_id).await {
+        Ok(guild) => guild.map(|guild| guild.owner_id),
+        Err(error) => {
+            error!(error = ?error, "failed to get cached guild");
+            None
+        }
+    }
+}
+
+/// Get the cached permissions of a role, before a `RoleUpdate` event is
+/// processed.
+pub async fn cached_permissions(
+    state: &ClusterState,
+    role_id: Id<RoleMarker>,
+) -> Option<Permissions> {
+    match state.cache.get::<CachedRole>(&role_id).await {
+        Ok(role) => role.map(|role| role.permissions),
+        Err(error) => {
+            error!(error = ?error, "failed to get cached role");
+            None
+        }
+    }
+}
+
+/// Handle `GuildUpdate` event.
+pub async fn guild_update(
+    event: &GuildUpdate,
+    previous_owner: Option<Id<UserMarker>>,
+    state: &ClusterState,
+) {
+    let previous_owner = match previous_owner {
+        Some(owner) if owner != event.owner_id => owner,
+        _ => return,
+    };
+
+    let alert = Alert::OwnerTransfer {
+        previous: previous_owner,
+        owner: event.owner_id,
+    };
+
+    if let Err(error) = send_alert(state, event.id, alert).await {
+        error!(error = ?error, guild = ?event.id, "failed to send ownership transfer alert");
+    }
+}
+
+/// Handle `RoleUpdate` event.
+pub async fn role_update(event: &RoleUpdate, previous: Option<Permissions>, state: &ClusterState) {
+    let previous = match previous {
+        Some(previous) => previous,
+        None => return,
+    };
+
+    let granted = event.role.permissions & DANGEROUS_PERMISSIONS & !previous;
+    if granted.is_empty() {
+        return;
+    }
+
+    let alert = Alert::RolePermissions {
+        role_id: event.role.id,
+        permissions: granted,
+    };
+
+    if let Err(error) = send_alert(state, event.guild_id, alert).await {
+        error!(error = ?error, role = ?event.role.id, "failed to send role permissions alert");
+    }
+}
+
+/// Handle `MemberUpdate` event.
+pub async fn member_update(
+    event: &MemberUpdate,
+    previous: Option<Vec<Id<RoleMarker>>>,
+    state: &ClusterState,
+) {
+    // Members without record have not been seen before, so the added roles
+    // cannot be determined.
+    let previous = match previous {
+        Some(previous) => previous,
+        None => return,
+    };
+
+    let added = event.roles.iter().filter(|role| !previous.contains(role));
+
+    for role_id in added {
+        let permissions = match cached_permissions(state, *role_id).await {
+            Some(permissions) => permissions & DANGEROUS_PERMISSIONS,
+            None => continue,
+        };
+
+        if permissions.is_empty() {
+            continue;
+        }
+
+        let alert = Alert::MemberRole {
+            user_id: event.user.id,
+            role_id: *role_id,
+            permissions,
+        };
+
+        if let Err(error) = send_alert(state, event.guild_id, alert).await {
+            error!(error = ?error, role = ?role_id, "failed to send member role alert");
+        }
+    }
+}
+
+/// Dangerous permission change.
+#[derive(Debug, Clone, Copy)]
+enum Alert {
+    /// Guild ownership transferred.
+    OwnerTransfer {
+        previous: Id<UserMarker>,
+        owner: Id<UserMarker>,
+    },
+    /// Role that gained dangerous permissions.
+    RolePermissions {
+        role_id: Id<RoleMarker>,
+        permissions: Permissions,
+    },
+    /// Member given a role with dangerous permissions.
+    MemberRole {
+        user_id: Id<UserMarker>,
+        role_id: Id<RoleMarker>,
+        permissions: Permissions,
+    },
+}
+
+impl Alert {
+    /// Audit log event type and target of the change.
+    fn audit_log_target(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> (AuditLogEventType, Id<GenericMarker>) {
+        match self {
+            Self::OwnerTransfer { .. } => (AuditLogEventType::GuildUpdate, guild_id.cast()),
+            Self::RolePermissions { role_id, .. } => {
+                (AuditLogEventType::RoleUpdate, role_id.cast())
+            }
+            Self::MemberRole { user_id, .. } => {
+                (AuditLogEventType::MemberRoleUpdate, user_id.cast())
+            }
+        }
+    }
+
+    /// Throttle key of the alert, if alerts of this type are deduplicated.
+    fn throttle_key(&self, guild_id: Id<GuildMarker>) -> Option<String> {
+        match self {
+            Self::OwnerTransfer { .. } => None,
+            Self::RolePermissions { role_id, .. } => {
+                Some(format!("sentinel:{guild_id}:role:{role_id}"))
+            }
+            Self::MemberRole { role_id, .. } => {
+                Some(format!("sentinel:{guild_id}:member-role:{role_id}"))
+            }
+        }
+    }
+
+    /// Pending state of the "strip role" button.
+    fn role_strip(
+        &self,
+        guild_id: Id<GuildMarker>,
+        actor_id: Option<Id<UserMarker>>,
+    ) -> Option<PendingRoleStrip> {
+        match *self {
+            Self::OwnerTransfer { .. } => None,
+            Self::RolePermissions {
+                role_id,
+                permissions,
+            } => Some(PendingRoleStrip {
+                guild_id,
+                role_id,
+                member_id: None,
+                permissions,
+                actor_id,
+            }),
+            Self::MemberRole {
+                user_id,
+                role_id,
+                permissions,
+            } => Some(PendingRoleStrip {
+                guild_id,
+                role_id,
+                member_id: Some(user_id),
+                permissions,
+                actor_id,
+            }),
+        }
+    }
+
+    /// Alert embed.
+    fn embed(&self, config: &GuildConfig, actor_id: Option<Id<UserMarker>>) -> Embed {
+        let lang = config.lang();
+
+        match *self {
+            Self::OwnerTransfer { previous, owner } => {
+                embed::logs::owner_transfer(lang, previous, owner, actor_id)
+            }
+            Self::RolePermissions {
+                role_id,
+                permissions,
+            } => embed::logs::role_permissions(lang, role_id, permissions, actor_id),
+            Self::MemberRole {
+                user_id,
+                role_id,
+                permissions,
+            } => embed::logs::member_role(lang, user_id, role_id, permissions, actor_id),
+        }
+    }
+}
+
+/// Send an alert in the logs channel, if enabled in the guild configuration.
+async fn send_alert(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    alert: Alert,
+) -> Result<(), anyhow::Error> {
+    let config = database::guild_config(state, guild_id).await?;
+
+    if !config.sentinel_alerts {
+        return Ok(());
+    }
+
+    if let Some(key) = alert.throttle_key(guild_id) {
+        if !state.cache.throttle(&key, ALERT_INTERVAL).await? {
+            return Ok(());
+        }
+    }
+
+    let actor_id = find_actor(state, guild_id, alert).await;
+
+    // Changes made by the bot itself (e.g. when stripping a role) are ignored.
+    if actor_id == Some(state.current_user.cast()) {
+        return Ok(());
+    }
+
+    let lang = config.lang();
+    let embed = alert.embed(&config, actor_id);
+
+    let mut components = Vec::new();
+    if let Some(pending) = alert.role_strip(guild_id, actor_id) {
+        state.cache.set(&pending).await?;
+
+        let custom_id = CustomId::new("role-strip", pending.id());
+        components.push(Component::ActionRow(ActionRow {
+            components: vec![Component::Button(Button {
+                custom_id: Some(custom_id.to_string()),
+                disabled: false,
+                emoji: None,
+                label: Some(lang.sentinel_strip_button().to_owned()),
+                style: ButtonStyle::Danger,
+                url: None,
+            })],
+        }));
+    }
+
+    let message = LogMessage::new(embed).components(components);
+    logs::post_message(state, &config, LogCategory::Raids, message).await?;
+
+    Ok(())
+}
+
+/// Find the author of a change in the guild audit log.
+///
+/// Returns [`None`] if the bot cannot access the audit log or if no matching
+/// entry is found.
+async fn find_actor(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    alert: Alert,
+) -> Option<Id<UserMarker>> {
+    let (kind, target) = alert.audit_log_target(guild_id);
+
+    let result = async {
+        let audit_log = state
+            .cache_http(guild_id)
+            .audit_log()
+            .await?
+            .action_type(kind)
+            .limit(AUDIT_LOG_LIMIT)?
+            .exec()
+            .await?
+            .model()
+            .await?;
+
+        let actor = audit_log
+            .entries
+            .into_iter()
+            .find(|entry| entry.target_id == Some(target))
+            .and_then(|entry| entry.user_id);
+
+        Ok::<_, anyhow::Error>(actor)
+    };
+
+    match result.await {
+        Ok(actor) => actor,
+        Err(error) => {
+            debug!(error = ?error, guild = ?guild_id, "failed to get audit log");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_throttle_key() {
+        let guild_id = Id::new(1);
+        let transfer = Alert::OwnerTransfer {
+            previous: Id::new(2),
+            owner: Id::new(3),
+        };
+        let role = Alert::RolePermissions {
+            role_id: Id::new(4),
+            permissions: Permissions::ADMINISTRATOR,
+        };
+        let member = Alert::MemberRole {
+            user_id: Id::new(2),
+            role_id: Id::new(4),
+            permissions: Permissions::ADMINISTRATOR,
+        };
+
+        assert_eq!(transfer.throttle_key(guild_id), None);
+        assert_eq!(role.throttle_key(guild_id).unwrap(), "sentinel:1:role:4");
+        assert_eq!(
+            member.throttle_key(guild_id).unwrap(),
+            "sentinel:1:member-role:4"
+        );
+    }
+
+    #[test]
+    fn test_role_strip() {
+        let transfer = Alert::OwnerTransfer {
+            previous: Id::new(2),
+            owner: Id::new(3),
+        };
+        let member = Alert::MemberRole {
+            user_id: Id::new(2),
+            role_id: Id::new(4),
+            permissions: Permissions::BAN_MEMBERS,
+        };
+
+        assert!(transfer.role_strip(Id::new(1), None).is_none());
+
+        let pending = member.role_strip(Id::new(1), Some(Id::new(5))).unwrap();
+        assert_eq!(pending.member_id, Some(Id::new(2)));
+        assert_eq!(pending.id(), "1-4-2");
+    }
+}
@@ -0,0 +1,370 @@
+//! Voice channels logs and hop detection.
+//!
+//! This module handles the `VoiceStateUpdate` event. The previous state of the
+//! member is retrieved from the cache to know what has changed, and the
+//! change is logged in the configured voice logs channel.
+//!
+//! Users that join voice channels too quickly (voice hop spam) are
+//! disconnected, and may be timed out.
+
+use raidprotect_model::{
+    cache::model::voice::CachedVoiceState,
+    database::model::{Feature, GuildConfig, LogCategory, StatsEvent, StatsFilter, VoiceHopAction},
+};
+use time::Duration;
+use tracing::{error, warn};
+use twilight_model::{
+    guild::{Guild, Member},
+    id::{
+        marker::{ChannelMarker, GuildMarker, UserMarker},
+        Id,
+    },
+    voice::VoiceState,
+};
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    feature::{
+        self,
+        automation::{AutoAction, MemberTrust},
+    },
+    interaction::{embed, util::GuildConfigExt},
+    util::{
+        account::{account_created_at, new_account_marker},
+        logs::{self, LogMessage},
+    },
+};
+
+/// Change of the voice state of a member.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoiceChange {
+    /// The member joined a voice channel.
+    Join(Id<ChannelMarker>),
+    /// The member left a voice channel.
+    Leave(Id<ChannelMarker>),
+    /// The member moved to another voice channel.
+    Move {
+        from: Id<ChannelMarker>,
+        to: Id<ChannelMarker>,
+    },
+    /// The member has been muted or unmuted by the server.
+    Mute {
+        channel: Id<ChannelMarker>,
+        mute: bool,
+    },
+    /// The member has been deafened or undeafened by the server.
+    Deaf {
+        channel: Id<ChannelMarker>,
+        deaf: bool,
+    },
+}
+
+impl VoiceChange {
+    /// Get the change between two voice states.
+    ///
+    /// Returns [`None`] if nothing relevant has changed (for example when the
+    /// member mutes itself).
+    pub fn between(
+        previous: Option<&CachedVoiceState>,
+        current: Option<&CachedVoiceState>,
+    ) -> Option<Self> {
+        match (previous, current) {
+            (None, None) => None,
+            (None, Some(current)) => Some(Self::Join(current.channel_id)),
+            (Some(previous), None) => Some(Self::Leave(previous.channel_id)),
+            (Some(previous), Some(current)) if previous.channel_id != current.channel_id => {
+                Some(Self::Move {
+                    from: previous.channel_id,
+                    to: current.channel_id,
+                })
+            }
+            (Some(previous), Some(current)) if previous.mute != current.mute => Some(Self::Mute {
+                channel: current.channel_id,
+                mute: current.mute,
+            }),
+            (Some(previous), Some(current)) if previous.deaf != current.deaf => Some(Self::Deaf {
+                channel: current.channel_id,
+                deaf: current.deaf,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Whether the member connected to a new channel.
+    pub fn is_hop(&self) -> bool {
+        matches!(self, Self::Join(_) | Self::Move { .. })
+    }
+}
+
+/// Handle `VoiceStateUpdate` event.
+pub async fn voice_state_update(voice: &VoiceState, state: &ClusterState) {
+    if let Err(error) = voice_state_update_inner(voice, state).await {
+        error!(error = ?error, voice = ?voice, "error while processing `VoiceStateUpdate` event");
+    }
+}
+
+async fn voice_state_update_inner(
+    voice: &VoiceState,
+    state: &ClusterState,
+) -> Result<(), anyhow::Error> {
+    let guild_id = match voice.guild_id {
+        Some(guild_id) => guild_id,
+        None => return Ok(()),
+    };
+
+    // Update the cached state.
+    let id = (guild_id, voice.user_id);
+    let previous = state.cache.get::<CachedVoiceState>(&id).await?;
+    let current = CachedVoiceState::from_voice_state(voice);
+
+    match (&previous, &current) {
+        (_, Some(current)) => state.cache.set(current).await?,
+        (Some(previous), None) => state.cache.delete(previous).await?,
+        (None, None) => {}
+    }
+
+    let change = match VoiceChange::between(previous.as_ref(), current.as_ref()) {
+        Some(change) => change,
+        None => return Ok(()),
+    };
+
+    let config = database::guild_config(state, guild_id).await?;
+
+    if let Some(channel) = config.voice.logs_chan {
+        let embed = embed::logs::voice_change(config.lang(), voice.user_id, change);
+
+        if let Err(error) = state
+            .cache_http(guild_id)
+            .create_message(channel)
+            .await?
+            .embeds(&[embed])?
+            .exec()
+            .await
+        {
+            warn!(error = ?error, guild = ?guild_id, "failed to send voice log");
+        }
+    }
+
+    if change.is_hop() {
+        check_hops(voice, guild_id, &config, state).await?;
+    }
+
+    Ok(())
+}
+
+/// Check whether the user joins voice channels too quickly.
+async fn check_hops(
+    voice: &VoiceState,
+    guild_id: Id<GuildMarker>,
+    config: &GuildConfig,
+    state: &ClusterState,
+) -> Result<(), anyhow::Error> {
+    let voice_config = &config.voice;
+    let member = match &voice.member {
+        Some(member) => member,
+        None => return Ok(()),
+    };
+
+    if !voice_config.hop_detection
+        || !config.features.contains(Feature::SpamFilter)
+        || member.user.bot
+        || config.is_automod_exempt(&voice_config.trusted_roles, &member.roles)
+    {
+        return Ok(());
+    }
+
+    let key = format!("voice-hop:{guild_id}:{}", voice.user_id);
+    let interval = Duration::seconds(voice_config.interval.into());
+
+    if state
+        .cache
+        .rate_limit(&key, voice_config.max_hops.into(), interval)
+        .await?
+    {
+        return Ok(());
+    }
+
+    // The action is only applied once per interval.
+    if !state.cache.throttle(&key, interval).await? {
+        return Ok(());
+    }
+
+    let event = StatsEvent::Filter(StatsFilter::VoiceHop);
+    feature::stats::record(state, guild_id, event).await;
+
+    sanction(state, config, guild_id, member).await
+}
+
+/// Disconnect the user and apply the configured action.
+///
+/// The timeout is applied with [`feature::automation::timeout`], so it is
+/// softened for trusted members.
+async fn sanction(
+    state: &ClusterState,
+    config: &GuildConfig,
+    guild_id: Id<GuildMarker>,
+    member: &Member,
+) -> Result<(), anyhow::Error> {
+    let lang = config.lang();
+    let voice_config = &config.voice;
+    let user_id = member.user.id;
+
+    let mut applied = true;
+
+    if let Err(error) = disconnect(state, guild_id, user_id).await {
+        warn!(error = ?error, guild = ?guild_id, "failed to disconnect member");
+        applied = false;
+    }
+
+    let action = match voice_config.action {
+        VoiceHopAction::Timeout => {
+            let result = feature::automation::timeout(
+                state,
+                config,
+                &member.user,
+                Some(MemberTrust::from(member)),
+                Duration::seconds(voice_config.timeout.into()),
+                lang.voice_hop_reason(),
+            );
+
+            match result.await {
+                Ok(action) => Some(action),
+                Err(error) => {
+                    warn!(error = ?error, guild = ?guild_id, "failed to timeout member");
+                    applied = false;
+                    None
+                }
+            }
+        }
+        VoiceHopAction::Disconnect => None,
+    };
+
+    // Send a message in the logs channel.
+    let created_at = account_created_at(user_id);
+    let new_account = new_account_marker(created_at, config.new_account_threshold, lang);
+    let timeout = matches!(action, Some(AutoAction::Timeout { .. }));
+    let mut embed =
+        embed::logs::voice_hop(lang, user_id, voice_config, applied, timeout, new_account);
+
+    if let Some(AutoAction::Softened(reason)) = action {
+        embed = embed::logs::softened(lang, embed, reason);
+    }
+
+    let mut message = LogMessage::new(embed);
+    if let Some(AutoAction::Timeout { case_id }) = action {
+        message = message.components(vec![feature::automation::adjust_components(lang, case_id)]);
+    }
+
+    logs::post_message(state, config, LogCategory::Filters, message).await?;
+
+    Ok(())
+}
+
+async fn disconnect(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+) -> Result<(), anyhow::Error> {
+    state
+        .cache_http(guild_id)
+        .disconnect_member(user_id)
+        .await?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+/// Store the voice states received with a guild.
+///
+/// Without these states, the first update of members already connected when
+/// the bot starts would be considered as a join.
+pub async fn guild_create(guild: &Guild, state: &ClusterState) {
+    for voice in &guild.voice_states {
+        let channel_id = match voice.channel_id {
+            Some(channel_id) => channel_id,
+            None => continue,
+        };
+
+        // The guild id is not included in the voice states of a guild.
+        let cached = CachedVoiceState {
+            guild_id: guild.id,
+            user_id: voice.user_id,
+            channel_id,
+            mute: voice.mute,
+            deaf: voice.deaf,
+        };
+
+        if let Err(error) = state.cache.set(&cached).await {
+            error!(error = ?error, guild = ?guild.id, "failed to cache voice state");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn voice_state(channel: u64, mute: bool) -> CachedVoiceState {
+        CachedVoiceState {
+            guild_id: Id::new(1),
+            user_id: Id::new(2),
+            channel_id: Id::new(channel),
+            mute,
+            deaf: false,
+        }
+    }
+
+    #[test]
+    fn test_change_join_leave() {
+        let state = voice_state(10, false);
+
+        assert_eq!(
+            VoiceChange::between(None, Some(&state)),
+            Some(VoiceChange::Join(Id::new(10)))
+        );
+        assert_eq!(
+            VoiceChange::between(Some(&state), None),
+            Some(VoiceChange::Leave(Id::new(10)))
+        );
+        assert_eq!(VoiceChange::between(None, None), None);
+    }
+
+    #[test]
+    fn test_change_move() {
+        let change =
+            VoiceChange::between(Some(&voice_state(10, false)), Some(&voice_state(11, true)));
+
+        assert_eq!(
+            change,
+            Some(VoiceChange::Move {
+                from: Id::new(10),
+                to: Id::new(11)
+            })
+        );
+        assert!(change.unwrap().is_hop());
+    }
+
+    #[test]
+    fn test_change_mute() {
+        let change =
+            VoiceChange::between(Some(&voice_state(10, false)), Some(&voice_state(10, true)));
+
+        assert_eq!(
+            change,
+            Some(VoiceChange::Mute {
+                channel: Id::new(10),
+                mute: true
+            })
+        );
+        assert!(!change.unwrap().is_hop());
+    }
+
+    #[test]
+    fn test_change_unchanged() {
+        let state = voice_state(10, false);
+
+        assert_eq!(VoiceChange::between(Some(&state), Some(&state)), None);
+    }
+}
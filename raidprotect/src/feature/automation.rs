@@ -0,0 +1,705 @@
+//! Shared stage of the automatic sanctions.
+//!
+//! The filters that time out members (link filter, pattern rules, reaction
+//! spam, voice hop detection and first message filter) apply the timeout with
+//! [`timeout`] instead of calling the API directly, so that the sanction can be
+//! softened for trusted members.
+//!
+//! ## Grace period
+//!
+//! If the [`GraceConfig`] is enabled, a member is trusted if they boost the
+//! guild, have been a member for longer than the configured tenure or have one
+//! of the trusted roles (see [`trust`]). The first offense of a trusted member
+//! is not sanctioned: the member receives a warning in private messages
+//! instead. A repeated offense within [`GRACE_INTERVAL`] is sanctioned as
+//! usual.
+//!
+//! The first message filter is explicitly exempted: it only applies to members
+//! who just joined the guild, so its timeouts are applied without the member
+//! information and are never softened.
+//!
+//! ## Adjustments
+//!
+//! Automatic timeouts are recorded in the modlogs with RaidProtect as
+//! moderator (see [`automatic_moderator`]), and stored as an [`ActiveMute`] like the mutes of the
+//! moderation commands. The log of the filter has buttons to lift, shorten or
+//! extend the timeout (see [`adjust_components`]), which moderators can use to
+//! correct a sanction without issuing a new one. Each adjustment is recorded
+//! as an amendment of the modlog (see [`adjust`]).
+//!
+//! ## Automatic bans
+//!
+//! The bans of the antinuke, of the first message filter and of the bulk ban
+//! command are recorded the same way with [`record_ban`], so that they can be
+//! reversed with the bulk unban command.
+
+use anyhow::Context;
+use raidprotect_model::{
+    cache::{
+        discord::CachedGuild,
+        model::{
+            interaction::PendingTimeoutAdjust, lock::DistributedLock, write_queue::PendingWrite,
+        },
+    },
+    database::model::{
+        ActiveMute, AmendmentKind, GraceConfig, GuildConfig, Modlog, ModlogAmendment, ModlogType,
+        ModlogUser, ObjectId,
+    },
+};
+use time::{Duration, OffsetDateTime};
+use tracing::{debug, warn};
+use twilight_http::request::AuditLogReason;
+use twilight_model::{
+    application::component::{button::ButtonStyle, ActionRow, Button, Component},
+    guild::{Member, PartialMember},
+    id::{
+        marker::{GuildMarker, RoleMarker, UserMarker},
+        Id,
+    },
+    user::User,
+    util::Timestamp,
+};
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    feature::sanction::{store_mute, MAX_TIMEOUT},
+    interaction::{
+        embed,
+        util::{CustomId, GuildConfigExt},
+    },
+    translations::Lang,
+    util::time::from_discord,
+};
+
+/// Interval during which a repeated offense of a trusted member is
+/// sanctioned.
+pub const GRACE_INTERVAL: Duration = Duration::hours(1);
+
+/// Duration removed from a timeout when it is shortened.
+pub const SHORTEN_STEP: Duration = Duration::minutes(30);
+
+/// Duration added to a timeout when it is extended.
+pub const EXTEND_STEP: Duration = Duration::HOUR;
+
+/// Expiration of the lock held while a timeout is adjusted.
+const LOCK_TTL: Duration = Duration::seconds(30);
+
+/// Name of the moderator of the automatic sanctions in the modlogs.
+const AUTOMATIC_MODERATOR: &str = "RaidProtect";
+
+/// Reason why a member is trusted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrustReason {
+    /// The member boosts the guild.
+    Booster,
+    /// The member has been in the guild for longer than the trusted tenure.
+    Tenure,
+    /// The member has one of the trusted roles.
+    Role,
+}
+
+/// Automatic action applied to a member.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoAction {
+    /// The member has been timed out.
+    Timeout {
+        /// Id of the moderation log of the timeout.
+        case_id: ObjectId,
+    },
+    /// The member has been warned instead of being timed out.
+    Softened(TrustReason),
+}
+
+/// Member information used to evaluate the trust.
+#[derive(Debug, Clone, Copy)]
+pub struct MemberTrust<'a> {
+    pub roles: &'a [Id<RoleMarker>],
+    pub joined_at: Timestamp,
+    pub premium_since: Option<Timestamp>,
+}
+
+impl<'a> From<&'a PartialMember> for MemberTrust<'a> {
+    fn from(member: &'a PartialMember) -> Self {
+        Self {
+            roles: &member.roles,
+            joined_at: member.joined_at,
+            premium_since: member.premium_since,
+        }
+    }
+}
+
+impl<'a> From<&'a Member> for MemberTrust<'a> {
+    fn from(member: &'a Member) -> Self {
+        Self {
+            roles: &member.roles,
+            joined_at: member.joined_at,
+            premium_since: member.premium_since,
+        }
+    }
+}
+
+/// Evaluate whether a member is trusted.
+///
+/// Returns [`None`] if the grace period is disabled or if the member is not
+/// trusted.
+pub fn trust(
+    config: &GraceConfig,
+    member: &MemberTrust,
+    now: OffsetDateTime,
+) -> Option<TrustReason> {
+    if !config.enabled {
+        return None;
+    }
+
+    if config.boosters && member.premium_since.is_some() {
+        return Some(TrustReason::Booster);
+    }
+
+    if member
+        .roles
+        .iter()
+        .any(|role| config.trusted_roles.contains(role))
+    {
+        return Some(TrustReason::Role);
+    }
+
+    match config.trusted_tenure {
+        Some(days) if now - from_discord(member.joined_at) >= Duration::days(days.into()) => {
+            Some(TrustReason::Tenure)
+        }
+        _ => None,
+    }
+}
+
+/// Timeout a member sanctioned by a filter.
+///
+/// The first offense of a trusted member is softened: the member is warned in
+/// private messages instead. The `member` is [`None`] if the member
+/// information is not available, in which case the member is not trusted.
+///
+/// The timeout is recorded in the modlogs and can be adjusted with the
+/// buttons of [`adjust_components`].
+pub async fn timeout(
+    state: &ClusterState,
+    config: &GuildConfig,
+    user: &User,
+    member: Option<MemberTrust<'_>>,
+    duration: Duration,
+    sanction_reason: &str,
+) -> Result<AutoAction, anyhow::Error> {
+    let guild_id = config.id;
+    let user_id = user.id;
+    let now = OffsetDateTime::now_utc();
+
+    if let Some(reason) = member.and_then(|member| trust(&config.grace, &member, now)) {
+        let key = format!("grace:{guild_id}:{user_id}");
+
+        if state.cache.throttle(&key, GRACE_INTERVAL).await? {
+            debug!(guild = ?guild_id, user = ?user_id, trust = ?reason, "automatic sanction softened");
+            warn_member(state, config, user_id, sanction_reason).await;
+
+            return Ok(AutoAction::Softened(reason));
+        }
+    }
+
+    let until = now + duration;
+    state
+        .cache_http(guild_id)
+        .timeout_member(user_id, Timestamp::from_secs(until.unix_timestamp())?)
+        .await?
+        .reason(sanction_reason)?
+        .exec()
+        .await?;
+
+    let case_id = ObjectId::new();
+    let modlog = Modlog {
+        id: Some(case_id),
+        date: now,
+        ..automatic_modlog(
+            ModlogType::Mute,
+            guild_id,
+            ModlogUser::from(user),
+            automatic_moderator(state.current_user.cast()),
+            sanction_reason,
+        )
+    };
+    database::write(
+        state,
+        PendingWrite::Modlog {
+            modlog: Box::new(modlog),
+        },
+    )
+    .await?;
+
+    // A longer mute issued by a moderator is kept.
+    let longer_mute = state
+        .database
+        .get_mute(guild_id, user_id)
+        .await?
+        .map_or(false, |mute| {
+            mute.expires_at.map_or(true, |expires| expires > until)
+        });
+
+    if !longer_mute {
+        let mute = ActiveMute {
+            guild_id,
+            user_id,
+            expires_at: Some(until),
+            timeout: true,
+            case_id: Some(case_id),
+        };
+        store_mute(state, &mute).await?;
+    }
+
+    let pending = PendingTimeoutAdjust {
+        guild_id,
+        user_id,
+        case_id,
+    };
+    state.cache.set(&pending).await?;
+
+    Ok(AutoAction::Timeout { case_id })
+}
+
+/// Record a ban applied automatically in the modlogs.
+///
+/// Automatic bans are recorded like the automatic timeouts, so that they can
+/// be found and reversed with the bulk unban command. The `moderator` is
+/// usually the [`automatic_moderator`].
+pub async fn record_ban(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    user: ModlogUser,
+    moderator: ModlogUser,
+    reason: &str,
+) -> Result<(), anyhow::Error> {
+    let modlog = automatic_modlog(ModlogType::Ban, guild_id, user, moderator, reason);
+
+    database::write(
+        state,
+        PendingWrite::Modlog {
+            modlog: Box::new(modlog),
+        },
+    )
+    .await
+}
+
+/// Get the moderator recorded in the modlogs of the automatic sanctions.
+pub fn automatic_moderator(bot_id: Id<UserMarker>) -> ModlogUser {
+    ModlogUser {
+        id: bot_id,
+        name: AUTOMATIC_MODERATOR.to_owned(),
+        discriminator: 0,
+        avatar: None,
+    }
+}
+
+/// Get the user recorded in the modlogs when only its id is known.
+pub fn unknown_user(user_id: Id<UserMarker>) -> ModlogUser {
+    ModlogUser {
+        id: user_id,
+        name: user_id.to_string(),
+        discriminator: 0,
+        avatar: None,
+    }
+}
+
+/// Build the modlog of an automatic sanction.
+pub fn automatic_modlog(
+    kind: ModlogType,
+    guild_id: Id<GuildMarker>,
+    user: ModlogUser,
+    moderator: ModlogUser,
+    reason: &str,
+) -> Modlog {
+    Modlog {
+        id: None,
+        kind,
+        guild_id,
+        user,
+        moderator,
+        date: OffsetDateTime::now_utc(),
+        reason: Some(reason.to_owned()),
+        notes: None,
+        pre_emptive: false,
+        confirmation_skipped: false,
+        shadow: false,
+        dm_disabled: false,
+        dm_status: None,
+        synced_from: None,
+        amendments: Vec::new(),
+    }
+}
+
+/// Adjustment of an automatic timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeoutAdjustment {
+    /// The timeout is removed.
+    Lift,
+    /// The timeout is shortened by [`SHORTEN_STEP`].
+    Shorten,
+    /// The timeout is extended by [`EXTEND_STEP`].
+    Extend,
+}
+
+impl TimeoutAdjustment {
+    /// Get the new end of a timeout ending at `expires_at`.
+    ///
+    /// Returns [`None`] if the timeout is lifted, including when it is
+    /// shortened to an end in the past. Extended timeouts are capped at
+    /// [`MAX_TIMEOUT`].
+    pub fn apply(self, expires_at: OffsetDateTime, now: OffsetDateTime) -> Option<OffsetDateTime> {
+        match self {
+            Self::Lift => None,
+            Self::Shorten => Some(expires_at - SHORTEN_STEP).filter(|until| *until > now),
+            Self::Extend => Some((expires_at + EXTEND_STEP).min(now + MAX_TIMEOUT)),
+        }
+    }
+
+    /// Custom id of the button of the adjustment.
+    fn custom_id(self) -> &'static str {
+        match self {
+            Self::Lift => "timeout-lift",
+            Self::Shorten => "timeout-shorten",
+            Self::Extend => "timeout-extend",
+        }
+    }
+}
+
+/// Result of an adjusted timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdjustResult {
+    /// The timeout had already ended.
+    Ended,
+    /// The timeout has been lifted.
+    Lifted,
+    /// The timeout now ends at the given date.
+    Ends(OffsetDateTime),
+}
+
+/// Buttons attached to the log of an automatic timeout.
+pub fn adjust_components(lang: Lang, case_id: ObjectId) -> Component {
+    let button = |adjustment: TimeoutAdjustment, label: &str, style: ButtonStyle| {
+        Component::Button(Button {
+            custom_id: Some(CustomId::new(adjustment.custom_id(), case_id.to_hex()).to_string()),
+            disabled: false,
+            emoji: None,
+            label: Some(label.to_owned()),
+            style,
+            url: None,
+        })
+    };
+
+    Component::ActionRow(ActionRow {
+        components: vec![
+            button(
+                TimeoutAdjustment::Lift,
+                lang.timeout_adjust_lift_button(),
+                ButtonStyle::Success,
+            ),
+            button(
+                TimeoutAdjustment::Shorten,
+                lang.timeout_adjust_shorten_button(),
+                ButtonStyle::Secondary,
+            ),
+            button(
+                TimeoutAdjustment::Extend,
+                lang.timeout_adjust_extend_button(),
+                ButtonStyle::Secondary,
+            ),
+        ],
+    })
+}
+
+/// Whether a component is the row created with [`adjust_components`].
+pub fn is_adjust_row(component: &Component) -> bool {
+    let row = match component {
+        Component::ActionRow(row) => row,
+        _ => return false,
+    };
+
+    row.components.iter().any(|component| match component {
+        Component::Button(button) => button
+            .custom_id
+            .as_deref()
+            .map_or(false, |id| id.starts_with("timeout-")),
+        _ => false,
+    })
+}
+
+/// Adjust an automatic timeout.
+///
+/// The timeout must still be active and tracked with the case of the
+/// `pending` state, otherwise [`AdjustResult::Ended`] is returned. The active
+/// mute is updated before the timeout, so that the change is not reverted as
+/// a cleared timeout when mutes are enforced.
+///
+/// Returns [`None`] if the timeout is already being adjusted.
+pub async fn adjust(
+    state: &ClusterState,
+    config: &GuildConfig,
+    pending: &PendingTimeoutAdjust,
+    adjustment: TimeoutAdjustment,
+    moderator: &User,
+) -> Result<Option<AdjustResult>, anyhow::Error> {
+    let key = format!("timeout-adjust:{}", pending.case_id);
+    let lock = match DistributedLock::acquire(&state.cache, &key, LOCK_TTL).await? {
+        Some(lock) => lock,
+        None => return Ok(None),
+    };
+
+    let result = adjust_inner(state, config, pending, adjustment, moderator).await;
+
+    if let Err(error) = lock.release().await {
+        warn!(error = ?error, case = %pending.case_id, "failed to release adjustment lock");
+    }
+
+    result.map(Some)
+}
+
+async fn adjust_inner(
+    state: &ClusterState,
+    config: &GuildConfig,
+    pending: &PendingTimeoutAdjust,
+    adjustment: TimeoutAdjustment,
+    moderator: &User,
+) -> Result<AdjustResult, anyhow::Error> {
+    let (guild_id, user_id) = (pending.guild_id, pending.user_id);
+    let now = OffsetDateTime::now_utc();
+
+    let expires_at = match state.database.get_mute(guild_id, user_id).await? {
+        Some(mute)
+            if mute.timeout && mute.case_id == Some(pending.case_id) && !mute.is_expired(now) =>
+        {
+            mute.expires_at.context("timeout without expiration")?
+        }
+        _ => return Ok(AdjustResult::Ended),
+    };
+
+    let lang = config.lang();
+    let cache_http = state.cache_http(guild_id);
+    let until = adjustment.apply(expires_at, now);
+
+    let result = match until {
+        Some(until) => {
+            let mute = ActiveMute {
+                guild_id,
+                user_id,
+                expires_at: Some(until),
+                timeout: true,
+                case_id: Some(pending.case_id),
+            };
+            store_mute(state, &mute).await?;
+
+            cache_http
+                .timeout_member(user_id, Timestamp::from_secs(until.unix_timestamp())?)
+                .await?
+                .reason(&lang.timeout_adjust_reason(moderator.id))?
+                .exec()
+                .await?;
+
+            AdjustResult::Ends(until)
+        }
+        None => {
+            state.database.delete_mute(guild_id, user_id).await?;
+
+            cache_http
+                .remove_timeout(user_id)
+                .await?
+                .reason(&lang.timeout_adjust_lift_reason(moderator.id))?
+                .exec()
+                .await?;
+
+            state.cache.delete(pending).await?;
+
+            AdjustResult::Lifted
+        }
+    };
+
+    let kind = match (adjustment, until) {
+        (_, None) => AmendmentKind::Lift,
+        (TimeoutAdjustment::Extend, Some(_)) => AmendmentKind::Extend,
+        (_, Some(_)) => AmendmentKind::Shorten,
+    };
+    let amendment = ModlogAmendment {
+        kind,
+        actor: ModlogUser::from(moderator),
+        date: now,
+        expires_at: until,
+    };
+
+    // The modlog may not be written yet if the database was unavailable.
+    if !state
+        .database
+        .amend_modlog(pending.case_id, &amendment)
+        .await?
+    {
+        debug!(case = %pending.case_id, "amended modlog not found");
+    }
+
+    Ok(result)
+}
+
+/// Send the grace period warning to a member.
+///
+/// Errors are ignored since members may have closed their private messages.
+async fn warn_member(
+    state: &ClusterState,
+    config: &GuildConfig,
+    user_id: Id<UserMarker>,
+    reason: &str,
+) {
+    let result = async {
+        let guild = state
+            .cache
+            .get::<CachedGuild>(&config.id)
+            .await?
+            .context("guild not cached")?;
+        let embed = embed::post_in_chat::grace_warning(config.lang(), &guild.name, reason);
+
+        let channel = state
+            .http
+            .create_private_channel(user_id)
+            .exec()
+            .await?
+            .model()
+            .await?;
+
+        state
+            .http
+            .create_message(channel.id)
+            .embeds(&[embed])?
+            .exec()
+            .await?;
+
+        Ok::<_, anyhow::Error>(())
+    };
+
+    if let Err(error) = result.await {
+        debug!(error = ?error, "failed to send grace period warning");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use raidprotect_model::database::model::StatsFilter;
+    use twilight_model::guild::Permissions;
+
+    use super::*;
+    use crate::{
+        feature::filter_feedback,
+        test_utils::fixture::{joined_at, member_with_perms},
+    };
+
+    fn date(timestamp: i64) -> OffsetDateTime {
+        OffsetDateTime::from_unix_timestamp(timestamp).unwrap()
+    }
+
+    fn config() -> GraceConfig {
+        GraceConfig {
+            enabled: true,
+            boosters: true,
+            trusted_tenure: Some(30),
+            trusted_roles: vec![Id::new(1)],
+        }
+    }
+
+    #[test]
+    fn test_trust_disabled() {
+        let mut member = member_with_perms(Permissions::empty());
+        member.roles = vec![Id::new(1)];
+        member.premium_since = Some(joined_at());
+        let partial = member.partial();
+
+        let config = GraceConfig {
+            enabled: false,
+            ..config()
+        };
+        let now = from_discord(joined_at()) + Duration::days(365);
+
+        assert_eq!(trust(&config, &(&partial).into(), now), None);
+    }
+
+    #[test]
+    fn test_trust_reasons() {
+        let config = config();
+        let joined = from_discord(joined_at());
+
+        let member = member_with_perms(Permissions::empty()).partial();
+        assert_eq!(trust(&config, &(&member).into(), joined), None);
+        assert_eq!(
+            trust(&config, &(&member).into(), joined + Duration::days(30)),
+            Some(TrustReason::Tenure)
+        );
+
+        let mut booster = member_with_perms(Permissions::empty());
+        booster.premium_since = Some(joined_at());
+        assert_eq!(
+            trust(&config, &(&booster.partial()).into(), joined),
+            Some(TrustReason::Booster)
+        );
+
+        let mut trusted = member_with_perms(Permissions::empty());
+        trusted.roles = vec![Id::new(2), Id::new(1)];
+        assert_eq!(
+            trust(&config, &(&trusted.partial()).into(), joined),
+            Some(TrustReason::Role)
+        );
+    }
+
+    #[test]
+    fn test_trust_criteria_disabled() {
+        let config = GraceConfig {
+            boosters: false,
+            trusted_tenure: None,
+            ..config()
+        };
+        let now = from_discord(joined_at()) + Duration::days(365);
+
+        let mut booster = member_with_perms(Permissions::empty());
+        booster.premium_since = Some(joined_at());
+
+        assert_eq!(trust(&config, &(&booster.partial()).into(), now), None);
+    }
+
+    #[test]
+    fn test_adjustment_apply() {
+        let now = date(1_628_594_197);
+        let expires_at = now + Duration::HOUR;
+
+        assert_eq!(TimeoutAdjustment::Lift.apply(expires_at, now), None);
+        assert_eq!(
+            TimeoutAdjustment::Shorten.apply(expires_at, now),
+            Some(now + Duration::minutes(30))
+        );
+        assert_eq!(
+            TimeoutAdjustment::Extend.apply(expires_at, now),
+            Some(now + Duration::hours(2))
+        );
+    }
+
+    #[test]
+    fn test_adjustment_apply_limits() {
+        let now = date(1_628_594_197);
+
+        // Shortening a timeout that ends soon lifts it.
+        let expires_at = now + Duration::minutes(20);
+        assert_eq!(TimeoutAdjustment::Shorten.apply(expires_at, now), None);
+
+        let expires_at = now + MAX_TIMEOUT - Duration::minutes(10);
+        assert_eq!(
+            TimeoutAdjustment::Extend.apply(expires_at, now),
+            Some(now + MAX_TIMEOUT)
+        );
+    }
+
+    #[test]
+    fn test_is_adjust_row() {
+        let components = adjust_components(Lang::DEFAULT, ObjectId::new());
+        assert!(is_adjust_row(&components));
+
+        let feedback = filter_feedback::components(Lang::DEFAULT, StatsFilter::Link, "00");
+        assert!(!is_adjust_row(&feedback[0]));
+    }
+}
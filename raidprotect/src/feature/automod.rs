@@ -0,0 +1,471 @@
+//! Native AutoMod rules synchronization.
+//!
+//! Discord provides native AutoMod rules that are applied even when the bot
+//! is offline. When enabled in the [`AutomodConfig`], RaidProtect creates
+//! native rules derived from the configuration as a second layer of
+//! protection, and updates them when the configuration changes.
+//!
+//! The rules created by the bot are stored in the configuration with a
+//! fingerprint of their content. If the fingerprint of a rule is different
+//! when synchronizing, the rule has been edited (or deleted) manually: it is
+//! not modified unless the synchronization is forced, which requires a
+//! confirmation from the user.
+//!
+//! The AutoMod endpoints and the mention spam trigger are not supported by
+//! twilight yet, so the rules are managed with raw requests and the models
+//! defined in this module.
+
+use raidprotect_model::database::model::{
+    AutomodConfig, AutomodRule, AutomodRuleKind, GuildConfig,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+use twilight_http::{
+    request::{Method, RequestBuilder},
+    response::marker::EmptyBody,
+    routing::Path,
+};
+use twilight_model::{
+    guild::auto_moderation::{
+        AutoModerationAction, AutoModerationActionType, AutoModerationEventType,
+        AutoModerationTriggerType,
+    },
+    id::{
+        marker::{AutoModerationRuleMarker, ChannelMarker, GuildMarker, RoleMarker},
+        Id,
+    },
+};
+
+use crate::cluster::ClusterState;
+
+/// Trigger type of the mention spam rules.
+const MENTION_SPAM_TRIGGER: u8 = 5;
+
+/// Kinds of rules managed by RaidProtect.
+const RULE_KINDS: [AutomodRuleKind; 3] = [
+    AutomodRuleKind::Keyword,
+    AutomodRuleKind::Spam,
+    AutomodRuleKind::MentionSpam,
+];
+
+/// Result of a synchronization.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    /// Number of rules created.
+    pub created: usize,
+    /// Number of rules replaced with an updated version.
+    pub updated: usize,
+    /// Number of rules deleted.
+    pub deleted: usize,
+    /// Rules that have been edited manually and were not modified.
+    pub conflicts: Vec<AutomodRuleKind>,
+    /// Rules that could not be created.
+    pub failed: Vec<AutomodRuleKind>,
+}
+
+/// Synchronize the native rules of a guild with its configuration.
+///
+/// Rules edited manually are only overwritten if `force` is `true`. The
+/// rules stored in the configuration are updated, the configuration must be
+/// saved by the caller.
+pub async fn sync(
+    state: &ClusterState,
+    config: &mut GuildConfig,
+    force: bool,
+) -> Result<SyncReport, anyhow::Error> {
+    let guild_id = config.id;
+    let mut report = SyncReport::default();
+
+    // Rules are only fetched if some rules have been created.
+    let rules = match config.automod.rules.is_empty() {
+        true => Vec::new(),
+        false => {
+            let request = rules_request(Method::Get, guild_id, None).build();
+
+            state
+                .http
+                .request::<Vec<NativeRule>>(request)
+                .await?
+                .model()
+                .await?
+        }
+    };
+
+    for kind in RULE_KINDS {
+        let managed = config.automod.rule(kind).cloned();
+        let current = managed
+            .as_ref()
+            .and_then(|managed| rules.iter().find(|rule| rule.id == managed.id));
+        let desired = RuleState::desired(&config.automod, kind);
+
+        if let Some(managed) = &managed {
+            let edited = match current.and_then(RuleState::from_rule) {
+                Some(state) => state.fingerprint() != managed.fingerprint,
+                None => true,
+            };
+
+            // Rules deleted manually are only an issue if they should exist.
+            if edited && !force && (current.is_some() || desired.is_some()) {
+                report.conflicts.push(kind);
+                continue;
+            }
+        }
+
+        let current_state = current.and_then(RuleState::from_rule);
+        if current_state.is_some() && current_state == desired {
+            // The rule may have been edited manually to match the configuration.
+            set_rule(&mut config.automod, kind, current);
+            continue;
+        }
+
+        if let Some(rule) = current {
+            let request = rules_request(Method::Delete, guild_id, Some(rule.id)).build();
+
+            state.http.request::<EmptyBody>(request).await?;
+        }
+
+        match &desired {
+            Some(desired) => match create_rule(state, config, desired).await {
+                Ok(rule) => {
+                    match current {
+                        Some(_) => report.updated += 1,
+                        None => report.created += 1,
+                    }
+
+                    set_rule(&mut config.automod, kind, Some(&rule));
+                }
+                Err(error) => {
+                    warn!(error = ?error, guild = ?guild_id, kind = ?kind, "failed to create automod rule");
+
+                    report.failed.push(kind);
+                    set_rule(&mut config.automod, kind, None);
+                }
+            },
+            None => {
+                if current.is_some() {
+                    report.deleted += 1;
+                }
+
+                set_rule(&mut config.automod, kind, None);
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Create a native rule.
+async fn create_rule(
+    state: &ClusterState,
+    config: &GuildConfig,
+    desired: &RuleState,
+) -> Result<NativeRule, anyhow::Error> {
+    let body = CreateRule::new(desired);
+    let request = rules_request(Method::Post, config.id, None)
+        .json(&body)?
+        .build();
+
+    Ok(state
+        .http
+        .request::<NativeRule>(request)
+        .await?
+        .model()
+        .await?)
+}
+
+/// Build a request to the AutoMod rules endpoints of a guild.
+///
+/// The requests share the ratelimit bucket of the guild.
+fn rules_request(
+    method: Method,
+    guild_id: Id<GuildMarker>,
+    rule_id: Option<Id<AutoModerationRuleMarker>>,
+) -> RequestBuilder {
+    let path = match rule_id {
+        Some(rule_id) => format!("guilds/{guild_id}/auto-moderation/rules/{rule_id}"),
+        None => format!("guilds/{guild_id}/auto-moderation/rules"),
+    };
+
+    RequestBuilder::raw(method, Path::GuildsId(guild_id.get()), path)
+}
+
+/// Native rule returned by Discord.
+///
+/// Only the fields used by the synchronization are deserialized.
+#[derive(Debug, Clone, Deserialize)]
+struct NativeRule {
+    id: Id<AutoModerationRuleMarker>,
+    name: String,
+    enabled: bool,
+    trigger_type: AutoModerationTriggerType,
+    #[serde(default)]
+    trigger_metadata: TriggerMetadata,
+    actions: Vec<AutoModerationAction>,
+    #[serde(default)]
+    exempt_roles: Vec<Id<RoleMarker>>,
+    #[serde(default)]
+    exempt_channels: Vec<Id<ChannelMarker>>,
+}
+
+/// Metadata of a rule trigger.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct TriggerMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    keyword_filter: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mention_total_limit: Option<u8>,
+}
+
+/// Body of a rule creation request.
+#[derive(Debug, Clone, Serialize)]
+struct CreateRule<'a> {
+    name: &'a str,
+    event_type: AutoModerationEventType,
+    trigger_type: AutoModerationTriggerType,
+    trigger_metadata: TriggerMetadata,
+    actions: Vec<AutoModerationAction>,
+    enabled: bool,
+}
+
+impl<'a> CreateRule<'a> {
+    /// Build the creation request of a rule.
+    fn new(desired: &'a RuleState) -> Self {
+        let (trigger_type, trigger_metadata) = match desired.kind {
+            AutomodRuleKind::Keyword => (
+                AutoModerationTriggerType::Keyword,
+                TriggerMetadata {
+                    keyword_filter: Some(desired.keywords.clone()),
+                    ..Default::default()
+                },
+            ),
+            AutomodRuleKind::Spam => (AutoModerationTriggerType::Spam, TriggerMetadata::default()),
+            AutomodRuleKind::MentionSpam => (
+                AutoModerationTriggerType::Unknown(MENTION_SPAM_TRIGGER),
+                TriggerMetadata {
+                    mention_total_limit: Some(desired.mention_limit.unwrap_or_default()),
+                    ..Default::default()
+                },
+            ),
+        };
+
+        Self {
+            name: &desired.name,
+            event_type: AutoModerationEventType::MessageSend,
+            trigger_type,
+            trigger_metadata,
+            actions: vec![AutoModerationAction {
+                kind: AutoModerationActionType::BlockMessage,
+                metadata: None,
+            }],
+            enabled: true,
+        }
+    }
+}
+
+/// Update the rule of a given kind stored in the configuration.
+///
+/// The fingerprint of the rule is computed from the rule returned by Discord,
+/// so that it can be compared on the next synchronization.
+fn set_rule(config: &mut AutomodConfig, kind: AutomodRuleKind, rule: Option<&NativeRule>) {
+    config.rules.retain(|rule| rule.kind != kind);
+
+    if let Some(rule) = rule {
+        if let Some(state) = RuleState::from_rule(rule) {
+            config.rules.push(AutomodRule {
+                kind,
+                id: rule.id,
+                fingerprint: state.fingerprint(),
+            });
+        }
+    }
+}
+
+/// Content of a native rule.
+///
+/// This type contains the fields of a rule that can be edited, and is used
+/// to compare rules and compute their fingerprint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct RuleState {
+    kind: AutomodRuleKind,
+    name: String,
+    enabled: bool,
+    keywords: Vec<String>,
+    mention_limit: Option<u8>,
+    actions: Vec<String>,
+    exempt_roles: Vec<u64>,
+    exempt_channels: Vec<u64>,
+}
+
+impl RuleState {
+    /// Get the state of the rule of a given kind derived from the
+    /// configuration.
+    ///
+    /// [`None`] is returned if the rule should not exist.
+    fn desired(config: &AutomodConfig, kind: AutomodRuleKind) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+
+        let (name, keywords, mention_limit) = match kind {
+            AutomodRuleKind::Keyword if !config.keywords.is_empty() => {
+                let mut keywords = config.keywords.clone();
+                keywords.sort();
+
+                ("RaidProtect: blocked keywords", keywords, None)
+            }
+            AutomodRuleKind::Spam if config.spam => ("RaidProtect: spam", Vec::new(), None),
+            AutomodRuleKind::MentionSpam if config.mention_limit.is_some() => (
+                "RaidProtect: mention spam",
+                Vec::new(),
+                config.mention_limit,
+            ),
+            _ => return None,
+        };
+
+        Some(Self {
+            kind,
+            name: name.to_owned(),
+            enabled: true,
+            keywords,
+            mention_limit,
+            actions: vec!["BlockMessage".to_owned()],
+            exempt_roles: Vec::new(),
+            exempt_channels: Vec::new(),
+        })
+    }
+
+    /// Get the state of a rule returned by Discord.
+    ///
+    /// [`None`] is returned for rules of a kind not managed by RaidProtect.
+    fn from_rule(rule: &NativeRule) -> Option<Self> {
+        let kind = match rule.trigger_type {
+            AutoModerationTriggerType::Keyword => AutomodRuleKind::Keyword,
+            AutoModerationTriggerType::Spam => AutomodRuleKind::Spam,
+            AutoModerationTriggerType::Unknown(MENTION_SPAM_TRIGGER) => {
+                AutomodRuleKind::MentionSpam
+            }
+            _ => return None,
+        };
+
+        let mut keywords = rule
+            .trigger_metadata
+            .keyword_filter
+            .clone()
+            .unwrap_or_default();
+        keywords.sort();
+
+        let mut exempt_roles = rule
+            .exempt_roles
+            .iter()
+            .map(|id| id.get())
+            .collect::<Vec<_>>();
+        exempt_roles.sort_unstable();
+        let mut exempt_channels = rule
+            .exempt_channels
+            .iter()
+            .map(|id| id.get())
+            .collect::<Vec<_>>();
+        exempt_channels.sort_unstable();
+
+        Some(Self {
+            kind,
+            name: rule.name.clone(),
+            enabled: rule.enabled,
+            keywords,
+            mention_limit: rule.trigger_metadata.mention_total_limit,
+            actions: rule
+                .actions
+                .iter()
+                .map(|action| format!("{:?}", action.kind))
+                .collect(),
+            exempt_roles,
+            exempt_channels,
+        })
+    }
+
+    /// Compute the fingerprint of the rule.
+    fn fingerprint(&self) -> String {
+        // Serializing this type cannot fail.
+        let serialized = serde_json::to_vec(self).unwrap_or_default();
+
+        hex::encode(Sha256::digest(serialized))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> AutomodConfig {
+        AutomodConfig {
+            enabled: true,
+            keywords: vec!["b".to_owned(), "a".to_owned()],
+            spam: false,
+            mention_limit: Some(5),
+            rules: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_desired() {
+        let config = config();
+
+        let keyword = RuleState::desired(&config, AutomodRuleKind::Keyword).unwrap();
+        assert_eq!(keyword.keywords, vec!["a", "b"]);
+        assert!(RuleState::desired(&config, AutomodRuleKind::Spam).is_none());
+
+        let mention = RuleState::desired(&config, AutomodRuleKind::MentionSpam).unwrap();
+        assert_eq!(mention.mention_limit, Some(5));
+    }
+
+    #[test]
+    fn test_desired_disabled() {
+        let config = AutomodConfig {
+            enabled: false,
+            ..config()
+        };
+
+        for kind in RULE_KINDS {
+            assert!(RuleState::desired(&config, kind).is_none());
+        }
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let config = config();
+        let keyword = RuleState::desired(&config, AutomodRuleKind::Keyword).unwrap();
+        let mut edited = keyword.clone();
+        edited.keywords.push("c".to_owned());
+
+        assert_eq!(keyword.fingerprint(), keyword.clone().fingerprint());
+        assert_ne!(keyword.fingerprint(), edited.fingerprint());
+    }
+
+    #[test]
+    fn test_native_rule() {
+        let config = config();
+        let desired = RuleState::desired(&config, AutomodRuleKind::MentionSpam).unwrap();
+
+        let body = serde_json::to_value(CreateRule::new(&desired)).unwrap();
+        assert_eq!(body["trigger_type"], MENTION_SPAM_TRIGGER);
+        assert_eq!(body["trigger_metadata"]["mention_total_limit"], 5);
+
+        let rule: NativeRule = serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "guild_id": "2",
+            "name": "RaidProtect: mention spam",
+            "creator_id": "3",
+            "event_type": 1,
+            "trigger_type": MENTION_SPAM_TRIGGER,
+            "trigger_metadata": { "mention_total_limit": 5 },
+            "actions": [{ "type": 1 }],
+            "enabled": true,
+            "exempt_roles": [],
+            "exempt_channels": []
+        }))
+        .unwrap();
+
+        assert_eq!(RuleState::from_rule(&rule), Some(desired));
+    }
+}
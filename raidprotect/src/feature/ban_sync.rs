@@ -0,0 +1,185 @@
+//! Ban synchronization between linked guilds.
+//!
+//! Networks of affiliated guilds can link their guilds with the `/link`
+//! command: a code generated with `/link create` in a guild is used with
+//! `/link join` in another guild (see [`generate_code`]). Codes are single-use
+//! and expire after a few minutes.
+//!
+//! Bans issued with the `sync` option of the `/ban` command are mirrored in
+//! the linked guilds, depending on the [`BanSync`] configuration of each
+//! guild. A [`ScheduledJob::SyncBan`] job is scheduled for each linked guild
+//! (see [`propagate`]), and the mirrored ban is recorded in the modlogs of
+//! the linked guild with a reference to the original ban (see [`apply`]).
+//!
+//! Mirrored bans are never synchronized again, so that bans cannot loop
+//! between linked guilds. Unlinking guilds stops future synchronizations,
+//! but past bans are never reverted.
+//!
+//! [`BanSync`]: raidprotect_model::database::model::BanSync
+
+use raidprotect_model::{
+    cache::model::job::ScheduledJob,
+    database::model::{GuildConfig, Modlog, ModlogType, ModlogUser, SyncOrigin},
+};
+use rand::seq::SliceRandom;
+use time::OffsetDateTime;
+use tracing::{debug, instrument, warn};
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    feature::sanction::{self, Sanction},
+    util::http::is_not_found,
+};
+
+/// Length of the link codes.
+pub const CODE_LENGTH: usize = 10;
+
+/// Characters used in the link codes.
+///
+/// Characters that are easily confused with each other are excluded.
+const CODE_CHARSET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+/// Generate a random link code.
+pub fn generate_code() -> String {
+    let mut rng = rand::thread_rng();
+
+    (0..CODE_LENGTH)
+        .map(|_| *CODE_CHARSET.choose(&mut rng).unwrap() as char)
+        .collect()
+}
+
+/// Normalize a link code entered by a member.
+///
+/// Returns [`None`] if the code is not valid.
+pub fn normalize_code(code: &str) -> Option<String> {
+    let code = code.trim().to_ascii_uppercase();
+    let valid = code.len() == CODE_LENGTH && code.bytes().all(|c| CODE_CHARSET.contains(&c));
+
+    valid.then_some(code)
+}
+
+/// Schedule the synchronization of a ban in the linked guilds.
+///
+/// Nothing is done if the guild does not send its bans, or if the ban has
+/// itself been synchronized from another guild. Errors are logged, the ban
+/// has already been applied in the origin guild.
+#[instrument(skip_all, fields(guild_id = config.id.get()))]
+pub async fn propagate(state: &ClusterState, config: &GuildConfig, modlog: &Modlog) {
+    let case_id = match (modlog.kind, modlog.id, modlog.synced_from) {
+        (ModlogType::Ban, Some(case_id), None) if config.ban_sync.sends() => case_id,
+        _ => return,
+    };
+
+    let links = match state.database.find_guild_links(config.id).await {
+        Ok(links) => links,
+        Err(error) => {
+            warn!(error = ?error, "failed to get linked guilds");
+            return;
+        }
+    };
+
+    let origin = SyncOrigin {
+        guild_id: config.id,
+        case_id,
+    };
+    let now = OffsetDateTime::now_utc();
+
+    for link in links {
+        let job = ScheduledJob::SyncBan {
+            guild_id: link.other(config.id),
+            user: modlog.user.clone(),
+            moderator: modlog.moderator.clone(),
+            reason: modlog.reason.clone(),
+            origin,
+        };
+
+        if let Err(error) = state.cache.schedule_job(&job, now).await {
+            warn!(error = ?error, job = ?job, "failed to schedule ban synchronization");
+        }
+    }
+}
+
+/// Apply a ban synchronized from a linked guild.
+///
+/// This function is called by the scheduler. Nothing is done if the guild no
+/// longer receives bans, if the guilds have been unlinked in the meantime or
+/// if the user is already banned.
+#[instrument(skip(state, user, moderator, reason))]
+pub async fn apply(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    user: &ModlogUser,
+    moderator: &ModlogUser,
+    reason: Option<&str>,
+    origin: SyncOrigin,
+) -> Result<(), anyhow::Error> {
+    let config = database::guild_config(state, guild_id).await?;
+
+    if !config.ban_sync.receives()
+        || !state
+            .database
+            .is_guild_linked(guild_id, origin.guild_id)
+            .await?
+    {
+        debug!("ban synchronization skipped");
+        return Ok(());
+    }
+
+    match state.http.ban(guild_id, user.id).exec().await {
+        Ok(_) => return Ok(()),
+        Err(error) if is_not_found(&error) => {}
+        Err(error) => return Err(error.into()),
+    }
+
+    let pre_emptive = match state.http.guild_member(guild_id, user.id).exec().await {
+        Ok(_) => false,
+        Err(error) if is_not_found(&error) => true,
+        Err(error) => return Err(error.into()),
+    };
+
+    let sanction = Sanction {
+        guild_id,
+        kind: ModlogType::Ban,
+        user: user.clone(),
+        moderator: moderator.clone(),
+        reason: reason.map(ToOwned::to_owned),
+        notes: None,
+        duration: None,
+        pre_emptive,
+        confirmation_skipped: false,
+        shadow: false,
+        sync: false,
+        synced_from: Some(origin),
+    };
+
+    sanction::execute(state, &config, sanction).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_code() {
+        let code = generate_code();
+
+        assert_eq!(code.len(), CODE_LENGTH);
+        assert_eq!(normalize_code(&code), Some(code.clone()));
+        assert_ne!(code, generate_code());
+    }
+
+    #[test]
+    fn test_normalize_code() {
+        assert_eq!(
+            normalize_code(" abcdefgh23 "),
+            Some("ABCDEFGH23".to_owned())
+        );
+        assert_eq!(normalize_code("ABCDEFGH2"), None);
+        assert_eq!(normalize_code("ABCDEFGH10"), None);
+        assert_eq!(normalize_code("ABCD-EFGH2"), None);
+    }
+}
@@ -0,0 +1,223 @@
+//! Bulk ban from a list of user ids.
+//!
+//! After a large raid, moderators may have a list of user ids exported from
+//! another tool. The list is sent as a text file, parsed with
+//! [`parse_user_ids`] and, once confirmed, the users are banned with the
+//! [`bulk`] utility. The confirmation message is updated with the progress and
+//! a summary of the ids that could not be banned. The bulk ban can be
+//! cancelled with a button until it is completed.
+//!
+//! Each ban is recorded in the modlogs, so that a wrong bulk ban can be
+//! reversed with the bulk unban command.
+
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use raidprotect_model::cache::model::interaction::PendingBulkBan;
+use tracing::error;
+use twilight_http::request::AuditLogReason;
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+
+use crate::{
+    cluster::ClusterState,
+    feature::automation,
+    interaction::{
+        embed,
+        response::{InteractionResponder, ProgressMessage},
+    },
+    translations::Lang,
+    util::{
+        bulk::{self, BulkOptions, BulkProgress, CancelToken, ProgressReporter},
+        ratelimit::Priority,
+    },
+};
+
+/// Maximum number of users in a bulk ban.
+pub const MAX_USERS: usize = 1000;
+
+/// Maximum size of a user ids file, in bytes.
+pub const MAX_FILE_SIZE: usize = 128 * 1024;
+
+/// Number of bans between two progress updates.
+const PROGRESS_INTERVAL: usize = 25;
+
+/// Maximum number of bans executed concurrently.
+const MAX_CONCURRENT_BANS: usize = 4;
+
+/// User ids parsed from a file.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ParsedIds {
+    /// Valid user ids, without duplicates.
+    pub ids: Vec<Id<UserMarker>>,
+    /// Entries that are not valid user ids.
+    pub invalid: Vec<String>,
+}
+
+/// Parse a list of user ids.
+///
+/// Ids may be separated by whitespaces, commas, semicolons or pipes, so both
+/// plain text and CSV files are accepted. Comments starting with `#` or `//`
+/// are ignored until the end of the line, as well as quotes and user mentions
+/// delimiters.
+pub fn parse_user_ids(content: &str) -> ParsedIds {
+    let mut parsed = ParsedIds::default();
+    let mut seen = HashSet::new();
+
+    for line in content.lines() {
+        let line = match line.find('#') {
+            Some(index) => &line[..index],
+            None => line,
+        };
+        let line = match line.find("//") {
+            Some(index) => &line[..index],
+            None => line,
+        };
+
+        let entries = line
+            .split(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | '|'))
+            .map(|entry| entry.trim_matches(|c| matches!(c, '"' | '\'' | '<' | '@' | '!' | '>')))
+            .filter(|entry| !entry.is_empty());
+
+        for entry in entries {
+            match entry.parse().ok().and_then(Id::new_checked) {
+                Some(id) => {
+                    if seen.insert(id) {
+                        parsed.ids.push(id);
+                    }
+                }
+                None => parsed.invalid.push(entry.to_owned()),
+            }
+        }
+    }
+
+    parsed
+}
+
+/// Ban all the users of a [`PendingBulkBan`].
+///
+/// The progress is reported by updating the original response of the
+/// interaction, or a new message if the interaction token is about to expire
+/// (see [`ProgressMessage`]).
+pub async fn run(
+    state: ClusterState,
+    pending: PendingBulkBan,
+    responder: InteractionResponder,
+    cancel: CancelToken,
+    lang: Lang,
+) {
+    let mut progress = BanProgress {
+        state: state.clone(),
+        message: ProgressMessage::new(responder),
+        lang,
+    };
+    let reason = match &pending.reason {
+        Some(reason) => reason.clone(),
+        None => lang.bulk_ban_default_reason(pending.author_id),
+    };
+    let options = BulkOptions {
+        concurrency: MAX_CONCURRENT_BANS,
+        progress_interval: PROGRESS_INTERVAL,
+    };
+
+    let guild_id = pending.guild_id;
+    let report = bulk::run(
+        pending.user_ids.iter().copied(),
+        options,
+        &cancel,
+        &mut progress,
+        |user| {
+            let request = ban(&state, guild_id, user, pending.author_id, &reason);
+            state.rate_guard.send(Priority::Background, request)
+        },
+    )
+    .await;
+
+    state.bulk_tasks.remove(pending.interaction_id);
+
+    let embed = embed::bulk_ban::summary(
+        lang,
+        report.succeeded.len(),
+        &report.failed,
+        report.cancelled.len(),
+    );
+    progress.message.finish(&state, embed).await;
+}
+
+/// Progress of a bulk ban, shown in the progress message.
+struct BanProgress {
+    state: ClusterState,
+    message: ProgressMessage,
+    lang: Lang,
+}
+
+#[async_trait]
+impl ProgressReporter for BanProgress {
+    async fn report(&mut self, progress: BulkProgress) {
+        let embed = embed::bulk_ban::progress(self.lang, progress.done, progress.total);
+
+        self.message.update(&self.state, embed).await;
+    }
+}
+
+/// Ban a single user.
+///
+/// The ban is recorded in the modlogs with the author of the bulk ban as
+/// moderator, so that it can be reversed with the bulk unban command.
+async fn ban(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    author_id: Id<UserMarker>,
+    reason: &str,
+) -> Result<(), anyhow::Error> {
+    state
+        .http
+        .create_ban(guild_id, user_id)
+        .reason(reason)?
+        .exec()
+        .await?;
+
+    let user = automation::unknown_user(user_id);
+    let moderator = automation::unknown_user(author_id);
+    if let Err(error) = automation::record_ban(state, guild_id, user, moderator, reason).await {
+        error!(error = ?error, guild = ?guild_id, "failed to record bulk ban");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_user_ids() {
+        let content = "# exported ids\n\
+            123456789012345678, 223456789012345678;323456789012345678\n\
+            \t \"423456789012345678\" | <@!523456789012345678>\n\
+            123456789012345678 // duplicate\n\
+            invalid 0\n";
+
+        let parsed = parse_user_ids(content);
+
+        assert_eq!(
+            parsed.ids,
+            vec![
+                Id::new(123456789012345678),
+                Id::new(223456789012345678),
+                Id::new(323456789012345678),
+                Id::new(423456789012345678),
+                Id::new(523456789012345678),
+            ]
+        );
+        assert_eq!(parsed.invalid, vec!["invalid".to_owned(), "0".to_owned()]);
+    }
+
+    #[test]
+    fn test_parse_empty() {
+        assert_eq!(parse_user_ids("\n  # nothing\n"), ParsedIds::default());
+    }
+}
@@ -0,0 +1,312 @@
+//! Bulk unban of recently banned users.
+//!
+//! After a false positive of the raid detection or a wrong bulk ban, the users
+//! banned by mistake must be unbanned one by one. The `bulk-unban` command
+//! finds the users banned within a recent time window in the modlogs (see
+//! [`banned_users`]) and, once confirmed, unbans them with the [`bulk`]
+//! utility.
+//!
+//! Users that are no longer banned are skipped without failing the bulk unban.
+//! The bulk unban is logged as a single entry referencing the moderation logs
+//! of the bans that have been lifted (see [`unbanned_cases`]), the full list is
+//! attached as a file when it is too long to be shown in the embed.
+//!
+//! The bans applied automatically, such as the bans of the antinuke or of the
+//! [`bulk_ban`] command, are also recorded in the modlogs (see
+//! [`automation::record_ban`]). Only these bans are reversed by default, the
+//! bans of the moderators must be explicitly included.
+//!
+//! [`bulk_ban`]: super::bulk_ban
+//! [`automation::record_ban`]: super::automation::record_ban
+
+use std::{collections::HashSet, sync::Mutex};
+
+use async_trait::async_trait;
+use raidprotect_model::{
+    cache::model::interaction::PendingBulkUnban,
+    database::model::{LogCategory, Modlog, ObjectId},
+};
+use tracing::error;
+use twilight_http::request::AuditLogReason;
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    interaction::{
+        embed,
+        response::{InteractionResponder, ProgressMessage},
+        util::GuildConfigExt,
+    },
+    translations::Lang,
+    util::{
+        bulk::{self, BulkOptions, BulkProgress, CancelToken, ProgressReporter},
+        http::is_not_found,
+        logs::{self, LogMessage},
+        ratelimit::Priority,
+    },
+};
+
+/// Maximum number of users in a bulk unban.
+pub const MAX_USERS: usize = 1000;
+
+/// Number of unbans between two progress updates.
+const PROGRESS_INTERVAL: usize = 25;
+
+/// Maximum number of unbans executed concurrently.
+const MAX_CONCURRENT_UNBANS: usize = 4;
+
+/// Users banned within a time window.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BannedUsers {
+    /// Banned users, without duplicates.
+    pub user_ids: Vec<Id<UserMarker>>,
+    /// Ids of the moderation logs of the bans, with the banned user.
+    pub cases: Vec<(Id<UserMarker>, ObjectId)>,
+}
+
+/// Collect the users banned in a list of ban modlogs.
+///
+/// Users banned several times are only unbanned once, but all their modlogs are
+/// referenced.
+pub fn banned_users(modlogs: &[Modlog]) -> BannedUsers {
+    let mut banned = BannedUsers::default();
+    let mut seen = HashSet::new();
+
+    for modlog in modlogs {
+        if seen.insert(modlog.user.id) {
+            banned.user_ids.push(modlog.user.id);
+        }
+
+        if let Some(id) = modlog.id {
+            banned.cases.push((modlog.user.id, id));
+        }
+    }
+
+    banned
+}
+
+/// Get the moderation logs of the users that have been unbanned.
+///
+/// Cases of the users that have not been unbanned (failed, cancelled or
+/// already unbanned) are not included.
+pub fn unbanned_cases(
+    cases: &[(Id<UserMarker>, ObjectId)],
+    unbanned: &HashSet<Id<UserMarker>>,
+) -> Vec<ObjectId> {
+    cases
+        .iter()
+        .filter(|(user_id, _)| unbanned.contains(user_id))
+        .map(|(_, case_id)| *case_id)
+        .collect()
+}
+
+/// Unban all the users of a [`PendingBulkUnban`].
+///
+/// The progress is reported by updating the original response of the
+/// interaction, or a new message if the interaction token is about to expire
+/// (see [`ProgressMessage`]).
+pub async fn run(
+    state: ClusterState,
+    pending: PendingBulkUnban,
+    responder: InteractionResponder,
+    cancel: CancelToken,
+    lang: Lang,
+) {
+    let mut progress = UnbanProgress {
+        state: state.clone(),
+        message: ProgressMessage::new(responder),
+        lang,
+    };
+    let reason = match &pending.reason {
+        Some(reason) => reason.clone(),
+        None => lang.bulk_unban_default_reason(pending.author_id),
+    };
+    let options = BulkOptions {
+        concurrency: MAX_CONCURRENT_UNBANS,
+        progress_interval: PROGRESS_INTERVAL,
+    };
+
+    let guild_id = pending.guild_id;
+    let skipped = Mutex::new(HashSet::new());
+    let report = bulk::run(
+        pending.user_ids.iter().copied(),
+        options,
+        &cancel,
+        &mut progress,
+        |user| {
+            let request = unban(&state, guild_id, user, &reason, &skipped);
+            state.rate_guard.send(Priority::Background, request)
+        },
+    )
+    .await;
+
+    state.bulk_tasks.remove(pending.interaction_id);
+
+    let skipped = skipped
+        .into_inner()
+        .unwrap_or_else(|error| error.into_inner());
+    let unbanned = report
+        .succeeded
+        .iter()
+        .filter(|user_id| !skipped.contains(*user_id))
+        .copied()
+        .collect::<HashSet<_>>();
+
+    let embed = embed::bulk_unban::summary(
+        lang,
+        unbanned.len(),
+        skipped.len(),
+        &report.failed,
+        report.cancelled.len(),
+    );
+    progress.message.finish(&state, embed).await;
+
+    if !unbanned.is_empty() {
+        if let Err(error) = log(&state, &pending, &unbanned).await {
+            error!(error = ?error, guild = ?guild_id, "failed to log bulk unban");
+        }
+    }
+}
+
+/// Log a completed bulk unban in the logs channel.
+///
+/// Only the moderation logs of the `unbanned` users are referenced.
+async fn log(
+    state: &ClusterState,
+    pending: &PendingBulkUnban,
+    unbanned: &HashSet<Id<UserMarker>>,
+) -> Result<(), anyhow::Error> {
+    let config = database::guild_config(state, pending.guild_id).await?;
+    let case_ids = unbanned_cases(&pending.cases, unbanned);
+    let embed = embed::logs::bulk_unban(
+        config.lang(),
+        pending.author_id,
+        unbanned.len(),
+        &case_ids,
+        pending.reason.as_deref(),
+    )?;
+
+    let mut message = LogMessage::new(embed);
+    if let Some(cases) = embed::logs::bulk_unban_cases(config.lang(), &case_ids) {
+        message = message.attachments(vec![cases]);
+    }
+
+    logs::post_message(state, &config, LogCategory::Sanctions, message).await?;
+
+    Ok(())
+}
+
+/// Progress of a bulk unban, shown in the progress message.
+struct UnbanProgress {
+    state: ClusterState,
+    message: ProgressMessage,
+    lang: Lang,
+}
+
+#[async_trait]
+impl ProgressReporter for UnbanProgress {
+    async fn report(&mut self, progress: BulkProgress) {
+        let embed = embed::bulk_unban::progress(self.lang, progress.done, progress.total);
+
+        self.message.update(&self.state, embed).await;
+    }
+}
+
+/// Unban a single user.
+///
+/// Users that are not banned anymore are added to `skipped` instead of
+/// failing.
+async fn unban(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    reason: &str,
+    skipped: &Mutex<HashSet<Id<UserMarker>>>,
+) -> Result<(), anyhow::Error> {
+    let result = state
+        .http
+        .delete_ban(guild_id, user_id)
+        .reason(reason)?
+        .exec()
+        .await;
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(error) if is_not_found(&error) => {
+            if let Ok(mut skipped) = skipped.lock() {
+                skipped.insert(user_id);
+            }
+
+            Ok(())
+        }
+        Err(error) => Err(error.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use raidprotect_model::database::model::ModlogType;
+
+    use super::*;
+    use crate::test_utils::fixture;
+
+    fn modlog(id: Option<ObjectId>, user: u64) -> Modlog {
+        let user = fixture::modlog_user(user, "user");
+
+        Modlog {
+            id,
+            ..fixture::modlog(ModlogType::Ban)
+                .user(user.clone())
+                .moderator(user)
+                .build()
+        }
+    }
+
+    #[test]
+    fn test_banned_users() {
+        let ids = [ObjectId::new(), ObjectId::new(), ObjectId::new()];
+        let modlogs = [
+            modlog(Some(ids[0]), 10),
+            modlog(Some(ids[1]), 11),
+            modlog(Some(ids[2]), 10),
+            modlog(None, 12),
+        ];
+
+        let banned = banned_users(&modlogs);
+
+        assert_eq!(banned.user_ids, vec![Id::new(10), Id::new(11), Id::new(12)]);
+        assert_eq!(
+            banned.cases,
+            vec![
+                (Id::new(10), ids[0]),
+                (Id::new(11), ids[1]),
+                (Id::new(10), ids[2]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unbanned_cases() {
+        let ids = [ObjectId::new(), ObjectId::new(), ObjectId::new()];
+        let cases = [
+            (Id::new(10), ids[0]),
+            (Id::new(11), ids[1]),
+            (Id::new(10), ids[2]),
+        ];
+
+        // User 11 failed, was cancelled or was already unbanned.
+        let unbanned = HashSet::from([Id::new(10), Id::new(12)]);
+
+        assert_eq!(unbanned_cases(&cases, &unbanned), vec![ids[0], ids[2]]);
+        assert!(unbanned_cases(&cases, &HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn test_banned_users_empty() {
+        assert_eq!(banned_users(&[]), BannedUsers::default());
+    }
+}
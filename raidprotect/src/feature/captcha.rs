@@ -1,8 +1,32 @@
 //! Captcha feature.
+//!
+//! Rendering captcha images is CPU-heavy, and a raid can trigger thousands of
+//! verifications in a few minutes. Renders are bounded by a [`RenderGuard`],
+//! which limits the number of concurrent renders globally and for each guild,
+//! and by a render budget of [`RENDER_BUDGET`] images per guild and per
+//! [`RENDER_BUDGET_WINDOW`].
+//!
+//! When the budget is exhausted or no render slot is available in time, the
+//! member is asked to solve an arithmetic question instead (see
+//! [`ChallengeKind`]). Render latency is recorded in the
+//! `raidprotect_captcha_render_seconds` metric, and fallbacks in the
+//! `raidprotect_captcha_fallbacks_total` metric.
 
-use std::time::Duration as StdDuration;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration as StdDuration, Instant},
+};
 
+use raidprotect_captcha::generate_captcha_png;
+pub use raidprotect_model::cache::model::interaction::ChallengeKind;
+use rand::Rng;
 use time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::{debug, instrument};
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::cluster::ClusterState;
 
 /// Default length of the generated captcha code.
 pub const DEFAULT_LENGTH: usize = 5;
@@ -15,3 +39,291 @@ pub const KICK_AFTER: StdDuration = StdDuration::from_secs(10);
 
 /// Maximum number of regenerations of the captcha code.
 pub const MAX_RETRY: u8 = 2;
+
+/// Maximum number of images rendered by a guild during the budget window.
+pub const RENDER_BUDGET: u32 = 100;
+
+/// Duration of the render budget window.
+pub const RENDER_BUDGET_WINDOW: Duration = Duration::minutes(1);
+
+/// Maximum number of concurrent renders.
+pub const MAX_RENDERS: usize = 8;
+
+/// Maximum number of concurrent renders for a single guild.
+pub const MAX_GUILD_RENDERS: usize = 2;
+
+/// Maximum duration to wait for a render slot.
+///
+/// Interactions must be answered within 3 seconds, so the member gets an
+/// arithmetic question instead of waiting longer.
+const RENDER_WAIT: StdDuration = StdDuration::from_secs(1);
+
+/// Render a captcha image, if the guild render budget allows it.
+///
+/// Returns [`None`] if the render budget of the guild is exhausted or if no
+/// render slot is available in time, in which case an arithmetic challenge
+/// should be used.
+#[instrument(skip(state, code))]
+pub async fn render(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    code: &str,
+) -> Result<Option<Vec<u8>>, anyhow::Error> {
+    let key = format!("captcha-render:{guild_id}");
+
+    if !state
+        .cache
+        .rate_limit(&key, RENDER_BUDGET, RENDER_BUDGET_WINDOW)
+        .await?
+    {
+        debug!("captcha render budget exhausted");
+        metrics::increment_counter!("raidprotect_captcha_fallbacks_total", "reason" => "budget");
+
+        return Ok(None);
+    }
+
+    let _permit = match state.captcha_renders.acquire(guild_id, RENDER_WAIT).await {
+        Some(permit) => permit,
+        None => {
+            debug!("no captcha render slot available");
+            metrics::increment_counter!("raidprotect_captcha_fallbacks_total", "reason" => "busy");
+
+            return Ok(None);
+        }
+    };
+
+    let start = Instant::now();
+    let code = code.to_owned();
+    let image = tokio::task::spawn_blocking(move || generate_captcha_png(&code)).await??;
+
+    metrics::histogram!(
+        "raidprotect_captcha_render_seconds",
+        start.elapsed().as_secs_f64()
+    );
+
+    Ok(Some(image))
+}
+
+/// Generate an arithmetic challenge.
+///
+/// Returns the question to display and the expected answer.
+pub fn arithmetic_challenge() -> (String, String) {
+    let mut rng = rand::thread_rng();
+    let (a, b) = (rng.gen_range(2..=20), rng.gen_range(2..=20));
+
+    match rng.gen_bool(0.5) {
+        true => (format!("{a} + {b}"), (a + b).to_string()),
+        false => {
+            let (a, b) = (a.max(b), a.min(b));
+            (format!("{a} - {b}"), (a - b).to_string())
+        }
+    }
+}
+
+/// Guard limiting the number of concurrent captcha renders.
+///
+/// A render holds both a global permit and a permit of its guild, so that a
+/// single guild cannot use all the render slots.
+#[derive(Debug, Clone)]
+pub struct RenderGuard {
+    inner: Arc<RenderGuardInner>,
+}
+
+#[derive(Debug)]
+struct RenderGuardInner {
+    /// Global render slots.
+    global: Arc<Semaphore>,
+    /// Render slots of each guild with a render in progress.
+    guilds: Mutex<HashMap<Id<GuildMarker>, Arc<Semaphore>>>,
+    /// Maximum number of concurrent renders for a single guild.
+    per_guild: usize,
+}
+
+impl RenderGuard {
+    /// Initialize a new [`RenderGuard`] with the given limits.
+    pub fn new(global: usize, per_guild: usize) -> Self {
+        Self {
+            inner: Arc::new(RenderGuardInner {
+                global: Arc::new(Semaphore::new(global)),
+                guilds: Mutex::new(HashMap::new()),
+                per_guild,
+            }),
+        }
+    }
+
+    /// Wait for a render slot.
+    ///
+    /// Returns [`None`] if no slot is available before the `timeout`.
+    pub async fn acquire(
+        &self,
+        guild_id: Id<GuildMarker>,
+        timeout: StdDuration,
+    ) -> Option<RenderPermit> {
+        // The guild semaphore is cloned while the map is locked, so that it is
+        // not removed while the permit is being acquired.
+        let guild = self
+            .guilds()
+            .entry(guild_id)
+            .or_insert_with(|| Arc::new(Semaphore::new(self.inner.per_guild)))
+            .clone();
+
+        let permits = {
+            let (guild, global) = (guild.clone(), self.inner.global.clone());
+
+            async move {
+                let guild = guild.acquire_owned().await.ok()?;
+                let global = global.acquire_owned().await.ok()?;
+
+                Some((guild, global))
+            }
+        };
+
+        // The permit is created before waiting, so that the guild semaphore is
+        // removed from the map if the timeout is reached.
+        let mut permit = RenderPermit {
+            guard: self.clone(),
+            guild_id,
+            semaphore: guild,
+            permits: None,
+        };
+
+        match tokio::time::timeout(timeout, permits).await {
+            Ok(Some(permits)) => {
+                permit.permits = Some(permits);
+                Some(permit)
+            }
+            _ => None,
+        }
+    }
+
+    fn guilds(&self) -> MutexGuard<'_, HashMap<Id<GuildMarker>, Arc<Semaphore>>> {
+        self.inner
+            .guilds
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+impl Default for RenderGuard {
+    fn default() -> Self {
+        Self::new(MAX_RENDERS, MAX_GUILD_RENDERS)
+    }
+}
+
+/// Permit held while a captcha is rendered.
+#[derive(Debug)]
+pub struct RenderPermit {
+    guard: RenderGuard,
+    guild_id: Id<GuildMarker>,
+    semaphore: Arc<Semaphore>,
+    permits: Option<(OwnedSemaphorePermit, OwnedSemaphorePermit)>,
+}
+
+impl Drop for RenderPermit {
+    fn drop(&mut self) {
+        // Release the permits before checking whether the guild semaphore
+        // is still used.
+        self.permits.take();
+
+        let mut guilds = self.guard.guilds();
+
+        // The semaphore is referenced by the map and this permit only.
+        if Arc::strong_count(&self.semaphore) == 2 {
+            guilds.remove(&self.guild_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// Concurrent renders tracker.
+    #[derive(Default)]
+    struct Tracker {
+        current: AtomicUsize,
+        max: AtomicUsize,
+    }
+
+    impl Tracker {
+        async fn render(&self) {
+            let current = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max.fetch_max(current, Ordering::SeqCst);
+
+            tokio::time::sleep(StdDuration::from_millis(5)).await;
+            self.current.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_render_guard_bounds() {
+        let guard = RenderGuard::new(4, 2);
+        let global = Arc::new(Tracker::default());
+        let guilds = (0..4)
+            .map(|_| Arc::new(Tracker::default()))
+            .collect::<Vec<_>>();
+
+        let tasks = (0..200)
+            .map(|n| {
+                let (guard, global) = (guard.clone(), global.clone());
+                let guild = guilds[n % guilds.len()].clone();
+                let guild_id = Id::new(n as u64 % guilds.len() as u64 + 1);
+
+                tokio::spawn(async move {
+                    let _permit = guard
+                        .acquire(guild_id, StdDuration::from_secs(60))
+                        .await
+                        .unwrap();
+
+                    tokio::join!(global.render(), guild.render());
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert!(global.max.load(Ordering::SeqCst) <= 4);
+        for guild in guilds {
+            assert!(guild.max.load(Ordering::SeqCst) <= 2);
+        }
+        assert!(guard.guilds().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_render_guard_timeout() {
+        let guard = RenderGuard::new(1, 1);
+        let timeout = StdDuration::from_millis(20);
+
+        let permit = guard.acquire(Id::new(1), timeout).await.unwrap();
+        assert!(guard.acquire(Id::new(1), timeout).await.is_none());
+        assert!(guard.acquire(Id::new(2), timeout).await.is_none());
+        assert_eq!(guard.guilds().len(), 1);
+
+        drop(permit);
+        assert!(guard.guilds().is_empty());
+        assert!(guard.acquire(Id::new(2), timeout).await.is_some());
+    }
+
+    #[test]
+    fn test_arithmetic_challenge() {
+        for _ in 0..100 {
+            let (question, answer) = arithmetic_challenge();
+            let (a, operator, b) = match question.split(' ').collect::<Vec<_>>()[..] {
+                [a, operator, b] => (a, operator, b),
+                _ => panic!("invalid question: {question}"),
+            };
+            let (a, b) = (a.parse::<u32>().unwrap(), b.parse::<u32>().unwrap());
+
+            let expected = match operator {
+                "+" => a + b,
+                "-" => a - b,
+                _ => panic!("invalid operator: {operator}"),
+            };
+            assert_eq!(answer, expected.to_string());
+        }
+    }
+}
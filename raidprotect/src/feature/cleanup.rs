@@ -0,0 +1,280 @@
+//! Messages cleanup.
+//!
+//! The recent messages of a user can be deleted after a sanction or with the
+//! `/cleanup` command. Message ids are retrieved from the author index of the
+//! message cache (see [`IndexedMessage`]), which is kept for
+//! [`AUTHOR_INDEX_RETENTION`].
+//!
+//! Messages are grouped by channel and deleted with the bulk delete endpoint,
+//! using the [`bulk`] utility to bound the number of concurrent requests.
+//! Messages older than two weeks cannot be bulk deleted and are skipped.
+
+use std::collections::BTreeMap;
+
+use raidprotect_model::{
+    cache::{
+        discord::http::CacheHttp,
+        model::message::{IndexedMessage, AUTHOR_INDEX_RETENTION},
+    },
+    database::model::StatsEvent,
+};
+use time::{Duration, OffsetDateTime};
+use tracing::{error, instrument, warn};
+use twilight_model::{
+    channel::embed::Embed,
+    id::{
+        marker::{ChannelMarker, GuildMarker, MessageMarker, UserMarker},
+        Id,
+    },
+};
+
+use crate::{
+    cluster::ClusterState,
+    feature,
+    interaction::{embed, response::InteractionResponder},
+    translations::Lang,
+    util::{
+        bulk::{self, BulkOptions, CancelToken},
+        ratelimit::Priority,
+    },
+};
+
+/// Maximum number of delete requests sent concurrently.
+const MAX_CONCURRENT_REQUESTS: usize = 4;
+
+/// Maximum number of messages deleted with a single request.
+const BULK_DELETE_LIMIT: usize = 100;
+
+/// Maximum age of messages that can be bulk deleted.
+///
+/// Discord limits bulk deletion to messages younger than two weeks, a margin
+/// is kept to avoid failing requests.
+const BULK_DELETE_MAX_AGE: Duration = Duration::minutes(14 * 24 * 60 - 5);
+
+/// Result of a messages cleanup.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CleanupReport {
+    /// Number of deleted messages in each channel.
+    pub channels: Vec<(Id<ChannelMarker>, usize)>,
+    /// Number of messages skipped because they are too old.
+    pub skipped: usize,
+    /// Number of messages that could not be deleted.
+    pub failed: usize,
+}
+
+impl CleanupReport {
+    /// Total number of deleted messages.
+    pub fn deleted(&self) -> usize {
+        self.channels.iter().map(|(_, count)| count).sum()
+    }
+}
+
+/// Run a messages cleanup in the background.
+///
+/// The interaction response is updated with the result of the cleanup. If a
+/// sanction `embed` is provided, the result is appended to it.
+pub async fn run(
+    state: ClusterState,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    period: Duration,
+    responder: InteractionResponder,
+    lang: Lang,
+    sanction: Option<Embed>,
+) {
+    let result = cleanup_messages(&state, guild_id, user_id, period).await;
+
+    if let Err(error) = &result {
+        error!(error = ?error, guild = ?guild_id, "failed to clean up messages");
+    }
+
+    let embed = match (result, sanction) {
+        (Ok(report), Some(sanction)) => embed::cleanup::with_report(sanction, lang, &report),
+        (Ok(report), None) => embed::cleanup::report(lang, &report),
+        (Err(_), Some(sanction)) => embed::cleanup::with_error(sanction, lang),
+        (Err(_), None) => embed::cleanup::error(lang),
+    };
+
+    if let Err(error) = responder.update_response(&state, embed).await {
+        warn!(error = ?error, "failed to send cleanup report");
+    }
+}
+
+/// Delete the messages sent by a user during the given period.
+///
+/// The period is limited to [`AUTHOR_INDEX_RETENTION`].
+#[instrument(skip(state))]
+pub async fn cleanup_messages(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    period: Duration,
+) -> Result<CleanupReport, anyhow::Error> {
+    let now = OffsetDateTime::now_utc();
+    let since = now - period.min(AUTHOR_INDEX_RETENTION);
+    let messages = state
+        .cache
+        .author_messages(guild_id, user_id, since)
+        .await?;
+
+    let (channels, skipped) = group_messages(messages, now);
+    let chunks = channels.into_iter().flat_map(|(channel_id, messages)| {
+        messages
+            .chunks(BULK_DELETE_LIMIT)
+            .map(|chunk| (channel_id, chunk.to_vec()))
+            .collect::<Vec<_>>()
+    });
+
+    let cache_http = state.cache_http(guild_id);
+    let options = BulkOptions {
+        concurrency: MAX_CONCURRENT_REQUESTS,
+        ..Default::default()
+    };
+    let result = bulk::run(
+        chunks,
+        options,
+        &CancelToken::new(),
+        &mut (),
+        |(channel_id, messages)| {
+            let cache_http = &cache_http;
+
+            state.rate_guard.send(Priority::Background, async move {
+                let ids = messages.iter().map(|m| m.message_id).collect::<Vec<_>>();
+                delete_chunk(cache_http, channel_id, &ids).await
+            })
+        },
+    )
+    .await;
+
+    let mut deleted = BTreeMap::new();
+    for (channel_id, messages) in result.succeeded {
+        state
+            .cache
+            .remove_author_messages(guild_id, user_id, &messages)
+            .await?;
+        *deleted.entry(channel_id).or_insert(0) += messages.len();
+    }
+
+    let failed: usize = result
+        .failed
+        .iter()
+        .map(|(_, messages)| messages.len())
+        .sum();
+    if failed > 0 {
+        warn!(guild = ?guild_id, failed, "failed to delete messages");
+    }
+
+    let report = CleanupReport {
+        channels: deleted.into_iter().collect(),
+        skipped,
+        failed,
+    };
+
+    if report.deleted() > 0 {
+        let event = StatsEvent::MessagesDeleted(report.deleted() as u64);
+        feature::stats::record(state, guild_id, event).await;
+    }
+
+    Ok(report)
+}
+
+/// Group messages by channel, skipping the messages that are too old to be
+/// bulk deleted.
+///
+/// The number of skipped messages is returned.
+fn group_messages(
+    messages: Vec<IndexedMessage>,
+    now: OffsetDateTime,
+) -> (BTreeMap<Id<ChannelMarker>, Vec<IndexedMessage>>, usize) {
+    let oldest = ((now - BULK_DELETE_MAX_AGE).unix_timestamp_nanos() / 1_000_000) as i64;
+    let mut channels: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    let mut skipped = 0;
+
+    for message in messages {
+        if message.timestamp < oldest {
+            skipped += 1;
+            continue;
+        }
+
+        channels
+            .entry(message.channel_id)
+            .or_default()
+            .push(message);
+    }
+
+    (channels, skipped)
+}
+
+/// Delete a chunk of messages.
+///
+/// The bulk delete endpoint requires at least two messages.
+async fn delete_chunk(
+    cache_http: &CacheHttp<'_>,
+    channel_id: Id<ChannelMarker>,
+    ids: &[Id<MessageMarker>],
+) -> Result<(), anyhow::Error> {
+    match ids {
+        [] => {}
+        [id] => {
+            cache_http
+                .delete_message(channel_id, *id)
+                .await?
+                .exec()
+                .await?;
+        }
+        ids => {
+            cache_http
+                .delete_messages(channel_id, ids)
+                .await?
+                .exec()
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(channel: u64, id: u64, timestamp: i64) -> IndexedMessage {
+        IndexedMessage {
+            channel_id: Id::new(channel),
+            message_id: Id::new(id),
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_group_messages() {
+        let now = OffsetDateTime::now_utc();
+        let recent = (now.unix_timestamp_nanos() / 1_000_000) as i64;
+        let old = recent - Duration::days(15).whole_milliseconds() as i64;
+
+        let (channels, skipped) = group_messages(
+            vec![
+                message(1, 1, recent),
+                message(2, 2, recent),
+                message(1, 3, recent),
+                message(1, 4, old),
+            ],
+            now,
+        );
+
+        assert_eq!(skipped, 1);
+        assert_eq!(channels.len(), 2);
+        assert_eq!(channels[&Id::new(1)].len(), 2);
+        assert_eq!(channels[&Id::new(2)].len(), 1);
+    }
+
+    #[test]
+    fn test_report_deleted() {
+        let report = CleanupReport {
+            channels: vec![(Id::new(1), 3), (Id::new(2), 2)],
+            ..Default::default()
+        };
+
+        assert_eq!(report.deleted(), 5);
+    }
+}
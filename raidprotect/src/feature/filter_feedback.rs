@@ -0,0 +1,165 @@
+//! False positives of the message filters.
+//!
+//! The logs of messages deleted by the link filter, the pattern rules and the
+//! first message filter have a "False positive" button. Moderators use it to
+//! mark a deletion as a false positive, which is stored as a
+//! [`FilterFeedback`] in the database and counted in the guild statistics.
+//!
+//! Deleted messages are identified by the [`fingerprint`] of their content.
+//! Messages with the same fingerprint as a false positive reported less than
+//! [`SUPPRESSION_DURATION`] ago are ignored by these filters (see
+//! [`is_suppressed`]).
+//!
+//! The button only stores the filter and the fingerprint in its custom id, so
+//! it keeps working after a restart of the bot.
+
+use raidprotect_model::database::model::{FilterFeedback, StatsEvent, StatsFilter};
+use sha2::{Digest, Sha256};
+use time::{Duration, OffsetDateTime};
+use tracing::warn;
+use twilight_model::{
+    application::component::{button::ButtonStyle, ActionRow, Button, Component},
+    id::{
+        marker::{GuildMarker, UserMarker},
+        Id,
+    },
+};
+
+use crate::{cluster::ClusterState, feature, interaction::util::CustomId, translations::Lang};
+
+/// Duration during which the fingerprint of a false positive is ignored by
+/// the filters.
+pub const SUPPRESSION_DURATION: Duration = Duration::days(30);
+
+/// Number of bytes of the hash kept in a fingerprint.
+const FINGERPRINT_BYTES: usize = 16;
+
+/// Compute the fingerprint of a message content.
+///
+/// The content is normalized (case and whitespace) before being hashed, so
+/// that trivial variations of a message share the same fingerprint.
+pub fn fingerprint(content: &str) -> String {
+    let normalized = content
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    hex::encode(&Sha256::digest(normalized.as_bytes())[..FINGERPRINT_BYTES])
+}
+
+/// Whether a message content has recently been marked as a false positive.
+///
+/// Errors are logged and the content is considered as not suppressed, so
+/// that the filters keep working if the database is unavailable.
+pub async fn is_suppressed(state: &ClusterState, guild_id: Id<GuildMarker>, content: &str) -> bool {
+    let after = OffsetDateTime::now_utc() - SUPPRESSION_DURATION;
+
+    match state
+        .database
+        .is_false_positive(guild_id, &fingerprint(content), after)
+        .await
+    {
+        Ok(suppressed) => suppressed,
+        Err(error) => {
+            warn!(error = ?error, guild = ?guild_id, "failed to check false positives");
+            false
+        }
+    }
+}
+
+/// Mark a deleted message as a false positive.
+pub async fn submit(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    filter: StatsFilter,
+    fingerprint: String,
+    reporter: Id<UserMarker>,
+) -> Result<(), anyhow::Error> {
+    let feedback = FilterFeedback {
+        id: None,
+        guild_id,
+        filter,
+        fingerprint,
+        reporter,
+        date: OffsetDateTime::now_utc(),
+    };
+
+    state.database.create_filter_feedback(&feedback).await?;
+    feature::stats::record(state, guild_id, StatsEvent::FalsePositive(filter)).await;
+
+    Ok(())
+}
+
+/// Button attached to the logs of deleted messages.
+pub fn components(lang: Lang, filter: StatsFilter, fingerprint: &str) -> Vec<Component> {
+    let id = format!("{}:{}", filter.id(), fingerprint);
+
+    vec![Component::ActionRow(ActionRow {
+        components: vec![Component::Button(Button {
+            custom_id: Some(CustomId::new("filter-feedback", id).to_string()),
+            disabled: false,
+            emoji: None,
+            label: Some(lang.filter_feedback_button().to_owned()),
+            style: ButtonStyle::Secondary,
+            url: None,
+        })],
+    })]
+}
+
+/// Parse the id of the button created with [`components`].
+pub fn parse_id(id: &str) -> Option<(StatsFilter, &str)> {
+    let (filter, fingerprint) = id.split_once(':')?;
+    let filter = StatsFilter::from_id(filter)?;
+
+    let valid = fingerprint.len() == FINGERPRINT_BYTES * 2
+        && fingerprint.bytes().all(|b| b.is_ascii_hexdigit());
+
+    valid.then_some((filter, fingerprint))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint() {
+        let fingerprint = fingerprint("Free  Nitro\nhttps://example.com");
+
+        assert_eq!(fingerprint.len(), FINGERPRINT_BYTES * 2);
+        assert_eq!(
+            fingerprint,
+            super::fingerprint("free nitro https://example.com")
+        );
+        assert_ne!(fingerprint, super::fingerprint("free nitro"));
+    }
+
+    #[test]
+    fn test_parse_id() {
+        let fingerprint = fingerprint("message");
+        let id = format!("link:{fingerprint}");
+
+        assert_eq!(
+            parse_id(&id),
+            Some((StatsFilter::Link, fingerprint.as_str()))
+        );
+        assert_eq!(parse_id("unknown:0123"), None);
+        assert_eq!(parse_id("link:not-a-fingerprint"), None);
+        assert_eq!(parse_id("link"), None);
+    }
+
+    #[test]
+    fn test_components_custom_id() {
+        let components = components(Lang::DEFAULT, StatsFilter::Pattern, &fingerprint("a"));
+
+        match &components[0] {
+            Component::ActionRow(row) => match &row.components[0] {
+                Component::Button(button) => {
+                    assert!(button.custom_id.as_ref().unwrap().len() <= 100)
+                }
+                _ => panic!("expected button"),
+            },
+            _ => panic!("expected action row"),
+        }
+    }
+}
@@ -0,0 +1,819 @@
+//! Channel lockdown.
+//!
+//! During a raid, moderators can lock every channel of the guild with the
+//! `/lockdown` command. The `@everyone` role is denied the
+//! [`LOCKED_PERMISSIONS`] in each text, forum and voice channel, and its
+//! previous permission overwrite is saved in a [`LockdownState`] to be
+//! restored when the lockdown is lifted.
+//!
+//! ## Engagement
+//! Locking a large guild takes a while, so channels are processed by batches
+//! of [`BATCH_SIZE`] (see [`engage`]). The snapshot of each batch is saved
+//! before its channels are modified, and the progress is reported after each
+//! batch. The engagement runs in the background and can be cancelled from a
+//! button, in which case the channels already locked are restored.
+//!
+//! A [`DistributedLock`] is held during the whole engagement (see [`lock`]),
+//! so that a lockdown cannot be engaged twice, even from several instances.
+//!
+//! ## Interrupted engagements
+//! If the bot stops during an engagement, the saved state is not marked as
+//! completed and the lock expires. Interrupted engagements are detected when
+//! the guild is received again from the gateway, and rolled back with the
+//! partial snapshot (see [`recover`]). Restoring a channel that was not
+//! modified yet is harmless.
+
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use raidprotect_model::{
+    cache::{
+        discord::{CachedChannel, CachedGuild},
+        model::{
+            lock::DistributedLock,
+            lockdown::{ChannelSnapshot, EveryoneOverwrite, LockdownState},
+        },
+    },
+    database::model::LogCategory,
+    store::CacheStore,
+};
+use tokio::time::Instant;
+use tracing::{error, warn};
+use twilight_model::{
+    channel::{permission_overwrite::PermissionOverwriteType, ChannelType},
+    guild::Permissions,
+    http::permission_overwrite::{
+        PermissionOverwrite as HttpPermissionOverwrite,
+        PermissionOverwriteType as HttpPermissionOverwriteType,
+    },
+    id::{
+        marker::{ChannelMarker, GuildMarker},
+        Id,
+    },
+};
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    interaction::{
+        embed,
+        response::{InteractionResponder, ProgressMessage},
+        util::GuildConfigExt,
+    },
+    translations::Lang,
+    util::{
+        bulk::{self, BulkOptions, BulkProgress, BulkReport, CancelToken, ProgressReporter},
+        logs,
+        ratelimit::Priority,
+    },
+};
+
+/// Permissions denied to the `@everyone` role in locked channels.
+pub const LOCKED_PERMISSIONS: Permissions = Permissions::SEND_MESSAGES
+    .union(Permissions::SEND_MESSAGES_IN_THREADS)
+    .union(Permissions::CREATE_PUBLIC_THREADS)
+    .union(Permissions::CREATE_PRIVATE_THREADS)
+    .union(Permissions::ADD_REACTIONS)
+    .union(Permissions::CONNECT);
+
+/// Number of channels locked between two saves of the snapshot.
+pub const BATCH_SIZE: usize = 50;
+
+/// Minimum delay between two updates of the progress message.
+const PROGRESS_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Maximum number of channels updated concurrently.
+const MAX_CONCURRENT_UPDATES: usize = 4;
+
+/// Expiration of the engagement lock, in case the instance holding it
+/// crashes.
+const LOCK_TTL: time::Duration = time::Duration::seconds(30);
+
+/// Types of the channels locked by a lockdown.
+const LOCKED_CHANNEL_TYPES: [ChannelType; 5] = [
+    ChannelType::GuildText,
+    ChannelType::GuildNews,
+    ChannelType::GuildForum,
+    ChannelType::GuildVoice,
+    ChannelType::GuildStageVoice,
+];
+
+/// Update the permissions of the `@everyone` role in channels.
+///
+/// This trait allows running [`engage`] and [`rollback`] against a mocked
+/// channel list in tests.
+#[async_trait]
+pub trait EveryonePermissions: Send + Sync {
+    /// Set the permission overwrite of the `@everyone` role in a channel.
+    ///
+    /// The overwrite is deleted if `overwrite` is [`None`].
+    async fn set(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        overwrite: Option<EveryoneOverwrite>,
+    ) -> Result<(), anyhow::Error>;
+}
+
+/// [`EveryonePermissions`] implementation using the Discord API.
+struct HttpPermissions<'a> {
+    state: &'a ClusterState,
+    guild_id: Id<GuildMarker>,
+}
+
+#[async_trait]
+impl EveryonePermissions for HttpPermissions<'_> {
+    async fn set(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        overwrite: Option<EveryoneOverwrite>,
+    ) -> Result<(), anyhow::Error> {
+        let request = async {
+            match overwrite {
+                Some(overwrite) => {
+                    let overwrite = HttpPermissionOverwrite {
+                        id: self.guild_id.cast(),
+                        kind: HttpPermissionOverwriteType::Role,
+                        allow: Some(overwrite.allow),
+                        deny: Some(overwrite.deny),
+                    };
+
+                    self.state
+                        .cache_http(self.guild_id)
+                        .update_channel_permission(channel_id, &overwrite)
+                        .await?
+                        .exec()
+                        .await?;
+                }
+                None => {
+                    self.state
+                        .http
+                        .delete_channel_permission(channel_id)
+                        .role(self.guild_id.cast())
+                        .exec()
+                        .await?;
+                }
+            }
+
+            Ok(())
+        };
+
+        self.state
+            .rate_guard
+            .send(Priority::Background, request)
+            .await
+    }
+}
+
+/// Outcome of a lockdown engagement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngageOutcome {
+    /// Every channel has been processed.
+    Completed {
+        /// Number of locked channels.
+        locked: usize,
+        /// Number of channels that could not be locked.
+        failed: usize,
+    },
+    /// The engagement has been cancelled and the locked channels restored.
+    Cancelled {
+        /// Number of restored channels.
+        restored: usize,
+        /// Number of channels that could not be restored.
+        failed: usize,
+    },
+}
+
+/// Get the channels of a guild to lock, with their current overwrite.
+pub async fn targets(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+) -> Result<Vec<ChannelSnapshot>, anyhow::Error> {
+    let guild = match state.cache.get::<CachedGuild>(&guild_id).await? {
+        Some(guild) => guild,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut targets = Vec::with_capacity(guild.channels.len());
+    for channel_id in guild.channels {
+        if let Some(channel) = state.cache.get::<CachedChannel>(&channel_id).await? {
+            targets.extend(snapshot(&channel));
+        }
+    }
+
+    Ok(targets)
+}
+
+/// Get the snapshot of a channel, if the channel can be locked.
+fn snapshot(channel: &CachedChannel) -> Option<ChannelSnapshot> {
+    if !LOCKED_CHANNEL_TYPES.contains(&channel.kind) {
+        return None;
+    }
+
+    let everyone = channel.guild_id.cast();
+    let overwrite = channel
+        .permission_overwrites
+        .iter()
+        .flatten()
+        .find(|overwrite| {
+            overwrite.kind == PermissionOverwriteType::Role && overwrite.id == everyone
+        })
+        .map(|overwrite| EveryoneOverwrite {
+            allow: overwrite.allow,
+            deny: overwrite.deny,
+        });
+
+    Some(ChannelSnapshot {
+        channel_id: channel.id,
+        overwrite,
+    })
+}
+
+/// Get the overwrite applied to a locked channel.
+///
+/// The other permissions of the previous overwrite are kept.
+fn locked(snapshot: &ChannelSnapshot) -> EveryoneOverwrite {
+    let previous = snapshot.overwrite.unwrap_or(EveryoneOverwrite {
+        allow: Permissions::empty(),
+        deny: Permissions::empty(),
+    });
+
+    EveryoneOverwrite {
+        allow: previous.allow - LOCKED_PERMISSIONS,
+        deny: previous.deny | LOCKED_PERMISSIONS,
+    }
+}
+
+/// Lock the `targets` channels.
+///
+/// Channels are locked by batches of [`BATCH_SIZE`]. The snapshot of a batch
+/// is saved in the `store` before its channels are modified, and the channels
+/// that could not be locked are removed from it afterwards. The progress is
+/// reported after each batch.
+///
+/// If the engagement is cancelled, the channels already locked are restored
+/// and the state is deleted. Otherwise, the state is marked as completed.
+pub async fn engage(
+    permissions: &impl EveryonePermissions,
+    store: &impl CacheStore,
+    mut lockdown: LockdownState,
+    targets: Vec<ChannelSnapshot>,
+    cancel: &CancelToken,
+    reporter: &mut impl ProgressReporter,
+) -> Result<EngageOutcome, anyhow::Error> {
+    let total = targets.len();
+    let options = BulkOptions {
+        concurrency: MAX_CONCURRENT_UPDATES,
+        ..Default::default()
+    };
+    let mut failed = 0;
+
+    for batch in targets.chunks(BATCH_SIZE) {
+        if cancel.is_cancelled() {
+            break;
+        }
+
+        lockdown.channels.extend_from_slice(batch);
+        store.set(&lockdown).await?;
+
+        let report = bulk::run(
+            batch.iter().copied(),
+            options,
+            cancel,
+            &mut (),
+            |snapshot| permissions.set(snapshot.channel_id, Some(locked(&snapshot))),
+        )
+        .await;
+
+        // Channels that have not been modified are removed from the snapshot.
+        let unchanged = report
+            .failed
+            .iter()
+            .chain(&report.cancelled)
+            .map(|snapshot| snapshot.channel_id)
+            .collect::<HashSet<_>>();
+        lockdown
+            .channels
+            .retain(|snapshot| !unchanged.contains(&snapshot.channel_id));
+        failed += report.failed.len();
+
+        let done = lockdown.channels.len() + failed;
+        reporter.report(BulkProgress { done, total }).await;
+    }
+
+    if cancel.is_cancelled() {
+        let report = rollback(permissions, store, &lockdown).await?;
+
+        return Ok(EngageOutcome::Cancelled {
+            restored: report.succeeded.len(),
+            failed: report.failed.len(),
+        });
+    }
+
+    lockdown.completed = true;
+    store.set(&lockdown).await?;
+
+    Ok(EngageOutcome::Completed {
+        locked: lockdown.channels.len(),
+        failed,
+    })
+}
+
+/// Restore the channels of a lockdown and delete its state.
+///
+/// Channels that could not be restored are logged and returned in the
+/// report, the state is deleted anyway.
+pub async fn rollback(
+    permissions: &impl EveryonePermissions,
+    store: &impl CacheStore,
+    lockdown: &LockdownState,
+) -> Result<BulkReport<ChannelSnapshot>, anyhow::Error> {
+    let options = BulkOptions {
+        concurrency: MAX_CONCURRENT_UPDATES,
+        ..Default::default()
+    };
+
+    let report = bulk::run(
+        lockdown.channels.iter().copied(),
+        options,
+        &CancelToken::new(),
+        &mut (),
+        |snapshot| permissions.set(snapshot.channel_id, snapshot.overwrite),
+    )
+    .await;
+
+    if !report.failed.is_empty() {
+        warn!(
+            guild = ?lockdown.guild_id,
+            failed = report.failed.len(),
+            "failed to restore channels after lockdown"
+        );
+    }
+
+    store.delete(lockdown).await?;
+
+    Ok(report)
+}
+
+/// Acquire the engagement lock of a guild.
+///
+/// Returns [`None`] if a lockdown is being engaged or rolled back.
+pub async fn lock(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+) -> Result<Option<DistributedLock>, anyhow::Error> {
+    let key = format!("lockdown:{guild_id}");
+
+    DistributedLock::acquire(&state.cache, &key, LOCK_TTL).await
+}
+
+/// Engage a lockdown in the background.
+///
+/// The progress is reported by updating the original response of the
+/// interaction at most every [`PROGRESS_INTERVAL`], and the engagement can be
+/// cancelled with the `cancel` token. The engagement `lock` is released once
+/// the engagement is done.
+pub async fn run(
+    state: ClusterState,
+    lock: DistributedLock,
+    lockdown: LockdownState,
+    targets: Vec<ChannelSnapshot>,
+    responder: InteractionResponder,
+    cancel: CancelToken,
+    lang: Lang,
+) {
+    let guild_id = lockdown.guild_id;
+    let interaction_id = lockdown.interaction_id;
+    let permissions = HttpPermissions {
+        state: &state,
+        guild_id,
+    };
+    let mut progress = LockdownProgress {
+        state: state.clone(),
+        message: ProgressMessage::new(responder),
+        lang,
+        updated_at: Instant::now(),
+    };
+
+    let result = engage(
+        &permissions,
+        &state.cache,
+        lockdown,
+        targets,
+        &cancel,
+        &mut progress,
+    )
+    .await;
+
+    state.bulk_tasks.remove(interaction_id);
+
+    if let Err(error) = lock.release().await {
+        warn!(error = ?error, guild = ?guild_id, "failed to release lockdown lock");
+    }
+
+    let embed = match result {
+        Ok(outcome) => embed::lockdown::summary(lang, outcome),
+        Err(error) => {
+            error!(error = ?error, guild = ?guild_id, "failed to engage lockdown");
+            embed::lockdown::error(lang)
+        }
+    };
+
+    progress.message.finish(&state, embed).await;
+}
+
+/// Progress of a lockdown engagement, shown in the progress message.
+struct LockdownProgress {
+    state: ClusterState,
+    message: ProgressMessage,
+    lang: Lang,
+    /// Date of the last update of the message.
+    updated_at: Instant,
+}
+
+#[async_trait]
+impl ProgressReporter for LockdownProgress {
+    async fn report(&mut self, progress: BulkProgress) {
+        if self.updated_at.elapsed() < PROGRESS_INTERVAL {
+            return;
+        }
+
+        let embed = embed::lockdown::progress(self.lang, progress.done, progress.total);
+        self.message.update(&self.state, embed).await;
+        self.updated_at = Instant::now();
+    }
+}
+
+/// Lift the lockdown of a guild in the background.
+///
+/// The interaction response is updated with the number of restored channels,
+/// or an error if no completed lockdown is engaged in the guild.
+pub async fn lift(
+    state: ClusterState,
+    guild_id: Id<GuildMarker>,
+    responder: InteractionResponder,
+    lang: Lang,
+) {
+    let result = async {
+        let lockdown = match state.cache.get::<LockdownState>(&guild_id).await? {
+            Some(lockdown) if lockdown.completed => lockdown,
+            _ => return Ok(None),
+        };
+
+        let permissions = HttpPermissions {
+            state: &state,
+            guild_id,
+        };
+
+        rollback(&permissions, &state.cache, &lockdown)
+            .await
+            .map(Some)
+    };
+
+    let embed = match result.await {
+        Ok(Some(report)) => {
+            embed::lockdown::lifted(lang, report.succeeded.len(), report.failed.len())
+        }
+        Ok(None) => embed::lockdown::not_engaged(lang),
+        Err(error) => {
+            error!(error = ?error, guild = ?guild_id, "failed to lift lockdown");
+            embed::lockdown::error(lang)
+        }
+    };
+
+    if let Err(error) = responder.update_response(&state, embed).await {
+        warn!(error = ?error, "failed to send lockdown report");
+    }
+}
+
+/// Roll back an interrupted lockdown engagement.
+///
+/// This function is called when a guild is received from the gateway. An
+/// engagement is interrupted if its state is not completed and it is not
+/// running in any instance. The rollback is notified in the logs channel.
+pub async fn recover(state: &ClusterState, guild_id: Id<GuildMarker>) {
+    let result = async {
+        let lockdown = match state.cache.get::<LockdownState>(&guild_id).await? {
+            Some(lockdown)
+                if !lockdown.completed && !state.bulk_tasks.contains(lockdown.interaction_id) =>
+            {
+                lockdown
+            }
+            _ => return Ok(()),
+        };
+
+        // The lock is held if the engagement is running in another instance.
+        let lock = match lock(state, guild_id).await? {
+            Some(lock) => lock,
+            None => return Ok(()),
+        };
+
+        warn!(guild = ?guild_id, "rolling back interrupted lockdown");
+
+        let permissions = HttpPermissions { state, guild_id };
+        let report = rollback(&permissions, &state.cache, &lockdown).await?;
+        lock.release().await?;
+
+        let config = database::guild_config(state, guild_id).await?;
+        let embed = embed::logs::lockdown_interrupted(config.lang(), report.succeeded.len());
+
+        logs::post(state, &config, LogCategory::Raids, embed).await
+    };
+
+    if let Err(error) = result.await {
+        error!(error = ?error, guild = ?guild_id, "failed to roll back interrupted lockdown");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use raidprotect_model::store::memory::MemoryCacheStore;
+    use twilight_model::channel::permission_overwrite::PermissionOverwrite;
+
+    use super::*;
+
+    /// Mocked channel list recording the permission updates.
+    #[derive(Default)]
+    struct MockPermissions {
+        /// Current overwrite of each channel.
+        channels: Mutex<Vec<(Id<ChannelMarker>, Option<EveryoneOverwrite>)>>,
+        /// Channels whose updates fail.
+        failing: HashSet<Id<ChannelMarker>>,
+        /// Cancel the engagement once this channel is locked.
+        cancel_at: Option<(Id<ChannelMarker>, CancelToken)>,
+    }
+
+    impl MockPermissions {
+        fn new(count: u64) -> Self {
+            let channels = (1..=count).map(|id| (Id::new(id), None)).collect();
+
+            Self {
+                channels: Mutex::new(channels),
+                ..Default::default()
+            }
+        }
+
+        fn targets(&self) -> Vec<ChannelSnapshot> {
+            self.channels
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(channel_id, overwrite)| ChannelSnapshot {
+                    channel_id: *channel_id,
+                    overwrite: *overwrite,
+                })
+                .collect()
+        }
+
+        fn locked(&self) -> usize {
+            self.channels
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(_, overwrite)| {
+                    overwrite.map_or(false, |overwrite| {
+                        overwrite.deny.contains(LOCKED_PERMISSIONS)
+                    })
+                })
+                .count()
+        }
+    }
+
+    #[async_trait]
+    impl EveryonePermissions for MockPermissions {
+        async fn set(
+            &self,
+            channel_id: Id<ChannelMarker>,
+            overwrite: Option<EveryoneOverwrite>,
+        ) -> Result<(), anyhow::Error> {
+            if self.failing.contains(&channel_id) {
+                anyhow::bail!("missing access");
+            }
+
+            let mut channels = self.channels.lock().unwrap();
+            let channel = channels
+                .iter_mut()
+                .find(|(id, _)| *id == channel_id)
+                .unwrap();
+            channel.1 = overwrite;
+
+            if let Some((cancel_at, cancel)) = &self.cancel_at {
+                if *cancel_at == channel_id {
+                    cancel.cancel();
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct Progress(Vec<BulkProgress>);
+
+    #[async_trait]
+    impl ProgressReporter for Progress {
+        async fn report(&mut self, progress: BulkProgress) {
+            self.0.push(progress);
+        }
+    }
+
+    fn lockdown() -> LockdownState {
+        LockdownState {
+            guild_id: Id::new(1),
+            interaction_id: Id::new(2),
+            author_id: Id::new(3),
+            completed: false,
+            channels: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let mut channel = CachedChannel {
+            id: Id::new(2),
+            guild_id: Id::new(1),
+            kind: ChannelType::GuildText,
+            name: "general".to_owned(),
+            parent_id: None,
+            permission_overwrites: Some(vec![
+                PermissionOverwrite {
+                    id: Id::new(3),
+                    kind: PermissionOverwriteType::Role,
+                    allow: Permissions::SEND_MESSAGES,
+                    deny: Permissions::empty(),
+                },
+                PermissionOverwrite {
+                    id: Id::new(1),
+                    kind: PermissionOverwriteType::Role,
+                    allow: Permissions::ADD_REACTIONS,
+                    deny: Permissions::VIEW_CHANNEL,
+                },
+            ]),
+            position: Some(0),
+            rate_limit_per_user: None,
+        };
+
+        let snapshot = snapshot(&channel).unwrap();
+        let overwrite = snapshot.overwrite.unwrap();
+        assert_eq!(overwrite.allow, Permissions::ADD_REACTIONS);
+        assert_eq!(overwrite.deny, Permissions::VIEW_CHANNEL);
+
+        // The other permissions of the overwrite are kept.
+        let locked = locked(&snapshot);
+        assert!(locked.allow.is_empty());
+        assert_eq!(locked.deny, Permissions::VIEW_CHANNEL | LOCKED_PERMISSIONS);
+
+        channel.kind = ChannelType::GuildCategory;
+        assert_eq!(super::snapshot(&channel), None);
+    }
+
+    #[tokio::test]
+    async fn test_engage_batches() {
+        let permissions = MockPermissions::new(1000);
+        let store = MemoryCacheStore::new();
+        let mut progress = Progress::default();
+
+        let outcome = engage(
+            &permissions,
+            &store,
+            lockdown(),
+            permissions.targets(),
+            &CancelToken::new(),
+            &mut progress,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            outcome,
+            EngageOutcome::Completed {
+                locked: 1000,
+                failed: 0
+            }
+        );
+        assert_eq!(permissions.locked(), 1000);
+
+        // The progress is reported after each batch.
+        let reported = progress.0.iter().map(|p| p.done).collect::<Vec<_>>();
+        let expected = (1..=1000 / BATCH_SIZE)
+            .map(|batch| batch * BATCH_SIZE)
+            .collect::<Vec<_>>();
+        assert_eq!(reported, expected);
+        assert!(progress.0.iter().all(|p| p.total == 1000));
+
+        let stored = store
+            .get::<LockdownState>(&Id::new(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(stored.completed);
+        assert_eq!(stored.channels.len(), 1000);
+    }
+
+    #[tokio::test]
+    async fn test_engage_failed_channels() {
+        let mut permissions = MockPermissions::new(120);
+        permissions.failing = [Id::new(10), Id::new(110)].into_iter().collect();
+        let store = MemoryCacheStore::new();
+
+        let outcome = engage(
+            &permissions,
+            &store,
+            lockdown(),
+            permissions.targets(),
+            &CancelToken::new(),
+            &mut (),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            outcome,
+            EngageOutcome::Completed {
+                locked: 118,
+                failed: 2
+            }
+        );
+
+        // Channels that have not been locked are not restored later.
+        let stored = store
+            .get::<LockdownState>(&Id::new(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(stored
+            .channels
+            .iter()
+            .all(|snapshot| !permissions.failing.contains(&snapshot.channel_id)));
+    }
+
+    #[tokio::test]
+    async fn test_engage_cancel_rollback() {
+        let cancel = CancelToken::new();
+        let mut permissions = MockPermissions::new(1000);
+        permissions.cancel_at = Some((Id::new(120), cancel.clone()));
+
+        // Channels with an existing overwrite get it back.
+        let previous = EveryoneOverwrite {
+            allow: Permissions::ADD_REACTIONS,
+            deny: Permissions::VIEW_CHANNEL,
+        };
+        permissions.channels.lock().unwrap()[4].1 = Some(previous);
+
+        let store = MemoryCacheStore::new();
+        let mut progress = Progress::default();
+
+        let outcome = engage(
+            &permissions,
+            &store,
+            lockdown(),
+            permissions.targets(),
+            &cancel,
+            &mut progress,
+        )
+        .await
+        .unwrap();
+
+        // The third batch is interrupted, the following ones are not started.
+        let restored = match outcome {
+            EngageOutcome::Cancelled { restored, failed } => {
+                assert_eq!(failed, 0);
+                restored
+            }
+            outcome => panic!("expected cancelled engagement, got {outcome:?}"),
+        };
+        assert!((120..150).contains(&restored));
+        assert_eq!(progress.0.len(), 3);
+
+        assert_eq!(permissions.locked(), 0);
+        assert_eq!(permissions.channels.lock().unwrap()[4].1, Some(previous));
+        assert!(permissions.channels.lock().unwrap()[5].1.is_none());
+        assert!(store.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rollback_partial_snapshot() {
+        let permissions = MockPermissions::new(100);
+        let store = MemoryCacheStore::new();
+
+        // An interrupted engagement saved the first batch before locking it.
+        let mut interrupted = lockdown();
+        interrupted.channels = permissions.targets()[..BATCH_SIZE].to_vec();
+        store.set(&interrupted).await.unwrap();
+
+        for snapshot in &interrupted.channels[..10] {
+            permissions
+                .set(snapshot.channel_id, Some(locked(snapshot)))
+                .await
+                .unwrap();
+        }
+        assert_eq!(permissions.locked(), 10);
+
+        let report = rollback(&permissions, &store, &interrupted).await.unwrap();
+
+        assert_eq!(report.succeeded.len(), BATCH_SIZE);
+        assert_eq!(permissions.locked(), 0);
+        assert!(store.is_empty());
+    }
+}
@@ -4,4 +4,27 @@
 //! wide to fit in another module, such as features involving both events and
 //! interactions.
 
+pub mod automation;
+pub mod automod;
+pub mod ban_sync;
+pub mod bulk_ban;
+pub mod bulk_unban;
 pub mod captcha;
+pub mod cleanup;
+pub mod filter_feedback;
+pub mod lockdown;
+pub mod onboarding;
+pub mod preset;
+pub mod raid_alert;
+pub mod raid_mode;
+pub mod reconcile;
+pub mod references;
+pub mod report;
+pub mod restore;
+pub mod retention;
+pub mod sanction;
+pub mod scenario;
+pub mod simulation;
+pub mod stats;
+pub mod watch;
+pub mod webhook;
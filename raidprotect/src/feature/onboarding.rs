@@ -0,0 +1,161 @@
+//! Guild onboarding.
+//!
+//! When the bot is added to a guild, a quick-start guide is sent to the guild
+//! owner. If the owner does not accept private messages, the guide is posted
+//! in the guild system channel instead.
+//!
+//! The guide is only sent once per guild, which is tracked with the
+//! `onboarded` field of the guild configuration. Guilds received on startup or
+//! after a reconnection are ignored based on the date the bot joined them.
+
+use std::slice;
+
+use time::OffsetDateTime;
+use tracing::{debug, warn};
+use twilight_model::{
+    application::component::{button::ButtonStyle, ActionRow, Button, Component},
+    channel::embed::Embed,
+    guild::Guild,
+    id::{
+        marker::{ChannelMarker, GuildMarker, UserMarker},
+        Id,
+    },
+};
+
+use crate::{cluster::ClusterState, database, interaction::embed, translations::Lang};
+
+/// Maximum delay since the bot joined a guild to send the onboarding message,
+/// in seconds.
+///
+/// `GUILD_CREATE` events are also received for all guilds on startup and after
+/// reconnections, but the bot join date is only recent for new guilds.
+const MAX_JOIN_DELAY: i64 = 5 * 60;
+
+/// Link to the documentation.
+const DOCS_URL: &str = "https://docs.raidprotect.org/";
+
+/// Information about a guild the bot joined.
+#[derive(Debug, Clone)]
+pub struct GuildJoin {
+    /// Id of the guild.
+    pub guild_id: Id<GuildMarker>,
+    /// Name of the guild.
+    pub name: String,
+    /// Id of the guild owner.
+    pub owner_id: Id<UserMarker>,
+    /// Channel where the guild system messages are sent.
+    pub system_channel_id: Option<Id<ChannelMarker>>,
+    /// Preferred locale of the guild.
+    pub preferred_locale: String,
+}
+
+impl GuildJoin {
+    /// Get the [`GuildJoin`] of a `GUILD_CREATE` event, if the bot has just
+    /// joined the guild.
+    pub fn from_guild(guild: &Guild) -> Option<Self> {
+        let joined_at = guild.joined_at?.as_secs();
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+
+        if now - joined_at > MAX_JOIN_DELAY {
+            return None;
+        }
+
+        Some(Self {
+            guild_id: guild.id,
+            name: guild.name.clone(),
+            owner_id: guild.owner_id,
+            system_channel_id: guild.system_channel_id,
+            preferred_locale: guild.preferred_locale.clone(),
+        })
+    }
+}
+
+/// Send the onboarding message of a guild the bot joined.
+pub async fn guild_join(state: &ClusterState, join: GuildJoin) {
+    if let Err(error) = guild_join_inner(state, &join).await {
+        warn!(error = ?error, guild = ?join.guild_id, "failed to send onboarding message");
+    }
+}
+
+async fn guild_join_inner(state: &ClusterState, join: &GuildJoin) -> Result<(), anyhow::Error> {
+    // Create the guild configuration if it does not exist yet.
+    database::guild_config(state, join.guild_id).await?;
+
+    if !state.database.claim_onboarding(join.guild_id).await? {
+        return Ok(());
+    }
+
+    // The owner locale is not known, the guild locale is used instead.
+    let lang = Lang::from(&*join.preferred_locale);
+    let embed = embed::onboarding::quick_start(lang, &join.name);
+    let components = quick_start_components(lang);
+
+    match send_owner(state, join.owner_id, &embed, &components).await {
+        Ok(()) => Ok(()),
+        Err(error) => {
+            debug!(error = ?error, guild = ?join.guild_id, "failed to send onboarding to owner");
+
+            if let Some(channel) = join.system_channel_id {
+                state
+                    .http
+                    .create_message(channel)
+                    .embeds(&[embed])?
+                    .components(&components)?
+                    .exec()
+                    .await?;
+            }
+
+            Ok(())
+        }
+    }
+}
+
+/// Send the onboarding message to the guild owner.
+async fn send_owner(
+    state: &ClusterState,
+    owner_id: Id<UserMarker>,
+    embed: &Embed,
+    components: &[Component],
+) -> Result<(), anyhow::Error> {
+    let channel = state
+        .http
+        .create_private_channel(owner_id)
+        .exec()
+        .await?
+        .model()
+        .await?;
+
+    state
+        .http
+        .create_message(channel.id)
+        .embeds(slice::from_ref(embed))?
+        .components(components)?
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+/// Buttons of the onboarding message.
+fn quick_start_components(lang: Lang) -> Vec<Component> {
+    vec![Component::ActionRow(ActionRow {
+        components: vec![
+            Component::Button(Button {
+                custom_id: None,
+                disabled: false,
+                emoji: None,
+                label: Some(lang.onboarding_setup_button().to_owned()),
+                style: ButtonStyle::Link,
+                url: Some(format!("{DOCS_URL}setup")),
+            }),
+            Component::Button(Button {
+                custom_id: None,
+                disabled: false,
+                emoji: None,
+                label: Some(lang.learn_more().to_owned()),
+                style: ButtonStyle::Link,
+                url: Some(DOCS_URL.to_owned()),
+            }),
+        ],
+    })]
+}
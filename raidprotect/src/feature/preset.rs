@@ -0,0 +1,359 @@
+//! Configuration presets.
+//!
+//! New guilds rarely know which thresholds fit them. Presets are built-in
+//! configurations for common kinds of guilds, applied with the `/preset`
+//! command. The changes are shown to the administrator before they are
+//! applied (see [`changes`]), and each application is logged in the logs
+//! channel with the name of the preset.
+//!
+//! Presets only change thresholds and feature toggles: channels and roles
+//! configured in the guild are never overwritten (see [`apply`]).
+
+use std::collections::BTreeMap;
+
+use raidprotect_model::database::model::{
+    AntinukeAction, Feature, FirstMessageAction, GuildConfig, VoiceHopAction,
+};
+use serde_json::Value;
+use twilight_interactions::command::{CommandOption, CreateOption};
+use twilight_model::{
+    guild::VerificationLevel,
+    id::{marker::GuildMarker, Id},
+};
+
+use crate::translations::Lang;
+
+/// Built-in configuration preset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CommandOption, CreateOption)]
+pub enum Preset {
+    #[option(name = "Small community", value = "small")]
+    Small,
+    #[option(name = "Large community", value = "large")]
+    Large,
+    #[option(name = "High-risk public server", value = "high-risk")]
+    HighRisk,
+}
+
+impl Preset {
+    /// List of all the presets.
+    pub const ALL: [Preset; 3] = [Preset::Small, Preset::Large, Preset::HighRisk];
+
+    /// Get the name of the preset.
+    ///
+    /// This name is stored in the pending confirmation of the preset.
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Small => "small",
+            Preset::Large => "large",
+            Preset::HighRisk => "high-risk",
+        }
+    }
+
+    /// Get a preset from its name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|preset| preset.name() == name)
+    }
+
+    /// Localized name of the preset.
+    pub fn title(self, lang: Lang) -> &'static str {
+        match self {
+            Preset::Small => lang.preset_small(),
+            Preset::Large => lang.preset_large(),
+            Preset::HighRisk => lang.preset_high_risk(),
+        }
+    }
+
+    /// Full configuration of the preset.
+    ///
+    /// The configuration has no channel or role configured.
+    pub fn config(self, guild_id: Id<GuildMarker>) -> GuildConfig {
+        let mut config = GuildConfig::new(guild_id);
+
+        match self {
+            Preset::Small => {
+                config.raid_mode.account_age = Some(24);
+                config.raid_mode.verification_level = Some(VerificationLevel::Medium);
+                config.antinuke.enabled = true;
+                config.features.set(Feature::AutoSlowmode, false);
+            }
+            Preset::Large => {
+                config.moderation.sanction_cooldown = 60;
+                config.raid_mode.account_age = Some(72);
+                config.raid_mode.verification_level = Some(VerificationLevel::High);
+                config.reaction_spam.enabled = true;
+                config.reaction_spam.timeout = Some(10 * 60);
+                config.link_filter.enabled = true;
+                config.first_message.enabled = true;
+                config.antinuke.enabled = true;
+                config.antinuke.max_deletions = 3;
+                config.join_burst.enabled = true;
+                config.voice.hop_detection = true;
+                config.sentinel_alerts = true;
+            }
+            Preset::HighRisk => {
+                config.moderation.enforce_reason = true;
+                config.moderation.sanction_cooldown = 60;
+                config.raid_mode.account_age = Some(7 * 24);
+                config.raid_mode.verification_level = Some(VerificationLevel::VeryHigh);
+                config.reaction_spam.enabled = true;
+                config.reaction_spam.max_reactions = 6;
+                config.reaction_spam.timeout = Some(60 * 60);
+                config.link_filter.enabled = true;
+                config.link_filter.links = true;
+                config.link_filter.timeout = Some(10 * 60);
+                config.first_message.enabled = true;
+                config.first_message.action = FirstMessageAction::Ban;
+                config.antinuke.enabled = true;
+                config.antinuke.max_deletions = 3;
+                config.antinuke.action = AntinukeAction::Ban;
+                config.join_burst.enabled = true;
+                config.join_burst.max_joins = 5;
+                config.voice.hop_detection = true;
+                config.voice.max_hops = 3;
+                config.voice.action = VoiceHopAction::Timeout;
+                config.new_account_threshold = 14 * 24;
+                config.sentinel_alerts = true;
+            }
+        }
+
+        config
+    }
+}
+
+/// Apply a preset to the configuration of a guild.
+///
+/// Only the thresholds and feature toggles of the preset are copied. The
+/// channels and roles, as well as the allowlists and the state of the modules
+/// (such as the raid mode or the captcha), are kept.
+pub fn apply(preset: Preset, current: &GuildConfig) -> GuildConfig {
+    let preset = preset.config(current.id);
+    let mut config = current.clone();
+
+    config.moderation.enforce_reason = preset.moderation.enforce_reason;
+    config.moderation.sanction_cooldown = preset.moderation.sanction_cooldown;
+
+    config.raid_mode.account_age = preset.raid_mode.account_age;
+    config.raid_mode.verification_level = preset.raid_mode.verification_level;
+    config.raid_mode.join_alerts = preset.raid_mode.join_alerts;
+
+    config.reaction_spam.enabled = preset.reaction_spam.enabled;
+    config.reaction_spam.max_reactions = preset.reaction_spam.max_reactions;
+    config.reaction_spam.interval = preset.reaction_spam.interval;
+    config.reaction_spam.timeout = preset.reaction_spam.timeout;
+
+    config.link_filter.enabled = preset.link_filter.enabled;
+    config.link_filter.invites = preset.link_filter.invites;
+    config.link_filter.links = preset.link_filter.links;
+    config.link_filter.timeout = preset.link_filter.timeout;
+
+    config.first_message = preset.first_message;
+
+    config.antinuke.enabled = preset.antinuke.enabled;
+    config.antinuke.max_deletions = preset.antinuke.max_deletions;
+    config.antinuke.interval = preset.antinuke.interval;
+    config.antinuke.action = preset.antinuke.action;
+
+    config.join_burst = preset.join_burst;
+
+    config.voice.hop_detection = preset.voice.hop_detection;
+    config.voice.max_hops = preset.voice.max_hops;
+    config.voice.interval = preset.voice.interval;
+    config.voice.action = preset.voice.action;
+    config.voice.timeout = preset.voice.timeout;
+
+    config.new_account_threshold = preset.new_account_threshold;
+    config.features = preset.features;
+    config.sentinel_alerts = preset.sentinel_alerts;
+
+    config
+}
+
+/// Setting changed by a preset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigChange {
+    /// Path of the setting, such as `reaction_spam.enabled`.
+    pub setting: String,
+    /// Value before the change, or [`None`] if the setting was not set.
+    pub before: Option<String>,
+    /// Value after the change, or [`None`] if the setting is unset.
+    pub after: Option<String>,
+}
+
+/// Settings that differ between two configurations.
+///
+/// The configurations are compared setting by setting, in the order of their
+/// path. Features are compared individually.
+pub fn changes(before: &GuildConfig, after: &GuildConfig) -> Vec<ConfigChange> {
+    let before = settings(before);
+    let mut after = settings(after);
+    let mut changes = Vec::new();
+
+    for (setting, value) in before {
+        let new = after.remove(&setting);
+
+        if new.as_ref() != Some(&value) {
+            changes.push(ConfigChange {
+                setting,
+                before: Some(value),
+                after: new,
+            });
+        }
+    }
+
+    for (setting, value) in after {
+        changes.push(ConfigChange {
+            setting,
+            before: None,
+            after: Some(value),
+        });
+    }
+
+    changes.sort_by(|a, b| a.setting.cmp(&b.setting));
+    changes
+}
+
+/// Flatten a configuration into its settings, indexed by path.
+fn settings(config: &GuildConfig) -> BTreeMap<String, String> {
+    let mut settings = BTreeMap::new();
+
+    if let Ok(value) = serde_json::to_value(config) {
+        flatten(String::new(), value, &mut settings);
+    }
+
+    // The features are stored as a bitflag.
+    settings.remove("features");
+    for feature in Feature::ALL {
+        settings.insert(
+            format!("features.{}", feature.name()),
+            config.features.contains(feature).to_string(),
+        );
+    }
+
+    settings
+}
+
+fn flatten(path: String, value: Value, settings: &mut BTreeMap<String, String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let path = match path.is_empty() {
+                    true => key,
+                    false => format!("{path}.{key}"),
+                };
+
+                flatten(path, value, settings);
+            }
+        }
+        Value::Null => {}
+        value => {
+            settings.insert(path, value.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use raidprotect_model::database::model::{FeatureSet, MuteMode};
+
+    use super::*;
+
+    fn configured() -> GuildConfig {
+        let mut config = GuildConfig::new(Id::new(1));
+        config.logs_chan = Some(Id::new(2));
+        config.raid_alert_role = Some(Id::new(3));
+        config.moderation.mute_mode = MuteMode::Role {
+            role_id: Id::new(4),
+        };
+        config.reaction_spam.trusted_roles = vec![Id::new(5)];
+        config.link_filter.allowed_domains = vec!["example.com".to_owned()];
+        config.voice.logs_chan = Some(Id::new(6));
+        config.raid_mode.enabled = true;
+
+        config
+    }
+
+    #[test]
+    fn test_preset_names() {
+        for preset in Preset::ALL {
+            assert_eq!(Preset::from_name(preset.name()), Some(preset));
+        }
+
+        assert_eq!(Preset::from_name("unknown"), None);
+    }
+
+    #[test]
+    fn test_preset_keeps_references() {
+        let current = configured();
+
+        for preset in Preset::ALL {
+            let config = apply(preset, &current);
+
+            assert_eq!(config.logs_chan, current.logs_chan);
+            assert_eq!(config.raid_alert_role, current.raid_alert_role);
+            assert_eq!(config.moderation.mute_mode, current.moderation.mute_mode);
+            assert_eq!(
+                config.reaction_spam.trusted_roles,
+                current.reaction_spam.trusted_roles
+            );
+            assert_eq!(
+                config.link_filter.allowed_domains,
+                current.link_filter.allowed_domains
+            );
+            assert_eq!(config.voice.logs_chan, current.voice.logs_chan);
+            assert!(config.raid_mode.enabled);
+        }
+    }
+
+    #[test]
+    fn test_preset_thresholds() {
+        let current = configured();
+
+        for preset in Preset::ALL {
+            let config = apply(preset, &current);
+            let expected = preset.config(current.id);
+
+            assert_eq!(config.reaction_spam.enabled, expected.reaction_spam.enabled);
+            assert_eq!(config.antinuke.action, expected.antinuke.action);
+            assert_eq!(config.voice.max_hops, expected.voice.max_hops);
+            assert_eq!(config.features, expected.features);
+
+            // Applying the same preset again changes nothing.
+            assert!(changes(&config, &apply(preset, &config)).is_empty());
+        }
+
+        let features = |preset: Preset| preset.config(current.id).features;
+        assert_ne!(features(Preset::Small), FeatureSet::DEFAULT);
+        assert_eq!(features(Preset::HighRisk), FeatureSet::DEFAULT);
+    }
+
+    #[test]
+    fn test_changes() {
+        let before = GuildConfig::new(Id::new(1));
+        let mut after = before.clone();
+        after.reaction_spam.enabled = true;
+        after.reaction_spam.timeout = Some(600);
+        after.features.set(Feature::AutoSlowmode, false);
+
+        assert_eq!(
+            changes(&before, &after),
+            vec![
+                ConfigChange {
+                    setting: "features.auto-slowmode".to_owned(),
+                    before: Some("true".to_owned()),
+                    after: Some("false".to_owned()),
+                },
+                ConfigChange {
+                    setting: "reaction_spam.enabled".to_owned(),
+                    before: Some("false".to_owned()),
+                    after: Some("true".to_owned()),
+                },
+                ConfigChange {
+                    setting: "reaction_spam.timeout".to_owned(),
+                    before: None,
+                    after: Some("600".to_owned()),
+                },
+            ]
+        );
+        assert!(changes(&before, &before).is_empty());
+    }
+}
@@ -0,0 +1,154 @@
+//! Anti-raid alerts.
+//!
+//! Alerts sent in the logs channel when a raid is detected (see the
+//! [`antinuke`] and [`join_burst`] modules) mention the role configured in
+//! [`GuildConfig::raid_alert_role`], so that staff members are notified
+//! immediately. The mention is throttled for [`PING_INTERVAL`] per guild: an
+//! alert triggered repeatedly is still sent, but without pinging the role.
+//!
+//! Alerts come with buttons to enable the raid mode or dismiss the alert (see
+//! the [`RaidAlertLockdown`] and [`RaidAlertDismiss`] components).
+//!
+//! [`antinuke`]: crate::event::antinuke
+//! [`join_burst`]: crate::event::join_burst
+//! [`RaidAlertLockdown`]: crate::interaction::component::RaidAlertLockdown
+//! [`RaidAlertDismiss`]: crate::interaction::component::RaidAlertDismiss
+
+use raidprotect_model::database::model::{GuildConfig, LogCategory, StatsEvent};
+use time::Duration;
+use twilight_mention::Mention;
+use twilight_model::{
+    application::component::{button::ButtonStyle, ActionRow, Button, Component},
+    channel::{embed::Embed, message::AllowedMentions},
+    id::{
+        marker::{GuildMarker, RoleMarker},
+        Id,
+    },
+};
+
+use crate::{
+    cluster::ClusterState,
+    feature,
+    interaction::util::{CustomId, GuildConfigExt},
+    translations::Lang,
+    util::logs::{self, LogMessage},
+};
+
+/// Minimum interval between two mentions of the alert role.
+pub const PING_INTERVAL: Duration = Duration::minutes(10);
+
+/// Send an anti-raid alert in the logs channel.
+///
+/// The `embed` summarizes what triggered the alert.
+pub async fn send(
+    state: &ClusterState,
+    config: &GuildConfig,
+    embed: Embed,
+) -> Result<(), anyhow::Error> {
+    feature::stats::record(state, config.id, StatsEvent::Raid).await;
+
+    let lang = config.lang();
+
+    let role = match config.raid_alert_role {
+        Some(role) => ping_role(state, config.id, role).await?,
+        None => None,
+    };
+
+    let components = components(lang, config.raid_mode.enabled);
+    let mut message = LogMessage::new(embed).components(components);
+
+    if let Some(role) = role {
+        let allowed_mentions = AllowedMentions {
+            roles: vec![role],
+            ..Default::default()
+        };
+
+        message = message.content(role.mention().to_string(), allowed_mentions);
+    }
+
+    logs::post_message(state, config, LogCategory::Raids, message).await?;
+
+    Ok(())
+}
+
+/// Get the role to mention in an alert, if it has not been mentioned during
+/// the last [`PING_INTERVAL`].
+async fn ping_role(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    role: Id<RoleMarker>,
+) -> Result<Option<Id<RoleMarker>>, anyhow::Error> {
+    let key = format!("raid-alert:{guild_id}");
+
+    if state.cache.throttle(&key, PING_INTERVAL).await? {
+        Ok(Some(role))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Buttons attached to the alerts.
+///
+/// The lockdown button is disabled if the raid mode is already enabled.
+fn components(lang: Lang, raid_mode_enabled: bool) -> Vec<Component> {
+    let lockdown = Button {
+        custom_id: Some(CustomId::name("raid-alert-lockdown").to_string()),
+        disabled: raid_mode_enabled,
+        emoji: None,
+        label: Some(lang.raid_alert_lockdown_button().to_owned()),
+        style: ButtonStyle::Danger,
+        url: None,
+    };
+    let dismiss = Button {
+        custom_id: Some(CustomId::name("raid-alert-dismiss").to_string()),
+        disabled: false,
+        emoji: None,
+        label: Some(lang.raid_alert_dismiss_button().to_owned()),
+        style: ButtonStyle::Secondary,
+        url: None,
+    };
+
+    vec![Component::ActionRow(ActionRow {
+        components: vec![Component::Button(lockdown), Component::Button(dismiss)],
+    })]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buttons(components: &[Component]) -> Vec<&Button> {
+        components
+            .iter()
+            .filter_map(|component| match component {
+                Component::ActionRow(row) => Some(&row.components),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|component| match component {
+                Component::Button(button) => Some(button),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_components() {
+        let components = components(Lang::DEFAULT, false);
+        let buttons = buttons(&components);
+
+        assert_eq!(buttons.len(), 2);
+        assert_eq!(buttons[0].custom_id.as_deref(), Some("raid-alert-lockdown"));
+        assert_eq!(buttons[1].custom_id.as_deref(), Some("raid-alert-dismiss"));
+        assert!(buttons.iter().all(|button| !button.disabled));
+    }
+
+    #[test]
+    fn test_components_raid_mode_enabled() {
+        let components = components(Lang::DEFAULT, true);
+        let buttons = buttons(&components);
+
+        assert!(buttons[0].disabled);
+        assert!(!buttons[1].disabled);
+    }
+}
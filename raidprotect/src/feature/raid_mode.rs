@@ -0,0 +1,100 @@
+//! Raid mode feature.
+//!
+//! The raid mode applies the stricter settings configured in the
+//! [`RaidModeConfig`] of a guild. It is enabled manually by the guild
+//! administrators and remains enabled until explicitly disabled.
+//!
+//! [`RaidModeConfig`]: raidprotect_model::database::model::RaidModeConfig
+
+use raidprotect_model::database::model::{GuildConfig, LogCategory};
+use tracing::{error, warn};
+use twilight_http::request::AuditLogReason;
+use twilight_model::{
+    channel::embed::Embed,
+    id::{marker::UserMarker, Id},
+};
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    interaction::{embed, util::GuildConfigExt},
+    util::logs,
+};
+
+/// Enable the raid mode.
+///
+/// The current verification level of the guild is stored in the configuration
+/// to be restored when the raid mode is disabled. The configuration is saved
+/// in the database.
+pub async fn enable(
+    state: &ClusterState,
+    config: &mut GuildConfig,
+    user: Id<UserMarker>,
+) -> Result<(), anyhow::Error> {
+    let lang = config.lang();
+
+    if let Some(level) = config.raid_mode.verification_level {
+        let guild = state.http.guild(config.id).exec().await?.model().await?;
+
+        // Only raise the verification level.
+        if u8::from(level) > u8::from(guild.verification_level) {
+            state
+                .cache_http(config.id)
+                .update_guild()
+                .await?
+                .verification_level(Some(level))
+                .reason(lang.raid_mode_enabled_reason())?
+                .exec()
+                .await?;
+
+            config.raid_mode.previous_verification_level = Some(guild.verification_level);
+        }
+    }
+
+    config.raid_mode.enabled = true;
+    database::update_guild_config(state, config).await?;
+
+    send_logs(state, config, embed::logs::raid_mode_enabled(lang, user)).await;
+
+    Ok(())
+}
+
+/// Disable the raid mode.
+///
+/// The verification level of the guild is restored to its previous value. The
+/// configuration is saved in the database.
+pub async fn disable(
+    state: &ClusterState,
+    config: &mut GuildConfig,
+    user: Id<UserMarker>,
+) -> Result<(), anyhow::Error> {
+    let lang = config.lang();
+
+    if let Some(level) = config.raid_mode.previous_verification_level.take() {
+        if let Err(error) = state
+            .cache_http(config.id)
+            .update_guild()
+            .await?
+            .verification_level(Some(level))
+            .reason(lang.raid_mode_disabled_reason())?
+            .exec()
+            .await
+        {
+            warn!(error = ?error, guild = ?config.id, "failed to restore verification level");
+        }
+    }
+
+    config.raid_mode.enabled = false;
+    database::update_guild_config(state, config).await?;
+
+    send_logs(state, config, embed::logs::raid_mode_disabled(lang, user)).await;
+
+    Ok(())
+}
+
+/// Send a message in the guild logs channel.
+async fn send_logs(state: &ClusterState, config: &GuildConfig, embed: Embed) {
+    if let Err(error) = logs::post(state, config, LogCategory::Raids, embed).await {
+        error!(error = ?error, guild = ?config.id, "failed to send raid mode logs message");
+    }
+}
@@ -0,0 +1,160 @@
+//! Cache reconciliation.
+//!
+//! When the cache is flushed or misses events, permission checks fail because
+//! the guild roles and channels are no longer cached. A reconciliation fetches
+//! them with the HTTP API and rewrites the cache (see [`CacheClient::reconcile_guild`]).
+//!
+//! Reconciliations are started manually with `/admin reconcile`, or
+//! automatically when cache misses of a guild exceed [`MISS_THRESHOLD`]. A
+//! lock ensures that a guild is never reconciled concurrently, even across
+//! several instances.
+//!
+//! Discrepancies found between the cache and the API are recorded in the
+//! `raidprotect_cache_discrepancies_total` counter, since they indicate a bug
+//! in the cache invalidation.
+//!
+//! [`CacheClient::reconcile_guild`]: raidprotect_model::cache::CacheClient::reconcile_guild
+
+use raidprotect_model::cache::{
+    discord::{permission::CacheMiss, reconcile::ReconcileReport},
+    model::lock::DistributedLock,
+};
+use time::Duration;
+use tracing::{error, info, warn};
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::cluster::ClusterState;
+
+/// Number of cache misses of a guild that triggers a reconciliation.
+pub const MISS_THRESHOLD: u32 = 10;
+
+/// Window during which cache misses are counted.
+const MISS_WINDOW: Duration = Duration::minutes(1);
+
+/// Minimum interval between two automatic reconciliations of a guild.
+const AUTO_INTERVAL: Duration = Duration::minutes(15);
+
+/// Expiration of the reconciliation lock, in case the instance holding it
+/// crashes.
+const LOCK_TTL: Duration = Duration::seconds(30);
+
+/// Reconcile the cache of a guild.
+///
+/// Returns [`None`] if the guild is already being reconciled.
+pub async fn reconcile(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+) -> Result<Option<ReconcileReport>, anyhow::Error> {
+    let key = format!("reconcile:{guild_id}");
+    let lock = match DistributedLock::acquire(&state.cache, &key, LOCK_TTL).await? {
+        Some(lock) => lock,
+        None => return Ok(None),
+    };
+
+    let result = reconcile_inner(state, guild_id).await;
+
+    if let Err(error) = lock.release().await {
+        warn!(error = ?error, guild = ?guild_id, "failed to release reconciliation lock");
+    }
+
+    let report = result?;
+    record_discrepancies(&report);
+
+    info!(guild = ?guild_id, report = ?report, "reconciled guild cache");
+
+    Ok(Some(report))
+}
+
+async fn reconcile_inner(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+) -> Result<ReconcileReport, anyhow::Error> {
+    let http = &state.http;
+
+    let (guild, channels, member) = tokio::try_join!(
+        async {
+            let guild = http.guild(guild_id).exec().await?.model().await?;
+            Ok::<_, anyhow::Error>(guild)
+        },
+        async {
+            let channels = http.guild_channels(guild_id).exec().await?.models().await?;
+            Ok(channels)
+        },
+        async {
+            let member = http
+                .guild_member(guild_id, state.current_user.cast())
+                .exec()
+                .await?
+                .model()
+                .await?;
+            Ok(member)
+        },
+    )?;
+
+    state
+        .cache
+        .reconcile_guild(&guild, &channels, &member)
+        .await
+}
+
+/// Record the discrepancies of a reconciliation in the metrics.
+fn record_discrepancies(report: &ReconcileReport) {
+    let resources = [("role", report.roles), ("channel", report.channels)];
+
+    for (resource, report) in resources {
+        if report.discrepancies() > 0 {
+            warn!(resource, report = ?report, "cache discrepancies found");
+
+            metrics::counter!(
+                "raidprotect_cache_discrepancies_total",
+                report.discrepancies() as u64,
+                "resource" => resource
+            );
+        }
+    }
+}
+
+/// Record the error of a handler.
+///
+/// If the error is caused by a cache miss, it is counted and a reconciliation
+/// of the guild is started in the background once [`MISS_THRESHOLD`] is
+/// exceeded.
+pub async fn record_error(state: &ClusterState, guild_id: Id<GuildMarker>, error: &anyhow::Error) {
+    if !error.chain().any(|cause| cause.is::<CacheMiss>()) {
+        return;
+    }
+
+    metrics::increment_counter!("raidprotect_cache_misses_total");
+
+    if let Err(error) = record_miss(state, guild_id).await {
+        error!(error = ?error, guild = ?guild_id, "failed to record cache miss");
+    }
+}
+
+async fn record_miss(state: &ClusterState, guild_id: Id<GuildMarker>) -> Result<(), anyhow::Error> {
+    let key = format!("cache-miss:{guild_id}");
+
+    if state
+        .cache
+        .rate_limit(&key, MISS_THRESHOLD, MISS_WINDOW)
+        .await?
+    {
+        return Ok(());
+    }
+
+    let key = format!("reconcile:{guild_id}");
+    if !state.cache.throttle(&key, AUTO_INTERVAL).await? {
+        return Ok(());
+    }
+
+    warn!(guild = ?guild_id, "too many cache misses, reconciling guild cache");
+
+    let state = state.clone();
+    tokio::spawn(async move {
+        if let Err(error) = reconcile(&state, guild_id).await {
+            error!(error = ?error, guild = ?guild_id, "failed to reconcile guild cache");
+        }
+    });
+
+    Ok(())
+}
@@ -0,0 +1,147 @@
+//! Broken configuration references.
+//!
+//! The channels and roles referenced in the guild configuration can be
+//! deleted at any time, which would make features fail silently. The
+//! references are checked when a channel or role is deleted and when the
+//! configuration is loaded for a command, and a notice is sent in the logs
+//! channel if some references are broken.
+//!
+//! Notices are throttled to avoid flooding the logs channel. Deleted channels
+//! can be replaced at once with the `/config check transfer_to` command.
+
+use raidprotect_model::{
+    cache::discord::CachedGuild,
+    database::{
+        model::{GuildConfig, LogCategory},
+        validate_references, BrokenReference, ReferenceKind,
+    },
+};
+use time::Duration;
+use tracing::warn;
+use twilight_mention::Mention;
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    interaction::{
+        embed::{EmbedBuilder, COLOR_RED},
+        util::GuildConfigExt,
+    },
+    translations::Lang,
+    util::logs,
+};
+
+/// Minimum interval between two notices sent in the same guild.
+const NOTICE_INTERVAL: Duration = Duration::hours(1);
+
+/// Get the broken references of a guild configuration.
+///
+/// If the guild is not cached, no references are returned.
+pub async fn broken_references(
+    state: &ClusterState,
+    config: &GuildConfig,
+) -> Result<Vec<BrokenReference>, anyhow::Error> {
+    let guild = match state.cache.get::<CachedGuild>(&config.id).await? {
+        Some(guild) if !guild.unavailable => guild,
+        _ => return Ok(Vec::new()),
+    };
+
+    Ok(validate_references(config, &guild.channels, &guild.roles))
+}
+
+/// Check the references of a guild configuration after a channel or role has
+/// been deleted.
+pub async fn check_guild(state: &ClusterState, guild_id: Id<GuildMarker>) {
+    match database::guild_config(state, guild_id).await {
+        Ok(config) => check(state, &config).await,
+        Err(error) => warn!(error = ?error, guild = ?guild_id, "failed to check config references"),
+    }
+}
+
+/// Check the references of a guild configuration.
+///
+/// A notice is sent in the logs channel if some references are broken.
+pub async fn check(state: &ClusterState, config: &GuildConfig) {
+    if let Err(error) = check_inner(state, config).await {
+        warn!(error = ?error, guild = ?config.id, "failed to check config references");
+    }
+}
+
+async fn check_inner(state: &ClusterState, config: &GuildConfig) -> Result<(), anyhow::Error> {
+    let broken = broken_references(state, config).await?;
+
+    if broken.is_empty() {
+        return Ok(());
+    }
+
+    let throttle_key = format!("references:{}", config.id);
+    if !state.cache.throttle(&throttle_key, NOTICE_INTERVAL).await? {
+        return Ok(());
+    }
+
+    let lang = config.lang();
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.broken_references_title())
+        .description(lang.broken_references_notice(format_references(&broken, lang)))
+        .build();
+
+    // If the logs channel has been deleted, a new one is created.
+    logs::post(state, config, LogCategory::Config, embed).await
+}
+
+/// Format a list of broken references.
+pub fn format_references(references: &[BrokenReference], lang: Lang) -> String {
+    references
+        .iter()
+        .map(|reference| {
+            let name = reference_name(reference.kind(), lang);
+
+            match reference {
+                BrokenReference::Channel { id, .. } => {
+                    format!("- {name}: {} (`{id}`)", id.mention())
+                }
+                BrokenReference::Role { id, .. } => format!("- {name}: {} (`{id}`)", id.mention()),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Get the display name of a [`ReferenceKind`].
+fn reference_name(kind: ReferenceKind, lang: Lang) -> &'static str {
+    match kind {
+        ReferenceKind::LogsChannel => lang.reference_logs_channel(),
+        ReferenceKind::CaptchaChannel => lang.reference_captcha_channel(),
+        ReferenceKind::CaptchaLogsChannel => lang.reference_captcha_logs_channel(),
+        ReferenceKind::CaptchaRole => lang.reference_captcha_role(),
+        ReferenceKind::CaptchaVerifiedRole => lang.reference_captcha_verified_role(),
+        ReferenceKind::ModeratorRole => lang.reference_moderator_role(),
+        ReferenceKind::ReactionSpamTrustedRole => lang.reference_reaction_spam_trusted_role(),
+        ReferenceKind::LinkFilterTrustedRole => lang.reference_link_filter_trusted_role(),
+        ReferenceKind::VoiceLogsChannel => lang.reference_voice_logs_channel(),
+        ReferenceKind::VoiceTrustedRole => lang.reference_voice_trusted_role(),
+        ReferenceKind::AutomodIgnoredRole => lang.reference_automod_ignored_role(),
+        ReferenceKind::ActivityRole => lang.reference_activity_role(),
+        ReferenceKind::WatchChannel => lang.reference_watch_channel(),
+        ReferenceKind::PinsLogsChannel => lang.reference_pins_logs_channel(),
+        ReferenceKind::RoleLogsChannel => lang.reference_role_logs_channel(),
+        ReferenceKind::QuarantineRole => lang.reference_quarantine_role(),
+        ReferenceKind::MuteRole => lang.reference_mute_role(),
+        ReferenceKind::RaidAlertRole => lang.reference_raid_alert_role(),
+        ReferenceKind::ReportsChannel => lang.reference_reports_channel(),
+        ReferenceKind::MilestonesChannel => lang.reference_milestones_channel(),
+        ReferenceKind::LogRouteChannel => lang.reference_log_route_channel(),
+        ReferenceKind::RaidModeReviewChannel => lang.reference_raid_mode_review_channel(),
+        ReferenceKind::RaidModeMemberRole => lang.reference_raid_mode_member_role(),
+        ReferenceKind::AnnouncementChannel => lang.reference_announcement_channel(),
+        ReferenceKind::GraceTrustedRole => lang.reference_grace_trusted_role(),
+        ReferenceKind::TagManagerRole => lang.reference_tag_manager_role(),
+        ReferenceKind::RoleLogsIgnoredRole => lang.reference_role_logs_ignored_role(),
+        ReferenceKind::SkipConfirmationRole => lang.reference_skip_confirmation_role(),
+        ReferenceKind::SanctionRole => lang.reference_sanction_role(),
+        ReferenceKind::ChannelRuleChannel => lang.reference_channel_rule_channel(),
+        ReferenceKind::PinsIgnoredChannel => lang.reference_pins_ignored_channel(),
+    }
+}
@@ -0,0 +1,190 @@
+//! Messages reported by members.
+//!
+//! Members report messages with the "Report to moderators" context menu
+//! command (see the [`report`] command). Each report is stored as a
+//! [`Report`] in the database, and an alert is sent in the channel configured
+//! in [`GuildConfig::reports_chan`] with buttons to resolve the report, delete
+//! the message or mute its author.
+//!
+//! Reports of a message that already has an unresolved report created less
+//! than [`MERGE_WINDOW`] ago are merged into this report, and the alert is
+//! updated with the new reports count. The reporters are stored in the
+//! database but are never shown in the alert.
+//!
+//! Members can send at most [`MAX_REPORTS`] reports every [`REPORTS_WINDOW`].
+//!
+//! [`report`]: crate::interaction::command::report
+//! [`GuildConfig::reports_chan`]: raidprotect_model::database::model::GuildConfig::reports_chan
+
+use raidprotect_model::{
+    cache::model::{interaction::PendingReport, message::DeletedMessage},
+    database::model::{ObjectId, Report, Reporter},
+};
+use time::{Duration, OffsetDateTime};
+use tracing::warn;
+use twilight_model::{
+    application::component::{button::ButtonStyle, ActionRow, Button, Component},
+    id::{
+        marker::{ChannelMarker, GuildMarker, UserMarker},
+        Id,
+    },
+};
+
+use crate::{
+    cluster::ClusterState,
+    interaction::{embed, util::CustomId},
+    translations::Lang,
+};
+
+/// Maximum number of reports a member can send during [`REPORTS_WINDOW`].
+pub const MAX_REPORTS: u32 = 3;
+
+/// Duration during which the reports of a member are counted.
+pub const REPORTS_WINDOW: Duration = Duration::minutes(10);
+
+/// Duration during which the reports of the same message are merged.
+pub const MERGE_WINDOW: Duration = Duration::hours(1);
+
+/// Outcome of a submitted report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportOutcome {
+    /// A new report has been created.
+    Created,
+    /// The report has been merged into an existing report.
+    Merged,
+    /// The member has already reported the message.
+    AlreadyReported,
+}
+
+/// Check whether a member is allowed to send a new report.
+pub async fn check_rate_limit(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+) -> Result<bool, anyhow::Error> {
+    let key = format!("report:{guild_id}:{user_id}");
+
+    state
+        .cache
+        .rate_limit(&key, MAX_REPORTS, REPORTS_WINDOW)
+        .await
+}
+
+/// Submit a report and send or update the alert in the reports channel.
+pub async fn submit(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    channel: Id<ChannelMarker>,
+    pending: PendingReport,
+    reason: Option<String>,
+    lang: Lang,
+) -> Result<ReportOutcome, anyhow::Error> {
+    let now = OffsetDateTime::now_utc();
+    let reporter = Reporter {
+        user_id: pending.author_id,
+        reason,
+        date: now,
+    };
+
+    // The message may have been deleted since the command has been used.
+    let deleted = state
+        .cache
+        .get::<DeletedMessage>(&pending.message_id)
+        .await?
+        .is_some();
+
+    if let Some(mut report) = state
+        .database
+        .find_open_report(guild_id, pending.message_id, now - MERGE_WINDOW)
+        .await?
+    {
+        if !report.add_reporter(reporter) {
+            return Ok(ReportOutcome::AlreadyReported);
+        }
+
+        state.database.update_report(&report).await?;
+
+        if let Some(alert_id) = report.alert_id {
+            let embed = embed::report::alert(lang, &report, deleted);
+
+            if let Err(error) = state
+                .http
+                .update_message(channel, alert_id)
+                .embeds(Some(&[embed]))?
+                .exec()
+                .await
+            {
+                warn!(error = ?error, guild = ?guild_id, "failed to update report alert");
+            }
+        }
+
+        return Ok(ReportOutcome::Merged);
+    }
+
+    let mut report = Report {
+        id: None,
+        guild_id,
+        channel_id: pending.channel_id,
+        message_id: pending.message_id,
+        author: pending.user,
+        content: pending.content,
+        reporters: vec![reporter],
+        alert_id: None,
+        created_at: now,
+        resolved_by: None,
+    };
+
+    let id = state.database.create_report(&report).await?;
+    report.id = Some(id);
+
+    let embed = embed::report::alert(lang, &report, deleted);
+    let alert = state
+        .cache_http(guild_id)
+        .create_message(channel)
+        .await?
+        .embeds(&[embed])?
+        .components(&alert_components(id, lang))?
+        .exec()
+        .await?
+        .model()
+        .await?;
+
+    report.alert_id = Some(alert.id);
+    state.database.update_report(&report).await?;
+
+    Ok(ReportOutcome::Created)
+}
+
+/// Buttons of a report alert.
+pub fn alert_components(id: ObjectId, lang: Lang) -> Vec<Component> {
+    let button = |name: &str, label: &str, style| {
+        Component::Button(Button {
+            custom_id: Some(CustomId::new(name, id.to_hex()).to_string()),
+            disabled: false,
+            emoji: None,
+            label: Some(label.to_owned()),
+            style,
+            url: None,
+        })
+    };
+
+    vec![Component::ActionRow(ActionRow {
+        components: vec![
+            button(
+                "report-resolve",
+                lang.report_button_resolve(),
+                ButtonStyle::Success,
+            ),
+            button(
+                "report-delete",
+                lang.report_button_delete(),
+                ButtonStyle::Secondary,
+            ),
+            button(
+                "report-sanction",
+                lang.report_button_sanction(),
+                ButtonStyle::Danger,
+            ),
+        ],
+    })]
+}
@@ -0,0 +1,598 @@
+//! Restoration of deleted channels and roles.
+//!
+//! The channels and roles of every guild are saved daily in a
+//! [`StructureSnapshot`] (see [`snapshot_batch`]). After a nuke, the
+//! administrators can compare the latest snapshot with the current state of
+//! the guild and recreate the deleted items with the `/restore` command.
+//!
+//! Items are recreated with the [`bulk`] utility, one at a time and in the
+//! order of the snapshot: categories must exist before the channels they
+//! contain, and roles are created by ascending position.
+//!
+//! Recreated items get a new id, so the references to the old id in the
+//! guild configuration are updated. Permission overwrites of recreated
+//! channels that target a role that no longer exists are dropped, since
+//! Discord rejects them.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+};
+
+use async_trait::async_trait;
+
+use raidprotect_model::{
+    cache::model::{
+        interaction::{PendingRestore, RestoreKind},
+        job::ScheduledJob,
+    },
+    database::{
+        model::{ChannelSnapshot, RoleSnapshot, StructureSnapshot},
+        remap_channel_references, remap_role_references,
+    },
+};
+use time::{Duration, OffsetDateTime};
+use tracing::{debug, info, warn};
+use twilight_http::request::AuditLogReason;
+use twilight_model::{
+    channel::{
+        permission_overwrite::{PermissionOverwrite, PermissionOverwriteType},
+        ChannelType,
+    },
+    id::{
+        marker::{ChannelMarker, GuildMarker, RoleMarker},
+        Id,
+    },
+};
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    interaction::{
+        embed,
+        response::{InteractionResponder, ProgressMessage},
+    },
+    translations::Lang,
+    util::{
+        bulk::{self, BulkOptions, BulkProgress, CancelToken, ProgressReporter},
+        ratelimit::Priority,
+    },
+};
+
+/// Interval between two snapshots of a guild.
+pub const SNAPSHOT_INTERVAL: Duration = Duration::days(1);
+
+/// Number of guilds processed in a single batch of snapshots.
+const SNAPSHOT_BATCH_SIZE: usize = 20;
+
+/// Delay between two batches of snapshots.
+const SNAPSHOT_BATCH_INTERVAL: Duration = Duration::seconds(10);
+
+/// Key used to ensure a single snapshot cycle runs at a time.
+const SNAPSHOT_CYCLE_KEY: &str = "structure-snapshots";
+
+/// Number of restored items between two progress updates.
+const PROGRESS_INTERVAL: usize = 5;
+
+/// Options of the bulk operations recreating the items.
+const BULK_OPTIONS: BulkOptions = BulkOptions {
+    concurrency: 1,
+    progress_interval: PROGRESS_INTERVAL,
+};
+
+/// Result of the restoration of a single item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestoredItem {
+    /// Name of the item.
+    pub name: String,
+    /// Whether the item has been recreated.
+    pub restored: bool,
+}
+
+/// Take a snapshot of a batch of guilds.
+///
+/// Guilds are processed in ascending id order, starting after `cursor`. The
+/// job for the next batch is scheduled before taking the snapshots, and the
+/// next cycle is scheduled once all guilds have been processed.
+pub async fn snapshot_batch(
+    state: &ClusterState,
+    cursor: Option<Id<GuildMarker>>,
+) -> Result<(), anyhow::Error> {
+    // A new cycle may be scheduled when the bot starts while another cycle
+    // is running.
+    let cycle_interval = SNAPSHOT_INTERVAL - Duration::hours(1);
+    if cursor.is_none()
+        && !state
+            .cache
+            .throttle(SNAPSHOT_CYCLE_KEY, cycle_interval)
+            .await?
+    {
+        return Ok(());
+    }
+
+    let mut guilds = state.cache.guild_ids().await?;
+    guilds.sort_unstable();
+    let guilds = guilds
+        .into_iter()
+        .filter(|id| cursor.map_or(true, |cursor| *id > cursor))
+        .take(SNAPSHOT_BATCH_SIZE)
+        .collect::<Vec<_>>();
+
+    let now = OffsetDateTime::now_utc();
+    let next = match guilds.last() {
+        Some(last) => (
+            ScheduledJob::StructureSnapshots {
+                cursor: Some(*last),
+            },
+            now + SNAPSHOT_BATCH_INTERVAL,
+        ),
+        None => {
+            info!("structure snapshots completed");
+
+            (
+                ScheduledJob::StructureSnapshots { cursor: None },
+                now + SNAPSHOT_INTERVAL,
+            )
+        }
+    };
+    state.cache.schedule_job(&next.0, next.1).await?;
+
+    for guild_id in guilds {
+        if let Err(error) = snapshot(state, guild_id).await {
+            debug!(error = ?error, guild = ?guild_id, "failed to take structure snapshot");
+        }
+    }
+
+    Ok(())
+}
+
+/// Take a snapshot of the channels and roles of a guild.
+async fn snapshot(state: &ClusterState, guild_id: Id<GuildMarker>) -> Result<(), anyhow::Error> {
+    let channels = state
+        .http
+        .guild_channels(guild_id)
+        .exec()
+        .await?
+        .models()
+        .await?;
+    let roles = state.http.roles(guild_id).exec().await?.models().await?;
+
+    let channels = channels
+        .into_iter()
+        .filter(|channel| {
+            !matches!(
+                channel.kind,
+                ChannelType::GuildPublicThread
+                    | ChannelType::GuildPrivateThread
+                    | ChannelType::GuildNewsThread
+            )
+        })
+        .map(|channel| ChannelSnapshot {
+            id: channel.id,
+            name: channel.name.unwrap_or_default(),
+            kind: channel.kind,
+            position: channel.position.map(i64::from),
+            parent_id: channel.parent_id,
+            topic: channel.topic,
+            nsfw: channel.nsfw.unwrap_or_default(),
+            rate_limit_per_user: channel.rate_limit_per_user,
+            permission_overwrites: channel.permission_overwrites.unwrap_or_default(),
+        })
+        .collect();
+
+    let roles = roles
+        .into_iter()
+        .filter(|role| !role.managed && role.id.cast() != guild_id)
+        .map(|role| RoleSnapshot {
+            id: role.id,
+            name: role.name,
+            color: role.color,
+            hoist: role.hoist,
+            mentionable: role.mentionable,
+            permissions: role.permissions,
+            position: role.position,
+        })
+        .collect();
+
+    let snapshot = StructureSnapshot {
+        id: None,
+        guild_id,
+        date: OffsetDateTime::now_utc(),
+        channels,
+        roles,
+    };
+
+    state.database.create_structure_snapshot(&snapshot).await
+}
+
+/// Get the channels of a snapshot that no longer exist.
+///
+/// Categories are returned first, so that they are recreated before the
+/// channels they contain.
+pub fn missing_channels<'a>(
+    snapshot: &'a StructureSnapshot,
+    current: &HashSet<Id<ChannelMarker>>,
+) -> Vec<&'a ChannelSnapshot> {
+    let mut missing = snapshot
+        .channels
+        .iter()
+        .filter(|channel| !current.contains(&channel.id))
+        .collect::<Vec<_>>();
+
+    missing.sort_by_key(|channel| (channel.kind != ChannelType::GuildCategory, channel.position));
+
+    missing
+}
+
+/// Get the roles of a snapshot that no longer exist.
+///
+/// Roles are sorted by ascending position.
+pub fn missing_roles<'a>(
+    snapshot: &'a StructureSnapshot,
+    current: &HashSet<Id<RoleMarker>>,
+) -> Vec<&'a RoleSnapshot> {
+    let mut missing = snapshot
+        .roles
+        .iter()
+        .filter(|role| !current.contains(&role.id))
+        .collect::<Vec<_>>();
+
+    missing.sort_by_key(|role| role.position);
+
+    missing
+}
+
+/// Restore the items of a [`PendingRestore`].
+///
+/// The progress is reported by updating the original response of the
+/// interaction, or a new message if the interaction token is about to expire
+/// (see [`ProgressMessage`]).
+pub async fn run(
+    state: ClusterState,
+    pending: PendingRestore,
+    responder: InteractionResponder,
+    lang: Lang,
+) {
+    let mut progress = ProgressMessage::new(responder);
+    let result = match pending.kind {
+        RestoreKind::Channels => restore_channels(&state, &pending, &mut progress, lang).await,
+        RestoreKind::Roles => restore_roles(&state, &pending, &mut progress, lang).await,
+    };
+
+    let embed = match result {
+        Ok(items) => embed::restore::summary(lang, pending.kind, &items),
+        Err(error) => {
+            warn!(error = ?error, guild = ?pending.guild_id, "failed to restore snapshot");
+
+            embed::restore::failed(lang)
+        }
+    };
+
+    progress.update(&state, embed).await;
+}
+
+/// Get the snapshot of a [`PendingRestore`].
+async fn pending_snapshot(
+    state: &ClusterState,
+    pending: &PendingRestore,
+) -> Result<StructureSnapshot, anyhow::Error> {
+    let id = pending.snapshot_id;
+
+    match state.database.get_structure_snapshot(id).await? {
+        Some(snapshot) if snapshot.guild_id == pending.guild_id => Ok(snapshot),
+        _ => anyhow::bail!("structure snapshot {id} not found"),
+    }
+}
+
+/// Recreate the deleted channels.
+async fn restore_channels(
+    state: &ClusterState,
+    pending: &PendingRestore,
+    progress: &mut ProgressMessage,
+    lang: Lang,
+) -> Result<Vec<RestoredItem>, anyhow::Error> {
+    let guild_id = pending.guild_id;
+    let snapshot = pending_snapshot(state, pending).await?;
+
+    let current = state
+        .cache
+        .guild_channels(guild_id)
+        .await?
+        .into_iter()
+        .map(|channel| channel.id)
+        .collect::<HashSet<_>>();
+    let roles = state
+        .cache
+        .guild_roles(guild_id)
+        .await?
+        .into_iter()
+        .map(|role| role.id)
+        .collect::<HashSet<_>>();
+
+    let missing = missing_channels(&snapshot, &current);
+    let reason = lang.restore_reason(pending.author_id);
+    let remapped = Mutex::new(HashMap::new());
+
+    let mut reporter = RestoreProgress {
+        state,
+        message: progress,
+        lang,
+    };
+    bulk::run(
+        missing.iter().copied(),
+        BULK_OPTIONS,
+        &CancelToken::new(),
+        &mut reporter,
+        |channel| {
+            let (current, roles, reason, remapped) = (&current, &roles, &reason, &remapped);
+
+            state.rate_guard.send(Priority::Background, async move {
+                // The parent category may have been recreated with a new id.
+                let parent_id = channel.parent_id.and_then(|parent_id| {
+                    let remapped = remapped.lock().unwrap().get(&parent_id).copied();
+
+                    remapped.or_else(|| current.contains(&parent_id).then_some(parent_id))
+                });
+
+                let new_id =
+                    create_channel(state, guild_id, channel, parent_id, roles, reason).await?;
+                remapped.lock().unwrap().insert(channel.id, new_id);
+
+                Ok(())
+            })
+        },
+    )
+    .await;
+
+    let remapped = remapped.into_inner().unwrap();
+    let items = missing
+        .iter()
+        .map(|channel| RestoredItem {
+            name: channel.name.clone(),
+            restored: remapped.contains_key(&channel.id),
+        })
+        .collect();
+
+    let mut config = database::guild_config(state, guild_id).await?;
+    let mut updated = false;
+    for (old, new) in remapped {
+        updated |= remap_channel_references(&mut config, old, new);
+    }
+
+    if updated {
+        database::update_guild_config(state, &config).await?;
+    }
+
+    Ok(items)
+}
+
+/// Recreate a single channel.
+async fn create_channel(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    channel: &ChannelSnapshot,
+    parent_id: Option<Id<ChannelMarker>>,
+    roles: &HashSet<Id<RoleMarker>>,
+    reason: &str,
+) -> Result<Id<ChannelMarker>, anyhow::Error> {
+    let overwrites = channel
+        .permission_overwrites
+        .iter()
+        .filter(|overwrite| {
+            overwrite.kind != PermissionOverwriteType::Role || roles.contains(&overwrite.id.cast())
+        })
+        .cloned()
+        .collect::<Vec<PermissionOverwrite>>();
+
+    let mut request = state
+        .http
+        .create_guild_channel(guild_id, &channel.name)?
+        .kind(channel.kind)
+        .nsfw(channel.nsfw)
+        .permission_overwrites(&overwrites);
+
+    if let Some(parent_id) = parent_id {
+        request = request.parent_id(parent_id);
+    }
+    if let Some(position) = channel.position {
+        request = request.position(position.max(0) as u64);
+    }
+    if let Some(topic) = &channel.topic {
+        request = request.topic(topic)?;
+    }
+    if let Some(rate_limit) = channel.rate_limit_per_user {
+        request = request.rate_limit_per_user(rate_limit)?;
+    }
+
+    let created = request.reason(reason)?.exec().await?.model().await?;
+
+    Ok(created.id)
+}
+
+/// Recreate the deleted roles.
+async fn restore_roles(
+    state: &ClusterState,
+    pending: &PendingRestore,
+    progress: &mut ProgressMessage,
+    lang: Lang,
+) -> Result<Vec<RestoredItem>, anyhow::Error> {
+    let guild_id = pending.guild_id;
+    let snapshot = pending_snapshot(state, pending).await?;
+
+    let current = state
+        .cache
+        .guild_roles(guild_id)
+        .await?
+        .into_iter()
+        .map(|role| role.id)
+        .collect::<HashSet<_>>();
+
+    let missing = missing_roles(&snapshot, &current);
+    let reason = lang.restore_reason(pending.author_id);
+    let remapped = Mutex::new(HashMap::new());
+
+    let mut reporter = RestoreProgress {
+        state,
+        message: progress,
+        lang,
+    };
+    bulk::run(
+        missing.iter().copied(),
+        BULK_OPTIONS,
+        &CancelToken::new(),
+        &mut reporter,
+        |role| {
+            let (reason, remapped) = (&reason, &remapped);
+
+            state.rate_guard.send(Priority::Background, async move {
+                let created = state
+                    .http
+                    .create_role(guild_id)
+                    .name(&role.name)
+                    .color(role.color)
+                    .hoist(role.hoist)
+                    .mentionable(role.mentionable)
+                    .permissions(role.permissions)
+                    .reason(reason)?
+                    .exec()
+                    .await?
+                    .model()
+                    .await?;
+                remapped.lock().unwrap().insert(role.id, created.id);
+
+                Ok(())
+            })
+        },
+    )
+    .await;
+
+    let remapped = remapped.into_inner().unwrap();
+    let mut positions = Vec::new();
+    let mut items = Vec::with_capacity(missing.len());
+
+    for role in &missing {
+        if let Some(new_id) = remapped.get(&role.id) {
+            positions.push((*new_id, role.position.max(1) as u64));
+        }
+
+        items.push(RestoredItem {
+            name: role.name.clone(),
+            restored: remapped.contains_key(&role.id),
+        });
+    }
+
+    // Positions are restored on a best-effort basis, the bot cannot move
+    // roles above its highest role.
+    if !positions.is_empty() {
+        if let Err(error) = state
+            .http
+            .update_role_positions(guild_id, &positions)
+            .exec()
+            .await
+        {
+            debug!(error = ?error, guild = ?guild_id, "failed to restore role positions");
+        }
+    }
+
+    let mut config = database::guild_config(state, guild_id).await?;
+    let mut updated = false;
+    for (old, new) in remapped {
+        updated |= remap_role_references(&mut config, old, new);
+    }
+
+    if updated {
+        database::update_guild_config(state, &config).await?;
+    }
+
+    Ok(items)
+}
+
+/// Progress of a restoration, shown in the progress message.
+struct RestoreProgress<'a> {
+    state: &'a ClusterState,
+    message: &'a mut ProgressMessage,
+    lang: Lang,
+}
+
+#[async_trait]
+impl ProgressReporter for RestoreProgress<'_> {
+    async fn report(&mut self, progress: BulkProgress) {
+        let embed = embed::restore::progress(self.lang, progress.done, progress.total);
+
+        self.message.update(self.state, embed).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::guild::Permissions;
+
+    use super::*;
+
+    fn channel(id: u64, kind: ChannelType, position: i64) -> ChannelSnapshot {
+        ChannelSnapshot {
+            id: Id::new(id),
+            name: format!("channel-{id}"),
+            kind,
+            position: Some(position),
+            parent_id: None,
+            topic: None,
+            nsfw: false,
+            rate_limit_per_user: None,
+            permission_overwrites: Vec::new(),
+        }
+    }
+
+    fn role(id: u64, position: i64) -> RoleSnapshot {
+        RoleSnapshot {
+            id: Id::new(id),
+            name: format!("role-{id}"),
+            color: 0,
+            hoist: false,
+            mentionable: false,
+            permissions: Permissions::empty(),
+            position,
+        }
+    }
+
+    fn snapshot() -> StructureSnapshot {
+        StructureSnapshot {
+            id: None,
+            guild_id: Id::new(1),
+            date: OffsetDateTime::UNIX_EPOCH,
+            channels: vec![
+                channel(10, ChannelType::GuildText, 0),
+                channel(11, ChannelType::GuildCategory, 1),
+                channel(12, ChannelType::GuildText, 2),
+                channel(13, ChannelType::GuildCategory, 0),
+            ],
+            roles: vec![role(20, 3), role(21, 1), role(22, 2)],
+        }
+    }
+
+    #[test]
+    fn test_missing_channels() {
+        let snapshot = snapshot();
+        let current = HashSet::from([Id::new(12)]);
+
+        let missing = missing_channels(&snapshot, &current)
+            .into_iter()
+            .map(|channel| channel.id.get())
+            .collect::<Vec<_>>();
+
+        assert_eq!(missing, vec![13, 11, 10]);
+    }
+
+    #[test]
+    fn test_missing_roles() {
+        let snapshot = snapshot();
+        let current = HashSet::from([Id::new(22)]);
+
+        let missing = missing_roles(&snapshot, &current)
+            .into_iter()
+            .map(|role| role.id.get())
+            .collect::<Vec<_>>();
+
+        assert_eq!(missing, vec![21, 20]);
+    }
+}
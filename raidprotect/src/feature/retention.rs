@@ -0,0 +1,154 @@
+//! Retention of the moderation logs.
+//!
+//! Guilds may configure a retention period for their moderation logs (see
+//! [`GuildConfig::modlog_retention`]). Older moderation logs are deleted
+//! daily by the [`ScheduledJob::ModlogCleanup`] job.
+//!
+//! The mute moderation logs of members that are still muted are kept until
+//! the mute expires, since they are needed to know why the member is muted.
+//! The quarantine moderation logs of members that are still quarantined are
+//! kept for the same reason, until the member is released.
+//!
+//! [`GuildConfig::modlog_retention`]: raidprotect_model::database::model::GuildConfig::modlog_retention
+
+use raidprotect_model::{
+    cache::model::job::ScheduledJob,
+    database::model::{GuildConfig, KeptModlogs, ModlogType},
+};
+use time::{Duration, OffsetDateTime};
+use tracing::{error, info};
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::cluster::ClusterState;
+
+/// Interval between two cleanups.
+pub const CLEANUP_INTERVAL: Duration = Duration::days(1);
+
+/// Number of guilds processed in a single batch.
+const CLEANUP_BATCH_SIZE: i64 = 50;
+
+/// Delay between two batches.
+const CLEANUP_BATCH_INTERVAL: Duration = Duration::seconds(5);
+
+/// Key used to ensure a single cleanup runs at a time.
+const CLEANUP_CYCLE_KEY: &str = "modlog-cleanup";
+
+/// Maximum retention period, in days.
+const MAX_RETENTION: u16 = 3650;
+
+/// Clean up the moderation logs of a batch of guilds.
+///
+/// Guilds are processed in ascending id order, starting after `cursor`. The
+/// job for the next batch is scheduled before the cleanup, and the next
+/// cleanup is scheduled once all guilds have been processed.
+pub async fn cleanup_batch(
+    state: &ClusterState,
+    cursor: Option<Id<GuildMarker>>,
+) -> Result<(), anyhow::Error> {
+    // A new cleanup may be scheduled when the bot starts while another
+    // cleanup is running.
+    let cycle_interval = CLEANUP_INTERVAL - Duration::hours(1);
+    if cursor.is_none()
+        && !state
+            .cache
+            .throttle(CLEANUP_CYCLE_KEY, cycle_interval)
+            .await?
+    {
+        return Ok(());
+    }
+
+    let mut guilds = Vec::new();
+    let mut results = state
+        .database
+        .find_guilds_with_modlog_retention(cursor, CLEANUP_BATCH_SIZE)
+        .await?;
+
+    while results.advance().await? {
+        guilds.push(results.deserialize_current()?);
+    }
+
+    let now = OffsetDateTime::now_utc();
+    let next = match guilds.last() {
+        Some(last) => (
+            ScheduledJob::ModlogCleanup {
+                cursor: Some(last.id),
+            },
+            now + CLEANUP_BATCH_INTERVAL,
+        ),
+        None => (
+            ScheduledJob::ModlogCleanup { cursor: None },
+            now + CLEANUP_INTERVAL,
+        ),
+    };
+    state.cache.schedule_job(&next.0, next.1).await?;
+
+    for config in guilds {
+        if let Err(error) = cleanup(state, &config, now).await {
+            error!(error = ?error, guild = ?config.id, "failed to clean up modlogs");
+        }
+    }
+
+    Ok(())
+}
+
+/// Delete the expired moderation logs of a guild.
+async fn cleanup(
+    state: &ClusterState,
+    config: &GuildConfig,
+    now: OffsetDateTime,
+) -> Result<(), anyhow::Error> {
+    let before = match retention_limit(config, now) {
+        Some(before) => before,
+        None => return Ok(()),
+    };
+
+    let kept = [
+        KeptModlogs {
+            kind: ModlogType::Mute,
+            users: state.database.find_muted_users(config.id).await?,
+        },
+        KeptModlogs {
+            kind: ModlogType::Quarantine,
+            users: state.database.find_quarantined_users(config.id).await?,
+        },
+    ];
+    let deleted = state
+        .database
+        .delete_modlogs_before(config.id, before, &kept)
+        .await?;
+
+    if deleted > 0 {
+        info!(guild = ?config.id, deleted = deleted, "cleaned up expired modlogs");
+    }
+
+    Ok(())
+}
+
+/// Get the date before which the moderation logs of a guild are deleted.
+fn retention_limit(config: &GuildConfig, now: OffsetDateTime) -> Option<OffsetDateTime> {
+    let days = config.modlog_retention?.clamp(1, MAX_RETENTION);
+
+    Some(now - Duration::days(days.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retention_limit() {
+        let now = OffsetDateTime::UNIX_EPOCH + Duration::days(10_000);
+        let mut config = GuildConfig::new(Id::new(1));
+
+        assert_eq!(retention_limit(&config, now), None);
+
+        config.modlog_retention = Some(30);
+        assert_eq!(
+            retention_limit(&config, now),
+            Some(now - Duration::days(30))
+        );
+
+        config.modlog_retention = Some(0);
+        assert_eq!(retention_limit(&config, now), Some(now - Duration::days(1)));
+    }
+}
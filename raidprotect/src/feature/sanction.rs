@@ -0,0 +1,757 @@
+//! Sanctions execution.
+//!
+//! Sanctions issued by the moderation commands are applied with [`execute`],
+//! which performs the action on Discord and records it in the modlogs. The
+//! way a sanction is applied may depend on the guild configuration: mutes use
+//! either a Discord timeout or a role (see [`MuteMode`]), but are recorded the
+//! same way in both cases.
+//!
+//! ## Mute role
+//! When members are muted with a role, an [`ActiveMute`] is stored in the
+//! database. It is used to remove the role once the mute expires (with a
+//! [`ScheduledJob::Unmute`] job), and to add the role again if the member
+//! leaves and rejoins the guild while muted.
+//!
+//! Timeouts are stored the same way, so that they can be reapplied if they
+//! are cleared by someone else than a moderator.
+//!
+//! ## Quarantine
+//! Quarantined members lose their roles in favor of the configured quarantine
+//! role. Their previous roles are stored in a [`QuarantineState`] before being
+//! removed, and restored once the member is released. Managed roles cannot be
+//! removed and are kept during the quarantine. Roles deleted in the meantime
+//! are skipped when the member is released (see [`SanctionOutcome`]).
+//!
+//! ## Shadow sanctions
+//! Sensitive cases can be handled with shadow sanctions (see
+//! [`Sanction::shadow`]). They are applied and recorded like other sanctions,
+//! but are only notified in the logs channel, which is restricted to the
+//! staff. They are never sent to the sanctioned user or to the outbound
+//! webhook of the guild.
+//!
+//! ## Private messages
+//! The sanctioned user is notified by private message. Users removed from the
+//! guild are notified before the sanction is applied, since private messages
+//! cannot be sent once they no longer share a guild with the bot. Other users
+//! are notified once the sanction has been applied (see [`NotifyOrder`]).
+//! Shadow sanctions and pre-emptive bans are never notified.
+//!
+//! The user may not accept private messages, so failures do not prevent the
+//! sanction from being applied, and the message is abandoned if it is not
+//! sent within [`DM_TIMEOUT`]. The outcome is recorded in the modlogs (see
+//! [`Modlog::dm_status`]) so that moderators can check whether the user has
+//! been notified.
+//!
+//! Guilds can disable the private messages of some sanctions, or of every
+//! sanction (see [`ModerationConfig::dm_enabled`]). The guild-wide setting
+//! takes precedence over the per-sanction one, and sanctions applied while it
+//! is enabled are marked in the modlogs with [`Modlog::dm_disabled`].
+//!
+//! ## Logs
+//! Every sanction is notified in the logs channel with the delivery status of
+//! its private message. Shadow sanctions have a dedicated embed, since they
+//! are not notified anywhere else.
+//!
+//! ## Announcements
+//! Guilds can announce some sanctions in a public channel (see
+//! [`ModerationConfig::announcement`]). Announcements are sent in the language
+//! of the guild, respect the anonymous moderation setting and never include
+//! the notes of the modlog. Shadow sanctions are never announced.
+//!
+//! ## Ban synchronization
+//! Bans issued with [`Sanction::sync`] are mirrored in the linked guilds once
+//! recorded (see the [`ban_sync`] feature module). Mirrored bans have a
+//! [`Sanction::synced_from`] origin and are never synchronized again.
+//!
+//! ## Audit log
+//! Sanctions are applied by RaidProtect, so the moderator is included in the
+//! audit log reason. This lets the staff identify the moderator even when
+//! the moderation is anonymous (see [`ModerationConfig::anonymize`]).
+//!
+//! [`ModerationConfig::announcement`]: raidprotect_model::database::model::ModerationConfig::announcement
+//! [`ModerationConfig::anonymize`]: raidprotect_model::database::model::ModerationConfig::anonymize
+//! [`ModerationConfig::dm_enabled`]: raidprotect_model::database::model::ModerationConfig::dm_enabled
+//! [`ban_sync`]: crate::feature::ban_sync
+
+use std::time::Duration as StdDuration;
+
+use anyhow::Context;
+use raidprotect_model::{
+    cache::{
+        discord::{CachedGuild, CachedRole},
+        model::{job::ScheduledJob, write_queue::PendingWrite},
+    },
+    database::model::{
+        ActiveMute, DmStatus, GuildConfig, LogCategory, Modlog, ModlogType, ModlogUser, MuteMode,
+        ObjectId, QuarantineState, StatsEvent, SyncOrigin,
+    },
+};
+use time::{Duration, OffsetDateTime};
+use tracing::{debug, instrument, warn};
+use twilight_http::request::AuditLogReason;
+use twilight_model::{
+    id::{
+        marker::{GuildMarker, RoleMarker, UserMarker},
+        Id,
+    },
+    util::Timestamp,
+};
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    feature::{self, watch::WatchEvent},
+    interaction::{embed, util::GuildConfigExt},
+    translations::Lang,
+    util::{http, logs},
+};
+
+/// Maximum duration of a Discord timeout.
+pub const MAX_TIMEOUT: Duration = Duration::days(28);
+
+/// Maximum length of an audit log reason.
+const MAX_AUDIT_REASON_LENGTH: usize = 512;
+
+/// Maximum duration of the private message sent to the sanctioned user.
+const DM_TIMEOUT: StdDuration = StdDuration::from_secs(3);
+
+/// Discord error code returned when a user does not accept private messages.
+const CANNOT_MESSAGE_USER: u64 = 50007;
+
+/// Sanction applied to a member.
+#[derive(Debug, Clone)]
+pub struct Sanction {
+    /// Guild where the sanction is applied.
+    pub guild_id: Id<GuildMarker>,
+    /// Type of sanction.
+    pub kind: ModlogType,
+    /// Sanctioned user.
+    pub user: ModlogUser,
+    /// Moderator that issued the sanction.
+    pub moderator: ModlogUser,
+    /// Optional reason provided by the moderator.
+    pub reason: Option<String>,
+    /// Optional notes provided by the moderator.
+    pub notes: Option<String>,
+    /// Duration of the sanction, for mutes.
+    ///
+    /// If [`None`], the mute is permanent. Timeouts are limited to
+    /// [`MAX_TIMEOUT`].
+    pub duration: Option<Duration>,
+    /// Whether the user is not a member of the guild, for pre-emptive bans.
+    pub pre_emptive: bool,
+    /// Whether the confirmation has been skipped although the user has just
+    /// been sanctioned.
+    pub confirmation_skipped: bool,
+    /// Whether the sanction is a shadow sanction.
+    pub shadow: bool,
+    /// Whether the ban is synchronized with the linked guilds.
+    pub sync: bool,
+    /// Origin of the ban, if synchronized from a linked guild.
+    pub synced_from: Option<SyncOrigin>,
+}
+
+/// Outcome of an applied sanction.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SanctionOutcome {
+    /// Id of the recorded modlog.
+    ///
+    /// The id is generated before the modlog is written, since the write may
+    /// be delayed if the database is unavailable.
+    pub case_id: Option<ObjectId>,
+    /// Roles that could not be restored because they have been deleted, for
+    /// releases.
+    pub skipped_roles: Vec<Id<RoleMarker>>,
+}
+
+/// Moment the sanctioned user is notified by private message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NotifyOrder {
+    /// The user is notified before the sanction is applied.
+    Before,
+    /// The user is notified once the sanction has been applied.
+    After,
+}
+
+impl NotifyOrder {
+    /// Get the moment the user is notified for a type of sanction.
+    ///
+    /// Users removed from the guild are notified first, since private
+    /// messages can no longer be sent once they have left.
+    fn of(kind: ModlogType) -> Self {
+        match kind.is_destructive() {
+            true => Self::Before,
+            false => Self::After,
+        }
+    }
+}
+
+/// Apply a sanction and record it in the modlogs.
+#[instrument(skip_all, fields(guild_id = sanction.guild_id.get(), kind = ?sanction.kind))]
+pub async fn execute(
+    state: &ClusterState,
+    config: &GuildConfig,
+    sanction: Sanction,
+) -> Result<SanctionOutcome, anyhow::Error> {
+    let guild_id = sanction.guild_id;
+    let user_id = sanction.user.id;
+    let moderator_id = sanction.moderator.id;
+    let audit_reason = audit_reason(
+        config.lang(),
+        &sanction.moderator,
+        sanction.reason.as_deref(),
+    );
+    let reason = Some(audit_reason.as_str());
+    let mute_mode = config.moderation.mute_mode;
+    let quarantine_role = config.moderation.quarantine_role;
+    let case_id = ObjectId::new();
+    let mut outcome = SanctionOutcome {
+        case_id: Some(case_id),
+        ..Default::default()
+    };
+
+    let notified = !sanction.shadow && !sanction.pre_emptive;
+    let order = NotifyOrder::of(sanction.kind);
+    let mut dm_status = None;
+
+    if notified && order == NotifyOrder::Before {
+        dm_status = Some(notify_user(state, config, &sanction).await);
+    }
+
+    match sanction.kind {
+        ModlogType::Ban => {
+            let mut request = state.http.create_ban(guild_id, user_id);
+            if let Some(reason) = reason {
+                request = request.reason(reason)?;
+            }
+
+            request.exec().await?;
+        }
+        ModlogType::Kick => {
+            let cache_http = state.cache_http(guild_id);
+            let mut request = cache_http.remove_guild_member(user_id).await?;
+            if let Some(reason) = reason {
+                request = request.reason(reason)?;
+            }
+
+            request.exec().await?;
+        }
+        ModlogType::Mute => {
+            mute(
+                state,
+                guild_id,
+                user_id,
+                mute_mode,
+                sanction.duration,
+                reason,
+                case_id,
+            )
+            .await?;
+        }
+        ModlogType::Unmute => {
+            unmute(state, guild_id, user_id, mute_mode, reason).await?;
+        }
+        ModlogType::Quarantine => {
+            let role_id = quarantine_role.context("quarantine role not configured")?;
+            quarantine(state, guild_id, user_id, role_id, reason).await?;
+        }
+        ModlogType::Release => {
+            outcome.skipped_roles =
+                release(state, guild_id, user_id, quarantine_role, reason).await?;
+        }
+    }
+
+    if notified && order == NotifyOrder::After {
+        dm_status = Some(notify_user(state, config, &sanction).await);
+    }
+
+    let modlog = Modlog {
+        id: Some(case_id),
+        kind: sanction.kind,
+        guild_id,
+        user: sanction.user,
+        moderator: sanction.moderator,
+        date: OffsetDateTime::now_utc(),
+        reason: sanction.reason,
+        notes: sanction.notes,
+        pre_emptive: sanction.pre_emptive,
+        confirmation_skipped: sanction.confirmation_skipped,
+        shadow: sanction.shadow,
+        dm_disabled: notified && config.moderation.dm_disabled,
+        dm_status,
+        synced_from: sanction.synced_from,
+        amendments: Vec::new(),
+    };
+
+    log_sanction(state, config, &modlog).await;
+
+    if !modlog.shadow {
+        announce(state, config, &modlog).await;
+    }
+
+    let sync = sanction.sync.then(|| modlog.clone());
+
+    database::write(
+        state,
+        PendingWrite::Modlog {
+            modlog: Box::new(modlog),
+        },
+    )
+    .await?;
+
+    if let Some(modlog) = sync {
+        feature::ban_sync::propagate(state, config, &modlog).await;
+    }
+
+    feature::stats::record(state, guild_id, StatsEvent::Sanction(sanction.kind)).await;
+
+    let event = WatchEvent::Sanction {
+        kind: sanction.kind,
+        moderator: moderator_id,
+    };
+    feature::watch::notify(state, guild_id, user_id, event).await;
+
+    Ok(outcome)
+}
+
+/// Format the audit log reason of a sanction.
+///
+/// The reason is truncated to the length allowed by Discord.
+fn audit_reason(lang: Lang, moderator: &ModlogUser, reason: Option<&str>) -> String {
+    let moderator = format!(
+        "{}#{:04} ({})",
+        moderator.name, moderator.discriminator, moderator.id
+    );
+    let reason = match reason {
+        Some(reason) => lang.sanction_audit_reason(moderator, reason),
+        None => lang.sanction_audit_reason_empty(moderator),
+    };
+
+    reason.chars().take(MAX_AUDIT_REASON_LENGTH).collect()
+}
+
+/// Notify the sanctioned user by private message.
+///
+/// The user may not accept private messages, so errors are only recorded in
+/// the returned status. The message is abandoned if it is not sent within
+/// [`DM_TIMEOUT`], so that the sanction is not delayed.
+async fn notify_user(state: &ClusterState, config: &GuildConfig, sanction: &Sanction) -> DmStatus {
+    if !config.moderation.dm_enabled(sanction.kind) {
+        return DmStatus::Skipped;
+    }
+
+    let result = async {
+        let guild = state
+            .cache
+            .get::<CachedGuild>(&sanction.guild_id)
+            .await?
+            .context("guild not cached")?;
+
+        // The moderator is hidden if the moderation is anonymous.
+        let moderator = (!config.moderation.anonymize).then_some(&sanction.moderator);
+        let embed = embed::post_in_chat::private_sanction(
+            config.lang(),
+            &guild.name,
+            sanction.kind,
+            &sanction.user,
+            moderator,
+            sanction.reason.as_deref(),
+        );
+
+        let channel = state
+            .http
+            .create_private_channel(sanction.user.id)
+            .exec()
+            .await?
+            .model()
+            .await?;
+
+        state
+            .http
+            .create_message(channel.id)
+            .embeds(&[embed])?
+            .exec()
+            .await?;
+
+        Ok::<_, anyhow::Error>(())
+    };
+
+    match tokio::time::timeout(DM_TIMEOUT, result).await {
+        Ok(Ok(())) => DmStatus::Delivered,
+        Ok(Err(error)) => {
+            debug!(error = ?error, "failed to notify sanctioned user");
+
+            dm_error_status(&error)
+        }
+        Err(_) => {
+            debug!(timeout = ?DM_TIMEOUT, "timed out while notifying sanctioned user");
+
+            DmStatus::Failed
+        }
+    }
+}
+
+/// Get the [`DmStatus`] of a private message that could not be sent.
+fn dm_error_status(error: &anyhow::Error) -> DmStatus {
+    let code = error
+        .downcast_ref::<twilight_http::Error>()
+        .and_then(http::error_code);
+
+    match code {
+        Some(CANNOT_MESSAGE_USER) => DmStatus::Closed,
+        _ => DmStatus::Failed,
+    }
+}
+
+/// Notify a sanction in the logs channel.
+///
+/// The sanction has already been applied, so errors are only logged.
+async fn log_sanction(state: &ClusterState, config: &GuildConfig, modlog: &Modlog) {
+    let result = async {
+        let embed = match modlog.shadow {
+            true => embed::logs::shadow_sanction(config.lang(), modlog),
+            false => embed::logs::sanction(config.lang(), modlog),
+        };
+
+        logs::post(state, config, LogCategory::Sanctions, embed).await
+    };
+
+    if let Err(error) = result.await {
+        warn!(error = ?error, "failed to log sanction");
+    }
+}
+
+/// Announce a sanction in the announcement channel of the guild.
+///
+/// Nothing is sent if this type of sanction is not announced. The sanction
+/// has already been applied, so errors are only logged.
+async fn announce(state: &ClusterState, config: &GuildConfig, modlog: &Modlog) {
+    let channel_id = match config.moderation.announcement(modlog.kind) {
+        Some(channel_id) => channel_id,
+        None => return,
+    };
+
+    let result = async {
+        let embed =
+            embed::post_in_chat::announcement(config.lang(), modlog, config.moderation.anonymize);
+
+        state
+            .http
+            .create_message(channel_id)
+            .embeds(&[embed])?
+            .exec()
+            .await?;
+
+        Ok::<_, anyhow::Error>(())
+    };
+
+    if let Err(error) = result.await {
+        warn!(error = ?error, "failed to announce sanction");
+    }
+}
+
+/// Mute a member according to the configured [`MuteMode`].
+async fn mute(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    mode: MuteMode,
+    duration: Option<Duration>,
+    reason: Option<&str>,
+    case_id: ObjectId,
+) -> Result<(), anyhow::Error> {
+    let now = OffsetDateTime::now_utc();
+    let cache_http = state.cache_http(guild_id);
+
+    match mode {
+        MuteMode::Timeout => {
+            let duration = duration.unwrap_or(MAX_TIMEOUT).min(MAX_TIMEOUT);
+            let until = Timestamp::from_secs((now + duration).unix_timestamp())?;
+
+            let mut request = cache_http.timeout_member(user_id, until).await?;
+            if let Some(reason) = reason {
+                request = request.reason(reason)?;
+            }
+
+            request.exec().await?;
+
+            // The mute is stored to reapply the timeout if it is cleared.
+            let mute = ActiveMute {
+                guild_id,
+                user_id,
+                expires_at: Some(now + duration),
+                timeout: true,
+                case_id: Some(case_id),
+            };
+            store_mute(state, &mute).await?;
+        }
+        MuteMode::Role { role_id } => {
+            let mut request = cache_http.add_guild_member_role(user_id, role_id).await?;
+            if let Some(reason) = reason {
+                request = request.reason(reason)?;
+            }
+
+            request.exec().await?;
+
+            let mute = ActiveMute {
+                guild_id,
+                user_id,
+                expires_at: duration.map(|duration| now + duration),
+                timeout: false,
+                case_id: Some(case_id),
+            };
+            store_mute(state, &mute).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Store an [`ActiveMute`] and schedule its expiration.
+pub async fn store_mute(state: &ClusterState, mute: &ActiveMute) -> Result<(), anyhow::Error> {
+    state.database.create_mute(mute).await?;
+
+    if let Some(expires_at) = mute.expires_at {
+        let job = ScheduledJob::Unmute {
+            guild_id: mute.guild_id,
+            user_id: mute.user_id,
+        };
+        state.cache.schedule_job(&job, expires_at).await?;
+    }
+
+    Ok(())
+}
+
+/// Unmute a member according to the configured [`MuteMode`].
+///
+/// The [`ActiveMute`] of the member is deleted in both modes, in case the
+/// mode has changed since the member was muted.
+async fn unmute(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    mode: MuteMode,
+    reason: Option<&str>,
+) -> Result<(), anyhow::Error> {
+    let cache_http = state.cache_http(guild_id);
+
+    match mode {
+        MuteMode::Timeout => {
+            let mut request = cache_http.remove_timeout(user_id).await?;
+            if let Some(reason) = reason {
+                request = request.reason(reason)?;
+            }
+
+            request.exec().await?;
+        }
+        MuteMode::Role { role_id } => {
+            let mut request = cache_http
+                .remove_guild_member_role(user_id, role_id)
+                .await?;
+            if let Some(reason) = reason {
+                request = request.reason(reason)?;
+            }
+
+            request.exec().await?;
+        }
+    }
+
+    state.database.delete_mute(guild_id, user_id).await?;
+
+    Ok(())
+}
+
+/// Replace the roles of a member with the quarantine role.
+///
+/// The removed roles are stored before the member is updated, so that they
+/// cannot be lost. The stored state is deleted if the update fails.
+async fn quarantine(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    role_id: Id<RoleMarker>,
+    reason: Option<&str>,
+) -> Result<(), anyhow::Error> {
+    let member = state
+        .http
+        .guild_member(guild_id, user_id)
+        .exec()
+        .await?
+        .model()
+        .await?;
+
+    // Managed roles cannot be removed.
+    let mut kept = vec![role_id];
+    let mut removed = Vec::new();
+    for member_role in member.roles {
+        match state.cache.get::<CachedRole>(&member_role).await? {
+            Some(role) if role.managed => kept.push(member_role),
+            _ if member_role == role_id => {}
+            _ => removed.push(member_role),
+        }
+    }
+
+    let quarantine = QuarantineState {
+        guild_id,
+        user_id,
+        roles: removed,
+        date: OffsetDateTime::now_utc(),
+    };
+    state.database.create_quarantine(&quarantine).await?;
+
+    let mut request = state
+        .http
+        .update_guild_member(guild_id, user_id)
+        .roles(&kept);
+    if let Some(reason) = reason {
+        request = request.reason(reason)?;
+    }
+
+    if let Err(error) = request.exec().await {
+        state.database.delete_quarantine(guild_id, user_id).await?;
+
+        return Err(error.into());
+    }
+
+    Ok(())
+}
+
+/// Restore the roles of a quarantined member.
+///
+/// The quarantine role is removed even if it is no longer configured. Returns
+/// the stored roles that no longer exist.
+async fn release(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    quarantine_role: Option<Id<RoleMarker>>,
+    reason: Option<&str>,
+) -> Result<Vec<Id<RoleMarker>>, anyhow::Error> {
+    let quarantine = state
+        .database
+        .get_quarantine(guild_id, user_id)
+        .await?
+        .context("member is not quarantined")?;
+
+    let member = state
+        .http
+        .guild_member(guild_id, user_id)
+        .exec()
+        .await?
+        .model()
+        .await?;
+
+    let mut roles = member
+        .roles
+        .into_iter()
+        .filter(|role| Some(*role) != quarantine_role)
+        .collect::<Vec<_>>();
+    let mut skipped = Vec::new();
+
+    for role_id in quarantine.roles {
+        if state.cache.get::<CachedRole>(&role_id).await?.is_none() {
+            skipped.push(role_id);
+        } else if !roles.contains(&role_id) {
+            roles.push(role_id);
+        }
+    }
+
+    let mut request = state
+        .http
+        .update_guild_member(guild_id, user_id)
+        .roles(&roles);
+    if let Some(reason) = reason {
+        request = request.reason(reason)?;
+    }
+
+    request.exec().await?;
+    state.database.delete_quarantine(guild_id, user_id).await?;
+
+    Ok(skipped)
+}
+
+/// Remove the mute role of a member once their mute has expired.
+///
+/// This function is called by the scheduler. Nothing is done if the member
+/// has been unmuted or muted again with a longer duration in the meantime.
+/// Timeouts are removed by Discord, so only the stored mute is deleted.
+#[instrument(skip(state))]
+pub async fn expire_mute(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+) -> Result<(), anyhow::Error> {
+    let timeout = match state.database.get_mute(guild_id, user_id).await? {
+        Some(mute) if mute.is_expired(OffsetDateTime::now_utc()) => mute.timeout,
+        _ => return Ok(()),
+    };
+
+    let config = database::guild_config(state, guild_id).await?;
+
+    if let (MuteMode::Role { role_id }, false) = (config.moderation.mute_mode, timeout) {
+        let result = async {
+            state
+                .cache_http(guild_id)
+                .remove_guild_member_role(user_id, role_id)
+                .await?
+                .reason(config.lang().mute_expired_reason())?
+                .exec()
+                .await?;
+
+            Ok::<_, anyhow::Error>(())
+        };
+
+        // The member may have left the guild, the mute is deleted anyway.
+        if let Err(error) = result.await {
+            warn!(error = ?error, "failed to remove expired mute role");
+        }
+    }
+
+    state.database.delete_mute(guild_id, user_id).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::fixture;
+
+    #[test]
+    fn test_audit_reason() {
+        let moderator = ModlogUser {
+            discriminator: 42,
+            ..fixture::modlog_user(1, "Moderator")
+        };
+
+        let reason = audit_reason(Lang::DEFAULT, &moderator, Some("Spam"));
+        assert!(reason.contains("Moderator#0042 (1)"));
+        assert!(reason.contains("Spam"));
+
+        let reason = audit_reason(Lang::DEFAULT, &moderator, None);
+        assert!(reason.contains("Moderator#0042 (1)"));
+
+        let long = "a".repeat(1000);
+        let reason = audit_reason(Lang::DEFAULT, &moderator, Some(&long));
+        assert_eq!(reason.chars().count(), MAX_AUDIT_REASON_LENGTH);
+    }
+
+    #[test]
+    fn test_notify_order() {
+        // Banned and kicked users no longer share a guild with the bot once
+        // the sanction is applied.
+        assert_eq!(NotifyOrder::of(ModlogType::Ban), NotifyOrder::Before);
+        assert_eq!(NotifyOrder::of(ModlogType::Kick), NotifyOrder::Before);
+
+        // Muted users are only notified once the mute has been applied.
+        assert_eq!(NotifyOrder::of(ModlogType::Mute), NotifyOrder::After);
+        assert_eq!(NotifyOrder::of(ModlogType::Quarantine), NotifyOrder::After);
+    }
+
+    #[test]
+    fn test_dm_error_status() {
+        let error = anyhow::anyhow!("guild not cached");
+
+        assert_eq!(dm_error_status(&error), DmStatus::Failed);
+    }
+}
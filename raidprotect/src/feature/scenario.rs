@@ -0,0 +1,234 @@
+//! Configuration scenarios.
+//!
+//! Scenarios evaluate sample data against the configuration of a guild to
+//! report whether a protection would trigger, which threshold is hit and what
+//! action would be taken. Unlike raid simulations (see [`simulation`]), the
+//! evaluation is purely in-memory: no event is processed, no message is sent
+//! in the logs channel and no member is affected.
+//!
+//! [`simulation`]: super::simulation
+
+use raidprotect_model::database::model::{Feature, GuildConfig, RaidModeAction};
+
+/// Outcome of a scenario.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScenarioOutcome {
+    /// The protection is disabled in the configuration.
+    Disabled,
+    /// The protection is disabled with a feature switch.
+    FeatureDisabled(Feature),
+    /// The scenario does not reach the configured threshold.
+    NotTriggered(Threshold),
+    /// The scenario reaches the configured threshold.
+    Triggered(Threshold, ScenarioAction),
+}
+
+/// Threshold evaluated by a scenario.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Threshold {
+    /// Minimum account age (in hours) of the joining members.
+    ///
+    /// `affected` is the number of members below the minimum age.
+    AccountAge {
+        min_age: u32,
+        affected: u32,
+        joins: u32,
+    },
+    /// Maximum number of reactions during the interval.
+    ///
+    /// `peak` is the highest number of reactions in a single interval.
+    Reactions {
+        max_reactions: u16,
+        interval: u16,
+        peak: u32,
+    },
+}
+
+/// Action that would be taken when a scenario is triggered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScenarioAction {
+    /// Members below the minimum account age are kicked.
+    Kick,
+    /// Members below the minimum account age are held for review.
+    HoldForReview,
+    /// Reactions are removed, and the user is timed out if `timeout` is set.
+    RemoveReactions { timeout: Option<u32> },
+}
+
+/// Evaluate a burst of member joins.
+///
+/// The account ages of the `joins` members are evenly spread between zero and
+/// `max_account_age` hours. The raid mode settings are evaluated even if the
+/// raid mode is not currently enabled.
+pub fn join_burst(config: &GuildConfig, joins: u32, max_account_age: u32) -> ScenarioOutcome {
+    if !config.features.contains(Feature::RaidDetection) {
+        return ScenarioOutcome::FeatureDisabled(Feature::RaidDetection);
+    }
+
+    let min_age = match config.raid_mode.account_age {
+        Some(min_age) => min_age,
+        None => return ScenarioOutcome::Disabled,
+    };
+
+    let affected = (0..joins)
+        .map(|index| u64::from(index) * u64::from(max_account_age) / u64::from(joins))
+        .filter(|age| *age < u64::from(min_age))
+        .count() as u32;
+
+    let threshold = Threshold::AccountAge {
+        min_age,
+        affected,
+        joins,
+    };
+
+    let action = match config.raid_mode.action {
+        RaidModeAction::Kick => ScenarioAction::Kick,
+        RaidModeAction::Review => ScenarioAction::HoldForReview,
+    };
+
+    match affected {
+        0 => ScenarioOutcome::NotTriggered(threshold),
+        _ => ScenarioOutcome::Triggered(threshold, action),
+    }
+}
+
+/// Evaluate a user adding reactions.
+///
+/// The `reactions` are evenly spread over `duration` seconds.
+pub fn reaction_spam(config: &GuildConfig, reactions: u32, duration: u32) -> ScenarioOutcome {
+    let reaction_spam = &config.reaction_spam;
+
+    if !reaction_spam.enabled {
+        return ScenarioOutcome::Disabled;
+    }
+
+    if !config.features.contains(Feature::SpamFilter) {
+        return ScenarioOutcome::FeatureDisabled(Feature::SpamFilter);
+    }
+
+    let interval = u32::from(reaction_spam.interval);
+    let peak = match duration <= interval {
+        true => reactions,
+        false => {
+            let spread = u64::from(reactions) * u64::from(interval);
+            ((spread + u64::from(duration) - 1) / u64::from(duration)) as u32
+        }
+    };
+
+    let threshold = Threshold::Reactions {
+        max_reactions: reaction_spam.max_reactions,
+        interval: reaction_spam.interval,
+        peak,
+    };
+
+    match peak > reaction_spam.max_reactions.into() {
+        true => ScenarioOutcome::Triggered(
+            threshold,
+            ScenarioAction::RemoveReactions {
+                timeout: reaction_spam.timeout,
+            },
+        ),
+        false => ScenarioOutcome::NotTriggered(threshold),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use raidprotect_model::database::model::FeatureSet;
+    use twilight_model::id::Id;
+
+    use super::*;
+
+    fn config() -> GuildConfig {
+        let mut config = GuildConfig::new(Id::new(1));
+        config.reaction_spam.enabled = true;
+
+        config
+    }
+
+    #[test]
+    fn test_join_burst() {
+        let config = config(); // minimum account age of 72 hours
+
+        assert_eq!(
+            join_burst(&config, 10, 720),
+            ScenarioOutcome::Triggered(
+                Threshold::AccountAge {
+                    min_age: 72,
+                    affected: 1,
+                    joins: 10
+                },
+                ScenarioAction::Kick
+            )
+        );
+        assert_eq!(
+            join_burst(&config, 10, 24),
+            ScenarioOutcome::Triggered(
+                Threshold::AccountAge {
+                    min_age: 72,
+                    affected: 10,
+                    joins: 10
+                },
+                ScenarioAction::Kick
+            )
+        );
+    }
+
+    #[test]
+    fn test_join_burst_review() {
+        let mut config = config();
+        config.raid_mode.action = RaidModeAction::Review;
+
+        assert!(matches!(
+            join_burst(&config, 10, 24),
+            ScenarioOutcome::Triggered(_, ScenarioAction::HoldForReview)
+        ));
+    }
+
+    #[test]
+    fn test_join_burst_disabled() {
+        let mut config = config();
+        config.raid_mode.account_age = None;
+
+        assert_eq!(join_burst(&config, 10, 24), ScenarioOutcome::Disabled);
+
+        config.features = FeatureSet::empty();
+        assert_eq!(
+            join_burst(&config, 10, 24),
+            ScenarioOutcome::FeatureDisabled(Feature::RaidDetection)
+        );
+    }
+
+    #[test]
+    fn test_reaction_spam() {
+        let config = config(); // 10 reactions every 5 seconds
+
+        assert_eq!(
+            reaction_spam(&config, 11, 5),
+            ScenarioOutcome::Triggered(
+                Threshold::Reactions {
+                    max_reactions: 10,
+                    interval: 5,
+                    peak: 11
+                },
+                ScenarioAction::RemoveReactions { timeout: None }
+            )
+        );
+        assert_eq!(
+            reaction_spam(&config, 40, 20),
+            ScenarioOutcome::NotTriggered(Threshold::Reactions {
+                max_reactions: 10,
+                interval: 5,
+                peak: 10
+            })
+        );
+    }
+
+    #[test]
+    fn test_reaction_spam_disabled() {
+        let mut config = config();
+        config.reaction_spam.enabled = false;
+
+        assert_eq!(reaction_spam(&config, 100, 1), ScenarioOutcome::Disabled);
+    }
+}
@@ -0,0 +1,137 @@
+//! Raid simulation.
+//!
+//! The raid mode settings are hard to verify without a real raid. Bot
+//! operators can start a simulation that injects synthetic joins in the raid
+//! mode pipeline (see [`JoinSource`]). Synthetic joins use fake user ids,
+//! never trigger moderation actions and the resulting logs messages are
+//! tagged as simulation.
+//!
+//! Simulations are only allowed on small guilds, such as staging guilds.
+
+use std::time::Duration as StdDuration;
+
+use async_trait::async_trait;
+use raidprotect_model::database::model::LogCategory;
+use time::OffsetDateTime;
+use tokio::time::{Interval, MissedTickBehavior};
+use tracing::error;
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    event::raid_mode::{process_joins, JoinSource, MemberJoin},
+    interaction::{embed, util::GuildConfigExt},
+    util::logs,
+};
+
+/// Maximum number of members of the guilds where simulations are allowed.
+pub const MAX_MEMBER_COUNT: u64 = 1000;
+
+/// Maximum account age of the synthetic users, in hours.
+const MAX_ACCOUNT_AGE: u64 = 24 * 30;
+
+/// First second of 2015, used as epoch by Discord snowflakes (in milliseconds).
+const DISCORD_EPOCH: u64 = 1_420_070_400_000;
+
+/// [`JoinSource`] generating synthetic joins at a fixed rate.
+pub struct SimulatedJoins {
+    guild_id: Id<GuildMarker>,
+    interval: Interval,
+    remaining: u64,
+    count: u64,
+}
+
+impl SimulatedJoins {
+    /// Create a new [`SimulatedJoins`].
+    ///
+    /// The `duration` is in seconds, and `joins_per_second` must not be zero.
+    pub fn new(guild_id: Id<GuildMarker>, joins_per_second: u32, duration: u32) -> Self {
+        let mut interval = tokio::time::interval(StdDuration::from_secs(1) / joins_per_second);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        Self {
+            guild_id,
+            interval,
+            remaining: u64::from(joins_per_second) * u64::from(duration),
+            count: 0,
+        }
+    }
+}
+
+#[async_trait]
+impl JoinSource for SimulatedJoins {
+    async fn next_join(&mut self) -> Option<MemberJoin> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        self.interval.tick().await;
+        self.remaining -= 1;
+        self.count += 1;
+
+        let now = OffsetDateTime::now_utc().unix_timestamp() as u64;
+        Some(synthetic_join(self.guild_id, self.count, now))
+    }
+
+    fn simulated(&self) -> bool {
+        true
+    }
+}
+
+/// Generate a synthetic join.
+///
+/// The account ages are spread between zero and [`MAX_ACCOUNT_AGE`], and the
+/// user id is a snowflake matching the account creation date.
+fn synthetic_join(guild_id: Id<GuildMarker>, index: u64, now: u64) -> MemberJoin {
+    let account_age = index.wrapping_mul(37) % MAX_ACCOUNT_AGE;
+    let created_at = now.saturating_sub(account_age * 3600);
+    let snowflake = ((created_at * 1000).saturating_sub(DISCORD_EPOCH) << 22) | (index & 0xfff);
+
+    MemberJoin {
+        guild_id,
+        user_id: Id::new(snowflake.max(1)),
+        created_at,
+    }
+}
+
+/// Run a simulation and send a summary in the logs channel.
+pub async fn run(state: ClusterState, source: SimulatedJoins) {
+    let guild_id = source.guild_id;
+
+    if let Err(error) = run_inner(&state, source).await {
+        error!(error = ?error, guild = ?guild_id, "error while running raid simulation");
+    }
+}
+
+async fn run_inner(state: &ClusterState, source: SimulatedJoins) -> Result<(), anyhow::Error> {
+    let guild_id = source.guild_id;
+    let stats = process_joins(source, state).await?;
+
+    let config = database::guild_config(state, guild_id).await?;
+    let lang = config.lang();
+
+    let embed = embed::logs::simulation_summary(lang, stats);
+    let embed = embed::logs::simulation(lang, embed);
+
+    logs::post(state, &config, LogCategory::Raids, embed).await
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_util::snowflake::Snowflake;
+
+    use super::*;
+
+    #[test]
+    fn test_synthetic_join() {
+        let now = 1_660_000_000;
+
+        for index in 1..100 {
+            let join = synthetic_join(Id::new(1), index, now);
+
+            assert_eq!(join.user_id.timestamp() as u64, join.created_at * 1000);
+            assert!(now - join.created_at < MAX_ACCOUNT_AGE * 3600);
+        }
+    }
+}
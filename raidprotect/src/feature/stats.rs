@@ -0,0 +1,26 @@
+//! Guild statistics.
+//!
+//! Events are recorded in the [`GuildStats`] of the guild as they happen, so
+//! that the `/stats` command does not have to aggregate the modlogs. Failing
+//! to record an event is not considered an error: the statistics can be
+//! recomputed from the modlogs with the `/admin recompute-stats` command.
+//!
+//! [`GuildStats`]: raidprotect_model::database::model::GuildStats
+
+use raidprotect_model::database::model::StatsEvent;
+use time::OffsetDateTime;
+use tracing::warn;
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::cluster::ClusterState;
+
+/// Record an event in the statistics of a guild.
+pub async fn record(state: &ClusterState, guild_id: Id<GuildMarker>, event: StatsEvent) {
+    if let Err(error) = state
+        .database
+        .increment_stats(guild_id, event, OffsetDateTime::now_utc())
+        .await
+    {
+        warn!(error = ?error, guild = ?guild_id, event = ?event, "failed to record stats");
+    }
+}
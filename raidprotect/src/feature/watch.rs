@@ -0,0 +1,196 @@
+//! Watchlist of users.
+//!
+//! Moderators can keep an eye on borderline users by adding them to the
+//! watchlist with the `/watch` command (see [`WatchlistEntry`]). While a user
+//! is watched, their activity ([`WatchEvent`]) is notified in the channel
+//! configured in [`GuildConfig::watch_chan`], or in the logs channel,
+//! mentioning the moderators that added the watch.
+//!
+//! Watches expire after [`GuildConfig::watch_duration`] days, and are removed
+//! from the database with a [`ScheduledJob::WatchExpire`] job.
+
+use raidprotect_model::{
+    cache::model::job::ScheduledJob,
+    database::model::{GuildConfig, LogCategory, ModlogType, WatchlistEntry},
+};
+use time::{Duration, OffsetDateTime};
+use tracing::error;
+use twilight_mention::Mention;
+use twilight_model::{
+    channel::message::AllowedMentions,
+    id::{
+        marker::{ChannelMarker, GuildMarker, UserMarker},
+        Id,
+    },
+};
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    interaction::{embed, util::GuildConfigExt},
+    util::logs::{self, LogMessage},
+};
+
+/// Maximum number of watched users per guild.
+pub const MAX_WATCHED_USERS: u64 = 50;
+
+/// Activity of a watched user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchEvent {
+    /// The user joined the guild.
+    Join,
+    /// The user left the guild.
+    Leave,
+    /// A message of the user contains a filtered link.
+    ///
+    /// If `dry_run` is set, the link filter is disabled and the message has
+    /// not been deleted.
+    LinkFilter {
+        channel: Id<ChannelMarker>,
+        link: String,
+        dry_run: bool,
+    },
+    /// The user has been sanctioned by a moderator.
+    Sanction {
+        kind: ModlogType,
+        moderator: Id<UserMarker>,
+    },
+}
+
+impl WatchEvent {
+    /// Get the [`LogCategory`] of the event.
+    pub fn category(&self) -> LogCategory {
+        match self {
+            Self::Join | Self::Leave => LogCategory::Joins,
+            Self::LinkFilter { .. } => LogCategory::Filters,
+            Self::Sanction { .. } => LogCategory::Sanctions,
+        }
+    }
+}
+
+/// Add a user to the watchlist.
+///
+/// If the user is already watched (`entry` is the current watch), the
+/// moderator is added to the moderators of the watch, and the watch is
+/// extended. The note replaces the previous one if provided.
+pub async fn add(
+    state: &ClusterState,
+    config: &GuildConfig,
+    entry: Option<WatchlistEntry>,
+    user_id: Id<UserMarker>,
+    moderator: Id<UserMarker>,
+    note: Option<String>,
+) -> Result<WatchlistEntry, anyhow::Error> {
+    let now = OffsetDateTime::now_utc();
+    let expires_at = now + Duration::days(config.watch_duration.into());
+
+    let mut entry = entry
+        .filter(|entry| !entry.is_expired(now))
+        .unwrap_or_else(|| WatchlistEntry {
+            guild_id: config.id,
+            user_id,
+            moderators: Vec::new(),
+            note: None,
+            created_at: now,
+            expires_at,
+        });
+
+    if !entry.moderators.contains(&moderator) {
+        entry.moderators.push(moderator);
+    }
+
+    if note.is_some() {
+        entry.note = note;
+    }
+
+    entry.expires_at = expires_at;
+    state.database.create_watch(&entry).await?;
+
+    // The job is rescheduled if the user was already watched.
+    let job = ScheduledJob::WatchExpire {
+        guild_id: config.id,
+        user_id,
+    };
+    state.cache.schedule_job(&job, expires_at).await?;
+
+    Ok(entry)
+}
+
+/// Remove an expired watch from the watchlist.
+///
+/// This function is called by the scheduler. Nothing is done if the watch
+/// has been removed or extended in the meantime.
+pub async fn expire(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+) -> Result<(), anyhow::Error> {
+    match state.database.get_watch(guild_id, user_id).await? {
+        Some(entry) if entry.is_expired(OffsetDateTime::now_utc()) => {
+            state.database.delete_watch(guild_id, user_id).await?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Notify the activity of a user if they are watched.
+pub async fn notify(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    event: WatchEvent,
+) {
+    if let Err(error) = notify_inner(state, guild_id, user_id, event).await {
+        error!(error = ?error, guild = ?guild_id, "failed to send watchlist notification");
+    }
+}
+
+async fn notify_inner(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    event: WatchEvent,
+) -> Result<(), anyhow::Error> {
+    let entry = match state.database.get_watch(guild_id, user_id).await? {
+        Some(entry) if !entry.is_expired(OffsetDateTime::now_utc()) => entry,
+        _ => return Ok(()),
+    };
+
+    let config = database::guild_config(state, guild_id).await?;
+    let lang = config.lang();
+
+    let content = entry
+        .moderators
+        .iter()
+        .map(|moderator| moderator.mention().to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let allowed_mentions = AllowedMentions {
+        users: entry.moderators.clone(),
+        ..Default::default()
+    };
+    let embed = embed::watch::notification(lang, user_id, &event, entry.note.as_deref());
+
+    // Without a dedicated channel, notifications are sent in the logs channel
+    // of the category of the event.
+    match config.watch_chan {
+        Some(channel) => {
+            state
+                .http
+                .create_message(channel)
+                .content(&content)?
+                .embeds(&[embed])?
+                .allowed_mentions(Some(&allowed_mentions))
+                .exec()
+                .await?;
+        }
+        None => {
+            let message = LogMessage::new(embed).content(content, allowed_mentions);
+            logs::post_message(state, &config, event.category(), message).await?;
+        }
+    }
+
+    Ok(())
+}
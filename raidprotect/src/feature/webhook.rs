@@ -0,0 +1,682 @@
+//! Outbound webhook for moderation events.
+//!
+//! Guilds may configure a webhook to receive their moderation events in
+//! external tools (see [`GuildConfig::outbound_webhook`]). After each
+//! moderation log is stored in the database, a [`WebhookPayload`] is sent to
+//! the webhook with a [`ScheduledJob::WebhookDelivery`] job.
+//!
+//! ## Signature
+//! Each payload is signed with an HMAC-SHA256 of the request body, using the
+//! secret generated when the webhook is configured. The hex-encoded signature
+//! is sent in the [`SIGNATURE_HEADER`] header, prefixed with `sha256=`.
+//!
+//! ## Delivery
+//! Delivery is best-effort: failed requests are retried [`MAX_RETRIES`] times
+//! by the scheduler. The webhook is disabled after [`MAX_FAILURES`]
+//! consecutive failed deliveries, and a notice is sent in the logs channel.
+//!
+//! [`GuildConfig::outbound_webhook`]: raidprotect_model::database::model::GuildConfig::outbound_webhook
+
+use std::{
+    future::Future,
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    pin::Pin,
+    task::{Context as TaskContext, Poll},
+    time::Duration as StdDuration,
+    vec,
+};
+
+use anyhow::{anyhow, bail, Context};
+use hmac::{Hmac, Mac};
+use hyper::{
+    client::{
+        connect::dns::{GaiResolver, Name},
+        HttpConnector,
+    },
+    header,
+    service::Service,
+    Body, Client, Request,
+};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use once_cell::sync::Lazy;
+use raidprotect_model::{
+    cache::model::job::ScheduledJob,
+    database::model::{GuildConfig, LogCategory, Modlog, ModlogType, ModlogUser},
+};
+use rand::Rng;
+use serde::Serialize;
+use sha2::Sha256;
+use time::{Duration, OffsetDateTime};
+use tracing::{debug, error, warn};
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+use url::{Host, Url};
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    interaction::{embed, util::GuildConfigExt},
+    util::logs,
+};
+
+/// Version of the [`WebhookPayload`] schema.
+///
+/// The version is incremented on breaking changes of the schema.
+pub const PAYLOAD_VERSION: u8 = 1;
+
+/// Header that contains the signature of the payload.
+pub const SIGNATURE_HEADER: &str = "X-RaidProtect-Signature";
+
+/// Maximum number of retries of a failed delivery.
+pub const MAX_RETRIES: u8 = 3;
+
+/// Number of consecutive failed deliveries after which the webhook is
+/// disabled.
+pub const MAX_FAILURES: u32 = 20;
+
+/// Delay before the first retry of a failed delivery.
+///
+/// The delay is doubled after each retry.
+const RETRY_DELAY: Duration = Duration::seconds(30);
+
+/// Maximum duration of a webhook request.
+const REQUEST_TIMEOUT: StdDuration = StdDuration::from_secs(10);
+
+/// HTTP client used to send the payloads.
+///
+/// Domains are resolved with [`PublicResolver`], so that the client only
+/// connects to the public addresses that passed the check.
+static CLIENT: Lazy<Client<HttpsConnector<HttpConnector<PublicResolver>>, Body>> =
+    Lazy::new(|| {
+        let mut http = HttpConnector::new_with_resolver(PublicResolver::new());
+        http.enforce_http(false);
+
+        let connector = HttpsConnectorBuilder::new()
+            .with_webpki_roots()
+            .https_only()
+            .enable_http1()
+            .wrap_connector(http);
+
+        Client::builder().build(connector)
+    });
+
+/// Payload sent to the outbound webhook.
+///
+/// All fields are always present in the serialized payload, fields that do
+/// not apply to an event are `null`.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct WebhookPayload {
+    /// Version of the payload schema (see [`PAYLOAD_VERSION`]).
+    pub version: u8,
+    /// Type of the event.
+    pub event: WebhookEvent,
+    /// Id of the moderation log.
+    pub case_id: Option<String>,
+    /// Id of the guild.
+    pub guild_id: Id<GuildMarker>,
+    /// Moderator that issued the sanction.
+    pub actor: Option<WebhookUser>,
+    /// User targeted by the sanction.
+    pub target: Option<WebhookUser>,
+    /// Reason of the sanction.
+    pub reason: Option<String>,
+    /// UNIX timestamp of the event, in seconds.
+    pub timestamp: i64,
+}
+
+impl WebhookPayload {
+    /// Payload of a moderation log.
+    ///
+    /// The `case_id` is the id of the moderation log in the database.
+    pub fn modlog(modlog: &Modlog, case_id: String) -> Self {
+        Self {
+            version: PAYLOAD_VERSION,
+            event: modlog.kind.into(),
+            case_id: Some(case_id),
+            guild_id: modlog.guild_id,
+            actor: Some((&modlog.moderator).into()),
+            target: Some((&modlog.user).into()),
+            reason: modlog.reason.clone(),
+            timestamp: modlog.date.unix_timestamp(),
+        }
+    }
+
+    /// Payload sent to test the webhook when it is configured.
+    pub fn ping(guild_id: Id<GuildMarker>, date: OffsetDateTime) -> Self {
+        Self {
+            version: PAYLOAD_VERSION,
+            event: WebhookEvent::Ping,
+            case_id: None,
+            guild_id,
+            actor: None,
+            target: None,
+            reason: None,
+            timestamp: date.unix_timestamp(),
+        }
+    }
+}
+
+/// Type of a [`WebhookPayload`] event.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    Ping,
+    Ban,
+    Kick,
+    Mute,
+    Unmute,
+    Quarantine,
+    Release,
+}
+
+impl From<ModlogType> for WebhookEvent {
+    fn from(kind: ModlogType) -> Self {
+        match kind {
+            ModlogType::Ban => Self::Ban,
+            ModlogType::Kick => Self::Kick,
+            ModlogType::Mute => Self::Mute,
+            ModlogType::Unmute => Self::Unmute,
+            ModlogType::Quarantine => Self::Quarantine,
+            ModlogType::Release => Self::Release,
+        }
+    }
+}
+
+/// User included in a [`WebhookPayload`].
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct WebhookUser {
+    pub id: Id<UserMarker>,
+    pub name: String,
+    pub discriminator: u16,
+}
+
+impl From<&ModlogUser> for WebhookUser {
+    fn from(user: &ModlogUser) -> Self {
+        Self {
+            id: user.id,
+            name: user.name.clone(),
+            discriminator: user.discriminator,
+        }
+    }
+}
+
+/// Parse the url of a webhook.
+///
+/// Only HTTPS urls are accepted. Urls pointing to `localhost` or to a
+/// loopback, link-local or private address are rejected, so that the webhook
+/// cannot be used to reach services of the local network. Domains are checked
+/// again once resolved, when the payloads are sent (see [`PublicResolver`]).
+pub fn parse_url(input: &str) -> Option<Url> {
+    let url = Url::parse(input.trim()).ok()?;
+
+    let local = match url.host()? {
+        Host::Domain(domain) => is_localhost(domain),
+        Host::Ipv4(ip) => is_local_ip(IpAddr::V4(ip)),
+        Host::Ipv6(ip) => is_local_ip(IpAddr::V6(ip)),
+    };
+
+    match url.scheme() == "https" && !local {
+        true => Some(url),
+        false => None,
+    }
+}
+
+/// Whether a domain is `localhost` or one of its subdomains.
+fn is_localhost(domain: &str) -> bool {
+    let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+
+    domain == "localhost" || domain.ends_with(".localhost")
+}
+
+/// Whether an address is a loopback, link-local, private, reserved or
+/// multicast address.
+///
+/// IPv6 addresses embedding an IPv4 address (IPv4-mapped, IPv4-compatible and
+/// 6to4 addresses) are checked against the embedded address.
+fn is_local_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_local_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4() {
+            Some(ip) => is_local_ipv4(ip),
+            None => is_local_ipv6(ip),
+        },
+    }
+}
+
+fn is_local_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+
+    ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_private()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || a == 0 // Current network (0.0.0.0/8)
+        || (a == 100 && b & 0xc0 == 64) // Shared address space (100.64.0.0/10)
+        || (a == 192 && b == 0 && c == 0) // IETF protocol assignments (192.0.0.0/24)
+        || (a == 198 && b & 0xfe == 18) // Benchmarking (198.18.0.0/15)
+}
+
+fn is_local_ipv6(ip: Ipv6Addr) -> bool {
+    let segments = ip.segments();
+
+    // 6to4 addresses (2002::/16) embed an IPv4 address.
+    if segments[0] == 0x2002 {
+        let [a, b] = segments[1].to_be_bytes();
+        let [c, d] = segments[2].to_be_bytes();
+
+        return is_local_ipv4(Ipv4Addr::new(a, b, c, d));
+    }
+
+    ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        || segments[0] & 0xfe00 == 0xfc00 // Unique local addresses (fc00::/7)
+        || segments[0] & 0xffc0 == 0xfe80 // Link-local addresses (fe80::/10)
+}
+
+/// DNS resolver of the webhook client.
+///
+/// [`parse_url`] only checks the host written in the url, which could be a
+/// public domain that resolves to a local address. The resolved addresses are
+/// checked before connecting, and the resolution fails if any of them is a
+/// local address (see [`public_addrs`]). The client connects to the checked
+/// addresses, so the domain cannot be resolved again to another address.
+#[derive(Debug, Clone)]
+struct PublicResolver(GaiResolver);
+
+impl PublicResolver {
+    fn new() -> Self {
+        Self(GaiResolver::new())
+    }
+}
+
+impl Service<Name> for PublicResolver {
+    type Response = vec::IntoIter<SocketAddr>;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        self.0.poll_ready(cx)
+    }
+
+    fn call(&mut self, name: Name) -> Self::Future {
+        let resolve = self.0.call(name);
+
+        Box::pin(async move { public_addrs(resolve.await?).map(Vec::into_iter) })
+    }
+}
+
+/// Check the addresses a webhook domain resolves to.
+///
+/// An error is returned if any address is a local address (see
+/// [`is_local_ip`]), or if there is no address.
+fn public_addrs(addrs: impl IntoIterator<Item = SocketAddr>) -> Result<Vec<SocketAddr>, io::Error> {
+    let addrs = addrs.into_iter().collect::<Vec<_>>();
+
+    if addrs.iter().any(|addr| is_local_ip(addr.ip())) {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "webhook domain resolves to a local address",
+        ));
+    }
+
+    if addrs.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "webhook domain has no address",
+        ));
+    }
+
+    Ok(addrs)
+}
+
+/// Generate a new signing secret.
+pub fn generate_secret() -> String {
+    let secret: [u8; 32] = rand::thread_rng().gen();
+
+    hex::encode(secret)
+}
+
+/// Compute the signature of a payload.
+fn sign(secret: &str, payload: &[u8]) -> Result<String, anyhow::Error> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .map_err(|_| anyhow!("invalid webhook secret"))?;
+    mac.update(payload);
+
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Send a payload to a webhook.
+pub async fn send(url: &Url, secret: &str, payload: &str) -> Result<(), anyhow::Error> {
+    // Urls configured before the local addresses were rejected.
+    if parse_url(url.as_str()).is_none() {
+        bail!("webhook url is not allowed");
+    }
+
+    let signature = sign(secret, payload.as_bytes())?;
+    let request = Request::post(url.as_str())
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(SIGNATURE_HEADER, format!("sha256={signature}"))
+        .body(Body::from(payload.to_owned()))?;
+
+    let response = tokio::time::timeout(REQUEST_TIMEOUT, CLIENT.request(request))
+        .await
+        .context("webhook request timed out")??;
+
+    if !response.status().is_success() {
+        bail!("webhook returned status {}", response.status());
+    }
+
+    Ok(())
+}
+
+/// Schedule the delivery of a moderation log to the webhook of its guild.
+///
+/// Nothing is sent if the guild has no webhook configured, or for shadow
+/// sanctions (see [`Modlog::shadow`]).
+pub async fn modlog_created(state: &ClusterState, modlog: &Modlog, case_id: String) {
+    if let Err(error) = schedule_modlog(state, modlog, case_id).await {
+        error!(error = ?error, guild = ?modlog.guild_id, "failed to schedule webhook delivery");
+    }
+}
+
+async fn schedule_modlog(
+    state: &ClusterState,
+    modlog: &Modlog,
+    case_id: String,
+) -> Result<(), anyhow::Error> {
+    if modlog.shadow {
+        return Ok(());
+    }
+
+    let config = database::guild_config(state, modlog.guild_id).await?;
+    if config.outbound_webhook.is_none() {
+        return Ok(());
+    }
+
+    let job = ScheduledJob::WebhookDelivery {
+        guild_id: modlog.guild_id,
+        payload: serde_json::to_string(&WebhookPayload::modlog(modlog, case_id))?,
+        attempt: 0,
+    };
+
+    state
+        .cache
+        .schedule_job(&job, OffsetDateTime::now_utc())
+        .await
+}
+
+/// Deliver a payload to the webhook of a guild.
+///
+/// Failed deliveries are retried with a new job, until [`MAX_RETRIES`] is
+/// reached.
+pub async fn deliver(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+    payload: &str,
+    attempt: u8,
+) -> Result<(), anyhow::Error> {
+    let config = database::guild_config(state, guild_id).await?;
+    let webhook = match &config.outbound_webhook {
+        Some(webhook) => webhook,
+        None => return Ok(()),
+    };
+
+    let error = match send(&webhook.url, &webhook.secret, payload).await {
+        Ok(()) => {
+            if webhook.failures > 0 {
+                state.database.reset_webhook_failures(guild_id).await?;
+            }
+
+            return Ok(());
+        }
+        Err(error) => error,
+    };
+
+    if attempt < MAX_RETRIES {
+        debug!(error = ?error, guild = ?guild_id, attempt, "webhook delivery failed, retrying");
+
+        let job = ScheduledJob::WebhookDelivery {
+            guild_id,
+            payload: payload.to_owned(),
+            attempt: attempt + 1,
+        };
+        let at = OffsetDateTime::now_utc() + retry_delay(attempt);
+
+        return state.cache.schedule_job(&job, at).await;
+    }
+
+    warn!(error = ?error, guild = ?guild_id, "webhook delivery failed");
+
+    match state.database.record_webhook_failure(guild_id).await? {
+        Some(failures) if failures >= MAX_FAILURES => disable(state, &config, failures).await,
+        _ => Ok(()),
+    }
+}
+
+/// Delay before retrying a failed delivery.
+fn retry_delay(attempt: u8) -> Duration {
+    RETRY_DELAY * 2_i32.pow(attempt.into())
+}
+
+/// Disable the webhook of a guild and send a notice in the logs channel.
+async fn disable(
+    state: &ClusterState,
+    config: &GuildConfig,
+    failures: u32,
+) -> Result<(), anyhow::Error> {
+    warn!(guild = ?config.id, failures, "disabling outbound webhook");
+    state.database.disable_outbound_webhook(config.id).await?;
+
+    let embed = embed::webhook::disabled_notice(config.lang(), failures);
+
+    logs::post(state, config, LogCategory::Config, embed).await
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use twilight_model::util::ImageHash;
+
+    use super::*;
+    use crate::test_utils::fixture;
+
+    fn modlog() -> Modlog {
+        let user = ModlogUser {
+            avatar: Some(ImageHash::parse("1acefe340fafb4ecefae407f3abdb323".as_bytes()).unwrap()),
+            ..fixture::modlog_user(2, "user")
+        };
+        let moderator = ModlogUser {
+            discriminator: 5678,
+            ..fixture::modlog_user(3, "moderator")
+        };
+
+        fixture::modlog(ModlogType::Ban)
+            .guild(Id::new(1))
+            .user(user)
+            .moderator(moderator)
+            .reason("spam")
+            .notes("internal notes")
+            .build()
+    }
+
+    #[test]
+    fn test_modlog_payload_schema() {
+        let payload = WebhookPayload::modlog(&modlog(), "62aca55a551e9a0102351bda".to_owned());
+
+        assert_eq!(
+            serde_json::to_value(&payload).unwrap(),
+            json!({
+                "version": 1,
+                "event": "ban",
+                "case_id": "62aca55a551e9a0102351bda",
+                "guild_id": "1",
+                "actor": { "id": "3", "name": "moderator", "discriminator": 5678 },
+                "target": { "id": "2", "name": "user", "discriminator": 1234 },
+                "reason": "spam",
+                "timestamp": 1_628_594_197,
+            })
+        );
+    }
+
+    #[test]
+    fn test_ping_payload_schema() {
+        let date = OffsetDateTime::from_unix_timestamp(1_628_594_197).unwrap();
+        let payload = WebhookPayload::ping(Id::new(1), date);
+
+        assert_eq!(
+            serde_json::to_value(&payload).unwrap(),
+            json!({
+                "version": 1,
+                "event": "ping",
+                "case_id": null,
+                "guild_id": "1",
+                "actor": null,
+                "target": null,
+                "reason": null,
+                "timestamp": 1_628_594_197,
+            })
+        );
+    }
+
+    #[test]
+    fn test_event_names() {
+        let events = [
+            (ModlogType::Ban, "ban"),
+            (ModlogType::Kick, "kick"),
+            (ModlogType::Mute, "mute"),
+            (ModlogType::Unmute, "unmute"),
+            (ModlogType::Quarantine, "quarantine"),
+            (ModlogType::Release, "release"),
+        ];
+
+        for (kind, name) in events {
+            let event = WebhookEvent::from(kind);
+            assert_eq!(serde_json::to_value(event).unwrap(), json!(name));
+        }
+    }
+
+    #[test]
+    fn test_parse_url() {
+        assert!(parse_url("https://example.com/hooks/raidprotect").is_some());
+        assert!(parse_url(" https://example.com ").is_some());
+
+        assert!(parse_url("http://example.com").is_none());
+        assert!(parse_url("ftp://example.com").is_none());
+        assert!(parse_url("example.com").is_none());
+        assert!(parse_url("https://").is_none());
+    }
+
+    #[test]
+    fn test_parse_url_local() {
+        assert!(parse_url("https://93.184.216.34/hooks").is_some());
+        assert!(parse_url("https://[2606:2800:220:1::]/hooks").is_some());
+        assert!(parse_url("https://localhost.example.com").is_some());
+
+        assert!(parse_url("https://localhost/hooks").is_none());
+        assert!(parse_url("https://LOCALHOST./hooks").is_none());
+        assert!(parse_url("https://api.localhost").is_none());
+        assert!(parse_url("https://127.0.0.1:8080").is_none());
+        assert!(parse_url("https://0.0.0.0").is_none());
+        assert!(parse_url("https://10.0.0.1").is_none());
+        assert!(parse_url("https://172.16.5.4").is_none());
+        assert!(parse_url("https://192.168.1.1").is_none());
+        assert!(parse_url("https://169.254.169.254/latest/meta-data").is_none());
+        assert!(parse_url("https://[::1]").is_none());
+        assert!(parse_url("https://[fd00::1]").is_none());
+        assert!(parse_url("https://[fe80::1]").is_none());
+        assert!(parse_url("https://[::ffff:127.0.0.1]").is_none());
+    }
+
+    #[test]
+    fn test_is_local_ip() {
+        let local = [
+            "0.1.2.3",
+            "100.64.0.1",
+            "100.127.255.254",
+            "255.255.255.255",
+            "224.0.0.1",
+            "239.255.255.250",
+            "192.0.0.8",
+            "198.18.0.1",
+            "198.19.255.254",
+            "::ffff:10.0.0.1",
+            "::ffff:169.254.169.254",
+            "::10.0.0.1",
+            "::127.0.0.1",
+            "2002:a00:1::",
+            "2002:7f00:1::1",
+            "2002:a9fe:a9fe::",
+            "ff02::1",
+            "ff0e::1",
+        ];
+        let public = [
+            "93.184.216.34",
+            "100.128.0.1",
+            "198.20.0.1",
+            "::ffff:93.184.216.34",
+            "2002:5db8:d822::",
+            "2606:2800:220:1::",
+        ];
+
+        for ip in local {
+            assert!(is_local_ip(ip.parse().unwrap()), "{ip} is local");
+        }
+        for ip in public {
+            assert!(!is_local_ip(ip.parse().unwrap()), "{ip} is public");
+        }
+    }
+
+    #[test]
+    fn test_public_addrs() {
+        let public = "93.184.216.34:443".parse::<SocketAddr>().unwrap();
+        let private = "10.0.0.1:443".parse::<SocketAddr>().unwrap();
+        let metadata = "169.254.169.254:443".parse::<SocketAddr>().unwrap();
+
+        assert_eq!(public_addrs([public]).unwrap(), vec![public]);
+
+        assert!(public_addrs([private]).is_err());
+        assert!(public_addrs([public, metadata]).is_err());
+        assert!(public_addrs(Vec::new()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolver_rejects_local_domain() {
+        // `localhost` is resolved from the hosts file, and is used as a public
+        // domain that resolves to a loopback address.
+        let name = "localhost".parse::<Name>().unwrap();
+        let error = PublicResolver::new().call(name).await.unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+    }
+
+    #[test]
+    fn test_generate_secret() {
+        let secret = generate_secret();
+
+        assert_eq!(secret.len(), 64);
+        assert_ne!(secret, generate_secret());
+    }
+
+    #[test]
+    fn test_sign() {
+        // Test vector from RFC 4231 (test case 2).
+        let signature = sign("Jefe", b"what do ya want for nothing?").unwrap();
+
+        assert_eq!(
+            signature,
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(0), Duration::seconds(30));
+        assert_eq!(retry_delay(1), Duration::seconds(60));
+        assert_eq!(retry_delay(2), Duration::seconds(120));
+    }
+}
@@ -0,0 +1,261 @@
+//! Health-check HTTP server.
+//!
+//! This server exposes the health of the bot to container orchestrators such
+//! as Kubernetes. It is only started if an address is configured (see
+//! [`BotConfig::health_address`]).
+//!
+//! ## Endpoints
+//! - `GET /health/live`: always returns `200 OK` while the process is running.
+//! - `GET /health/ready`: checks the gateway, MongoDB and Redis, and returns
+//!   `200 OK` only if all of them are healthy, `503 Service Unavailable`
+//!   otherwise. The response body is a JSON object with the result of each
+//!   check. The gateway is not checked in consumer-only mode.
+//!
+//! Each dependency check is cancelled after [`CHECK_TIMEOUT`].
+//!
+//! [`BotConfig::health_address`]: raidprotect_model::config::BotConfig::health_address
+
+use std::{convert::Infallible, future::Future, net::SocketAddr, sync::Arc, time::Duration};
+
+use hyper::{
+    header,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use serde::Serialize;
+use tracing::{error, info};
+use twilight_gateway::{shard::Stage, Cluster};
+
+use crate::{cluster::ClusterState, util::shutdown::ShutdownSubscriber};
+
+/// Maximum duration of each dependency check.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Run the health-check server until a shutdown signal is received.
+///
+/// No cluster is provided in consumer-only mode.
+pub async fn run(
+    address: SocketAddr,
+    cluster: Option<Arc<Cluster>>,
+    state: ClusterState,
+    mut shutdown: ShutdownSubscriber,
+) {
+    let make_service = make_service_fn(move |_| {
+        let cluster = cluster.clone();
+        let state = state.clone();
+
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                handle(request, cluster.clone(), state.clone())
+            }))
+        }
+    });
+
+    let server = match Server::try_bind(&address) {
+        Ok(builder) => builder.serve(make_service),
+        Err(error) => {
+            error!(error = ?error, "failed to bind health-check server");
+            return;
+        }
+    };
+
+    info!("health-check server listening on {address}");
+
+    let server = server.with_graceful_shutdown(async move { shutdown.wait_shutdown().await });
+    if let Err(error) = server.await {
+        error!(error = ?error, "health-check server error");
+    }
+}
+
+/// Handle an incoming request.
+async fn handle(
+    request: Request<Body>,
+    cluster: Option<Arc<Cluster>>,
+    state: ClusterState,
+) -> Result<Response<Body>, Infallible> {
+    let response = match (request.method(), request.uri().path()) {
+        (&Method::GET, "/health/live") => Response::new(Body::from("OK")),
+        (&Method::GET, "/health/ready") => {
+            let report = readiness(cluster.as_deref(), &state).await;
+            json_response(report.status_code(), &report)
+        }
+        _ => empty_response(StatusCode::NOT_FOUND),
+    };
+
+    Ok(response)
+}
+
+/// Check the readiness of the bot dependencies.
+async fn readiness(cluster: Option<&Cluster>, state: &ClusterState) -> ReadinessReport {
+    let (mongodb, redis) = tokio::join!(check(state.database.ping()), check(state.cache.ping()));
+
+    let gateway = match cluster {
+        Some(cluster) => {
+            let stages = cluster
+                .info()
+                .into_values()
+                .map(|info| info.stage())
+                .collect::<Vec<_>>();
+
+            gateway_status(&stages)
+        }
+        None => CheckStatus::healthy(Some("consumer-only mode".to_owned())),
+    };
+
+    ReadinessReport {
+        gateway,
+        mongodb,
+        redis,
+    }
+}
+
+/// Run a dependency check with a timeout.
+async fn check(future: impl Future<Output = Result<(), anyhow::Error>>) -> CheckStatus {
+    match tokio::time::timeout(CHECK_TIMEOUT, future).await {
+        Ok(Ok(())) => CheckStatus::healthy(None),
+        Ok(Err(error)) => CheckStatus::unhealthy(error.to_string()),
+        Err(_) => CheckStatus::unhealthy(format!(
+            "timed out after {} seconds",
+            CHECK_TIMEOUT.as_secs()
+        )),
+    }
+}
+
+/// Get the gateway status from the stage of each shard.
+///
+/// The gateway is healthy if all shards are connected.
+fn gateway_status(stages: &[Stage]) -> CheckStatus {
+    let connected = stages
+        .iter()
+        .filter(|stage| **stage == Stage::Connected)
+        .count();
+    let details = format!("{connected}/{} shards connected", stages.len());
+
+    if !stages.is_empty() && connected == stages.len() {
+        CheckStatus::healthy(Some(details))
+    } else {
+        CheckStatus::unhealthy(details)
+    }
+}
+
+/// Result of the readiness checks.
+#[derive(Debug, Serialize)]
+struct ReadinessReport {
+    gateway: CheckStatus,
+    mongodb: CheckStatus,
+    redis: CheckStatus,
+}
+
+impl ReadinessReport {
+    /// Whether all dependencies are healthy.
+    fn is_healthy(&self) -> bool {
+        self.gateway.healthy && self.mongodb.healthy && self.redis.healthy
+    }
+
+    /// Status code of the response.
+    fn status_code(&self) -> StatusCode {
+        match self.is_healthy() {
+            true => StatusCode::OK,
+            false => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+}
+
+/// Result of a single dependency check.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct CheckStatus {
+    /// Whether the dependency is healthy.
+    healthy: bool,
+    /// Details about the dependency state, or the error message.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<String>,
+}
+
+impl CheckStatus {
+    fn healthy(details: Option<String>) -> Self {
+        Self {
+            healthy: true,
+            details,
+        }
+    }
+
+    fn unhealthy(details: String) -> Self {
+        Self {
+            healthy: false,
+            details: Some(details),
+        }
+    }
+}
+
+/// Build a JSON response.
+fn json_response(status: StatusCode, body: &impl Serialize) -> Response<Body> {
+    let body = match serde_json::to_vec(body) {
+        Ok(body) => body,
+        Err(error) => {
+            error!(error = ?error, "failed to serialize health-check response");
+            return empty_response(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("application/json"),
+    );
+
+    response
+}
+
+/// Build an empty response with the given status code.
+fn empty_response(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gateway_status() {
+        let status = gateway_status(&[Stage::Connected, Stage::Connected]);
+        assert_eq!(
+            status,
+            CheckStatus::healthy(Some("2/2 shards connected".to_owned()))
+        );
+
+        let status = gateway_status(&[Stage::Connected, Stage::Resuming]);
+        assert_eq!(
+            status,
+            CheckStatus::unhealthy("1/2 shards connected".to_owned())
+        );
+
+        assert!(!gateway_status(&[]).healthy);
+    }
+
+    #[test]
+    fn test_report() {
+        let mut report = ReadinessReport {
+            gateway: CheckStatus::healthy(None),
+            mongodb: CheckStatus::healthy(None),
+            redis: CheckStatus::healthy(None),
+        };
+        assert_eq!(report.status_code(), StatusCode::OK);
+
+        report.redis = CheckStatus::unhealthy("connection refused".to_owned());
+        assert_eq!(report.status_code(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "gateway": { "healthy": true },
+                "mongodb": { "healthy": true },
+                "redis": { "healthy": false, "details": "connection refused" },
+            })
+        );
+    }
+}
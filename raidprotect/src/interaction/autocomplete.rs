@@ -0,0 +1,215 @@
+//! Autocomplete interactions.
+//!
+//! Autocomplete is used to suggest the reason presets of the server (see the
+//! `/config reasons` command) in the `reason` option of sanction commands, the
+//! commands available to the member in the `command` option of the `/help`
+//! command, and the tags of the server in the `name` option of the `/tag`
+//! command.
+
+use anyhow::bail;
+use twilight_model::{
+    application::{
+        command::{CommandOptionChoice, CommandType},
+        interaction::{
+            application_command::{CommandDataOption, CommandOptionValue},
+            Interaction, InteractionData,
+        },
+    },
+    http::interaction::{InteractionResponseData, InteractionResponseType},
+};
+
+use super::{
+    command::{available_commands, CommandMeta},
+    response::InteractionResponse,
+    util::CommandPath,
+};
+use crate::{cluster::ClusterState, database};
+
+/// Commands with a `reason` option that suggests the reason presets.
+const SANCTION_COMMANDS: &[&str] = &["ban", "kick", "mute", "unmute", "quarantine", "release"];
+
+/// Maximum number of autocomplete choices.
+const MAX_CHOICES: usize = 25;
+
+/// Handle incoming autocomplete interaction.
+pub async fn handle_autocomplete(
+    interaction: Interaction,
+    state: &ClusterState,
+) -> Result<InteractionResponse, anyhow::Error> {
+    let data = match &interaction.data {
+        Some(InteractionData::ApplicationCommand(data)) => data,
+        _ => bail!("expected application command data"),
+    };
+
+    let (path, options) = CommandPath::resolve(data);
+    let (option, value) = match focused_option(options) {
+        Some(focused) => focused,
+        None => bail!("missing focused option"),
+    };
+
+    let suggest_presets = match (path.route(), option) {
+        ((name, None, None), "reason") => SANCTION_COMMANDS.contains(&name),
+        (("config", Some("reasons"), Some("remove")), "preset") => true,
+        _ => false,
+    };
+
+    let choices = match (suggest_presets, interaction.guild_id) {
+        (true, Some(guild_id)) => {
+            let config = database::guild_config(state, guild_id).await?;
+
+            text_choices(&config.moderation.reason_presets, value)
+        }
+        (false, Some(guild_id)) if matches!((path.route(), option), (("tag", None, _), "name")) => {
+            let config = database::guild_config(state, guild_id).await?;
+            let names = config
+                .tags
+                .tags
+                .into_iter()
+                .map(|tag| tag.name)
+                .collect::<Vec<_>>();
+
+            text_choices(&names, value)
+        }
+        _ if (path.route(), option) == (("help", None, None), "command") => {
+            let permissions = interaction.member.as_ref().and_then(|m| m.permissions);
+            let is_operator = interaction
+                .author_id()
+                .map_or(false, |id| state.is_operator(id));
+            let commands = available_commands(permissions, is_operator).collect::<Vec<_>>();
+
+            command_choices(&commands, value)
+        }
+        _ => Vec::new(),
+    };
+
+    Ok(InteractionResponse::Raw {
+        kind: InteractionResponseType::ApplicationCommandAutocompleteResult,
+        data: Some(InteractionResponseData {
+            choices: Some(choices),
+            ..Default::default()
+        }),
+    })
+}
+
+/// Get the name and value of the focused option.
+///
+/// The options are the options of the invoked subcommand (see
+/// [`CommandPath::resolve`]).
+fn focused_option(options: &[CommandDataOption]) -> Option<(&str, &str)> {
+    options.iter().find_map(|option| match &option.value {
+        CommandOptionValue::Focused(value, _) => Some((&*option.name, &**value)),
+        _ => None,
+    })
+}
+
+/// Reason presets or tag names that match the user input, ignoring case.
+fn text_choices(texts: &[String], input: &str) -> Vec<CommandOptionChoice> {
+    let input = input.trim().to_lowercase();
+
+    texts
+        .iter()
+        .filter(|text| text.to_lowercase().contains(&input))
+        .take(MAX_CHOICES)
+        .map(|text| CommandOptionChoice::String {
+            name: text.clone(),
+            name_localizations: None,
+            value: text.clone(),
+        })
+        .collect()
+}
+
+/// Commands whose name match the user input, ignoring case.
+///
+/// Only slash commands are suggested, context menu commands cannot be used
+/// with a slash.
+fn command_choices(commands: &[&CommandMeta], input: &str) -> Vec<CommandOptionChoice> {
+    let input = input.trim().trim_start_matches('/').to_lowercase();
+
+    commands
+        .iter()
+        .filter(|command| command.kind == CommandType::ChatInput)
+        .filter(|command| command.name.contains(&input))
+        .take(MAX_CHOICES)
+        .map(|command| CommandOptionChoice::String {
+            name: format!("/{}", command.name),
+            name_localizations: None,
+            value: command.name.to_owned(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::{
+        application::{command::CommandOptionType, interaction::application_command::CommandData},
+        id::Id,
+    };
+
+    use super::*;
+    use crate::interaction::command::COMMANDS;
+
+    #[test]
+    fn test_focused_option() {
+        let data = CommandData {
+            guild_id: None,
+            id: Id::new(1),
+            name: "config".to_owned(),
+            kind: CommandType::ChatInput,
+            options: vec![CommandDataOption {
+                name: "reasons".to_owned(),
+                value: CommandOptionValue::SubCommandGroup(vec![CommandDataOption {
+                    name: "remove".to_owned(),
+                    value: CommandOptionValue::SubCommand(vec![CommandDataOption {
+                        name: "preset".to_owned(),
+                        value: CommandOptionValue::Focused(
+                            "sp".to_owned(),
+                            CommandOptionType::String,
+                        ),
+                    }]),
+                }]),
+            }],
+            resolved: None,
+            target_id: None,
+        };
+
+        let (path, options) = CommandPath::resolve(&data);
+        assert_eq!(path.route(), ("config", Some("reasons"), Some("remove")));
+        assert_eq!(focused_option(options), Some(("preset", "sp")));
+        assert_eq!(focused_option(&[]), None);
+    }
+
+    #[test]
+    fn test_text_choices() {
+        let presets = vec!["Spam".to_owned(), "Insults".to_owned()];
+
+        let choices = text_choices(&presets, "SP");
+        assert_eq!(
+            choices,
+            vec![CommandOptionChoice::String {
+                name: "Spam".to_owned(),
+                name_localizations: None,
+                value: "Spam".to_owned(),
+            }]
+        );
+
+        assert_eq!(text_choices(&presets, "").len(), 2);
+    }
+
+    #[test]
+    fn test_command_choices() {
+        let commands = COMMANDS.iter().collect::<Vec<_>>();
+
+        let choices = command_choices(&commands, "/BAN");
+        assert!(choices.contains(&CommandOptionChoice::String {
+            name: "/ban".to_owned(),
+            name_localizations: None,
+            value: "ban".to_owned(),
+        }));
+        assert!(choices.iter().all(|choice| match choice {
+            CommandOptionChoice::String { value, .. } => value.contains("ban"),
+            _ => false,
+        }));
+
+        assert!(command_choices(&commands, "report").is_empty());
+    }
+}
@@ -0,0 +1,595 @@
+//! Administration command.
+//!
+//! This command allows the bot operators to manage the bot globally. It is
+//! only available to users configured as operators, which is checked when
+//! dispatching the command.
+
+use std::{cmp::Reverse, str::FromStr};
+
+use anyhow::Context;
+use raidprotect_model::cache::{
+    discord::{reconcile::ResourceReport, CachedGuild},
+    model::{job::ScheduledJob, maintenance::Maintenance},
+};
+use time::OffsetDateTime;
+use tracing::warn;
+use twilight_interactions::command::{CommandModel, CommandOption, CreateCommand, CreateOption};
+use twilight_model::id::{marker::GuildMarker, Id};
+use twilight_util::builder::embed::EmbedFieldBuilder;
+
+use crate::{
+    cluster::ClusterState,
+    desc_localizations,
+    feature::{
+        reconcile,
+        simulation::{self, SimulatedJoins},
+    },
+    impl_command_handle,
+    interaction::{
+        embed::{self, EmbedBuilder, COLOR_RED, COLOR_SUCCESS, COLOR_TRANSPARENT},
+        response::InteractionResponse,
+        sync_commands,
+        util::InteractionContext,
+    },
+    util::{log::refresh_debug_guilds, TextProcessExt},
+};
+
+/// Administration command model.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "admin",
+    desc = "Manage RaidProtect (reserved to bot operators)",
+    desc_localizations = "admin_description"
+)]
+pub enum AdminCommand {
+    #[command(name = "guilds")]
+    Guilds(AdminGuildsCommand),
+    #[command(name = "leave")]
+    Leave(AdminLeaveCommand),
+    #[command(name = "broadcast")]
+    Broadcast(AdminBroadcastCommand),
+    #[command(name = "maintenance")]
+    Maintenance(AdminMaintenanceCommand),
+    #[command(name = "simulate-raid")]
+    SimulateRaid(AdminSimulateRaidCommand),
+    #[command(name = "debug-guild")]
+    DebugGuild(AdminDebugGuildCommand),
+    #[command(name = "reconcile")]
+    Reconcile(AdminReconcileCommand),
+    #[command(name = "recompute-stats")]
+    RecomputeStats(AdminRecomputeStatsCommand),
+    #[command(name = "sync-commands")]
+    SyncCommands(AdminSyncCommandsCommand),
+}
+
+impl_command_handle!(AdminCommand);
+desc_localizations!(admin_description);
+
+impl AdminCommand {
+    async fn exec(
+        self,
+        ctx: InteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        match self {
+            Self::Guilds(command) => command.exec(ctx, state).await,
+            Self::Leave(command) => command.exec(ctx, state).await,
+            Self::Broadcast(command) => command.exec(ctx, state).await,
+            Self::Maintenance(command) => command.exec(ctx, state).await,
+            Self::SimulateRaid(command) => command.exec(ctx, state).await,
+            Self::DebugGuild(command) => command.exec(ctx, state).await,
+            Self::Reconcile(command) => command.exec(ctx, state).await,
+            Self::RecomputeStats(command) => command.exec(ctx, state).await,
+            Self::SyncCommands(command) => command.exec(ctx, state).await,
+        }
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "guilds",
+    desc = "Show the guilds RaidProtect is in",
+    desc_localizations = "admin_guilds_description"
+)]
+pub struct AdminGuildsCommand;
+
+desc_localizations!(admin_guilds_description);
+
+impl AdminGuildsCommand {
+    /// Number of guilds shown in the largest guilds list.
+    const LARGEST_LEN: usize = 10;
+
+    async fn exec(
+        self,
+        ctx: InteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut guilds = state.cache.guilds().await?;
+        guilds.sort_unstable_by_key(|guild| Reverse(guild.member_count));
+
+        let largest = guilds
+            .iter()
+            .take(Self::LARGEST_LEN)
+            .map(|guild| {
+                ctx.lang
+                    .admin_guilds_entry(guild.id, guild.member_count, &guild.name)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let embed = EmbedBuilder::new()
+            .color(COLOR_TRANSPARENT)
+            .title(ctx.lang.admin_guilds_title(guilds.len()))
+            .description(largest)
+            .build();
+
+        Ok(InteractionResponse::EphemeralEmbed(embed))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "leave",
+    desc = "Make RaidProtect leave a guild",
+    desc_localizations = "admin_leave_description"
+)]
+pub struct AdminLeaveCommand {
+    /// ID of the guild to leave.
+    guild_id: String,
+}
+
+desc_localizations!(admin_leave_description);
+
+impl AdminLeaveCommand {
+    async fn exec(
+        self,
+        ctx: InteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let guild_id = match parse_guild_id(&ctx, &self.guild_id) {
+            Ok(guild_id) => guild_id,
+            Err(response) => return Ok(response),
+        };
+
+        state.http.leave_guild(guild_id).exec().await?;
+
+        let embed = EmbedBuilder::new()
+            .color(COLOR_SUCCESS)
+            .description(ctx.lang.admin_leave_success(guild_id))
+            .build();
+
+        Ok(InteractionResponse::EphemeralEmbed(embed))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "broadcast",
+    desc = "Send an announcement to every configured logs channel",
+    desc_localizations = "admin_broadcast_description"
+)]
+pub struct AdminBroadcastCommand {
+    /// Title of the announcement.
+    #[command(max_length = 256)]
+    title: String,
+    /// Content of the announcement.
+    #[command(max_length = 4000)]
+    message: String,
+}
+
+desc_localizations!(admin_broadcast_description);
+
+impl AdminBroadcastCommand {
+    async fn exec(
+        self,
+        ctx: InteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        // The broadcast is sent in batches by the scheduler.
+        let job = ScheduledJob::Broadcast {
+            title: self.title,
+            message: self.message,
+            cursor: None,
+        };
+        state
+            .cache
+            .schedule_job(&job, OffsetDateTime::now_utc())
+            .await?;
+
+        let embed = EmbedBuilder::new()
+            .color(COLOR_SUCCESS)
+            .description(ctx.lang.admin_broadcast_started())
+            .build();
+
+        Ok(InteractionResponse::EphemeralEmbed(embed))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "maintenance",
+    desc = "Enable or disable the maintenance mode",
+    desc_localizations = "admin_maintenance_description"
+)]
+pub struct AdminMaintenanceCommand {
+    /// Maintenance mode state.
+    mode: MaintenanceMode,
+}
+
+desc_localizations!(admin_maintenance_description);
+
+/// Maintenance mode state.
+#[derive(Debug, Clone, Copy, CommandOption, CreateOption)]
+pub enum MaintenanceMode {
+    #[option(name = "on", value = "on")]
+    On,
+    #[option(name = "off", value = "off")]
+    Off,
+}
+
+impl AdminMaintenanceCommand {
+    async fn exec(
+        self,
+        ctx: InteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let description = match self.mode {
+            MaintenanceMode::On => {
+                Maintenance::set_enabled(&state.cache, true).await?;
+                ctx.lang.admin_maintenance_enabled()
+            }
+            MaintenanceMode::Off => {
+                Maintenance::set_enabled(&state.cache, false).await?;
+                ctx.lang.admin_maintenance_disabled()
+            }
+        };
+
+        let embed = EmbedBuilder::new()
+            .color(COLOR_SUCCESS)
+            .description(description)
+            .build();
+
+        Ok(InteractionResponse::EphemeralEmbed(embed))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "simulate-raid",
+    desc = "Simulate a raid on this server to test the raid mode settings",
+    desc_localizations = "admin_simulate_raid_description"
+)]
+pub struct AdminSimulateRaidCommand {
+    /// Number of synthetic joins per second.
+    #[command(min_value = 1, max_value = 10)]
+    joins_per_second: i64,
+    /// Duration of the simulation (in seconds).
+    #[command(min_value = 1, max_value = 60)]
+    duration: i64,
+}
+
+desc_localizations!(admin_simulate_raid_description);
+
+impl AdminSimulateRaidCommand {
+    async fn exec(
+        self,
+        ctx: InteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let guild_id = match ctx.interaction.guild_id {
+            Some(guild_id) => guild_id,
+            None => {
+                return Ok(simulation_error(
+                    &ctx,
+                    ctx.lang.admin_simulate_raid_guild_only().to_owned(),
+                ))
+            }
+        };
+
+        let guild = state
+            .cache
+            .get::<CachedGuild>(&guild_id)
+            .await?
+            .context("guild not cached")?;
+
+        if guild.member_count > simulation::MAX_MEMBER_COUNT {
+            let description = ctx
+                .lang
+                .admin_simulate_raid_too_large(guild.member_count, simulation::MAX_MEMBER_COUNT);
+
+            return Ok(simulation_error(&ctx, description));
+        }
+
+        // The values are bounded by the command options.
+        let joins_per_second = self.joins_per_second as u32;
+        let duration = self.duration as u32;
+
+        let source = SimulatedJoins::new(guild_id, joins_per_second, duration);
+        tokio::spawn(simulation::run(state.clone(), source));
+
+        let embed = EmbedBuilder::new()
+            .color(COLOR_SUCCESS)
+            .description(
+                ctx.lang
+                    .admin_simulate_raid_started(duration, joins_per_second * duration),
+            )
+            .build();
+
+        Ok(InteractionResponse::EphemeralEmbed(embed))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "debug-guild",
+    desc = "Enable or disable the debug logs of a guild",
+    desc_localizations = "admin_debug_guild_description"
+)]
+pub struct AdminDebugGuildCommand {
+    /// ID of the guild.
+    guild_id: String,
+    /// Debug logs state.
+    mode: DebugLogsState,
+}
+
+desc_localizations!(admin_debug_guild_description);
+
+/// Debug logs state of a guild.
+#[derive(Debug, Clone, Copy, CommandOption, CreateOption)]
+pub enum DebugLogsState {
+    #[option(name = "enabled", value = "enabled")]
+    Enabled,
+    #[option(name = "disabled", value = "disabled")]
+    Disabled,
+}
+
+impl AdminDebugGuildCommand {
+    async fn exec(
+        self,
+        ctx: InteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let guild_id = match parse_guild_id(&ctx, &self.guild_id) {
+            Ok(guild_id) => guild_id,
+            Err(response) => return Ok(response),
+        };
+
+        let enabled = matches!(self.mode, DebugLogsState::Enabled);
+        state.cache.set_debug_guild(guild_id, enabled).await?;
+
+        // Other instances pick up the change on their next refresh.
+        refresh_debug_guilds(state).await?;
+
+        let description = match enabled {
+            true => ctx.lang.admin_debug_guild_enabled(guild_id),
+            false => ctx.lang.admin_debug_guild_disabled(guild_id),
+        };
+
+        let embed = EmbedBuilder::new()
+            .color(COLOR_SUCCESS)
+            .description(description)
+            .build();
+
+        Ok(InteractionResponse::EphemeralEmbed(embed))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "reconcile",
+    desc = "Refresh the cached roles and channels of a guild",
+    desc_localizations = "admin_reconcile_description"
+)]
+pub struct AdminReconcileCommand {
+    /// ID of the guild.
+    guild_id: String,
+}
+
+desc_localizations!(admin_reconcile_description);
+
+impl AdminReconcileCommand {
+    async fn exec(
+        self,
+        ctx: InteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let guild_id = match parse_guild_id(&ctx, &self.guild_id) {
+            Ok(guild_id) => guild_id,
+            Err(response) => return Ok(response),
+        };
+
+        let report = match reconcile::reconcile(state, guild_id).await? {
+            Some(report) => report,
+            None => {
+                let embed = EmbedBuilder::new()
+                    .color(COLOR_RED)
+                    .description(ctx.lang.admin_reconcile_running(guild_id))
+                    .build();
+
+                return Ok(InteractionResponse::EphemeralEmbed(embed));
+            }
+        };
+
+        let (color, description) = match report.discrepancies() {
+            0 => (
+                COLOR_SUCCESS,
+                ctx.lang.admin_reconcile_consistent().to_owned(),
+            ),
+            count => (COLOR_RED, ctx.lang.admin_reconcile_discrepancies(count)),
+        };
+
+        let embed = EmbedBuilder::new()
+            .color(color)
+            .title(ctx.lang.admin_reconcile_title(guild_id))
+            .description(description)
+            .field(reconcile_field(
+                &ctx,
+                ctx.lang.admin_reconcile_roles(),
+                report.roles,
+            ))
+            .field(reconcile_field(
+                &ctx,
+                ctx.lang.admin_reconcile_channels(),
+                report.channels,
+            ))
+            .build();
+
+        Ok(InteractionResponse::EphemeralEmbed(embed))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "recompute-stats",
+    desc = "Recompute the sanction statistics of a guild from its modlogs",
+    desc_localizations = "admin_recompute_stats_description"
+)]
+pub struct AdminRecomputeStatsCommand {
+    /// ID of the guild.
+    guild_id: String,
+}
+
+desc_localizations!(admin_recompute_stats_description);
+
+impl AdminRecomputeStatsCommand {
+    async fn exec(
+        self,
+        ctx: InteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let guild_id = match parse_guild_id(&ctx, &self.guild_id) {
+            Ok(guild_id) => guild_id,
+            Err(response) => return Ok(response),
+        };
+
+        let (previous, recomputed) = state
+            .database
+            .recompute_stats(guild_id, OffsetDateTime::now_utc())
+            .await?;
+
+        let description = match previous == recomputed {
+            true => ctx.lang.admin_recompute_stats_consistent(),
+            false => ctx.lang.admin_recompute_stats_corrected(),
+        };
+
+        let embed = EmbedBuilder::new()
+            .color(COLOR_SUCCESS)
+            .title(ctx.lang.admin_recompute_stats_title(guild_id))
+            .description(description)
+            .field(
+                EmbedFieldBuilder::new(
+                    ctx.lang.admin_recompute_stats_previous(),
+                    embed::stats::sanctions(ctx.lang, previous),
+                )
+                .inline(),
+            )
+            .field(
+                EmbedFieldBuilder::new(
+                    ctx.lang.admin_recompute_stats_recomputed(),
+                    embed::stats::sanctions(ctx.lang, recomputed),
+                )
+                .inline(),
+            )
+            .build();
+
+        Ok(InteractionResponse::EphemeralEmbed(embed))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "sync-commands",
+    desc = "Register the commands to Discord again",
+    desc_localizations = "admin_sync_commands_description"
+)]
+pub struct AdminSyncCommandsCommand {
+    /// ID of a test guild (commands are registered globally if not set).
+    guild_id: Option<String>,
+}
+
+desc_localizations!(admin_sync_commands_description);
+
+impl AdminSyncCommandsCommand {
+    async fn exec(
+        self,
+        ctx: InteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let guild_id = match self.guild_id.as_deref().map(|id| parse_guild_id(&ctx, id)) {
+            Some(Ok(guild_id)) => Some(guild_id),
+            Some(Err(response)) => return Ok(response),
+            None => None,
+        };
+
+        let (color, description) = match sync_commands(state, state.current_user, guild_id).await {
+            Ok(count) => match guild_id {
+                Some(guild_id) => (
+                    COLOR_SUCCESS,
+                    ctx.lang.admin_sync_commands_guild(count, guild_id),
+                ),
+                None => (COLOR_SUCCESS, ctx.lang.admin_sync_commands_global(count)),
+            },
+            Err(error) => {
+                warn!(error = ?error, guild = ?guild_id, "failed to sync commands");
+
+                (
+                    COLOR_RED,
+                    ctx.lang
+                        .admin_sync_commands_failed(error.to_string().max_len(1000)),
+                )
+            }
+        };
+
+        let embed = EmbedBuilder::new()
+            .color(color)
+            .description(description)
+            .build();
+
+        Ok(InteractionResponse::EphemeralEmbed(embed))
+    }
+}
+
+/// Parse the guild id given to a command.
+///
+/// An error response is returned if the id is invalid.
+fn parse_guild_id(
+    ctx: &InteractionContext,
+    input: &str,
+) -> Result<Id<GuildMarker>, InteractionResponse> {
+    Id::from_str(input.trim()).map_err(|_| {
+        let embed = EmbedBuilder::new()
+            .color(COLOR_RED)
+            .title(ctx.lang.admin_invalid_guild_title())
+            .description(ctx.lang.admin_invalid_guild_description())
+            .build();
+
+        InteractionResponse::EphemeralEmbed(embed)
+    })
+}
+
+/// Field of the reconcile command embed.
+fn reconcile_field(
+    ctx: &InteractionContext,
+    name: &str,
+    report: ResourceReport,
+) -> EmbedFieldBuilder {
+    let value = ctx.lang.admin_reconcile_summary(
+        report.missing,
+        report.outdated,
+        report.refreshed,
+        report.stale,
+    );
+
+    EmbedFieldBuilder::new(name, value).inline()
+}
+
+/// Error embed of the simulate raid command.
+fn simulation_error(ctx: &InteractionContext, description: String) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(ctx.lang.admin_simulate_raid_error_title())
+        .description(description)
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
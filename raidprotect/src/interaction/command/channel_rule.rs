@@ -0,0 +1,189 @@
+//! Channel rule command.
+//!
+//! This command allows administrators to restrict the content of a channel,
+//! such as media-only channels. Messages that do not follow the rule are
+//! deleted (see the `channel_rules` event module).
+
+use raidprotect_model::database::model::{ChannelRule, ChannelRuleKind};
+use twilight_interactions::command::{CommandModel, CommandOption, CreateCommand, CreateOption};
+use twilight_model::id::{marker::ChannelMarker, Id};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations, impl_guild_command_handle,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+    util::{
+        collection::upsert,
+        link::{parse_allowed, AllowedLink},
+    },
+};
+
+/// Channel rule command model.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "channel-rule",
+    desc = "Restrict the content allowed in channels",
+    desc_localizations = "channel_rule_description"
+)]
+pub enum ChannelRuleCommand {
+    #[command(name = "set")]
+    Set(ChannelRuleSetCommand),
+    #[command(name = "clear")]
+    Clear(ChannelRuleClearCommand),
+    #[command(name = "list")]
+    List(ChannelRuleListCommand),
+}
+
+impl_guild_command_handle!(ChannelRuleCommand);
+desc_localizations!(channel_rule_description);
+
+impl ChannelRuleCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        match self {
+            Self::Set(command) => command.exec(ctx, state).await,
+            Self::Clear(command) => command.exec(ctx, state).await,
+            Self::List(command) => command.exec(ctx, state).await,
+        }
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "set",
+    desc = "Set the content allowed in a channel",
+    desc_localizations = "channel_rule_set_description"
+)]
+pub struct ChannelRuleSetCommand {
+    /// Channel to restrict. Threads follow the rule of their channel.
+    #[command(channel_types = "guild_text guild_news")]
+    pub channel: Id<ChannelMarker>,
+    /// Content allowed in the channel.
+    pub rule: ChannelRuleOption,
+    /// Allowed domains for the links-only rule, separated by spaces.
+    #[command(max_length = 500)]
+    pub domains: Option<String>,
+}
+
+desc_localizations!(channel_rule_set_description);
+
+/// Content allowed by a channel rule.
+#[derive(Debug, Clone, Copy, CommandOption, CreateOption)]
+pub enum ChannelRuleOption {
+    #[option(name = "Media only", value = "media-only")]
+    Media,
+    #[option(name = "Text only", value = "text-only")]
+    Text,
+    #[option(name = "Links from allowed domains only", value = "link-only")]
+    Link,
+}
+
+impl ChannelRuleSetCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        let kind = match self.rule {
+            ChannelRuleOption::Media => ChannelRuleKind::MediaOnly,
+            ChannelRuleOption::Text => ChannelRuleKind::TextOnly,
+            ChannelRuleOption::Link => {
+                let values = self.domains.unwrap_or_default();
+                let mut domains = Vec::new();
+
+                for value in values.split_whitespace() {
+                    match parse_allowed(value) {
+                        Some(AllowedLink::Domain(domain)) if !domains.contains(&domain) => {
+                            domains.push(domain)
+                        }
+                        Some(AllowedLink::Domain(_)) => {}
+                        _ => return Ok(embed::channel_rule::invalid_domain(ctx.lang, value)),
+                    }
+                }
+
+                if domains.is_empty() || domains.len() > ChannelRule::MAX_DOMAINS {
+                    return Ok(embed::channel_rule::domains_count(ctx.lang));
+                }
+
+                ChannelRuleKind::LinkOnlyFrom { domains }
+            }
+        };
+
+        let channel = self.channel;
+        let rule = ChannelRule {
+            channel_id: channel,
+            kind,
+        };
+        let rules = &mut config.channel_rules;
+        let same_channel = |rule: &ChannelRule| rule.channel_id == channel;
+
+        if upsert(rules, ChannelRule::MAX_RULES, rule, same_channel).is_err() {
+            return Ok(embed::channel_rule::too_many(ctx.lang));
+        }
+
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::channel_rule::list(ctx.lang, &config.channel_rules))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "clear",
+    desc = "Remove the rule of a channel",
+    desc_localizations = "channel_rule_clear_description"
+)]
+pub struct ChannelRuleClearCommand {
+    /// Channel to remove the rule from.
+    pub channel: Id<ChannelMarker>,
+}
+
+desc_localizations!(channel_rule_clear_description);
+
+impl ChannelRuleClearCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        if config.channel_rule(self.channel).is_none() {
+            return Ok(embed::channel_rule::not_found(ctx.lang, self.channel));
+        }
+
+        config
+            .channel_rules
+            .retain(|rule| rule.channel_id != self.channel);
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::channel_rule::list(ctx.lang, &config.channel_rules))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "list",
+    desc = "Show the channel rules",
+    desc_localizations = "channel_rule_list_description"
+)]
+pub struct ChannelRuleListCommand;
+
+desc_localizations!(channel_rule_list_description);
+
+impl ChannelRuleListCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let config = ctx.config(state).await?;
+
+        Ok(embed::channel_rule::list(ctx.lang, &config.channel_rules))
+    }
+}
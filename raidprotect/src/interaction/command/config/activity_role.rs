@@ -0,0 +1,90 @@
+//! Activity role configuration command.
+
+use raidprotect_model::cache::discord::permission::RoleOrdering;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::guild::Role;
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        embed::{self, EmbedBuilder, COLOR_RED},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "activity-role",
+    desc = "Configure the role granted to active members",
+    desc_localizations = "config_activity_role_description"
+)]
+pub struct ActivityRoleConfigCommand {
+    /// Role granted once enough messages have been sent.
+    role: Option<Role>,
+    /// Number of messages required to be granted the role.
+    #[command(min_value = 1, max_value = 10000)]
+    threshold: Option<i64>,
+    /// Minimum delay (in seconds) between two counted messages.
+    #[command(min_value = 0, max_value = 3600)]
+    cooldown: Option<i64>,
+    /// Whether granted roles are logged in the logs channel.
+    log: Option<bool>,
+    /// Disable the activity role.
+    disable: Option<bool>,
+}
+
+desc_localizations!(config_activity_role_description);
+
+impl ActivityRoleConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+        let activity_role = &mut config.activity_role;
+
+        if let Some(role) = self.role {
+            let permissions = state.cache.permissions(ctx.guild_id).await?;
+            let bot_member = state.own_member(ctx.guild_id).await?;
+            let bot_permissions = permissions.bot_member(&bot_member).await?;
+
+            if RoleOrdering::from(&role) >= bot_permissions.highest_role() {
+                let embed = EmbedBuilder::new()
+                    .color(COLOR_RED)
+                    .description(ctx.lang.config_activity_role_hierarchy())
+                    .build();
+
+                return Ok(InteractionResponse::EphemeralEmbed(embed));
+            }
+
+            activity_role.role = Some(role.id);
+        }
+
+        // Values are bounded by the command options.
+        if let Some(threshold) = self.threshold {
+            activity_role.threshold = threshold as u32;
+        }
+
+        if let Some(cooldown) = self.cooldown {
+            activity_role.cooldown = cooldown as u16;
+        }
+
+        if let Some(log) = self.log {
+            activity_role.log = log;
+        }
+
+        if self.disable == Some(true) {
+            activity_role.role = None;
+        }
+
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::config::activity_role_updated(
+            ctx.lang,
+            &config.activity_role,
+        ))
+    }
+}
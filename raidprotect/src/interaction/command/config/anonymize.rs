@@ -0,0 +1,57 @@
+//! Anonymous moderation configuration command.
+//!
+//! When enabled, public sanction messages show the moderation team instead of
+//! the moderator who applied the sanction. The moderator is still recorded in
+//! the modlogs and in the audit log reason, which are only visible to the
+//! staff.
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        embed::{EmbedBuilder, COLOR_SUCCESS},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "anonymize",
+    desc = "Hide the moderator in the public sanction messages",
+    desc_localizations = "config_anonymize_description"
+)]
+pub struct AnonymizeConfigCommand {
+    /// Whether the moderator is replaced by the moderation team.
+    enabled: bool,
+}
+
+desc_localizations!(config_anonymize_description);
+
+impl AnonymizeConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        config.moderation.anonymize = self.enabled;
+        database::update_guild_config(state, &config).await?;
+
+        let description = match self.enabled {
+            true => ctx.lang.config_anonymize_enabled(),
+            false => ctx.lang.config_anonymize_disabled(),
+        };
+
+        let embed = EmbedBuilder::new()
+            .color(COLOR_SUCCESS)
+            .title(ctx.lang.config_updated_title())
+            .description(description)
+            .build();
+
+        Ok(InteractionResponse::EphemeralEmbed(embed))
+    }
+}
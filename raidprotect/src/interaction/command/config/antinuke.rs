@@ -0,0 +1,112 @@
+//! Anti-nuke configuration command.
+
+use raidprotect_model::database::model::{AntinukeAction, AntinukeConfig};
+use twilight_interactions::command::{CommandModel, CommandOption, CreateCommand, CreateOption};
+use twilight_model::{
+    id::{marker::UserMarker, Id},
+    user::User,
+};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        embed::{self, EmbedBuilder, COLOR_RED},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "antinuke",
+    desc = "Configure the protection against mass channel and role deletions",
+    desc_localizations = "config_antinuke_description"
+)]
+pub struct AntinukeConfigCommand {
+    /// Whether the anti-nuke protection is enabled.
+    enabled: Option<bool>,
+    /// Maximum number of deletions allowed during the interval.
+    #[command(min_value = 2, max_value = 50)]
+    max_deletions: Option<i64>,
+    /// Interval (in seconds) during which deletions are counted.
+    #[command(min_value = 10, max_value = 3600)]
+    interval: Option<i64>,
+    /// Action applied to users that exceed the limit.
+    action: Option<AntinukeActionOption>,
+    /// User (usually a bot) to add to the trusted users.
+    trusted_add: Option<User>,
+    /// User to remove from the trusted users.
+    trusted_remove: Option<Id<UserMarker>>,
+}
+
+desc_localizations!(config_antinuke_description);
+
+/// Action applied by the anti-nuke module.
+#[derive(Debug, Clone, Copy, CommandOption, CreateOption)]
+pub enum AntinukeActionOption {
+    #[option(name = "Remove all roles", value = "strip_roles")]
+    StripRoles,
+    #[option(name = "Ban", value = "ban")]
+    Ban,
+}
+
+impl From<AntinukeActionOption> for AntinukeAction {
+    fn from(action: AntinukeActionOption) -> Self {
+        match action {
+            AntinukeActionOption::StripRoles => AntinukeAction::StripRoles,
+            AntinukeActionOption::Ban => AntinukeAction::Ban,
+        }
+    }
+}
+
+impl AntinukeConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+        let antinuke = &mut config.antinuke;
+
+        // Update the configuration. Values are bounded by the command options.
+        if let Some(enabled) = self.enabled {
+            antinuke.enabled = enabled;
+        }
+
+        if let Some(max_deletions) = self.max_deletions {
+            antinuke.max_deletions = max_deletions as u16;
+        }
+
+        if let Some(interval) = self.interval {
+            antinuke.interval = interval as u16;
+        }
+
+        if let Some(action) = self.action {
+            antinuke.action = action.into();
+        }
+
+        if let Some(user) = self.trusted_add {
+            if !antinuke.trusted.contains(&user.id) {
+                if antinuke.trusted.len() >= AntinukeConfig::MAX_TRUSTED_LEN {
+                    let embed = EmbedBuilder::new()
+                        .color(COLOR_RED)
+                        .description(ctx.lang.antinuke_trusted_too_many())
+                        .build();
+
+                    return Ok(InteractionResponse::EphemeralEmbed(embed));
+                }
+
+                antinuke.trusted.push(user.id);
+            }
+        }
+
+        if let Some(user) = self.trusted_remove {
+            antinuke.trusted.retain(|u| u != &user);
+        }
+
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::config::antinuke_updated(ctx.lang, &config.antinuke))
+    }
+}
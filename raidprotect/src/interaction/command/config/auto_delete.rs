@@ -0,0 +1,54 @@
+//! Auto-delete configuration command.
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        embed::{EmbedBuilder, COLOR_SUCCESS},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "auto-delete",
+    desc = "Automatically delete the replies of RaidProtect commands",
+    desc_localizations = "config_auto_delete_description"
+)]
+pub struct AutoDeleteConfigCommand {
+    /// Delay in seconds before replies are deleted. Leave empty to disable.
+    #[command(min_value = 5, max_value = 3600)]
+    delay: Option<i64>,
+}
+
+desc_localizations!(config_auto_delete_description);
+
+impl AutoDeleteConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        // The delay is bounded by the command options.
+        config.delete_replies_after = self.delay.map(|delay| delay as u16);
+        database::update_guild_config(state, &config).await?;
+
+        let description = match self.delay {
+            Some(delay) => ctx.lang.config_auto_delete_enabled(delay),
+            None => ctx.lang.config_auto_delete_disabled().to_owned(),
+        };
+
+        let embed = EmbedBuilder::new()
+            .color(COLOR_SUCCESS)
+            .title(ctx.lang.config_updated_title())
+            .description(description)
+            .build();
+
+        Ok(InteractionResponse::EphemeralEmbed(embed))
+    }
+}
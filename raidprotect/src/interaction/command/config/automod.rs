@@ -0,0 +1,135 @@
+//! AutoMod configuration command.
+//!
+//! The native AutoMod rules are synchronized after each update (see
+//! [`feature::automod`]). If some rules have been edited manually, the
+//! administrator is asked to confirm before overwriting them.
+//!
+//! [`feature::automod`]: crate::feature::automod
+
+use raidprotect_model::{
+    cache::model::interaction::PendingAutomodOverwrite, database::model::AutomodConfig,
+};
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::{
+    application::component::{button::ButtonStyle, ActionRow, Button, Component},
+    channel::message::MessageFlags,
+    http::interaction::InteractionResponseType,
+};
+use twilight_util::builder::InteractionResponseDataBuilder;
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    feature::automod,
+    interaction::{
+        embed,
+        response::InteractionResponse,
+        util::{CustomId, GuildInteractionContext},
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "automod",
+    desc = "Configure the native AutoMod rules created by RaidProtect",
+    desc_localizations = "config_automod_description"
+)]
+pub struct AutomodConfigCommand {
+    /// Whether the native rules are created.
+    enabled: Option<bool>,
+    /// Keyword to add to the blocked keywords.
+    keyword_add: Option<String>,
+    /// Keyword to remove from the blocked keywords.
+    keyword_remove: Option<String>,
+    /// Whether messages detected as spam by Discord are blocked.
+    spam: Option<bool>,
+    /// Maximum number of mentions in a message (0 to disable).
+    #[command(min_value = 0, max_value = 50)]
+    mention_limit: Option<i64>,
+}
+
+desc_localizations!(config_automod_description);
+
+impl AutomodConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+        let automod = &mut config.automod;
+
+        // Update the configuration. Values are bounded by the command options.
+        if let Some(enabled) = self.enabled {
+            automod.enabled = enabled;
+        }
+
+        if let Some(keyword) = self.keyword_add {
+            let keyword = keyword.trim().to_lowercase();
+
+            if !keyword.is_empty() && !automod.keywords.contains(&keyword) {
+                if automod.keywords.len() >= AutomodConfig::MAX_KEYWORDS_LEN {
+                    return Ok(embed::automod::keywords_too_many(ctx.lang));
+                }
+
+                automod.keywords.push(keyword);
+            }
+        }
+
+        if let Some(keyword) = self.keyword_remove {
+            let keyword = keyword.trim().to_lowercase();
+            automod.keywords.retain(|k| k != &keyword);
+        }
+
+        if let Some(spam) = self.spam {
+            automod.spam = spam;
+        }
+
+        if let Some(limit) = self.mention_limit {
+            automod.mention_limit = match limit {
+                0 => None,
+                limit => Some(limit as u8),
+            };
+        }
+
+        let report = automod::sync(state, &mut config, false).await?;
+        database::update_guild_config(state, &config).await?;
+
+        let embed = embed::automod::updated(ctx.lang, &config.automod, &report);
+
+        if report.conflicts.is_empty() {
+            return Ok(InteractionResponse::EphemeralEmbed(embed));
+        }
+
+        // Store the pending overwrite until it is confirmed.
+        let pending = PendingAutomodOverwrite {
+            interaction_id: ctx.interaction.id,
+            author_id: ctx.author.id,
+            guild_id: ctx.guild_id,
+        };
+        state.cache.set(&pending).await?;
+
+        let custom_id = CustomId::new("automod-overwrite", ctx.interaction.id.to_string());
+        let components = Component::ActionRow(ActionRow {
+            components: vec![Component::Button(Button {
+                custom_id: Some(custom_id.to_string()),
+                disabled: false,
+                emoji: None,
+                label: Some(ctx.lang.automod_overwrite_button().to_owned()),
+                style: ButtonStyle::Danger,
+                url: None,
+            })],
+        });
+
+        let response = InteractionResponseDataBuilder::new()
+            .embeds([embed])
+            .components([components])
+            .flags(MessageFlags::EPHEMERAL)
+            .build();
+
+        Ok(InteractionResponse::Raw {
+            kind: InteractionResponseType::ChannelMessageWithSource,
+            data: Some(response),
+        })
+    }
+}
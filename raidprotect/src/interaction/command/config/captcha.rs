@@ -14,13 +14,13 @@ use twilight_model::{
         Id,
     },
 };
-use twilight_util::builder::{embed::EmbedBuilder, InteractionResponseDataBuilder};
+use twilight_util::builder::InteractionResponseDataBuilder;
 
 use crate::{
     cluster::ClusterState,
-    desc_localizations,
+    database, desc_localizations,
     interaction::{
-        embed::{self, COLOR_RED, COLOR_SUCCESS, COLOR_TRANSPARENT},
+        embed::{self, EmbedBuilder, COLOR_RED, COLOR_SUCCESS, COLOR_TRANSPARENT},
         response::InteractionResponse,
         util::{CustomId, GuildInteractionContext},
     },
@@ -233,7 +233,7 @@ impl CaptchaLogsCommand {
 
         // Update the config.
         config.captcha.logs = Some(self.channel);
-        state.database.update_guild(&config).await?;
+        database::update_guild_config(state, &config).await?;
 
         // Send the embed.
         let embed = EmbedBuilder::new()
@@ -300,7 +300,7 @@ impl CaptchaAutoroleAddCommand {
         }
 
         config.captcha.verified_roles.push(self.role.id);
-        state.database.update_guild(&config).await?;
+        database::update_guild_config(state, &config).await?;
 
         // Send the embed.
         let embed = EmbedBuilder::new()
@@ -346,7 +346,7 @@ impl CaptchaAutoroleRemoveCommand {
         }
 
         config.captcha.verified_roles.retain(|r| r != &self.role);
-        state.database.update_guild(&config).await?;
+        database::update_guild_config(state, &config).await?;
 
         // Send the embed.
         let embed = EmbedBuilder::new()
@@ -0,0 +1,121 @@
+//! Configuration check command.
+
+use raidprotect_model::database::{remap_channel_references, BrokenReference};
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_mention::Mention;
+use twilight_model::{
+    guild::Permissions,
+    id::{marker::ChannelMarker, Id},
+};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    feature::references,
+    interaction::{
+        embed::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "check",
+    desc = "Check that the configured channels and roles still exist",
+    desc_localizations = "config_check_description"
+)]
+pub struct CheckConfigCommand {
+    /// Channel that replaces the deleted channels in the configuration.
+    #[command(channel_types = "guild_text")]
+    transfer_to: Option<Id<ChannelMarker>>,
+    /// Channel to replace instead of the deleted channels.
+    transfer_from: Option<Id<ChannelMarker>>,
+}
+
+desc_localizations!(config_check_description);
+
+impl CheckConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+        let broken = references::broken_references(state, &config).await?;
+
+        if let Some(target) = self.transfer_to {
+            // Ensure RaidProtect has permissions to send messages in the channel.
+            let (permissions, _) = state
+                .cache
+                .permissions(ctx.guild_id)
+                .await?
+                .current_member()
+                .await?
+                .channel(target)
+                .await?;
+
+            if !permissions.contains(Permissions::SEND_MESSAGES | Permissions::EMBED_LINKS) {
+                let embed = EmbedBuilder::new()
+                    .color(COLOR_RED)
+                    .description(ctx.lang.config_check_transfer_missing_permission())
+                    .build();
+
+                return Ok(InteractionResponse::EphemeralEmbed(embed));
+            }
+
+            // Without a source channel, all the deleted channels are replaced.
+            let sources = match self.transfer_from {
+                Some(source) => vec![source],
+                None => broken
+                    .iter()
+                    .filter_map(|reference| match reference {
+                        BrokenReference::Channel { id, .. } => Some(*id),
+                        BrokenReference::Role { .. } => None,
+                    })
+                    .collect(),
+            };
+
+            let mut updated = false;
+            for source in sources.into_iter().filter(|source| *source != target) {
+                updated |= remap_channel_references(&mut config, source, target);
+            }
+
+            let embed = if updated {
+                database::update_guild_config(state, &config).await?;
+
+                let channel = target.mention().to_string();
+                EmbedBuilder::new()
+                    .color(COLOR_SUCCESS)
+                    .title(ctx.lang.config_check_transfer_title())
+                    .description(ctx.lang.config_check_transfer_description(channel))
+                    .build()
+            } else {
+                EmbedBuilder::new()
+                    .color(COLOR_RED)
+                    .description(ctx.lang.config_check_transfer_nothing())
+                    .build()
+            };
+
+            return Ok(InteractionResponse::EphemeralEmbed(embed));
+        }
+
+        let embed = if broken.is_empty() {
+            EmbedBuilder::new()
+                .color(COLOR_SUCCESS)
+                .title(ctx.lang.config_check_valid_title())
+                .description(ctx.lang.config_check_valid_description())
+                .build()
+        } else {
+            EmbedBuilder::new()
+                .color(COLOR_RED)
+                .title(ctx.lang.broken_references_title())
+                .description(ctx.lang.config_check_broken_description(
+                    references::format_references(&broken, ctx.lang),
+                ))
+                .build()
+        };
+
+        Ok(InteractionResponse::EphemeralEmbed(embed))
+    }
+}
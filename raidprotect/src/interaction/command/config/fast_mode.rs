@@ -0,0 +1,141 @@
+//! Fast mode configuration commands.
+//!
+//! Members of fast mode roles apply sanctions immediately, without the
+//! confirmation asked when the user has just been sanctioned by another
+//! moderator. The reason modal is still displayed if required.
+
+use raidprotect_model::database::model::ModerationConfig;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::id::{marker::RoleMarker, Id};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "fast-mode",
+    desc = "Manage the roles that apply sanctions without confirmation",
+    desc_localizations = "config_fast_mode_description"
+)]
+pub enum FastModeConfigCommand {
+    #[command(name = "add")]
+    Add(FastModeAddCommand),
+    #[command(name = "remove")]
+    Remove(FastModeRemoveCommand),
+    #[command(name = "list")]
+    List(FastModeListCommand),
+}
+
+desc_localizations!(config_fast_mode_description);
+
+impl FastModeConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        match self {
+            FastModeConfigCommand::Add(command) => command.exec(ctx, state).await,
+            FastModeConfigCommand::Remove(command) => command.exec(ctx, state).await,
+            FastModeConfigCommand::List(command) => command.exec(ctx, state).await,
+        }
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "add",
+    desc = "Allow a role to apply sanctions without confirmation",
+    desc_localizations = "config_fast_mode_add_description"
+)]
+pub struct FastModeAddCommand {
+    /// Trusted moderator role.
+    role: Id<RoleMarker>,
+}
+
+desc_localizations!(config_fast_mode_add_description);
+
+impl FastModeAddCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+        let roles = &mut config.moderation.skip_confirmation_roles;
+
+        if roles.contains(&self.role) {
+            return Ok(embed::fast_mode::already_added(ctx.lang));
+        }
+
+        if roles.len() >= ModerationConfig::MAX_SKIP_CONFIRMATION_ROLES_LEN {
+            return Ok(embed::fast_mode::too_many(ctx.lang));
+        }
+
+        roles.push(self.role);
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::fast_mode::added(ctx.lang, self.role))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "remove",
+    desc = "Require a confirmation again for a role",
+    desc_localizations = "config_fast_mode_remove_description"
+)]
+pub struct FastModeRemoveCommand {
+    /// Trusted moderator role.
+    role: Id<RoleMarker>,
+}
+
+desc_localizations!(config_fast_mode_remove_description);
+
+impl FastModeRemoveCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+        let roles = &mut config.moderation.skip_confirmation_roles;
+
+        if !roles.contains(&self.role) {
+            return Ok(embed::fast_mode::not_found(ctx.lang));
+        }
+
+        roles.retain(|role| *role != self.role);
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::fast_mode::removed(ctx.lang, self.role))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "list",
+    desc = "List the roles that apply sanctions without confirmation",
+    desc_localizations = "config_fast_mode_list_description"
+)]
+pub struct FastModeListCommand;
+
+desc_localizations!(config_fast_mode_list_description);
+
+impl FastModeListCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let config = ctx.config(state).await?;
+
+        Ok(embed::fast_mode::list(
+            ctx.lang,
+            &config.moderation.skip_confirmation_roles,
+        ))
+    }
+}
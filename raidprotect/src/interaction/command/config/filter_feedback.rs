@@ -0,0 +1,37 @@
+//! Filters false positives command.
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    cluster::ClusterState,
+    desc_localizations,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+};
+
+/// Number of false positives shown.
+const MAX_FEEDBACK: i64 = 15;
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "feedback",
+    desc = "Show the recent false positives reported on the filters",
+    desc_localizations = "config_filter_feedback_description"
+)]
+pub struct FilterFeedbackConfigCommand;
+
+desc_localizations!(config_filter_feedback_description);
+
+impl FilterFeedbackConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let feedback = state
+            .database
+            .recent_filter_feedback(ctx.guild_id, MAX_FEEDBACK)
+            .await?;
+
+        Ok(embed::filter_feedback::list(ctx.lang, &feedback))
+    }
+}
@@ -0,0 +1,62 @@
+//! Filters configuration commands.
+//!
+//! Discord limits commands to 25 subcommands, so the filters applied to the
+//! messages and reactions of members are configured in a subcommand group.
+//! The false positives reported on the filters, the roles ignored by all the
+//! filters and the grace period of trusted members are also configured here.
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use super::{
+    FilterFeedbackConfigCommand, FirstMessageConfigCommand, GraceConfigCommand,
+    IgnoredRolesConfigCommand, LinkFilterConfigCommand, PatternRulesConfigCommand,
+    ReactionSpamConfigCommand,
+};
+use crate::{
+    cluster::ClusterState,
+    desc_localizations,
+    interaction::{response::InteractionResponse, util::GuildInteractionContext},
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "filters",
+    desc = "Configure the messages and reactions filters",
+    desc_localizations = "config_filters_description"
+)]
+pub enum FiltersConfigCommand {
+    #[command(name = "reaction-spam")]
+    ReactionSpam(ReactionSpamConfigCommand),
+    #[command(name = "link-filter")]
+    LinkFilter(LinkFilterConfigCommand),
+    #[command(name = "pattern-rules")]
+    PatternRules(PatternRulesConfigCommand),
+    #[command(name = "first-message")]
+    FirstMessage(FirstMessageConfigCommand),
+    #[command(name = "feedback")]
+    Feedback(FilterFeedbackConfigCommand),
+    #[command(name = "ignored-roles")]
+    IgnoredRoles(IgnoredRolesConfigCommand),
+    #[command(name = "grace")]
+    Grace(GraceConfigCommand),
+}
+
+desc_localizations!(config_filters_description);
+
+impl FiltersConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        match self {
+            FiltersConfigCommand::ReactionSpam(command) => command.exec(ctx, state).await,
+            FiltersConfigCommand::LinkFilter(command) => command.exec(ctx, state).await,
+            FiltersConfigCommand::PatternRules(command) => command.exec(ctx, state).await,
+            FiltersConfigCommand::FirstMessage(command) => command.exec(ctx, state).await,
+            FiltersConfigCommand::Feedback(command) => command.exec(ctx, state).await,
+            FiltersConfigCommand::IgnoredRoles(command) => command.exec(ctx, state).await,
+            FiltersConfigCommand::Grace(command) => command.exec(ctx, state).await,
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! First message filter configuration command.
+
+use raidprotect_model::database::model::FirstMessageAction;
+use twilight_interactions::command::{CommandModel, CommandOption, CreateCommand, CreateOption};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "first-message",
+    desc = "Configure the stricter checks of the first message of new members",
+    desc_localizations = "config_first_message_description"
+)]
+pub struct FirstMessageConfigCommand {
+    /// Whether the first message filter is enabled.
+    enabled: Option<bool>,
+    /// Whether links that are not allowed by the link filter are forbidden.
+    links: Option<bool>,
+    /// Maximum number of mentions allowed.
+    #[command(min_value = 0, max_value = 50)]
+    max_mentions: Option<i64>,
+    /// Action applied to the author of a filtered message.
+    action: Option<FirstMessageActionOption>,
+}
+
+desc_localizations!(config_first_message_description);
+
+/// Action applied by the first message filter.
+#[derive(Debug, Clone, Copy, CommandOption, CreateOption)]
+pub enum FirstMessageActionOption {
+    #[option(name = "Delete the message", value = "delete")]
+    Delete,
+    #[option(name = "Delete the message and timeout", value = "timeout")]
+    Timeout,
+    #[option(name = "Delete the message and ban", value = "ban")]
+    Ban,
+}
+
+impl From<FirstMessageActionOption> for FirstMessageAction {
+    fn from(action: FirstMessageActionOption) -> Self {
+        match action {
+            FirstMessageActionOption::Delete => FirstMessageAction::Delete,
+            FirstMessageActionOption::Timeout => FirstMessageAction::Timeout,
+            FirstMessageActionOption::Ban => FirstMessageAction::Ban,
+        }
+    }
+}
+
+impl FirstMessageConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+        let first_message = &mut config.first_message;
+
+        // Update the configuration. Values are bounded by the command options.
+        if let Some(enabled) = self.enabled {
+            first_message.enabled = enabled;
+        }
+
+        if let Some(links) = self.links {
+            first_message.links = links;
+        }
+
+        if let Some(max_mentions) = self.max_mentions {
+            first_message.max_mentions = max_mentions as u8;
+        }
+
+        if let Some(action) = self.action {
+            first_message.action = action.into();
+        }
+
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::config::first_message_updated(
+            ctx.lang,
+            &config.first_message,
+        ))
+    }
+}
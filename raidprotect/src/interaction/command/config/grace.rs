@@ -0,0 +1,95 @@
+//! Grace period configuration command.
+//!
+//! The first automatic sanction of trusted members is replaced by a warning
+//! (see the [`automation`] module).
+//!
+//! [`automation`]: crate::feature::automation
+
+use raidprotect_model::database::model::GraceConfig;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::{
+    guild::Role,
+    id::{marker::RoleMarker, Id},
+};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        embed::{self, EmbedBuilder, COLOR_RED},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "grace",
+    desc = "Warn trusted members instead of their first automatic sanction",
+    desc_localizations = "config_grace_description"
+)]
+pub struct GraceConfigCommand {
+    /// Whether the grace period is enabled.
+    enabled: Option<bool>,
+    /// Whether members boosting the server are trusted.
+    boosters: Option<bool>,
+    /// Number of days after which members are trusted (0 to disable).
+    #[command(min_value = 0, max_value = 3650)]
+    tenure: Option<i64>,
+    /// Role to add to the trusted roles.
+    trusted_role_add: Option<Role>,
+    /// Role to remove from the trusted roles.
+    trusted_role_remove: Option<Id<RoleMarker>>,
+}
+
+desc_localizations!(config_grace_description);
+
+impl GraceConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+        let grace = &mut config.grace;
+
+        // Update the configuration. Values are bounded by the command options.
+        if let Some(enabled) = self.enabled {
+            grace.enabled = enabled;
+        }
+
+        if let Some(boosters) = self.boosters {
+            grace.boosters = boosters;
+        }
+
+        if let Some(tenure) = self.tenure {
+            grace.trusted_tenure = match tenure {
+                0 => None,
+                days => Some(days as u16),
+            };
+        }
+
+        if let Some(role) = self.trusted_role_add {
+            if !grace.trusted_roles.contains(&role.id) {
+                if grace.trusted_roles.len() >= GraceConfig::MAX_TRUSTED_ROLES_LEN {
+                    let embed = EmbedBuilder::new()
+                        .color(COLOR_RED)
+                        .description(ctx.lang.reaction_spam_trusted_role_too_many())
+                        .build();
+
+                    return Ok(InteractionResponse::EphemeralEmbed(embed));
+                }
+
+                grace.trusted_roles.push(role.id);
+            }
+        }
+
+        if let Some(role) = self.trusted_role_remove {
+            grace.trusted_roles.retain(|r| r != &role);
+        }
+
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::config::grace_updated(ctx.lang, &config.grace))
+    }
+}
@@ -0,0 +1,154 @@
+//! Automod ignored roles configuration command.
+
+use raidprotect_model::database::model::GuildConfig;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::id::{marker::RoleMarker, Id};
+
+use crate::{
+    cluster::HandlerState,
+    database, desc_localizations,
+    interaction::{
+        embed::{self, EmbedBuilder, COLOR_RED},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "ignored-roles",
+    desc = "Configure the roles ignored by all the filters",
+    desc_localizations = "config_ignored_roles_description"
+)]
+pub struct IgnoredRolesConfigCommand {
+    /// Role whose members are not checked by the filters.
+    role_add: Option<Id<RoleMarker>>,
+    /// Role to remove from the ignored roles.
+    role_remove: Option<Id<RoleMarker>>,
+}
+
+desc_localizations!(config_ignored_roles_description);
+
+impl IgnoredRolesConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &impl HandlerState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+        let ignored_roles = &mut config.automod_ignored_roles;
+
+        if let Some(role) = self.role_add {
+            if !ignored_roles.contains(&role) {
+                if ignored_roles.len() >= GuildConfig::MAX_AUTOMOD_IGNORED_ROLES_LEN {
+                    let embed = EmbedBuilder::new()
+                        .color(COLOR_RED)
+                        .description(
+                            ctx.lang
+                                .ignored_roles_too_many(GuildConfig::MAX_AUTOMOD_IGNORED_ROLES_LEN),
+                        )
+                        .build();
+
+                    return Ok(InteractionResponse::EphemeralEmbed(embed));
+                }
+
+                ignored_roles.push(role);
+            }
+        }
+
+        if let Some(role) = self.role_remove {
+            ignored_roles.retain(|r| r != &role);
+        }
+
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::config::ignored_roles_updated(
+            ctx.lang,
+            &config.automod_ignored_roles,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use raidprotect_model::store::{CacheStore, GuildStore};
+    use twilight_model::guild::Permissions;
+
+    use super::*;
+    use crate::test_utils::{
+        assert,
+        fixture::{self, member_with_perms, GUILD_ID},
+        MockClusterState,
+    };
+
+    fn command(option: &str, role: u64) -> (IgnoredRolesConfigCommand, GuildInteractionContext) {
+        fixture::guild_command("ignored-roles")
+            .option_role(option, Id::new(role))
+            .invoker(member_with_perms(Permissions::ADMINISTRATOR))
+            .parse()
+    }
+
+    /// Add and remove an ignored role, and check the stored configuration.
+    async fn check_ignored_roles(state: &impl HandlerState) {
+        let (add, ctx) = command("role_add", 10);
+        let response = add.exec(ctx, state).await.unwrap();
+        let embed = assert::ephemeral_embed(&response);
+        assert!(embed.description.as_deref().unwrap().contains("<@&10>"));
+
+        let config = state
+            .guild_store()
+            .get_guild_or_create(GUILD_ID)
+            .await
+            .unwrap();
+        assert_eq!(config.automod_ignored_roles, vec![Id::new(10)]);
+
+        // The updated configuration is also cached.
+        let cached = state
+            .cache_store()
+            .get::<GuildConfig>(&GUILD_ID)
+            .await
+            .unwrap();
+        assert_eq!(cached, Some(config));
+
+        let (remove, ctx) = command("role_remove", 10);
+        remove.exec(ctx, state).await.unwrap();
+
+        let config = state
+            .guild_store()
+            .get_guild_or_create(GUILD_ID)
+            .await
+            .unwrap();
+        assert!(config.automod_ignored_roles.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ignored_roles() {
+        let state = MockClusterState::new();
+
+        check_ignored_roles(&state).await;
+        assert!(state.guilds.guild(GUILD_ID).is_some());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a MongoDB and a Redis server"]
+    async fn test_ignored_roles_connected() {
+        check_ignored_roles(&MockClusterState::connect().await).await;
+    }
+
+    #[tokio::test]
+    async fn test_ignored_roles_too_many() {
+        let state = MockClusterState::new();
+        let mut config = GuildConfig::new(GUILD_ID);
+        config.automod_ignored_roles = (1..=GuildConfig::MAX_AUTOMOD_IGNORED_ROLES_LEN as u64)
+            .map(Id::new)
+            .collect();
+        state.guilds.update_guild(&config).await.unwrap();
+
+        let (add, ctx) = command("role_add", 100);
+        let response = add.exec(ctx, &state).await.unwrap();
+        let embed = assert::ephemeral_embed(&response);
+        assert_eq!(embed.color, Some(COLOR_RED));
+
+        assert_eq!(state.guilds.guild(GUILD_ID), Some(config));
+    }
+}
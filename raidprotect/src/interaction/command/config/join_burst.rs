@@ -0,0 +1,84 @@
+//! Join burst detection configuration command.
+
+use raidprotect_model::database::model::JoinBurstSignal;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "join-burst",
+    desc = "Configure the detection of mass joins",
+    desc_localizations = "config_join_burst_description"
+)]
+pub struct JoinBurstConfigCommand {
+    /// Whether the join burst detection is enabled.
+    enabled: Option<bool>,
+    /// Maximum number of joins allowed during the interval.
+    #[command(min_value = 3, max_value = 100)]
+    max_joins: Option<i64>,
+    /// Interval (in seconds) during which joins are counted.
+    #[command(min_value = 5, max_value = 600)]
+    interval: Option<i64>,
+    /// Whether accounts with identical avatars are a signal of a raid.
+    avatar_signal: Option<bool>,
+    /// Whether accounts with similar names are a signal of a raid.
+    name_signal: Option<bool>,
+    /// Percentage of the accounts that must share an avatar or name pattern.
+    #[command(min_value = 10, max_value = 100)]
+    cluster_ratio: Option<i64>,
+}
+
+desc_localizations!(config_join_burst_description);
+
+impl JoinBurstConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+        let join_burst = &mut config.join_burst;
+
+        // Update the configuration. Values are bounded by the command options.
+        if let Some(enabled) = self.enabled {
+            join_burst.enabled = enabled;
+        }
+
+        if let Some(max_joins) = self.max_joins {
+            join_burst.max_joins = max_joins as u16;
+        }
+
+        if let Some(interval) = self.interval {
+            join_burst.interval = interval as u16;
+        }
+
+        for (signal, enabled) in [
+            (JoinBurstSignal::Avatar, self.avatar_signal),
+            (JoinBurstSignal::Name, self.name_signal),
+        ] {
+            match enabled {
+                Some(true) if !join_burst.signals.contains(&signal) => {
+                    join_burst.signals.push(signal)
+                }
+                Some(false) => join_burst.signals.retain(|s| s != &signal),
+                _ => {}
+            }
+        }
+
+        if let Some(cluster_ratio) = self.cluster_ratio {
+            join_burst.cluster_ratio = cluster_ratio as u8;
+        }
+
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::config::join_burst_updated(
+            ctx.lang,
+            &config.join_burst,
+        ))
+    }
+}
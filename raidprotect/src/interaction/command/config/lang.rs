@@ -0,0 +1,71 @@
+//! Lang configuration command.
+
+use twilight_interactions::command::{CommandModel, CommandOption, CreateCommand, CreateOption};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "lang",
+    desc = "Configure the language used by the bot on the server",
+    desc_localizations = "config_lang_description"
+)]
+pub struct LangConfigCommand {
+    /// Language of the server, used for logs and pinned responses.
+    lang: Option<ConfigLang>,
+    /// Whether the server language is used for public responses.
+    pin: Option<bool>,
+    /// Whether the server language is also used for ephemeral replies.
+    pin_ephemeral: Option<bool>,
+}
+
+desc_localizations!(config_lang_description);
+
+/// Language of the server.
+#[derive(Debug, Clone, Copy, CommandOption, CreateOption)]
+pub enum ConfigLang {
+    #[option(name = "English", value = "en")]
+    English,
+    #[option(name = "Français", value = "fr")]
+    French,
+}
+
+impl ConfigLang {
+    /// Get the lang code stored in the configuration.
+    fn code(self) -> &'static str {
+        match self {
+            ConfigLang::English => "en",
+            ConfigLang::French => "fr",
+        }
+    }
+}
+
+impl LangConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        if let Some(lang) = self.lang {
+            config.lang = lang.code().to_owned();
+        }
+
+        if let Some(pin) = self.pin {
+            config.lang_override = pin;
+        }
+
+        if let Some(pin_ephemeral) = self.pin_ephemeral {
+            config.lang_override_ephemeral = pin_ephemeral;
+        }
+
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::config::lang_updated(ctx.lang, &config))
+    }
+}
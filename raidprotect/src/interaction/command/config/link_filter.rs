@@ -0,0 +1,134 @@
+//! Link filter configuration command.
+
+use raidprotect_model::database::model::LinkFilterConfig;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::{
+    guild::Role,
+    id::{marker::RoleMarker, Id},
+};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        embed::{self, EmbedBuilder, COLOR_RED},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+    util::link::{parse_allowed, AllowedLink},
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "link-filter",
+    desc = "Configure the invites and links filter",
+    desc_localizations = "config_link_filter_description"
+)]
+pub struct LinkFilterConfigCommand {
+    /// Whether the link filter is enabled.
+    enabled: Option<bool>,
+    /// Whether Discord invites are deleted.
+    invites: Option<bool>,
+    /// Whether external links are deleted.
+    links: Option<bool>,
+    /// Duration (in seconds) of the timeout. Set to 0 to disable.
+    #[command(min_value = 0, max_value = 2419200)]
+    timeout: Option<i64>,
+    /// Domain or Discord invite to allow.
+    #[command(max_length = 100)]
+    allow_add: Option<String>,
+    /// Domain or Discord invite to remove from the allowed links.
+    #[command(max_length = 100)]
+    allow_remove: Option<String>,
+    /// Role to add to the trusted roles.
+    trusted_role_add: Option<Role>,
+    /// Role to remove from the trusted roles.
+    trusted_role_remove: Option<Id<RoleMarker>>,
+}
+
+desc_localizations!(config_link_filter_description);
+
+impl LinkFilterConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+        let link_filter = &mut config.link_filter;
+
+        // Update the configuration. Values are bounded by the command options.
+        if let Some(enabled) = self.enabled {
+            link_filter.enabled = enabled;
+        }
+
+        if let Some(invites) = self.invites {
+            link_filter.invites = invites;
+        }
+
+        if let Some(links) = self.links {
+            link_filter.links = links;
+        }
+
+        if let Some(timeout) = self.timeout {
+            link_filter.timeout = match timeout {
+                0 => None,
+                timeout => Some(timeout as u32),
+            };
+        }
+
+        if let Some(value) = self.allow_add {
+            let (allowed, value) = match parse_allowed(&value) {
+                Some(AllowedLink::Invite(code)) => (&mut link_filter.allowed_invites, code),
+                Some(AllowedLink::Domain(domain)) => (&mut link_filter.allowed_domains, domain),
+                None => return Ok(embed::config::link_filter_invalid(ctx.lang)),
+            };
+
+            if !allowed.contains(&value) {
+                if allowed.len() >= LinkFilterConfig::MAX_ALLOWED_LEN {
+                    return Ok(embed::config::link_filter_allowed_too_many(ctx.lang));
+                }
+
+                allowed.push(value);
+            }
+        }
+
+        if let Some(value) = self.allow_remove {
+            match parse_allowed(&value) {
+                Some(AllowedLink::Invite(code)) => {
+                    link_filter.allowed_invites.retain(|c| c != &code);
+                }
+                Some(AllowedLink::Domain(domain)) => {
+                    link_filter.allowed_domains.retain(|d| d != &domain);
+                }
+                None => return Ok(embed::config::link_filter_invalid(ctx.lang)),
+            }
+        }
+
+        if let Some(role) = self.trusted_role_add {
+            if !link_filter.trusted_roles.contains(&role.id) {
+                if link_filter.trusted_roles.len() >= LinkFilterConfig::MAX_TRUSTED_ROLES_LEN {
+                    let embed = EmbedBuilder::new()
+                        .color(COLOR_RED)
+                        .description(ctx.lang.reaction_spam_trusted_role_too_many())
+                        .build();
+
+                    return Ok(InteractionResponse::EphemeralEmbed(embed));
+                }
+
+                link_filter.trusted_roles.push(role.id);
+            }
+        }
+
+        if let Some(role) = self.trusted_role_remove {
+            link_filter.trusted_roles.retain(|r| r != &role);
+        }
+
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::config::link_filter_updated(
+            ctx.lang,
+            &config.link_filter,
+        ))
+    }
+}
@@ -0,0 +1,110 @@
+//! Logs routing configuration command.
+
+use raidprotect_model::database::model::{LogCategory, LogDestination};
+use twilight_interactions::command::{CommandModel, CommandOption, CreateCommand, CreateOption};
+use twilight_model::{
+    guild::Permissions,
+    id::{marker::ChannelMarker, Id},
+};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        embed::{self, EmbedBuilder, COLOR_RED},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "route",
+    desc = "Send a category of logs in a dedicated channel",
+    desc_localizations = "config_log_route_description"
+)]
+pub struct LogRouteConfigCommand {
+    /// Category of logs to configure.
+    category: LogCategoryOption,
+    /// Channel where the logs of this category are sent.
+    #[command(channel_types = "guild_text")]
+    channel: Option<Id<ChannelMarker>>,
+    /// Stop sending the logs of this category.
+    disable: Option<bool>,
+}
+
+desc_localizations!(config_log_route_description);
+
+/// Category of logs.
+#[derive(Debug, Clone, Copy, CommandOption, CreateOption)]
+pub enum LogCategoryOption {
+    #[option(name = "Sanctions", value = "sanctions")]
+    Sanctions,
+    #[option(name = "Joins", value = "joins")]
+    Joins,
+    #[option(name = "Messages", value = "messages")]
+    Messages,
+    #[option(name = "Filters", value = "filters")]
+    Filters,
+    #[option(name = "Raids", value = "raids")]
+    Raids,
+    #[option(name = "Configuration", value = "config")]
+    Config,
+}
+
+impl From<LogCategoryOption> for LogCategory {
+    fn from(category: LogCategoryOption) -> Self {
+        match category {
+            LogCategoryOption::Sanctions => LogCategory::Sanctions,
+            LogCategoryOption::Joins => LogCategory::Joins,
+            LogCategoryOption::Messages => LogCategory::Messages,
+            LogCategoryOption::Filters => LogCategory::Filters,
+            LogCategoryOption::Raids => LogCategory::Raids,
+            LogCategoryOption::Config => LogCategory::Config,
+        }
+    }
+}
+
+impl LogRouteConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        // Without a channel, the category is sent back to the default logs
+        // channel.
+        let destination = match (self.channel, self.disable) {
+            (_, Some(true)) => LogDestination::Off,
+            (Some(channel), _) => LogDestination::Channel(channel),
+            (None, _) => LogDestination::Default,
+        };
+
+        if let LogDestination::Channel(channel) = destination {
+            // Ensure RaidProtect has permissions to send messages in the channel.
+            let (permissions, _) = state
+                .cache
+                .permissions(ctx.guild_id)
+                .await?
+                .current_member()
+                .await?
+                .channel(channel)
+                .await?;
+
+            if !permissions.contains(Permissions::SEND_MESSAGES | Permissions::EMBED_LINKS) {
+                let embed = EmbedBuilder::new()
+                    .color(COLOR_RED)
+                    .description(ctx.lang.config_log_route_missing_permission())
+                    .build();
+
+                return Ok(InteractionResponse::EphemeralEmbed(embed));
+            }
+        }
+
+        config.log_routing.set(self.category.into(), destination);
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::config::log_routing_updated(ctx.lang, &config))
+    }
+}
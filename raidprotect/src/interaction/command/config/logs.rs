@@ -0,0 +1,56 @@
+//! Logs configuration commands.
+//!
+//! Settings related to the logs sent or stored by RaidProtect are grouped in a
+//! subcommand group, since Discord limits commands to 25 subcommands.
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use super::{
+    LogRouteConfigCommand, MilestonesConfigCommand, ModlogRetentionConfigCommand,
+    ReportsConfigCommand, RoleLogsConfigCommand, WatchConfigCommand,
+};
+use crate::{
+    cluster::ClusterState,
+    desc_localizations,
+    interaction::{response::InteractionResponse, util::GuildInteractionContext},
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "logs",
+    desc = "Configure the logs and notifications",
+    desc_localizations = "config_logs_description"
+)]
+pub enum LogsConfigCommand {
+    #[command(name = "modlog-retention")]
+    ModlogRetention(ModlogRetentionConfigCommand),
+    #[command(name = "watch")]
+    Watch(WatchConfigCommand),
+    #[command(name = "roles")]
+    Roles(RoleLogsConfigCommand),
+    #[command(name = "reports")]
+    Reports(ReportsConfigCommand),
+    #[command(name = "route")]
+    Route(LogRouteConfigCommand),
+    #[command(name = "milestones")]
+    Milestones(MilestonesConfigCommand),
+}
+
+desc_localizations!(config_logs_description);
+
+impl LogsConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        match self {
+            LogsConfigCommand::ModlogRetention(command) => command.exec(ctx, state).await,
+            LogsConfigCommand::Watch(command) => command.exec(ctx, state).await,
+            LogsConfigCommand::Roles(command) => command.exec(ctx, state).await,
+            LogsConfigCommand::Reports(command) => command.exec(ctx, state).await,
+            LogsConfigCommand::Route(command) => command.exec(ctx, state).await,
+            LogsConfigCommand::Milestones(command) => command.exec(ctx, state).await,
+        }
+    }
+}
@@ -3,11 +3,94 @@
 //! The configuration command allows the user to change the configuration of the
 //! bot.
 
+mod activity_role;
+mod anonymize;
+mod antinuke;
+mod auto_delete;
+mod automod;
 mod captcha;
+mod check;
+mod fast_mode;
+mod filter_feedback;
+mod filters;
+mod first_message;
+mod grace;
+mod ignored_roles;
+mod join_burst;
+mod lang;
+mod link_filter;
+mod log_route;
+mod logs;
+mod milestones;
+mod modlog_retention;
+mod mute_role;
+mod names;
+mod new_account;
+mod pattern_rules;
+mod pins;
+mod quarantine_role;
+mod raid_alert;
+mod raid_mode;
+mod reaction_spam;
+mod reasons;
+mod reports;
+mod role_logs;
+mod sanction_announce;
+mod sanction_confirmation;
+mod sanction_cooldown;
+mod sanction_dm;
+mod sanction_roles;
+mod sanctions;
+mod screening;
+mod sentinel;
+mod voice;
+mod watch;
+mod webhook;
 
+pub use activity_role::ActivityRoleConfigCommand;
+pub use anonymize::AnonymizeConfigCommand;
+pub use antinuke::AntinukeConfigCommand;
+pub use auto_delete::AutoDeleteConfigCommand;
+pub use automod::AutomodConfigCommand;
 pub use captcha::CaptchaConfigCommand;
+pub use check::CheckConfigCommand;
+pub use fast_mode::FastModeConfigCommand;
+pub use filter_feedback::FilterFeedbackConfigCommand;
+pub use filters::FiltersConfigCommand;
+pub use first_message::FirstMessageConfigCommand;
+pub use grace::GraceConfigCommand;
+pub use ignored_roles::IgnoredRolesConfigCommand;
+pub use join_burst::JoinBurstConfigCommand;
+pub use lang::LangConfigCommand;
+pub use link_filter::LinkFilterConfigCommand;
+pub use log_route::LogRouteConfigCommand;
+pub use logs::LogsConfigCommand;
+pub use milestones::MilestonesConfigCommand;
+pub use modlog_retention::ModlogRetentionConfigCommand;
+pub use mute_role::MuteRoleConfigCommand;
+pub use names::NamesConfigCommand;
+pub use new_account::NewAccountConfigCommand;
+pub use pattern_rules::PatternRulesConfigCommand;
+pub use pins::PinsConfigCommand;
+pub use quarantine_role::QuarantineRoleConfigCommand;
+pub use raid_alert::RaidAlertConfigCommand;
+pub use raid_mode::RaidModeConfigCommand;
+pub use reaction_spam::ReactionSpamConfigCommand;
+pub use reasons::ReasonsConfigCommand;
+pub use reports::ReportsConfigCommand;
+pub use role_logs::RoleLogsConfigCommand;
+pub use sanction_announce::SanctionAnnounceConfigCommand;
+pub use sanction_confirmation::SanctionConfirmationConfigCommand;
+pub use sanction_cooldown::SanctionCooldownConfigCommand;
+pub use sanction_dm::SanctionDmConfigCommand;
+pub use sanction_roles::SanctionRolesConfigCommand;
+pub use sanctions::SanctionsConfigCommand;
+pub use screening::ScreeningConfigCommand;
+pub use sentinel::SentinelConfigCommand;
 use twilight_interactions::command::{CommandModel, CreateCommand};
-use twilight_model::guild::Permissions;
+pub use voice::VoiceConfigCommand;
+pub use watch::WatchConfigCommand;
+pub use webhook::WebhookConfigCommand;
 
 use crate::{
     cluster::ClusterState,
@@ -22,22 +105,64 @@ use crate::{
 #[command(
     name = "config",
     desc = "Configure RaidProtect on your server",
-    desc_localizations = "config_description",
-    default_permissions = "config_permissions",
-    dm_permission = false
+    desc_localizations = "config_description"
 )]
 pub enum ConfigCommand {
+    #[command(name = "check")]
+    Check(CheckConfigCommand),
     #[command(name = "captcha")]
     Captcha(CaptchaConfigCommand),
+    #[command(name = "lang")]
+    Lang(LangConfigCommand),
+    #[command(name = "auto-delete")]
+    AutoDelete(AutoDeleteConfigCommand),
+    #[command(name = "logs")]
+    Logs(LogsConfigCommand),
+    #[command(name = "raid-alert")]
+    RaidAlert(RaidAlertConfigCommand),
+    #[command(name = "raid-mode")]
+    RaidMode(RaidModeConfigCommand),
+    #[command(name = "filters")]
+    Filters(FiltersConfigCommand),
+    #[command(name = "reasons")]
+    Reasons(ReasonsConfigCommand),
+    #[command(name = "sanctions")]
+    Sanctions(SanctionsConfigCommand),
+    #[command(name = "sanction-roles")]
+    SanctionRoles(SanctionRolesConfigCommand),
+    #[command(name = "fast-mode")]
+    FastMode(FastModeConfigCommand),
+    #[command(name = "mute-role")]
+    MuteRole(MuteRoleConfigCommand),
+    #[command(name = "quarantine-role")]
+    QuarantineRole(QuarantineRoleConfigCommand),
+    #[command(name = "new-account")]
+    NewAccount(NewAccountConfigCommand),
+    #[command(name = "sentinel")]
+    Sentinel(SentinelConfigCommand),
+    #[command(name = "antinuke")]
+    Antinuke(AntinukeConfigCommand),
+    #[command(name = "join-burst")]
+    JoinBurst(JoinBurstConfigCommand),
+    #[command(name = "automod")]
+    Automod(AutomodConfigCommand),
+    #[command(name = "screening")]
+    Screening(ScreeningConfigCommand),
+    #[command(name = "names")]
+    Names(NamesConfigCommand),
+    #[command(name = "voice")]
+    Voice(VoiceConfigCommand),
+    #[command(name = "pins")]
+    Pins(PinsConfigCommand),
+    #[command(name = "activity-role")]
+    ActivityRole(ActivityRoleConfigCommand),
+    #[command(name = "webhook")]
+    Webhook(WebhookConfigCommand),
 }
 
 impl_guild_command_handle!(ConfigCommand);
 desc_localizations!(config_description);
 
-fn config_permissions() -> Permissions {
-    Permissions::ADMINISTRATOR
-}
-
 impl ConfigCommand {
     async fn exec(
         self,
@@ -45,7 +170,31 @@ impl ConfigCommand {
         state: &ClusterState,
     ) -> Result<InteractionResponse, anyhow::Error> {
         match self {
+            Self::Check(command) => command.exec(ctx, state).await,
             Self::Captcha(command) => command.exec(ctx, state).await,
+            Self::Lang(command) => command.exec(ctx, state).await,
+            Self::AutoDelete(command) => command.exec(ctx, state).await,
+            Self::Logs(command) => command.exec(ctx, state).await,
+            Self::RaidAlert(command) => command.exec(ctx, state).await,
+            Self::RaidMode(command) => command.exec(ctx, state).await,
+            Self::Filters(command) => command.exec(ctx, state).await,
+            Self::Reasons(command) => command.exec(ctx, state).await,
+            Self::Sanctions(command) => command.exec(ctx, state).await,
+            Self::SanctionRoles(command) => command.exec(ctx, state).await,
+            Self::FastMode(command) => command.exec(ctx, state).await,
+            Self::MuteRole(command) => command.exec(ctx, state).await,
+            Self::QuarantineRole(command) => command.exec(ctx, state).await,
+            Self::NewAccount(command) => command.exec(ctx, state).await,
+            Self::Sentinel(command) => command.exec(ctx, state).await,
+            Self::Antinuke(command) => command.exec(ctx, state).await,
+            Self::JoinBurst(command) => command.exec(ctx, state).await,
+            Self::Automod(command) => command.exec(ctx, state).await,
+            Self::Screening(command) => command.exec(ctx, state).await,
+            Self::Names(command) => command.exec(ctx, state).await,
+            Self::Voice(command) => command.exec(ctx, state).await,
+            Self::Pins(command) => command.exec(ctx, state).await,
+            Self::ActivityRole(command) => command.exec(ctx, state).await,
+            Self::Webhook(command) => command.exec(ctx, state).await,
         }
     }
 }
@@ -0,0 +1,54 @@
+//! Modlog retention configuration command.
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        embed::{EmbedBuilder, COLOR_SUCCESS},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "modlog-retention",
+    desc = "Automatically delete old moderation logs",
+    desc_localizations = "config_modlog_retention_description"
+)]
+pub struct ModlogRetentionConfigCommand {
+    /// Number of days moderation logs are kept. Leave empty to keep them forever.
+    #[command(min_value = 1, max_value = 3650)]
+    days: Option<i64>,
+}
+
+desc_localizations!(config_modlog_retention_description);
+
+impl ModlogRetentionConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        // The retention is bounded by the command options.
+        config.modlog_retention = self.days.map(|days| days as u16);
+        database::update_guild_config(state, &config).await?;
+
+        let description = match self.days {
+            Some(days) => ctx.lang.config_modlog_retention_enabled(days),
+            None => ctx.lang.config_modlog_retention_disabled().to_owned(),
+        };
+
+        let embed = EmbedBuilder::new()
+            .color(COLOR_SUCCESS)
+            .title(ctx.lang.config_updated_title())
+            .description(description)
+            .build();
+
+        Ok(InteractionResponse::EphemeralEmbed(embed))
+    }
+}
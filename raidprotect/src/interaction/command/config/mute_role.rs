@@ -0,0 +1,252 @@
+//! Mute role configuration commands.
+//!
+//! By default, members are muted with a Discord timeout. Guilds that disallow
+//! timeouts can use a role instead. The `setup` command creates the role (or
+//! uses an existing one) and denies it the permission to talk in every
+//! channel of the guild.
+//!
+//! The `enforce` command enables the reapplication of timeouts cleared by
+//! someone else than a moderator.
+
+use raidprotect_model::{
+    cache::discord::{permission::RoleOrdering, CachedChannel},
+    database::model::MuteMode,
+};
+use tracing::{error, trace};
+use twilight_http::request::AuditLogReason;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::{
+    guild::{Permissions, Role},
+    http::permission_overwrite::{PermissionOverwrite, PermissionOverwriteType},
+    id::{
+        marker::{GuildMarker, RoleMarker},
+        Id,
+    },
+};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        embed,
+        response::InteractionResponse,
+        util::{GuildConfigExt, GuildInteractionContext},
+    },
+};
+
+/// Permissions denied to the mute role in every channel.
+const MUTE_ROLE_DENY: Permissions = Permissions::SEND_MESSAGES
+    .union(Permissions::SEND_MESSAGES_IN_THREADS)
+    .union(Permissions::ADD_REACTIONS)
+    .union(Permissions::SPEAK);
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "mute-role",
+    desc = "Configure how members are muted",
+    desc_localizations = "config_mute_role_description"
+)]
+pub enum MuteRoleConfigCommand {
+    #[command(name = "setup")]
+    Setup(MuteRoleSetupCommand),
+    #[command(name = "disable")]
+    Disable(MuteRoleDisableCommand),
+    #[command(name = "enforce")]
+    Enforce(MuteEnforceCommand),
+}
+
+desc_localizations!(config_mute_role_description);
+
+impl MuteRoleConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        match self {
+            MuteRoleConfigCommand::Setup(command) => command.exec(ctx, state).await,
+            MuteRoleConfigCommand::Disable(command) => command.exec(ctx, state).await,
+            MuteRoleConfigCommand::Enforce(command) => command.exec(ctx, state).await,
+        }
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "setup",
+    desc = "Set up the role used to mute members",
+    desc_localizations = "config_mute_role_setup_description"
+)]
+pub struct MuteRoleSetupCommand {
+    /// Existing role to use. A new role is created if not specified.
+    role: Option<Role>,
+}
+
+desc_localizations!(config_mute_role_setup_description);
+
+impl MuteRoleSetupCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+        let guild_lang = config.lang();
+
+        // The bot needs the `MANAGE_CHANNELS` and `MANAGE_ROLES` permissions.
+        let permissions = state
+            .cache
+            .permissions(ctx.guild_id)
+            .await?
+            .current_member()
+            .await?;
+
+        if !permissions
+            .guild()
+            .contains(Permissions::MANAGE_CHANNELS | Permissions::MANAGE_ROLES)
+        {
+            return Ok(embed::mute::role_missing_permission(ctx.lang));
+        }
+
+        let role_id = match self.role {
+            Some(role) => {
+                if RoleOrdering::from(&role) >= permissions.highest_role() {
+                    return Ok(embed::mute::role_hierarchy(ctx.lang));
+                }
+
+                role.id
+            }
+            None => match state
+                .http
+                .create_role(ctx.guild_id)
+                .name(guild_lang.mute_role_name())
+                .permissions(Permissions::empty())
+                .reason(guild_lang.mute_role_reason())?
+                .exec()
+                .await
+            {
+                Ok(response) => response.model().await?.id,
+                Err(err) => {
+                    error!(error = ?err, "failed to create the mute role");
+
+                    return Ok(embed::mute::role_error(ctx.lang));
+                }
+            },
+        };
+
+        config.moderation.mute_mode = MuteMode::Role { role_id };
+        database::update_guild_config(state, &config).await?;
+
+        // Start the configuration of channels permissions.
+        let state_clone = state.clone();
+        tokio::spawn(async move {
+            if let Err(error) = configure_channels(&state_clone, ctx.guild_id, role_id).await {
+                error!(error = ?error, guild = ?ctx.guild_id, "failed to configure mute role channels permissions");
+            }
+        });
+
+        Ok(embed::mute::role_configured(role_id, ctx.lang))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "disable",
+    desc = "Mute members with a timeout again",
+    desc_localizations = "config_mute_role_disable_description"
+)]
+pub struct MuteRoleDisableCommand;
+
+desc_localizations!(config_mute_role_disable_description);
+
+impl MuteRoleDisableCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        config.moderation.mute_mode = MuteMode::Timeout;
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::mute::role_disabled(ctx.lang))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "enforce",
+    desc = "Reapply timeouts cleared by other bots or by mistake",
+    desc_localizations = "config_mute_enforce_description"
+)]
+pub struct MuteEnforceCommand {
+    /// Whether cleared timeouts are reapplied.
+    enabled: bool,
+}
+
+desc_localizations!(config_mute_enforce_description);
+
+impl MuteEnforceCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        config.moderation.enforce_mutes = self.enabled;
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::mute::enforce_updated(self.enabled, ctx.lang))
+    }
+}
+
+/// Deny the mute role the permission to talk in every channel of the guild.
+///
+/// This function should be used as a background task. Threads are skipped
+/// since they inherit permissions from their parent channel, and channels
+/// where the permissions are already denied are not updated.
+async fn configure_channels(
+    state: &ClusterState,
+    guild: Id<GuildMarker>,
+    role: Id<RoleMarker>,
+) -> Result<(), anyhow::Error> {
+    let guild_channels = state.cache.guild_channels(guild).await?;
+
+    for channel in guild_channels {
+        if channel.is_thread() || is_configured(&channel, role) {
+            continue;
+        }
+
+        trace!(channel = ?channel.id, role = ?role, guild = ?guild, "updating channel permissions for mute role");
+
+        let permission_overwrite = PermissionOverwrite {
+            id: role.cast(),
+            kind: PermissionOverwriteType::Role,
+            allow: None,
+            deny: Some(MUTE_ROLE_DENY),
+        };
+
+        if let Err(error) = state
+            .http
+            .update_channel_permission(channel.id, &permission_overwrite)
+            .exec()
+            .await
+        {
+            error!(error = ?error, "failed to update channel permissions for mute role");
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether the mute role permissions are already denied in a channel.
+fn is_configured(channel: &CachedChannel, role: Id<RoleMarker>) -> bool {
+    channel
+        .permission_overwrites
+        .iter()
+        .flatten()
+        .find(|overwrite| overwrite.id == role.cast())
+        .map_or(false, |overwrite| overwrite.deny.contains(MUTE_ROLE_DENY))
+}
@@ -0,0 +1,94 @@
+//! Member names normalization configuration command.
+
+use raidprotect_model::database::model::{NameAction, NameConfig};
+use twilight_interactions::command::{CommandModel, CommandOption, CreateCommand, CreateOption};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        embed::{self, EmbedBuilder, COLOR_RED},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+    util::name::check_name,
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "names",
+    desc = "Configure the detection of names with zalgo or invisible characters",
+    desc_localizations = "config_names_description"
+)]
+pub struct NamesConfigCommand {
+    /// Whether the names of members are checked.
+    enabled: Option<bool>,
+    /// Action applied to members with a problematic name.
+    action: Option<NameActionOption>,
+    /// Nickname used when nothing remains of the cleaned name.
+    fallback: Option<String>,
+}
+
+desc_localizations!(config_names_description);
+
+/// Action applied by the names normalization.
+#[derive(Debug, Clone, Copy, CommandOption, CreateOption)]
+pub enum NameActionOption {
+    #[option(name = "Reset the nickname", value = "reset")]
+    Reset,
+    #[option(name = "Report to the moderators", value = "flag")]
+    Flag,
+}
+
+impl From<NameActionOption> for NameAction {
+    fn from(action: NameActionOption) -> Self {
+        match action {
+            NameActionOption::Reset => NameAction::Reset,
+            NameActionOption::Flag => NameAction::Flag,
+        }
+    }
+}
+
+impl NamesConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+        let names = &mut config.names;
+
+        if let Some(enabled) = self.enabled {
+            names.enabled = enabled;
+        }
+
+        if let Some(action) = self.action {
+            names.action = action.into();
+        }
+
+        if let Some(fallback) = self.fallback {
+            let fallback = fallback.trim();
+
+            // The fallback must not be reset itself.
+            if fallback.chars().count() > NameConfig::MAX_FALLBACK_LENGTH
+                || check_name(fallback).is_some()
+            {
+                let embed = EmbedBuilder::new()
+                    .color(COLOR_RED)
+                    .description(
+                        ctx.lang
+                            .config_names_fallback_invalid(NameConfig::MAX_FALLBACK_LENGTH),
+                    )
+                    .build();
+
+                return Ok(InteractionResponse::EphemeralEmbed(embed));
+            }
+
+            names.fallback = fallback.to_owned();
+        }
+
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::config::names_updated(ctx.lang, &config.names))
+    }
+}
@@ -0,0 +1,54 @@
+//! New account threshold configuration command.
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        embed::{EmbedBuilder, COLOR_SUCCESS},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "new-account",
+    desc = "Configure the age below which accounts are marked as new in logs",
+    desc_localizations = "config_new_account_description"
+)]
+pub struct NewAccountConfigCommand {
+    /// Account age in hours. Set to 0 to disable the marker.
+    #[command(min_value = 0, max_value = 8760)]
+    threshold: i64,
+}
+
+desc_localizations!(config_new_account_description);
+
+impl NewAccountConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        // The threshold is bounded by the command options.
+        config.new_account_threshold = self.threshold as u32;
+        database::update_guild_config(state, &config).await?;
+
+        let description = match self.threshold {
+            0 => ctx.lang.config_new_account_disabled().to_owned(),
+            threshold => ctx.lang.config_new_account_enabled(threshold),
+        };
+
+        let embed = EmbedBuilder::new()
+            .color(COLOR_SUCCESS)
+            .title(ctx.lang.config_updated_title())
+            .description(description)
+            .build();
+
+        Ok(InteractionResponse::EphemeralEmbed(embed))
+    }
+}
@@ -0,0 +1,112 @@
+//! Pattern rules configuration command.
+
+use raidprotect_model::database::model::{PatternAction, PatternRule};
+use twilight_interactions::command::{CommandModel, CommandOption, CreateCommand, CreateOption};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+    util::pattern::compile,
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "pattern-rules",
+    desc = "Configure the custom pattern rules",
+    desc_localizations = "config_pattern_rules_description"
+)]
+pub struct PatternRulesConfigCommand {
+    /// Name of the rule to add, update or remove.
+    #[command(max_length = 32)]
+    name: Option<String>,
+    /// Regular expression matched against the messages.
+    #[command(max_length = 200)]
+    pattern: Option<String>,
+    /// Action applied to the matching messages.
+    action: Option<PatternActionOption>,
+    /// Remove the rule.
+    remove: Option<bool>,
+}
+
+desc_localizations!(config_pattern_rules_description);
+
+/// Action applied by a pattern rule.
+#[derive(Debug, Clone, Copy, CommandOption, CreateOption)]
+pub enum PatternActionOption {
+    #[option(name = "Log only", value = "log")]
+    Log,
+    #[option(name = "Delete the message", value = "delete")]
+    Delete,
+    #[option(name = "Delete the message and timeout", value = "timeout")]
+    Timeout,
+}
+
+impl From<PatternActionOption> for PatternAction {
+    fn from(action: PatternActionOption) -> Self {
+        match action {
+            PatternActionOption::Log => PatternAction::Log,
+            PatternActionOption::Delete => PatternAction::Delete,
+            PatternActionOption::Timeout => PatternAction::Timeout,
+        }
+    }
+}
+
+impl PatternRulesConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+        let rules = &mut config.pattern_rules;
+
+        // Without a rule name, the configured rules are displayed.
+        let name = match self.name.as_deref().map(str::trim) {
+            Some(name) if !name.is_empty() => name.to_owned(),
+            _ => return Ok(embed::config::pattern_rules_updated(ctx.lang, rules)),
+        };
+        let position = rules.iter().position(|rule| rule.name == name);
+
+        if self.remove == Some(true) {
+            match position {
+                Some(position) => rules.remove(position),
+                None => return Ok(embed::config::pattern_rules_not_found(ctx.lang, &name)),
+            };
+        } else if let Some(pattern) = self.pattern {
+            // Patterns are validated before being saved.
+            if let Err(error) = compile(&pattern) {
+                return Ok(embed::config::pattern_rules_invalid(ctx.lang, error));
+            }
+
+            let action = self.action.map(Into::into).unwrap_or_default();
+            let rule = PatternRule {
+                name,
+                pattern,
+                action,
+            };
+
+            match position {
+                Some(position) => rules[position] = rule,
+                None if rules.len() >= PatternRule::MAX_RULES => {
+                    return Ok(embed::config::pattern_rules_too_many(ctx.lang));
+                }
+                None => rules.push(rule),
+            }
+        } else if let Some(action) = self.action {
+            match position {
+                Some(position) => rules[position].action = action.into(),
+                None => return Ok(embed::config::pattern_rules_not_found(ctx.lang, &name)),
+            }
+        } else {
+            return Ok(embed::config::pattern_rules_updated(ctx.lang, rules));
+        }
+
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::config::pattern_rules_updated(
+            ctx.lang,
+            &config.pattern_rules,
+        ))
+    }
+}
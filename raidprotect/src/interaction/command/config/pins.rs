@@ -0,0 +1,102 @@
+//! Message pins logs configuration command.
+
+use raidprotect_model::database::model::PinsConfig;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::{
+    guild::Permissions,
+    id::{marker::ChannelMarker, Id},
+};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        embed::{self, EmbedBuilder, COLOR_RED},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "pins",
+    desc = "Configure the message pins logs",
+    desc_localizations = "config_pins_description"
+)]
+pub struct PinsConfigCommand {
+    /// Channel where pinned and unpinned messages are logged.
+    #[command(channel_types = "guild_text")]
+    logs_channel: Option<Id<ChannelMarker>>,
+    /// Disable the message pins logs.
+    logs_disable: Option<bool>,
+    /// Channel whose pin changes are not logged.
+    ignored_channel_add: Option<Id<ChannelMarker>>,
+    /// Channel to remove from the ignored channels.
+    ignored_channel_remove: Option<Id<ChannelMarker>>,
+}
+
+desc_localizations!(config_pins_description);
+
+impl PinsConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+        let pins = &mut config.pins;
+
+        if let Some(channel) = self.logs_channel {
+            // Ensure RaidProtect has permissions to send messages in the channel.
+            let (permissions, _) = state
+                .cache
+                .permissions(ctx.guild_id)
+                .await?
+                .current_member()
+                .await?
+                .channel(channel)
+                .await?;
+
+            if !permissions.contains(Permissions::SEND_MESSAGES | Permissions::EMBED_LINKS) {
+                let embed = EmbedBuilder::new()
+                    .color(COLOR_RED)
+                    .description(ctx.lang.pins_logs_missing_permission())
+                    .build();
+
+                return Ok(InteractionResponse::EphemeralEmbed(embed));
+            }
+
+            pins.logs_chan = Some(channel);
+        }
+
+        if self.logs_disable == Some(true) {
+            pins.logs_chan = None;
+        }
+
+        if let Some(channel) = self.ignored_channel_add {
+            if !pins.ignored_channels.contains(&channel) {
+                if pins.ignored_channels.len() >= PinsConfig::MAX_IGNORED_CHANNELS_LEN {
+                    let embed =
+                        EmbedBuilder::new()
+                            .color(COLOR_RED)
+                            .description(ctx.lang.pins_ignored_channel_too_many(
+                                PinsConfig::MAX_IGNORED_CHANNELS_LEN,
+                            ))
+                            .build();
+
+                    return Ok(InteractionResponse::EphemeralEmbed(embed));
+                }
+
+                pins.ignored_channels.push(channel);
+            }
+        }
+
+        if let Some(channel) = self.ignored_channel_remove {
+            pins.ignored_channels.retain(|c| c != &channel);
+        }
+
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::config::pins_updated(ctx.lang, &config.pins))
+    }
+}
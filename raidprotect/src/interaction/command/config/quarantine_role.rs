@@ -0,0 +1,104 @@
+//! Quarantine role configuration commands.
+//!
+//! The quarantine role is assigned to members isolated with the `/quarantine`
+//! command. Unlike the mute role, the channel permissions of the role are not
+//! configured by the bot, since guilds usually give quarantined members access
+//! to a dedicated channel.
+
+use raidprotect_model::cache::discord::permission::RoleOrdering;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::guild::Role;
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "quarantine-role",
+    desc = "Configure the role assigned to quarantined members",
+    desc_localizations = "config_quarantine_role_description"
+)]
+pub enum QuarantineRoleConfigCommand {
+    #[command(name = "set")]
+    Set(QuarantineRoleSetCommand),
+    #[command(name = "disable")]
+    Disable(QuarantineRoleDisableCommand),
+}
+
+desc_localizations!(config_quarantine_role_description);
+
+impl QuarantineRoleConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        match self {
+            QuarantineRoleConfigCommand::Set(command) => command.exec(ctx, state).await,
+            QuarantineRoleConfigCommand::Disable(command) => command.exec(ctx, state).await,
+        }
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "set",
+    desc = "Set the role assigned to quarantined members",
+    desc_localizations = "config_quarantine_role_set_description"
+)]
+pub struct QuarantineRoleSetCommand {
+    /// Role assigned to quarantined members.
+    role: Role,
+}
+
+desc_localizations!(config_quarantine_role_set_description);
+
+impl QuarantineRoleSetCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let permissions = state.cache.permissions(ctx.guild_id).await?;
+        let bot_member = state.own_member(ctx.guild_id).await?;
+        let bot_permissions = permissions.bot_member(&bot_member).await?;
+
+        if RoleOrdering::from(&self.role) >= bot_permissions.highest_role() {
+            return Ok(embed::quarantine::role_hierarchy(ctx.lang));
+        }
+
+        let mut config = ctx.config(state).await?;
+        config.moderation.quarantine_role = Some(self.role.id);
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::quarantine::role_configured(self.role.id, ctx.lang))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "disable",
+    desc = "Disable the quarantine command",
+    desc_localizations = "config_quarantine_role_disable_description"
+)]
+pub struct QuarantineRoleDisableCommand;
+
+desc_localizations!(config_quarantine_role_disable_description);
+
+impl QuarantineRoleDisableCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        config.moderation.quarantine_role = None;
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::quarantine::role_disabled(ctx.lang))
+    }
+}
@@ -0,0 +1,54 @@
+//! Anti-raid alert configuration command.
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_mention::Mention;
+use twilight_model::guild::Role;
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        embed::{EmbedBuilder, COLOR_SUCCESS},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "raid-alert",
+    desc = "Configure the role mentioned in anti-raid alerts",
+    desc_localizations = "config_raid_alert_description"
+)]
+pub struct RaidAlertConfigCommand {
+    /// Role to mention. No role is mentioned if not specified.
+    role: Option<Role>,
+}
+
+desc_localizations!(config_raid_alert_description);
+
+impl RaidAlertConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        config.raid_alert_role = self.role.as_ref().map(|role| role.id);
+        database::update_guild_config(state, &config).await?;
+
+        let description = match self.role {
+            Some(role) => ctx.lang.config_raid_alert_enabled(role.id.mention()),
+            None => ctx.lang.config_raid_alert_disabled().to_owned(),
+        };
+
+        let embed = EmbedBuilder::new()
+            .color(COLOR_SUCCESS)
+            .title(ctx.lang.config_updated_title())
+            .description(description)
+            .build();
+
+        Ok(InteractionResponse::EphemeralEmbed(embed))
+    }
+}
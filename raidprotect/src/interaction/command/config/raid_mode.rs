@@ -0,0 +1,153 @@
+//! Raid mode configuration command.
+
+use raidprotect_model::database::model::RaidModeAction;
+use twilight_interactions::command::{CommandModel, CommandOption, CreateCommand, CreateOption};
+use twilight_model::{
+    guild::{Permissions, VerificationLevel},
+    id::{
+        marker::{ChannelMarker, RoleMarker},
+        Id,
+    },
+};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        embed::{self, EmbedBuilder, COLOR_RED},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "raid-mode",
+    desc = "Configure the settings applied by the raid mode",
+    desc_localizations = "config_raid_mode_description"
+)]
+pub struct RaidModeConfigCommand {
+    /// Minimum account age (in hours) to join the server. Set to 0 to disable.
+    #[command(min_value = 0, max_value = 8760)]
+    account_age: Option<i64>,
+    /// Verification level applied to the server.
+    verification_level: Option<RaidModeVerificationLevel>,
+    /// Whether an alert is sent for each member join.
+    join_alerts: Option<bool>,
+    /// Action applied to the members with a recent account.
+    action: Option<RaidModeActionOption>,
+    /// Channel where the members held for review are posted.
+    #[command(channel_types = "guild_text")]
+    review_channel: Option<Id<ChannelMarker>>,
+    /// Role given to the members approved by a moderator.
+    member_role: Option<Id<RoleMarker>>,
+}
+
+desc_localizations!(config_raid_mode_description);
+
+/// Verification level applied by the raid mode.
+#[derive(Debug, Clone, Copy, CommandOption, CreateOption)]
+pub enum RaidModeVerificationLevel {
+    #[option(name = "Unchanged", value = "unchanged")]
+    Unchanged,
+    #[option(name = "Medium", value = "medium")]
+    Medium,
+    #[option(name = "High", value = "high")]
+    High,
+    #[option(name = "Very high", value = "very_high")]
+    VeryHigh,
+}
+
+impl From<RaidModeVerificationLevel> for Option<VerificationLevel> {
+    fn from(level: RaidModeVerificationLevel) -> Self {
+        match level {
+            RaidModeVerificationLevel::Unchanged => None,
+            RaidModeVerificationLevel::Medium => Some(VerificationLevel::Medium),
+            RaidModeVerificationLevel::High => Some(VerificationLevel::High),
+            RaidModeVerificationLevel::VeryHigh => Some(VerificationLevel::VeryHigh),
+        }
+    }
+}
+
+/// Action applied by the raid mode to the members with a recent account.
+#[derive(Debug, Clone, Copy, CommandOption, CreateOption)]
+pub enum RaidModeActionOption {
+    #[option(name = "Kick", value = "kick")]
+    Kick,
+    #[option(name = "Hold for review", value = "review")]
+    Review,
+}
+
+impl From<RaidModeActionOption> for RaidModeAction {
+    fn from(action: RaidModeActionOption) -> Self {
+        match action {
+            RaidModeActionOption::Kick => RaidModeAction::Kick,
+            RaidModeActionOption::Review => RaidModeAction::Review,
+        }
+    }
+}
+
+impl RaidModeConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        // Update the configuration.
+        if let Some(account_age) = self.account_age {
+            // The account age is bounded by the command options.
+            config.raid_mode.account_age = match account_age {
+                0 => None,
+                age => Some(age as u32),
+            };
+        }
+
+        if let Some(level) = self.verification_level {
+            config.raid_mode.verification_level = level.into();
+        }
+
+        if let Some(join_alerts) = self.join_alerts {
+            config.raid_mode.join_alerts = join_alerts;
+        }
+
+        if let Some(action) = self.action {
+            config.raid_mode.action = action.into();
+        }
+
+        if let Some(channel) = self.review_channel {
+            // Ensure RaidProtect has permissions to send messages in the channel.
+            let (permissions, _) = state
+                .cache
+                .permissions(ctx.guild_id)
+                .await?
+                .current_member()
+                .await?
+                .channel(channel)
+                .await?;
+
+            if !permissions.contains(Permissions::SEND_MESSAGES | Permissions::EMBED_LINKS) {
+                let embed = EmbedBuilder::new()
+                    .color(COLOR_RED)
+                    .description(ctx.lang.config_raid_mode_review_missing_permission())
+                    .build();
+
+                return Ok(InteractionResponse::EphemeralEmbed(embed));
+            }
+
+            config.raid_mode.review_channel = Some(channel);
+        }
+
+        if let Some(role) = self.member_role {
+            config.raid_mode.member_role = Some(role);
+        }
+
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::config::raid_mode_updated(
+            ctx.lang,
+            &config.raid_mode,
+        ))
+    }
+}
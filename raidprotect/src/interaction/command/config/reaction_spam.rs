@@ -0,0 +1,101 @@
+//! Reaction spam configuration command.
+
+use raidprotect_model::database::model::ReactionSpamConfig;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::{
+    guild::Role,
+    id::{marker::RoleMarker, Id},
+};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        embed::{self, EmbedBuilder, COLOR_RED},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "reaction-spam",
+    desc = "Configure the reaction spam detection",
+    desc_localizations = "config_reaction_spam_description"
+)]
+pub struct ReactionSpamConfigCommand {
+    /// Whether the reaction spam detection is enabled.
+    enabled: Option<bool>,
+    /// Maximum number of reactions allowed during the interval.
+    #[command(min_value = 2, max_value = 100)]
+    max_reactions: Option<i64>,
+    /// Interval (in seconds) during which reactions are counted.
+    #[command(min_value = 1, max_value = 60)]
+    interval: Option<i64>,
+    /// Duration (in seconds) of the timeout. Set to 0 to disable.
+    #[command(min_value = 0, max_value = 2419200)]
+    timeout: Option<i64>,
+    /// Role to add to the trusted roles.
+    trusted_role_add: Option<Role>,
+    /// Role to remove from the trusted roles.
+    trusted_role_remove: Option<Id<RoleMarker>>,
+}
+
+desc_localizations!(config_reaction_spam_description);
+
+impl ReactionSpamConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+        let reaction_spam = &mut config.reaction_spam;
+
+        // Update the configuration. Values are bounded by the command options.
+        if let Some(enabled) = self.enabled {
+            reaction_spam.enabled = enabled;
+        }
+
+        if let Some(max_reactions) = self.max_reactions {
+            reaction_spam.max_reactions = max_reactions as u16;
+        }
+
+        if let Some(interval) = self.interval {
+            reaction_spam.interval = interval as u16;
+        }
+
+        if let Some(timeout) = self.timeout {
+            reaction_spam.timeout = match timeout {
+                0 => None,
+                timeout => Some(timeout as u32),
+            };
+        }
+
+        if let Some(role) = self.trusted_role_add {
+            if !reaction_spam.trusted_roles.contains(&role.id) {
+                if reaction_spam.trusted_roles.len() >= ReactionSpamConfig::MAX_TRUSTED_ROLES_LEN {
+                    let embed = EmbedBuilder::new()
+                        .color(COLOR_RED)
+                        .description(ctx.lang.reaction_spam_trusted_role_too_many())
+                        .build();
+
+                    return Ok(InteractionResponse::EphemeralEmbed(embed));
+                }
+
+                reaction_spam.trusted_roles.push(role.id);
+            }
+        }
+
+        if let Some(role) = self.trusted_role_remove {
+            reaction_spam.trusted_roles.retain(|r| r != &role);
+        }
+
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::config::reaction_spam_updated(
+            ctx.lang,
+            &config.reaction_spam,
+        ))
+    }
+}
@@ -0,0 +1,156 @@
+//! Sanction reason presets configuration commands.
+//!
+//! Reason presets are suggested in the `reason` option of sanction commands
+//! and before the reason modal of the kick command.
+//!
+//! Presets may be templates with variables such as `{rule}`, which are filled
+//! by the moderator when the preset is used (see the [`moderation`] commands).
+//!
+//! [`moderation`]: crate::interaction::command::moderation
+
+use raidprotect_model::database::model::ModerationConfig;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        command::moderation::MAX_TEMPLATE_VARIABLES, component::Paginator, embed,
+        response::InteractionResponse, util::GuildInteractionContext,
+    },
+    util::template,
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "reasons",
+    desc = "Manage the sanction reason presets",
+    desc_localizations = "config_reasons_description"
+)]
+pub enum ReasonsConfigCommand {
+    #[command(name = "add")]
+    Add(ReasonsAddCommand),
+    #[command(name = "remove")]
+    Remove(ReasonsRemoveCommand),
+    #[command(name = "list")]
+    List(ReasonsListCommand),
+}
+
+desc_localizations!(config_reasons_description);
+
+impl ReasonsConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        match self {
+            ReasonsConfigCommand::Add(command) => command.exec(ctx, state).await,
+            ReasonsConfigCommand::Remove(command) => command.exec(ctx, state).await,
+            ReasonsConfigCommand::List(command) => command.exec(ctx, state).await,
+        }
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "add",
+    desc = "Add a sanction reason preset",
+    desc_localizations = "config_reasons_add_description"
+)]
+pub struct ReasonsAddCommand {
+    /// Reason to add to the presets.
+    reason: String,
+}
+
+desc_localizations!(config_reasons_add_description);
+
+impl ReasonsAddCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+        let reason = self.reason.trim();
+
+        if reason.chars().count() > ModerationConfig::MAX_REASON_PRESET_LENGTH {
+            return Ok(embed::reasons::too_long(ctx.lang));
+        }
+
+        if template::variables(reason).len() > MAX_TEMPLATE_VARIABLES {
+            return Ok(embed::reasons::too_many_variables(ctx.lang));
+        }
+
+        // Presets are compared case-insensitively to avoid duplicates.
+        if config.moderation.reason_preset(reason).is_some() {
+            return Ok(embed::reasons::already_added(ctx.lang));
+        }
+
+        if config.moderation.reason_presets.len() >= ModerationConfig::MAX_REASON_PRESETS_LEN {
+            return Ok(embed::reasons::too_many(ctx.lang));
+        }
+
+        config.moderation.reason_presets.push(reason.to_owned());
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::reasons::added(ctx.lang, reason))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "remove",
+    desc = "Remove a sanction reason preset",
+    desc_localizations = "config_reasons_remove_description"
+)]
+pub struct ReasonsRemoveCommand {
+    /// Reason to remove from the presets.
+    #[command(autocomplete = true)]
+    preset: String,
+}
+
+desc_localizations!(config_reasons_remove_description);
+
+impl ReasonsRemoveCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        let preset = match config.moderation.reason_preset(self.preset.trim()) {
+            Some(preset) => preset.clone(),
+            None => return Ok(embed::reasons::not_found(ctx.lang)),
+        };
+
+        config.moderation.reason_presets.retain(|p| p != &preset);
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::reasons::removed(ctx.lang, &preset))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "list",
+    desc = "List the sanction reason presets",
+    desc_localizations = "config_reasons_list_description"
+)]
+pub struct ReasonsListCommand;
+
+desc_localizations!(config_reasons_list_description);
+
+impl ReasonsListCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let config = ctx.config(state).await?;
+        let pages = embed::reasons::list(ctx.lang, &config.moderation.reason_presets);
+
+        Paginator::create(pages, ctx.interaction.id, ctx.author.id, state, ctx.lang).await
+    }
+}
@@ -0,0 +1,74 @@
+//! Message reports configuration command.
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::{
+    guild::Permissions,
+    id::{marker::ChannelMarker, Id},
+};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        embed::{self, EmbedBuilder, COLOR_RED},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "reports",
+    desc = "Configure the messages reported by members",
+    desc_localizations = "config_reports_description"
+)]
+pub struct ReportsConfigCommand {
+    /// Channel where the reported messages are sent.
+    #[command(channel_types = "guild_text")]
+    channel: Option<Id<ChannelMarker>>,
+    /// Disable the message reports.
+    disable: Option<bool>,
+}
+
+desc_localizations!(config_reports_description);
+
+impl ReportsConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        if let Some(channel) = self.channel {
+            // Ensure RaidProtect has permissions to send messages in the channel.
+            let (permissions, _) = state
+                .cache
+                .permissions(ctx.guild_id)
+                .await?
+                .current_member()
+                .await?
+                .channel(channel)
+                .await?;
+
+            if !permissions.contains(Permissions::SEND_MESSAGES | Permissions::EMBED_LINKS) {
+                let embed = EmbedBuilder::new()
+                    .color(COLOR_RED)
+                    .description(ctx.lang.config_reports_missing_permission())
+                    .build();
+
+                return Ok(InteractionResponse::EphemeralEmbed(embed));
+            }
+
+            config.reports_chan = Some(channel);
+        }
+
+        if self.disable == Some(true) {
+            config.reports_chan = None;
+        }
+
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::config::reports_updated(ctx.lang, &config))
+    }
+}
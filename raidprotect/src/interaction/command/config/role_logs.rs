@@ -0,0 +1,108 @@
+//! Member role changes logs configuration command.
+
+use raidprotect_model::database::model::RoleLogsConfig;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::{
+    guild::Permissions,
+    id::{
+        marker::{ChannelMarker, RoleMarker},
+        Id,
+    },
+};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        embed::{self, EmbedBuilder, COLOR_RED},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "roles",
+    desc = "Configure the member role changes logs",
+    desc_localizations = "config_role_logs_description"
+)]
+pub struct RoleLogsConfigCommand {
+    /// Channel where roles added to or removed from members are logged.
+    #[command(channel_types = "guild_text")]
+    logs_channel: Option<Id<ChannelMarker>>,
+    /// Disable the member role changes logs.
+    logs_disable: Option<bool>,
+    /// Role whose changes are not logged.
+    ignored_role_add: Option<Id<RoleMarker>>,
+    /// Role to remove from the ignored roles.
+    ignored_role_remove: Option<Id<RoleMarker>>,
+}
+
+desc_localizations!(config_role_logs_description);
+
+impl RoleLogsConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+        let role_logs = &mut config.role_logs;
+
+        if let Some(channel) = self.logs_channel {
+            // Ensure RaidProtect has permissions to send messages in the channel.
+            let (permissions, _) = state
+                .cache
+                .permissions(ctx.guild_id)
+                .await?
+                .current_member()
+                .await?
+                .channel(channel)
+                .await?;
+
+            if !permissions.contains(Permissions::SEND_MESSAGES | Permissions::EMBED_LINKS) {
+                let embed = EmbedBuilder::new()
+                    .color(COLOR_RED)
+                    .description(ctx.lang.role_logs_missing_permission())
+                    .build();
+
+                return Ok(InteractionResponse::EphemeralEmbed(embed));
+            }
+
+            role_logs.logs_chan = Some(channel);
+        }
+
+        if self.logs_disable == Some(true) {
+            role_logs.logs_chan = None;
+        }
+
+        if let Some(role) = self.ignored_role_add {
+            if !role_logs.ignored_roles.contains(&role) {
+                if role_logs.ignored_roles.len() >= RoleLogsConfig::MAX_IGNORED_ROLES_LEN {
+                    let embed =
+                        EmbedBuilder::new()
+                            .color(COLOR_RED)
+                            .description(ctx.lang.role_logs_ignored_role_too_many(
+                                RoleLogsConfig::MAX_IGNORED_ROLES_LEN,
+                            ))
+                            .build();
+
+                    return Ok(InteractionResponse::EphemeralEmbed(embed));
+                }
+
+                role_logs.ignored_roles.push(role);
+            }
+        }
+
+        if let Some(role) = self.ignored_role_remove {
+            role_logs.ignored_roles.retain(|r| r != &role);
+        }
+
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::config::role_logs_updated(
+            ctx.lang,
+            &config.role_logs,
+        ))
+    }
+}
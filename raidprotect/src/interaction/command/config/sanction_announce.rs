@@ -0,0 +1,111 @@
+//! Sanction announcements configuration command.
+//!
+//! Some guilds announce sanctions publicly to deter other members. The
+//! announcements are sent in a public channel, separate from the logs channel
+//! that is restricted to the staff. Only the sanctions enabled with this
+//! command are announced, and shadow sanctions are never announced.
+
+use raidprotect_model::database::model::ModlogType;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_mention::Mention;
+use twilight_model::{
+    guild::Permissions,
+    id::{marker::ChannelMarker, Id},
+};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        command::modlog::ModlogTypeOption,
+        embed::{self, EmbedBuilder, COLOR_RED, COLOR_SUCCESS},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "announce",
+    desc = "Announce sanctions in a public channel",
+    desc_localizations = "config_sanction_announce_description"
+)]
+pub struct SanctionAnnounceConfigCommand {
+    /// Sanction to configure.
+    sanction: ModlogTypeOption,
+    /// Whether this sanction is announced.
+    enabled: bool,
+    /// Public channel where sanctions are announced.
+    #[command(channel_types = "guild_text")]
+    channel: Option<Id<ChannelMarker>>,
+}
+
+desc_localizations!(config_sanction_announce_description);
+
+impl SanctionAnnounceConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        if let Some(channel) = self.channel {
+            // Ensure RaidProtect has permissions to send messages in the channel.
+            let (permissions, _) = state
+                .cache
+                .permissions(ctx.guild_id)
+                .await?
+                .current_member()
+                .await?
+                .channel(channel)
+                .await?;
+
+            if !permissions.contains(Permissions::SEND_MESSAGES | Permissions::EMBED_LINKS) {
+                return Ok(error(
+                    ctx.lang.config_sanction_announce_missing_permission(),
+                ));
+            }
+
+            config.moderation.announcement_channel = Some(channel);
+        }
+
+        let kind = ModlogType::from(self.sanction);
+        let name = embed::sanction_roles::sanction_kind(ctx.lang, kind);
+        let description = match (self.enabled, config.moderation.announcement_channel) {
+            (true, Some(channel)) => ctx
+                .lang
+                .config_sanction_announce_enabled(channel.mention(), name),
+            (true, None) => {
+                return Ok(error(ctx.lang.config_sanction_announce_missing_channel()));
+            }
+            (false, _) => ctx.lang.config_sanction_announce_disabled(name),
+        };
+
+        let moderation = &mut config.moderation;
+        moderation.announced.retain(|announced| *announced != kind);
+        if self.enabled {
+            moderation.announced.push(kind);
+        }
+
+        database::update_guild_config(state, &config).await?;
+
+        let embed = EmbedBuilder::new()
+            .color(COLOR_SUCCESS)
+            .title(ctx.lang.config_updated_title())
+            .description(description)
+            .build();
+
+        Ok(InteractionResponse::EphemeralEmbed(embed))
+    }
+}
+
+/// Error response of the command.
+fn error(description: &str) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(description)
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
@@ -0,0 +1,91 @@
+//! Sanction confirmation configuration command.
+//!
+//! When a member has just been sanctioned, other moderators must confirm
+//! before sanctioning them again (see the sanction cooldown). Guilds can skip
+//! this confirmation for routine sanctions. Bans and kicks always require a
+//! confirmation, and thus cannot be selected.
+
+use raidprotect_model::database::model::ModlogType;
+use twilight_interactions::command::{CommandModel, CommandOption, CreateCommand, CreateOption};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        embed::{self, EmbedBuilder, COLOR_SUCCESS},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "sanction-confirmation",
+    desc = "Skip the confirmation when sanctioning a member that was just sanctioned",
+    desc_localizations = "config_sanction_confirmation_description"
+)]
+pub struct SanctionConfirmationConfigCommand {
+    /// Sanction to configure.
+    sanction: SanctionOption,
+    /// Whether the sanction is applied without confirmation.
+    skip: bool,
+}
+
+desc_localizations!(config_sanction_confirmation_description);
+
+/// Sanction that can be applied without confirmation.
+#[derive(Debug, Clone, Copy, CommandOption, CreateOption)]
+pub enum SanctionOption {
+    #[option(name = "Mute", value = "mute")]
+    Mute,
+    #[option(name = "Unmute", value = "unmute")]
+    Unmute,
+    #[option(name = "Quarantine", value = "quarantine")]
+    Quarantine,
+    #[option(name = "Release", value = "release")]
+    Release,
+}
+
+impl From<SanctionOption> for ModlogType {
+    fn from(sanction: SanctionOption) -> Self {
+        match sanction {
+            SanctionOption::Mute => ModlogType::Mute,
+            SanctionOption::Unmute => ModlogType::Unmute,
+            SanctionOption::Quarantine => ModlogType::Quarantine,
+            SanctionOption::Release => ModlogType::Release,
+        }
+    }
+}
+
+impl SanctionConfirmationConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let kind = ModlogType::from(self.sanction);
+        let mut config = ctx.config(state).await?;
+        let skipped = &mut config.moderation.skip_confirmation;
+
+        skipped.retain(|skipped| *skipped != kind);
+        if self.skip {
+            skipped.push(kind);
+        }
+
+        database::update_guild_config(state, &config).await?;
+
+        let name = embed::sanction_roles::sanction_kind(ctx.lang, kind);
+        let description = match self.skip {
+            true => ctx.lang.config_sanction_confirmation_skipped(name),
+            false => ctx.lang.config_sanction_confirmation_required(name),
+        };
+
+        let embed = EmbedBuilder::new()
+            .color(COLOR_SUCCESS)
+            .title(ctx.lang.config_updated_title())
+            .description(description)
+            .build();
+
+        Ok(InteractionResponse::EphemeralEmbed(embed))
+    }
+}
@@ -0,0 +1,54 @@
+//! Sanction cooldown configuration command.
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        embed::{EmbedBuilder, COLOR_SUCCESS},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "sanction-cooldown",
+    desc = "Ask for a confirmation before sanctioning a member that was just sanctioned",
+    desc_localizations = "config_sanction_cooldown_description"
+)]
+pub struct SanctionCooldownConfigCommand {
+    /// Delay in seconds after a sanction. Set to 0 to disable.
+    #[command(min_value = 0, max_value = 600)]
+    delay: i64,
+}
+
+desc_localizations!(config_sanction_cooldown_description);
+
+impl SanctionCooldownConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        // The delay is bounded by the command options.
+        config.moderation.sanction_cooldown = self.delay as u16;
+        database::update_guild_config(state, &config).await?;
+
+        let description = match self.delay {
+            0 => ctx.lang.config_sanction_cooldown_disabled().to_owned(),
+            delay => ctx.lang.config_sanction_cooldown_enabled(delay),
+        };
+
+        let embed = EmbedBuilder::new()
+            .color(COLOR_SUCCESS)
+            .title(ctx.lang.config_updated_title())
+            .description(description)
+            .build();
+
+        Ok(InteractionResponse::EphemeralEmbed(embed))
+    }
+}
@@ -0,0 +1,89 @@
+//! Sanction private messages configuration command.
+//!
+//! Sanctioned users are notified by private message by default. Guilds can
+//! disable these messages for some sanctions, or for every sanction. The
+//! guild-wide setting takes precedence over the per-sanction one: when it is
+//! disabled, no private message is sent regardless of the sanction, and the
+//! modlogs note that private messages are disabled.
+
+use raidprotect_model::database::model::ModlogType;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        command::modlog::ModlogTypeOption,
+        embed::{self, EmbedBuilder, COLOR_SUCCESS},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "dm",
+    desc = "Configure the private messages sent to sanctioned users",
+    desc_localizations = "config_sanction_dm_description"
+)]
+pub struct SanctionDmConfigCommand {
+    /// Whether sanctioned users are notified by private message.
+    enabled: bool,
+    /// Sanction to configure. Every sanction is configured if not set.
+    sanction: Option<ModlogTypeOption>,
+}
+
+desc_localizations!(config_sanction_dm_description);
+
+impl SanctionDmConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+        let moderation = &mut config.moderation;
+
+        let description = match self.sanction.map(ModlogType::from) {
+            Some(kind) => {
+                moderation.dm_skipped.retain(|skipped| *skipped != kind);
+                if !self.enabled {
+                    moderation.dm_skipped.push(kind);
+                }
+
+                let name = embed::sanction_roles::sanction_kind(ctx.lang, kind);
+                let description = match self.enabled {
+                    true => ctx.lang.config_sanction_dm_sanction_enabled(name),
+                    false => ctx.lang.config_sanction_dm_sanction_disabled(name),
+                };
+
+                // The guild-wide setting takes precedence.
+                match moderation.dm_disabled {
+                    true => format!(
+                        "{description}\n{}",
+                        ctx.lang.config_sanction_dm_overridden()
+                    ),
+                    false => description,
+                }
+            }
+            None => {
+                moderation.dm_disabled = !self.enabled;
+
+                match self.enabled {
+                    true => ctx.lang.config_sanction_dm_enabled().to_owned(),
+                    false => ctx.lang.config_sanction_dm_disabled().to_owned(),
+                }
+            }
+        };
+
+        database::update_guild_config(state, &config).await?;
+
+        let embed = EmbedBuilder::new()
+            .color(COLOR_SUCCESS)
+            .title(ctx.lang.config_updated_title())
+            .description(description)
+            .build();
+
+        Ok(InteractionResponse::EphemeralEmbed(embed))
+    }
+}
@@ -0,0 +1,177 @@
+//! Moderator roles sanctions configuration commands.
+//!
+//! By default, moderators can apply every sanction. Once a role is configured,
+//! moderators can only apply the sanctions allowed by at least one of their
+//! roles. Administrators can always apply every sanction.
+//!
+//! Shadow sanctions are only allowed for administrators, unless a role is
+//! explicitly allowed to apply them with the `shadow` option.
+
+use raidprotect_model::database::model::{ModerationConfig, ModlogType, SanctionRole};
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::id::{marker::RoleMarker, Id};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+    util::collection::upsert,
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "sanction-roles",
+    desc = "Manage the sanctions allowed for each moderator role",
+    desc_localizations = "config_sanction_roles_description"
+)]
+pub enum SanctionRolesConfigCommand {
+    #[command(name = "set")]
+    Set(SanctionRolesSetCommand),
+    #[command(name = "remove")]
+    Remove(SanctionRolesRemoveCommand),
+    #[command(name = "list")]
+    List(SanctionRolesListCommand),
+}
+
+desc_localizations!(config_sanction_roles_description);
+
+impl SanctionRolesConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        match self {
+            SanctionRolesConfigCommand::Set(command) => command.exec(ctx, state).await,
+            SanctionRolesConfigCommand::Remove(command) => command.exec(ctx, state).await,
+            SanctionRolesConfigCommand::List(command) => command.exec(ctx, state).await,
+        }
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "set",
+    desc = "Set the sanctions allowed for a role",
+    desc_localizations = "config_sanction_roles_set_description"
+)]
+pub struct SanctionRolesSetCommand {
+    /// Moderator role.
+    role: Id<RoleMarker>,
+    /// Whether the role allows to ban members.
+    ban: Option<bool>,
+    /// Whether the role allows to kick members.
+    kick: Option<bool>,
+    /// Whether the role allows to mute members.
+    mute: Option<bool>,
+    /// Whether the role allows to unmute members.
+    unmute: Option<bool>,
+    /// Whether the role allows to quarantine members.
+    quarantine: Option<bool>,
+    /// Whether the role allows to release quarantined members.
+    release: Option<bool>,
+    /// Whether the role allows to apply shadow sanctions.
+    shadow: Option<bool>,
+}
+
+desc_localizations!(config_sanction_roles_set_description);
+
+impl SanctionRolesSetCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let sanctions = [
+            (ModlogType::Ban, self.ban),
+            (ModlogType::Kick, self.kick),
+            (ModlogType::Mute, self.mute),
+            (ModlogType::Unmute, self.unmute),
+            (ModlogType::Quarantine, self.quarantine),
+            (ModlogType::Release, self.release),
+        ]
+        .into_iter()
+        .filter(|(_, allowed)| allowed.unwrap_or(false))
+        .map(|(kind, _)| kind)
+        .collect::<Vec<_>>();
+
+        if sanctions.is_empty() {
+            return Ok(embed::sanction_roles::none_selected(ctx.lang));
+        }
+
+        let mut config = ctx.config(state).await?;
+        let roles = &mut config.moderation.sanction_roles;
+        let role = SanctionRole {
+            role_id: self.role,
+            sanctions,
+            shadow: self.shadow.unwrap_or(false),
+        };
+
+        let max_len = ModerationConfig::MAX_SANCTION_ROLES_LEN;
+        if upsert(roles, max_len, role.clone(), |r| r.role_id == self.role).is_err() {
+            return Ok(embed::sanction_roles::too_many(ctx.lang));
+        }
+
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::sanction_roles::set(ctx.lang, &role))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "remove",
+    desc = "Remove the sanctions allowed for a role",
+    desc_localizations = "config_sanction_roles_remove_description"
+)]
+pub struct SanctionRolesRemoveCommand {
+    /// Moderator role.
+    role: Id<RoleMarker>,
+}
+
+desc_localizations!(config_sanction_roles_remove_description);
+
+impl SanctionRolesRemoveCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+        let roles = &mut config.moderation.sanction_roles;
+
+        if !roles.iter().any(|role| role.role_id == self.role) {
+            return Ok(embed::sanction_roles::not_found(ctx.lang));
+        }
+
+        roles.retain(|role| role.role_id != self.role);
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::sanction_roles::removed(ctx.lang, self.role))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "list",
+    desc = "List the sanctions allowed for each moderator role",
+    desc_localizations = "config_sanction_roles_list_description"
+)]
+pub struct SanctionRolesListCommand;
+
+desc_localizations!(config_sanction_roles_list_description);
+
+impl SanctionRolesListCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let config = ctx.config(state).await?;
+
+        Ok(embed::sanction_roles::list(
+            ctx.lang,
+            &config.moderation.sanction_roles,
+        ))
+    }
+}
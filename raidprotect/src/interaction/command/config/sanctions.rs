@@ -0,0 +1,53 @@
+//! Sanctions configuration commands.
+//!
+//! Settings related to how moderators apply sanctions are grouped in a
+//! subcommand group, since Discord limits commands to 25 subcommands.
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use super::{
+    AnonymizeConfigCommand, SanctionAnnounceConfigCommand, SanctionConfirmationConfigCommand,
+    SanctionCooldownConfigCommand, SanctionDmConfigCommand,
+};
+use crate::{
+    cluster::ClusterState,
+    desc_localizations,
+    interaction::{response::InteractionResponse, util::GuildInteractionContext},
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "sanctions",
+    desc = "Configure how sanctions are applied",
+    desc_localizations = "config_sanctions_description"
+)]
+pub enum SanctionsConfigCommand {
+    #[command(name = "sanction-cooldown")]
+    SanctionCooldown(SanctionCooldownConfigCommand),
+    #[command(name = "sanction-confirmation")]
+    SanctionConfirmation(SanctionConfirmationConfigCommand),
+    #[command(name = "anonymize")]
+    Anonymize(AnonymizeConfigCommand),
+    #[command(name = "dm")]
+    Dm(SanctionDmConfigCommand),
+    #[command(name = "announce")]
+    Announce(SanctionAnnounceConfigCommand),
+}
+
+desc_localizations!(config_sanctions_description);
+
+impl SanctionsConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        match self {
+            SanctionsConfigCommand::SanctionCooldown(command) => command.exec(ctx, state).await,
+            SanctionsConfigCommand::SanctionConfirmation(command) => command.exec(ctx, state).await,
+            SanctionsConfigCommand::Anonymize(command) => command.exec(ctx, state).await,
+            SanctionsConfigCommand::Dm(command) => command.exec(ctx, state).await,
+            SanctionsConfigCommand::Announce(command) => command.exec(ctx, state).await,
+        }
+    }
+}
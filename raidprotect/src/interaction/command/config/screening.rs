@@ -0,0 +1,53 @@
+//! Membership screening configuration command.
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        embed::{EmbedBuilder, COLOR_SUCCESS},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "screening",
+    desc = "Configure how members pending the membership screening are handled",
+    desc_localizations = "config_screening_description"
+)]
+pub struct ScreeningConfigCommand {
+    /// Whether members are processed once they accept the rules.
+    enabled: bool,
+}
+
+desc_localizations!(config_screening_description);
+
+impl ScreeningConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        config.screening_aware = self.enabled;
+        database::update_guild_config(state, &config).await?;
+
+        let description = if self.enabled {
+            ctx.lang.config_screening_enabled()
+        } else {
+            ctx.lang.config_screening_disabled()
+        };
+
+        let embed = EmbedBuilder::new()
+            .color(COLOR_SUCCESS)
+            .title(ctx.lang.config_updated_title())
+            .description(description)
+            .build();
+
+        Ok(InteractionResponse::EphemeralEmbed(embed))
+    }
+}
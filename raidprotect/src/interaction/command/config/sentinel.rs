@@ -0,0 +1,53 @@
+//! Sentinel alerts configuration command.
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        embed::{EmbedBuilder, COLOR_SUCCESS},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "sentinel",
+    desc = "Configure alerts sent when dangerous permissions are granted",
+    desc_localizations = "config_sentinel_description"
+)]
+pub struct SentinelConfigCommand {
+    /// Whether alerts are sent in the logs channel.
+    enabled: bool,
+}
+
+desc_localizations!(config_sentinel_description);
+
+impl SentinelConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        config.sentinel_alerts = self.enabled;
+        database::update_guild_config(state, &config).await?;
+
+        let description = if self.enabled {
+            ctx.lang.config_sentinel_enabled()
+        } else {
+            ctx.lang.config_sentinel_disabled()
+        };
+
+        let embed = EmbedBuilder::new()
+            .color(COLOR_SUCCESS)
+            .title(ctx.lang.config_updated_title())
+            .description(description)
+            .build();
+
+        Ok(InteractionResponse::EphemeralEmbed(embed))
+    }
+}
@@ -0,0 +1,154 @@
+//! Voice channels configuration command.
+
+use raidprotect_model::database::model::{VoiceConfig, VoiceHopAction};
+use twilight_interactions::command::{CommandModel, CommandOption, CreateCommand, CreateOption};
+use twilight_model::{
+    guild::{Permissions, Role},
+    id::{
+        marker::{ChannelMarker, RoleMarker},
+        Id,
+    },
+};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        embed::{self, EmbedBuilder, COLOR_RED},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "voice",
+    desc = "Configure the voice channels logs and hop detection",
+    desc_localizations = "config_voice_description"
+)]
+pub struct VoiceConfigCommand {
+    /// Channel where voice channel joins, leaves, moves and mutes are logged.
+    #[command(channel_types = "guild_text")]
+    logs_channel: Option<Id<ChannelMarker>>,
+    /// Disable the voice channels logs.
+    logs_disable: Option<bool>,
+    /// Whether users that hop between voice channels are sanctioned.
+    hop_detection: Option<bool>,
+    /// Maximum number of channel joins allowed during the interval.
+    #[command(min_value = 2, max_value = 30)]
+    max_hops: Option<i64>,
+    /// Interval (in seconds) during which channel joins are counted.
+    #[command(min_value = 5, max_value = 300)]
+    interval: Option<i64>,
+    /// Action applied to users that exceed the limit.
+    action: Option<VoiceHopActionOption>,
+    /// Duration (in seconds) of the timeout.
+    #[command(min_value = 60, max_value = 2419200)]
+    timeout: Option<i64>,
+    /// Role to add to the trusted roles.
+    trusted_role_add: Option<Role>,
+    /// Role to remove from the trusted roles.
+    trusted_role_remove: Option<Id<RoleMarker>>,
+}
+
+desc_localizations!(config_voice_description);
+
+/// Action applied by the voice hop detection.
+#[derive(Debug, Clone, Copy, CommandOption, CreateOption)]
+pub enum VoiceHopActionOption {
+    #[option(name = "Disconnect", value = "disconnect")]
+    Disconnect,
+    #[option(name = "Disconnect and timeout", value = "timeout")]
+    Timeout,
+}
+
+impl From<VoiceHopActionOption> for VoiceHopAction {
+    fn from(action: VoiceHopActionOption) -> Self {
+        match action {
+            VoiceHopActionOption::Disconnect => VoiceHopAction::Disconnect,
+            VoiceHopActionOption::Timeout => VoiceHopAction::Timeout,
+        }
+    }
+}
+
+impl VoiceConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+        let voice = &mut config.voice;
+
+        // Update the configuration. Values are bounded by the command options.
+        if let Some(channel) = self.logs_channel {
+            // Ensure RaidProtect has permissions to send messages in the channel.
+            let (permissions, _) = state
+                .cache
+                .permissions(ctx.guild_id)
+                .await?
+                .current_member()
+                .await?
+                .channel(channel)
+                .await?;
+
+            if !permissions.contains(Permissions::SEND_MESSAGES | Permissions::EMBED_LINKS) {
+                let embed = EmbedBuilder::new()
+                    .color(COLOR_RED)
+                    .description(ctx.lang.voice_logs_missing_permission())
+                    .build();
+
+                return Ok(InteractionResponse::EphemeralEmbed(embed));
+            }
+
+            voice.logs_chan = Some(channel);
+        }
+
+        if self.logs_disable == Some(true) {
+            voice.logs_chan = None;
+        }
+
+        if let Some(hop_detection) = self.hop_detection {
+            voice.hop_detection = hop_detection;
+        }
+
+        if let Some(max_hops) = self.max_hops {
+            voice.max_hops = max_hops as u16;
+        }
+
+        if let Some(interval) = self.interval {
+            voice.interval = interval as u16;
+        }
+
+        if let Some(action) = self.action {
+            voice.action = action.into();
+        }
+
+        if let Some(timeout) = self.timeout {
+            voice.timeout = timeout as u32;
+        }
+
+        if let Some(role) = self.trusted_role_add {
+            if !voice.trusted_roles.contains(&role.id) {
+                if voice.trusted_roles.len() >= VoiceConfig::MAX_TRUSTED_ROLES_LEN {
+                    let embed = EmbedBuilder::new()
+                        .color(COLOR_RED)
+                        .description(ctx.lang.reaction_spam_trusted_role_too_many())
+                        .build();
+
+                    return Ok(InteractionResponse::EphemeralEmbed(embed));
+                }
+
+                voice.trusted_roles.push(role.id);
+            }
+        }
+
+        if let Some(role) = self.trusted_role_remove {
+            voice.trusted_roles.retain(|r| r != &role);
+        }
+
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::config::voice_updated(ctx.lang, &config.voice))
+    }
+}
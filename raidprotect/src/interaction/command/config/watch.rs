@@ -0,0 +1,82 @@
+//! Watchlist configuration command.
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::{
+    guild::Permissions,
+    id::{marker::ChannelMarker, Id},
+};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    interaction::{
+        embed::{self, EmbedBuilder, COLOR_RED},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "watch",
+    desc = "Configure the watchlist notifications",
+    desc_localizations = "config_watch_description"
+)]
+pub struct WatchConfigCommand {
+    /// Channel where the activity of watched users is notified.
+    #[command(channel_types = "guild_text")]
+    channel: Option<Id<ChannelMarker>>,
+    /// Send the notifications in the logs channel.
+    channel_reset: Option<bool>,
+    /// Number of days users remain watched.
+    #[command(min_value = 1, max_value = 365)]
+    duration: Option<i64>,
+}
+
+desc_localizations!(config_watch_description);
+
+impl WatchConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        // Update the configuration. Values are bounded by the command options.
+        if let Some(channel) = self.channel {
+            // Ensure RaidProtect has permissions to send messages in the channel.
+            let (permissions, _) = state
+                .cache
+                .permissions(ctx.guild_id)
+                .await?
+                .current_member()
+                .await?
+                .channel(channel)
+                .await?;
+
+            if !permissions.contains(Permissions::SEND_MESSAGES | Permissions::EMBED_LINKS) {
+                let embed = EmbedBuilder::new()
+                    .color(COLOR_RED)
+                    .description(ctx.lang.voice_logs_missing_permission())
+                    .build();
+
+                return Ok(InteractionResponse::EphemeralEmbed(embed));
+            }
+
+            config.watch_chan = Some(channel);
+        }
+
+        if self.channel_reset == Some(true) {
+            config.watch_chan = None;
+        }
+
+        if let Some(duration) = self.duration {
+            config.watch_duration = duration as u16;
+        }
+
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::config::watch_updated(ctx.lang, &config))
+    }
+}
@@ -0,0 +1,98 @@
+//! Outbound webhook configuration command.
+//!
+//! The webhook url is tested with a ping payload before being saved. A new
+//! signing secret is generated each time the webhook is configured, and is
+//! only shown once in the command response (see [`feature::webhook`]).
+//!
+//! [`feature::webhook`]: crate::feature::webhook
+
+use raidprotect_model::database::model::OutboundWebhook;
+use time::OffsetDateTime;
+use tracing::{debug, error};
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    feature::webhook::{self, WebhookPayload},
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+};
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "webhook",
+    desc = "Send moderation events to an external webhook",
+    desc_localizations = "config_webhook_description"
+)]
+pub struct WebhookConfigCommand {
+    /// HTTPS url of the webhook. Leave empty to disable.
+    url: Option<String>,
+}
+
+desc_localizations!(config_webhook_description);
+
+impl WebhookConfigCommand {
+    pub(super) async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        let url = match self.url {
+            Some(url) => match webhook::parse_url(&url) {
+                Some(url) => url,
+                None => return Ok(embed::webhook::invalid_url(ctx.lang)),
+            },
+            None => {
+                config.outbound_webhook = None;
+                database::update_guild_config(state, &config).await?;
+
+                return Ok(embed::webhook::disabled(ctx.lang));
+            }
+        };
+
+        // The webhook is tested in background, since the request may take
+        // longer than the interaction response delay.
+        let state = state.clone();
+        tokio::spawn(async move {
+            let responder = ctx.responder();
+            let secret = webhook::generate_secret();
+            let payload = WebhookPayload::ping(ctx.guild_id, OffsetDateTime::now_utc());
+
+            let result = match serde_json::to_string(&payload) {
+                Ok(payload) => webhook::send(&url, &secret, &payload).await,
+                Err(error) => Err(error.into()),
+            };
+
+            let embed = match result {
+                Ok(()) => {
+                    config.outbound_webhook = Some(OutboundWebhook {
+                        url,
+                        secret: secret.clone(),
+                        failures: 0,
+                    });
+
+                    if let Err(error) = database::update_guild_config(&state, &config).await {
+                        error!(error = ?error, guild = ?ctx.guild_id, "failed to save outbound webhook");
+
+                        embed::error::internal_error_embed(ctx.lang, ctx.interaction.id)
+                    } else {
+                        embed::webhook::enabled(ctx.lang, &secret)
+                    }
+                }
+                Err(error) => {
+                    debug!(error = ?error, guild = ?ctx.guild_id, "outbound webhook ping failed");
+
+                    embed::webhook::ping_failed(ctx.lang)
+                }
+            };
+
+            if let Err(error) = responder.update_response(&state, embed).await {
+                error!(error = ?error, "failed to update webhook configuration response");
+            }
+        });
+
+        Ok(InteractionResponse::EphemeralDeferredMessage)
+    }
+}
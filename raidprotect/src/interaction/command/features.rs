@@ -0,0 +1,46 @@
+//! Features command.
+//!
+//! This command shows the features of the bot with a button to enable or
+//! disable each of them. A disabled feature is skipped regardless of its own
+//! configuration, which allows to quickly turn a whole subsystem off (for
+//! example, the join logs during an event).
+//!
+//! The buttons are handled by [`FeatureToggle`].
+//!
+//! [`FeatureToggle`]: crate::interaction::component::FeatureToggle
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::http::interaction::InteractionResponseType;
+
+use crate::{
+    cluster::ClusterState,
+    desc_localizations, impl_guild_command_handle,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+};
+
+/// Features command model.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "features",
+    desc = "Enable or disable the features of RaidProtect",
+    desc_localizations = "features_description"
+)]
+pub struct FeaturesCommand;
+
+impl_guild_command_handle!(FeaturesCommand);
+desc_localizations!(features_description);
+
+impl FeaturesCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let config = ctx.config(state).await?;
+
+        Ok(InteractionResponse::Raw {
+            kind: InteractionResponseType::ChannelMessageWithSource,
+            data: Some(embed::features::features(ctx.lang, config.features)),
+        })
+    }
+}
@@ -1,31 +1,49 @@
 //! Help command.
 //!
-//! This command shows basic information and link about how to use the bot.
+//! Without argument, this command shows basic information and links about how
+//! to use the bot, with the categories of commands the member can use. A
+//! select menu lets the member browse the commands of each category.
+//!
+//! With the `command` option, the details of a command are shown: options,
+//! usage examples, required permissions and related settings.
+//!
+//! The help is generated from the commands registry (see [`COMMANDS`]), and
+//! only lists the commands the member is allowed to use (see
+//! [`CommandMeta::is_available`]).
+//!
+//! [`COMMANDS`]: super::COMMANDS
 
 use twilight_interactions::command::{CommandModel, CreateCommand};
 use twilight_model::{
-    application::component::{button::ButtonStyle, ActionRow, Button, Component},
+    application::component::{
+        button::ButtonStyle, select_menu::SelectMenuOption, ActionRow, Button, Component,
+        SelectMenu,
+    },
     channel::message::MessageFlags,
+    guild::Permissions,
     http::interaction::InteractionResponseType,
 };
-use twilight_util::builder::{embed::EmbedBuilder, InteractionResponseDataBuilder};
+use twilight_util::builder::InteractionResponseDataBuilder;
 
+use super::{available_commands, find_command, CommandCategory, CommandMeta};
 use crate::{
     cluster::ClusterState,
     desc_localizations, impl_command_handle,
-    interaction::{
-        embed::COLOR_TRANSPARENT, response::InteractionResponse, util::InteractionContext,
-    },
+    interaction::{embed, response::InteractionResponse, util::InteractionContext},
+    translations::Lang,
 };
 
 #[derive(Debug, Clone, CommandModel, CreateCommand)]
 #[command(
     name = "help",
     desc = "Need help to use RaidProtect?",
-    desc_localizations = "help_description",
-    dm_permission = true
+    desc_localizations = "help_description"
 )]
-pub struct HelpCommand;
+pub struct HelpCommand {
+    /// Show the details of a command.
+    #[command(autocomplete = true, max_length = 32)]
+    pub command: Option<String>,
+}
 
 impl_command_handle!(HelpCommand);
 desc_localizations!(help_description);
@@ -34,39 +52,28 @@ impl HelpCommand {
     async fn exec(
         self,
         ctx: InteractionContext,
-        _state: &ClusterState,
+        state: &ClusterState,
     ) -> Result<InteractionResponse, anyhow::Error> {
-        // Create embed
-        let embed = EmbedBuilder::new()
-            .color(COLOR_TRANSPARENT)
-            .title(ctx.lang.help_embed_title())
-            .description(ctx.lang.help_embed_description());
-
-        // Add components (buttons)
-        let components = Component::ActionRow(ActionRow {
-            components: vec![
-                Component::Button(Button {
-                    custom_id: None,
-                    disabled: false,
-                    emoji: None,
-                    label: Some(ctx.lang.help_support().into()),
-                    style: ButtonStyle::Link,
-                    url: Some("https://raidpro.tk/discord".to_owned()),
-                }),
-                Component::Button(Button {
-                    custom_id: None,
-                    disabled: false,
-                    emoji: None,
-                    label: Some(ctx.lang.help_bot_invite().into()),
-                    style: ButtonStyle::Link,
-                    url: Some("https://raidpro.tk/invite".to_owned()),
-                }),
-            ],
-        });
+        let permissions = ctx.interaction.member.as_ref().and_then(|m| m.permissions);
+        let is_operator = state.is_operator(ctx.author.id);
+
+        if let Some(name) = self.command {
+            let name = name.trim().trim_start_matches('/');
+
+            return match find_command(name)
+                .filter(|command| command.is_available(permissions, is_operator))
+            {
+                Some(command) => Ok(InteractionResponse::EphemeralEmbed(embed::help::command(
+                    ctx.lang, command,
+                ))),
+                None => Ok(embed::help::command_not_found(ctx.lang, name)),
+            };
+        }
 
+        let categories = categories(permissions, is_operator);
         let response = InteractionResponseDataBuilder::new()
-            .embeds([embed.build()])
-            .components([components])
+            .embeds([embed::help::overview(ctx.lang, &categories)])
+            .components(components(ctx.lang, &categories, None))
             .flags(MessageFlags::EPHEMERAL)
             .build();
 
@@ -77,3 +84,121 @@ impl HelpCommand {
         })
     }
 }
+
+/// Categories with at least one command available to the member.
+pub fn categories(permissions: Option<Permissions>, is_operator: bool) -> Vec<CommandCategory> {
+    CommandCategory::ALL
+        .into_iter()
+        .filter(|category| {
+            available_commands(permissions, is_operator).any(|c| c.category == *category)
+        })
+        .collect()
+}
+
+/// Commands of a category available to the member.
+pub fn category_commands(
+    category: CommandCategory,
+    permissions: Option<Permissions>,
+    is_operator: bool,
+) -> Vec<&'static CommandMeta> {
+    available_commands(permissions, is_operator)
+        .filter(|command| command.category == category)
+        .collect()
+}
+
+/// Components of the help message.
+///
+/// The select menu is used to browse the categories (see [`HelpCategory`]),
+/// the buttons are links to the support server and the bot invite.
+///
+/// [`HelpCategory`]: crate::interaction::component::HelpCategory
+pub fn components(
+    lang: Lang,
+    categories: &[CommandCategory],
+    selected: Option<CommandCategory>,
+) -> Vec<Component> {
+    let mut components = Vec::new();
+
+    if !categories.is_empty() {
+        let options = categories
+            .iter()
+            .map(|category| SelectMenuOption {
+                default: Some(*category) == selected,
+                description: None,
+                emoji: None,
+                label: embed::help::category_name(lang, *category).to_owned(),
+                value: category.id().to_owned(),
+            })
+            .collect();
+
+        components.push(Component::ActionRow(ActionRow {
+            components: vec![Component::SelectMenu(SelectMenu {
+                custom_id: "help-category".to_owned(),
+                disabled: false,
+                max_values: Some(1),
+                min_values: Some(1),
+                options,
+                placeholder: Some(lang.help_select_placeholder().to_owned()),
+            })],
+        }));
+    }
+
+    components.push(Component::ActionRow(ActionRow {
+        components: vec![
+            Component::Button(Button {
+                custom_id: None,
+                disabled: false,
+                emoji: None,
+                label: Some(lang.help_support().into()),
+                style: ButtonStyle::Link,
+                url: Some("https://raidpro.tk/discord".to_owned()),
+            }),
+            Component::Button(Button {
+                custom_id: None,
+                disabled: false,
+                emoji: None,
+                label: Some(lang.help_bot_invite().into()),
+                style: ButtonStyle::Link,
+                url: Some("https://raidpro.tk/invite".to_owned()),
+            }),
+        ],
+    }));
+
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_categories() {
+        assert_eq!(
+            categories(Some(Permissions::empty()), false),
+            vec![CommandCategory::General]
+        );
+        assert!(!categories(Some(Permissions::ADMINISTRATOR), false)
+            .contains(&CommandCategory::Operator));
+        assert!(categories(None, true).contains(&CommandCategory::Operator));
+    }
+
+    #[test]
+    fn test_category_commands() {
+        let commands = category_commands(
+            CommandCategory::Moderation,
+            Some(Permissions::BAN_MEMBERS),
+            false,
+        );
+
+        assert!(commands.iter().any(|command| command.name == "ban"));
+        assert!(!commands.iter().any(|command| command.name == "kick"));
+    }
+
+    #[test]
+    fn test_components() {
+        let categories = [CommandCategory::General, CommandCategory::Moderation];
+
+        assert_eq!(components(Lang::DEFAULT, &categories, None).len(), 2);
+        assert_eq!(components(Lang::DEFAULT, &[], None).len(), 1);
+    }
+}
@@ -0,0 +1,305 @@
+//! Link command.
+//!
+//! This command links affiliated guilds to synchronize their bans (see the
+//! [`ban_sync`] feature module). A single-use code is generated with
+//! `/link create` in a guild, and used with `/link join` in the other guild
+//! before it expires.
+//!
+//! Whether bans are sent to or received from the linked guilds is configured
+//! with `/link mode`. Removing a link stops future synchronizations, but
+//! synchronized bans are never reverted.
+//!
+//! [`ban_sync`]: crate::feature::ban_sync
+
+use raidprotect_model::{
+    cache::{discord::CachedGuild, model::link::PendingGuildLink},
+    database::model::{BanSync, GuildLink},
+};
+use time::OffsetDateTime;
+use twilight_interactions::command::{CommandModel, CommandOption, CreateCommand, CreateOption};
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations,
+    feature::ban_sync,
+    impl_guild_command_handle,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+};
+
+/// Link command model.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "link",
+    desc = "Link servers to synchronize their bans",
+    desc_localizations = "link_description"
+)]
+pub enum LinkCommand {
+    #[command(name = "create")]
+    Create(LinkCreateCommand),
+    #[command(name = "join")]
+    Join(LinkJoinCommand),
+    #[command(name = "list")]
+    List(LinkListCommand),
+    #[command(name = "remove")]
+    Remove(LinkRemoveCommand),
+    #[command(name = "mode")]
+    Mode(LinkModeCommand),
+}
+
+impl_guild_command_handle!(LinkCommand);
+desc_localizations!(link_description);
+
+impl LinkCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        match self {
+            Self::Create(command) => command.exec(ctx, state).await,
+            Self::Join(command) => command.exec(ctx, state).await,
+            Self::List(command) => command.exec(ctx, state).await,
+            Self::Remove(command) => command.exec(ctx, state).await,
+            Self::Mode(command) => command.exec(ctx, state).await,
+        }
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "create",
+    desc = "Create a code to link another server",
+    desc_localizations = "link_create_description"
+)]
+pub struct LinkCreateCommand;
+
+desc_localizations!(link_create_description);
+
+impl LinkCreateCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        if state.database.count_guild_links(ctx.guild_id).await? >= GuildLink::MAX_LINKS {
+            return Ok(embed::link::too_many(ctx.lang));
+        }
+
+        let pending = PendingGuildLink {
+            code: ban_sync::generate_code(),
+            guild_id: ctx.guild_id,
+            author_id: ctx.author.id,
+        };
+        state.cache.set(&pending).await?;
+
+        Ok(embed::link::created(ctx.lang, &pending.code))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "join",
+    desc = "Link this server with a code created in another server",
+    desc_localizations = "link_join_description"
+)]
+pub struct LinkJoinCommand {
+    /// Code created with /link create.
+    #[command(max_length = 20)]
+    pub code: String,
+}
+
+desc_localizations!(link_join_description);
+
+impl LinkJoinCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let pending = match ban_sync::normalize_code(&self.code) {
+            Some(code) => state.cache.get::<PendingGuildLink>(&code).await?,
+            None => None,
+        };
+
+        let pending = match pending {
+            Some(pending) => pending,
+            None => return Ok(embed::link::invalid_code(ctx.lang)),
+        };
+
+        if pending.guild_id == ctx.guild_id {
+            return Ok(embed::link::same_guild(ctx.lang));
+        }
+
+        if state
+            .database
+            .is_guild_linked(ctx.guild_id, pending.guild_id)
+            .await?
+        {
+            return Ok(embed::link::already_linked(ctx.lang));
+        }
+
+        for guild_id in [ctx.guild_id, pending.guild_id] {
+            if state.database.count_guild_links(guild_id).await? >= GuildLink::MAX_LINKS {
+                return Ok(embed::link::too_many(ctx.lang));
+            }
+        }
+
+        // The code may have been used concurrently in another guild.
+        if !state.cache.consume(&pending).await? {
+            return Ok(embed::link::invalid_code(ctx.lang));
+        }
+
+        let link = GuildLink {
+            id: None,
+            source: pending.guild_id,
+            target: ctx.guild_id,
+            created_by: ctx.author.id,
+            date: OffsetDateTime::now_utc(),
+        };
+        state.database.create_guild_link(&link).await?;
+
+        let name = guild_name(state, pending.guild_id).await?;
+
+        Ok(embed::link::joined(ctx.lang, &name))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "list",
+    desc = "List the linked servers",
+    desc_localizations = "link_list_description"
+)]
+pub struct LinkListCommand;
+
+desc_localizations!(link_list_description);
+
+impl LinkListCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let config = ctx.config(state).await?;
+        let links = state.database.find_guild_links(ctx.guild_id).await?;
+
+        let mut guilds = Vec::with_capacity(links.len());
+        for link in links {
+            let guild_id = link.other(ctx.guild_id);
+            guilds.push((guild_id, guild_name(state, guild_id).await?));
+        }
+
+        Ok(embed::link::list(ctx.lang, config.ban_sync, &guilds))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "remove",
+    desc = "Remove the link with a server",
+    desc_localizations = "link_remove_description"
+)]
+pub struct LinkRemoveCommand {
+    /// Id of the linked server (see /link list).
+    pub server: String,
+}
+
+desc_localizations!(link_remove_description);
+
+impl LinkRemoveCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let guild_id = self
+            .server
+            .trim()
+            .parse()
+            .ok()
+            .and_then(Id::<GuildMarker>::new_checked);
+
+        // Synchronized bans are kept, only future bans are no longer synchronized.
+        let removed = match guild_id {
+            Some(guild_id) => {
+                state
+                    .database
+                    .delete_guild_link(ctx.guild_id, guild_id)
+                    .await?
+            }
+            None => false,
+        };
+
+        match removed {
+            true => Ok(embed::link::removed(ctx.lang)),
+            false => Ok(embed::link::not_linked(ctx.lang)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "mode",
+    desc = "Configure the synchronization of the bans",
+    desc_localizations = "link_mode_description"
+)]
+pub struct LinkModeCommand {
+    /// Whether bans are sent to or received from the linked servers.
+    pub mode: BanSyncOption,
+}
+
+desc_localizations!(link_mode_description);
+
+/// Synchronization of the bans with the linked guilds.
+#[derive(Debug, Clone, Copy, CommandOption, CreateOption)]
+pub enum BanSyncOption {
+    #[option(name = "Send and receive", value = "both")]
+    Both,
+    #[option(name = "Send only", value = "send")]
+    Send,
+    #[option(name = "Receive only", value = "receive")]
+    Receive,
+    #[option(name = "Off", value = "off")]
+    Off,
+}
+
+impl From<BanSyncOption> for BanSync {
+    fn from(mode: BanSyncOption) -> Self {
+        match mode {
+            BanSyncOption::Both => BanSync::Both,
+            BanSyncOption::Send => BanSync::Send,
+            BanSyncOption::Receive => BanSync::Receive,
+            BanSyncOption::Off => BanSync::Off,
+        }
+    }
+}
+
+impl LinkModeCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        config.ban_sync = self.mode.into();
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::link::mode_updated(ctx.lang, config.ban_sync))
+    }
+}
+
+/// Get the name of a guild, or its id if it is not cached.
+async fn guild_name(
+    state: &ClusterState,
+    guild_id: Id<GuildMarker>,
+) -> Result<String, anyhow::Error> {
+    let name = state
+        .cache
+        .get::<CachedGuild>(&guild_id)
+        .await?
+        .map_or_else(|| guild_id.to_string(), |guild| guild.name);
+
+    Ok(name)
+}
@@ -0,0 +1,187 @@
+//! Lockdown command.
+//!
+//! This command allows moderators to lock every channel of the guild during a
+//! raid, and to restore them once the raid is over. Engaging a lockdown in a
+//! large guild takes a while: the progress is shown in the response, with a
+//! button to cancel the lockdown (see the [`lockdown`] module).
+
+use raidprotect_model::cache::model::lockdown::LockdownState;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::{
+    application::component::{button::ButtonStyle, ActionRow, Button, Component},
+    channel::message::MessageFlags,
+    guild::Permissions,
+    http::interaction::InteractionResponseType,
+};
+use twilight_util::builder::InteractionResponseDataBuilder;
+
+use crate::{
+    cluster::ClusterState,
+    desc_localizations,
+    feature::lockdown,
+    impl_guild_command_handle,
+    interaction::{
+        embed,
+        response::InteractionResponse,
+        util::{CustomId, GuildInteractionContext},
+    },
+};
+
+/// Lockdown command model.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "lockdown",
+    desc = "Lock or unlock every channel of the server",
+    desc_localizations = "lockdown_description"
+)]
+pub enum LockdownCommand {
+    #[command(name = "engage")]
+    Engage(LockdownEngageCommand),
+    #[command(name = "lift")]
+    Lift(LockdownLiftCommand),
+}
+
+impl_guild_command_handle!(LockdownCommand);
+desc_localizations!(lockdown_description);
+
+impl LockdownCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        match self {
+            Self::Engage(command) => command.exec(ctx, state).await,
+            Self::Lift(command) => command.exec(ctx, state).await,
+        }
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "engage",
+    desc = "Prevent members from sending messages in every channel",
+    desc_localizations = "lockdown_engage_description"
+)]
+pub struct LockdownEngageCommand;
+
+desc_localizations!(lockdown_engage_description);
+
+impl LockdownEngageCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let guild_id = ctx.guild_id;
+
+        // The lock is held until the end of the engagement, so that the
+        // lockdown cannot be engaged twice.
+        let lock = match lockdown::lock(state, guild_id).await? {
+            Some(lock) => lock,
+            None => {
+                let embed = embed::lockdown::already_engaged(ctx.lang);
+                return Ok(InteractionResponse::EphemeralEmbed(embed));
+            }
+        };
+
+        if state.cache.get::<LockdownState>(&guild_id).await?.is_some() {
+            let embed = embed::lockdown::already_engaged(ctx.lang);
+            return Ok(InteractionResponse::EphemeralEmbed(embed));
+        }
+
+        let permissions = state
+            .cache
+            .permissions(guild_id)
+            .await?
+            .current_member()
+            .await?
+            .guild();
+
+        if !permissions.contains(Permissions::MANAGE_ROLES | Permissions::MANAGE_CHANNELS) {
+            return Ok(embed::error::bot_missing_permissions(ctx.lang));
+        }
+
+        let targets = lockdown::targets(state, guild_id).await?;
+        if targets.is_empty() {
+            let embed = embed::lockdown::no_channels(ctx.lang);
+            return Ok(InteractionResponse::EphemeralEmbed(embed));
+        }
+
+        // The state is saved before the engagement starts so that a completed
+        // lockdown is not engaged again.
+        let interaction_id = ctx.interaction.id;
+        let pending = LockdownState {
+            guild_id,
+            interaction_id,
+            author_id: ctx.author.id,
+            completed: false,
+            channels: Vec::new(),
+        };
+        state.cache.set(&pending).await?;
+
+        let total = targets.len();
+        let custom_id = CustomId::new("lockdown-cancel", interaction_id.to_string());
+        let cancel = state.bulk_tasks.register(interaction_id);
+
+        tokio::spawn(lockdown::run(
+            state.clone(),
+            lock,
+            pending,
+            targets,
+            ctx.responder(),
+            cancel,
+            ctx.lang,
+        ));
+
+        let components = Component::ActionRow(ActionRow {
+            components: vec![Component::Button(Button {
+                custom_id: Some(custom_id.to_string()),
+                disabled: false,
+                emoji: None,
+                label: Some(ctx.lang.lockdown_cancel_button().to_owned()),
+                style: ButtonStyle::Secondary,
+                url: None,
+            })],
+        });
+
+        let response = InteractionResponseDataBuilder::new()
+            .embeds([embed::lockdown::progress(ctx.lang, 0, total)])
+            .components([components])
+            .flags(MessageFlags::EPHEMERAL)
+            .build();
+
+        Ok(InteractionResponse::Raw {
+            kind: InteractionResponseType::ChannelMessageWithSource,
+            data: Some(response),
+        })
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "lift",
+    desc = "Restore the channels locked by the lockdown",
+    desc_localizations = "lockdown_lift_description"
+)]
+pub struct LockdownLiftCommand;
+
+desc_localizations!(lockdown_lift_description);
+
+impl LockdownLiftCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        // Channels are restored in the background since it may take a while.
+        tokio::spawn(lockdown::lift(
+            state.clone(),
+            ctx.guild_id,
+            ctx.responder(),
+            ctx.lang,
+        ));
+
+        Ok(InteractionResponse::EphemeralDeferredMessage)
+    }
+}
@@ -1,8 +1,892 @@
 //! Slash commands implementation.
 //!
 //! This module contains implementations of the bot slash commands.
+//!
+//! The commands are listed in [`COMMANDS`] with the permissions required to
+//! use them. These permissions are sent to Discord when registering the
+//! commands to hide them from members that cannot use them, and are checked
+//! again when a command is received since server administrators can override
+//! the commands visibility (see [`check_command`]). Handlers therefore don't
+//! need to check the permissions of the member themselves.
+//!
+//! Context menu commands, such as the message [`report`] command, are listed
+//! with the slash commands and only differ by their [`CommandType`].
+//!
+//! The [`help`] command is generated from the same list: each command has a
+//! [`CommandCategory`], usage examples and related settings, and its options
+//! are read from the registered command data.
 
+pub mod admin;
+pub mod channel_rule;
 pub mod config;
+pub mod features;
 pub mod help;
+pub mod link;
+pub mod lockdown;
 pub mod moderation;
+pub mod modlog;
+pub mod preset;
 pub mod profile;
+pub mod raid_mode;
+pub mod report;
+pub mod restore;
+pub mod simulate;
+pub mod stats;
+pub mod status;
+pub mod tag;
+pub mod user_info;
+pub mod watch;
+
+use raidprotect_model::cache::model::maintenance::Maintenance;
+use twilight_interactions::command::{ApplicationCommandData, CreateCommand};
+use twilight_model::{
+    application::{
+        command::{Command, CommandType},
+        interaction::Interaction,
+    },
+    guild::Permissions,
+};
+
+use self::{
+    admin::AdminCommand,
+    channel_rule::ChannelRuleCommand,
+    config::ConfigCommand,
+    features::FeaturesCommand,
+    help::HelpCommand,
+    link::LinkCommand,
+    lockdown::LockdownCommand,
+    moderation::{
+        BanCommand, BulkBanCommand, BulkUnbanCommand, CleanupCommand, KickCommand, MuteCommand,
+        PostCommand, QuarantineCommand, ReleaseCommand, RestoreMessageCommand, UnmuteCommand,
+    },
+    modlog::ModlogCommand,
+    preset::PresetCommand,
+    profile::ProfileCommand,
+    raid_mode::RaidModeCommand,
+    report::ReportCommand,
+    restore::RestoreCommand,
+    simulate::SimulateCommand,
+    stats::StatsCommand,
+    status::StatusCommand,
+    tag::TagCommand,
+    user_info::UserInfoCommand,
+    watch::WatchCommand,
+};
+use super::{embed, response::InteractionResponse, util::InteractionExt};
+use crate::cluster::HandlerState;
+
+/// Commands that remain available when the maintenance mode is enabled.
+const MAINTENANCE_COMMANDS: &[&str] = &["admin", "help", "status"];
+
+/// Commands registered by the bot.
+pub const COMMANDS: &[CommandMeta] = &[
+    CommandMeta {
+        name: "admin",
+        category: CommandCategory::Operator,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::ADMINISTRATOR),
+        dm_permission: true,
+        examples: &[],
+        settings: &[],
+        create: AdminCommand::create_command,
+    },
+    CommandMeta {
+        name: "ban",
+        category: CommandCategory::Moderation,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::BAN_MEMBERS),
+        dm_permission: false,
+        examples: &[
+            "/ban user:@user reason:Spam",
+            "/ban user:123456789012345678 shadow:True",
+            "/ban user:@user reason:Scam sync:True",
+        ],
+        settings: &[
+            "/config reasons",
+            "/config sanction-roles",
+            "/config sanctions",
+            "/link mode",
+        ],
+        create: BanCommand::create_command,
+    },
+    CommandMeta {
+        name: "bulk-ban",
+        category: CommandCategory::Moderation,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::BAN_MEMBERS),
+        dm_permission: false,
+        examples: &[],
+        settings: &[],
+        create: BulkBanCommand::create_command,
+    },
+    CommandMeta {
+        name: "bulk-unban",
+        category: CommandCategory::Moderation,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::BAN_MEMBERS),
+        dm_permission: false,
+        examples: &[
+            "/bulk-unban hours:24",
+            "/bulk-unban hours:24 manual:True",
+            "/bulk-unban hours:2 moderator:@moderator",
+        ],
+        settings: &["/config sanction-roles"],
+        create: BulkUnbanCommand::create_command,
+    },
+    CommandMeta {
+        name: "channel-rule",
+        category: CommandCategory::Protection,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::MANAGE_GUILD),
+        dm_permission: false,
+        examples: &[],
+        settings: &[],
+        create: ChannelRuleCommand::create_command,
+    },
+    CommandMeta {
+        name: "cleanup",
+        category: CommandCategory::Moderation,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::MANAGE_MESSAGES),
+        dm_permission: false,
+        examples: &[],
+        settings: &[],
+        create: CleanupCommand::create_command,
+    },
+    CommandMeta {
+        name: "config",
+        category: CommandCategory::Configuration,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::MANAGE_GUILD),
+        dm_permission: false,
+        examples: &[],
+        settings: &[],
+        create: ConfigCommand::create_command,
+    },
+    CommandMeta {
+        name: "features",
+        category: CommandCategory::Configuration,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::MANAGE_GUILD),
+        dm_permission: false,
+        examples: &[],
+        settings: &["/config check"],
+        create: FeaturesCommand::create_command,
+    },
+    CommandMeta {
+        name: "help",
+        category: CommandCategory::General,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Everyone,
+        dm_permission: true,
+        examples: &["/help command:ban"],
+        settings: &[],
+        create: HelpCommand::create_command,
+    },
+    CommandMeta {
+        name: "kick",
+        category: CommandCategory::Moderation,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::KICK_MEMBERS),
+        dm_permission: false,
+        examples: &["/kick member:@user reason:Spam cleanup:Last hour"],
+        settings: &[
+            "/config reasons",
+            "/config sanction-roles",
+            "/config sanctions",
+        ],
+        create: KickCommand::create_command,
+    },
+    CommandMeta {
+        name: "link",
+        category: CommandCategory::Configuration,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::ADMINISTRATOR),
+        dm_permission: false,
+        examples: &[
+            "/link join code:ABCDEFGH23",
+            "/link mode mode:Send and receive",
+        ],
+        settings: &[],
+        create: LinkCommand::create_command,
+    },
+    CommandMeta {
+        name: "lockdown",
+        category: CommandCategory::Protection,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::MANAGE_GUILD),
+        dm_permission: false,
+        examples: &["/lockdown engage", "/lockdown lift"],
+        settings: &[],
+        create: LockdownCommand::create_command,
+    },
+    CommandMeta {
+        name: "modlog",
+        category: CommandCategory::Moderation,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::MODERATE_MEMBERS),
+        dm_permission: false,
+        examples: &[
+            "/modlog search contains:scam",
+            "/modlog search type:Ban moderator:@Moderator since:2022-01-24",
+        ],
+        settings: &[],
+        create: ModlogCommand::create_command,
+    },
+    CommandMeta {
+        name: "mute",
+        category: CommandCategory::Moderation,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::MODERATE_MEMBERS),
+        dm_permission: false,
+        examples: &["/mute member:@user duration:60 reason:Spam"],
+        settings: &[
+            "/config reasons",
+            "/config sanction-roles",
+            "/config sanctions",
+        ],
+        create: MuteCommand::create_command,
+    },
+    CommandMeta {
+        name: "post",
+        category: CommandCategory::Moderation,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::MANAGE_THREADS),
+        dm_permission: false,
+        examples: &[],
+        settings: &[],
+        create: PostCommand::create_command,
+    },
+    CommandMeta {
+        name: "preset",
+        category: CommandCategory::Configuration,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::MANAGE_GUILD),
+        dm_permission: false,
+        examples: &[],
+        settings: &[],
+        create: PresetCommand::create_command,
+    },
+    CommandMeta {
+        name: "profile",
+        category: CommandCategory::General,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Everyone,
+        dm_permission: true,
+        examples: &[],
+        settings: &[],
+        create: ProfileCommand::create_command,
+    },
+    CommandMeta {
+        name: "quarantine",
+        category: CommandCategory::Moderation,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::MANAGE_ROLES),
+        dm_permission: false,
+        examples: &["/quarantine member:@user reason:Compromised account"],
+        settings: &[
+            "/config reasons",
+            "/config sanction-roles",
+            "/config sanctions",
+            "/config quarantine-role",
+        ],
+        create: QuarantineCommand::create_command,
+    },
+    CommandMeta {
+        name: "raidmode",
+        category: CommandCategory::Protection,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::MANAGE_GUILD),
+        dm_permission: false,
+        examples: &[],
+        settings: &["/config raid-mode", "/config raid-alert"],
+        create: RaidModeCommand::create_command,
+    },
+    CommandMeta {
+        name: "release",
+        category: CommandCategory::Moderation,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::MANAGE_ROLES),
+        dm_permission: false,
+        examples: &["/release member:@user"],
+        settings: &[
+            "/config reasons",
+            "/config sanction-roles",
+            "/config sanctions",
+            "/config quarantine-role",
+        ],
+        create: ReleaseCommand::create_command,
+    },
+    CommandMeta {
+        name: ReportCommand::NAME,
+        category: CommandCategory::General,
+        kind: CommandType::Message,
+        permissions: MemberPermissions::Everyone,
+        dm_permission: false,
+        examples: &[],
+        settings: &["/config logs reports"],
+        create: ReportCommand::create_command,
+    },
+    CommandMeta {
+        name: "restore",
+        category: CommandCategory::Protection,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::ADMINISTRATOR),
+        dm_permission: false,
+        examples: &[],
+        settings: &[],
+        create: RestoreCommand::create_command,
+    },
+    CommandMeta {
+        name: "restore-message",
+        category: CommandCategory::Moderation,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::MANAGE_MESSAGES),
+        dm_permission: false,
+        examples: &[],
+        settings: &[],
+        create: RestoreMessageCommand::create_command,
+    },
+    CommandMeta {
+        name: "simulate",
+        category: CommandCategory::Protection,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::MANAGE_GUILD),
+        dm_permission: false,
+        examples: &[],
+        settings: &[],
+        create: SimulateCommand::create_command,
+    },
+    CommandMeta {
+        name: "stats",
+        category: CommandCategory::Moderation,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::MANAGE_GUILD),
+        dm_permission: false,
+        examples: &[],
+        settings: &["/config filters feedback"],
+        create: StatsCommand::create_command,
+    },
+    CommandMeta {
+        name: "status",
+        category: CommandCategory::General,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Everyone,
+        dm_permission: true,
+        examples: &[],
+        settings: &[],
+        create: StatusCommand::create_command,
+    },
+    CommandMeta {
+        name: "tag",
+        category: CommandCategory::General,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Everyone,
+        dm_permission: false,
+        examples: &[
+            "/tag send name:rules member:@user",
+            "/tag set name:rules content:Please read the rules, {user}.",
+        ],
+        settings: &["/tag roles"],
+        create: TagCommand::create_command,
+    },
+    CommandMeta {
+        name: "unmute",
+        category: CommandCategory::Moderation,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::MODERATE_MEMBERS),
+        dm_permission: false,
+        examples: &["/unmute member:@user"],
+        settings: &[
+            "/config reasons",
+            "/config sanction-roles",
+            "/config sanctions",
+        ],
+        create: UnmuteCommand::create_command,
+    },
+    CommandMeta {
+        name: "userinfo",
+        category: CommandCategory::Moderation,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::MODERATE_MEMBERS),
+        dm_permission: false,
+        examples: &["/userinfo member:@user"],
+        settings: &[],
+        create: UserInfoCommand::create_command,
+    },
+    CommandMeta {
+        name: "watch",
+        category: CommandCategory::Moderation,
+        kind: CommandType::ChatInput,
+        permissions: MemberPermissions::Required(Permissions::MODERATE_MEMBERS),
+        dm_permission: false,
+        examples: &["/watch add member:@user"],
+        settings: &["/config logs watch"],
+        create: WatchCommand::create_command,
+    },
+];
+
+/// Check whether a member can use a command.
+///
+/// The permissions required by the command (see [`COMMANDS`]) are checked
+/// centrally before running the command, so handlers don't need to check them
+/// again. The missing permissions are returned if the member cannot use the
+/// command.
+///
+/// Commands received in private messages have no member permissions and are
+/// always allowed, as well as unknown commands. Operator commands are not
+/// gated by the guild permissions since they are reserved to the bot
+/// operators, which are checked before (see [`CommandCategory::Operator`]).
+pub fn check_permissions(name: &str, permissions: Option<Permissions>) -> Result<(), Permissions> {
+    let (command, permissions) = match (find_command(name), permissions) {
+        (Some(command), Some(_)) if command.category == CommandCategory::Operator => return Ok(()),
+        (Some(command), Some(permissions)) => (command, permissions),
+        _ => return Ok(()),
+    };
+
+    let missing = command.missing_permissions(permissions);
+
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(missing)
+    }
+}
+
+/// Check whether the author of a command interaction can run it.
+///
+/// The operator commands, the maintenance mode and the permissions of the
+/// member (see [`check_permissions`]) are checked before running a command.
+/// Returns the error response if the command cannot be run.
+pub async fn check_command(
+    interaction: &Interaction,
+    name: &str,
+    state: &impl HandlerState,
+) -> Result<Option<InteractionResponse>, anyhow::Error> {
+    // Operators are checked before any other permission, they may not have the
+    // guild permissions the command is registered with.
+    let is_operator_command = find_command(name).map_or(false, |command| {
+        command.category == CommandCategory::Operator
+    });
+
+    if is_operator_command {
+        let is_operator = interaction
+            .author_id()
+            .map_or(false, |id| state.is_operator(id));
+
+        if !is_operator {
+            return Ok(Some(embed::error::not_operator(interaction.lang()?)));
+        }
+    }
+
+    // The maintenance mode is shared by all the instances of the bot.
+    if !MAINTENANCE_COMMANDS.contains(&name) && Maintenance::is_enabled(state.cache_store()).await?
+    {
+        return Ok(Some(embed::error::maintenance(interaction.lang()?)));
+    }
+
+    // Commands visibility can be overridden by server administrators, so the
+    // permissions are checked again.
+    let member_permissions = interaction.member.as_ref().and_then(|m| m.permissions);
+
+    // Discord may omit the member during outages, its permissions cannot be
+    // checked in this case.
+    if !is_operator_command && interaction.guild_id.is_some() && member_permissions.is_none() {
+        let restricted = find_command(name).map_or(false, |command| {
+            command.permissions != MemberPermissions::Everyone
+        });
+
+        if restricted {
+            return Ok(Some(embed::error::discord_unavailable(interaction.lang()?)));
+        }
+    }
+
+    if let Err(missing) = check_permissions(name, member_permissions) {
+        let lang = interaction.lang()?;
+
+        return Ok(Some(embed::error::missing_command_permissions(
+            lang, missing,
+        )));
+    }
+
+    Ok(None)
+}
+
+/// Get the commands a member can use.
+///
+/// See [`CommandMeta::is_available`].
+pub fn available_commands(
+    permissions: Option<Permissions>,
+    is_operator: bool,
+) -> impl Iterator<Item = &'static CommandMeta> {
+    COMMANDS
+        .iter()
+        .filter(move |command| command.is_available(permissions, is_operator))
+}
+
+/// Get the [`CommandMeta`] of a command from its name.
+pub fn find_command(name: &str) -> Option<&'static CommandMeta> {
+    COMMANDS.iter().find(|command| command.name == name)
+}
+
+/// Metadata of a registered command.
+#[derive(Debug, Clone, Copy)]
+pub struct CommandMeta {
+    /// Name of the command.
+    pub name: &'static str,
+    /// Category of the command, used by the help command.
+    pub category: CommandCategory,
+    /// Type of the command.
+    ///
+    /// Context menu commands have no description and no options.
+    pub kind: CommandType,
+    /// Permissions required to use the command.
+    pub permissions: MemberPermissions,
+    /// Whether the command is available in private messages.
+    pub dm_permission: bool,
+    /// Usage examples shown by the help command.
+    pub examples: &'static [&'static str],
+    /// Commands used to configure the command, shown by the help command.
+    pub settings: &'static [&'static str],
+    /// Function that creates the command data.
+    create: fn() -> ApplicationCommandData,
+}
+
+/// Permissions required to use a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemberPermissions {
+    /// The command is available to all members.
+    Everyone,
+    /// The command requires the given permissions.
+    Required(Permissions),
+}
+
+/// Category of a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandCategory {
+    /// Commands available to all members.
+    General,
+    /// Moderation and sanction commands.
+    Moderation,
+    /// Raid and server protection commands.
+    Protection,
+    /// Server configuration commands.
+    Configuration,
+    /// Commands reserved to the bot operators.
+    ///
+    /// Operators are checked before any other permission, and the guild
+    /// permissions of the command are not required to use it.
+    Operator,
+}
+
+impl CommandCategory {
+    /// All the categories, in the order they are shown.
+    pub const ALL: [CommandCategory; 5] = [
+        CommandCategory::General,
+        CommandCategory::Moderation,
+        CommandCategory::Protection,
+        CommandCategory::Configuration,
+        CommandCategory::Operator,
+    ];
+
+    /// Identifier of the category, used in components.
+    pub fn id(self) -> &'static str {
+        match self {
+            CommandCategory::General => "general",
+            CommandCategory::Moderation => "moderation",
+            CommandCategory::Protection => "protection",
+            CommandCategory::Configuration => "configuration",
+            CommandCategory::Operator => "operator",
+        }
+    }
+
+    /// Get a category from its identifier.
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|category| category.id() == id)
+    }
+}
+
+impl CommandMeta {
+    /// Create the [`Command`] registered to Discord.
+    pub fn command(&self) -> Command {
+        let mut command: Command = (self.create)().into();
+
+        command.default_member_permissions = match self.permissions {
+            MemberPermissions::Everyone => None,
+            MemberPermissions::Required(permissions) => Some(permissions),
+        };
+        command.dm_permission = Some(self.dm_permission);
+        command.kind = self.kind;
+
+        command
+    }
+
+    /// Whether a member can use the command.
+    ///
+    /// The permissions are checked with [`check_permissions`], as when the
+    /// command is received. Commands used in private messages (without member
+    /// permissions) must be available in private messages, and the operator
+    /// commands are only available to the bot operators.
+    pub fn is_available(&self, permissions: Option<Permissions>, is_operator: bool) -> bool {
+        if self.category == CommandCategory::Operator && !is_operator {
+            return false;
+        }
+
+        match permissions {
+            Some(_) => check_permissions(self.name, permissions).is_ok(),
+            None => self.dm_permission,
+        }
+    }
+
+    /// Get the permissions a member is missing to use the command.
+    ///
+    /// Members with the [`ADMINISTRATOR`] permission can use all commands.
+    ///
+    /// [`ADMINISTRATOR`]: Permissions::ADMINISTRATOR
+    pub fn missing_permissions(&self, permissions: Permissions) -> Permissions {
+        match self.permissions {
+            MemberPermissions::Everyone => Permissions::empty(),
+            MemberPermissions::Required(_) if permissions.contains(Permissions::ADMINISTRATOR) => {
+                Permissions::empty()
+            }
+            MemberPermissions::Required(required) => required - permissions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use twilight_model::application::command::CommandOption;
+
+    use super::*;
+    use crate::{
+        test_utils::{
+            fixture::{guild_command, member_with_perms, INVOKER_ID},
+            MockClusterState,
+        },
+        translations::Lang,
+    };
+
+    #[test]
+    fn test_commands_permissions() {
+        let mut names = HashSet::new();
+
+        for meta in COMMANDS {
+            let command = meta.command();
+
+            assert_eq!(command.name, meta.name);
+            assert!(names.insert(meta.name), "duplicate command {}", meta.name);
+            assert_eq!(command.dm_permission, Some(meta.dm_permission));
+            assert_eq!(command.kind, meta.kind);
+
+            if meta.kind != CommandType::ChatInput {
+                assert!(
+                    command.description.is_empty(),
+                    "{} has a description",
+                    meta.name
+                );
+                assert!(command.options.is_empty(), "{} has options", meta.name);
+            }
+
+            match meta.permissions {
+                MemberPermissions::Everyone => {
+                    assert_eq!(command.default_member_permissions, None)
+                }
+                MemberPermissions::Required(permissions) => {
+                    assert!(
+                        !permissions.is_empty(),
+                        "{} has empty permissions",
+                        meta.name
+                    );
+                    assert_eq!(command.default_member_permissions, Some(permissions));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_commands_options_limit() {
+        // Discord rejects commands with more than 25 options or subcommands.
+        for meta in COMMANDS {
+            let command = meta.command();
+
+            assert!(
+                command.options.len() <= 25,
+                "{} has too many options",
+                meta.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_missing_permissions() {
+        let kick = find_command("kick").unwrap();
+
+        assert!(kick
+            .missing_permissions(Permissions::KICK_MEMBERS)
+            .is_empty());
+        assert!(kick
+            .missing_permissions(Permissions::ADMINISTRATOR)
+            .is_empty());
+        assert_eq!(
+            kick.missing_permissions(Permissions::BAN_MEMBERS),
+            Permissions::KICK_MEMBERS
+        );
+        assert!(find_command("help")
+            .unwrap()
+            .missing_permissions(Permissions::empty())
+            .is_empty());
+    }
+
+    #[test]
+    fn test_commands_help() {
+        for meta in COMMANDS {
+            for example in meta.examples {
+                let name = example.trim_start_matches('/').split(' ').next();
+
+                assert_eq!(name, Some(meta.name), "invalid example for {}", meta.name);
+            }
+
+            for setting in meta.settings {
+                let mut path = setting.trim_start_matches('/').split(' ');
+                let command = path.next().and_then(find_command).unwrap();
+                let options = command.command().options;
+
+                // Settings must point to an existing subcommand.
+                path.try_fold(options, |options, name| {
+                    options.into_iter().find_map(|option| match option {
+                        CommandOption::SubCommand(data) | CommandOption::SubCommandGroup(data)
+                            if data.name == name =>
+                        {
+                            Some(data.options)
+                        }
+                        _ => None,
+                    })
+                })
+                .unwrap_or_else(|| panic!("invalid setting {setting} for {}", meta.name));
+            }
+        }
+    }
+
+    #[test]
+    fn test_command_category_id() {
+        for category in CommandCategory::ALL {
+            assert_eq!(CommandCategory::from_id(category.id()), Some(category));
+        }
+
+        assert_eq!(CommandCategory::from_id("unknown"), None);
+    }
+
+    #[test]
+    fn test_is_available() {
+        let ban = find_command("ban").unwrap();
+        let admin = find_command("admin").unwrap();
+        let help = find_command("help").unwrap();
+
+        assert!(ban.is_available(Some(Permissions::BAN_MEMBERS), false));
+        assert!(!ban.is_available(Some(Permissions::KICK_MEMBERS), false));
+        assert!(!ban.is_available(None, false));
+        assert!(help.is_available(None, false));
+
+        assert!(!admin.is_available(Some(Permissions::ADMINISTRATOR), false));
+        assert!(admin.is_available(Some(Permissions::ADMINISTRATOR), true));
+        assert!(admin.is_available(Some(Permissions::empty()), true));
+
+        assert!(available_commands(Some(Permissions::empty()), false)
+            .all(|command| command.permissions == MemberPermissions::Everyone));
+    }
+
+    #[test]
+    fn test_check_permissions() {
+        assert_eq!(
+            check_permissions("ban", Some(Permissions::KICK_MEMBERS)),
+            Err(Permissions::BAN_MEMBERS)
+        );
+        assert_eq!(
+            check_permissions("ban", Some(Permissions::BAN_MEMBERS)),
+            Ok(())
+        );
+        assert_eq!(
+            check_permissions("ban", Some(Permissions::ADMINISTRATOR)),
+            Ok(())
+        );
+        assert_eq!(
+            check_permissions("help", Some(Permissions::empty())),
+            Ok(())
+        );
+        assert_eq!(
+            check_permissions("admin", Some(Permissions::empty())),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_check_permissions_without_member() {
+        assert_eq!(check_permissions("ban", None), Ok(()));
+        assert_eq!(
+            check_permissions("unknown", Some(Permissions::empty())),
+            Ok(())
+        );
+    }
+
+    async fn check(
+        name: &str,
+        permissions: Permissions,
+        state: &impl HandlerState,
+    ) -> Option<InteractionResponse> {
+        let interaction = guild_command(name)
+            .invoker(member_with_perms(permissions))
+            .interaction();
+
+        check_command(&interaction, name, state).await.unwrap()
+    }
+
+    /// Run the command checks against a state, whose maintenance mode is
+    /// disabled.
+    async fn check_command_state(state: &impl HandlerState) {
+        assert_eq!(check("kick", Permissions::KICK_MEMBERS, state).await, None);
+        assert_eq!(
+            check("kick", Permissions::empty(), state).await,
+            Some(embed::error::missing_command_permissions(
+                Lang::DEFAULT,
+                Permissions::KICK_MEMBERS
+            ))
+        );
+
+        Maintenance::set_enabled(state.cache_store(), true)
+            .await
+            .unwrap();
+        let maintenance = check("kick", Permissions::KICK_MEMBERS, state).await;
+        assert_eq!(maintenance, Some(embed::error::maintenance(Lang::DEFAULT)));
+        assert_eq!(check("help", Permissions::empty(), state).await, None);
+
+        Maintenance::set_enabled(state.cache_store(), false)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_check_command() {
+        check_command_state(&MockClusterState::new()).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a MongoDB and a Redis server"]
+    async fn test_check_command_connected() {
+        check_command_state(&MockClusterState::connect().await).await;
+    }
+
+    #[tokio::test]
+    async fn test_check_command_operator() {
+        let mut state = MockClusterState::new();
+
+        let response = check("admin", Permissions::ADMINISTRATOR, &state).await;
+        assert_eq!(response, Some(embed::error::not_operator(Lang::DEFAULT)));
+
+        // Operators do not need the permissions of the command.
+        state.operators.push(INVOKER_ID);
+        assert_eq!(check("admin", Permissions::empty(), &state).await, None);
+    }
+}
@@ -0,0 +1,193 @@
+//! Ban command.
+//!
+//! The command allows to ban a user from the server. The user is specified
+//! with its id (or a mention), so users that are not a member of the server can
+//! also be banned ahead of time (pre-emptive ban).
+//!
+//! The role hierarchy is only checked when the user is a member of the server.
+//! When a user is banned, the action is logged in the database and a message
+//! is sent in the guild's logs channel.
+//!
+//! With the `sync` option, the ban is mirrored in the guilds linked with the
+//! `/link` command (see the [`ban_sync`] feature module).
+//!
+//! [`ban_sync`]: crate::feature::ban_sync
+
+use raidprotect_model::{cache::model::interaction::PendingSanction, database::model::ModlogType};
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::{
+    guild::Permissions,
+    id::{marker::UserMarker, Id},
+};
+
+use super::{check_cooldown, check_sanction_role, check_shadow, execute_sanction};
+use crate::{
+    cluster::ClusterState,
+    desc_localizations, impl_guild_command_handle,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+    util::http::is_not_found,
+};
+
+/// Ban command model.
+///
+/// See the [`module`][self] documentation for more information.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "ban",
+    desc = "Bans a user from the server",
+    desc_localizations = "ban_description"
+)]
+pub struct BanCommand {
+    /// Id or mention of the user to ban.
+    pub user: String,
+    /// Reason for ban.
+    #[command(autocomplete = true)]
+    pub reason: Option<String>,
+    /// Apply the sanction without public notice.
+    pub shadow: Option<bool>,
+    /// Mirror the ban in the linked servers.
+    pub sync: Option<bool>,
+}
+
+impl_guild_command_handle!(BanCommand);
+desc_localizations!(ban_description);
+
+impl BanCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let user_id = match parse_user_id(&self.user) {
+            Some(user_id) => user_id,
+            None => return Ok(embed::ban::invalid_id(self.user, ctx.lang)),
+        };
+
+        let permissions = state.cache.permissions(ctx.guild_id).await?;
+        let bot_member = state.own_member(ctx.guild_id).await?;
+        let bot_permissions = permissions.bot_member(&bot_member).await?;
+
+        if !bot_permissions.guild().contains(Permissions::BAN_MEMBERS) {
+            return Ok(embed::ban::bot_missing_permission(ctx.lang));
+        }
+
+        // Users that are not a member of the guild are banned ahead of time,
+        // without checking the role hierarchy.
+        let member = match state.http.guild_member(ctx.guild_id, user_id).exec().await {
+            Ok(response) => Some(response.model().await?),
+            Err(error) if is_not_found(&error) => None,
+            Err(error) => return Err(error.into()),
+        };
+        let pre_emptive = member.is_none();
+
+        let user = match member {
+            Some(member) => {
+                let author_permissions = permissions.member(ctx.author.id, &member.roles).await?;
+                let member_permissions = permissions.member(user_id, &member.roles).await?;
+
+                if member_permissions.is_owner() {
+                    return Ok(embed::ban::member_owner(ctx.lang));
+                }
+
+                // Check if the role hierarchy allow the author and the bot to
+                // perform the ban.
+                let member_highest_role = member_permissions.highest_role();
+
+                if member_highest_role >= author_permissions.highest_role() {
+                    return Ok(embed::ban::user_hierarchy(ctx.lang));
+                }
+
+                if member_highest_role >= bot_permissions.highest_role() {
+                    return Ok(embed::ban::bot_hierarchy(ctx.lang));
+                }
+
+                member.user
+            }
+            None => match state.http.user(user_id).exec().await {
+                Ok(response) => response.model().await?,
+                Err(error) if is_not_found(&error) => {
+                    return Ok(embed::ban::unknown_user(user_id, ctx.lang))
+                }
+                Err(error) => return Err(error.into()),
+            },
+        };
+
+        match state.http.ban(ctx.guild_id, user_id).exec().await {
+            Ok(_) => return Ok(embed::ban::already_banned(user.name, ctx.lang)),
+            Err(error) if is_not_found(&error) => {}
+            Err(error) => return Err(error.into()),
+        }
+
+        let config = ctx.config(state).await?;
+        if let Some(response) = check_sanction_role(&ctx, &config.moderation, ModlogType::Ban) {
+            return Ok(response);
+        }
+
+        let shadow = self.shadow.unwrap_or(false);
+        if let Some(response) = check_shadow(&ctx, &config.moderation, shadow) {
+            return Ok(response);
+        }
+
+        if config.moderation.enforce_reason && self.reason.is_none() {
+            return Ok(embed::mute::missing_reason(ctx.lang));
+        }
+
+        let sync = self.sync.unwrap_or(false);
+        if sync && !config.ban_sync.sends() {
+            return Ok(embed::link::sync_disabled(ctx.lang));
+        }
+
+        let mut pending = PendingSanction {
+            interaction_id: ctx.interaction.id,
+            author_id: ctx.author.id,
+            kind: ModlogType::Ban,
+            user,
+            reason: self.reason,
+            duration: None,
+            pre_emptive,
+            cleanup: None,
+            confirmation_skipped: false,
+            shadow,
+            sync,
+        };
+
+        // Ask for a confirmation if the user has just been sanctioned.
+        if let Some(response) =
+            check_cooldown(&mut pending, &ctx, &config.moderation, state).await?
+        {
+            return Ok(response);
+        }
+
+        execute_sanction(pending, &ctx, &config, state).await
+    }
+}
+
+/// Parse a user id, optionally formatted as a mention.
+fn parse_user_id(input: &str) -> Option<Id<UserMarker>> {
+    let input = input.trim();
+    let input = input
+        .strip_prefix("<@")
+        .and_then(|input| input.strip_suffix('>'))
+        .map(|input| input.trim_start_matches('!'))
+        .unwrap_or(input);
+
+    input.parse().ok().and_then(Id::new_checked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_user_id() {
+        assert_eq!(parse_user_id("123456789"), Some(Id::new(123456789)));
+        assert_eq!(parse_user_id(" 123456789 "), Some(Id::new(123456789)));
+        assert_eq!(parse_user_id("<@123456789>"), Some(Id::new(123456789)));
+        assert_eq!(parse_user_id("<@!123456789>"), Some(Id::new(123456789)));
+
+        assert_eq!(parse_user_id("0"), None);
+        assert_eq!(parse_user_id("user"), None);
+        assert_eq!(parse_user_id("<#123456789>"), None);
+        assert_eq!(parse_user_id("99999999999999999999999"), None);
+    }
+}
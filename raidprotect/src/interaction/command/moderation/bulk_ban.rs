@@ -0,0 +1,142 @@
+//! Bulk ban command.
+//!
+//! The command accepts a text file containing user ids (see
+//! [`parse_user_ids`]) and bans all of them once the moderator has confirmed.
+//! Ids that could not be banned are listed at the end.
+//!
+//! [`parse_user_ids`]: crate::feature::bulk_ban::parse_user_ids
+
+use raidprotect_model::{cache::model::interaction::PendingBulkBan, database::model::ModlogType};
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::{
+    application::component::{button::ButtonStyle, ActionRow, Button, Component},
+    channel::{message::MessageFlags, Attachment},
+    guild::Permissions,
+    http::interaction::InteractionResponseType,
+    id::marker::UserMarker,
+};
+use twilight_util::builder::InteractionResponseDataBuilder;
+
+use super::check_sanction_role;
+use crate::{
+    cluster::ClusterState,
+    desc_localizations,
+    feature::bulk_ban::{parse_user_ids, MAX_FILE_SIZE, MAX_USERS},
+    impl_guild_command_handle,
+    interaction::{
+        embed,
+        response::InteractionResponse,
+        util::{CustomId, GuildInteractionContext},
+    },
+    util::download::download_text,
+};
+
+/// Bulk ban command model.
+///
+/// See the [`module`][self] documentation for more information.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "bulk-ban",
+    desc = "Ban all the users listed in a file",
+    desc_localizations = "bulk_ban_description"
+)]
+pub struct BulkBanCommand {
+    /// Text or CSV file with the ids of the users to ban.
+    pub file: Attachment,
+    /// Reason for the ban.
+    #[command(max_length = 400)]
+    pub reason: Option<String>,
+}
+
+impl_guild_command_handle!(BulkBanCommand);
+desc_localizations!(bulk_ban_description);
+
+impl BulkBanCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let config = ctx.config(state).await?;
+        if let Some(response) = check_sanction_role(&ctx, &config.moderation, ModlogType::Ban) {
+            return Ok(response);
+        }
+
+        let permissions = state.cache.permissions(ctx.guild_id).await?;
+        let bot_member = state.own_member(ctx.guild_id).await?;
+        let bot_permissions = permissions.bot_member(&bot_member).await?;
+
+        if !bot_permissions.guild().contains(Permissions::BAN_MEMBERS) {
+            return Ok(embed::bulk_ban::error(
+                ctx.lang,
+                ctx.lang.bot_missing_permission(),
+            ));
+        }
+
+        if self.file.size > MAX_FILE_SIZE as u64 {
+            return Ok(embed::bulk_ban::error(
+                ctx.lang,
+                ctx.lang.bulk_ban_file_too_large(MAX_FILE_SIZE / 1024),
+            ));
+        }
+
+        let content = download_text(&self.file.url, MAX_FILE_SIZE).await?;
+        let mut parsed = parse_user_ids(&content);
+
+        // The author and the bot cannot be banned.
+        let bot_id = state.current_user.cast::<UserMarker>();
+        parsed
+            .ids
+            .retain(|id| *id != ctx.author.id && *id != bot_id);
+
+        if parsed.ids.is_empty() {
+            return Ok(embed::bulk_ban::error(
+                ctx.lang,
+                ctx.lang.bulk_ban_no_users(),
+            ));
+        }
+
+        if parsed.ids.len() > MAX_USERS {
+            return Ok(embed::bulk_ban::error(
+                ctx.lang,
+                ctx.lang.bulk_ban_too_many(parsed.ids.len(), MAX_USERS),
+            ));
+        }
+
+        // Store the pending bulk ban until it is confirmed.
+        let pending = PendingBulkBan {
+            interaction_id: ctx.interaction.id,
+            author_id: ctx.author.id,
+            guild_id: ctx.guild_id,
+            user_ids: parsed.ids,
+            reason: self.reason,
+        };
+        state.cache.set(&pending).await?;
+
+        let embed =
+            embed::bulk_ban::confirmation(ctx.lang, pending.user_ids.len(), parsed.invalid.len());
+
+        let custom_id = CustomId::new("bulk-ban-confirm", ctx.interaction.id.to_string());
+        let components = Component::ActionRow(ActionRow {
+            components: vec![Component::Button(Button {
+                custom_id: Some(custom_id.to_string()),
+                disabled: false,
+                emoji: None,
+                label: Some(ctx.lang.bulk_ban_confirm_button().to_owned()),
+                style: ButtonStyle::Danger,
+                url: None,
+            })],
+        });
+
+        let response = InteractionResponseDataBuilder::new()
+            .embeds([embed])
+            .components([components])
+            .flags(MessageFlags::EPHEMERAL)
+            .build();
+
+        Ok(InteractionResponse::Raw {
+            kind: InteractionResponseType::ChannelMessageWithSource,
+            data: Some(response),
+        })
+    }
+}
@@ -0,0 +1,257 @@
+//! Bulk unban command.
+//!
+//! The command unbans all the users banned within a recent time window, for
+//! example after a false positive of the raid detection. The bans are found in
+//! the modlogs (see [`banned_users`]) and the users are unbanned once the
+//! moderator has confirmed.
+//!
+//! Only the bans applied automatically by the bot are reversed by default, the
+//! bans of the moderators are included with the `manual` option or by choosing
+//! a `moderator`.
+//!
+//! [`banned_users`]: crate::feature::bulk_unban::banned_users
+
+use raidprotect_model::{
+    cache::model::interaction::PendingBulkUnban,
+    database::model::{ModlogSearch, ModlogType},
+};
+use time::{Duration, OffsetDateTime};
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::{
+    application::component::{button::ButtonStyle, ActionRow, Button, Component},
+    channel::message::MessageFlags,
+    guild::Permissions,
+    http::interaction::InteractionResponseType,
+    id::{marker::UserMarker, Id},
+};
+use twilight_util::builder::InteractionResponseDataBuilder;
+
+use super::check_sanction_role;
+use crate::{
+    cluster::ClusterState,
+    desc_localizations,
+    feature::bulk_unban::{banned_users, MAX_USERS},
+    impl_guild_command_handle,
+    interaction::{
+        embed,
+        response::InteractionResponse,
+        util::{CustomId, GuildInteractionContext},
+    },
+};
+
+/// Bulk unban command model.
+///
+/// See the [`module`][self] documentation for more information.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "bulk-unban",
+    desc = "Unban all the users banned within a recent time window",
+    desc_localizations = "bulk_unban_description"
+)]
+pub struct BulkUnbanCommand {
+    /// Number of hours during which the users have been banned.
+    #[command(min_value = 1, max_value = 168)]
+    pub hours: i64,
+    /// Only unban the users banned by this moderator.
+    pub moderator: Option<Id<UserMarker>>,
+    /// Also unban the users banned manually by moderators.
+    pub manual: Option<bool>,
+    /// Reason for the unban.
+    #[command(max_length = 400)]
+    pub reason: Option<String>,
+}
+
+impl_guild_command_handle!(BulkUnbanCommand);
+desc_localizations!(bulk_unban_description);
+
+impl BulkUnbanCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let config = ctx.config(state).await?;
+        if let Some(response) = check_sanction_role(&ctx, &config.moderation, ModlogType::Ban) {
+            return Ok(response);
+        }
+
+        let permissions = state.cache.permissions(ctx.guild_id).await?;
+        let bot_member = state.own_member(ctx.guild_id).await?;
+        let bot_permissions = permissions.bot_member(&bot_member).await?;
+
+        if !bot_permissions.guild().contains(Permissions::BAN_MEMBERS) {
+            return Ok(embed::bulk_unban::error(
+                ctx.lang,
+                ctx.lang.bot_missing_permission(),
+            ));
+        }
+
+        let search = self.search(OffsetDateTime::now_utc(), state.current_user.cast());
+        let modlogs = state
+            .database
+            .search_modlogs(ctx.guild_id, &search, MAX_USERS as i64 + 1)
+            .await?;
+
+        // Users banned several times have several modlogs, so the search may
+        // be truncated even with fewer users than the limit.
+        if modlogs.len() > MAX_USERS {
+            return Ok(embed::bulk_unban::error(
+                ctx.lang,
+                ctx.lang.bulk_unban_too_many(self.hours, MAX_USERS),
+            ));
+        }
+
+        let banned = banned_users(&modlogs);
+        if banned.user_ids.is_empty() {
+            return Ok(embed::bulk_unban::error(
+                ctx.lang,
+                ctx.lang.bulk_unban_no_users(self.hours),
+            ));
+        }
+
+        // Store the pending bulk unban until it is confirmed.
+        let pending = PendingBulkUnban {
+            interaction_id: ctx.interaction.id,
+            author_id: ctx.author.id,
+            guild_id: ctx.guild_id,
+            user_ids: banned.user_ids,
+            cases: banned.cases,
+            reason: self.reason,
+        };
+        state.cache.set(&pending).await?;
+
+        let embed = embed::bulk_unban::confirmation(ctx.lang, pending.user_ids.len(), self.hours);
+
+        let custom_id = CustomId::new("bulk-unban-confirm", ctx.interaction.id.to_string());
+        let components = Component::ActionRow(ActionRow {
+            components: vec![Component::Button(Button {
+                custom_id: Some(custom_id.to_string()),
+                disabled: false,
+                emoji: None,
+                label: Some(ctx.lang.bulk_unban_confirm_button().to_owned()),
+                style: ButtonStyle::Danger,
+                url: None,
+            })],
+        });
+
+        let response = InteractionResponseDataBuilder::new()
+            .embeds([embed])
+            .components([components])
+            .flags(MessageFlags::EPHEMERAL)
+            .build();
+
+        Ok(InteractionResponse::Raw {
+            kind: InteractionResponseType::ChannelMessageWithSource,
+            data: Some(response),
+        })
+    }
+
+    /// Build the search of the bans issued within the time window.
+    ///
+    /// Unless a moderator is chosen or the manual bans are included, only the
+    /// automatic bans (issued by `bot_id`) are searched.
+    fn search(&self, now: OffsetDateTime, bot_id: Id<UserMarker>) -> ModlogSearch {
+        let moderator = match (self.moderator, self.manual) {
+            (Some(moderator), _) => Some(moderator),
+            (None, Some(true)) => None,
+            (None, _) => Some(bot_id),
+        };
+
+        ModlogSearch {
+            kind: Some(ModlogType::Ban),
+            moderator,
+            after: Some(now - Duration::hours(self.hours)),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use raidprotect_model::database::model::Modlog;
+
+    use super::*;
+    use crate::{feature::automation, test_utils::fixture};
+
+    const BOT_ID: Id<UserMarker> = Id::new(2);
+
+    fn command(moderator: Option<Id<UserMarker>>, manual: Option<bool>) -> BulkUnbanCommand {
+        BulkUnbanCommand {
+            hours: 1,
+            moderator,
+            manual,
+            reason: None,
+        }
+    }
+
+    fn automatic_ban() -> Modlog {
+        automation::automatic_modlog(
+            ModlogType::Ban,
+            Id::new(1),
+            automation::unknown_user(Id::new(10)),
+            automation::automatic_moderator(BOT_ID),
+            "antinuke",
+        )
+    }
+
+    fn manual_ban(date: OffsetDateTime) -> Modlog {
+        fixture::modlog(ModlogType::Ban)
+            .guild(Id::new(1))
+            .user(fixture::modlog_user(11, "user"))
+            .moderator(fixture::modlog_user(3, "moderator"))
+            .date(date.unix_timestamp())
+            .build()
+    }
+
+    #[test]
+    fn test_search() {
+        let command = BulkUnbanCommand {
+            hours: 24,
+            moderator: Some(Id::new(3)),
+            manual: None,
+            reason: None,
+        };
+        let now = OffsetDateTime::from_unix_timestamp(1_628_594_197).unwrap();
+
+        let search = command.search(now, BOT_ID);
+
+        assert_eq!(search.kind, Some(ModlogType::Ban));
+        assert_eq!(search.moderator, Some(Id::new(3)));
+        assert_eq!(search.after, Some(now - Duration::DAY));
+        assert_eq!(search.before, None);
+        assert!(!search.is_empty());
+    }
+
+    #[test]
+    fn test_search_automatic_ban() {
+        let modlog = automatic_ban();
+
+        let search = command(None, None).search(OffsetDateTime::now_utc(), BOT_ID);
+        assert_eq!(search.moderator, Some(BOT_ID));
+        assert!(search.matches(Id::new(1), &modlog));
+
+        let banned = banned_users(&[modlog]);
+        assert_eq!(banned.user_ids, vec![Id::new(10)]);
+    }
+
+    #[test]
+    fn test_search_manual_ban() {
+        let now = OffsetDateTime::now_utc();
+        let modlog = manual_ban(now);
+
+        // Manual bans are excluded by default.
+        let search = command(None, None).search(now, BOT_ID);
+        assert!(!search.matches(Id::new(1), &modlog));
+
+        let search = command(None, Some(false)).search(now, BOT_ID);
+        assert!(!search.matches(Id::new(1), &modlog));
+
+        let search = command(None, Some(true)).search(now, BOT_ID);
+        assert_eq!(search.moderator, None);
+        assert!(search.matches(Id::new(1), &modlog));
+        assert!(search.matches(Id::new(1), &automatic_ban()));
+
+        let search = command(Some(Id::new(3)), None).search(now, BOT_ID);
+        assert!(search.matches(Id::new(1), &modlog));
+    }
+}
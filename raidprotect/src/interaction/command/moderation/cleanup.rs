@@ -0,0 +1,81 @@
+//! Cleanup command.
+//!
+//! The command deletes the messages sent by a user during the last hours (see
+//! [`feature::cleanup`]). The same cleanup can be applied with the `cleanup`
+//! option of the `mute` and `kick` commands.
+//!
+//! [`feature::cleanup`]: crate::feature::cleanup
+
+use time::Duration;
+use twilight_interactions::command::{CommandModel, CommandOption, CreateCommand, CreateOption};
+use twilight_model::id::{marker::UserMarker, Id};
+
+use crate::{
+    cluster::ClusterState,
+    desc_localizations,
+    feature::cleanup,
+    impl_guild_command_handle,
+    interaction::{response::InteractionResponse, util::GuildInteractionContext},
+};
+
+/// Cleanup command model.
+///
+/// See the [`module`][self] documentation for more information.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "cleanup",
+    desc = "Delete the recent messages of a user",
+    desc_localizations = "cleanup_description"
+)]
+pub struct CleanupCommand {
+    /// User whose messages are deleted.
+    user: Id<UserMarker>,
+    /// Period during which the messages have been sent.
+    duration: CleanupWindow,
+}
+
+impl_guild_command_handle!(CleanupCommand);
+desc_localizations!(cleanup_description);
+
+/// Period during which the messages of a user are deleted.
+#[derive(Debug, Clone, Copy, CommandOption, CreateOption)]
+pub enum CleanupWindow {
+    #[option(name = "Last hour", value = "1h")]
+    Hour,
+    #[option(name = "Last 6 hours", value = "6h")]
+    SixHours,
+    #[option(name = "Last 24 hours", value = "24h")]
+    Day,
+}
+
+impl CleanupWindow {
+    /// Duration of the period, in minutes.
+    pub fn minutes(self) -> u32 {
+        match self {
+            Self::Hour => 60,
+            Self::SixHours => 6 * 60,
+            Self::Day => 24 * 60,
+        }
+    }
+}
+
+impl CleanupCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        // Messages are deleted in the background since it may take a while.
+        tokio::spawn(cleanup::run(
+            state.clone(),
+            ctx.guild_id,
+            self.user,
+            Duration::minutes(self.duration.minutes().into()),
+            ctx.responder(),
+            ctx.lang,
+            None,
+        ));
+
+        Ok(InteractionResponse::EphemeralDeferredMessage)
+    }
+}
@@ -2,23 +2,36 @@
 //!
 //! The command allows to kick a member from the server. User can specify a
 //! reason directly in the command (as an optional parameter), or in the modal
-//! that is shown if it hasn't been set in the command.
+//! that is shown if it hasn't been set in the command. If the server has
+//! reason presets, they are proposed in a select menu before the modal. Presets
+//! with variables are filled in a dedicated modal.
 //!
 //! When a user is kicked, the action is logged in the database and a message is
 //! sent in the guild's logs channel. The kicked user receives a pm with the
 //! reason of the kick.
 
-use raidprotect_model::{cache::model::interaction::PendingSanction, database::model::ModlogType};
+use raidprotect_model::{
+    cache::{discord::permission::GuildPermissions, model::interaction::PendingSanction},
+    database::model::{ModerationConfig, ModlogType},
+    store::CacheStore,
+};
 use twilight_interactions::command::{CommandModel, CreateCommand, ResolvedUser};
 use twilight_model::{
-    application::component::{text_input::TextInputStyle, ActionRow, Component, TextInput},
+    application::component::{
+        select_menu::SelectMenuOption, text_input::TextInputStyle, ActionRow, Component,
+        SelectMenu, TextInput,
+    },
+    channel::message::MessageFlags,
     guild::Permissions,
-    id::{marker::InteractionMarker, Id},
-    user::User,
+    http::interaction::InteractionResponseType,
 };
+use twilight_util::builder::InteractionResponseDataBuilder;
 
+use super::{
+    check_cooldown, check_reason_template, check_sanction_role, check_shadow, CleanupWindow,
+};
 use crate::{
-    cluster::ClusterState,
+    cluster::HandlerState,
     desc_localizations, impl_guild_command_handle,
     interaction::{
         embed,
@@ -29,6 +42,12 @@ use crate::{
     util::TextProcessExt,
 };
 
+/// Value of the "Other…" option of the reason select menu.
+pub const REASON_OTHER: &str = "other";
+
+/// Maximum number of options in a select menu.
+const MAX_SELECT_OPTIONS: usize = 25;
+
 /// Kick command model.
 ///
 /// See the [`module`][self] documentation for more information.
@@ -36,30 +55,29 @@ use crate::{
 #[command(
     name = "kick",
     desc = "Kicks a user from the server",
-    desc_localizations = "kick_description",
-    default_permissions = "KickCommand::default_permissions",
-    dm_permission = false
+    desc_localizations = "kick_description"
 )]
 pub struct KickCommand {
     /// Member to kick.
     #[command(rename = "member")]
     pub user: ResolvedUser,
     /// Reason for kick.
+    #[command(autocomplete = true)]
     pub reason: Option<String>,
+    /// Delete the messages sent by the member during this period.
+    pub cleanup: Option<CleanupWindow>,
+    /// Apply the sanction without public notice.
+    pub shadow: Option<bool>,
 }
 
 impl_guild_command_handle!(KickCommand);
 desc_localizations!(kick_description);
 
 impl KickCommand {
-    fn default_permissions() -> Permissions {
-        Permissions::KICK_MEMBERS
-    }
-
     async fn exec(
         self,
         ctx: GuildInteractionContext,
-        state: &ClusterState,
+        state: &impl HandlerState,
     ) -> Result<InteractionResponse, anyhow::Error> {
         let user = self.user.resolved;
         let member = match self.user.member {
@@ -68,10 +86,11 @@ impl KickCommand {
         };
 
         // Fetch the author and the bot permissions.
-        let permissions = state.cache.permissions(ctx.guild_id).await?;
-        let author_permissions = permissions.member(ctx.author.id, &member.roles).await?;
+        let permissions = GuildPermissions::new(state.cache_store(), ctx.guild_id).await?;
+        let author_permissions = permissions.member(ctx.author.id, &ctx.member.roles).await?;
         let member_permissions = permissions.member(user.id, &member.roles).await?;
-        let bot_permissions = permissions.current_member().await?;
+        let bot_member = state.own_member(ctx.guild_id).await?;
+        let bot_permissions = permissions.bot_member(&bot_member).await?;
 
         // Check if the author and the bot have required permissions.
         if member_permissions.is_owner() {
@@ -94,35 +113,136 @@ impl KickCommand {
             return Ok(embed::kick::bot_hierarchy(ctx.lang));
         }
 
-        // Send reason modal.
-        let enforce_reason = state
-            .database
-            .get_guild_or_create(ctx.guild_id)
-            .await?
-            .moderation
-            .enforce_reason;
-
-        match self.reason {
-            Some(_reason) => Ok(InteractionResponse::EphemeralDeferredMessage),
-            None => {
-                KickCommand::reason_modal(ctx.interaction.id, user, enforce_reason, state, ctx.lang)
-                    .await
+        let config = ctx.config(state).await?;
+        if let Some(response) = check_sanction_role(&ctx, &config.moderation, ModlogType::Kick) {
+            return Ok(response);
+        }
+
+        let shadow = self.shadow.unwrap_or(false);
+        if let Some(response) = check_shadow(&ctx, &config.moderation, shadow) {
+            return Ok(response);
+        }
+
+        let mut pending = PendingSanction {
+            interaction_id: ctx.interaction.id,
+            author_id: ctx.author.id,
+            kind: ModlogType::Kick,
+            user,
+            reason: self.reason,
+            duration: None,
+            pre_emptive: false,
+            cleanup: self.cleanup.map(CleanupWindow::minutes),
+            confirmation_skipped: false,
+            shadow,
+            sync: false,
+        };
+
+        // Ask for a confirmation if the member has just been sanctioned.
+        if let Some(response) =
+            check_cooldown(&mut pending, &ctx, &config.moderation, state).await?
+        {
+            return Ok(response);
+        }
+
+        KickCommand::start(pending, &config.moderation, state, ctx.lang).await
+    }
+
+    /// Start the kick once all the checks have passed.
+    ///
+    /// If the user has not specified a reason in the initial command, the
+    /// reason presets of the server are proposed, or a modal is shown to let
+    /// them enter a reason. Reason templates are filled before the kick.
+    pub async fn start(
+        pending: PendingSanction,
+        config: &ModerationConfig,
+        state: &impl HandlerState,
+        lang: Lang,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        if let Some(response) = check_reason_template(&pending, state, lang).await? {
+            return Ok(response);
+        }
+
+        match pending.reason {
+            Some(_) => Ok(InteractionResponse::EphemeralDeferredMessage),
+            None if !config.reason_presets.is_empty() => {
+                KickCommand::reason_select(pending, config, state, lang).await
             }
+            None => KickCommand::reason_modal(pending, config.enforce_reason, state, lang).await,
         }
     }
 
+    /// Select menu that lets the user choose one of the reason presets.
+    ///
+    /// Options values are the index of the preset, the "Other…" option opens
+    /// the reason modal.
+    async fn reason_select(
+        pending: PendingSanction,
+        config: &ModerationConfig,
+        state: &impl HandlerState,
+        lang: Lang,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        // The "Other…" option takes one of the available options, the last
+        // presets can still be entered in the modal.
+        let mut options = config
+            .reason_presets
+            .iter()
+            .take(MAX_SELECT_OPTIONS - 1)
+            .enumerate()
+            .map(|(index, preset)| SelectMenuOption {
+                default: false,
+                description: None,
+                emoji: None,
+                label: preset.clone(),
+                value: index.to_string(),
+            })
+            .collect::<Vec<_>>();
+
+        options.push(SelectMenuOption {
+            default: false,
+            description: None,
+            emoji: None,
+            label: lang.sanction_reason_other().to_owned(),
+            value: REASON_OTHER.to_owned(),
+        });
+
+        // Add pending component in Redis
+        let custom_id = CustomId::new("sanction-reason", pending.interaction_id.to_string());
+        state.cache_store().set(&pending).await?;
+
+        let components = Component::ActionRow(ActionRow {
+            components: vec![Component::SelectMenu(SelectMenu {
+                custom_id: custom_id.to_string(),
+                disabled: false,
+                max_values: Some(1),
+                min_values: Some(1),
+                options,
+                placeholder: Some(lang.sanction_reason_select_placeholder().to_owned()),
+            })],
+        });
+
+        let response = InteractionResponseDataBuilder::new()
+            .embeds([embed::reasons::select(lang, pending.user.id)])
+            .components([components])
+            .flags(MessageFlags::EPHEMERAL)
+            .build();
+
+        Ok(InteractionResponse::Raw {
+            kind: InteractionResponseType::ChannelMessageWithSource,
+            data: Some(response),
+        })
+    }
+
     /// Modal that asks the user to enter a reason for the kick.
     ///
     /// This modal is only shown if the user has not specified a reason in the
-    /// initial command.
-    async fn reason_modal(
-        interaction_id: Id<InteractionMarker>,
-        user: User,
+    /// initial command, or selected the "Other…" option of the reason presets.
+    pub async fn reason_modal(
+        pending: PendingSanction,
         enforce_reason: bool,
-        state: &ClusterState,
+        state: &impl HandlerState,
         lang: Lang,
     ) -> Result<InteractionResponse, anyhow::Error> {
-        let username = user.name.max_len(15);
+        let username = pending.user.name.max_len(15);
         let components = vec![
             Component::ActionRow(ActionRow {
                 components: vec![Component::TextInput(TextInput {
@@ -151,14 +271,8 @@ impl KickCommand {
         ];
 
         // Add pending component in Redis
-        let custom_id = CustomId::new("sanction", interaction_id.to_string());
-        let pending = PendingSanction {
-            interaction_id,
-            kind: ModlogType::Kick,
-            user,
-        };
-
-        state.cache.set(&pending).await?;
+        let custom_id = CustomId::new("sanction", pending.interaction_id.to_string());
+        state.cache_store().set(&pending).await?;
 
         Ok(InteractionResponse::Modal {
             custom_id: custom_id.to_string(),
@@ -167,3 +281,235 @@ impl KickCommand {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use raidprotect_model::cache::model::sanction::SanctionCooldown;
+    use twilight_model::id::{marker::RoleMarker, Id};
+
+    use super::*;
+    use crate::test_utils::{
+        assert,
+        fixture::{self, member_with_perms, MemberFixture, GUILD_ID, INTERACTION_ID},
+        MockClusterState,
+    };
+
+    const MODERATOR_ROLE: Id<RoleMarker> = Id::new(20);
+    const ADMIN_ROLE: Id<RoleMarker> = Id::new(21);
+    const BOT_ROLE: Id<RoleMarker> = Id::new(22);
+
+    /// Cache the guild with the moderator role at position 2, the admin role
+    /// at position 3 and the bot role at the given position.
+    async fn cache_guild(state: &MockClusterState, bot_position: i64) {
+        fixture::guild()
+            .role(MODERATOR_ROLE, 2, Permissions::empty())
+            .role(ADMIN_ROLE, 3, Permissions::empty())
+            .role(BOT_ROLE, bot_position, Permissions::KICK_MEMBERS)
+            .bot_roles([BOT_ROLE])
+            .cache(&state.cache)
+            .await;
+    }
+
+    /// Run the kick command like a received interaction, with the member
+    /// `10` with the given role as target.
+    async fn run(
+        state: &MockClusterState,
+        invoker: MemberFixture,
+        target_role: Id<RoleMarker>,
+    ) -> InteractionResponse {
+        let target = MemberFixture {
+            id: Id::new(10),
+            ..member_with_perms(Permissions::empty())
+        };
+
+        let prepared = fixture::guild_command("kick")
+            .option_member("member", target.roles([target_role]))
+            .option_string("reason", "spam")
+            .invoker(invoker)
+            .prepare::<KickCommand>(state)
+            .await;
+
+        match prepared {
+            Ok((command, ctx)) => command.exec(ctx, state).await.unwrap(),
+            Err(response) => response,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_kick_missing_permission() {
+        let state = MockClusterState::new();
+        cache_guild(&state, 5).await;
+
+        let invoker = member_with_perms(Permissions::empty()).roles([ADMIN_ROLE]);
+        let response = run(&state, invoker, MODERATOR_ROLE).await;
+
+        assert_eq!(
+            response,
+            embed::error::missing_command_permissions(Lang::DEFAULT, Permissions::KICK_MEMBERS)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_kick_target_above_invoker() {
+        let state = MockClusterState::new();
+        cache_guild(&state, 5).await;
+
+        let invoker = member_with_perms(Permissions::KICK_MEMBERS).roles([MODERATOR_ROLE]);
+        let response = run(&state, invoker, ADMIN_ROLE).await;
+
+        assert_eq!(response, embed::kick::user_hierarchy(Lang::DEFAULT));
+    }
+
+    #[tokio::test]
+    async fn test_kick_target_above_bot() {
+        let state = MockClusterState::new();
+        cache_guild(&state, 1).await;
+
+        let invoker = member_with_perms(Permissions::KICK_MEMBERS).roles([ADMIN_ROLE]);
+        let response = run(&state, invoker, MODERATOR_ROLE).await;
+
+        assert_eq!(response, embed::kick::bot_hierarchy(Lang::DEFAULT));
+    }
+
+    #[tokio::test]
+    async fn test_kick_success() {
+        let state = MockClusterState::new();
+        cache_guild(&state, 5).await;
+
+        let invoker = member_with_perms(Permissions::KICK_MEMBERS).roles([ADMIN_ROLE]);
+        let response = run(&state, invoker, MODERATOR_ROLE).await;
+
+        assert::ephemeral_deferred(&response);
+
+        // The sanction cooldown of the member has been started.
+        let cooldown = state
+            .cache
+            .get::<SanctionCooldown>(&(GUILD_ID, Id::new(10)))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(cooldown.moderator_id, fixture::INVOKER_ID);
+        assert_eq!(cooldown.kind, ModlogType::Kick);
+    }
+
+    /// Parse a kick command and build the pending sanction like
+    /// [`KickCommand::exec`] does once the permissions are checked.
+    fn pending(reason: Option<&str>) -> PendingSanction {
+        let mut fixture = fixture::guild_command("kick").option_user("member", Id::new(10));
+        if let Some(reason) = reason {
+            fixture = fixture.option_string("reason", reason);
+        }
+
+        let (command, ctx) = fixture
+            .invoker(member_with_perms(Permissions::KICK_MEMBERS))
+            .parse::<KickCommand>();
+        assert!(command.user.member.is_some());
+
+        PendingSanction {
+            interaction_id: ctx.interaction.id,
+            author_id: ctx.author.id,
+            kind: ModlogType::Kick,
+            user: command.user.resolved,
+            reason: command.reason,
+            duration: None,
+            pre_emptive: false,
+            cleanup: None,
+            confirmation_skipped: false,
+            shadow: false,
+            sync: false,
+        }
+    }
+
+    async fn cached(state: &MockClusterState) -> Option<PendingSanction> {
+        state
+            .cache
+            .get::<PendingSanction>(&INTERACTION_ID.to_string())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_kick_with_reason() {
+        let state = MockClusterState::new();
+        let config = ModerationConfig::default();
+
+        let response = KickCommand::start(pending(Some("spam")), &config, &state, Lang::DEFAULT)
+            .await
+            .unwrap();
+
+        assert::ephemeral_deferred(&response);
+        assert!(state.cache.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_kick_reason_modal() {
+        let state = MockClusterState::new();
+        let config = ModerationConfig {
+            enforce_reason: true,
+            ..Default::default()
+        };
+
+        let response = KickCommand::start(pending(None), &config, &state, Lang::DEFAULT)
+            .await
+            .unwrap();
+
+        let components = assert::modal(&response, &format!("sanction:{INTERACTION_ID}"));
+        match &components[0] {
+            Component::ActionRow(row) => match &row.components[0] {
+                Component::TextInput(input) => assert_eq!(input.required, Some(true)),
+                component => panic!("expected text input, got {component:?}"),
+            },
+            component => panic!("expected action row, got {component:?}"),
+        }
+
+        let cached = cached(&state).await.unwrap();
+        assert_eq!(cached.user.id, Id::new(10));
+        assert_eq!(cached.author_id, fixture::INVOKER_ID);
+    }
+
+    #[tokio::test]
+    async fn test_kick_reason_presets() {
+        let state = MockClusterState::new();
+        let config = ModerationConfig {
+            reason_presets: vec!["Spam".to_owned(), "Scam".to_owned()],
+            ..Default::default()
+        };
+
+        let response = KickCommand::start(pending(None), &config, &state, Lang::DEFAULT)
+            .await
+            .unwrap();
+
+        let data = assert::raw_data(&response);
+        assert_eq!(data.flags, Some(MessageFlags::EPHEMERAL));
+
+        match &data.components.as_deref().unwrap()[0] {
+            Component::ActionRow(row) => match &row.components[0] {
+                Component::SelectMenu(menu) => {
+                    let values = menu.options.iter().map(|o| &*o.value).collect::<Vec<_>>();
+                    assert_eq!(values, ["0", "1", REASON_OTHER]);
+                }
+                component => panic!("expected select menu, got {component:?}"),
+            },
+            component => panic!("expected action row, got {component:?}"),
+        }
+
+        assert!(cached(&state).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_kick_reason_template() {
+        let state = MockClusterState::new();
+        let config = ModerationConfig::default();
+        let pending = pending(Some("Breaking rule {rule}"));
+
+        let response = KickCommand::start(pending, &config, &state, Lang::DEFAULT)
+            .await
+            .unwrap();
+
+        let components = assert::modal(&response, &format!("sanction-template:{INTERACTION_ID}"));
+        assert_eq!(components.len(), 1);
+
+        let cached = cached(&state).await.unwrap();
+        assert_eq!(cached.reason.as_deref(), Some("Breaking rule {rule}"));
+    }
+}
@@ -15,8 +15,453 @@
 //!
 //! The sanctioned user receive a private message with the reason, and the
 //! sanction is applied. It is also logged in the guild's logs channel and in
-//! the bot database.
+//! the bot database. Private messages can be disabled with the
+//! `/config sanctions dm` command (see [`feature::sanction`]).
+//!
+//! The `ban` command accepts a user id, so users that are not a member of
+//! the server can be banned ahead of time. The role hierarchy is not checked
+//! in this case (see the [`ban`] module).
+//!
+//! The `ban`, `mute`, `unmute`, `quarantine` and `release` commands do not
+//! show a modal, the sanction is applied directly with the shared executor
+//! (see [`feature::sanction`]).
+//!
+//! The `bulk-ban` command bans a list of users sent as a file, and does not
+//! follow this flow (see the [`bulk_ban`] module). The `bulk-unban` command
+//! unbans the users banned within a recent time window (see the
+//! [`bulk_unban`] module).
+//!
+//! The `post` command locks or archives a forum post (see the [`post`]
+//! module).
+//!
+//! The `restore-message` command re-posts a recently deleted message (see the
+//! [`restore_message`] module).
+//!
+//! The `cleanup` command deletes the recent messages of a user (see the
+//! [`cleanup`] module). The `mute` and `kick` commands can also delete them
+//! with their `cleanup` option.
+//!
+//! ## Sanction roles
+//! Guilds can restrict the sanctions each moderator role is allowed to apply
+//! (see [`ModerationConfig::sanction_allowed`]). This is checked by each
+//! sanction command with [`check_sanction_role`], administrators are always
+//! allowed to apply every sanction.
+//!
+//! ## Sanction cooldown
+//! When two moderators act on the same user within a few seconds, the user
+//! could be sanctioned twice. The moderator that starts a sanction is stored
+//! in the cache for the duration configured in [`ModerationConfig`]. If
+//! another moderator starts a sanction on the same user during this delay, the
+//! bot asks for a confirmation before continuing. Guilds can skip this
+//! confirmation for non-destructive sanctions, or for every sanction started
+//! by members of trusted roles (fast mode). Skipped confirmations are recorded
+//! in the modlogs.
+//!
+//! ## Shadow sanctions
+//! The `ban`, `kick`, `mute` and `quarantine` commands have a `shadow` option
+//! to apply the sanction without any public notice (see
+//! [`feature::sanction`]). Shadow sanctions are restricted to administrators
+//! and to the roles allowed with the `/config sanction-roles` command (see
+//! [`check_shadow`]).
+//!
+//! ## Reason templates
+//! Reason presets may contain variables, such as `{rule}` or `{count}` (see
+//! [`util::template`]). When such a template is used as a reason, a modal asks
+//! the moderator for the value of each variable before the sanction is applied
+//! (see [`check_reason_template`]). Sanctions are never applied with unfilled
+//! variables.
+//!
+//! [`feature::sanction`]: crate::feature::sanction
+//! [`util::template`]: crate::util::template
 
+mod ban;
+mod bulk_ban;
+mod bulk_unban;
+mod cleanup;
 mod kick;
+mod mute;
+mod post;
+mod quarantine;
+mod release;
+mod restore_message;
+mod unmute;
+
+pub use ban::BanCommand;
+pub use bulk_ban::BulkBanCommand;
+pub use bulk_unban::BulkUnbanCommand;
+pub use cleanup::{CleanupCommand, CleanupWindow};
+pub use kick::{KickCommand, REASON_OTHER};
+pub use mute::MuteCommand;
+pub use post::{PostAction, PostCommand};
+pub use quarantine::QuarantineCommand;
+use raidprotect_model::{
+    cache::model::{
+        interaction::{PendingSanction, PostInChatMessage, PublicMessageTemplate},
+        sanction::SanctionCooldown,
+    },
+    database::model::{GuildConfig, ModerationConfig, ModlogType, ModlogUser},
+    store::CacheStore,
+};
+pub use release::ReleaseCommand;
+pub use restore_message::RestoreMessageCommand;
+use time::{Duration, OffsetDateTime};
+use tracing::error;
+use twilight_mention::Mention;
+use twilight_model::{
+    application::component::{
+        button::ButtonStyle, text_input::TextInputStyle, ActionRow, Button, Component, TextInput,
+    },
+    channel::message::MessageFlags,
+    guild::Permissions,
+    http::interaction::InteractionResponseType,
+    id::{marker::GuildMarker, Id},
+};
+use twilight_util::builder::InteractionResponseDataBuilder;
+pub use unmute::UnmuteCommand;
+
+use crate::{
+    cluster::{ClusterState, HandlerState},
+    feature::sanction::{self, Sanction},
+    interaction::{
+        component::PostInChat,
+        embed::{self, EmbedBuilder, COLOR_RED},
+        response::InteractionResponse,
+        util::{CustomId, GuildInteractionContext},
+    },
+    translations::Lang,
+    util::{template, time::relative},
+};
+
+/// Maximum number of variables in a reason template.
+///
+/// The variables are asked in a modal, which has at most five fields.
+pub const MAX_TEMPLATE_VARIABLES: usize = 5;
+
+/// Check whether the roles of the author allow them to apply a sanction.
+///
+/// An error response is returned if the sanction is not allowed, or if the
+/// roles of the author are unavailable (see [`GuildInteractionContext::partial`]).
+pub fn check_sanction_role(
+    ctx: &GuildInteractionContext,
+    config: &ModerationConfig,
+    kind: ModlogType,
+) -> Option<InteractionResponse> {
+    if let Some(response) = ctx.check_partial() {
+        return Some(response);
+    }
+
+    if is_admin(ctx) || config.sanction_allowed(&ctx.member.roles, kind) {
+        return None;
+    }
+
+    Some(embed::sanction_roles::not_allowed(ctx.lang, kind))
+}
+
+/// Check whether the roles of the author allow them to apply a shadow
+/// sanction.
+///
+/// An error response is returned if `shadow` is set and the author is not
+/// allowed to apply shadow sanctions.
+pub fn check_shadow(
+    ctx: &GuildInteractionContext,
+    config: &ModerationConfig,
+    shadow: bool,
+) -> Option<InteractionResponse> {
+    if !shadow || is_admin(ctx) || config.shadow_allowed(&ctx.member.roles) {
+        return None;
+    }
+
+    Some(embed::sanction_roles::shadow_not_allowed(ctx.lang))
+}
+
+/// Whether the author of the interaction is an administrator.
+fn is_admin(ctx: &GuildInteractionContext) -> bool {
+    ctx.member.permissions.map_or(false, |permissions| {
+        permissions.contains(Permissions::ADMINISTRATOR)
+    })
+}
+
+/// Check whether the user targeted by a sanction has just been sanctioned.
+///
+/// If the user has been sanctioned by another moderator during the cooldown,
+/// the pending sanction is stored and a confirmation is returned. Otherwise,
+/// the cooldown is started for the sanction author. Sanctions configured to
+/// skip the confirmation (see [`ModerationConfig::confirmation_required`])
+/// and sanctions started by a member with a fast mode role (see
+/// [`ModerationConfig::fast_mode`]) are applied immediately, and marked as
+/// such in the modlogs.
+///
+/// The cooldown is claimed atomically, so that concurrent sanctions of the
+/// same user by different moderators cannot both skip the confirmation.
+pub async fn check_cooldown(
+    pending: &mut PendingSanction,
+    ctx: &GuildInteractionContext,
+    config: &ModerationConfig,
+    state: &impl HandlerState,
+) -> Result<Option<InteractionResponse>, anyhow::Error> {
+    if config.sanction_cooldown == 0 {
+        return Ok(None);
+    }
+
+    let guild_id = ctx.guild_id;
+
+    let cooldown = sanction_cooldown(pending, guild_id);
+    if state
+        .cache_store()
+        .set_if_absent(&cooldown, config.sanction_cooldown.into())
+        .await?
+    {
+        return Ok(None);
+    }
+
+    let id = (guild_id, pending.user.id);
+    if let Some(cooldown) = state.cache_store().get::<SanctionCooldown>(&id).await? {
+        if cooldown.moderator_id != pending.author_id {
+            if config.confirmation_required(pending.kind) && !config.fast_mode(&ctx.member.roles) {
+                state.cache_store().set(pending).await?;
+
+                return Ok(Some(cooldown_confirmation(pending, &cooldown, ctx.lang)));
+            }
+
+            pending.confirmation_skipped = true;
+        }
+    }
+
+    start_cooldown(pending, guild_id, config, state).await?;
+
+    Ok(None)
+}
+
+/// Start the sanction cooldown of the user targeted by a sanction.
+pub async fn start_cooldown(
+    pending: &PendingSanction,
+    guild_id: Id<GuildMarker>,
+    config: &ModerationConfig,
+    state: &impl HandlerState,
+) -> Result<(), anyhow::Error> {
+    if config.sanction_cooldown == 0 {
+        return Ok(());
+    }
+
+    let cooldown = sanction_cooldown(pending, guild_id);
+
+    state
+        .cache_store()
+        .set_with_expiry(&cooldown, config.sanction_cooldown.into())
+        .await
+}
+
+/// Cooldown started by a sanction.
+fn sanction_cooldown(pending: &PendingSanction, guild_id: Id<GuildMarker>) -> SanctionCooldown {
+    SanctionCooldown {
+        guild_id,
+        user_id: pending.user.id,
+        moderator_id: pending.author_id,
+        kind: pending.kind,
+        date: OffsetDateTime::now_utc(),
+    }
+}
+
+/// Confirmation shown when the targeted user has just been sanctioned.
+fn cooldown_confirmation(
+    pending: &PendingSanction,
+    cooldown: &SanctionCooldown,
+    lang: Lang,
+) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.sanction_cooldown_title())
+        .description(lang.sanction_cooldown_description(
+            relative(cooldown.date),
+            cooldown.moderator_id.mention(),
+            pending.user.id.mention(),
+        ))
+        .build();
+
+    let custom_id = CustomId::new("sanction-confirm", pending.interaction_id.to_string());
+    let components = Component::ActionRow(ActionRow {
+        components: vec![Component::Button(Button {
+            custom_id: Some(custom_id.to_string()),
+            disabled: false,
+            emoji: None,
+            label: Some(lang.sanction_cooldown_button().to_owned()),
+            style: ButtonStyle::Danger,
+            url: None,
+        })],
+    });
+
+    let response = InteractionResponseDataBuilder::new()
+        .embeds([embed])
+        .components([components])
+        .flags(MessageFlags::EPHEMERAL)
+        .build();
+
+    InteractionResponse::Raw {
+        kind: InteractionResponseType::ChannelMessageWithSource,
+        data: Some(response),
+    }
+}
+
+/// Check whether the reason of a sanction is a template with variables.
+///
+/// If so, the pending sanction is stored with the template as reason, and a
+/// modal is returned to let the moderator fill each variable. Templates with
+/// too many variables to fit in a modal are rejected.
+pub async fn check_reason_template(
+    pending: &PendingSanction,
+    state: &impl HandlerState,
+    lang: Lang,
+) -> Result<Option<InteractionResponse>, anyhow::Error> {
+    let variables = match &pending.reason {
+        Some(reason) => template::variables(reason),
+        None => return Ok(None),
+    };
+
+    if variables.is_empty() {
+        return Ok(None);
+    }
+
+    if variables.len() > MAX_TEMPLATE_VARIABLES {
+        return Ok(Some(embed::reasons::too_many_variables(lang)));
+    }
+
+    let components = variables
+        .iter()
+        .map(|name| {
+            Component::ActionRow(ActionRow {
+                components: vec![Component::TextInput(TextInput {
+                    custom_id: (*name).to_owned(),
+                    label: (*name).to_owned(),
+                    max_length: Some(100),
+                    min_length: None,
+                    placeholder: Some(lang.sanction_template_placeholder().to_owned()),
+                    required: Some(true),
+                    style: TextInputStyle::Short,
+                    value: None,
+                })],
+            })
+        })
+        .collect();
+
+    // Add pending modal in Redis
+    let custom_id = CustomId::new("sanction-template", pending.interaction_id.to_string());
+    state.cache_store().set(pending).await?;
+
+    Ok(Some(InteractionResponse::Modal {
+        custom_id: custom_id.to_string(),
+        title: lang.sanction_template_title().to_owned(),
+        components,
+    }))
+}
+
+/// Apply a sanction that does not require a modal.
+///
+/// The sanction is applied with the shared executor, and a confirmation is
+/// returned to the moderator. The confirmation has a button to post the
+/// sanction in the channel, except for shadow sanctions.
+///
+/// If the reason is a template, the variables are asked first (see
+/// [`check_reason_template`]).
+pub async fn execute_sanction(
+    pending: PendingSanction,
+    ctx: &GuildInteractionContext,
+    config: &GuildConfig,
+    state: &ClusterState,
+) -> Result<InteractionResponse, anyhow::Error> {
+    if let Some(response) = check_reason_template(&pending, state, ctx.lang).await? {
+        return Ok(response);
+    }
+
+    let kind = pending.kind;
+    let username = pending.user.name.clone();
+    let user_id = pending.user.id;
+    let cleanup = pending.cleanup;
+    let shadow = pending.shadow;
+    let sanction = Sanction {
+        guild_id: ctx.guild_id,
+        kind,
+        user: ModlogUser::from(&pending.user),
+        moderator: ModlogUser::from(&ctx.author),
+        reason: pending.reason,
+        notes: None,
+        duration: pending
+            .duration
+            .map(|duration| Duration::minutes(duration.into())),
+        pre_emptive: pending.pre_emptive,
+        confirmation_skipped: pending.confirmation_skipped,
+        shadow,
+        sync: pending.sync,
+        synced_from: None,
+    };
+
+    // The moderator is hidden from the public message if the moderation is
+    // anonymous, but is still recorded in the modlog.
+    let template = PublicMessageTemplate::Sanction {
+        kind,
+        user: sanction.user.clone(),
+        moderator: (!config.moderation.anonymize).then(|| sanction.moderator.clone()),
+        reason: sanction.reason.clone(),
+    };
+
+    let outcome = match sanction::execute(state, config, sanction).await {
+        Ok(outcome) => outcome,
+        Err(error) => {
+            error!(error = ?error, guild = ?ctx.guild_id, "failed to apply sanction");
+
+            return match kind {
+                ModlogType::Ban => Ok(embed::ban::error(ctx.lang)),
+                ModlogType::Quarantine | ModlogType::Release => {
+                    Ok(embed::quarantine::error(ctx.lang))
+                }
+                _ => Ok(embed::mute::error(ctx.lang)),
+            };
+        }
+    };
+
+    let response = match kind {
+        ModlogType::Ban => embed::ban::success(username, pending.pre_emptive, ctx.lang),
+        ModlogType::Quarantine => embed::quarantine::success(username, ctx.lang),
+        ModlogType::Release => {
+            embed::quarantine::released(username, outcome.skipped_roles.len(), ctx.lang)
+        }
+        _ => embed::mute::success(kind, username, ctx.lang),
+    };
+
+    // Delete the messages of the user in the background, the response is
+    // updated with the number of deleted messages.
+    if let (Some(minutes), InteractionResponse::EphemeralEmbed(embed)) = (cleanup, &response) {
+        tokio::spawn(crate::feature::cleanup::run(
+            state.clone(),
+            ctx.guild_id,
+            user_id,
+            Duration::minutes(minutes.into()),
+            ctx.responder(),
+            ctx.lang,
+            Some(embed.clone()),
+        ));
+    }
+
+    // Shadow sanctions are never posted in the channel.
+    match (response, outcome.case_id) {
+        (InteractionResponse::EphemeralEmbed(embed), Some(case_id)) if !shadow => {
+            let response = InteractionResponseDataBuilder::new()
+                .embeds([embed])
+                .build();
+            let message = PostInChatMessage::Template {
+                case_id: Some(case_id),
+                template,
+            };
 
-pub use kick::KickCommand;
+            PostInChat::create(
+                response,
+                message,
+                ctx.interaction.id,
+                ctx.author.id,
+                state,
+                ctx.lang,
+            )
+            .await
+        }
+        (response, _) => Ok(response),
+    }
+}
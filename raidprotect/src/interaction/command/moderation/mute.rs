@@ -0,0 +1,149 @@
+//! Mute command.
+//!
+//! The command allows to mute a member for a given duration. Depending on the
+//! guild configuration, members are muted with a Discord timeout or with the
+//! mute role (see [`MuteMode`]).
+//!
+//! If no duration is specified, the mute is permanent when the mute role is
+//! used. Discord timeouts are limited to 28 days.
+
+use raidprotect_model::{
+    cache::model::interaction::PendingSanction,
+    database::model::{ModlogType, MuteMode},
+};
+use twilight_interactions::command::{CommandModel, CreateCommand, ResolvedUser};
+use twilight_model::{
+    guild::Permissions,
+    id::{marker::RoleMarker, Id},
+};
+
+use super::{check_cooldown, check_sanction_role, check_shadow, execute_sanction, CleanupWindow};
+use crate::{
+    cluster::ClusterState,
+    desc_localizations, impl_guild_command_handle,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+};
+
+/// Mute command model.
+///
+/// See the [`module`][self] documentation for more information.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "mute",
+    desc = "Mutes a member of the server",
+    desc_localizations = "mute_description"
+)]
+pub struct MuteCommand {
+    /// Member to mute.
+    #[command(rename = "member")]
+    pub user: ResolvedUser,
+    /// Duration of the mute, in minutes.
+    #[command(min_value = 1, max_value = 40320)]
+    pub duration: Option<i64>,
+    /// Reason for mute.
+    #[command(autocomplete = true)]
+    pub reason: Option<String>,
+    /// Delete the messages sent by the member during this period.
+    pub cleanup: Option<CleanupWindow>,
+    /// Apply the sanction without public notice.
+    pub shadow: Option<bool>,
+}
+
+impl_guild_command_handle!(MuteCommand);
+desc_localizations!(mute_description);
+
+impl MuteCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let user = self.user.resolved;
+        let member = match self.user.member {
+            Some(member) => member,
+            None => return Ok(embed::mute::not_member(user.name, ctx.lang)),
+        };
+
+        let pending = PendingSanction {
+            interaction_id: ctx.interaction.id,
+            author_id: ctx.author.id,
+            kind: ModlogType::Mute,
+            user,
+            reason: self.reason,
+            // The duration is bounded by the command options.
+            duration: self.duration.map(|duration| duration as u32),
+            pre_emptive: false,
+            cleanup: self.cleanup.map(CleanupWindow::minutes),
+            confirmation_skipped: false,
+            shadow: self.shadow.unwrap_or(false),
+            sync: false,
+        };
+
+        MuteCommand::start(pending, &member.roles, &ctx, state).await
+    }
+
+    /// Check that a member can be muted and apply the mute.
+    ///
+    /// This is also used by the "Sanction author" button of message reports,
+    /// which mutes the author of the reported message.
+    pub async fn start(
+        mut pending: PendingSanction,
+        roles: &[Id<RoleMarker>],
+        ctx: &GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        // Fetch the author and the bot permissions.
+        let permissions = state.cache.permissions(ctx.guild_id).await?;
+        let author_permissions = permissions.member(ctx.author.id, roles).await?;
+        let member_permissions = permissions.member(pending.user.id, roles).await?;
+        let bot_member = state.own_member(ctx.guild_id).await?;
+        let bot_permissions = permissions.bot_member(&bot_member).await?;
+
+        if member_permissions.is_owner() {
+            return Ok(embed::mute::member_owner(ctx.lang));
+        }
+
+        // The required permission depends on the way members are muted.
+        let config = ctx.config(state).await?;
+        if let Some(response) = check_sanction_role(ctx, &config.moderation, ModlogType::Mute) {
+            return Ok(response);
+        }
+
+        if let Some(response) = check_shadow(ctx, &config.moderation, pending.shadow) {
+            return Ok(response);
+        }
+
+        let required_permission = match config.moderation.mute_mode {
+            MuteMode::Timeout => Permissions::MODERATE_MEMBERS,
+            MuteMode::Role { .. } => Permissions::MANAGE_ROLES,
+        };
+
+        if !bot_permissions.guild().contains(required_permission) {
+            return Ok(embed::mute::bot_missing_permission(ctx.lang));
+        }
+
+        // Check if the role hierarchy allow the author and the bot to perform
+        // the mute.
+        let member_highest_role = member_permissions.highest_role();
+
+        if member_highest_role >= author_permissions.highest_role() {
+            return Ok(embed::mute::user_hierarchy(ctx.lang));
+        }
+
+        if member_highest_role >= bot_permissions.highest_role() {
+            return Ok(embed::mute::bot_hierarchy(ctx.lang));
+        }
+
+        if config.moderation.enforce_reason && pending.reason.is_none() {
+            return Ok(embed::mute::missing_reason(ctx.lang));
+        }
+
+        // Ask for a confirmation if the member has just been sanctioned.
+        if let Some(response) = check_cooldown(&mut pending, ctx, &config.moderation, state).await?
+        {
+            return Ok(response);
+        }
+
+        execute_sanction(pending, ctx, &config, state).await
+    }
+}
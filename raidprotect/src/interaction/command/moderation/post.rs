@@ -0,0 +1,157 @@
+//! Forum post command.
+//!
+//! The command allows to lock or archive the forum post it is used in. Forum
+//! posts are threads whose parent is a forum channel, other threads and
+//! channels are rejected.
+//!
+//! Actions taken on forum posts are logged in the guild's logs channel.
+
+use anyhow::Context;
+use raidprotect_model::{cache::discord::CachedChannel, database::model::LogCategory};
+use tracing::warn;
+use twilight_http::request::AuditLogReason;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::id::{marker::ChannelMarker, Id};
+
+use crate::{
+    cluster::ClusterState,
+    desc_localizations, impl_guild_command_handle,
+    interaction::{
+        embed,
+        response::InteractionResponse,
+        util::{GuildConfigExt, GuildInteractionContext},
+    },
+    util::logs,
+};
+
+/// Forum post command model.
+///
+/// See the [`module`][self] documentation for more information.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "post",
+    desc = "Moderate the forum post this command is used in",
+    desc_localizations = "post_description"
+)]
+pub enum PostCommand {
+    #[command(name = "lock")]
+    Lock(PostLockCommand),
+    #[command(name = "archive")]
+    Archive(PostArchiveCommand),
+}
+
+impl_guild_command_handle!(PostCommand);
+desc_localizations!(post_description);
+
+impl PostCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        match self {
+            Self::Lock(command) => update_post(PostAction::Lock, command.reason, ctx, state).await,
+            Self::Archive(command) => {
+                update_post(PostAction::Archive, command.reason, ctx, state).await
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "lock",
+    desc = "Lock the forum post, only moderators can send messages in it",
+    desc_localizations = "post_lock_description"
+)]
+pub struct PostLockCommand {
+    /// Reason for locking the post.
+    reason: Option<String>,
+}
+
+desc_localizations!(post_lock_description);
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "archive",
+    desc = "Archive the forum post",
+    desc_localizations = "post_archive_description"
+)]
+pub struct PostArchiveCommand {
+    /// Reason for archiving the post.
+    reason: Option<String>,
+}
+
+desc_localizations!(post_archive_description);
+
+/// Action performed on a forum post.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostAction {
+    /// The post is locked.
+    Lock,
+    /// The post is archived.
+    Archive,
+}
+
+async fn update_post(
+    action: PostAction,
+    reason: Option<String>,
+    ctx: GuildInteractionContext,
+    state: &ClusterState,
+) -> Result<InteractionResponse, anyhow::Error> {
+    let channel_id = ctx
+        .interaction
+        .channel_id
+        .context("missing interaction channel id")?;
+
+    if !is_forum_post(state, channel_id).await? {
+        return Ok(embed::post::not_forum_post(ctx.lang));
+    }
+
+    let config = ctx.config(state).await?;
+    let cache_http = state.cache_http(ctx.guild_id);
+    let mut request = match cache_http.update_thread(channel_id).await {
+        Ok(request) => request,
+        Err(error) => {
+            warn!(error = ?error, "cannot update forum post");
+
+            return Ok(embed::post::bot_missing_permission(ctx.lang));
+        }
+    };
+
+    request = match action {
+        PostAction::Lock => request.locked(true),
+        PostAction::Archive => request.archived(true),
+    };
+    if let Some(reason) = &reason {
+        request = request.reason(reason)?;
+    }
+
+    request.exec().await?;
+
+    // Log the action in the logs channel.
+    let lang = config.lang();
+    let log = embed::logs::forum_post(lang, action, channel_id, ctx.author.id, reason);
+
+    logs::post(state, &config, LogCategory::Sanctions, log).await?;
+
+    Ok(embed::post::success(ctx.lang, action))
+}
+
+/// Check whether a channel is a forum post.
+async fn is_forum_post(
+    state: &ClusterState,
+    channel_id: Id<ChannelMarker>,
+) -> Result<bool, anyhow::Error> {
+    let channel = match state.cache.get::<CachedChannel>(&channel_id).await? {
+        Some(channel) if channel.is_thread() => channel,
+        _ => return Ok(false),
+    };
+
+    let parent = match channel.parent_id {
+        Some(parent_id) => state.cache.get::<CachedChannel>(&parent_id).await?,
+        None => None,
+    };
+
+    Ok(parent.map_or(false, |parent| parent.is_forum()))
+}
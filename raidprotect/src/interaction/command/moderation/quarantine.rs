@@ -0,0 +1,137 @@
+//! Quarantine command.
+//!
+//! The command allows to isolate a member with the quarantine role configured
+//! with `/config quarantine`. The roles of the member are removed during the
+//! quarantine, and restored with the `/release` command.
+//!
+//! The quarantine is kept if the member leaves the guild, the quarantine role
+//! is added again when they rejoin (see [`feature::sanction`]).
+//!
+//! [`feature::sanction`]: crate::feature::sanction
+
+use raidprotect_model::{cache::model::interaction::PendingSanction, database::model::ModlogType};
+use twilight_interactions::command::{CommandModel, CreateCommand, ResolvedUser};
+use twilight_model::guild::Permissions;
+
+use super::{check_cooldown, check_sanction_role, check_shadow, execute_sanction};
+use crate::{
+    cluster::ClusterState,
+    desc_localizations, impl_guild_command_handle,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+};
+
+/// Quarantine command model.
+///
+/// See the [`module`][self] documentation for more information.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "quarantine",
+    desc = "Isolates a member of the server with the quarantine role",
+    desc_localizations = "quarantine_description"
+)]
+pub struct QuarantineCommand {
+    /// Member to quarantine.
+    #[command(rename = "member")]
+    pub user: ResolvedUser,
+    /// Reason for quarantine.
+    #[command(autocomplete = true)]
+    pub reason: Option<String>,
+    /// Apply the sanction without public notice.
+    pub shadow: Option<bool>,
+}
+
+impl_guild_command_handle!(QuarantineCommand);
+desc_localizations!(quarantine_description);
+
+impl QuarantineCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let user = self.user.resolved;
+        let member = match self.user.member {
+            Some(member) => member,
+            None => return Ok(embed::quarantine::not_member(user.name, ctx.lang)),
+        };
+
+        let config = ctx.config(state).await?;
+        if let Some(response) =
+            check_sanction_role(&ctx, &config.moderation, ModlogType::Quarantine)
+        {
+            return Ok(response);
+        }
+
+        let shadow = self.shadow.unwrap_or(false);
+        if let Some(response) = check_shadow(&ctx, &config.moderation, shadow) {
+            return Ok(response);
+        }
+
+        if config.moderation.quarantine_role.is_none() {
+            return Ok(embed::quarantine::not_configured(ctx.lang));
+        }
+
+        if state
+            .database
+            .get_quarantine(ctx.guild_id, user.id)
+            .await?
+            .is_some()
+        {
+            return Ok(embed::quarantine::already_quarantined(user.name, ctx.lang));
+        }
+
+        // Fetch the author and the bot permissions.
+        let permissions = state.cache.permissions(ctx.guild_id).await?;
+        let author_permissions = permissions.member(ctx.author.id, &member.roles).await?;
+        let member_permissions = permissions.member(user.id, &member.roles).await?;
+        let bot_member = state.own_member(ctx.guild_id).await?;
+        let bot_permissions = permissions.bot_member(&bot_member).await?;
+
+        if member_permissions.is_owner() {
+            return Ok(embed::quarantine::member_owner(ctx.lang));
+        }
+
+        if !bot_permissions.guild().contains(Permissions::MANAGE_ROLES) {
+            return Ok(embed::quarantine::bot_missing_permission(ctx.lang));
+        }
+
+        // Check if the role hierarchy allow the author and the bot to perform
+        // the quarantine.
+        let member_highest_role = member_permissions.highest_role();
+
+        if member_highest_role >= author_permissions.highest_role() {
+            return Ok(embed::quarantine::user_hierarchy(ctx.lang));
+        }
+
+        if member_highest_role >= bot_permissions.highest_role() {
+            return Ok(embed::quarantine::bot_hierarchy(ctx.lang));
+        }
+
+        if config.moderation.enforce_reason && self.reason.is_none() {
+            return Ok(embed::quarantine::missing_reason(ctx.lang));
+        }
+
+        let mut pending = PendingSanction {
+            interaction_id: ctx.interaction.id,
+            author_id: ctx.author.id,
+            kind: ModlogType::Quarantine,
+            user,
+            reason: self.reason,
+            duration: None,
+            pre_emptive: false,
+            cleanup: None,
+            confirmation_skipped: false,
+            shadow,
+            sync: false,
+        };
+
+        // Ask for a confirmation if the member has just been sanctioned.
+        if let Some(response) =
+            check_cooldown(&mut pending, &ctx, &config.moderation, state).await?
+        {
+            return Ok(response);
+        }
+
+        execute_sanction(pending, &ctx, &config, state).await
+    }
+}
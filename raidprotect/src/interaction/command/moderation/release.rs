@@ -0,0 +1,95 @@
+//! Release command.
+//!
+//! The command allows to release a member placed in quarantine with the
+//! `/quarantine` command. The roles the member had before the quarantine are
+//! restored, except the ones that have been deleted in the meantime.
+
+use raidprotect_model::{cache::model::interaction::PendingSanction, database::model::ModlogType};
+use twilight_interactions::command::{CommandModel, CreateCommand, ResolvedUser};
+use twilight_model::guild::Permissions;
+
+use super::{check_sanction_role, execute_sanction};
+use crate::{
+    cluster::ClusterState,
+    desc_localizations, impl_guild_command_handle,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+};
+
+/// Release command model.
+///
+/// See the [`module`][self] documentation for more information.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "release",
+    desc = "Releases a member of the server from quarantine",
+    desc_localizations = "release_description"
+)]
+pub struct ReleaseCommand {
+    /// Member to release.
+    #[command(rename = "member")]
+    pub user: ResolvedUser,
+    /// Reason for release.
+    #[command(autocomplete = true)]
+    pub reason: Option<String>,
+}
+
+impl_guild_command_handle!(ReleaseCommand);
+desc_localizations!(release_description);
+
+impl ReleaseCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let user = self.user.resolved;
+        let member = match self.user.member {
+            Some(member) => member,
+            None => return Ok(embed::quarantine::not_member(user.name, ctx.lang)),
+        };
+
+        let config = ctx.config(state).await?;
+        if let Some(response) = check_sanction_role(&ctx, &config.moderation, ModlogType::Release) {
+            return Ok(response);
+        }
+
+        if state
+            .database
+            .get_quarantine(ctx.guild_id, user.id)
+            .await?
+            .is_none()
+        {
+            return Ok(embed::quarantine::not_quarantined(user.name, ctx.lang));
+        }
+
+        // Fetch the member and the bot permissions.
+        let permissions = state.cache.permissions(ctx.guild_id).await?;
+        let member_permissions = permissions.member(user.id, &member.roles).await?;
+        let bot_member = state.own_member(ctx.guild_id).await?;
+        let bot_permissions = permissions.bot_member(&bot_member).await?;
+
+        if !bot_permissions.guild().contains(Permissions::MANAGE_ROLES) {
+            return Ok(embed::quarantine::bot_missing_permission(ctx.lang));
+        }
+
+        if member_permissions.highest_role() >= bot_permissions.highest_role() {
+            return Ok(embed::quarantine::bot_hierarchy(ctx.lang));
+        }
+
+        let pending = PendingSanction {
+            interaction_id: ctx.interaction.id,
+            author_id: ctx.author.id,
+            kind: ModlogType::Release,
+            user,
+            reason: self.reason,
+            duration: None,
+            pre_emptive: false,
+            cleanup: None,
+            confirmation_skipped: false,
+            shadow: false,
+            sync: false,
+        };
+
+        execute_sanction(pending, &ctx, &config, state).await
+    }
+}
@@ -0,0 +1,142 @@
+//! Restore message command.
+//!
+//! Messages are kept in the cache for a short time after they are sent. When
+//! a cached message is deleted, a copy is kept for a longer time (see
+//! [`DeletedMessage`]), which allows moderators to re-post a message deleted
+//! maliciously, either in its original channel or in the logs channel.
+//!
+//! Messages deleted after they expired from the cache, or restored after the
+//! copy expired, are no longer available.
+
+use raidprotect_model::{cache::model::message::DeletedMessage, database::model::LogCategory};
+use twilight_interactions::command::{CommandModel, CommandOption, CreateCommand, CreateOption};
+use twilight_model::{
+    channel::message::AllowedMentions,
+    id::{marker::MessageMarker, Id},
+};
+
+use crate::{
+    cluster::ClusterState,
+    desc_localizations, impl_guild_command_handle,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+    util::logs::{self, LogMessage},
+};
+
+/// Restore message command model.
+///
+/// See the [`module`][self] documentation for more information.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "restore-message",
+    desc = "Re-post a recently deleted message",
+    desc_localizations = "restore_message_description"
+)]
+pub struct RestoreMessageCommand {
+    /// ID or link of the deleted message.
+    message: String,
+    /// Where to post the message. Defaults to its original channel.
+    destination: Option<RestoreDestination>,
+}
+
+impl_guild_command_handle!(RestoreMessageCommand);
+desc_localizations!(restore_message_description);
+
+/// Channel where a deleted message is restored.
+#[derive(Debug, Clone, Copy, CommandOption, CreateOption)]
+pub enum RestoreDestination {
+    #[option(name = "Original channel", value = "channel")]
+    Channel,
+    #[option(name = "Logs channel", value = "logs")]
+    Logs,
+}
+
+impl RestoreMessageCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let message_id = match parse_message_id(&self.message) {
+            Some(id) => id,
+            None => return Ok(embed::restore_message::invalid_message(ctx.lang)),
+        };
+
+        let deleted = match state.cache.get::<DeletedMessage>(&message_id).await? {
+            Some(deleted) if deleted.guild_id == ctx.guild_id => deleted,
+            _ => return Ok(embed::restore_message::not_available(ctx.lang)),
+        };
+
+        let embed =
+            embed::restore_message::restored(ctx.public_lang, &deleted.message, &ctx.author.name);
+
+        let channel = match self.destination.unwrap_or(RestoreDestination::Channel) {
+            RestoreDestination::Channel => {
+                let channel = deleted.message.channel_id;
+
+                // Mentions of the original message are not sent again.
+                state
+                    .http
+                    .create_message(channel)
+                    .embeds(&[embed])?
+                    .allowed_mentions(Some(&AllowedMentions::default()))
+                    .exec()
+                    .await?;
+
+                channel
+            }
+            RestoreDestination::Logs => {
+                let config = ctx.config(state).await?;
+                let message = LogMessage::new(embed);
+
+                match logs::post_message(state, &config, LogCategory::Messages, message).await? {
+                    Some(channel) => channel,
+                    None => return Ok(embed::restore_message::logs_off(ctx.lang)),
+                }
+            }
+        };
+
+        Ok(embed::restore_message::success(ctx.lang, channel))
+    }
+}
+
+/// Parse a message id, or a message link.
+fn parse_message_id(input: &str) -> Option<Id<MessageMarker>> {
+    let input = input.trim().trim_end_matches('/');
+    let id = match input.rsplit_once('/') {
+        Some((prefix, id)) if prefix.contains("/channels/") => id,
+        Some(_) => return None,
+        None => input,
+    };
+
+    id.parse().ok().and_then(Id::new_checked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_message_id() {
+        assert_eq!(parse_message_id("123456789"), Some(Id::new(123456789)));
+        assert_eq!(parse_message_id(" 123456789 "), Some(Id::new(123456789)));
+        assert_eq!(
+            parse_message_id("https://discord.com/channels/1/2/123456789"),
+            Some(Id::new(123456789))
+        );
+        assert_eq!(
+            parse_message_id("https://ptb.discord.com/channels/1/2/123456789/"),
+            Some(Id::new(123456789))
+        );
+    }
+
+    #[test]
+    fn test_parse_message_id_invalid() {
+        assert_eq!(parse_message_id("0"), None);
+        assert_eq!(parse_message_id("message"), None);
+        assert_eq!(parse_message_id("https://example.com/123456789"), None);
+        assert_eq!(
+            parse_message_id("https://discord.com/channels/1/2/abc"),
+            None
+        );
+    }
+}
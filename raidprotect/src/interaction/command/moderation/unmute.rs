@@ -0,0 +1,94 @@
+//! Unmute command.
+//!
+//! The command allows to unmute a member that has been muted with the `/mute`
+//! command. Members are unmuted the same way they are muted, according to the
+//! guild configuration (see [`MuteMode`]).
+
+use raidprotect_model::{
+    cache::model::interaction::PendingSanction,
+    database::model::{ModlogType, MuteMode},
+};
+use twilight_interactions::command::{CommandModel, CreateCommand, ResolvedUser};
+use twilight_model::guild::Permissions;
+
+use super::{check_sanction_role, execute_sanction};
+use crate::{
+    cluster::ClusterState,
+    desc_localizations, impl_guild_command_handle,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+};
+
+/// Unmute command model.
+///
+/// See the [`module`][self] documentation for more information.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "unmute",
+    desc = "Unmutes a member of the server",
+    desc_localizations = "unmute_description"
+)]
+pub struct UnmuteCommand {
+    /// Member to unmute.
+    #[command(rename = "member")]
+    pub user: ResolvedUser,
+    /// Reason for unmute.
+    #[command(autocomplete = true)]
+    pub reason: Option<String>,
+}
+
+impl_guild_command_handle!(UnmuteCommand);
+desc_localizations!(unmute_description);
+
+impl UnmuteCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let user = self.user.resolved;
+        let member = match self.user.member {
+            Some(member) => member,
+            None => return Ok(embed::mute::not_member(user.name, ctx.lang)),
+        };
+
+        // Fetch the member and the bot permissions.
+        let permissions = state.cache.permissions(ctx.guild_id).await?;
+        let member_permissions = permissions.member(user.id, &member.roles).await?;
+        let bot_member = state.own_member(ctx.guild_id).await?;
+        let bot_permissions = permissions.bot_member(&bot_member).await?;
+
+        // The required permission depends on the way members are muted.
+        let config = ctx.config(state).await?;
+        if let Some(response) = check_sanction_role(&ctx, &config.moderation, ModlogType::Unmute) {
+            return Ok(response);
+        }
+        let required_permission = match config.moderation.mute_mode {
+            MuteMode::Timeout => Permissions::MODERATE_MEMBERS,
+            MuteMode::Role { .. } => Permissions::MANAGE_ROLES,
+        };
+
+        if !bot_permissions.guild().contains(required_permission) {
+            return Ok(embed::mute::bot_missing_permission(ctx.lang));
+        }
+
+        if member_permissions.highest_role() >= bot_permissions.highest_role() {
+            return Ok(embed::mute::bot_hierarchy(ctx.lang));
+        }
+
+        let pending = PendingSanction {
+            interaction_id: ctx.interaction.id,
+            author_id: ctx.author.id,
+            kind: ModlogType::Unmute,
+            user,
+            reason: self.reason,
+            duration: None,
+            pre_emptive: false,
+            cleanup: None,
+            confirmation_skipped: false,
+            shadow: false,
+            sync: false,
+        };
+
+        execute_sanction(pending, &ctx, &config, state).await
+    }
+}
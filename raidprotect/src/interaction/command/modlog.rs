@@ -0,0 +1,301 @@
+//! Modlog command.
+//!
+//! This command allows moderators to search the moderation logs of the
+//! server, for example to find all the bans issued by a moderator last week,
+//! or all the sanctions mentioning a scam. Filters are combined, and at least
+//! one filter is required to avoid listing all the logs of the server. The
+//! most recent results are shown first, and at most [`MAX_SEARCH_RESULTS`]
+//! results are shown.
+
+use raidprotect_model::{
+    database::model::{ModlogSearch, ModlogType},
+    store::ModlogStore,
+};
+use time::Duration;
+use twilight_interactions::command::{CommandModel, CommandOption, CreateCommand, CreateOption};
+use twilight_model::id::{marker::UserMarker, Id};
+
+use crate::{
+    cluster::{ClusterState, HandlerState},
+    desc_localizations, impl_guild_command_handle,
+    interaction::{
+        component::Paginator, embed, response::InteractionResponse, util::GuildInteractionContext,
+    },
+    util::time::parse_date,
+};
+
+/// Maximum number of results of a search.
+pub const MAX_SEARCH_RESULTS: i64 = 500;
+
+/// Modlog command model.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "modlog",
+    desc = "Search the moderation logs",
+    desc_localizations = "modlog_description"
+)]
+pub enum ModlogCommand {
+    #[command(name = "search")]
+    Search(ModlogSearchCommand),
+}
+
+impl_guild_command_handle!(ModlogCommand);
+desc_localizations!(modlog_description);
+
+impl ModlogCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        match self {
+            Self::Search(command) => command.exec(ctx, state).await,
+        }
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "search",
+    desc = "Search the moderation logs with filters",
+    desc_localizations = "modlog_search_description"
+)]
+pub struct ModlogSearchCommand {
+    /// Type of sanction.
+    #[command(rename = "type")]
+    pub kind: Option<ModlogTypeOption>,
+    /// Moderator that issued the sanctions.
+    pub moderator: Option<Id<UserMarker>>,
+    /// User targeted by the sanctions.
+    pub target: Option<Id<UserMarker>>,
+    /// Words contained in the reason.
+    #[command(max_length = 100)]
+    pub contains: Option<String>,
+    /// Only sanctions issued since this date (YYYY-MM-DD).
+    pub since: Option<String>,
+    /// Only sanctions issued until this date (YYYY-MM-DD).
+    pub until: Option<String>,
+}
+
+desc_localizations!(modlog_search_description);
+
+/// Type of sanction.
+#[derive(Debug, Clone, Copy, CommandOption, CreateOption)]
+pub enum ModlogTypeOption {
+    #[option(name = "Ban", value = "ban")]
+    Ban,
+    #[option(name = "Kick", value = "kick")]
+    Kick,
+    #[option(name = "Mute", value = "mute")]
+    Mute,
+    #[option(name = "Unmute", value = "unmute")]
+    Unmute,
+    #[option(name = "Quarantine", value = "quarantine")]
+    Quarantine,
+    #[option(name = "Release", value = "release")]
+    Release,
+}
+
+impl From<ModlogTypeOption> for ModlogType {
+    fn from(kind: ModlogTypeOption) -> Self {
+        match kind {
+            ModlogTypeOption::Ban => ModlogType::Ban,
+            ModlogTypeOption::Kick => ModlogType::Kick,
+            ModlogTypeOption::Mute => ModlogType::Mute,
+            ModlogTypeOption::Unmute => ModlogType::Unmute,
+            ModlogTypeOption::Quarantine => ModlogType::Quarantine,
+            ModlogTypeOption::Release => ModlogType::Release,
+        }
+    }
+}
+
+impl ModlogSearchCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &impl HandlerState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let search = match self.search() {
+            Some(search) => search,
+            None => return Ok(embed::modlog::invalid_date(ctx.lang)),
+        };
+
+        if search.is_empty() {
+            return Ok(embed::modlog::no_filter(ctx.lang));
+        }
+
+        let modlogs = state
+            .modlog_store()
+            .search_modlogs(ctx.guild_id, &search, MAX_SEARCH_RESULTS)
+            .await?;
+        let pages = embed::modlog::search_results(ctx.lang, &modlogs, MAX_SEARCH_RESULTS);
+
+        Paginator::create(pages, ctx.interaction.id, ctx.author.id, state, ctx.lang).await
+    }
+
+    /// Build the search from the command options.
+    ///
+    /// The `until` date is inclusive. Returns [`None`] if a date is invalid.
+    fn search(&self) -> Option<ModlogSearch> {
+        let since = match &self.since {
+            Some(since) => Some(parse_date(since)?.midnight().assume_utc()),
+            None => None,
+        };
+        let until = match &self.until {
+            Some(until) => Some(parse_date(until)?.midnight().assume_utc() + Duration::DAY),
+            None => None,
+        };
+
+        let reason = self
+            .contains
+            .as_deref()
+            .map(str::trim)
+            .filter(|reason| !reason.is_empty());
+
+        Some(ModlogSearch {
+            reason: reason.map(ToOwned::to_owned),
+            kind: self.kind.map(ModlogType::from),
+            moderator: self.moderator,
+            user: self.target,
+            after: since,
+            before: until,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use raidprotect_model::database::model::Modlog;
+    use twilight_model::id::marker::GuildMarker;
+
+    use super::*;
+    use crate::{
+        test_utils::{
+            assert,
+            fixture::{self, GUILD_ID},
+            MockClusterState,
+        },
+        translations::Lang,
+    };
+
+    fn command(since: Option<&str>, until: Option<&str>) -> ModlogSearchCommand {
+        ModlogSearchCommand {
+            kind: Some(ModlogTypeOption::Ban),
+            moderator: Some(Id::new(2)),
+            target: None,
+            contains: Some(" scam ".to_owned()),
+            since: since.map(ToOwned::to_owned),
+            until: until.map(ToOwned::to_owned),
+        }
+    }
+
+    #[test]
+    fn test_search() {
+        let search = command(Some("2021-08-10"), Some("2021-08-10"))
+            .search()
+            .unwrap();
+
+        assert_eq!(search.reason.as_deref(), Some("scam"));
+        assert_eq!(search.kind, Some(ModlogType::Ban));
+        assert_eq!(search.moderator, Some(Id::new(2)));
+        assert_eq!(search.user, None);
+        assert_eq!(
+            search.after.unwrap().unix_timestamp(),
+            1_628_553_600 // 2021-08-10 00:00 UTC
+        );
+        assert_eq!(
+            search.before.unwrap().unix_timestamp(),
+            1_628_640_000 // 2021-08-11 00:00 UTC
+        );
+
+        let search = command(None, None).search().unwrap();
+        assert_eq!((search.after, search.before), (None, None));
+    }
+
+    #[test]
+    fn test_search_empty() {
+        let command = ModlogSearchCommand {
+            kind: None,
+            moderator: None,
+            target: None,
+            contains: Some("  ".to_owned()),
+            since: None,
+            until: None,
+        };
+
+        assert!(command.search().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_search_invalid_date() {
+        assert!(command(Some("yesterday"), None).search().is_none());
+        assert!(command(None, Some("2021-02-30")).search().is_none());
+    }
+
+    fn modlog(guild_id: Id<GuildMarker>, reason: &str) -> Modlog {
+        fixture::modlog(ModlogType::Ban)
+            .guild(guild_id)
+            .reason(reason)
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_exec() {
+        let state = MockClusterState::new();
+        for modlog in [
+            modlog(GUILD_ID, "Scam link"),
+            modlog(GUILD_ID, "spam"),
+            modlog(Id::new(1), "scam"),
+        ] {
+            state.modlogs.create_modlog(&modlog).await.unwrap();
+        }
+
+        let (command, ctx) = fixture::guild_command("search")
+            .option_string("contains", "scam")
+            .parse::<ModlogSearchCommand>();
+        let response = command.exec(ctx, &state).await.unwrap();
+
+        let embed = assert::ephemeral_embed(&response);
+        let description = embed.description.as_deref().unwrap();
+        assert!(description.contains("Scam link"));
+        assert_eq!(description.lines().count(), 1);
+        assert!(state.cache.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_exec_no_filter() {
+        let state = MockClusterState::new();
+        state
+            .modlogs
+            .create_modlog(&modlog(GUILD_ID, "scam"))
+            .await
+            .unwrap();
+
+        let (command, ctx) = fixture::guild_command("search").parse::<ModlogSearchCommand>();
+        let response = command.exec(ctx, &state).await.unwrap();
+
+        let embed = assert::ephemeral_embed(&response);
+        assert_eq!(
+            embed.description.as_deref(),
+            Some(Lang::DEFAULT.modlog_search_no_filter())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_exec_paginated() {
+        let state = MockClusterState::new();
+        for _ in 0..15 {
+            let modlog = modlog(GUILD_ID, "scam");
+            state.modlogs.create_modlog(&modlog).await.unwrap();
+        }
+
+        let (command, ctx) = fixture::guild_command("search")
+            .option_string("contains", "scam")
+            .parse::<ModlogSearchCommand>();
+        let response = command.exec(ctx, &state).await.unwrap();
+
+        let data = assert::raw_data(&response);
+        assert_eq!(data.embeds.as_ref().map(Vec::len), Some(1));
+        assert_eq!(state.cache.len(), 1);
+    }
+}
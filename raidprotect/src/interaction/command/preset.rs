@@ -0,0 +1,93 @@
+//! Preset command.
+//!
+//! This command applies a built-in configuration preset to the guild (see
+//! [`feature::preset`]). The settings changed by the preset are listed before
+//! the administrator confirms.
+//!
+//! [`feature::preset`]: crate::feature::preset
+
+use raidprotect_model::cache::model::interaction::PendingPreset;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::{
+    application::component::{button::ButtonStyle, ActionRow, Button, Component},
+    channel::message::MessageFlags,
+    http::interaction::InteractionResponseType,
+};
+use twilight_util::builder::InteractionResponseDataBuilder;
+
+use crate::{
+    cluster::ClusterState,
+    desc_localizations,
+    feature::preset::{self, Preset},
+    impl_guild_command_handle,
+    interaction::{
+        embed,
+        response::InteractionResponse,
+        util::{CustomId, GuildInteractionContext},
+    },
+};
+
+/// Preset command model.
+///
+/// See the [`module`][self] documentation for more information.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "preset",
+    desc = "Apply a configuration preset",
+    desc_localizations = "preset_description"
+)]
+pub struct PresetCommand {
+    /// Preset to apply.
+    pub name: Preset,
+}
+
+impl_guild_command_handle!(PresetCommand);
+desc_localizations!(preset_description);
+
+impl PresetCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let config = ctx.config(state).await?;
+        let changes = preset::changes(&config, &preset::apply(self.name, &config));
+
+        if changes.is_empty() {
+            return Ok(embed::preset::unchanged(ctx.lang, self.name));
+        }
+
+        // Store the pending preset until it is confirmed.
+        let pending = PendingPreset {
+            interaction_id: ctx.interaction.id,
+            author_id: ctx.author.id,
+            guild_id: ctx.guild_id,
+            preset: self.name.name().to_owned(),
+        };
+        state.cache.set(&pending).await?;
+
+        let embed = embed::preset::confirmation(ctx.lang, self.name, &changes);
+        let custom_id = CustomId::new("preset-confirm", ctx.interaction.id.to_string());
+        let components = Component::ActionRow(ActionRow {
+            components: vec![Component::Button(Button {
+                custom_id: Some(custom_id.to_string()),
+                disabled: false,
+                emoji: None,
+                label: Some(ctx.lang.preset_confirm_button().to_owned()),
+                style: ButtonStyle::Danger,
+                url: None,
+            })],
+        });
+
+        let response = InteractionResponseDataBuilder::new()
+            .embeds([embed])
+            .components([components])
+            .flags(MessageFlags::EPHEMERAL)
+            .build();
+
+        Ok(InteractionResponse::Raw {
+            kind: InteractionResponseType::ChannelMessageWithSource,
+            data: Some(response),
+        })
+    }
+}
@@ -2,30 +2,18 @@
 //!
 //! This command shows basic information about a given user.
 
-use std::time::Duration;
-
-use twilight_interactions::command::{CommandModel, CreateCommand, ResolvedUser};
-use twilight_mention::{
-    timestamp::{Timestamp, TimestampStyle},
-    Mention,
-};
-use twilight_model::application::component::{button::ButtonStyle, ActionRow, Button, Component};
-use twilight_util::{
-    builder::{
-        embed::{EmbedBuilder, EmbedFieldBuilder, EmbedFooterBuilder, ImageSource},
-        InteractionResponseDataBuilder,
-    },
-    snowflake::Snowflake,
+use raidprotect_model::{
+    cache::model::interaction::{PostInChatMessage, PublicMessageTemplate},
+    database::model::ModlogUser,
 };
+use twilight_interactions::command::{CommandModel, CreateCommand, ResolvedUser};
 
 use crate::{
     cluster::ClusterState,
     desc_localizations, impl_command_handle,
     interaction::{
-        component::PostInChat, embed::COLOR_TRANSPARENT, response::InteractionResponse,
-        util::InteractionContext,
+        component::PostInChat, embed, response::InteractionResponse, util::InteractionContext,
     },
-    util::resource::avatar_url,
 };
 
 /// Profile command model.
@@ -33,8 +21,7 @@ use crate::{
 #[command(
     name = "profile",
     desc = "Show information about a user profile",
-    desc_localizations = "profile_description",
-    dm_permission = true
+    desc_localizations = "profile_description"
 )]
 pub struct ProfileCommand {
     /// Mention or ID of the user.
@@ -51,55 +38,28 @@ impl ProfileCommand {
         state: &ClusterState,
     ) -> Result<InteractionResponse, anyhow::Error> {
         let user = self.user.resolved;
+        let template = PublicMessageTemplate::Profile {
+            user: ModlogUser::from(&user),
+            joined_at: self
+                .user
+                .member
+                .map(|member| member.joined_at.as_secs() as u64),
+        };
 
-        let avatar = avatar_url(&user, "jpg", 1024);
-        let mut embed = EmbedBuilder::new()
-            .color(COLOR_TRANSPARENT)
-            .title(ctx.lang.profile_title(user.discriminator(), &user.name))
-            .footer(EmbedFooterBuilder::new(format!("ID: {}", user.id)).build())
-            .thumbnail(ImageSource::url(&avatar)?);
-
-        // User profile creation time.
-        let created_at = Duration::from_millis(user.id.timestamp() as u64).as_secs();
-        let timestamp_long = Timestamp::new(created_at, Some(TimestampStyle::LongDate)).mention();
-        let timestamp_relative =
-            Timestamp::new(created_at, Some(TimestampStyle::RelativeTime)).mention();
-
-        embed = embed.field(EmbedFieldBuilder::new(
-            ctx.lang.profile_created_at(),
-            format!("{timestamp_long} ({timestamp_relative})"),
-        ));
-
-        // Member join date.
-        if let Some(member) = self.user.member {
-            let joined_at = member.joined_at.as_secs();
-            let timestamp_long =
-                Timestamp::new(joined_at as u64, Some(TimestampStyle::LongDate)).mention();
-            let timestamp_relative =
-                Timestamp::new(joined_at as u64, Some(TimestampStyle::RelativeTime)).mention();
-
-            embed = embed.field(EmbedFieldBuilder::new(
-                ctx.lang.profile_joined_at(),
-                format!("{timestamp_long} ({timestamp_relative})"),
-            ));
-        }
-
-        let components = Component::ActionRow(ActionRow {
-            components: vec![Component::Button(Button {
-                custom_id: None,
-                disabled: false,
-                emoji: None,
-                label: Some(ctx.lang.profile_avatar_button().into()),
-                style: ButtonStyle::Link,
-                url: Some(avatar),
-            })],
-        });
-
-        let response = InteractionResponseDataBuilder::new()
-            .embeds([embed.validate()?.build()])
-            .components([components])
-            .build();
+        let response = embed::post_in_chat::render(ctx.public_lang, &template, None)?;
+        let message = PostInChatMessage::Template {
+            case_id: None,
+            template,
+        };
 
-        PostInChat::create(response, ctx.interaction.id, ctx.author.id, state, ctx.lang).await
+        PostInChat::create(
+            response,
+            message,
+            ctx.interaction.id,
+            ctx.author.id,
+            state,
+            ctx.lang,
+        )
+        .await
     }
 }
@@ -0,0 +1,129 @@
+//! Raid mode command.
+//!
+//! This command allows administrators to manually enable or disable the raid
+//! mode. The settings applied by the raid mode are configured with the
+//! `/config raid-mode` command.
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    cluster::ClusterState,
+    desc_localizations,
+    feature::raid_mode,
+    impl_guild_command_handle,
+    interaction::{
+        embed::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+};
+
+/// Raid mode command model.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "raidmode",
+    desc = "Enable or disable the raid mode",
+    desc_localizations = "raid_mode_description"
+)]
+pub enum RaidModeCommand {
+    #[command(name = "enable")]
+    Enable(RaidModeEnableCommand),
+    #[command(name = "disable")]
+    Disable(RaidModeDisableCommand),
+}
+
+impl_guild_command_handle!(RaidModeCommand);
+desc_localizations!(raid_mode_description);
+
+impl RaidModeCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        match self {
+            Self::Enable(command) => command.exec(ctx, state).await,
+            Self::Disable(command) => command.exec(ctx, state).await,
+        }
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "enable",
+    desc = "Enable the raid mode",
+    desc_localizations = "raid_mode_enable_description"
+)]
+pub struct RaidModeEnableCommand;
+
+desc_localizations!(raid_mode_enable_description);
+
+impl RaidModeEnableCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        if config.raid_mode.enabled {
+            let embed = EmbedBuilder::new()
+                .color(COLOR_RED)
+                .title(ctx.lang.raid_mode_already_enabled_title())
+                .description(ctx.lang.raid_mode_already_enabled_description())
+                .build();
+
+            return Ok(InteractionResponse::EphemeralEmbed(embed));
+        }
+
+        raid_mode::enable(state, &mut config, ctx.author.id).await?;
+
+        let embed = EmbedBuilder::new()
+            .color(COLOR_SUCCESS)
+            .title(ctx.lang.raid_mode_enabled_title())
+            .description(ctx.lang.raid_mode_enabled_description())
+            .build();
+
+        Ok(InteractionResponse::EphemeralEmbed(embed))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "disable",
+    desc = "Disable the raid mode",
+    desc_localizations = "raid_mode_disable_description"
+)]
+pub struct RaidModeDisableCommand;
+
+desc_localizations!(raid_mode_disable_description);
+
+impl RaidModeDisableCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let mut config = ctx.config(state).await?;
+
+        if !config.raid_mode.enabled {
+            let embed = EmbedBuilder::new()
+                .color(COLOR_RED)
+                .title(ctx.lang.raid_mode_not_enabled_title())
+                .description(ctx.lang.raid_mode_not_enabled_description())
+                .build();
+
+            return Ok(InteractionResponse::EphemeralEmbed(embed));
+        }
+
+        raid_mode::disable(state, &mut config, ctx.author.id).await?;
+
+        let embed = EmbedBuilder::new()
+            .color(COLOR_SUCCESS)
+            .title(ctx.lang.raid_mode_disabled_title())
+            .description(ctx.lang.raid_mode_disabled_description())
+            .build();
+
+        Ok(InteractionResponse::EphemeralEmbed(embed))
+    }
+}
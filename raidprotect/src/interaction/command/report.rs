@@ -0,0 +1,130 @@
+//! Message report command.
+//!
+//! "Report to moderators" is a message context menu command available to all
+//! members. It opens a modal asking for the reason of the report, which is
+//! then sent to the reports channel of the guild (see [`feature::report`]).
+//!
+//! Members cannot report messages if no reports channel is configured.
+//!
+//! [`feature::report`]: crate::feature::report
+
+use std::mem;
+
+use anyhow::{bail, Context};
+use raidprotect_model::{cache::model::interaction::PendingReport, database::model::ModlogUser};
+use tracing::instrument;
+use twilight_interactions::command::ApplicationCommandData;
+use twilight_model::application::{
+    component::{text_input::TextInputStyle, ActionRow, Component, TextInput},
+    interaction::{Interaction, InteractionData},
+};
+
+use crate::{
+    cluster::ClusterState,
+    desc_localizations,
+    interaction::{
+        embed,
+        response::InteractionResponse,
+        util::{CustomId, GuildInteractionContext},
+    },
+};
+
+/// Message report command.
+///
+/// See the [`module`][self] documentation for more information.
+pub struct ReportCommand;
+
+desc_localizations!(report_name);
+
+impl ReportCommand {
+    /// Name of the command, shown in the context menu.
+    pub const NAME: &'static str = "Report to moderators";
+
+    /// Create the command data.
+    ///
+    /// Context menu commands cannot be derived with [`CreateCommand`], so the
+    /// command data is created manually.
+    ///
+    /// [`CreateCommand`]: twilight_interactions::command::CreateCommand
+    pub fn create_command() -> ApplicationCommandData {
+        let name_localizations = report_name()
+            .into_iter()
+            .map(|(locale, name)| (locale.to_owned(), name.to_owned()))
+            .collect();
+
+        ApplicationCommandData {
+            name: Self::NAME.to_owned(),
+            name_localizations: Some(name_localizations),
+            description: String::new(),
+            description_localizations: None,
+            options: Vec::new(),
+            dm_permission: None,
+            default_member_permissions: None,
+            group: false,
+        }
+    }
+
+    /// Handle the command.
+    ///
+    /// The reported message is stored in the cache, and a modal is shown to
+    /// let the member enter the reason of the report.
+    #[instrument(skip(state))]
+    pub async fn handle(
+        mut interaction: Interaction,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let data = match mem::take(&mut interaction.data) {
+            Some(InteractionData::ApplicationCommand(data)) => *data,
+            _ => bail!("expected application command data"),
+        };
+
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
+        let config = ctx.config(state).await?;
+
+        if config.reports_chan.is_none() {
+            return Ok(embed::report::not_configured(ctx.lang));
+        }
+
+        let message_id = data.target_id.context("missing target message")?.cast();
+        let message = data
+            .resolved
+            .and_then(|mut resolved| resolved.messages.remove(&message_id))
+            .context("missing resolved message")?;
+
+        if message.author.id == ctx.author.id {
+            return Ok(embed::report::own_message(ctx.lang));
+        }
+
+        let pending = PendingReport {
+            interaction_id: ctx.interaction.id,
+            author_id: ctx.author.id,
+            channel_id: message.channel_id,
+            message_id,
+            user: ModlogUser::from(&message.author),
+            content: message.content,
+        };
+
+        let components = vec![Component::ActionRow(ActionRow {
+            components: vec![Component::TextInput(TextInput {
+                custom_id: "reason".to_owned(),
+                label: ctx.lang.report_modal_reason_label().to_owned(),
+                max_length: Some(500),
+                min_length: None,
+                placeholder: Some(ctx.lang.report_modal_reason_placeholder().to_owned()),
+                required: Some(true),
+                style: TextInputStyle::Paragraph,
+                value: None,
+            })],
+        })];
+
+        // Add pending modal in Redis
+        let custom_id = CustomId::new("report", pending.interaction_id.to_string());
+        state.cache.set(&pending).await?;
+
+        Ok(InteractionResponse::Modal {
+            custom_id: custom_id.to_string(),
+            title: ctx.lang.report_modal_title().to_owned(),
+            components,
+        })
+    }
+}
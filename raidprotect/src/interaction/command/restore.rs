@@ -0,0 +1,208 @@
+//! Restore command.
+//!
+//! This command compares the latest structure snapshot of the guild with its
+//! current channels and roles, and recreates the deleted items once the
+//! administrator has confirmed (see [`feature::restore`]).
+//!
+//! [`feature::restore`]: crate::feature::restore
+
+use std::collections::HashSet;
+
+use anyhow::Context;
+use raidprotect_model::cache::model::interaction::{PendingRestore, RestoreKind};
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::{
+    application::component::{button::ButtonStyle, ActionRow, Button, Component},
+    channel::message::MessageFlags,
+    guild::Permissions,
+    http::interaction::InteractionResponseType,
+};
+use twilight_util::builder::InteractionResponseDataBuilder;
+
+use crate::{
+    cluster::ClusterState,
+    desc_localizations,
+    feature::restore::{missing_channels, missing_roles},
+    impl_guild_command_handle,
+    interaction::{
+        embed,
+        response::InteractionResponse,
+        util::{CustomId, GuildInteractionContext},
+    },
+    util::time,
+};
+
+/// Restore command model.
+///
+/// See the [`module`][self] documentation for more information.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "restore",
+    desc = "Recreate the channels or roles deleted since the last snapshot",
+    desc_localizations = "restore_description"
+)]
+pub enum RestoreCommand {
+    #[command(name = "channels")]
+    Channels(RestoreChannelsCommand),
+    #[command(name = "roles")]
+    Roles(RestoreRolesCommand),
+}
+
+impl_guild_command_handle!(RestoreCommand);
+desc_localizations!(restore_description);
+
+impl RestoreCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        match self {
+            Self::Channels(_) => prepare(RestoreKind::Channels, ctx, state).await,
+            Self::Roles(_) => prepare(RestoreKind::Roles, ctx, state).await,
+        }
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "channels",
+    desc = "Recreate the channels deleted since the last snapshot",
+    desc_localizations = "restore_channels_description"
+)]
+pub struct RestoreChannelsCommand;
+
+desc_localizations!(restore_channels_description);
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "roles",
+    desc = "Recreate the roles deleted since the last snapshot",
+    desc_localizations = "restore_roles_description"
+)]
+pub struct RestoreRolesCommand;
+
+desc_localizations!(restore_roles_description);
+
+/// Show the difference with the latest snapshot and ask for confirmation.
+async fn prepare(
+    kind: RestoreKind,
+    ctx: GuildInteractionContext,
+    state: &ClusterState,
+) -> Result<InteractionResponse, anyhow::Error> {
+    let required = match kind {
+        RestoreKind::Channels => Permissions::MANAGE_CHANNELS,
+        RestoreKind::Roles => Permissions::MANAGE_ROLES,
+    };
+
+    let permissions = state.cache.permissions(ctx.guild_id).await?;
+    let bot_permissions = permissions.current_member().await?;
+    if !bot_permissions.guild().contains(required) {
+        return Ok(embed::restore::error(
+            ctx.lang,
+            ctx.lang.bot_missing_permission(),
+        ));
+    }
+
+    let snapshot = match state
+        .database
+        .latest_structure_snapshot(ctx.guild_id)
+        .await?
+    {
+        Some(snapshot) => snapshot,
+        None => {
+            return Ok(embed::restore::error(
+                ctx.lang,
+                ctx.lang.restore_no_snapshot(),
+            ))
+        }
+    };
+    let snapshot_id = snapshot.id.context("missing snapshot id")?;
+
+    // Items deleted since the snapshot, and number of items created since.
+    let (missing, added) = match kind {
+        RestoreKind::Channels => {
+            let channels = state.cache.guild_channels(ctx.guild_id).await?;
+            let current = channels.iter().map(|channel| channel.id).collect();
+            let known = snapshot
+                .channels
+                .iter()
+                .map(|channel| channel.id)
+                .collect::<HashSet<_>>();
+
+            let missing = missing_channels(&snapshot, &current)
+                .into_iter()
+                .map(|channel| channel.name.clone())
+                .collect::<Vec<_>>();
+            let added = channels
+                .iter()
+                .filter(|channel| !channel.is_thread() && !known.contains(&channel.id))
+                .count();
+
+            (missing, added)
+        }
+        RestoreKind::Roles => {
+            let roles = state.cache.guild_roles(ctx.guild_id).await?;
+            let current = roles.iter().map(|role| role.id).collect();
+            let known = snapshot
+                .roles
+                .iter()
+                .map(|role| role.id)
+                .collect::<HashSet<_>>();
+
+            let missing = missing_roles(&snapshot, &current)
+                .into_iter()
+                .map(|role| role.name.clone())
+                .collect::<Vec<_>>();
+            let added = roles
+                .iter()
+                .filter(|role| {
+                    !role.managed && role.id.cast() != ctx.guild_id && !known.contains(&role.id)
+                })
+                .count();
+
+            (missing, added)
+        }
+    };
+
+    if missing.is_empty() {
+        return Ok(embed::restore::error(
+            ctx.lang,
+            ctx.lang.restore_nothing(time::relative(snapshot.date)),
+        ));
+    }
+
+    // Store the pending restore until it is confirmed.
+    let pending = PendingRestore {
+        interaction_id: ctx.interaction.id,
+        author_id: ctx.author.id,
+        guild_id: ctx.guild_id,
+        kind,
+        snapshot_id,
+    };
+    state.cache.set(&pending).await?;
+
+    let embed = embed::restore::confirmation(ctx.lang, kind, snapshot.date, &missing, added);
+    let custom_id = CustomId::new("restore-confirm", ctx.interaction.id.to_string());
+    let components = Component::ActionRow(ActionRow {
+        components: vec![Component::Button(Button {
+            custom_id: Some(custom_id.to_string()),
+            disabled: false,
+            emoji: None,
+            label: Some(ctx.lang.restore_confirm_button().to_owned()),
+            style: ButtonStyle::Danger,
+            url: None,
+        })],
+    });
+
+    let response = InteractionResponseDataBuilder::new()
+        .embeds([embed])
+        .components([components])
+        .flags(MessageFlags::EPHEMERAL)
+        .build();
+
+    Ok(InteractionResponse::Raw {
+        kind: InteractionResponseType::ChannelMessageWithSource,
+        data: Some(response),
+    })
+}
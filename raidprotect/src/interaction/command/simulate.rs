@@ -0,0 +1,130 @@
+//! Simulate command.
+//!
+//! This command evaluates a sample scenario against the current configuration
+//! of the guild, to help administrators tune the protection settings. The
+//! evaluation is purely in-memory (see [`feature::scenario`]) and never
+//! affects the guild members.
+//!
+//! [`feature::scenario`]: crate::feature::scenario
+
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    cluster::ClusterState,
+    desc_localizations,
+    feature::scenario,
+    impl_guild_command_handle,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+};
+
+/// Simulate command model.
+///
+/// See the [`module`][self] documentation for more information.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "simulate",
+    desc = "Check whether a sample scenario would trigger the protections",
+    desc_localizations = "simulate_description"
+)]
+pub enum SimulateCommand {
+    #[command(name = "join-burst")]
+    JoinBurst(SimulateJoinBurstCommand),
+    #[command(name = "reaction-spam")]
+    ReactionSpam(SimulateReactionSpamCommand),
+}
+
+impl_guild_command_handle!(SimulateCommand);
+desc_localizations!(simulate_description);
+
+impl SimulateCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        match self {
+            Self::JoinBurst(command) => command.exec(ctx, state).await,
+            Self::ReactionSpam(command) => command.exec(ctx, state).await,
+        }
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "join-burst",
+    desc = "Simulate many members joining the server at once",
+    desc_localizations = "simulate_join_burst_description"
+)]
+pub struct SimulateJoinBurstCommand {
+    /// Number of members joining the server.
+    #[command(min_value = 1, max_value = 1000)]
+    joins: i64,
+    /// Age of the oldest joining account (in hours).
+    #[command(min_value = 1, max_value = 8760)]
+    max_account_age: i64,
+}
+
+desc_localizations!(simulate_join_burst_description);
+
+impl SimulateJoinBurstCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let config = ctx.config(state).await?;
+
+        // The values are bounded by the command options.
+        let joins = self.joins as u32;
+        let max_account_age = self.max_account_age as u32;
+
+        let outcome = scenario::join_burst(&config, joins, max_account_age);
+
+        Ok(embed::simulate::result(
+            ctx.lang,
+            ctx.lang
+                .simulate_join_burst_scenario(joins, max_account_age),
+            outcome,
+        ))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "reaction-spam",
+    desc = "Simulate a member adding many reactions",
+    desc_localizations = "simulate_reaction_spam_description"
+)]
+pub struct SimulateReactionSpamCommand {
+    /// Number of reactions added by the member.
+    #[command(min_value = 1, max_value = 1000)]
+    reactions: i64,
+    /// Duration during which the reactions are added (in seconds).
+    #[command(min_value = 1, max_value = 3600)]
+    duration: i64,
+}
+
+desc_localizations!(simulate_reaction_spam_description);
+
+impl SimulateReactionSpamCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let config = ctx.config(state).await?;
+
+        // The values are bounded by the command options.
+        let reactions = self.reactions as u32;
+        let duration = self.duration as u32;
+
+        let outcome = scenario::reaction_spam(&config, reactions, duration);
+
+        Ok(embed::simulate::result(
+            ctx.lang,
+            ctx.lang
+                .simulate_reaction_spam_scenario(duration, reactions),
+            outcome,
+        ))
+    }
+}
@@ -0,0 +1,45 @@
+//! Stats command.
+//!
+//! This command shows the statistics of the guild, read from its
+//! [`GuildStats`] document which is updated each time an event occurs.
+//!
+//! [`GuildStats`]: raidprotect_model::database::model::GuildStats
+
+use raidprotect_model::database::model::{epoch_day, GuildStats};
+use time::OffsetDateTime;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    cluster::ClusterState,
+    desc_localizations, impl_guild_command_handle,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+};
+
+/// Stats command model.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "stats",
+    desc = "Show the moderation statistics of the server",
+    desc_localizations = "stats_description"
+)]
+pub struct StatsCommand;
+
+impl_guild_command_handle!(StatsCommand);
+desc_localizations!(stats_description);
+
+impl StatsCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let stats = state
+            .database
+            .get_stats(ctx.guild_id)
+            .await?
+            .unwrap_or_else(|| GuildStats::new(ctx.guild_id));
+        let today = epoch_day(OffsetDateTime::now_utc());
+
+        Ok(embed::stats::stats(ctx.lang, &stats, today))
+    }
+}
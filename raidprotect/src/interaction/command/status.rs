@@ -0,0 +1,40 @@
+//! Status command.
+//!
+//! This command shows the number of servers protected by the bot, aggregated
+//! across all the shards (see the [`presence`] module).
+//!
+//! [`presence`]: crate::presence
+
+use raidprotect_model::cache::model::maintenance::Maintenance;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    cluster::ClusterState,
+    desc_localizations, impl_command_handle,
+    interaction::{embed, response::InteractionResponse, util::InteractionContext},
+};
+
+/// Status command model.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "status",
+    desc = "Show the status of RaidProtect",
+    desc_localizations = "status_description"
+)]
+pub struct StatusCommand;
+
+impl_command_handle!(StatusCommand);
+desc_localizations!(status_description);
+
+impl StatusCommand {
+    async fn exec(
+        self,
+        ctx: InteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let count = state.cache.shard_guild_count(state.shard_count()).await?;
+        let maintenance = Maintenance::is_enabled(&state.cache).await?;
+
+        Ok(embed::status::status(ctx.lang, count, maintenance))
+    }
+}
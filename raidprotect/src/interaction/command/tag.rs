@@ -0,0 +1,404 @@
+//! Tag command.
+//!
+//! Tags are named responses defined by the server staff, such as answers to
+//! frequently asked questions. Any member can send a tag with `/tag send`,
+//! with autocomplete on the tag names.
+//!
+//! The content of a tag may use the following variables (see
+//! [`util::template`]):
+//! - `{user}`: mention of the member given in the `member` option, or of the
+//!   member that sent the tag
+//! - `{author}`: mention of the member that sent the tag
+//! - `{channel}`: mention of the channel where the tag is sent
+//!
+//! Tags are managed by the members that can manage the server, and by the
+//! members with one of the roles configured with `/tag roles`.
+//!
+//! [`util::template`]: crate::util::template
+
+use raidprotect_model::database::model::{Tag, TagsConfig};
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_mention::Mention;
+use twilight_model::{
+    channel::message::AllowedMentions,
+    guild::Permissions,
+    http::interaction::InteractionResponseType,
+    id::{
+        marker::{ChannelMarker, RoleMarker, UserMarker},
+        Id,
+    },
+};
+use twilight_util::builder::InteractionResponseDataBuilder;
+
+use crate::{
+    cluster::ClusterState,
+    database, desc_localizations, impl_guild_command_handle,
+    interaction::{
+        component::Paginator, embed, response::InteractionResponse, util::GuildInteractionContext,
+    },
+    translations::Lang,
+    util::{collection::upsert, template, TextProcessExt},
+};
+
+/// Tag command model.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "tag",
+    desc = "Send or manage the server tags",
+    desc_localizations = "tag_description"
+)]
+pub enum TagCommand {
+    #[command(name = "send")]
+    Send(TagSendCommand),
+    #[command(name = "set")]
+    Set(TagSetCommand),
+    #[command(name = "remove")]
+    Remove(TagRemoveCommand),
+    #[command(name = "list")]
+    List(TagListCommand),
+    #[command(name = "roles")]
+    Roles(TagRolesCommand),
+}
+
+impl_guild_command_handle!(TagCommand);
+desc_localizations!(tag_description);
+
+impl TagCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        match self {
+            Self::Send(command) => command.exec(ctx, state).await,
+            Self::Set(command) => command.exec(ctx, state).await,
+            Self::Remove(command) => command.exec(ctx, state).await,
+            Self::List(command) => command.exec(ctx, state).await,
+            Self::Roles(command) => command.exec(ctx, state).await,
+        }
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "send",
+    desc = "Send a tag in the channel",
+    desc_localizations = "tag_send_description"
+)]
+pub struct TagSendCommand {
+    /// Name of the tag.
+    #[command(autocomplete = true, max_length = 32)]
+    pub name: String,
+    /// Member mentioned in the tag.
+    pub member: Option<Id<UserMarker>>,
+}
+
+desc_localizations!(tag_send_description);
+
+impl TagSendCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let config = ctx.config(state).await?;
+        let channel = ctx.interaction.channel_id;
+
+        Ok(send_tag(
+            ctx.lang,
+            &config.tags,
+            &self.name,
+            ctx.author.id,
+            self.member,
+            channel,
+        ))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "set",
+    desc = "Create or update a tag",
+    desc_localizations = "tag_set_description"
+)]
+pub struct TagSetCommand {
+    /// Name of the tag.
+    #[command(autocomplete = true, max_length = 32)]
+    pub name: String,
+    /// Content of the tag. Variables: {user}, {author} and {channel}.
+    #[command(max_length = 2000)]
+    pub content: String,
+}
+
+desc_localizations!(tag_set_description);
+
+impl TagSetCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        if let Some(response) = ctx.check_partial() {
+            return Ok(response);
+        }
+
+        let mut config = ctx.config(state).await?;
+
+        if !can_manage(&ctx, &config.tags) {
+            return Ok(embed::tag::not_manager(ctx.lang));
+        }
+
+        let name = self.name.trim();
+        let content = self.content.trim();
+
+        if name.is_empty() || name.chars().count() > Tag::MAX_NAME_LENGTH {
+            return Ok(embed::tag::invalid_name(ctx.lang));
+        }
+
+        if content.chars().count() > Tag::MAX_CONTENT_LENGTH {
+            return Ok(embed::tag::too_long(ctx.lang));
+        }
+
+        let tag = Tag {
+            name: name.to_owned(),
+            content: content.to_owned(),
+        };
+        let tags = &mut config.tags.tags;
+        let same_name = |t: &Tag| t.name.eq_ignore_ascii_case(name);
+
+        if upsert(tags, TagsConfig::MAX_TAGS_LEN, tag, same_name).is_err() {
+            return Ok(embed::tag::too_many(ctx.lang));
+        }
+
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::tag::set(ctx.lang, name))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "remove",
+    desc = "Remove a tag",
+    desc_localizations = "tag_remove_description"
+)]
+pub struct TagRemoveCommand {
+    /// Name of the tag.
+    #[command(autocomplete = true, max_length = 32)]
+    pub name: String,
+}
+
+desc_localizations!(tag_remove_description);
+
+impl TagRemoveCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        if let Some(response) = ctx.check_partial() {
+            return Ok(response);
+        }
+
+        let mut config = ctx.config(state).await?;
+
+        if !can_manage(&ctx, &config.tags) {
+            return Ok(embed::tag::not_manager(ctx.lang));
+        }
+
+        let name = match config.tags.tag(&self.name) {
+            Some(tag) => tag.name.clone(),
+            None => return Ok(embed::tag::not_found(ctx.lang, &self.name)),
+        };
+
+        config.tags.tags.retain(|tag| tag.name != name);
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::tag::removed(ctx.lang, &name))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "list",
+    desc = "List the server tags",
+    desc_localizations = "tag_list_description"
+)]
+pub struct TagListCommand;
+
+desc_localizations!(tag_list_description);
+
+impl TagListCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let config = ctx.config(state).await?;
+        let pages = embed::tag::list(ctx.lang, &config.tags.tags);
+
+        Paginator::create(pages, ctx.interaction.id, ctx.author.id, state, ctx.lang).await
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "roles",
+    desc = "Add or remove a role allowed to manage the tags",
+    desc_localizations = "tag_roles_description"
+)]
+pub struct TagRolesCommand {
+    /// Role to add, or to remove if it is already allowed.
+    pub role: Id<RoleMarker>,
+}
+
+desc_localizations!(tag_roles_description);
+
+impl TagRolesCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        if let Some(response) = ctx.check_partial() {
+            return Ok(response);
+        }
+
+        // Only members that can manage the server can change the managers.
+        if !has_manage_guild(&ctx) {
+            return Ok(embed::error::missing_permissions(ctx.lang));
+        }
+
+        let mut config = ctx.config(state).await?;
+        let roles = &mut config.tags.manager_roles;
+
+        if roles.contains(&self.role) {
+            roles.retain(|role| *role != self.role);
+        } else if roles.len() >= TagsConfig::MAX_MANAGER_ROLES_LEN {
+            return Ok(embed::tag::too_many_roles(ctx.lang));
+        } else {
+            roles.push(self.role);
+        }
+
+        database::update_guild_config(state, &config).await?;
+
+        Ok(embed::tag::roles_updated(ctx.lang, &config.tags))
+    }
+}
+
+/// Response of the `/tag send` command.
+///
+/// The tag content is rendered with the variables described in the
+/// [module](self) documentation. Only the mentioned member is pinged.
+fn send_tag(
+    lang: Lang,
+    tags: &TagsConfig,
+    name: &str,
+    author: Id<UserMarker>,
+    member: Option<Id<UserMarker>>,
+    channel: Option<Id<ChannelMarker>>,
+) -> InteractionResponse {
+    let tag = match tags.tag(name) {
+        Some(tag) => tag,
+        None => return embed::tag::not_found(lang, name),
+    };
+
+    let user = member.unwrap_or(author).mention().to_string();
+    let author_mention = author.mention().to_string();
+    let channel = channel.map(|channel| channel.mention().to_string());
+
+    let rendered = template::render(&tag.content, |variable| match variable {
+        "user" => Some(user.as_str()),
+        "author" => Some(author_mention.as_str()),
+        "channel" => channel.as_deref(),
+        _ => None,
+    });
+
+    let allowed_mentions = AllowedMentions {
+        users: member.into_iter().collect(),
+        ..Default::default()
+    };
+    let response = InteractionResponseDataBuilder::new()
+        .content(rendered.text.max_len(Tag::MAX_CONTENT_LENGTH))
+        .allowed_mentions(allowed_mentions)
+        .build();
+
+    InteractionResponse::Raw {
+        kind: InteractionResponseType::ChannelMessageWithSource,
+        data: Some(response),
+    }
+}
+
+/// Whether the member is allowed to manage the tags.
+fn can_manage(ctx: &GuildInteractionContext, config: &TagsConfig) -> bool {
+    has_manage_guild(ctx) || config.can_manage(&ctx.member.roles)
+}
+
+/// Whether the member can manage the server.
+fn has_manage_guild(ctx: &GuildInteractionContext) -> bool {
+    ctx.member.permissions.map_or(false, |permissions| {
+        permissions.contains(Permissions::MANAGE_GUILD)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::http::interaction::InteractionResponseData;
+
+    use super::*;
+
+    fn tags() -> TagsConfig {
+        TagsConfig {
+            tags: vec![Tag {
+                name: "rules".to_owned(),
+                content: "Hello {user}, please read the rules in {channel}. {unknown}".to_owned(),
+            }],
+            manager_roles: Vec::new(),
+        }
+    }
+
+    fn response_data(response: InteractionResponse) -> InteractionResponseData {
+        match response {
+            InteractionResponse::Raw {
+                kind: InteractionResponseType::ChannelMessageWithSource,
+                data: Some(data),
+            } => data,
+            other => panic!("expected message response, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_send_tag() {
+        let response = send_tag(
+            Lang::En,
+            &tags(),
+            "Rules",
+            Id::new(1),
+            Some(Id::new(2)),
+            Some(Id::new(3)),
+        );
+        let data = response_data(response);
+
+        assert_eq!(
+            data.content.as_deref(),
+            Some("Hello <@2>, please read the rules in <#3>. {unknown}")
+        );
+        assert_eq!(data.allowed_mentions.unwrap().users, vec![Id::new(2)]);
+    }
+
+    #[test]
+    fn test_send_tag_author() {
+        let response = send_tag(Lang::En, &tags(), "rules", Id::new(1), None, None);
+        let data = response_data(response);
+
+        assert!(data.content.unwrap().starts_with("Hello <@1>,"));
+        assert!(data.allowed_mentions.unwrap().users.is_empty());
+    }
+
+    #[test]
+    fn test_send_tag_missing() {
+        let response = send_tag(Lang::En, &tags(), "faq", Id::new(1), None, None);
+
+        assert!(matches!(response, InteractionResponse::EphemeralEmbed(_)));
+    }
+}
@@ -0,0 +1,239 @@
+//! User info command.
+//!
+//! This command shows a summary of a user for moderators: account age, join
+//! date, roles, moderation logs and notes. The last-known data is shown for
+//! users that left the guild (see [`MemberRecord`]), and only the account
+//! information is shown for users that never joined.
+//!
+//! Risk signals such as a recently created account are highlighted.
+
+use futures_util::TryStreamExt;
+use raidprotect_model::{
+    cache::model::member::MemberRecord,
+    database::{
+        model::{Modlog, ModlogType},
+        DbClient,
+    },
+};
+use twilight_interactions::command::{CommandModel, CreateCommand, ResolvedUser};
+use twilight_mention::Mention;
+use twilight_model::{
+    id::{marker::RoleMarker, Id},
+    user::User,
+};
+use twilight_util::builder::embed::{EmbedFieldBuilder, EmbedFooterBuilder, ImageSource};
+
+use crate::{
+    cluster::ClusterState,
+    desc_localizations, impl_guild_command_handle,
+    interaction::{
+        embed::{EmbedBuilder, COLOR_RED, COLOR_TRANSPARENT},
+        response::InteractionResponse,
+        util::GuildInteractionContext,
+    },
+    translations::Lang,
+    util::{
+        account::{account_created_at, new_account_marker},
+        resource::avatar_url,
+        time, TextProcessExt,
+    },
+};
+
+/// Maximum number of notes displayed.
+///
+/// Each note is truncated to 150 characters to fit in the embed field.
+const MAX_NOTES: usize = 5;
+
+/// User info command model.
+///
+/// See the [`module`][self] documentation for more information.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "userinfo",
+    desc = "Show moderation information about a user",
+    desc_localizations = "userinfo_description"
+)]
+pub struct UserInfoCommand {
+    /// Mention or ID of the user.
+    pub user: ResolvedUser,
+}
+
+impl_guild_command_handle!(UserInfoCommand);
+desc_localizations!(userinfo_description);
+
+impl UserInfoCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let user = self.user.resolved;
+        let lang = ctx.lang;
+
+        let avatar = avatar_url(&user, "jpg", 1024);
+        let mut embed = EmbedBuilder::new()
+            .color(COLOR_TRANSPARENT)
+            .title(lang.profile_title(user.discriminator(), &user.name))
+            .required_footer(EmbedFooterBuilder::new(format!("ID: {}", user.id)))?
+            .thumbnail(ImageSource::url(&avatar)?);
+
+        // Account creation date and risk signals.
+        let config = ctx.config(state).await?;
+        let created_at = account_created_at(user.id);
+        embed = embed.field(EmbedFieldBuilder::new(
+            lang.profile_created_at(),
+            time::long_date_relative(time::from_secs(created_at)),
+        ));
+
+        let risks = risk_signals(&user, created_at, config.new_account_threshold, lang);
+        if !risks.is_empty() {
+            embed = embed.color(COLOR_RED).field(EmbedFieldBuilder::new(
+                lang.userinfo_risks(),
+                risks.join("\n"),
+            ));
+        }
+
+        // Member information, or last-known information if the user left.
+        let record = match self.user.member {
+            Some(member) => Some((member.joined_at, member.roles, None)),
+            None => state
+                .cache
+                .get::<MemberRecord>(&(ctx.guild_id, user.id))
+                .await?
+                .map(|record| (record.joined_at, record.roles, record.left_at)),
+        };
+
+        match record {
+            Some((joined_at, roles, left_at)) => {
+                embed = embed.field(EmbedFieldBuilder::new(
+                    lang.profile_joined_at(),
+                    time::long_date_relative(time::from_discord(joined_at)),
+                ));
+
+                if let Some(left_at) = left_at {
+                    embed = embed.field(EmbedFieldBuilder::new(
+                        lang.userinfo_left_at(),
+                        time::long_date_relative(time::from_discord(left_at)),
+                    ));
+                }
+
+                embed = embed.field(EmbedFieldBuilder::new(
+                    lang.userinfo_roles(),
+                    format_roles(&roles, lang),
+                ));
+            }
+            None => {
+                embed = embed.description(lang.userinfo_not_member());
+            }
+        }
+
+        // Moderation logs of the user.
+        match user_modlogs(state, &ctx, &user).await? {
+            Some(modlogs) => {
+                embed = embed
+                    .field(EmbedFieldBuilder::new(
+                        lang.userinfo_sanctions(),
+                        format_sanctions(&modlogs, lang),
+                    ))
+                    .field(EmbedFieldBuilder::new(
+                        lang.userinfo_notes(),
+                        format_notes(&modlogs, lang),
+                    ));
+            }
+            None => {
+                embed = embed.field(EmbedFieldBuilder::new(
+                    lang.userinfo_sanctions(),
+                    lang.userinfo_sanctions_unavailable(),
+                ));
+            }
+        }
+
+        Ok(InteractionResponse::EphemeralEmbed(embed.build()))
+    }
+}
+
+/// Get the moderation logs of a user.
+///
+/// Returns [`None`] if the database is unavailable.
+async fn user_modlogs(
+    state: &ClusterState,
+    ctx: &GuildInteractionContext,
+    user: &User,
+) -> Result<Option<Vec<Modlog>>, anyhow::Error> {
+    let cursor = match state
+        .database
+        .find_modlogs(ctx.guild_id, Some(user.id))
+        .await
+    {
+        Ok(cursor) => cursor,
+        Err(error) if DbClient::is_unavailable(&error) => return Ok(None),
+        Err(error) => return Err(error),
+    };
+
+    Ok(Some(cursor.try_collect().await?))
+}
+
+/// Get the risk signals of a user account.
+fn risk_signals(user: &User, created_at: u64, threshold: u32, lang: Lang) -> Vec<String> {
+    let mut risks = Vec::new();
+
+    if let Some(marker) = new_account_marker(created_at, threshold, lang) {
+        risks.push(marker);
+    }
+
+    if user.avatar.is_none() {
+        risks.push(lang.userinfo_risk_no_avatar().to_owned());
+    }
+
+    risks
+}
+
+/// Format the roles of a member.
+fn format_roles(roles: &[Id<RoleMarker>], lang: Lang) -> String {
+    if roles.is_empty() {
+        return lang.userinfo_no_roles().to_owned();
+    }
+
+    roles
+        .iter()
+        .map(|role| role.mention().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Format the number of sanctions of each type.
+fn format_sanctions(modlogs: &[Modlog], lang: Lang) -> String {
+    if modlogs.is_empty() {
+        return lang.userinfo_no_sanctions().to_owned();
+    }
+
+    let count = |kind| modlogs.iter().filter(|modlog| modlog.kind == kind).count();
+
+    lang.userinfo_sanctions_count(count(ModlogType::Kick), count(ModlogType::Mute))
+}
+
+/// Format the most recent notes of the moderation logs.
+fn format_notes(modlogs: &[Modlog], lang: Lang) -> String {
+    let mut modlogs = modlogs
+        .iter()
+        .filter(|modlog| modlog.notes.is_some())
+        .collect::<Vec<_>>();
+
+    if modlogs.is_empty() {
+        return lang.userinfo_no_notes().to_owned();
+    }
+
+    modlogs.sort_by_key(|modlog| std::cmp::Reverse(modlog.date));
+
+    modlogs
+        .into_iter()
+        .take(MAX_NOTES)
+        .filter_map(|modlog| {
+            let note = modlog.notes.as_deref()?;
+            let date = time::short_date(modlog.date);
+
+            Some(format!("{date}: {}", note.max_len(150)))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,149 @@
+//! Watchlist command.
+//!
+//! This command allows moderators to watch the activity of borderline users.
+//! The activity of watched users is notified in the watch channel (see the
+//! [`watch`] feature module), and watches expire after the duration
+//! configured with the `/config logs watch` command.
+
+use twilight_interactions::command::{CommandModel, CreateCommand, ResolvedUser};
+
+use crate::{
+    cluster::ClusterState,
+    desc_localizations,
+    feature::watch::{self, MAX_WATCHED_USERS},
+    impl_guild_command_handle,
+    interaction::{
+        component::Paginator, embed, response::InteractionResponse, util::GuildInteractionContext,
+    },
+};
+
+/// Watch command model.
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "watch",
+    desc = "Manage the watched users",
+    desc_localizations = "watch_description"
+)]
+pub enum WatchCommand {
+    #[command(name = "add")]
+    Add(WatchAddCommand),
+    #[command(name = "remove")]
+    Remove(WatchRemoveCommand),
+    #[command(name = "list")]
+    List(WatchListCommand),
+}
+
+impl_guild_command_handle!(WatchCommand);
+desc_localizations!(watch_description);
+
+impl WatchCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        match self {
+            Self::Add(command) => command.exec(ctx, state).await,
+            Self::Remove(command) => command.exec(ctx, state).await,
+            Self::List(command) => command.exec(ctx, state).await,
+        }
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "add",
+    desc = "Watch the activity of a user",
+    desc_localizations = "watch_add_description"
+)]
+pub struct WatchAddCommand {
+    /// Mention or ID of the user.
+    pub user: ResolvedUser,
+    /// Note shown in the notifications.
+    #[command(max_length = 200)]
+    pub note: Option<String>,
+}
+
+desc_localizations!(watch_add_description);
+
+impl WatchAddCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let config = ctx.config(state).await?;
+        let user_id = self.user.resolved.id;
+        let current = state.database.get_watch(ctx.guild_id, user_id).await?;
+
+        // Users already watched can be added again to extend the watch.
+        if current.is_none() {
+            let count = state.database.count_watches(ctx.guild_id).await?;
+
+            if count >= MAX_WATCHED_USERS {
+                return Ok(embed::watch::too_many(ctx.lang));
+            }
+        }
+
+        let note = self
+            .note
+            .map(|note| note.trim().to_owned())
+            .filter(|note| !note.is_empty());
+        let entry = watch::add(state, &config, current, user_id, ctx.author.id, note).await?;
+
+        Ok(embed::watch::added(ctx.lang, user_id, entry.expires_at))
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "remove",
+    desc = "Stop watching a user",
+    desc_localizations = "watch_remove_description"
+)]
+pub struct WatchRemoveCommand {
+    /// Mention or ID of the user.
+    pub user: ResolvedUser,
+}
+
+desc_localizations!(watch_remove_description);
+
+impl WatchRemoveCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let user_id = self.user.resolved.id;
+
+        // The expiration job does nothing once the watch is deleted.
+        if state.database.delete_watch(ctx.guild_id, user_id).await? {
+            Ok(embed::watch::removed(ctx.lang, user_id))
+        } else {
+            Ok(embed::watch::not_watched(ctx.lang, user_id))
+        }
+    }
+}
+
+#[derive(Debug, Clone, CommandModel, CreateCommand)]
+#[command(
+    name = "list",
+    desc = "Show the watched users",
+    desc_localizations = "watch_list_description"
+)]
+pub struct WatchListCommand;
+
+desc_localizations!(watch_list_description);
+
+impl WatchListCommand {
+    async fn exec(
+        self,
+        ctx: GuildInteractionContext,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let entries = state.database.find_watches(ctx.guild_id).await?;
+        let pages = embed::watch::list(ctx.lang, &entries);
+
+        Paginator::create(pages, ctx.interaction.id, ctx.author.id, state, ctx.lang).await
+    }
+}
@@ -0,0 +1,49 @@
+//! AutoMod rules overwrite button.
+
+use raidprotect_model::cache::model::interaction::PendingAutomodOverwrite;
+use twilight_model::{
+    application::interaction::Interaction,
+    http::interaction::{InteractionResponseData, InteractionResponseType},
+};
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    feature::automod,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+};
+
+/// AutoMod rules overwrite button.
+pub struct AutomodOverwrite;
+
+impl AutomodOverwrite {
+    /// Handle the overwrite button click.
+    ///
+    /// The native rules are synchronized again, overwriting the rules edited
+    /// manually. The configuration is loaded again since it may have been
+    /// updated after the confirmation was sent.
+    pub async fn handle(
+        interaction: Interaction,
+        pending: PendingAutomodOverwrite,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
+
+        if !state.cache.consume(&pending).await? {
+            return Ok(embed::error::already_handled(ctx.lang));
+        }
+
+        let mut config = ctx.config(state).await?;
+        let report = automod::sync(state, &mut config, true).await?;
+        database::update_guild_config(state, &config).await?;
+
+        Ok(InteractionResponse::Raw {
+            kind: InteractionResponseType::UpdateMessage,
+            data: Some(InteractionResponseData {
+                components: Some(Vec::new()),
+                embeds: Some(vec![embed::automod::overwritten(ctx.lang, &report)]),
+                ..Default::default()
+            }),
+        })
+    }
+}
@@ -0,0 +1,112 @@
+//! Bulk unban confirmation and cancel buttons.
+
+use anyhow::Context;
+use raidprotect_model::cache::model::interaction::PendingBulkUnban;
+use twilight_model::{
+    application::{
+        component::{button::ButtonStyle, ActionRow, Button, Component},
+        interaction::Interaction,
+    },
+    http::interaction::{InteractionResponseData, InteractionResponseType},
+    id::Id,
+};
+
+use crate::{
+    cluster::ClusterState,
+    feature::bulk_unban,
+    interaction::{
+        embed,
+        response::InteractionResponse,
+        util::{CustomId, GuildInteractionContext},
+    },
+};
+
+/// Bulk unban confirmation button.
+pub struct BulkUnbanConfirm;
+
+impl BulkUnbanConfirm {
+    /// Handle the confirmation button click.
+    ///
+    /// The pending bulk unban is consumed to prevent it from being executed
+    /// twice, and the unbans are executed in the background. The confirmation
+    /// message is updated with the progress and a button to cancel the bulk
+    /// unban.
+    pub async fn handle(
+        interaction: Interaction,
+        pending: PendingBulkUnban,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
+
+        if !state.cache.consume(&pending).await? {
+            return Ok(embed::error::already_handled(ctx.lang));
+        }
+
+        let total = pending.user_ids.len();
+        let custom_id = CustomId::new("bulk-unban-cancel", pending.interaction_id.to_string());
+        let cancel = state.bulk_tasks.register(pending.interaction_id);
+
+        tokio::spawn(bulk_unban::run(
+            state.clone(),
+            pending,
+            ctx.responder(),
+            cancel,
+            ctx.lang,
+        ));
+
+        let components = Component::ActionRow(ActionRow {
+            components: vec![Component::Button(Button {
+                custom_id: Some(custom_id.to_string()),
+                disabled: false,
+                emoji: None,
+                label: Some(ctx.lang.bulk_unban_cancel_button().to_owned()),
+                style: ButtonStyle::Secondary,
+                url: None,
+            })],
+        });
+
+        Ok(InteractionResponse::Raw {
+            kind: InteractionResponseType::UpdateMessage,
+            data: Some(InteractionResponseData {
+                components: Some(vec![components]),
+                embeds: Some(vec![embed::bulk_unban::progress(ctx.lang, 0, total)]),
+                ..Default::default()
+            }),
+        })
+    }
+}
+
+/// Bulk unban cancel button.
+pub struct BulkUnbanCancel;
+
+impl BulkUnbanCancel {
+    /// Handle the cancel button click.
+    ///
+    /// The button is only shown in the ephemeral response of the moderator
+    /// that started the bulk unban. The unbans already sent are not reverted.
+    pub async fn handle(
+        interaction: Interaction,
+        id: &str,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
+        let id = id
+            .parse()
+            .ok()
+            .and_then(Id::new_checked)
+            .context("invalid bulk unban id")?;
+
+        if !state.bulk_tasks.cancel(id) {
+            return Ok(embed::error::already_handled(ctx.lang));
+        }
+
+        Ok(InteractionResponse::Raw {
+            kind: InteractionResponseType::UpdateMessage,
+            data: Some(InteractionResponseData {
+                components: Some(Vec::new()),
+                embeds: Some(vec![embed::bulk_unban::cancelling(ctx.lang)]),
+                ..Default::default()
+            }),
+        })
+    }
+}
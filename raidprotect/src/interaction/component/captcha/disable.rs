@@ -1,26 +1,23 @@
 //! Captcha disable button.
 
+use raidprotect_model::database::model::{GuildConfig, LogCategory};
 use tracing::{error, warn};
 use twilight_http::request::AuditLogReason;
 use twilight_mention::Mention;
 use twilight_model::{
     application::interaction::Interaction,
-    id::{
-        marker::{ChannelMarker, GuildMarker, UserMarker},
-        Id,
-    },
+    id::{marker::UserMarker, Id},
 };
-use twilight_util::builder::embed::EmbedBuilder;
 
 use crate::{
     cluster::ClusterState,
+    database,
     interaction::{
-        embed::{self, COLOR_RED, COLOR_SUCCESS},
+        embed::{self, EmbedBuilder, COLOR_RED, COLOR_SUCCESS},
         response::InteractionResponse,
         util::{GuildConfigExt, GuildInteractionContext},
     },
-    translations::Lang,
-    util::guild_logs_channel,
+    util::logs,
 };
 
 /// Captcha disable button.
@@ -37,7 +34,7 @@ impl CaptchaDisable {
         interaction: Interaction,
         state: &ClusterState,
     ) -> Result<InteractionResponse, anyhow::Error> {
-        let ctx = GuildInteractionContext::new(interaction)?;
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
         let mut config = ctx.config(state).await?;
         let guild_lang = config.lang();
 
@@ -73,20 +70,12 @@ impl CaptchaDisable {
 
         // Update the configuration.
         config.captcha = Default::default();
-        state.database.update_guild(&config).await?;
+        database::update_guild_config(state, &config).await?;
 
         // Send message in logs channel.
         let state_clone = state.clone();
         tokio::spawn(async move {
-            if let Err(error) = logs_message(
-                &state_clone,
-                ctx.guild_id,
-                config.logs_chan,
-                ctx.author.id,
-                guild_lang,
-            )
-            .await
-            {
+            if let Err(error) = logs_message(&state_clone, &config, ctx.author.id).await {
                 error!(error = ?error, guild = ?ctx.guild_id, "failed to send captcha disable logs message");
             }
         });
@@ -104,24 +93,14 @@ impl CaptchaDisable {
 
 async fn logs_message(
     state: &ClusterState,
-    guild: Id<GuildMarker>,
-    logs_channel: Option<Id<ChannelMarker>>,
+    config: &GuildConfig,
     user: Id<UserMarker>,
-    lang: Lang,
 ) -> Result<(), anyhow::Error> {
-    let channel = guild_logs_channel(state, guild, logs_channel, lang).await?;
-
+    let lang = config.lang();
     let embed = EmbedBuilder::new()
         .color(COLOR_RED)
         .description(lang.captcha_disabled_log(user.mention()))
         .build();
 
-    state
-        .http
-        .create_message(channel)
-        .embeds(&[embed])?
-        .exec()
-        .await?;
-
-    Ok(())
+    logs::post(state, config, LogCategory::Config, embed).await
 }
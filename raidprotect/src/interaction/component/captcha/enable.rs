@@ -3,7 +3,10 @@
 use std::time::Duration;
 
 use anyhow::Context;
-use raidprotect_model::cache::discord::{CachedChannel, CachedGuild};
+use raidprotect_model::{
+    cache::discord::{CachedChannel, CachedGuild},
+    database::model::{GuildConfig, LogCategory},
+};
 use tracing::{debug, error, trace};
 use twilight_http::request::AuditLogReason;
 use twilight_mention::Mention;
@@ -26,17 +29,18 @@ use twilight_model::{
         Id,
     },
 };
-use twilight_util::builder::embed::{EmbedBuilder, EmbedFieldBuilder};
+use twilight_util::builder::embed::EmbedFieldBuilder;
 
 use crate::{
     cluster::ClusterState,
+    database,
     interaction::{
-        embed::{self, COLOR_RED, COLOR_SUCCESS},
+        embed::{self, EmbedBuilder, COLOR_RED, COLOR_SUCCESS},
         response::InteractionResponse,
         util::{CustomId, GuildConfigExt, GuildInteractionContext},
     },
     translations::Lang,
-    util::{guild_logs_channel, TextProcessExt},
+    util::{logs, TextProcessExt},
 };
 
 /// Captcha enabling button.
@@ -57,7 +61,7 @@ impl CaptchaEnable {
         interaction: Interaction,
         state: &ClusterState,
     ) -> Result<InteractionResponse, anyhow::Error> {
-        let ctx = GuildInteractionContext::new(interaction)?;
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
         let mut config = ctx.config(state).await?;
         let guild_lang = config.lang();
 
@@ -169,7 +173,7 @@ impl CaptchaEnable {
             Err(err) => {
                 error!(error = ?err, "failed to send the verification message");
 
-                return Ok(embed::error::internal_error(ctx.lang));
+                return Ok(embed::error::internal_error(ctx.lang, ctx.interaction.id));
             }
         };
 
@@ -179,7 +183,7 @@ impl CaptchaEnable {
         config.captcha.message = Some(message.id);
         config.captcha.role = Some(unverified_role.id);
 
-        state.database.update_guild(&config).await?;
+        database::update_guild_config(state, &config).await?;
 
         // Start the configuration of channels permissions.
         let state_clone = state.clone();
@@ -199,15 +203,7 @@ impl CaptchaEnable {
         // Send message in logs channel.
         let state_clone = state.clone();
         tokio::spawn(async move {
-            if let Err(error) = logs_message(
-                &state_clone,
-                ctx.guild_id,
-                config.logs_chan,
-                ctx.author.id,
-                guild_lang,
-            )
-            .await
-            {
+            if let Err(error) = logs_message(&state_clone, &config, ctx.author.id).await {
                 error!(error = ?error, guild = ?ctx.guild_id, "failed to send captcha enable logs message");
             }
         });
@@ -281,26 +277,16 @@ pub async fn verification_message(
 /// enabled.
 async fn logs_message(
     state: &ClusterState,
-    guild: Id<GuildMarker>,
-    logs_channel: Option<Id<ChannelMarker>>,
+    config: &GuildConfig,
     user: Id<UserMarker>,
-    lang: Lang,
 ) -> Result<(), anyhow::Error> {
-    let channel = guild_logs_channel(state, guild, logs_channel, lang).await?;
-
+    let lang = config.lang();
     let embed = EmbedBuilder::new()
         .color(COLOR_RED)
         .description(lang.captcha_enabled_log(user.mention()))
         .build();
 
-    state
-        .http
-        .create_message(channel)
-        .embeds(&[embed])?
-        .exec()
-        .await?;
-
-    Ok(())
+    logs::post(state, config, LogCategory::Config, embed).await
 }
 
 /// Configure the permissions of the guild channels.
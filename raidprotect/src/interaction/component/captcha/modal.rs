@@ -4,9 +4,12 @@ use std::time::Duration;
 
 use anyhow::{bail, Context};
 use raidprotect_model::{
-    cache::discord::{
-        permission::{CachePermissions, RoleOrdering},
-        CachedRole,
+    cache::{
+        discord::{
+            permission::{CachePermissions, RoleOrdering},
+            CachedRole,
+        },
+        model::interaction::{ChallengeKind, PendingCaptcha},
     },
     database::model::GuildConfig,
 };
@@ -19,13 +22,12 @@ use twilight_model::{
         Id,
     },
 };
-use twilight_util::builder::embed::EmbedBuilder;
 
-use super::verify::{get_captcha, kick_after};
+use super::verify::kick_after;
 use crate::{
     cluster::ClusterState,
     interaction::{
-        embed,
+        embed::{self, EmbedBuilder},
         response::InteractionResponse,
         util::{
             parse_modal_data, parse_modal_field_required, GuildConfigExt, GuildInteractionContext,
@@ -39,27 +41,23 @@ use crate::{
 pub struct CaptchaModal;
 
 impl CaptchaModal {
+    /// Handle the modal submit.
+    ///
+    /// The pending captcha is retrieved and authorized by the dispatcher.
     #[instrument(skip(state))]
     pub async fn handle(
         mut interaction: Interaction,
+        captcha: PendingCaptcha,
         state: &ClusterState,
     ) -> Result<InteractionResponse, anyhow::Error> {
         let data = parse_modal_data(&mut interaction)?;
-        let ctx = GuildInteractionContext::new(interaction)?;
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
         let config = ctx.config(state).await?;
 
-        // Get the pending captcha from the cache.
-        let captcha = match get_captcha(&ctx, state).await? {
-            Some(captcha) => captcha,
-            None => {
-                return Ok(embed::captcha::captcha_not_found(ctx.lang));
-            }
-        };
-
         // Check if the entered code is correct.
         let code = parse_modal_field_required(&data, "captcha-input")?;
 
-        if !validate_code(code, &captcha.code) {
+        if !validate_answer(captcha.kind, code, &captcha.code) {
             let state_clone = state.clone();
             tokio::spawn(async move {
                 kick_after(&state_clone, ctx.guild_id, ctx.author.id, config.lang()).await
@@ -175,6 +173,17 @@ async fn check_role_permission(
     true
 }
 
+/// Validate the answer to a captcha challenge.
+///
+/// Arithmetic answers must be exact, while image codes accept one error (see
+/// [`validate_code`]).
+fn validate_answer(kind: ChallengeKind, answer: &str, expected: &str) -> bool {
+    match kind {
+        ChallengeKind::Image => validate_code(answer, expected),
+        ChallengeKind::Arithmetic => answer.trim() == expected,
+    }
+}
+
 /// Validate the captcha code, accepting at most one error.
 fn validate_code(a: &str, b: &str) -> bool {
     let mut errors: u8 = 0;
@@ -190,7 +199,7 @@ fn validate_code(a: &str, b: &str) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::validate_code;
+    use super::{validate_answer, validate_code, ChallengeKind};
 
     #[test]
     fn test_validate_code() {
@@ -198,4 +207,11 @@ mod tests {
         assert!(validate_code("abc", "abd")); // one error
         assert!(!validate_code("abc", "ade")); // two errors (fail)
     }
+
+    #[test]
+    fn test_validate_answer() {
+        assert!(validate_answer(ChallengeKind::Image, "abd", "abc"));
+        assert!(validate_answer(ChallengeKind::Arithmetic, " 12 ", "12"));
+        assert!(!validate_answer(ChallengeKind::Arithmetic, "13", "12"));
+    }
 }
@@ -1,6 +1,6 @@
 //! Captcha verification button and modal.
 
-use raidprotect_captcha::{code::random_human_code, generate_captcha_png};
+use raidprotect_captcha::code::random_human_code;
 use raidprotect_model::cache::model::interaction::PendingCaptcha;
 use tracing::{error, instrument};
 use twilight_http::request::AuditLogReason;
@@ -19,16 +19,13 @@ use twilight_model::{
         Id,
     },
 };
-use twilight_util::builder::{
-    embed::{EmbedBuilder, ImageSource},
-    InteractionResponseDataBuilder,
-};
+use twilight_util::builder::{embed::ImageSource, InteractionResponseDataBuilder};
 
 use crate::{
     cluster::ClusterState,
-    feature::captcha,
+    feature::captcha::{self, ChallengeKind},
     interaction::{
-        embed::{self, COLOR_TRANSPARENT},
+        embed::{self, EmbedBuilder, COLOR_TRANSPARENT},
         response::InteractionResponse,
         util::{CustomId, GuildConfigExt, GuildInteractionContext},
     },
@@ -42,20 +39,16 @@ use crate::{
 pub struct CaptchaVerifyButton;
 
 impl CaptchaVerifyButton {
+    /// Handle the button click.
+    ///
+    /// The pending captcha is retrieved and authorized by the dispatcher.
     #[instrument(skip(state))]
     pub async fn handle(
         interaction: Interaction,
+        mut captcha: PendingCaptcha,
         state: &ClusterState,
     ) -> Result<InteractionResponse, anyhow::Error> {
-        let ctx = GuildInteractionContext::new(interaction)?;
-
-        // Get the pending captcha from the cache.
-        let mut captcha = match get_captcha(&ctx, state).await? {
-            Some(captcha) => captcha,
-            None => {
-                return Ok(embed::captcha::captcha_not_found(ctx.lang));
-            }
-        };
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
 
         // Kick the user if the captcha has been regenerated too many times.
         if captcha.regenerate_count >= captcha::MAX_RETRY {
@@ -69,16 +62,29 @@ impl CaptchaVerifyButton {
             return Ok(embed::captcha::regenerate_error(ctx.lang));
         }
 
-        // Generate the captcha image.
+        // Generate the captcha image, or fall back to an arithmetic challenge
+        // if too many images are rendered.
         let code = random_human_code(captcha::DEFAULT_LENGTH);
+        let image = captcha::render(state, ctx.guild_id, &code).await?;
 
-        let code_clone = code.clone();
-        let image =
-            tokio::task::spawn_blocking(move || generate_captcha_png(&code_clone)).await??;
+        captcha.regenerate_count += 1;
+
+        let image = match image {
+            Some(image) => image,
+            None => {
+                let (question, answer) = captcha::arithmetic_challenge();
+
+                captcha.kind = ChallengeKind::Arithmetic;
+                captcha.code = answer;
+                state.cache.set(&captcha).await?;
+
+                return Ok(arithmetic_modal(ctx.lang, &question));
+            }
+        };
 
         // Update the captcha in the cache.
+        captcha.kind = ChallengeKind::Image;
         captcha.code = code;
-        captcha.regenerate_count += 1;
 
         state.cache.set(&captcha).await?;
 
@@ -170,20 +176,17 @@ pub async fn kick_after(
 pub struct CaptchaValidateButton;
 
 impl CaptchaValidateButton {
+    /// Handle the button click.
+    ///
+    /// The pending captcha is retrieved and authorized by the dispatcher.
     #[instrument(skip(state))]
     pub async fn handle(
         interaction: Interaction,
+        captcha: PendingCaptcha,
         state: &ClusterState,
     ) -> Result<InteractionResponse, anyhow::Error> {
-        let ctx = GuildInteractionContext::new(interaction)?;
-
-        // Get the captcha code length from the cache.
-        let code_length = match get_captcha(&ctx, state).await? {
-            Some(captcha) => captcha.code.len(),
-            None => {
-                return Ok(embed::captcha::captcha_not_found(ctx.lang));
-            }
-        };
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
+        let code_length = captcha.code.len();
 
         // Send the captcha modal.
         let input_custom_id = CustomId::name("captcha-input");
@@ -210,15 +213,30 @@ impl CaptchaValidateButton {
     }
 }
 
-/// Get the captcha key from the current context.
-pub fn captcha_key(ctx: &GuildInteractionContext) -> (Id<GuildMarker>, Id<UserMarker>) {
-    (ctx.guild_id, ctx.author.id)
-}
-
-/// Get the pending captcha from the cache.
-pub async fn get_captcha(
-    ctx: &GuildInteractionContext,
-    state: &ClusterState,
-) -> Result<Option<PendingCaptcha>, anyhow::Error> {
-    state.cache.get::<PendingCaptcha>(&captcha_key(ctx)).await
+/// Modal with an arithmetic challenge.
+///
+/// This modal is sent instead of the captcha image when the render budget of
+/// the guild is exhausted. It is submitted as a regular captcha modal.
+fn arithmetic_modal(lang: Lang, question: &str) -> InteractionResponse {
+    let input_custom_id = CustomId::name("captcha-input");
+    let modal_custom_id = CustomId::name("captcha-modal");
+
+    let components = vec![Component::ActionRow(ActionRow {
+        components: vec![Component::TextInput(TextInput {
+            custom_id: input_custom_id.to_string(),
+            label: lang.captcha_arithmetic_label(question),
+            max_length: Some(3),
+            min_length: Some(1),
+            placeholder: None,
+            required: Some(true),
+            style: TextInputStyle::Short,
+            value: None,
+        })],
+    })];
+
+    InteractionResponse::Modal {
+        custom_id: modal_custom_id.to_string(),
+        title: lang.captcha_image_title().to_owned(),
+        components,
+    }
 }
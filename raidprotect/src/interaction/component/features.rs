@@ -0,0 +1,47 @@
+//! Feature toggle buttons.
+
+use raidprotect_model::database::model::Feature;
+use twilight_model::{
+    application::interaction::Interaction, http::interaction::InteractionResponseType,
+};
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+};
+
+/// Feature toggle button.
+///
+/// This type handle the buttons sent by the `/features` command. The buttons
+/// are attached to an ephemeral message, so the permissions of the user are
+/// not checked again.
+pub struct FeatureToggle;
+
+impl FeatureToggle {
+    /// Handle the button click.
+    ///
+    /// The feature is toggled and the message is updated in place with the
+    /// new state of the features.
+    pub async fn handle(
+        interaction: Interaction,
+        feature: &str,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
+
+        let feature = match Feature::from_name(feature) {
+            Some(feature) => feature,
+            None => return Ok(embed::error::unknown_command(ctx.lang)),
+        };
+
+        let mut config = ctx.config(state).await?;
+        config.features.toggle(feature);
+        database::update_guild_config(state, &config).await?;
+
+        Ok(InteractionResponse::Raw {
+            kind: InteractionResponseType::UpdateMessage,
+            data: Some(embed::features::features(ctx.lang, config.features)),
+        })
+    }
+}
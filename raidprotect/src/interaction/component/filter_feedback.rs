@@ -0,0 +1,60 @@
+//! False positive button of the filters logs.
+//!
+//! The button is attached to the logs of messages deleted by the filters (see
+//! the [`feature::filter_feedback`] module).
+//!
+//! [`feature::filter_feedback`]: crate::feature::filter_feedback
+
+use anyhow::Context;
+use twilight_model::{application::interaction::Interaction, guild::Permissions};
+
+use super::report::{has_permission, update_alert};
+use crate::{
+    cluster::ClusterState,
+    feature::filter_feedback,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+};
+
+/// False positive button.
+pub struct FilterFeedbackButton;
+
+impl FilterFeedbackButton {
+    /// Handle the button click.
+    ///
+    /// The button is sent in the logs channel, so the user must have the
+    /// [`MANAGE_MESSAGES`] permission. The feedback is recorded and the log
+    /// message is updated to show who reported the false positive.
+    ///
+    /// [`MANAGE_MESSAGES`]: Permissions::MANAGE_MESSAGES
+    pub async fn handle(
+        interaction: Interaction,
+        id: &str,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
+
+        if let Some(response) = ctx.check_partial() {
+            return Ok(response);
+        }
+
+        if !has_permission(&ctx, Permissions::MANAGE_MESSAGES) {
+            return Ok(embed::error::missing_permissions(ctx.lang));
+        }
+
+        let (filter, fingerprint) =
+            filter_feedback::parse_id(id).context("invalid filter feedback id")?;
+
+        filter_feedback::submit(
+            state,
+            ctx.guild_id,
+            filter,
+            fingerprint.to_owned(),
+            ctx.author.id,
+        )
+        .await?;
+
+        let embed = embed::filter_feedback::marked(ctx.public_lang, ctx.author.id);
+
+        Ok(update_alert(ctx, embed))
+    }
+}
@@ -0,0 +1,70 @@
+//! Help category select menu.
+//!
+//! The select menu is sent with the [`help`] command to browse the commands
+//! of each category. It has no pending state: the commands available to the
+//! member are computed again when a category is selected.
+//!
+//! [`help`]: crate::interaction::command::help
+
+use anyhow::{bail, Context};
+use twilight_model::{
+    application::interaction::{Interaction, InteractionData},
+    http::interaction::InteractionResponseType,
+};
+use twilight_util::builder::InteractionResponseDataBuilder;
+
+use crate::{
+    cluster::ClusterState,
+    interaction::{
+        command::{help, CommandCategory},
+        embed,
+        response::InteractionResponse,
+        util::InteractionContext,
+    },
+};
+
+/// Help category select menu.
+pub struct HelpCategory;
+
+impl HelpCategory {
+    /// Handle the category selection.
+    ///
+    /// The help message is updated with the commands of the selected category
+    /// the member can use.
+    pub async fn handle(
+        interaction: Interaction,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let value = match &interaction.data {
+            Some(InteractionData::MessageComponent(data)) => data
+                .values
+                .first()
+                .cloned()
+                .context("missing select menu value")?,
+            _ => bail!("expected message component data"),
+        };
+
+        let ctx = InteractionContext::new(interaction, state).await?;
+        let category = CommandCategory::from_id(&value).context("unknown help category")?;
+
+        let permissions = ctx.interaction.member.as_ref().and_then(|m| m.permissions);
+        let is_operator = state.is_operator(ctx.author.id);
+
+        // Permissions may have changed since the help was sent.
+        let categories = help::categories(permissions, is_operator);
+        if !categories.contains(&category) {
+            return Ok(embed::error::expired_interaction(ctx.lang));
+        }
+
+        let commands = help::category_commands(category, permissions, is_operator);
+        let response = InteractionResponseDataBuilder::new()
+            .embeds([embed::help::category(ctx.lang, category, &commands)])
+            .components(help::components(ctx.lang, &categories, Some(category)))
+            .build();
+
+        Ok(InteractionResponse::Raw {
+            kind: InteractionResponseType::UpdateMessage,
+            data: Some(response),
+        })
+    }
+}
@@ -0,0 +1,156 @@
+//! Review buttons of the members held by the raid mode.
+//!
+//! These buttons are attached to the message sent when a member is held for
+//! review (see the [`raid_mode`] module).
+//!
+//! [`raid_mode`]: crate::event::raid_mode
+
+use raidprotect_model::{
+    cache::model::interaction::PendingJoinReview, database::model::GuildConfig,
+};
+use tracing::warn;
+use twilight_http::request::AuditLogReason;
+use twilight_model::{
+    application::interaction::Interaction,
+    guild::Permissions,
+    http::interaction::{InteractionResponseData, InteractionResponseType},
+    id::{marker::UserMarker, Id},
+};
+
+use crate::{
+    cluster::ClusterState,
+    interaction::{
+        embed,
+        response::InteractionResponse,
+        util::{GuildConfigExt, GuildInteractionContext},
+    },
+};
+
+/// Action of a review button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinReviewAction {
+    /// The member is allowed in the guild.
+    Approve,
+    /// The member is kicked from the guild.
+    Kick,
+}
+
+/// Review buttons.
+pub struct JoinReview;
+
+impl JoinReview {
+    /// Handle the button click.
+    ///
+    /// The buttons are sent in the review channel, so the user must have the
+    /// [`KICK_MEMBERS`] permission. Approved members are no longer timed out
+    /// and receive the configured member role. The review message is then
+    /// updated to show who reviewed the member.
+    ///
+    /// [`KICK_MEMBERS`]: Permissions::KICK_MEMBERS
+    pub async fn handle(
+        interaction: Interaction,
+        pending: PendingJoinReview,
+        action: JoinReviewAction,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
+
+        if let Some(response) = ctx.check_partial() {
+            return Ok(response);
+        }
+
+        let permissions = ctx.member.permissions.unwrap_or_else(Permissions::empty);
+        if pending.guild_id != ctx.guild_id || !permissions.contains(Permissions::KICK_MEMBERS) {
+            return Ok(embed::error::missing_permissions(ctx.lang));
+        }
+
+        let config = ctx.config(state).await?;
+
+        // The pending state is consumed before applying the action, so that
+        // concurrent clicks only apply it once. It is restored if the action
+        // fails to allow another attempt.
+        if !state.cache.consume(&pending).await? {
+            return Ok(embed::error::already_handled(ctx.lang));
+        }
+
+        let result = match action {
+            JoinReviewAction::Approve => approve(state, &config, &pending, ctx.author.id).await,
+            JoinReviewAction::Kick => kick(state, &config, &pending, ctx.author.id).await,
+        };
+
+        if let Err(error) = result {
+            warn!(error = ?error, action = ?action, "failed to review member");
+            state.cache.set(&pending).await?;
+
+            return Ok(embed::join_review::failed(ctx.lang));
+        }
+
+        let mut embeds = ctx
+            .interaction
+            .message
+            .map(|message| message.embeds)
+            .unwrap_or_default();
+        embeds.push(match action {
+            JoinReviewAction::Approve => {
+                embed::join_review::approved(ctx.public_lang, ctx.author.id)
+            }
+            JoinReviewAction::Kick => embed::join_review::kicked(ctx.public_lang, ctx.author.id),
+        });
+
+        Ok(InteractionResponse::Raw {
+            kind: InteractionResponseType::UpdateMessage,
+            data: Some(InteractionResponseData {
+                components: Some(Vec::new()),
+                embeds: Some(embeds),
+                ..Default::default()
+            }),
+        })
+    }
+}
+
+/// Remove the timeout of the member and give the member role.
+async fn approve(
+    state: &ClusterState,
+    config: &GuildConfig,
+    pending: &PendingJoinReview,
+    moderator: Id<UserMarker>,
+) -> Result<(), anyhow::Error> {
+    let cache_http = state.cache_http(pending.guild_id);
+    let reason = config.lang().join_review_approve_reason(moderator);
+
+    cache_http
+        .remove_timeout(pending.user_id)
+        .await?
+        .reason(&reason)?
+        .exec()
+        .await?;
+
+    if let Some(role_id) = config.raid_mode.member_role {
+        cache_http
+            .add_guild_member_role(pending.user_id, role_id)
+            .await?
+            .reason(&reason)?
+            .exec()
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Kick the member from the guild.
+async fn kick(
+    state: &ClusterState,
+    config: &GuildConfig,
+    pending: &PendingJoinReview,
+    moderator: Id<UserMarker>,
+) -> Result<(), anyhow::Error> {
+    state
+        .cache_http(pending.guild_id)
+        .remove_guild_member(pending.user_id)
+        .await?
+        .reason(&config.lang().join_review_kick_reason(moderator))?
+        .exec()
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+//! Lockdown cancel button.
+
+use anyhow::Context;
+use twilight_model::{
+    application::interaction::Interaction,
+    http::interaction::{InteractionResponseData, InteractionResponseType},
+    id::Id,
+};
+
+use crate::{
+    cluster::ClusterState,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+};
+
+/// Lockdown cancel button.
+pub struct LockdownCancel;
+
+impl LockdownCancel {
+    /// Handle the cancel button click.
+    ///
+    /// The button is only shown in the ephemeral response of the moderator
+    /// that engaged the lockdown. The channels already locked are restored by
+    /// the running engagement, which then updates the response with a summary.
+    pub async fn handle(
+        interaction: Interaction,
+        id: &str,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
+        let id = id
+            .parse()
+            .ok()
+            .and_then(Id::new_checked)
+            .context("invalid lockdown id")?;
+
+        if !state.bulk_tasks.cancel(id) {
+            return Ok(embed::error::already_handled(ctx.lang));
+        }
+
+        Ok(InteractionResponse::Raw {
+            kind: InteractionResponseType::UpdateMessage,
+            data: Some(InteractionResponseData {
+                components: Some(Vec::new()),
+                embeds: Some(vec![embed::lockdown::cancelling(ctx.lang)]),
+                ..Default::default()
+            }),
+        })
+    }
+}
@@ -1,6 +1,121 @@
 //! Component interactions handling.
+//!
+//! Components that perform an action have a pending state stored in the
+//! cache, which records the users authorized to use the component. The state
+//! is retrieved with [`authorize`] by the dispatcher before running the
+//! component handler, to prevent users from reusing the custom id of a
+//! component sent to someone else.
 
+mod automod;
+mod bulk_ban;
+mod bulk_unban;
 pub mod captcha;
+mod features;
+mod filter_feedback;
+mod help;
+mod join_review;
+mod lockdown;
+pub mod paginator;
 mod post_in_chat;
+mod preset;
+mod raid_alert;
+mod report;
+mod restore;
+mod role_strip;
+mod sanction;
+mod timeout_adjust;
 
+pub use automod::AutomodOverwrite;
+pub use bulk_ban::{BulkBanCancel, BulkBanConfirm};
+pub use bulk_unban::{BulkUnbanCancel, BulkUnbanConfirm};
+pub use features::FeatureToggle;
+pub use filter_feedback::FilterFeedbackButton;
+pub use help::HelpCategory;
+pub use join_review::{JoinReview, JoinReviewAction};
+pub use lockdown::LockdownCancel;
+pub use paginator::Paginator;
 pub use post_in_chat::PostInChat;
+pub use preset::PresetConfirm;
+pub use raid_alert::{RaidAlertDismiss, RaidAlertLockdown};
+use raidprotect_model::cache::model::interaction::PendingComponent;
+pub use report::{ReportDelete, ReportModal, ReportResolve, ReportSanction};
+pub use restore::RestoreConfirm;
+pub use role_strip::RoleStrip;
+pub use sanction::{SanctionConfirm, SanctionReasonSelect, SanctionTemplateModal};
+pub use timeout_adjust::TimeoutAdjust;
+use twilight_model::id::{marker::UserMarker, Id};
+
+use crate::cluster::ClusterState;
+
+/// Authorization of a user to use a pending component.
+#[derive(Debug)]
+pub enum Authorization<T> {
+    /// The user is authorized to use the component.
+    Authorized(T),
+    /// The component state has expired.
+    Expired,
+    /// The user is not authorized to use the component.
+    Denied,
+}
+
+impl<T: PendingComponent> Authorization<T> {
+    /// Check whether a user is authorized to use a pending component.
+    pub fn check(pending: Option<T>, user: Id<UserMarker>) -> Self {
+        match pending {
+            Some(pending) if pending.is_authorized(user) => Self::Authorized(pending),
+            Some(_) => Self::Denied,
+            None => Self::Expired,
+        }
+    }
+}
+
+/// Get the state of a pending component and check whether a user is
+/// authorized to use it.
+pub async fn authorize<T: PendingComponent>(
+    state: &ClusterState,
+    id: &T::Id,
+    user: Id<UserMarker>,
+) -> Result<Authorization<T>, anyhow::Error> {
+    let pending = state.cache.get::<T>(id).await?;
+
+    Ok(Authorization::check(pending, user))
+}
+
+#[cfg(test)]
+mod tests {
+    use raidprotect_model::cache::model::interaction::{PostInChatButton, PostInChatMessage};
+    use twilight_model::http::interaction::InteractionResponseData;
+
+    use super::*;
+
+    fn button() -> PostInChatButton {
+        PostInChatButton {
+            message: PostInChatMessage::Legacy {
+                response: InteractionResponseData::default(),
+            },
+            interaction_id: Id::new(1),
+            author_id: Id::new(2),
+        }
+    }
+
+    #[test]
+    fn test_authorized_click() {
+        let authorization = Authorization::check(Some(button()), Id::new(2));
+
+        assert!(matches!(authorization, Authorization::Authorized(_)));
+    }
+
+    #[test]
+    fn test_unauthorized_click() {
+        let authorization = Authorization::check(Some(button()), Id::new(3));
+
+        assert!(matches!(authorization, Authorization::Denied));
+    }
+
+    #[test]
+    fn test_expired_click() {
+        let authorization = Authorization::check(None::<PostInChatButton>, Id::new(2));
+
+        assert!(matches!(authorization, Authorization::Expired));
+    }
+}
@@ -0,0 +1,300 @@
+//! Paginated messages.
+//!
+//! This module implement a generic paginator that display a list of embeds
+//! with buttons to navigate between them. Features only have to provide the
+//! pages, the state of the message is stored in Redis and the buttons can
+//! only be used by the user that invoked the command.
+
+use std::str::FromStr;
+
+use anyhow::bail;
+use raidprotect_model::{cache::model::interaction::PendingPaginator, store::CacheStore};
+use twilight_model::{
+    application::{
+        component::{button::ButtonStyle, ActionRow, Button, Component},
+        interaction::Interaction,
+    },
+    channel::{
+        embed::{Embed, EmbedFooter},
+        message::MessageFlags,
+    },
+    http::interaction::{InteractionResponseData, InteractionResponseType},
+    id::{
+        marker::{InteractionMarker, UserMarker},
+        Id,
+    },
+};
+use twilight_util::builder::InteractionResponseDataBuilder;
+
+use crate::{
+    cluster::HandlerState,
+    interaction::{
+        response::InteractionResponse,
+        util::{CustomId, InteractionExt},
+    },
+    translations::Lang,
+};
+
+/// Navigation button of a paginated message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageAction {
+    First,
+    Previous,
+    Next,
+    Last,
+}
+
+impl PageAction {
+    const ALL: [PageAction; 4] = [Self::First, Self::Previous, Self::Next, Self::Last];
+
+    /// Index of the page displayed after the button click.
+    pub fn target(self, page: usize, len: usize) -> usize {
+        let last = len.saturating_sub(1);
+
+        match self {
+            Self::First => 0,
+            Self::Previous => page.saturating_sub(1),
+            Self::Next => (page + 1).min(last),
+            Self::Last => last,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::First => "first",
+            Self::Previous => "previous",
+            Self::Next => "next",
+            Self::Last => "last",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::First => "«",
+            Self::Previous => "‹",
+            Self::Next => "›",
+            Self::Last => "»",
+        }
+    }
+}
+
+impl FromStr for PageAction {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "first" => Ok(Self::First),
+            "previous" => Ok(Self::Previous),
+            "next" => Ok(Self::Next),
+            "last" => Ok(Self::Last),
+            other => bail!("unknown page action: {other}"),
+        }
+    }
+}
+
+/// Paginated message.
+pub struct Paginator;
+
+impl Paginator {
+    /// Create a new paginated message.
+    ///
+    /// The response is ephemeral. If there is only a single page, no buttons
+    /// are added and no state is stored.
+    pub async fn create(
+        pages: Vec<Embed>,
+        interaction_id: Id<InteractionMarker>,
+        author_id: Id<UserMarker>,
+        state: &impl HandlerState,
+        lang: Lang,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        if pages.len() <= 1 {
+            let page = match pages.into_iter().next() {
+                Some(page) => page,
+                None => bail!("paginator requires at least one page"),
+            };
+
+            return Ok(InteractionResponse::EphemeralEmbed(page));
+        }
+
+        // Store paginator state in redis
+        let paginator = PendingPaginator {
+            interaction_id,
+            author_id,
+            pages,
+            page: 0,
+        };
+
+        state.cache_store().set(&paginator).await?;
+
+        let response = InteractionResponseDataBuilder::new()
+            .embeds([render_page(&paginator, lang)])
+            .components([render_buttons(&paginator)])
+            .flags(MessageFlags::EPHEMERAL)
+            .build();
+
+        Ok(InteractionResponse::Raw {
+            kind: InteractionResponseType::ChannelMessageWithSource,
+            data: Some(response),
+        })
+    }
+
+    /// Handle a navigation button click.
+    ///
+    /// The component state is retrieved and authorized by the dispatcher.
+    pub async fn handle(
+        interaction: Interaction,
+        mut paginator: PendingPaginator,
+        action: PageAction,
+        state: &impl HandlerState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let lang = interaction.lang()?;
+
+        paginator.page = action.target(paginator.page, paginator.pages.len());
+        state.cache_store().set(&paginator).await?;
+
+        Ok(InteractionResponse::Raw {
+            kind: InteractionResponseType::UpdateMessage,
+            data: Some(InteractionResponseData {
+                components: Some(vec![render_buttons(&paginator)]),
+                embeds: Some(vec![render_page(&paginator, lang)]),
+                ..Default::default()
+            }),
+        })
+    }
+}
+
+/// Custom id of a navigation button.
+///
+/// The id contains the initial interaction id and the button action,
+/// separated by a colon.
+pub fn button_id(interaction_id: Id<InteractionMarker>, action: PageAction) -> CustomId {
+    CustomId::new("paginator", format!("{interaction_id}:{}", action.name()))
+}
+
+/// Parse the id of a navigation button.
+pub fn parse_button_id(id: &str) -> Result<(String, PageAction), anyhow::Error> {
+    match id.split_once(':') {
+        Some((interaction_id, action)) => Ok((interaction_id.to_owned(), action.parse()?)),
+        None => bail!("invalid paginator button id: {id}"),
+    }
+}
+
+/// Render the current page, with the page number in the footer.
+fn render_page(paginator: &PendingPaginator, lang: Lang) -> Embed {
+    let mut embed = paginator.pages[paginator.page].clone();
+    let number = lang.paginator_page(paginator.page + 1, paginator.pages.len());
+
+    embed.footer = Some(match embed.footer {
+        Some(footer) => EmbedFooter {
+            text: format!("{} • {number}", footer.text),
+            ..footer
+        },
+        None => EmbedFooter {
+            icon_url: None,
+            proxy_icon_url: None,
+            text: number,
+        },
+    });
+
+    embed
+}
+
+/// Render the navigation buttons.
+fn render_buttons(paginator: &PendingPaginator) -> Component {
+    let last = paginator.pages.len().saturating_sub(1);
+
+    let buttons = PageAction::ALL
+        .into_iter()
+        .map(|action| {
+            let disabled = match action {
+                PageAction::First | PageAction::Previous => paginator.page == 0,
+                PageAction::Next | PageAction::Last => paginator.page >= last,
+            };
+
+            Component::Button(Button {
+                custom_id: Some(button_id(paginator.interaction_id, action).to_string()),
+                disabled,
+                emoji: None,
+                label: Some(action.label().to_owned()),
+                style: ButtonStyle::Secondary,
+                url: None,
+            })
+        })
+        .collect();
+
+    Component::ActionRow(ActionRow {
+        components: buttons,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interaction::embed::EmbedBuilder;
+
+    fn paginator(page: usize) -> PendingPaginator {
+        let pages = (1..=3)
+            .map(|n| EmbedBuilder::new().description(n.to_string()).build())
+            .collect();
+
+        PendingPaginator {
+            interaction_id: Id::new(1),
+            author_id: Id::new(2),
+            pages,
+            page,
+        }
+    }
+
+    #[test]
+    fn test_action_target() {
+        assert_eq!(PageAction::First.target(2, 3), 0);
+        assert_eq!(PageAction::Previous.target(0, 3), 0);
+        assert_eq!(PageAction::Previous.target(2, 3), 1);
+        assert_eq!(PageAction::Next.target(1, 3), 2);
+        assert_eq!(PageAction::Next.target(2, 3), 2);
+        assert_eq!(PageAction::Last.target(0, 3), 2);
+    }
+
+    #[test]
+    fn test_button_id() {
+        for action in PageAction::ALL {
+            let custom_id = button_id(Id::new(1), action);
+            let id = custom_id.id.unwrap();
+
+            assert_eq!(parse_button_id(&id).unwrap(), ("1".to_owned(), action));
+        }
+
+        assert!(parse_button_id("1").is_err());
+        assert!(parse_button_id("1:unknown").is_err());
+    }
+
+    #[test]
+    fn test_render_page() {
+        let embed = render_page(&paginator(1), Lang::DEFAULT);
+
+        assert_eq!(embed.description.as_deref(), Some("2"));
+        assert_eq!(
+            embed.footer.unwrap().text,
+            Lang::DEFAULT.paginator_page(2, 3)
+        );
+    }
+
+    #[test]
+    fn test_render_buttons() {
+        let disabled = |paginator: &PendingPaginator| match render_buttons(paginator) {
+            Component::ActionRow(row) => row
+                .components
+                .into_iter()
+                .map(|component| match component {
+                    Component::Button(button) => button.disabled,
+                    _ => panic!("expected button"),
+                })
+                .collect::<Vec<_>>(),
+            _ => panic!("expected action row"),
+        };
+
+        assert_eq!(disabled(&paginator(0)), [true, true, false, false]);
+        assert_eq!(disabled(&paginator(1)), [false, false, false, false]);
+        assert_eq!(disabled(&paginator(2)), [false, false, true, true]);
+    }
+}
@@ -2,9 +2,12 @@
 //!
 //! This module implement the "Post in chat" button, that allow users to post
 //! in the channel an ephemeral response.
+//!
+//! The public message is rendered from a template when the button is clicked
+//! (see [`PostInChatMessage`]), so it may differ from the ephemeral response
+//! and uses the current guild configuration.
 
-use anyhow::anyhow;
-use raidprotect_model::cache::model::interaction::PostInChatButton;
+use raidprotect_model::cache::model::interaction::{PostInChatButton, PostInChatMessage};
 use twilight_model::{
     application::{
         component::{button::ButtonStyle, ActionRow, Button, Component},
@@ -23,7 +26,7 @@ use crate::{
     interaction::{
         embed,
         response::InteractionResponse,
-        util::{CustomId, GuildConfigExt, GuildInteractionContext},
+        util::{CustomId, GuildInteractionContext},
     },
     translations::Lang,
 };
@@ -33,8 +36,12 @@ pub struct PostInChat;
 
 impl PostInChat {
     /// Create a new [`PostInChat`] component.
+    ///
+    /// The `response` is shown to the author of the interaction, and the
+    /// `message` is posted in the channel when the button is clicked.
     pub async fn create(
         mut response: InteractionResponseData,
+        message: PostInChatMessage,
         interaction_id: Id<InteractionMarker>,
         author_id: Id<UserMarker>,
         state: &ClusterState,
@@ -42,7 +49,7 @@ impl PostInChat {
     ) -> Result<InteractionResponse, anyhow::Error> {
         // Store button state in redis
         let component = PostInChatButton {
-            response: response.clone(),
+            message,
             interaction_id,
             author_id,
         };
@@ -85,33 +92,34 @@ impl PostInChat {
     }
 
     /// Handle the button click.
+    ///
+    /// The component state is retrieved and authorized by the dispatcher.
     pub async fn handle(
         interaction: Interaction,
-        custom_id: CustomId,
+        component: PostInChatButton,
         state: &ClusterState,
     ) -> Result<InteractionResponse, anyhow::Error> {
-        let ctx = GuildInteractionContext::new(interaction)?;
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
 
-        // Fetch component from redis
-        let component_id = custom_id
-            .id
-            .ok_or_else(|| anyhow!("missing component id in custom_id"))?;
-        let mut component = match state.cache.get::<PostInChatButton>(&component_id).await? {
-            Some(component) => component,
-            None => return Ok(embed::error::expired_interaction(ctx.lang)),
-        };
+        let mut response = match component.message {
+            PostInChatMessage::Template { case_id, template } => {
+                embed::post_in_chat::render(ctx.public_lang, &template, case_id)?
+            }
+            PostInChatMessage::Legacy { mut response } => {
+                // Remove ephemeral flag
+                if let Some(flags) = response.flags.as_mut() {
+                    flags.set(MessageFlags::EPHEMERAL, false);
+                }
 
-        // Remove ephemeral flag
-        if let Some(flags) = component.response.flags.as_mut() {
-            flags.set(MessageFlags::EPHEMERAL, false);
-        }
+                response
+            }
+        };
 
-        let config = ctx.config(state).await?;
-        component.response.content = Some(config.lang().post_in_chat_author(component.author_id));
+        response.content = Some(ctx.public_lang.post_in_chat_author(component.author_id));
 
         Ok(InteractionResponse::Raw {
             kind: InteractionResponseType::ChannelMessageWithSource,
-            data: Some(component.response),
+            data: Some(response),
         })
     }
 }
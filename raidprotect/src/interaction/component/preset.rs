@@ -0,0 +1,87 @@
+//! Preset confirmation button.
+
+use raidprotect_model::{
+    cache::model::interaction::PendingPreset,
+    database::model::{GuildConfig, LogCategory},
+};
+use tracing::warn;
+use twilight_model::{
+    application::interaction::Interaction,
+    http::interaction::{InteractionResponseData, InteractionResponseType},
+    id::{marker::UserMarker, Id},
+};
+
+use crate::{
+    cluster::ClusterState,
+    database,
+    feature::preset::{self, Preset},
+    interaction::{
+        embed,
+        response::InteractionResponse,
+        util::{GuildConfigExt, GuildInteractionContext},
+    },
+    util::logs,
+};
+
+/// Preset confirmation button.
+pub struct PresetConfirm;
+
+impl PresetConfirm {
+    /// Handle the confirmation button click.
+    ///
+    /// The pending preset is consumed to prevent it from being applied twice.
+    /// The preset is applied to the current configuration, which may have
+    /// changed since the confirmation was sent.
+    pub async fn handle(
+        interaction: Interaction,
+        pending: PendingPreset,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
+
+        if !state.cache.consume(&pending).await? {
+            return Ok(embed::error::already_handled(ctx.lang));
+        }
+
+        let preset = match Preset::from_name(&pending.preset) {
+            Some(preset) => preset,
+            None => return Ok(embed::error::unknown_command(ctx.lang)),
+        };
+
+        let current = ctx.config(state).await?;
+        let config = preset::apply(preset, &current);
+        let count = preset::changes(&current, &config).len();
+        database::update_guild_config(state, &config).await?;
+
+        log_preset(state, &config, preset, ctx.author.id, count).await;
+
+        Ok(InteractionResponse::Raw {
+            kind: InteractionResponseType::UpdateMessage,
+            data: Some(InteractionResponseData {
+                components: Some(Vec::new()),
+                embeds: Some(vec![embed::preset::applied(ctx.lang, preset, count)]),
+                ..Default::default()
+            }),
+        })
+    }
+}
+
+/// Log the application of a preset in the logs channel.
+async fn log_preset(
+    state: &ClusterState,
+    config: &GuildConfig,
+    preset: Preset,
+    user: Id<UserMarker>,
+    count: usize,
+) {
+    let result = async {
+        let lang = config.lang();
+        let embed = embed::logs::preset_applied(lang, preset.title(lang), user, count);
+
+        logs::post(state, config, LogCategory::Config, embed).await
+    };
+
+    if let Err(error) = result.await {
+        warn!(error = ?error, "failed to log preset");
+    }
+}
@@ -0,0 +1,115 @@
+//! Buttons of anti-raid alerts.
+//!
+//! These buttons are attached to the alerts sent when a raid is detected (see
+//! the [`raid_alert`] module).
+//!
+//! [`raid_alert`]: crate::feature::raid_alert
+
+use twilight_model::{
+    application::interaction::Interaction,
+    channel::embed::Embed,
+    guild::Permissions,
+    http::interaction::{InteractionResponseData, InteractionResponseType},
+};
+
+use crate::{
+    cluster::ClusterState,
+    feature::raid_mode,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+};
+
+/// Lockdown button.
+pub struct RaidAlertLockdown;
+
+impl RaidAlertLockdown {
+    /// Handle the button click.
+    ///
+    /// The button is sent in the logs channel, so the user must have the
+    /// [`MANAGE_GUILD`] permission, like for the `/raidmode` command. The raid
+    /// mode is enabled and the alert message is updated to show who enabled
+    /// it.
+    ///
+    /// [`MANAGE_GUILD`]: Permissions::MANAGE_GUILD
+    pub async fn handle(
+        interaction: Interaction,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
+
+        if let Some(response) = ctx.check_partial() {
+            return Ok(response);
+        }
+
+        if !has_permission(&ctx) {
+            return Ok(embed::error::missing_permissions(ctx.lang));
+        }
+
+        let mut config = ctx.config(state).await?;
+
+        // The raid mode may have been enabled since the alert was sent.
+        if !config.raid_mode.enabled {
+            raid_mode::enable(state, &mut config, ctx.author.id).await?;
+        }
+
+        let embed = embed::raid_alert::locked_down(ctx.public_lang, ctx.author.id);
+
+        Ok(update_alert(ctx, embed))
+    }
+}
+
+/// Dismiss button.
+pub struct RaidAlertDismiss;
+
+impl RaidAlertDismiss {
+    /// Handle the button click.
+    ///
+    /// The user must have the [`MANAGE_GUILD`] permission. The buttons are
+    /// removed from the alert message, which is updated to show who dismissed
+    /// the alert.
+    ///
+    /// [`MANAGE_GUILD`]: Permissions::MANAGE_GUILD
+    pub async fn handle(
+        interaction: Interaction,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
+
+        if let Some(response) = ctx.check_partial() {
+            return Ok(response);
+        }
+
+        if !has_permission(&ctx) {
+            return Ok(embed::error::missing_permissions(ctx.lang));
+        }
+
+        let embed = embed::raid_alert::dismissed(ctx.public_lang, ctx.author.id);
+
+        Ok(update_alert(ctx, embed))
+    }
+}
+
+/// Whether the user is allowed to use the alert buttons.
+fn has_permission(ctx: &GuildInteractionContext) -> bool {
+    ctx.member.permissions.map_or(false, |permissions| {
+        permissions.contains(Permissions::MANAGE_GUILD)
+    })
+}
+
+/// Add an embed to the alert message and remove its buttons.
+fn update_alert(ctx: GuildInteractionContext, embed: Embed) -> InteractionResponse {
+    let mut embeds = ctx
+        .interaction
+        .message
+        .map(|message| message.embeds)
+        .unwrap_or_default();
+    embeds.push(embed);
+
+    InteractionResponse::Raw {
+        kind: InteractionResponseType::UpdateMessage,
+        data: Some(InteractionResponseData {
+            components: Some(Vec::new()),
+            embeds: Some(embeds),
+            ..Default::default()
+        }),
+    }
+}
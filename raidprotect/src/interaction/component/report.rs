@@ -0,0 +1,267 @@
+//! Message report components.
+//!
+//! The modal is shown by the message [`report`] command to let the member
+//! enter the reason of the report. The buttons are attached to the alerts sent
+//! in the reports channel (see the [`feature::report`] module).
+//!
+//! [`report`]: crate::interaction::command::report
+//! [`feature::report`]: crate::feature::report
+
+use anyhow::Context;
+use raidprotect_model::{
+    cache::model::interaction::{PendingReport, PendingSanction},
+    database::model::{ModlogType, ObjectId, Report},
+};
+use tracing::{instrument, warn};
+use twilight_model::{
+    application::interaction::Interaction,
+    channel::embed::Embed,
+    guild::Permissions,
+    http::interaction::{InteractionResponseData, InteractionResponseType},
+};
+
+use crate::{
+    cluster::ClusterState,
+    feature::report,
+    interaction::{
+        command::moderation::MuteCommand,
+        embed,
+        response::InteractionResponse,
+        util::{parse_modal_data, parse_modal_field, GuildConfigExt, GuildInteractionContext},
+    },
+};
+
+/// Duration (in minutes) of the mute applied with the "Sanction author" button.
+const SANCTION_MUTE_DURATION: u32 = 60;
+
+/// Report reason modal.
+pub struct ReportModal;
+
+impl ReportModal {
+    /// Handle the modal submit.
+    ///
+    /// The pending report is retrieved and authorized by the dispatcher. The
+    /// report is rate-limited before being submitted.
+    #[instrument(skip(state))]
+    pub async fn handle(
+        mut interaction: Interaction,
+        pending: PendingReport,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let data = parse_modal_data(&mut interaction)?;
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
+
+        if !state.cache.consume(&pending).await? {
+            return Ok(embed::error::already_handled(ctx.lang));
+        }
+
+        // The reports channel may have been removed since the modal was shown.
+        let config = ctx.config(state).await?;
+        let channel = match config.reports_chan {
+            Some(channel) => channel,
+            None => return Ok(embed::report::not_configured(ctx.lang)),
+        };
+
+        if !report::check_rate_limit(state, ctx.guild_id, ctx.author.id).await? {
+            return Ok(embed::report::rate_limited(ctx.lang));
+        }
+
+        let reason = parse_modal_field(&data, "reason")?
+            .map(str::trim)
+            .filter(|reason| !reason.is_empty())
+            .map(ToOwned::to_owned);
+
+        let outcome =
+            report::submit(state, ctx.guild_id, channel, pending, reason, config.lang()).await?;
+
+        Ok(embed::report::submitted(ctx.lang, outcome))
+    }
+}
+
+/// Resolve button.
+pub struct ReportResolve;
+
+impl ReportResolve {
+    /// Handle the button click.
+    ///
+    /// The button is sent in the reports channel, so the user must have the
+    /// [`MANAGE_MESSAGES`] permission. The report is marked as resolved and
+    /// the alert message is updated to show who resolved it.
+    ///
+    /// [`MANAGE_MESSAGES`]: Permissions::MANAGE_MESSAGES
+    pub async fn handle(
+        interaction: Interaction,
+        id: &str,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
+
+        if let Some(response) = ctx.check_partial() {
+            return Ok(response);
+        }
+
+        if !has_permission(&ctx, Permissions::MANAGE_MESSAGES) {
+            return Ok(embed::error::missing_permissions(ctx.lang));
+        }
+
+        let mut report = match get_report(state, &ctx, id).await? {
+            Some(report) => report,
+            None => return Ok(embed::report::not_found(ctx.lang)),
+        };
+
+        if !report.is_resolved() {
+            report.resolved_by = Some(ctx.author.id);
+            state.database.update_report(&report).await?;
+        }
+
+        let embed = embed::report::resolved(ctx.public_lang, ctx.author.id);
+
+        Ok(update_alert(ctx, embed))
+    }
+}
+
+/// Delete message button.
+pub struct ReportDelete;
+
+impl ReportDelete {
+    /// Handle the button click.
+    ///
+    /// The user must have the [`MANAGE_MESSAGES`] permission. Messages that
+    /// have already been deleted are ignored.
+    ///
+    /// [`MANAGE_MESSAGES`]: Permissions::MANAGE_MESSAGES
+    pub async fn handle(
+        interaction: Interaction,
+        id: &str,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
+
+        if let Some(response) = ctx.check_partial() {
+            return Ok(response);
+        }
+
+        if !has_permission(&ctx, Permissions::MANAGE_MESSAGES) {
+            return Ok(embed::error::missing_permissions(ctx.lang));
+        }
+
+        let report = match get_report(state, &ctx, id).await? {
+            Some(report) => report,
+            None => return Ok(embed::report::not_found(ctx.lang)),
+        };
+
+        if let Err(error) = state
+            .cache_http(ctx.guild_id)
+            .delete_message(report.channel_id, report.message_id)
+            .await?
+            .exec()
+            .await
+        {
+            warn!(error = ?error, guild = ?ctx.guild_id, "failed to delete reported message");
+        }
+
+        Ok(embed::report::message_deleted(ctx.lang))
+    }
+}
+
+/// Sanction author button.
+pub struct ReportSanction;
+
+impl ReportSanction {
+    /// Handle the button click.
+    ///
+    /// The author of the reported message is muted for
+    /// [`SANCTION_MUTE_DURATION`] minutes, with the same checks as the
+    /// `/mute` command. The user must have the [`MODERATE_MEMBERS`]
+    /// permission.
+    ///
+    /// [`MODERATE_MEMBERS`]: Permissions::MODERATE_MEMBERS
+    pub async fn handle(
+        interaction: Interaction,
+        id: &str,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
+
+        if let Some(response) = ctx.check_partial() {
+            return Ok(response);
+        }
+
+        if !has_permission(&ctx, Permissions::MODERATE_MEMBERS) {
+            return Ok(embed::error::missing_permissions(ctx.lang));
+        }
+
+        let report = match get_report(state, &ctx, id).await? {
+            Some(report) => report,
+            None => return Ok(embed::report::not_found(ctx.lang)),
+        };
+
+        // The author may have left the guild since the report.
+        let member = match state
+            .http
+            .guild_member(ctx.guild_id, report.author.id)
+            .exec()
+            .await
+        {
+            Ok(response) => response.model().await?,
+            Err(_) => return Ok(embed::mute::not_member(report.author.name, ctx.lang)),
+        };
+
+        let config = ctx.config(state).await?;
+        let pending = PendingSanction {
+            interaction_id: ctx.interaction.id,
+            author_id: ctx.author.id,
+            kind: ModlogType::Mute,
+            user: member.user,
+            reason: Some(config.lang().report_sanction_reason().to_owned()),
+            duration: Some(SANCTION_MUTE_DURATION),
+            pre_emptive: false,
+            cleanup: None,
+            confirmation_skipped: false,
+            shadow: false,
+            sync: false,
+        };
+
+        MuteCommand::start(pending, &member.roles, &ctx, state).await
+    }
+}
+
+/// Get the report of an alert button.
+///
+/// Reports of other guilds are never returned.
+async fn get_report(
+    state: &ClusterState,
+    ctx: &GuildInteractionContext,
+    id: &str,
+) -> Result<Option<Report>, anyhow::Error> {
+    let id = ObjectId::parse_str(id).context("invalid report id")?;
+    let report = state.database.get_report(id).await?;
+
+    Ok(report.filter(|report| report.guild_id == ctx.guild_id))
+}
+
+/// Whether the user has the permissions required to use an alert button.
+pub(super) fn has_permission(ctx: &GuildInteractionContext, required: Permissions) -> bool {
+    ctx.member
+        .permissions
+        .map_or(false, |permissions| permissions.contains(required))
+}
+
+/// Add an embed to the alert message and remove its buttons.
+pub(super) fn update_alert(ctx: GuildInteractionContext, embed: Embed) -> InteractionResponse {
+    let mut embeds = ctx
+        .interaction
+        .message
+        .map(|message| message.embeds)
+        .unwrap_or_default();
+    embeds.push(embed);
+
+    InteractionResponse::Raw {
+        kind: InteractionResponseType::UpdateMessage,
+        data: Some(InteractionResponseData {
+            components: Some(Vec::new()),
+            embeds: Some(embeds),
+            ..Default::default()
+        }),
+    }
+}
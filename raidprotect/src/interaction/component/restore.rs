@@ -0,0 +1,51 @@
+//! Restore confirmation button.
+
+use raidprotect_model::cache::model::interaction::PendingRestore;
+use twilight_model::{
+    application::interaction::Interaction,
+    http::interaction::{InteractionResponseData, InteractionResponseType},
+};
+
+use crate::{
+    cluster::ClusterState,
+    feature::restore,
+    interaction::{embed, response::InteractionResponse, util::GuildInteractionContext},
+};
+
+/// Restore confirmation button.
+pub struct RestoreConfirm;
+
+impl RestoreConfirm {
+    /// Handle the confirmation button click.
+    ///
+    /// The pending restore is consumed to prevent it from being executed
+    /// twice, and the items are recreated in the background. The confirmation
+    /// message is updated with the progress.
+    pub async fn handle(
+        interaction: Interaction,
+        pending: PendingRestore,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
+
+        if !state.cache.consume(&pending).await? {
+            return Ok(embed::error::already_handled(ctx.lang));
+        }
+
+        tokio::spawn(restore::run(
+            state.clone(),
+            pending,
+            ctx.responder(),
+            ctx.lang,
+        ));
+
+        Ok(InteractionResponse::Raw {
+            kind: InteractionResponseType::UpdateMessage,
+            data: Some(InteractionResponseData {
+                components: Some(Vec::new()),
+                embeds: Some(vec![embed::restore::started(ctx.lang)]),
+                ..Default::default()
+            }),
+        })
+    }
+}
@@ -0,0 +1,125 @@
+//! Strip role button of sentinel alerts.
+//!
+//! This button is attached to the alerts sent when a role gains dangerous
+//! permissions or is given to a member (see the [`sentinel`] module).
+//!
+//! [`sentinel`]: crate::event::sentinel
+
+use raidprotect_model::cache::{discord::CachedRole, model::interaction::PendingRoleStrip};
+use tracing::warn;
+use twilight_http::request::AuditLogReason;
+use twilight_model::{
+    application::interaction::Interaction,
+    guild::Permissions,
+    http::interaction::{InteractionResponseData, InteractionResponseType},
+};
+
+use crate::{
+    cluster::ClusterState,
+    interaction::{
+        embed,
+        response::InteractionResponse,
+        util::{GuildConfigExt, GuildInteractionContext},
+    },
+};
+
+/// Strip role button.
+pub struct RoleStrip;
+
+impl RoleStrip {
+    /// Handle the button click.
+    ///
+    /// The button is sent in the logs channel, so the user must have the
+    /// [`MANAGE_ROLES`] permission. The role is removed from the member, or
+    /// the dangerous permissions are removed from the role. The alert message
+    /// is then updated to show who stripped the role.
+    ///
+    /// [`MANAGE_ROLES`]: Permissions::MANAGE_ROLES
+    pub async fn handle(
+        interaction: Interaction,
+        pending: PendingRoleStrip,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
+
+        if let Some(response) = ctx.check_partial() {
+            return Ok(response);
+        }
+
+        let permissions = ctx.member.permissions.unwrap_or_else(Permissions::empty);
+        if pending.guild_id != ctx.guild_id || !permissions.contains(Permissions::MANAGE_ROLES) {
+            return Ok(embed::error::missing_permissions(ctx.lang));
+        }
+
+        let config = ctx.config(state).await?;
+        let reason = config.lang().sentinel_strip_reason(ctx.author.id);
+
+        // The pending state is consumed before stripping the role, so that
+        // concurrent clicks only strip it once. It is restored if the strip
+        // fails to allow another attempt.
+        if !state.cache.consume(&pending).await? {
+            return Ok(embed::error::already_handled(ctx.lang));
+        }
+
+        if let Err(error) = strip(state, &pending, &reason).await {
+            warn!(error = ?error, "failed to strip role");
+            state.cache.set(&pending).await?;
+
+            return Ok(embed::sentinel::strip_failed(ctx.lang));
+        }
+
+        let mut embeds = ctx
+            .interaction
+            .message
+            .map(|message| message.embeds)
+            .unwrap_or_default();
+        embeds.push(embed::sentinel::stripped(ctx.public_lang, ctx.author.id));
+
+        Ok(InteractionResponse::Raw {
+            kind: InteractionResponseType::UpdateMessage,
+            data: Some(InteractionResponseData {
+                components: Some(Vec::new()),
+                embeds: Some(embeds),
+                ..Default::default()
+            }),
+        })
+    }
+}
+
+/// Remove the role from the member, or the dangerous permissions from the
+/// role.
+async fn strip(
+    state: &ClusterState,
+    pending: &PendingRoleStrip,
+    reason: &str,
+) -> Result<(), anyhow::Error> {
+    let cache_http = state.cache_http(pending.guild_id);
+
+    match pending.member_id {
+        Some(member_id) => {
+            cache_http
+                .remove_guild_member_role(member_id, pending.role_id)
+                .await?
+                .reason(reason)?
+                .exec()
+                .await?;
+        }
+        None => {
+            let role = state
+                .cache
+                .get::<CachedRole>(&pending.role_id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("role to strip not found"))?;
+
+            cache_http
+                .update_role(pending.role_id)
+                .await?
+                .permissions(role.permissions - pending.permissions)
+                .reason(reason)?
+                .exec()
+                .await?;
+        }
+    }
+
+    Ok(())
+}
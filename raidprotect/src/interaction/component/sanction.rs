@@ -0,0 +1,160 @@
+//! Sanction components.
+//!
+//! The confirmation button is shown when a moderator starts a sanction on a
+//! user that has just been sanctioned by another moderator (see the
+//! [`moderation`] module). The reason select menu is shown before the reason
+//! modal when the server has reason presets. The template modal is shown to
+//! fill the variables of reason templates.
+//!
+//! [`moderation`]: crate::interaction::command::moderation
+
+use anyhow::{bail, Context};
+use raidprotect_model::{cache::model::interaction::PendingSanction, database::model::ModlogType};
+use twilight_model::application::interaction::{Interaction, InteractionData};
+
+use crate::{
+    cluster::ClusterState,
+    interaction::{
+        command::moderation::{execute_sanction, start_cooldown, KickCommand, REASON_OTHER},
+        embed,
+        response::InteractionResponse,
+        util::{parse_modal_data, parse_modal_field, GuildInteractionContext},
+    },
+    util::template,
+};
+
+/// Sanction confirmation button.
+pub struct SanctionConfirm;
+
+impl SanctionConfirm {
+    /// Handle the confirmation button click.
+    ///
+    /// The pending sanction is consumed to prevent it from being executed
+    /// twice. The sanction cooldown is restarted for the moderator that
+    /// confirmed, and the sanction continues as if there was no cooldown.
+    pub async fn handle(
+        interaction: Interaction,
+        pending: PendingSanction,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
+
+        if !state.cache.consume(&pending).await? {
+            return Ok(embed::error::already_handled(ctx.lang));
+        }
+
+        let config = ctx.config(state).await?;
+
+        start_cooldown(&pending, ctx.guild_id, &config.moderation, state).await?;
+
+        match pending.kind {
+            ModlogType::Kick => {
+                KickCommand::start(pending, &config.moderation, state, ctx.lang).await
+            }
+            _ => execute_sanction(pending, &ctx, &config, state).await,
+        }
+    }
+}
+
+/// Sanction reason select menu.
+pub struct SanctionReasonSelect;
+
+impl SanctionReasonSelect {
+    /// Handle the reason selection.
+    ///
+    /// The selected preset is used as the sanction reason, its variables are
+    /// asked if it is a template. If the "Other…" option is selected, the
+    /// reason modal is shown instead.
+    pub async fn handle(
+        interaction: Interaction,
+        mut pending: PendingSanction,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let value = match &interaction.data {
+            Some(InteractionData::MessageComponent(data)) => data
+                .values
+                .first()
+                .cloned()
+                .context("missing select menu value")?,
+            _ => bail!("expected message component data"),
+        };
+
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
+        let config = ctx.config(state).await?;
+
+        // The pending sanction is kept for the modal.
+        if value == REASON_OTHER {
+            let enforce_reason = config.moderation.enforce_reason;
+            return KickCommand::reason_modal(pending, enforce_reason, state, ctx.lang).await;
+        }
+
+        if !state.cache.consume(&pending).await? {
+            return Ok(embed::error::already_handled(ctx.lang));
+        }
+
+        // The presets may have been updated since the menu was sent.
+        let preset = match value
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| config.moderation.reason_presets.get(index))
+        {
+            Some(preset) => preset.clone(),
+            None => return Ok(embed::error::expired_interaction(ctx.lang)),
+        };
+
+        pending.reason = Some(preset);
+
+        KickCommand::start(pending, &config.moderation, state, ctx.lang).await
+    }
+}
+
+/// Reason template modal.
+pub struct SanctionTemplateModal;
+
+impl SanctionTemplateModal {
+    /// Handle the modal submit.
+    ///
+    /// The reason template of the pending sanction is rendered with the values
+    /// entered by the moderator. If a variable is left unfilled, the sanction
+    /// is not applied and the variables are reported to the moderator.
+    pub async fn handle(
+        mut interaction: Interaction,
+        mut pending: PendingSanction,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let data = parse_modal_data(&mut interaction)?;
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
+
+        if !state.cache.consume(&pending).await? {
+            return Ok(embed::error::already_handled(ctx.lang));
+        }
+
+        let reason = pending.reason.take().context("missing reason template")?;
+        let rendered = template::render(&reason, |name| {
+            parse_modal_field(&data, name).ok().flatten()
+        });
+
+        // Values are inserted as is, and may contain variables themselves.
+        let unfilled = match rendered.is_complete() {
+            true => template::variables(&rendered.text)
+                .into_iter()
+                .map(ToOwned::to_owned)
+                .collect(),
+            false => rendered.unfilled,
+        };
+
+        if !unfilled.is_empty() {
+            return Ok(embed::reasons::unfilled(ctx.lang, &unfilled));
+        }
+
+        pending.reason = Some(rendered.text);
+        let config = ctx.config(state).await?;
+
+        match pending.kind {
+            ModlogType::Kick => {
+                KickCommand::start(pending, &config.moderation, state, ctx.lang).await
+            }
+            _ => execute_sanction(pending, &ctx, &config, state).await,
+        }
+    }
+}
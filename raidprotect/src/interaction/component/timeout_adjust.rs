@@ -0,0 +1,97 @@
+//! Adjustment buttons of the automatic timeouts.
+//!
+//! These buttons are attached to the logs of the filters that timed out a
+//! member (see the [`automation`] module).
+//!
+//! [`automation`]: crate::feature::automation
+
+use raidprotect_model::cache::model::interaction::PendingTimeoutAdjust;
+use twilight_model::{
+    application::interaction::Interaction,
+    guild::Permissions,
+    http::interaction::{InteractionResponseData, InteractionResponseType},
+};
+
+use crate::{
+    cluster::ClusterState,
+    feature::automation::{self, AdjustResult, TimeoutAdjustment},
+    interaction::{
+        embed,
+        response::InteractionResponse,
+        util::{GuildConfigExt, GuildInteractionContext},
+    },
+};
+
+/// Timeout adjustment buttons.
+pub struct TimeoutAdjust;
+
+impl TimeoutAdjust {
+    /// Handle the button click.
+    ///
+    /// The buttons are sent in the logs channel, so the user must have the
+    /// [`MODERATE_MEMBERS`] permission, which is checked at click time since
+    /// it may have changed since the log was sent. The log is then updated to
+    /// show the new end of the timeout. The buttons are removed once the
+    /// timeout is lifted.
+    ///
+    /// [`MODERATE_MEMBERS`]: Permissions::MODERATE_MEMBERS
+    pub async fn handle(
+        interaction: Interaction,
+        pending: PendingTimeoutAdjust,
+        adjustment: TimeoutAdjustment,
+        state: &ClusterState,
+    ) -> Result<InteractionResponse, anyhow::Error> {
+        let ctx = GuildInteractionContext::new(interaction, state).await?;
+
+        if let Some(response) = ctx.check_partial() {
+            return Ok(response);
+        }
+
+        let permissions = ctx.member.permissions.unwrap_or_else(Permissions::empty);
+        if pending.guild_id != ctx.guild_id || !permissions.contains(Permissions::MODERATE_MEMBERS)
+        {
+            return Ok(embed::error::missing_permissions(ctx.lang));
+        }
+
+        let config = ctx.config(state).await?;
+        let result =
+            match automation::adjust(state, &config, &pending, adjustment, &ctx.author).await? {
+                Some(result) => result,
+                None => return Ok(embed::error::already_handled(ctx.lang)),
+            };
+
+        let until = match result {
+            AdjustResult::Ended => return Ok(embed::timeout_adjust::ended(ctx.lang)),
+            AdjustResult::Lifted => None,
+            AdjustResult::Ends(until) => Some(until),
+        };
+
+        let (mut embeds, mut components) = ctx
+            .interaction
+            .message
+            .map(|message| (message.embeds, message.components))
+            .unwrap_or_default();
+
+        if let Some(embed) = embeds.pop() {
+            embeds.push(embed::timeout_adjust::adjusted(
+                config.lang(),
+                embed,
+                ctx.author.id,
+                until,
+            ));
+        }
+
+        if until.is_none() {
+            components.retain(|component| !automation::is_adjust_row(component));
+        }
+
+        Ok(InteractionResponse::Raw {
+            kind: InteractionResponseType::UpdateMessage,
+            data: Some(InteractionResponseData {
+                components: Some(components),
+                embeds: Some(embeds),
+                ..Default::default()
+            }),
+        })
+    }
+}
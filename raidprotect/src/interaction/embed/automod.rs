@@ -0,0 +1,142 @@
+//! Embeds for the AutoMod configuration.
+
+use raidprotect_model::database::model::{AutomodConfig, AutomodRuleKind};
+use twilight_model::channel::embed::Embed;
+
+use super::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS};
+use crate::{
+    feature::automod::SyncReport, interaction::response::InteractionResponse, translations::Lang,
+};
+
+/// AutoMod configuration updated.
+///
+/// The result of the native rules synchronization is appended to the
+/// configuration summary.
+pub fn updated(lang: Lang, config: &AutomodConfig, report: &SyncReport) -> Embed {
+    let enabled = match config.enabled {
+        true => lang.enabled(),
+        false => lang.disabled(),
+    };
+    let keywords = match config.keywords.is_empty() {
+        true => lang.automod_no_keywords().to_owned(),
+        false => config
+            .keywords
+            .iter()
+            .map(|keyword| format!("`{keyword}`"))
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+    let spam = match config.spam {
+        true => lang.enabled(),
+        false => lang.disabled(),
+    };
+    let mention_limit = match config.mention_limit {
+        Some(limit) => limit.to_string(),
+        None => lang.disabled().to_owned(),
+    };
+
+    let mut description = lang.config_automod_updated(enabled, keywords, mention_limit, spam);
+    description.push_str("\n\n");
+    description.push_str(&sync_summary(lang, report));
+
+    EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(description)
+        .build()
+}
+
+/// Manually edited rules overwritten.
+pub fn overwritten(lang: Lang, report: &SyncReport) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(sync_summary(lang, report))
+        .build()
+}
+
+/// Too many blocked keywords.
+pub fn keywords_too_many(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.automod_keywords_too_many(AutomodConfig::MAX_KEYWORDS_LEN))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Summary of a native rules synchronization.
+fn sync_summary(lang: Lang, report: &SyncReport) -> String {
+    let mut summary = lang.automod_synced(report.created, report.deleted, report.updated);
+
+    if !report.failed.is_empty() {
+        summary.push('\n');
+        summary.push_str(&lang.automod_failed(rule_names(lang, &report.failed)));
+    }
+
+    if !report.conflicts.is_empty() {
+        summary.push('\n');
+        summary.push_str(&lang.automod_conflicts(rule_names(lang, &report.conflicts)));
+    }
+
+    summary
+}
+
+/// Names of a list of rules.
+fn rule_names(lang: Lang, rules: &[AutomodRuleKind]) -> String {
+    rules
+        .iter()
+        .map(|kind| match kind {
+            AutomodRuleKind::Keyword => lang.automod_rule_keyword(),
+            AutomodRuleKind::Spam => lang.automod_rule_spam(),
+            AutomodRuleKind::MentionSpam => lang.automod_rule_mention_spam(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> SyncReport {
+        SyncReport {
+            created: 1,
+            conflicts: vec![AutomodRuleKind::Keyword],
+            failed: vec![AutomodRuleKind::Spam, AutomodRuleKind::MentionSpam],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_updated() {
+        let config = AutomodConfig {
+            enabled: true,
+            keywords: vec!["spam".to_owned()],
+            mention_limit: Some(5),
+            ..Default::default()
+        };
+
+        updated(Lang::DEFAULT, &config, &report());
+        updated(
+            Lang::DEFAULT,
+            &AutomodConfig::default(),
+            &SyncReport::default(),
+        );
+    }
+
+    #[test]
+    fn test_overwritten() {
+        let embed = overwritten(Lang::DEFAULT, &report());
+
+        assert!(embed
+            .description
+            .unwrap()
+            .contains(&rule_names(Lang::DEFAULT, &[AutomodRuleKind::Keyword])));
+    }
+
+    #[test]
+    fn test_keywords_too_many() {
+        keywords_too_many(Lang::DEFAULT);
+    }
+}
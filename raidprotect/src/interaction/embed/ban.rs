@@ -0,0 +1,158 @@
+//! Embed for the ban command.
+
+use twilight_model::id::{marker::UserMarker, Id};
+
+use super::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS};
+use crate::{interaction::response::InteractionResponse, translations::Lang, util::TextProcessExt};
+
+/// The specified user id is not valid.
+pub fn invalid_id(input: String, lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.ban_invalid_id(input.remove_markdown().max_len(30)))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// No Discord user exists with the specified id.
+pub fn unknown_user(user: Id<UserMarker>, lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.ban_unknown_user(user))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// The user is already banned from the server.
+pub fn already_banned(user: String, lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.ban_already_banned(user.remove_markdown().max_len(30)))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Bot is missing the `BAN_MEMBERS` permission
+pub fn bot_missing_permission(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.ban_bot_missing_permission_title())
+        .description(lang.bot_missing_permission())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// User cannot ban due to the role hierarchy
+pub fn user_hierarchy(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.ban_missing_permission_title())
+        .description(lang.hierarchy_user())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Bot cannot ban due to the role hierarchy
+pub fn bot_hierarchy(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.ban_bot_missing_permission_title())
+        .description(lang.hierarchy_bot())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Member is the guild owner, and thus cannot be banned
+pub fn member_owner(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.ban_missing_permission_title())
+        .description(lang.hierarchy_owner())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// The ban could not be applied.
+pub fn error(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.ban_error_title())
+        .description(lang.ban_error_description())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// The user has been banned.
+pub fn success(user: String, pre_emptive: bool, lang: Lang) -> InteractionResponse {
+    let user = user.remove_markdown().max_len(30);
+    let description = match pre_emptive {
+        true => lang.ban_success_pre_emptive(user),
+        false => lang.ban_success(user),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .description(description)
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_id() {
+        invalid_id("test".to_owned(), Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_unknown_user() {
+        unknown_user(Id::new(1), Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_already_banned() {
+        already_banned("test".to_owned(), Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_bot_missing_permission() {
+        bot_missing_permission(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_user_hierarchy() {
+        user_hierarchy(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_bot_hierarchy() {
+        bot_hierarchy(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_member_owner() {
+        member_owner(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_error() {
+        error(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_success() {
+        success("test".to_owned(), false, Lang::DEFAULT);
+        success("test".to_owned(), true, Lang::DEFAULT);
+    }
+}
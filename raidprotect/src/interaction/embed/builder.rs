@@ -0,0 +1,375 @@
+//! Embed builder enforcing Discord limits.
+//!
+//! Discord rejects messages with embeds that exceed its [limits], which makes
+//! the bot silently fail to respond. All outgoing embeds are built with the
+//! [`EmbedBuilder`] of this module, which wraps the [twilight builder] and
+//! truncates values that exceed these limits.
+//!
+//! Values that must not be truncated, such as identifiers, are set with the
+//! `required_*` methods that return an [`EmbedLimitError`] instead.
+//!
+//! [limits]: https://discord.com/developers/docs/resources/channel#embed-object-embed-limits
+//! [twilight builder]: twilight_util::builder::embed::EmbedBuilder
+
+use std::{error::Error, fmt};
+
+use twilight_model::channel::embed::{Embed, EmbedField, EmbedFooter};
+use twilight_util::builder::embed::{EmbedBuilder as TwilightEmbedBuilder, ImageSource};
+
+/// Maximum length of the embed title.
+pub const TITLE_LENGTH: usize = 256;
+/// Maximum length of the embed description.
+pub const DESCRIPTION_LENGTH: usize = 4096;
+/// Maximum number of fields.
+pub const FIELD_COUNT: usize = 25;
+/// Maximum length of a field name.
+pub const FIELD_NAME_LENGTH: usize = 256;
+/// Maximum length of a field value.
+pub const FIELD_VALUE_LENGTH: usize = 1024;
+/// Maximum length of the footer text.
+pub const FOOTER_TEXT_LENGTH: usize = 2048;
+/// Maximum length of the author name.
+pub const AUTHOR_NAME_LENGTH: usize = 256;
+/// Maximum total length of the embed.
+pub const TOTAL_LENGTH: usize = 6000;
+
+/// Error returned when a required value exceeds the embed limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmbedLimitError {
+    /// Name of the value.
+    pub kind: &'static str,
+    /// Length of the value.
+    pub len: usize,
+    /// Maximum length of the value.
+    pub max: usize,
+}
+
+impl fmt::Display for EmbedLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "embed {} is too long ({} > {})",
+            self.kind, self.len, self.max
+        )
+    }
+}
+
+impl Error for EmbedLimitError {}
+
+/// Builder for an [`Embed`] that respects Discord limits.
+///
+/// See the [module documentation](self) for more information.
+#[derive(Debug)]
+#[must_use = "must be built into an embed"]
+pub struct EmbedBuilder {
+    inner: TwilightEmbedBuilder,
+    /// Number of fields added to the embed.
+    field_count: usize,
+    /// Index of the fields that must not be removed.
+    required_fields: Vec<usize>,
+    /// Whether the footer must not be truncated.
+    required_footer: bool,
+}
+
+impl EmbedBuilder {
+    /// Create a new [`EmbedBuilder`].
+    pub fn new() -> Self {
+        Self {
+            inner: TwilightEmbedBuilder::new(),
+            field_count: 0,
+            required_fields: Vec::new(),
+            required_footer: false,
+        }
+    }
+
+    /// Set the color of the embed.
+    pub fn color(mut self, color: u32) -> Self {
+        self.inner = self.inner.color(color);
+        self
+    }
+
+    /// Set the title of the embed.
+    ///
+    /// The title is truncated to [`TITLE_LENGTH`] characters.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.inner = self.inner.title(title);
+        self
+    }
+
+    /// Set the description of the embed.
+    ///
+    /// The description is truncated to [`DESCRIPTION_LENGTH`] characters, and
+    /// may be truncated further to respect the [`TOTAL_LENGTH`] limit.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.inner = self.inner.description(description);
+        self
+    }
+
+    /// Add a field to the embed.
+    ///
+    /// The field name and value are truncated to [`FIELD_NAME_LENGTH`] and
+    /// [`FIELD_VALUE_LENGTH`] characters. Fields after the [`FIELD_COUNT`]
+    /// first ones are removed, and the last fields may be removed to respect
+    /// the [`TOTAL_LENGTH`] limit.
+    pub fn field(mut self, field: impl Into<EmbedField>) -> Self {
+        self.inner = self.inner.field(field);
+        self.field_count += 1;
+        self
+    }
+
+    /// Add a field that must not be truncated or removed.
+    pub fn required_field(mut self, field: impl Into<EmbedField>) -> Result<Self, EmbedLimitError> {
+        let field = field.into();
+
+        check_len("field count", self.field_count + 1, FIELD_COUNT)?;
+        check_len("field name", len(&field.name), FIELD_NAME_LENGTH)?;
+        check_len("field value", len(&field.value), FIELD_VALUE_LENGTH)?;
+
+        self.required_fields.push(self.field_count);
+        Ok(self.field(field))
+    }
+
+    /// Set the footer of the embed.
+    ///
+    /// The footer text is truncated to [`FOOTER_TEXT_LENGTH`] characters.
+    pub fn footer(mut self, footer: impl Into<EmbedFooter>) -> Self {
+        self.inner = self.inner.footer(footer);
+        self.required_footer = false;
+        self
+    }
+
+    /// Set a footer that must not be truncated.
+    pub fn required_footer(
+        mut self,
+        footer: impl Into<EmbedFooter>,
+    ) -> Result<Self, EmbedLimitError> {
+        let footer = footer.into();
+        check_len("footer", len(&footer.text), FOOTER_TEXT_LENGTH)?;
+
+        self = self.footer(footer);
+        self.required_footer = true;
+        Ok(self)
+    }
+
+    /// Set the thumbnail of the embed.
+    pub fn thumbnail(mut self, thumbnail: ImageSource) -> Self {
+        self.inner = self.inner.thumbnail(thumbnail);
+        self
+    }
+
+    /// Set the image of the embed.
+    pub fn image(mut self, image: ImageSource) -> Self {
+        self.inner = self.inner.image(image);
+        self
+    }
+
+    /// Build the [`Embed`], truncating the values that exceed the limits.
+    pub fn build(self) -> Embed {
+        let mut embed = self.inner.build();
+
+        if let Some(title) = &mut embed.title {
+            truncate(title, TITLE_LENGTH);
+        }
+
+        if let Some(description) = &mut embed.description {
+            truncate(description, DESCRIPTION_LENGTH);
+        }
+
+        if let Some(author) = &mut embed.author {
+            truncate(&mut author.name, AUTHOR_NAME_LENGTH);
+        }
+
+        if let Some(footer) = &mut embed.footer {
+            if !self.required_footer {
+                truncate(&mut footer.text, FOOTER_TEXT_LENGTH);
+            }
+        }
+
+        embed.fields.truncate(FIELD_COUNT);
+        for field in &mut embed.fields {
+            truncate(&mut field.name, FIELD_NAME_LENGTH);
+            truncate(&mut field.value, FIELD_VALUE_LENGTH);
+        }
+
+        fit_total_length(&mut embed, &self.required_fields);
+
+        embed
+    }
+}
+
+impl Default for EmbedBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Truncate a text to a maximum number of characters.
+///
+/// Truncated texts end with `...`, without exceeding the maximum length.
+pub fn truncate(text: &mut String, max: usize) {
+    if len(text) <= max {
+        return;
+    }
+
+    let end = text
+        .char_indices()
+        .nth(max.saturating_sub(3))
+        .map(|(index, _)| index)
+        .unwrap_or(text.len());
+
+    text.truncate(end);
+    text.push_str("...");
+}
+
+/// Reduce the length of the embed to respect the [`TOTAL_LENGTH`] limit.
+///
+/// The description is truncated first, then the last non-required fields are
+/// removed.
+fn fit_total_length(embed: &mut Embed, required_fields: &[usize]) {
+    let excess = total_length(embed).saturating_sub(TOTAL_LENGTH);
+
+    if excess == 0 {
+        return;
+    }
+
+    if let Some(description) = &mut embed.description {
+        let max = len(description).saturating_sub(excess).max(3);
+        truncate(description, max);
+    }
+
+    let mut index = embed.fields.len();
+    while total_length(embed) > TOTAL_LENGTH && index > 0 {
+        index -= 1;
+
+        if !required_fields.contains(&index) {
+            embed.fields.remove(index);
+        }
+    }
+}
+
+/// Compute the total length of an embed, as counted by Discord.
+fn total_length(embed: &Embed) -> usize {
+    let title = embed.title.as_deref().map(len).unwrap_or_default();
+    let description = embed.description.as_deref().map(len).unwrap_or_default();
+    let author = embed
+        .author
+        .as_ref()
+        .map(|a| len(&a.name))
+        .unwrap_or_default();
+    let footer = embed
+        .footer
+        .as_ref()
+        .map(|f| len(&f.text))
+        .unwrap_or_default();
+    let fields: usize = embed
+        .fields
+        .iter()
+        .map(|field| len(&field.name) + len(&field.value))
+        .sum();
+
+    title + description + author + footer + fields
+}
+
+/// Get the number of characters of a text.
+fn len(text: &str) -> usize {
+    text.chars().count()
+}
+
+/// Check the length of a required value.
+fn check_len(kind: &'static str, len: usize, max: usize) -> Result<(), EmbedLimitError> {
+    if len > max {
+        return Err(EmbedLimitError { kind, len, max });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_util::builder::embed::{EmbedFieldBuilder, EmbedFooterBuilder};
+
+    use super::*;
+
+    #[test]
+    fn test_truncate() {
+        let mut text = "hello world".to_owned();
+        truncate(&mut text, 9);
+        assert_eq!(text, "hello ...");
+
+        let mut text = "hello world".to_owned();
+        truncate(&mut text, 11);
+        assert_eq!(text, "hello world");
+
+        let mut text = "héllo wörld".to_owned();
+        truncate(&mut text, 8);
+        assert_eq!(text, "héllo...");
+    }
+
+    #[test]
+    fn test_truncate_values() {
+        let embed = EmbedBuilder::new()
+            .title("a".repeat(300))
+            .description("b".repeat(5000))
+            .field(EmbedFieldBuilder::new("c".repeat(300), "d".repeat(1100)))
+            .footer(EmbedFooterBuilder::new("e".repeat(2100)))
+            .build();
+
+        assert_eq!(len(embed.title.as_ref().unwrap()), TITLE_LENGTH);
+        assert_eq!(len(&embed.fields[0].name), FIELD_NAME_LENGTH);
+        assert_eq!(len(&embed.fields[0].value), FIELD_VALUE_LENGTH);
+        assert_eq!(
+            len(&embed.footer.as_ref().unwrap().text),
+            FOOTER_TEXT_LENGTH
+        );
+        assert!(total_length(&embed) <= TOTAL_LENGTH);
+    }
+
+    #[test]
+    fn test_field_count() {
+        let mut builder = EmbedBuilder::new();
+        for i in 0..30 {
+            builder = builder.field(EmbedFieldBuilder::new(i.to_string(), "value"));
+        }
+
+        assert_eq!(builder.build().fields.len(), FIELD_COUNT);
+    }
+
+    #[test]
+    fn test_total_length() {
+        let mut builder = EmbedBuilder::new().description("a".repeat(100));
+        builder = builder
+            .required_field(EmbedFieldBuilder::new("id", "required"))
+            .unwrap();
+        for _ in 0..10 {
+            builder = builder.field(EmbedFieldBuilder::new("name", "b".repeat(1000)));
+        }
+
+        let embed = builder.build();
+
+        assert!(total_length(&embed) <= TOTAL_LENGTH);
+        assert_eq!(embed.fields[0].value, "required");
+    }
+
+    #[test]
+    fn test_required_values() {
+        let footer = EmbedBuilder::new().required_footer(EmbedFooterBuilder::new("a".repeat(3000)));
+        assert_eq!(
+            footer.unwrap_err(),
+            EmbedLimitError {
+                kind: "footer",
+                len: 3000,
+                max: FOOTER_TEXT_LENGTH
+            }
+        );
+
+        let field =
+            EmbedBuilder::new().required_field(EmbedFieldBuilder::new("id", "a".repeat(2000)));
+        assert!(field.is_err());
+
+        let embed = EmbedBuilder::new()
+            .required_footer(EmbedFooterBuilder::new("ID: 1"))
+            .unwrap()
+            .build();
+        assert_eq!(embed.footer.unwrap().text, "ID: 1");
+    }
+}
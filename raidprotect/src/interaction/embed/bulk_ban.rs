@@ -0,0 +1,140 @@
+//! Embeds for the bulk ban command.
+
+use std::fmt::Display;
+
+use twilight_model::{
+    channel::embed::Embed,
+    id::{marker::UserMarker, Id},
+};
+
+use super::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS, COLOR_TRANSPARENT};
+use crate::{interaction::response::InteractionResponse, translations::Lang};
+
+/// Maximum number of ids listed in an embed.
+pub(super) const MAX_IDS_LISTED: usize = 20;
+
+/// Error while preparing a bulk ban.
+pub fn error(lang: Lang, description: impl Into<String>) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.bulk_ban_error_title())
+        .description(description)
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Confirmation before executing a bulk ban.
+pub fn confirmation(lang: Lang, count: usize, invalid: usize) -> Embed {
+    let mut description = lang.bulk_ban_confirm(count);
+    if invalid > 0 {
+        description.push_str("\n\n");
+        description.push_str(&lang.bulk_ban_invalid_entries(invalid));
+    }
+
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.bulk_ban_confirm_title())
+        .description(description)
+        .build()
+}
+
+/// The bulk ban is being cancelled.
+pub fn cancelling(lang: Lang) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .description(lang.bulk_ban_cancelling())
+        .build()
+}
+
+/// Progress of a running bulk ban.
+pub fn progress(lang: Lang, done: usize, total: usize) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .description(lang.bulk_ban_progress(done, total))
+        .build()
+}
+
+/// Summary of a completed bulk ban.
+///
+/// If the bulk ban has been cancelled, the number of users that have not been
+/// banned is shown.
+pub fn summary(lang: Lang, banned: usize, failed: &[Id<UserMarker>], cancelled: usize) -> Embed {
+    let mut description = lang.bulk_ban_summary(banned);
+
+    if cancelled > 0 {
+        description.push_str("\n\n");
+        description.push_str(&lang.bulk_ban_cancelled(cancelled));
+    }
+
+    if !failed.is_empty() {
+        description.push_str("\n\n");
+        description.push_str(&lang.bulk_ban_failed(failed.len(), id_list(lang, failed)));
+    }
+
+    EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .description(description)
+        .build()
+}
+
+/// List of ids shown in an embed.
+///
+/// At most [`MAX_IDS_LISTED`] ids are listed, followed by the number of ids
+/// that are not shown.
+pub(super) fn id_list<T: Display>(lang: Lang, ids: &[T]) -> String {
+    let mut listed = ids
+        .iter()
+        .take(MAX_IDS_LISTED)
+        .map(|id| format!("`{id}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    if ids.len() > MAX_IDS_LISTED {
+        listed.push(' ');
+        listed.push_str(&lang.bulk_ban_more_ids(ids.len() - MAX_IDS_LISTED));
+    }
+
+    listed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error() {
+        error(Lang::DEFAULT, Lang::DEFAULT.bulk_ban_no_users());
+    }
+
+    #[test]
+    fn test_confirmation() {
+        confirmation(Lang::DEFAULT, 10, 2);
+    }
+
+    #[test]
+    fn test_progress() {
+        progress(Lang::DEFAULT, 25, 100);
+    }
+
+    #[test]
+    fn test_summary() {
+        let failed = (1..30).map(Id::new).collect::<Vec<_>>();
+
+        summary(Lang::DEFAULT, 70, &failed, 0);
+        summary(Lang::DEFAULT, 20, &[], 80);
+    }
+
+    #[test]
+    fn test_id_list() {
+        let ids = (1..=21).collect::<Vec<u64>>();
+
+        assert_eq!(id_list(Lang::DEFAULT, &ids[..2]), "`1`, `2`");
+        assert!(id_list(Lang::DEFAULT, &ids).ends_with("`20` and 1 more"));
+    }
+
+    #[test]
+    fn test_cancelling() {
+        cancelling(Lang::DEFAULT);
+    }
+}
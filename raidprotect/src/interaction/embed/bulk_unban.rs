@@ -0,0 +1,113 @@
+//! Embeds for the bulk unban command.
+
+use twilight_model::{
+    channel::embed::Embed,
+    id::{marker::UserMarker, Id},
+};
+
+use super::{bulk_ban::id_list, EmbedBuilder, COLOR_RED, COLOR_SUCCESS, COLOR_TRANSPARENT};
+use crate::{interaction::response::InteractionResponse, translations::Lang};
+
+/// Error while preparing a bulk unban.
+pub fn error(lang: Lang, description: impl Into<String>) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.bulk_unban_error_title())
+        .description(description)
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Confirmation before executing a bulk unban.
+pub fn confirmation(lang: Lang, count: usize, hours: i64) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.bulk_unban_confirm_title())
+        .description(lang.bulk_unban_confirm(count, hours))
+        .build()
+}
+
+/// The bulk unban is being cancelled.
+pub fn cancelling(lang: Lang) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .description(lang.bulk_unban_cancelling())
+        .build()
+}
+
+/// Progress of a running bulk unban.
+pub fn progress(lang: Lang, done: usize, total: usize) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .description(lang.bulk_unban_progress(done, total))
+        .build()
+}
+
+/// Summary of a completed bulk unban.
+///
+/// The users that were already unbanned are counted in `skipped`. If the bulk
+/// unban has been cancelled, the number of users that have not been unbanned
+/// is shown.
+pub fn summary(
+    lang: Lang,
+    unbanned: usize,
+    skipped: usize,
+    failed: &[Id<UserMarker>],
+    cancelled: usize,
+) -> Embed {
+    let mut description = lang.bulk_unban_summary(unbanned);
+
+    if skipped > 0 {
+        description.push_str("\n\n");
+        description.push_str(&lang.bulk_unban_skipped(skipped));
+    }
+
+    if cancelled > 0 {
+        description.push_str("\n\n");
+        description.push_str(&lang.bulk_unban_cancelled(cancelled));
+    }
+
+    if !failed.is_empty() {
+        description.push_str("\n\n");
+        description.push_str(&lang.bulk_unban_failed(failed.len(), id_list(lang, failed)));
+    }
+
+    EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .description(description)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error() {
+        error(Lang::DEFAULT, Lang::DEFAULT.bulk_unban_no_users(24));
+    }
+
+    #[test]
+    fn test_confirmation() {
+        confirmation(Lang::DEFAULT, 10, 24);
+    }
+
+    #[test]
+    fn test_progress() {
+        progress(Lang::DEFAULT, 25, 100);
+    }
+
+    #[test]
+    fn test_summary() {
+        let failed = (1..30).map(Id::new).collect::<Vec<_>>();
+
+        summary(Lang::DEFAULT, 70, 2, &failed, 0);
+        summary(Lang::DEFAULT, 20, 0, &[], 80);
+    }
+
+    #[test]
+    fn test_cancelling() {
+        cancelling(Lang::DEFAULT);
+    }
+}
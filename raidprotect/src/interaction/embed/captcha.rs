@@ -1,7 +1,6 @@
 //! Embeds for the captcha configuration commands.
-use twilight_util::builder::embed::EmbedBuilder;
 
-use super::COLOR_RED;
+use super::{EmbedBuilder, COLOR_RED};
 use crate::{interaction::response::InteractionResponse, translations::Lang};
 
 /// Captcha not enabled on the server.
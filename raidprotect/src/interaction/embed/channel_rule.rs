@@ -0,0 +1,113 @@
+//! Embeds for the channel rule command.
+
+use raidprotect_model::database::model::{ChannelRule, ChannelRuleKind};
+use twilight_mention::Mention;
+use twilight_model::id::{marker::ChannelMarker, Id};
+
+use super::{EmbedBuilder, COLOR_RED, COLOR_TRANSPARENT};
+use crate::{interaction::response::InteractionResponse, translations::Lang};
+
+/// List of the channel rules.
+pub fn list(lang: Lang, rules: &[ChannelRule]) -> InteractionResponse {
+    let description = match rules.is_empty() {
+        true => lang.channel_rule_list_empty().to_owned(),
+        false => rules
+            .iter()
+            .map(|rule| {
+                format!(
+                    "- {}: {}",
+                    rule.channel_id.mention(),
+                    rule_kind(lang, &rule.kind)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .title(lang.channel_rule_list_title())
+        .description(description)
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Description of a channel rule.
+fn rule_kind(lang: Lang, kind: &ChannelRuleKind) -> String {
+    match kind {
+        ChannelRuleKind::MediaOnly => lang.channel_rule_media_only().to_owned(),
+        ChannelRuleKind::TextOnly => lang.channel_rule_text_only().to_owned(),
+        ChannelRuleKind::LinkOnlyFrom { domains } => {
+            let domains = domains
+                .iter()
+                .map(|domain| format!("`{domain}`"))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            lang.channel_rule_link_only(domains)
+        }
+    }
+}
+
+/// Invalid domain provided for a links-only rule.
+pub fn invalid_domain(lang: Lang, value: &str) -> InteractionResponse {
+    error(lang.channel_rule_invalid_domain(value))
+}
+
+/// Missing or too many domains for a links-only rule.
+pub fn domains_count(lang: Lang) -> InteractionResponse {
+    error(lang.channel_rule_domains_count(ChannelRule::MAX_DOMAINS))
+}
+
+/// Too many channel rules.
+pub fn too_many(lang: Lang) -> InteractionResponse {
+    error(lang.channel_rule_too_many(ChannelRule::MAX_RULES))
+}
+
+/// No rule set in the channel.
+pub fn not_found(lang: Lang, channel: Id<ChannelMarker>) -> InteractionResponse {
+    error(lang.channel_rule_not_found(channel.mention()))
+}
+
+/// Error while updating the channel rules.
+fn error(description: String) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(description)
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list() {
+        let rules = vec![
+            ChannelRule {
+                channel_id: Id::new(1),
+                kind: ChannelRuleKind::MediaOnly,
+            },
+            ChannelRule {
+                channel_id: Id::new(2),
+                kind: ChannelRuleKind::LinkOnlyFrom {
+                    domains: vec!["youtube.com".to_owned()],
+                },
+            },
+        ];
+
+        list(Lang::DEFAULT, &[]);
+        list(Lang::DEFAULT, &rules);
+    }
+
+    #[test]
+    fn test_errors() {
+        invalid_domain(Lang::DEFAULT, "invalid");
+        domains_count(Lang::DEFAULT);
+        too_many(Lang::DEFAULT);
+        not_found(Lang::DEFAULT, Id::new(1));
+    }
+}
@@ -0,0 +1,116 @@
+//! Embeds for the messages cleanup.
+
+use twilight_mention::Mention;
+use twilight_model::channel::embed::{Embed, EmbedField};
+
+use super::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS};
+use crate::{feature::cleanup::CleanupReport, translations::Lang};
+
+/// Maximum number of channels listed in the summary, to keep it within the
+/// embed field length limit.
+const MAX_LISTED_CHANNELS: usize = 10;
+
+/// Result of a messages cleanup.
+pub fn report(lang: Lang, report: &CleanupReport) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.cleanup_title())
+        .description(summary(lang, report))
+        .build()
+}
+
+/// Add the result of a messages cleanup to a sanction embed.
+pub fn with_report(embed: Embed, lang: Lang, report: &CleanupReport) -> Embed {
+    push_field(embed, lang, summary(lang, report))
+}
+
+/// Add the failure of a messages cleanup to a sanction embed.
+pub fn with_error(embed: Embed, lang: Lang) -> Embed {
+    push_field(embed, lang, lang.cleanup_error().to_owned())
+}
+
+/// Messages cleanup failed.
+pub fn error(lang: Lang) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.cleanup_title())
+        .description(lang.cleanup_error())
+        .build()
+}
+
+/// Add the messages cleanup field to an embed.
+fn push_field(mut embed: Embed, lang: Lang, value: String) -> Embed {
+    embed.fields.push(EmbedField {
+        inline: false,
+        name: lang.cleanup_title().to_owned(),
+        value,
+    });
+
+    embed
+}
+
+/// Summary of a messages cleanup, with the number of deleted messages in
+/// each channel.
+fn summary(lang: Lang, report: &CleanupReport) -> String {
+    let mut summary = lang.cleanup_deleted(report.deleted());
+
+    for (channel, count) in report.channels.iter().take(MAX_LISTED_CHANNELS) {
+        summary.push('\n');
+        summary.push_str(&lang.cleanup_channel(channel.mention(), count));
+    }
+
+    if report.channels.len() > MAX_LISTED_CHANNELS {
+        summary.push_str("\n…");
+    }
+
+    if report.skipped > 0 {
+        summary.push('\n');
+        summary.push_str(&lang.cleanup_skipped(report.skipped));
+    }
+
+    if report.failed > 0 {
+        summary.push('\n');
+        summary.push_str(&lang.cleanup_failed(report.failed));
+    }
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::id::Id;
+
+    use super::*;
+
+    fn cleanup_report() -> CleanupReport {
+        CleanupReport {
+            channels: vec![(Id::new(1), 3), (Id::new(2), 1)],
+            skipped: 1,
+            failed: 2,
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let embed = report(Lang::DEFAULT, &cleanup_report());
+
+        assert!(embed.description.unwrap().contains("<#1>"));
+        report(Lang::DEFAULT, &CleanupReport::default());
+    }
+
+    #[test]
+    fn test_with_report() {
+        let embed = EmbedBuilder::new().description("muted").build();
+        let embed = with_report(embed, Lang::DEFAULT, &cleanup_report());
+
+        assert_eq!(embed.fields.len(), 1);
+
+        let embed = with_error(embed, Lang::DEFAULT);
+        assert_eq!(embed.fields.len(), 2);
+    }
+
+    #[test]
+    fn test_error() {
+        error(Lang::DEFAULT);
+    }
+}
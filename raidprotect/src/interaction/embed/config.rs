@@ -0,0 +1,862 @@
+//! Embeds for the configuration commands.
+
+use raidprotect_model::database::model::{
+    ActivityRoleConfig, AntinukeAction, AntinukeConfig, FirstMessageAction, FirstMessageConfig,
+    GraceConfig, GuildConfig, JoinBurstConfig, JoinBurstSignal, LinkFilterConfig, LogCategory,
+    LogDestination, NameAction, NameConfig, PatternAction, PatternRule, PinsConfig, RaidModeAction,
+    RaidModeConfig, ReactionSpamConfig, RoleLogsConfig, VoiceConfig, VoiceHopAction,
+};
+use twilight_mention::Mention;
+use twilight_model::{
+    guild::VerificationLevel,
+    id::{marker::RoleMarker, Id},
+};
+
+use super::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS};
+use crate::{
+    interaction::{response::InteractionResponse, util::GuildConfigExt},
+    translations::Lang,
+    util::pattern::PatternError,
+};
+
+/// Raid mode configuration updated.
+pub fn raid_mode_updated(lang: Lang, config: &RaidModeConfig) -> InteractionResponse {
+    let account_age = match config.account_age {
+        Some(age) => lang.raid_mode_account_age(age),
+        None => lang.disabled().to_owned(),
+    };
+    let verification_level = verification_level_name(config.verification_level, lang);
+    let join_alerts = match config.join_alerts {
+        true => lang.enabled(),
+        false => lang.disabled(),
+    };
+    let action = match config.action {
+        RaidModeAction::Kick => lang.raid_mode_action_kick().to_owned(),
+        RaidModeAction::Review => {
+            let channel = match config.review_channel {
+                Some(channel) => channel.mention().to_string(),
+                None => lang.raid_mode_review_logs_channel().to_owned(),
+            };
+
+            lang.raid_mode_action_review(channel)
+        }
+    };
+
+    let mut description =
+        lang.config_raid_mode_updated(account_age, action, join_alerts, verification_level);
+    if config.action == RaidModeAction::Review {
+        let member_role = match config.member_role {
+            Some(role) => role.mention().to_string(),
+            None => lang.raid_mode_no_member_role().to_owned(),
+        };
+
+        description.push('\n');
+        description.push_str(&lang.config_raid_mode_member_role(member_role));
+    }
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(description)
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Reaction spam configuration updated.
+pub fn reaction_spam_updated(lang: Lang, config: &ReactionSpamConfig) -> InteractionResponse {
+    let enabled = match config.enabled {
+        true => lang.enabled(),
+        false => lang.disabled(),
+    };
+    let timeout = match config.timeout {
+        Some(timeout) => lang.reaction_spam_timeout(timeout),
+        None => lang.disabled().to_owned(),
+    };
+    let trusted_roles = match config.trusted_roles.is_empty() {
+        true => lang.reaction_spam_no_trusted_roles().to_owned(),
+        false => config
+            .trusted_roles
+            .iter()
+            .map(|role| role.mention().to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_reaction_spam_updated(
+            enabled,
+            config.interval,
+            config.max_reactions,
+            timeout,
+            trusted_roles,
+        ))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Link filter configuration updated.
+pub fn link_filter_updated(lang: Lang, config: &LinkFilterConfig) -> InteractionResponse {
+    let status = |enabled: bool| match enabled {
+        true => lang.enabled(),
+        false => lang.disabled(),
+    };
+    let timeout = match config.timeout {
+        Some(timeout) => lang.reaction_spam_timeout(timeout),
+        None => lang.disabled().to_owned(),
+    };
+    let allowed = config
+        .allowed_domains
+        .iter()
+        .map(|domain| format!("`{domain}`"))
+        .chain(
+            config
+                .allowed_invites
+                .iter()
+                .map(|code| format!("`discord.gg/{code}`")),
+        )
+        .collect::<Vec<_>>();
+    let allowed = match allowed.is_empty() {
+        true => lang.link_filter_none().to_owned(),
+        false => allowed.join(", "),
+    };
+    let trusted_roles = match config.trusted_roles.is_empty() {
+        true => lang.link_filter_none().to_owned(),
+        false => config
+            .trusted_roles
+            .iter()
+            .map(|role| role.mention().to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_link_filter_updated(
+            allowed,
+            status(config.enabled),
+            status(config.invites),
+            status(config.links),
+            timeout,
+            trusted_roles,
+        ))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Invalid domain or invite in the link filter allowlist.
+pub fn link_filter_invalid(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.config_link_filter_invalid())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Too many domains or invites in the link filter allowlist.
+pub fn link_filter_allowed_too_many(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.config_link_filter_allowed_too_many(LinkFilterConfig::MAX_ALLOWED_LEN))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Pattern rules configuration updated.
+pub fn pattern_rules_updated(lang: Lang, rules: &[PatternRule]) -> InteractionResponse {
+    let rules = match rules.is_empty() {
+        true => lang.link_filter_none().to_owned(),
+        false => rules
+            .iter()
+            .map(|rule| {
+                let action = match rule.action {
+                    PatternAction::Log => lang.pattern_action_log(),
+                    PatternAction::Delete => lang.pattern_action_delete(),
+                    PatternAction::Timeout => lang.pattern_action_timeout(),
+                };
+
+                format!("- **{}** (`{}`): {action}", rule.name, rule.pattern)
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_pattern_rules_updated(rules))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Pattern rejected when creating a pattern rule.
+pub fn pattern_rules_invalid(lang: Lang, error: PatternError) -> InteractionResponse {
+    let description = match error {
+        PatternError::TooLong => lang.config_pattern_rules_too_long(PatternRule::MAX_PATTERN_LEN),
+        PatternError::Invalid(error) => lang.config_pattern_rules_invalid(error),
+        PatternError::TooComplex => lang.config_pattern_rules_too_complex().to_owned(),
+        PatternError::MatchesEmpty => lang.config_pattern_rules_matches_empty().to_owned(),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(description)
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Pattern rule not found.
+pub fn pattern_rules_not_found(lang: Lang, name: &str) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.config_pattern_rules_not_found(name))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Too many pattern rules.
+pub fn pattern_rules_too_many(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.config_pattern_rules_too_many(PatternRule::MAX_RULES))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// First message filter configuration updated.
+pub fn first_message_updated(lang: Lang, config: &FirstMessageConfig) -> InteractionResponse {
+    let status = |enabled: bool| match enabled {
+        true => lang.enabled(),
+        false => lang.disabled(),
+    };
+    let action = match config.action {
+        FirstMessageAction::Delete => lang.first_message_action_delete(),
+        FirstMessageAction::Timeout => lang.first_message_action_timeout(),
+        FirstMessageAction::Ban => lang.first_message_action_ban(),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_first_message_updated(
+            action,
+            status(config.enabled),
+            status(config.links),
+            config.max_mentions,
+        ))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Anti-nuke configuration updated.
+pub fn antinuke_updated(lang: Lang, config: &AntinukeConfig) -> InteractionResponse {
+    let enabled = match config.enabled {
+        true => lang.enabled(),
+        false => lang.disabled(),
+    };
+    let trusted = match config.trusted.is_empty() {
+        true => lang.antinuke_no_trusted().to_owned(),
+        false => config
+            .trusted
+            .iter()
+            .map(|user| user.mention().to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_antinuke_updated(
+            antinuke_action_name(config.action, lang),
+            enabled,
+            config.interval,
+            config.max_deletions,
+            trusted,
+        ))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Get the display name of an anti-nuke action.
+pub fn antinuke_action_name(action: AntinukeAction, lang: Lang) -> &'static str {
+    match action {
+        AntinukeAction::StripRoles => lang.antinuke_action_strip_roles(),
+        AntinukeAction::Ban => lang.antinuke_action_ban(),
+    }
+}
+
+/// Join burst detection configuration updated.
+pub fn join_burst_updated(lang: Lang, config: &JoinBurstConfig) -> InteractionResponse {
+    let enabled = match config.enabled {
+        true => lang.enabled(),
+        false => lang.disabled(),
+    };
+    let signals = match config.signals.is_empty() {
+        true => lang.join_burst_no_signals().to_owned(),
+        false => config
+            .signals
+            .iter()
+            .map(|signal| join_burst_signal_name(*signal, lang))
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_join_burst_updated(
+            config.cluster_ratio,
+            enabled,
+            config.interval,
+            config.max_joins,
+            signals,
+        ))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Get the display name of a join burst signal.
+pub fn join_burst_signal_name(signal: JoinBurstSignal, lang: Lang) -> &'static str {
+    match signal {
+        JoinBurstSignal::Avatar => lang.join_burst_signal_avatar(),
+        JoinBurstSignal::Name => lang.join_burst_signal_name(),
+    }
+}
+
+/// Voice channels configuration updated.
+pub fn voice_updated(lang: Lang, config: &VoiceConfig) -> InteractionResponse {
+    let logs_channel = match config.logs_chan {
+        Some(channel) => channel.mention().to_string(),
+        None => lang.disabled().to_owned(),
+    };
+    let hop_detection = match config.hop_detection {
+        true => lang.enabled(),
+        false => lang.disabled(),
+    };
+    let action = match config.action {
+        VoiceHopAction::Disconnect => lang.voice_action_disconnect().to_owned(),
+        VoiceHopAction::Timeout => lang.voice_action_timeout(config.timeout),
+    };
+    let trusted_roles = match config.trusted_roles.is_empty() {
+        true => lang.reaction_spam_no_trusted_roles().to_owned(),
+        false => config
+            .trusted_roles
+            .iter()
+            .map(|role| role.mention().to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_voice_updated(
+            action,
+            hop_detection,
+            config.interval,
+            logs_channel,
+            config.max_hops,
+            trusted_roles,
+        ))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Grace period configuration updated.
+pub fn grace_updated(lang: Lang, config: &GraceConfig) -> InteractionResponse {
+    let enabled = match config.enabled {
+        true => lang.enabled(),
+        false => lang.disabled(),
+    };
+    let boosters = match config.boosters {
+        true => lang.enabled(),
+        false => lang.disabled(),
+    };
+    let tenure = match config.trusted_tenure {
+        Some(days) => lang.grace_tenure_days(days),
+        None => lang.disabled().to_owned(),
+    };
+    let trusted_roles = match config.trusted_roles.is_empty() {
+        true => lang.reaction_spam_no_trusted_roles().to_owned(),
+        false => config
+            .trusted_roles
+            .iter()
+            .map(|role| role.mention().to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_grace_updated(boosters, enabled, tenure, trusted_roles))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Member names normalization configuration updated.
+pub fn names_updated(lang: Lang, config: &NameConfig) -> InteractionResponse {
+    let enabled = match config.enabled {
+        true => lang.enabled(),
+        false => lang.disabled(),
+    };
+    let action = match config.action {
+        NameAction::Reset => lang.name_action_reset(),
+        NameAction::Flag => lang.name_action_flag(),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_names_updated(action, enabled, &config.fallback))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Message pins logs configuration updated.
+pub fn pins_updated(lang: Lang, config: &PinsConfig) -> InteractionResponse {
+    let logs_channel = match config.logs_chan {
+        Some(channel) => channel.mention().to_string(),
+        None => lang.disabled().to_owned(),
+    };
+    let ignored_channels = match config.ignored_channels.is_empty() {
+        true => lang.pins_no_ignored_channels().to_owned(),
+        false => config
+            .ignored_channels
+            .iter()
+            .map(|channel| channel.mention().to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_pins_updated(ignored_channels, logs_channel))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Member role changes logs configuration updated.
+pub fn role_logs_updated(lang: Lang, config: &RoleLogsConfig) -> InteractionResponse {
+    let logs_channel = match config.logs_chan {
+        Some(channel) => channel.mention().to_string(),
+        None => lang.disabled().to_owned(),
+    };
+    let ignored_roles = match config.ignored_roles.is_empty() {
+        true => lang.role_logs_no_ignored_roles().to_owned(),
+        false => config
+            .ignored_roles
+            .iter()
+            .map(|role| role.mention().to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_role_logs_updated(ignored_roles, logs_channel))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Automod ignored roles configuration updated.
+pub fn ignored_roles_updated(lang: Lang, roles: &[Id<RoleMarker>]) -> InteractionResponse {
+    let roles = match roles.is_empty() {
+        true => lang.ignored_roles_none().to_owned(),
+        false => roles
+            .iter()
+            .map(|role| role.mention().to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_ignored_roles_updated(roles))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Activity role configuration updated.
+pub fn activity_role_updated(lang: Lang, config: &ActivityRoleConfig) -> InteractionResponse {
+    let role = match config.role {
+        Some(role) => role.mention().to_string(),
+        None => lang.disabled().to_owned(),
+    };
+    let log = match config.log {
+        true => lang.enabled(),
+        false => lang.disabled(),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_activity_role_updated(
+            config.cooldown,
+            log,
+            role,
+            config.threshold,
+        ))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Watchlist configuration updated.
+pub fn watch_updated(lang: Lang, config: &GuildConfig) -> InteractionResponse {
+    let channel = match config.watch_chan {
+        Some(channel) => channel.mention().to_string(),
+        None => lang.config_watch_logs_channel().to_owned(),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_watch_updated(channel, config.watch_duration))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Logs routing configuration updated.
+///
+/// The destination of every category is shown.
+pub fn log_routing_updated(lang: Lang, config: &GuildConfig) -> InteractionResponse {
+    let routes = LogCategory::ALL
+        .into_iter()
+        .map(|category| {
+            let destination = match config.log_routing.get(category) {
+                LogDestination::Default => lang.config_log_route_default().to_owned(),
+                LogDestination::Channel(channel) => channel.mention().to_string(),
+                LogDestination::Off => lang.disabled().to_owned(),
+            };
+
+            format!("**{}**: {destination}", log_category_name(category, lang))
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_log_route_updated(routes))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Message reports configuration updated.
+pub fn reports_updated(lang: Lang, config: &GuildConfig) -> InteractionResponse {
+    let description = match config.reports_chan {
+        Some(channel) => lang.config_reports_enabled(channel.mention()),
+        None => lang.config_reports_disabled().to_owned(),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(description)
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Member count milestones configuration updated.
+pub fn milestones_updated(lang: Lang, config: &GuildConfig) -> InteractionResponse {
+    let description = match config.milestones_chan {
+        Some(channel) => lang.config_milestones_enabled(channel.mention()),
+        None => lang.config_milestones_disabled().to_owned(),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(description)
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Lang configuration updated.
+pub fn lang_updated(lang: Lang, config: &GuildConfig) -> InteractionResponse {
+    let source = |pinned: bool| match pinned {
+        true => lang.lang_server(),
+        false => lang.lang_user(),
+    };
+    let public = source(config.lang_override);
+    let ephemeral = source(config.lang_override && config.lang_override_ephemeral);
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_lang_updated(ephemeral, lang_name(config.lang()), public))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Get the native name of a lang.
+fn lang_name(lang: Lang) -> &'static str {
+    match lang {
+        Lang::En => "English",
+        Lang::Fr => "Français",
+    }
+}
+
+/// Get the display name of a verification level.
+fn verification_level_name(level: Option<VerificationLevel>, lang: Lang) -> &'static str {
+    match level {
+        Some(VerificationLevel::VeryHigh) => lang.verification_level_very_high(),
+        Some(VerificationLevel::High) => lang.verification_level_high(),
+        Some(VerificationLevel::Medium) => lang.verification_level_medium(),
+        Some(VerificationLevel::Low) => lang.verification_level_low(),
+        Some(VerificationLevel::None) => lang.verification_level_none(),
+        Some(_) => lang.verification_level_unknown(),
+        None => lang.verification_level_unchanged(),
+    }
+}
+
+fn log_category_name(category: LogCategory, lang: Lang) -> &'static str {
+    match category {
+        LogCategory::Sanctions => lang.log_category_sanctions(),
+        LogCategory::Joins => lang.log_category_joins(),
+        LogCategory::Messages => lang.log_category_messages(),
+        LogCategory::Filters => lang.log_category_filters(),
+        LogCategory::Raids => lang.log_category_raids(),
+        LogCategory::Config => lang.log_category_config(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::id::Id;
+
+    use super::*;
+
+    #[test]
+    fn test_antinuke_updated() {
+        antinuke_updated(Lang::DEFAULT, &AntinukeConfig::default());
+    }
+
+    #[test]
+    fn test_join_burst_updated() {
+        let mut config = JoinBurstConfig::default();
+        join_burst_updated(Lang::DEFAULT, &config);
+
+        config.signals.clear();
+        join_burst_updated(Lang::DEFAULT, &config);
+    }
+
+    #[test]
+    fn test_lang_updated() {
+        lang_updated(Lang::DEFAULT, &GuildConfig::new(Id::new(1)));
+    }
+
+    #[test]
+    fn test_raid_mode_updated() {
+        raid_mode_updated(Lang::DEFAULT, &RaidModeConfig::default());
+
+        let config = RaidModeConfig {
+            action: RaidModeAction::Review,
+            review_channel: Some(Id::new(1)),
+            member_role: Some(Id::new(2)),
+            ..Default::default()
+        };
+        raid_mode_updated(Lang::DEFAULT, &config);
+    }
+
+    #[test]
+    fn test_reaction_spam_updated() {
+        reaction_spam_updated(Lang::DEFAULT, &ReactionSpamConfig::default());
+    }
+
+    #[test]
+    fn test_link_filter_updated() {
+        let config = LinkFilterConfig {
+            allowed_domains: vec!["raidprotect.org".to_owned()],
+            allowed_invites: vec!["raidprotect".to_owned()],
+            trusted_roles: vec![Id::new(1)],
+            ..Default::default()
+        };
+
+        link_filter_updated(Lang::DEFAULT, &LinkFilterConfig::default());
+        link_filter_updated(Lang::DEFAULT, &config);
+        link_filter_invalid(Lang::DEFAULT);
+        link_filter_allowed_too_many(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_first_message_updated() {
+        let config = FirstMessageConfig {
+            action: FirstMessageAction::Ban,
+            ..Default::default()
+        };
+
+        first_message_updated(Lang::DEFAULT, &FirstMessageConfig::default());
+        first_message_updated(Lang::DEFAULT, &config);
+    }
+
+    #[test]
+    fn test_pattern_rules() {
+        let rule = PatternRule {
+            name: "scam".to_owned(),
+            pattern: r"free\s+nitro".to_owned(),
+            action: PatternAction::Timeout,
+        };
+
+        pattern_rules_updated(Lang::DEFAULT, &[]);
+        pattern_rules_updated(Lang::DEFAULT, &[rule]);
+        pattern_rules_not_found(Lang::DEFAULT, "scam");
+        pattern_rules_too_many(Lang::DEFAULT);
+
+        let errors = [
+            PatternError::TooLong,
+            PatternError::Invalid("error".to_owned()),
+            PatternError::TooComplex,
+            PatternError::MatchesEmpty,
+        ];
+        for error in errors {
+            pattern_rules_invalid(Lang::DEFAULT, error);
+        }
+    }
+
+    #[test]
+    fn test_voice_updated() {
+        let config = VoiceConfig {
+            logs_chan: Some(Id::new(1)),
+            action: VoiceHopAction::Timeout,
+            ..Default::default()
+        };
+
+        voice_updated(Lang::DEFAULT, &VoiceConfig::default());
+        voice_updated(Lang::DEFAULT, &config);
+    }
+
+    #[test]
+    fn test_grace_updated() {
+        let config = GraceConfig {
+            enabled: true,
+            trusted_tenure: Some(90),
+            trusted_roles: vec![Id::new(1)],
+            ..Default::default()
+        };
+
+        grace_updated(Lang::DEFAULT, &GraceConfig::default());
+        grace_updated(Lang::DEFAULT, &config);
+    }
+
+    #[test]
+    fn test_names_updated() {
+        let config = NameConfig {
+            action: NameAction::Flag,
+            ..Default::default()
+        };
+
+        names_updated(Lang::DEFAULT, &NameConfig::default());
+        names_updated(Lang::DEFAULT, &config);
+    }
+
+    #[test]
+    fn test_pins_updated() {
+        let config = PinsConfig {
+            logs_chan: Some(Id::new(1)),
+            ignored_channels: vec![Id::new(2), Id::new(3)],
+        };
+
+        pins_updated(Lang::DEFAULT, &PinsConfig::default());
+        pins_updated(Lang::DEFAULT, &config);
+    }
+
+    #[test]
+    fn test_role_logs_updated() {
+        let config = RoleLogsConfig {
+            logs_chan: Some(Id::new(1)),
+            ignored_roles: vec![Id::new(2), Id::new(3)],
+        };
+
+        role_logs_updated(Lang::DEFAULT, &RoleLogsConfig::default());
+        role_logs_updated(Lang::DEFAULT, &config);
+    }
+
+    #[test]
+    fn test_ignored_roles_updated() {
+        ignored_roles_updated(Lang::DEFAULT, &[]);
+        ignored_roles_updated(Lang::DEFAULT, &[Id::new(1), Id::new(2)]);
+    }
+
+    #[test]
+    fn test_activity_role_updated() {
+        let config = ActivityRoleConfig {
+            role: Some(Id::new(1)),
+            ..Default::default()
+        };
+
+        activity_role_updated(Lang::DEFAULT, &ActivityRoleConfig::default());
+        activity_role_updated(Lang::DEFAULT, &config);
+    }
+
+    #[test]
+    fn test_watch_updated() {
+        let mut config = GuildConfig::new(Id::new(1));
+
+        watch_updated(Lang::DEFAULT, &config);
+        config.watch_chan = Some(Id::new(2));
+        watch_updated(Lang::DEFAULT, &config);
+    }
+
+    #[test]
+    fn test_log_routing_updated() {
+        let mut config = GuildConfig::new(Id::new(1));
+
+        log_routing_updated(Lang::DEFAULT, &config);
+        config
+            .log_routing
+            .set(LogCategory::Sanctions, LogDestination::Channel(Id::new(2)));
+        config
+            .log_routing
+            .set(LogCategory::Joins, LogDestination::Off);
+        log_routing_updated(Lang::DEFAULT, &config);
+    }
+
+    #[test]
+    fn test_reports_updated() {
+        let mut config = GuildConfig::new(Id::new(1));
+
+        reports_updated(Lang::DEFAULT, &config);
+        config.reports_chan = Some(Id::new(2));
+        reports_updated(Lang::DEFAULT, &config);
+    }
+
+    #[test]
+    fn test_milestones_updated() {
+        let mut config = GuildConfig::new(Id::new(1));
+
+        milestones_updated(Lang::DEFAULT, &config);
+        config.milestones_chan = Some(Id::new(2));
+        milestones_updated(Lang::DEFAULT, &config);
+    }
+}
@@ -1,22 +1,39 @@
 //! Error embeds.
 
-use twilight_util::builder::embed::{EmbedBuilder, EmbedFooterBuilder};
+use twilight_model::{
+    channel::embed::Embed,
+    guild::Permissions,
+    id::{marker::InteractionMarker, Id},
+};
+use twilight_util::builder::embed::EmbedFooterBuilder;
 
-use super::COLOR_RED;
+use super::{EmbedBuilder, COLOR_RED, COLOR_TRANSPARENT};
 use crate::{interaction::response::InteractionResponse, translations::Lang};
 
 /// Internal error embed
-pub fn internal_error(lang: Lang) -> InteractionResponse {
-    let embed = EmbedBuilder::new()
+///
+/// The interaction id is shown to let users report the error.
+pub fn internal_error(lang: Lang, id: Id<InteractionMarker>) -> InteractionResponse {
+    InteractionResponse::EphemeralEmbed(internal_error_embed(lang, id))
+}
+
+/// Internal error embed, for responses sent after the interaction has been
+/// deferred.
+pub fn internal_error_embed(lang: Lang, id: Id<InteractionMarker>) -> Embed {
+    let description = format!(
+        "{}\n\n{}",
+        lang.internal_error_description(),
+        lang.internal_error_id(id)
+    );
+
+    EmbedBuilder::new()
         .title(lang.internal_error_title())
         .color(COLOR_RED)
-        .description(lang.internal_error_description())
+        .description(description)
         .footer(EmbedFooterBuilder::new(
             "Okay, Houston, I believe we've had a problem here ...", // No translation here
         ))
-        .build();
-
-    InteractionResponse::EphemeralEmbed(embed)
+        .build()
 }
 
 /// Unknown command received
@@ -40,13 +57,201 @@ pub fn expired_interaction(lang: Lang) -> InteractionResponse {
     InteractionResponse::EphemeralEmbed(embed)
 }
 
+/// Bot missing permissions to perform an action
+pub fn bot_missing_permissions(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .title(lang.bot_missing_permissions_title())
+        .color(COLOR_RED)
+        .description(lang.bot_missing_permissions_description())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Discord resource used by an action no longer exists
+pub fn unknown_resource(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .title(lang.unknown_resource_title())
+        .color(COLOR_RED)
+        .description(lang.unknown_resource_description())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Bot rate limited by Discord
+pub fn rate_limited(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .title(lang.rate_limited_title())
+        .color(COLOR_RED)
+        .description(lang.rate_limited_description())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Bot member not known yet, right after the bot has joined a guild
+pub fn bot_member_unknown(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .title(lang.bot_member_unknown_title())
+        .color(COLOR_RED)
+        .description(lang.bot_member_unknown_description())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Database unavailable
+pub fn database_unavailable(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .title(lang.database_unavailable_title())
+        .color(COLOR_RED)
+        .description(lang.database_unavailable_description())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Command reserved to bot operators
+pub fn not_operator(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .title(lang.not_operator_title())
+        .color(COLOR_RED)
+        .description(lang.not_operator_description())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Bot in maintenance mode
+pub fn maintenance(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .title(lang.maintenance_title())
+        .color(COLOR_RED)
+        .description(lang.maintenance_description())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Discord data required by the interaction temporarily unavailable
+pub fn discord_unavailable(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .title(lang.discord_unavailable_title())
+        .color(COLOR_RED)
+        .description(lang.discord_unavailable_description())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Member missing permissions to use a command
+pub fn missing_permissions(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .title(lang.missing_permissions_title())
+        .color(COLOR_RED)
+        .description(lang.missing_permissions_description())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Member missing permissions to use a command
+///
+/// The permissions the member is missing are listed in the description.
+pub fn missing_command_permissions(lang: Lang, missing: Permissions) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .title(lang.missing_permissions_title())
+        .color(COLOR_RED)
+        .description(lang.missing_permissions_required(permission_names(lang, missing)))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Permissions with a localized name.
+const NAMED_PERMISSIONS: [Permissions; 8] = [
+    Permissions::ADMINISTRATOR,
+    Permissions::MANAGE_GUILD,
+    Permissions::BAN_MEMBERS,
+    Permissions::KICK_MEMBERS,
+    Permissions::MODERATE_MEMBERS,
+    Permissions::MANAGE_MESSAGES,
+    Permissions::MANAGE_THREADS,
+    Permissions::MANAGE_ROLES,
+];
+
+/// Format the names of a set of permissions.
+///
+/// Permissions without a localized name are shown with their flag name.
+pub(super) fn permission_names(lang: Lang, permissions: Permissions) -> String {
+    let mut names = Vec::new();
+    let mut remaining = permissions;
+
+    for permission in NAMED_PERMISSIONS {
+        if !permissions.contains(permission) {
+            continue;
+        }
+
+        if let Some(name) = permission_name(lang, permission) {
+            names.push(format!("**{name}**"));
+            remaining.remove(permission);
+        }
+    }
+
+    if !remaining.is_empty() {
+        names.push(format!("`{remaining:?}`"));
+    }
+
+    names.join(", ")
+}
+
+/// Get the localized name of a single permission.
+fn permission_name(lang: Lang, permission: Permissions) -> Option<&'static str> {
+    let name = match permission {
+        Permissions::ADMINISTRATOR => lang.permission_administrator(),
+        Permissions::MANAGE_GUILD => lang.permission_manage_guild(),
+        Permissions::BAN_MEMBERS => lang.permission_ban_members(),
+        Permissions::KICK_MEMBERS => lang.permission_kick_members(),
+        Permissions::MODERATE_MEMBERS => lang.permission_moderate_members(),
+        Permissions::MANAGE_MESSAGES => lang.permission_manage_messages(),
+        Permissions::MANAGE_THREADS => lang.permission_manage_threads(),
+        Permissions::MANAGE_ROLES => lang.permission_manage_roles(),
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+/// Component already used, possibly by a concurrent click
+pub fn already_handled(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .description(lang.already_handled_component())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Component used by another user than the one it was sent to
+pub fn unauthorized_component(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .title(lang.unauthorized_component_title())
+        .color(COLOR_RED)
+        .description(lang.unauthorized_component_description())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::interaction::command::{MemberPermissions, COMMANDS};
 
     #[test]
     fn test_internal_error() {
-        internal_error(Lang::DEFAULT);
+        internal_error(Lang::DEFAULT, Id::new(1));
     }
 
     #[test]
@@ -58,4 +263,95 @@ mod tests {
     fn test_expired_component() {
         expired_interaction(Lang::DEFAULT);
     }
+
+    #[test]
+    fn test_bot_missing_permissions() {
+        bot_missing_permissions(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_unknown_resource() {
+        unknown_resource(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_rate_limited() {
+        rate_limited(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_bot_member_unknown() {
+        bot_member_unknown(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_database_unavailable() {
+        database_unavailable(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_not_operator() {
+        not_operator(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_maintenance() {
+        maintenance(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_missing_permissions() {
+        missing_permissions(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_missing_command_permissions() {
+        missing_command_permissions(Lang::DEFAULT, Permissions::BAN_MEMBERS);
+    }
+
+    #[test]
+    fn test_permission_names() {
+        assert_eq!(
+            permission_names(
+                Lang::En,
+                Permissions::BAN_MEMBERS | Permissions::KICK_MEMBERS
+            ),
+            "**Ban Members**, **Kick Members**"
+        );
+        assert_eq!(
+            permission_names(Lang::En, Permissions::MANAGE_WEBHOOKS),
+            "`MANAGE_WEBHOOKS`"
+        );
+    }
+
+    #[test]
+    fn test_command_permissions_are_named() {
+        for permission in NAMED_PERMISSIONS {
+            assert!(permission_name(Lang::DEFAULT, permission).is_some());
+        }
+
+        for command in COMMANDS {
+            if let MemberPermissions::Required(required) = command.permissions {
+                let unnamed = NAMED_PERMISSIONS
+                    .iter()
+                    .fold(required, |remaining, named| remaining - *named);
+
+                assert!(
+                    unnamed.is_empty(),
+                    "{} has unnamed permissions",
+                    command.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_unauthorized_component() {
+        unauthorized_component(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_already_handled() {
+        already_handled(Lang::DEFAULT);
+    }
 }
@@ -0,0 +1,109 @@
+//! Embeds for the fast mode roles.
+
+use raidprotect_model::database::model::ModerationConfig;
+use twilight_mention::Mention;
+use twilight_model::id::{marker::RoleMarker, Id};
+
+use super::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS, COLOR_TRANSPARENT};
+use crate::{interaction::response::InteractionResponse, translations::Lang};
+
+/// Fast mode role added.
+pub fn added(lang: Lang, role_id: Id<RoleMarker>) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_fast_mode_added(role_id.mention()))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Fast mode role removed.
+pub fn removed(lang: Lang, role_id: Id<RoleMarker>) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_fast_mode_removed(role_id.mention()))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// List of the fast mode roles.
+pub fn list(lang: Lang, roles: &[Id<RoleMarker>]) -> InteractionResponse {
+    if roles.is_empty() {
+        let embed = EmbedBuilder::new()
+            .color(COLOR_TRANSPARENT)
+            .title(lang.config_fast_mode_empty_title())
+            .description(lang.config_fast_mode_empty_description())
+            .build();
+
+        return InteractionResponse::EphemeralEmbed(embed);
+    }
+
+    let roles = roles
+        .iter()
+        .map(|role| format!("• {}", role.mention()))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .title(lang.config_fast_mode_list_title())
+        .description(lang.config_fast_mode_list(roles))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Role already in fast mode.
+pub fn already_added(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.config_fast_mode_already_added())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Role not in fast mode.
+pub fn not_found(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.config_fast_mode_not_found())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Too many fast mode roles.
+pub fn too_many(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(
+            lang.config_fast_mode_too_many(ModerationConfig::MAX_SKIP_CONFIRMATION_ROLES_LEN),
+        )
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list() {
+        list(Lang::DEFAULT, &[]);
+        list(Lang::DEFAULT, &[Id::new(1), Id::new(2)]);
+        added(Lang::DEFAULT, Id::new(1));
+        removed(Lang::DEFAULT, Id::new(1));
+    }
+
+    #[test]
+    fn test_errors() {
+        already_added(Lang::DEFAULT);
+        not_found(Lang::DEFAULT);
+        too_many(Lang::DEFAULT);
+    }
+}
@@ -0,0 +1,102 @@
+//! Embed for the features command.
+
+use raidprotect_model::database::model::{Feature, FeatureSet};
+use twilight_model::{
+    application::component::{button::ButtonStyle, ActionRow, Button, Component},
+    channel::message::MessageFlags,
+    http::interaction::InteractionResponseData,
+};
+use twilight_util::builder::InteractionResponseDataBuilder;
+
+use super::{EmbedBuilder, COLOR_TRANSPARENT};
+use crate::{interaction::util::CustomId, translations::Lang};
+
+/// Maximum number of buttons in an action row.
+const BUTTONS_PER_ROW: usize = 5;
+
+/// List of features with a toggle button for each feature.
+///
+/// The same message is used for the initial response and when a feature is
+/// toggled, to update the message in place.
+pub fn features(lang: Lang, features: FeatureSet) -> InteractionResponseData {
+    let description = Feature::ALL
+        .into_iter()
+        .map(|feature| {
+            let status = match features.contains(feature) {
+                true => "✅",
+                false => "❌",
+            };
+
+            format!("{status} {}", feature_name(feature, lang))
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .title(lang.features_title())
+        .description(format!("{}\n\n{description}", lang.features_help()))
+        .build();
+
+    let buttons = Feature::ALL
+        .into_iter()
+        .map(|feature| {
+            let style = match features.contains(feature) {
+                true => ButtonStyle::Success,
+                false => ButtonStyle::Secondary,
+            };
+
+            Component::Button(Button {
+                custom_id: Some(
+                    CustomId::new("feature-toggle", feature.name().to_owned()).to_string(),
+                ),
+                disabled: false,
+                emoji: None,
+                label: Some(feature_name(feature, lang).to_owned()),
+                style,
+                url: None,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let components = buttons
+        .chunks(BUTTONS_PER_ROW)
+        .map(|buttons| {
+            Component::ActionRow(ActionRow {
+                components: buttons.to_vec(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    InteractionResponseDataBuilder::new()
+        .embeds([embed])
+        .components(components)
+        .flags(MessageFlags::EPHEMERAL)
+        .build()
+}
+
+/// Get the display name of a feature.
+pub fn feature_name(feature: Feature, lang: Lang) -> &'static str {
+    match feature {
+        Feature::SpamFilter => lang.feature_spam_filter(),
+        Feature::LinkFilter => lang.feature_link_filter(),
+        Feature::RaidDetection => lang.feature_raid_detection(),
+        Feature::JoinLogs => lang.feature_join_logs(),
+        Feature::MessageLogs => lang.feature_message_logs(),
+        Feature::Verification => lang.feature_verification(),
+        Feature::AutoSlowmode => lang.feature_auto_slowmode(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_features() {
+        let data = features(Lang::DEFAULT, FeatureSet::DEFAULT);
+
+        assert_eq!(data.components.unwrap().len(), 2);
+        features(Lang::DEFAULT, FeatureSet::empty());
+    }
+}
@@ -0,0 +1,92 @@
+//! Embeds for the false positives of the filters.
+
+use raidprotect_model::database::model::{FilterFeedback, StatsFilter};
+use twilight_mention::Mention;
+use twilight_model::{
+    channel::embed::Embed,
+    id::{marker::UserMarker, Id},
+};
+
+use super::{EmbedBuilder, COLOR_SUCCESS, COLOR_TRANSPARENT};
+use crate::{interaction::response::InteractionResponse, translations::Lang};
+
+/// Number of characters of the fingerprints shown in the list.
+const FINGERPRINT_LENGTH: usize = 8;
+
+/// Log message marked as a false positive by a moderator.
+pub fn marked(lang: Lang, moderator: Id<UserMarker>) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .description(lang.filter_feedback_marked(moderator.mention()))
+        .build()
+}
+
+/// Recent false positives of a guild.
+pub fn list(lang: Lang, feedback: &[FilterFeedback]) -> InteractionResponse {
+    let description = match feedback.is_empty() {
+        true => lang.filter_feedback_list_empty().to_owned(),
+        false => feedback
+            .iter()
+            .map(|feedback| {
+                lang.filter_feedback_list_entry(
+                    feedback.date.unix_timestamp(),
+                    filter_name(lang, feedback.filter),
+                    &feedback.fingerprint[..FINGERPRINT_LENGTH.min(feedback.fingerprint.len())],
+                    feedback.reporter.mention(),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .title(lang.filter_feedback_list_title())
+        .description(description)
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Localized name of a filter.
+pub fn filter_name(lang: Lang, filter: StatsFilter) -> &'static str {
+    match filter {
+        StatsFilter::ReactionSpam => lang.filter_name_reaction_spam(),
+        StatsFilter::VoiceHop => lang.filter_name_voice_hop(),
+        StatsFilter::Name => lang.filter_name_name(),
+        StatsFilter::Link => lang.filter_name_link(),
+        StatsFilter::Pattern => lang.filter_name_pattern(),
+        StatsFilter::FirstMessage => lang.filter_name_first_message(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::OffsetDateTime;
+
+    use super::*;
+
+    #[test]
+    fn test_list() {
+        let feedback = FilterFeedback {
+            id: None,
+            guild_id: Id::new(1),
+            filter: StatsFilter::Link,
+            fingerprint: "0123456789abcdef".to_owned(),
+            reporter: Id::new(2),
+            date: OffsetDateTime::from_unix_timestamp(1_628_594_197).unwrap(),
+        };
+
+        let embed = match list(Lang::En, &[feedback]) {
+            InteractionResponse::EphemeralEmbed(embed) => embed,
+            _ => panic!("expected embed response"),
+        };
+        let description = embed.description.unwrap();
+
+        assert!(description.contains("`01234567`"));
+        assert!(description.contains("<t:1628594197:R>"));
+        assert!(!description.contains("89abcdef"));
+
+        list(Lang::En, &[]);
+    }
+}
@@ -0,0 +1,329 @@
+//! Embeds for the help command.
+//!
+//! The content of the embeds is generated from the commands registry (see
+//! [`COMMANDS`]), so the help is always up to date with the registered
+//! commands.
+//!
+//! [`COMMANDS`]: crate::interaction::command::COMMANDS
+
+use std::collections::HashMap;
+
+use twilight_model::{
+    application::command::{Command, CommandOption, CommandOptionType, CommandType},
+    channel::embed::Embed,
+};
+use twilight_util::builder::embed::EmbedFieldBuilder;
+
+use super::{error::permission_names, EmbedBuilder, COLOR_RED, COLOR_TRANSPARENT};
+use crate::{
+    interaction::{
+        command::{CommandCategory, CommandMeta, MemberPermissions},
+        response::InteractionResponse,
+    },
+    translations::Lang,
+    util::TextProcessExt,
+};
+
+/// Maximum length of an embed description.
+const MAX_DESCRIPTION_LENGTH: usize = 4096;
+
+/// Help overview, with the categories of commands available to the member.
+pub fn overview(lang: Lang, categories: &[CommandCategory]) -> Embed {
+    let categories = categories
+        .iter()
+        .map(|category| format!("• {}", category_name(lang, *category)))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .title(lang.help_embed_title())
+        .description(lang.help_embed_description())
+        .field(EmbedFieldBuilder::new(
+            lang.help_categories_title(),
+            lang.help_categories(categories),
+        ))
+        .build()
+}
+
+/// Commands of a category available to the member.
+pub fn category(lang: Lang, category: CommandCategory, commands: &[&CommandMeta]) -> Embed {
+    let commands = commands
+        .iter()
+        .map(|meta| {
+            let command = meta.command();
+
+            format!(
+                "**{}** — {}",
+                display_name(meta),
+                description(lang, meta, &command)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .title(category_name(lang, category))
+        .description(commands.max_len(MAX_DESCRIPTION_LENGTH))
+        .build()
+}
+
+/// Details of a command.
+///
+/// The options (or subcommands) of the command are shown with its usage
+/// examples, the permissions required to use it and its related settings.
+pub fn command(lang: Lang, meta: &CommandMeta) -> Embed {
+    let command = meta.command();
+    let mut description = description(lang, meta, &command);
+
+    let options = options(lang, &command.options, "");
+    if !options.is_empty() {
+        let title = match command.options.iter().any(is_subcommand) {
+            true => lang.help_command_subcommands(),
+            false => lang.help_command_options(),
+        };
+
+        description = format!("{description}\n\n**{title}**\n{}", options.join("\n"));
+    }
+
+    let permissions = match meta.permissions {
+        MemberPermissions::Everyone => lang.help_command_everyone().to_owned(),
+        MemberPermissions::Required(permissions) => permission_names(lang, permissions),
+    };
+
+    let mut embed = EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .title(display_name(meta))
+        .description(description.max_len(MAX_DESCRIPTION_LENGTH))
+        .field(
+            EmbedFieldBuilder::new(
+                lang.help_command_category(),
+                category_name(lang, meta.category),
+            )
+            .inline(),
+        )
+        .field(EmbedFieldBuilder::new(lang.help_command_permissions(), permissions).inline());
+
+    if !meta.examples.is_empty() {
+        let examples = meta
+            .examples
+            .iter()
+            .map(|example| format!("`{example}`"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        embed = embed.field(EmbedFieldBuilder::new(
+            lang.help_command_examples(),
+            examples,
+        ));
+    }
+
+    if !meta.settings.is_empty() {
+        let settings = meta
+            .settings
+            .iter()
+            .map(|setting| format!("`{setting}`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        embed = embed.field(EmbedFieldBuilder::new(
+            lang.help_command_settings(),
+            settings,
+        ));
+    }
+
+    embed.build()
+}
+
+/// Command not found or not available to the member.
+pub fn command_not_found(lang: Lang, name: &str) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.help_command_not_found(name.remove_markdown()))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Localized name of a category.
+pub fn category_name(lang: Lang, category: CommandCategory) -> &'static str {
+    match category {
+        CommandCategory::General => lang.help_category_general(),
+        CommandCategory::Moderation => lang.help_category_moderation(),
+        CommandCategory::Protection => lang.help_category_protection(),
+        CommandCategory::Configuration => lang.help_category_configuration(),
+        CommandCategory::Operator => lang.help_category_operator(),
+    }
+}
+
+/// Name of a command as shown to the members.
+fn display_name(meta: &CommandMeta) -> String {
+    match meta.kind {
+        CommandType::ChatInput => format!("/{}", meta.name),
+        _ => meta.name.to_owned(),
+    }
+}
+
+/// Localized description of a command.
+///
+/// Context menu commands have no description, the way to use them is shown
+/// instead.
+fn description(lang: Lang, meta: &CommandMeta, command: &Command) -> String {
+    match meta.kind {
+        CommandType::ChatInput => localized(
+            lang,
+            &command.description,
+            &command.description_localizations,
+        )
+        .to_owned(),
+        CommandType::Message => lang.help_context_menu_message(meta.name),
+        _ => lang.help_context_menu_user(meta.name),
+    }
+}
+
+/// List the options of a command, with subcommands expanded.
+fn options(lang: Lang, options: &[CommandOption], prefix: &str) -> Vec<String> {
+    options
+        .iter()
+        .flat_map(|option| {
+            let (name, description, localizations) = option_text(option);
+            let name = format!("{prefix}{name}");
+            let description = localized(lang, description, localizations);
+
+            match option {
+                CommandOption::SubCommandGroup(data) => {
+                    let prefix = format!("{name} ");
+
+                    self::options(lang, &data.options, &prefix)
+                }
+                _ => {
+                    let required = match option.is_required() {
+                        true => format!(" *({})*", lang.help_option_required()),
+                        false => String::new(),
+                    };
+
+                    vec![format!("• `{name}`{required} — {description}")]
+                }
+            }
+        })
+        .collect()
+}
+
+/// Get the name, description and description localizations of an option.
+fn option_text(option: &CommandOption) -> (&str, &str, &Option<HashMap<String, String>>) {
+    match option {
+        CommandOption::SubCommand(data) | CommandOption::SubCommandGroup(data) => (
+            &data.name,
+            &data.description,
+            &data.description_localizations,
+        ),
+        CommandOption::String(data) => (
+            &data.name,
+            &data.description,
+            &data.description_localizations,
+        ),
+        CommandOption::Integer(data) | CommandOption::Number(data) => (
+            &data.name,
+            &data.description,
+            &data.description_localizations,
+        ),
+        CommandOption::Channel(data) => (
+            &data.name,
+            &data.description,
+            &data.description_localizations,
+        ),
+        CommandOption::Boolean(data)
+        | CommandOption::User(data)
+        | CommandOption::Role(data)
+        | CommandOption::Mentionable(data)
+        | CommandOption::Attachment(data) => (
+            &data.name,
+            &data.description,
+            &data.description_localizations,
+        ),
+    }
+}
+
+/// Whether an option is a subcommand or a subcommand group.
+fn is_subcommand(option: &CommandOption) -> bool {
+    matches!(
+        option.kind(),
+        CommandOptionType::SubCommand | CommandOptionType::SubCommandGroup
+    )
+}
+
+/// Get the localization of a text for a lang, if any.
+fn localized<'a>(
+    lang: Lang,
+    default: &'a str,
+    localizations: &'a Option<HashMap<String, String>>,
+) -> &'a str {
+    localizations
+        .iter()
+        .flatten()
+        .find(|(locale, _)| Lang::from(&***locale) == lang)
+        .map_or(default, |(_, text)| text.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interaction::command::{find_command, COMMANDS};
+
+    #[test]
+    fn test_overview() {
+        overview(Lang::DEFAULT, &CommandCategory::ALL);
+    }
+
+    #[test]
+    fn test_category() {
+        for category in CommandCategory::ALL {
+            let commands = COMMANDS
+                .iter()
+                .filter(|command| command.category == category)
+                .collect::<Vec<_>>();
+
+            let embed = self::category(Lang::DEFAULT, category, &commands);
+            assert_eq!(embed.description.unwrap().lines().count(), commands.len());
+        }
+    }
+
+    #[test]
+    fn test_command() {
+        for meta in COMMANDS {
+            for lang in [Lang::En, Lang::Fr] {
+                let embed = command(lang, meta);
+
+                assert!(embed.description.unwrap().chars().count() <= MAX_DESCRIPTION_LENGTH);
+            }
+        }
+    }
+
+    #[test]
+    fn test_command_options() {
+        let embed = command(Lang::En, find_command("mute").unwrap());
+        let description = embed.description.unwrap();
+
+        assert!(description.contains("`member` *(required)*"));
+        assert!(description.contains("`duration` —"));
+
+        let embed = command(Lang::En, find_command("config").unwrap());
+        assert!(embed.description.unwrap().contains("`logs reports`"));
+    }
+
+    #[test]
+    fn test_command_localized() {
+        let embed = command(Lang::Fr, find_command("help").unwrap());
+
+        assert!(embed
+            .description
+            .unwrap()
+            .starts_with(Lang::Fr.help_description()));
+    }
+
+    #[test]
+    fn test_command_not_found() {
+        command_not_found(Lang::DEFAULT, "unknown");
+    }
+}
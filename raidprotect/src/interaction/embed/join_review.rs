@@ -0,0 +1,93 @@
+//! Embeds for the members held for review by the raid mode.
+
+use twilight_mention::Mention;
+use twilight_model::{
+    channel::embed::Embed,
+    id::{marker::UserMarker, Id},
+};
+
+use super::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS};
+use crate::{
+    interaction::response::InteractionResponse,
+    translations::Lang,
+    util::time::{from_secs, relative},
+};
+
+/// Member held for review.
+///
+/// The `created_at` parameter is the account creation date, in seconds. The
+/// `new_account` marker is added if the account is new.
+pub fn held(
+    lang: Lang,
+    user: Id<UserMarker>,
+    created_at: u64,
+    new_account: Option<String>,
+) -> Embed {
+    let created_at = relative(from_secs(created_at));
+    let mut description = lang.join_review_held(created_at, user.mention());
+
+    if let Some(marker) = new_account {
+        description = format!("{description}\n{marker}");
+    }
+
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.join_review_title())
+        .description(description)
+        .build()
+}
+
+/// Member approved by a moderator.
+///
+/// This embed is added to the review message.
+pub fn approved(lang: Lang, moderator: Id<UserMarker>) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .description(lang.join_review_approved(moderator.mention()))
+        .build()
+}
+
+/// Member kicked by a moderator.
+///
+/// This embed is added to the review message.
+pub fn kicked(lang: Lang, moderator: Id<UserMarker>) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.join_review_kicked(moderator.mention()))
+        .build()
+}
+
+/// The review action could not be applied.
+pub fn failed(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.join_review_failed_title())
+        .description(lang.join_review_failed())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_held() {
+        held(Lang::DEFAULT, Id::new(1), 0, None);
+
+        let embed = held(Lang::DEFAULT, Id::new(1), 0, Some("marker".to_owned()));
+        assert!(embed.description.unwrap().ends_with("marker"));
+    }
+
+    #[test]
+    fn test_reviewed() {
+        approved(Lang::DEFAULT, Id::new(1));
+        kicked(Lang::DEFAULT, Id::new(1));
+    }
+
+    #[test]
+    fn test_failed() {
+        failed(Lang::DEFAULT);
+    }
+}
@@ -1,8 +1,6 @@
 //! Embed for the kick command.
 
-use twilight_util::builder::embed::EmbedBuilder;
-
-use super::COLOR_RED;
+use super::{EmbedBuilder, COLOR_RED};
 use crate::{interaction::response::InteractionResponse, translations::Lang, util::TextProcessExt};
 
 /// User is not a server member.
@@ -0,0 +1,163 @@
+//! Embeds for the guild links.
+
+use raidprotect_model::database::model::{BanSync, GuildLink};
+use twilight_model::id::{marker::GuildMarker, Id};
+use twilight_util::builder::embed::EmbedFieldBuilder;
+
+use super::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS, COLOR_TRANSPARENT};
+use crate::{interaction::response::InteractionResponse, translations::Lang, util::TextProcessExt};
+
+/// Link code created.
+pub fn created(lang: Lang, code: &str) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.link_created_title())
+        .description(lang.link_created(code))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Guild linked with a code.
+pub fn joined(lang: Lang, name: &str) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .description(lang.link_joined(name.remove_markdown()))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Linked guilds, with the synchronization mode.
+pub fn list(
+    lang: Lang,
+    mode: BanSync,
+    guilds: &[(Id<GuildMarker>, String)],
+) -> InteractionResponse {
+    let description = match guilds.is_empty() {
+        true => lang.link_list_empty().to_owned(),
+        false => guilds
+            .iter()
+            .map(|(id, name)| format!("• **{}** (`{id}`)", name.remove_markdown()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .title(lang.link_list_title())
+        .description(description)
+        .field(EmbedFieldBuilder::new(
+            lang.link_mode_title(),
+            mode_name(lang, mode),
+        ))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Link removed.
+pub fn removed(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .description(lang.link_removed())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Synchronization mode updated.
+pub fn mode_updated(lang: Lang, mode: BanSync) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.link_mode_updated(mode_name(lang, mode)))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Invalid or expired link code.
+pub fn invalid_code(lang: Lang) -> InteractionResponse {
+    error(lang.link_invalid_code())
+}
+
+/// Link code created in the same guild.
+pub fn same_guild(lang: Lang) -> InteractionResponse {
+    error(lang.link_same_guild())
+}
+
+/// Guilds already linked.
+pub fn already_linked(lang: Lang) -> InteractionResponse {
+    error(lang.link_already_linked())
+}
+
+/// Guild not linked.
+pub fn not_linked(lang: Lang) -> InteractionResponse {
+    error(lang.link_not_linked())
+}
+
+/// Too many linked guilds.
+pub fn too_many(lang: Lang) -> InteractionResponse {
+    error(&lang.link_too_many(GuildLink::MAX_LINKS))
+}
+
+/// Ban synchronization requested but the guild does not send its bans.
+pub fn sync_disabled(lang: Lang) -> InteractionResponse {
+    error(lang.link_sync_disabled())
+}
+
+/// Localized name of a synchronization mode.
+pub fn mode_name(lang: Lang, mode: BanSync) -> &'static str {
+    match mode {
+        BanSync::Off => lang.link_mode_off(),
+        BanSync::Send => lang.link_mode_send(),
+        BanSync::Receive => lang.link_mode_receive(),
+        BanSync::Both => lang.link_mode_both(),
+    }
+}
+
+fn error(description: &str) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(description)
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list() {
+        let guilds = vec![(Id::new(1), "Server **one**".to_owned())];
+
+        match list(Lang::DEFAULT, BanSync::Both, &guilds) {
+            InteractionResponse::EphemeralEmbed(embed) => {
+                assert!(embed.description.unwrap().contains("(`1`)"));
+            }
+            _ => panic!("expected ephemeral embed"),
+        }
+
+        list(Lang::DEFAULT, BanSync::Off, &[]);
+    }
+
+    #[test]
+    fn test_responses() {
+        created(Lang::DEFAULT, "ABCDEFGH23");
+        joined(Lang::DEFAULT, "Server");
+        removed(Lang::DEFAULT);
+        invalid_code(Lang::DEFAULT);
+        same_guild(Lang::DEFAULT);
+        already_linked(Lang::DEFAULT);
+        not_linked(Lang::DEFAULT);
+        too_many(Lang::DEFAULT);
+        sync_disabled(Lang::DEFAULT);
+
+        for mode in [BanSync::Off, BanSync::Send, BanSync::Receive, BanSync::Both] {
+            mode_updated(Lang::DEFAULT, mode);
+        }
+    }
+}
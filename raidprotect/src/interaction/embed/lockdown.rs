@@ -0,0 +1,135 @@
+//! Embeds for the lockdown command.
+
+use twilight_model::channel::embed::Embed;
+
+use super::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS, COLOR_TRANSPARENT};
+use crate::{feature::lockdown::EngageOutcome, translations::Lang};
+
+/// Progress of a running lockdown engagement.
+pub fn progress(lang: Lang, done: usize, total: usize) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .title(lang.lockdown_title())
+        .description(lang.lockdown_progress(done, total))
+        .build()
+}
+
+/// The lockdown engagement is being cancelled.
+pub fn cancelling(lang: Lang) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .title(lang.lockdown_title())
+        .description(lang.lockdown_cancelling())
+        .build()
+}
+
+/// Summary of a completed or cancelled lockdown engagement.
+pub fn summary(lang: Lang, outcome: EngageOutcome) -> Embed {
+    let (mut description, failed) = match outcome {
+        EngageOutcome::Completed { locked, failed } => (lang.lockdown_completed(locked), failed),
+        EngageOutcome::Cancelled { restored, failed } => {
+            (lang.lockdown_cancelled(restored), failed)
+        }
+    };
+
+    if failed > 0 {
+        description.push_str("\n\n");
+        description.push_str(&lang.lockdown_failed(failed));
+    }
+
+    EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.lockdown_title())
+        .description(description)
+        .build()
+}
+
+/// The lockdown has been lifted.
+pub fn lifted(lang: Lang, restored: usize, failed: usize) -> Embed {
+    let mut description = lang.lockdown_lifted(restored);
+
+    if failed > 0 {
+        description.push_str("\n\n");
+        description.push_str(&lang.lockdown_failed(failed));
+    }
+
+    EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.lockdown_title())
+        .description(description)
+        .build()
+}
+
+/// A lockdown is already engaged in the guild.
+pub fn already_engaged(lang: Lang) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.lockdown_title())
+        .description(lang.lockdown_already_engaged())
+        .build()
+}
+
+/// No lockdown is engaged in the guild.
+pub fn not_engaged(lang: Lang) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.lockdown_title())
+        .description(lang.lockdown_not_engaged())
+        .build()
+}
+
+/// The guild has no channel to lock.
+pub fn no_channels(lang: Lang) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.lockdown_title())
+        .description(lang.lockdown_no_channels())
+        .build()
+}
+
+/// Error while engaging or lifting a lockdown.
+pub fn error(lang: Lang) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.lockdown_title())
+        .description(lang.lockdown_error())
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress() {
+        progress(Lang::DEFAULT, 50, 500);
+        cancelling(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_summary() {
+        summary(
+            Lang::DEFAULT,
+            EngageOutcome::Completed {
+                locked: 498,
+                failed: 2,
+            },
+        );
+        summary(
+            Lang::DEFAULT,
+            EngageOutcome::Cancelled {
+                restored: 120,
+                failed: 0,
+            },
+        );
+        lifted(Lang::DEFAULT, 500, 1);
+    }
+
+    #[test]
+    fn test_errors() {
+        already_engaged(Lang::DEFAULT);
+        not_engaged(Lang::DEFAULT);
+        no_channels(Lang::DEFAULT);
+        error(Lang::DEFAULT);
+    }
+}
@@ -0,0 +1,1223 @@
+//! Embeds sent in the guild logs channel.
+
+use raidprotect_model::database::model::{
+    AntinukeAction, FirstMessageAction, JoinBurstSignal, Modlog, ObjectId, PatternAction,
+    PatternRule, VoiceConfig,
+};
+use time::OffsetDateTime;
+use twilight_mention::Mention;
+use twilight_model::{
+    channel::embed::Embed,
+    guild::Permissions,
+    http::attachment::Attachment,
+    id::{
+        marker::{ChannelMarker, GuildMarker, RoleMarker, UserMarker},
+        Id,
+    },
+};
+use twilight_util::builder::embed::{EmbedFieldBuilder, EmbedFooterBuilder};
+
+use super::{
+    builder::EmbedLimitError,
+    bulk_ban::{id_list, MAX_IDS_LISTED},
+    sanction_roles::{dm_status, sanction_kind},
+    EmbedBuilder, COLOR_RED, COLOR_TRANSPARENT,
+};
+use crate::{
+    event::{
+        first_message::FilterReason,
+        growth::GrowthRate,
+        join_burst::{Confidence, JoinBurst},
+        pins::PinChange,
+        raid_mode::JoinStats,
+        role_logs::{ChangeAuthor, RoleChange},
+        voice::VoiceChange,
+    },
+    feature::automation::TrustReason,
+    interaction::command::moderation::PostAction,
+    translations::Lang,
+    util::{
+        name::NameIssue,
+        time::{from_secs, plain_date, relative},
+        TextProcessExt,
+    },
+};
+
+/// Maximum length of the link displayed in the link filter logs.
+const MAX_LINK_LENGTH: usize = 256;
+
+/// Maximum length of the audit log reason displayed in the role logs.
+const MAX_REASON_LENGTH: usize = 512;
+
+/// Member joined the server while the raid mode is enabled.
+///
+/// The `created_at` parameter is the account creation date, in seconds. The
+/// `new_account` marker is added if the account is new (see
+/// [`new_account_marker`]).
+///
+/// [`new_account_marker`]: crate::util::account::new_account_marker
+pub fn join_alert(
+    lang: Lang,
+    user: Id<UserMarker>,
+    created_at: u64,
+    kicked: bool,
+    new_account: Option<String>,
+) -> Embed {
+    let created_at = relative(from_secs(created_at));
+    let description = if kicked {
+        lang.raid_mode_join_alert_kicked(created_at, user.mention())
+    } else {
+        lang.raid_mode_join_alert(created_at, user.mention())
+    };
+
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(with_marker(description, new_account))
+        .build()
+}
+
+/// Raid mode enabled by a moderator.
+pub fn raid_mode_enabled(lang: Lang, user: Id<UserMarker>) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.raid_mode_enabled_log(user.mention()))
+        .build()
+}
+
+/// Raid mode disabled by a moderator.
+pub fn raid_mode_disabled(lang: Lang, user: Id<UserMarker>) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.raid_mode_disabled_log(user.mention()))
+        .build()
+}
+
+/// Interrupted lockdown engagement rolled back.
+pub fn lockdown_interrupted(lang: Lang, restored: usize) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.lockdown_title())
+        .description(lang.lockdown_interrupted_log(restored))
+        .build()
+}
+
+/// User sanctioned for adding reactions too quickly.
+pub fn reaction_spam(
+    lang: Lang,
+    user: Id<UserMarker>,
+    count: usize,
+    timeout: bool,
+    new_account: Option<String>,
+) -> Embed {
+    let description = if timeout {
+        lang.reaction_spam_log_timeout(count, user.mention())
+    } else {
+        lang.reaction_spam_log(count, user.mention())
+    };
+
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(with_marker(description, new_account))
+        .build()
+}
+
+/// Message deleted for containing a filtered invite or link.
+pub fn link_filter(
+    lang: Lang,
+    user: Id<UserMarker>,
+    channel: Id<ChannelMarker>,
+    link: &str,
+    timeout: bool,
+    new_account: Option<String>,
+) -> Embed {
+    let description = if timeout {
+        lang.link_filter_log_timeout(channel.mention(), user.mention())
+    } else {
+        lang.link_filter_log(channel.mention(), user.mention())
+    };
+
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(with_marker(description, new_account))
+        .field(EmbedFieldBuilder::new(
+            lang.link_filter_link_field(),
+            format!("`{}`", link.max_len(MAX_LINK_LENGTH)),
+        ))
+        .build()
+}
+
+/// Message matching a custom pattern rule.
+///
+/// The message has been deleted unless the action of the rule is
+/// [`PatternAction::Log`].
+pub fn pattern_rule(
+    lang: Lang,
+    user: Id<UserMarker>,
+    channel: Id<ChannelMarker>,
+    rule: &PatternRule,
+    timeout: bool,
+    new_account: Option<String>,
+) -> Embed {
+    let description = match rule.action {
+        PatternAction::Log => lang.pattern_rule_log(channel.mention(), user.mention()),
+        _ if timeout => lang.pattern_rule_log_timeout(channel.mention(), user.mention()),
+        _ => lang.pattern_rule_log_deleted(channel.mention(), user.mention()),
+    };
+
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(with_marker(description, new_account))
+        .field(EmbedFieldBuilder::new(
+            lang.pattern_rule_field(),
+            rule.name.clone(),
+        ))
+        .build()
+}
+
+/// First message of a new member filtered.
+///
+/// The message has been deleted. The `action` is [`None`] if the configured
+/// action could not be applied to the user.
+pub fn first_message(
+    lang: Lang,
+    user: Id<UserMarker>,
+    channel: Id<ChannelMarker>,
+    reason: &FilterReason,
+    action: Option<FirstMessageAction>,
+    new_account: Option<String>,
+) -> Embed {
+    let description = match action {
+        Some(FirstMessageAction::Delete) => {
+            lang.first_message_log(channel.mention(), user.mention())
+        }
+        Some(FirstMessageAction::Timeout) => {
+            lang.first_message_log_timeout(channel.mention(), user.mention())
+        }
+        Some(FirstMessageAction::Ban) => {
+            lang.first_message_log_ban(channel.mention(), user.mention())
+        }
+        None => lang.first_message_log_failed(channel.mention(), user.mention()),
+    };
+    let reason = match reason {
+        FilterReason::Link(link) => lang.first_message_reason_link(link.max_len(MAX_LINK_LENGTH)),
+        FilterReason::Mentions(count) => lang.first_message_reason_mentions(*count),
+    };
+
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(with_marker(description, new_account))
+        .field(EmbedFieldBuilder::new(
+            lang.first_message_reason_field(),
+            reason,
+        ))
+        .build()
+}
+
+/// Action taken on a forum post by a moderator.
+pub fn forum_post(
+    lang: Lang,
+    action: PostAction,
+    channel: Id<ChannelMarker>,
+    moderator: Id<UserMarker>,
+    reason: Option<String>,
+) -> Embed {
+    let description = match action {
+        PostAction::Lock => lang.post_locked_log(channel.mention(), moderator.mention()),
+        PostAction::Archive => lang.post_archived_log(channel.mention(), moderator.mention()),
+    };
+
+    let mut builder = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(description);
+    if let Some(reason) = reason {
+        builder = builder.field(EmbedFieldBuilder::new(lang.post_reason_field(), reason));
+    }
+
+    builder.build()
+}
+
+/// User that deleted too many channels or roles.
+///
+/// If `applied` is `false`, the configured action could not be applied to
+/// the user.
+pub fn antinuke(
+    lang: Lang,
+    user: Id<UserMarker>,
+    count: usize,
+    action: AntinukeAction,
+    applied: bool,
+) -> Embed {
+    let description = match (applied, action) {
+        (true, AntinukeAction::StripRoles) => lang.antinuke_log_strip_roles(count, user.mention()),
+        (true, AntinukeAction::Ban) => lang.antinuke_log_ban(count, user.mention()),
+        (false, _) => lang.antinuke_log_failed(count, user.mention()),
+    };
+
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.antinuke_log_title())
+        .description(description)
+        .build()
+}
+
+/// Join burst detected in the guild.
+///
+/// The signals that matched on the accounts of the burst are listed in a
+/// field, if any.
+pub fn join_burst(lang: Lang, burst: &JoinBurst, interval: u16) -> Embed {
+    let (color, description) = match burst.confidence() {
+        Confidence::High if burst.signals.is_empty() => (
+            COLOR_RED,
+            lang.join_burst_log_growth_high(burst.joins, interval),
+        ),
+        Confidence::High => (COLOR_RED, lang.join_burst_log_high(burst.joins, interval)),
+        Confidence::Low => (
+            COLOR_TRANSPARENT,
+            lang.join_burst_log_low(burst.joins, interval),
+        ),
+    };
+
+    let mut builder = EmbedBuilder::new()
+        .color(color)
+        .title(lang.join_burst_log_title())
+        .description(description);
+
+    if !burst.signals.is_empty() {
+        let signals = burst
+            .signals
+            .iter()
+            .map(|signal| match signal.signal {
+                JoinBurstSignal::Avatar => lang.join_burst_log_avatar(signal.count),
+                JoinBurstSignal::Name => lang.join_burst_log_name(signal.count),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        builder = builder.field(EmbedFieldBuilder::new(
+            lang.join_burst_log_signals(),
+            signals,
+        ));
+    }
+
+    if let Some(growth) = burst.growth.filter(GrowthRate::is_abnormal) {
+        builder = builder.field(EmbedFieldBuilder::new(
+            lang.join_burst_log_growth(),
+            growth_rate(lang, growth),
+        ));
+    }
+
+    builder.build()
+}
+
+/// Member count of the guild grows abnormally.
+pub fn abnormal_growth(lang: Lang, rate: GrowthRate) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.growth_log_title())
+        .description(lang.growth_log())
+        .field(EmbedFieldBuilder::new(
+            lang.join_burst_log_growth(),
+            growth_rate(lang, rate),
+        ))
+        .build()
+}
+
+/// Current and usual growth rate of a guild.
+fn growth_rate(lang: Lang, rate: GrowthRate) -> String {
+    lang.growth_log_rate(rate.current, rate.usual.unwrap_or_default())
+}
+
+/// Voice state of a member changed.
+pub fn voice_change(lang: Lang, user: Id<UserMarker>, change: VoiceChange) -> Embed {
+    let user = user.mention();
+    let description = match change {
+        VoiceChange::Join(channel) => lang.voice_log_join(channel.mention(), user),
+        VoiceChange::Leave(channel) => lang.voice_log_leave(channel.mention(), user),
+        VoiceChange::Move { from, to } => lang.voice_log_move(from.mention(), to.mention(), user),
+        VoiceChange::Mute {
+            channel,
+            mute: true,
+        } => lang.voice_log_mute(channel.mention(), user),
+        VoiceChange::Mute {
+            channel,
+            mute: false,
+        } => lang.voice_log_unmute(channel.mention(), user),
+        VoiceChange::Deaf {
+            channel,
+            deaf: true,
+        } => lang.voice_log_deaf(channel.mention(), user),
+        VoiceChange::Deaf {
+            channel,
+            deaf: false,
+        } => lang.voice_log_undeaf(channel.mention(), user),
+    };
+
+    EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .description(description)
+        .build()
+}
+
+/// Message pinned or unpinned by a moderator.
+///
+/// The `content` of the message is shown if it could be fetched.
+pub fn pin_change(
+    lang: Lang,
+    guild_id: Id<GuildMarker>,
+    change: &PinChange,
+    content: Option<String>,
+) -> Embed {
+    let link = format!(
+        "https://discord.com/channels/{guild_id}/{}/{}",
+        change.channel_id, change.message_id
+    );
+    let channel = change.channel_id.mention();
+    let description = match (change.pinned, change.moderator_id) {
+        (true, Some(moderator)) => lang.pin_log_pinned(channel, link, moderator.mention()),
+        (false, Some(moderator)) => lang.pin_log_unpinned(channel, link, moderator.mention()),
+        (true, None) => lang.pin_log_pinned_unknown(channel, link),
+        (false, None) => lang.pin_log_unpinned_unknown(channel, link),
+    };
+
+    let mut builder = EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .description(description);
+    if let Some(content) = content {
+        builder = builder.field(EmbedFieldBuilder::new(lang.pin_log_content(), content));
+    }
+
+    builder.build()
+}
+
+/// Pins of a channel updated, without a matching audit log entry.
+pub fn pins_updated(lang: Lang, channel: Id<ChannelMarker>) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .description(lang.pin_log_updated(channel.mention()))
+        .build()
+}
+
+/// Roles added to or removed from a member.
+///
+/// The `reason` of the audit log entry is shown if available.
+pub fn role_change(
+    lang: Lang,
+    user: Id<UserMarker>,
+    change: &RoleChange,
+    author: ChangeAuthor,
+    reason: Option<String>,
+) -> Embed {
+    let description = match author {
+        ChangeAuthor::Bot => lang.role_log_bot(user.mention()),
+        ChangeAuthor::Moderator(moderator) => {
+            lang.role_log_moderator(moderator.mention(), user.mention())
+        }
+        ChangeAuthor::Unknown => lang.role_log_unknown(user.mention()),
+    };
+    let roles = |roles: &[Id<RoleMarker>]| {
+        roles
+            .iter()
+            .map(|role| role.mention().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let mut builder = EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .description(description);
+    if !change.added.is_empty() {
+        builder = builder.field(EmbedFieldBuilder::new(
+            lang.role_log_added(),
+            roles(&change.added),
+        ));
+    }
+    if !change.removed.is_empty() {
+        builder = builder.field(EmbedFieldBuilder::new(
+            lang.role_log_removed(),
+            roles(&change.removed),
+        ));
+    }
+    if let Some(reason) = reason {
+        builder = builder.field(EmbedFieldBuilder::new(
+            lang.role_log_reason(),
+            reason.max_len(MAX_REASON_LENGTH),
+        ));
+    }
+
+    builder.build()
+}
+
+/// Timeout of a muted member reapplied after being cleared.
+///
+/// The `clearer` is [`None`] if the author could not be found in the audit
+/// log.
+pub fn mute_enforced(
+    lang: Lang,
+    user: Id<UserMarker>,
+    clearer: Option<Id<UserMarker>>,
+    expires_at: OffsetDateTime,
+) -> Embed {
+    let (expires, user) = (relative(expires_at), user.mention());
+    let description = match clearer {
+        Some(clearer) => lang.mute_enforced_log(clearer.mention(), expires, user),
+        None => lang.mute_enforced_log_unknown(expires, user),
+    };
+
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(description)
+        .build()
+}
+
+/// User that joined voice channels too quickly.
+///
+/// If `applied` is `false`, the configured action could not be applied to
+/// the user. The `timeout` parameter is `false` if the timeout has been
+/// softened for a trusted member.
+pub fn voice_hop(
+    lang: Lang,
+    user: Id<UserMarker>,
+    config: &VoiceConfig,
+    applied: bool,
+    timeout: bool,
+    new_account: Option<String>,
+) -> Embed {
+    let (count, interval, user) = (config.max_hops, config.interval, user.mention());
+    let description = match (applied, timeout) {
+        (true, false) => lang.voice_hop_log(count, interval, user),
+        (true, true) => lang.voice_hop_log_timeout(count, interval, user),
+        (false, _) => lang.voice_hop_log_failed(count, interval, user),
+    };
+
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.voice_hop_log_title())
+        .description(with_marker(description, new_account))
+        .build()
+}
+
+/// Member with a problematic name whose nickname has been reset.
+///
+/// If `nick` is [`None`], the nickname could not be updated.
+pub fn name_reset(
+    lang: Lang,
+    user: Id<UserMarker>,
+    issue: NameIssue,
+    nick: Option<&str>,
+    new_account: Option<String>,
+) -> Embed {
+    let (issue, user) = (name_issue(issue, lang), user.mention());
+    let description = match nick {
+        Some(nick) => lang.name_log_reset(issue, nick, user),
+        None => lang.name_log_failed(issue, user),
+    };
+
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.name_log_title())
+        .description(with_marker(description, new_account))
+        .build()
+}
+
+/// Member with a problematic name reported to the moderators.
+pub fn name_flagged(
+    lang: Lang,
+    user: Id<UserMarker>,
+    issue: NameIssue,
+    new_account: Option<String>,
+) -> Embed {
+    let description = lang.name_log_flagged(name_issue(issue, lang), user.mention());
+
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.name_log_title())
+        .description(with_marker(description, new_account))
+        .build()
+}
+
+/// Member granted the activity role.
+pub fn activity_role(lang: Lang, user: Id<UserMarker>, role: Id<RoleMarker>, count: u32) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .description(lang.activity_role_log(count, role.mention(), user.mention()))
+        .build()
+}
+
+/// Configuration preset applied by an administrator.
+pub fn preset_applied(lang: Lang, preset: &str, user: Id<UserMarker>, count: usize) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .description(lang.preset_log(count, preset, user.mention()))
+        .build()
+}
+
+/// Sanction applied by a moderator.
+///
+/// The delivery status of the private message sent to the sanctioned user is
+/// shown if the user has been notified (see [`Modlog::dm_status`]).
+pub fn sanction(lang: Lang, modlog: &Modlog) -> Embed {
+    let description = lang.sanction_log(
+        sanction_kind(lang, modlog.kind),
+        modlog.moderator.id.mention(),
+        modlog.user.id.mention(),
+    );
+
+    let mut builder = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.sanction_log_title())
+        .description(description);
+    if let Some(reason) = &modlog.reason {
+        builder = builder.field(EmbedFieldBuilder::new(
+            lang.sanction_log_reason(),
+            reason.clone(),
+        ));
+    }
+    if let Some(status) = modlog.dm_status {
+        builder = builder.field(
+            EmbedFieldBuilder::new(lang.sanction_log_dm(), dm_status(lang, status)).inline(),
+        );
+    }
+
+    builder.build()
+}
+
+/// Shadow sanction applied by a moderator.
+///
+/// Shadow sanctions are only logged in the logs channel (see
+/// [`Modlog::shadow`]).
+pub fn shadow_sanction(lang: Lang, modlog: &Modlog) -> Embed {
+    let description = lang.shadow_sanction_log(
+        sanction_kind(lang, modlog.kind),
+        modlog.moderator.id.mention(),
+        modlog.user.id.mention(),
+    );
+
+    let mut builder = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.shadow_sanction_log_title())
+        .description(description);
+    if let Some(reason) = &modlog.reason {
+        builder = builder.field(EmbedFieldBuilder::new(
+            lang.shadow_sanction_log_reason(),
+            reason.clone(),
+        ));
+    }
+
+    builder.build()
+}
+
+/// Users banned within a time window unbanned at once by a moderator.
+///
+/// The moderation logs of the bans are listed, at most 20 of them are shown
+/// (see [`id_list`]) and the full list is attached to the message (see
+/// [`bulk_unban_cases`]). The list is never truncated, an [`EmbedLimitError`]
+/// is returned if it does not fit in the field.
+pub fn bulk_unban(
+    lang: Lang,
+    moderator: Id<UserMarker>,
+    count: usize,
+    cases: &[ObjectId],
+    reason: Option<&str>,
+) -> Result<Embed, EmbedLimitError> {
+    let mut builder = EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .title(lang.bulk_unban_log_title())
+        .description(lang.bulk_unban_log(count, moderator.mention()));
+
+    if let Some(reason) = reason {
+        builder = builder.field(EmbedFieldBuilder::new(lang.bulk_unban_log_reason(), reason));
+    }
+    if !cases.is_empty() {
+        builder = builder.required_field(EmbedFieldBuilder::new(
+            lang.bulk_unban_log_cases(),
+            id_list(lang, cases),
+        ))?;
+    }
+
+    Ok(builder.build())
+}
+
+/// File with the moderation logs of a bulk unban, one per line.
+///
+/// Returns [`None`] if all the moderation logs are shown in the embed.
+pub fn bulk_unban_cases(lang: Lang, cases: &[ObjectId]) -> Option<Attachment> {
+    if cases.len() <= MAX_IDS_LISTED {
+        return None;
+    }
+
+    let file = cases
+        .iter()
+        .map(|case| format!("{case}\n"))
+        .collect::<String>();
+
+    Some(Attachment {
+        file: file.into_bytes(),
+        filename: "cases.txt".to_owned(),
+        id: 0,
+        description: Some(lang.bulk_unban_log_cases_file().to_owned()),
+    })
+}
+
+/// Description of a name issue.
+fn name_issue(issue: NameIssue, lang: Lang) -> &'static str {
+    match issue {
+        NameIssue::Zalgo => lang.name_issue_zalgo(),
+        NameIssue::Invisible => lang.name_issue_invisible(),
+    }
+}
+
+/// Copy of a log embed sent by private message.
+///
+/// The date is added as plain text in the footer since the private message
+/// may be read long after it was sent.
+pub fn private_copy(lang: Lang, mut embed: Embed, date: OffsetDateTime) -> Embed {
+    let footer = EmbedFooterBuilder::new(plain_date(lang, date)).build();
+    embed.footer = Some(footer);
+
+    embed
+}
+
+/// Guild ownership transferred.
+pub fn owner_transfer(
+    lang: Lang,
+    previous: Id<UserMarker>,
+    owner: Id<UserMarker>,
+    actor: Option<Id<UserMarker>>,
+) -> Embed {
+    let description = lang.sentinel_owner_transfer(owner.mention(), previous.mention());
+
+    sentinel_alert(lang, description, actor)
+}
+
+/// Role that gained dangerous permissions.
+pub fn role_permissions(
+    lang: Lang,
+    role: Id<RoleMarker>,
+    permissions: Permissions,
+    actor: Option<Id<UserMarker>>,
+) -> Embed {
+    let description = lang.sentinel_role_permissions(permission_names(permissions), role.mention());
+
+    sentinel_alert(lang, description, actor)
+}
+
+/// Member given a role with dangerous permissions.
+pub fn member_role(
+    lang: Lang,
+    user: Id<UserMarker>,
+    role: Id<RoleMarker>,
+    permissions: Permissions,
+    actor: Option<Id<UserMarker>>,
+) -> Embed {
+    let description = lang.sentinel_member_role(
+        permission_names(permissions),
+        role.mention(),
+        user.mention(),
+    );
+
+    sentinel_alert(lang, description, actor)
+}
+
+/// Dangerous permission change alert, with the author of the change.
+fn sentinel_alert(lang: Lang, description: String, actor: Option<Id<UserMarker>>) -> Embed {
+    let actor = match actor {
+        Some(actor) => lang.sentinel_actor(actor.mention()),
+        None => lang.sentinel_actor_unknown().to_owned(),
+    };
+
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.sentinel_alert_title())
+        .description(format!("{description}\n{actor}"))
+        .build()
+}
+
+/// Format a list of permission names.
+fn permission_names(permissions: Permissions) -> String {
+    let names = [
+        (Permissions::ADMINISTRATOR, "ADMINISTRATOR"),
+        (Permissions::BAN_MEMBERS, "BAN_MEMBERS"),
+        (Permissions::MANAGE_WEBHOOKS, "MANAGE_WEBHOOKS"),
+    ];
+
+    names
+        .into_iter()
+        .filter(|(permission, _)| permissions.contains(*permission))
+        .map(|(_, name)| format!("`{name}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Summary of a raid simulation.
+pub fn simulation_summary(lang: Lang, stats: JoinStats) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .description(lang.simulation_summary(stats.held, stats.joins, stats.kicked))
+        .build()
+}
+
+/// Append the new account marker to a log description.
+fn with_marker(description: String, new_account: Option<String>) -> String {
+    match new_account {
+        Some(marker) => format!("{description}\n{marker}"),
+        None => description,
+    }
+}
+
+/// Tag an embed generated by a raid simulation.
+///
+/// The title is prefixed with a simulation tag and the footer explains that no
+/// action has been taken.
+pub fn simulation(lang: Lang, embed: Embed) -> Embed {
+    let title = match &embed.title {
+        Some(title) => format!("[{}] {title}", lang.simulation_tag()),
+        None => format!("[{}]", lang.simulation_tag()),
+    };
+
+    let mut builder = EmbedBuilder::new()
+        .title(title)
+        .footer(EmbedFooterBuilder::new(lang.simulation_footer()));
+
+    if let Some(color) = embed.color {
+        builder = builder.color(color);
+    }
+    if let Some(description) = embed.description {
+        builder = builder.description(description);
+    }
+    for field in embed.fields {
+        builder = builder.field(field);
+    }
+
+    builder.build()
+}
+
+/// Explain why an automatic sanction has been softened.
+///
+/// The first offense of trusted members is not sanctioned (see the
+/// [`automation`] module).
+///
+/// [`automation`]: crate::feature::automation
+pub fn softened(lang: Lang, mut embed: Embed, reason: TrustReason) -> Embed {
+    let reason = match reason {
+        TrustReason::Booster => lang.grace_trust_booster(),
+        TrustReason::Tenure => lang.grace_trust_tenure(),
+        TrustReason::Role => lang.grace_trust_role(),
+    };
+    let field = EmbedFieldBuilder::new(lang.grace_log_field(), lang.grace_log(reason));
+
+    embed.fields.push(field.build());
+    embed
+}
+
+#[cfg(test)]
+mod tests {
+    use raidprotect_model::database::model::{DmStatus, ModlogType, VoiceHopAction};
+
+    use super::*;
+    use crate::{event::join_burst::SignalMatch, test_utils::fixture};
+
+    #[test]
+    fn test_join_alert() {
+        join_alert(Lang::DEFAULT, Id::new(1), 0, false, None);
+        join_alert(
+            Lang::DEFAULT,
+            Id::new(1),
+            0,
+            true,
+            Some("marker".to_owned()),
+        );
+    }
+
+    #[test]
+    fn test_raid_mode() {
+        raid_mode_enabled(Lang::DEFAULT, Id::new(1));
+        raid_mode_disabled(Lang::DEFAULT, Id::new(1));
+    }
+
+    #[test]
+    fn test_lockdown_interrupted() {
+        lockdown_interrupted(Lang::DEFAULT, 120);
+    }
+
+    #[test]
+    fn test_simulation() {
+        let embed = simulation(
+            Lang::DEFAULT,
+            join_alert(Lang::DEFAULT, Id::new(1), 0, true, None),
+        );
+        assert!(embed.title.unwrap().starts_with('['));
+        assert!(embed.footer.is_some());
+
+        let stats = JoinStats {
+            joins: 10,
+            kicked: 5,
+            held: 2,
+        };
+        simulation(Lang::DEFAULT, simulation_summary(Lang::DEFAULT, stats));
+    }
+
+    #[test]
+    fn test_mute_enforced() {
+        let expires_at = OffsetDateTime::UNIX_EPOCH;
+        let embed = mute_enforced(Lang::DEFAULT, Id::new(1), Some(Id::new(2)), expires_at);
+        assert!(embed.description.unwrap().contains("<@2>"));
+
+        let embed = mute_enforced(Lang::DEFAULT, Id::new(1), None, expires_at);
+        assert!(embed.description.unwrap().contains("<@1>"));
+    }
+
+    #[test]
+    fn test_softened() {
+        let embed = link_filter(Lang::DEFAULT, Id::new(1), Id::new(2), "link", false, None);
+        let fields = embed.fields.len();
+
+        for reason in [TrustReason::Booster, TrustReason::Tenure, TrustReason::Role] {
+            let embed = softened(Lang::DEFAULT, embed.clone(), reason);
+            assert_eq!(embed.fields.len(), fields + 1);
+        }
+    }
+
+    #[test]
+    fn test_role_change() {
+        let change = RoleChange {
+            added: vec![Id::new(2), Id::new(3)],
+            removed: vec![Id::new(4)],
+        };
+
+        let author = ChangeAuthor::Moderator(Id::new(5));
+        let embed = role_change(Lang::DEFAULT, Id::new(1), &change, author, None);
+        assert_eq!(embed.fields.len(), 2);
+        assert_eq!(embed.fields[0].value, "<@&2>, <@&3>");
+
+        let change = RoleChange {
+            added: Vec::new(),
+            removed: vec![Id::new(4)],
+        };
+        let reason = Some("Sanction role".to_owned());
+        let author = ChangeAuthor::Bot;
+        let embed = role_change(Lang::DEFAULT, Id::new(1), &change, author, reason);
+        assert_eq!(embed.fields.len(), 2);
+
+        let author = ChangeAuthor::Unknown;
+        role_change(Lang::DEFAULT, Id::new(1), &change, author, None);
+    }
+
+    #[test]
+    fn test_pin_change() {
+        let mut change = PinChange {
+            id: Id::new(1),
+            pinned: true,
+            channel_id: Id::new(2),
+            message_id: Id::new(3),
+            moderator_id: Some(Id::new(4)),
+        };
+
+        let embed = pin_change(Lang::DEFAULT, Id::new(1), &change, Some("raid".to_owned()));
+        assert!(embed
+            .description
+            .unwrap()
+            .contains("https://discord.com/channels/1/2/3"));
+        assert_eq!(embed.fields.len(), 1);
+
+        change.pinned = false;
+        change.moderator_id = None;
+        assert!(pin_change(Lang::DEFAULT, Id::new(1), &change, None)
+            .fields
+            .is_empty());
+
+        pins_updated(Lang::DEFAULT, Id::new(2));
+    }
+
+    #[test]
+    fn test_with_marker() {
+        assert_eq!(with_marker("log".to_owned(), None), "log");
+        assert_eq!(
+            with_marker("log".to_owned(), Some("marker".to_owned())),
+            "log\nmarker"
+        );
+    }
+
+    #[test]
+    fn test_antinuke() {
+        antinuke(
+            Lang::DEFAULT,
+            Id::new(1),
+            6,
+            AntinukeAction::StripRoles,
+            true,
+        );
+        antinuke(Lang::DEFAULT, Id::new(1), 6, AntinukeAction::Ban, true);
+        antinuke(Lang::DEFAULT, Id::new(1), 6, AntinukeAction::Ban, false);
+    }
+
+    #[test]
+    fn test_join_burst() {
+        let mut burst = JoinBurst {
+            joins: 12,
+            signals: Vec::new(),
+            growth: None,
+        };
+
+        let embed = join_burst(Lang::DEFAULT, &burst, 30);
+        assert_eq!(embed.color, Some(COLOR_TRANSPARENT));
+        assert!(embed.fields.is_empty());
+
+        burst.signals.push(SignalMatch {
+            signal: JoinBurstSignal::Name,
+            count: 8,
+        });
+        let embed = join_burst(Lang::DEFAULT, &burst, 30);
+        assert_eq!(embed.color, Some(COLOR_RED));
+        assert_eq!(embed.fields.len(), 1);
+
+        burst.growth = Some(GrowthRate {
+            current: 50,
+            usual: Some(2),
+        });
+        let embed = join_burst(Lang::DEFAULT, &burst, 30);
+        assert_eq!(embed.fields.len(), 2);
+        assert!(embed.fields[1].value.contains("50"));
+    }
+
+    #[test]
+    fn test_abnormal_growth() {
+        let rate = GrowthRate {
+            current: 50,
+            usual: Some(2),
+        };
+
+        let embed = abnormal_growth(Lang::DEFAULT, rate);
+        assert_eq!(embed.color, Some(COLOR_RED));
+        assert!(embed.fields[0].value.contains("50"));
+    }
+
+    #[test]
+    fn test_private_copy() {
+        let embed = antinuke(Lang::DEFAULT, Id::new(1), 6, AntinukeAction::Ban, true);
+        let embed = private_copy(Lang::DEFAULT, embed, OffsetDateTime::UNIX_EPOCH);
+
+        assert_eq!(embed.footer.unwrap().text, "1970-01-01 00:00 UTC");
+    }
+
+    #[test]
+    fn test_forum_post() {
+        forum_post(
+            Lang::DEFAULT,
+            PostAction::Lock,
+            Id::new(1),
+            Id::new(2),
+            None,
+        );
+        forum_post(
+            Lang::DEFAULT,
+            PostAction::Archive,
+            Id::new(1),
+            Id::new(2),
+            Some("reason".to_owned()),
+        );
+    }
+
+    #[test]
+    fn test_sentinel() {
+        owner_transfer(Lang::DEFAULT, Id::new(1), Id::new(2), None);
+        role_permissions(
+            Lang::DEFAULT,
+            Id::new(1),
+            Permissions::ADMINISTRATOR,
+            Some(Id::new(2)),
+        );
+        member_role(
+            Lang::DEFAULT,
+            Id::new(1),
+            Id::new(2),
+            Permissions::BAN_MEMBERS,
+            None,
+        );
+    }
+
+    #[test]
+    fn test_permission_names() {
+        assert_eq!(
+            permission_names(Permissions::ADMINISTRATOR | Permissions::MANAGE_WEBHOOKS),
+            "`ADMINISTRATOR`, `MANAGE_WEBHOOKS`"
+        );
+    }
+
+    #[test]
+    fn test_voice_change() {
+        let (from, to) = (Id::new(2), Id::new(3));
+        let changes = [
+            VoiceChange::Join(from),
+            VoiceChange::Leave(from),
+            VoiceChange::Move { from, to },
+            VoiceChange::Mute {
+                channel: from,
+                mute: true,
+            },
+            VoiceChange::Deaf {
+                channel: from,
+                deaf: false,
+            },
+        ];
+
+        for change in changes {
+            voice_change(Lang::DEFAULT, Id::new(1), change);
+        }
+    }
+
+    #[test]
+    fn test_voice_hop() {
+        let mut config = VoiceConfig::default();
+        voice_hop(Lang::DEFAULT, Id::new(1), &config, true, false, None);
+        voice_hop(Lang::DEFAULT, Id::new(1), &config, false, false, None);
+
+        config.action = VoiceHopAction::Timeout;
+        voice_hop(
+            Lang::DEFAULT,
+            Id::new(1),
+            &config,
+            true,
+            true,
+            Some("marker".to_owned()),
+        );
+    }
+
+    #[test]
+    fn test_name() {
+        name_reset(
+            Lang::DEFAULT,
+            Id::new(1),
+            NameIssue::Zalgo,
+            Some("Zalgo"),
+            None,
+        );
+        name_reset(
+            Lang::DEFAULT,
+            Id::new(1),
+            NameIssue::Invisible,
+            None,
+            Some("marker".to_owned()),
+        );
+        name_flagged(Lang::DEFAULT, Id::new(1), NameIssue::Invisible, None);
+    }
+
+    #[test]
+    fn test_link_filter() {
+        link_filter(
+            Lang::DEFAULT,
+            Id::new(1),
+            Id::new(2),
+            "discord.gg/raid",
+            false,
+            None,
+        );
+        link_filter(
+            Lang::DEFAULT,
+            Id::new(1),
+            Id::new(2),
+            &"a".repeat(1000),
+            true,
+            Some("marker".to_owned()),
+        );
+    }
+
+    #[test]
+    fn test_first_message() {
+        let link = FilterReason::Link("discord.gg/raid".to_owned());
+        let mentions = FilterReason::Mentions(10);
+
+        for action in [
+            Some(FirstMessageAction::Delete),
+            Some(FirstMessageAction::Timeout),
+            Some(FirstMessageAction::Ban),
+            None,
+        ] {
+            first_message(Lang::DEFAULT, Id::new(1), Id::new(2), &link, action, None);
+        }
+
+        first_message(
+            Lang::DEFAULT,
+            Id::new(1),
+            Id::new(2),
+            &mentions,
+            None,
+            Some("marker".to_owned()),
+        );
+    }
+
+    #[test]
+    fn test_pattern_rule() {
+        let mut rule = PatternRule {
+            name: "scam".to_owned(),
+            pattern: "free nitro".to_owned(),
+            action: PatternAction::Log,
+        };
+
+        pattern_rule(Lang::DEFAULT, Id::new(1), Id::new(2), &rule, false, None);
+        rule.action = PatternAction::Timeout;
+        pattern_rule(
+            Lang::DEFAULT,
+            Id::new(1),
+            Id::new(2),
+            &rule,
+            true,
+            Some("marker".to_owned()),
+        );
+    }
+
+    #[test]
+    fn test_shadow_sanction() {
+        let mut modlog = fixture::modlog(ModlogType::Ban).shadow().build();
+
+        shadow_sanction(Lang::DEFAULT, &modlog);
+        modlog.reason = Some("reason".to_owned());
+        shadow_sanction(Lang::DEFAULT, &modlog);
+    }
+
+    #[test]
+    fn test_sanction() {
+        let mut modlog = fixture::modlog(ModlogType::Kick).reason("reason").build();
+
+        assert_eq!(sanction(Lang::DEFAULT, &modlog).fields.len(), 1);
+
+        modlog.dm_status = Some(DmStatus::Closed);
+        let embed = sanction(Lang::DEFAULT, &modlog);
+        assert_eq!(embed.fields[1].value, Lang::DEFAULT.dm_status_closed());
+    }
+
+    #[test]
+    fn test_bulk_unban() {
+        let cases = (0..25).map(|_| ObjectId::new()).collect::<Vec<_>>();
+
+        let embed = bulk_unban(Lang::DEFAULT, Id::new(1), 25, &cases, Some("mistake")).unwrap();
+        assert_eq!(embed.fields.len(), 2);
+        assert!(embed.fields[1].value.ends_with(" and 5 more"));
+
+        let attachment = bulk_unban_cases(Lang::DEFAULT, &cases).unwrap();
+        let lines = attachment.file.iter().filter(|&&byte| byte == b'\n');
+        assert_eq!(lines.count(), 25);
+        assert!(bulk_unban_cases(Lang::DEFAULT, &cases[..20]).is_none());
+
+        assert!(bulk_unban(Lang::DEFAULT, Id::new(1), 1, &[], None)
+            .unwrap()
+            .fields
+            .is_empty());
+    }
+
+    #[test]
+    fn test_preset_applied() {
+        preset_applied(Lang::DEFAULT, Lang::DEFAULT.preset_small(), Id::new(1), 3);
+    }
+
+    #[test]
+    fn test_reaction_spam() {
+        reaction_spam(Lang::DEFAULT, Id::new(1), 10, false, None);
+        reaction_spam(
+            Lang::DEFAULT,
+            Id::new(1),
+            10,
+            true,
+            Some("marker".to_owned()),
+        );
+    }
+}
@@ -2,9 +2,48 @@
 //!
 //! This crate contains types used to generate embeds used as bot responses.
 
+pub mod automod;
+pub mod ban;
+pub mod builder;
+pub mod bulk_ban;
+pub mod bulk_unban;
 pub mod captcha;
+pub mod channel_rule;
+pub mod cleanup;
+pub mod config;
 pub mod error;
+pub mod fast_mode;
+pub mod features;
+pub mod filter_feedback;
+pub mod help;
+pub mod join_review;
 pub mod kick;
+pub mod link;
+pub mod lockdown;
+pub mod logs;
+pub mod modlog;
+pub mod mute;
+pub mod onboarding;
+pub mod post;
+pub mod post_in_chat;
+pub mod preset;
+pub mod quarantine;
+pub mod raid_alert;
+pub mod reasons;
+pub mod report;
+pub mod restore;
+pub mod restore_message;
+pub mod sanction_roles;
+pub mod sentinel;
+pub mod simulate;
+pub mod stats;
+pub mod status;
+pub mod tag;
+pub mod timeout_adjust;
+pub mod watch;
+pub mod webhook;
+
+pub use builder::EmbedBuilder;
 
 /// RaidProtect's red color.
 pub const COLOR_RED: u32 = 0xd35f5f;
@@ -16,3 +55,123 @@ pub const COLOR_TRANSPARENT: u32 = 0x2f3136;
 ///
 /// Used for success messages, like after a configuration change.
 pub const COLOR_SUCCESS: u32 = 0xa0d995;
+
+#[cfg(test)]
+mod tests {
+    //! Snapshot tests of the outgoing embeds.
+    //!
+    //! Each kind of embed is rendered in every supported language to catch
+    //! unexpected changes in the messages sent by the bot. Update the
+    //! snapshots with `cargo insta review` after an intended change.
+
+    use std::fmt::Write;
+
+    use raidprotect_model::database::model::RaidModeConfig;
+    use twilight_model::{channel::embed::Embed, id::Id};
+
+    use super::*;
+    use crate::{interaction::response::InteractionResponse, translations::Lang};
+
+    /// Render an embed as plain text.
+    fn render(embed: &Embed) -> String {
+        let mut output = String::new();
+
+        if let Some(color) = embed.color {
+            writeln!(output, "color: #{color:06x}").unwrap();
+        }
+        if let Some(title) = &embed.title {
+            writeln!(output, "title: {title}").unwrap();
+        }
+        if let Some(description) = &embed.description {
+            writeln!(output, "description:\n{description}").unwrap();
+        }
+        for field in &embed.fields {
+            writeln!(output, "field: {}\n{}", field.name, field.value).unwrap();
+        }
+        if let Some(footer) = &embed.footer {
+            writeln!(output, "footer: {}", footer.text).unwrap();
+        }
+
+        output
+    }
+
+    /// Render the embed of an [`InteractionResponse`].
+    fn render_response(response: InteractionResponse) -> String {
+        match response {
+            InteractionResponse::Embed(embed) | InteractionResponse::EphemeralEmbed(embed) => {
+                render(&embed)
+            }
+            _ => panic!("response is not an embed"),
+        }
+    }
+
+    #[test]
+    fn test_error_snapshots() {
+        let id = Id::new(1);
+
+        insta::assert_snapshot!(
+            "error_en",
+            render_response(error::internal_error(Lang::En, id))
+        );
+        insta::assert_snapshot!(
+            "error_fr",
+            render_response(error::internal_error(Lang::Fr, id))
+        );
+    }
+
+    #[test]
+    fn test_modlog_snapshots() {
+        let user = Id::new(1);
+
+        insta::assert_snapshot!(
+            "modlog_en",
+            render(&logs::reaction_spam(Lang::En, user, 10, true, None))
+        );
+        insta::assert_snapshot!(
+            "modlog_fr",
+            render(&logs::reaction_spam(Lang::Fr, user, 10, true, None))
+        );
+    }
+
+    #[test]
+    fn test_join_log_snapshots() {
+        let user = Id::new(1);
+
+        insta::assert_snapshot!(
+            "join_log_en",
+            render(&logs::join_alert(Lang::En, user, 0, false, None))
+        );
+        insta::assert_snapshot!(
+            "join_log_fr",
+            render(&logs::join_alert(Lang::Fr, user, 0, false, None))
+        );
+    }
+
+    #[test]
+    fn test_raid_alert_snapshots() {
+        let user = Id::new(1);
+
+        insta::assert_snapshot!(
+            "raid_alert_en",
+            render(&logs::raid_mode_enabled(Lang::En, user))
+        );
+        insta::assert_snapshot!(
+            "raid_alert_fr",
+            render(&logs::raid_mode_enabled(Lang::Fr, user))
+        );
+    }
+
+    #[test]
+    fn test_settings_snapshots() {
+        let raid_mode = RaidModeConfig::default();
+
+        insta::assert_snapshot!(
+            "settings_en",
+            render_response(config::raid_mode_updated(Lang::En, &raid_mode))
+        );
+        insta::assert_snapshot!(
+            "settings_fr",
+            render_response(config::raid_mode_updated(Lang::Fr, &raid_mode))
+        );
+    }
+}
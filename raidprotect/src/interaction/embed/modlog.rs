@@ -0,0 +1,160 @@
+//! Embeds for the modlog search.
+
+use raidprotect_model::database::model::Modlog;
+use twilight_mention::Mention;
+use twilight_model::channel::embed::Embed;
+use twilight_util::builder::embed::EmbedFooterBuilder;
+
+use super::{
+    sanction_roles::{dm_status, sanction_kind},
+    EmbedBuilder, COLOR_RED, COLOR_TRANSPARENT,
+};
+use crate::{interaction::response::InteractionResponse, translations::Lang, util::TextProcessExt};
+
+/// Number of moderation logs displayed on each page of the results.
+const MODLOGS_PER_PAGE: usize = 10;
+
+/// Length of the reason preview in the results.
+const REASON_LENGTH: usize = 80;
+
+/// Pages of the search results (see [`Paginator`]).
+///
+/// Each result shows the case id, the type of sanction, the targeted user and
+/// the beginning of the reason, followed by the delivery status of the
+/// private message if the user has been notified. A notice is added in the footer if the number of results reached the
+/// `limit` of the search, as older results may have been omitted.
+///
+/// [`Paginator`]: crate::interaction::component::Paginator
+pub fn search_results(lang: Lang, modlogs: &[Modlog], limit: i64) -> Vec<Embed> {
+    if modlogs.is_empty() {
+        return vec![EmbedBuilder::new()
+            .color(COLOR_RED)
+            .title(lang.modlog_search_title())
+            .description(lang.modlog_search_empty())
+            .build()];
+    }
+
+    let truncated = modlogs.len() as i64 >= limit;
+
+    modlogs
+        .chunks(MODLOGS_PER_PAGE)
+        .map(|chunk| {
+            let modlogs = chunk
+                .iter()
+                .map(|modlog| {
+                    let reason = match &modlog.reason {
+                        Some(reason) => reason.replace('\n', " ").max_len(REASON_LENGTH),
+                        None => lang.modlog_no_reason().to_owned(),
+                    };
+
+                    let case = match modlog.id {
+                        Some(id) => format!("`{}` • ", id.to_hex()),
+                        None => String::new(),
+                    };
+
+                    let dm = match modlog.dm_status {
+                        Some(status) => {
+                            format!(" • {}", lang.modlog_dm_status(dm_status(lang, status)))
+                        }
+                        None => String::new(),
+                    };
+
+                    format!(
+                        "{case}**{}** {} — {}{dm}",
+                        sanction_kind(lang, modlog.kind),
+                        modlog.user.id.mention(),
+                        reason.remove_markdown()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            let mut embed = EmbedBuilder::new()
+                .color(COLOR_TRANSPARENT)
+                .title(lang.modlog_search_title())
+                .description(modlogs);
+
+            if truncated {
+                embed = embed.footer(EmbedFooterBuilder::new(lang.modlog_search_truncated(limit)));
+            }
+
+            embed.build()
+        })
+        .collect()
+}
+
+/// Search without any filter.
+pub fn no_filter(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.modlog_search_no_filter())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Invalid date in the search options.
+pub fn invalid_date(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.modlog_invalid_date())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+#[cfg(test)]
+mod tests {
+    use raidprotect_model::database::model::{DmStatus, ModlogType, ObjectId};
+
+    use super::*;
+    use crate::test_utils::fixture;
+
+    fn modlog(reason: Option<&str>) -> Modlog {
+        Modlog {
+            reason: reason.map(ToOwned::to_owned),
+            ..fixture::modlog(ModlogType::Ban).build()
+        }
+    }
+
+    #[test]
+    fn test_search_results() {
+        let modlogs = (0..15)
+            .map(|_| modlog(Some("scam\nlink")))
+            .collect::<Vec<_>>();
+
+        assert_eq!(search_results(Lang::DEFAULT, &[], 100).len(), 1);
+        assert_eq!(search_results(Lang::DEFAULT, &modlogs, 100).len(), 2);
+
+        let case = Modlog {
+            id: Some(ObjectId::parse_str("63761b2a1c8ab0b9e3f5a7c1").unwrap()),
+            ..modlog(Some("scam\nlink"))
+        };
+        let page = &search_results(Lang::DEFAULT, &[case], 100)[0];
+        let description = page.description.as_deref().unwrap();
+        assert!(description.starts_with("`63761b2a1c8ab0b9e3f5a7c1` • "));
+        assert!(description.contains("<@1>"));
+        assert!(!description.contains('\n'));
+        assert!(page.footer.is_none());
+
+        let page = &search_results(Lang::DEFAULT, &[modlog(None)], 1)[0];
+        assert!(page.footer.is_some());
+        assert!(!page.description.as_deref().unwrap().contains(" • "));
+
+        let notified = Modlog {
+            dm_status: Some(DmStatus::Delivered),
+            ..modlog(None)
+        };
+        let page = &search_results(Lang::DEFAULT, &[notified], 100)[0];
+        assert!(page
+            .description
+            .as_deref()
+            .unwrap()
+            .ends_with(Lang::DEFAULT.dm_status_delivered()));
+    }
+
+    #[test]
+    fn test_invalid_date() {
+        invalid_date(Lang::DEFAULT);
+    }
+}
@@ -0,0 +1,244 @@
+//! Embed for the mute and unmute commands.
+
+use raidprotect_model::database::model::ModlogType;
+use twilight_mention::Mention;
+use twilight_model::id::{marker::RoleMarker, Id};
+
+use super::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS};
+use crate::{interaction::response::InteractionResponse, translations::Lang, util::TextProcessExt};
+
+/// User is not a server member.
+pub fn not_member(user: String, lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.mute_not_member(user.remove_markdown().max_len(30)))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Bot is missing the permission required by the mute mode.
+pub fn bot_missing_permission(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.mute_bot_missing_permission_title())
+        .description(lang.bot_missing_permission())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// User cannot mute due to the role hierarchy
+pub fn user_hierarchy(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.mute_missing_permission_title())
+        .description(lang.hierarchy_user())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Bot cannot mute due to the role hierarchy
+pub fn bot_hierarchy(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.mute_bot_missing_permission_title())
+        .description(lang.hierarchy_bot())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Member is the guild owner, and thus cannot be muted
+pub fn member_owner(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.mute_missing_permission_title())
+        .description(lang.hierarchy_owner())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// The guild requires a reason for each sanction.
+pub fn missing_reason(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.mute_missing_reason())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// The sanction could not be applied.
+pub fn error(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.mute_error_title())
+        .description(lang.mute_error_description())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// The member has been muted or unmuted.
+pub fn success(kind: ModlogType, user: String, lang: Lang) -> InteractionResponse {
+    let user = user.remove_markdown().max_len(30);
+    let description = match kind {
+        ModlogType::Unmute => lang.unmute_success(user),
+        _ => lang.mute_success(user),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .description(description)
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// The mute role has been configured.
+pub fn role_configured(role: Id<RoleMarker>, lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_mute_role_enabled(role.mention()))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// The mute role could not be created.
+pub fn role_error(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.config_mute_role_error())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Bot is missing the permissions required to configure the mute role.
+pub fn role_missing_permission(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.config_mute_role_missing_permission())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// The mute role is above the bot's highest role.
+pub fn role_hierarchy(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.config_mute_role_hierarchy())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Members are muted with a timeout again.
+pub fn role_disabled(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_mute_role_disabled())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Reapplication of cleared timeouts enabled or disabled.
+pub fn enforce_updated(enabled: bool, lang: Lang) -> InteractionResponse {
+    let description = match enabled {
+        true => lang.config_mute_enforce_enabled(),
+        false => lang.config_mute_enforce_disabled(),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(description)
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_not_member() {
+        not_member("test".to_owned(), Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_bot_missing_permission() {
+        bot_missing_permission(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_user_hierarchy() {
+        user_hierarchy(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_bot_hierarchy() {
+        bot_hierarchy(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_member_owner() {
+        member_owner(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_missing_reason() {
+        missing_reason(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_error() {
+        error(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_success() {
+        success(ModlogType::Mute, "test".to_owned(), Lang::DEFAULT);
+        success(ModlogType::Unmute, "test".to_owned(), Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_role_configured() {
+        role_configured(Id::new(1), Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_role_error() {
+        role_error(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_role_missing_permission() {
+        role_missing_permission(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_role_hierarchy() {
+        role_hierarchy(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_role_disabled() {
+        role_disabled(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_enforce_updated() {
+        enforce_updated(true, Lang::DEFAULT);
+        enforce_updated(false, Lang::DEFAULT);
+    }
+}
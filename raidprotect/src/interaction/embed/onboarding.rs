@@ -0,0 +1,34 @@
+//! Embed sent to the guild owner when the bot joins a guild.
+
+use twilight_model::channel::embed::Embed;
+use twilight_util::builder::embed::EmbedFieldBuilder;
+
+use super::{EmbedBuilder, COLOR_TRANSPARENT};
+use crate::translations::Lang;
+
+/// Quick-start guide sent when the bot joins a guild.
+pub fn quick_start(lang: Lang, guild_name: &str) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .title(lang.onboarding_title(guild_name))
+        .description(lang.onboarding_description())
+        .field(EmbedFieldBuilder::new(
+            lang.onboarding_setup_title(),
+            lang.onboarding_setup_description(),
+        ))
+        .field(EmbedFieldBuilder::new(
+            lang.onboarding_features_title(),
+            lang.onboarding_features_description(),
+        ))
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quick_start() {
+        quick_start(Lang::DEFAULT, "guild");
+    }
+}
@@ -0,0 +1,64 @@
+//! Embeds for the forum post command.
+
+use super::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS};
+use crate::{
+    interaction::{command::moderation::PostAction, response::InteractionResponse},
+    translations::Lang,
+};
+
+/// Command not used in a forum post.
+pub fn not_forum_post(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.post_not_forum_post())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Bot is missing the permission to manage the post.
+pub fn bot_missing_permission(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.post_bot_missing_permission_title())
+        .description(lang.bot_missing_permission())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Forum post successfully updated.
+pub fn success(lang: Lang, action: PostAction) -> InteractionResponse {
+    let description = match action {
+        PostAction::Lock => lang.post_locked(),
+        PostAction::Archive => lang.post_archived(),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .description(description)
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_not_forum_post() {
+        not_forum_post(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_bot_missing_permission() {
+        bot_missing_permission(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_success() {
+        success(Lang::DEFAULT, PostAction::Lock);
+        success(Lang::DEFAULT, PostAction::Archive);
+    }
+}
@@ -0,0 +1,342 @@
+//! Messages posted with the "post in chat" button.
+//!
+//! The public messages are rendered from a [`PublicMessageTemplate`] when the
+//! button is clicked, in the language of the guild. Sanction messages show
+//! the moderation team instead of the moderator if the moderator is hidden.
+//!
+//! The same sanction message is sent by private message to the sanctioned
+//! user, with the name of the guild (see [`private_sanction`]), and in the
+//! announcement channel of the guild (see [`announcement`]). Trusted members
+//! receive a warning instead of their first automatic sanction (see
+//! [`grace_warning`]).
+
+use raidprotect_model::{
+    cache::model::interaction::PublicMessageTemplate,
+    database::model::{Modlog, ModlogType, ModlogUser, ObjectId},
+};
+use twilight_model::{
+    application::component::{button::ButtonStyle, ActionRow, Button, Component},
+    channel::embed::Embed,
+    http::interaction::InteractionResponseData,
+};
+use twilight_util::builder::{
+    embed::{EmbedFieldBuilder, EmbedFooterBuilder, ImageSource},
+    InteractionResponseDataBuilder,
+};
+
+use super::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS, COLOR_TRANSPARENT};
+use crate::{
+    translations::Lang,
+    util::{
+        account::account_created_at,
+        resource::{default_avatar_url, user_avatar_url},
+        time, TextProcessExt,
+    },
+};
+
+/// Maximum length of the sanction reason.
+const MAX_REASON_LENGTH: usize = 512;
+
+/// Render a public message.
+pub fn render(
+    lang: Lang,
+    template: &PublicMessageTemplate,
+    case_id: Option<ObjectId>,
+) -> Result<InteractionResponseData, anyhow::Error> {
+    match template {
+        PublicMessageTemplate::Sanction {
+            kind,
+            user,
+            moderator,
+            reason,
+        } => {
+            let moderator = moderator.as_ref();
+            let embed = sanction(lang, *kind, user, moderator, reason.as_deref(), case_id);
+
+            Ok(InteractionResponseDataBuilder::new()
+                .embeds([embed])
+                .build())
+        }
+        PublicMessageTemplate::Profile { user, joined_at } => profile(lang, user, *joined_at),
+    }
+}
+
+/// Sanction applied to a member.
+fn sanction(
+    lang: Lang,
+    kind: ModlogType,
+    user: &ModlogUser,
+    moderator: Option<&ModlogUser>,
+    reason: Option<&str>,
+    case_id: Option<ObjectId>,
+) -> Embed {
+    let name = user.name.remove_markdown().max_len(30);
+    let description = match kind {
+        ModlogType::Kick => lang.public_sanction_kick(name),
+        ModlogType::Ban => lang.public_sanction_ban(name),
+        ModlogType::Mute => lang.public_sanction_mute(name),
+        ModlogType::Unmute => lang.public_sanction_unmute(name),
+        ModlogType::Quarantine => lang.public_sanction_quarantine(name),
+        ModlogType::Release => lang.public_sanction_release(name),
+    };
+
+    let moderator = match moderator {
+        Some(moderator) => moderator.name.remove_markdown().max_len(30),
+        None => lang.public_sanction_team().to_owned(),
+    };
+
+    let mut embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(description)
+        .field(EmbedFieldBuilder::new(lang.public_sanction_moderator(), moderator).inline());
+    if let Some(reason) = reason {
+        embed = embed.field(EmbedFieldBuilder::new(
+            lang.public_sanction_reason(),
+            reason.max_len(MAX_REASON_LENGTH),
+        ));
+    }
+    if let Some(case_id) = case_id {
+        embed = embed.footer(EmbedFooterBuilder::new(
+            lang.public_sanction_case(case_id.to_hex()),
+        ));
+    }
+
+    embed.build()
+}
+
+/// Sanction notified by private message to the sanctioned user.
+///
+/// The message is the same as the public one, with the name of the guild
+/// where the sanction was applied as title.
+pub fn private_sanction(
+    lang: Lang,
+    guild_name: &str,
+    kind: ModlogType,
+    user: &ModlogUser,
+    moderator: Option<&ModlogUser>,
+    reason: Option<&str>,
+) -> Embed {
+    let mut embed = sanction(lang, kind, user, moderator, reason, None);
+    embed.title = Some(lang.private_sanction_title(guild_name.remove_markdown().max_len(50)));
+
+    embed
+}
+
+/// Warning sent to a trusted member instead of an automatic sanction.
+///
+/// The `reason` is the reason of the sanction that has been softened (see the
+/// [`automation`] module).
+///
+/// [`automation`]: crate::feature::automation
+pub fn grace_warning(lang: Lang, guild_name: &str, reason: &str) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.grace_warning_title(guild_name.remove_markdown().max_len(50)))
+        .description(lang.grace_warning(reason))
+        .build()
+}
+
+/// Member count milestone announced in the milestones channel.
+pub fn milestone(lang: Lang, guild_name: &str, members: u64) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.milestone_title(members))
+        .description(lang.milestone(guild_name.remove_markdown().max_len(50), members))
+        .build()
+}
+
+/// Sanction announced in the announcement channel of the guild.
+///
+/// The message is the same as the public one. The notes of the modlog are
+/// internal to the staff and are never included.
+pub fn announcement(lang: Lang, modlog: &Modlog, anonymize: bool) -> Embed {
+    let moderator = (!anonymize).then_some(&modlog.moderator);
+
+    sanction(
+        lang,
+        modlog.kind,
+        &modlog.user,
+        moderator,
+        modlog.reason.as_deref(),
+        modlog.id,
+    )
+}
+
+/// Profile of a user.
+fn profile(
+    lang: Lang,
+    user: &ModlogUser,
+    joined_at: Option<u64>,
+) -> Result<InteractionResponseData, anyhow::Error> {
+    let avatar = match user.avatar {
+        Some(avatar) => user_avatar_url(user.id, avatar, "jpg", 1024),
+        None => default_avatar_url(user.discriminator),
+    };
+
+    let mut embed = EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .title(lang.profile_title(user.discriminator, &user.name))
+        .required_footer(EmbedFooterBuilder::new(format!("ID: {}", user.id)))?
+        .thumbnail(ImageSource::url(&avatar)?);
+
+    // User profile creation time.
+    let created_at = time::from_secs(account_created_at(user.id));
+    embed = embed.field(EmbedFieldBuilder::new(
+        lang.profile_created_at(),
+        time::long_date_relative(created_at),
+    ));
+
+    // Member join date.
+    if let Some(joined_at) = joined_at {
+        embed = embed.field(EmbedFieldBuilder::new(
+            lang.profile_joined_at(),
+            time::long_date_relative(time::from_secs(joined_at)),
+        ));
+    }
+
+    let components = Component::ActionRow(ActionRow {
+        components: vec![Component::Button(Button {
+            custom_id: None,
+            disabled: false,
+            emoji: None,
+            label: Some(lang.profile_avatar_button().into()),
+            style: ButtonStyle::Link,
+            url: Some(avatar),
+        })],
+    });
+
+    Ok(InteractionResponseDataBuilder::new()
+        .embeds([embed.build()])
+        .components([components])
+        .build())
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::id::Id;
+
+    use super::*;
+    use crate::test_utils::fixture;
+
+    fn user() -> ModlogUser {
+        ModlogUser {
+            id: Id::new(1),
+            name: "Raider".to_owned(),
+            discriminator: 1234,
+            avatar: None,
+        }
+    }
+
+    #[test]
+    fn test_render_sanction() {
+        let template = PublicMessageTemplate::Sanction {
+            kind: ModlogType::Ban,
+            user: user(),
+            moderator: None,
+            reason: Some("Raid".to_owned()),
+        };
+        let case_id = ObjectId::new();
+
+        let response = render(Lang::DEFAULT, &template, Some(case_id)).unwrap();
+        let embed = &response.embeds.unwrap()[0];
+
+        assert_eq!(embed.fields.len(), 2);
+        assert_eq!(embed.fields[0].value, Lang::DEFAULT.public_sanction_team());
+        assert!(embed
+            .footer
+            .as_ref()
+            .unwrap()
+            .text
+            .contains(&case_id.to_hex()));
+    }
+
+    #[test]
+    fn test_render_sanction_moderator() {
+        let template = PublicMessageTemplate::Sanction {
+            kind: ModlogType::Mute,
+            user: user(),
+            moderator: Some(ModlogUser {
+                id: Id::new(2),
+                name: "Moderator".to_owned(),
+                discriminator: 5678,
+                avatar: None,
+            }),
+            reason: None,
+        };
+
+        let response = render(Lang::DEFAULT, &template, None).unwrap();
+        let embed = &response.embeds.unwrap()[0];
+
+        assert_eq!(embed.fields.len(), 1);
+        assert_eq!(embed.fields[0].value, "Moderator");
+    }
+
+    #[test]
+    fn test_private_sanction() {
+        let embed = private_sanction(
+            Lang::DEFAULT,
+            "RaidProtect",
+            ModlogType::Kick,
+            &user(),
+            None,
+            Some("Spam"),
+        );
+
+        assert!(embed.title.unwrap().contains("RaidProtect"));
+        assert_eq!(embed.fields.len(), 2);
+        assert!(embed.footer.is_none());
+    }
+
+    #[test]
+    fn test_grace_warning() {
+        let embed = grace_warning(Lang::DEFAULT, "RaidProtect", "Link filter");
+
+        assert!(embed.title.unwrap().contains("RaidProtect"));
+        assert!(embed.description.unwrap().contains("Link filter"));
+    }
+
+    #[test]
+    fn test_milestone() {
+        let embed = milestone(Lang::DEFAULT, "RaidProtect", 1000);
+
+        assert!(embed.title.unwrap().contains("1000"));
+        assert!(embed.description.unwrap().contains("RaidProtect"));
+    }
+
+    #[test]
+    fn test_announcement() {
+        let mut modlog = fixture::modlog(ModlogType::Ban)
+            .id(ObjectId::new())
+            .user(user())
+            .moderator(fixture::modlog_user(3, "Moderator"))
+            .reason("Raid")
+            .notes("Internal notes")
+            .build();
+
+        let embed = announcement(Lang::DEFAULT, &modlog, true);
+        assert_eq!(embed.fields.len(), 2);
+        assert_eq!(embed.fields[0].value, Lang::DEFAULT.public_sanction_team());
+        assert!(embed
+            .fields
+            .iter()
+            .all(|field| !field.value.contains("Internal notes")));
+
+        modlog.reason = None;
+        let embed = announcement(Lang::DEFAULT, &modlog, false);
+        assert_eq!(embed.fields.len(), 1);
+        assert_eq!(embed.fields[0].value, "Moderator");
+    }
+
+    #[test]
+    fn test_render_profile() {
+        let template = PublicMessageTemplate::Profile {
+            user: user(),
+            joined_at: Some(1_600_000_000),
+        };
+
+        let response = render(Lang::DEFAULT, &template, None).unwrap();
+
+        assert_eq!(response.embeds.unwrap()[0].fields.len(), 2);
+        assert!(response.components.is_some());
+    }
+}
@@ -0,0 +1,95 @@
+//! Embeds for the preset command.
+
+use twilight_model::channel::embed::Embed;
+
+use super::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS};
+use crate::{
+    feature::preset::{ConfigChange, Preset},
+    interaction::response::InteractionResponse,
+    translations::Lang,
+};
+
+/// Maximum number of changes listed in the confirmation.
+const MAX_LISTED: usize = 30;
+
+/// Confirmation before applying a preset, listing the changed settings.
+pub fn confirmation(lang: Lang, preset: Preset, changes: &[ConfigChange]) -> Embed {
+    let mut lines = changes
+        .iter()
+        .take(MAX_LISTED)
+        .map(|change| {
+            let before = change.before.as_deref().unwrap_or(lang.preset_none());
+            let after = change.after.as_deref().unwrap_or(lang.preset_none());
+
+            format!("`{}`: {before} → {after}", change.setting)
+        })
+        .collect::<Vec<_>>();
+
+    if changes.len() > MAX_LISTED {
+        lines.push(lang.preset_more(changes.len() - MAX_LISTED));
+    }
+
+    let description = format!(
+        "{}\n\n{}",
+        lang.preset_confirm_description(preset.title(lang)),
+        lines.join("\n")
+    );
+
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.preset_confirm_title())
+        .description(description)
+        .build()
+}
+
+/// Preset already matching the configuration.
+pub fn unchanged(lang: Lang, preset: Preset) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .description(lang.preset_unchanged(preset.title(lang)))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Preset applied.
+pub fn applied(lang: Lang, preset: Preset, count: usize) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .description(lang.preset_applied(count, preset.title(lang)))
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(setting: &str) -> ConfigChange {
+        ConfigChange {
+            setting: setting.to_owned(),
+            before: None,
+            after: Some("true".to_owned()),
+        }
+    }
+
+    #[test]
+    fn test_confirmation() {
+        let changes = (0..40)
+            .map(|i| change(&format!("setting.{i}")))
+            .collect::<Vec<_>>();
+
+        let embed = confirmation(Lang::DEFAULT, Preset::Large, &changes);
+
+        assert!(embed
+            .description
+            .unwrap()
+            .ends_with(&Lang::DEFAULT.preset_more(10)));
+        confirmation(Lang::DEFAULT, Preset::Small, &changes[..1]);
+    }
+
+    #[test]
+    fn test_responses() {
+        unchanged(Lang::DEFAULT, Preset::HighRisk);
+        applied(Lang::DEFAULT, Preset::HighRisk, 5);
+    }
+}
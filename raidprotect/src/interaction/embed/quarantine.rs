@@ -0,0 +1,255 @@
+//! Embed for the quarantine and release commands.
+
+use twilight_mention::Mention;
+use twilight_model::id::{marker::RoleMarker, Id};
+
+use super::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS};
+use crate::{interaction::response::InteractionResponse, translations::Lang, util::TextProcessExt};
+
+/// User is not a server member.
+pub fn not_member(user: String, lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.quarantine_not_member(user.remove_markdown().max_len(30)))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// No quarantine role is configured.
+pub fn not_configured(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.quarantine_not_configured_title())
+        .description(lang.quarantine_not_configured_description())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Member is already quarantined.
+pub fn already_quarantined(user: String, lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.quarantine_already(user.remove_markdown().max_len(30)))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Member is not quarantined.
+pub fn not_quarantined(user: String, lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.release_not_quarantined(user.remove_markdown().max_len(30)))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Bot is missing the permission to manage roles.
+pub fn bot_missing_permission(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.quarantine_bot_missing_permission_title())
+        .description(lang.bot_missing_permission())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// User cannot quarantine due to the role hierarchy
+pub fn user_hierarchy(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.quarantine_missing_permission_title())
+        .description(lang.hierarchy_user())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Bot cannot quarantine due to the role hierarchy
+pub fn bot_hierarchy(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.quarantine_bot_missing_permission_title())
+        .description(lang.hierarchy_bot())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Member is the guild owner, and thus cannot be quarantined
+pub fn member_owner(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.quarantine_missing_permission_title())
+        .description(lang.hierarchy_owner())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// The guild requires a reason for each sanction.
+pub fn missing_reason(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.quarantine_missing_reason())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// The quarantine or release could not be applied.
+pub fn error(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.quarantine_error_title())
+        .description(lang.quarantine_error_description())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// The member has been quarantined.
+pub fn success(user: String, lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .description(lang.quarantine_success(user.remove_markdown().max_len(30)))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// The member has been released.
+///
+/// The number of roles that could not be restored is shown if any.
+pub fn released(user: String, skipped: usize, lang: Lang) -> InteractionResponse {
+    let mut description = lang.release_success(user.remove_markdown().max_len(30));
+
+    if skipped > 0 {
+        description.push_str("\n\n");
+        description.push_str(&lang.release_skipped_roles(skipped));
+    }
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .description(description)
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// The quarantine role has been configured.
+pub fn role_configured(role: Id<RoleMarker>, lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_quarantine_role_enabled(role.mention()))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// The quarantine role is above the bot's highest role.
+pub fn role_hierarchy(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.config_quarantine_role_hierarchy())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// The quarantine role has been removed.
+pub fn role_disabled(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_quarantine_role_disabled())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_not_member() {
+        not_member("test".to_owned(), Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_not_configured() {
+        not_configured(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_already_quarantined() {
+        already_quarantined("test".to_owned(), Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_not_quarantined() {
+        not_quarantined("test".to_owned(), Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_bot_missing_permission() {
+        bot_missing_permission(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_user_hierarchy() {
+        user_hierarchy(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_bot_hierarchy() {
+        bot_hierarchy(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_member_owner() {
+        member_owner(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_missing_reason() {
+        missing_reason(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_error() {
+        error(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_success() {
+        success("test".to_owned(), Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_released() {
+        released("test".to_owned(), 0, Lang::DEFAULT);
+        released("test".to_owned(), 2, Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_role_configured() {
+        role_configured(Id::new(1), Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_role_hierarchy() {
+        role_hierarchy(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_role_disabled() {
+        role_disabled(Lang::DEFAULT);
+    }
+}
@@ -0,0 +1,45 @@
+//! Embeds for the buttons of anti-raid alerts.
+
+use twilight_mention::Mention;
+use twilight_model::{
+    channel::embed::Embed,
+    id::{marker::UserMarker, Id},
+};
+
+use super::{EmbedBuilder, COLOR_SUCCESS, COLOR_TRANSPARENT};
+use crate::translations::Lang;
+
+/// Raid mode enabled from an alert.
+///
+/// This embed is added to the alert message.
+pub fn locked_down(lang: Lang, user: Id<UserMarker>) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .description(lang.raid_alert_locked_down(user.mention()))
+        .build()
+}
+
+/// Alert dismissed by a moderator.
+///
+/// This embed is added to the alert message.
+pub fn dismissed(lang: Lang, user: Id<UserMarker>) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .description(lang.raid_alert_dismissed(user.mention()))
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locked_down() {
+        locked_down(Lang::DEFAULT, Id::new(1));
+    }
+
+    #[test]
+    fn test_dismissed() {
+        dismissed(Lang::DEFAULT, Id::new(1));
+    }
+}
@@ -0,0 +1,192 @@
+//! Embeds for the sanction reason presets.
+
+use raidprotect_model::database::model::ModerationConfig;
+use twilight_mention::Mention;
+use twilight_model::{
+    channel::embed::Embed,
+    id::{marker::UserMarker, Id},
+};
+
+use super::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS, COLOR_TRANSPARENT};
+use crate::{
+    interaction::{command::moderation::MAX_TEMPLATE_VARIABLES, response::InteractionResponse},
+    translations::Lang,
+};
+
+/// Reason preset added.
+pub fn added(lang: Lang, reason: &str) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_reasons_added(reason))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Reason preset removed.
+pub fn removed(lang: Lang, reason: &str) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_reasons_removed(reason))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Number of reason presets displayed on each page of the list.
+const PRESETS_PER_PAGE: usize = 10;
+
+/// Pages of the reason presets list (see [`Paginator`]).
+///
+/// [`Paginator`]: crate::interaction::component::Paginator
+pub fn list(lang: Lang, presets: &[String]) -> Vec<Embed> {
+    if presets.is_empty() {
+        return vec![EmbedBuilder::new()
+            .color(COLOR_RED)
+            .title(lang.config_reasons_empty_title())
+            .description(lang.config_reasons_empty_description())
+            .build()];
+    }
+
+    presets
+        .chunks(PRESETS_PER_PAGE)
+        .map(|chunk| {
+            let reasons = chunk
+                .iter()
+                .map(|preset| format!("• {preset}"))
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            EmbedBuilder::new()
+                .color(COLOR_TRANSPARENT)
+                .title(lang.config_reasons_list_title())
+                .description(lang.config_reasons_list(reasons))
+                .build()
+        })
+        .collect()
+}
+
+/// Reason preset already in the list.
+pub fn already_added(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.config_reasons_already_added())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Reason preset not in the list.
+pub fn not_found(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.config_reasons_not_found())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Too many reason presets.
+pub fn too_many(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.config_reasons_too_many(ModerationConfig::MAX_REASON_PRESETS_LEN))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Reason preset too long.
+pub fn too_long(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.config_reasons_too_long(ModerationConfig::MAX_REASON_PRESET_LENGTH))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Reason template with too many variables.
+pub fn too_many_variables(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.config_reasons_too_many_variables(MAX_TEMPLATE_VARIABLES))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Reason template with unfilled variables.
+pub fn unfilled(lang: Lang, variables: &[String]) -> InteractionResponse {
+    let variables = variables
+        .iter()
+        .map(|name| format!("`{{{name}}}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.sanction_template_unfilled(variables))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Reason selection shown before the reason modal.
+pub fn select(lang: Lang, user: Id<UserMarker>) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .title(lang.sanction_reason_select_title())
+        .description(lang.sanction_reason_select_description(user.mention()))
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_added_removed() {
+        added(Lang::DEFAULT, "Spam");
+        removed(Lang::DEFAULT, "Spam");
+    }
+
+    #[test]
+    fn test_list() {
+        let presets = (0..25).map(|n| n.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(list(Lang::DEFAULT, &[]).len(), 1);
+        assert_eq!(list(Lang::DEFAULT, &presets[..2]).len(), 1);
+        assert_eq!(list(Lang::DEFAULT, &presets).len(), 3);
+    }
+
+    #[test]
+    fn test_errors() {
+        already_added(Lang::DEFAULT);
+        not_found(Lang::DEFAULT);
+        too_many(Lang::DEFAULT);
+        too_long(Lang::DEFAULT);
+        too_many_variables(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_unfilled() {
+        let response = unfilled(Lang::DEFAULT, &["rule".to_owned(), "count".to_owned()]);
+
+        match response {
+            InteractionResponse::EphemeralEmbed(embed) => {
+                let description = embed.description.unwrap();
+
+                assert!(description.contains("`{rule}`, `{count}`"));
+            }
+            _ => panic!("expected ephemeral embed"),
+        }
+    }
+
+    #[test]
+    fn test_select() {
+        select(Lang::DEFAULT, Id::new(1));
+    }
+}
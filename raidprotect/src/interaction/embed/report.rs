@@ -0,0 +1,250 @@
+//! Embeds for the message reports.
+
+use raidprotect_model::database::model::Report;
+use twilight_mention::Mention;
+use twilight_model::{
+    channel::embed::Embed,
+    id::{marker::UserMarker, Id},
+};
+use twilight_util::builder::embed::{EmbedFieldBuilder, EmbedFooterBuilder};
+
+use super::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS, COLOR_TRANSPARENT};
+use crate::{
+    feature::report::{ReportOutcome, MAX_REPORTS},
+    interaction::response::InteractionResponse,
+    translations::Lang,
+};
+
+/// Alert sent in the reports channel.
+///
+/// The reported message is quoted, with its author, a link to the message,
+/// the number of reports and their reasons. The reporters are not shown.
+pub fn alert(lang: Lang, report: &Report, deleted: bool) -> Embed {
+    let content = match report.content.is_empty() {
+        true => lang.report_alert_no_content().to_owned(),
+        false => report
+            .content
+            .lines()
+            .map(|line| format!("> {line}"))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+
+    let author = &report.author;
+    let link = format!(
+        "https://discord.com/channels/{}/{}/{}",
+        report.guild_id, report.channel_id, report.message_id
+    );
+    let reasons = report
+        .reporters
+        .iter()
+        .filter_map(|reporter| reporter.reason.as_deref())
+        .map(|reason| format!("• {reason}"))
+        .collect::<Vec<_>>();
+
+    let mut embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.report_alert_title())
+        .description(content)
+        .field(
+            EmbedFieldBuilder::new(
+                lang.report_alert_author(),
+                format!(
+                    "{}#{:04} ({})",
+                    author.name,
+                    author.discriminator,
+                    author.id.mention()
+                ),
+            )
+            .inline(),
+        )
+        .field(
+            EmbedFieldBuilder::new(
+                lang.report_alert_channel(),
+                lang.report_alert_jump(report.channel_id.mention(), link),
+            )
+            .inline(),
+        )
+        .field(
+            EmbedFieldBuilder::new(
+                lang.report_alert_count(),
+                report.reporters.len().to_string(),
+            )
+            .inline(),
+        );
+
+    if !reasons.is_empty() {
+        embed = embed.field(EmbedFieldBuilder::new(
+            lang.report_alert_reasons(),
+            reasons.join("\n"),
+        ));
+    }
+
+    if deleted {
+        embed = embed.footer(EmbedFooterBuilder::new(lang.report_alert_deleted()));
+    }
+
+    embed.build()
+}
+
+/// Report resolved by a moderator.
+///
+/// This embed is added to the alert message.
+pub fn resolved(lang: Lang, moderator: Id<UserMarker>) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .description(lang.report_resolved(moderator.mention()))
+        .build()
+}
+
+/// Reported message deleted by a moderator.
+pub fn message_deleted(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .description(lang.report_message_deleted())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Report submitted by a member.
+pub fn submitted(lang: Lang, outcome: ReportOutcome) -> InteractionResponse {
+    let description = match outcome {
+        ReportOutcome::Created | ReportOutcome::Merged => lang.report_submitted(),
+        ReportOutcome::AlreadyReported => lang.report_already_reported(),
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .title(lang.report_submitted_title())
+        .description(description)
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// No reports channel configured in the guild.
+pub fn not_configured(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.report_not_configured())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Member reporting their own message.
+pub fn own_message(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.report_own_message())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Member that sent too many reports.
+pub fn rate_limited(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.report_rate_limited(MAX_REPORTS))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Report not found in the database.
+pub fn not_found(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.report_not_found())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+#[cfg(test)]
+mod tests {
+    use raidprotect_model::database::model::{ModlogUser, Reporter};
+    use time::OffsetDateTime;
+
+    use super::*;
+
+    fn report() -> Report {
+        Report {
+            id: None,
+            guild_id: Id::new(1),
+            channel_id: Id::new(2),
+            message_id: Id::new(3),
+            author: ModlogUser {
+                id: Id::new(4),
+                name: "username".to_owned(),
+                discriminator: 1,
+                avatar: None,
+            },
+            content: "first line\nsecond line".to_owned(),
+            reporters: vec![
+                Reporter {
+                    user_id: Id::new(5),
+                    reason: Some("spam".to_owned()),
+                    date: OffsetDateTime::UNIX_EPOCH,
+                },
+                Reporter {
+                    user_id: Id::new(6),
+                    reason: None,
+                    date: OffsetDateTime::UNIX_EPOCH,
+                },
+            ],
+            alert_id: None,
+            created_at: OffsetDateTime::UNIX_EPOCH,
+            resolved_by: None,
+        }
+    }
+
+    #[test]
+    fn test_alert() {
+        let embed = alert(Lang::DEFAULT, &report(), false);
+
+        assert_eq!(
+            embed.description.as_deref(),
+            Some("> first line\n> second line")
+        );
+        assert!(embed.fields.iter().any(|field| field.value == "2"));
+        assert!(embed.fields.iter().any(|field| field.value == "• spam"));
+        assert!(embed.footer.is_none());
+
+        // Reporters are never shown in the alert.
+        let rendered = format!("{embed:?}");
+        assert!(!rendered.contains("<@5>"));
+        assert!(!rendered.contains("<@6>"));
+    }
+
+    #[test]
+    fn test_alert_deleted() {
+        let mut report = report();
+        report.content = String::new();
+
+        let embed = alert(Lang::DEFAULT, &report, true);
+        assert!(embed.footer.is_some());
+    }
+
+    #[test]
+    fn test_resolved() {
+        resolved(Lang::DEFAULT, Id::new(1));
+    }
+
+    #[test]
+    fn test_submitted() {
+        submitted(Lang::DEFAULT, ReportOutcome::Created);
+        submitted(Lang::DEFAULT, ReportOutcome::AlreadyReported);
+    }
+
+    #[test]
+    fn test_errors() {
+        not_configured(Lang::DEFAULT);
+        own_message(Lang::DEFAULT);
+        rate_limited(Lang::DEFAULT);
+        not_found(Lang::DEFAULT);
+        message_deleted(Lang::DEFAULT);
+    }
+}
@@ -0,0 +1,180 @@
+//! Embeds for the restore command.
+
+use raidprotect_model::cache::model::interaction::RestoreKind;
+use time::OffsetDateTime;
+use twilight_model::channel::embed::Embed;
+
+use super::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS, COLOR_TRANSPARENT};
+use crate::{
+    feature::restore::RestoredItem, interaction::response::InteractionResponse, translations::Lang,
+    util::time::relative,
+};
+
+/// Maximum number of items listed in the confirmation.
+const MAX_LISTED: usize = 30;
+
+/// Error while preparing a restore.
+pub fn error(lang: Lang, description: impl Into<String>) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.restore_error_title())
+        .description(description)
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Confirmation before restoring the deleted items.
+///
+/// `missing` are the names of the deleted items, and `added` the number of
+/// items created since the snapshot.
+pub fn confirmation(
+    lang: Lang,
+    kind: RestoreKind,
+    date: OffsetDateTime,
+    missing: &[String],
+    added: usize,
+) -> Embed {
+    let date = relative(date);
+    let mut description = match kind {
+        RestoreKind::Channels => lang.restore_confirm_channels(missing.len(), date),
+        RestoreKind::Roles => lang.restore_confirm_roles(missing.len(), date),
+    };
+
+    description.push_str("\n\n");
+    description.push_str(&list(missing.iter().map(|name| format!("- {name}"))));
+
+    if added > 0 {
+        description.push_str("\n\n");
+        description.push_str(&lang.restore_added(added));
+    }
+
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.restore_confirm_title())
+        .description(description)
+        .build()
+}
+
+/// Restore started.
+pub fn started(lang: Lang) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .description(lang.restore_started())
+        .build()
+}
+
+/// Progress of a running restore.
+pub fn progress(lang: Lang, done: usize, total: usize) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .description(lang.restore_progress(done, total))
+        .build()
+}
+
+/// Summary of a completed restore, listing the result of each item.
+pub fn summary(lang: Lang, kind: RestoreKind, items: &[RestoredItem]) -> Embed {
+    let restored = items.iter().filter(|item| item.restored).count();
+    let mut description = match kind {
+        RestoreKind::Channels => lang.restore_summary_channels(restored, items.len()),
+        RestoreKind::Roles => lang.restore_summary_roles(restored, items.len()),
+    };
+
+    description.push_str("\n\n");
+    description.push_str(&list(items.iter().map(|item| match item.restored {
+        true => format!("✅ {}", item.name),
+        false => format!("❌ {}", item.name),
+    })));
+
+    let color = match restored == items.len() {
+        true => COLOR_SUCCESS,
+        false => COLOR_RED,
+    };
+
+    EmbedBuilder::new()
+        .color(color)
+        .description(description)
+        .build()
+}
+
+/// Restore failed before any item was processed.
+pub fn failed(lang: Lang) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.restore_error_title())
+        .description(lang.restore_failed())
+        .build()
+}
+
+/// List the first [`MAX_LISTED`] lines.
+fn list(lines: impl ExactSizeIterator<Item = String>) -> String {
+    let len = lines.len();
+    let mut listed = lines.take(MAX_LISTED).collect::<Vec<_>>();
+
+    if len > MAX_LISTED {
+        listed.push("…".to_owned());
+    }
+
+    listed.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error() {
+        error(Lang::DEFAULT, Lang::DEFAULT.restore_no_snapshot());
+    }
+
+    #[test]
+    fn test_confirmation() {
+        let missing = vec!["general".to_owned(); 40];
+
+        let embed = confirmation(
+            Lang::DEFAULT,
+            RestoreKind::Channels,
+            OffsetDateTime::UNIX_EPOCH,
+            &missing,
+            2,
+        );
+
+        assert!(embed
+            .description
+            .unwrap()
+            .ends_with(&Lang::DEFAULT.restore_added(2)));
+        confirmation(
+            Lang::DEFAULT,
+            RestoreKind::Roles,
+            OffsetDateTime::UNIX_EPOCH,
+            &missing[..1],
+            0,
+        );
+    }
+
+    #[test]
+    fn test_progress() {
+        started(Lang::DEFAULT);
+        progress(Lang::DEFAULT, 5, 10);
+    }
+
+    #[test]
+    fn test_summary() {
+        let items = vec![
+            RestoredItem {
+                name: "general".to_owned(),
+                restored: true,
+            },
+            RestoredItem {
+                name: "rules".to_owned(),
+                restored: false,
+            },
+        ];
+
+        let embed = summary(Lang::DEFAULT, RestoreKind::Channels, &items);
+
+        assert_eq!(embed.color, Some(COLOR_RED));
+        summary(Lang::DEFAULT, RestoreKind::Roles, &items[..1]);
+        failed(Lang::DEFAULT);
+    }
+}
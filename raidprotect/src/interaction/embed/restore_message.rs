@@ -0,0 +1,139 @@
+//! Embeds for the restore message command.
+
+use raidprotect_model::cache::model::message::CachedMessage;
+use time::OffsetDateTime;
+use twilight_mention::Mention;
+use twilight_model::{
+    channel::embed::Embed,
+    id::{marker::ChannelMarker, Id},
+};
+use twilight_util::builder::embed::{EmbedFieldBuilder, EmbedFooterBuilder};
+
+use super::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS, COLOR_TRANSPARENT};
+use crate::{interaction::response::InteractionResponse, translations::Lang, util::time::relative};
+
+/// Restored message.
+///
+/// The message content is shown as description, with its author and
+/// attachments. The moderator that restored the message is shown in the
+/// footer.
+pub fn restored(lang: Lang, message: &CachedMessage, moderator: &str) -> Embed {
+    let sent_at = OffsetDateTime::from_unix_timestamp(message.timestamp.as_secs())
+        .unwrap_or(OffsetDateTime::UNIX_EPOCH);
+
+    let mut embed = EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .title(lang.restore_message_title())
+        .description(message.content.clone())
+        .field(
+            EmbedFieldBuilder::new(
+                lang.restore_message_author(),
+                format!("{} ({})", message.author_id.mention(), message.author_id),
+            )
+            .inline(),
+        )
+        .field(EmbedFieldBuilder::new(lang.restore_message_sent(), relative(sent_at)).inline());
+
+    if !message.attachments.is_empty() {
+        let attachments = message
+            .attachments
+            .iter()
+            .map(|attachment| format!("`{}`", attachment.filename))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        embed = embed.field(EmbedFieldBuilder::new(
+            lang.restore_message_attachments(),
+            attachments,
+        ));
+    }
+
+    embed
+        .footer(EmbedFooterBuilder::new(
+            lang.restore_message_footer(moderator),
+        ))
+        .build()
+}
+
+/// Message restored successfully.
+pub fn success(lang: Lang, channel: Id<ChannelMarker>) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .description(lang.restore_message_success(channel.mention()))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Invalid message id or link.
+pub fn invalid_message(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.restore_message_invalid())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Message not found in the cache.
+pub fn not_available(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.restore_message_not_available_title())
+        .description(lang.restore_message_not_available())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// The messages logs are disabled.
+pub fn logs_off(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.restore_message_logs_off())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::util::Timestamp;
+
+    use super::*;
+
+    #[test]
+    fn test_restored() {
+        let message = CachedMessage {
+            id: Id::new(1),
+            author_id: Id::new(2),
+            channel_id: Id::new(3),
+            content: "hello".to_owned(),
+            timestamp: Timestamp::from_secs(1_600_000_000).unwrap(),
+            words: vec!["hello".to_owned()],
+            attachments: Vec::new(),
+            links: Vec::new(),
+            mention_everyone: false,
+            mention_users: Vec::new(),
+            mention_roles: Vec::new(),
+        };
+
+        restored(Lang::DEFAULT, &message, "moderator#0001");
+    }
+
+    #[test]
+    fn test_success() {
+        success(Lang::DEFAULT, Id::new(1));
+    }
+
+    #[test]
+    fn test_invalid_message() {
+        invalid_message(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_not_available() {
+        not_available(Lang::DEFAULT);
+        logs_off(Lang::DEFAULT);
+    }
+}
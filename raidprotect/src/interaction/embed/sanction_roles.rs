@@ -0,0 +1,196 @@
+//! Embeds for the sanctions allowed for each moderator role.
+
+use raidprotect_model::database::model::{DmStatus, ModerationConfig, ModlogType, SanctionRole};
+use twilight_mention::Mention;
+use twilight_model::id::{marker::RoleMarker, Id};
+
+use super::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS, COLOR_TRANSPARENT};
+use crate::{interaction::response::InteractionResponse, translations::Lang};
+
+/// Sanctions allowed for a role updated.
+pub fn set(lang: Lang, role: &SanctionRole) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(
+            lang.config_sanction_roles_set(role.role_id.mention(), role_sanctions(lang, role)),
+        )
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Sanctions allowed for a role removed.
+pub fn removed(lang: Lang, role_id: Id<RoleMarker>) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_sanction_roles_removed(role_id.mention()))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// List of the sanctions allowed for each role.
+pub fn list(lang: Lang, roles: &[SanctionRole]) -> InteractionResponse {
+    if roles.is_empty() {
+        let embed = EmbedBuilder::new()
+            .color(COLOR_TRANSPARENT)
+            .title(lang.config_sanction_roles_empty_title())
+            .description(lang.config_sanction_roles_empty_description())
+            .build();
+
+        return InteractionResponse::EphemeralEmbed(embed);
+    }
+
+    let roles = roles
+        .iter()
+        .map(|role| {
+            format!(
+                "• {}: {}",
+                role.role_id.mention(),
+                role_sanctions(lang, role)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .title(lang.config_sanction_roles_list_title())
+        .description(lang.config_sanction_roles_list(roles))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// No sanction selected.
+pub fn none_selected(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.config_sanction_roles_none_selected())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Role without allowed sanctions.
+pub fn not_found(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.config_sanction_roles_not_found())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Too many roles with allowed sanctions.
+pub fn too_many(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.config_sanction_roles_too_many(ModerationConfig::MAX_SANCTION_ROLES_LEN))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Sanction not allowed by the roles of the moderator.
+pub fn not_allowed(lang: Lang, kind: ModlogType) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.sanction_not_allowed_title())
+        .description(lang.sanction_not_allowed_description(sanction_kind(lang, kind)))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Shadow sanctions not allowed by the roles of the moderator.
+pub fn shadow_not_allowed(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.sanction_not_allowed_title())
+        .description(lang.sanction_shadow_not_allowed_description())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Localized name of a sanction.
+pub fn sanction_kind(lang: Lang, kind: ModlogType) -> &'static str {
+    match kind {
+        ModlogType::Ban => lang.sanction_kind_ban(),
+        ModlogType::Kick => lang.sanction_kind_kick(),
+        ModlogType::Mute => lang.sanction_kind_mute(),
+        ModlogType::Unmute => lang.sanction_kind_unmute(),
+        ModlogType::Quarantine => lang.sanction_kind_quarantine(),
+        ModlogType::Release => lang.sanction_kind_release(),
+    }
+}
+
+/// Localized delivery status of the private message of a sanction.
+pub fn dm_status(lang: Lang, status: DmStatus) -> &'static str {
+    match status {
+        DmStatus::Delivered => lang.dm_status_delivered(),
+        DmStatus::Closed => lang.dm_status_closed(),
+        DmStatus::Failed => lang.dm_status_failed(),
+        DmStatus::Skipped => lang.dm_status_skipped(),
+    }
+}
+
+/// Comma-separated list of localized sanction names.
+fn sanction_kinds(lang: Lang, kinds: &[ModlogType]) -> String {
+    kinds
+        .iter()
+        .map(|kind| sanction_kind(lang, *kind))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Localized sanctions allowed for a role, including shadow sanctions.
+fn role_sanctions(lang: Lang, role: &SanctionRole) -> String {
+    let sanctions = sanction_kinds(lang, &role.sanctions);
+
+    match role.shadow {
+        true => lang.config_sanction_roles_shadow(sanctions),
+        false => sanctions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list() {
+        let mut role = SanctionRole {
+            role_id: Id::new(1),
+            sanctions: vec![ModlogType::Mute, ModlogType::Unmute],
+            shadow: false,
+        };
+
+        list(Lang::DEFAULT, &[]);
+        list(Lang::DEFAULT, &[role.clone()]);
+        role.shadow = true;
+        list(Lang::DEFAULT, &[role.clone()]);
+        set(Lang::DEFAULT, &role);
+        removed(Lang::DEFAULT, role.role_id);
+    }
+
+    #[test]
+    fn test_sanction_kinds() {
+        let kinds = [ModlogType::Ban, ModlogType::Kick];
+
+        assert_eq!(sanction_kinds(Lang::En, &kinds), "Ban, Kick");
+        assert_eq!(sanction_kinds(Lang::En, &[]), "");
+    }
+
+    #[test]
+    fn test_errors() {
+        none_selected(Lang::DEFAULT);
+        not_found(Lang::DEFAULT);
+        too_many(Lang::DEFAULT);
+        not_allowed(Lang::DEFAULT, ModlogType::Ban);
+        shadow_not_allowed(Lang::DEFAULT);
+    }
+}
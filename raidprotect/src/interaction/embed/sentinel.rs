@@ -0,0 +1,46 @@
+//! Embeds for the "strip role" button of sentinel alerts.
+
+use twilight_mention::Mention;
+use twilight_model::{
+    channel::embed::Embed,
+    id::{marker::UserMarker, Id},
+};
+
+use super::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS};
+use crate::{interaction::response::InteractionResponse, translations::Lang};
+
+/// Role stripped by a moderator.
+///
+/// This embed is added to the alert message.
+pub fn stripped(lang: Lang, user: Id<UserMarker>) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .description(lang.sentinel_stripped(user.mention()))
+        .build()
+}
+
+/// The role could not be stripped.
+pub fn strip_failed(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.sentinel_strip_failed_title())
+        .description(lang.sentinel_strip_failed())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stripped() {
+        stripped(Lang::DEFAULT, Id::new(1));
+    }
+
+    #[test]
+    fn test_strip_failed() {
+        strip_failed(Lang::DEFAULT);
+    }
+}
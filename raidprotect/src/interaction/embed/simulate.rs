@@ -0,0 +1,119 @@
+//! Embeds for the simulate command.
+
+use twilight_util::builder::embed::{EmbedFieldBuilder, EmbedFooterBuilder};
+
+use super::{features::feature_name, EmbedBuilder, COLOR_RED, COLOR_SUCCESS, COLOR_TRANSPARENT};
+use crate::{
+    feature::scenario::{ScenarioAction, ScenarioOutcome, Threshold},
+    interaction::response::InteractionResponse,
+    translations::Lang,
+};
+
+/// Result of a simulated scenario.
+pub fn result(lang: Lang, scenario: String, outcome: ScenarioOutcome) -> InteractionResponse {
+    let mut embed = EmbedBuilder::new()
+        .title(lang.simulate_result_title())
+        .field(EmbedFieldBuilder::new(lang.simulate_scenario(), scenario));
+
+    embed = match outcome {
+        ScenarioOutcome::Disabled => embed
+            .color(COLOR_TRANSPARENT)
+            .description(lang.simulate_disabled()),
+        ScenarioOutcome::FeatureDisabled(feature) => embed
+            .color(COLOR_TRANSPARENT)
+            .description(lang.simulate_feature_disabled(feature_name(feature, lang))),
+        ScenarioOutcome::NotTriggered(threshold) => {
+            let threshold = threshold_description(lang, threshold);
+
+            embed
+                .color(COLOR_SUCCESS)
+                .description(lang.simulate_not_triggered())
+                .field(EmbedFieldBuilder::new(lang.simulate_threshold(), threshold))
+        }
+        ScenarioOutcome::Triggered(threshold, action) => {
+            let threshold = threshold_description(lang, threshold);
+            let action = action_description(lang, action);
+
+            embed
+                .color(COLOR_RED)
+                .description(lang.simulate_triggered())
+                .field(EmbedFieldBuilder::new(lang.simulate_threshold(), threshold))
+                .field(EmbedFieldBuilder::new(lang.simulate_action(), action))
+        }
+    };
+
+    let embed = embed
+        .footer(EmbedFooterBuilder::new(lang.simulate_footer()))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+fn threshold_description(lang: Lang, threshold: Threshold) -> String {
+    match threshold {
+        Threshold::AccountAge {
+            min_age,
+            affected,
+            joins,
+        } => lang.simulate_threshold_account_age(affected, joins, min_age),
+        Threshold::Reactions {
+            max_reactions,
+            interval,
+            peak,
+        } => lang.simulate_threshold_reactions(interval, max_reactions, peak),
+    }
+}
+
+fn action_description(lang: Lang, action: ScenarioAction) -> String {
+    match action {
+        ScenarioAction::Kick => lang.simulate_action_kick().to_owned(),
+        ScenarioAction::HoldForReview => lang.simulate_action_review().to_owned(),
+        ScenarioAction::RemoveReactions { timeout: None } => {
+            lang.simulate_action_remove_reactions().to_owned()
+        }
+        ScenarioAction::RemoveReactions {
+            timeout: Some(timeout),
+        } => lang.simulate_action_remove_reactions_timeout(timeout),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use raidprotect_model::database::model::Feature;
+
+    use super::*;
+
+    #[test]
+    fn test_result() {
+        let threshold = Threshold::Reactions {
+            max_reactions: 10,
+            interval: 5,
+            peak: 11,
+        };
+
+        result(Lang::DEFAULT, String::new(), ScenarioOutcome::Disabled);
+        result(
+            Lang::DEFAULT,
+            String::new(),
+            ScenarioOutcome::FeatureDisabled(Feature::SpamFilter),
+        );
+        result(
+            Lang::DEFAULT,
+            String::new(),
+            ScenarioOutcome::NotTriggered(threshold),
+        );
+        result(
+            Lang::DEFAULT,
+            String::new(),
+            ScenarioOutcome::Triggered(threshold, ScenarioAction::Kick),
+        );
+        result(
+            Lang::DEFAULT,
+            String::new(),
+            ScenarioOutcome::Triggered(
+                threshold,
+                ScenarioAction::RemoveReactions { timeout: Some(60) },
+            ),
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! Embeds for the guild statistics.
+
+use raidprotect_model::database::model::{FilterCounts, GuildStats, ModlogCounts};
+use twilight_util::builder::embed::EmbedFieldBuilder;
+
+use super::{EmbedBuilder, COLOR_TRANSPARENT};
+use crate::{interaction::response::InteractionResponse, translations::Lang};
+
+/// Number of days displayed in the activity sparkline.
+const SPARKLINE_DAYS: i64 = 14;
+
+/// Characters used to draw the sparkline, from lowest to highest.
+const SPARKLINE_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Statistics of a guild.
+///
+/// `today` is the current day since the Unix epoch (see [`epoch_day`]).
+///
+/// [`epoch_day`]: raidprotect_model::database::model::epoch_day
+pub fn stats(lang: Lang, stats: &GuildStats, today: i64) -> InteractionResponse {
+    let totals = &stats.totals;
+    let week = stats.last_days(today, 7).total();
+    let month = stats.last_days(today, 30).total();
+
+    let mut embed = EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .title(lang.stats_title())
+        .description(lang.stats_trend(activity(stats, today), month, week))
+        .field(field(
+            lang.stats_sanctions(),
+            sanctions(lang, totals.sanctions),
+        ))
+        .field(field(lang.stats_filters(), filters(lang, totals.filters)))
+        .field(field(lang.stats_raids(), totals.raids))
+        .field(field(
+            lang.stats_messages_deleted(),
+            totals.messages_deleted,
+        ));
+
+    // False positives are only shown once moderators started to report them.
+    if totals.false_positives.total() > 0 {
+        embed = embed.field(field(
+            lang.stats_false_positives(),
+            filters(lang, totals.false_positives),
+        ));
+    }
+
+    InteractionResponse::EphemeralEmbed(embed.build())
+}
+
+/// Inline field of the statistics embed.
+fn field(name: &str, value: impl ToString) -> EmbedFieldBuilder {
+    EmbedFieldBuilder::new(name, value.to_string()).inline()
+}
+
+/// Number of sanctions of each type.
+pub fn sanctions(lang: Lang, counts: ModlogCounts) -> String {
+    lang.stats_sanctions_value(
+        counts.ban,
+        counts.kick,
+        counts.mute,
+        counts.quarantine,
+        counts.release,
+        counts.unmute,
+    )
+}
+
+/// Number of detections of each filter.
+fn filters(lang: Lang, filters: FilterCounts) -> String {
+    lang.stats_filters_value(
+        filters.first_message,
+        filters.link,
+        filters.name,
+        filters.pattern,
+        filters.reaction_spam,
+        filters.voice_hop,
+    )
+}
+
+/// Sparkline of the number of events during the last days.
+fn activity(stats: &GuildStats, today: i64) -> String {
+    let values = (today - SPARKLINE_DAYS + 1..=today)
+        .map(|day| {
+            stats
+                .days
+                .iter()
+                .find(|bucket| bucket.day == day)
+                .map(|bucket| bucket.counters.total())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+
+    sparkline(&values)
+}
+
+/// Draw a sparkline from a list of values.
+fn sparkline(values: &[u64]) -> String {
+    let max = values.iter().copied().max().unwrap_or_default();
+    let last = (SPARKLINE_BARS.len() - 1) as u64;
+
+    values
+        .iter()
+        .map(|value| match max {
+            0 => SPARKLINE_BARS[0],
+            max => SPARKLINE_BARS[(value * last / max) as usize],
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use raidprotect_model::database::model::{
+        DailyStats, ModlogType, StatsCounters, StatsEvent, StatsFilter,
+    };
+    use twilight_model::id::Id;
+
+    use super::*;
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[0, 0, 0]), "▁▁▁");
+        assert_eq!(sparkline(&[0, 7, 14]), "▁▄█");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut stats = GuildStats::new(Id::new(1));
+        let mut counters = StatsCounters::default();
+        counters.add(StatsEvent::Sanction(ModlogType::Ban));
+
+        stats.totals = counters;
+        stats.days.push(DailyStats { day: 99, counters });
+
+        assert_eq!(activity(&stats, 100).chars().count(), 14);
+        assert!(activity(&stats, 100).ends_with("█▁"));
+        super::stats(Lang::DEFAULT, &stats, 100);
+    }
+
+    #[test]
+    fn test_stats_false_positives() {
+        let mut stats = GuildStats::new(Id::new(1));
+        let fields = |stats: &GuildStats| match super::stats(Lang::DEFAULT, stats, 100) {
+            InteractionResponse::EphemeralEmbed(embed) => embed.fields.len(),
+            _ => panic!("expected embed response"),
+        };
+
+        assert_eq!(fields(&stats), 4);
+
+        stats
+            .totals
+            .add(StatsEvent::FalsePositive(StatsFilter::Link));
+        assert_eq!(fields(&stats), 5);
+    }
+}
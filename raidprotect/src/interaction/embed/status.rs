@@ -0,0 +1,49 @@
+//! Embeds for the status command.
+
+use raidprotect_model::cache::model::shard::GuildCount;
+use twilight_util::builder::embed::EmbedFieldBuilder;
+
+use super::{EmbedBuilder, COLOR_TRANSPARENT};
+use crate::{interaction::response::InteractionResponse, translations::Lang};
+
+/// Status of the bot.
+///
+/// The guild count is marked as partial if some shards have not reported
+/// their guild count yet.
+pub fn status(lang: Lang, count: GuildCount, maintenance: bool) -> InteractionResponse {
+    let guilds = match count.is_complete() {
+        true => count.guilds.to_string(),
+        false => lang.status_guilds_partial(count.guilds, count.reported, count.shards),
+    };
+
+    let mut embed = EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .title(lang.status_title())
+        .field(EmbedFieldBuilder::new(lang.status_guilds(), guilds).inline())
+        .field(EmbedFieldBuilder::new(lang.status_shards(), count.shards.to_string()).inline());
+
+    if maintenance {
+        embed = embed.description(lang.status_maintenance());
+    }
+
+    InteractionResponse::EphemeralEmbed(embed.build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status() {
+        let mut count = GuildCount {
+            guilds: 1500,
+            reported: 1,
+            shards: 2,
+        };
+
+        status(Lang::DEFAULT, count, false);
+
+        count.reported = 2;
+        status(Lang::DEFAULT, count, true);
+    }
+}
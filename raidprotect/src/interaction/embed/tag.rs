@@ -0,0 +1,204 @@
+//! Embeds for the tags.
+
+use raidprotect_model::database::model::{Tag, TagsConfig};
+use twilight_mention::Mention;
+use twilight_model::channel::embed::Embed;
+
+use super::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS, COLOR_TRANSPARENT};
+use crate::{interaction::response::InteractionResponse, translations::Lang, util::TextProcessExt};
+
+/// Number of tags displayed on each page of the list.
+const TAGS_PER_PAGE: usize = 15;
+
+/// Length of the content preview in the list.
+const PREVIEW_LENGTH: usize = 60;
+
+/// Tag created or updated.
+pub fn set(lang: Lang, name: &str) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .description(lang.tag_set(name.remove_markdown()))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Tag removed.
+pub fn removed(lang: Lang, name: &str) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .description(lang.tag_removed(name.remove_markdown()))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Pages of the tags list (see [`Paginator`]).
+///
+/// [`Paginator`]: crate::interaction::component::Paginator
+pub fn list(lang: Lang, tags: &[Tag]) -> Vec<Embed> {
+    if tags.is_empty() {
+        return vec![EmbedBuilder::new()
+            .color(COLOR_RED)
+            .title(lang.tag_list_title())
+            .description(lang.tag_list_empty())
+            .build()];
+    }
+
+    tags.chunks(TAGS_PER_PAGE)
+        .map(|chunk| {
+            let tags = chunk
+                .iter()
+                .map(|tag| {
+                    let preview = tag.content.replace('\n', " ").max_len(PREVIEW_LENGTH);
+
+                    format!("• **{}** — {}", tag.name.remove_markdown(), preview)
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            EmbedBuilder::new()
+                .color(COLOR_TRANSPARENT)
+                .title(lang.tag_list_title())
+                .description(tags)
+                .build()
+        })
+        .collect()
+}
+
+/// Roles allowed to manage the tags updated.
+pub fn roles_updated(lang: Lang, config: &TagsConfig) -> InteractionResponse {
+    let description = match config.manager_roles.is_empty() {
+        true => lang.tag_roles_none().to_owned(),
+        false => {
+            let roles = config
+                .manager_roles
+                .iter()
+                .map(|role| role.mention().to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            lang.tag_roles_updated(roles)
+        }
+    };
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(description)
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Tag not found.
+pub fn not_found(lang: Lang, name: &str) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.tag_not_found(name.trim().remove_markdown()))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Member not allowed to manage the tags.
+pub fn not_manager(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.tag_not_manager())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Invalid tag name.
+pub fn invalid_name(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.tag_invalid_name(Tag::MAX_NAME_LENGTH))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Tag content too long.
+pub fn too_long(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.tag_too_long(Tag::MAX_CONTENT_LENGTH))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Too many tags.
+pub fn too_many(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.tag_too_many(TagsConfig::MAX_TAGS_LEN))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Too many roles allowed to manage the tags.
+pub fn too_many_roles(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.tag_too_many_roles(TagsConfig::MAX_MANAGER_ROLES_LEN))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::id::Id;
+
+    use super::*;
+
+    #[test]
+    fn test_list() {
+        let tags = (0..20)
+            .map(|n| Tag {
+                name: n.to_string(),
+                content: "a\nb".repeat(100),
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(list(Lang::DEFAULT, &[]).len(), 1);
+        assert_eq!(list(Lang::DEFAULT, &tags).len(), 2);
+
+        let description = list(Lang::DEFAULT, &tags[..1])[0].description.clone();
+        assert!(!description.unwrap().contains('\n'));
+    }
+
+    #[test]
+    fn test_roles_updated() {
+        let config = TagsConfig {
+            tags: Vec::new(),
+            manager_roles: vec![Id::new(1), Id::new(2)],
+        };
+
+        match roles_updated(Lang::DEFAULT, &config) {
+            InteractionResponse::EphemeralEmbed(embed) => {
+                assert!(embed.description.unwrap().contains("<@&1>, <@&2>"));
+            }
+            _ => panic!("expected ephemeral embed"),
+        }
+
+        roles_updated(Lang::DEFAULT, &TagsConfig::default());
+    }
+
+    #[test]
+    fn test_errors() {
+        set(Lang::DEFAULT, "rules");
+        removed(Lang::DEFAULT, "rules");
+        not_found(Lang::DEFAULT, "rules");
+        not_manager(Lang::DEFAULT);
+        invalid_name(Lang::DEFAULT);
+        too_long(Lang::DEFAULT);
+        too_many(Lang::DEFAULT);
+        too_many_roles(Lang::DEFAULT);
+    }
+}
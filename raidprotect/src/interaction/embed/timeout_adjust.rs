@@ -0,0 +1,68 @@
+//! Embeds for the adjustment buttons of the automatic timeouts.
+
+use time::OffsetDateTime;
+use twilight_mention::Mention;
+use twilight_model::{
+    channel::embed::Embed,
+    id::{marker::UserMarker, Id},
+};
+use twilight_util::builder::embed::EmbedFieldBuilder;
+
+use super::{EmbedBuilder, COLOR_RED};
+use crate::{interaction::response::InteractionResponse, translations::Lang, util::time::relative};
+
+/// The adjusted timeout has already ended.
+pub fn ended(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.timeout_adjust_ended())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Show the adjusted timeout in the log of the filter.
+///
+/// The field of a previous adjustment is replaced. The `until` parameter is
+/// [`None`] if the timeout has been lifted.
+pub fn adjusted(
+    lang: Lang,
+    mut embed: Embed,
+    moderator: Id<UserMarker>,
+    until: Option<OffsetDateTime>,
+) -> Embed {
+    let name = lang.timeout_adjust_log_field();
+    let value = match until {
+        Some(until) => lang.timeout_adjust_log_ends(relative(until), moderator.mention()),
+        None => lang.timeout_adjust_log_lifted(moderator.mention()),
+    };
+
+    let field = EmbedFieldBuilder::new(name, value);
+
+    embed.fields.retain(|field| field.name != name);
+    embed.fields.push(field.build());
+    embed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ended() {
+        ended(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_adjusted() {
+        let embed = EmbedBuilder::new().description("link filter").build();
+        let until = OffsetDateTime::from_unix_timestamp(1_628_594_197).unwrap();
+
+        let embed = adjusted(Lang::DEFAULT, embed, Id::new(1), Some(until));
+        assert_eq!(embed.fields.len(), 1);
+
+        let embed = adjusted(Lang::DEFAULT, embed, Id::new(1), None);
+        assert_eq!(embed.fields.len(), 1);
+        assert!(embed.fields[0].value.contains("<@1>"));
+    }
+}
@@ -0,0 +1,222 @@
+//! Embeds for the watchlist.
+
+use raidprotect_model::database::model::WatchlistEntry;
+use time::OffsetDateTime;
+use twilight_mention::Mention;
+use twilight_model::{
+    channel::embed::Embed,
+    id::{marker::UserMarker, Id},
+};
+use twilight_util::builder::embed::EmbedFooterBuilder;
+
+use super::{
+    sanction_roles::sanction_kind, EmbedBuilder, COLOR_RED, COLOR_SUCCESS, COLOR_TRANSPARENT,
+};
+use crate::{
+    feature::watch::{WatchEvent, MAX_WATCHED_USERS},
+    interaction::response::InteractionResponse,
+    translations::Lang,
+    util::{
+        time::{long_date_relative, relative},
+        TextProcessExt,
+    },
+};
+
+/// Number of watched users displayed on each page of the list.
+const WATCHES_PER_PAGE: usize = 10;
+
+/// Maximum length of the note displayed in the list.
+const MAX_NOTE_LENGTH: usize = 100;
+
+/// Maximum length of the link displayed in the notifications.
+const MAX_LINK_LENGTH: usize = 256;
+
+/// Activity of a watched user, sent in the watch channel.
+pub fn notification(
+    lang: Lang,
+    user: Id<UserMarker>,
+    event: &WatchEvent,
+    note: Option<&str>,
+) -> Embed {
+    let description = match event {
+        WatchEvent::Join => lang.watch_join(user.mention()),
+        WatchEvent::Leave => lang.watch_leave(user.mention()),
+        WatchEvent::LinkFilter {
+            channel,
+            link,
+            dry_run,
+        } => {
+            let link = link.max_len(MAX_LINK_LENGTH);
+
+            match dry_run {
+                true => lang.watch_link_filter_dry_run(channel.mention(), link, user.mention()),
+                false => lang.watch_link_filter(channel.mention(), link, user.mention()),
+            }
+        }
+        WatchEvent::Sanction { kind, moderator } => lang.watch_sanction(
+            sanction_kind(lang, *kind),
+            moderator.mention(),
+            user.mention(),
+        ),
+    };
+
+    let mut builder = EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .description(description);
+    if let Some(note) = note {
+        builder = builder.footer(EmbedFooterBuilder::new(lang.watch_note(note)));
+    }
+
+    builder.build()
+}
+
+/// User added to the watchlist.
+pub fn added(lang: Lang, user: Id<UserMarker>, expires_at: OffsetDateTime) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .description(lang.watch_added(long_date_relative(expires_at), user.mention()))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// User removed from the watchlist.
+pub fn removed(lang: Lang, user: Id<UserMarker>) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .description(lang.watch_removed(user.mention()))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// User not in the watchlist.
+pub fn not_watched(lang: Lang, user: Id<UserMarker>) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.watch_not_watched(user.mention()))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Too many watched users.
+pub fn too_many(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.watch_too_many(MAX_WATCHED_USERS))
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Pages of the watchlist (see [`Paginator`]).
+///
+/// [`Paginator`]: crate::interaction::component::Paginator
+pub fn list(lang: Lang, entries: &[WatchlistEntry]) -> Vec<Embed> {
+    if entries.is_empty() {
+        return vec![EmbedBuilder::new()
+            .color(COLOR_RED)
+            .description(lang.watch_list_empty())
+            .build()];
+    }
+
+    entries
+        .chunks(WATCHES_PER_PAGE)
+        .map(|chunk| {
+            let watches = chunk
+                .iter()
+                .map(|entry| list_entry(lang, entry))
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            EmbedBuilder::new()
+                .color(COLOR_TRANSPARENT)
+                .title(lang.watch_list_title())
+                .description(watches)
+                .build()
+        })
+        .collect()
+}
+
+/// Line of a watched user in the list.
+fn list_entry(lang: Lang, entry: &WatchlistEntry) -> String {
+    let moderators = entry
+        .moderators
+        .iter()
+        .map(|moderator| moderator.mention().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let line = lang.watch_list_entry(
+        relative(entry.expires_at),
+        moderators,
+        entry.user_id.mention(),
+    );
+
+    match &entry.note {
+        Some(note) => format!(
+            "• {line}\n> {}",
+            note.remove_markdown().max_len(MAX_NOTE_LENGTH)
+        ),
+        None => format!("• {line}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use raidprotect_model::database::model::ModlogType;
+
+    use super::*;
+
+    fn entry(user: u64) -> WatchlistEntry {
+        WatchlistEntry {
+            guild_id: Id::new(1),
+            user_id: Id::new(user),
+            moderators: vec![Id::new(2), Id::new(3)],
+            note: Some("a".repeat(200)),
+            created_at: OffsetDateTime::from_unix_timestamp(1_628_594_197).unwrap(),
+            expires_at: OffsetDateTime::from_unix_timestamp(1_631_186_197).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_notification() {
+        let events = [
+            WatchEvent::Join,
+            WatchEvent::Leave,
+            WatchEvent::LinkFilter {
+                channel: Id::new(2),
+                link: "discord.gg/raid".to_owned(),
+                dry_run: true,
+            },
+            WatchEvent::Sanction {
+                kind: ModlogType::Mute,
+                moderator: Id::new(3),
+            },
+        ];
+
+        for event in &events {
+            notification(Lang::DEFAULT, Id::new(1), event, None);
+            notification(Lang::DEFAULT, Id::new(1), event, Some("note"));
+        }
+    }
+
+    #[test]
+    fn test_list() {
+        let entries = (10..35).map(entry).collect::<Vec<_>>();
+
+        assert_eq!(list(Lang::DEFAULT, &[]).len(), 1);
+        assert_eq!(list(Lang::DEFAULT, &entries[..2]).len(), 1);
+        assert_eq!(list(Lang::DEFAULT, &entries).len(), 3);
+    }
+
+    #[test]
+    fn test_responses() {
+        let expires_at = OffsetDateTime::from_unix_timestamp(1_631_186_197).unwrap();
+
+        added(Lang::DEFAULT, Id::new(1), expires_at);
+        removed(Lang::DEFAULT, Id::new(1));
+        not_watched(Lang::DEFAULT, Id::new(1));
+        too_many(Lang::DEFAULT);
+    }
+}
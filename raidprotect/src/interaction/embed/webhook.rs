@@ -0,0 +1,90 @@
+//! Embeds for the outbound webhook.
+
+use twilight_model::channel::embed::Embed;
+
+use super::{EmbedBuilder, COLOR_RED, COLOR_SUCCESS};
+use crate::{
+    feature::webhook::SIGNATURE_HEADER, interaction::response::InteractionResponse,
+    translations::Lang,
+};
+
+/// The webhook url is not a valid HTTPS url.
+pub fn invalid_url(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_RED)
+        .description(lang.config_webhook_invalid_url())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// The test payload could not be delivered.
+pub fn ping_failed(lang: Lang) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.config_webhook_ping_failed_title())
+        .description(lang.config_webhook_ping_failed_description())
+        .build()
+}
+
+/// The webhook has been configured.
+///
+/// The signing secret is only shown in this message.
+pub fn enabled(lang: Lang, secret: &str) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_webhook_enabled(SIGNATURE_HEADER, secret))
+        .build()
+}
+
+/// The webhook has been removed.
+pub fn disabled(lang: Lang) -> InteractionResponse {
+    let embed = EmbedBuilder::new()
+        .color(COLOR_SUCCESS)
+        .title(lang.config_updated_title())
+        .description(lang.config_webhook_disabled())
+        .build();
+
+    InteractionResponse::EphemeralEmbed(embed)
+}
+
+/// Notice sent in the logs channel when the webhook is disabled after too
+/// many failed deliveries.
+pub fn disabled_notice(lang: Lang, failures: u32) -> Embed {
+    EmbedBuilder::new()
+        .color(COLOR_RED)
+        .title(lang.webhook_disabled_title())
+        .description(lang.webhook_disabled_description(failures))
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_url() {
+        invalid_url(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_ping_failed() {
+        ping_failed(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_enabled() {
+        enabled(Lang::DEFAULT, "secret");
+    }
+
+    #[test]
+    fn test_disabled() {
+        disabled(Lang::DEFAULT);
+    }
+
+    #[test]
+    fn test_disabled_notice() {
+        disabled_notice(Lang::DEFAULT, 20);
+    }
+}
@@ -0,0 +1,221 @@
+//! Interaction handlers errors.
+//!
+//! Handlers return an [`anyhow::Error`] when they fail. Before responding to
+//! the user, the error is mapped to a [`HandlerError`] with
+//! [`HandlerError::from_error`], and a localized message is sent for known
+//! errors (see [`error_response`]).
+//!
+//! Unknown errors are reported with a generic message that contains the id of
+//! the interaction. This id is also recorded in the `interaction` span, so it
+//! can be used to find the full error in the logs when a user reports it.
+
+use std::{error::Error, fmt};
+
+use raidprotect_model::cache::discord::permission::BotMemberUnknown;
+use tracing::{error, warn};
+use twilight_http::error::ErrorType;
+use twilight_model::id::{marker::InteractionMarker, Id};
+
+use super::{
+    embed,
+    response::{InteractionResponse, TokenExpired},
+};
+use crate::{database::DatabaseUnavailable, translations::Lang};
+
+/// Known errors of interaction handlers.
+///
+/// Handlers may return these errors directly, or they are detected from the
+/// source error (see [`HandlerError::from_error`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandlerError {
+    /// The database is unavailable.
+    DatabaseUnavailable,
+    /// The interaction token has expired.
+    TokenExpired,
+    /// The bot is missing permissions to perform an action.
+    BotMissingPermissions,
+    /// A Discord resource used by the action no longer exists.
+    UnknownResource,
+    /// The bot is rate limited by Discord.
+    RateLimited,
+    /// The bot member is not known yet, role hierarchy cannot be checked.
+    BotMemberUnknown,
+}
+
+impl HandlerError {
+    /// Get the [`HandlerError`] corresponding to an error, if it is known.
+    pub fn from_error(error: &anyhow::Error) -> Option<Self> {
+        if let Some(error) = error.downcast_ref::<HandlerError>() {
+            return Some(*error);
+        }
+
+        if error.downcast_ref::<DatabaseUnavailable>().is_some() {
+            return Some(Self::DatabaseUnavailable);
+        }
+
+        if error.downcast_ref::<TokenExpired>().is_some() {
+            return Some(Self::TokenExpired);
+        }
+
+        if error.downcast_ref::<BotMemberUnknown>().is_some() {
+            return Some(Self::BotMemberUnknown);
+        }
+
+        error
+            .chain()
+            .filter_map(|cause| cause.downcast_ref::<twilight_http::Error>())
+            .find_map(Self::from_http_error)
+    }
+
+    /// Get the [`HandlerError`] corresponding to a Discord API error.
+    fn from_http_error(error: &twilight_http::Error) -> Option<Self> {
+        match error.kind() {
+            ErrorType::Response { status, .. } => Self::from_status(status.get()),
+            _ => None,
+        }
+    }
+
+    /// Get the [`HandlerError`] corresponding to a Discord API status code.
+    fn from_status(status: u16) -> Option<Self> {
+        match status {
+            403 => Some(Self::BotMissingPermissions),
+            404 => Some(Self::UnknownResource),
+            429 => Some(Self::RateLimited),
+            _ => None,
+        }
+    }
+
+    /// Response sent to the user.
+    fn response(self, lang: Lang) -> InteractionResponse {
+        match self {
+            Self::DatabaseUnavailable => embed::error::database_unavailable(lang),
+            Self::TokenExpired => embed::error::expired_interaction(lang),
+            Self::BotMissingPermissions => embed::error::bot_missing_permissions(lang),
+            Self::UnknownResource => embed::error::unknown_resource(lang),
+            Self::RateLimited => embed::error::rate_limited(lang),
+            Self::BotMemberUnknown => embed::error::bot_member_unknown(lang),
+        }
+    }
+}
+
+impl fmt::Display for HandlerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DatabaseUnavailable => f.write_str("database unavailable"),
+            Self::TokenExpired => f.write_str("interaction token expired"),
+            Self::BotMissingPermissions => f.write_str("bot missing permissions"),
+            Self::UnknownResource => f.write_str("unknown discord resource"),
+            Self::RateLimited => f.write_str("rate limited by discord"),
+            Self::BotMemberUnknown => f.write_str("bot member unknown"),
+        }
+    }
+}
+
+impl Error for HandlerError {}
+
+/// Map the error returned by a handler to a response.
+///
+/// The full error is logged with the interaction id, which is shown to the
+/// user for unknown errors.
+pub fn error_response(
+    error: &anyhow::Error,
+    id: Id<InteractionMarker>,
+    lang: Lang,
+) -> InteractionResponse {
+    match HandlerError::from_error(error) {
+        Some(kind) => {
+            warn!(error = ?error, kind = %kind, correlation_id = id.get(), "interaction handler failed");
+
+            kind.response(lang)
+        }
+        None => {
+            error!(error = ?error, correlation_id = id.get(), "error while processing interaction");
+
+            embed::error::internal_error(lang, id)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::{anyhow, Context};
+    use raidprotect_model::cache::discord::permission::CacheMiss;
+
+    use super::*;
+
+    #[test]
+    fn test_from_error_direct() {
+        let error = anyhow::Error::from(HandlerError::RateLimited);
+
+        assert_eq!(
+            HandlerError::from_error(&error),
+            Some(HandlerError::RateLimited)
+        );
+    }
+
+    #[test]
+    fn test_from_error_context() {
+        let error = anyhow!("connection refused").context(DatabaseUnavailable);
+        assert_eq!(
+            HandlerError::from_error(&error),
+            Some(HandlerError::DatabaseUnavailable)
+        );
+
+        let error = Err::<(), _>(TokenExpired)
+            .context("failed to update response")
+            .unwrap_err();
+        assert_eq!(
+            HandlerError::from_error(&error),
+            Some(HandlerError::TokenExpired)
+        );
+    }
+
+    #[test]
+    fn test_from_error_bot_member() {
+        let error = Err::<(), _>(CacheMiss("current member"))
+            .context(BotMemberUnknown)
+            .context("failed to compute permissions")
+            .unwrap_err();
+
+        assert_eq!(
+            HandlerError::from_error(&error),
+            Some(HandlerError::BotMemberUnknown)
+        );
+    }
+
+    #[test]
+    fn test_from_error_unknown() {
+        let error = anyhow!("unexpected error");
+
+        assert_eq!(HandlerError::from_error(&error), None);
+    }
+
+    #[test]
+    fn test_from_status() {
+        assert_eq!(
+            HandlerError::from_status(403),
+            Some(HandlerError::BotMissingPermissions)
+        );
+        assert_eq!(
+            HandlerError::from_status(404),
+            Some(HandlerError::UnknownResource)
+        );
+        assert_eq!(
+            HandlerError::from_status(429),
+            Some(HandlerError::RateLimited)
+        );
+        assert_eq!(HandlerError::from_status(500), None);
+    }
+
+    #[test]
+    fn test_error_response() {
+        let id = Id::new(1);
+
+        error_response(&anyhow!("unexpected error"), id, Lang::DEFAULT);
+        error_response(
+            &HandlerError::BotMissingPermissions.into(),
+            id,
+            Lang::DEFAULT,
+        );
+    }
+}
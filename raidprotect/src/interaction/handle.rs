@@ -1,38 +1,167 @@
-use std::str::FromStr;
+use std::{str::FromStr, time::Duration};
 
-use anyhow::bail;
-use tracing::{debug, error, warn};
-use twilight_interactions::command::CreateCommand;
+use anyhow::{bail, Context};
+use raidprotect_model::cache::model::interaction::{
+    PendingAutomodOverwrite, PendingBulkBan, PendingBulkUnban, PendingCaptcha, PendingComponent,
+    PendingJoinReview, PendingPaginator, PendingPreset, PendingReport, PendingRestore,
+    PendingRoleStrip, PendingSanction, PendingTimeoutAdjust, PostInChatButton,
+};
+use tracing::{debug, error, info_span, warn, Instrument, Span};
 use twilight_model::{
-    application::{
-        command::Command,
-        interaction::{Interaction, InteractionData, InteractionType},
+    application::interaction::{Interaction, InteractionData, InteractionType},
+    id::{
+        marker::{ApplicationMarker, GuildMarker, UserMarker},
+        Id,
     },
-    id::{marker::ApplicationMarker, Id},
 };
 
 use super::{
+    autocomplete::handle_autocomplete,
     command::{
-        config::ConfigCommand, help::HelpCommand, moderation::KickCommand, profile::ProfileCommand,
+        admin::AdminCommand,
+        channel_rule::ChannelRuleCommand,
+        check_command,
+        config::ConfigCommand,
+        features::FeaturesCommand,
+        help::HelpCommand,
+        link::LinkCommand,
+        lockdown::LockdownCommand,
+        moderation::{
+            BanCommand, BulkBanCommand, BulkUnbanCommand, CleanupCommand, KickCommand, MuteCommand,
+            PostCommand, QuarantineCommand, ReleaseCommand, RestoreMessageCommand, UnmuteCommand,
+        },
+        modlog::ModlogCommand,
+        preset::PresetCommand,
+        profile::ProfileCommand,
+        raid_mode::RaidModeCommand,
+        report::ReportCommand,
+        restore::RestoreCommand,
+        simulate::SimulateCommand,
+        stats::StatsCommand,
+        status::StatusCommand,
+        tag::TagCommand,
+        user_info::UserInfoCommand,
+        watch::WatchCommand,
+        COMMANDS,
+    },
+    component::{
+        self, captcha::*, paginator, Authorization, AutomodOverwrite, BulkBanCancel,
+        BulkBanConfirm, BulkUnbanCancel, BulkUnbanConfirm, FeatureToggle, FilterFeedbackButton,
+        HelpCategory, JoinReview, JoinReviewAction, LockdownCancel, Paginator, PostInChat,
+        PresetConfirm, RaidAlertDismiss, RaidAlertLockdown, ReportDelete, ReportModal,
+        ReportResolve, ReportSanction, RestoreConfirm, RoleStrip, SanctionConfirm,
+        SanctionReasonSelect, SanctionTemplateModal, TimeoutAdjust,
     },
-    component::{captcha::*, PostInChat},
     embed,
+    error::error_response,
     response::{InteractionResponder, InteractionResponse},
-    util::{CustomId, InteractionExt},
+    util::{CommandPath, CustomId, InteractionExt},
 };
-use crate::{cluster::ClusterState, translations::Lang};
+use crate::{
+    cluster::ClusterState,
+    feature::{automation::TimeoutAdjustment, reconcile},
+    translations::Lang,
+};
+
+/// Maximum value of the interaction handler timeout.
+///
+/// Interaction tokens are only valid for 15 minutes, a response cannot be
+/// sent after this delay.
+const MAX_INTERACTION_TIMEOUT: Duration = Duration::from_secs(15 * 60);
 
 /// Handle incoming [`Interaction`].
+///
+/// The interaction is processed inside an `interaction` span that carries the
+/// interaction id, guild id, user id, command path (or component custom id)
+/// and locale.
+///
+/// Handlers that take longer than the configured timeout are cancelled, and
+/// an error response is sent if possible.
+///
+/// Interactions sent to another application are ignored (see
+/// [`is_own_interaction`]).
 pub async fn handle_interaction(interaction: Interaction, state: &ClusterState) {
+    let span = interaction_span(&interaction);
+
+    if !is_own_interaction(&interaction, state.current_user) {
+        span.in_scope(|| {
+            warn!(
+                application_id = interaction.application_id.get(),
+                expected = state.current_user.get(),
+                "received interaction for another application"
+            )
+        });
+
+        return;
+    }
+
+    let responder = InteractionResponder::from_interaction(&interaction);
+    let lang = interaction.lang().unwrap_or(Lang::DEFAULT);
+    let id = interaction.id;
+    let timeout = state.interaction_timeout().min(MAX_INTERACTION_TIMEOUT);
+
+    let result = tokio::time::timeout(timeout, process_interaction(interaction, state))
+        .instrument(span.clone())
+        .await;
+
+    if result.is_err() {
+        async {
+            error!(timeout = ?timeout, correlation_id = id.get(), "interaction handler timed out");
+
+            responder
+                .respond_error(state, embed::error::internal_error(lang, id))
+                .await;
+        }
+        .instrument(span)
+        .await;
+    }
+}
+
+/// Check whether an [`Interaction`] was sent to the bot application.
+///
+/// Discord only sends the interactions of the bot application, so a mismatch
+/// means the interaction was misrouted or spoofed. Such interactions are not
+/// processed since their custom ids and token belong to another application.
+fn is_own_interaction(interaction: &Interaction, application_id: Id<ApplicationMarker>) -> bool {
+    interaction.application_id == application_id
+}
+
+/// Create the span of an [`Interaction`].
+fn interaction_span(interaction: &Interaction) -> Span {
+    let command = match &interaction.data {
+        Some(InteractionData::ApplicationCommand(data)) => {
+            Some(CommandPath::resolve(data).0.to_string())
+        }
+        Some(InteractionData::MessageComponent(data)) => Some(data.custom_id.clone()),
+        Some(InteractionData::ModalSubmit(data)) => Some(data.custom_id.clone()),
+        _ => None,
+    };
+
+    info_span!(
+        "interaction",
+        id = interaction.id.get(),
+        guild_id = interaction.guild_id.map(Id::get),
+        user_id = interaction.author_id().map(Id::get),
+        command = command.as_deref(),
+        locale = interaction.locale.as_deref(),
+    )
+}
+
+async fn process_interaction(interaction: Interaction, state: &ClusterState) {
     let responder = InteractionResponder::from_interaction(&interaction);
     debug!(id = ?interaction.id, "received {} interaction", interaction.kind.kind());
 
     let lang = interaction.lang().unwrap_or(Lang::DEFAULT);
+    let id = interaction.id;
+    let guild_id = interaction.guild_id;
 
     let response = match interaction.kind {
         InteractionType::ApplicationCommand => handle_command(interaction, state).await,
         InteractionType::MessageComponent => handle_component(interaction, state).await,
         InteractionType::ModalSubmit => handle_modal(interaction, state).await,
+        InteractionType::ApplicationCommandAutocomplete => {
+            handle_autocomplete(interaction, state).await
+        }
         other => {
             warn!("received unexpected {} interaction", other.kind());
 
@@ -43,11 +172,13 @@ pub async fn handle_interaction(interaction: Interaction, state: &ClusterState)
     match response {
         Ok(response) => responder.respond(state, response).await,
         Err(error) => {
-            error!(error = ?error, "error while processing interaction");
-
             responder
-                .respond(state, embed::error::internal_error(lang))
+                .respond(state, error_response(&error, id, lang))
                 .await;
+
+            if let Some(guild_id) = guild_id {
+                reconcile::record_error(state, guild_id, &error).await;
+            }
         }
     }
 }
@@ -62,11 +193,41 @@ async fn handle_command(
         _ => bail!("expected application command data"),
     };
 
+    if let Some(response) = check_command(&interaction, name, state).await? {
+        return Ok(response);
+    }
+
     match name {
+        "admin" => AdminCommand::handle(interaction, state).await,
+        "ban" => BanCommand::handle(interaction, state).await,
+        "bulk-ban" => BulkBanCommand::handle(interaction, state).await,
+        "bulk-unban" => BulkUnbanCommand::handle(interaction, state).await,
+        "channel-rule" => ChannelRuleCommand::handle(interaction, state).await,
+        "cleanup" => CleanupCommand::handle(interaction, state).await,
         "config" => ConfigCommand::handle(interaction, state).await,
+        "features" => FeaturesCommand::handle(interaction, state).await,
         "help" => HelpCommand::handle(interaction, state).await,
         "kick" => KickCommand::handle(interaction, state).await,
+        "link" => LinkCommand::handle(interaction, state).await,
+        "lockdown" => LockdownCommand::handle(interaction, state).await,
+        "modlog" => ModlogCommand::handle(interaction, state).await,
+        "mute" => MuteCommand::handle(interaction, state).await,
+        "post" => PostCommand::handle(interaction, state).await,
+        "preset" => PresetCommand::handle(interaction, state).await,
         "profile" => ProfileCommand::handle(interaction, state).await,
+        "quarantine" => QuarantineCommand::handle(interaction, state).await,
+        "raidmode" => RaidModeCommand::handle(interaction, state).await,
+        "release" => ReleaseCommand::handle(interaction, state).await,
+        "restore" => RestoreCommand::handle(interaction, state).await,
+        "restore-message" => RestoreMessageCommand::handle(interaction, state).await,
+        ReportCommand::NAME => ReportCommand::handle(interaction, state).await,
+        "simulate" => SimulateCommand::handle(interaction, state).await,
+        "stats" => StatsCommand::handle(interaction, state).await,
+        "status" => StatusCommand::handle(interaction, state).await,
+        "tag" => TagCommand::handle(interaction, state).await,
+        "unmute" => UnmuteCommand::handle(interaction, state).await,
+        "userinfo" => UserInfoCommand::handle(interaction, state).await,
+        "watch" => WatchCommand::handle(interaction, state).await,
         name => {
             warn!(name = name, "received unknown command");
 
@@ -86,11 +247,218 @@ async fn handle_component(
     };
 
     match &*custom_id.name {
+        "automod-overwrite" => {
+            let id = custom_id.id.context("missing component id in custom_id")?;
+            let expired = embed::error::expired_interaction;
+
+            match pending_component::<PendingAutomodOverwrite>(&interaction, &id, state, expired)
+                .await?
+            {
+                Ok(pending) => AutomodOverwrite::handle(interaction, pending, state).await,
+                Err(response) => Ok(response),
+            }
+        }
+        "bulk-ban-cancel" => {
+            let id = custom_id.id.context("missing component id in custom_id")?;
+
+            BulkBanCancel::handle(interaction, &id, state).await
+        }
+        "bulk-ban-confirm" => {
+            let id = custom_id.id.context("missing component id in custom_id")?;
+            let expired = embed::error::expired_interaction;
+
+            match pending_component::<PendingBulkBan>(&interaction, &id, state, expired).await? {
+                Ok(pending) => BulkBanConfirm::handle(interaction, pending, state).await,
+                Err(response) => Ok(response),
+            }
+        }
+        "bulk-unban-cancel" => {
+            let id = custom_id.id.context("missing component id in custom_id")?;
+
+            BulkUnbanCancel::handle(interaction, &id, state).await
+        }
+        "bulk-unban-confirm" => {
+            let id = custom_id.id.context("missing component id in custom_id")?;
+            let expired = embed::error::expired_interaction;
+
+            match pending_component::<PendingBulkUnban>(&interaction, &id, state, expired).await? {
+                Ok(pending) => BulkUnbanConfirm::handle(interaction, pending, state).await,
+                Err(response) => Ok(response),
+            }
+        }
         "captcha-disable" => CaptchaDisable::handle(interaction, state).await,
         "captcha-enable" => CaptchaEnable::handle(interaction, state).await,
-        "captcha-validate" => CaptchaValidateButton::handle(interaction, state).await,
-        "captcha-verify" => CaptchaVerifyButton::handle(interaction, state).await,
-        "post-in-chat" => PostInChat::handle(interaction, custom_id, state).await,
+        "captcha-validate" => {
+            let id = captcha_id(&interaction)?;
+            let expired = embed::captcha::captcha_not_found;
+
+            match pending_component::<PendingCaptcha>(&interaction, &id, state, expired).await? {
+                Ok(captcha) => CaptchaValidateButton::handle(interaction, captcha, state).await,
+                Err(response) => Ok(response),
+            }
+        }
+        "captcha-verify" => {
+            let id = captcha_id(&interaction)?;
+            let expired = embed::captcha::captcha_not_found;
+
+            match pending_component::<PendingCaptcha>(&interaction, &id, state, expired).await? {
+                Ok(captcha) => CaptchaVerifyButton::handle(interaction, captcha, state).await,
+                Err(response) => Ok(response),
+            }
+        }
+        "feature-toggle" => {
+            let feature = custom_id.id.context("missing feature in custom_id")?;
+
+            FeatureToggle::handle(interaction, &feature, state).await
+        }
+        "filter-feedback" => {
+            let id = custom_id.id.context("missing feedback id in custom_id")?;
+
+            FilterFeedbackButton::handle(interaction, &id, state).await
+        }
+        "help-category" => HelpCategory::handle(interaction, state).await,
+        "join-review-approve" => {
+            let id = custom_id.id.context("missing component id in custom_id")?;
+            let expired = embed::error::expired_interaction;
+            let action = JoinReviewAction::Approve;
+
+            match pending_component::<PendingJoinReview>(&interaction, &id, state, expired).await? {
+                Ok(pending) => JoinReview::handle(interaction, pending, action, state).await,
+                Err(response) => Ok(response),
+            }
+        }
+        "join-review-kick" => {
+            let id = custom_id.id.context("missing component id in custom_id")?;
+            let expired = embed::error::expired_interaction;
+            let action = JoinReviewAction::Kick;
+
+            match pending_component::<PendingJoinReview>(&interaction, &id, state, expired).await? {
+                Ok(pending) => JoinReview::handle(interaction, pending, action, state).await,
+                Err(response) => Ok(response),
+            }
+        }
+        "lockdown-cancel" => {
+            let id = custom_id.id.context("missing component id in custom_id")?;
+
+            LockdownCancel::handle(interaction, &id, state).await
+        }
+        "paginator" => {
+            let id = custom_id.id.context("missing component id in custom_id")?;
+            let (id, action) = paginator::parse_button_id(&id)?;
+            let expired = embed::error::expired_interaction;
+
+            match pending_component::<PendingPaginator>(&interaction, &id, state, expired).await? {
+                Ok(pending) => Paginator::handle(interaction, pending, action, state).await,
+                Err(response) => Ok(response),
+            }
+        }
+        "post-in-chat" => {
+            let id = custom_id.id.context("missing component id in custom_id")?;
+            let expired = embed::error::expired_interaction;
+
+            match pending_component::<PostInChatButton>(&interaction, &id, state, expired).await? {
+                Ok(component) => PostInChat::handle(interaction, component, state).await,
+                Err(response) => Ok(response),
+            }
+        }
+        "preset-confirm" => {
+            let id = custom_id.id.context("missing component id in custom_id")?;
+            let expired = embed::error::expired_interaction;
+
+            match pending_component::<PendingPreset>(&interaction, &id, state, expired).await? {
+                Ok(pending) => PresetConfirm::handle(interaction, pending, state).await,
+                Err(response) => Ok(response),
+            }
+        }
+        "raid-alert-dismiss" => RaidAlertDismiss::handle(interaction, state).await,
+        "raid-alert-lockdown" => RaidAlertLockdown::handle(interaction, state).await,
+        "report-delete" => {
+            let id = custom_id.id.context("missing report id in custom_id")?;
+
+            ReportDelete::handle(interaction, &id, state).await
+        }
+        "report-resolve" => {
+            let id = custom_id.id.context("missing report id in custom_id")?;
+
+            ReportResolve::handle(interaction, &id, state).await
+        }
+        "report-sanction" => {
+            let id = custom_id.id.context("missing report id in custom_id")?;
+
+            ReportSanction::handle(interaction, &id, state).await
+        }
+        "restore-confirm" => {
+            let id = custom_id.id.context("missing component id in custom_id")?;
+            let expired = embed::error::expired_interaction;
+
+            match pending_component::<PendingRestore>(&interaction, &id, state, expired).await? {
+                Ok(pending) => RestoreConfirm::handle(interaction, pending, state).await,
+                Err(response) => Ok(response),
+            }
+        }
+        "role-strip" => {
+            let id = custom_id.id.context("missing component id in custom_id")?;
+            let expired = embed::error::expired_interaction;
+
+            match pending_component::<PendingRoleStrip>(&interaction, &id, state, expired).await? {
+                Ok(pending) => RoleStrip::handle(interaction, pending, state).await,
+                Err(response) => Ok(response),
+            }
+        }
+        "sanction-confirm" => {
+            let id = custom_id.id.context("missing component id in custom_id")?;
+            let expired = embed::error::expired_interaction;
+
+            match pending_component::<PendingSanction>(&interaction, &id, state, expired).await? {
+                Ok(pending) => SanctionConfirm::handle(interaction, pending, state).await,
+                Err(response) => Ok(response),
+            }
+        }
+        "sanction-reason" => {
+            let id = custom_id.id.context("missing component id in custom_id")?;
+            let expired = embed::error::expired_interaction;
+
+            match pending_component::<PendingSanction>(&interaction, &id, state, expired).await? {
+                Ok(pending) => SanctionReasonSelect::handle(interaction, pending, state).await,
+                Err(response) => Ok(response),
+            }
+        }
+        "timeout-extend" => {
+            let id = custom_id.id.context("missing component id in custom_id")?;
+            let expired = embed::timeout_adjust::ended;
+            let adjustment = TimeoutAdjustment::Extend;
+
+            match pending_component::<PendingTimeoutAdjust>(&interaction, &id, state, expired)
+                .await?
+            {
+                Ok(pending) => TimeoutAdjust::handle(interaction, pending, adjustment, state).await,
+                Err(response) => Ok(response),
+            }
+        }
+        "timeout-lift" => {
+            let id = custom_id.id.context("missing component id in custom_id")?;
+            let expired = embed::timeout_adjust::ended;
+            let adjustment = TimeoutAdjustment::Lift;
+
+            match pending_component::<PendingTimeoutAdjust>(&interaction, &id, state, expired)
+                .await?
+            {
+                Ok(pending) => TimeoutAdjust::handle(interaction, pending, adjustment, state).await,
+                Err(response) => Ok(response),
+            }
+        }
+        "timeout-shorten" => {
+            let id = custom_id.id.context("missing component id in custom_id")?;
+            let expired = embed::timeout_adjust::ended;
+            let adjustment = TimeoutAdjustment::Shorten;
+
+            match pending_component::<PendingTimeoutAdjust>(&interaction, &id, state, expired)
+                .await?
+            {
+                Ok(pending) => TimeoutAdjust::handle(interaction, pending, adjustment, state).await,
+                Err(response) => Ok(response),
+            }
+        }
         name => {
             warn!(name = name, "received unknown component");
 
@@ -110,7 +478,33 @@ async fn handle_modal(
     };
 
     match &*custom_id.name {
-        "captcha-modal" => CaptchaModal::handle(interaction, state).await,
+        "captcha-modal" => {
+            let id = captcha_id(&interaction)?;
+            let expired = embed::captcha::captcha_not_found;
+
+            match pending_component::<PendingCaptcha>(&interaction, &id, state, expired).await? {
+                Ok(captcha) => CaptchaModal::handle(interaction, captcha, state).await,
+                Err(response) => Ok(response),
+            }
+        }
+        "report" => {
+            let id = custom_id.id.context("missing component id in custom_id")?;
+            let expired = embed::error::expired_interaction;
+
+            match pending_component::<PendingReport>(&interaction, &id, state, expired).await? {
+                Ok(pending) => ReportModal::handle(interaction, pending, state).await,
+                Err(response) => Ok(response),
+            }
+        }
+        "sanction-template" => {
+            let id = custom_id.id.context("missing component id in custom_id")?;
+            let expired = embed::error::expired_interaction;
+
+            match pending_component::<PendingSanction>(&interaction, &id, state, expired).await? {
+                Ok(pending) => SanctionTemplateModal::handle(interaction, pending, state).await,
+                Err(response) => Ok(response),
+            }
+        }
         // "sanction" => bail!("not implemented"),
         name => {
             warn!(name = name, "received unknown modal");
@@ -120,18 +514,104 @@ async fn handle_modal(
     }
 }
 
+/// Get the state of a pending component used by the interaction author.
+///
+/// If the state has expired, the `expired` response is returned. Users that
+/// are not authorized to use the component receive an error response, and the
+/// component handler is not run.
+async fn pending_component<T: PendingComponent>(
+    interaction: &Interaction,
+    id: &T::Id,
+    state: &ClusterState,
+    expired: fn(Lang) -> InteractionResponse,
+) -> Result<Result<T, InteractionResponse>, anyhow::Error> {
+    let author = interaction
+        .author_id()
+        .context("missing interaction author")?;
+
+    match component::authorize::<T>(state, id, author).await? {
+        Authorization::Authorized(pending) => Ok(Ok(pending)),
+        Authorization::Expired => Ok(Err(expired(interaction.lang()?))),
+        Authorization::Denied => {
+            warn!(user = ?author, id = ?id, "unauthorized component interaction");
+
+            Ok(Err(embed::error::unauthorized_component(
+                interaction.lang()?,
+            )))
+        }
+    }
+}
+
+/// Get the id of the pending captcha of the interaction author.
+fn captcha_id(
+    interaction: &Interaction,
+) -> Result<(Id<GuildMarker>, Id<UserMarker>), anyhow::Error> {
+    let guild_id = interaction
+        .guild_id
+        .context("missing interaction guild id")?;
+    let author = interaction
+        .author_id()
+        .context("missing interaction author")?;
+
+    Ok((guild_id, author))
+}
+
 /// Register commands to the Discord API.
+///
+/// The commands are registered globally when the bot starts, errors are
+/// only logged.
 pub async fn register_commands(state: &ClusterState, application_id: Id<ApplicationMarker>) {
-    let commands: Vec<Command> = vec![
-        ConfigCommand::create_command().into(),
-        HelpCommand::create_command().into(),
-        KickCommand::create_command().into(),
-        ProfileCommand::create_command().into(),
-    ];
+    if let Err(error) = sync_commands(state, application_id, None).await {
+        error!(error = ?error, "failed to register commands");
+    }
+}
+
+/// Register commands to the Discord API, globally or in a single guild.
+///
+/// Guild commands are updated instantly, which is useful to test changes to
+/// the commands. Returns the number of registered commands.
+pub async fn sync_commands(
+    state: &ClusterState,
+    application_id: Id<ApplicationMarker>,
+    guild_id: Option<Id<GuildMarker>>,
+) -> Result<usize, anyhow::Error> {
+    let mut commands = COMMANDS
+        .iter()
+        .map(|command| command.command())
+        .collect::<Vec<_>>();
 
     let client = state.http.interaction(application_id);
 
-    if let Err(error) = client.set_global_commands(&commands).exec().await {
-        error!(error = ?error, "failed to register commands");
+    match guild_id {
+        Some(guild_id) => {
+            // The DM permission only applies to global commands.
+            for command in &mut commands {
+                command.dm_permission = None;
+            }
+
+            client
+                .set_guild_commands(guild_id, &commands)
+                .exec()
+                .await?;
+        }
+        None => {
+            client.set_global_commands(&commands).exec().await?;
+        }
+    }
+
+    Ok(commands.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::fixture::{guild_command, APPLICATION_ID};
+
+    #[test]
+    fn test_is_own_interaction() {
+        let interaction = guild_command("help").interaction();
+
+        assert!(is_own_interaction(&interaction, APPLICATION_ID));
+        assert!(!is_own_interaction(&interaction, Id::new(1)));
     }
 }
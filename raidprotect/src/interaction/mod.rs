@@ -3,12 +3,14 @@
 //! This crate contain types used to handle and respond to incoming Discord
 //! interactions.
 
+mod autocomplete;
 mod handle;
 
 pub mod command;
 pub mod component;
 pub mod embed;
+pub mod error;
 pub mod response;
 pub mod util;
 
-pub use handle::{handle_interaction, register_commands};
+pub use handle::{handle_interaction, register_commands, sync_commands};
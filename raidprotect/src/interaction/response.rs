@@ -1,8 +1,22 @@
 //! Interactions responses.
 //!
 //! This module exports types and traits used to respond to an interaction.
+//!
+//! ## Token expiry
+//! Interaction tokens are only valid for [`TOKEN_LIFETIME`] after the
+//! interaction is created. The [`InteractionResponder`] refuses to edit the
+//! response or send follow-up messages after this delay and returns a
+//! [`TokenExpired`] error instead.
+//!
+//! Long-running flows report their progress with a [`ProgressMessage`], which
+//! switches to a new message in the channel when the token is about to
+//! expire.
+
+use std::{error::Error, fmt};
 
-use tracing::error;
+use raidprotect_model::{cache::model::job::ScheduledJob, database::model::GuildConfig};
+use time::{Duration, OffsetDateTime};
+use tracing::{debug, error, warn};
 use twilight_model::{
     application::{component::Component, interaction::Interaction},
     channel::{embed::Embed, message::MessageFlags},
@@ -11,16 +25,42 @@ use twilight_model::{
         InteractionResponseType,
     },
     id::{
-        marker::{ApplicationMarker, InteractionMarker},
+        marker::{ApplicationMarker, ChannelMarker, GuildMarker, InteractionMarker, MessageMarker},
         Id,
     },
 };
-use twilight_util::builder::InteractionResponseDataBuilder;
+use twilight_util::{builder::InteractionResponseDataBuilder, snowflake::Snowflake};
+
+use crate::{cluster::ClusterState, util::ratelimit::Priority};
+
+/// Duration during which an interaction token is valid.
+pub const TOKEN_LIFETIME: Duration = Duration::minutes(15);
 
-use crate::cluster::ClusterState;
+/// Remaining token validity below which a [`ProgressMessage`] switches to a
+/// new message.
+const PROGRESS_FALLBACK_MARGIN: Duration = Duration::minutes(1);
+
+/// Error returned when the token of an interaction has expired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenExpired;
+
+impl fmt::Display for TokenExpired {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("interaction token expired")
+    }
+}
+
+impl Error for TokenExpired {}
+
+/// Get the creation date of an interaction from its id.
+pub fn interaction_created_at(id: Id<InteractionMarker>) -> OffsetDateTime {
+    let nanos = i128::from(id.timestamp()) * 1_000_000;
+
+    OffsetDateTime::from_unix_timestamp_nanos(nanos).unwrap_or(OffsetDateTime::UNIX_EPOCH)
+}
 
 /// Credentials used to respond to an interaction.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InteractionResponder {
     /// ID of the interaction.
     pub id: Id<InteractionMarker>,
@@ -28,6 +68,12 @@ pub struct InteractionResponder {
     pub application_id: Id<ApplicationMarker>,
     /// Token of the command.
     pub token: String,
+    /// ID of the guild the interaction was invoked in.
+    pub guild_id: Option<Id<GuildMarker>>,
+    /// ID of the channel the interaction was invoked in.
+    pub channel_id: Option<Id<ChannelMarker>>,
+    /// Creation date of the interaction.
+    pub created_at: OffsetDateTime,
 }
 
 impl InteractionResponder {
@@ -37,21 +83,319 @@ impl InteractionResponder {
             id: interaction.id,
             application_id: interaction.application_id,
             token: interaction.token.clone(),
+            guild_id: interaction.guild_id,
+            channel_id: interaction.channel_id,
+            created_at: interaction_created_at(interaction.id),
         }
     }
 
+    /// Get the remaining validity of the interaction token.
+    ///
+    /// Handlers can use this value to decide how often to update their
+    /// progress. Returns [`Duration::ZERO`] if the token has expired.
+    pub fn remaining_budget(&self) -> Duration {
+        self.remaining_budget_at(OffsetDateTime::now_utc())
+    }
+
+    /// Get the remaining validity of the interaction token at a given date.
+    fn remaining_budget_at(&self, now: OffsetDateTime) -> Duration {
+        let remaining = self.created_at + TOKEN_LIFETIME - now;
+
+        remaining.max(Duration::ZERO)
+    }
+
+    /// Return a [`TokenExpired`] error if the token has expired.
+    fn check_expiry(&self) -> Result<(), TokenExpired> {
+        match self.remaining_budget().is_positive() {
+            true => Ok(()),
+            false => Err(TokenExpired),
+        }
+    }
+
+    /// Update the original response of the interaction.
+    ///
+    /// Returns a [`TokenExpired`] error if the token has expired.
+    pub async fn update_response(
+        &self,
+        state: &ClusterState,
+        embed: Embed,
+    ) -> Result<(), anyhow::Error> {
+        self.check_expiry()?;
+
+        let request = async {
+            state
+                .http
+                .interaction(self.application_id)
+                .update_response(&self.token)
+                .embeds(Some(&[embed]))?
+                .exec()
+                .await?;
+
+            Ok::<_, anyhow::Error>(())
+        };
+
+        state.rate_guard.send(Priority::Interaction, request).await
+    }
+
+    /// Update the original response of the interaction and remove its
+    /// components.
+    ///
+    /// Returns a [`TokenExpired`] error if the token has expired.
+    pub async fn finish_response(
+        &self,
+        state: &ClusterState,
+        embed: Embed,
+    ) -> Result<(), anyhow::Error> {
+        self.check_expiry()?;
+
+        let request = async {
+            state
+                .http
+                .interaction(self.application_id)
+                .update_response(&self.token)
+                .embeds(Some(&[embed]))?
+                .components(Some(&[]))?
+                .exec()
+                .await?;
+
+            Ok::<_, anyhow::Error>(())
+        };
+
+        state.rate_guard.send(Priority::Interaction, request).await
+    }
+
+    /// Send an ephemeral follow-up message.
+    ///
+    /// Returns a [`TokenExpired`] error if the token has expired.
+    pub async fn followup(
+        &self,
+        state: &ClusterState,
+        embeds: &[Embed],
+    ) -> Result<(), anyhow::Error> {
+        self.check_expiry()?;
+
+        let request = async {
+            state
+                .http
+                .interaction(self.application_id)
+                .create_followup(&self.token)
+                .embeds(embeds)?
+                .flags(MessageFlags::EPHEMERAL)
+                .exec()
+                .await?;
+
+            Ok::<_, anyhow::Error>(())
+        };
+
+        state.rate_guard.send(Priority::Interaction, request).await
+    }
+
     /// Send a response to an interaction.
+    ///
+    /// If the response is a public message sent in a guild that configured
+    /// automatic deletion of replies, the deletion is scheduled.
     pub async fn respond(&self, state: &ClusterState, response: InteractionResponse) {
-        let client = state.http.interaction(self.application_id);
+        let deletable = response.is_deletable();
+
+        if let Err(error) = self.create_response(state, &response.into_http()).await {
+            error!(error = ?error, "failed to respond to interaction");
+            return;
+        }
+
+        if let (true, Some(guild_id)) = (deletable, self.guild_id) {
+            if let Err(error) = self.schedule_delete(state, guild_id).await {
+                error!(error = ?error, "failed to schedule reply deletion");
+            }
+        }
+    }
+
+    /// Send an error response to an interaction that may have already been
+    /// acknowledged.
+    ///
+    /// Interactions must be acknowledged within 3 seconds. If the initial
+    /// response cannot be created, the error is sent as a follow-up message.
+    pub async fn respond_error(&self, state: &ClusterState, response: InteractionResponse) {
+        let response = response.into_http();
+
+        if self.create_response(state, &response).await.is_ok() {
+            return;
+        }
+
+        let embeds = response
+            .data
+            .and_then(|data| data.embeds)
+            .unwrap_or_default();
+
+        if let Err(error) = self.followup(state, &embeds).await {
+            debug!(error = ?error, "failed to send error response to interaction");
+        }
+    }
+
+    /// Send the initial response of the interaction.
+    async fn create_response(
+        &self,
+        state: &ClusterState,
+        response: &HttpInteractionResponse,
+    ) -> Result<(), anyhow::Error> {
+        let request = async {
+            state
+                .http
+                .interaction(self.application_id)
+                .create_response(self.id, &self.token, response)
+                .exec()
+                .await?;
+
+            Ok::<_, anyhow::Error>(())
+        };
+
+        state.rate_guard.send(Priority::Interaction, request).await
+    }
 
-        if let Err(error) = client
-            .create_response(self.id, &self.token, &response.into_http())
+    /// Schedule the deletion of the interaction response, if enabled in the
+    /// guild configuration.
+    ///
+    /// The configuration is read from the cache, where it is stored when the
+    /// handler loads it, to avoid querying the database on every reply.
+    async fn schedule_delete(
+        &self,
+        state: &ClusterState,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<(), anyhow::Error> {
+        let config = state.cache.get::<GuildConfig>(&guild_id).await?;
+        let delay = match config.and_then(|config| config.delete_replies_after) {
+            Some(delay) => Duration::seconds(delay.into()),
+            None => return Ok(()),
+        };
+
+        let message = state
+            .http
+            .interaction(self.application_id)
+            .response(&self.token)
             .exec()
+            .await?
+            .model()
+            .await?;
+
+        let job = ScheduledJob::DeleteMessage {
+            channel_id: message.channel_id,
+            message_id: message.id,
+        };
+
+        state
+            .cache
+            .schedule_job(&job, OffsetDateTime::now_utc() + delay)
             .await
+    }
+}
+
+/// Message used to report the progress of a long-running flow.
+///
+/// The progress is shown by editing the original response of the interaction.
+/// When the token is about to expire, a new message is sent in the channel
+/// and edited instead.
+#[derive(Debug)]
+pub struct ProgressMessage {
+    /// Responder of the interaction that started the flow.
+    responder: InteractionResponder,
+    /// Message sent in the channel once the token is about to expire.
+    fallback: Option<Id<MessageMarker>>,
+}
+
+/// Message updated by a [`ProgressMessage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProgressTarget {
+    /// Original response of the interaction.
+    Original,
+    /// New message sent in the channel.
+    Fallback,
+}
+
+impl ProgressMessage {
+    /// Initialize a new [`ProgressMessage`].
+    pub fn new(responder: InteractionResponder) -> Self {
+        Self {
+            responder,
+            fallback: None,
+        }
+    }
+
+    /// Update the progress message.
+    ///
+    /// Errors are logged and ignored since the flow should not be interrupted
+    /// if its progress cannot be reported.
+    pub async fn update(&mut self, state: &ClusterState, embed: Embed) {
+        let result = match self.target(OffsetDateTime::now_utc()) {
+            ProgressTarget::Original => self.responder.update_response(state, embed).await,
+            ProgressTarget::Fallback => self.update_fallback(state, embed).await,
+        };
+
+        if let Err(error) = result {
+            warn!(error = ?error, "failed to update progress message");
+        }
+    }
+
+    /// Update the progress message once the flow is completed.
+    ///
+    /// The components of the original response, such as a cancel button, are
+    /// removed.
+    pub async fn finish(&mut self, state: &ClusterState, embed: Embed) {
+        let result = match self.target(OffsetDateTime::now_utc()) {
+            ProgressTarget::Original => self.responder.finish_response(state, embed).await,
+            ProgressTarget::Fallback => self.update_fallback(state, embed).await,
+        };
+
+        if let Err(error) = result {
+            warn!(error = ?error, "failed to update progress message");
+        }
+    }
+
+    /// Get the message to update at a given date.
+    fn target(&self, now: OffsetDateTime) -> ProgressTarget {
+        if self.fallback.is_some()
+            || self.responder.remaining_budget_at(now) <= PROGRESS_FALLBACK_MARGIN
         {
-            error!(error = ?error, "failed to respond to interaction");
+            ProgressTarget::Fallback
+        } else {
+            ProgressTarget::Original
         }
     }
+
+    /// Send or edit the fallback message.
+    async fn update_fallback(
+        &mut self,
+        state: &ClusterState,
+        embed: Embed,
+    ) -> Result<(), anyhow::Error> {
+        let channel_id = match self.responder.channel_id {
+            Some(channel_id) => channel_id,
+            None => return Err(TokenExpired.into()),
+        };
+
+        match self.fallback {
+            Some(message_id) => {
+                state
+                    .http
+                    .update_message(channel_id, message_id)
+                    .embeds(Some(&[embed]))?
+                    .exec()
+                    .await?;
+            }
+            None => {
+                let message = state
+                    .http
+                    .create_message(channel_id)
+                    .embeds(&[embed])?
+                    .exec()
+                    .await?
+                    .model()
+                    .await?;
+
+                self.fallback = Some(message.id);
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Response to an interaction.
@@ -82,6 +426,31 @@ pub enum InteractionResponse {
 }
 
 impl InteractionResponse {
+    /// Whether the response is a public message that can be automatically
+    /// deleted.
+    ///
+    /// Ephemeral messages are only visible to the user and don't need to be
+    /// deleted. Messages with interactive components (such as menus) are kept
+    /// since deleting them would prevent the user from using them.
+    fn is_deletable(&self) -> bool {
+        match self {
+            Self::Embed(_) => true,
+            Self::Raw {
+                kind: InteractionResponseType::ChannelMessageWithSource,
+                data: Some(data),
+            } => {
+                let ephemeral = data
+                    .flags
+                    .map(|flags| flags.contains(MessageFlags::EPHEMERAL))
+                    .unwrap_or(false);
+                let interactive = data.components.iter().flatten().any(is_interactive);
+
+                !ephemeral && !interactive
+            }
+            _ => false,
+        }
+    }
+
     /// Convert the response into a [`HttpInteractionResponse`].
     fn into_http(self) -> HttpInteractionResponse {
         let kind = match self {
@@ -127,3 +496,80 @@ impl InteractionResponse {
         HttpInteractionResponse { kind, data }
     }
 }
+
+/// Whether a component can trigger an interaction.
+fn is_interactive(component: &Component) -> bool {
+    match component {
+        Component::ActionRow(row) => row.components.iter().any(is_interactive),
+        Component::Button(button) => button.custom_id.is_some(),
+        Component::SelectMenu(_) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn responder(created_at: OffsetDateTime) -> InteractionResponder {
+        InteractionResponder {
+            id: Id::new(1),
+            application_id: Id::new(2),
+            token: "token".to_owned(),
+            guild_id: None,
+            channel_id: Some(Id::new(3)),
+            created_at,
+        }
+    }
+
+    #[test]
+    fn test_interaction_created_at() {
+        // Snowflakes store the number of milliseconds since the Discord epoch
+        // (2015-01-01) in their upper 42 bits.
+        let id = Id::new(1_000 << 22);
+
+        assert_eq!(
+            interaction_created_at(id).unix_timestamp(),
+            1_420_070_400 + 1
+        );
+    }
+
+    #[test]
+    fn test_remaining_budget() {
+        let created_at = OffsetDateTime::UNIX_EPOCH;
+        let responder = responder(created_at);
+
+        assert_eq!(responder.remaining_budget_at(created_at), TOKEN_LIFETIME);
+        assert_eq!(
+            responder.remaining_budget_at(created_at + Duration::minutes(10)),
+            Duration::minutes(5)
+        );
+        assert_eq!(
+            responder.remaining_budget_at(created_at + Duration::hours(1)),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn test_progress_switchover() {
+        let created_at = OffsetDateTime::UNIX_EPOCH;
+        let mut progress = ProgressMessage::new(responder(created_at));
+
+        assert_eq!(
+            progress.target(created_at + Duration::minutes(13)),
+            ProgressTarget::Original
+        );
+        assert_eq!(
+            progress.target(created_at + TOKEN_LIFETIME - PROGRESS_FALLBACK_MARGIN),
+            ProgressTarget::Fallback
+        );
+        assert_eq!(
+            progress.target(created_at + TOKEN_LIFETIME),
+            ProgressTarget::Fallback
+        );
+
+        // The fallback message is kept once it has been sent.
+        progress.fallback = Some(Id::new(4));
+        assert_eq!(progress.target(created_at), ProgressTarget::Fallback);
+    }
+}
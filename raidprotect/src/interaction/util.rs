@@ -1,4 +1,42 @@
 //! Utility function to handle incoming interactions.
+//!
+//! ## Response lang
+//!
+//! The lang of the messages sent by the bot is resolved in the following
+//! order:
+//!
+//! 1. Messages that are not sent in response to an interaction, such as logs,
+//!    always use the guild lang ([`GuildConfig::lang`]).
+//! 2. Interaction responses sent outside a guild use the user locale.
+//! 3. If [`GuildConfig::lang_override`] is enabled, interaction responses
+//!    visible by other members use the guild lang.
+//! 4. If [`GuildConfig::lang_override_ephemeral`] is also enabled, ephemeral
+//!    replies use the guild lang.
+//! 5. Otherwise, the user locale is used.
+//!
+//! The resolved langs are available in the [`InteractionContext`] and
+//! [`GuildInteractionContext`] types.
+//!
+//! ## Partial member data
+//!
+//! During Discord outages, guild interactions may be received without the
+//! member that invoked them. The member is then rebuilt from its last-known
+//! [`MemberRecord`], or a [partial] context is created with the user only.
+//! Handlers that depend on the roles or permissions of the member check it
+//! with [`GuildInteractionContext::check_partial`].
+//!
+//! [partial]: GuildInteractionContext::partial
+//!
+//! ## Command path
+//!
+//! Commands may be nested in subcommands and subcommand groups, such as
+//! `/config sanctions dm`. [`CommandPath::resolve`] resolves the path of the
+//! invoked command from its data, along with the options of the invoked
+//! subcommand. The path can be matched with [`CommandPath::route`] instead of
+//! walking the command options, as done for autocomplete interactions.
+//!
+//! Commands are parsed into their typed models before being handled, so the
+//! path is not stored in the contexts.
 
 use std::{
     fmt::{self, Display},
@@ -7,17 +45,31 @@ use std::{
 };
 
 use anyhow::{anyhow, bail, Context};
-use raidprotect_model::database::model::GuildConfig;
-use tracing::instrument;
+use raidprotect_model::{
+    cache::{discord::permission::GuildPermissions, model::member::MemberRecord},
+    database::model::GuildConfig,
+    store::CacheStore,
+};
+use time::OffsetDateTime;
+use tracing::{instrument, warn};
 use twilight_interactions::command::CommandModel;
 use twilight_model::{
-    application::interaction::{modal::ModalInteractionData, Interaction, InteractionData},
-    guild::PartialMember,
+    application::interaction::{
+        application_command::{CommandData, CommandDataOption, CommandOptionValue},
+        modal::ModalInteractionData,
+        Interaction, InteractionData,
+    },
+    guild::{PartialMember, Permissions},
     id::{marker::GuildMarker, Id},
     user::User,
+    util::Timestamp,
 };
 
-use crate::{cluster::ClusterState, translations::Lang};
+use super::{
+    embed,
+    response::{interaction_created_at, InteractionResponder, InteractionResponse},
+};
+use crate::{cluster::HandlerState, database, translations::Lang};
 
 /// Wrapper around [`Interaction`] to provide some utility functions.
 #[derive(Debug)]
@@ -26,29 +78,66 @@ pub struct InteractionContext {
     pub interaction: Interaction,
     /// User that invoked the interaction.
     pub author: User,
-    /// Lang of the user that invoked the interaction.
+    /// Lang used for ephemeral replies.
     pub lang: Lang,
+    /// Lang used for responses visible by other members.
+    pub public_lang: Lang,
 }
 
 impl InteractionContext {
     /// Create a new [`InteractionContext`] from an [`Interaction`].
-    #[instrument]
-    pub fn new(interaction: Interaction) -> Result<Self, anyhow::Error> {
+    #[instrument(skip(state))]
+    pub async fn new(
+        interaction: Interaction,
+        state: &impl HandlerState,
+    ) -> Result<Self, anyhow::Error> {
         let author = interaction_user(&interaction).context("missing interaction user")?;
-        let locale = interaction
-            .locale
-            .as_ref()
-            .context("missing interaction locale")?;
-        let lang = Lang::from(&**locale);
+        let (lang, public_lang) = resolve_langs(&interaction, state).await?;
 
         Ok(Self {
             interaction,
             author,
             lang,
+            public_lang,
         })
     }
 }
 
+/// Resolve the langs used to respond to an interaction.
+///
+/// Returns the lang of ephemeral replies and the lang of responses visible by
+/// other members. See the [module documentation](self) for the resolution
+/// order.
+async fn resolve_langs(
+    interaction: &Interaction,
+    state: &impl HandlerState,
+) -> Result<(Lang, Lang), anyhow::Error> {
+    let locale = interaction
+        .locale
+        .as_ref()
+        .context("missing interaction locale")?;
+    let user = Lang::from(&**locale);
+
+    let guild_id = match interaction.guild_id {
+        Some(guild_id) => guild_id,
+        None => return Ok((user, user)),
+    };
+
+    // The user locale is used if the configuration is unavailable, to avoid
+    // failing the whole interaction.
+    match database::guild_config(state, guild_id).await {
+        Ok(config) => Ok((
+            config.interaction_lang(user, true),
+            config.interaction_lang(user, false),
+        )),
+        Err(error) => {
+            warn!(error = ?error, guild = ?guild_id, "failed to get guild config to resolve lang");
+
+            Ok((user, user))
+        }
+    }
+}
+
 fn interaction_user(interaction: &Interaction) -> Option<User> {
     if let Some(member) = &interaction.member {
         if let Some(user) = &member.user {
@@ -63,6 +152,68 @@ fn interaction_user(interaction: &Interaction) -> Option<User> {
     None
 }
 
+/// Build the member of an interaction from its last-known [`MemberRecord`].
+async fn cached_member(
+    state: &impl HandlerState,
+    guild_id: Id<GuildMarker>,
+    author: &User,
+) -> Result<PartialMember, anyhow::Error> {
+    let record = state
+        .cache_store()
+        .get::<MemberRecord>(&(guild_id, author.id))
+        .await?
+        .context("missing interaction member")?;
+
+    let permissions = GuildPermissions::new(state.cache_store(), guild_id)
+        .await?
+        .member(author.id, &record.roles)
+        .await?
+        .guild();
+
+    warn!(guild = ?guild_id, user = ?author.id, "interaction member missing, using cached member");
+
+    Ok(synthesize_member(author, record, permissions))
+}
+
+/// Build a minimal [`PartialMember`] from a [`MemberRecord`].
+fn synthesize_member(
+    author: &User,
+    record: MemberRecord,
+    permissions: Permissions,
+) -> PartialMember {
+    PartialMember {
+        avatar: None,
+        communication_disabled_until: None,
+        deaf: false,
+        joined_at: record.joined_at,
+        mute: false,
+        nick: None,
+        permissions: Some(permissions),
+        premium_since: None,
+        roles: record.roles,
+        user: Some(author.clone()),
+    }
+}
+
+/// Build a [`PartialMember`] without roles nor permissions.
+///
+/// This member is used when neither the interaction member nor its
+/// [`MemberRecord`] are available.
+fn partial_member(author: &User, joined_at: Timestamp) -> PartialMember {
+    PartialMember {
+        avatar: None,
+        communication_disabled_until: None,
+        deaf: false,
+        joined_at,
+        mute: false,
+        nick: None,
+        permissions: None,
+        premium_since: None,
+        roles: Vec::new(),
+        user: Some(author.clone()),
+    }
+}
+
 /// Wrapper around an [`Interaction`] that was invoked in a guild.
 ///
 /// This type is similar to [`InteractionContext`], but provides additional
@@ -74,51 +225,107 @@ pub struct GuildInteractionContext {
     pub author: User,
     /// Member object of the user that invoked the interaction.
     pub member: PartialMember,
-    /// Lang of the user that invoked the interaction.
+    /// Whether the member data is partial.
+    ///
+    /// If set, the [`member`] has no roles nor permissions, since they were
+    /// unavailable when the interaction was received.
+    ///
+    /// [`member`]: Self::member
+    pub partial: bool,
+    /// Lang used for ephemeral replies.
     pub lang: Lang,
+    /// Lang used for responses visible by other members.
+    pub public_lang: Lang,
     /// Id of the guild the interaction was invoked in.
     pub guild_id: Id<GuildMarker>,
+    /// Creation date of the interaction.
+    ///
+    /// The interaction token expires [`TOKEN_LIFETIME`](super::response::TOKEN_LIFETIME)
+    /// after this date.
+    pub created_at: OffsetDateTime,
 }
 
 impl GuildInteractionContext {
     /// Create a new [`GuildInteractionContext`] from an [`Interaction`].
-    #[instrument]
-    pub fn new(interaction: Interaction) -> Result<Self, anyhow::Error> {
-        let member = interaction
-            .member
-            .clone()
-            .context("missing interaction member")?;
-        let author = member
-            .user
-            .clone()
-            .context("missing interaction member user")?;
+    ///
+    /// Discord may omit the user of the interaction member, for example in
+    /// newly created threads. The top-level user is used in this case. If the
+    /// member itself is missing, a minimal member is built from its
+    /// last-known [`MemberRecord`]. If this record is also unavailable, the
+    /// context is created with a [`partial`] member.
+    ///
+    /// [`partial`]: Self::partial
+    #[instrument(skip(state))]
+    pub async fn new(
+        interaction: Interaction,
+        state: &impl HandlerState,
+    ) -> Result<Self, anyhow::Error> {
         let guild_id = interaction
             .guild_id
             .context("missing interaction guild id")?;
-
-        let locale = interaction
-            .locale
-            .as_ref()
-            .context("missing interaction locale")?;
-        let lang = Lang::from(&**locale);
+        let author = interaction_user(&interaction).context("missing interaction user")?;
+        let created_at = interaction_created_at(interaction.id);
+        let (member, partial) = match &interaction.member {
+            Some(member) => (member.clone(), false),
+            None => match cached_member(state, guild_id, &author).await {
+                Ok(member) => (member, false),
+                Err(error) => {
+                    warn!(error = ?error, guild = ?guild_id, user = ?author.id, "interaction member unavailable, using partial member");
+
+                    let joined_at = Timestamp::from_secs(created_at.unix_timestamp())?;
+                    (partial_member(&author, joined_at), true)
+                }
+            },
+        };
+        let (lang, public_lang) = resolve_langs(&interaction, state).await?;
 
         Ok(Self {
             interaction,
             author,
             member,
+            partial,
             lang,
+            public_lang,
             guild_id,
+            created_at,
         })
     }
 
+    /// Check whether the member data of the interaction is complete.
+    ///
+    /// Handlers that depend on the roles or permissions of the member return
+    /// the error response if the context is [`partial`].
+    ///
+    /// [`partial`]: Self::partial
+    pub fn check_partial(&self) -> Option<InteractionResponse> {
+        self.partial
+            .then(|| embed::error::discord_unavailable(self.lang))
+    }
+
+    /// Get an [`InteractionResponder`] to respond to the interaction after
+    /// the handler has returned.
+    pub fn responder(&self) -> InteractionResponder {
+        InteractionResponder {
+            id: self.interaction.id,
+            application_id: self.interaction.application_id,
+            token: self.interaction.token.clone(),
+            guild_id: Some(self.guild_id),
+            channel_id: self.interaction.channel_id,
+            created_at: self.created_at,
+        }
+    }
+
     /// Get the [`GuildConfig`] for the guild the interaction was invoked in.
-    pub async fn config(&self, state: &ClusterState) -> Result<GuildConfig, anyhow::Error> {
-        let config = state
-            .database
-            .get_guild_or_create(self.guild_id)
+    ///
+    /// The configuration references are checked in the background (see
+    /// [`HandlerState::check_references`]).
+    pub async fn config(&self, state: &impl HandlerState) -> Result<GuildConfig, anyhow::Error> {
+        let config = database::guild_config(state, self.guild_id)
             .await
             .context("failed to get guild config")?;
 
+        state.check_references(config.clone());
+
         Ok(config)
     }
 }
@@ -141,6 +348,13 @@ impl InteractionExt for Interaction {
 pub trait GuildConfigExt {
     /// Get the lang of the guild.
     fn lang(&self) -> Lang;
+
+    /// Get the lang used to respond to an interaction invoked in the guild.
+    ///
+    /// The `user` lang is the interaction locale, and `ephemeral` is whether
+    /// the response is only visible by the user. See the [module
+    /// documentation](self) for the resolution order.
+    fn interaction_lang(&self, user: Lang, ephemeral: bool) -> Lang;
 }
 
 impl GuildConfigExt for GuildConfig {
@@ -148,6 +362,13 @@ impl GuildConfigExt for GuildConfig {
     fn lang(&self) -> Lang {
         Lang::from(&*self.lang)
     }
+
+    fn interaction_lang(&self, user: Lang, ephemeral: bool) -> Lang {
+        match (self.lang_override, self.lang_override_ephemeral, ephemeral) {
+            (true, _, false) | (true, true, true) => self.lang(),
+            _ => user,
+        }
+    }
 }
 
 /// Component custom id.
@@ -213,6 +434,90 @@ impl Display for CustomId {
     }
 }
 
+/// Path of an invoked command.
+///
+/// The path is made of the command name, and the names of the subcommand
+/// group and subcommand if the invoked command is nested. For example, the
+/// path of `/config sanctions dm` has the `sanctions` group and the `dm`
+/// subcommand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandPath {
+    /// Name of the top-level command.
+    pub name: String,
+    /// Name of the subcommand group.
+    pub group: Option<String>,
+    /// Name of the subcommand.
+    pub subcommand: Option<String>,
+}
+
+impl CommandPath {
+    /// Resolve the path of a command.
+    ///
+    /// Also returns the options of the invoked subcommand, or the top-level
+    /// options if the command has no subcommand.
+    pub fn resolve(data: &CommandData) -> (Self, &[CommandDataOption]) {
+        let mut path = Self {
+            name: data.name.clone(),
+            group: None,
+            subcommand: None,
+        };
+        let mut options = &*data.options;
+
+        if let [CommandDataOption {
+            name,
+            value: CommandOptionValue::SubCommandGroup(nested),
+        }] = options
+        {
+            path.group = Some(name.clone());
+            options = nested;
+        }
+
+        if let [CommandDataOption {
+            name,
+            value: CommandOptionValue::SubCommand(nested),
+        }] = options
+        {
+            path.subcommand = Some(name.clone());
+            options = nested;
+        }
+
+        (path, options)
+    }
+
+    /// Get the route of the command.
+    ///
+    /// The route contains the command name, group and subcommand, and is used
+    /// to match a command path:
+    ///
+    /// ```ignore
+    /// match path.route() {
+    ///     ("config", Some("reasons"), Some("remove")) => { /* ... */ }
+    ///     ("tag", None, _) => { /* ... */ }
+    ///     _ => { /* ... */ }
+    /// }
+    /// ```
+    pub fn route(&self) -> (&str, Option<&str>, Option<&str>) {
+        (
+            &self.name,
+            self.group.as_deref(),
+            self.subcommand.as_deref(),
+        )
+    }
+}
+
+impl Display for CommandPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)?;
+
+        for segment in [&self.group, &self.subcommand].into_iter().flatten() {
+            f.write_str(" ")?;
+            f.write_str(segment)?;
+        }
+
+        Ok(())
+    }
+}
+
 /// Parse incoming [`ApplicationCommand`] or [`ApplicationCommandAutocomplete`]
 /// interactions into typed struct.
 ///
@@ -286,6 +591,9 @@ pub fn parse_modal_field_required<'a>(
 /// method with the following signature:
 ///
 /// `async fn exec(self, ctx: InteractionContext, state: &ClusterState) -> Result<InteractionResponse, anyhow::Error>`
+///
+/// The state may also be a `&impl HandlerState` if the command only needs the
+/// stores, so that it can be tested with in-memory stores.
 #[macro_export]
 macro_rules! impl_command_handle {
     ($name:path) => {
@@ -297,7 +605,8 @@ macro_rules! impl_command_handle {
             ) -> Result<$crate::interaction::response::InteractionResponse, ::anyhow::Error> {
                 let parsed =
                     $crate::interaction::util::parse_command_data::<Self>(&mut interaction)?;
-                let ctx = $crate::interaction::util::InteractionContext::new(interaction)?;
+                let ctx =
+                    $crate::interaction::util::InteractionContext::new(interaction, state).await?;
 
                 parsed.exec(ctx, state).await
             }
@@ -315,6 +624,8 @@ macro_rules! impl_command_handle {
 /// with the following signature:
 ///
 ///`async fn exec(self, ctx: GuildInteractionContext, state: &ClusterState) -> Result<InteractionResponse, anyhow::Error>`
+///
+/// Like with [`impl_command_handle`], the state may be a `&impl HandlerState`.
 #[macro_export]
 macro_rules! impl_guild_command_handle {
     ($name:path) => {
@@ -326,7 +637,9 @@ macro_rules! impl_guild_command_handle {
             ) -> Result<$crate::interaction::response::InteractionResponse, ::anyhow::Error> {
                 let parsed =
                     $crate::interaction::util::parse_command_data::<Self>(&mut interaction)?;
-                let ctx = $crate::interaction::util::GuildInteractionContext::new(interaction)?;
+                let ctx =
+                    $crate::interaction::util::GuildInteractionContext::new(interaction, state)
+                        .await?;
 
                 parsed.exec(ctx, state).await
             }
@@ -359,3 +672,195 @@ macro_rules! desc_localizations {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::application::command::CommandType;
+
+    use super::*;
+    use crate::{
+        interaction::command::config::{ConfigCommand, SanctionsConfigCommand},
+        test_utils::fixture,
+    };
+
+    #[test]
+    fn test_interaction_lang() {
+        let mut config = GuildConfig::new(Id::new(1));
+        config.lang = "fr".to_owned();
+
+        assert_eq!(config.interaction_lang(Lang::En, false), Lang::En);
+        assert_eq!(config.interaction_lang(Lang::En, true), Lang::En);
+
+        config.lang_override = true;
+        assert_eq!(config.interaction_lang(Lang::En, false), Lang::Fr);
+        assert_eq!(config.interaction_lang(Lang::En, true), Lang::En);
+
+        config.lang_override_ephemeral = true;
+        assert_eq!(config.interaction_lang(Lang::En, false), Lang::Fr);
+        assert_eq!(config.interaction_lang(Lang::En, true), Lang::Fr);
+
+        config.lang_override = false;
+        assert_eq!(config.interaction_lang(Lang::En, true), Lang::En);
+    }
+
+    /// Interaction sent in a newly created thread, with a member object
+    /// without the nested user.
+    const MEMBER_WITHOUT_USER: &str = include_str!("fixtures/member_without_user.json");
+
+    /// Interaction sent in a guild without the member object.
+    const MISSING_MEMBER: &str = include_str!("fixtures/missing_member.json");
+
+    #[test]
+    fn test_member_without_user() {
+        let interaction: Interaction = serde_json::from_str(MEMBER_WITHOUT_USER).unwrap();
+
+        let member = interaction.member.as_ref().unwrap();
+        assert!(member.user.is_none());
+
+        let user = interaction_user(&interaction).unwrap();
+        assert_eq!(user.id, Id::new(233615937548369921));
+    }
+
+    #[test]
+    fn test_missing_member() {
+        let interaction: Interaction = serde_json::from_str(MISSING_MEMBER).unwrap();
+
+        assert!(interaction.member.is_none());
+        assert_eq!(interaction.guild_id, Some(Id::new(764128497018208297)));
+
+        let user = interaction_user(&interaction).unwrap();
+        let record = MemberRecord {
+            guild_id: Id::new(764128497018208297),
+            user_id: user.id,
+            joined_at: Timestamp::from_secs(1_635_066_765).unwrap(),
+            roles: vec![Id::new(904672211366789140)],
+            left_at: None,
+            pending: false,
+        };
+
+        let member = synthesize_member(&user, record.clone(), Permissions::ADMINISTRATOR);
+        assert_eq!(member.user.map(|user| user.id), Some(user.id));
+        assert_eq!(member.roles, record.roles);
+        assert_eq!(member.joined_at, record.joined_at);
+        assert_eq!(member.permissions, Some(Permissions::ADMINISTRATOR));
+    }
+
+    fn command_data(name: &str, options: Vec<CommandDataOption>) -> CommandData {
+        CommandData {
+            guild_id: None,
+            id: Id::new(1),
+            name: name.to_owned(),
+            kind: CommandType::ChatInput,
+            options,
+            resolved: None,
+            target_id: None,
+        }
+    }
+
+    fn option(name: &str, value: CommandOptionValue) -> CommandDataOption {
+        CommandDataOption {
+            name: name.to_owned(),
+            value,
+        }
+    }
+
+    #[test]
+    fn test_command_path_top_level() {
+        let data = command_data(
+            "ban",
+            vec![option("user", CommandOptionValue::User(Id::new(2)))],
+        );
+
+        let (path, options) = CommandPath::resolve(&data);
+        assert_eq!(path.route(), ("ban", None, None));
+        assert_eq!(path.to_string(), "ban");
+        assert_eq!(options, &data.options[..]);
+    }
+
+    #[test]
+    fn test_command_path_subcommand() {
+        let data = command_data(
+            "tag",
+            vec![option(
+                "send",
+                CommandOptionValue::SubCommand(vec![option(
+                    "name",
+                    CommandOptionValue::String("rules".to_owned()),
+                )]),
+            )],
+        );
+
+        let (path, options) = CommandPath::resolve(&data);
+        assert_eq!(path.route(), ("tag", None, Some("send")));
+        assert_eq!(path.to_string(), "tag send");
+        assert_eq!(options.len(), 1);
+        assert_eq!(options[0].name, "name");
+    }
+
+    #[test]
+    fn test_command_path_group() {
+        let data = command_data(
+            "config",
+            vec![option(
+                "sanctions",
+                CommandOptionValue::SubCommandGroup(vec![option(
+                    "dm",
+                    CommandOptionValue::SubCommand(vec![
+                        option("enabled", CommandOptionValue::Boolean(false)),
+                        option("sanction", CommandOptionValue::String("ban".to_owned())),
+                    ]),
+                )]),
+            )],
+        );
+
+        let (path, options) = CommandPath::resolve(&data);
+        assert_eq!(path.route(), ("config", Some("sanctions"), Some("dm")));
+        assert_eq!(path.to_string(), "config sanctions dm");
+        assert_eq!(
+            options.iter().map(|o| &*o.name).collect::<Vec<_>>(),
+            ["enabled", "sanction"]
+        );
+
+        // Subcommands without options.
+        let data = command_data(
+            "lockdown",
+            vec![option("engage", CommandOptionValue::SubCommand(Vec::new()))],
+        );
+        let (path, options) = CommandPath::resolve(&data);
+        assert_eq!(path.route(), ("lockdown", None, Some("engage")));
+        assert!(options.is_empty());
+    }
+
+    #[test]
+    fn test_command_path_fixture() {
+        let builder = fixture::guild_command("config")
+            .subcommand(Some("sanctions"), "dm")
+            .option_bool("enabled", false);
+
+        let data = match builder.clone().interaction().data {
+            Some(InteractionData::ApplicationCommand(data)) => data,
+            _ => panic!("expected application command data"),
+        };
+        let (path, options) = CommandPath::resolve(&data);
+        assert_eq!(path.route(), ("config", Some("sanctions"), Some("dm")));
+        assert_eq!(options.len(), 1);
+
+        let (command, _) = builder.parse::<ConfigCommand>();
+        assert!(matches!(
+            command,
+            ConfigCommand::Sanctions(SanctionsConfigCommand::Dm(_))
+        ));
+    }
+
+    #[test]
+    fn test_partial_member() {
+        let interaction: Interaction = serde_json::from_str(MISSING_MEMBER).unwrap();
+        let user = interaction_user(&interaction).unwrap();
+        let joined_at = Timestamp::from_secs(1_635_066_765).unwrap();
+
+        let member = partial_member(&user, joined_at);
+        assert_eq!(member.user.map(|user| user.id), Some(user.id));
+        assert!(member.roles.is_empty());
+        assert_eq!(member.permissions, None);
+    }
+}
@@ -4,45 +4,126 @@
 //! all other `raidprotect`-prefixed crates.
 //!
 //! ## Crates structure
+//! - `api`: dashboard API server
 //! - `cache`: custom cache that store Discord objects
+//! - `cli`: command-line flags and configuration checks
+//! - `database`: database access with fallback on the cache
 //! - `event`: Discord event handlers
+//! - `health`: health-check HTTP server
 //! - `interaction`: interaction handlers
 //! - `model`: models shared between crates
+//! - `presence`: bot presence and guild count across shards
+//! - `scheduler`: execution of scheduled jobs
 //! - `util`: contain utilities such as logging and shutdown
 
+mod api;
+mod cli;
 mod cluster;
+mod database;
 mod event;
 mod feature;
+mod health;
 mod interaction;
+mod presence;
+mod scheduler;
+#[cfg(test)]
+mod test_utils;
 mod util;
 
 use anyhow::{Context, Result};
-use raidprotect_model::config::{parse_config, BotConfig};
+use clap::Parser;
+use metrics_exporter_prometheus::PrometheusBuilder;
+use raidprotect_model::config::parse_bot_config;
 use tracing::{debug, info};
 
-use crate::util::shutdown::{wait_shutdown, Shutdown};
+use crate::{
+    cli::Cli,
+    util::shutdown::{wait_shutdown, Shutdown},
+};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let config = parse_config::<BotConfig>().context("failed to load configuration")?;
-    let log_config = config.log.clone();
-    let _guard = log_config.init("raidprotect");
+    let cli = Cli::parse();
+    let config = parse_bot_config(cli.config.as_deref(), &cli.overrides)
+        .context("failed to load configuration")?;
+
+    if cli.check_config {
+        println!("{config:#?}");
+        return cli::check_config(&config).await;
+    }
+
+    let health_address = config.health_address;
+    let consumer_only = config.consumer_only;
+    let api_address = config.api_address;
+    let api_settings = api::ApiSettings::from_config(&config);
+    let _guard = config.log.init("raidprotect");
+
+    debug!(config = ?config, "loaded configuration");
+    translations::check();
+
+    // Start the metrics exporter
+    if let Some(address) = config.metrics_address {
+        PrometheusBuilder::new()
+            .with_http_listener(address)
+            .install()
+            .context("failed to start metrics exporter")?;
+    }
 
     // Initialize shard cluster
     let shutdown = Shutdown::new();
-    let cluster = cluster::ShardCluster::new(config)
+    let cluster = cluster::ShardCluster::new(&config)
         .await
         .context("failed to start shard cluster")?;
 
-    // Start the shard cluster
-    let cluster_run = tokio::spawn(cluster.start(shutdown.subscriber()));
-    info!("started shard cluster");
+    // Start the jobs scheduler, the database write queue flusher and the
+    // debug guilds refresh
+    tokio::spawn(scheduler::run(cluster.state(), shutdown.subscriber()));
+    tokio::spawn(database::run_flusher(
+        cluster.state(),
+        shutdown.subscriber(),
+    ));
+    tokio::spawn(util::log::run_debug_guilds_refresh(
+        cluster.state(),
+        shutdown.subscriber(),
+    ));
+
+    // Start the health-check server
+    if let Some(address) = health_address {
+        let gateway = (!consumer_only).then(|| cluster.gateway());
+        tokio::spawn(health::run(
+            address,
+            gateway,
+            cluster.state(),
+            shutdown.subscriber(),
+        ));
+    }
+
+    // Start the dashboard API server
+    if let Some(address) = api_address {
+        tokio::spawn(api::run(
+            address,
+            api_settings,
+            cluster.state(),
+            shutdown.subscriber(),
+        ));
+    }
+
+    // Start the shard cluster, unless only the consumers are run
+    if consumer_only {
+        info!("running in consumer-only mode, the shards are not started");
 
-    // Wait for shutdown
-    tokio::select! {
-        _ = cluster_run => (),
-        _ = wait_shutdown() => debug!("shutdown signal received")
-    };
+        wait_shutdown().await;
+        debug!("shutdown signal received");
+    } else {
+        let cluster_run = tokio::spawn(cluster.start(shutdown.subscriber()));
+        info!("started shard cluster");
+
+        // Wait for shutdown
+        tokio::select! {
+            _ = cluster_run => (),
+            _ = wait_shutdown() => debug!("shutdown signal received")
+        };
+    }
 
     info!("shutting down ...");
     shutdown.shutdown(5).await;
@@ -62,6 +143,38 @@ mod translations {
 
     rosetta_i18n::include_translations!();
 
+    use tracing::{info, warn};
+
+    /// Report of the translations consistency, generated by the build script.
+    mod report {
+        include!(concat!(env!("OUT_DIR"), "/translations_report.rs"));
+    }
+
+    /// Check the consistency of the translations.
+    ///
+    /// Every translation used by the bot (error responses, embeds, commands
+    /// metadata) is a generated method, so checking all the keys covers every
+    /// key referenced in the code. Keys missing in a language silently fall back
+    /// to French, so they are reported in a single aggregated warning instead
+    /// of failing at first use.
+    pub fn check() {
+        if report::MISSING_KEYS.is_empty() && report::MISMATCHED_KEYS.is_empty() {
+            info!("all translations are complete");
+            return;
+        }
+
+        let mut missing = std::collections::BTreeMap::<_, Vec<_>>::new();
+        for (key, lang) in report::MISSING_KEYS {
+            missing.entry(*lang).or_default().push(*key);
+        }
+
+        warn!(
+            missing = ?missing,
+            mismatched = ?report::MISMATCHED_KEYS,
+            "some translations are missing or inconsistent"
+        );
+    }
+
     impl Lang {
         /// Default language used when the user language is not supported.
         pub const DEFAULT: Self = Self::En;
@@ -81,4 +194,66 @@ mod translations {
             }
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use std::collections::BTreeMap;
+
+        use super::*;
+
+        /// Sources of the translations.
+        const SOURCES: &[(&str, &str)] = &[
+            ("fr", include_str!("../locales/fr.json")),
+            ("en", include_str!("../locales/en.json")),
+        ];
+
+        /// Get the parameters of a translation value.
+        fn parameters(value: &str) -> Vec<&str> {
+            value
+                .split('{')
+                .skip(1)
+                .filter_map(|part| part.split_once('}'))
+                .map(|(name, _)| name)
+                .collect()
+        }
+
+        #[test]
+        fn test_translations_complete() {
+            assert!(
+                report::MISSING_KEYS.is_empty(),
+                "missing translations: {:?}",
+                report::MISSING_KEYS
+            );
+            assert!(
+                report::MISMATCHED_KEYS.is_empty(),
+                "mismatched translations: {:?}",
+                report::MISMATCHED_KEYS
+            );
+        }
+
+        #[test]
+        fn test_format_every_key() {
+            let sources: Vec<(&str, BTreeMap<String, String>)> = SOURCES
+                .iter()
+                .map(|(lang, source)| (*lang, serde_json::from_str(source).unwrap()))
+                .collect();
+            let (_, fallback) = &sources[0];
+
+            for (lang, values) in &sources {
+                for (key, value) in values {
+                    // Arguments of the generated method are the parameters of
+                    // the fallback language.
+                    let mut formatted = value.clone();
+                    for parameter in parameters(&fallback[key]) {
+                        formatted = formatted.replace(&format!("{{{parameter}}}"), "dummy");
+                    }
+
+                    assert!(
+                        !formatted.contains('{') && !formatted.contains('}'),
+                        "invalid interpolation for {key} in {lang}: {formatted}"
+                    );
+                }
+            }
+        }
+    }
 }
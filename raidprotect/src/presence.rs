@@ -0,0 +1,241 @@
+//! Bot presence and guild count.
+//!
+//! Each shard tracks the guilds it handles from the gateway events, and
+//! periodically publishes its guild count in the cache (see
+//! [`raidprotect_model::cache::model::shard`]). The counts are aggregated
+//! across all the shards, which may run in other processes, to update the bot
+//! presence and the guild count metric.
+//!
+//! Until every shard has reported, the partial count is shown but is never
+//! lowered, so the presence does not flap while the shards start. The
+//! presence is only updated when the count changes.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use raidprotect_model::cache::model::shard::GuildCount;
+use tokio::time::interval;
+use tracing::warn;
+use twilight_gateway::Cluster;
+use twilight_model::{
+    gateway::{
+        event::Event,
+        payload::outgoing::UpdatePresence,
+        presence::{Activity, ActivityType, MinimalActivity, Status},
+    },
+    id::{marker::GuildMarker, Id},
+};
+
+use crate::{cluster::ClusterState, translations::Lang, util::template};
+
+/// Interval between two guild count updates.
+///
+/// Discord limits the number of presence updates per shard. The presence is
+/// updated at most once per interval, and only if the guild count changed.
+const COUNT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Duration (in seconds) after which the published count of a shard expires.
+const COUNT_EXPIRES: usize = 3 * 60;
+
+/// Activity shown in the bot presence.
+#[derive(Debug, Clone)]
+pub enum ActivityTemplate {
+    /// Custom activity, with an optional `{guilds}` placeholder.
+    Custom(String),
+    /// Localized guild count.
+    GuildCount(Lang),
+}
+
+impl ActivityTemplate {
+    /// Whether the activity depends on the guild count.
+    fn shows_count(&self) -> bool {
+        match self {
+            ActivityTemplate::Custom(template) => template::variables(template).contains(&"guilds"),
+            ActivityTemplate::GuildCount(_) => true,
+        }
+    }
+
+    /// Format the activity with the guild count.
+    fn format(&self, guild_count: u64) -> String {
+        match self {
+            ActivityTemplate::Custom(template) => {
+                let count = guild_count.to_string();
+
+                template::render(template, |name| (name == "guilds").then(|| &*count)).text
+            }
+            ActivityTemplate::GuildCount(lang) => lang.presence_guilds(guild_count),
+        }
+    }
+
+    /// Get the bot activity with the guild count.
+    pub fn activity(&self, guild_count: u64) -> Activity {
+        MinimalActivity {
+            kind: ActivityType::Watching,
+            name: self.format(guild_count),
+            url: None,
+        }
+        .into()
+    }
+}
+
+/// Guilds handled by each shard of the cluster.
+///
+/// Shards are only tracked once their `READY` event has been received, since
+/// it contains the full list of guilds of the shard.
+#[derive(Debug, Clone, Default)]
+pub struct ShardGuilds {
+    shards: Arc<Mutex<HashMap<u64, HashSet<Id<GuildMarker>>>>>,
+}
+
+impl ShardGuilds {
+    /// Update the guilds of a shard from a gateway event.
+    pub fn update(&self, shard_id: u64, event: &Event) {
+        match event {
+            Event::Ready(ready) => {
+                let guilds = ready.guilds.iter().map(|guild| guild.id).collect();
+                self.lock().insert(shard_id, guilds);
+            }
+            Event::GuildCreate(guild) => {
+                if let Some(guilds) = self.lock().get_mut(&shard_id) {
+                    guilds.insert(guild.id);
+                }
+            }
+            Event::GuildDelete(guild) if !guild.unavailable => {
+                if let Some(guilds) = self.lock().get_mut(&shard_id) {
+                    guilds.remove(&guild.id);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Get the guild count of each tracked shard.
+    fn counts(&self) -> Vec<(u64, u64)> {
+        self.lock()
+            .iter()
+            .map(|(shard_id, guilds)| (*shard_id, guilds.len() as u64))
+            .collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<u64, HashSet<Id<GuildMarker>>>> {
+        self.shards
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+/// Publish the guild count of the shards and update the bot presence.
+pub async fn run(
+    cluster: Arc<Cluster>,
+    state: ClusterState,
+    guilds: ShardGuilds,
+    template: ActivityTemplate,
+    initial: u64,
+) {
+    let mut shown = initial;
+    let mut interval = interval(COUNT_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        for (shard_id, count) in guilds.counts() {
+            if let Err(error) = state
+                .cache
+                .publish_shard_guilds(shard_id, count, COUNT_EXPIRES)
+                .await
+            {
+                warn!(error = ?error, shard = shard_id, "failed to publish shard guild count");
+            }
+        }
+
+        let count = match state.cache.shard_guild_count(state.shard_count()).await {
+            Ok(count) => count,
+            Err(error) => {
+                warn!(error = ?error, "failed to get guild count");
+                continue;
+            }
+        };
+
+        metrics::gauge!("raidprotect_guilds", count.guilds as f64);
+        metrics::gauge!("raidprotect_shards_reported", count.reported as f64);
+
+        if !template.shows_count() || !should_update(shown, count) {
+            continue;
+        }
+
+        let activities = vec![template.activity(count.guilds)];
+        let payload = match UpdatePresence::new(activities, false, None, Status::Online) {
+            Ok(payload) => payload,
+            Err(error) => {
+                warn!(error = ?error, "invalid presence");
+                continue;
+            }
+        };
+
+        for shard in cluster.shards() {
+            if let Err(error) = shard.command(&payload).await {
+                warn!(error = ?error, "failed to update shard presence");
+            }
+        }
+
+        shown = count.guilds;
+    }
+}
+
+/// Whether the presence should be updated with a new guild count.
+///
+/// The presence is only updated when the count changes. A partial count
+/// never replaces a higher count, so the presence does not drop while the
+/// shards are starting.
+fn should_update(shown: u64, count: GuildCount) -> bool {
+    match count.is_complete() {
+        true => count.guilds != shown,
+        false => count.guilds > shown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_activity() {
+        let template = ActivityTemplate::Custom("{guilds} servers".to_owned());
+        assert!(template.shows_count());
+        assert_eq!(template.format(42), "42 servers");
+
+        let template = ActivityTemplate::Custom("raidprotect.org".to_owned());
+        assert!(!template.shows_count());
+        assert_eq!(template.format(42), "raidprotect.org");
+
+        let template = ActivityTemplate::GuildCount(Lang::En);
+        assert!(template.format(42).contains("42"));
+    }
+
+    #[test]
+    fn test_should_update() {
+        let partial = |guilds| GuildCount {
+            guilds,
+            reported: 1,
+            shards: 2,
+        };
+        let complete = |guilds| GuildCount {
+            guilds,
+            reported: 2,
+            shards: 2,
+        };
+
+        assert!(!should_update(10, complete(10)));
+        assert!(!should_update(10, partial(10)));
+
+        // Partial counts only increase the shown count.
+        assert!(should_update(10, partial(20)));
+        assert!(!should_update(20, partial(10)));
+
+        // Complete counts are always shown.
+        assert!(should_update(20, complete(15)));
+    }
+}
@@ -0,0 +1,249 @@
+//! Scheduled jobs execution.
+//!
+//! The scheduler periodically polls the cache for jobs that are due and
+//! executes them in background tasks. Jobs are stored in Redis (see
+//! [`ScheduledJob`]), so they are not lost when the bot restarts.
+//!
+//! To schedule a new job, use [`CacheClient::schedule_job`].
+//!
+//! When several instances of the bot are running, a single instance claims the
+//! due jobs at a time, using a [`DistributedLock`]. Each job is still removed
+//! from the cache when it is claimed, so that it cannot be executed twice if
+//! the lock expires during a claim.
+//!
+//! [`CacheClient::schedule_job`]: raidprotect_model::cache::CacheClient::schedule_job
+
+use std::{slice, time::Duration};
+
+use raidprotect_model::cache::model::{job::ScheduledJob, lock::DistributedLock};
+use time::OffsetDateTime;
+use tracing::{error, info, instrument, trace, warn};
+use twilight_model::id::{
+    marker::{ChannelMarker, GuildMarker, MessageMarker},
+    Id,
+};
+
+use crate::{
+    cluster::ClusterState,
+    feature,
+    interaction::embed::{EmbedBuilder, COLOR_TRANSPARENT},
+    util::shutdown::ShutdownSubscriber,
+};
+
+/// Interval between two polls of the scheduled jobs.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Maximum number of jobs claimed at each poll.
+const BATCH_SIZE: isize = 100;
+
+/// Expiration of the lock held while claiming jobs.
+const CLAIM_LOCK_TTL: time::Duration = time::Duration::seconds(10);
+
+/// Number of guilds a broadcast is sent to in a single batch.
+const BROADCAST_BATCH_SIZE: i64 = 20;
+
+/// Delay between two batches of a broadcast.
+const BROADCAST_INTERVAL: time::Duration = time::Duration::seconds(10);
+
+/// Delay before the first run of the periodic jobs after the bot starts.
+///
+/// This leaves time for the guilds to be received from the gateway.
+const PERIODIC_STARTUP_DELAY: time::Duration = time::Duration::minutes(10);
+
+/// Run the scheduler until a shutdown signal is received.
+#[instrument(name = "scheduler", skip_all)]
+pub async fn run(state: ClusterState, mut shutdown: ShutdownSubscriber) {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+    // Periodic jobs reschedule themselves, the first run is only scheduled
+    // if no run is already pending.
+    let at = OffsetDateTime::now_utc() + PERIODIC_STARTUP_DELAY;
+    for job in [
+        ScheduledJob::StructureSnapshots { cursor: None },
+        ScheduledJob::ModlogCleanup { cursor: None },
+    ] {
+        if let Err(error) = state.cache.schedule_job_if_absent(&job, at).await {
+            error!(error = ?error, job = ?job, "failed to schedule periodic job");
+        }
+    }
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {},
+            _ = shutdown.wait_shutdown() => break,
+        };
+
+        // Jobs are claimed by another instance if the lock is not acquired.
+        let lock = match DistributedLock::acquire(&state.cache, "scheduler", CLAIM_LOCK_TTL).await {
+            Ok(Some(lock)) => lock,
+            Ok(None) => continue,
+            Err(error) => {
+                error!(error = ?error, "failed to acquire scheduler lock");
+                continue;
+            }
+        };
+
+        let jobs = state
+            .cache
+            .claim_due_jobs(OffsetDateTime::now_utc(), BATCH_SIZE)
+            .await;
+
+        if let Err(error) = lock.release().await {
+            warn!(error = ?error, "failed to release scheduler lock");
+        }
+
+        let jobs = match jobs {
+            Ok(jobs) => jobs,
+            Err(error) => {
+                error!(error = ?error, "failed to claim scheduled jobs");
+                continue;
+            }
+        };
+
+        for job in jobs {
+            trace!(job = ?job, "executing scheduled job");
+
+            let state = state.clone();
+            tokio::spawn(async move { execute(job, &state).await });
+        }
+    }
+}
+
+/// Execute a scheduled job.
+async fn execute(job: ScheduledJob, state: &ClusterState) {
+    let result = match &job {
+        ScheduledJob::DeleteMessage {
+            channel_id,
+            message_id,
+        } => delete_message(*channel_id, *message_id, state).await,
+        ScheduledJob::Broadcast {
+            title,
+            message,
+            cursor,
+        } => broadcast(title, message, *cursor, state).await,
+        ScheduledJob::Unmute { guild_id, user_id } => {
+            feature::sanction::expire_mute(state, *guild_id, *user_id).await
+        }
+        ScheduledJob::StructureSnapshots { cursor } => {
+            feature::restore::snapshot_batch(state, *cursor).await
+        }
+        ScheduledJob::ModlogCleanup { cursor } => {
+            feature::retention::cleanup_batch(state, *cursor).await
+        }
+        ScheduledJob::WebhookDelivery {
+            guild_id,
+            payload,
+            attempt,
+        } => feature::webhook::deliver(state, *guild_id, payload, *attempt).await,
+        ScheduledJob::WatchExpire { guild_id, user_id } => {
+            feature::watch::expire(state, *guild_id, *user_id).await
+        }
+        ScheduledJob::SyncBan {
+            guild_id,
+            user,
+            moderator,
+            reason,
+            origin,
+        } => {
+            feature::ban_sync::apply(
+                state,
+                *guild_id,
+                user,
+                moderator,
+                reason.as_deref(),
+                *origin,
+            )
+            .await
+        }
+    };
+
+    if let Err(error) = result {
+        error!(error = ?error, job = ?job, "failed to execute scheduled job");
+    }
+}
+
+/// Delete a message sent by the bot.
+async fn delete_message(
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+    state: &ClusterState,
+) -> Result<(), anyhow::Error> {
+    state
+        .http
+        .delete_message(channel_id, message_id)
+        .exec()
+        .await?;
+
+    Ok(())
+}
+
+/// Send a batch of a broadcast.
+///
+/// The job for the next batch is scheduled before sending the messages, so
+/// that a restart of the bot never sends the announcement twice to the same
+/// guild.
+async fn broadcast(
+    title: &str,
+    message: &str,
+    cursor: Option<Id<GuildMarker>>,
+    state: &ClusterState,
+) -> Result<(), anyhow::Error> {
+    let mut guilds = Vec::new();
+    let mut results = state
+        .database
+        .find_guilds_with_logs(cursor, BROADCAST_BATCH_SIZE)
+        .await?;
+
+    while results.advance().await? {
+        guilds.push(results.deserialize_current()?);
+    }
+
+    let last = match guilds.last() {
+        Some(guild) => guild.id,
+        None => {
+            info!("broadcast completed");
+            return Ok(());
+        }
+    };
+
+    let next = ScheduledJob::Broadcast {
+        title: title.to_owned(),
+        message: message.to_owned(),
+        cursor: Some(last),
+    };
+    state
+        .cache
+        .schedule_job(&next, OffsetDateTime::now_utc() + BROADCAST_INTERVAL)
+        .await?;
+
+    let embed = EmbedBuilder::new()
+        .color(COLOR_TRANSPARENT)
+        .title(title)
+        .description(message)
+        .build();
+
+    for guild in guilds {
+        let channel = match guild.logs_chan {
+            Some(channel) => channel,
+            None => continue,
+        };
+
+        let result = async {
+            state
+                .cache_http(guild.id)
+                .create_message(channel)
+                .await?
+                .embeds(slice::from_ref(&embed))?
+                .exec()
+                .await?;
+
+            Ok::<_, anyhow::Error>(())
+        };
+
+        if let Err(error) = result.await {
+            error!(error = ?error, guild = ?guild.id, "failed to send broadcast");
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+//! Assertions on the [`InteractionResponse`] returned by the handlers.
+//!
+//! Each function panics if the response is not of the expected variant, and
+//! returns its content otherwise.
+
+use twilight_model::{
+    application::component::Component, channel::embed::Embed,
+    http::interaction::InteractionResponseData,
+};
+
+use crate::interaction::response::InteractionResponse;
+
+/// Assert that the response is an ephemeral embed.
+#[track_caller]
+pub fn ephemeral_embed(response: &InteractionResponse) -> &Embed {
+    match response {
+        InteractionResponse::EphemeralEmbed(embed) => embed,
+        _ => panic!("expected ephemeral embed, got {response:?}"),
+    }
+}
+
+/// Assert that the response is a modal with the given custom id.
+///
+/// Returns the components of the modal.
+#[track_caller]
+pub fn modal<'a>(response: &'a InteractionResponse, expected_id: &str) -> &'a [Component] {
+    match response {
+        InteractionResponse::Modal {
+            custom_id,
+            components,
+            ..
+        } => {
+            assert_eq!(custom_id, expected_id);
+            components
+        }
+        _ => panic!("expected modal, got {response:?}"),
+    }
+}
+
+/// Assert that the response is an ephemeral deferred message.
+#[track_caller]
+pub fn ephemeral_deferred(response: &InteractionResponse) {
+    if !matches!(response, InteractionResponse::EphemeralDeferredMessage) {
+        panic!("expected ephemeral deferred message, got {response:?}");
+    }
+}
+
+/// Assert that the response is a raw response with data.
+#[track_caller]
+pub fn raw_data(response: &InteractionResponse) -> &InteractionResponseData {
+    match response {
+        InteractionResponse::Raw {
+            data: Some(data), ..
+        } => data,
+        _ => panic!("expected raw response with data, got {response:?}"),
+    }
+}
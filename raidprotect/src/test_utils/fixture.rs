@@ -0,0 +1,535 @@
+//! Interaction and modlog fixtures.
+//!
+//! The builders of this module create the [`Interaction`] received when a
+//! command is invoked in a guild, with the resolved data of its options:
+//!
+//! ```ignore
+//! let (command, ctx) = fixture::guild_command("kick")
+//!     .option_user("member", Id::new(10))
+//!     .invoker(member_with_perms(Permissions::KICK_MEMBERS))
+//!     .parse::<KickCommand>();
+//! ```
+//!
+//! Options of nested commands are added after [`CommandFixture::subcommand`]:
+//!
+//! ```ignore
+//! let (command, ctx) = fixture::guild_command("config")
+//!     .subcommand(Some("sanctions"), "dm")
+//!     .option_bool("enabled", false)
+//!     .parse::<ConfigCommand>();
+//! ```
+//!
+//! [`CommandFixture::prepare`] runs the same checks as when a command is
+//! received, and creates the context from the state. The Discord guild used
+//! by the handlers is stored in the cache with [`guild`]:
+//!
+//! ```ignore
+//! fixture::guild()
+//!     .role(MODERATOR_ROLE, 2, Permissions::KICK_MEMBERS)
+//!     .bot_roles([MODERATOR_ROLE])
+//!     .cache(&state.cache)
+//!     .await;
+//! ```
+//!
+//! Modlogs are created with [`modlog`], only the fields relevant to the test
+//! need to be set:
+//!
+//! ```ignore
+//! let modlog = fixture::modlog(ModlogType::Kick).reason("spam").build();
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use raidprotect_model::{
+    cache::discord::{CachedGuild, CachedRole, CurrentMember},
+    database::model::{Modlog, ModlogType, ModlogUser, ObjectId},
+    store::CacheStore,
+};
+use time::OffsetDateTime;
+use twilight_interactions::command::CommandModel;
+use twilight_model::{
+    application::{
+        command::CommandType,
+        interaction::{
+            application_command::{
+                CommandData, CommandDataOption, CommandInteractionDataResolved, CommandOptionValue,
+                InteractionMember,
+            },
+            Interaction, InteractionData, InteractionType,
+        },
+    },
+    guild::{PartialMember, Permissions},
+    id::{
+        marker::{ApplicationMarker, GuildMarker, InteractionMarker, RoleMarker, UserMarker},
+        Id,
+    },
+    user::User,
+    util::Timestamp,
+};
+
+use crate::{
+    cluster::HandlerState,
+    interaction::{
+        command::check_command,
+        response::{interaction_created_at, InteractionResponse},
+        util::{parse_command_data, GuildInteractionContext},
+    },
+    translations::Lang,
+};
+
+/// Id of the application that receives the interactions.
+pub const APPLICATION_ID: Id<ApplicationMarker> = Id::new(902214960584003614);
+
+/// Id of the guild the interactions are invoked in.
+pub const GUILD_ID: Id<GuildMarker> = Id::new(764128497018208297);
+
+/// Id of the built interactions.
+pub const INTERACTION_ID: Id<InteractionMarker> = Id::new(1041703299887448094);
+
+/// Id of the member that invokes the interactions by default.
+pub const INVOKER_ID: Id<UserMarker> = Id::new(233615937548369921);
+
+/// Id of the bot user.
+pub const BOT_ID: Id<UserMarker> = Id::new(902214960584003614);
+
+/// Id of the owner of [`GUILD_ID`].
+pub const OWNER_ID: Id<UserMarker> = Id::new(166214584391286785);
+
+/// Create a builder of a command invoked in [`GUILD_ID`].
+///
+/// The command is invoked by a member without permissions, use
+/// [`CommandFixture::invoker`] to change it.
+pub fn guild_command(name: &str) -> CommandFixture {
+    CommandFixture {
+        name: name.to_owned(),
+        group: None,
+        subcommand: None,
+        options: Vec::new(),
+        resolved: CommandInteractionDataResolved {
+            attachments: HashMap::new(),
+            channels: HashMap::new(),
+            members: HashMap::new(),
+            messages: HashMap::new(),
+            roles: HashMap::new(),
+            users: HashMap::new(),
+        },
+        invoker: member_with_perms(Permissions::empty()),
+    }
+}
+
+/// Create a member with the given permissions.
+pub fn member_with_perms(permissions: Permissions) -> MemberFixture {
+    MemberFixture {
+        id: INVOKER_ID,
+        roles: Vec::new(),
+        permissions,
+        premium_since: None,
+    }
+}
+
+/// Builder of a guild command [`Interaction`].
+#[derive(Debug, Clone)]
+pub struct CommandFixture {
+    name: String,
+    group: Option<String>,
+    subcommand: Option<String>,
+    options: Vec<CommandDataOption>,
+    resolved: CommandInteractionDataResolved,
+    invoker: MemberFixture,
+}
+
+impl CommandFixture {
+    /// Add a user option.
+    ///
+    /// The user is resolved as a member of the guild without roles.
+    pub fn option_user(self, name: &str, id: Id<UserMarker>) -> Self {
+        let member = MemberFixture {
+            id,
+            roles: Vec::new(),
+            permissions: Permissions::empty(),
+            premium_since: None,
+        };
+
+        self.option_member(name, member)
+    }
+
+    /// Add a user option resolved as the given member.
+    pub fn option_member(mut self, name: &str, member: MemberFixture) -> Self {
+        let id = member.id;
+
+        self.resolved.users.insert(id, member.user());
+        self.resolved.members.insert(id, member.resolved());
+
+        self.option(name, CommandOptionValue::User(id))
+    }
+
+    /// Add a role option.
+    pub fn option_role(self, name: &str, id: Id<RoleMarker>) -> Self {
+        self.option(name, CommandOptionValue::Role(id))
+    }
+
+    /// Add a string option.
+    pub fn option_string(self, name: &str, value: &str) -> Self {
+        self.option(name, CommandOptionValue::String(value.to_owned()))
+    }
+
+    /// Add a boolean option.
+    pub fn option_bool(self, name: &str, value: bool) -> Self {
+        self.option(name, CommandOptionValue::Boolean(value))
+    }
+
+    /// Invoke a subcommand, optionally nested in a subcommand group.
+    ///
+    /// The options are sent as options of the subcommand.
+    pub fn subcommand(mut self, group: Option<&str>, name: &str) -> Self {
+        self.group = group.map(ToOwned::to_owned);
+        self.subcommand = Some(name.to_owned());
+        self
+    }
+
+    /// Set the member that invokes the command.
+    pub fn invoker(mut self, member: MemberFixture) -> Self {
+        self.invoker = member;
+        self
+    }
+
+    /// Build the [`Interaction`].
+    pub fn interaction(self) -> Interaction {
+        let mut options = self.options;
+
+        if let Some(subcommand) = self.subcommand {
+            options = vec![CommandDataOption {
+                name: subcommand,
+                value: CommandOptionValue::SubCommand(options),
+            }];
+        }
+
+        if let Some(group) = self.group {
+            options = vec![CommandDataOption {
+                name: group,
+                value: CommandOptionValue::SubCommandGroup(options),
+            }];
+        }
+
+        let data = CommandData {
+            guild_id: Some(GUILD_ID),
+            id: Id::new(951887307426353163),
+            name: self.name,
+            kind: CommandType::ChatInput,
+            options,
+            resolved: Some(self.resolved),
+            target_id: None,
+        };
+
+        Interaction {
+            app_permissions: Some(Permissions::all()),
+            application_id: APPLICATION_ID,
+            channel_id: Some(Id::new(1041703217402318879)),
+            data: Some(InteractionData::ApplicationCommand(Box::new(data))),
+            guild_id: Some(GUILD_ID),
+            guild_locale: Some("en-US".to_owned()),
+            id: INTERACTION_ID,
+            kind: InteractionType::ApplicationCommand,
+            locale: Some("en-US".to_owned()),
+            member: Some(self.invoker.partial()),
+            message: None,
+            token: "aW50ZXJhY3Rpb246MTA0MTcwMzI5OTg4NzQ0ODA5NA".to_owned(),
+            user: None,
+        }
+    }
+
+    /// Build the interaction and parse it into the command model `T`.
+    ///
+    /// The returned context is created without accessing the cache, and uses
+    /// the default lang.
+    pub fn parse<T: CommandModel>(self) -> (T, GuildInteractionContext) {
+        let invoker = self.invoker.clone();
+        let mut interaction = self.interaction();
+        let command = parse_command_data::<T>(&mut interaction).unwrap();
+
+        let ctx = GuildInteractionContext {
+            author: invoker.user(),
+            member: invoker.partial(),
+            partial: false,
+            lang: Lang::DEFAULT,
+            public_lang: Lang::DEFAULT,
+            guild_id: GUILD_ID,
+            created_at: interaction_created_at(interaction.id),
+            interaction,
+        };
+
+        (command, ctx)
+    }
+
+    /// Build the interaction and prepare it like a received command.
+    ///
+    /// The command checks are run (see [`check_command`]), and the context is
+    /// created from the state. The error response is returned if the command
+    /// cannot be run.
+    pub async fn prepare<T: CommandModel>(
+        self,
+        state: &impl HandlerState,
+    ) -> Result<(T, GuildInteractionContext), InteractionResponse> {
+        let name = self.name.clone();
+        let mut interaction = self.interaction();
+
+        if let Some(response) = check_command(&interaction, &name, state).await.unwrap() {
+            return Err(response);
+        }
+
+        let command = parse_command_data::<T>(&mut interaction).unwrap();
+        let ctx = GuildInteractionContext::new(interaction, state)
+            .await
+            .unwrap();
+
+        Ok((command, ctx))
+    }
+
+    fn option(mut self, name: &str, value: CommandOptionValue) -> Self {
+        self.options.push(CommandDataOption {
+            name: name.to_owned(),
+            value,
+        });
+        self
+    }
+}
+
+/// Member of the guild used in the fixtures.
+#[derive(Debug, Clone)]
+pub struct MemberFixture {
+    pub id: Id<UserMarker>,
+    pub roles: Vec<Id<RoleMarker>>,
+    pub permissions: Permissions,
+    pub premium_since: Option<Timestamp>,
+}
+
+impl MemberFixture {
+    /// Set the roles of the member.
+    pub fn roles(mut self, roles: impl IntoIterator<Item = Id<RoleMarker>>) -> Self {
+        self.roles = roles.into_iter().collect();
+        self
+    }
+
+    fn user(&self) -> User {
+        User {
+            accent_color: None,
+            avatar: None,
+            banner: None,
+            bot: false,
+            discriminator: 4242,
+            email: None,
+            flags: None,
+            id: self.id,
+            locale: None,
+            mfa_enabled: None,
+            name: format!("user-{}", self.id),
+            premium_type: None,
+            public_flags: None,
+            system: None,
+            verified: None,
+        }
+    }
+
+    /// Build the [`PartialMember`] of the member.
+    pub fn partial(&self) -> PartialMember {
+        PartialMember {
+            avatar: None,
+            communication_disabled_until: None,
+            deaf: false,
+            joined_at: joined_at(),
+            mute: false,
+            nick: None,
+            permissions: Some(self.permissions),
+            premium_since: self.premium_since,
+            roles: self.roles.clone(),
+            user: Some(self.user()),
+        }
+    }
+
+    fn resolved(&self) -> InteractionMember {
+        InteractionMember {
+            avatar: None,
+            communication_disabled_until: None,
+            joined_at: joined_at(),
+            nick: None,
+            pending: false,
+            permissions: self.permissions,
+            premium_since: self.premium_since,
+            roles: self.roles.clone(),
+        }
+    }
+}
+
+/// Create a builder of the cached [`GUILD_ID`] guild.
+///
+/// The guild is owned by [`OWNER_ID`], and only has the `@everyone` role
+/// without permissions. The bot member has no roles.
+pub fn guild() -> GuildFixture {
+    GuildFixture {
+        roles: vec![cached_role(GUILD_ID.cast(), 0, Permissions::empty())],
+        bot_roles: Vec::new(),
+    }
+}
+
+/// Builder of the cached guild and its roles.
+#[derive(Debug, Clone)]
+pub struct GuildFixture {
+    roles: Vec<CachedRole>,
+    bot_roles: Vec<Id<RoleMarker>>,
+}
+
+impl GuildFixture {
+    /// Add a role at the given position.
+    pub fn role(mut self, id: Id<RoleMarker>, position: i64, permissions: Permissions) -> Self {
+        self.roles.push(cached_role(id, position, permissions));
+        self
+    }
+
+    /// Set the roles of the bot member.
+    pub fn bot_roles(mut self, roles: impl IntoIterator<Item = Id<RoleMarker>>) -> Self {
+        self.bot_roles = roles.into_iter().collect();
+        self
+    }
+
+    /// Store the guild and its roles in the cache.
+    pub async fn cache(self, cache: &impl CacheStore) {
+        let guild = CachedGuild {
+            id: GUILD_ID,
+            unavailable: false,
+            name: "guild".to_owned(),
+            icon: None,
+            owner_id: OWNER_ID,
+            member_count: 3,
+            current_member: Some(CurrentMember {
+                id: BOT_ID,
+                communication_disabled_until: None,
+                roles: self.bot_roles.into_iter().collect(),
+            }),
+            roles: self.roles.iter().map(|role| role.id).collect(),
+            channels: HashSet::new(),
+        };
+
+        for role in &self.roles {
+            cache.set(role).await.unwrap();
+        }
+        cache.set(&guild).await.unwrap();
+    }
+}
+
+fn cached_role(id: Id<RoleMarker>, position: i64, permissions: Permissions) -> CachedRole {
+    CachedRole {
+        id,
+        guild_id: GUILD_ID,
+        name: format!("role-{id}"),
+        color: 0,
+        icon: None,
+        unicode_emoji: None,
+        position,
+        permissions,
+        managed: false,
+    }
+}
+
+/// Create a builder of a modlog of the given kind.
+///
+/// The modlog is created in [`GUILD_ID`] by the sanctioned user itself, use
+/// [`ModlogFixture::moderator`] to change it.
+pub fn modlog(kind: ModlogType) -> ModlogFixture {
+    let user = modlog_user(1, "user");
+
+    ModlogFixture {
+        modlog: Modlog {
+            id: None,
+            kind,
+            guild_id: GUILD_ID,
+            user: user.clone(),
+            moderator: user,
+            date: OffsetDateTime::from_unix_timestamp(1_628_594_197).unwrap(),
+            reason: None,
+            notes: None,
+            pre_emptive: false,
+            confirmation_skipped: false,
+            shadow: false,
+            dm_disabled: false,
+            dm_status: None,
+            synced_from: None,
+            amendments: Vec::new(),
+        },
+    }
+}
+
+/// Create a user of a modlog without avatar.
+pub fn modlog_user(id: u64, name: &str) -> ModlogUser {
+    ModlogUser {
+        id: Id::new(id),
+        name: name.to_owned(),
+        discriminator: 1234,
+        avatar: None,
+    }
+}
+
+/// Builder of a [`Modlog`].
+#[derive(Debug, Clone)]
+pub struct ModlogFixture {
+    modlog: Modlog,
+}
+
+impl ModlogFixture {
+    /// Build the [`Modlog`].
+    pub fn build(self) -> Modlog {
+        self.modlog
+    }
+
+    /// Set the id of the modlog, as if it was stored in the database.
+    pub fn id(mut self, id: ObjectId) -> Self {
+        self.modlog.id = Some(id);
+        self
+    }
+
+    /// Set the guild of the modlog.
+    pub fn guild(mut self, guild_id: Id<GuildMarker>) -> Self {
+        self.modlog.guild_id = guild_id;
+        self
+    }
+
+    /// Set the sanctioned user.
+    pub fn user(mut self, user: ModlogUser) -> Self {
+        self.modlog.user = user;
+        self
+    }
+
+    /// Set the moderator that applied the sanction.
+    pub fn moderator(mut self, moderator: ModlogUser) -> Self {
+        self.modlog.moderator = moderator;
+        self
+    }
+
+    /// Set the date of the sanction from a unix timestamp.
+    pub fn date(mut self, timestamp: i64) -> Self {
+        self.modlog.date = OffsetDateTime::from_unix_timestamp(timestamp).unwrap();
+        self
+    }
+
+    /// Set the reason of the sanction.
+    pub fn reason(mut self, reason: &str) -> Self {
+        self.modlog.reason = Some(reason.to_owned());
+        self
+    }
+
+    /// Set the internal notes of the sanction.
+    pub fn notes(mut self, notes: &str) -> Self {
+        self.modlog.notes = Some(notes.to_owned());
+        self
+    }
+
+    /// Mark the sanction as a shadow sanction.
+    pub fn shadow(mut self) -> Self {
+        self.modlog.shadow = true;
+        self
+    }
+}
+
+/// Date at which the members of the fixtures joined the guild.
+pub fn joined_at() -> Timestamp {
+    Timestamp::from_secs(1_635_066_765).unwrap()
+}
@@ -0,0 +1,17 @@
+//! Utilities to test the interaction handlers.
+//!
+//! Handlers that take a `&impl HandlerState` can be run end-to-end without a
+//! Discord connection, a MongoDB database or a Redis server:
+//!
+//! - [`fixture`] builds the interactions received from Discord and the
+//!   modlogs stored in the database.
+//! - [`MockClusterState`] replaces the database and the cache with in-memory
+//!   stores. It can also connect to MongoDB and Redis, to run the same
+//!   handler tests against both implementations.
+//! - [`assert`] checks the responses returned by the handlers.
+
+pub mod assert;
+pub mod fixture;
+mod state;
+
+pub use state::MockClusterState;
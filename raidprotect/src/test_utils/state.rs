@@ -0,0 +1,114 @@
+//! Mock of the cluster state.
+
+use std::env;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use raidprotect_model::{
+    cache::{
+        discord::{
+            permission::{BotMemberUnknown, CacheMiss},
+            CachedGuild, CurrentMember,
+        },
+        CacheClient,
+    },
+    database::DbClient,
+    store::{
+        memory::{MemoryCacheStore, MemoryGuildStore, MemoryModlogStore},
+        CacheStore, GuildStore, ModlogStore,
+    },
+};
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+
+use crate::cluster::HandlerState;
+
+/// State used to run the handlers in tests.
+///
+/// By default, the database and the cache are replaced by in-memory stores,
+/// that can be inspected after the handler has run. The same handlers can be
+/// run against MongoDB and Redis with [`MockClusterState::connect`], to check
+/// that both stores behave the same.
+///
+/// Unlike [`ClusterState`], the bot member is never fetched from Discord: it
+/// must be set in the cached guild.
+///
+/// [`ClusterState`]: crate::cluster::ClusterState
+#[derive(Debug, Clone, Default)]
+pub struct MockClusterState<G = MemoryGuildStore, M = MemoryModlogStore, C = MemoryCacheStore> {
+    pub guilds: G,
+    pub modlogs: M,
+    pub cache: C,
+    pub operators: Vec<Id<UserMarker>>,
+}
+
+impl MockClusterState {
+    /// Initialize a new [`MockClusterState`] with empty stores.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl MockClusterState<DbClient, DbClient, CacheClient> {
+    /// Initialize a new [`MockClusterState`] connected to MongoDB and Redis.
+    ///
+    /// The servers uri can be set with the `MONGODB_URI` and `REDIS_URI`
+    /// environment variables. Tests that use this state must be ignored by
+    /// default.
+    pub async fn connect() -> Self {
+        let mongodb_uri =
+            env::var("MONGODB_URI").unwrap_or_else(|_| "mongodb://localhost:27017".to_owned());
+        let redis_uri =
+            env::var("REDIS_URI").unwrap_or_else(|_| "redis://localhost:6379".to_owned());
+
+        let database = DbClient::connect(&mongodb_uri, "raidprotect-test".to_owned())
+            .await
+            .unwrap();
+
+        Self {
+            guilds: database.clone(),
+            modlogs: database,
+            cache: CacheClient::connect(&redis_uri).await.unwrap(),
+            operators: Vec::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl<G, M, C> HandlerState for MockClusterState<G, M, C>
+where
+    G: GuildStore,
+    M: ModlogStore,
+    C: CacheStore,
+{
+    type Guilds = G;
+    type Modlogs = M;
+    type Cache = C;
+
+    fn guild_store(&self) -> &Self::Guilds {
+        &self.guilds
+    }
+
+    fn modlog_store(&self) -> &Self::Modlogs {
+        &self.modlogs
+    }
+
+    fn cache_store(&self) -> &Self::Cache {
+        &self.cache
+    }
+
+    fn is_operator(&self, user_id: Id<UserMarker>) -> bool {
+        self.operators.contains(&user_id)
+    }
+
+    async fn own_member(&self, guild_id: Id<GuildMarker>) -> Result<CurrentMember, anyhow::Error> {
+        self.cache
+            .get::<CachedGuild>(&guild_id)
+            .await?
+            .ok_or(CacheMiss("guild"))
+            .and_then(|guild| guild.current_member.ok_or(CacheMiss("current member")))
+            .context(BotMemberUnknown)
+    }
+}
@@ -0,0 +1,72 @@
+//! New accounts detection.
+//!
+//! Accounts created recently are often used to raid or spam guilds. They are
+//! marked in logs and reports so moderators can spot them quickly. The age
+//! below which an account is considered new is configured for each guild
+//! (see [`GuildConfig::new_account_threshold`]).
+//!
+//! [`GuildConfig::new_account_threshold`]: raidprotect_model::database::model::GuildConfig::new_account_threshold
+
+use std::time::Duration as StdDuration;
+
+use time::{Duration, OffsetDateTime};
+use twilight_model::id::{marker::UserMarker, Id};
+use twilight_util::snowflake::Snowflake;
+
+use super::time::{from_secs, relative};
+use crate::translations::Lang;
+
+/// Get the creation date of an account, in seconds.
+pub fn account_created_at(user: Id<UserMarker>) -> u64 {
+    StdDuration::from_millis(user.timestamp() as u64).as_secs()
+}
+
+/// Whether an account is considered new.
+///
+/// The `created_at` parameter is the account creation date, in seconds, and
+/// `threshold` is the age (in hours) below which accounts are new. A
+/// threshold of `0` disables the check.
+pub fn is_new_account(created_at: u64, threshold: u32, now: OffsetDateTime) -> bool {
+    if threshold == 0 {
+        return false;
+    }
+
+    let account_age = now.unix_timestamp() - created_at as i64;
+    account_age < Duration::hours(threshold.into()).whole_seconds()
+}
+
+/// Get the marker displayed next to new accounts.
+///
+/// Returns [`None`] if the account is not considered new (see
+/// [`is_new_account`]).
+pub fn new_account_marker(created_at: u64, threshold: u32, lang: Lang) -> Option<String> {
+    if !is_new_account(created_at, threshold, OffsetDateTime::now_utc()) {
+        return None;
+    }
+
+    Some(lang.new_account_marker(relative(from_secs(created_at))))
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{Duration, OffsetDateTime};
+
+    use super::is_new_account;
+
+    #[test]
+    fn test_is_new_account() {
+        let now = OffsetDateTime::from_unix_timestamp(1_000_000).unwrap();
+        let created_at = (now - Duration::hours(2)).unix_timestamp() as u64;
+
+        assert!(is_new_account(created_at, 24, now));
+        assert!(!is_new_account(created_at, 1, now));
+    }
+
+    #[test]
+    fn test_is_new_account_disabled() {
+        let now = OffsetDateTime::from_unix_timestamp(1_000_000).unwrap();
+        let created_at = now.unix_timestamp() as u64;
+
+        assert!(!is_new_account(created_at, 0, now));
+    }
+}
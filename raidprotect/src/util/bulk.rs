@@ -0,0 +1,527 @@
+//! Bulk HTTP operations.
+//!
+//! Some features send a large number of requests to the Discord API, such as
+//! bulk bans or messages cleanup. The [`run`] function executes these
+//! operations with a bounded concurrency and a shared strategy when the API
+//! responds with a `429 Too Many Requests` status.
+//!
+//! ## Rate limits
+//! When an operation is rate limited, the whole batch is paused for the
+//! duration indicated by Discord, not only the rate limited operation. The
+//! operations already in flight are awaited, and the rate limited operation is
+//! retried first once the pause ends. Operations are retried at most
+//! [`MAX_RETRIES`] times.
+//!
+//! ## Cancellation
+//! A batch can be cancelled with its [`CancelToken`]. The operations in flight
+//! are awaited, and the remaining ones are returned in the [`BulkReport`].
+//! Running batches started from an interaction are tracked in [`BulkTasks`],
+//! so that they can be cancelled from a component.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use futures_util::{stream::FuturesUnordered, StreamExt};
+use tokio::{
+    sync::Notify,
+    time::{sleep_until, Instant},
+};
+use tracing::debug;
+use twilight_http::{
+    api_error::{ApiError, RatelimitedApiError},
+    error::ErrorType,
+};
+use twilight_model::id::{marker::InteractionMarker, Id};
+
+/// Maximum number of times an operation is retried after a rate limit.
+pub const MAX_RETRIES: u32 = 5;
+
+/// Pause applied when a rate limit does not indicate its duration.
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// Options of a bulk operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BulkOptions {
+    /// Maximum number of operations executed concurrently.
+    pub concurrency: usize,
+    /// Number of completed operations between two progress reports.
+    pub progress_interval: usize,
+}
+
+impl Default for BulkOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            progress_interval: 25,
+        }
+    }
+}
+
+/// Progress of a bulk operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BulkProgress {
+    /// Number of completed operations, successful or not.
+    pub done: usize,
+    /// Total number of operations.
+    pub total: usize,
+}
+
+/// Receive the progress of a bulk operation.
+///
+/// Handlers usually implement this trait to edit the deferred response of an
+/// interaction. The unit type can be used when the progress is not reported.
+#[async_trait]
+pub trait ProgressReporter: Send {
+    /// Report the progress of the operation.
+    async fn report(&mut self, progress: BulkProgress);
+}
+
+#[async_trait]
+impl ProgressReporter for () {
+    async fn report(&mut self, _progress: BulkProgress) {}
+}
+
+/// Result of a bulk operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BulkReport<T> {
+    /// Items whose operation succeeded.
+    pub succeeded: Vec<T>,
+    /// Items whose operation failed.
+    pub failed: Vec<T>,
+    /// Items not processed because the operation has been cancelled.
+    pub cancelled: Vec<T>,
+    /// Number of rate limited requests.
+    pub rate_limited: usize,
+}
+
+impl<T> Default for BulkReport<T> {
+    fn default() -> Self {
+        Self {
+            succeeded: Vec::new(),
+            failed: Vec::new(),
+            cancelled: Vec::new(),
+            rate_limited: 0,
+        }
+    }
+}
+
+/// Token used to cancel a bulk operation.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    inner: Arc<CancelTokenInner>,
+}
+
+#[derive(Debug, Default)]
+struct CancelTokenInner {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancelToken {
+    /// Initialize a new [`CancelToken`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the operation.
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::Release);
+        self.inner.notify.notify_waiters();
+    }
+
+    /// Whether the operation has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Wait until the operation is cancelled.
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.inner.notify.notified();
+
+            if self.is_cancelled() {
+                return;
+            }
+
+            notified.await;
+        }
+    }
+}
+
+/// Bulk operations started from interactions.
+///
+/// Operations are identified by the id of the interaction that started them.
+#[derive(Debug, Clone, Default)]
+pub struct BulkTasks {
+    tasks: Arc<Mutex<HashMap<Id<InteractionMarker>, CancelToken>>>,
+}
+
+impl BulkTasks {
+    /// Register a new operation and get its [`CancelToken`].
+    pub fn register(&self, id: Id<InteractionMarker>) -> CancelToken {
+        let token = CancelToken::new();
+        self.lock().insert(id, token.clone());
+
+        token
+    }
+
+    /// Cancel an operation.
+    ///
+    /// Returns `false` if the operation is not running.
+    pub fn cancel(&self, id: Id<InteractionMarker>) -> bool {
+        match self.lock().remove(&id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether an operation is running.
+    pub fn contains(&self, id: Id<InteractionMarker>) -> bool {
+        self.lock().contains_key(&id)
+    }
+
+    /// Remove a completed operation.
+    pub fn remove(&self, id: Id<InteractionMarker>) {
+        self.lock().remove(&id);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Id<InteractionMarker>, CancelToken>> {
+        self.tasks.lock().unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+/// Error returned by an operation that has been rate limited.
+///
+/// HTTP errors with a `429` status are detected automatically, this type
+/// allows other errors to be treated as rate limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimited {
+    /// Duration to wait before sending another request.
+    pub retry_after: Duration,
+    /// Whether the global rate limit of the bot has been reached.
+    pub global: bool,
+}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rate limited for {:?}", self.retry_after)
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+/// Get the rate limit of an operation, if the error is a rate limit.
+pub fn rate_limit(error: &anyhow::Error) -> Option<RateLimited> {
+    if let Some(limited) = error.downcast_ref::<RateLimited>() {
+        return Some(*limited);
+    }
+
+    let limited = match error.downcast_ref::<twilight_http::Error>()?.kind() {
+        ErrorType::Response {
+            error:
+                ApiError::Ratelimited(RatelimitedApiError {
+                    retry_after,
+                    global,
+                    ..
+                }),
+            ..
+        } => RateLimited {
+            retry_after: Duration::from_secs_f64(retry_after.max(0.0)),
+            global: *global,
+        },
+        ErrorType::Response { status, .. } if status.get() == 429 => RateLimited {
+            retry_after: DEFAULT_RETRY_AFTER,
+            global: false,
+        },
+        _ => return None,
+    };
+
+    Some(limited)
+}
+
+/// Execute an operation for each item.
+///
+/// See the [module documentation](self) for more information.
+pub async fn run<T, F, Fut>(
+    items: impl IntoIterator<Item = T>,
+    options: BulkOptions,
+    cancel: &CancelToken,
+    reporter: &mut impl ProgressReporter,
+    mut operation: F,
+) -> BulkReport<T>
+where
+    T: Clone,
+    F: FnMut(T) -> Fut,
+    Fut: Future<Output = Result<(), anyhow::Error>>,
+{
+    let mut queue = items
+        .into_iter()
+        .map(|item| (item, 0))
+        .collect::<VecDeque<_>>();
+    let total = queue.len();
+    let concurrency = options.concurrency.max(1);
+
+    let mut report = BulkReport::default();
+    let mut running = FuturesUnordered::new();
+    let mut paused_until: Option<Instant> = None;
+    let mut reported = 0;
+
+    loop {
+        let paused = paused_until.map_or(false, |until| Instant::now() < until);
+
+        if !paused && !cancel.is_cancelled() {
+            paused_until = None;
+
+            while running.len() < concurrency {
+                let (item, retries) = match queue.pop_front() {
+                    Some(entry) => entry,
+                    None => break,
+                };
+
+                let future = operation(item.clone());
+                running.push(async move { (item, retries, future.await) });
+            }
+        }
+
+        if running.is_empty() {
+            match paused_until {
+                Some(until) if !queue.is_empty() && !cancel.is_cancelled() => {
+                    tokio::select! {
+                        _ = sleep_until(until) => {}
+                        _ = cancel.cancelled() => {}
+                    }
+
+                    continue;
+                }
+                _ => break,
+            }
+        }
+
+        let (item, retries, result) = match running.next().await {
+            Some(result) => result,
+            None => continue,
+        };
+
+        match result {
+            Ok(()) => report.succeeded.push(item),
+            Err(error) => match rate_limit(&error) {
+                Some(limited) if retries < MAX_RETRIES => {
+                    debug!(retry_after = ?limited.retry_after, "bulk operation rate limited");
+
+                    let until = Instant::now() + limited.retry_after;
+                    paused_until = Some(paused_until.map_or(until, |current| current.max(until)));
+                    report.rate_limited += 1;
+                    queue.push_front((item, retries + 1));
+                }
+                _ => {
+                    debug!(error = ?error, "bulk operation failed");
+                    report.failed.push(item);
+                }
+            },
+        }
+
+        let done = report.succeeded.len() + report.failed.len();
+        if done - reported >= options.progress_interval && done < total {
+            reporter.report(BulkProgress { done, total }).await;
+            reported = done;
+        }
+    }
+
+    if report.rate_limited > 0 {
+        debug!(
+            rate_limited = report.rate_limited,
+            "bulk operation completed with rate limits"
+        );
+    }
+
+    report.cancelled = queue.into_iter().map(|(item, _)| item).collect();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Mock HTTP layer recording requests and injecting rate limits.
+    #[derive(Default)]
+    struct MockHttp {
+        /// Requests sent, with the date they were sent.
+        requests: Mutex<Vec<(u32, Instant)>>,
+        /// Items rate limited on their first request.
+        limited: Vec<u32>,
+    }
+
+    impl MockHttp {
+        fn new(limited: Vec<u32>) -> Self {
+            Self {
+                limited,
+                ..Default::default()
+            }
+        }
+
+        async fn request(&self, item: u32, retry_after: Duration) -> Result<(), anyhow::Error> {
+            let first = {
+                let mut requests = self.requests.lock().unwrap();
+                let first = !requests.iter().any(|(sent, _)| *sent == item);
+                requests.push((item, Instant::now()));
+
+                first
+            };
+
+            tokio::time::sleep(Duration::from_millis(5)).await;
+
+            if first && self.limited.contains(&item) {
+                return Err(RateLimited {
+                    retry_after,
+                    global: false,
+                }
+                .into());
+            }
+
+            if item == 0 {
+                anyhow::bail!("unknown user");
+            }
+
+            Ok(())
+        }
+
+        fn order(&self) -> Vec<u32> {
+            self.requests
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(item, _)| *item)
+                .collect()
+        }
+    }
+
+    #[derive(Default)]
+    struct Progress(Vec<BulkProgress>);
+
+    #[async_trait]
+    impl ProgressReporter for Progress {
+        async fn report(&mut self, progress: BulkProgress) {
+            self.0.push(progress);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_sequential() {
+        let http = MockHttp::new(vec![2]);
+        let retry_after = Duration::from_millis(50);
+        let options = BulkOptions {
+            concurrency: 1,
+            progress_interval: 2,
+        };
+        let mut progress = Progress::default();
+
+        let start = Instant::now();
+        let report = run(
+            [1, 2, 0, 3],
+            options,
+            &CancelToken::new(),
+            &mut progress,
+            |item| http.request(item, retry_after),
+        )
+        .await;
+
+        // The rate limited request is retried before the next ones.
+        assert_eq!(http.order(), vec![1, 2, 2, 0, 3]);
+        assert!(start.elapsed() >= retry_after);
+        assert_eq!(report.succeeded, vec![1, 2, 3]);
+        assert_eq!(report.failed, vec![0]);
+        assert!(report.cancelled.is_empty());
+        assert_eq!(report.rate_limited, 1);
+        assert_eq!(progress.0, vec![BulkProgress { done: 2, total: 4 }]);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_pauses_batch() {
+        let http = MockHttp::new(vec![1]);
+        let retry_after = Duration::from_millis(100);
+        let options = BulkOptions {
+            concurrency: 3,
+            progress_interval: 100,
+        };
+
+        let report = run(1..=9, options, &CancelToken::new(), &mut (), |item| {
+            http.request(item, retry_after)
+        })
+        .await;
+
+        assert_eq!(report.succeeded.len(), 9);
+        assert_eq!(report.rate_limited, 1);
+
+        // No request is sent during the pause.
+        let requests = http.requests.lock().unwrap().clone();
+        let limited_at = requests[0].1;
+        let order = requests.iter().map(|(item, _)| *item).collect::<Vec<_>>();
+        let retry = order.iter().rposition(|item| *item == 1).unwrap();
+
+        assert_eq!(&order[..3], &[1, 2, 3]);
+        assert_eq!(order[3], 1);
+        assert!(requests[retry].1 >= limited_at + retry_after);
+        assert!(requests[retry..]
+            .iter()
+            .all(|(_, sent)| *sent >= limited_at + retry_after));
+    }
+
+    #[tokio::test]
+    async fn test_cancel() {
+        let http = MockHttp::new(vec![]);
+        let cancel = CancelToken::new();
+        let options = BulkOptions {
+            concurrency: 1,
+            progress_interval: 100,
+        };
+
+        let report = run(1..=5, options, &cancel, &mut (), |item| {
+            if item == 2 {
+                cancel.cancel();
+            }
+
+            http.request(item, Duration::ZERO)
+        })
+        .await;
+
+        assert_eq!(http.order(), vec![1, 2]);
+        assert_eq!(report.succeeded, vec![1, 2]);
+        assert_eq!(report.cancelled, vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let limited = RateLimited {
+            retry_after: Duration::from_secs(2),
+            global: true,
+        };
+
+        assert_eq!(rate_limit(&limited.into()), Some(limited));
+        assert_eq!(rate_limit(&anyhow::anyhow!("error")), None);
+    }
+
+    #[test]
+    fn test_bulk_tasks() {
+        let tasks = BulkTasks::default();
+        let token = tasks.register(Id::new(1));
+
+        assert!(tasks.contains(Id::new(1)));
+        assert!(!tasks.cancel(Id::new(2)));
+        assert!(tasks.cancel(Id::new(1)));
+        assert!(!tasks.contains(Id::new(1)));
+        assert!(token.is_cancelled());
+        assert!(!tasks.cancel(Id::new(1)));
+    }
+}
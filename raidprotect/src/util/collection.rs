@@ -0,0 +1,50 @@
+//! Collection utilities.
+
+/// The list has reached its maximum length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListFull;
+
+/// Replace the first item matching `predicate`, or push `item` at the end of
+/// the list.
+///
+/// Existing items are always replaced, but [`ListFull`] is returned if a new
+/// item would make the list longer than `max_len`.
+pub fn upsert<T>(
+    items: &mut Vec<T>,
+    max_len: usize,
+    item: T,
+    predicate: impl Fn(&T) -> bool,
+) -> Result<(), ListFull> {
+    match items.iter().position(predicate) {
+        Some(index) => items[index] = item,
+        None if items.len() >= max_len => return Err(ListFull),
+        None => items.push(item),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upsert() {
+        let mut items = vec![(1, "a"), (2, "b")];
+
+        assert_eq!(upsert(&mut items, 3, (2, "c"), |i| i.0 == 2), Ok(()));
+        assert_eq!(items, vec![(1, "a"), (2, "c")]);
+
+        assert_eq!(upsert(&mut items, 3, (3, "d"), |i| i.0 == 3), Ok(()));
+        assert_eq!(items, vec![(1, "a"), (2, "c"), (3, "d")]);
+    }
+
+    #[test]
+    fn test_upsert_full() {
+        let mut items = vec![1, 2];
+
+        assert_eq!(upsert(&mut items, 2, 3, |i| *i == 3), Err(ListFull));
+        assert_eq!(upsert(&mut items, 2, 2, |i| *i == 2), Ok(()));
+        assert_eq!(items, vec![1, 2]);
+    }
+}
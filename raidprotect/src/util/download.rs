@@ -0,0 +1,40 @@
+//! Download of files sent by users.
+//!
+//! Some commands accept attachments, which must be downloaded from the Discord
+//! CDN. The download size is limited to avoid loading large files in memory.
+
+use anyhow::{bail, Context};
+use hyper::{client::HttpConnector, Body, Client, Uri};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use once_cell::sync::Lazy;
+
+/// HTTP client used to download files.
+static CLIENT: Lazy<Client<HttpsConnector<HttpConnector>, Body>> = Lazy::new(|| {
+    let connector = HttpsConnectorBuilder::new()
+        .with_webpki_roots()
+        .https_only()
+        .enable_http1()
+        .build();
+
+    Client::builder().build(connector)
+});
+
+/// Download a text file.
+///
+/// Invalid UTF-8 sequences are replaced, and an error is returned if the file
+/// is larger than `max_size` bytes.
+pub async fn download_text(url: &str, max_size: usize) -> Result<String, anyhow::Error> {
+    let uri: Uri = url.parse().context("invalid file url")?;
+    let response = CLIENT.get(uri).await?;
+
+    if !response.status().is_success() {
+        bail!("failed to download file: status {}", response.status());
+    }
+
+    let body = hyper::body::to_bytes(response.into_body()).await?;
+    if body.len() > max_size {
+        bail!("file is too large ({} bytes)", body.len());
+    }
+
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
@@ -0,0 +1,37 @@
+//! Utilities for the Discord HTTP API.
+
+use twilight_http::{
+    api_error::{ApiError, GeneralApiError},
+    error::ErrorType,
+    Error,
+};
+
+/// Whether an HTTP error is a `404 Not Found` response.
+///
+/// Discord returns this status for unknown resources, such as a user that is
+/// not a member of a guild or a user that is not banned.
+pub fn is_not_found(error: &Error) -> bool {
+    matches!(error.kind(), ErrorType::Response { status, .. } if status.get() == 404)
+}
+
+/// Whether an HTTP error is a `403 Forbidden` response.
+///
+/// Discord returns this status when the bot is missing permissions, such as
+/// sending messages in a channel it cannot access.
+pub fn is_forbidden(error: &Error) -> bool {
+    matches!(error.kind(), ErrorType::Response { status, .. } if status.get() == 403)
+}
+
+/// Get the JSON error code of an HTTP error.
+///
+/// Error codes are more specific than the response status, such as the error
+/// returned when a user does not accept private messages.
+pub fn error_code(error: &Error) -> Option<u64> {
+    match error.kind() {
+        ErrorType::Response {
+            error: ApiError::General(GeneralApiError { code, .. }),
+            ..
+        } => Some(*code),
+        _ => None,
+    }
+}
@@ -0,0 +1,378 @@
+//! Discord invites and external links detection.
+//!
+//! Links are often obfuscated to bypass filters: lookalike dots
+//! (`discord。gg`), spaces around the dots (`discord . gg / code`) or
+//! invisible characters. The content is normalized before searching for
+//! links (see [`normalize`]).
+//!
+//! Spaces around dots and slashes are only removed to search for invites,
+//! since doing so for external links would turn regular sentences into
+//! domains.
+
+use std::fmt;
+
+use any_ascii::any_ascii_char;
+use linkify::{LinkFinder, LinkKind};
+use url::Url;
+
+use super::name::is_invisible;
+
+/// Prefixes of Discord invites, including common redirection services.
+const INVITE_PREFIXES: [&str; 5] = [
+    "discord.gg/",
+    "discord.com/invite/",
+    "discordapp.com/invite/",
+    "discord.me/",
+    "dsc.gg/",
+];
+
+/// Domains that are never reported as external links.
+///
+/// Links to Discord itself (attachments, message links) are common in
+/// conversations, and invites are detected separately.
+const IGNORED_DOMAINS: [&str; 6] = [
+    "discord.com",
+    "discordapp.com",
+    "discordapp.net",
+    "discord.gg",
+    "discord.me",
+    "dsc.gg",
+];
+
+/// Top-level domains of links detected without scheme, path or `www.`
+/// prefix.
+///
+/// Other words containing a dot (such as file names) are too common to be
+/// considered as links.
+const COMMON_TLDS: [&str; 30] = [
+    "app", "biz", "cf", "click", "club", "co", "com", "de", "fr", "ga", "gg", "gq", "info", "io",
+    "link", "live", "ly", "me", "ml", "net", "online", "org", "ru", "shop", "site", "tk", "top",
+    "uk", "us", "xyz",
+];
+
+/// Link found in a message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FoundLink {
+    /// Discord invite, with its code in lowercase.
+    Invite(String),
+    /// External link, with its domain.
+    External { domain: String, link: String },
+}
+
+impl fmt::Display for FoundLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FoundLink::Invite(code) => write!(f, "discord.gg/{code}"),
+            FoundLink::External { link, .. } => f.write_str(link),
+        }
+    }
+}
+
+/// Entry of the link filter allowlist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AllowedLink {
+    /// Discord invite code, in lowercase.
+    Invite(String),
+    /// Domain, in lowercase.
+    Domain(String),
+}
+
+/// Parse an entry of the link filter allowlist.
+///
+/// The entry is either a Discord invite or a domain, which can be provided
+/// as a link. [`None`] is returned if the entry is invalid.
+pub fn parse_allowed(value: &str) -> Option<AllowedLink> {
+    let value = value.trim().to_ascii_lowercase();
+
+    if let Some(FoundLink::Invite(code)) = find_invites(&value).into_iter().next() {
+        return Some(AllowedLink::Invite(code));
+    }
+
+    let value = value
+        .strip_prefix("https://")
+        .or_else(|| value.strip_prefix("http://"))
+        .unwrap_or(&value);
+    let domain = value.split('/').next()?;
+    let domain = domain.strip_prefix("www.").unwrap_or(domain);
+
+    let valid = domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !domain.contains("..")
+        && domain
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.');
+
+    match valid {
+        true => Some(AllowedLink::Domain(domain.to_owned())),
+        false => None,
+    }
+}
+
+/// Find the Discord invites and external links of a message.
+///
+/// Invites are returned first. Each link is only returned once.
+pub fn find_links(content: &str) -> Vec<FoundLink> {
+    let normalized = normalize(content);
+    let mut links = find_invites(&collapse_spaces(&normalized));
+
+    let mut finder = LinkFinder::new();
+    finder.kinds(&[LinkKind::Url]).url_must_have_scheme(false);
+
+    for link in finder.links(&normalized) {
+        if let Some(link) = parse_external(link.as_str()) {
+            if !links.contains(&link) {
+                links.push(link);
+            }
+        }
+    }
+
+    links
+}
+
+/// Normalize the content of a message to reveal obfuscated links.
+///
+/// Invisible characters are removed, lookalike dots and slashes are replaced
+/// with their ASCII equivalent, and the content is converted to lowercase
+/// ASCII. Bracketed dots (`[.]`, `(dot)`) are also replaced.
+pub fn normalize(content: &str) -> String {
+    let mut normalized = String::with_capacity(content.len());
+
+    for c in content.chars() {
+        match c {
+            c if is_invisible(c) || matches!(c, '\u{200C}' | '\u{200D}') => {}
+            c if is_dot(c) => normalized.push('.'),
+            c if is_slash(c) => normalized.push('/'),
+            c if c.is_ascii() => normalized.push(c.to_ascii_lowercase()),
+            c if c.is_whitespace() => normalized.push(' '),
+            c => normalized.push_str(&any_ascii_char(c).to_ascii_lowercase()),
+        }
+    }
+
+    for pattern in ["[.]", "(.)", "{.}", "[dot]", "(dot)", "{dot}"] {
+        normalized = normalized.replace(pattern, ".");
+    }
+
+    normalized
+}
+
+/// Remove the whitespaces around dots and slashes.
+fn collapse_spaces(normalized: &str) -> String {
+    let is_separator = |c: Option<char>| matches!(c, Some('.' | '/'));
+    let mut collapsed = String::with_capacity(normalized.len());
+    let mut chars = normalized.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if !c.is_whitespace() {
+            collapsed.push(c);
+            continue;
+        }
+
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+        if !is_separator(collapsed.chars().last()) && !is_separator(chars.peek().copied()) {
+            collapsed.push(' ');
+        }
+    }
+
+    collapsed
+}
+
+/// Find the Discord invites in a normalized content.
+fn find_invites(text: &str) -> Vec<FoundLink> {
+    let mut invites = Vec::new();
+
+    for prefix in INVITE_PREFIXES {
+        for (index, _) in text.match_indices(prefix) {
+            let rest = &text[index + prefix.len()..];
+            let rest = rest.strip_prefix("invite/").unwrap_or(rest);
+            let code = rest
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || *c == '-')
+                .collect::<String>();
+
+            if code.is_empty() {
+                continue;
+            }
+
+            let invite = FoundLink::Invite(code);
+            if !invites.contains(&invite) {
+                invites.push(invite);
+            }
+        }
+    }
+
+    invites
+}
+
+/// Parse an external link found in a normalized content.
+///
+/// [`None`] is returned if the link is not an external link.
+fn parse_external(link: &str) -> Option<FoundLink> {
+    let has_scheme = link.contains("://");
+    let url = match has_scheme {
+        true => Url::parse(link).ok()?,
+        false => Url::parse(&format!("https://{link}")).ok()?,
+    };
+
+    let domain = url.host_str()?.trim_end_matches('.').to_owned();
+    let ignored = IGNORED_DOMAINS
+        .iter()
+        .any(|ignored| domain == *ignored || domain.ends_with(&format!(".{ignored}")));
+
+    if ignored {
+        return None;
+    }
+
+    // Links without scheme are only reported if they are likely to be links.
+    if !has_scheme && !domain.starts_with("www.") && url.path() == "/" {
+        let tld = domain.rsplit('.').next()?;
+
+        if !COMMON_TLDS.contains(&tld) {
+            return None;
+        }
+    }
+
+    Some(FoundLink::External {
+        domain,
+        link: link.to_owned(),
+    })
+}
+
+/// Whether a character looks like a dot.
+fn is_dot(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00B7}' // Middle dot
+        | '\u{2024}' // One dot leader
+        | '\u{2027}' // Hyphenation point
+        | '\u{2E31}' // Word separator middle dot
+        | '\u{3002}' // Ideographic full stop
+        | '\u{30FB}' // Katakana middle dot
+        | '\u{FE52}' // Small full stop
+        | '\u{FF0E}' // Fullwidth full stop
+        | '\u{FF61}' // Halfwidth ideographic full stop
+    )
+}
+
+/// Whether a character looks like a slash.
+fn is_slash(c: char) -> bool {
+    matches!(
+        c,
+        '\u{2044}' // Fraction slash
+        | '\u{2215}' // Division slash
+        | '\u{29F8}' // Big solidus
+        | '\u{FF0F}' // Fullwidth solidus
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invite(code: &str) -> FoundLink {
+        FoundLink::Invite(code.to_owned())
+    }
+
+    #[test]
+    fn test_invites() {
+        let cases = [
+            ("join discord.gg/raid", "raid"),
+            ("https://discord.com/invite/Raid-Protect", "raid-protect"),
+            ("https://discordapp.com/invite/raid", "raid"),
+            ("discord.gg/invite/raid", "raid"),
+            ("dsc.gg/raid", "raid"),
+        ];
+
+        for (content, code) in cases {
+            assert_eq!(find_links(content), vec![invite(code)], "{content}");
+        }
+    }
+
+    #[test]
+    fn test_obfuscated_invites() {
+        let cases = [
+            "discord . gg / raid",
+            "discord。gg/raid",
+            "discord．gg∕raid",
+            "disc\u{200B}ord.gg/raid",
+            "discord[.]gg/raid",
+            "discord(dot)gg/raid",
+            "ＤＩＳＣＯＲＤ.ＧＧ/raid",
+        ];
+
+        for content in cases {
+            assert_eq!(find_links(content), vec![invite("raid")], "{content}");
+        }
+    }
+
+    #[test]
+    fn test_external_links() {
+        let links = find_links("see https://free-nitro.xyz/claim and www.example.org");
+
+        assert_eq!(
+            links,
+            vec![
+                FoundLink::External {
+                    domain: "free-nitro.xyz".to_owned(),
+                    link: "https://free-nitro.xyz/claim".to_owned(),
+                },
+                FoundLink::External {
+                    domain: "www.example.org".to_owned(),
+                    link: "www.example.org".to_owned(),
+                },
+            ]
+        );
+
+        assert_eq!(
+            find_links("steamcommunity。com/gift"),
+            vec![FoundLink::External {
+                domain: "steamcommunity.com".to_owned(),
+                link: "steamcommunity.com/gift".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_no_links() {
+        let cases = [
+            "Hello. How are you?",
+            "Open the config.json file",
+            "e.g. this one",
+            "https://cdn.discordapp.com/attachments/1/2/image.png",
+            "https://discord.com/channels/1/2/3",
+        ];
+
+        for content in cases {
+            assert_eq!(find_links(content), Vec::new(), "{content}");
+        }
+    }
+
+    #[test]
+    fn test_parse_allowed() {
+        let invite = AllowedLink::Invite("raidprotect".to_owned());
+        let domain = AllowedLink::Domain("raidprotect.org".to_owned());
+
+        assert_eq!(
+            parse_allowed("discord.gg/RaidProtect"),
+            Some(invite.clone())
+        );
+        assert_eq!(
+            parse_allowed("https://discord.com/invite/raidprotect"),
+            Some(invite)
+        );
+        assert_eq!(parse_allowed("raidprotect.org"), Some(domain.clone()));
+        assert_eq!(
+            parse_allowed(" https://www.RaidProtect.org/docs "),
+            Some(domain)
+        );
+        assert_eq!(parse_allowed("raidprotect"), None);
+        assert_eq!(parse_allowed("raid protect.org"), None);
+        assert_eq!(parse_allowed("raidprotect..org"), None);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(invite("raid").to_string(), "discord.gg/raid");
+    }
+}
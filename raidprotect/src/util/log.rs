@@ -0,0 +1,131 @@
+//! Logging utilities.
+//!
+//! Gateway events are processed inside an `event` span carrying the event
+//! kind and guild id. High-volume events (such as `MESSAGE_CREATE`) are
+//! sampled: only one event out of [`SAMPLING_RATE`] gets a span, except for
+//! the debug guilds (see [`raidprotect_model::log`]).
+
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use raidprotect_model::log::{is_debug_guild, set_debug_guilds};
+use tracing::{info_span, warn, Span};
+use twilight_model::{
+    gateway::event::Event as GatewayEvent,
+    id::{marker::GuildMarker, Id},
+};
+
+use super::shutdown::ShutdownSubscriber;
+use crate::cluster::ClusterState;
+
+/// Sampling rate of high-volume events.
+pub const SAMPLING_RATE: u64 = 100;
+
+/// Interval between refreshes of the debug guilds.
+const DEBUG_GUILDS_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Counter of the high-volume events, used for sampling.
+static SAMPLING_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Create the span of a gateway event.
+///
+/// Returns [`Span::none`] for high-volume events that are not sampled.
+pub fn event_span(event: &GatewayEvent) -> Span {
+    let guild_id = event_guild_id(event);
+
+    if is_high_volume(event) && !guild_id.map_or(false, |id| is_debug_guild(id.get())) {
+        let count = SAMPLING_COUNTER.fetch_add(1, Ordering::Relaxed);
+
+        if count % SAMPLING_RATE != 0 {
+            return Span::none();
+        }
+    }
+
+    info_span!(
+        "event",
+        kind = event.kind().name(),
+        guild_id = guild_id.map(Id::get),
+    )
+}
+
+/// Whether an event is a high-volume event, subject to sampling.
+fn is_high_volume(event: &GatewayEvent) -> bool {
+    matches!(
+        event,
+        GatewayEvent::MessageCreate(_)
+            | GatewayEvent::MessageUpdate(_)
+            | GatewayEvent::ReactionAdd(_)
+            | GatewayEvent::TypingStart(_)
+    )
+}
+
+/// Get the guild id of a processed event.
+fn event_guild_id(event: &GatewayEvent) -> Option<Id<GuildMarker>> {
+    match event {
+        GatewayEvent::GuildCreate(guild) => Some(guild.id),
+        GatewayEvent::GuildUpdate(guild) => Some(guild.id),
+        GatewayEvent::GuildDelete(guild) => Some(guild.id),
+        GatewayEvent::ChannelCreate(channel) => channel.guild_id,
+        GatewayEvent::ChannelDelete(channel) => channel.guild_id,
+        GatewayEvent::ChannelUpdate(channel) => channel.guild_id,
+        GatewayEvent::InteractionCreate(interaction) => interaction.guild_id,
+        GatewayEvent::MemberAdd(member) => Some(member.guild_id),
+        GatewayEvent::MemberUpdate(member) => Some(member.guild_id),
+        GatewayEvent::MemberRemove(member) => Some(member.guild_id),
+        GatewayEvent::MessageCreate(message) => message.guild_id,
+        GatewayEvent::MessageUpdate(message) => message.guild_id,
+        GatewayEvent::MessageDelete(message) => message.guild_id,
+        GatewayEvent::ReactionAdd(reaction) => reaction.guild_id,
+        GatewayEvent::RoleCreate(role) => Some(role.guild_id),
+        GatewayEvent::RoleDelete(role) => Some(role.guild_id),
+        GatewayEvent::TypingStart(typing) => typing.guild_id,
+        _ => None,
+    }
+}
+
+/// Load the debug guilds from the cache.
+pub async fn refresh_debug_guilds(state: &ClusterState) -> Result<(), anyhow::Error> {
+    let guilds = state.cache.debug_guilds().await?;
+    set_debug_guilds(guilds.into_iter().map(Id::get).collect());
+
+    Ok(())
+}
+
+/// Periodically refresh the debug guilds.
+///
+/// The debug guilds are shared between the instances of the bot, so they are
+/// reloaded from the cache every [`DEBUG_GUILDS_INTERVAL`].
+pub async fn run_debug_guilds_refresh(state: ClusterState, mut shutdown: ShutdownSubscriber) {
+    let mut interval = tokio::time::interval(DEBUG_GUILDS_INTERVAL);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {},
+            _ = shutdown.wait_shutdown() => break,
+        };
+
+        if let Err(error) = refresh_debug_guilds(&state).await {
+            warn!(error = ?error, "failed to refresh debug guilds");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::gateway::payload::incoming::GuildDelete;
+
+    use super::*;
+
+    #[test]
+    fn test_event_guild_id() {
+        let event = GatewayEvent::GuildDelete(GuildDelete {
+            id: Id::new(1),
+            unavailable: false,
+        });
+
+        assert_eq!(event_guild_id(&event), Some(Id::new(1)));
+        assert!(!is_high_volume(&event));
+    }
+}
@@ -0,0 +1,172 @@
+//! Post messages in the logs channels.
+//!
+//! Logs messages are grouped by [`LogCategory`], and each category can be
+//! routed to its own channel with the `/config logs route` command. Every
+//! message sent in the logs goes through [`post`] (or [`post_message`] for
+//! messages with components or mentions), which resolves the route of the
+//! category:
+//!
+//! - categories routed to a channel are sent in this channel
+//! - categories routed to off are silently dropped
+//! - other categories are sent in the default logs channel, which is created
+//!   if needed (see [`guild_logs_channel`])
+//!
+//! The permissions of the bot in a routed channel are checked when the route
+//! is configured. If messages can no longer be sent in the channel, the route
+//! is reset and the message is sent in the default logs channel instead.
+
+use std::slice;
+
+use raidprotect_model::database::model::{GuildConfig, LogCategory, LogDestination};
+use tracing::warn;
+use twilight_model::{
+    application::component::Component,
+    channel::{embed::Embed, message::AllowedMentions},
+    http::attachment::Attachment,
+    id::{marker::ChannelMarker, Id},
+};
+
+use super::{guild_logs_channel, http};
+use crate::{cluster::ClusterState, database, interaction::util::GuildConfigExt};
+
+/// Message sent in the logs.
+///
+/// Logs messages do not ping anyone, unless mentions are explicitly allowed
+/// with [`content`](Self::content).
+#[derive(Debug, Clone)]
+pub struct LogMessage {
+    embed: Embed,
+    content: Option<String>,
+    allowed_mentions: AllowedMentions,
+    components: Vec<Component>,
+    attachments: Vec<Attachment>,
+}
+
+impl LogMessage {
+    /// Create a new message with an embed.
+    pub fn new(embed: Embed) -> Self {
+        Self {
+            embed,
+            content: None,
+            allowed_mentions: AllowedMentions::default(),
+            components: Vec::new(),
+            attachments: Vec::new(),
+        }
+    }
+
+    /// Set the content of the message, with the mentions allowed to ping.
+    pub fn content(mut self, content: String, allowed_mentions: AllowedMentions) -> Self {
+        self.content = Some(content);
+        self.allowed_mentions = allowed_mentions;
+        self
+    }
+
+    /// Set the components of the message.
+    pub fn components(mut self, components: Vec<Component>) -> Self {
+        self.components = components;
+        self
+    }
+
+    /// Set the files attached to the message.
+    pub fn attachments(mut self, attachments: Vec<Attachment>) -> Self {
+        self.attachments = attachments;
+        self
+    }
+}
+
+/// Post an embed in the logs channel of a category.
+///
+/// See the [module documentation](self) for more information.
+pub async fn post(
+    state: &ClusterState,
+    config: &GuildConfig,
+    category: LogCategory,
+    embed: Embed,
+) -> Result<(), anyhow::Error> {
+    post_message(state, config, category, LogMessage::new(embed)).await?;
+
+    Ok(())
+}
+
+/// Post a message in the logs channel of a category.
+///
+/// Returns the channel where the message has been sent, or [`None`] if the
+/// category is routed to off.
+///
+/// See the [module documentation](self) for more information.
+pub async fn post_message(
+    state: &ClusterState,
+    config: &GuildConfig,
+    category: LogCategory,
+    message: LogMessage,
+) -> Result<Option<Id<ChannelMarker>>, anyhow::Error> {
+    let channel = match config.log_routing.get(category) {
+        LogDestination::Off => return Ok(None),
+        LogDestination::Default => None,
+        LogDestination::Channel(channel) => Some(channel),
+    };
+
+    if let Some(channel) = channel {
+        match send(state, channel, &message).await {
+            Ok(()) => return Ok(Some(channel)),
+            Err(error) if is_unusable(&error) => {
+                warn!(error = ?error, guild = ?config.id, category = ?category, "logs route channel is unusable, resetting route");
+
+                if let Err(error) = reset_route(state, config, category).await {
+                    warn!(error = ?error, guild = ?config.id, "failed to reset logs route");
+                }
+            }
+            Err(error) => return Err(error),
+        }
+    }
+
+    let channel = guild_logs_channel(state, config.id, config.logs_chan, config.lang()).await?;
+    send(state, channel, &message).await?;
+
+    Ok(Some(channel))
+}
+
+/// Send a message in a channel.
+async fn send(
+    state: &ClusterState,
+    channel: Id<ChannelMarker>,
+    message: &LogMessage,
+) -> Result<(), anyhow::Error> {
+    let mut request = state
+        .http
+        .create_message(channel)
+        .embeds(slice::from_ref(&message.embed))?
+        .components(&message.components)?
+        .attachments(&message.attachments)?
+        .allowed_mentions(Some(&message.allowed_mentions));
+
+    if let Some(content) = &message.content {
+        request = request.content(content)?;
+    }
+
+    request.exec().await?;
+
+    Ok(())
+}
+
+/// Whether an error means that messages cannot be sent in the channel.
+fn is_unusable(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<twilight_http::Error>()
+        .map_or(false, |error| {
+            http::is_not_found(error) || http::is_forbidden(error)
+        })
+}
+
+/// Reset the route of a category to the default logs channel.
+async fn reset_route(
+    state: &ClusterState,
+    config: &GuildConfig,
+    category: LogCategory,
+) -> Result<(), anyhow::Error> {
+    // The configuration is fetched again since it may have changed.
+    let mut config = database::guild_config(state, config.id).await?;
+    config.log_routing.set(category, LogDestination::Default);
+
+    database::update_guild_config(state, &config).await
+}
@@ -13,7 +13,7 @@ use std::collections::HashMap;
 
 use anyhow::{anyhow, Context};
 use once_cell::sync::Lazy;
-use raidprotect_model::cache::discord::CachedChannel;
+use raidprotect_model::cache::{discord::CachedChannel, model::write_queue::PendingWrite};
 use tokio::sync::{broadcast, RwLock};
 use tracing::{error, trace, warn};
 use twilight_model::{
@@ -31,9 +31,13 @@ use twilight_model::{
         Id,
     },
 };
-use twilight_util::builder::embed::EmbedBuilder;
 
-use crate::{cluster::ClusterState, interaction::embed::COLOR_RED, translations::Lang};
+use crate::{
+    cluster::ClusterState,
+    database,
+    interaction::embed::{EmbedBuilder, COLOR_RED},
+    translations::Lang,
+};
 
 /// Default logs channel name.
 const DEFAULT_LOGS_NAME: &str = "raidprotect-logs";
@@ -121,9 +125,11 @@ async fn configure_logs_channel(
     };
 
     // Update the guild configuration
-    let mut config = state.database.get_guild_or_create(guild).await?;
-    config.logs_chan = Some(logs_channel);
-    state.database.update_guild(&config).await?;
+    let write = PendingWrite::LogsChannel {
+        guild_id: guild,
+        channel_id: logs_channel,
+    };
+    database::write(state, write).await?;
 
     // Notify pending tasks that the channel has been created.
     sender.send(logs_channel).ok();
@@ -2,10 +2,23 @@
 //!
 //! This module provides various utilities that doesn't fit in other modules.
 
+pub mod account;
+pub mod bulk;
+pub mod collection;
+pub mod download;
+pub mod http;
+pub mod link;
+pub mod log;
+pub mod logs;
 mod logs_channel;
+pub mod name;
+pub mod pattern;
+pub mod ratelimit;
 pub mod resource;
 pub mod shutdown;
+pub mod template;
 mod text;
+pub mod time;
 
 pub use logs_channel::guild_logs_channel;
 pub use text::TextProcessExt;
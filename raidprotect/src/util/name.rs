@@ -0,0 +1,167 @@
+//! Member names normalization.
+//!
+//! Names can contain characters that disrupt the member list or are used to
+//! impersonate other members: stacked combining marks (zalgo text) and
+//! invisible characters such as zero-width spaces or bidirectional overrides.
+//!
+//! Characters that are legitimately used in names are allowed, such as a few
+//! diacritics on a letter or the zero-width joiner of emoji sequences.
+
+/// Maximum number of combining marks allowed on a single character.
+///
+/// Some languages stack up to two diacritics on a letter (for example
+/// Vietnamese), while zalgo text stacks many more.
+pub const MAX_COMBINING_MARKS: usize = 2;
+
+/// Problem found in a name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameIssue {
+    /// The name contains excessive combining marks.
+    Zalgo,
+    /// The name contains invisible characters, or is blank.
+    Invisible,
+}
+
+/// Check whether a name contains zalgo or invisible characters.
+pub fn check_name(name: &str) -> Option<NameIssue> {
+    let mut marks = 0;
+
+    for c in name.chars() {
+        if is_combining_mark(c) {
+            marks += 1;
+
+            if marks > MAX_COMBINING_MARKS {
+                return Some(NameIssue::Zalgo);
+            }
+        } else {
+            marks = 0;
+        }
+    }
+
+    if name.chars().any(is_invisible) || name.trim().is_empty() {
+        return Some(NameIssue::Invisible);
+    }
+
+    None
+}
+
+/// Remove invisible characters and excessive combining marks from a name.
+///
+/// The returned name is empty if it only contained removed characters.
+pub fn normalize_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    let mut marks = 0;
+
+    for c in name.chars().filter(|c| !is_invisible(*c)) {
+        if is_combining_mark(c) {
+            marks += 1;
+
+            if marks > MAX_COMBINING_MARKS {
+                continue;
+            }
+        } else {
+            marks = 0;
+        }
+
+        normalized.push(c);
+    }
+
+    normalized.trim().to_owned()
+}
+
+/// Whether a character is a generic combining mark.
+///
+/// Marks that are part of the writing of a script (such as Devanagari vowel
+/// signs) are not included.
+fn is_combining_mark(c: char) -> bool {
+    matches!(c,
+        '\u{0300}'..='\u{036F}' // Combining Diacritical Marks
+        | '\u{0483}'..='\u{0489}' // Cyrillic combining marks
+        | '\u{1AB0}'..='\u{1AFF}' // Combining Diacritical Marks Extended
+        | '\u{1DC0}'..='\u{1DFF}' // Combining Diacritical Marks Supplement
+        | '\u{20D0}'..='\u{20FF}' // Combining Diacritical Marks for Symbols
+        | '\u{FE20}'..='\u{FE2F}' // Combining Half Marks
+    )
+}
+
+/// Whether a character is invisible.
+///
+/// The zero-width joiner and non-joiner are not included since they are used
+/// in emoji sequences and some scripts.
+pub fn is_invisible(c: char) -> bool {
+    matches!(c,
+        '\u{00AD}' // Soft hyphen
+        | '\u{034F}' // Combining grapheme joiner
+        | '\u{061C}' // Arabic letter mark
+        | '\u{115F}' | '\u{1160}' | '\u{3164}' | '\u{FFA0}' // Hangul fillers
+        | '\u{17B4}' | '\u{17B5}' // Khmer inherent vowels
+        | '\u{180B}'..='\u{180F}' // Mongolian variation selectors and vowel separator
+        | '\u{200B}' // Zero-width space
+        | '\u{200E}' | '\u{200F}' // Directional marks
+        | '\u{202A}'..='\u{202E}' // Directional embeddings and overrides
+        | '\u{2060}'..='\u{206F}' // Word joiner, invisible operators and directional isolates
+        | '\u{2800}' // Braille pattern blank
+        | '\u{FEFF}' // Zero-width no-break space
+        | '\u{FFF0}'..='\u{FFF8}' // Unassigned specials
+        | '\u{1D173}'..='\u{1D17A}' // Musical symbol formatting
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_names() {
+        let names = [
+            "RaidProtect",
+            "Crème brûlée",
+            "Tie\u{0302}\u{0301}ng Vie\u{0323}\u{0302}t", // Decomposed Vietnamese
+            "👨\u{200D}👩\u{200D}👧",                     // Emoji sequence
+            "1\u{FE0F}\u{20E3}",                          // Keycap emoji
+            "می\u{200C}خواهم",                            // Persian with ZWNJ
+        ];
+
+        for name in names {
+            assert_eq!(check_name(name), None, "{name}");
+            assert_eq!(normalize_name(name), name);
+        }
+    }
+
+    #[test]
+    fn test_zalgo() {
+        let name = "Z\u{0336}\u{0322}\u{031B}\u{032A}a\u{0354}\u{0351}\u{0300}\u{0301}lgo";
+
+        assert_eq!(check_name(name), Some(NameIssue::Zalgo));
+        assert_eq!(
+            normalize_name(name),
+            "Z\u{0336}\u{0322}a\u{0354}\u{0351}lgo"
+        );
+    }
+
+    #[test]
+    fn test_invisible() {
+        let cases = [
+            ("Raid\u{200B}Protect", "RaidProtect"),
+            ("\u{202E}tcetorPdiaR", "tcetorPdiaR"),
+            ("\u{FEFF}Admin\u{2060}", "Admin"),
+            ("\u{3164}", ""),
+            ("\u{2800}\u{2800}\u{2800}", ""),
+            ("  ", ""),
+        ];
+
+        for (name, normalized) in cases {
+            assert_eq!(check_name(name), Some(NameIssue::Invisible), "{name}");
+            assert_eq!(normalize_name(name), normalized);
+        }
+    }
+
+    #[test]
+    fn test_normalized_name_is_clean() {
+        let name = "\u{200B}M\u{0336}\u{0322}\u{031B}o\u{3164}d ";
+        let normalized = normalize_name(name);
+
+        assert_eq!(normalized, "M\u{0336}\u{0322}od");
+        assert_eq!(check_name(&normalized), None);
+    }
+}
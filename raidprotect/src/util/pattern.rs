@@ -0,0 +1,167 @@
+//! Custom pattern rules compilation.
+//!
+//! Patterns of the [`PatternRule`] are written by guild administrators, so
+//! they must not be able to slow down the bot. Patterns are compiled with the
+//! [`regex`] crate, which does not backtrack and matches in linear time of the
+//! message length. The size of the compiled patterns and their nesting depth
+//! are limited, and patterns that match an empty message are rejected since
+//! they would match every message.
+//!
+//! Compiled patterns are kept in memory to avoid compiling them again for
+//! each message (see [`compiled`]).
+//!
+//! [`PatternRule`]: raidprotect_model::database::model::PatternRule
+
+use std::{collections::HashMap, sync::RwLock};
+
+use once_cell::sync::Lazy;
+use raidprotect_model::database::model::PatternRule;
+use regex::{Regex, RegexBuilder};
+
+/// Maximum size (in bytes) of a compiled pattern.
+const SIZE_LIMIT: usize = 1024 * 1024;
+
+/// Maximum size (in bytes) of the cache of the lazy DFA of a pattern.
+const DFA_SIZE_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Maximum nesting depth of a pattern.
+const NEST_LIMIT: u32 = 10;
+
+/// Maximum number of patterns kept in memory.
+const MAX_COMPILED: usize = 10_000;
+
+/// Compiled patterns, indexed by pattern.
+static COMPILED: Lazy<RwLock<HashMap<String, Regex>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Error returned when a pattern is rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternError {
+    /// The pattern is longer than [`PatternRule::MAX_PATTERN_LEN`].
+    TooLong,
+    /// The pattern is not a valid regular expression.
+    Invalid(String),
+    /// The compiled pattern exceeds the size limits.
+    TooComplex,
+    /// The pattern matches an empty message.
+    MatchesEmpty,
+}
+
+/// Compile a pattern, ensuring it is safe to match against messages.
+pub fn compile(pattern: &str) -> Result<Regex, PatternError> {
+    if pattern.chars().count() > PatternRule::MAX_PATTERN_LEN {
+        return Err(PatternError::TooLong);
+    }
+
+    let regex = RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .size_limit(SIZE_LIMIT)
+        .dfa_size_limit(DFA_SIZE_LIMIT)
+        .nest_limit(NEST_LIMIT)
+        .build()
+        .map_err(|error| match error {
+            regex::Error::CompiledTooBig(_) => PatternError::TooComplex,
+            regex::Error::Syntax(error) => PatternError::Invalid(error),
+            error => PatternError::Invalid(error.to_string()),
+        })?;
+
+    if regex.is_match("") {
+        return Err(PatternError::MatchesEmpty);
+    }
+
+    Ok(regex)
+}
+
+/// Get the compiled pattern of a rule.
+///
+/// Patterns are validated when the rule is created, so an error is only
+/// returned if the limits have changed since.
+pub fn compiled(pattern: &str) -> Result<Regex, PatternError> {
+    if let Some(regex) = COMPILED.read().unwrap().get(pattern) {
+        return Ok(regex.clone());
+    }
+
+    let regex = compile(pattern)?;
+    let mut compiled = COMPILED.write().unwrap();
+
+    // Patterns of deleted rules are never removed, so the cache is cleared
+    // once it grows too large.
+    if compiled.len() >= MAX_COMPILED {
+        compiled.clear();
+    }
+    compiled.insert(pattern.to_owned(), regex.clone());
+
+    Ok(regex)
+}
+
+/// Find the first rule matching a message.
+pub fn find_match<'a>(rules: &'a [PatternRule], content: &str) -> Option<&'a PatternRule> {
+    rules.iter().find(|rule| match compiled(&rule.pattern) {
+        Ok(regex) => regex.is_match(content),
+        Err(_) => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use raidprotect_model::database::model::PatternAction;
+
+    use super::*;
+
+    fn rule(name: &str, pattern: &str) -> PatternRule {
+        PatternRule {
+            name: name.to_owned(),
+            pattern: pattern.to_owned(),
+            action: PatternAction::Delete,
+        }
+    }
+
+    #[test]
+    fn test_compile() {
+        assert!(compile(r"free\s+nitro").is_ok());
+        assert!(matches!(
+            compile("(unclosed"),
+            Err(PatternError::Invalid(_))
+        ));
+        assert_eq!(compile("a*").unwrap_err(), PatternError::MatchesEmpty);
+        assert_eq!(compile("|").unwrap_err(), PatternError::MatchesEmpty);
+        assert_eq!(
+            compile(&"a".repeat(PatternRule::MAX_PATTERN_LEN + 1)).unwrap_err(),
+            PatternError::TooLong
+        );
+    }
+
+    #[test]
+    fn test_compile_limits() {
+        // Large repetitions of Unicode classes compile to a very large program.
+        assert_eq!(
+            compile(r"\w{100}\w{100}\w{100}").unwrap_err(),
+            PatternError::TooComplex
+        );
+        assert!(matches!(
+            compile(&format!("{}a{}", "(".repeat(20), ")".repeat(20))),
+            Err(PatternError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_find_match() {
+        let rules = vec![
+            rule("scam", r"free\s+nitro"),
+            rule("invite", r"discord\.gg"),
+        ];
+
+        assert_eq!(
+            find_match(&rules, "Get FREE   Nitro here").map(|rule| &*rule.name),
+            Some("scam")
+        );
+        assert_eq!(
+            find_match(&rules, "join discord.gg/raid").map(|rule| &*rule.name),
+            Some("invite")
+        );
+        assert!(find_match(&rules, "hello").is_none());
+
+        // Catastrophic patterns for backtracking engines match in linear time.
+        let rules = vec![rule("redos", "(a+)+b")];
+        assert!(find_match(&rules, &"a".repeat(10_000)).is_none());
+    }
+}
@@ -0,0 +1,253 @@
+//! Central guard against Discord rate limits.
+//!
+//! The twilight HTTP client already respects the rate limits of each route,
+//! but all requests share the global rate limit of the bot. Under heavy load,
+//! background operations such as bulk bans can consume this limit and delay
+//! responses to interactions, which must be sent within 3 seconds.
+//!
+//! Requests sent through a [`RateGuard`] are given a [`Priority`]:
+//! - [`Priority::Interaction`] requests are sent immediately, unless the
+//!   global rate limit has been reached.
+//! - [`Priority::Background`] requests wait until no interaction request is
+//!   in flight, so bulk operations yield to interactive ones.
+//!
+//! When a request is rate limited, the hit is recorded in the
+//! `raidprotect_rate_limit_hits_total` metric. If the global rate limit has
+//! been reached, all requests are paused until it is reset.
+
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use tokio::{
+    sync::Notify,
+    time::{sleep_until, Instant},
+};
+use tracing::warn;
+
+use super::bulk;
+
+/// Priority of a request sent through a [`RateGuard`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    /// Response to an interaction.
+    Interaction,
+    /// Request sent by a background task.
+    Background,
+}
+
+impl Priority {
+    /// Get the name of the priority used in metrics.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Interaction => "interaction",
+            Self::Background => "background",
+        }
+    }
+}
+
+/// Guard shared by all requests sent to the Discord API.
+///
+/// See the [module documentation](self) for more information.
+#[derive(Debug, Clone, Default)]
+pub struct RateGuard {
+    inner: Arc<RateGuardInner>,
+}
+
+#[derive(Debug, Default)]
+struct RateGuardInner {
+    /// Number of interaction requests in flight.
+    interactions: AtomicUsize,
+    /// Date until which all requests are paused.
+    paused_until: Mutex<Option<Instant>>,
+    /// Notified when an interaction request completes.
+    notify: Notify,
+}
+
+impl RateGuard {
+    /// Send a request with a given priority.
+    ///
+    /// The request is delayed according to its priority, and rate limits
+    /// returned by Discord are recorded.
+    pub async fn send<T, F>(&self, priority: Priority, request: F) -> Result<T, anyhow::Error>
+    where
+        F: Future<Output = Result<T, anyhow::Error>>,
+    {
+        let _permit = self.acquire(priority).await;
+        let result = request.await;
+
+        if let Err(error) = &result {
+            if let Some(limited) = bulk::rate_limit(error) {
+                self.record(priority, limited.retry_after, limited.global);
+            }
+        }
+
+        result
+    }
+
+    /// Get the number of interaction requests in flight.
+    fn interactions(&self) -> usize {
+        self.inner.interactions.load(Ordering::Acquire)
+    }
+
+    /// Get the date until which all requests are paused.
+    fn paused_until(&self) -> Option<Instant> {
+        let paused_until = self
+            .inner
+            .paused_until
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+
+        paused_until.filter(|until| *until > Instant::now())
+    }
+
+    /// Wait until a request with a given priority can be sent.
+    async fn acquire(&self, priority: Priority) -> Permit {
+        if priority == Priority::Interaction {
+            // Register the request before waiting, so that background
+            // requests yield during the pause.
+            self.inner.interactions.fetch_add(1, Ordering::AcqRel);
+        }
+
+        let permit = Permit {
+            guard: self.clone(),
+            priority,
+        };
+
+        loop {
+            let notified = self.inner.notify.notified();
+
+            if let Some(until) = self.paused_until() {
+                sleep_until(until).await;
+                continue;
+            }
+
+            if priority == Priority::Background && self.interactions() > 0 {
+                notified.await;
+                continue;
+            }
+
+            return permit;
+        }
+    }
+
+    /// Record a rate limit hit.
+    fn record(&self, priority: Priority, retry_after: Duration, global: bool) {
+        let scope = if global { "global" } else { "route" };
+        metrics::increment_counter!(
+            "raidprotect_rate_limit_hits_total",
+            "priority" => priority.name(),
+            "scope" => scope,
+        );
+
+        if !global {
+            return;
+        }
+
+        warn!(retry_after = ?retry_after, "global rate limit reached, pausing requests");
+
+        let until = Instant::now() + retry_after;
+        let mut paused_until = self
+            .inner
+            .paused_until
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+
+        if paused_until.map_or(true, |current| current < until) {
+            *paused_until = Some(until);
+        }
+    }
+}
+
+/// Permit held while a request is in flight.
+struct Permit {
+    guard: RateGuard,
+    priority: Priority,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if self.priority == Priority::Interaction {
+            self.guard.inner.interactions.fetch_sub(1, Ordering::AcqRel);
+            self.guard.inner.notify.notify_waiters();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::bulk::RateLimited;
+
+    #[tokio::test]
+    async fn test_background_yields() {
+        let guard = RateGuard::default();
+        let interaction = guard.acquire(Priority::Interaction).await;
+
+        let background = tokio::spawn({
+            let guard = guard.clone();
+            async move { guard.send(Priority::Background, async { Ok(()) }).await }
+        });
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!background.is_finished());
+
+        drop(interaction);
+        tokio::time::timeout(Duration::from_secs(1), background)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_global_pause() {
+        let guard = RateGuard::default();
+        let retry_after = Duration::from_millis(50);
+
+        let result = guard
+            .send(Priority::Background, async {
+                Err::<(), _>(
+                    RateLimited {
+                        retry_after,
+                        global: true,
+                    }
+                    .into(),
+                )
+            })
+            .await;
+        assert!(result.is_err());
+        assert!(guard.paused_until().is_some());
+
+        let start = Instant::now();
+        guard
+            .send(Priority::Interaction, async { Ok(()) })
+            .await
+            .unwrap();
+        assert!(start.elapsed() >= retry_after - Duration::from_millis(5));
+    }
+
+    #[tokio::test]
+    async fn test_route_limit_does_not_pause() {
+        let guard = RateGuard::default();
+
+        let _ = guard
+            .send(Priority::Background, async {
+                Err::<(), _>(
+                    RateLimited {
+                        retry_after: Duration::from_secs(10),
+                        global: false,
+                    }
+                    .into(),
+                )
+            })
+            .await;
+
+        assert_eq!(guard.paused_until(), None);
+    }
+}
@@ -0,0 +1,191 @@
+//! Text templates with variables.
+//!
+//! Templates are plain text with `{variable}` placeholders, which are replaced
+//! by their value when the template is rendered. Variable names are made of
+//! lowercase ASCII letters, digits, `_` and `-`, other text between braces is
+//! kept as is.
+//!
+//! Templates are used for the custom bot activity (see [`presence`]), for
+//! the sanction reason templates (see the [`moderation`] commands) and for the
+//! server tags (see the [`tag`] command).
+//!
+//! [`presence`]: crate::presence
+//! [`moderation`]: crate::interaction::command::moderation
+//! [`tag`]: crate::interaction::command::tag
+
+/// Maximum length of a variable name.
+const MAX_NAME_LENGTH: usize = 32;
+
+/// Template rendered with [`render`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rendered {
+    /// Rendered text.
+    ///
+    /// Variables without value are kept as literals.
+    pub text: String,
+    /// Variables that have not been filled, in order of appearance.
+    pub unfilled: Vec<String>,
+}
+
+impl Rendered {
+    /// Whether all the variables of the template have been filled.
+    pub fn is_complete(&self) -> bool {
+        self.unfilled.is_empty()
+    }
+}
+
+/// Get the variables of a template, in order of appearance.
+///
+/// Each variable is only returned once.
+pub fn variables(template: &str) -> Vec<&str> {
+    let mut variables = Vec::new();
+
+    for (_, segment) in segments(template) {
+        if let Segment::Variable(name) = segment {
+            if !variables.contains(&name) {
+                variables.push(name);
+            }
+        }
+    }
+
+    variables
+}
+
+/// Render a template.
+///
+/// The value of each variable is given by `lookup`. Variables without value,
+/// or with a blank value, are reported in [`Rendered::unfilled`]. Values are
+/// inserted as is: variables in values are not substituted.
+pub fn render<'a, F>(template: &str, mut lookup: F) -> Rendered
+where
+    F: FnMut(&str) -> Option<&'a str>,
+{
+    let mut text = String::with_capacity(template.len());
+    let mut unfilled = Vec::new();
+
+    for (raw, segment) in segments(template) {
+        match segment {
+            Segment::Text => text.push_str(raw),
+            Segment::Variable(name) => match lookup(name).filter(|v| !v.trim().is_empty()) {
+                Some(value) => text.push_str(value.trim()),
+                None => {
+                    text.push_str(raw);
+
+                    if !unfilled.iter().any(|v| v == name) {
+                        unfilled.push(name.to_owned());
+                    }
+                }
+            },
+        }
+    }
+
+    Rendered { text, unfilled }
+}
+
+/// Segment of a template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Segment<'a> {
+    /// Plain text.
+    Text,
+    /// Variable with its name.
+    Variable(&'a str),
+}
+
+/// Split a template into segments, with their raw text.
+fn segments(template: &str) -> Vec<(&str, Segment<'_>)> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        let variable = rest[start + 1..]
+            .find('}')
+            .map(|end| &rest[start + 1..start + 1 + end])
+            .filter(|name| is_variable_name(name));
+
+        match variable {
+            Some(name) => {
+                if start > 0 {
+                    segments.push((&rest[..start], Segment::Text));
+                }
+
+                let end = start + name.len() + 2;
+                segments.push((&rest[start..end], Segment::Variable(name)));
+                rest = &rest[end..];
+            }
+            None => {
+                segments.push((&rest[..start + 1], Segment::Text));
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+
+    if !rest.is_empty() {
+        segments.push((rest, Segment::Text));
+    }
+
+    segments
+}
+
+/// Whether a text is a valid variable name.
+fn is_variable_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_NAME_LENGTH
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_' || b == b'-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variables() {
+        assert_eq!(
+            variables("Breaking rule {rule} ({count} warnings, rule {rule})"),
+            vec!["rule", "count"]
+        );
+        assert!(variables("No variables").is_empty());
+    }
+
+    #[test]
+    fn test_variables_invalid_names() {
+        assert!(variables("{}").is_empty());
+        assert!(variables("{Rule} { rule } {rule").is_empty());
+        assert_eq!(variables("{{rule}}"), vec!["rule"]);
+    }
+
+    #[test]
+    fn test_render() {
+        let rendered = render("Rule {rule}, {count} times", |name| match name {
+            "rule" => Some("3"),
+            "count" => Some(" 2 "),
+            _ => None,
+        });
+
+        assert!(rendered.is_complete());
+        assert_eq!(rendered.text, "Rule 3, 2 times");
+    }
+
+    #[test]
+    fn test_render_unfilled() {
+        let rendered = render("Rule {rule}, {count} times, {rule}", |name| match name {
+            "count" => Some("  "),
+            _ => None,
+        });
+
+        assert_eq!(rendered.text, "Rule {rule}, {count} times, {rule}");
+        assert_eq!(rendered.unfilled, vec!["rule", "count"]);
+    }
+
+    #[test]
+    fn test_render_values_not_substituted() {
+        let rendered = render("{a} {b}", |name| match name {
+            "a" => Some("{b}"),
+            "b" => Some("x"),
+            _ => None,
+        });
+
+        assert_eq!(rendered.text, "{b} x");
+    }
+}
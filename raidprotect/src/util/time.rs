@@ -0,0 +1,165 @@
+//! Date rendering utilities.
+//!
+//! Dates shown in embeds are rendered with the Discord timestamp markup (see
+//! [`timestamp`]), which is displayed in the timezone and language of each
+//! user. The [`plain_date`] function renders a localized text instead, for
+//! places where the markup may not be rendered such as direct messages.
+//!
+//! Dates entered by members are parsed with [`parse_date`].
+
+use time::{Date, Month, OffsetDateTime, UtcOffset};
+use twilight_mention::{
+    timestamp::{Timestamp, TimestampStyle},
+    Mention,
+};
+use twilight_model::util::Timestamp as DiscordTimestamp;
+
+use crate::translations::Lang;
+
+/// Render a date with the Discord timestamp markup.
+///
+/// Dates before the UNIX epoch cannot be represented with the markup and are
+/// rendered as the epoch.
+pub fn timestamp(date: OffsetDateTime, style: TimestampStyle) -> String {
+    let secs = u64::try_from(date.unix_timestamp()).unwrap_or(0);
+
+    Timestamp::new(secs, Some(style)).mention().to_string()
+}
+
+/// Render a date relatively to the current time (e.g. "3 days ago").
+pub fn relative(date: OffsetDateTime) -> String {
+    timestamp(date, TimestampStyle::RelativeTime)
+}
+
+/// Render a date with the short date format (e.g. "20/04/2021").
+pub fn short_date(date: OffsetDateTime) -> String {
+    timestamp(date, TimestampStyle::ShortDate)
+}
+
+/// Render a date with the long date format followed by the relative time
+/// (e.g. "20 April 2021 (3 days ago)").
+pub fn long_date_relative(date: OffsetDateTime) -> String {
+    format!(
+        "{} ({})",
+        timestamp(date, TimestampStyle::LongDate),
+        relative(date)
+    )
+}
+
+/// Render a date in UTC as a localized plain text (e.g. "2021-04-20 16:20
+/// UTC").
+pub fn plain_date(lang: Lang, date: OffsetDateTime) -> String {
+    let date = date.to_offset(UtcOffset::UTC);
+
+    lang.time_date(
+        format!("{:02}", date.day()),
+        format!("{:02}", date.hour()),
+        format!("{:02}", date.minute()),
+        format!("{:02}", u8::from(date.month())),
+        format!("{:04}", date.year()),
+    )
+}
+
+/// Convert a twilight [`Timestamp`](DiscordTimestamp) into an
+/// [`OffsetDateTime`].
+///
+/// Sub-second precision is discarded.
+pub fn from_discord(timestamp: DiscordTimestamp) -> OffsetDateTime {
+    OffsetDateTime::from_unix_timestamp(timestamp.as_secs()).unwrap_or(OffsetDateTime::UNIX_EPOCH)
+}
+
+/// Convert a UNIX timestamp in seconds into an [`OffsetDateTime`].
+///
+/// Timestamps outside of the supported range are converted to the UNIX epoch.
+pub fn from_secs(secs: u64) -> OffsetDateTime {
+    i64::try_from(secs)
+        .ok()
+        .and_then(|secs| OffsetDateTime::from_unix_timestamp(secs).ok())
+        .unwrap_or(OffsetDateTime::UNIX_EPOCH)
+}
+
+/// Parse a date entered by a member, in the `YYYY-MM-DD` format.
+///
+/// Returns [`None`] if the date is not valid.
+pub fn parse_date(input: &str) -> Option<Date> {
+    let mut parts = input.trim().splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse::<u8>().ok()?;
+    let day = parts.next()?.parse().ok()?;
+
+    Date::from_calendar_date(year, Month::try_from(month).ok()?, day).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use time::Duration;
+
+    use super::*;
+
+    /// 9999-12-31 23:59:59 UTC, the maximum date supported by `time`.
+    const FAR_FUTURE: i64 = 253_402_300_799;
+
+    #[test]
+    fn test_timestamp() {
+        let date = OffsetDateTime::from_unix_timestamp(1_628_594_197).unwrap();
+
+        assert_eq!(relative(date), "<t:1628594197:R>");
+        assert_eq!(short_date(date), "<t:1628594197:d>");
+        assert_eq!(
+            long_date_relative(date),
+            "<t:1628594197:D> (<t:1628594197:R>)"
+        );
+    }
+
+    #[test]
+    fn test_timestamp_edge_values() {
+        let far_future = OffsetDateTime::from_unix_timestamp(FAR_FUTURE).unwrap();
+        let before_epoch = OffsetDateTime::UNIX_EPOCH - Duration::days(1);
+
+        assert_eq!(relative(OffsetDateTime::UNIX_EPOCH), "<t:0:R>");
+        assert_eq!(relative(before_epoch), "<t:0:R>");
+        assert_eq!(relative(far_future), format!("<t:{FAR_FUTURE}:R>"));
+    }
+
+    #[test]
+    fn test_plain_date() {
+        let date = OffsetDateTime::from_unix_timestamp(1_628_594_197).unwrap();
+
+        assert_eq!(plain_date(Lang::En, date), "2021-08-10 11:16 UTC");
+        assert_eq!(plain_date(Lang::Fr, date), "10/08/2021 à 11:16 UTC");
+        assert_eq!(
+            plain_date(Lang::En, OffsetDateTime::UNIX_EPOCH),
+            "1970-01-01 00:00 UTC"
+        );
+    }
+
+    #[test]
+    fn test_plain_date_far_future() {
+        let far_future = OffsetDateTime::from_unix_timestamp(FAR_FUTURE).unwrap();
+
+        assert_eq!(plain_date(Lang::En, far_future), "9999-12-31 23:59 UTC");
+        assert_eq!(plain_date(Lang::Fr, far_future), "31/12/9999 à 23:59 UTC");
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(from_secs(0), OffsetDateTime::UNIX_EPOCH);
+        assert_eq!(from_secs(u64::MAX), OffsetDateTime::UNIX_EPOCH);
+        assert_eq!(from_secs(FAR_FUTURE as u64).year(), 9999);
+
+        let timestamp = DiscordTimestamp::from_secs(1_628_594_197).unwrap();
+        assert_eq!(from_discord(timestamp).unix_timestamp(), 1_628_594_197);
+    }
+
+    #[test]
+    fn test_parse_date() {
+        let date = Date::from_calendar_date(2021, Month::August, 10).unwrap();
+
+        assert_eq!(parse_date("2021-08-10"), Some(date));
+        assert_eq!(parse_date(" 2021-8-10 "), Some(date));
+        assert_eq!(parse_date("2021-02-30"), None);
+        assert_eq!(parse_date("2021-13-01"), None);
+        assert_eq!(parse_date("10/08/2021"), None);
+        assert_eq!(parse_date("2021-08"), None);
+    }
+}